@@ -0,0 +1,123 @@
+//! Benchmarks the steps of a keyword search on a generated corpus,
+//! which doesn't need any dataset to be downloaded.
+
+use std::fs::{create_dir_all, remove_dir_all};
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId};
+use milli::documents::documents_batch_reader_from_objects;
+use milli::heed::EnvOpenOptions;
+use milli::test_utils::{CorpusBuilder, BUCKET_COUNT};
+use milli::update::{IndexDocuments, IndexDocumentsConfig, IndexerConfig, Settings};
+use milli::{Filter, Index, SearchContext};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+const DATABASE_NAME: &str = "search_engine.mmdb";
+const DOCUMENTS: usize = 100_000;
+const QUERY_TERMS: &[usize] = &[1, 3, 8];
+
+fn setup(corpus: &CorpusBuilder) -> Index {
+    match remove_dir_all(DATABASE_NAME) {
+        Ok(_) => (),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+        Err(e) => panic!("{}", e),
+    }
+    create_dir_all(DATABASE_NAME).unwrap();
+
+    let mut options = EnvOpenOptions::new();
+    options.map_size(100 * 1024 * 1024 * 1024); // 100 GB
+    options.max_readers(10);
+    let index = Index::new(options, DATABASE_NAME).unwrap();
+
+    let config = IndexerConfig::default();
+    let mut wtxn = index.write_txn().unwrap();
+    let mut builder = Settings::new(&mut wtxn, &index, &config);
+    CorpusBuilder::configure(&mut builder);
+    builder.execute(|_| (), || false).unwrap();
+
+    let indexing_config = IndexDocumentsConfig::default();
+    let builder =
+        IndexDocuments::new(&mut wtxn, &index, &config, indexing_config, |_| (), || false).unwrap();
+    let documents = documents_batch_reader_from_objects(corpus.build());
+    let (builder, user_error) = builder.add_documents(documents).unwrap();
+    user_error.unwrap();
+    builder.execute().unwrap();
+    wtxn.commit().unwrap();
+
+    index
+}
+
+fn bench_search_engine(c: &mut criterion::Criterion) {
+    let corpus = CorpusBuilder::new(DOCUMENTS);
+    let index = setup(&corpus);
+    let rtxn = index.read_txn().unwrap();
+    let universe = index.documents_ids(&rtxn).unwrap();
+    let queries: Vec<_> = QUERY_TERMS.iter().map(|&terms| (terms, corpus.query(terms))).collect();
+
+    let mut group = c.benchmark_group("query graph construction");
+    for (terms, query) in &queries {
+        group.bench_with_input(BenchmarkId::from_parameter(terms), query, |b, query| {
+            b.iter(|| {
+                let mut ctx = SearchContext::new(&index, &rtxn);
+                ctx.query_graph(query, None).unwrap()
+            });
+        });
+    }
+    group.finish();
+
+    // a new context is created for every iteration to not measure its caches
+    let mut group = c.benchmark_group("resolve query graph");
+    for (terms, query) in &queries {
+        group.bench_with_input(BenchmarkId::from_parameter(terms), query, |b, query| {
+            b.iter_batched(
+                || {
+                    let mut ctx = SearchContext::new(&index, &rtxn);
+                    let graph = ctx.query_graph(query, None).unwrap().unwrap();
+                    (ctx, graph)
+                },
+                |(mut ctx, graph)| ctx.resolve_query_graph(&graph, &universe).unwrap(),
+                BatchSize::SmallInput,
+            );
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("bucket sort with the default ranking rules");
+    for (terms, query) in &queries {
+        group.bench_with_input(BenchmarkId::from_parameter(terms), query, |b, query| {
+            b.iter_batched(
+                || {
+                    let mut ctx = SearchContext::new(&index, &rtxn);
+                    let graph = ctx.query_graph(query, None).unwrap().unwrap();
+                    (ctx, graph)
+                },
+                |(mut ctx, graph)| ctx.bucket_sort_query_graph(&graph, &universe, 0, 20).unwrap(),
+                BatchSize::SmallInput,
+            );
+        });
+    }
+    group.finish();
+
+    let filters =
+        [("1%", "bucket = 0".to_string()), ("50%", format!("bucket < {}", BUCKET_COUNT / 2))];
+    let (_, query) = &queries[1];
+    let mut group = c.benchmark_group("filtered search");
+    for (selectivity, filter) in &filters {
+        group.bench_with_input(BenchmarkId::from_parameter(selectivity), filter, |b, filter| {
+            b.iter(|| {
+                let mut search = index.search(&rtxn);
+                search.query(query);
+                search.filter(Filter::from_str(filter).unwrap().unwrap());
+                search.execute().unwrap()
+            });
+        });
+    }
+    group.finish();
+
+    drop(rtxn);
+    index.prepare_for_closing().wait();
+}
+
+criterion_group!(benches, bench_search_engine);
+criterion_main!(benches);
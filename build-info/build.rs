@@ -1,7 +1,12 @@
+use std::path::Path;
+
 fn main() {
     if let Err(err) = emit_git_variables() {
         println!("cargo:warning=vergen: {}", err);
     }
+    if let Err(err) = emit_dependencies_versions() {
+        println!("cargo:warning=dependencies versions: {}", err);
+    }
 }
 
 fn emit_git_variables() -> anyhow::Result<()> {
@@ -20,3 +25,29 @@ fn emit_git_variables() -> anyhow::Result<()> {
 
     vergen_git2::Emitter::default().fail_on_error().add_instructions(&git2)?.emit()
 }
+
+/// Emits the versions of the storage crates, as resolved in the `Cargo.lock` of the workspace.
+fn emit_dependencies_versions() -> anyhow::Result<()> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")?;
+    let lockfile = Path::new(&manifest_dir).join("..").join("Cargo.lock");
+    println!("cargo:rerun-if-changed={}", lockfile.display());
+    let lockfile = std::fs::read_to_string(lockfile)?;
+
+    for (name, variable) in
+        [("heed", "MEILI_HEED_VERSION"), ("lmdb-master-sys", "MEILI_LMDB_MASTER_SYS_VERSION")]
+    {
+        if let Some(version) = locked_version(&lockfile, name) {
+            println!("cargo:rustc-env={variable}={version}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the version of the package with this name in the lockfile.
+fn locked_version<'a>(lockfile: &'a str, name: &str) -> Option<&'a str> {
+    let name_line = format!("name = \"{name}\"");
+    let mut lines = lockfile.lines();
+    lines.find(|line| *line == name_line)?;
+    lines.next()?.strip_prefix("version = \"")?.strip_suffix('"')
+}
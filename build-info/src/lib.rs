@@ -7,6 +7,9 @@ pub struct BuildInfo {
     pub commit_sha1: Option<&'static str>,
     pub commit_msg: Option<&'static str>,
     pub commit_timestamp: Option<time::OffsetDateTime>,
+    /// The versions of the storage crates, read from the `Cargo.lock` at build time.
+    pub heed_version: Option<&'static str>,
+    pub lmdb_master_sys_version: Option<&'static str>,
 }
 
 impl BuildInfo {
@@ -21,7 +24,18 @@ impl BuildInfo {
             time::OffsetDateTime::parse(commit_timestamp, &Iso8601::DEFAULT).ok()
         });
 
-        Self { branch, describe, commit_sha1, commit_msg, commit_timestamp }
+        let heed_version = option_env!("MEILI_HEED_VERSION");
+        let lmdb_master_sys_version = option_env!("MEILI_LMDB_MASTER_SYS_VERSION");
+
+        Self {
+            branch,
+            describe,
+            commit_sha1,
+            commit_msg,
+            commit_timestamp,
+            heed_version,
+            lmdb_master_sys_version,
+        }
     }
 }
 
@@ -5,7 +5,9 @@ use meilisearch_types::error::ResponseError;
 use meilisearch_types::keys::Key;
 use meilisearch_types::milli::update::IndexDocumentsMethod;
 use meilisearch_types::settings::Unchecked;
-use meilisearch_types::tasks::{Details, IndexSwap, KindWithContent, Status, Task, TaskId};
+use meilisearch_types::tasks::{
+    Details, IndexSwap, KindWithContent, Status, Task, TaskAttempt, TaskId,
+};
 use meilisearch_types::InstanceUid;
 use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize};
@@ -85,6 +87,9 @@ pub struct TaskDump {
         default
     )]
     pub finished_at: Option<OffsetDateTime>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<TaskAttempt>,
 }
 
 // A `Kind` specific version made for the dump. If modified you may break the dump.
@@ -119,6 +124,17 @@ pub enum KindDump {
     IndexSwap {
         swaps: Vec<IndexSwap>,
     },
+    IndexRestore {
+        snapshot_path: String,
+        snapshot_index_uid: String,
+    },
+    IntegrityCheck {
+        repair: bool,
+    },
+    IndexWarmup {
+        prefixes: Vec<String>,
+        populate_cache: bool,
+    },
     TaskCancelation {
         query: String,
         tasks: RoaringBitmap,
@@ -132,6 +148,7 @@ pub enum KindDump {
         instance_uid: Option<InstanceUid>,
     },
     SnapshotCreation,
+    SnapshotImportIncremental,
 }
 
 impl From<Task> for TaskDump {
@@ -147,6 +164,7 @@ impl From<Task> for TaskDump {
             enqueued_at: task.enqueued_at,
             started_at: task.started_at,
             finished_at: task.finished_at,
+            attempts: task.attempts,
         }
     }
 }
@@ -187,6 +205,13 @@ impl From<KindWithContent> for KindDump {
                 KindDump::IndexUpdate { primary_key }
             }
             KindWithContent::IndexSwap { swaps } => KindDump::IndexSwap { swaps },
+            KindWithContent::IndexRestore { snapshot_path, snapshot_index_uid, .. } => {
+                KindDump::IndexRestore { snapshot_path, snapshot_index_uid }
+            }
+            KindWithContent::IntegrityCheck { repair, .. } => KindDump::IntegrityCheck { repair },
+            KindWithContent::IndexWarmup { prefixes, populate_cache, .. } => {
+                KindDump::IndexWarmup { prefixes, populate_cache }
+            }
             KindWithContent::TaskCancelation { query, tasks } => {
                 KindDump::TaskCancelation { query, tasks }
             }
@@ -197,12 +222,14 @@ impl From<KindWithContent> for KindDump {
                 KindDump::DumpCreation { keys, instance_uid }
             }
             KindWithContent::SnapshotCreation => KindDump::SnapshotCreation,
+            KindWithContent::SnapshotImportIncremental => KindDump::SnapshotImportIncremental,
         }
     }
 }
 
 #[cfg(test)]
 pub(crate) mod test {
+    use std::collections::BTreeMap;
     use std::fs::File;
     use std::io::Seek;
     use std::str::FromStr;
@@ -278,6 +305,17 @@ pub(crate) mod test {
             pagination: Setting::NotSet,
             embedders: Setting::NotSet,
             search_cutoff_ms: Setting::NotSet,
+            search_defaults: Setting::NotSet,
+            min_numeric_prefix_length: Setting::NotSet,
+            allow_underscore_fields: Setting::NotSet,
+            localized_attributes: Setting::NotSet,
+            number_normalization: Setting::NotSet,
+            phrase_across_fields: Setting::NotSet,
+            exact_attributes: Setting::NotSet,
+            decompounding_locales: Setting::NotSet,
+            facet_types: Setting::NotSet,
+            stemming: Setting::NotSet,
+            presets: Setting::NotSet,
             _kind: std::marker::PhantomData,
         };
         settings.check()
@@ -300,11 +338,16 @@ pub(crate) mod test {
                     details: Some(Details::DocumentAdditionOrUpdate {
                         received_documents: 12,
                         indexed_documents: Some(10),
+                        duplicate_documents: 0,
+                        invalid_utf8_replacements: 0,
+                        indexing_errors: Vec::new(),
+                        facet_update_strategies: BTreeMap::new(),
                     }),
                     error: None,
                     enqueued_at: datetime!(2022-11-11 0:00 UTC),
                     started_at: Some(datetime!(2022-11-20 0:00 UTC)),
                     finished_at: Some(datetime!(2022-11-21 0:00 UTC)),
+                    attempts: Vec::new(),
                 },
                 None,
             ),
@@ -323,11 +366,16 @@ pub(crate) mod test {
                     details: Some(Details::DocumentAdditionOrUpdate {
                         received_documents: 2,
                         indexed_documents: None,
+                        duplicate_documents: 0,
+                        invalid_utf8_replacements: 0,
+                        indexing_errors: Vec::new(),
+                        facet_update_strategies: BTreeMap::new(),
                     }),
                     error: None,
                     enqueued_at: datetime!(2022-11-11 0:00 UTC),
                     started_at: None,
                     finished_at: None,
+                    attempts: Vec::new(),
                 },
                 Some(vec![
                     json!({ "id": 4, "race": "leonberg" }).as_object().unwrap().clone(),
@@ -346,6 +394,7 @@ pub(crate) mod test {
                     enqueued_at: datetime!(2022-11-15 0:00 UTC),
                     started_at: None,
                     finished_at: None,
+                    attempts: Vec::new(),
                 },
                 None,
             ),
@@ -360,6 +409,8 @@ pub(crate) mod test {
                 uid: Uuid::from_str("9f8a34da-b6b2-42f0-939b-dbd4c3448655").unwrap(),
                 actions: vec![Action::DocumentsAll],
                 indexes: vec![IndexUidPattern::from_str("doggos").unwrap()],
+                index_groups: vec![],
+                default_filters: Default::default(),
                 expires_at: Some(datetime!(4130-03-14 12:21 UTC)),
                 created_at: datetime!(1960-11-15 0:00 UTC),
                 updated_at: datetime!(2022-11-10 0:00 UTC),
@@ -370,6 +421,8 @@ pub(crate) mod test {
                 uid: Uuid::from_str("4622f717-1c00-47bb-a494-39d76a49b591").unwrap(),
                 actions: vec![Action::All],
                 indexes: vec![IndexUidPattern::all()],
+                index_groups: vec![],
+                default_filters: Default::default(),
                 expires_at: None,
                 created_at: datetime!(0000-01-01 00:01 UTC),
                 updated_at: datetime!(1964-05-04 17:25 UTC),
@@ -380,6 +433,8 @@ pub(crate) mod test {
                 uid: Uuid::from_str("fb80b58b-0a34-412f-8ba7-1ce868f8ac5c").unwrap(),
                 actions: vec![],
                 indexes: vec![],
+                index_groups: vec![],
+                default_filters: Default::default(),
                 expires_at: None,
                 created_at: datetime!(400-02-29 0:00 UTC),
                 updated_at: datetime!(1024-02-29 0:00 UTC),
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use super::v4_to_v5::{CompatIndexV4ToV5, CompatV4ToV5};
@@ -132,11 +133,16 @@ impl CompatV5ToV6 {
                             v6::Details::DocumentAdditionOrUpdate {
                                 received_documents: received_documents as u64,
                                 indexed_documents,
+                                duplicate_documents: 0,
+                                invalid_utf8_replacements: 0,
+                                indexing_errors: Vec::new(),
+                                facet_update_strategies: BTreeMap::new(),
                             }
                         }
-                        v5::Details::Settings { settings } => {
-                            v6::Details::SettingsUpdate { settings: Box::new(settings.into()) }
-                        }
+                        v5::Details::Settings { settings } => v6::Details::SettingsUpdate {
+                            settings: Box::new(settings.into()),
+                            warnings: Vec::new(),
+                        },
                         v5::Details::IndexInfo { primary_key } => {
                             v6::Details::IndexInfo { primary_key }
                         }
@@ -158,6 +164,7 @@ impl CompatV5ToV6 {
                     enqueued_at: task_view.enqueued_at,
                     started_at: task_view.started_at,
                     finished_at: task_view.finished_at,
+                    attempts: Vec::new(),
                 };
 
                 (task, content_file)
@@ -185,6 +192,8 @@ impl CompatV5ToV6 {
                         v5::StarOr::Other(uid) => v6::IndexUidPattern::new_unchecked(uid.as_str()),
                     })
                     .collect(),
+                index_groups: Vec::new(),
+                default_filters: Default::default(),
                 expires_at: key.expires_at,
                 created_at: key.created_at,
                 updated_at: key.updated_at,
@@ -326,7 +335,7 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
                         for rule in ranking_rules {
                             match v6::RankingRuleView::from_str(&rule) {
                                 Ok(new_rule) => {
-                                    new_ranking_rules.push(new_rule);
+                                    new_ranking_rules.push(new_rule.into());
                                 }
                                 Err(_) => {
                                     tracing::warn!("Error while importing settings. The ranking rule `{rule}` does not exist anymore.")
@@ -380,6 +389,17 @@ impl<T> From<v5::Settings<T>> for v6::Settings<v6::Unchecked> {
             },
             embedders: v6::Setting::NotSet,
             search_cutoff_ms: v6::Setting::NotSet,
+            search_defaults: v6::Setting::NotSet,
+            min_numeric_prefix_length: v6::Setting::NotSet,
+            allow_underscore_fields: v6::Setting::NotSet,
+            localized_attributes: v6::Setting::NotSet,
+            number_normalization: v6::Setting::NotSet,
+            phrase_across_fields: v6::Setting::NotSet,
+            exact_attributes: v6::Setting::NotSet,
+            decompounding_locales: v6::Setting::NotSet,
+            facet_types: v6::Setting::NotSet,
+            stemming: v6::Setting::NotSet,
+            presets: v6::Setting::NotSet,
             _kind: std::marker::PhantomData,
         }
     }
@@ -4,6 +4,7 @@ use std::path::PathBuf;
 
 use flate2::write::GzEncoder;
 use flate2::Compression;
+use meilisearch_types::compression::{dir_size, ProgressWriter};
 use meilisearch_types::features::RuntimeTogglableFeatures;
 use meilisearch_types::keys::Key;
 use meilisearch_types::settings::{Checked, Settings};
@@ -61,11 +62,26 @@ impl DumpWriter {
         )?)
     }
 
-    pub fn persist_to(self, mut writer: impl Write) -> Result<()> {
+    /// The size of the dump before it is compressed.
+    pub fn uncompressed_size(&self) -> Result<u64> {
+        Ok(dir_size(self.dir.path())?)
+    }
+
+    pub fn persist_to(self, writer: impl Write) -> Result<()> {
+        self.persist_to_with_progress(writer, |_| ())
+    }
+
+    /// Like [`DumpWriter::persist_to`], but calls `on_progress` with the number of
+    /// uncompressed bytes added to the archive every time it grows.
+    pub fn persist_to_with_progress(
+        self,
+        mut writer: impl Write,
+        on_progress: impl FnMut(u64),
+    ) -> Result<()> {
         let gz_encoder = GzEncoder::new(&mut writer, Compression::default());
-        let mut tar_encoder = tar::Builder::new(gz_encoder);
+        let mut tar_encoder = tar::Builder::new(ProgressWriter::new(gz_encoder, on_progress));
         tar_encoder.append_dir_all(".", self.dir.path())?;
-        let gz_encoder = tar_encoder.into_inner()?;
+        let gz_encoder = tar_encoder.into_inner()?.into_inner();
         gz_encoder.finish()?;
         writer.flush()?;
 
@@ -1,8 +1,10 @@
 use std::fs::File as StdFile;
-use std::io::Write;
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::SystemTime;
 
+use milli::{EncryptionError, EncryptionKey};
 use tempfile::NamedTempFile;
 use uuid::Uuid;
 
@@ -13,6 +15,8 @@ pub enum Error {
     #[error("Could not parse file name as utf-8")]
     CouldNotParseFileNameAsUtf8,
     #[error(transparent)]
+    Encryption(#[from] EncryptionError),
+    #[error(transparent)]
     IoError(#[from] std::io::Error),
     #[error(transparent)]
     PersistError(#[from] tempfile::PersistError),
@@ -25,13 +29,23 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Clone, Debug)]
 pub struct FileStore {
     path: PathBuf,
+    encryption_key: Option<EncryptionKey>,
 }
 
 impl FileStore {
     pub fn new(path: impl AsRef<Path>) -> Result<FileStore> {
         let path = path.as_ref().to_path_buf();
         std::fs::create_dir_all(&path)?;
-        Ok(FileStore { path })
+        Ok(FileStore { path, encryption_key: None })
+    }
+
+    /// Encrypts the update files with this key.
+    ///
+    /// The content of an encrypted update file is kept in memory until it is persisted,
+    /// and is decrypted in memory when it is read.
+    pub fn with_encryption_key(mut self, encryption_key: Option<EncryptionKey>) -> FileStore {
+        self.encryption_key = encryption_key;
+        self
     }
 }
 
@@ -42,7 +56,7 @@ impl FileStore {
         let file = NamedTempFile::new_in(&self.path)?;
         let uuid = Uuid::new_v4();
         let path = self.path.join(uuid.to_string());
-        let update_file = File { file: Some(file), path };
+        let update_file = File { file: Some(file), path, encryption: self.file_encryption(uuid) };
 
         Ok((uuid, update_file))
     }
@@ -53,22 +67,34 @@ impl FileStore {
         let file = NamedTempFile::new_in(&self.path)?;
         let uuid = Uuid::from_u128(uuid);
         let path = self.path.join(uuid.to_string());
-        let update_file = File { file: Some(file), path };
+        let update_file = File { file: Some(file), path, encryption: self.file_encryption(uuid) };
 
         Ok((uuid, update_file))
     }
 
+    fn file_encryption(&self, uuid: Uuid) -> Option<FileEncryption> {
+        self.encryption_key.clone().map(|key| FileEncryption { key, uuid, content: Vec::new() })
+    }
+
     /// Returns the file corresponding to the requested uuid.
-    pub fn get_update(&self, uuid: Uuid) -> Result<StdFile> {
+    pub fn get_update(&self, uuid: Uuid) -> Result<UpdateFile> {
         let path = self.get_update_path(uuid);
-        let file = match StdFile::open(path) {
+        let mut file = match StdFile::open(path) {
             Ok(file) => file,
             Err(e) => {
                 tracing::error!("Can't access update file {uuid}: {e}");
                 return Err(e.into());
             }
         };
-        Ok(file)
+        match &self.encryption_key {
+            Some(key) => {
+                let mut content = Vec::new();
+                file.read_to_end(&mut content)?;
+                let content = key.decrypt(uuid.as_bytes(), &content)?;
+                Ok(UpdateFile::Decrypted(Cursor::new(content)))
+            }
+            None => Ok(UpdateFile::File(file)),
+        }
     }
 
     /// Returns the path that correspond to this uuid, the path could not exists.
@@ -95,9 +121,14 @@ impl FileStore {
         Ok(total)
     }
 
-    /// Compute the size of one update
+    /// Compute the size of one update, as it is stored on disk.
     pub fn compute_size(&self, uuid: Uuid) -> Result<u64> {
-        Ok(self.get_update(uuid)?.metadata()?.len())
+        Ok(std::fs::metadata(self.get_update_path(uuid))?.len())
+    }
+
+    /// The last time one update was modified, i.e. when it was written.
+    pub fn modified_at(&self, uuid: Uuid) -> Result<SystemTime> {
+        Ok(std::fs::metadata(self.get_update_path(uuid))?.modified()?)
     }
 
     pub fn delete(&self, uuid: Uuid) -> Result<()> {
@@ -130,18 +161,53 @@ impl FileStore {
     }
 }
 
+/// The content of an update file, decrypted in memory when the file store is encrypted.
+pub enum UpdateFile {
+    File(StdFile),
+    Decrypted(Cursor<Vec<u8>>),
+}
+
+impl Read for UpdateFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            UpdateFile::File(file) => file.read(buf),
+            UpdateFile::Decrypted(cursor) => cursor.read(buf),
+        }
+    }
+}
+
+impl Seek for UpdateFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            UpdateFile::File(file) => file.seek(pos),
+            UpdateFile::Decrypted(cursor) => cursor.seek(pos),
+        }
+    }
+}
+
 pub struct File {
     path: PathBuf,
     file: Option<NamedTempFile>,
+    encryption: Option<FileEncryption>,
+}
+
+/// The plain text content of an encrypted update file, that is only written once encrypted.
+struct FileEncryption {
+    key: EncryptionKey,
+    uuid: Uuid,
+    content: Vec<u8>,
 }
 
 impl File {
     pub fn dry_file() -> Result<Self> {
-        Ok(Self { path: PathBuf::new(), file: None })
+        Ok(Self { path: PathBuf::new(), file: None, encryption: None })
     }
 
     pub fn persist(self) -> Result<()> {
-        if let Some(file) = self.file {
+        if let Some(mut file) = self.file {
+            if let Some(FileEncryption { key, uuid, content }) = self.encryption {
+                file.write_all(&key.encrypt(uuid.as_bytes(), &content)?)?;
+            }
             file.persist(&self.path)?;
         }
         Ok(())
@@ -150,25 +216,27 @@ impl File {
 
 impl Write for File {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        if let Some(file) = self.file.as_mut() {
-            file.write(buf)
-        } else {
-            Ok(buf.len())
+        match (self.file.as_mut(), self.encryption.as_mut()) {
+            (Some(_), Some(encryption)) => {
+                encryption.content.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            (Some(file), None) => file.write(buf),
+            (None, _) => Ok(buf.len()),
         }
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
-        if let Some(file) = self.file.as_mut() {
-            file.flush()
-        } else {
-            Ok(())
+        match (self.file.as_mut(), self.encryption.as_ref()) {
+            (Some(file), None) => file.flush(),
+            _ => Ok(()),
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use std::io::Write;
+    use std::io::{Read, Write};
 
     use tempfile::TempDir;
 
@@ -195,4 +263,26 @@ mod test {
         expected.sort();
         assert_eq!(all_uuids, expected);
     }
+
+    #[test]
+    fn encrypted_update_files() {
+        let dir = TempDir::new().unwrap();
+        let key: EncryptionKey = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=".parse().unwrap();
+        let fs = FileStore::new(dir.path()).unwrap().with_encryption_key(Some(key));
+        let (uuid, mut file) = fs.new_update().unwrap();
+        file.write_all(b"Hello world").unwrap();
+        file.persist().unwrap();
+
+        let on_disk = std::fs::read(fs.get_update_path(uuid)).unwrap();
+        assert!(!on_disk.windows(11).any(|w| w == b"Hello world"));
+
+        let mut content = String::new();
+        fs.get_update(uuid).unwrap().read_to_string(&mut content).unwrap();
+        assert_eq!(content, "Hello world");
+
+        let other_key: EncryptionKey =
+            "Hx4dHBsaGRgXFhUUExIREA8ODQwLCgkIBwYFBAMCAQA=".parse().unwrap();
+        let fs = FileStore::new(dir.path()).unwrap().with_encryption_key(Some(other_key));
+        assert!(matches!(fs.get_update(uuid), Err(Error::Encryption(_))));
+    }
 }
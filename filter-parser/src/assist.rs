@@ -0,0 +1,411 @@
+//! The partial mode of the parser, reporting what is expected at a position of a filter being written.
+//!
+//! Unlike the parser, it only reads the filter up to the cursor and never fails on a filter
+//! that isn't finished: it returns `None` when what comes before the cursor can't start a valid filter.
+//!
+//! ```text
+//! genre = hor|        => a value of `genre` after `=`, starting with `hor`
+//! genre IS N|         => an operator of `genre`, starting with `IS N`
+//! (genre = horror |   => `AND` or `OR`
+//! ```
+
+use std::borrow::Cow;
+
+use nom::Slice;
+
+use crate::value::{is_keyword, is_value_component};
+use crate::{Span, Token};
+
+/// What is expected at the cursor, along with what is already written of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expectation<'a> {
+    pub expected: Expected<'a>,
+    /// The part of the expected element written before the cursor, empty if nothing is.
+    ///
+    /// A completion replaces the span of this token, that ends at the cursor.
+    pub prefix: Token<'a>,
+}
+
+/// The kind of element expected at the cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expected<'a> {
+    /// The name of an attribute to filter on.
+    Attribute,
+    /// An operator applied on this attribute, like `=`, `IN`, or `IS NOT NULL`.
+    Operator { attribute: Token<'a> },
+    /// A value compared to this attribute with this operator.
+    Value { attribute: Token<'a>, operator: &'a str },
+    /// `AND` or `OR`, to combine the filter with another one.
+    Combinator,
+}
+
+/// The operators written with symbols, ordered so that the longest ones are matched first.
+const SYMBOLS: [&str; 7] = ["<=", ">=", "!=", "<", ">", "=", "!"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LexemeKind {
+    Word,
+    Quoted { closed: bool },
+    Symbol,
+    Open,
+    Close,
+    OpenBracket,
+    CloseBracket,
+    Comma,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Lexeme<'a> {
+    kind: LexemeKind,
+    span: Span<'a>,
+}
+
+impl<'a> Lexeme<'a> {
+    fn text(&self) -> &'a str {
+        self.span.fragment()
+    }
+
+    fn end(&self) -> usize {
+        self.span.location_offset() + self.text().len()
+    }
+
+    fn is_value(&self) -> bool {
+        match self.kind {
+            LexemeKind::Word => !is_keyword(self.text()),
+            LexemeKind::Quoted { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// The token of a word or a quoted value, whose value is without its quotes and unescaped.
+    fn token(&self) -> Token<'a> {
+        match self.kind {
+            LexemeKind::Quoted { closed } => {
+                let text = self.text();
+                let quote = &text[..1];
+                let inner = if closed { &text[1..text.len() - 1] } else { &text[1..] };
+                let value = inner.replace(&format!("\\{quote}"), quote);
+                Token::new(self.span, Some(value)).into_quoted()
+            }
+            _ => Token::new(self.span, None),
+        }
+    }
+}
+
+/// Splits the filter into the elements of its syntax, `None` on a character that can't be part of a filter.
+fn lex(input: Span) -> Option<Vec<Lexeme>> {
+    let text = *input.fragment();
+    let mut lexemes = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let (kind, end) = match c {
+            c if c.is_whitespace() => continue,
+            '(' => (LexemeKind::Open, start + 1),
+            ')' => (LexemeKind::Close, start + 1),
+            '[' => (LexemeKind::OpenBracket, start + 1),
+            ']' => (LexemeKind::CloseBracket, start + 1),
+            ',' => (LexemeKind::Comma, start + 1),
+            '<' | '>' | '!' | '=' => {
+                let symbol =
+                    SYMBOLS.iter().find(|symbol| text[start..].starts_with(*symbol)).unwrap();
+                for _ in 1..symbol.len() {
+                    chars.next();
+                }
+                (LexemeKind::Symbol, start + symbol.len())
+            }
+            '\'' | '"' => {
+                let mut closing = None;
+                while let Some((i, next)) = chars.next() {
+                    if next == '\\' {
+                        chars.next();
+                    } else if next == c {
+                        closing = Some(i);
+                        break;
+                    }
+                }
+                match closing {
+                    Some(i) => (LexemeKind::Quoted { closed: true }, i + 1),
+                    None => (LexemeKind::Quoted { closed: false }, text.len()),
+                }
+            }
+            c if is_value_component(c) => {
+                let mut end = start + c.len_utf8();
+                while let Some((i, next)) = chars.peek().copied() {
+                    if !is_value_component(next) {
+                        break;
+                    }
+                    end = i + next.len_utf8();
+                    chars.next();
+                }
+                (LexemeKind::Word, end)
+            }
+            _ => return None,
+        };
+        lexemes.push(Lexeme { kind, span: input.slice(start..end) });
+    }
+
+    Some(lexemes)
+}
+
+#[derive(Debug, Clone)]
+enum State<'a> {
+    Attribute,
+    /// The arguments of a geo filter, with the number of parenthesis and brackets still open.
+    GeoArguments {
+        open: usize,
+    },
+    Operator {
+        attribute: Token<'a>,
+        start: usize,
+    },
+    Value {
+        attribute: Token<'a>,
+        operator: &'a str,
+    },
+    /// After `attribute value`, only `TO` can follow.
+    To {
+        attribute: Token<'a>,
+    },
+    InOpeningBracket {
+        attribute: Token<'a>,
+    },
+    InValue {
+        attribute: Token<'a>,
+    },
+    InSeparator {
+        attribute: Token<'a>,
+    },
+    Combinator,
+}
+
+impl<'a> State<'a> {
+    /// The state after this lexeme, `None` if it is not expected.
+    fn next(self, lexeme: &Lexeme<'a>, input: &'a str, depth: &mut usize) -> Option<Self> {
+        use LexemeKind::*;
+
+        let text = lexeme.text();
+        let state = match (self, lexeme.kind) {
+            (State::Attribute, Open) => {
+                *depth += 1;
+                State::Attribute
+            }
+            (State::Attribute, Word) if text == "NOT" => State::Attribute,
+            (State::Attribute, Word) if text == "_geoRadius" || text == "_geoBoundingBox" => {
+                State::GeoArguments { open: 0 }
+            }
+            (State::Attribute, Word | Quoted { .. }) if lexeme.is_value() => {
+                State::Operator { attribute: lexeme.token(), start: lexeme.end() }
+            }
+            (State::GeoArguments { open }, Open | OpenBracket) => {
+                State::GeoArguments { open: open + 1 }
+            }
+            (State::GeoArguments { open: 0 }, _) => return None,
+            (State::GeoArguments { open: 1 }, Close) => State::Combinator,
+            (State::GeoArguments { open }, Close | CloseBracket) => {
+                State::GeoArguments { open: open - 1 }
+            }
+            (state @ State::GeoArguments { .. }, Word | Comma) => state,
+            (State::Operator { attribute, start }, Symbol)
+                if input[start..lexeme.span.location_offset()].trim().is_empty() && text != "!" =>
+            {
+                State::Value { attribute, operator: text }
+            }
+            (State::Operator { attribute, start }, Word) if is_keyword(text) => {
+                let operator = input[start..lexeme.end()].trim_start();
+                let words: Vec<_> = operator.split_whitespace().collect();
+                match words.as_slice() {
+                    ["IN"] | ["NOT", "IN"] => State::InOpeningBracket { attribute },
+                    ["NOT"] | ["IS"] | ["IS", "NOT"] => State::Operator { attribute, start },
+                    ["EXISTS"] | ["NOT", "EXISTS"] => State::Combinator,
+                    ["IS", "NULL" | "EMPTY"] | ["IS", "NOT", "NULL" | "EMPTY"] => State::Combinator,
+                    _ => return None,
+                }
+            }
+            (State::Operator { attribute, start }, Word | Quoted { .. })
+                if input[start..lexeme.span.location_offset()].trim().is_empty() =>
+            {
+                State::To { attribute }
+            }
+            (State::To { attribute }, Word) if text == "TO" => {
+                State::Value { attribute, operator: text }
+            }
+            (State::Value { .. }, Word | Quoted { .. }) if lexeme.is_value() => State::Combinator,
+            (State::InOpeningBracket { attribute }, OpenBracket) => State::InValue { attribute },
+            (State::InValue { attribute }, Word | Quoted { .. }) if lexeme.is_value() => {
+                State::InSeparator { attribute }
+            }
+            (State::InValue { .. } | State::InSeparator { .. }, CloseBracket) => State::Combinator,
+            (State::InSeparator { attribute }, Comma) => State::InValue { attribute },
+            (State::Combinator, Word) if text == "AND" || text == "OR" => State::Attribute,
+            (State::Combinator, Close) if *depth > 0 => {
+                *depth -= 1;
+                State::Combinator
+            }
+            _ => return None,
+        };
+        Some(state)
+    }
+
+    /// What this state expects, `None` if it can't be completed.
+    fn expected(&self) -> Option<Expected<'a>> {
+        match self {
+            State::Attribute => Some(Expected::Attribute),
+            State::Operator { attribute, .. } => {
+                Some(Expected::Operator { attribute: attribute.clone() })
+            }
+            State::Value { attribute, operator } => {
+                Some(Expected::Value { attribute: attribute.clone(), operator })
+            }
+            State::InValue { attribute } => {
+                Some(Expected::Value { attribute: attribute.clone(), operator: "IN" })
+            }
+            State::Combinator => Some(Expected::Combinator),
+            State::GeoArguments { .. }
+            | State::To { .. }
+            | State::InOpeningBracket { .. }
+            | State::InSeparator { .. } => None,
+        }
+    }
+}
+
+/// Returns what is expected at the byte offset `cursor` of the filter, `None` if the filter
+/// written before the cursor is invalid or if nothing can be suggested at this position.
+pub fn expected_at(input: &str, cursor: usize) -> Option<Expectation> {
+    let cursor = cursor.min(input.len());
+    if !input.is_char_boundary(cursor) {
+        return None;
+    }
+    let span = Span::new_extra(input, input);
+    let lexemes = lex(span.slice(..cursor))?;
+
+    // a word, an unclosed quoted value, or the beginning of an operator touching the cursor is still being written
+    let (lexemes, partial) = match lexemes.split_last() {
+        Some((last, rest))
+            if last.end() == cursor
+                && match last.kind {
+                    LexemeKind::Word | LexemeKind::Quoted { closed: false } => true,
+                    LexemeKind::Symbol => ["<", ">", "!"].contains(&last.text()),
+                    _ => false,
+                } =>
+        {
+            (rest, Some(last))
+        }
+        _ => (lexemes.as_slice(), None),
+    };
+
+    let mut depth = 0;
+    let mut state = State::Attribute;
+    for lexeme in lexemes {
+        state = state.next(lexeme, input, &mut depth)?;
+    }
+
+    let prefix = match (&state, partial) {
+        // the operators can be made of several words
+        (State::Operator { start, .. }, _) => {
+            let written = input[*start..cursor].trim_start();
+            Token::from(span.slice(cursor - written.len()..cursor))
+        }
+        (_, Some(partial)) => partial.token(),
+        (_, None) => Token::from(span.slice(cursor..cursor)),
+    };
+
+    Some(Expectation { expected: state.expected()?, prefix })
+}
+
+/// Writes a value so that it is parsed as itself, between double quotes if it isn't a single word.
+pub fn quote_if_needed(value: &str) -> Cow<str> {
+    if !value.is_empty() && value.chars().all(is_value_component) && !is_keyword(value) {
+        Cow::Borrowed(value)
+    } else {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\\\"")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Formats the expectation at the `|` of the filter.
+    fn expected(filter: &str) -> String {
+        let cursor = filter.find('|').unwrap();
+        let filter = filter.replacen('|', "", 1);
+        match expected_at(&filter, cursor) {
+            Some(Expectation { expected, prefix }) => {
+                let expected = match expected {
+                    Expected::Attribute => "attribute".to_string(),
+                    Expected::Operator { attribute } => {
+                        format!("operator of {}", attribute.value())
+                    }
+                    Expected::Value { attribute, operator } => {
+                        format!("value of {} {operator}", attribute.value())
+                    }
+                    Expected::Combinator => "combinator".to_string(),
+                };
+                let offset = prefix.original_span().location_offset();
+                format!("{expected}, prefix {:?} at {offset}", prefix.value())
+            }
+            None => "nothing".to_string(),
+        }
+    }
+
+    #[test]
+    fn attribute() {
+        insta::assert_snapshot!(expected("|"), @r###"attribute, prefix "" at 0"###);
+        insta::assert_snapshot!(expected("gen|"), @r###"attribute, prefix "gen" at 0"###);
+        insta::assert_snapshot!(expected("gen|re = horror"), @r###"attribute, prefix "gen" at 0"###);
+        insta::assert_snapshot!(expected("genre = horror AND (NOT ye|"), @r###"attribute, prefix "ye" at 24"###);
+        insta::assert_snapshot!(expected("genre = horror OR |"), @r###"attribute, prefix "" at 18"###);
+        insta::assert_snapshot!(expected("'release date|"), @r###"attribute, prefix "release date" at 0"###);
+    }
+
+    #[test]
+    fn operator() {
+        insta::assert_snapshot!(expected("genre |"), @r###"operator of genre, prefix "" at 6"###);
+        insta::assert_snapshot!(expected("genre IS N|"), @r###"operator of genre, prefix "IS N" at 6"###);
+        insta::assert_snapshot!(expected("genre NOT |"), @r###"operator of genre, prefix "NOT " at 6"###);
+        insta::assert_snapshot!(expected("price <|"), @r###"operator of price, prefix "<" at 6"###);
+        insta::assert_snapshot!(expected("'release date' |"), @r###"operator of release date, prefix "" at 15"###);
+    }
+
+    #[test]
+    fn value() {
+        insta::assert_snapshot!(expected("genre =|"), @r###"value of genre =, prefix "" at 7"###);
+        insta::assert_snapshot!(expected("genre = |"), @r###"value of genre =, prefix "" at 8"###);
+        insta::assert_snapshot!(expected("genre != hor|"), @r###"value of genre !=, prefix "hor" at 9"###);
+        insta::assert_snapshot!(expected("price < |"), @r###"value of price <, prefix "" at 8"###);
+        insta::assert_snapshot!(expected("genre = \"science fi|"), @r###"value of genre =, prefix "science fi" at 8"###);
+        insta::assert_snapshot!(expected("genre IN [horror, dra|"), @r###"value of genre IN, prefix "dra" at 18"###);
+        insta::assert_snapshot!(expected("price 10 TO |"), @r###"value of price TO, prefix "" at 12"###);
+    }
+
+    #[test]
+    fn combinator() {
+        insta::assert_snapshot!(expected("genre = horror |"), @r###"combinator, prefix "" at 15"###);
+        insta::assert_snapshot!(expected("genre = horror A|"), @r###"combinator, prefix "A" at 15"###);
+        insta::assert_snapshot!(expected("(genre = horror) |"), @r###"combinator, prefix "" at 17"###);
+        insta::assert_snapshot!(expected("genre EXISTS |"), @r###"combinator, prefix "" at 13"###);
+        insta::assert_snapshot!(expected("genre IS NOT NULL |"), @r###"combinator, prefix "" at 18"###);
+        insta::assert_snapshot!(expected("genre IN [horror] |"), @r###"combinator, prefix "" at 18"###);
+        insta::assert_snapshot!(expected("_geoRadius(45.4, 3.5, 100) |"), @r###"combinator, prefix "" at 27"###);
+    }
+
+    #[test]
+    fn quoting() {
+        insta::assert_snapshot!(quote_if_needed("horror"), @"horror");
+        insta::assert_snapshot!(quote_if_needed("12.5"), @"12.5");
+        insta::assert_snapshot!(quote_if_needed("science fiction"), @r###""science fiction""###);
+        insta::assert_snapshot!(quote_if_needed("AND"), @r###""AND""###);
+        insta::assert_snapshot!(quote_if_needed("say \"hi\""), @r###""say \"hi\"""###);
+    }
+
+    #[test]
+    fn invalid() {
+        insta::assert_snapshot!(expected("genre = horror) |"), @"nothing");
+        insta::assert_snapshot!(expected("= |"), @"nothing");
+        insta::assert_snapshot!(expected("genre IN horror |"), @"nothing");
+        insta::assert_snapshot!(expected("genre = horror AND AND |"), @"nothing");
+        insta::assert_snapshot!(expected("genre ~ |"), @"nothing");
+        insta::assert_snapshot!(expected("genre IN [horror |"), @"nothing");
+    }
+}
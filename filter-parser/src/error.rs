@@ -87,6 +87,12 @@ impl<'a> Error<'a> {
         &self.context
     }
 
+    /// The range of bytes of the filter expression where the error happened.
+    pub fn span(&self) -> std::ops::Range<usize> {
+        let start = self.context.location_offset();
+        start..start + self.context.fragment().len()
+    }
+
     pub fn new_from_kind(context: Span<'a>, kind: ErrorKind<'a>) -> Self {
         Self { context, kind }
     }
@@ -202,7 +208,23 @@ impl<'a> Display for Error<'a> {
         }
         let base_column = self.context.get_utf8_column();
         let size = self.context.fragment().chars().count();
-
-        write!(f, "{}:{} {}", base_column, base_column + size, self.context.extra)
+        let location = format!("{}:{} ", base_column, base_column + size);
+        write!(f, "{location}{}", self.context.extra)?;
+
+        // Underline the incriminated part of the filter. The columns are counted in characters
+        // and not in bytes so the carets stay aligned after multi-byte characters.
+        let lines: Vec<_> = self.context.extra.lines().collect();
+        let line =
+            lines.get(self.context.location_line() as usize - 1).copied().unwrap_or_default();
+        let indent = if lines.len() > 1 {
+            // the location is not on the first line, the line must be repeated to be underlined
+            write!(f, "\n{line}")?;
+            base_column - 1
+        } else {
+            location.chars().count() + base_column - 1
+        };
+        let remaining = line.chars().count().saturating_sub(base_column - 1);
+        let carets = size.min(remaining).max(1);
+        write!(f, "\n{:indent$}{:^<carets$}", "", "")
     }
 }
@@ -1,7 +1,7 @@
 use nom::branch::alt;
 use nom::bytes::complete::{take_till, take_while, take_while1};
 use nom::character::complete::{char, multispace0};
-use nom::combinator::cut;
+use nom::combinator::{cut, map};
 use nom::sequence::{delimited, terminated};
 use nom::{InputIter, InputLength, InputTake, Slice};
 
@@ -139,8 +139,14 @@ pub fn parse_value(input: Span) -> IResult<Token> {
 
     let (input, value) = terminated(
         alt((
-            delimited(char('\''), cut(|input| quoted_by('\'', input)), cut(char('\''))),
-            delimited(char('"'), cut(|input| quoted_by('"', input)), cut(char('"'))),
+            map(
+                delimited(char('\''), cut(|input| quoted_by('\'', input)), cut(char('\''))),
+                Token::into_quoted,
+            ),
+            map(
+                delimited(char('"'), cut(|input| quoted_by('"', input)), cut(char('"'))),
+                Token::into_quoted,
+            ),
             word_not_keyword,
         )),
         multispace0,
@@ -174,7 +180,8 @@ pub fn parse_value(input: Span) -> IResult<Token> {
     match unescaper::unescape(value.value()) {
         Ok(content) => {
             if content.len() != value.value().len() {
-                Ok((input, Token::new(value.original_span(), Some(content))))
+                let token = Token::new(value.original_span(), Some(content));
+                Ok((input, if value.is_quoted() { token.into_quoted() } else { token }))
             } else {
                 Ok((input, value))
             }
@@ -191,7 +198,7 @@ pub fn parse_value(input: Span) -> IResult<Token> {
     }
 }
 
-fn is_value_component(c: char) -> bool {
+pub(crate) fn is_value_component(c: char) -> bool {
     c.is_alphanumeric() || ['_', '-', '.'].contains(&c)
 }
 
@@ -199,7 +206,7 @@ fn is_syntax_component(c: char) -> bool {
     c.is_whitespace() || ['(', ')', '=', '<', '>', '!'].contains(&c)
 }
 
-fn is_keyword(s: &str) -> bool {
+pub(crate) fn is_keyword(s: &str) -> bool {
     matches!(
         s,
         "AND"
@@ -378,6 +385,32 @@ pub mod test {
         }
     }
 
+    #[test]
+    fn test_boolean_value() {
+        let test_case = [
+            // (input, expected boolean)
+            ("true", Some(true)),
+            ("false", Some(false)),
+            ("   true  ", Some(true)),
+            ("'true'", None),
+            ("\"false\"", None),
+            ("TRUE", None),
+            ("truee", None),
+        ];
+
+        for (input, expected) in test_case {
+            let span = Span::new_extra(input, input);
+            let token = parse_value(span).unwrap().1;
+            assert_eq!(token.as_bool(), expected, "Filter `{}` failed.", input);
+            assert_eq!(
+                token.is_quoted(),
+                input.contains(['\'', '"']),
+                "Filter `{}` failed.",
+                input
+            );
+        }
+    }
+
     #[test]
     fn diagnostic() {
         let test_case = [
@@ -34,6 +34,9 @@ enum AutobatchKind {
     IndexDeletion,
     IndexUpdate,
     IndexSwap,
+    IndexRestore,
+    IntegrityCheck,
+    IndexWarmup,
 }
 
 impl AutobatchKind {
@@ -77,10 +80,14 @@ impl From<KindWithContent> for AutobatchKind {
             KindWithContent::IndexCreation { .. } => AutobatchKind::IndexCreation,
             KindWithContent::IndexUpdate { .. } => AutobatchKind::IndexUpdate,
             KindWithContent::IndexSwap { .. } => AutobatchKind::IndexSwap,
+            KindWithContent::IndexRestore { .. } => AutobatchKind::IndexRestore,
+            KindWithContent::IntegrityCheck { .. } => AutobatchKind::IntegrityCheck,
+            KindWithContent::IndexWarmup { .. } => AutobatchKind::IndexWarmup,
             KindWithContent::TaskCancelation { .. }
             | KindWithContent::TaskDeletion { .. }
             | KindWithContent::DumpCreation { .. }
-            | KindWithContent::SnapshotCreation => {
+            | KindWithContent::SnapshotCreation
+            | KindWithContent::SnapshotImportIncremental => {
                 panic!("The autobatcher should never be called with tasks that don't apply to an index.")
             }
         }
@@ -132,6 +139,15 @@ pub enum BatchKind {
     IndexSwap {
         id: TaskId,
     },
+    IndexRestore {
+        id: TaskId,
+    },
+    IntegrityCheck {
+        id: TaskId,
+    },
+    IndexWarmup {
+        id: TaskId,
+    },
 }
 
 impl BatchKind {
@@ -175,6 +191,9 @@ impl BatchKind {
             K::IndexDeletion => (Break(BatchKind::IndexDeletion { ids: vec![task_id] }), false),
             K::IndexUpdate => (Break(BatchKind::IndexUpdate { id: task_id }), false),
             K::IndexSwap => (Break(BatchKind::IndexSwap { id: task_id }), false),
+            K::IndexRestore => (Break(BatchKind::IndexRestore { id: task_id }), true),
+            K::IntegrityCheck => (Break(BatchKind::IntegrityCheck { id: task_id }), false),
+            K::IndexWarmup => (Break(BatchKind::IndexWarmup { id: task_id }), false),
             K::DocumentClear => (Continue(BatchKind::DocumentClear { ids: vec![task_id] }), false),
             K::DocumentImport { method, allow_index_creation, primary_key: pk }
                 if primary_key.is_none() || pk.is_none() || primary_key == pk.as_deref() =>
@@ -222,7 +241,7 @@ impl BatchKind {
 
         match (self, kind) {
             // We don't batch any of these operations
-            (this, K::IndexCreation | K::IndexUpdate | K::IndexSwap | K::DocumentDeletionByFilter) => Break(this),
+            (this, K::IndexCreation | K::IndexUpdate | K::IndexSwap | K::IndexRestore | K::IntegrityCheck | K::IndexWarmup | K::DocumentDeletionByFilter) => Break(this),
             // We must not batch tasks that don't have the same index creation rights if the index doesn't already exists.
             (this, kind) if !index_already_exists && this.allow_index_creation() == Some(false) && kind.allow_index_creation() == Some(true) => {
                 Break(this)
@@ -519,6 +538,9 @@ impl BatchKind {
                 | BatchKind::IndexDeletion { .. }
                 | BatchKind::IndexUpdate { .. }
                 | BatchKind::IndexSwap { .. }
+                | BatchKind::IndexRestore { .. }
+                | BatchKind::IntegrityCheck { .. }
+                | BatchKind::IndexWarmup { .. }
                 | BatchKind::DocumentDeletionByFilter { .. },
                 _,
             ) => {
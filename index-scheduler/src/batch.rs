@@ -17,11 +17,11 @@ tasks individually, but should be much faster since we are only performing
 one indexing operation.
 */
 
-use std::collections::{BTreeSet, HashSet};
-use std::ffi::OsStr;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt;
 use std::fs::{self, File};
-use std::io::BufWriter;
+use std::io::{self, BufWriter};
+use std::path::Path;
 
 use dump::IndexMetadata;
 use meilisearch_types::error::Code;
@@ -29,18 +29,20 @@ use meilisearch_types::heed::{RoTxn, RwTxn};
 use meilisearch_types::milli::documents::{obkv_to_object, DocumentsBatchReader};
 use meilisearch_types::milli::heed::CompactionOption;
 use meilisearch_types::milli::update::{
-    IndexDocumentsConfig, IndexDocumentsMethod, IndexerConfig, Settings as MilliSettings,
+    IndexDocumentsConfig, IndexDocumentsMethod, IndexerConfig, InvalidDocuments, Setting,
+    Settings as MilliSettings,
 };
-use meilisearch_types::milli::{self, Filter};
-use meilisearch_types::settings::{apply_settings_to_builder, Settings, Unchecked};
+use meilisearch_types::milli::{self, AscDesc, Filter, FilterCondition, Member};
+use meilisearch_types::settings::{apply_settings_to_builder, Checked, Settings, Unchecked};
 use meilisearch_types::tasks::{Details, IndexSwap, Kind, KindWithContent, Status, Task};
-use meilisearch_types::{compression, Index, VERSION_FILE_NAME};
+use meilisearch_types::{compression, versioning, Index, VERSION_FILE_NAME};
 use roaring::RoaringBitmap;
 use time::macros::format_description;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
 use crate::autobatcher::{self, BatchKind};
+use crate::index_mapper::{IndexMapper, InstalledIndex};
 use crate::utils::{self, swap_index_uid_in_task};
 use crate::{Error, IndexScheduler, MustStopProcessing, ProcessingTasks, Result, TaskId};
 
@@ -61,6 +63,7 @@ pub(crate) enum Batch {
     },
     TaskDeletions(Vec<Task>),
     SnapshotCreation(Vec<Task>),
+    SnapshotImportIncremental(Vec<Task>),
     Dump(Task),
     IndexOperation {
         op: IndexOperation,
@@ -84,6 +87,21 @@ pub(crate) enum Batch {
     IndexSwap {
         task: Task,
     },
+    IndexRestore {
+        index_uid: String,
+        task: Task,
+    },
+    IntegrityCheck {
+        index_uid: String,
+        repair: bool,
+        task: Task,
+    },
+    IndexWarmup {
+        index_uid: String,
+        prefixes: Vec<String>,
+        populate_cache: bool,
+        task: Task,
+    },
 }
 
 #[derive(Debug)]
@@ -147,10 +165,14 @@ impl Batch {
             Batch::TaskCancelation { task, .. }
             | Batch::Dump(task)
             | Batch::IndexCreation { task, .. }
-            | Batch::IndexUpdate { task, .. } => {
+            | Batch::IndexUpdate { task, .. }
+            | Batch::IndexRestore { task, .. }
+            | Batch::IntegrityCheck { task, .. }
+            | Batch::IndexWarmup { task, .. } => {
                 RoaringBitmap::from_sorted_iter(std::iter::once(task.uid)).unwrap()
             }
             Batch::SnapshotCreation(tasks)
+            | Batch::SnapshotImportIncremental(tasks)
             | Batch::TaskDeletions(tasks)
             | Batch::IndexDeletion { tasks, .. } => {
                 RoaringBitmap::from_iter(tasks.iter().map(|task| task.uid))
@@ -188,12 +210,16 @@ impl Batch {
             TaskCancelation { .. }
             | TaskDeletions(_)
             | SnapshotCreation(_)
+            | SnapshotImportIncremental(_)
             | Dump(_)
             | IndexSwap { .. } => None,
             IndexOperation { op, .. } => Some(op.index_uid()),
             IndexCreation { index_uid, .. }
             | IndexUpdate { index_uid, .. }
-            | IndexDeletion { index_uid, .. } => Some(index_uid),
+            | IndexDeletion { index_uid, .. }
+            | IndexRestore { index_uid, .. }
+            | IntegrityCheck { index_uid, .. }
+            | IndexWarmup { index_uid, .. } => Some(index_uid),
         }
     }
 }
@@ -207,12 +233,16 @@ impl fmt::Display for Batch {
             Batch::TaskCancelation { .. } => f.write_str("TaskCancelation")?,
             Batch::TaskDeletions(_) => f.write_str("TaskDeletion")?,
             Batch::SnapshotCreation(_) => f.write_str("SnapshotCreation")?,
+            Batch::SnapshotImportIncremental(_) => f.write_str("SnapshotImportIncremental")?,
             Batch::Dump(_) => f.write_str("Dump")?,
             Batch::IndexOperation { op, .. } => write!(f, "{op}")?,
             Batch::IndexCreation { .. } => f.write_str("IndexCreation")?,
             Batch::IndexUpdate { .. } => f.write_str("IndexUpdate")?,
             Batch::IndexDeletion { .. } => f.write_str("IndexDeletion")?,
             Batch::IndexSwap { .. } => f.write_str("IndexSwap")?,
+            Batch::IndexRestore { .. } => f.write_str("IndexRestore")?,
+            Batch::IntegrityCheck { .. } => f.write_str("IntegrityCheck")?,
+            Batch::IndexWarmup { .. } => f.write_str("IndexWarmup")?,
         };
         match index_uid {
             Some(name) => f.write_fmt(format_args!(" on {name:?} from tasks: {tasks:?}")),
@@ -512,6 +542,28 @@ impl IndexScheduler {
                 let task = self.get_task(rtxn, id)?.ok_or(Error::CorruptedTaskQueue)?;
                 Ok(Some(Batch::IndexSwap { task }))
             }
+            BatchKind::IndexRestore { id } => {
+                let task = self.get_task(rtxn, id)?.ok_or(Error::CorruptedTaskQueue)?;
+                Ok(Some(Batch::IndexRestore { index_uid, task }))
+            }
+            BatchKind::IntegrityCheck { id } => {
+                let task = self.get_task(rtxn, id)?.ok_or(Error::CorruptedTaskQueue)?;
+                let repair = match &task.kind {
+                    KindWithContent::IntegrityCheck { repair, .. } => *repair,
+                    _ => unreachable!(),
+                };
+                Ok(Some(Batch::IntegrityCheck { index_uid, repair, task }))
+            }
+            BatchKind::IndexWarmup { id } => {
+                let task = self.get_task(rtxn, id)?.ok_or(Error::CorruptedTaskQueue)?;
+                let (prefixes, populate_cache) = match &task.kind {
+                    KindWithContent::IndexWarmup { prefixes, populate_cache, .. } => {
+                        (prefixes.clone(), *populate_cache)
+                    }
+                    _ => unreachable!(),
+                };
+                Ok(Some(Batch::IndexWarmup { index_uid, prefixes, populate_cache, task }))
+            }
         }
     }
 
@@ -528,14 +580,18 @@ impl IndexScheduler {
 
         puffin::profile_function!();
 
-        let enqueued = &self.get_status(rtxn, Status::Enqueued)?;
+        // The tasks waiting for their next attempt after a transient failure are skipped.
+        let mut enqueued = self.get_status(rtxn, Status::Enqueued)?;
+        let waiting = self.retrying_tasks.write().unwrap().waiting() & &enqueued;
+        enqueued -= &waiting;
+        let enqueued = &enqueued;
         let to_cancel = self.get_kind(rtxn, Kind::TaskCancelation)? & enqueued;
 
         // 1. we get the last task to cancel.
         if let Some(task_id) = to_cancel.max() {
             // We retrieve the tasks that were processing before this tasks cancelation started.
             // We must *not* reset the processing tasks before calling this method.
-            let ProcessingTasks { started_at, processing } =
+            let ProcessingTasks { started_at, processing, .. } =
                 &*self.processing_tasks.read().unwrap();
             return Ok(Some(Batch::TaskCancelation {
                 task: self.get_task(rtxn, task_id)?.ok_or(Error::CorruptedTaskQueue)?,
@@ -557,6 +613,14 @@ impl IndexScheduler {
             return Ok(Some(Batch::SnapshotCreation(self.get_existing_tasks(rtxn, to_snapshot)?)));
         }
 
+        // 3.1 we batch the snapshot imports, a single import brings the replica up to date.
+        let to_import = self.get_kind(rtxn, Kind::SnapshotImportIncremental)? & enqueued;
+        if !to_import.is_empty() {
+            return Ok(Some(Batch::SnapshotImportIncremental(
+                self.get_existing_tasks(rtxn, to_import)?,
+            )));
+        }
+
         // 4. we batch the dumps.
         let to_dump = self.get_kind(rtxn, Kind::DumpCreation)? & enqueued;
         if let Some(to_dump) = to_dump.min() {
@@ -566,6 +630,21 @@ impl IndexScheduler {
         }
 
         // 5. We make a batch from the unprioritised tasks. Start by taking the next enqueued task.
+        // The tasks of the parked indexes are skipped, they stay enqueued until the index is unparked.
+        let mut enqueued = enqueued.clone();
+        for index_uid in self.panicked_indexes.read().unwrap().parked.keys() {
+            enqueued -= self.index_tasks(rtxn, index_uid)?;
+        }
+        // The tasks of the indexes with a task waiting for its next attempt are skipped too,
+        // so the tasks of an index are still processed in order.
+        for task_id in &waiting {
+            let task = self.get_task(rtxn, task_id)?.ok_or(Error::CorruptedTaskQueue)?;
+            for index_uid in task.indexes() {
+                enqueued -= self.index_tasks(rtxn, index_uid)?;
+            }
+        }
+        let enqueued = &enqueued;
+
         let task_id = if let Some(task_id) = enqueued.min() { task_id } else { return Ok(None) };
         let task = self.get_task(rtxn, task_id)?.ok_or(Error::CorruptedTaskQueue)?;
 
@@ -633,6 +712,7 @@ impl IndexScheduler {
         {
             self.maybe_fail(crate::tests::FailureLocation::InsideProcessBatch)?;
             self.maybe_fail(crate::tests::FailureLocation::PanicInsideProcessBatch)?;
+            self.maybe_fail(crate::tests::FailureLocation::TransientInsideProcessBatch)?;
             self.breakpoint(crate::Breakpoint::InsideProcessBatch);
         }
 
@@ -729,6 +809,7 @@ impl IndexScheduler {
                 Ok(tasks)
             }
             Batch::SnapshotCreation(mut tasks) => {
+                let progress = self.processing_tasks.read().unwrap().progress.clone();
                 fs::create_dir_all(&self.snapshots_path)?;
                 let temp_snapshot_dir = tempfile::tempdir()?;
 
@@ -749,9 +830,13 @@ impl IndexScheduler {
                 // two read operations as the task processing is synchronous.
 
                 // 2.1 First copy the LMDB env of the index-scheduler
+                // LMDB doesn't tell us how far it is in the copy so we report it all at once.
+                let total = self.env.real_disk_size()?;
+                let step = progress.start_step("copying the task queue", "bytes", Some(total));
                 let dst = temp_snapshot_dir.path().join("tasks");
                 fs::create_dir_all(&dst)?;
                 self.env.copy_to_file(dst.join("data.mdb"), CompactionOption::Enabled)?;
+                step.set(total);
 
                 // 2.2 Create a read transaction on the index-scheduler
                 let rtxn = self.env.read_txn()?;
@@ -761,22 +846,37 @@ impl IndexScheduler {
                 fs::create_dir_all(&update_files_dir)?;
 
                 // 2.4 Only copy the update files of the enqueued tasks
+                let mut content_uuids = Vec::new();
                 for task_id in self.get_status(&rtxn, Status::Enqueued)? {
                     let task = self.get_task(&rtxn, task_id)?.ok_or(Error::CorruptedTaskQueue)?;
-                    if let Some(content_uuid) = task.content_uuid() {
-                        let src = self.file_store.get_update_path(content_uuid);
-                        let dst = update_files_dir.join(content_uuid.to_string());
-                        fs::copy(src, dst)?;
-                    }
+                    content_uuids.extend(task.content_uuid());
+                }
+                let mut total = 0;
+                for content_uuid in &content_uuids {
+                    total += fs::metadata(self.file_store.get_update_path(*content_uuid))?.len();
+                }
+                let step = progress.start_step("copying the update files", "bytes", Some(total));
+                for content_uuid in content_uuids {
+                    let mut src = File::open(self.file_store.get_update_path(content_uuid))?;
+                    let dst = File::create(update_files_dir.join(content_uuid.to_string()))?;
+                    let mut dst = compression::ProgressWriter::new(dst, |bytes| step.add(bytes));
+                    io::copy(&mut src, &mut dst)?;
                 }
 
                 // 3. Snapshot every indexes
                 for result in self.index_mapper.index_mapping.iter(&rtxn)? {
                     let (name, uuid) = result?;
                     let index = self.index_mapper.index(&rtxn, name)?;
+                    let total = index.on_disk_size()?;
+                    let step = progress.start_step(
+                        format!("copying the index `{name}`"),
+                        "bytes",
+                        Some(total),
+                    );
                     let dst = temp_snapshot_dir.path().join("indexes").join(uuid.to_string());
                     fs::create_dir_all(&dst)?;
                     index.copy_to_file(dst.join("data.mdb"), CompactionOption::Enabled)?;
+                    step.set(total);
                 }
 
                 drop(rtxn);
@@ -789,19 +889,24 @@ impl IndexScheduler {
                     .map_size(1024 * 1024 * 1024) // 1 GiB
                     .max_dbs(2)
                     .open(&self.auth_path)?;
+                let total = auth.real_disk_size()?;
+                let step = progress.start_step("copying the API keys", "bytes", Some(total));
                 auth.copy_to_file(dst.join("data.mdb"), CompactionOption::Enabled)?;
+                step.set(total);
 
                 // 5. Copy and tarball the flat snapshot
-                // 5.1 Find the original name of the database
-                // TODO find a better way to get this path
-                let mut base_path = self.env.path().to_owned();
-                base_path.pop();
-                let db_name = base_path.file_name().and_then(OsStr::to_str).unwrap_or("data.ms");
+                // 5.1 Find the path of the snapshot from the original name of the database
+                let snapshot_path = self.snapshot_path();
 
                 // 5.2 Tarball the content of the snapshot in a tempfile with a .snapshot extension
-                let snapshot_path = self.snapshots_path.join(format!("{}.snapshot", db_name));
                 let temp_snapshot_file = tempfile::NamedTempFile::new_in(&self.snapshots_path)?;
-                compression::to_tar_gz(temp_snapshot_dir.path(), temp_snapshot_file.path())?;
+                let total = compression::dir_size(temp_snapshot_dir.path())?;
+                let step = progress.start_step("compressing the snapshot", "bytes", Some(total));
+                compression::to_tar_gz_with_progress(
+                    temp_snapshot_dir.path(),
+                    temp_snapshot_file.path(),
+                    |bytes| step.add(bytes),
+                )?;
                 let file = temp_snapshot_file.persist(snapshot_path)?;
 
                 // 5.3 Change the permission to make the snapshot readonly
@@ -823,7 +928,91 @@ impl IndexScheduler {
 
                 Ok(tasks)
             }
+            Batch::SnapshotImportIncremental(mut tasks) => {
+                let replication =
+                    self.replication.as_ref().ok_or(Error::ReplicationNotConfigured)?;
+                let temp_snapshot_dir = tempfile::tempdir()?;
+
+                // 1. Ask the primary for a new snapshot and download it.
+                let snapshot_path = temp_snapshot_dir.path().join("primary.snapshot");
+                let captured_at =
+                    replication.fetch_snapshot(&snapshot_path, &self.must_stop_processing)?;
+
+                // 2. Extract the version file, the index-scheduler env, and the indexes.
+                let snapshot_dir = temp_snapshot_dir.path().join("snapshot");
+                compression::from_tar_gz_filtered(&snapshot_path, &snapshot_dir, |path| {
+                    path == Path::new(VERSION_FILE_NAME)
+                        || path.starts_with("tasks")
+                        || path.starts_with("indexes")
+                })
+                .map_err(|e| replication.failure(e))?;
+                versioning::check_version_file(&snapshot_dir)
+                    .map_err(|e| replication.failure(e))?;
+                let primary_indexes = IndexMapper::indexes_in_env(&snapshot_dir.join("tasks"))?;
+
+                // 3. Install the indexes of the primary under new uuids and remove the ones that
+                //    were deleted on the primary, all in the same transaction, so that the searches
+                //    see either all the previous indexes or all the new ones.
+                let mut wtxn = self.env.write_txn()?;
+                let mut installed = Vec::with_capacity(primary_indexes.len());
+                let replaced = (|| -> Result<_> {
+                    let mut replaced = Vec::new();
+                    for (index_uid, uuid) in &primary_indexes {
+                        let data_path =
+                            snapshot_dir.join("indexes").join(uuid.to_string()).join("data.mdb");
+                        if !data_path.exists() {
+                            return Err(replication.failure(format!(
+                                "the environment of the index `{index_uid}` is missing."
+                            )));
+                        }
+                        let InstalledIndex { index, uuid, replaced: previous } =
+                            self.index_mapper.install_index(&mut wtxn, index_uid, &data_path)?;
+                        installed.push((index_uid.as_str(), uuid));
+                        replaced.extend(previous.map(|previous| (index_uid.clone(), previous)));
+
+                        let index_rtxn = index.read_txn()?;
+                        let stats = crate::index_mapper::IndexStats::new(&index, &index_rtxn)?;
+                        self.index_mapper.store_stats_of(&mut wtxn, index_uid, &stats)?;
+                    }
+
+                    for index_uid in self.index_mapper.index_names(&wtxn)? {
+                        if primary_indexes.iter().all(|(uid, _)| *uid != index_uid) {
+                            let uuid = self.index_mapper.unmap_index(&mut wtxn, &index_uid)?;
+                            replaced.push((index_uid, uuid));
+                        }
+                    }
+
+                    wtxn.commit()?;
+                    Ok(replaced)
+                })();
+
+                // 4. Remove the environments that are not mapped anymore, the previous ones are
+                //    only closed once the searches using them are done.
+                let replaced = match replaced {
+                    Ok(replaced) => replaced,
+                    Err(e) => {
+                        for (index_uid, uuid) in installed {
+                            self.index_mapper.delete_index_env(index_uid, uuid)?;
+                        }
+                        return Err(e);
+                    }
+                };
+                for (index_uid, uuid) in replaced {
+                    self.index_mapper.delete_index_env(&index_uid, uuid)?;
+                }
+
+                replication.record_sync(captured_at);
+                for task in &mut tasks {
+                    task.status = Status::Succeeded;
+                    task.details = Some(Details::SnapshotImport {
+                        imported_indexes: Some(primary_indexes.len() as u64),
+                    });
+                }
+
+                Ok(tasks)
+            }
             Batch::Dump(mut task) => {
+                let progress = self.processing_tasks.read().unwrap().progress.clone();
                 let started_at = OffsetDateTime::now_utc();
                 let (keys, instance_uid) =
                     if let KindWithContent::DumpCreation { keys, instance_uid } = &task.kind {
@@ -834,20 +1023,26 @@ impl IndexScheduler {
                 let dump = dump::DumpWriter::new(*instance_uid)?;
 
                 // 1. dump the keys
+                let step =
+                    progress.start_step("dumping the API keys", "keys", Some(keys.len() as u64));
                 let mut dump_keys = dump.create_keys()?;
                 for key in keys {
                     dump_keys.push_key(key)?;
+                    step.add(1);
                 }
                 dump_keys.flush()?;
 
                 let rtxn = self.env.read_txn()?;
 
                 // 2. dump the tasks
+                let total = self.all_tasks.len(&rtxn)?;
+                let step = progress.start_step("dumping the tasks", "tasks", Some(total));
                 let mut dump_tasks = dump.create_tasks_queue()?;
                 for ret in self.all_tasks.iter(&rtxn)? {
                     if self.must_stop_processing.get() {
                         return Err(Error::AbortedTask);
                     }
+                    step.add(1);
 
                     let (_, mut t) = ret?;
                     let status = t.status;
@@ -910,13 +1105,20 @@ impl IndexScheduler {
                     let all_fields: Vec<_> = fields_ids_map.iter().map(|(id, _)| id).collect();
 
                     // 3.1. Dump the documents
+                    let step = progress.start_step(
+                        format!("dumping the documents of the index `{uid}`"),
+                        "documents",
+                        Some(index.number_of_documents(&rtxn)?),
+                    );
                     for ret in index.all_documents(&rtxn)? {
                         if self.must_stop_processing.get() {
                             return Err(Error::AbortedTask);
                         }
                         let (_id, doc) = ret?;
-                        let document = milli::obkv_to_json(&all_fields, &fields_ids_map, doc)?;
+                        let document =
+                            milli::obkv_to_json(&all_fields, &fields_ids_map, doc.reader())?;
                         index_dumper.push_document(&document)?;
+                        step.add(1);
                     }
 
                     // 3.2. Dump the settings
@@ -942,7 +1144,12 @@ impl IndexScheduler {
                 }
                 let path = self.dumps_path.join(format!("{}.dump", dump_uid));
                 let file = File::create(path)?;
-                dump.persist_to(BufWriter::new(file))?;
+                let step = progress.start_step(
+                    "compressing the dump",
+                    "bytes",
+                    Some(dump.uncompressed_size()?),
+                );
+                dump.persist_to_with_progress(BufWriter::new(file), |bytes| step.add(bytes))?;
 
                 // if we reached this step we can tell the scheduler we succeeded to dump ourselves.
                 task.status = Status::Succeeded;
@@ -1050,7 +1257,7 @@ impl IndexScheduler {
                 Ok(vec![task])
             }
             Batch::IndexDeletion { index_uid, index_has_been_created, mut tasks } => {
-                let wtxn = self.env.write_txn()?;
+                let mut wtxn = self.env.write_txn()?;
 
                 // it's possible that the index doesn't exist
                 let number_of_documents = || -> Result<u64> {
@@ -1060,6 +1267,8 @@ impl IndexScheduler {
                 }()
                 .unwrap_or_default();
 
+                self.recent_searches.delete(&mut wtxn, &index_uid)?;
+
                 // The write transaction is directly owned and committed inside.
                 match self.index_mapper.delete_index(wtxn, &index_uid) {
                     Ok(()) => (),
@@ -1114,6 +1323,136 @@ impl IndexScheduler {
                 task.status = Status::Succeeded;
                 Ok(vec![task])
             }
+            Batch::IndexRestore { index_uid, mut task } => {
+                let (snapshot_path, snapshot_index_uid) = match &task.kind {
+                    KindWithContent::IndexRestore { snapshot_path, snapshot_index_uid, .. } => {
+                        (snapshot_path.clone(), snapshot_index_uid.clone())
+                    }
+                    _ => unreachable!(),
+                };
+                let invalid_snapshot =
+                    |reason: String| Error::InvalidSnapshot { path: snapshot_path.clone(), reason };
+                let temp_snapshot_dir = tempfile::tempdir()?;
+
+                // 1. Extract the version file and the index-scheduler env to find the index uuid.
+                compression::from_tar_gz_filtered(
+                    &snapshot_path,
+                    temp_snapshot_dir.path(),
+                    |path| path == Path::new(VERSION_FILE_NAME) || path.starts_with("tasks"),
+                )
+                .map_err(|e| invalid_snapshot(e.to_string()))?;
+                versioning::check_version_file(temp_snapshot_dir.path())
+                    .map_err(|e| invalid_snapshot(e.to_string()))?;
+                let tasks_path = temp_snapshot_dir.path().join("tasks");
+                if !tasks_path.join("data.mdb").exists() {
+                    return Err(invalid_snapshot(String::from("the task database is missing.")));
+                }
+                let uuid = IndexMapper::index_uuid_in_env(&tasks_path, &snapshot_index_uid)?
+                    .ok_or_else(|| Error::IndexNotFoundInSnapshot {
+                        index_uid: snapshot_index_uid.clone(),
+                        path: snapshot_path.clone(),
+                    })?;
+
+                // 2. Extract the environment of this index only.
+                let index_dir = Path::new("indexes").join(uuid.to_string());
+                compression::from_tar_gz_filtered(
+                    &snapshot_path,
+                    temp_snapshot_dir.path(),
+                    |path| path.starts_with(&index_dir),
+                )
+                .map_err(|e| invalid_snapshot(e.to_string()))?;
+                let data_path = temp_snapshot_dir.path().join(&index_dir).join("data.mdb");
+                if !data_path.exists() {
+                    return Err(invalid_snapshot(format!(
+                        "the environment of the index `{snapshot_index_uid}` is missing."
+                    )));
+                }
+
+                // 3. Install the index of the snapshot under a new uuid, the current index, if any,
+                //    is only replaced once it is installed and its stats are rebuilt.
+                let mut wtxn = self.env.write_txn()?;
+                let InstalledIndex { index, uuid, replaced } =
+                    self.index_mapper.install_index(&mut wtxn, &index_uid, &data_path)?;
+                let stats = (|| -> Result<_> {
+                    let index_rtxn = index.read_txn()?;
+                    let stats = crate::index_mapper::IndexStats::new(&index, &index_rtxn)?;
+                    self.index_mapper.store_stats_of(&mut wtxn, &index_uid, &stats)?;
+                    wtxn.commit()?;
+                    Ok(stats)
+                })();
+                drop(index);
+
+                // 4. Remove the environment that is not mapped anymore.
+                let stats = match stats {
+                    Ok(stats) => stats,
+                    Err(e) => {
+                        self.index_mapper.delete_index_env(&index_uid, uuid)?;
+                        return Err(e);
+                    }
+                };
+                if let Some(replaced) = replaced {
+                    self.index_mapper.delete_index_env(&index_uid, replaced)?;
+                }
+
+                task.status = Status::Succeeded;
+                task.details = Some(Details::IndexRestore {
+                    snapshot_path,
+                    snapshot_index_uid,
+                    restored_documents: Some(stats.number_of_documents),
+                });
+
+                Ok(vec![task])
+            }
+            Batch::IntegrityCheck { index_uid, repair, mut task } => {
+                let rtxn = self.env.read_txn()?;
+                let index = self.index_mapper.index(&rtxn, &index_uid)?;
+                // drop rtxn before starting a new wtxn on the same db
+                rtxn.commit()?;
+
+                let mut index_wtxn = index.write_txn()?;
+                let report = milli::update::IntegrityCheck::new(&mut index_wtxn, &index, repair)
+                    .execute()?;
+                if repair && !report.is_empty() {
+                    index_wtxn.commit()?;
+
+                    // The repair may have removed documents, the stats of the index are updated.
+                    let mut wtxn = self.env.write_txn()?;
+                    let index_rtxn = index.read_txn()?;
+                    let stats = crate::index_mapper::IndexStats::new(&index, &index_rtxn)?;
+                    self.index_mapper.store_stats_of(&mut wtxn, &index_uid, &stats)?;
+                    wtxn.commit()?;
+                } else {
+                    index_wtxn.abort();
+                }
+
+                task.status = Status::Succeeded;
+                task.details =
+                    Some(Details::IntegrityCheck { repair, violations: Some(report.violations) });
+
+                Ok(vec![task])
+            }
+            Batch::IndexWarmup { index_uid, prefixes, populate_cache, mut task } => {
+                let rtxn = self.env.read_txn()?;
+                let index = self.index_mapper.index(&rtxn, &index_uid)?;
+                // drop rtxn before starting a new wtxn on the same db
+                rtxn.commit()?;
+
+                // The warm-up only writes the stamp of the shared cache, but it must be
+                // done in a write transaction for no update to be committed meanwhile.
+                let mut index_wtxn = index.write_txn()?;
+                let stats =
+                    index.warm_up_search_cache(&mut index_wtxn, &prefixes, populate_cache)?;
+                index_wtxn.commit()?;
+
+                task.status = Status::Succeeded;
+                task.details = Some(Details::IndexWarmup {
+                    prefixes,
+                    populate_cache,
+                    warmed_entries: Some(stats.word_entries + stats.prefix_entries),
+                });
+
+                Ok(vec![task])
+            }
         }
     }
 
@@ -1159,6 +1498,15 @@ impl IndexScheduler {
         Ok(())
     }
 
+    /// The uid of the task and the number of tasks kept in the history of the documents
+    /// it modifies, when the history of the documents is recorded.
+    fn document_task(&self, task_uid: TaskId) -> Option<(TaskId, usize)> {
+        self.features()
+            .runtime_features()
+            .document_tasks
+            .then_some((task_uid, self.document_tasks_history_size))
+    }
+
     /// Process the index operation on the given index.
     ///
     /// ## Return
@@ -1251,44 +1599,72 @@ impl IndexScheduler {
                     || must_stop_processing.get(),
                 )?;
 
+                let mut last_indexing_errors = None;
                 for (operation, task) in operations.into_iter().zip(tasks.iter_mut()) {
+                    builder = builder.with_document_task(self.document_task(task.uid));
                     match operation {
                         DocumentOperation::Add(content_uuid) => {
                             let content_file = self.file_store.get_update(content_uuid)?;
                             let reader = DocumentsBatchReader::from_reader(content_file)
                                 .map_err(milli::Error::from)?;
-                            let (new_builder, user_result) = builder.add_documents(reader)?;
+                            let (new_builder, user_result) = builder.add_payload(reader)?;
                             builder = new_builder;
 
                             builder = builder.with_embedders(embedders.clone());
 
-                            let received_documents =
+                            let (received_documents, invalid_utf8_replacements) =
                                 if let Some(Details::DocumentAdditionOrUpdate {
                                     received_documents,
+                                    invalid_utf8_replacements,
                                     ..
                                 }) = task.details
                                 {
-                                    received_documents
+                                    (received_documents, invalid_utf8_replacements)
                                 } else {
                                     // In the case of a `documentAdditionOrUpdate` the details MUST be set
                                     unreachable!();
                                 };
 
                             match user_result {
-                                Ok(count) => {
+                                Ok(result) => {
                                     task.status = Status::Succeeded;
                                     task.details = Some(Details::DocumentAdditionOrUpdate {
                                         received_documents,
-                                        indexed_documents: Some(count),
+                                        indexed_documents: Some(result.indexed_documents),
+                                        duplicate_documents: result.duplicate_documents,
+                                        invalid_utf8_replacements,
+                                        indexing_errors: Vec::new(),
+                                        facet_update_strategies: BTreeMap::new(),
                                     })
                                 }
-                                Err(e) => {
+                                Err(InvalidDocuments { error, other_errors }) => {
+                                    let error = self
+                                        .redact_payloads(Error::from(milli::Error::from(error)));
+                                    let indexing_errors: Vec<_> =
+                                        std::iter::once(error.to_string())
+                                            .chain(other_errors.into_iter().map(|e| {
+                                                let e = Error::from(milli::Error::from(e));
+                                                self.redact_payloads(e).to_string()
+                                            }))
+                                            .collect();
+                                    // The error of the task is enough when a single document is invalid.
+                                    let details_errors = if indexing_errors.len() > 1 {
+                                        indexing_errors.clone()
+                                    } else {
+                                        Vec::new()
+                                    };
+                                    last_indexing_errors = Some(indexing_errors);
+
                                     task.status = Status::Failed;
                                     task.details = Some(Details::DocumentAdditionOrUpdate {
                                         received_documents,
                                         indexed_documents: Some(0),
+                                        duplicate_documents: 0,
+                                        invalid_utf8_replacements,
+                                        indexing_errors: details_errors,
+                                        facet_update_strategies: BTreeMap::new(),
                                     });
-                                    task.error = Some(milli::Error::from(e).into());
+                                    task.error = Some(error.into());
                                 }
                             }
                         }
@@ -1320,6 +1696,20 @@ impl IndexScheduler {
                 if !tasks.iter().all(|res| res.error.is_some()) {
                     let addition = builder.execute()?;
                     tracing::info!(indexing_result = ?addition, "document indexing done");
+
+                    let strategies = index.last_facet_update_strategies(index_wtxn)?;
+                    if !strategies.is_empty() {
+                        tracing::info!(?strategies, "facet update strategies");
+                    }
+                    for task in tasks.iter_mut().filter(|task| task.status == Status::Succeeded) {
+                        if let Some(Details::DocumentAdditionOrUpdate {
+                            facet_update_strategies,
+                            ..
+                        }) = &mut task.details
+                        {
+                            facet_update_strategies.clone_from(&strategies);
+                        }
+                    }
                 } else if primary_key_has_been_set {
                     // Everything failed but we've set a primary key.
                     // We need to remove it.
@@ -1332,6 +1722,11 @@ impl IndexScheduler {
                     )?;
                 }
 
+                // The errors of the last refused payload stay available in the stats of the index.
+                if let Some(errors) = last_indexing_errors {
+                    index.put_last_indexing_errors(index_wtxn, &errors)?;
+                }
+
                 Ok(tasks)
             }
             IndexOperation::IndexDocumentDeletionByFilter { mut task, index_uid: _ } => {
@@ -1346,6 +1741,7 @@ impl IndexScheduler {
                 let deleted_documents = delete_document_by_filter(
                     index_wtxn,
                     filter,
+                    self.document_task(task.uid),
                     self.index_mapper.indexer_config(),
                     self.must_stop_processing.clone(),
                     index,
@@ -1375,32 +1771,78 @@ impl IndexScheduler {
                             original_filter,
                             deleted_documents: Some(0),
                         });
-                        task.error = Some(e.into());
+                        task.error = Some(self.redact_payloads(e).into());
                     }
                 }
 
                 Ok(vec![task])
             }
             IndexOperation::Settings { index_uid: _, settings, mut tasks } => {
-                let indexer_config = self.index_mapper.indexer_config();
-                let mut builder = milli::update::Settings::new(index_wtxn, index, indexer_config);
+                let previous_sortable_fields = index.sortable_fields(index_wtxn)?;
+                let previous_filterable_fields = index.filterable_fields(index_wtxn)?;
 
+                let mut checked_settings = Vec::with_capacity(tasks.len());
                 for (task, (_, settings)) in tasks.iter_mut().zip(settings) {
-                    let checked_settings = settings.clone().check();
-                    task.details = Some(Details::SettingsUpdate { settings: Box::new(settings) });
-                    apply_settings_to_builder(&checked_settings, &mut builder);
+                    checked_settings.push(settings.clone().check());
+                    task.details = Some(Details::SettingsUpdate {
+                        settings: Box::new(settings),
+                        warnings: Vec::new(),
+                    });
 
                     // We can apply the status right now and if an update fail later
                     // the whole batch will be marked as failed.
                     task.status = Status::Succeeded;
                 }
 
+                // the limits are checked before indexing anything
+                self.check_attributes_limits(index_wtxn, index, &mut tasks, &checked_settings)?;
+
+                let indexer_config = self.index_mapper.indexer_config();
+                let mut builder = milli::update::Settings::new(index_wtxn, index, indexer_config);
+                for checked_settings in &checked_settings {
+                    apply_settings_to_builder(checked_settings, &mut builder);
+                }
+
                 let must_stop_processing = self.must_stop_processing.clone();
                 builder.execute(
                     |indexing_step| tracing::debug!(update = ?indexing_step),
                     || must_stop_processing.get(),
                 )?;
 
+                let warnings = search_defaults_warnings(
+                    index_wtxn,
+                    index,
+                    &previous_sortable_fields,
+                    &previous_filterable_fields,
+                )?;
+                if !warnings.is_empty() {
+                    // only the tasks that changed the sortable or filterable attributes are concerned
+                    for task in tasks.iter_mut() {
+                        if let Some(Details::SettingsUpdate { settings, warnings: task_warnings }) =
+                            &mut task.details
+                        {
+                            if !settings.sortable_attributes.is_not_set()
+                                || !settings.filterable_attributes.is_not_set()
+                            {
+                                task_warnings.clone_from(&warnings);
+                            }
+                        }
+                    }
+                }
+
+                if let Some(note) = implicit_distinct_facet_note(index_wtxn, index)? {
+                    // only the tasks setting the distinct attribute are concerned
+                    for task in tasks.iter_mut() {
+                        if let Some(Details::SettingsUpdate { settings, warnings }) =
+                            &mut task.details
+                        {
+                            if settings.distinct_attribute.as_ref().set().is_some() {
+                                warnings.push(note.clone());
+                            }
+                        }
+                    }
+                }
+
                 Ok(tasks)
             }
             IndexOperation::SettingsAndDocumentOperation {
@@ -1471,7 +1913,7 @@ impl IndexScheduler {
     /// Delete each given task from all the databases (if it is deleteable).
     ///
     /// Return the number of tasks that were actually deleted.
-    fn delete_matched_tasks(
+    pub(crate) fn delete_matched_tasks(
         &self,
         wtxn: &mut RwTxn,
         matched_tasks: &RoaringBitmap,
@@ -1581,11 +2023,111 @@ impl IndexScheduler {
 
         Ok(content_files_to_delete)
     }
+
+    /// Counts the filterable, sortable, and searchable attributes the index will have once the
+    /// `settings` of the `tasks` are applied, and compares them to the attributes limits.
+    /// Only the capabilities changed by at least one of the `settings` are checked.
+    ///
+    /// The attributes are expanded against the fields the index currently knows: `*` counts for
+    /// all of them and an attribute counts for every field nested under it.
+    /// Exceeding the soft limit adds a warning to the tasks that changed these attributes,
+    /// exceeding the hard limit returns an error that fails the whole batch.
+    fn check_attributes_limits(
+        &self,
+        rtxn: &RoTxn,
+        index: &Index,
+        tasks: &mut [Task],
+        settings: &[Settings<Checked>],
+    ) -> Result<()> {
+        let mut filterable = Some(index.filterable_fields(rtxn)?.into_iter().collect());
+        let mut sortable = Some(index.sortable_fields(rtxn)?.into_iter().collect());
+        let mut searchable = index
+            .user_defined_searchable_fields(rtxn)?
+            .map(|fields| fields.into_iter().map(String::from).collect());
+        for settings in settings {
+            filterable = updated_attributes(
+                filterable,
+                settings.filterable_attributes.as_ref(),
+                Some(vec![]),
+            );
+            sortable =
+                updated_attributes(sortable, settings.sortable_attributes.as_ref(), Some(vec![]));
+            searchable =
+                updated_attributes(searchable, settings.searchable_attributes.as_ref(), None);
+        }
+
+        let fields_ids_map = index.fields_ids_map(rtxn)?;
+        let capabilities: [(
+            &'static str,
+            Option<Vec<String>>,
+            Code,
+            fn(&Settings<Checked>) -> bool,
+        ); 3] = [
+            ("filterable", filterable, Code::InvalidSettingsFilterableAttributes, |settings| {
+                !settings.filterable_attributes.is_not_set()
+            }),
+            ("sortable", sortable, Code::InvalidSettingsSortableAttributes, |settings| {
+                !settings.sortable_attributes.is_not_set()
+            }),
+            ("searchable", searchable, Code::InvalidSettingsSearchableAttributes, |settings| {
+                !settings.searchable_attributes.is_not_set()
+            }),
+        ];
+
+        for (capability, attributes, code, is_changed_by) in capabilities {
+            // an index that already exceeds a limit must not block the updates of the other settings
+            if !settings.iter().any(is_changed_by) {
+                continue;
+            }
+
+            let count = match attributes {
+                None => fields_ids_map.len(),
+                Some(attributes) => {
+                    // an attribute that doesn't match any field yet still counts for one
+                    let unknown_attributes = attributes
+                        .iter()
+                        .filter(|attribute| {
+                            !fields_ids_map
+                                .names()
+                                .any(|field| milli::is_faceted_by(field, attribute))
+                        })
+                        .count();
+                    fields_ids_map
+                        .names()
+                        .filter(|field| milli::is_faceted(field, &attributes))
+                        .count()
+                        + unknown_attributes
+                }
+            };
+
+            if let Some(limit) = self.attributes_hard_limit.filter(|limit| count > *limit) {
+                return Err(Error::TooManyAttributes { capability, count, limit }
+                    .with_custom_error_code(code));
+            }
+
+            if count > self.attributes_soft_limit {
+                let warning = format!(
+                    "The index has {count} {capability} attributes, which exceeds the recommended limit of {}.",
+                    self.attributes_soft_limit
+                );
+                for (task, settings) in tasks.iter_mut().zip(settings) {
+                    if let Some(Details::SettingsUpdate { warnings, .. }) = &mut task.details {
+                        if is_changed_by(settings) {
+                            warnings.push(warning.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 fn delete_document_by_filter<'a>(
     wtxn: &mut RwTxn<'a>,
     filter: &serde_json::Value,
+    document_task: Option<(TaskId, usize)>,
     indexer_config: &IndexerConfig,
     must_stop_processing: MustStopProcessing,
     index: &'a Index,
@@ -1611,7 +2153,8 @@ fn delete_document_by_filter<'a>(
             config,
             |indexing_step| tracing::debug!(update = ?indexing_step),
             || must_stop_processing.get(),
-        )?;
+        )?
+        .with_document_task(document_task);
 
         let (new_builder, count) = builder.remove_documents_from_db_no_batch(&candidates)?;
         builder = new_builder;
@@ -1622,3 +2165,96 @@ fn delete_document_by_filter<'a>(
         0
     })
 }
+
+/// Returns the attributes once the `setting` is applied, `None` standing for all the fields.
+fn updated_attributes<'a>(
+    current: Option<Vec<String>>,
+    setting: Setting<impl IntoIterator<Item = &'a String>>,
+    default: Option<Vec<String>>,
+) -> Option<Vec<String>> {
+    match setting {
+        Setting::Set(attributes) => Some(attributes.into_iter().cloned().collect()),
+        Setting::Reset => default,
+        Setting::NotSet => current,
+    }
+}
+
+/// Returns a warning for each field referenced by the `searchDefaults` of the index that stopped
+/// being sortable or filterable with the settings that were just applied.
+fn search_defaults_warnings(
+    rtxn: &RoTxn,
+    index: &Index,
+    previous_sortable_fields: &HashSet<String>,
+    previous_filterable_fields: &HashSet<String>,
+) -> Result<Vec<String>> {
+    let Some(defaults) = index.search_defaults(rtxn)? else { return Ok(Vec::new()) };
+    let sortable_fields = index.sortable_fields(rtxn)?;
+    let filterable_fields = index.filterable_fields(rtxn)?;
+
+    let mut warnings = Vec::new();
+
+    let sort = defaults.get("sort").and_then(serde_json::Value::as_array);
+    let sorted_fields: BTreeSet<_> = sort
+        .into_iter()
+        .flatten()
+        .filter_map(|sort| sort.as_str()?.parse::<AscDesc>().ok())
+        .filter_map(|asc_desc| match asc_desc {
+            AscDesc::Asc(Member::Field(field)) | AscDesc::Desc(Member::Field(field)) => Some(field),
+            _ => None,
+        })
+        .collect();
+    for field in sorted_fields {
+        if previous_sortable_fields.contains(&field) && !sortable_fields.contains(&field) {
+            warnings.push(format!(
+                "The `sort` of the `searchDefaults` references `{field}`, which is no longer a sortable attribute."
+            ));
+        }
+    }
+
+    // an invalid filter has already been reported when the search defaults were set
+    let mut filtered_fields = BTreeSet::new();
+    if let Some(Ok(Some(filter))) = defaults.get("filter").map(Filter::from_json) {
+        fields_of_filter(&FilterCondition::from(filter), &mut filtered_fields);
+    }
+    for field in filtered_fields {
+        if milli::is_faceted(&field, previous_filterable_fields)
+            && !milli::is_faceted(&field, &filterable_fields)
+        {
+            warnings.push(format!(
+                "The `filter` of the `searchDefaults` references `{field}`, which is no longer a filterable attribute."
+            ));
+        }
+    }
+
+    Ok(warnings)
+}
+
+/// Returns a note if the distinct attribute of the index is neither filterable nor sortable,
+/// in which case its facet databases are built implicitly.
+fn implicit_distinct_facet_note(rtxn: &RoTxn, index: &Index) -> Result<Option<String>> {
+    let Some(distinct) = index.distinct_field(rtxn)? else { return Ok(None) };
+    if milli::is_faceted(distinct, &index.filterable_fields(rtxn)?)
+        || milli::is_faceted(distinct, &index.sortable_fields(rtxn)?)
+    {
+        return Ok(None);
+    }
+
+    Ok(Some(format!(
+        "The distinct attribute `{distinct}` is not filterable, its values were implicitly indexed as facets."
+    )))
+}
+
+fn fields_of_filter(condition: &FilterCondition, fields: &mut BTreeSet<String>) {
+    match condition {
+        FilterCondition::Not(condition) => fields_of_filter(condition, fields),
+        FilterCondition::Condition { fid, .. } | FilterCondition::In { fid, .. } => {
+            fields.insert(fid.value().to_string());
+        }
+        FilterCondition::Or(conditions) | FilterCondition::And(conditions) => {
+            conditions.iter().for_each(|condition| fields_of_filter(condition, fields))
+        }
+        FilterCondition::GeoLowerThan { .. } | FilterCondition::GeoBoundingBox { .. } => {
+            fields.insert("_geo".to_string());
+        }
+    }
+}
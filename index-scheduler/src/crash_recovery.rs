@@ -0,0 +1,96 @@
+use meilisearch_types::heed::types::{Bytes, SerdeJson, Str};
+use meilisearch_types::heed::{Database, Env, RoTxn, RwTxn};
+use meilisearch_types::tasks::TaskId;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::Result;
+
+const CRASH_RECOVERY: &str = "crash-recovery";
+
+const IN_FLIGHT_BATCH: &str = "in-flight-batch";
+const LAST_RECOVERY: &str = "last-recovery";
+
+/// The batch being processed, written before processing it and deleted in the same
+/// transaction as the result of its tasks.
+///
+/// Finding it when the scheduler starts means that the instance stopped while processing
+/// this batch, and that the result of its tasks was never committed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct InFlightBatch {
+    pub task_uids: Vec<TaskId>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub started_at: OffsetDateTime,
+}
+
+/// What was done with the tasks of the batch that was processing when the instance stopped unexpectedly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecoveryAction {
+    /// The tasks were marked as failed with the `crashed_during_processing` error.
+    Failed,
+    /// The tasks were enqueued again.
+    Reenqueued,
+}
+
+/// The report of the last recovery from a batch that was processing when the instance stopped unexpectedly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashRecoveryReport {
+    /// The uids of the tasks of the batch that were still enqueued.
+    pub task_uids: Vec<TaskId>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub batch_started_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub recovered_at: OffsetDateTime,
+    pub action: RecoveryAction,
+}
+
+#[derive(Clone)]
+pub(crate) struct CrashRecovery {
+    /// The in-flight batch and the last recovery report, stored as JSON.
+    records: Database<Str, Bytes>,
+}
+
+impl CrashRecovery {
+    pub fn new(env: &Env) -> Result<Self> {
+        let mut wtxn = env.write_txn()?;
+        let records = env.create_database(&mut wtxn, Some(CRASH_RECOVERY))?;
+        wtxn.commit()?;
+
+        Ok(Self { records })
+    }
+
+    pub fn in_flight_batch(&self, rtxn: &RoTxn) -> Result<Option<InFlightBatch>> {
+        Ok(self.records.remap_data_type::<SerdeJson<InFlightBatch>>().get(rtxn, IN_FLIGHT_BATCH)?)
+    }
+
+    pub fn put_in_flight_batch(&self, wtxn: &mut RwTxn, batch: &InFlightBatch) -> Result<()> {
+        Ok(self.records.remap_data_type::<SerdeJson<InFlightBatch>>().put(
+            wtxn,
+            IN_FLIGHT_BATCH,
+            batch,
+        )?)
+    }
+
+    pub fn delete_in_flight_batch(&self, wtxn: &mut RwTxn) -> Result<()> {
+        self.records.delete(wtxn, IN_FLIGHT_BATCH)?;
+        Ok(())
+    }
+
+    pub fn last_recovery(&self, rtxn: &RoTxn) -> Result<Option<CrashRecoveryReport>> {
+        Ok(self
+            .records
+            .remap_data_type::<SerdeJson<CrashRecoveryReport>>()
+            .get(rtxn, LAST_RECOVERY)?)
+    }
+
+    pub fn put_last_recovery(&self, wtxn: &mut RwTxn, report: &CrashRecoveryReport) -> Result<()> {
+        Ok(self.records.remap_data_type::<SerdeJson<CrashRecoveryReport>>().put(
+            wtxn,
+            LAST_RECOVERY,
+            report,
+        )?)
+    }
+}
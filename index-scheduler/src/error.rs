@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
 use meilisearch_types::error::{Code, ErrorCode};
+use meilisearch_types::index_uid::IndexUidFormatError;
 use meilisearch_types::tasks::{Kind, Status};
 use meilisearch_types::{heed, milli};
 use thiserror::Error;
@@ -67,6 +68,8 @@ pub enum Error {
     SwapIndexNotFound(String),
     #[error("Meilisearch cannot receive write operations because the limit of the task database has been reached. Please delete tasks to continue performing write operations.")]
     NoSpaceLeftInTaskQueue,
+    #[error("Meilisearch cannot receive write operations because {enqueued} tasks are enqueued, which reaches the limit of {limit} enqueued tasks. Please retry in {retry_after} seconds.")]
+    TooManyEnqueuedTasks { enqueued: u64, limit: u64, retry_after: u64 },
     #[error(
         "Indexes {} not found.",
         .0.iter().map(|s| format!("`{}`", s)).collect::<Vec<_>>().join(", ")
@@ -100,18 +103,38 @@ pub enum Error {
         "Task canceledBy `{canceled_by}` is invalid. It should only contains numeric characters separated by `,` character."
     )]
     InvalidTaskCanceledBy { canceled_by: String },
-    #[error(
-        "{index_uid} is not a valid index uid. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_)."
-    )]
-    InvalidIndexUid { index_uid: String },
+    #[error(transparent)]
+    InvalidIndexUid(#[from] IndexUidFormatError),
     #[error("Task `{0}` not found.")]
     TaskNotFound(TaskId),
+    #[error("No task webhook is configured on this instance.")]
+    WebhookNotFound,
+    #[error("The task webhook `{url}` did not receive the payload: {reason}")]
+    WebhookDeliveryFailed { url: String, reason: String },
+    #[error("Index group `{0}` not found.")]
+    IndexGroupNotFound(String),
     #[error("Query parameters to filter the tasks to delete are missing. Available query parameters are: `uids`, `indexUids`, `statuses`, `types`, `canceledBy`, `beforeEnqueuedAt`, `afterEnqueuedAt`, `beforeStartedAt`, `afterStartedAt`, `beforeFinishedAt`, `afterFinishedAt`.")]
     TaskDeletionWithEmptyQuery,
     #[error("Query parameters to filter the tasks to cancel are missing. Available query parameters are: `uids`, `indexUids`, `statuses`, `types`, `canceledBy`, `beforeEnqueuedAt`, `afterEnqueuedAt`, `beforeStartedAt`, `afterStartedAt`, `beforeFinishedAt`, `afterFinishedAt`.")]
     TaskCancelationWithEmptyQuery,
+    #[error("The index would have {count} {capability} attributes, which exceeds the limit of {limit} {capability} attributes.")]
+    TooManyAttributes { capability: &'static str, count: usize, limit: usize },
     #[error("Aborted task")]
     AbortedTask,
+    #[error("The snapshot `{path}` cannot be restored: {reason}")]
+    InvalidSnapshot { path: String, reason: String },
+    #[error("Index `{index_uid}` not found in the snapshot `{path}`.")]
+    IndexNotFoundInSnapshot { index_uid: String, path: String },
+    #[error("This instance is a read-only replica, `{0}` tasks cannot be enqueued on it.")]
+    ReadOnlyReplica(Kind),
+    #[error(
+        "This instance is not a replica, there is no primary instance to import a snapshot from."
+    )]
+    ReplicationNotConfigured,
+    #[error("Could not import a snapshot of the primary instance `{primary_url}`: {reason}")]
+    ReplicationFailed { primary_url: String, reason: String },
+    #[error(transparent)]
+    OutboundHostNotAllowed(#[from] milli::outbound::OutboundHostNotAllowed),
 
     #[error(transparent)]
     Dump(#[from] dump::Error),
@@ -119,8 +142,10 @@ pub enum Error {
     Heed(#[from] heed::Error),
     #[error(transparent)]
     Milli(#[from] milli::Error),
-    #[error("An unexpected crash occurred when processing the task.")]
-    ProcessBatchPanicked,
+    #[error("An unexpected crash occurred when processing the task: {0}")]
+    ProcessBatchPanicked(String),
+    #[error("Meilisearch stopped unexpectedly while processing the task.")]
+    CrashedDuringProcessing,
     #[error(transparent)]
     FileStore(#[from] file_store::Error),
     #[error(transparent)]
@@ -146,6 +171,9 @@ pub enum Error {
     #[cfg(test)]
     #[error("Planned failure for tests.")]
     PlannedFailure,
+    #[cfg(test)]
+    #[error("Planned transient failure for tests.")]
+    PlannedTransientFailure,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -169,6 +197,7 @@ impl Error {
             | Error::SwapDuplicateIndexesFound(_)
             | Error::SwapIndexNotFound(_)
             | Error::NoSpaceLeftInTaskQueue
+            | Error::TooManyEnqueuedTasks { .. }
             | Error::SwapIndexesNotFound(_)
             | Error::CorruptedDump
             | Error::InvalidTaskDate { .. }
@@ -176,15 +205,26 @@ impl Error {
             | Error::InvalidTaskStatuses { .. }
             | Error::InvalidTaskTypes { .. }
             | Error::InvalidTaskCanceledBy { .. }
-            | Error::InvalidIndexUid { .. }
+            | Error::InvalidIndexUid(_)
             | Error::TaskNotFound(_)
+            | Error::WebhookNotFound
+            | Error::WebhookDeliveryFailed { .. }
+            | Error::IndexGroupNotFound(_)
             | Error::TaskDeletionWithEmptyQuery
             | Error::TaskCancelationWithEmptyQuery
+            | Error::TooManyAttributes { .. }
             | Error::AbortedTask
+            | Error::InvalidSnapshot { .. }
+            | Error::IndexNotFoundInSnapshot { .. }
+            | Error::ReadOnlyReplica(_)
+            | Error::ReplicationNotConfigured
+            | Error::ReplicationFailed { .. }
+            | Error::OutboundHostNotAllowed(_)
             | Error::Dump(_)
             | Error::Heed(_)
             | Error::Milli(_)
-            | Error::ProcessBatchPanicked
+            | Error::ProcessBatchPanicked(_)
+            | Error::CrashedDuringProcessing
             | Error::FileStore(_)
             | Error::IoError(_)
             | Error::Persist(_)
@@ -195,7 +235,31 @@ impl Error {
             | Error::TaskDatabaseUpdate(_)
             | Error::HeedTransaction(_) => false,
             #[cfg(test)]
-            Error::PlannedFailure => false,
+            Error::PlannedFailure | Error::PlannedTransientFailure => false,
+        }
+    }
+
+    /// Truncates the long document values echoed in the message of this error.
+    pub fn redact_payloads(self) -> Self {
+        match self {
+            Error::WithCustomErrorCode(code, error) => {
+                Error::WithCustomErrorCode(code, Box::new(error.redact_payloads()))
+            }
+            Error::Milli(error) => Error::Milli(error.redact_payloads()),
+            error => error,
+        }
+    }
+
+    /// Whether a batch failed because of a condition that may not happen again,
+    /// in which case its tasks are attempted again later instead of being marked as failed.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::WithCustomErrorCode(_, error) => error.is_transient(),
+            Error::Milli(error) => error.is_transient(),
+            Error::IoError(_) => true,
+            #[cfg(test)]
+            Error::PlannedTransientFailure => true,
+            _ => false,
         }
     }
 
@@ -220,15 +284,26 @@ impl ErrorCode for Error {
             Error::InvalidTaskStatuses { .. } => Code::InvalidTaskStatuses,
             Error::InvalidTaskTypes { .. } => Code::InvalidTaskTypes,
             Error::InvalidTaskCanceledBy { .. } => Code::InvalidTaskCanceledBy,
-            Error::InvalidIndexUid { .. } => Code::InvalidIndexUid,
+            Error::InvalidIndexUid(e) => e.error_code(),
             Error::TaskNotFound(_) => Code::TaskNotFound,
+            Error::WebhookNotFound => Code::WebhookNotFound,
+            Error::WebhookDeliveryFailed { .. } => Code::WebhookDeliveryFailed,
+            Error::IndexGroupNotFound(_) => Code::IndexGroupNotFound,
             Error::TaskDeletionWithEmptyQuery => Code::MissingTaskFilters,
             Error::TaskCancelationWithEmptyQuery => Code::MissingTaskFilters,
-            // TODO: not sure of the Code to use
-            Error::NoSpaceLeftInTaskQueue => Code::NoSpaceLeftOnDevice,
+            Error::NoSpaceLeftInTaskQueue => Code::TaskQueueFull,
+            Error::TooManyEnqueuedTasks { .. } => Code::TooManyEnqueuedTasks,
+            Error::TooManyAttributes { .. } => Code::BadRequest,
+            Error::InvalidSnapshot { .. } => Code::InvalidSnapshot,
+            Error::IndexNotFoundInSnapshot { .. } => Code::IndexNotFound,
+            Error::ReadOnlyReplica(_) => Code::ReadOnlyReplica,
+            Error::ReplicationNotConfigured => Code::ReplicationNotConfigured,
+            Error::ReplicationFailed { .. } => Code::ReplicationFailed,
+            Error::OutboundHostNotAllowed(_) => Code::OutboundHostNotAllowed,
             Error::Dump(e) => e.error_code(),
             Error::Milli(e) => e.error_code(),
-            Error::ProcessBatchPanicked => Code::Internal,
+            Error::ProcessBatchPanicked(_) => Code::Internal,
+            Error::CrashedDuringProcessing => Code::CrashedDuringProcessing,
             Error::Heed(e) => e.error_code(),
             Error::HeedTransaction(e) => e.error_code(),
             Error::FileStore(e) => e.error_code(),
@@ -247,7 +322,15 @@ impl ErrorCode for Error {
             Error::AbortedTask => Code::Internal,
 
             #[cfg(test)]
-            Error::PlannedFailure => Code::Internal,
+            Error::PlannedFailure | Error::PlannedTransientFailure => Code::Internal,
+        }
+    }
+
+    fn retry_after(&self) -> Option<u64> {
+        match self {
+            Error::TooManyEnqueuedTasks { retry_after, .. } => Some(*retry_after),
+            Error::WithCustomErrorCode(_, e) => e.retry_after(),
+            _ => None,
         }
     }
 }
@@ -81,6 +81,85 @@ impl RoFeatures {
             .into())
         }
     }
+
+    pub fn check_debug_routes(&self) -> Result<()> {
+        if self.runtime.debug_routes {
+            Ok(())
+        } else {
+            Err(FeatureNotEnabledError {
+                disabled_action: "Using the `/indexes/{indexUid}/_debug/*` routes",
+                feature: "debug routes",
+                issue_link: "https://www.meilisearch.com/docs/learn/experimental/overview",
+            }
+            .into())
+        }
+    }
+
+    pub fn check_relevance_check(&self) -> Result<()> {
+        if self.runtime.relevance_check {
+            Ok(())
+        } else {
+            Err(FeatureNotEnabledError {
+                disabled_action: "Using the `/indexes/{indexUid}/relevance-check` route",
+                feature: "relevance check",
+                issue_link: "https://www.meilisearch.com/docs/learn/experimental/overview",
+            }
+            .into())
+        }
+    }
+
+    pub fn check_word_frequency(&self) -> Result<()> {
+        if self.runtime.word_frequency {
+            Ok(())
+        } else {
+            Err(FeatureNotEnabledError {
+                disabled_action: "Using the `/indexes/{indexUid}/word-frequency` route",
+                feature: "word frequency",
+                issue_link: "https://www.meilisearch.com/docs/learn/experimental/overview",
+            }
+            .into())
+        }
+    }
+
+    pub fn check_document_tasks(&self) -> Result<()> {
+        if self.runtime.document_tasks {
+            Ok(())
+        } else {
+            Err(FeatureNotEnabledError {
+                disabled_action:
+                    "Using the `/indexes/{indexUid}/documents/{documentId}/tasks` route",
+                feature: "document tasks",
+                issue_link: "https://www.meilisearch.com/docs/learn/experimental/overview",
+            }
+            .into())
+        }
+    }
+
+    pub fn check_recent_searches(&self) -> Result<()> {
+        if self.runtime.recent_searches {
+            Ok(())
+        } else {
+            Err(FeatureNotEnabledError {
+                disabled_action: "Using the `/indexes/{indexUid}/recent-searches` route",
+                feature: "recent searches",
+                issue_link: "https://www.meilisearch.com/docs/learn/experimental/overview",
+            }
+            .into())
+        }
+    }
+
+    pub fn check_ranking_rules_override(&self) -> Result<()> {
+        if self.runtime.ranking_rules_override {
+            Ok(())
+        } else {
+            Err(FeatureNotEnabledError {
+                disabled_action: "Passing `rankingRulesOverride` as a search parameter",
+                feature: "ranking rules override",
+                issue_link: "https://www.meilisearch.com/docs/learn/experimental/overview",
+            }
+            .into())
+        }
+    }
 }
 
 impl FeatureData {
@@ -95,6 +174,8 @@ impl FeatureData {
         let runtime = Arc::new(RwLock::new(RuntimeTogglableFeatures {
             metrics: instance_features.metrics || persisted_features.metrics,
             logs_route: instance_features.logs_route || persisted_features.logs_route,
+            ranking_rules_override: instance_features.ranking_rules_override
+                || persisted_features.ranking_rules_override,
             ..persisted_features
         }));
 
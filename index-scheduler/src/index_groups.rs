@@ -0,0 +1,49 @@
+use std::collections::BTreeMap;
+
+use meilisearch_types::heed::types::{SerdeJson, Str};
+use meilisearch_types::heed::{Database, Env, RoTxn, RwTxn};
+use meilisearch_types::index_uid_pattern::IndexUidPattern;
+
+use crate::Result;
+
+const INDEX_GROUPS: &str = "index-groups";
+
+/// The named groups of index uid patterns, referenced by the API keys, the tasks filter and the stats.
+#[derive(Clone)]
+pub(crate) struct IndexGroups {
+    /// The index uid patterns of each group, by group name.
+    groups: Database<Str, SerdeJson<Vec<IndexUidPattern>>>,
+}
+
+impl IndexGroups {
+    pub fn new(env: &Env) -> Result<Self> {
+        let mut wtxn = env.write_txn()?;
+        let groups = env.create_database(&mut wtxn, Some(INDEX_GROUPS))?;
+        wtxn.commit()?;
+
+        Ok(Self { groups })
+    }
+
+    pub fn all(&self, rtxn: &RoTxn) -> Result<BTreeMap<String, Vec<IndexUidPattern>>> {
+        let mut groups = BTreeMap::new();
+        for result in self.groups.iter(rtxn)? {
+            let (name, patterns) = result?;
+            groups.insert(name.to_string(), patterns);
+        }
+        Ok(groups)
+    }
+
+    pub fn get(&self, rtxn: &RoTxn, name: &str) -> Result<Option<Vec<IndexUidPattern>>> {
+        Ok(self.groups.get(rtxn, name)?)
+    }
+
+    /// Creates the group or replaces its patterns if it already exists.
+    pub fn put(&self, wtxn: &mut RwTxn, name: &str, patterns: &Vec<IndexUidPattern>) -> Result<()> {
+        Ok(self.groups.put(wtxn, name, patterns)?)
+    }
+
+    /// Returns `false` if there was no group with this name.
+    pub fn delete(&self, wtxn: &mut RwTxn, name: &str) -> Result<bool> {
+        Ok(self.groups.delete(wtxn, name)?)
+    }
+}
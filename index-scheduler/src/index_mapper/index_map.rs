@@ -3,7 +3,7 @@ use std::path::Path;
 use std::time::Duration;
 
 use meilisearch_types::heed::{EnvClosingEvent, EnvFlags, EnvOpenOptions};
-use meilisearch_types::milli::Index;
+use meilisearch_types::milli::{EncryptionKey, Index};
 use time::OffsetDateTime;
 use uuid::Uuid;
 
@@ -44,6 +44,12 @@ pub struct IndexMap {
     /// closing request was made, so the reader that "lost the race" has the old generation and will need to wait again for the index
     /// to close.
     generation: usize,
+
+    /// The key the documents of the indexes are encrypted with, if any.
+    encryption_key: Option<EncryptionKey>,
+
+    /// Whether the indexes are opened without flushing them to disk after each commit.
+    no_sync: bool,
 }
 
 #[derive(Clone)]
@@ -132,8 +138,14 @@ impl ReopenableIndex {
 }
 
 impl IndexMap {
-    pub fn new(cap: usize) -> IndexMap {
-        Self { unavailable: Default::default(), available: LruMap::new(cap), generation: 0 }
+    pub fn new(cap: usize, encryption_key: Option<EncryptionKey>, no_sync: bool) -> IndexMap {
+        Self {
+            unavailable: Default::default(),
+            available: LruMap::new(cap),
+            generation: 0,
+            encryption_key,
+            no_sync,
+        }
     }
 
     /// Gets the current status of an index in the map.
@@ -176,7 +188,14 @@ impl IndexMap {
         if !matches!(self.get_unavailable(uuid), Missing) {
             panic!("Attempt to open an index that was unavailable");
         }
-        let index = create_or_open_index(path, date, enable_mdb_writemap, map_size)?;
+        let index = create_or_open_index(
+            path,
+            date,
+            enable_mdb_writemap,
+            self.no_sync,
+            map_size,
+            self.encryption_key.clone(),
+        )?;
         match self.available.insert(*uuid, index.clone()) {
             InsertionOutcome::InsertedNew => (),
             InsertionOutcome::Evicted(evicted_uuid, evicted_index) => {
@@ -299,20 +318,27 @@ fn create_or_open_index(
     path: &Path,
     date: Option<(OffsetDateTime, OffsetDateTime)>,
     enable_mdb_writemap: bool,
+    no_sync: bool,
     map_size: usize,
+    encryption_key: Option<EncryptionKey>,
 ) -> Result<Index> {
     let mut options = EnvOpenOptions::new();
     options.map_size(clamp_to_page_size(map_size));
     options.max_readers(1024);
+    let mut flags = EnvFlags::empty();
     if enable_mdb_writemap {
-        unsafe { options.flags(EnvFlags::WRITE_MAP) };
+        flags |= EnvFlags::WRITE_MAP;
     }
-
-    if let Some((created, updated)) = date {
-        Ok(Index::new_with_creation_dates(options, path, created, updated)?)
-    } else {
-        Ok(Index::new(options, path)?)
+    if no_sync {
+        flags |= EnvFlags::NO_SYNC;
+    }
+    if !flags.is_empty() {
+        unsafe { options.flags(flags) };
     }
+
+    let now = OffsetDateTime::now_utc();
+    let (created, updated) = date.unwrap_or((now, now));
+    Ok(Index::new_with_encryption_key(options, path, created, updated, encryption_key)?)
 }
 
 /// Putting the tests of the LRU down there so we have access to the cache's private members
@@ -1,12 +1,13 @@
-use std::path::PathBuf;
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use std::{fs, thread};
 
 use meilisearch_types::heed::types::{SerdeJson, Str};
-use meilisearch_types::heed::{Database, Env, RoTxn, RwTxn};
+use meilisearch_types::heed::{Database, Env, EnvOpenOptions, RoTxn, RwTxn};
 use meilisearch_types::milli::update::IndexerConfig;
-use meilisearch_types::milli::{FieldDistribution, Index};
+use meilisearch_types::milli::{DatabaseStats, EncryptionKey, FieldDistribution, Index};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use tracing::error;
@@ -21,6 +22,10 @@ mod index_map;
 
 const INDEX_MAPPING: &str = "index-mapping";
 const INDEX_STATS: &str = "index-stats";
+const INDEX_STATS_HISTORY: &str = "index-stats-history";
+
+/// The maximum number of stats samples kept for each index, the oldest ones are evicted first.
+const MAX_STATS_SAMPLES: usize = 1000;
 
 /// Structure managing meilisearch's indexes.
 ///
@@ -59,6 +64,8 @@ pub struct IndexMapper {
     /// Using an UUID forces to use the index_mapping table to recover the index behind a name, ensuring
     /// consistency wrt index swapping.
     pub(crate) index_stats: Database<UuidCodec, SerdeJson<IndexStats>>,
+    /// Map an index UUID with the samples of its stats recorded after each batch, oldest first.
+    pub(crate) index_stats_history: Database<UuidCodec, SerdeJson<VecDeque<IndexStatsSample>>>,
 
     /// Path to the folder where the LMDB environments of each index are.
     base_path: PathBuf,
@@ -75,6 +82,14 @@ pub struct IndexMapper {
     currently_updating_index: Arc<RwLock<Option<(String, Index)>>>,
 }
 
+/// An index installed by [`IndexMapper::install_index`] in a transaction that is not committed yet.
+pub struct InstalledIndex {
+    pub index: Index,
+    pub uuid: Uuid,
+    /// The uuid of the index that had the same name, replaced once the transaction is committed.
+    pub replaced: Option<Uuid>,
+}
+
 /// Whether the index is available for use or is forbidden to be inserted back in the index map
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone)]
@@ -105,12 +120,20 @@ pub struct IndexStats {
     /// As the DB backend does not return to the disk the pages that are not currently used by the DB,
     /// this value is typically smaller than `database_size`.
     pub used_database_size: u64,
+    /// The LMDB statistics of every database of the index, by database name.
+    ///
+    /// Empty for the stats computed by a version of Meilisearch that did not collect them.
+    #[serde(default)]
+    pub database_stats: BTreeMap<String, DatabaseStats>,
     /// Association of every field name with the number of times it occurs in the documents.
     pub field_distribution: FieldDistribution,
     /// Creation date of the index.
     pub created_at: OffsetDateTime,
     /// Date of the last update of the index.
     pub updated_at: OffsetDateTime,
+    /// The errors of the invalid documents of the last refused payload of documents.
+    #[serde(default)]
+    pub last_indexing_errors: Vec<String>,
 }
 
 impl IndexStats {
@@ -124,14 +147,51 @@ impl IndexStats {
             number_of_documents: index.number_of_documents(rtxn)?,
             database_size: index.on_disk_size()?,
             used_database_size: index.used_size()?,
+            database_stats: index.database_stats(rtxn)?,
             field_distribution: index.field_distribution(rtxn)?,
             created_at: index.created_at(rtxn)?,
             updated_at: index.updated_at(rtxn)?,
+            last_indexing_errors: index.last_indexing_errors(rtxn)?,
         })
     }
+
+    /// The proportion of the pages of the index' DB that are free, between `0.0` and `1.0`.
+    ///
+    /// The free pages are not returned to the disk, only a compaction of the index reclaims them.
+    pub fn fragmentation(&self) -> f64 {
+        if self.database_size == 0 {
+            return 0.0;
+        }
+        self.database_size.saturating_sub(self.used_database_size) as f64
+            / self.database_size as f64
+    }
+}
+
+/// The sizes of an index recorded after a batch, to follow its growth over time.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexStatsSample {
+    /// When the stats of the index were computed, at the end of the batch.
+    #[serde(with = "time::serde::rfc3339")]
+    pub recorded_at: OffsetDateTime,
+    pub number_of_documents: u64,
+    pub database_size: u64,
+    pub used_database_size: u64,
+}
+
+impl IndexStatsSample {
+    fn new(stats: &IndexStats) -> Self {
+        Self {
+            recorded_at: OffsetDateTime::now_utc(),
+            number_of_documents: stats.number_of_documents,
+            database_size: stats.database_size,
+            used_database_size: stats.used_database_size,
+        }
+    }
 }
 
 impl IndexMapper {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         env: &Env,
         base_path: PathBuf,
@@ -140,16 +200,21 @@ impl IndexMapper {
         index_count: usize,
         enable_mdb_writemap: bool,
         indexer_config: IndexerConfig,
+        encryption_key: Option<EncryptionKey>,
     ) -> Result<Self> {
         let mut wtxn = env.write_txn()?;
         let index_mapping = env.create_database(&mut wtxn, Some(INDEX_MAPPING))?;
         let index_stats = env.create_database(&mut wtxn, Some(INDEX_STATS))?;
+        let index_stats_history = env.create_database(&mut wtxn, Some(INDEX_STATS_HISTORY))?;
         wtxn.commit()?;
 
+        let index_map =
+            IndexMap::new(index_count, encryption_key, indexer_config.ephemeral_storage);
         Ok(Self {
-            index_map: Arc::new(RwLock::new(IndexMap::new(index_count))),
+            index_map: Arc::new(RwLock::new(index_map)),
             index_mapping,
             index_stats,
+            index_stats_history,
             base_path,
             index_base_map_size,
             index_growth_amount,
@@ -199,20 +264,37 @@ impl IndexMapper {
 
     /// Removes the index from the mapping table and the in-memory index map
     /// but keeps the associated tasks.
+    ///
+    /// The handles on the index that were handed out before, e.g. to in-flight searches, stay valid:
+    /// the environment is only closed and removed from disk once all of them are dropped.
     pub fn delete_index(&self, mut wtxn: RwTxn, name: &str) -> Result<()> {
+        let uuid = self.unmap_index(&mut wtxn, name)?;
+        wtxn.commit()?;
+        self.delete_index_env(name, uuid)
+    }
+
+    /// Removes the index from the mapping table and its cached stats, and returns its uuid.
+    ///
+    /// Once the transaction is committed, the environment must be removed with [`Self::delete_index_env`].
+    pub fn unmap_index(&self, wtxn: &mut RwTxn, name: &str) -> Result<Uuid> {
         let uuid = self
             .index_mapping
-            .get(&wtxn, name)?
+            .get(wtxn, name)?
             .ok_or_else(|| Error::IndexNotFound(name.to_string()))?;
 
         // Not an error if the index had no stats in cache.
-        self.index_stats.delete(&mut wtxn, &uuid)?;
+        self.index_stats.delete(wtxn, &uuid)?;
+        self.index_stats_history.delete(wtxn, &uuid)?;
 
         // Once we retrieved the UUID of the index we remove it from the mapping table.
-        assert!(self.index_mapping.delete(&mut wtxn, name)?);
+        assert!(self.index_mapping.delete(wtxn, name)?);
 
-        wtxn.commit()?;
+        Ok(uuid)
+    }
 
+    /// Closes the environment of an index that is not in the mapping table anymore, and removes it
+    /// from disk once the handles that were handed out before are dropped.
+    pub fn delete_index_env(&self, name: &str, uuid: Uuid) -> Result<()> {
         let mut tries = 0;
         // Attempts to remove the index from the in-memory index map in a loop.
         //
@@ -278,6 +360,86 @@ impl IndexMapper {
         Ok(())
     }
 
+    /// Installs the environment of an index, typically extracted from a snapshot, under the given
+    /// name, in place of the index of that name if there is one.
+    ///
+    /// The `data.mdb` file is copied into a new index directory before the mapping table is
+    /// changed, so that the current index is left untouched if the copy fails. The new index only
+    /// replaces the current one once the transaction is committed, after which the environment of
+    /// the replaced index must be removed with [`Self::delete_index_env`]. If the transaction is
+    /// aborted instead, the environment of the new index must be removed the same way.
+    pub fn install_index(
+        &self,
+        wtxn: &mut RwTxn,
+        name: &str,
+        data_path: &Path,
+    ) -> Result<InstalledIndex> {
+        let uuid = Uuid::new_v4();
+        let index_path = self.base_path.join(uuid.to_string());
+        fs::create_dir_all(&index_path)?;
+        if let Err(e) = fs::copy(data_path, index_path.join("data.mdb")) {
+            let _ = fs::remove_dir_all(&index_path);
+            return Err(e.into());
+        }
+
+        let index = match self.index_map.write().unwrap().create(
+            &uuid,
+            &index_path,
+            None,
+            self.enable_mdb_writemap,
+            self.index_base_map_size,
+        ) {
+            Ok(index) => index,
+            Err(e) => {
+                let _ = fs::remove_dir_all(&index_path);
+                return Err(e);
+            }
+        };
+
+        let replaced = match self.index_mapping.get(wtxn, name)? {
+            Some(replaced) => {
+                self.index_stats.delete(wtxn, &replaced)?;
+                self.index_stats_history.delete(wtxn, &replaced)?;
+                Some(replaced)
+            }
+            None => None,
+        };
+        self.index_mapping.put(wtxn, name, &uuid)?;
+
+        Ok(InstalledIndex { index, uuid, replaced })
+    }
+
+    /// Returns the uuid associated with the index name in the index-scheduler environment
+    /// stored at the given path, e.g. the one of a snapshot.
+    pub fn index_uuid_in_env(env_path: &Path, name: &str) -> Result<Option<Uuid>> {
+        let env = EnvOpenOptions::new().max_dbs(1).open(env_path)?;
+        let rtxn = env.read_txn()?;
+        let uuid = match env.open_database::<Str, UuidCodec>(&rtxn, Some(INDEX_MAPPING))? {
+            Some(index_mapping) => index_mapping.get(&rtxn, name)?,
+            None => None,
+        };
+        drop(rtxn);
+        env.prepare_for_closing().wait();
+        Ok(uuid)
+    }
+
+    /// Returns the names and uuids of all the indexes of the index-scheduler environment
+    /// stored at the given path, e.g. the one of a snapshot.
+    pub fn indexes_in_env(env_path: &Path) -> Result<Vec<(String, Uuid)>> {
+        let env = EnvOpenOptions::new().max_dbs(1).open(env_path)?;
+        let rtxn = env.read_txn()?;
+        let indexes = match env.open_database::<Str, UuidCodec>(&rtxn, Some(INDEX_MAPPING))? {
+            Some(index_mapping) => index_mapping
+                .iter(&rtxn)?
+                .map(|res| res.map_err(Error::from).map(|(name, uuid)| (name.to_string(), uuid)))
+                .collect::<Result<_>>()?,
+            None => Vec::new(),
+        };
+        drop(rtxn);
+        env.prepare_for_closing().wait();
+        Ok(indexes)
+    }
+
     pub fn exists(&self, rtxn: &RoTxn, name: &str) -> Result<bool> {
         Ok(self.index_mapping.get(rtxn, name)?.is_some())
     }
@@ -369,6 +531,11 @@ impl IndexMapper {
                     match index_map.get(&uuid) {
                         Missing => {
                             let index_path = self.base_path.join(uuid.to_string());
+                            // The mapping may have been read before the deletion of the index was committed,
+                            // if the deletion finished since, reopening its environment would fail.
+                            if !index_path.exists() {
+                                return Err(Error::IndexNotFound(name.to_string()));
+                            }
 
                             break index_map.create(
                                 &uuid,
@@ -462,7 +629,7 @@ impl IndexMapper {
         }
     }
 
-    /// Stores the new stats for an index.
+    /// Stores the new stats for an index, and records a sample of them in its stats history.
     ///
     /// Expected usage is to compute the stats the index using `IndexStats::new`, the pass it to this function.
     pub fn store_stats_of(
@@ -477,9 +644,27 @@ impl IndexMapper {
             .ok_or_else(|| Error::IndexNotFound(index_uid.to_string()))?;
 
         self.index_stats.put(wtxn, &uuid, stats)?;
+
+        let mut history = self.index_stats_history.get(wtxn, &uuid)?.unwrap_or_default();
+        history.push_back(IndexStatsSample::new(stats));
+        while history.len() > MAX_STATS_SAMPLES {
+            history.pop_front();
+        }
+        self.index_stats_history.put(wtxn, &uuid, &history)?;
         Ok(())
     }
 
+    /// The samples of the stats of the index recorded after each batch, oldest first.
+    pub fn stats_history_of(&self, rtxn: &RoTxn, index_uid: &str) -> Result<Vec<IndexStatsSample>> {
+        let uuid = self
+            .index_mapping
+            .get(rtxn, index_uid)?
+            .ok_or_else(|| Error::IndexNotFound(index_uid.to_string()))?;
+
+        let history = self.index_stats_history.get(rtxn, &uuid)?.unwrap_or_default();
+        Ok(history.into())
+    }
+
     pub fn index_exists(&self, rtxn: &RoTxn, name: &str) -> Result<bool> {
         Ok(self.index_mapping.get(rtxn, name)?.is_some())
     }
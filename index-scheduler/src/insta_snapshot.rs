@@ -16,8 +16,14 @@ pub fn snapshot_index_scheduler(scheduler: &IndexScheduler) -> String {
     let IndexScheduler {
         autobatching_enabled,
         cleanup_enabled: _,
+        auto_cleanup_tasks: _,
+        attributes_soft_limit: _,
+        attributes_hard_limit: _,
+        max_enqueued_tasks: _,
         must_stop_processing: _,
         processing_tasks,
+        panicked_indexes: _,
+        retrying_tasks: _,
         file_store,
         env,
         all_tasks,
@@ -30,20 +36,31 @@ pub fn snapshot_index_scheduler(scheduler: &IndexScheduler) -> String {
         finished_at,
         index_mapper,
         features: _,
+        index_groups: _,
+        recent_searches: _,
         max_number_of_tasks: _,
         max_number_of_batched_tasks: _,
+        max_task_attempts: _,
+        redact_payload_errors: _,
+        reenqueue_crashed_tasks: _,
+        document_tasks_history_size: _,
+        recent_searches_size: _,
+        crash_recovery: _,
         puffin_frame: _,
         wake_up: _,
+        webhook_wake_up: _,
         dumps_path: _,
         snapshots_path: _,
         auth_path: _,
         version_file_path: _,
-        webhook_url: _,
-        webhook_authorization_header: _,
+        webhook_settings: _,
+        webhook_queue: _,
+        replication: _,
         test_breakpoint_sdr: _,
         planned_failures: _,
         run_loop_iteration: _,
         embedders: _,
+        query_embeddings: _,
     } = scheduler;
 
     let rtxn = env.read_txn().unwrap();
@@ -151,6 +168,7 @@ pub fn snapshot_task(task: &Task) -> String {
         details,
         status,
         kind,
+        attempts,
     } = task;
     snap.push('{');
     snap.push_str(&format!("uid: {uid}, "));
@@ -164,6 +182,10 @@ pub fn snapshot_task(task: &Task) -> String {
     if let Some(details) = details {
         snap.push_str(&format!("details: {}, ", &snapshot_details(details)));
     }
+    if !attempts.is_empty() {
+        let errors: Vec<_> = attempts.iter().map(|attempt| &attempt.error).collect();
+        snap.push_str(&format!("attempts: {errors:?}, "));
+    }
     snap.push_str(&format!("kind: {kind:?}"));
 
     snap.push('}');
@@ -175,12 +197,39 @@ fn snapshot_details(d: &Details) -> String {
         Details::DocumentAdditionOrUpdate {
             received_documents,
             indexed_documents,
-        } => {
+            duplicate_documents: 0,
+            invalid_utf8_replacements: 0,
+            indexing_errors,
+            facet_update_strategies,
+        } if indexing_errors.is_empty() && facet_update_strategies.is_empty() => {
             format!("{{ received_documents: {received_documents}, indexed_documents: {indexed_documents:?} }}")
         }
-        Details::SettingsUpdate { settings } => {
+        Details::DocumentAdditionOrUpdate {
+            received_documents,
+            indexed_documents,
+            duplicate_documents,
+            invalid_utf8_replacements: _,
+            indexing_errors,
+            facet_update_strategies,
+        } if indexing_errors.is_empty() && facet_update_strategies.is_empty() => {
+            format!("{{ received_documents: {received_documents}, indexed_documents: {indexed_documents:?}, duplicate_documents: {duplicate_documents} }}")
+        }
+        Details::DocumentAdditionOrUpdate {
+            received_documents,
+            indexed_documents,
+            duplicate_documents,
+            invalid_utf8_replacements: _,
+            indexing_errors,
+            facet_update_strategies,
+        } => {
+            format!("{{ received_documents: {received_documents}, indexed_documents: {indexed_documents:?}, duplicate_documents: {duplicate_documents}, indexing_errors: {indexing_errors:?}, facet_update_strategies: {facet_update_strategies:?} }}")
+        }
+        Details::SettingsUpdate { settings, warnings } if warnings.is_empty() => {
             format!("{{ settings: {settings:?} }}")
         }
+        Details::SettingsUpdate { settings, warnings } => {
+            format!("{{ settings: {settings:?}, warnings: {warnings:?} }}")
+        }
         Details::IndexInfo { primary_key } => {
             format!("{{ primary_key: {primary_key:?} }}")
         }
@@ -214,6 +263,18 @@ fn snapshot_details(d: &Details) -> String {
         Details::IndexSwap { swaps } => {
             format!("{{ swaps: {swaps:?} }}")
         }
+        Details::IndexRestore { snapshot_path, snapshot_index_uid, restored_documents } => {
+            format!("{{ snapshot_path: {snapshot_path:?}, snapshot_index_uid: {snapshot_index_uid:?}, restored_documents: {restored_documents:?} }}")
+        }
+        Details::IntegrityCheck { repair, violations } => {
+            format!("{{ repair: {repair:?}, violations: {violations:?} }}")
+        }
+        Details::IndexWarmup { prefixes, populate_cache, warmed_entries } => {
+            format!("{{ prefixes: {prefixes:?}, populate_cache: {populate_cache:?}, warmed_entries: {warmed_entries:?} }}")
+        }
+        Details::SnapshotImport { imported_indexes } => {
+            format!("{{ imported_indexes: {imported_indexes:?} }}")
+        }
     }
 }
 
@@ -20,57 +20,90 @@ content of the scheduler or enqueue new tasks.
 
 mod autobatcher;
 mod batch;
+mod crash_recovery;
 pub mod error;
 mod features;
+mod index_groups;
 mod index_mapper;
 #[cfg(test)]
 mod insta_snapshot;
 mod lru;
+mod progress;
+mod query_embeddings;
+mod recent_searches;
+mod replication;
+mod retention;
+mod task_queue_stats;
 mod utils;
 pub mod uuid_codec;
+mod webhook;
 
 pub type Result<T> = std::result::Result<T, Error>;
 pub type TaskId = u32;
 
+use std::any::Any;
 use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsStr;
 use std::fs::File;
 use std::io::{self, BufReader, Read};
 use std::ops::{Bound, RangeBounds};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering::{self, Relaxed};
 use std::sync::atomic::{AtomicBool, AtomicU32};
-use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
+pub use crash_recovery::{CrashRecoveryReport, RecoveryAction};
 use dump::{KindDump, TaskDump, UpdateFile};
 pub use error::Error;
 pub use features::RoFeatures;
 use file_store::FileStore;
 use flate2::bufread::GzEncoder;
 use flate2::Compression;
+pub use index_mapper::IndexStatsSample;
 use meilisearch_types::error::ResponseError;
 use meilisearch_types::features::{InstanceTogglableFeatures, RuntimeTogglableFeatures};
 use meilisearch_types::heed::byteorder::BE;
-use meilisearch_types::heed::types::{SerdeBincode, SerdeJson, Str, I128};
+use meilisearch_types::heed::types::{Bytes, SerdeBincode, SerdeJson, Str, I128};
 use meilisearch_types::heed::{self, Database, Env, PutFlags, RoTxn, RwTxn};
+use meilisearch_types::index_uid_pattern::IndexUidPattern;
 use meilisearch_types::milli::documents::DocumentsBatchBuilder;
+use meilisearch_types::milli::encryption::check_encryption_key;
 use meilisearch_types::milli::update::IndexerConfig;
 use meilisearch_types::milli::vector::{Embedder, EmbedderOptions, EmbeddingConfigs};
-use meilisearch_types::milli::{self, CboRoaringBitmapCodec, Index, RoaringBitmapCodec, BEU32};
-use meilisearch_types::task_view::TaskView;
-use meilisearch_types::tasks::{Kind, KindWithContent, Status, Task};
+use meilisearch_types::milli::{
+    self, CboRoaringBitmapCodec, EncryptionKey, Index, RoaringBitmapCodec, BEU32,
+};
+use meilisearch_types::task_view::{ProgressView, TaskView};
+use meilisearch_types::tasks::{Details, Kind, KindWithContent, Status, Task, TaskAttempt, TaskId};
 use puffin::FrameView;
+pub use query_embeddings::QueryEmbeddingCache;
 use rayon::current_num_threads;
 use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+pub use replication::ReplicationStatus;
+use retention::RetentionPolicy;
+pub use retention::DEFAULT_RETENTION_INTERVAL;
 use roaring::RoaringBitmap;
 use synchronoise::SignalEvent;
+use task_queue_stats::FinishedBatches;
+pub use task_queue_stats::TaskQueueStats;
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
+use tokio::sync::watch;
 use utils::{filter_out_references_to_newer_tasks, keep_tasks_within_datetimes, map_bound};
 use uuid::Uuid;
+pub use webhook::{WebhookSettings, WebhookStatus};
 
+use crate::crash_recovery::{CrashRecovery, InFlightBatch};
+use crate::index_groups::IndexGroups;
 use crate::index_mapper::IndexMapper;
+use crate::lru::LruMap;
+use crate::progress::Progress;
+use crate::query_embeddings::{QueryEmbeddings, QUERY_EMBEDDINGS_CACHE_SIZE};
+use crate::recent_searches::RecentSearches;
+use crate::replication::Replication;
 use crate::utils::{check_index_swap_validity, clamp_to_page_size};
+use crate::webhook::{WebhookQueue, WebhookSettingsData, MAX_PENDING_WEBHOOK_PAYLOADS};
 
 pub(crate) type BEI128 = I128<BE>;
 
@@ -82,7 +115,9 @@ pub(crate) type BEI128 = I128<BE>;
 pub struct Query {
     /// The maximum number of tasks to be matched
     pub limit: Option<u32>,
-    /// The minimum [task id](`meilisearch_types::tasks::Task::uid`) to be matched
+    /// The maximum [task id](`meilisearch_types::tasks::Task::uid`) to be matched, inclusive.
+    ///
+    /// Tasks are matched from the most recent to the oldest, so this is where a page starts.
     pub from: Option<u32>,
     /// The allowed [statuses](`meilisearch_types::tasks::Task::status`) of the matched tasls
     pub statuses: Option<Vec<Status>>,
@@ -98,6 +133,9 @@ pub struct Query {
     pub types: Option<Vec<Kind>>,
     /// The allowed [index ids](meilisearch_types::tasks::Task::index_uid) of the matched tasks
     pub index_uids: Option<Vec<String>>,
+    /// The index groups whose patterns must match the [index ids](meilisearch_types::tasks::Task::index_uid)
+    /// of the matched tasks.
+    pub index_groups: Option<Vec<String>>,
     /// The [task ids](`meilisearch_types::tasks::Task::uid`) to be matched
     pub uids: Option<Vec<TaskId>>,
     /// The [task ids](`meilisearch_types::tasks::Task::uid`) of the [`TaskCancelation`](meilisearch_types::tasks::Task::Kind::TaskCancelation) tasks
@@ -129,6 +167,7 @@ impl Query {
                 statuses: None,
                 types: None,
                 index_uids: None,
+                index_groups: None,
                 uids: None,
                 canceled_by: None,
                 before_enqueued_at: None,
@@ -161,31 +200,188 @@ struct ProcessingTasks {
     started_at: OffsetDateTime,
     /// The list of tasks ids that are currently running.
     processing: RoaringBitmap,
+    /// The number of tasks processed by the last batch and the time it took to process them.
+    last_batch: Option<(u64, Duration)>,
+    /// The steps reported by the batch that is currently running.
+    progress: Progress,
 }
 
 impl ProcessingTasks {
     /// Creates an empty `ProcessingAt` struct.
     fn new() -> ProcessingTasks {
-        ProcessingTasks { started_at: OffsetDateTime::now_utc(), processing: RoaringBitmap::new() }
+        ProcessingTasks {
+            started_at: OffsetDateTime::now_utc(),
+            processing: RoaringBitmap::new(),
+            last_batch: None,
+            progress: Progress::default(),
+        }
     }
 
     /// Stores the currently processing tasks, and the date time at which it started.
     fn start_processing_at(&mut self, started_at: OffsetDateTime, processing: RoaringBitmap) {
         self.started_at = started_at;
         self.processing = processing;
+        self.progress = Progress::default();
     }
 
     /// Set the processing tasks to an empty list
     fn stop_processing(&mut self) -> RoaringBitmap {
+        if !self.processing.is_empty() {
+            let duration = (OffsetDateTime::now_utc() - self.started_at).unsigned_abs();
+            self.last_batch = Some((self.processing.len(), duration));
+        }
+        self.progress = Progress::default();
         std::mem::take(&mut self.processing)
     }
 
+    /// Estimates, from the throughput of the last batch, the time needed to process the given number of tasks.
+    fn estimate_processing_time(&self, tasks: u64) -> Duration {
+        match self.last_batch {
+            Some((processed, duration)) if processed > 0 => {
+                duration.mul_f64(tasks as f64 / processed as f64)
+            }
+            // we have never processed anything, let's hope it will be quick
+            _ => Duration::from_secs(1),
+        }
+    }
+
     /// Returns `true` if there, at least, is one task that is currently processing that we must stop.
     fn must_cancel_processing_tasks(&self, canceled_tasks: &RoaringBitmap) -> bool {
         !self.processing.is_disjoint(canceled_tasks)
     }
 }
 
+/// The number of batches of an index that must panic in a row for the index to be parked.
+const MAX_CONSECUTIVE_PANICS: u32 = 3;
+
+/// The time to wait before sending again a payload the webhook did not acknowledge.
+const WEBHOOK_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The time to wait before attempting again a task that failed because of a transient error,
+/// doubled after every failed attempt.
+const TASK_RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// The maximum time to wait before attempting again a task.
+const TASK_RETRY_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// An index that isn't processed anymore because its batches kept panicking.
+///
+/// Its tasks stay enqueued until it is unparked with [`IndexScheduler::unpark_index`]
+/// or until Meilisearch restarts, as the parked indexes are only kept in memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParkedIndex {
+    /// The date and time at which the index was parked.
+    pub parked_at: OffsetDateTime,
+    /// The message of the last panic.
+    pub reason: String,
+}
+
+/// Keeps track of the batches that panicked, to park the indexes whose batches
+/// keep panicking instead of retrying them forever and wedging the whole queue.
+#[derive(Debug, Default)]
+struct PanickedIndexes {
+    /// The number of batches of every index that panicked in a row.
+    consecutive_panics: HashMap<String, u32>,
+    /// The indexes whose tasks must not be processed.
+    parked: HashMap<String, ParkedIndex>,
+}
+
+impl PanickedIndexes {
+    /// Registers the outcome of a batch of the index, `panic` being the panic message if it panicked.
+    ///
+    /// Returns `true` if the index has just been parked.
+    fn register_batch(&mut self, index_uid: &str, panic: Option<&str>) -> bool {
+        let Some(panic) = panic else {
+            self.consecutive_panics.remove(index_uid);
+            return false;
+        };
+
+        let panics = self.consecutive_panics.entry(index_uid.to_string()).or_default();
+        *panics += 1;
+        if *panics < MAX_CONSECUTIVE_PANICS {
+            return false;
+        }
+
+        self.consecutive_panics.remove(index_uid);
+        let parked =
+            ParkedIndex { parked_at: OffsetDateTime::now_utc(), reason: panic.to_string() };
+        self.parked.insert(index_uid.to_string(), parked);
+        true
+    }
+
+    /// Unparks the index and returns `true` if it was parked.
+    fn unpark(&mut self, index_uid: &str) -> bool {
+        self.consecutive_panics.remove(index_uid);
+        self.parked.remove(index_uid).is_some()
+    }
+}
+
+/// The tasks that failed because of a transient error and wait to be attempted again.
+///
+/// The delays are only kept in memory, the tasks are attempted again right away when Meilisearch restarts.
+#[derive(Debug, Default)]
+struct RetryingTasks {
+    /// The instant after which every task can be attempted again.
+    retry_at: HashMap<TaskId, Instant>,
+}
+
+impl RetryingTasks {
+    /// Delays the task after its `failed_attempts`-th failed attempt, and returns the delay.
+    fn delay(&mut self, task_id: TaskId, failed_attempts: u32) -> Duration {
+        let factor = 1u32 << failed_attempts.saturating_sub(1).min(16);
+        let delay = TASK_RETRY_BASE_DELAY.saturating_mul(factor).min(TASK_RETRY_MAX_DELAY);
+        self.retry_at.insert(task_id, Instant::now() + delay);
+        delay
+    }
+
+    /// Forgets the tasks that can be attempted again and returns the ones that must still wait.
+    fn waiting(&mut self) -> RoaringBitmap {
+        let now = Instant::now();
+        self.retry_at.retain(|_, retry_at| *retry_at > now);
+        self.retry_at.keys().copied().collect()
+    }
+
+    /// Returns the time to wait before the next task can be attempted again.
+    fn next_retry_in(&self) -> Option<Duration> {
+        let now = Instant::now();
+        self.retry_at.values().map(|retry_at| retry_at.saturating_duration_since(now)).min()
+    }
+}
+
+/// Counts the batches whose tasks were committed, to wake up the requests waiting for a task to finish.
+///
+/// The requests are woken up through a channel that they can await, so that they don't keep a
+/// thread busy while waiting.
+#[derive(Debug)]
+struct ProcessedBatches(watch::Sender<u64>);
+
+impl Default for ProcessedBatches {
+    fn default() -> Self {
+        Self(watch::channel(0).0)
+    }
+}
+
+impl ProcessedBatches {
+    fn notify(&self) {
+        self.0.send_modify(|count| *count += 1);
+    }
+
+    fn subscribe(&self) -> watch::Receiver<u64> {
+        self.0.subscribe()
+    }
+}
+
+/// Returns the message of a panic caught when joining a thread.
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
+
 #[derive(Default, Clone, Debug)]
 struct MustStopProcessing(Arc<AtomicBool>);
 
@@ -213,6 +409,7 @@ mod db_name {
     pub const ENQUEUED_AT: &str = "enqueued-at";
     pub const STARTED_AT: &str = "started-at";
     pub const FINISHED_AT: &str = "finished-at";
+    pub const ENCRYPTION: &str = "encryption";
 }
 
 #[cfg(test)]
@@ -251,6 +448,10 @@ pub struct IndexSchedulerOptions {
     pub webhook_url: Option<String>,
     /// The value we will send into the Authorization HTTP header on the webhook URL
     pub webhook_authorization_header: Option<String>,
+    /// The URL of the primary instance this instance is a read-only replica of, if any.
+    pub replica_primary_url: Option<String>,
+    /// The API key sent to the primary instance.
+    pub replica_primary_api_key: Option<String>,
     /// The maximum size, in bytes, of the task index.
     pub task_db_size: usize,
     /// The size, in bytes, with which a meilisearch index is opened the first time of each meilisearch index.
@@ -272,9 +473,44 @@ pub struct IndexSchedulerOptions {
     /// The maximum number of tasks stored in the task queue before starting
     /// to auto schedule task deletions.
     pub max_number_of_tasks: usize,
+    /// Set to `true` iff the index scheduler is allowed to automatically
+    /// enqueue a deletion of the oldest succeeded tasks when the task database is full.
+    pub auto_cleanup_tasks: bool,
+    /// The number of filterable, sortable, or searchable attributes of an index
+    /// above which a warning is added to the details of the settings tasks.
+    pub attributes_soft_limit: usize,
+    /// The number of filterable, sortable, or searchable attributes of an index
+    /// above which the settings tasks fail, if any.
+    pub attributes_hard_limit: Option<usize>,
+    /// The number of enqueued tasks above which the new tasks are refused,
+    /// except for the task cancelations, task deletions, and dumps.
+    pub max_enqueued_tasks: Option<u64>,
     /// If the autobatcher is allowed to automatically batch tasks
     /// it will only batch this defined number of tasks at once.
     pub max_number_of_batched_tasks: usize,
+    /// The number of times a task failing because of a transient error is attempted
+    /// before being marked as failed.
+    pub max_task_attempts: u32,
+    /// Set to `true` to truncate the long document values echoed in the errors of the tasks.
+    pub redact_payload_errors: bool,
+    /// Set to `true` to enqueue again the tasks of the batch that was processing when the instance
+    /// stopped unexpectedly, instead of marking them as failed.
+    pub reenqueue_crashed_tasks: bool,
+    /// The number of tasks kept in the history of each document, when the `documentTasks`
+    /// experimental feature is enabled.
+    pub document_tasks_history_size: usize,
+    /// The number of searches kept for each index, when the `recentSearches` experimental
+    /// feature is enabled.
+    pub recent_searches_size: usize,
+    /// The key the documents of the indexes and the update files are encrypted with, if any.
+    pub encryption_key: Option<EncryptionKey>,
+    /// How long the finished tasks are kept before being deleted, forever when `None`.
+    pub task_retention: Option<Duration>,
+    /// How long the update files that no enqueued task refers to are kept before being deleted,
+    /// forever when `None`.
+    pub update_file_retention: Option<Duration>,
+    /// How often the finished tasks and update files past their retention are deleted.
+    pub retention_interval: Duration,
     /// The experimental features enabled for this instance.
     pub instance_features: InstanceTogglableFeatures,
 }
@@ -291,6 +527,18 @@ pub struct IndexScheduler {
     /// The list of tasks currently processing
     pub(crate) processing_tasks: Arc<RwLock<ProcessingTasks>>,
 
+    /// The indexes whose batches panicked, and the ones that are parked because of it.
+    pub(crate) panicked_indexes: Arc<RwLock<PanickedIndexes>>,
+
+    /// The tasks waiting to be attempted again after a transient failure.
+    pub(crate) retrying_tasks: Arc<RwLock<RetryingTasks>>,
+
+    /// The aggregates of the finished batches, used to monitor the task queue.
+    pub(crate) finished_batches: Arc<Mutex<FinishedBatches>>,
+
+    /// When the finished tasks and the orphaned update files are deleted.
+    pub(crate) retention_policy: Arc<RetentionPolicy>,
+
     /// The list of files referenced by the tasks
     pub(crate) file_store: FileStore,
 
@@ -323,15 +571,40 @@ pub struct IndexScheduler {
     /// In charge of fetching and setting the status of experimental features.
     features: features::FeatureData,
 
+    /// The named groups of index uid patterns.
+    index_groups: IndexGroups,
+
+    /// The last searches made on each index.
+    pub(crate) recent_searches: RecentSearches,
+
     /// Get a signal when a batch needs to be processed.
     pub(crate) wake_up: Arc<SignalEvent>,
 
+    /// Get a signal when a payload needs to be delivered to the webhook.
+    webhook_wake_up: Arc<SignalEvent>,
+
+    /// Notified every time a batch is processed.
+    processed_batches: Arc<ProcessedBatches>,
+
     /// Whether auto-batching is enabled or not.
     pub(crate) autobatching_enabled: bool,
 
     /// Whether we should automatically cleanup the task queue or not.
     pub(crate) cleanup_enabled: bool,
 
+    /// Whether we should enqueue a deletion of the oldest succeeded tasks
+    /// when the task database is full.
+    pub(crate) auto_cleanup_tasks: bool,
+
+    /// The number of attributes per capability above which a settings task emits a warning.
+    pub(crate) attributes_soft_limit: usize,
+
+    /// The number of attributes per capability above which a settings task fails.
+    pub(crate) attributes_hard_limit: Option<usize>,
+
+    /// The number of enqueued tasks above which the new tasks are refused.
+    pub(crate) max_enqueued_tasks: Option<u64>,
+
     /// The max number of tasks allowed before the scheduler starts to delete
     /// the finished tasks automatically.
     pub(crate) max_number_of_tasks: usize,
@@ -339,10 +612,31 @@ pub struct IndexScheduler {
     /// The maximum number of tasks that will be batched together.
     pub(crate) max_number_of_batched_tasks: usize,
 
-    /// The webhook url we should send tasks to after processing every batches.
-    pub(crate) webhook_url: Option<String>,
-    /// The Authorization header to send to the webhook URL.
-    pub(crate) webhook_authorization_header: Option<String>,
+    /// The number of times a task failing because of a transient error is attempted.
+    pub(crate) max_task_attempts: u32,
+
+    /// Whether the long document values echoed in the errors of the tasks are truncated.
+    pub(crate) redact_payload_errors: bool,
+
+    /// Whether the tasks of a batch interrupted by a crash are enqueued again instead of failed.
+    pub(crate) reenqueue_crashed_tasks: bool,
+
+    /// The number of tasks kept in the history of each document.
+    pub(crate) document_tasks_history_size: usize,
+
+    /// The number of searches kept for each index.
+    pub(crate) recent_searches_size: usize,
+
+    /// The batch being processed and the last recovery from a crash.
+    pub(crate) crash_recovery: CrashRecovery,
+
+    /// The webhook url we should send tasks to after processing every batches, and its Authorization header.
+    pub(crate) webhook_settings: WebhookSettingsData,
+    /// The payloads waiting to be delivered to the webhook URL.
+    pub(crate) webhook_queue: WebhookQueue,
+
+    /// The primary instance to import the snapshots from, if this instance is a read-only replica.
+    pub(crate) replication: Option<Replication>,
 
     /// A frame to output the indexation profiling files to disk.
     pub(crate) puffin_frame: Arc<puffin::GlobalFrameView>,
@@ -361,6 +655,9 @@ pub struct IndexScheduler {
 
     embedders: Arc<RwLock<HashMap<EmbedderOptions, Arc<Embedder>>>>,
 
+    /// The embeddings of the last search queries, see [`QueryEmbeddingCache`].
+    query_embeddings: QueryEmbeddings,
+
     // ================= test
     // The next entry is dedicated to the tests.
     /// Provide a way to set a breakpoint in multiple part of the scheduler.
@@ -386,6 +683,10 @@ impl IndexScheduler {
             env: self.env.clone(),
             must_stop_processing: self.must_stop_processing.clone(),
             processing_tasks: self.processing_tasks.clone(),
+            panicked_indexes: self.panicked_indexes.clone(),
+            retrying_tasks: self.retrying_tasks.clone(),
+            finished_batches: self.finished_batches.clone(),
+            retention_policy: self.retention_policy.clone(),
             file_store: self.file_store.clone(),
             all_tasks: self.all_tasks,
             status: self.status,
@@ -397,18 +698,32 @@ impl IndexScheduler {
             finished_at: self.finished_at,
             index_mapper: self.index_mapper.clone(),
             wake_up: self.wake_up.clone(),
+            webhook_wake_up: self.webhook_wake_up.clone(),
+            processed_batches: self.processed_batches.clone(),
             autobatching_enabled: self.autobatching_enabled,
             cleanup_enabled: self.cleanup_enabled,
+            auto_cleanup_tasks: self.auto_cleanup_tasks,
+            attributes_soft_limit: self.attributes_soft_limit,
+            attributes_hard_limit: self.attributes_hard_limit,
+            max_enqueued_tasks: self.max_enqueued_tasks,
             max_number_of_tasks: self.max_number_of_tasks,
             max_number_of_batched_tasks: self.max_number_of_batched_tasks,
+            max_task_attempts: self.max_task_attempts,
+            redact_payload_errors: self.redact_payload_errors,
+            reenqueue_crashed_tasks: self.reenqueue_crashed_tasks,
+            document_tasks_history_size: self.document_tasks_history_size,
+            recent_searches_size: self.recent_searches_size,
+            crash_recovery: self.crash_recovery.clone(),
             puffin_frame: self.puffin_frame.clone(),
             snapshots_path: self.snapshots_path.clone(),
             dumps_path: self.dumps_path.clone(),
             auth_path: self.auth_path.clone(),
             version_file_path: self.version_file_path.clone(),
-            webhook_url: self.webhook_url.clone(),
-            webhook_authorization_header: self.webhook_authorization_header.clone(),
+            webhook_settings: self.webhook_settings.clone(),
+            webhook_queue: self.webhook_queue.clone(),
+            replication: self.replication.clone(),
             embedders: self.embedders.clone(),
+            query_embeddings: self.query_embeddings.clone(),
             #[cfg(test)]
             test_breakpoint_sdr: self.test_breakpoint_sdr.clone(),
             #[cfg(test)]
@@ -416,6 +731,8 @@ impl IndexScheduler {
             #[cfg(test)]
             run_loop_iteration: self.run_loop_iteration.clone(),
             features: self.features.clone(),
+            index_groups: self.index_groups.clone(),
+            recent_searches: self.recent_searches.clone(),
         }
     }
 }
@@ -438,6 +755,7 @@ impl IndexScheduler {
         }
 
         let task_db_size = clamp_to_page_size(options.task_db_size);
+        let outbound_policy = options.indexer_config.outbound_policy.clone();
         let budget = if options.indexer_config.skip_index_budget {
             IndexBudget {
                 map_size: options.index_base_map_size,
@@ -453,14 +771,29 @@ impl IndexScheduler {
             )
         };
 
-        let env = heed::EnvOpenOptions::new()
-            .max_dbs(11)
-            .map_size(budget.task_db_size)
-            .open(options.tasks_path)?;
+        let mut env_options = heed::EnvOpenOptions::new();
+        env_options.max_dbs(19).map_size(budget.task_db_size);
+        if options.indexer_config.ephemeral_storage {
+            // Safety: the data of an ephemeral instance doesn't have to survive a crash.
+            unsafe { env_options.flags(heed::EnvFlags::NO_SYNC) };
+        }
+        let env = env_options.open(options.tasks_path)?;
 
         let features = features::FeatureData::new(&env, options.instance_features)?;
+        let webhook_settings = WebhookSettingsData::new(
+            &env,
+            WebhookSettings {
+                url: options.webhook_url,
+                authorization_header: options.webhook_authorization_header,
+            },
+        )?;
+        let webhook_queue = WebhookQueue::new(&env)?;
+        let index_groups = IndexGroups::new(&env)?;
+        let recent_searches = RecentSearches::new(&env)?;
+        let crash_recovery = CrashRecovery::new(&env)?;
 
-        let file_store = FileStore::new(&options.update_file_path)?;
+        let file_store = FileStore::new(&options.update_file_path)?
+            .with_encryption_key(options.encryption_key.clone());
 
         let mut wtxn = env.write_txn()?;
         let all_tasks = env.create_database(&mut wtxn, Some(db_name::ALL_TASKS))?;
@@ -471,12 +804,21 @@ impl IndexScheduler {
         let enqueued_at = env.create_database(&mut wtxn, Some(db_name::ENQUEUED_AT))?;
         let started_at = env.create_database(&mut wtxn, Some(db_name::STARTED_AT))?;
         let finished_at = env.create_database(&mut wtxn, Some(db_name::FINISHED_AT))?;
+        Self::check_encryption_key(&env, &mut wtxn, all_tasks, options.encryption_key.as_ref())?;
         wtxn.commit()?;
 
         // allow unreachable_code to get rids of the warning in the case of a test build.
         let this = Self {
             must_stop_processing: MustStopProcessing::default(),
             processing_tasks: Arc::new(RwLock::new(ProcessingTasks::new())),
+            panicked_indexes: Arc::default(),
+            retrying_tasks: Arc::default(),
+            finished_batches: Arc::default(),
+            retention_policy: Arc::new(RetentionPolicy::new(
+                options.task_retention,
+                options.update_file_retention,
+                options.retention_interval,
+            )),
             file_store,
             all_tasks,
             status,
@@ -494,22 +836,40 @@ impl IndexScheduler {
                 budget.index_count,
                 options.enable_mdb_writemap,
                 options.indexer_config,
+                options.encryption_key,
             )?,
             env,
             // we want to start the loop right away in case meilisearch was ctrl+Ced while processing things
             wake_up: Arc::new(SignalEvent::auto(true)),
+            // the payloads that were not delivered before a restart are sent right away
+            webhook_wake_up: Arc::new(SignalEvent::auto(true)),
+            processed_batches: Arc::default(),
             puffin_frame: Arc::new(puffin::GlobalFrameView::default()),
             autobatching_enabled: options.autobatching_enabled,
             cleanup_enabled: options.cleanup_enabled,
+            auto_cleanup_tasks: options.auto_cleanup_tasks,
+            attributes_soft_limit: options.attributes_soft_limit,
+            attributes_hard_limit: options.attributes_hard_limit,
+            max_enqueued_tasks: options.max_enqueued_tasks,
             max_number_of_tasks: options.max_number_of_tasks,
             max_number_of_batched_tasks: options.max_number_of_batched_tasks,
+            max_task_attempts: options.max_task_attempts,
+            redact_payload_errors: options.redact_payload_errors,
+            reenqueue_crashed_tasks: options.reenqueue_crashed_tasks,
+            document_tasks_history_size: options.document_tasks_history_size,
+            recent_searches_size: options.recent_searches_size,
+            crash_recovery,
             dumps_path: options.dumps_path,
             snapshots_path: options.snapshots_path,
             auth_path: options.auth_path,
             version_file_path: options.version_file_path,
-            webhook_url: options.webhook_url,
-            webhook_authorization_header: options.webhook_authorization_header,
+            webhook_settings,
+            webhook_queue,
+            replication: options
+                .replica_primary_url
+                .map(|url| Replication::new(url, options.replica_primary_api_key, outbound_policy)),
             embedders: Default::default(),
+            query_embeddings: Arc::new(Mutex::new(LruMap::new(QUERY_EMBEDDINGS_CACHE_SIZE))),
 
             #[cfg(test)]
             test_breakpoint_sdr,
@@ -518,12 +878,106 @@ impl IndexScheduler {
             #[cfg(test)]
             run_loop_iteration: Arc::new(RwLock::new(0)),
             features,
+            index_groups,
+            recent_searches,
         };
 
+        this.recover_in_flight_batch()?;
         this.run();
+        this.run_webhook();
         Ok(this)
     }
 
+    /// Fails before anything is processed if the instance was encrypted with another key,
+    /// or if its data was written without encryption.
+    fn check_encryption_key(
+        env: &Env,
+        wtxn: &mut RwTxn,
+        all_tasks: Database<BEU32, SerdeJson<Task>>,
+        encryption_key: Option<&EncryptionKey>,
+    ) -> Result<()> {
+        let encryption: Database<Str, Bytes> =
+            env.create_database(wtxn, Some(db_name::ENCRYPTION))?;
+        let key_check = encryption.get(wtxn, "key-check")?;
+        let has_data = !all_tasks.is_empty(wtxn)?;
+        if let Some(key_check) =
+            check_encryption_key(encryption_key, key_check, has_data).map_err(milli::Error::from)?
+        {
+            encryption.put(wtxn, "key-check", &key_check)?;
+        }
+        Ok(())
+    }
+
+    /// Marks the tasks of the batch that was processing when the instance stopped unexpectedly
+    /// as failed, or lets them enqueued to be processed again, and reports it.
+    fn recover_in_flight_batch(&self) -> Result<()> {
+        let mut wtxn = self.env.write_txn()?;
+        let Some(batch) = self.crash_recovery.in_flight_batch(&wtxn)? else { return Ok(()) };
+
+        let enqueued = self.get_status(&wtxn, Status::Enqueued)?;
+        let task_uids: Vec<_> =
+            batch.task_uids.iter().copied().filter(|uid| enqueued.contains(*uid)).collect();
+        let recovered_at = OffsetDateTime::now_utc();
+        let action = if self.reenqueue_crashed_tasks {
+            RecoveryAction::Reenqueued
+        } else {
+            RecoveryAction::Failed
+        };
+
+        let mut failed = Vec::new();
+        if action == RecoveryAction::Failed {
+            let error: ResponseError = Error::CrashedDuringProcessing.into();
+            for &uid in &task_uids {
+                let mut task = self.get_task(&wtxn, uid)?.ok_or(Error::CorruptedTaskQueue)?;
+                task.started_at = Some(batch.started_at);
+                task.finished_at = Some(recovered_at);
+                task.status = Status::Failed;
+                task.error = Some(error.clone());
+                task.details = task.details.map(|d| d.to_failed());
+                self.update_task(&mut wtxn, &task)?;
+                failed.push(task);
+            }
+        }
+
+        let report = CrashRecoveryReport {
+            task_uids,
+            batch_started_at: batch.started_at,
+            recovered_at,
+            action,
+        };
+        self.crash_recovery.put_last_recovery(&mut wtxn, &report)?;
+        self.crash_recovery.delete_in_flight_batch(&mut wtxn)?;
+        if self.webhook_settings.is_enabled() && !failed.is_empty() {
+            let processed = failed.iter().map(|task| task.uid).collect();
+            self.webhook_queue.push(&mut wtxn, &processed, MAX_PENDING_WEBHOOK_PAYLOADS)?;
+        }
+        wtxn.commit()?;
+
+        tracing::warn!(
+            tasks = ?report.task_uids,
+            batch_started_at = %report.batch_started_at,
+            action = ?report.action,
+            "Meilisearch stopped unexpectedly while processing a batch of tasks"
+        );
+
+        for task in failed {
+            if let Err(e) = self.delete_persisted_task_data(&task) {
+                tracing::error!(
+                    "Failure to delete the content files associated with task {}. Error: {e}",
+                    task.uid
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the report of the last recovery from a batch interrupted by a crash, if any.
+    pub fn last_crash_recovery(&self) -> Result<Option<CrashRecoveryReport>> {
+        let rtxn = self.env.read_txn()?;
+        self.crash_recovery.last_recovery(&rtxn)
+    }
+
     /// Return `Ok(())` if the index scheduler is able to access one of its database.
     pub fn health(&self) -> Result<()> {
         let rtxn = self.env.read_txn()?;
@@ -604,6 +1058,28 @@ impl IndexScheduler {
         self.env.read_txn().map_err(|e| e.into())
     }
 
+    /// Start the thread delivering the payloads of the webhook.
+    ///
+    /// This function must be called only once per index scheduler.
+    fn run_webhook(&self) {
+        let run = self.private_clone();
+        std::thread::Builder::new()
+            .name(String::from("webhook"))
+            .spawn(move || loop {
+                // We shouldn't stop the thread if we can't send data to the webhook.
+                if let Err(e) = run.deliver_webhook_payloads() {
+                    tracing::warn!("While delivering the webhook payloads: {e}");
+                }
+                // The undelivered payloads are sent again even if no batch is processed.
+                if run.has_pending_webhook_payloads().unwrap_or_default() {
+                    run.webhook_wake_up.wait_timeout(WEBHOOK_RETRY_INTERVAL);
+                } else {
+                    run.webhook_wake_up.wait();
+                }
+            })
+            .unwrap();
+    }
+
     /// Start the run loop for the given index scheduler.
     ///
     /// This function will execute in a different thread and must be called
@@ -625,7 +1101,23 @@ impl IndexScheduler {
 
                     match run.tick() {
                         Ok(TickOutcome::TickAgain(_)) => (),
-                        Ok(TickOutcome::WaitForSignal) => run.wake_up.wait(),
+                        Ok(TickOutcome::WaitForSignal) => {
+                            // The tasks waiting for their next attempt are retried even if no
+                            // task is registered.
+                            let mut timeout = run.retrying_tasks.read().unwrap().next_retry_in();
+                            // The retention policy is applied even if no task is registered.
+                            if let Some(next_run_in) = run.retention_policy.next_run_in() {
+                                timeout = Some(
+                                    timeout.map_or(next_run_in, |timeout| timeout.min(next_run_in)),
+                                );
+                            }
+                            match timeout {
+                                Some(timeout) => {
+                                    run.wake_up.wait_timeout(timeout);
+                                }
+                                None => run.wake_up.wait(),
+                            }
+                        }
                         Err(e) => {
                             tracing::error!("{e}");
                             // Wait one second when an irrecoverable error occurs.
@@ -787,6 +1279,27 @@ impl IndexScheduler {
             tasks &= &index_tasks;
         }
 
+        if let Some(groups) = &query.index_groups {
+            let mut patterns = Vec::new();
+            for group in groups {
+                let group_patterns = self
+                    .index_groups
+                    .get(rtxn, group)?
+                    .ok_or_else(|| Error::IndexGroupNotFound(group.clone()))?;
+                patterns.extend(group_patterns);
+            }
+
+            // The tasks of the deleted indexes are matched too, as long as their uid matches a pattern.
+            let mut index_tasks = RoaringBitmap::new();
+            for result in self.index_tasks.iter(rtxn)? {
+                let (index, tasks) = result?;
+                if patterns.iter().any(|pattern| pattern.matches_str(index)) {
+                    index_tasks |= tasks;
+                }
+            }
+            tasks &= &index_tasks;
+        }
+
         // For the started_at filter, we need to treat the part of the tasks that are processing from the part of the
         // tasks that are not processing. The non-processing ones are filtered normally while the processing ones
         // are entirely removed unless the in-memory startedAt variable falls within the date filter.
@@ -896,11 +1409,66 @@ impl IndexScheduler {
         Ok(res)
     }
 
+    /// Returns a receiver that changes every time a batch is processed, to wait for a task to
+    /// finish without blocking a thread.
+    pub fn processed_batches(&self) -> watch::Receiver<u64> {
+        self.processed_batches.subscribe()
+    }
+
+    /// Returns `true` if the task is finished or does not exist.
+    pub fn is_task_finished(&self, task_id: TaskId) -> Result<bool> {
+        let rtxn = self.read_txn()?;
+        Ok(match self.get_task(&rtxn, task_id)? {
+            Some(task) => {
+                matches!(task.status, Status::Succeeded | Status::Failed | Status::Canceled)
+            }
+            None => true,
+        })
+    }
+
+    /// Returns the aggregates used to monitor the task queue.
+    ///
+    /// Only the oldest task waiting to be processed is read, the other aggregates are
+    /// maintained every time a batch is committed.
+    pub fn task_queue_stats(&self) -> Result<TaskQueueStats> {
+        let rtxn = self.read_txn()?;
+        let processing = self.processing_tasks.read().unwrap().processing.clone();
+        let enqueued = self.get_status(&rtxn, Status::Enqueued)? - processing;
+        let oldest_enqueued_task_age_seconds = match enqueued.min() {
+            Some(task_id) => {
+                let task = self.get_task(&rtxn, task_id)?.ok_or(Error::CorruptedTaskQueue)?;
+                let age = OffsetDateTime::now_utc() - task.enqueued_at;
+                Some(age.as_seconds_f64().max(0.0))
+            }
+            None => None,
+        };
+
+        let mut finished_batches = self.finished_batches.lock().unwrap();
+        Ok(TaskQueueStats {
+            oldest_enqueued_task_age_seconds,
+            finished_tasks_per_minute: finished_batches.finished_tasks_per_minute(),
+            average_batch_duration_seconds: finished_batches
+                .average_duration()
+                .map(|duration| duration.as_secs_f64()),
+            tasks_deleted_by_retention: self.retention_policy.deleted_tasks(),
+            update_files_deleted_by_retention: self.retention_policy.deleted_update_files(),
+        })
+    }
+
     // Return true if there is at least one task that is processing.
     pub fn is_task_processing(&self) -> Result<bool> {
         Ok(!self.processing_tasks.read().unwrap().processing.is_empty())
     }
 
+    /// Returns the steps reported by the batch being processed, along with the ids of its tasks.
+    ///
+    /// Only the dumps and snapshots report their progress for now.
+    pub fn processing_progress(&self) -> Option<(RoaringBitmap, ProgressView)> {
+        let processing_tasks = self.processing_tasks.read().unwrap();
+        let progress = processing_tasks.progress.as_view()?;
+        Some((processing_tasks.processing.clone(), progress))
+    }
+
     /// Return true iff there is at least one task associated with this index
     /// that is processing.
     pub fn is_index_processing(&self, index: &str) -> Result<bool> {
@@ -932,11 +1500,16 @@ impl IndexScheduler {
         // us from modifying the underlying implementation, and the performance remains sufficient.
         // Should this change, we would modify `get_task_ids` to directly return the number of matching tasks.
         let total_tasks = self.get_task_ids(rtxn, &query.clone().without_limits())?;
-        let mut tasks = self.get_task_ids(rtxn, query)?;
+        // The limit must only be applied once the tasks the user can't see have been removed,
+        // otherwise a page could be shorter than requested and the pagination would stop early.
+        let mut tasks = self.get_task_ids(rtxn, &Query { limit: None, ..query.clone() })?;
 
         // If the query contains a list of index uid or there is a finite list of authorized indexes,
         // then we must exclude all the kinds that aren't associated to one and only one index.
-        if query.index_uids.is_some() || !filters.all_indexes_authorized() {
+        if query.index_uids.is_some()
+            || query.index_groups.is_some()
+            || !filters.all_indexes_authorized()
+        {
             for kind in enum_iterator::all::<Kind>().filter(|kind| !kind.related_to_one_index()) {
                 tasks -= self.get_kind(rtxn, kind)?;
             }
@@ -954,6 +1527,10 @@ impl IndexScheduler {
             }
         }
 
+        if let Some(limit) = query.limit {
+            tasks = tasks.into_iter().rev().take(limit as usize).collect();
+        }
+
         Ok((tasks, total_tasks.len()))
     }
 
@@ -1009,15 +1586,78 @@ impl IndexScheduler {
         task_id: Option<TaskId>,
         dry_run: bool,
     ) -> Result<Task> {
+        let details = kind.default_details();
+        self.register_with_details(kind, details, task_id, dry_run)
+    }
+
+    /// Register a new task in the scheduler with details known before it is processed,
+    /// e.g. the invalid UTF-8 sequences replaced while its payload was received.
+    pub fn register_with_details(
+        &self,
+        kind: KindWithContent,
+        details: Option<Details>,
+        task_id: Option<TaskId>,
+        dry_run: bool,
+    ) -> Result<Task> {
+        match &self.replication {
+            Some(_) if !Replication::accepts(&kind) => {
+                return Err(Error::ReadOnlyReplica(kind.as_kind()))
+            }
+            None if matches!(kind, KindWithContent::SnapshotImportIncremental) => {
+                return Err(Error::ReplicationNotConfigured)
+            }
+            _ => (),
+        }
+
         let mut wtxn = self.env.write_txn()?;
 
         // if the task doesn't delete anything and 50% of the task queue is full, we must refuse to enqueue the incomming task
         if !matches!(&kind, KindWithContent::TaskDeletion { tasks, .. } if !tasks.is_empty())
-            && (self.env.non_free_pages_size()? * 100) / self.env.info().map_size as u64 > 50
+            && self.is_task_queue_full()?
         {
+            // the deletion is checked for and registered in the same write transaction, so that
+            // the concurrent registrations don't enqueue it several times
+            if self.auto_cleanup_tasks && !matches!(kind, KindWithContent::TaskDeletion { .. }) {
+                match self.register_deletion_of_oldest_succeeded_tasks(&mut wtxn) {
+                    Ok(true) => match wtxn.commit() {
+                        Ok(()) => self.wake_up.signal(),
+                        Err(e) => tracing::error!("While auto-cleaning the full task queue: {e}"),
+                    },
+                    Ok(false) => (),
+                    Err(e) => tracing::error!("While auto-cleaning the full task queue: {e}"),
+                }
+            }
             return Err(Error::NoSpaceLeftInTaskQueue);
         }
 
+        // the tasks that help the operators recover from a full queue are never refused
+        let is_priority_task = matches!(
+            kind,
+            KindWithContent::TaskCancelation { .. }
+                | KindWithContent::TaskDeletion { .. }
+                | KindWithContent::DumpCreation { .. }
+        );
+        if let Some(limit) = self.max_enqueued_tasks.filter(|_| !is_priority_task) {
+            // The processing tasks are still marked as enqueued in the database, so they are
+            // counted too. The count can be slightly off if a batch finishes meanwhile.
+            let enqueued = self.get_status(&wtxn, Status::Enqueued)?.len();
+            if enqueued >= limit {
+                let exceeding = enqueued - limit + 1;
+                let retry_after = self
+                    .processing_tasks
+                    .read()
+                    .unwrap()
+                    .estimate_processing_time(exceeding)
+                    .as_secs_f64()
+                    .ceil() as u64;
+                return Err(Error::TooManyEnqueuedTasks {
+                    enqueued,
+                    limit,
+                    retry_after: retry_after.max(1),
+                });
+            }
+        }
+
         let next_task_id = self.next_task_id(&wtxn)?;
 
         if let Some(uid) = task_id {
@@ -1033,9 +1673,10 @@ impl IndexScheduler {
             finished_at: None,
             error: None,
             canceled_by: None,
-            details: kind.default_details(),
+            details,
             status: Status::Enqueued,
             kind: kind.clone(),
+            attempts: Vec::new(),
         };
         // For deletion and cancelation tasks, we want to make extra sure that they
         // don't attempt to delete/cancel tasks that are newer than themselves.
@@ -1052,23 +1693,7 @@ impl IndexScheduler {
         // Get rid of the mutability.
         let task = task;
 
-        self.all_tasks.put_with_flags(&mut wtxn, PutFlags::APPEND, &task.uid, &task)?;
-
-        for index in task.indexes() {
-            self.update_index(&mut wtxn, index, |bitmap| {
-                bitmap.insert(task.uid);
-            })?;
-        }
-
-        self.update_status(&mut wtxn, Status::Enqueued, |bitmap| {
-            bitmap.insert(task.uid);
-        })?;
-
-        self.update_kind(&mut wtxn, task.kind.as_kind(), |bitmap| {
-            bitmap.insert(task.uid);
-        })?;
-
-        utils::insert_task_datetime(&mut wtxn, self.enqueued_at, task.enqueued_at, task.uid)?;
+        self.put_enqueued_task(&mut wtxn, &task)?;
 
         if let Err(e) = wtxn.commit() {
             self.delete_persisted_task_data(&task)?;
@@ -1091,6 +1716,104 @@ impl IndexScheduler {
         Ok(task)
     }
 
+    /// Returns `true` if more than 50% of the task database is used.
+    ///
+    /// Past this point, only task deletions that actually delete something can be registered.
+    fn is_task_queue_full(&self) -> Result<bool> {
+        Ok((self.env.non_free_pages_size()? * 100) / self.env.info().map_size as u64 > 50)
+    }
+
+    /// Writes an enqueued task and adds it to the indexes of the task queue.
+    fn put_enqueued_task(&self, wtxn: &mut RwTxn, task: &Task) -> Result<()> {
+        self.all_tasks.put_with_flags(wtxn, PutFlags::APPEND, &task.uid, task)?;
+
+        for index in task.indexes() {
+            self.update_index(wtxn, index, |bitmap| {
+                bitmap.insert(task.uid);
+            })?;
+        }
+
+        self.update_status(wtxn, Status::Enqueued, |bitmap| {
+            bitmap.insert(task.uid);
+        })?;
+
+        self.update_kind(wtxn, task.kind.as_kind(), |bitmap| {
+            bitmap.insert(task.uid);
+        })?;
+
+        utils::insert_task_datetime(wtxn, self.enqueued_at, task.enqueued_at, task.uid)?;
+
+        Ok(())
+    }
+
+    /// Register a task deleting the oldest half of the succeeded tasks in the write transaction,
+    /// unless a task deletion is already pending.
+    ///
+    /// Used to free the task database when it's full and the `auto_cleanup_tasks` option is enabled.
+    /// Returns whether a task was registered, the transaction must then be committed.
+    fn register_deletion_of_oldest_succeeded_tasks(&self, wtxn: &mut RwTxn) -> Result<bool> {
+        // The processing tasks are still marked as enqueued in the database. A pending deletion
+        // will free the task database by itself, there is no need to enqueue another one.
+        let pending_deletions =
+            self.get_kind(wtxn, Kind::TaskDeletion)? & self.get_status(wtxn, Status::Enqueued)?;
+        for task_id in pending_deletions {
+            let task = self.get_task(wtxn, task_id)?.ok_or(Error::CorruptedTaskQueue)?;
+            if matches!(task.kind, KindWithContent::TaskDeletion { tasks, .. } if !tasks.is_empty())
+            {
+                tracing::debug!(
+                    "The task queue is full, but task {task_id} is already deleting tasks."
+                );
+                return Ok(false);
+            }
+        }
+
+        let succeeded = self.status.get(wtxn, &Status::Succeeded)?.unwrap_or_default();
+        let to_delete =
+            RoaringBitmap::from_iter(succeeded.iter().take((succeeded.len() as usize + 1) / 2));
+
+        let newest_task_id = match to_delete.max() {
+            Some(task_id) => task_id,
+            None => {
+                tracing::warn!("The task queue is full, but no succeeded task can be deleted.");
+                return Ok(false);
+            }
+        };
+        let last_task_to_delete =
+            self.get_task(wtxn, newest_task_id)?.ok_or(Error::CorruptedTaskQueue)?;
+
+        tracing::info!(
+            "The task queue is full. Deleting the oldest {} succeeded tasks.",
+            to_delete.len()
+        );
+
+        // increase time by one nanosecond so that the enqueuedAt of the last task to delete is also lower than that date.
+        let delete_before = last_task_to_delete.enqueued_at + Duration::from_nanos(1);
+
+        let kind = KindWithContent::TaskDeletion {
+            query: format!(
+                "?beforeEnqueuedAt={}&statuses=succeeded",
+                delete_before.format(&Rfc3339).map_err(|_| Error::CorruptedTaskQueue)?,
+            ),
+            tasks: to_delete,
+        };
+        let mut task = Task {
+            uid: self.next_task_id(wtxn)?,
+            enqueued_at: OffsetDateTime::now_utc(),
+            started_at: None,
+            finished_at: None,
+            error: None,
+            canceled_by: None,
+            details: kind.default_details(),
+            status: Status::Enqueued,
+            kind,
+            attempts: Vec::new(),
+        };
+        filter_out_references_to_newer_tasks(&mut task);
+        self.put_enqueued_task(wtxn, &task)?;
+
+        Ok(true)
+    }
+
     /// Register a new task coming from a dump in the scheduler.
     /// By taking a mutable ref we're pretty sure no one will ever import a dump while actix is running.
     pub fn register_dumped_task(&mut self) -> Result<Dump> {
@@ -1138,6 +1861,15 @@ impl IndexScheduler {
         Ok(self.file_store.delete(uuid)?)
     }
 
+    /// Truncates the long document values echoed in the error when `redact_payload_errors` is enabled.
+    pub(crate) fn redact_payloads(&self, error: Error) -> Error {
+        if self.redact_payload_errors {
+            error.redact_payloads()
+        } else {
+            error
+        }
+    }
+
     /// Perform one iteration of the run loop.
     ///
     /// 1. See if we need to cleanup the task queue
@@ -1162,6 +1894,12 @@ impl IndexScheduler {
             self.cleanup_task_queue()?;
         }
 
+        // The retention policy is applied before the next batch is created, so that it never
+        // races with the processing of a batch.
+        if let Err(e) = self.apply_retention_policy() {
+            tracing::error!("While applying the retention policy of the task queue: {e}");
+        }
+
         let rtxn = self.env.read_txn().map_err(Error::HeedTransaction)?;
         let batch =
             match self.create_next_batch(&rtxn).map_err(|e| Error::CreateBatch(Box::new(e)))? {
@@ -1180,6 +1918,13 @@ impl IndexScheduler {
         self.must_stop_processing.reset();
         self.processing_tasks.write().unwrap().start_processing_at(started_at, ids.clone());
 
+        // The batch is deleted in the same transaction as the result of its tasks,
+        // finding it at startup means that the instance crashed while processing it.
+        let mut wtxn = self.env.write_txn().map_err(Error::HeedTransaction)?;
+        let in_flight_batch = InFlightBatch { task_uids: ids.iter().collect(), started_at };
+        self.crash_recovery.put_in_flight_batch(&mut wtxn, &in_flight_batch)?;
+        wtxn.commit().map_err(Error::HeedTransaction)?;
+
         #[cfg(test)]
         self.breakpoint(Breakpoint::BatchCreated);
 
@@ -1190,9 +1935,24 @@ impl IndexScheduler {
                 .name(String::from("batch-operation"))
                 .spawn(move || cloned_index_scheduler.process_batch(batch))
                 .unwrap();
-            handle.join().unwrap_or(Err(Error::ProcessBatchPanicked))
+            handle.join().unwrap_or_else(|panic| {
+                Err(Error::ProcessBatchPanicked(panic_message(panic.as_ref())))
+            })
         };
 
+        // Park the index if its batches keep panicking so the other indexes can still be processed
+        if let Some(index_uid) = &index_uid {
+            let panic = match &res {
+                Err(Error::ProcessBatchPanicked(message)) => Some(message.as_str()),
+                _ => None,
+            };
+            if self.panicked_indexes.write().unwrap().register_batch(index_uid, panic) {
+                tracing::error!(
+                    "The index `{index_uid}` is parked because {MAX_CONSECUTIVE_PANICS} of its batches panicked in a row."
+                );
+            }
+        }
+
         // Reset the currently updating index to relinquish the index handle
         self.index_mapper.set_currently_updating_index(None);
 
@@ -1202,6 +1962,8 @@ impl IndexScheduler {
         let mut wtxn = self.env.write_txn().map_err(Error::HeedTransaction)?;
 
         let finished_at = OffsetDateTime::now_utc();
+        // The tasks that are enqueued again to be retried after a transient failure.
+        let mut retried = RoaringBitmap::new();
         match res {
             Ok(tasks) => {
                 #[cfg(test)]
@@ -1239,7 +2001,9 @@ impl IndexScheduler {
             | Err(Error::AbortedTask) => {
                 #[cfg(test)]
                 self.breakpoint(Breakpoint::AbortedIndexation);
-                wtxn.abort();
+                // the tasks are still enqueued, they will be part of the next batch
+                self.crash_recovery.delete_in_flight_batch(&mut wtxn)?;
+                wtxn.commit().map_err(Error::HeedTransaction)?;
 
                 tracing::info!("A batch of tasks was aborted.");
                 // We make sure that we don't call `stop_processing` on the `processing_tasks`,
@@ -1261,27 +2025,46 @@ impl IndexScheduler {
                 let index_uid = index_uid.unwrap();
                 // fixme: handle error more gracefully? not sure when this could happen
                 self.index_mapper.resize_index(&wtxn, &index_uid)?;
-                wtxn.abort();
+                self.crash_recovery.delete_in_flight_batch(&mut wtxn)?;
+                wtxn.commit().map_err(Error::HeedTransaction)?;
 
                 tracing::info!("The max database size was reached. Resizing the index.");
 
                 return Ok(TickOutcome::TickAgain(0));
             }
             // In case of a failure we must get back and patch all the tasks with the error.
+            // If the error is transient the tasks are enqueued again, until they reach the maximum number of attempts.
             Err(err) => {
                 #[cfg(test)]
                 self.breakpoint(Breakpoint::ProcessBatchFailed);
-                let error: ResponseError = err.into();
+                let is_transient = err.is_transient();
+                let error: ResponseError = self.redact_payloads(err).into();
                 for id in ids.iter() {
                     let mut task = self
                         .get_task(&wtxn, id)
                         .map_err(|e| Error::TaskDatabaseUpdate(Box::new(e)))?
                         .ok_or(Error::CorruptedTaskQueue)?;
-                    task.started_at = Some(started_at);
-                    task.finished_at = Some(finished_at);
-                    task.status = Status::Failed;
-                    task.error = Some(error.clone());
-                    task.details = task.details.map(|d| d.to_failed());
+                    if is_transient {
+                        let attempt = TaskAttempt { started_at, finished_at, error: error.clone() };
+                        task.attempts.push(attempt);
+                    }
+
+                    let failed_attempts = task.attempts.len() as u32;
+                    if is_transient && failed_attempts < self.max_task_attempts {
+                        let delay =
+                            self.retrying_tasks.write().unwrap().delay(task.uid, failed_attempts);
+                        tracing::warn!(
+                            "Task {} failed because of a transient error, attempting it again in {delay:?}: {error}",
+                            task.uid
+                        );
+                        retried.insert(task.uid);
+                    } else {
+                        task.started_at = Some(started_at);
+                        task.finished_at = Some(finished_at);
+                        task.status = Status::Failed;
+                        task.error = Some(error.clone());
+                        task.details = task.details.map(|d| d.to_failed());
+                    }
 
                     #[cfg(test)]
                     self.maybe_fail(tests::FailureLocation::UpdatingTaskAfterProcessBatchFailure)?;
@@ -1294,21 +2077,28 @@ impl IndexScheduler {
             }
         }
 
-        let processed = self.processing_tasks.write().unwrap().stop_processing();
+        let processed = self.processing_tasks.write().unwrap().stop_processing() - &retried;
+        self.crash_recovery.delete_in_flight_batch(&mut wtxn)?;
 
         #[cfg(test)]
         self.maybe_fail(tests::FailureLocation::CommittingWtxn)?;
 
         wtxn.commit().map_err(Error::HeedTransaction)?;
+        self.finished_batches
+            .lock()
+            .unwrap()
+            .register(processed.len(), (finished_at - started_at).unsigned_abs());
+        self.processed_batches.notify();
 
         // Once the tasks are committed, we should delete all the update files associated ASAP to avoid leaking files in case of a restart
         tracing::debug!("Deleting the update files");
 
         //We take one read transaction **per thread**. Then, every thread is going to pull out new IDs from the roaring bitmap with the help of an atomic shared index into the bitmap
+        let finished = &ids - &retried;
         let idx = AtomicU32::new(0);
         (0..current_num_threads()).into_par_iter().try_for_each(|_| -> Result<()> {
             let rtxn = self.read_txn()?;
-            while let Some(id) = ids.select(idx.fetch_add(1, Ordering::Relaxed)) {
+            while let Some(id) = finished.select(idx.fetch_add(1, Ordering::Relaxed)) {
                 let task = self
                     .get_task(&rtxn, id)
                     .map_err(|e| Error::TaskDatabaseUpdate(Box::new(e)))?
@@ -1323,8 +2113,14 @@ impl IndexScheduler {
             Ok(())
         })?;
 
-        // We shouldn't crash the tick function if we can't send data to the webhook.
-        let _ = self.notify_webhook(&processed);
+        // The processed tasks are sent to the webhook by its own thread, so that a slow webhook
+        // never delays the processing of the next batch.
+        if self.webhook_settings.is_enabled() && !processed.is_empty() {
+            let mut wtxn = self.env.write_txn().map_err(Error::HeedTransaction)?;
+            self.webhook_queue.push(&mut wtxn, &processed, MAX_PENDING_WEBHOOK_PAYLOADS)?;
+            wtxn.commit().map_err(Error::HeedTransaction)?;
+            self.webhook_wake_up.signal();
+        }
 
         #[cfg(test)]
         self.breakpoint(Breakpoint::AfterProcessing);
@@ -1332,11 +2128,81 @@ impl IndexScheduler {
         Ok(TickOutcome::TickAgain(processed_tasks))
     }
 
-    /// Once the tasks changes have been committed we must send all the tasks that were updated to our webhook if there is one.
-    fn notify_webhook(&self, updated: &RoaringBitmap) -> Result<()> {
-        if let Some(ref url) = self.webhook_url {
-            struct TaskReader<'a, 'b> {
-                rtxn: &'a RoTxn<'a>,
+    /// Returns `true` if some payloads are waiting to be delivered to the webhook.
+    fn has_pending_webhook_payloads(&self) -> Result<bool> {
+        let rtxn = self.env.read_txn()?;
+        Ok(self.webhook_queue.next(&rtxn)?.is_some())
+    }
+
+    /// Returns the delivery state of the webhook, if there is one.
+    pub fn webhook_status(&self) -> Result<Option<WebhookStatus>> {
+        let Some(url) = self.webhook_settings.get().url else {
+            return Ok(None);
+        };
+        let rtxn = self.env.read_txn()?;
+        Ok(Some(self.webhook_queue.status(&rtxn, Some(url))?))
+    }
+
+    /// Returns where the payloads of the webhook are sent.
+    pub fn webhook_settings(&self) -> WebhookSettings {
+        self.webhook_settings.get()
+    }
+
+    /// Changes where the payloads of the webhook are sent, the pending payloads are sent to the new URL.
+    ///
+    /// Without a URL the webhook is disabled and its pending payloads are dropped.
+    pub fn put_webhook_settings(&self, settings: WebhookSettings) -> Result<()> {
+        let wtxn = self.env.write_txn().map_err(Error::HeedTransaction)?;
+        self.webhook_settings.put(wtxn, settings)?;
+        // the last error was about the previous settings
+        self.webhook_queue.set_last_error(None);
+        self.webhook_wake_up.signal();
+        Ok(())
+    }
+
+    /// Returns the path at which the snapshots of this instance are created.
+    pub fn snapshot_path(&self) -> PathBuf {
+        // TODO find a better way to get this path
+        let mut base_path = self.env.path().to_owned();
+        base_path.pop();
+        let db_name = base_path.file_name().and_then(OsStr::to_str).unwrap_or("data.ms");
+        self.snapshots_path.join(format!("{}.snapshot", db_name))
+    }
+
+    /// Returns the sync state with the primary instance, if this instance is a read-only replica.
+    pub fn replication_status(&self) -> Option<ReplicationStatus> {
+        self.replication.as_ref().map(Replication::status)
+    }
+
+    /// Sends the payloads waiting to be delivered to the webhook, in order.
+    ///
+    /// Stops at the first payload that is not acknowledged by the webhook, it will be sent again later.
+    fn deliver_webhook_payloads(&self) -> Result<()> {
+        loop {
+            let rtxn = self.env.read_txn()?;
+            let Some((sequence, tasks)) = self.webhook_queue.next(&rtxn)? else {
+                return Ok(());
+            };
+            drop(rtxn);
+
+            if !self.notify_webhook(sequence, &tasks)? {
+                return Ok(());
+            }
+
+            let mut wtxn = self.env.write_txn()?;
+            self.webhook_queue.acknowledge(&mut wtxn, sequence)?;
+            wtxn.commit()?;
+        }
+    }
+
+    /// Once the tasks changes have been committed we must send all the tasks that were updated to our webhook if there is one.
+    ///
+    /// Returns `true` if the webhook acknowledged the payload.
+    fn notify_webhook(&self, sequence: u64, updated: &RoaringBitmap) -> Result<bool> {
+        let WebhookSettings { url, authorization_header } = self.webhook_settings.get();
+        if let Some(ref url) = url {
+            struct TaskReader<'a, 'b> {
+                rtxn: &'a RoTxn<'a>,
                 index_scheduler: &'a IndexScheduler,
                 tasks: &'b mut roaring::bitmap::Iter<'b>,
                 buffer: Vec<u8>,
@@ -1385,31 +2251,52 @@ impl IndexScheduler {
 
             let rtxn = self.env.read_txn()?;
 
+            // The tasks deleted since the payload was registered are not sent.
+            let updated = updated & self.all_task_ids(&rtxn)?;
             let task_reader = TaskReader {
                 rtxn: &rtxn,
                 index_scheduler: self,
-                tasks: &mut updated.into_iter(),
+                tasks: &mut updated.iter(),
                 buffer: Vec::with_capacity(50), // on average a task is around ~100 bytes
                 written: 0,
             };
 
+            // The payload stays in the queue, it will be sent once the allow-list accepts the URL.
+            let outbound_policy = &self.index_mapper.indexer_config().outbound_policy;
+            if let Err(error) = outbound_policy.check_url(url) {
+                tracing::warn!("While sending data to the webhook: {error}");
+                self.webhook_queue.set_last_error(Some(Error::from(error).into()));
+                return Ok(false);
+            }
+
             // let reader = GzEncoder::new(BufReader::new(task_reader), Compression::default());
             let reader = GzEncoder::new(BufReader::new(task_reader), Compression::default());
-            let request = ureq::post(url)
+            let request = outbound_policy
+                .agent()
+                .post(url)
                 .timeout(Duration::from_secs(30))
                 .set("Content-Encoding", "gzip")
-                .set("Content-Type", "application/x-ndjson");
-            let request = match &self.webhook_authorization_header {
+                .set("Content-Type", "application/x-ndjson")
+                .set("X-Meilisearch-Webhook-Sequence", &sequence.to_string());
+            let request = match &authorization_header {
                 Some(header) => request.set("Authorization", header),
                 None => request,
             };
 
             if let Err(e) = request.send(reader) {
-                tracing::error!("While sending data to the webhook: {e}");
+                tracing::warn!("While sending data to the webhook: {e}");
+                let reason = match e {
+                    ureq::Error::Status(code, _) => format!("it answered with the status {code}."),
+                    ureq::Error::Transport(transport) => transport.to_string(),
+                };
+                let error = Error::WebhookDeliveryFailed { url: url.clone(), reason };
+                self.webhook_queue.set_last_error(Some(error.into()));
+                return Ok(false);
             }
+            self.webhook_queue.set_last_error(None);
         }
 
-        Ok(())
+        Ok(true)
     }
 
     /// Register a task to cleanup the task queue if needed
@@ -1467,10 +2354,33 @@ impl IndexScheduler {
 
     pub fn index_stats(&self, index_uid: &str) -> Result<IndexStats> {
         let is_indexing = self.is_index_processing(index_uid)?;
+        let parked = self.parked_index(index_uid);
         let rtxn = self.read_txn()?;
         let index_stats = self.index_mapper.stats_of(&rtxn, index_uid)?;
 
-        Ok(IndexStats { is_indexing, inner_stats: index_stats })
+        Ok(IndexStats { is_indexing, parked, inner_stats: index_stats })
+    }
+
+    /// Returns the samples of the stats of the index recorded after each batch, oldest first.
+    pub fn index_stats_history(&self, index_uid: &str) -> Result<Vec<IndexStatsSample>> {
+        let rtxn = self.read_txn()?;
+        self.index_mapper.stats_history_of(&rtxn, index_uid)
+    }
+
+    /// Returns the parked state of the index, if its batches panicked too many times in a row.
+    pub fn parked_index(&self, index_uid: &str) -> Option<ParkedIndex> {
+        self.panicked_indexes.read().unwrap().parked.get(index_uid).cloned()
+    }
+
+    /// Unparks the index so its enqueued tasks are processed again.
+    ///
+    /// Returns `true` if the index was parked.
+    pub fn unpark_index(&self, index_uid: &str) -> bool {
+        let unparked = self.panicked_indexes.write().unwrap().unpark(index_uid);
+        if unparked {
+            self.wake_up.signal();
+        }
+        unparked
     }
 
     pub fn features(&self) -> RoFeatures {
@@ -1483,6 +2393,51 @@ impl IndexScheduler {
         Ok(())
     }
 
+    /// Returns the index uid patterns of every index group, by group name.
+    pub fn index_groups(&self) -> Result<BTreeMap<String, Vec<IndexUidPattern>>> {
+        let rtxn = self.env.read_txn().map_err(Error::HeedTransaction)?;
+        self.index_groups.all(&rtxn)
+    }
+
+    /// Returns the index uid patterns of this index group.
+    pub fn index_group(&self, name: &str) -> Result<Vec<IndexUidPattern>> {
+        let rtxn = self.env.read_txn().map_err(Error::HeedTransaction)?;
+        self.index_groups
+            .get(&rtxn, name)?
+            .ok_or_else(|| Error::IndexGroupNotFound(name.to_string()))
+    }
+
+    /// Creates the index group or replaces its patterns if it already exists.
+    pub fn put_index_group(&self, name: &str, patterns: Vec<IndexUidPattern>) -> Result<()> {
+        let mut wtxn = self.env.write_txn().map_err(Error::HeedTransaction)?;
+        self.index_groups.put(&mut wtxn, name, &patterns)?;
+        wtxn.commit().map_err(Error::HeedTransaction)?;
+        Ok(())
+    }
+
+    pub fn delete_index_group(&self, name: &str) -> Result<()> {
+        let mut wtxn = self.env.write_txn().map_err(Error::HeedTransaction)?;
+        if !self.index_groups.delete(&mut wtxn, name)? {
+            return Err(Error::IndexGroupNotFound(name.to_string()));
+        }
+        wtxn.commit().map_err(Error::HeedTransaction)?;
+        Ok(())
+    }
+
+    /// Records a search made on the index, only keeping its last searches.
+    pub fn record_recent_search(&self, index_uid: &str, search: serde_json::Value) -> Result<()> {
+        let mut wtxn = self.env.write_txn().map_err(Error::HeedTransaction)?;
+        self.recent_searches.push(&mut wtxn, index_uid, search, self.recent_searches_size)?;
+        wtxn.commit().map_err(Error::HeedTransaction)?;
+        Ok(())
+    }
+
+    /// Returns the last searches made on the index, newest first.
+    pub fn recent_searches(&self, index_uid: &str) -> Result<Vec<serde_json::Value>> {
+        let rtxn = self.env.read_txn().map_err(Error::HeedTransaction)?;
+        self.recent_searches.get(&rtxn, index_uid)
+    }
+
     pub(crate) fn delete_persisted_task_data(&self, task: &Task) -> Result<()> {
         match task.content_uuid() {
             Some(content_file) => self.delete_update_file(content_file),
@@ -1510,9 +2465,12 @@ impl IndexScheduler {
 
                 // add missing embedder
                 let embedder = Arc::new(
-                    Embedder::new(embedder_options.clone())
-                        .map_err(meilisearch_types::milli::vector::Error::from)
-                        .map_err(meilisearch_types::milli::Error::from)?,
+                    Embedder::new(
+                        embedder_options.clone(),
+                        &self.index_mapper.indexer_config().outbound_policy,
+                    )
+                    .map_err(meilisearch_types::milli::vector::Error::from)
+                    .map_err(meilisearch_types::milli::Error::from)?,
                 );
                 {
                     let mut embedders = self.embedders.write().unwrap();
@@ -1524,6 +2482,11 @@ impl IndexScheduler {
         res.map(EmbeddingConfigs::new)
     }
 
+    /// Returns a handle to embed the search queries with the embedder configured by `embedder_options`.
+    pub fn query_embedding_cache(&self, embedder_options: &EmbedderOptions) -> QueryEmbeddingCache {
+        QueryEmbeddingCache::new(self.query_embeddings.clone(), embedder_options)
+    }
+
     /// Blocks the thread until the test handle asks to progress to/through this breakpoint.
     ///
     /// Two messages are sent through the channel for each breakpoint.
@@ -1653,6 +2616,24 @@ impl<'a> Dump<'a> {
                     primary_key,
                 },
                 KindDump::IndexSwap { swaps } => KindWithContent::IndexSwap { swaps },
+                KindDump::IndexRestore { snapshot_path, snapshot_index_uid } => {
+                    KindWithContent::IndexRestore {
+                        index_uid: task.index_uid.ok_or(Error::CorruptedDump)?,
+                        snapshot_path,
+                        snapshot_index_uid,
+                    }
+                }
+                KindDump::IntegrityCheck { repair } => KindWithContent::IntegrityCheck {
+                    index_uid: task.index_uid.ok_or(Error::CorruptedDump)?,
+                    repair,
+                },
+                KindDump::IndexWarmup { prefixes, populate_cache } => {
+                    KindWithContent::IndexWarmup {
+                        index_uid: task.index_uid.ok_or(Error::CorruptedDump)?,
+                        prefixes,
+                        populate_cache,
+                    }
+                }
                 KindDump::TaskCancelation { query, tasks } => {
                     KindWithContent::TaskCancelation { query, tasks }
                 }
@@ -1663,7 +2644,9 @@ impl<'a> Dump<'a> {
                     KindWithContent::DumpCreation { keys, instance_uid }
                 }
                 KindDump::SnapshotCreation => KindWithContent::SnapshotCreation,
+                KindDump::SnapshotImportIncremental => KindWithContent::SnapshotImportIncremental,
             },
+            attempts: task.attempts,
         };
 
         self.index_scheduler.all_tasks.put(&mut self.wtxn, &task.uid, &task)?;
@@ -1760,6 +2743,8 @@ struct IndexBudget {
 pub struct IndexStats {
     /// Whether this index is currently performing indexation, according to the scheduler.
     pub is_indexing: bool,
+    /// Whether this index is parked because its batches kept panicking.
+    pub parked: Option<ParkedIndex>,
     /// Internal stats computed from the index.
     pub inner_stats: index_mapper::IndexStats,
 }
@@ -1796,6 +2781,7 @@ mod tests {
         InsideCreateBatch,
         InsideProcessBatch,
         PanicInsideProcessBatch,
+        TransientInsideProcessBatch,
         AcquiringWtxn,
         UpdatingTaskAfterProcessBatchSuccess { task_uid: u32 },
         UpdatingTaskAfterProcessBatchFailure,
@@ -1831,6 +2817,8 @@ mod tests {
                 dumps_path: tempdir.path().join("dumps"),
                 webhook_url: None,
                 webhook_authorization_header: None,
+                replica_primary_url: None,
+                replica_primary_api_key: None,
                 task_db_size: 1000 * 1000, // 1 MB, we don't use MiB on purpose.
                 index_base_map_size: 1000 * 1000, // 1 MB, we don't use MiB on purpose.
                 enable_mdb_writemap: false,
@@ -1840,7 +2828,20 @@ mod tests {
                 autobatching_enabled: true,
                 cleanup_enabled: true,
                 max_number_of_tasks: 1_000_000,
+                auto_cleanup_tasks: false,
+                attributes_soft_limit: 100,
+                attributes_hard_limit: None,
+                max_enqueued_tasks: None,
                 max_number_of_batched_tasks: usize::MAX,
+                max_task_attempts: 3,
+                redact_payload_errors: false,
+                reenqueue_crashed_tasks: false,
+                document_tasks_history_size: 5,
+                recent_searches_size: 3,
+                encryption_key: None,
+                task_retention: None,
+                update_file_retention: None,
+                retention_interval: DEFAULT_RETENTION_INTERVAL,
                 instance_features: Default::default(),
             };
             configuration(&mut options);
@@ -1875,6 +2876,9 @@ mod tests {
                     FailureLocation::PanicInsideProcessBatch => {
                         panic!("simulated panic")
                     }
+                    FailureLocation::TransientInsideProcessBatch => {
+                        Err(Error::PlannedTransientFailure)
+                    }
                     _ => Err(Error::PlannedFailure),
                 }
             } else {
@@ -2116,6 +3120,25 @@ mod tests {
         snapshot!(snapshot_index_scheduler(&index_scheduler), name: "registered_the_third_task");
     }
 
+    #[test]
+    fn wait_for_task() {
+        let (index_scheduler, mut handle) = IndexScheduler::test(true, vec![]);
+        let mut processed_batches = index_scheduler.processed_batches();
+        index_scheduler.register(index_creation_task("catto", "mouse"), None, false).unwrap();
+
+        // the scheduler does not process the task until the handle lets it
+        assert!(!index_scheduler.is_task_finished(0).unwrap());
+        assert!(!processed_batches.has_changed().unwrap());
+
+        handle.advance_one_successful_batch();
+        assert!(processed_batches.has_changed().unwrap());
+        processed_batches.borrow_and_update();
+        assert!(index_scheduler.is_task_finished(0).unwrap());
+
+        // a task that does not exist is not waited for
+        assert!(index_scheduler.is_task_finished(42).unwrap());
+    }
+
     #[test]
     fn test_task_is_processing() {
         let (index_scheduler, mut handle) = IndexScheduler::test(true, vec![]);
@@ -2477,7 +3500,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -2537,7 +3560,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -2916,7 +3939,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -2967,7 +3990,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -3023,7 +4046,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -3138,7 +4161,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -3193,7 +4216,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -3581,6 +4604,33 @@ mod tests {
             .unwrap();
         // we asked for all the tasks with all index authorized -> all tasks returned
         snapshot!(snapshot_bitmap(&tasks), @"[0,1,2,3,]");
+
+        let query = Query { limit: Some(1), ..Default::default() };
+        let (tasks, _) = index_scheduler
+            .get_task_ids_from_authorized_indexes(
+                &rtxn,
+                &query,
+                &AuthFilter::with_allowed_indexes(
+                    vec![IndexUidPattern::new_unchecked("doggo")].into_iter().collect(),
+                ),
+            )
+            .unwrap();
+        // the limit is applied after the unauthorized tasks have been removed
+        // -> the index creation of doggo is returned even though it is not the last task
+        snapshot!(snapshot_bitmap(&tasks), @"[1,]");
+
+        let query = Query { from: Some(2), limit: Some(1), ..Default::default() };
+        let (tasks, _) = index_scheduler
+            .get_task_ids_from_authorized_indexes(
+                &rtxn,
+                &query,
+                &AuthFilter::with_allowed_indexes(
+                    vec![IndexUidPattern::new_unchecked("catto")].into_iter().collect(),
+                ),
+            )
+            .unwrap();
+        // `from` is excluded by the filter, the page still starts right below it
+        snapshot!(snapshot_bitmap(&tasks), @"[0,]");
     }
 
     #[test]
@@ -3907,7 +4957,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -3978,7 +5028,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -4046,7 +5096,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -4107,7 +5157,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -4172,7 +5222,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -4233,7 +5283,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -4316,7 +5366,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -4402,7 +5452,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -4481,7 +5531,7 @@ mod tests {
         let documents = index
             .all_documents(&rtxn)
             .unwrap()
-            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1).unwrap())
+            .map(|ret| obkv_to_json(&field_ids, &field_ids_map, ret.unwrap().1.reader()).unwrap())
             .collect::<Vec<_>>();
         snapshot!(serde_json::to_string_pretty(&documents).unwrap(), name: "documents");
     }
@@ -4504,6 +5554,244 @@ mod tests {
         snapshot!(snapshot_index_scheduler(&index_scheduler), name: "index_creation_failed");
     }
 
+    #[test]
+    fn park_index_after_consecutive_panics() {
+        let (index_scheduler, mut handle) = IndexScheduler::test(
+            false,
+            vec![
+                (1, FailureLocation::PanicInsideProcessBatch),
+                (2, FailureLocation::PanicInsideProcessBatch),
+                (3, FailureLocation::PanicInsideProcessBatch),
+            ],
+        );
+
+        for _ in 0..4 {
+            index_scheduler.register(index_creation_task("catto", "mouse"), None, false).unwrap();
+        }
+        index_scheduler.register(index_creation_task("doggo", "bone"), None, false).unwrap();
+
+        for _ in 0..MAX_CONSECUTIVE_PANICS {
+            assert_eq!(index_scheduler.parked_index("catto"), None);
+            handle.advance_till([Start, BatchCreated, ProcessBatchFailed, AfterProcessing]);
+        }
+
+        let parked = index_scheduler.parked_index("catto").unwrap();
+        assert_eq!(parked.reason, "simulated panic");
+
+        // the last task of `catto` is skipped while the task of `doggo` is processed
+        handle.advance_one_successful_batch();
+        let rtxn = index_scheduler.env.read_txn().unwrap();
+        for uid in 0..MAX_CONSECUTIVE_PANICS {
+            let task = index_scheduler.get_task(&rtxn, uid).unwrap().unwrap();
+            assert_eq!(task.status, Status::Failed);
+            let error = task.error.unwrap();
+            assert_eq!(
+                error.message,
+                "An unexpected crash occurred when processing the task: simulated panic"
+            );
+        }
+        let task = index_scheduler.get_task(&rtxn, 3).unwrap().unwrap();
+        assert_eq!(task.status, Status::Enqueued);
+        let task = index_scheduler.get_task(&rtxn, 4).unwrap().unwrap();
+        assert_eq!(task.status, Status::Succeeded);
+        drop(rtxn);
+
+        assert!(index_scheduler.unpark_index("catto"));
+        assert!(!index_scheduler.unpark_index("catto"));
+        assert_eq!(index_scheduler.parked_index("catto"), None);
+
+        handle.advance_one_successful_batch();
+        let rtxn = index_scheduler.env.read_txn().unwrap();
+        let task = index_scheduler.get_task(&rtxn, 3).unwrap().unwrap();
+        assert_eq!(task.status, Status::Succeeded);
+    }
+
+    #[test]
+    fn retry_task_after_transient_failure() {
+        let (index_scheduler, mut handle) =
+            IndexScheduler::test(true, vec![(1, FailureLocation::TransientInsideProcessBatch)]);
+
+        index_scheduler.register(index_creation_task("catto", "mouse"), None, false).unwrap();
+        index_scheduler.register(index_creation_task("doggo", "bone"), None, false).unwrap();
+
+        let before_failure = Instant::now();
+        handle.advance_one_failed_batch();
+        let rtxn = index_scheduler.env.read_txn().unwrap();
+        let task = index_scheduler.get_task(&rtxn, 0).unwrap().unwrap();
+        assert_eq!(task.status, Status::Enqueued);
+        assert_eq!(task.error, None);
+        assert_eq!(task.attempts.len(), 1);
+        drop(rtxn);
+
+        // the task of `doggo` is processed while `catto` waits for its next attempt
+        handle.advance_one_successful_batch();
+        let rtxn = index_scheduler.env.read_txn().unwrap();
+        let task = index_scheduler.get_task(&rtxn, 0).unwrap().unwrap();
+        assert_eq!(task.status, Status::Enqueued);
+        let task = index_scheduler.get_task(&rtxn, 1).unwrap().unwrap();
+        assert_eq!(task.status, Status::Succeeded);
+        drop(rtxn);
+
+        // the ticks happening before the end of the delay don't create any batch
+        while handle.advance() != BatchCreated {}
+        assert!(before_failure.elapsed() >= TASK_RETRY_BASE_DELAY);
+        handle.advance_till([InsideProcessBatch, ProcessBatchSucceeded, AfterProcessing]);
+
+        // the task succeeded on its second attempt and the failed one is kept in its details
+        let rtxn = index_scheduler.env.read_txn().unwrap();
+        let task = index_scheduler.get_task(&rtxn, 0).unwrap().unwrap();
+        assert_eq!(task.status, Status::Succeeded);
+        let view = TaskView::from_task(&task);
+        let attempts = view.details.unwrap().attempts.unwrap();
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(attempts[0].error.message, "Planned transient failure for tests.");
+        assert!(attempts[0].finished_at <= task.started_at.unwrap());
+    }
+
+    #[test]
+    fn fail_task_after_max_attempts() {
+        let (index_scheduler, mut handle) = IndexScheduler::test_with_custom_config(
+            vec![(1, FailureLocation::TransientInsideProcessBatch)],
+            |config| {
+                config.max_task_attempts = 1;
+            },
+        );
+
+        index_scheduler.register(index_creation_task("catto", "mouse"), None, false).unwrap();
+        handle.advance_one_failed_batch();
+
+        let rtxn = index_scheduler.env.read_txn().unwrap();
+        let task = index_scheduler.get_task(&rtxn, 0).unwrap().unwrap();
+        assert_eq!(task.status, Status::Failed);
+        assert_eq!(task.error.unwrap().message, "Planned transient failure for tests.");
+        assert_eq!(task.attempts.len(), 1);
+    }
+
+    #[test]
+    fn in_flight_batch_recorded_while_processing() {
+        let (index_scheduler, mut handle) = IndexScheduler::test(true, vec![]);
+
+        index_scheduler.register(index_creation_task("catto", "mouse"), None, false).unwrap();
+        handle.advance_till([Start, BatchCreated]);
+        let rtxn = index_scheduler.env.read_txn().unwrap();
+        let batch = index_scheduler.crash_recovery.in_flight_batch(&rtxn).unwrap().unwrap();
+        assert_eq!(batch.task_uids, vec![0]);
+        drop(rtxn);
+
+        handle.advance_till([InsideProcessBatch, ProcessBatchSucceeded, AfterProcessing]);
+        let rtxn = index_scheduler.env.read_txn().unwrap();
+        assert_eq!(index_scheduler.crash_recovery.in_flight_batch(&rtxn).unwrap(), None);
+        drop(rtxn);
+        assert_eq!(index_scheduler.last_crash_recovery().unwrap(), None);
+    }
+
+    /// Writes the in-flight batch of these tasks as if the instance crashed while processing
+    /// them, then runs the recovery the scheduler does when it starts.
+    fn simulate_crash(index_scheduler: &IndexScheduler, task_uids: Vec<TaskId>) -> OffsetDateTime {
+        let started_at = OffsetDateTime::now_utc();
+        let mut wtxn = index_scheduler.env.write_txn().unwrap();
+        let batch = InFlightBatch { task_uids, started_at };
+        index_scheduler.crash_recovery.put_in_flight_batch(&mut wtxn, &batch).unwrap();
+        wtxn.commit().unwrap();
+
+        index_scheduler.recover_in_flight_batch().unwrap();
+        started_at
+    }
+
+    #[test]
+    fn fail_tasks_of_crashed_batch() {
+        let (index_scheduler, _handle) = IndexScheduler::test(true, vec![]);
+
+        index_scheduler.register(index_creation_task("catto", "mouse"), None, false).unwrap();
+        index_scheduler.register(index_creation_task("doggo", "bone"), None, false).unwrap();
+        index_scheduler.register(index_creation_task("girafo", "leaves"), None, false).unwrap();
+
+        let started_at = simulate_crash(&index_scheduler, vec![0, 1]);
+
+        let rtxn = index_scheduler.env.read_txn().unwrap();
+        for uid in [0, 1] {
+            let task = index_scheduler.get_task(&rtxn, uid).unwrap().unwrap();
+            assert_eq!(task.status, Status::Failed);
+            assert_eq!(task.started_at, Some(started_at));
+            let view = TaskView::from_task(&task);
+            snapshot!(json_string!(view.error.unwrap()), @r###"
+            {
+              "message": "Meilisearch stopped unexpectedly while processing the task.",
+              "code": "crashed_during_processing",
+              "type": "internal",
+              "link": "https://docs.meilisearch.com/errors#crashed_during_processing"
+            }
+            "###);
+        }
+        let task = index_scheduler.get_task(&rtxn, 2).unwrap().unwrap();
+        assert_eq!(task.status, Status::Enqueued);
+        assert_eq!(index_scheduler.crash_recovery.in_flight_batch(&rtxn).unwrap(), None);
+        drop(rtxn);
+
+        let report = index_scheduler.last_crash_recovery().unwrap().unwrap();
+        assert_eq!(report.task_uids, vec![0, 1]);
+        assert_eq!(report.batch_started_at, started_at);
+        assert_eq!(report.action, RecoveryAction::Failed);
+
+        // the recovery only happens once
+        index_scheduler.recover_in_flight_batch().unwrap();
+        assert_eq!(index_scheduler.last_crash_recovery().unwrap(), Some(report));
+    }
+
+    #[test]
+    fn reenqueue_tasks_of_crashed_batch() {
+        let (index_scheduler, mut handle) =
+            IndexScheduler::test_with_custom_config(vec![], |config| {
+                config.reenqueue_crashed_tasks = true;
+            });
+
+        index_scheduler.register(index_creation_task("catto", "mouse"), None, false).unwrap();
+        simulate_crash(&index_scheduler, vec![0]);
+
+        let rtxn = index_scheduler.env.read_txn().unwrap();
+        let task = index_scheduler.get_task(&rtxn, 0).unwrap().unwrap();
+        assert_eq!(task.status, Status::Enqueued);
+        drop(rtxn);
+        let report = index_scheduler.last_crash_recovery().unwrap().unwrap();
+        assert_eq!(report.task_uids, vec![0]);
+        assert_eq!(report.action, RecoveryAction::Reenqueued);
+
+        handle.advance_one_successful_batch();
+        let rtxn = index_scheduler.env.read_txn().unwrap();
+        let task = index_scheduler.get_task(&rtxn, 0).unwrap().unwrap();
+        assert_eq!(task.status, Status::Succeeded);
+    }
+
+    #[test]
+    fn task_queue_stats() {
+        let (index_scheduler, mut handle) = IndexScheduler::test(true, vec![]);
+
+        let stats = index_scheduler.task_queue_stats().unwrap();
+        assert_eq!(stats.oldest_enqueued_task_age_seconds, None);
+        assert_eq!(stats.finished_tasks_per_minute, 0);
+        assert_eq!(stats.average_batch_duration_seconds, None);
+
+        // the scheduler doesn't process anything until it is advanced
+        index_scheduler.register(index_creation_task("catto", "mouse"), None, false).unwrap();
+        index_scheduler.register(index_creation_task("doggo", "bone"), None, false).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        let stats = index_scheduler.task_queue_stats().unwrap();
+        assert!(stats.oldest_enqueued_task_age_seconds.unwrap() >= 0.1);
+        assert_eq!(stats.finished_tasks_per_minute, 0);
+        assert_eq!(stats.average_batch_duration_seconds, None);
+
+        handle.advance_one_successful_batch();
+        let stats = index_scheduler.task_queue_stats().unwrap();
+        assert!(stats.oldest_enqueued_task_age_seconds.is_some());
+        assert_eq!(stats.finished_tasks_per_minute, 1);
+        assert!(stats.average_batch_duration_seconds.is_some());
+
+        handle.advance_one_successful_batch();
+        let stats = index_scheduler.task_queue_stats().unwrap();
+        assert_eq!(stats.oldest_enqueued_task_age_seconds, None);
+        assert_eq!(stats.finished_tasks_per_minute, 2);
+    }
+
     #[test]
     fn test_task_queue_is_full() {
         let (index_scheduler, mut handle) =
@@ -4544,7 +5832,7 @@ mod tests {
             .unwrap_err();
         snapshot!(result, @"Meilisearch cannot receive write operations because the limit of the task database has been reached. Please delete tasks to continue performing write operations.");
         // we won't be able to test this error in an integration test thus as a best effort test I still ensure the error return the expected error code
-        snapshot!(format!("{:?}", result.error_code()), @"NoSpaceLeftOnDevice");
+        snapshot!(format!("{:?}", result.error_code()), @"TaskQueueFull");
 
         // Even the task deletion that doesn't delete anything shouldn't be accepted
         let result = index_scheduler
@@ -4556,7 +5844,7 @@ mod tests {
             .unwrap_err();
         snapshot!(result, @"Meilisearch cannot receive write operations because the limit of the task database has been reached. Please delete tasks to continue performing write operations.");
         // we won't be able to test this error in an integration test thus as a best effort test I still ensure the error return the expected error code
-        snapshot!(format!("{:?}", result.error_code()), @"NoSpaceLeftOnDevice");
+        snapshot!(format!("{:?}", result.error_code()), @"TaskQueueFull");
 
         // But a task deletion that delete something should works
         index_scheduler
@@ -4579,6 +5867,198 @@ mod tests {
         handle.advance_one_failed_batch();
     }
 
+    #[test]
+    fn test_task_queue_is_full_with_auto_cleanup() {
+        let (index_scheduler, mut handle) =
+            IndexScheduler::test_with_custom_config(vec![], |config| {
+                // that's the minimum map size possible
+                config.task_db_size = 1048576;
+                config.auto_cleanup_tasks = true;
+            });
+
+        index_scheduler
+            .register(
+                KindWithContent::IndexCreation { index_uid: S("doggo"), primary_key: None },
+                None,
+                false,
+            )
+            .unwrap();
+        handle.advance_one_successful_batch();
+        // fill the task queue with succeeded tasks
+        loop {
+            let result = index_scheduler.register(
+                KindWithContent::SettingsUpdate {
+                    index_uid: S("doggo"),
+                    new_settings: Box::default(),
+                    is_deletion: false,
+                    allow_index_creation: true,
+                },
+                None,
+                false,
+            );
+            match result {
+                Ok(_) => handle.advance_one_successful_batch(),
+                Err(e) => {
+                    snapshot!(format!("{:?}", e.error_code()), @"TaskQueueFull");
+                    break;
+                }
+            }
+        }
+
+        // the rejected registration must have enqueued a task deletion by itself
+        let rtxn = index_scheduler.read_txn().unwrap();
+        let last_task_id = index_scheduler.next_task_id(&rtxn).unwrap() - 1;
+        let last_task = index_scheduler.get_task(&rtxn, last_task_id).unwrap().unwrap();
+        drop(rtxn);
+        assert_eq!(last_task.kind.as_kind(), Kind::TaskDeletion);
+        assert_eq!(last_task.status, Status::Enqueued);
+
+        // while this deletion is pending, the rejected registrations don't enqueue another one
+        let result = index_scheduler.register(
+            KindWithContent::IndexCreation { index_uid: S("doggo"), primary_key: None },
+            None,
+            false,
+        );
+        snapshot!(format!("{:?}", result.unwrap_err().error_code()), @"TaskQueueFull");
+        let rtxn = index_scheduler.read_txn().unwrap();
+        assert_eq!(index_scheduler.next_task_id(&rtxn).unwrap() - 1, last_task_id);
+        drop(rtxn);
+
+        handle.advance_one_successful_batch();
+        index_scheduler.assert_internally_consistent();
+
+        // Now we should be able to enqueue a few tasks again
+        index_scheduler
+            .register(
+                KindWithContent::IndexCreation { index_uid: S("doggo"), primary_key: None },
+                None,
+                false,
+            )
+            .unwrap();
+        handle.advance_one_failed_batch();
+    }
+
+    #[test]
+    fn test_retention_policy() {
+        let (index_scheduler, mut handle) =
+            IndexScheduler::test_with_custom_config(vec![], |config| {
+                config.task_retention = Some(std::time::Duration::ZERO);
+                config.update_file_retention = Some(std::time::Duration::ZERO);
+                config.retention_interval = std::time::Duration::ZERO;
+            });
+
+        index_scheduler
+            .register(
+                KindWithContent::IndexCreation { index_uid: S("doggos"), primary_key: None },
+                None,
+                false,
+            )
+            .unwrap();
+        handle.advance_one_successful_batch();
+
+        // an update file no task refers to, left by a crash
+        let (_orphan, file) = index_scheduler.create_update_file_with_uuid(0).unwrap();
+        file.persist().unwrap();
+
+        let content = r#"{ "id": 1, "doggo": "bob" }"#;
+        let (uuid, mut file) = index_scheduler.create_update_file_with_uuid(1).unwrap();
+        let documents_count = read_json(content.as_bytes(), &mut file).unwrap();
+        file.persist().unwrap();
+        index_scheduler
+            .register(
+                KindWithContent::DocumentAdditionOrUpdate {
+                    index_uid: S("doggos"),
+                    primary_key: Some(S("id")),
+                    method: ReplaceDocuments,
+                    content_file: uuid,
+                    documents_count,
+                    allow_index_creation: true,
+                },
+                None,
+                false,
+            )
+            .unwrap();
+
+        // the policy is applied before the batch of the document addition is created
+        handle.advance_till([Start, BatchCreated]);
+        let rtxn = index_scheduler.read_txn().unwrap();
+        let tasks = index_scheduler.all_task_ids(&rtxn).unwrap();
+        snapshot!(snapshot_bitmap(&tasks), @"[1,]");
+        drop(rtxn);
+        let files: Vec<_> =
+            index_scheduler.file_store.all_uuids().unwrap().map(|uuid| uuid.unwrap()).collect();
+        assert_eq!(files, vec![uuid]);
+        let stats = index_scheduler.task_queue_stats().unwrap();
+        assert_eq!(stats.tasks_deleted_by_retention, 1);
+        assert_eq!(stats.update_files_deleted_by_retention, 1);
+
+        // the task being processed is only deleted once it is finished
+        loop {
+            match handle.advance() {
+                InsideProcessBatch => (),
+                ProcessBatchSucceeded => break,
+                breakpoint => panic!("Encountered an unexpected breakpoint `{:?}`.", breakpoint),
+            }
+        }
+        handle.advance_till([AfterProcessing, Start]);
+        handle.advance_till([Start]);
+        let rtxn = index_scheduler.read_txn().unwrap();
+        let tasks = index_scheduler.all_task_ids(&rtxn).unwrap();
+        snapshot!(snapshot_bitmap(&tasks), @"[]");
+        drop(rtxn);
+        let stats = index_scheduler.task_queue_stats().unwrap();
+        assert_eq!(stats.tasks_deleted_by_retention, 2);
+        assert_eq!(stats.update_files_deleted_by_retention, 1);
+    }
+
+    #[test]
+    fn test_too_many_enqueued_tasks() {
+        let (index_scheduler, _handle) =
+            IndexScheduler::test_with_custom_config(vec![], |config| {
+                config.max_enqueued_tasks = Some(2);
+            });
+
+        let register_addition = |id: u32| {
+            let content = format!(r#"{{ "id": {id}, "doggo": "bob {id}" }}"#);
+            let (uuid, mut file) =
+                index_scheduler.create_update_file_with_uuid(id as u128).unwrap();
+            let documents_count = read_json(content.as_bytes(), &mut file).unwrap();
+            file.persist().unwrap();
+            index_scheduler.register(
+                KindWithContent::DocumentAdditionOrUpdate {
+                    index_uid: S("doggos"),
+                    primary_key: Some(S("id")),
+                    method: ReplaceDocuments,
+                    content_file: uuid,
+                    documents_count,
+                    allow_index_creation: true,
+                },
+                None,
+                false,
+            )
+        };
+
+        register_addition(0).unwrap();
+        register_addition(1).unwrap();
+        let error = register_addition(2).unwrap_err();
+        snapshot!(format!("{:?}", error.error_code()), @"TooManyEnqueuedTasks");
+        snapshot!(format!("{error}"), @"Meilisearch cannot receive write operations because 2 tasks are enqueued, which reaches the limit of 2 enqueued tasks. Please retry in 1 seconds.");
+        assert_eq!(error.retry_after(), Some(1));
+
+        // the operators can still clean up the queue
+        index_scheduler
+            .register(
+                KindWithContent::TaskDeletion {
+                    query: "test_query".to_owned(),
+                    tasks: RoaringBitmap::from_iter([0, 1]),
+                },
+                None,
+                false,
+            )
+            .unwrap();
+        index_scheduler.assert_internally_consistent();
+    }
+
     #[test]
     fn test_auto_deletion_of_tasks() {
         let (index_scheduler, mut handle) =
@@ -4751,10 +6231,14 @@ mod tests {
             "dumpCreation": 0,
             "indexCreation": 3,
             "indexDeletion": 0,
+            "indexRestore": 0,
             "indexSwap": 0,
             "indexUpdate": 0,
+            "indexWarmup": 0,
+            "integrityCheck": 0,
             "settingsUpdate": 0,
             "snapshotCreation": 0,
+            "snapshotImportIncremental": 0,
             "taskCancelation": 0,
             "taskDeletion": 0
           }
@@ -4782,10 +6266,14 @@ mod tests {
             "dumpCreation": 0,
             "indexCreation": 3,
             "indexDeletion": 0,
+            "indexRestore": 0,
             "indexSwap": 0,
             "indexUpdate": 0,
+            "indexWarmup": 0,
+            "integrityCheck": 0,
             "settingsUpdate": 0,
             "snapshotCreation": 0,
+            "snapshotImportIncremental": 0,
             "taskCancelation": 0,
             "taskDeletion": 0
           }
@@ -4820,10 +6308,14 @@ mod tests {
             "dumpCreation": 0,
             "indexCreation": 3,
             "indexDeletion": 0,
+            "indexRestore": 0,
             "indexSwap": 0,
             "indexUpdate": 0,
+            "indexWarmup": 0,
+            "integrityCheck": 0,
             "settingsUpdate": 0,
             "snapshotCreation": 0,
+            "snapshotImportIncremental": 0,
             "taskCancelation": 0,
             "taskDeletion": 0
           }
@@ -4859,10 +6351,14 @@ mod tests {
             "dumpCreation": 0,
             "indexCreation": 3,
             "indexDeletion": 0,
+            "indexRestore": 0,
             "indexSwap": 0,
             "indexUpdate": 0,
+            "indexWarmup": 0,
+            "integrityCheck": 0,
             "settingsUpdate": 0,
             "snapshotCreation": 0,
+            "snapshotImportIncremental": 0,
             "taskCancelation": 0,
             "taskDeletion": 0
           }
@@ -4892,6 +6388,59 @@ mod tests {
         snapshot!(snapshot_index_scheduler(&index_scheduler), name: "cancel_processed");
     }
 
+    #[test]
+    fn dump_progress() {
+        let (index_scheduler, mut handle) = IndexScheduler::test(true, vec![]);
+
+        let documents: Vec<_> =
+            (0..5000).map(|id| format!(r#"{{ "id": {id}, "doggo": "bob" }}"#)).collect();
+        let content = format!("[{}]", documents.join(","));
+        let (uuid, mut file) = index_scheduler.create_update_file_with_uuid(0).unwrap();
+        let documents_count = read_json(content.as_bytes(), &mut file).unwrap();
+        file.persist().unwrap();
+        index_scheduler
+            .register(
+                KindWithContent::DocumentAdditionOrUpdate {
+                    index_uid: S("doggos"),
+                    primary_key: Some(S("id")),
+                    method: ReplaceDocuments,
+                    content_file: uuid,
+                    documents_count,
+                    allow_index_creation: true,
+                },
+                None,
+                false,
+            )
+            .unwrap();
+        handle.advance_one_successful_batch();
+        // the batches that don't report anything don't have any progress
+        assert!(index_scheduler.processing_progress().is_none());
+
+        let dump_creation = KindWithContent::DumpCreation { keys: Vec::new(), instance_uid: None };
+        let _ = index_scheduler.register(dump_creation, None, false).unwrap();
+        handle.advance_till([Start, BatchCreated, InsideProcessBatch, ProcessBatchSucceeded]);
+
+        let (processing, progress) = index_scheduler.processing_progress().unwrap();
+        snapshot!(snapshot_bitmap(&processing), @"[1,]");
+        let steps: Vec<_> = progress
+            .steps
+            .iter()
+            .filter(|step| step.unit != "bytes")
+            .map(|step| format!("{}: {}/{:?} {}", step.name, step.finished, step.total, step.unit))
+            .collect();
+        snapshot!(steps.join("\n"), @r###"
+        dumping the API keys: 0/Some(0) keys
+        dumping the tasks: 2/Some(2) tasks
+        dumping the documents of the index `doggos`: 5000/Some(5000) documents
+        "###);
+        let compressing = progress.steps.last().unwrap();
+        assert_eq!(compressing.name, "compressing the dump");
+        assert_eq!(Some(compressing.finished), compressing.total);
+
+        handle.advance_till([AfterProcessing]);
+        assert!(index_scheduler.processing_progress().is_none());
+    }
+
     #[test]
     fn basic_set_taskid() {
         let (index_scheduler, _handle) = IndexScheduler::test(true, vec![]);
@@ -0,0 +1,70 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use meilisearch_types::task_view::{ProgressStepView, ProgressView};
+
+/// The steps the batch being processed went through, shared with the routes showing the tasks.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Progress {
+    steps: Arc<RwLock<Vec<Step>>>,
+}
+
+#[derive(Debug)]
+struct Step {
+    name: String,
+    unit: &'static str,
+    finished: StepProgress,
+    total: Option<u64>,
+}
+
+/// The counter of a step, incremented by the batch while it works on the step.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StepProgress(Arc<AtomicU64>);
+
+impl StepProgress {
+    pub fn add(&self, finished: u64) {
+        self.0.fetch_add(finished, Ordering::Relaxed);
+    }
+
+    pub fn set(&self, finished: u64) {
+        self.0.store(finished, Ordering::Relaxed);
+    }
+}
+
+impl Progress {
+    /// Starts a new step, the previous ones are kept in the trace.
+    pub fn start_step(
+        &self,
+        name: impl Into<String>,
+        unit: &'static str,
+        total: Option<u64>,
+    ) -> StepProgress {
+        let finished = StepProgress::default();
+        let step = Step { name: name.into(), unit, finished: finished.clone(), total };
+        self.steps.write().unwrap().push(step);
+        finished
+    }
+
+    /// Returns `None` if the batch did not report any step.
+    pub fn as_view(&self) -> Option<ProgressView> {
+        let steps = self.steps.read().unwrap();
+        if steps.is_empty() {
+            return None;
+        }
+
+        let steps = steps
+            .iter()
+            .map(|Step { name, unit, finished, total }| {
+                let finished = finished.0.load(Ordering::Relaxed);
+                ProgressStepView {
+                    name: name.clone(),
+                    unit: unit.to_string(),
+                    // the counters can overshoot estimated totals, e.g. with the headers of an archive
+                    finished: total.map_or(finished, |total| finished.min(total)),
+                    total: *total,
+                }
+            })
+            .collect();
+        Some(ProgressView { steps })
+    }
+}
@@ -0,0 +1,152 @@
+//! Cache of the embeddings of the search queries, shared by all the indexes.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use meilisearch_types::milli::vector::error::EmbedError;
+use meilisearch_types::milli::vector::{Embedder, EmbedderOptions, Embedding};
+
+use crate::lru::LruMap;
+
+/// Number of query embeddings kept in memory, all embedders included.
+pub(crate) const QUERY_EMBEDDINGS_CACHE_SIZE: usize = 512;
+
+/// The embeddings of the last search queries.
+///
+/// The embeddings are keyed on the exact query text and on a fingerprint of the embedder options,
+/// so that updating the settings of an embedder never serves an embedding made with the previous settings.
+pub(crate) type QueryEmbeddings = Arc<Mutex<LruMap<(u64, String), Embedding>>>;
+
+/// A handle to embed search queries with a specific embedder, going through the query embeddings cache.
+#[derive(Clone)]
+pub struct QueryEmbeddingCache {
+    cache: QueryEmbeddings,
+    fingerprint: u64,
+}
+
+impl QueryEmbeddingCache {
+    pub(crate) fn new(cache: QueryEmbeddings, embedder_options: &EmbedderOptions) -> Self {
+        // The `Hash` implementation of the options skips some of them, like the query of the REST embedder,
+        // so the fingerprint is computed on their serialization instead.
+        let mut hasher = DefaultHasher::new();
+        serde_json::to_vec(embedder_options).unwrap_or_default().hash(&mut hasher);
+        Self { cache, fingerprint: hasher.finish() }
+    }
+
+    /// Returns the embedding of `text`, only calling the embedder if it is not in the cache.
+    ///
+    /// The returned boolean is `true` when the embedding comes from the cache.
+    pub fn embed(&self, embedder: &Embedder, text: &str) -> Result<(Embedding, bool), EmbedError> {
+        let key = (self.fingerprint, text.to_owned());
+        if let Some(embedding) = self.cache.lock().unwrap().get(&key) {
+            return Ok((embedding.clone(), true));
+        }
+
+        // the lock is not held while the embedder, that may be a remote service, is called
+        let embedding = embedder.embed_one(key.1.clone())?;
+        self.cache.lock().unwrap().insert(key, embedding.clone());
+        Ok((embedding, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use meilisearch_types::milli::vector::rest;
+
+    use super::*;
+
+    /// Starts an embedding server answering `[0.0, 1.0, 2.0]` to any request,
+    /// returns its URL and the number of requests it received.
+    fn mock_embedding_server() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let counter = counter.clone();
+                std::thread::spawn(move || {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    // the connections are kept alive by the client
+                    loop {
+                        let mut content_length = 0;
+                        let mut line = String::new();
+                        loop {
+                            line.clear();
+                            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                                return;
+                            }
+                            let header = line.trim_end().to_ascii_lowercase();
+                            if header.is_empty() {
+                                break;
+                            }
+                            if let Some(length) = header.strip_prefix("content-length:") {
+                                content_length = length.trim().parse().unwrap();
+                            }
+                        }
+                        let mut body = vec![0; content_length];
+                        reader.read_exact(&mut body).unwrap();
+                        counter.fetch_add(1, Ordering::SeqCst);
+
+                        let response = r#"{"data":{"embedding":[0.0,1.0,2.0]}}"#;
+                        write!(
+                            stream,
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                            response.len(),
+                            response
+                        )
+                        .unwrap();
+                    }
+                });
+            }
+        });
+        (url, requests)
+    }
+
+    fn embedder_options(url: &str, query: serde_json::Value) -> EmbedderOptions {
+        EmbedderOptions::Rest(rest::EmbedderOptions {
+            url: url.to_owned(),
+            query,
+            dimensions: Some(3),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn identical_queries_are_embedded_once() {
+        let (url, requests) = mock_embedding_server();
+        let options = embedder_options(&url, serde_json::json!({}));
+        let embedder = Embedder::new(options.clone(), &Default::default()).unwrap();
+        let cache: QueryEmbeddings = Arc::new(Mutex::new(LruMap::new(QUERY_EMBEDDINGS_CACHE_SIZE)));
+        let query_embeddings = QueryEmbeddingCache::new(cache.clone(), &options);
+
+        let (embedding, hit) = query_embeddings.embed(&embedder, "kefir").unwrap();
+        assert_eq!(embedding, vec![0.0, 1.0, 2.0]);
+        assert!(!hit);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        let (embedding, hit) = query_embeddings.embed(&embedder, "kefir").unwrap();
+        assert_eq!(embedding, vec![0.0, 1.0, 2.0]);
+        assert!(hit);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // the key is the exact text
+        let (_, hit) = query_embeddings.embed(&embedder, "Kefir").unwrap();
+        assert!(!hit);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        // changing the settings of the embedder must not serve the previous embeddings
+        let options = embedder_options(&url, serde_json::json!({ "model": "doggo" }));
+        let embedder = Embedder::new(options.clone(), &Default::default()).unwrap();
+        let query_embeddings = QueryEmbeddingCache::new(cache, &options);
+        let (_, hit) = query_embeddings.embed(&embedder, "kefir").unwrap();
+        assert!(!hit);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,56 @@
+use std::collections::VecDeque;
+
+use meilisearch_types::heed::types::{SerdeJson, Str};
+use meilisearch_types::heed::{Database, Env, RoTxn, RwTxn};
+use serde_json::Value;
+
+use crate::Result;
+
+const RECENT_SEARCHES: &str = "recent-searches";
+
+/// The last searches made on each index, recorded when the `recentSearches` experimental
+/// feature is enabled.
+///
+/// The searches are opaque to the scheduler, they are sanitized before being recorded.
+#[derive(Clone)]
+pub(crate) struct RecentSearches {
+    /// The last searches of each index, oldest first, by index uid.
+    searches: Database<Str, SerdeJson<VecDeque<Value>>>,
+}
+
+impl RecentSearches {
+    pub fn new(env: &Env) -> Result<Self> {
+        let mut wtxn = env.write_txn()?;
+        let searches = env.create_database(&mut wtxn, Some(RECENT_SEARCHES))?;
+        wtxn.commit()?;
+
+        Ok(Self { searches })
+    }
+
+    /// Returns the recorded searches of the index, newest first.
+    pub fn get(&self, rtxn: &RoTxn, index_uid: &str) -> Result<Vec<Value>> {
+        let searches = self.searches.get(rtxn, index_uid)?.unwrap_or_default();
+        Ok(searches.into_iter().rev().collect())
+    }
+
+    /// Records a search, evicting the oldest ones to only keep the `max_searches` last ones.
+    pub fn push(
+        &self,
+        wtxn: &mut RwTxn,
+        index_uid: &str,
+        search: Value,
+        max_searches: usize,
+    ) -> Result<()> {
+        let mut searches = self.searches.get(wtxn, index_uid)?.unwrap_or_default();
+        searches.push_back(search);
+        while searches.len() > max_searches {
+            searches.pop_front();
+        }
+        Ok(self.searches.put(wtxn, index_uid, &searches)?)
+    }
+
+    pub fn delete(&self, wtxn: &mut RwTxn, index_uid: &str) -> Result<()> {
+        self.searches.delete(wtxn, index_uid)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use meilisearch_types::milli::outbound::OutboundPolicy;
+use meilisearch_types::tasks::KindWithContent;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::{Error, MustStopProcessing, Result};
+
+/// The interval at which the snapshot task of the primary is polled.
+const SNAPSHOT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// The time after which the snapshot of the primary is not waited for anymore.
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// The primary instance a read-only replica imports its snapshots from.
+#[derive(Clone)]
+pub(crate) struct Replication {
+    primary_url: String,
+    primary_api_key: Option<String>,
+    outbound_policy: OutboundPolicy,
+    client: ureq::Agent,
+    status: Arc<RwLock<SyncState>>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct SyncState {
+    last_successful_sync: Option<OffsetDateTime>,
+    /// When the primary started to create the last imported snapshot.
+    captured_at: Option<OffsetDateTime>,
+}
+
+/// The sync state of a read-only replica with its primary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicationStatus {
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_successful_sync: Option<OffsetDateTime>,
+    /// The number of seconds since the primary created the last imported snapshot.
+    pub lag_seconds: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EnqueuedTask {
+    task_uid: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PolledTask {
+    status: String,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    started_at: Option<OffsetDateTime>,
+    error: Option<serde_json::Value>,
+}
+
+impl Replication {
+    pub fn new(
+        primary_url: String,
+        primary_api_key: Option<String>,
+        outbound_policy: OutboundPolicy,
+    ) -> Self {
+        let primary_url = primary_url.trim_end_matches('/').to_string();
+        let client = outbound_policy.agent();
+        Self { primary_url, primary_api_key, outbound_policy, client, status: Default::default() }
+    }
+
+    /// The tasks modifying the indexes are refused, they only change through the snapshot imports.
+    pub fn accepts(kind: &KindWithContent) -> bool {
+        matches!(
+            kind,
+            KindWithContent::SnapshotImportIncremental
+                | KindWithContent::TaskCancelation { .. }
+                | KindWithContent::TaskDeletion { .. }
+                | KindWithContent::DumpCreation { .. }
+                | KindWithContent::SnapshotCreation
+        )
+    }
+
+    /// Asks the primary to create a snapshot and downloads it at the given path.
+    ///
+    /// Returns the date at which the primary started to create the snapshot.
+    pub fn fetch_snapshot(
+        &self,
+        dest: &Path,
+        must_stop_processing: &MustStopProcessing,
+    ) -> Result<OffsetDateTime> {
+        self.outbound_policy.check_url(&self.primary_url)?;
+
+        // 1. Enqueue a snapshot creation on the primary.
+        let response = self.request("POST", "/snapshots").call().map_err(|e| self.failure(e))?;
+        let EnqueuedTask { task_uid } =
+            serde_json::from_reader(response.into_reader()).map_err(|e| self.failure(e))?;
+
+        // 2. Wait for the snapshot to be created.
+        let waiting_since = Instant::now();
+        let captured_at = loop {
+            if must_stop_processing.get() {
+                return Err(Error::AbortedTask);
+            }
+            let response = self
+                .request("GET", &format!("/tasks/{task_uid}"))
+                .call()
+                .map_err(|e| self.failure(e))?;
+            let task: PolledTask =
+                serde_json::from_reader(response.into_reader()).map_err(|e| self.failure(e))?;
+            match task.status.as_str() {
+                "succeeded" => break task.started_at.unwrap_or_else(OffsetDateTime::now_utc),
+                "failed" | "canceled" => {
+                    let error = task.error.map(|error| error.to_string()).unwrap_or_default();
+                    return Err(self.failure(format!(
+                        "the snapshot task `{task_uid}` is {}. {error}",
+                        task.status
+                    )));
+                }
+                _ if waiting_since.elapsed() > SNAPSHOT_TIMEOUT => {
+                    return Err(self.failure(format!(
+                        "the snapshot task `{task_uid}` did not finish in time."
+                    )));
+                }
+                _ => thread::sleep(SNAPSHOT_POLL_INTERVAL),
+            }
+        };
+
+        // 3. Download the snapshot.
+        let response =
+            self.request("GET", "/snapshots/latest").call().map_err(|e| self.failure(e))?;
+        let mut file = BufWriter::new(File::create(dest)?);
+        io::copy(&mut response.into_reader(), &mut file)?;
+        file.flush()?;
+
+        Ok(captured_at)
+    }
+
+    /// Records a successful import of a snapshot created at the given date.
+    pub fn record_sync(&self, captured_at: OffsetDateTime) {
+        let mut state = self.status.write().unwrap();
+        state.last_successful_sync = Some(OffsetDateTime::now_utc());
+        state.captured_at = Some(captured_at);
+    }
+
+    pub fn status(&self) -> ReplicationStatus {
+        let state = *self.status.read().unwrap();
+        let lag_seconds = state
+            .captured_at
+            .map(|captured_at| (OffsetDateTime::now_utc() - captured_at).whole_seconds().max(0))
+            .map(|lag| lag as u64);
+        ReplicationStatus { last_successful_sync: state.last_successful_sync, lag_seconds }
+    }
+
+    pub fn failure(&self, reason: impl ToString) -> Error {
+        Error::ReplicationFailed {
+            primary_url: self.primary_url.clone(),
+            reason: reason.to_string(),
+        }
+    }
+
+    fn request(&self, method: &str, route: &str) -> ureq::Request {
+        let request = self
+            .client
+            .request(method, &format!("{}{route}", self.primary_url))
+            .timeout(SNAPSHOT_TIMEOUT);
+        match &self.primary_api_key {
+            Some(key) => request.set("Authorization", &format!("Bearer {key}")),
+            None => request,
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! The retention policy of the task queue, deleting the finished tasks and the orphaned update
+//! files once they are older than the configured retention.
+//!
+//! The policy is applied by the run loop, at most once per retention interval and before the
+//! next batch is created, so that it never deletes the tasks of a processing batch. The finished
+//! tasks are deleted the same way as a task deletion does, but without registering one, which
+//! would itself be a finished task to delete the next time.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use meilisearch_types::tasks::Status;
+use time::OffsetDateTime;
+
+use crate::utils::keep_tasks_within_datetimes;
+use crate::{IndexScheduler, Result};
+
+/// How often the retention policy is applied by default.
+pub const DEFAULT_RETENTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug)]
+pub(crate) struct RetentionPolicy {
+    /// How long the finished tasks are kept, forever when `None`.
+    task_retention: Option<Duration>,
+    /// How long the update files no enqueued task refers to are kept, forever when `None`.
+    update_file_retention: Option<Duration>,
+    interval: Duration,
+    last_run: Mutex<Option<Instant>>,
+    /// What was deleted since Meilisearch started.
+    deleted_tasks: AtomicU64,
+    deleted_update_files: AtomicU64,
+}
+
+impl RetentionPolicy {
+    pub fn new(
+        task_retention: Option<Duration>,
+        update_file_retention: Option<Duration>,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            task_retention,
+            update_file_retention,
+            interval,
+            last_run: Mutex::new(None),
+            deleted_tasks: AtomicU64::new(0),
+            deleted_update_files: AtomicU64::new(0),
+        }
+    }
+
+    /// The time left before the policy must be applied again, `None` when there is no retention.
+    pub fn next_run_in(&self) -> Option<Duration> {
+        if self.task_retention.is_none() && self.update_file_retention.is_none() {
+            return None;
+        }
+        let last_run = *self.last_run.lock().unwrap();
+        Some(
+            last_run.map_or(Duration::ZERO, |last_run| {
+                self.interval.saturating_sub(last_run.elapsed())
+            }),
+        )
+    }
+
+    /// The number of finished tasks deleted since Meilisearch started.
+    pub fn deleted_tasks(&self) -> u64 {
+        self.deleted_tasks.load(Ordering::Relaxed)
+    }
+
+    /// The number of update files deleted since Meilisearch started.
+    pub fn deleted_update_files(&self) -> u64 {
+        self.deleted_update_files.load(Ordering::Relaxed)
+    }
+}
+
+impl IndexScheduler {
+    /// Deletes the finished tasks and the orphaned update files older than their retention,
+    /// if the policy wasn't applied during the last retention interval.
+    pub(crate) fn apply_retention_policy(&self) -> Result<()> {
+        let retention = &self.retention_policy;
+        if retention.next_run_in() != Some(Duration::ZERO) {
+            return Ok(());
+        }
+        *retention.last_run.lock().unwrap() = Some(Instant::now());
+
+        let deleted_tasks = match retention.task_retention {
+            Some(task_retention) => {
+                self.delete_tasks_finished_before(OffsetDateTime::now_utc() - task_retention)?
+            }
+            None => 0,
+        };
+        let deleted_update_files = match retention.update_file_retention {
+            Some(update_file_retention) => {
+                self.delete_orphaned_update_files_older_than(update_file_retention)?
+            }
+            None => 0,
+        };
+
+        if deleted_tasks > 0 || deleted_update_files > 0 {
+            tracing::info!(
+                deleted_tasks,
+                deleted_update_files,
+                "Deleted the finished tasks and the update files past their retention."
+            );
+        }
+        retention.deleted_tasks.fetch_add(deleted_tasks, Ordering::Relaxed);
+        retention.deleted_update_files.fetch_add(deleted_update_files, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Returns the number of deleted tasks.
+    fn delete_tasks_finished_before(&self, before: OffsetDateTime) -> Result<u64> {
+        let mut wtxn = self.env.write_txn()?;
+        let mut finished = self.get_status(&wtxn, Status::Succeeded)?
+            | self.get_status(&wtxn, Status::Failed)?
+            | self.get_status(&wtxn, Status::Canceled)?;
+        keep_tasks_within_datetimes(&wtxn, &mut finished, self.finished_at, None, Some(before))?;
+        if finished.is_empty() {
+            return Ok(0);
+        }
+
+        // the enqueued and processing tasks are never deleted
+        let deleted = self.delete_matched_tasks(&mut wtxn, &finished)?;
+        wtxn.commit()?;
+        Ok(deleted.len())
+    }
+
+    /// Returns the number of deleted update files.
+    ///
+    /// The update files of the finished tasks are deleted when they finish, the remaining ones
+    /// that no enqueued task refers to were left by a crash or are being registered: the latter
+    /// are kept as long as they are younger than the retention.
+    fn delete_orphaned_update_files_older_than(&self, retention: Duration) -> Result<u64> {
+        let rtxn = self.read_txn()?;
+        let enqueued = self.get_status(&rtxn, Status::Enqueued)?;
+        let referenced: HashSet<_> = self
+            .get_existing_tasks(&rtxn, enqueued.iter())?
+            .iter()
+            .filter_map(|task| task.content_uuid())
+            .collect();
+        drop(rtxn);
+
+        let mut deleted = 0;
+        for uuid in self.file_store.all_uuids()? {
+            let uuid = uuid?;
+            if referenced.contains(&uuid) {
+                continue;
+            }
+            let age = self.file_store.modified_at(uuid)?.elapsed().unwrap_or_default();
+            if age >= retention {
+                self.file_store.delete(uuid)?;
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,63 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// The window over which the finished tasks are counted.
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(60);
+
+/// The aggregates used to monitor the task queue.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskQueueStats {
+    /// The age in seconds of the oldest task waiting to be processed, if any.
+    pub oldest_enqueued_task_age_seconds: Option<f64>,
+    /// The number of tasks finished during the last minute.
+    pub finished_tasks_per_minute: u64,
+    /// The average processing duration in seconds of the batches, if any was processed.
+    pub average_batch_duration_seconds: Option<f64>,
+    /// The number of finished tasks deleted because of their age since Meilisearch started.
+    pub tasks_deleted_by_retention: u64,
+    /// The number of orphaned update files deleted because of their age since Meilisearch started.
+    pub update_files_deleted_by_retention: u64,
+}
+
+/// The aggregates of the finished batches, updated every time a batch is committed
+/// so that the task queue can be monitored without reading the tasks.
+///
+/// They are only kept in memory and start over when Meilisearch restarts.
+#[derive(Debug, Default)]
+pub(crate) struct FinishedBatches {
+    /// When the batches of the last minute finished, along with their number of finished tasks.
+    last_minute: VecDeque<(Instant, u64)>,
+    count: u64,
+    total_duration: Duration,
+}
+
+impl FinishedBatches {
+    pub fn register(&mut self, finished_tasks: u64, duration: Duration) {
+        let now = Instant::now();
+        self.forget_before(now);
+        self.last_minute.push_back((now, finished_tasks));
+        self.count += 1;
+        self.total_duration += duration;
+    }
+
+    pub fn finished_tasks_per_minute(&mut self) -> u64 {
+        self.forget_before(Instant::now());
+        self.last_minute.iter().map(|(_, finished_tasks)| finished_tasks).sum()
+    }
+
+    pub fn average_duration(&self) -> Option<Duration> {
+        (self.count > 0).then(|| self.total_duration.div_f64(self.count as f64))
+    }
+
+    fn forget_before(&mut self, now: Instant) {
+        while let Some((finished_at, _)) = self.last_minute.front() {
+            if now.saturating_duration_since(*finished_at) < THROUGHPUT_WINDOW {
+                break;
+            }
+            self.last_minute.pop_front();
+        }
+    }
+}
@@ -245,6 +245,9 @@ pub fn swap_index_uid_in_task(task: &mut Task, swap: (&str, &str)) {
         K::IndexDeletion { index_uid } => index_uids.push(index_uid),
         K::IndexCreation { index_uid, .. } => index_uids.push(index_uid),
         K::IndexUpdate { index_uid, .. } => index_uids.push(index_uid),
+        K::IndexRestore { index_uid, .. } => index_uids.push(index_uid),
+        K::IntegrityCheck { index_uid, .. } => index_uids.push(index_uid),
+        K::IndexWarmup { index_uid, .. } => index_uids.push(index_uid),
         K::IndexSwap { swaps } => {
             for IndexSwap { indexes: (lhs, rhs) } in swaps.iter_mut() {
                 if lhs == swap.0 || lhs == swap.1 {
@@ -258,7 +261,8 @@ pub fn swap_index_uid_in_task(task: &mut Task, swap: (&str, &str)) {
         K::TaskCancelation { .. }
         | K::TaskDeletion { .. }
         | K::DumpCreation { .. }
-        | K::SnapshotCreation => (),
+        | K::SnapshotCreation
+        | K::SnapshotImportIncremental => (),
     };
     if let Some(Details::IndexSwap { swaps }) = &mut task.details {
         for IndexSwap { indexes: (lhs, rhs) } in swaps.iter_mut() {
@@ -349,6 +353,7 @@ impl IndexScheduler {
                 details,
                 status,
                 kind,
+                attempts: _,
             } = task;
             assert_eq!(uid, task.uid);
             if let Some(task_index_uid) = &task_index_uid {
@@ -397,7 +402,14 @@ impl IndexScheduler {
                             assert_eq!(&sw1, sw2);
                         }
                     }
-                    Details::DocumentAdditionOrUpdate { received_documents, indexed_documents } => {
+                    Details::DocumentAdditionOrUpdate {
+                        received_documents,
+                        indexed_documents,
+                        duplicate_documents: _,
+                        invalid_utf8_replacements: _,
+                        indexing_errors: _,
+                        facet_update_strategies: _,
+                    } => {
                         assert_eq!(kind.as_kind(), Kind::DocumentAdditionOrUpdate);
                         match indexed_documents {
                             Some(indexed_documents) => {
@@ -416,7 +428,7 @@ impl IndexScheduler {
                             }
                         }
                     }
-                    Details::SettingsUpdate { settings: _ } => {
+                    Details::SettingsUpdate { settings: _, warnings: _ } => {
                         assert_eq!(kind.as_kind(), Kind::SettingsUpdate);
                     }
                     Details::IndexInfo { primary_key: pk1 } => match &kind {
@@ -483,6 +495,66 @@ impl IndexScheduler {
                             }
                         }
                     }
+                    Details::IndexRestore {
+                        snapshot_path,
+                        snapshot_index_uid,
+                        restored_documents,
+                    } => {
+                        match &kind {
+                            KindWithContent::IndexRestore {
+                                snapshot_path: path,
+                                snapshot_index_uid: uid,
+                                ..
+                            } => {
+                                assert_eq!(&snapshot_path, path);
+                                assert_eq!(&snapshot_index_uid, uid);
+                            }
+                            _ => panic!(),
+                        }
+                        match status {
+                            Status::Enqueued | Status::Processing => {
+                                assert!(restored_documents.is_none())
+                            }
+                            Status::Succeeded => assert!(restored_documents.is_some()),
+                            Status::Failed | Status::Canceled => {
+                                assert_eq!(restored_documents, Some(0))
+                            }
+                        }
+                    }
+                    Details::IntegrityCheck { repair, violations } => {
+                        assert!(
+                            matches!(kind, KindWithContent::IntegrityCheck { repair: r, .. } if r == repair)
+                        );
+                        assert_eq!(violations.is_some(), status == Status::Succeeded);
+                    }
+                    Details::IndexWarmup { prefixes, populate_cache, warmed_entries } => {
+                        assert!(matches!(
+                            kind,
+                            KindWithContent::IndexWarmup { prefixes: p, populate_cache: c, .. }
+                                if p == prefixes && c == populate_cache
+                        ));
+                        match status {
+                            Status::Enqueued | Status::Processing => {
+                                assert!(warmed_entries.is_none())
+                            }
+                            Status::Succeeded => assert!(warmed_entries.is_some()),
+                            Status::Failed | Status::Canceled => {
+                                assert_eq!(warmed_entries, Some(0))
+                            }
+                        }
+                    }
+                    Details::SnapshotImport { imported_indexes } => {
+                        assert_eq!(kind.as_kind(), Kind::SnapshotImportIncremental);
+                        match status {
+                            Status::Enqueued | Status::Processing => {
+                                assert!(imported_indexes.is_none())
+                            }
+                            Status::Succeeded => assert!(imported_indexes.is_some()),
+                            Status::Failed | Status::Canceled => {
+                                assert_eq!(imported_indexes, Some(0))
+                            }
+                        }
+                    }
                     Details::ClearAll { deleted_documents } => {
                         assert!(matches!(
                             kind.as_kind(),
@@ -0,0 +1,218 @@
+use std::sync::{Arc, RwLock};
+
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::heed::types::{SerdeJson, Str};
+use meilisearch_types::heed::{Database, Env, RoTxn, RwTxn};
+use meilisearch_types::milli::{RoaringBitmapCodec, BEU64};
+use roaring::RoaringBitmap;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::Result;
+
+const WEBHOOK_PAYLOADS: &str = "webhook-payloads";
+const WEBHOOK_STATE: &str = "webhook-state";
+const WEBHOOK_SETTINGS: &str = "webhook-settings";
+
+const LAST_ACKNOWLEDGED_SEQUENCE: &str = "last-acknowledged-sequence";
+const DROPPED_PAYLOADS: &str = "dropped-payloads";
+
+/// The number of payloads waiting to be delivered above which the oldest ones are dropped.
+pub(crate) const MAX_PENDING_WEBHOOK_PAYLOADS: u64 = 1000;
+
+/// The payloads of the task webhook, delivered strictly in the order of their sequence number.
+#[derive(Clone)]
+pub(crate) struct WebhookQueue {
+    /// The ids of the tasks of each payload that is not delivered yet, by sequence number.
+    payloads: Database<BEU64, RoaringBitmapCodec>,
+    /// The last sequence number acknowledged by the webhook and the number of dropped payloads.
+    state: Database<Str, BEU64>,
+    /// Why the last payload could not be delivered and when, kept in memory only.
+    last_error: Arc<RwLock<Option<(ResponseError, OffsetDateTime)>>>,
+}
+
+/// The delivery state of the task webhook.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookStatus {
+    /// The URL the payloads are sent to.
+    pub url: Option<String>,
+    /// The sequence number of the last payload acknowledged by the webhook.
+    pub last_acknowledged_sequence: Option<u64>,
+    /// The number of payloads waiting to be delivered.
+    pub lag: u64,
+    /// The number of payloads that were dropped because too many were waiting to be delivered.
+    pub dropped: u64,
+    /// Why the last payload could not be delivered, cleared once a payload is delivered.
+    pub last_error: Option<ResponseError>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_error_at: Option<OffsetDateTime>,
+}
+
+/// Where to send the payloads of the task webhook.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSettings {
+    pub url: Option<String>,
+    /// The value of the Authorization header sent with the payloads.
+    pub authorization_header: Option<String>,
+}
+
+/// The settings of the task webhook.
+///
+/// Once they are updated at runtime, the persisted settings take precedence over the ones given at launch.
+#[derive(Clone)]
+pub(crate) struct WebhookSettingsData {
+    persisted: Database<Str, SerdeJson<WebhookSettings>>,
+    runtime: Arc<RwLock<WebhookSettings>>,
+}
+
+impl WebhookSettingsData {
+    pub fn new(env: &Env, launch_settings: WebhookSettings) -> Result<Self> {
+        let mut wtxn = env.write_txn()?;
+        let persisted = env.create_database(&mut wtxn, Some(WEBHOOK_SETTINGS))?;
+        wtxn.commit()?;
+
+        let rtxn = env.read_txn()?;
+        let settings = persisted.get(&rtxn, WEBHOOK_SETTINGS)?.unwrap_or(launch_settings);
+        Ok(Self { persisted, runtime: Arc::new(RwLock::new(settings)) })
+    }
+
+    pub fn get(&self) -> WebhookSettings {
+        self.runtime.read().unwrap().clone()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.runtime.read().unwrap().url.is_some()
+    }
+
+    pub fn put(&self, mut wtxn: RwTxn, settings: WebhookSettings) -> Result<()> {
+        self.persisted.put(&mut wtxn, WEBHOOK_SETTINGS, &settings)?;
+        wtxn.commit()?;
+
+        *self.runtime.write().unwrap() = settings;
+        Ok(())
+    }
+}
+
+impl WebhookQueue {
+    pub fn new(env: &Env) -> Result<Self> {
+        let mut wtxn = env.write_txn()?;
+        let payloads = env.create_database(&mut wtxn, Some(WEBHOOK_PAYLOADS))?;
+        let state = env.create_database(&mut wtxn, Some(WEBHOOK_STATE))?;
+        wtxn.commit()?;
+
+        Ok(Self { payloads, state, last_error: Default::default() })
+    }
+
+    /// Registers a new payload containing these tasks and returns its sequence number.
+    ///
+    /// Drops the oldest payloads if more than `max_pending` are waiting to be delivered.
+    pub fn push(&self, wtxn: &mut RwTxn, tasks: &RoaringBitmap, max_pending: u64) -> Result<u64> {
+        let last_sequence = match self.payloads.last(wtxn)? {
+            Some((sequence, _)) => Some(sequence),
+            None => self.state.get(wtxn, LAST_ACKNOWLEDGED_SEQUENCE)?,
+        };
+        let sequence = last_sequence.map_or(1, |sequence| sequence + 1);
+        self.payloads.put(wtxn, &sequence, tasks)?;
+
+        let mut dropped = 0;
+        while self.payloads.len(wtxn)? > max_pending {
+            let Some((oldest, _)) = self.payloads.first(wtxn)? else { break };
+            self.payloads.delete(wtxn, &oldest)?;
+            dropped += 1;
+        }
+        if dropped != 0 {
+            tracing::warn!(
+                "Dropped {dropped} webhook payloads that could not be delivered in time"
+            );
+            let total = self.state.get(wtxn, DROPPED_PAYLOADS)?.unwrap_or_default() + dropped;
+            self.state.put(wtxn, DROPPED_PAYLOADS, &total)?;
+        }
+
+        Ok(sequence)
+    }
+
+    /// Returns the oldest payload that is not delivered yet, with its sequence number.
+    pub fn next(&self, rtxn: &RoTxn) -> Result<Option<(u64, RoaringBitmap)>> {
+        Ok(self.payloads.first(rtxn)?)
+    }
+
+    /// Removes the payload once the webhook acknowledged it.
+    pub fn acknowledge(&self, wtxn: &mut RwTxn, sequence: u64) -> Result<()> {
+        self.payloads.delete(wtxn, &sequence)?;
+        self.state.put(wtxn, LAST_ACKNOWLEDGED_SEQUENCE, &sequence)?;
+        Ok(())
+    }
+
+    pub fn status(&self, rtxn: &RoTxn, url: Option<String>) -> Result<WebhookStatus> {
+        let (last_error, last_error_at) = self.last_error.read().unwrap().clone().unzip();
+        Ok(WebhookStatus {
+            url,
+            last_acknowledged_sequence: self.state.get(rtxn, LAST_ACKNOWLEDGED_SEQUENCE)?,
+            lag: self.payloads.len(rtxn)?,
+            dropped: self.state.get(rtxn, DROPPED_PAYLOADS)?.unwrap_or_default(),
+            last_error,
+            last_error_at,
+        })
+    }
+
+    /// Records why the next payload could not be delivered, or clears the error once it is delivered.
+    pub fn set_last_error(&self, error: Option<ResponseError>) {
+        *self.last_error.write().unwrap() = error.map(|error| (error, OffsetDateTime::now_utc()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use meilisearch_types::heed::EnvOpenOptions;
+
+    use super::*;
+
+    #[test]
+    fn payloads_are_sequenced_and_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = EnvOpenOptions::new().max_dbs(2).open(dir.path()).unwrap();
+        let queue = WebhookQueue::new(&env).unwrap();
+
+        let mut wtxn = env.write_txn().unwrap();
+        for task in 0..4 {
+            queue.push(&mut wtxn, &RoaringBitmap::from_iter([task]), 3).unwrap();
+        }
+        let status = queue.status(&wtxn, None).unwrap();
+        assert_eq!(
+            status,
+            WebhookStatus {
+                url: None,
+                last_acknowledged_sequence: None,
+                lag: 3,
+                dropped: 1,
+                last_error: None,
+                last_error_at: None
+            }
+        );
+
+        // the oldest payload was dropped
+        let (sequence, tasks) = queue.next(&wtxn).unwrap().unwrap();
+        assert_eq!((sequence, tasks), (2, RoaringBitmap::from_iter([1])));
+        queue.acknowledge(&mut wtxn, sequence).unwrap();
+        let status = queue.status(&wtxn, None).unwrap();
+        assert_eq!(
+            status,
+            WebhookStatus {
+                url: None,
+                last_acknowledged_sequence: Some(2),
+                lag: 2,
+                dropped: 1,
+                last_error: None,
+                last_error_at: None
+            }
+        );
+
+        // the sequence keeps increasing once every payload is acknowledged
+        while let Some((sequence, _)) = queue.next(&wtxn).unwrap() {
+            queue.acknowledge(&mut wtxn, sequence).unwrap();
+        }
+        assert_eq!(queue.push(&mut wtxn, &RoaringBitmap::from_iter([4]), 3).unwrap(), 5);
+    }
+}
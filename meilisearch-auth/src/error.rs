@@ -11,6 +11,10 @@ pub enum AuthControllerError {
     ApiKeyNotFound(String),
     #[error("`uid` field value `{0}` is already an existing API key.")]
     ApiKeyAlreadyExists(String),
+    #[error("The API key `{uid}` can't be overwritten because its immutable field `{field}` differs from the one of the imported key.")]
+    ImmutableApiKeyField { uid: String, field: &'static str, code: Code },
+    #[error("Index group `{0}` not found.")]
+    IndexGroupNotFound(String),
     #[error("Internal error: {0}")]
     Internal(Box<dyn Error + Send + Sync + 'static>),
 }
@@ -27,6 +31,8 @@ impl ErrorCode for AuthControllerError {
         match self {
             Self::ApiKeyNotFound(_) => Code::ApiKeyNotFound,
             Self::ApiKeyAlreadyExists(_) => Code::ApiKeyAlreadyExists,
+            Self::ImmutableApiKeyField { code, .. } => *code,
+            Self::IndexGroupNotFound(_) => Code::IndexGroupNotFound,
             Self::Internal(_) => Code::Internal,
         }
     }
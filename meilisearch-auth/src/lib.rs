@@ -2,16 +2,18 @@ mod dump;
 pub mod error;
 mod store;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use error::{AuthControllerError, Result};
 use maplit::hashset;
+use meilisearch_types::error::Code;
 use meilisearch_types::index_uid_pattern::IndexUidPattern;
 use meilisearch_types::keys::{Action, CreateApiKey, Key, PatchApiKey};
 use meilisearch_types::milli::update::Setting;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 pub use store::open_auth_store_env;
 use store::{generate_key_as_hexa, HeedAuthStore};
 use time::OffsetDateTime;
@@ -21,6 +23,8 @@ use uuid::Uuid;
 pub struct AuthController {
     store: Arc<HeedAuthStore>,
     master_key: Option<String>,
+    /// The index uid patterns of the index groups, stored by the scheduler.
+    index_groups: Arc<RwLock<HashMap<String, Vec<IndexUidPattern>>>>,
 }
 
 impl AuthController {
@@ -31,7 +35,11 @@ impl AuthController {
             generate_default_keys(&store)?;
         }
 
-        Ok(Self { store: Arc::new(store), master_key: master_key.clone() })
+        Ok(Self {
+            store: Arc::new(store),
+            master_key: master_key.clone(),
+            index_groups: Arc::default(),
+        })
     }
 
     /// Return `Ok(())` if the auth controller is able to access one of its database.
@@ -51,12 +59,49 @@ impl AuthController {
     }
 
     pub fn create_key(&self, create_key: CreateApiKey) -> Result<Key> {
+        self.check_index_groups_exist(&create_key.index_groups)?;
+
         match self.store.get_api_key(create_key.uid)? {
             Some(_) => Err(AuthControllerError::ApiKeyAlreadyExists(create_key.uid.to_string())),
             None => self.store.put_api_key(create_key.to_key()),
         }
     }
 
+    /// Create the key, or replace the existing key with the same uid when `overwrite` is true.
+    /// Like an update, replacing a key can only change its name and description.
+    ///
+    /// Returns the key and whether it replaced an existing one.
+    pub fn import_key(&self, create_key: CreateApiKey, overwrite: bool) -> Result<(Key, bool)> {
+        self.check_index_groups_exist(&create_key.index_groups)?;
+
+        match self.store.get_api_key(create_key.uid)? {
+            Some(_) if !overwrite => {
+                Err(AuthControllerError::ApiKeyAlreadyExists(create_key.uid.to_string()))
+            }
+            Some(existing) => {
+                let mut key = create_key.to_key();
+                if let Some((field, code)) = changed_immutable_field(&existing, &key) {
+                    return Err(AuthControllerError::ImmutableApiKeyField {
+                        uid: key.uid.to_string(),
+                        field,
+                        code,
+                    });
+                }
+                key.created_at = existing.created_at;
+                Ok((self.store.put_api_key(key)?, true))
+            }
+            None => Ok((self.store.put_api_key(create_key.to_key())?, false)),
+        }
+    }
+
+    fn check_index_groups_exist(&self, groups: &[String]) -> Result<()> {
+        let index_groups = self.index_groups.read().unwrap();
+        match groups.iter().find(|group| !index_groups.contains_key(*group)) {
+            Some(group) => Err(AuthControllerError::IndexGroupNotFound(group.clone())),
+            None => Ok(()),
+        }
+    }
+
     pub fn update_key(&self, uid: Uuid, patch: PatchApiKey) -> Result<Key> {
         let mut key = self.get_key(uid)?;
         match patch.description {
@@ -98,11 +143,20 @@ impl AuthController {
     ) -> Result<AuthFilter> {
         let key = self.get_key(uid)?;
 
-        let key_authorized_indexes = SearchRules::Set(key.indexes.into_iter().collect());
+        let mut key_authorized_indexes: HashSet<_> = key.indexes.into_iter().collect();
+        key_authorized_indexes.extend(self.resolve_index_groups(&key.index_groups));
+        let key_authorized_indexes = SearchRules::Set(key_authorized_indexes);
 
         let allow_index_creation = self.is_key_authorized(uid, Action::IndexesAdd, None)?;
-
-        Ok(AuthFilter { search_rules, key_authorized_indexes, allow_index_creation })
+        let allow_search_debug = self.is_key_authorized(uid, Action::SearchDebug, None)?;
+
+        Ok(AuthFilter {
+            search_rules,
+            key_authorized_indexes,
+            key_default_filters: key.default_filters,
+            allow_index_creation,
+            allow_search_debug,
+        })
     }
 
     pub fn list_keys(&self) -> Result<Vec<Key>> {
@@ -140,8 +194,12 @@ impl AuthController {
             // check if the key has access to all indexes.
             .get_expiration_date(uid, action, None)?
             .or(match index {
-                // else check if the key has access to the requested index.
-                Some(index) => self.store.get_expiration_date(uid, action, Some(index))?,
+                // else check if the key has access to the requested index,
+                // directly or through one of its index groups.
+                Some(index) => match self.store.get_expiration_date(uid, action, Some(index))? {
+                    Some(expiration) => Some(expiration),
+                    None => self.index_group_expiration_date(uid, action, index)?,
+                },
                 // or to any index if no index has been requested.
                 None => self.store.prefix_first_expiration_date(uid, action)?,
             }) {
@@ -154,6 +212,39 @@ impl AuthController {
         }
     }
 
+    /// Returns the expiration date of the action if one of the index groups of the key matches the index.
+    fn index_group_expiration_date(
+        &self,
+        uid: Uuid,
+        action: Action,
+        index: &str,
+    ) -> Result<Option<Option<OffsetDateTime>>> {
+        let Some(key) = self.store.get_api_key(uid)? else { return Ok(None) };
+        let patterns = self.resolve_index_groups(&key.index_groups);
+        if patterns.iter().any(|pattern| pattern.matches_str(index)) {
+            // the key has an entry for each of its actions, whatever the index.
+            self.store.prefix_first_expiration_date(uid, action)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Replaces the cached index uid patterns of the index groups.
+    ///
+    /// Must be called each time the index groups are modified in the scheduler.
+    pub fn set_index_groups(
+        &self,
+        groups: impl IntoIterator<Item = (String, Vec<IndexUidPattern>)>,
+    ) {
+        *self.index_groups.write().unwrap() = groups.into_iter().collect();
+    }
+
+    /// Returns the index uid patterns of these index groups, ignoring the ones that were deleted.
+    fn resolve_index_groups(&self, groups: &[String]) -> Vec<IndexUidPattern> {
+        let index_groups = self.index_groups.read().unwrap();
+        groups.iter().filter_map(|group| index_groups.get(group)).flatten().cloned().collect()
+    }
+
     /// Delete all the keys in the DB.
     pub fn raw_delete_all_keys(&mut self) -> Result<()> {
         self.store.delete_all_keys()
@@ -169,7 +260,10 @@ impl AuthController {
 pub struct AuthFilter {
     search_rules: Option<SearchRules>,
     key_authorized_indexes: SearchRules,
+    /// The filters of the key applied on top of the search rules, by index uid pattern.
+    key_default_filters: BTreeMap<IndexUidPattern, String>,
     allow_index_creation: bool,
+    allow_search_debug: bool,
 }
 
 impl Default for AuthFilter {
@@ -177,7 +271,9 @@ impl Default for AuthFilter {
         Self {
             search_rules: None,
             key_authorized_indexes: SearchRules::default(),
+            key_default_filters: BTreeMap::new(),
             allow_index_creation: true,
+            allow_search_debug: true,
         }
     }
 }
@@ -188,11 +284,19 @@ impl AuthFilter {
         self.allow_index_creation && self.is_index_authorized(index)
     }
 
+    /// Whether the search requests can ask for the debug artifacts of the search.
+    #[inline]
+    pub fn allow_search_debug(&self) -> bool {
+        self.allow_search_debug
+    }
+
     pub fn with_allowed_indexes(allowed_indexes: HashSet<IndexUidPattern>) -> Self {
         Self {
             search_rules: None,
             key_authorized_indexes: SearchRules::Set(allowed_indexes),
+            key_default_filters: BTreeMap::new(),
             allow_index_creation: false,
+            allow_search_debug: false,
         }
     }
 
@@ -214,12 +318,33 @@ impl AuthFilter {
                 .unwrap_or(true)
     }
 
+    /// The rules to apply on the searches and the documents fetches made on this index.
+    ///
+    /// The default filter of the key is AND-ed to the filter of the tenant token.
     pub fn get_index_search_rules(&self, index: &str) -> Option<IndexSearchRules> {
         if !self.is_index_authorized(index) {
             return None;
         }
         let search_rules = self.search_rules.as_ref().unwrap_or(&self.key_authorized_indexes);
-        search_rules.get_index_search_rules(index)
+        let rules = search_rules.get_index_search_rules(index);
+        match self.key_default_filter(index) {
+            Some(default_filter) => {
+                let mut rules = rules.unwrap_or_default();
+                let default_filter = Value::String(default_filter.to_owned());
+                rules.filter = and_filters(rules.filter, Some(default_filter));
+                Some(rules)
+            }
+            None => rules,
+        }
+    }
+
+    /// The default filter of the key for this index, the one of the most specific matching pattern.
+    fn key_default_filter(&self, index: &str) -> Option<&str> {
+        self.key_default_filters
+            .iter()
+            .filter(|(pattern, _)| pattern.matches_str(index))
+            .max_by_key(|(pattern, _)| (pattern.is_exact(), pattern.len()))
+            .map(|(_, filter)| filter.as_str())
     }
 }
 
@@ -304,6 +429,44 @@ pub struct IndexSearchRules {
     pub filter: Option<serde_json::Value>,
 }
 
+/// Combines two filters so that the documents must match both of them.
+pub fn and_filters(filter: Option<Value>, other: Option<Value>) -> Option<Value> {
+    match (filter, other) {
+        (None, other) => other,
+        (filter, None) => filter,
+        (Some(filter), Some(other)) => {
+            let filter = match filter {
+                Value::Array(filter) => filter,
+                filter => vec![filter],
+            };
+            let other = match other {
+                Value::Array(other) => other,
+                other => vec![other],
+            };
+
+            Some(Value::Array([filter, other].concat()))
+        }
+    }
+}
+
+/// Returns the name of the first field that can't be updated and differs between the keys,
+/// with the code of the error reporting it.
+fn changed_immutable_field(existing: &Key, key: &Key) -> Option<(&'static str, Code)> {
+    if existing.actions != key.actions {
+        Some(("actions", Code::ImmutableApiKeyActions))
+    } else if existing.indexes != key.indexes {
+        Some(("indexes", Code::ImmutableApiKeyIndexes))
+    } else if existing.index_groups != key.index_groups {
+        Some(("indexGroups", Code::ImmutableApiKeyIndexGroups))
+    } else if existing.default_filters != key.default_filters {
+        Some(("defaultFilters", Code::ImmutableApiKeyDefaultFilters))
+    } else if existing.expires_at != key.expires_at {
+        Some(("expiresAt", Code::ImmutableApiKeyExpiresAt))
+    } else {
+        None
+    }
+}
+
 fn generate_default_keys(store: &HeedAuthStore) -> Result<()> {
     store.put_api_key(Key::default_admin())?;
     store.put_api_key(Key::default_search())?;
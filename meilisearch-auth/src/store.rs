@@ -29,6 +29,9 @@ const AUTH_DB_PATH: &str = "auth";
 const KEY_DB_NAME: &str = "api-keys";
 const KEY_ID_ACTION_INDEX_EXPIRATION_DB_NAME: &str = "keyid-action-index-expiration";
 
+/// Prefixes the index groups in the inverted database, it can't start an index uid pattern.
+const INDEX_GROUP_PREFIX: &str = "@";
+
 #[derive(Clone)]
 pub struct HeedAuthStore {
     env: Arc<Env>,
@@ -143,6 +146,12 @@ impl HeedAuthStore {
                 Action::MetricsAll => {
                     actions.insert(Action::MetricsGet);
                 }
+                Action::PresetsAll => {
+                    actions.extend([Action::PresetsGet, Action::PresetsUpdate]);
+                }
+                Action::LogsAll => {
+                    actions.extend([Action::LogsGet, Action::LogsCreate, Action::LogsDelete]);
+                }
                 other => {
                     actions.insert(*other);
                 }
@@ -163,6 +172,11 @@ impl HeedAuthStore {
                         &key.expires_at,
                     )?;
                 }
+                // and for each index group, whose patterns are only known when the key is used.
+                for group in key.index_groups.iter() {
+                    let group = format!("{INDEX_GROUP_PREFIX}{group}");
+                    db.put(&mut wtxn, &(&uid, &action, Some(group.as_bytes())), &key.expires_at)?;
+                }
             }
         }
 
@@ -245,6 +259,9 @@ impl HeedAuthStore {
                 for result in self.action_keyid_index_expiration.prefix_iter(&rtxn, &tuple)? {
                     let ((_, _, index_uid_pattern), expiration) = result?;
                     if let Some((pattern, index)) = index_uid_pattern.zip(index) {
+                        if pattern.starts_with(INDEX_GROUP_PREFIX.as_bytes()) {
+                            continue;
+                        }
                         let index_uid_pattern = str::from_utf8(pattern)?;
                         let pattern = IndexUidPattern::from_str(index_uid_pattern)
                             .map_err(|e| AuthControllerError::Internal(Box::new(e)))?;
@@ -1,6 +1,6 @@
 use std::fs::{create_dir_all, File};
 use std::io::Write;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
@@ -8,16 +8,69 @@ use flate2::Compression;
 use tar::{Archive, Builder};
 
 pub fn to_tar_gz(src: impl AsRef<Path>, dest: impl AsRef<Path>) -> anyhow::Result<()> {
+    to_tar_gz_with_progress(src, dest, |_| ())
+}
+
+/// Like [`to_tar_gz`], but calls `on_progress` with the number of uncompressed bytes
+/// written to the archive every time the archive grows.
+pub fn to_tar_gz_with_progress(
+    src: impl AsRef<Path>,
+    dest: impl AsRef<Path>,
+    on_progress: impl FnMut(u64),
+) -> anyhow::Result<()> {
     let mut f = File::create(dest)?;
     let gz_encoder = GzEncoder::new(&mut f, Compression::default());
-    let mut tar_encoder = Builder::new(gz_encoder);
+    let mut tar_encoder = Builder::new(ProgressWriter::new(gz_encoder, on_progress));
     tar_encoder.append_dir_all(".", src)?;
-    let gz_encoder = tar_encoder.into_inner()?;
+    let gz_encoder = tar_encoder.into_inner()?.into_inner();
     gz_encoder.finish()?;
     f.flush()?;
     Ok(())
 }
 
+/// Returns the total size of the files contained in the directory and its subdirectories.
+pub fn dir_size(path: impl AsRef<Path>) -> std::io::Result<u64> {
+    let mut size = 0;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            size += dir_size(entry.path())?;
+        } else {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
+/// A writer that calls a function with the number of bytes written through it.
+pub struct ProgressWriter<W, F> {
+    inner: W,
+    on_write: F,
+}
+
+impl<W: Write, F: FnMut(u64)> ProgressWriter<W, F> {
+    pub fn new(inner: W, on_write: F) -> Self {
+        ProgressWriter { inner, on_write }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write, F: FnMut(u64)> Write for ProgressWriter<W, F> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        (self.on_write)(written as u64);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 pub fn from_tar_gz(src: impl AsRef<Path>, dest: impl AsRef<Path>) -> anyhow::Result<()> {
     let f = File::open(&src)?;
     let gz = GzDecoder::new(f);
@@ -26,3 +79,29 @@ pub fn from_tar_gz(src: impl AsRef<Path>, dest: impl AsRef<Path>) -> anyhow::Res
     ar.unpack(&dest)?;
     Ok(())
 }
+
+/// Extracts only the entries of the archive whose path, relative to the root of the archive,
+/// is accepted by the `filter`.
+pub fn from_tar_gz_filtered(
+    src: impl AsRef<Path>,
+    dest: impl AsRef<Path>,
+    mut filter: impl FnMut(&Path) -> bool,
+) -> anyhow::Result<()> {
+    let f = File::open(&src)?;
+    let gz = GzDecoder::new(f);
+    let mut ar = Archive::new(gz);
+    create_dir_all(&dest)?;
+    for entry in ar.entries()? {
+        let mut entry = entry?;
+        // The archives are built from `.` so we ignore the leading `./` of the paths.
+        let path: PathBuf = entry
+            .path()?
+            .components()
+            .filter(|component| !matches!(component, Component::CurDir))
+            .collect();
+        if filter(&path) {
+            entry.unpack_in(&dest)?;
+        }
+    }
+    Ok(())
+}
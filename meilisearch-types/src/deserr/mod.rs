@@ -8,8 +8,8 @@ use deserr::{take_cf_content, DeserializeError, IntoValue, MergeWithError, Value
 
 use crate::error::deserr_codes::*;
 use crate::error::{
-    Code, DeserrParseBoolError, DeserrParseIntError, ErrorCode, InvalidTaskDateError,
-    ParseOffsetDateTimeError,
+    Code, DeserrParseBoolError, DeserrParseIntError, ErrorCode, InvalidDefaultFilterError,
+    InvalidTaskDateError, ParseOffsetDateTimeError,
 };
 use crate::index_uid::IndexUidFormatError;
 use crate::tasks::{ParseTaskKindError, ParseTaskStatusError};
@@ -149,12 +149,19 @@ make_missing_field_convenience_builder!(MissingIndexUid, missing_index_uid);
 make_missing_field_convenience_builder!(MissingApiKeyActions, missing_api_key_actions);
 make_missing_field_convenience_builder!(MissingApiKeyExpiresAt, missing_api_key_expires_at);
 make_missing_field_convenience_builder!(MissingApiKeyIndexes, missing_api_key_indexes);
+make_missing_field_convenience_builder!(MissingApiKeyUid, missing_api_key_uid);
+make_missing_field_convenience_builder!(MissingIndexGroupIndexes, missing_index_group_indexes);
 make_missing_field_convenience_builder!(MissingSwapIndexes, missing_swap_indexes);
+make_missing_field_convenience_builder!(MissingSnapshotPath, missing_snapshot_path);
+make_missing_field_convenience_builder!(MissingIndexWarmupPrefixes, missing_index_warmup_prefixes);
 make_missing_field_convenience_builder!(MissingDocumentFilter, missing_document_filter);
 make_missing_field_convenience_builder!(
     MissingFacetSearchFacetName,
     missing_facet_search_facet_name
 );
+make_missing_field_convenience_builder!(MissingRelatedTermsWord, missing_related_terms_word);
+make_missing_field_convenience_builder!(MissingFilterAssistFilter, missing_filter_assist_filter);
+make_missing_field_convenience_builder!(MissingDocumentId, missing_document_id);
 
 // Integrate a sub-error into a [`DeserrError`] by taking its error message but using
 // the default error code (C) from `Self`
@@ -185,7 +192,10 @@ merge_with_error_impl_take_error_message!(DeserrParseBoolError);
 merge_with_error_impl_take_error_message!(uuid::Error);
 merge_with_error_impl_take_error_message!(InvalidTaskDateError);
 merge_with_error_impl_take_error_message!(ParseOffsetDateTimeError);
+merge_with_error_impl_take_error_message!(InvalidDefaultFilterError);
 merge_with_error_impl_take_error_message!(ParseTaskKindError);
 merge_with_error_impl_take_error_message!(ParseTaskStatusError);
 merge_with_error_impl_take_error_message!(IndexUidFormatError);
 merge_with_error_impl_take_error_message!(InvalidSearchSemanticRatio);
+merge_with_error_impl_take_error_message!(InvalidSearchRankingScoreThreshold);
+merge_with_error_impl_take_error_message!(InvalidMultiSearchWeight);
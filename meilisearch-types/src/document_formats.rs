@@ -14,7 +14,7 @@ use crate::error::{Code, ErrorCode};
 
 type Result<T> = std::result::Result<T, DocumentFormatError>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum PayloadType {
     Ndjson,
     Json,
@@ -35,6 +35,13 @@ impl fmt::Display for PayloadType {
 pub enum DocumentFormatError {
     Io(io::Error),
     MalformedPayload(Error, PayloadType),
+    /// The payload contains an invalid UTF-8 sequence starting at `offset`,
+    /// `snippet` holds the bytes around it.
+    InvalidUtf8 {
+        payload_type: PayloadType,
+        offset: u64,
+        snippet: Vec<u8>,
+    },
 }
 
 impl Display for DocumentFormatError {
@@ -73,6 +80,15 @@ impl Display for DocumentFormatError {
                 }
                 _ => write!(f, "The `{}` payload provided is malformed: `{}`.", b, me),
             },
+            Self::InvalidUtf8 { payload_type, offset, snippet } => {
+                let snippet: Vec<_> = snippet.iter().map(|byte| format!("{byte:02x}")).collect();
+                write!(
+                    f,
+                    "The `{payload_type}` payload provided contains an invalid UTF-8 sequence at byte {offset}: `{}`. \
+Use the `lossyUtf8=true` query parameter to replace the invalid sequences with U+FFFD.",
+                    snippet.join(" ")
+                )
+            }
         }
     }
 }
@@ -99,8 +115,105 @@ impl ErrorCode for DocumentFormatError {
         match self {
             DocumentFormatError::Io(e) => e.error_code(),
             DocumentFormatError::MalformedPayload(_, _) => Code::MalformedPayload,
+            DocumentFormatError::InvalidUtf8 { .. } => Code::InvalidDocumentFormat,
+        }
+    }
+}
+
+/// The number of bytes shown before and after an invalid UTF-8 sequence in the errors.
+const UTF8_SNIPPET_CONTEXT: usize = 8;
+
+/// Validates the encoding of a payload received in chunks.
+///
+/// An invalid UTF-8 sequence is rejected with its offset in the payload, or replaced
+/// with U+FFFD when the decoder is lossy.
+#[derive(Debug)]
+pub struct Utf8Decoder {
+    payload_type: PayloadType,
+    lossy: bool,
+    /// The number of bytes of the payload decoded so far.
+    offset: u64,
+    /// The start of a sequence cut at the end of the previous chunk.
+    pending: Vec<u8>,
+    replacements: u64,
+}
+
+impl Utf8Decoder {
+    pub fn new(payload_type: PayloadType, lossy: bool) -> Self {
+        Utf8Decoder { payload_type, lossy, offset: 0, pending: Vec::new(), replacements: 0 }
+    }
+
+    /// Decodes the next chunk of the payload and appends its valid bytes to `output`.
+    pub fn decode(&mut self, chunk: &[u8], output: &mut Vec<u8>) -> Result<()> {
+        let joined;
+        let mut bytes = if self.pending.is_empty() {
+            chunk
+        } else {
+            self.pending.extend_from_slice(chunk);
+            joined = std::mem::take(&mut self.pending);
+            joined.as_slice()
+        };
+
+        loop {
+            let error = match std::str::from_utf8(bytes) {
+                Ok(_) => {
+                    output.extend_from_slice(bytes);
+                    self.offset += bytes.len() as u64;
+                    return Ok(());
+                }
+                Err(error) => error,
+            };
+
+            let (valid, rest) = bytes.split_at(error.valid_up_to());
+            output.extend_from_slice(valid);
+            self.offset += valid.len() as u64;
+            match error.error_len() {
+                // the sequence is completed by the next chunk
+                None => {
+                    self.pending = rest.to_vec();
+                    return Ok(());
+                }
+                Some(len) => {
+                    self.replace_invalid_sequence(bytes, valid.len(), len, output)?;
+                    bytes = &rest[len..];
+                }
+            }
         }
     }
+
+    /// Checks that the payload doesn't end in the middle of a sequence and returns the number of
+    /// invalid sequences that were replaced.
+    pub fn finish(mut self, output: &mut Vec<u8>) -> Result<u64> {
+        if !self.pending.is_empty() {
+            let pending = std::mem::take(&mut self.pending);
+            self.replace_invalid_sequence(&pending, 0, pending.len(), output)?;
+        }
+        Ok(self.replacements)
+    }
+
+    /// Rejects the invalid sequence of `len` bytes at `start` in `bytes`, or replaces it.
+    fn replace_invalid_sequence(
+        &mut self,
+        bytes: &[u8],
+        start: usize,
+        len: usize,
+        output: &mut Vec<u8>,
+    ) -> Result<()> {
+        if !self.lossy {
+            let snippet_start = start.saturating_sub(UTF8_SNIPPET_CONTEXT);
+            let snippet_end = (start + len + UTF8_SNIPPET_CONTEXT).min(bytes.len());
+            return Err(DocumentFormatError::InvalidUtf8 {
+                payload_type: self.payload_type,
+                offset: self.offset,
+                snippet: bytes[snippet_start..snippet_end].to_vec(),
+            });
+        }
+
+        output.extend_from_slice(char::REPLACEMENT_CHARACTER.encode_utf8(&mut [0; 4]).as_bytes());
+        self.offset += len as u64;
+        self.replacements += 1;
+        Ok(())
+    }
 }
 
 /// Reads CSV from input and write an obkv batch to writer.
@@ -211,3 +324,48 @@ where
     let visitor = SeqVisitor(f, PhantomData);
     deserializer.deserialize_seq(visitor)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_chunks(chunks: &[&[u8]], lossy: bool) -> Result<(Vec<u8>, u64)> {
+        let mut decoder = Utf8Decoder::new(PayloadType::Json, lossy);
+        let mut output = Vec::new();
+        for chunk in chunks {
+            decoder.decode(chunk, &mut output)?;
+        }
+        let replacements = decoder.finish(&mut output)?;
+        Ok((output, replacements))
+    }
+
+    #[test]
+    fn utf8_sequence_cut_between_chunks() {
+        // `é` is encoded as 0xC3 0xA9
+        let (output, replacements) = decode_chunks(&[b"caf\xc3", b"\xa9!"], false).unwrap();
+        assert_eq!(output, "café!".as_bytes());
+        assert_eq!(replacements, 0);
+    }
+
+    #[test]
+    fn invalid_utf8_offset() {
+        let error = decode_chunks(&[b"abc", b"de\xfff"], false).unwrap_err();
+        let DocumentFormatError::InvalidUtf8 { offset, snippet, .. } = error else {
+            panic!("unexpected error: {error}");
+        };
+        assert_eq!(offset, 5);
+        assert_eq!(snippet, b"de\xfff");
+
+        // a payload ending in the middle of a sequence is invalid too
+        let error = decode_chunks(&[b"caf\xc3"], false).unwrap_err();
+        assert!(matches!(error, DocumentFormatError::InvalidUtf8 { offset: 3, .. }), "{error}");
+    }
+
+    #[test]
+    fn lossy_utf8() {
+        let (output, replacements) =
+            decode_chunks(&[b"\xffab", b"c\xc3", b"\xa9\xc3"], true).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "\u{FFFD}abcé\u{FFFD}");
+        assert_eq!(replacements, 2);
+    }
+}
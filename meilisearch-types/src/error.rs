@@ -20,6 +20,8 @@ pub struct ResponseError {
     error_type: String,
     #[serde(rename = "link")]
     error_link: String,
+    #[serde(skip)]
+    retry_after: Option<u64>,
 }
 
 impl ResponseError {
@@ -33,6 +35,7 @@ impl ResponseError {
             error_code: code.name(),
             error_type: code.type_(),
             error_link: code.url(),
+            retry_after: None,
         }
     }
 }
@@ -50,7 +53,8 @@ where
     T: std::error::Error + ErrorCode,
 {
     fn from(other: T) -> Self {
-        Self::from_msg(other.to_string(), other.error_code())
+        let retry_after = other.retry_after();
+        Self { retry_after, ..Self::from_msg(other.to_string(), other.error_code()) }
     }
 }
 
@@ -60,7 +64,9 @@ impl aweb::error::ResponseError for ResponseError {
         let mut builder = HttpResponseBuilder::new(self.status_code());
         builder.content_type("application/json");
 
-        if self.code == StatusCode::SERVICE_UNAVAILABLE {
+        if let Some(retry_after) = self.retry_after {
+            builder.insert_header((header::RETRY_AFTER, retry_after.to_string()));
+        } else if self.code == StatusCode::SERVICE_UNAVAILABLE {
             builder.insert_header((header::RETRY_AFTER, "10"));
         }
 
@@ -94,6 +100,11 @@ pub trait ErrorCode {
     fn error_type(&self) -> String {
         self.error_code().type_()
     }
+
+    /// returns the number of seconds after which the request can be retried, if known
+    fn retry_after(&self) -> Option<u64> {
+        None
+    }
 }
 
 #[allow(clippy::enum_variant_names)]
@@ -187,6 +198,7 @@ ApiKeyAlreadyExists                   , InvalidRequest       , CONFLICT ;
 ApiKeyNotFound                        , InvalidRequest       , NOT_FOUND ;
 BadParameter                          , InvalidRequest       , BAD_REQUEST;
 BadRequest                            , InvalidRequest       , BAD_REQUEST;
+CrashedDuringProcessing               , Internal             , INTERNAL_SERVER_ERROR;
 DatabaseSizeLimitReached              , Internal             , INTERNAL_SERVER_ERROR;
 DocumentNotFound                      , InvalidRequest       , NOT_FOUND;
 DumpAlreadyProcessing                 , InvalidRequest       , CONFLICT;
@@ -195,7 +207,9 @@ DumpProcessFailed                     , Internal             , INTERNAL_SERVER_E
 DuplicateIndexFound                   , InvalidRequest       , BAD_REQUEST;
 ImmutableApiKeyActions                , InvalidRequest       , BAD_REQUEST;
 ImmutableApiKeyCreatedAt              , InvalidRequest       , BAD_REQUEST;
+ImmutableApiKeyDefaultFilters         , InvalidRequest       , BAD_REQUEST;
 ImmutableApiKeyExpiresAt              , InvalidRequest       , BAD_REQUEST;
+ImmutableApiKeyIndexGroups            , InvalidRequest       , BAD_REQUEST;
 ImmutableApiKeyIndexes                , InvalidRequest       , BAD_REQUEST;
 ImmutableApiKeyKey                    , InvalidRequest       , BAD_REQUEST;
 ImmutableApiKeyUid                    , InvalidRequest       , BAD_REQUEST;
@@ -205,6 +219,7 @@ ImmutableIndexUid                     , InvalidRequest       , BAD_REQUEST;
 ImmutableIndexUpdatedAt               , InvalidRequest       , BAD_REQUEST;
 IndexAlreadyExists                    , InvalidRequest       , CONFLICT ;
 IndexCreationFailed                   , Internal             , INTERNAL_SERVER_ERROR;
+IndexGroupNotFound                    , InvalidRequest       , NOT_FOUND;
 IndexNotFound                         , InvalidRequest       , NOT_FOUND;
 IndexPrimaryKeyAlreadyExists          , InvalidRequest       , BAD_REQUEST ;
 IndexPrimaryKeyMultipleCandidatesFound, InvalidRequest       , BAD_REQUEST;
@@ -212,30 +227,48 @@ IndexPrimaryKeyNoCandidateFound       , InvalidRequest       , BAD_REQUEST ;
 Internal                              , Internal             , INTERNAL_SERVER_ERROR ;
 InvalidApiKey                         , Auth                 , FORBIDDEN ;
 InvalidApiKeyActions                  , InvalidRequest       , BAD_REQUEST ;
+InvalidApiKeyDefaultFilters           , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyDescription              , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyExpiresAt                , InvalidRequest       , BAD_REQUEST ;
+InvalidApiKeyIndexGroups              , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyIndexes                  , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyLimit                    , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyName                     , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyOffset                   , InvalidRequest       , BAD_REQUEST ;
+InvalidApiKeyOnConflict               , InvalidRequest       , BAD_REQUEST ;
 InvalidApiKeyUid                      , InvalidRequest       , BAD_REQUEST ;
 InvalidContentType                    , InvalidRequest       , UNSUPPORTED_MEDIA_TYPE ;
 InvalidDocumentCsvDelimiter           , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentFields                 , InvalidRequest       , BAD_REQUEST ;
 MissingDocumentFilter                 , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentFilter                 , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentFormat                 , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentGeoField               , InvalidRequest       , BAD_REQUEST ;
 InvalidVectorDimensions               , InvalidRequest       , BAD_REQUEST ;
 InvalidVectorsType                    , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentId                     , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentLimit                  , InvalidRequest       , BAD_REQUEST ;
+InvalidDocumentLossyUtf8              , InvalidRequest       , BAD_REQUEST ;
 InvalidDocumentOffset                 , InvalidRequest       , BAD_REQUEST ;
 InvalidEmbedder                       , InvalidRequest       , BAD_REQUEST ;
 InvalidHybridQuery                    , InvalidRequest       , BAD_REQUEST ;
+InvalidIndexGroupIndexes              , InvalidRequest       , BAD_REQUEST ;
+InvalidIndexGroupName                 , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexLimit                     , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexOffset                    , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexPrimaryKey                , InvalidRequest       , BAD_REQUEST ;
 InvalidIndexUid                       , InvalidRequest       , BAD_REQUEST ;
+InvalidIndexWarmupPopulateCache       , InvalidRequest       , BAD_REQUEST ;
+InvalidIndexWarmupPrefixes            , InvalidRequest       , BAD_REQUEST ;
+InvalidIntegrityCheckRepair           , InvalidRequest       , BAD_REQUEST ;
+InvalidMultiSearchContinueOnError     , InvalidRequest       , BAD_REQUEST ;
+InvalidMultiSearchFacetsByIndex       , InvalidRequest       , BAD_REQUEST ;
+InvalidMultiSearchFederated           , InvalidRequest       , BAD_REQUEST ;
+InvalidMultiSearchFederationOptions   , InvalidRequest       , BAD_REQUEST ;
+InvalidMultiSearchMergeFacets         , InvalidRequest       , BAD_REQUEST ;
+InvalidMultiSearchQueryFacets         , InvalidRequest       , BAD_REQUEST ;
+InvalidMultiSearchQueryPagination     , InvalidRequest       , BAD_REQUEST ;
+InvalidMultiSearchWeight              , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchAttributesToSearchOn     , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchAttributesToCrop         , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchAttributesToHighlight    , InvalidRequest       , BAD_REQUEST ;
@@ -243,24 +276,57 @@ InvalidSearchAttributesToRetrieve     , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchCropLength               , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchCropMarker               , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchFacets                   , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchFacetDistributionMode    , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchFacetApproximationThreshold, InvalidRequest     , BAD_REQUEST ;
 InvalidSearchSemanticRatio            , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchRankingScoreThreshold    , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchRankingRules             , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchFacetName           , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchFilter                   , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchDebug                    , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchDistinct                 , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchExplain                  , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchStreaming                , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchHighlightPostTag         , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchHighlightPreTag          , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchHighlightTypoPostTag     , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchHighlightTypoPreTag      , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchHitsPerPage              , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchLimit                    , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchMatchingStrategy         , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchOffset                   , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchPage                     , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchPreset                   , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchPreviewSettings          , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchQ                        , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchSynonyms                 , InvalidRequest       , BAD_REQUEST ;
+InvalidRelevanceCheckQueries          , InvalidRequest       , BAD_REQUEST ;
+InvalidRelatedTermsWord               , InvalidRequest       , BAD_REQUEST ;
+InvalidRelatedTermsLimit              , InvalidRequest       , BAD_REQUEST ;
+InvalidRelatedTermsAttributes         , InvalidRequest       , BAD_REQUEST ;
+InvalidWordFrequencyPrefix            , InvalidRequest       , BAD_REQUEST ;
+InvalidWordFrequencyLimit             , InvalidRequest       , BAD_REQUEST ;
+InvalidWebhookUrl                     , InvalidRequest       , BAD_REQUEST ;
+InvalidWebhookAuthorizationHeader     , InvalidRequest       , BAD_REQUEST ;
+InvalidFilterAssistFilter             , InvalidRequest       , BAD_REQUEST ;
+InvalidFilterAssistCursor             , InvalidRequest       , BAD_REQUEST ;
+InvalidFilterAssistLimit              , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchQuery               , InvalidRequest       , BAD_REQUEST ;
 InvalidFacetSearchName                , InvalidRequest       , BAD_REQUEST ;
+FacetSearchQueryTooLong               , InvalidRequest       , BAD_REQUEST ;
+FacetSearchQueryWithoutWords          , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchVector                   , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchShowMatchesPosition      , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchShowProcessingBreakdown  , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchShowQueryTokens          , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchShowSearchMetrics        , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchSearchAfter              , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchShowRankingBucketPath    , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchShowRankingScore         , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchShowRankingScoreDetails  , InvalidRequest       , BAD_REQUEST ;
 InvalidSearchSort                     , InvalidRequest       , BAD_REQUEST ;
+InvalidSearchTieBreak                 , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsCanonical              , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsDisplayedAttributes    , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsDistinctAttribute      , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsProximityPrecision     , InvalidRequest       , BAD_REQUEST ;
@@ -268,6 +334,17 @@ InvalidSettingsFaceting               , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsFilterableAttributes   , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsPagination             , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsSearchCutoffMs           , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsSearchDefaults           , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsMinNumericPrefixLength   , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsAllowUnderscoreFields  , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsLocalizedAttributes    , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsNumberNormalization    , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsPhraseAcrossFields     , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsExactAttributes        , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsDecompoundingLocales   , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsFacetTypes             , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsStemming               , InvalidRequest       , BAD_REQUEST ;
+InvalidSettingsPresets                , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsEmbedders              , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsRankingRules           , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsSearchableAttributes   , InvalidRequest       , BAD_REQUEST ;
@@ -278,7 +355,11 @@ InvalidSettingsSeparatorTokens        , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsDictionary             , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsSynonyms               , InvalidRequest       , BAD_REQUEST ;
 InvalidSettingsTypoTolerance          , InvalidRequest       , BAD_REQUEST ;
+InvalidSnapshot                       , InvalidRequest       , BAD_REQUEST ;
+InvalidSnapshotIndexUid               , InvalidRequest       , BAD_REQUEST ;
+InvalidSnapshotPath                   , InvalidRequest       , BAD_REQUEST ;
 InvalidState                          , Internal             , INTERNAL_SERVER_ERROR ;
+InvalidStatsGroup                     , InvalidRequest       , BAD_REQUEST ;
 InvalidStoreFile                      , Internal             , INTERNAL_SERVER_ERROR ;
 InvalidSwapDuplicateIndexFound        , InvalidRequest       , BAD_REQUEST ;
 InvalidSwapIndexes                    , InvalidRequest       , BAD_REQUEST ;
@@ -290,36 +371,62 @@ InvalidTaskBeforeFinishedAt           , InvalidRequest       , BAD_REQUEST ;
 InvalidTaskBeforeStartedAt            , InvalidRequest       , BAD_REQUEST ;
 InvalidTaskCanceledBy                 , InvalidRequest       , BAD_REQUEST ;
 InvalidTaskFrom                       , InvalidRequest       , BAD_REQUEST ;
+InvalidTaskIndexGroups                , InvalidRequest       , BAD_REQUEST ;
 InvalidTaskLimit                      , InvalidRequest       , BAD_REQUEST ;
 InvalidTaskStatuses                   , InvalidRequest       , BAD_REQUEST ;
 InvalidTaskTypes                      , InvalidRequest       , BAD_REQUEST ;
 InvalidTaskUids                       , InvalidRequest       , BAD_REQUEST  ;
+InvalidTaskWaitTimeout                , InvalidRequest       , BAD_REQUEST ;
+InvalidTimelineFrom                   , InvalidRequest       , BAD_REQUEST ;
+InvalidTimelineInterval               , InvalidRequest       , BAD_REQUEST ;
+InvalidTimelineTo                     , InvalidRequest       , BAD_REQUEST ;
 IoError                               , System               , UNPROCESSABLE_ENTITY;
 FeatureNotEnabled                     , InvalidRequest       , BAD_REQUEST ;
+LogStreamAlreadyExists                , InvalidRequest       , CONFLICT ;
+LogStreamNotFound                     , InvalidRequest       , NOT_FOUND ;
+InvalidLogFile                        , InvalidRequest       , BAD_REQUEST ;
 MalformedPayload                      , InvalidRequest       , BAD_REQUEST ;
 MaxFieldsLimitExceeded                , InvalidRequest       , BAD_REQUEST ;
 MissingApiKeyActions                  , InvalidRequest       , BAD_REQUEST ;
 MissingApiKeyExpiresAt                , InvalidRequest       , BAD_REQUEST ;
 MissingApiKeyIndexes                  , InvalidRequest       , BAD_REQUEST ;
+MissingApiKeyUid                      , InvalidRequest       , BAD_REQUEST ;
+MissingIndexGroupIndexes              , InvalidRequest       , BAD_REQUEST ;
 MissingAuthorizationHeader            , Auth                 , UNAUTHORIZED ;
 MissingContentType                    , InvalidRequest       , UNSUPPORTED_MEDIA_TYPE ;
 MissingDocumentId                     , InvalidRequest       , BAD_REQUEST ;
 MissingFacetSearchFacetName           , InvalidRequest       , BAD_REQUEST ;
+MissingFilterAssistFilter             , InvalidRequest       , BAD_REQUEST ;
 MissingIndexUid                       , InvalidRequest       , BAD_REQUEST ;
+MissingIndexWarmupPrefixes            , InvalidRequest       , BAD_REQUEST ;
 MissingMasterKey                      , Auth                 , UNAUTHORIZED ;
 MissingPayload                        , InvalidRequest       , BAD_REQUEST ;
+MissingRelatedTermsWord               , InvalidRequest       , BAD_REQUEST ;
 MissingSearchHybrid                   , InvalidRequest       , BAD_REQUEST ;
+MissingSnapshotPath                   , InvalidRequest       , BAD_REQUEST ;
 MissingSwapIndexes                    , InvalidRequest       , BAD_REQUEST ;
 MissingTaskFilters                    , InvalidRequest       , BAD_REQUEST ;
 NoSpaceLeftOnDevice                   , System               , UNPROCESSABLE_ENTITY;
+OutboundHostNotAllowed                , InvalidRequest       , FORBIDDEN ;
+PayloadChecksumMismatch               , InvalidRequest       , BAD_REQUEST ;
 PayloadTooLarge                       , InvalidRequest       , PAYLOAD_TOO_LARGE ;
+PresetNotFound                        , InvalidRequest       , NOT_FOUND ;
+ReadOnlyReplica                       , InvalidRequest       , FORBIDDEN ;
+ReplicationFailed                     , System               , BAD_GATEWAY ;
+ReplicationNotConfigured              , InvalidRequest       , BAD_REQUEST ;
 TooManySearchRequests                 , System               , SERVICE_UNAVAILABLE ;
+SettingsVersionMismatch               , InvalidRequest       , PRECONDITION_FAILED ;
+SnapshotNotFound                      , InvalidRequest       , NOT_FOUND ;
 TaskNotFound                          , InvalidRequest       , NOT_FOUND ;
+TaskQueueFull                         , System               , UNPROCESSABLE_ENTITY ;
+TooManyEnqueuedTasks                  , System               , TOO_MANY_REQUESTS ;
 TooManyOpenFiles                      , System               , UNPROCESSABLE_ENTITY ;
 TooManyVectors                        , InvalidRequest       , BAD_REQUEST ;
 UnretrievableDocument                 , Internal             , BAD_REQUEST ;
 UnretrievableErrorCode                , InvalidRequest       , BAD_REQUEST ;
 UnsupportedMediaType                  , InvalidRequest       , UNSUPPORTED_MEDIA_TYPE ;
+WebhookDeliveryFailed                 , System               , BAD_GATEWAY ;
+WebhookNotFound                       , InvalidRequest       , NOT_FOUND ;
 
 // Experimental features
 VectorEmbeddingError                  , InvalidRequest       , BAD_REQUEST
@@ -338,6 +445,8 @@ impl ErrorCode for milli::Error {
         match self {
             Error::InternalError(_) => Code::Internal,
             Error::IoError(e) => e.error_code(),
+            // the client of a canceled search is gone, this error is never sent
+            Error::SearchCancelled => Code::Internal,
             Error::UserError(ref error) => {
                 match error {
                     // TODO: wait for spec for new error codes.
@@ -355,7 +464,8 @@ impl ErrorCode for milli::Error {
                     UserError::InvalidDocumentId { .. } | UserError::TooManyDocumentIds { .. } => {
                         Code::InvalidDocumentId
                     }
-                    UserError::MissingDocumentField(_) => Code::InvalidDocumentFields,
+                    UserError::MissingDocumentField(_)
+                    | UserError::ReservedDocumentField { .. } => Code::InvalidDocumentFields,
                     UserError::InvalidFieldForSource { .. }
                     | UserError::MissingFieldForSource { .. }
                     | UserError::InvalidOpenAiModel { .. }
@@ -371,15 +481,24 @@ impl ErrorCode for milli::Error {
                         Code::IndexPrimaryKeyMultipleCandidatesFound
                     }
                     UserError::PrimaryKeyCannotBeChanged(_) => Code::IndexPrimaryKeyAlreadyExists,
+                    UserError::PrimaryKeyMissingInDocuments { .. }
+                    | UserError::PrimaryKeyValueNotUnique { .. } => Code::InvalidIndexPrimaryKey,
                     UserError::SortRankingRuleMissing => Code::InvalidSearchSort,
                     UserError::InvalidFacetsDistribution { .. } => Code::InvalidSearchFacets,
                     UserError::InvalidSortableAttribute { .. } => Code::InvalidSearchSort,
+                    UserError::InvalidDistinctAttribute { .. } => Code::InvalidSearchDistinct,
                     UserError::InvalidSearchableAttribute { .. } => {
                         Code::InvalidSearchAttributesToSearchOn
                     }
+                    UserError::InvalidSearchAfter { .. } => Code::InvalidSearchSearchAfter,
+                    UserError::InvalidSearchDefaults(_) => Code::InvalidSettingsSearchDefaults,
                     UserError::InvalidFacetSearchFacetName { .. } => {
                         Code::InvalidFacetSearchFacetName
                     }
+                    UserError::FacetSearchQueryTooLong { .. } => Code::FacetSearchQueryTooLong,
+                    UserError::FacetSearchQueryWithoutWords(_) => {
+                        Code::FacetSearchQueryWithoutWords
+                    }
                     UserError::CriterionError(_) => Code::InvalidSettingsRankingRules,
                     UserError::InvalidGeoField { .. } => Code::InvalidDocumentGeoField,
                     UserError::InvalidVectorDimensions { .. } => Code::InvalidVectorDimensions,
@@ -391,6 +510,11 @@ impl ErrorCode for milli::Error {
                         Code::InvalidSettingsTypoTolerance
                     }
                     UserError::InvalidEmbedder(_) => Code::InvalidEmbedder,
+                    UserError::VectorEmbeddingError(error)
+                        if error.is_outbound_host_not_allowed() =>
+                    {
+                        Code::OutboundHostNotAllowed
+                    }
                     UserError::VectorEmbeddingError(_) => Code::VectorEmbeddingError,
                 }
             }
@@ -403,7 +527,9 @@ impl ErrorCode for file_store::Error {
         match self {
             Self::IoError(e) => e.error_code(),
             Self::PersistError(e) => e.error_code(),
-            Self::CouldNotParseFileNameAsUtf8 | Self::UuidError(_) => Code::Internal,
+            Self::CouldNotParseFileNameAsUtf8 | Self::UuidError(_) | Self::Encryption(_) => {
+                Code::Internal
+            }
         }
     }
 }
@@ -450,6 +576,25 @@ impl fmt::Display for ParseOffsetDateTimeError {
     }
 }
 
+/// Deserialization when `deserr` cannot parse the default filters of an API key.
+#[derive(Debug)]
+pub enum InvalidDefaultFilterError {
+    IndexUidPattern(String, String),
+    Filter(String, String),
+}
+impl fmt::Display for InvalidDefaultFilterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IndexUidPattern(pattern, error) => {
+                write!(f, "`{pattern}` is not a valid index uid pattern: {error}")
+            }
+            Self::Filter(pattern, error) => {
+                write!(f, "The default filter of `{pattern}` is invalid: {error}")
+            }
+        }
+    }
+}
+
 /// Deserialization when `deserr` cannot parse a task date.
 #[derive(Debug)]
 pub struct InvalidTaskDateError(pub String);
@@ -488,6 +633,21 @@ impl fmt::Display for deserr_codes::InvalidSearchSemanticRatio {
     }
 }
 
+impl fmt::Display for deserr_codes::InvalidSearchRankingScoreThreshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the value of `rankingScoreThreshold` is invalid, expected a float between `0.0` and `1.0`."
+        )
+    }
+}
+
+impl fmt::Display for deserr_codes::InvalidMultiSearchWeight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the value of `weight` is invalid, expected a positive float (>= 0.0).")
+    }
+}
+
 #[macro_export]
 macro_rules! internal_error {
     ($target:ty : $($other:path), *) => {
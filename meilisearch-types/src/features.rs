@@ -7,10 +7,50 @@ pub struct RuntimeTogglableFeatures {
     pub metrics: bool,
     pub logs_route: bool,
     pub export_puffin_reports: bool,
+    pub debug_routes: bool,
+    pub relevance_check: bool,
+    pub word_frequency: bool,
+    pub document_tasks: bool,
+    pub recent_searches: bool,
+    pub ranking_rules_override: bool,
+}
+
+impl RuntimeTogglableFeatures {
+    /// The names of the enabled features, as they are named in the API.
+    pub fn enabled_features(&self) -> Vec<&'static str> {
+        let Self {
+            vector_store,
+            metrics,
+            logs_route,
+            export_puffin_reports,
+            debug_routes,
+            relevance_check,
+            word_frequency,
+            document_tasks,
+            recent_searches,
+            ranking_rules_override,
+        } = *self;
+        [
+            ("vectorStore", vector_store),
+            ("metrics", metrics),
+            ("logsRoute", logs_route),
+            ("exportPuffinReports", export_puffin_reports),
+            ("debugRoutes", debug_routes),
+            ("relevanceCheck", relevance_check),
+            ("wordFrequency", word_frequency),
+            ("documentTasks", document_tasks),
+            ("recentSearches", recent_searches),
+            ("rankingRulesOverride", ranking_rules_override),
+        ]
+        .into_iter()
+        .filter_map(|(name, enabled)| enabled.then_some(name))
+        .collect()
+    }
 }
 
 #[derive(Default, Debug, Clone, Copy)]
 pub struct InstanceTogglableFeatures {
     pub metrics: bool,
     pub logs_route: bool,
+    pub ranking_rules_override: bool,
 }
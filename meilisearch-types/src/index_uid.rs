@@ -6,6 +6,9 @@ use deserr::Deserr;
 
 use crate::error::{Code, ErrorCode};
 
+/// The maximum length of an index uid, in bytes.
+pub const MAX_INDEX_UID_LENGTH: usize = 400;
+
 /// An index uid is composed of only ascii alphanumeric characters, - and _, between 1 and 400
 /// bytes long
 #[derive(Debug, Clone, PartialEq, Eq, Deserr)]
@@ -45,13 +48,74 @@ impl TryFrom<String> for IndexUid {
     type Error = IndexUidFormatError;
 
     fn try_from(uid: String) -> Result<Self, Self::Error> {
-        if !uid.chars().all(|x| x.is_ascii_alphanumeric() || x == '-' || x == '_')
-            || uid.is_empty()
-            || uid.len() > 400
-        {
-            Err(IndexUidFormatError { invalid_uid: uid })
-        } else {
-            Ok(IndexUid(uid))
+        match validate_index_uid(&uid) {
+            Ok(()) => Ok(IndexUid(uid)),
+            Err(reason) => Err(IndexUidFormatError { invalid_uid: uid, reason }),
+        }
+    }
+}
+
+fn is_valid_index_uid_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+/// Checks that an index uid is between 1 and [`MAX_INDEX_UID_LENGTH`] bytes long and only
+/// contains ascii alphanumeric characters, hyphens and underscores.
+///
+/// Every place accepting an index uid, or an index uid pattern, must go through this function
+/// so that they all accept the same uids and report them the same way.
+pub fn validate_index_uid(uid: &str) -> Result<(), InvalidIndexUidReason> {
+    if uid.is_empty() {
+        return Err(InvalidIndexUidReason::Empty);
+    }
+    if let Some((position, character)) =
+        uid.chars().enumerate().find(|(_, c)| !is_valid_index_uid_char(*c))
+    {
+        return Err(InvalidIndexUidReason::InvalidCharacter { character, position });
+    }
+    if uid.len() > MAX_INDEX_UID_LENGTH {
+        return Err(InvalidIndexUidReason::TooLong { length: uid.len() });
+    }
+    Ok(())
+}
+
+/// Returns a valid index uid close to the given one, by replacing the invalid characters
+/// with underscores and truncating it to [`MAX_INDEX_UID_LENGTH`] bytes.
+///
+/// Returns `None` when nothing is left of the uid, i.e. when it is empty.
+pub fn sanitize_index_uid(uid: &str) -> Option<String> {
+    let sanitized: String = uid
+        .chars()
+        .map(|c| if is_valid_index_uid_char(c) { c } else { '_' })
+        .take(MAX_INDEX_UID_LENGTH)
+        .collect();
+    (!sanitized.is_empty()).then_some(sanitized)
+}
+
+/// The first reason why a string is not a valid index uid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidIndexUidReason {
+    Empty,
+    /// The position is counted in characters, starting at 0.
+    InvalidCharacter {
+        character: char,
+        position: usize,
+    },
+    TooLong {
+        length: usize,
+    },
+}
+
+impl fmt::Display for InvalidIndexUidReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidIndexUidReason::Empty => write!(f, "it is empty"),
+            InvalidIndexUidReason::InvalidCharacter { character, position } => {
+                write!(f, "the character `{character}` at position {position} is not allowed")
+            }
+            InvalidIndexUidReason::TooLong { length } => {
+                write!(f, "it is {length} bytes long, but at most {MAX_INDEX_UID_LENGTH} bytes are allowed")
+            }
         }
     }
 }
@@ -73,17 +137,22 @@ impl From<IndexUid> for String {
 #[derive(Debug)]
 pub struct IndexUidFormatError {
     pub invalid_uid: String,
+    pub reason: InvalidIndexUidReason,
 }
 
 impl fmt::Display for IndexUidFormatError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "`{}` is not a valid index uid. Index uid can be an \
+            "`{}` is not a valid index uid: {}. Index uid can be an \
             integer or a string containing only alphanumeric \
-            characters, hyphens (-) and underscores (_).",
-            self.invalid_uid,
-        )
+            characters, hyphens (-) and underscores (_), of at most 400 bytes.",
+            self.invalid_uid, self.reason,
+        )?;
+        match sanitize_index_uid(&self.invalid_uid) {
+            Some(sanitized) => write!(f, " Try `{sanitized}` instead."),
+            None => Ok(()),
+        }
     }
 }
 
@@ -8,11 +8,11 @@ use deserr::Deserr;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{Code, ErrorCode};
-use crate::index_uid::{IndexUid, IndexUidFormatError};
+use crate::index_uid::{sanitize_index_uid, validate_index_uid, IndexUid, InvalidIndexUidReason};
 
 /// An index uid pattern is composed of only ascii alphanumeric characters, - and _, between 1 and 400
 /// bytes long and optionally ending with a *.
-#[derive(Serialize, Deserialize, Deserr, Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Deserr, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[deserr(try_from(&String) = FromStr::from_str -> IndexUidPatternFormatError)]
 pub struct IndexUidPattern(String);
 
@@ -69,16 +69,14 @@ impl TryFrom<String> for IndexUidPattern {
 
     fn try_from(uid: String) -> Result<Self, Self::Error> {
         let result = match uid.strip_suffix('*') {
-            Some("") => Ok(IndexUidPattern(uid)),
-            Some(prefix) => IndexUid::from_str(prefix).map(|_| IndexUidPattern(uid)),
-            None => IndexUid::try_from(uid).map(IndexUid::into_inner).map(IndexUidPattern),
+            Some("") => Ok(()),
+            Some(prefix) => validate_index_uid(prefix),
+            None => validate_index_uid(&uid),
         };
 
         match result {
-            Ok(index_uid_pattern) => Ok(index_uid_pattern),
-            Err(IndexUidFormatError { invalid_uid }) => {
-                Err(IndexUidPatternFormatError { invalid_uid })
-            }
+            Ok(()) => Ok(IndexUidPattern(uid)),
+            Err(reason) => Err(IndexUidPatternFormatError { invalid_uid: uid, reason }),
         }
     }
 }
@@ -100,18 +98,27 @@ impl From<IndexUidPattern> for String {
 #[derive(Debug)]
 pub struct IndexUidPatternFormatError {
     pub invalid_uid: String,
+    pub reason: InvalidIndexUidReason,
 }
 
 impl fmt::Display for IndexUidPatternFormatError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "`{}` is not a valid index uid pattern. Index uid patterns \
+            "`{}` is not a valid index uid pattern: {}. Index uid patterns \
             can be an integer or a string containing only alphanumeric \
-            characters, hyphens (-), underscores (_), and \
+            characters, hyphens (-), underscores (_), of at most 400 bytes, and \
             optionally end with a star (*).",
-            self.invalid_uid,
-        )
+            self.invalid_uid, self.reason,
+        )?;
+        let sanitized = match self.invalid_uid.strip_suffix('*') {
+            Some(prefix) => sanitize_index_uid(prefix).map(|prefix| format!("{prefix}*")),
+            None => sanitize_index_uid(&self.invalid_uid),
+        };
+        match sanitized {
+            Some(sanitized) => write!(f, " Try `{sanitized}` instead."),
+            None => Ok(()),
+        }
     }
 }
 
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::convert::Infallible;
 use std::hash::Hash;
 use std::str::FromStr;
@@ -13,7 +14,7 @@ use uuid::Uuid;
 
 use crate::deserr::{immutable_field_error, DeserrError, DeserrJsonError};
 use crate::error::deserr_codes::*;
-use crate::error::{Code, ErrorCode, ParseOffsetDateTimeError};
+use crate::error::{Code, ErrorCode, InvalidDefaultFilterError, ParseOffsetDateTimeError};
 use crate::index_uid_pattern::{IndexUidPattern, IndexUidPatternFormatError};
 
 pub type KeyId = Uuid;
@@ -45,13 +46,26 @@ pub struct CreateApiKey {
     pub actions: Vec<Action>,
     #[deserr(error = DeserrJsonError<InvalidApiKeyIndexes>, missing_field_error = DeserrJsonError::missing_api_key_indexes)]
     pub indexes: Vec<IndexUidPattern>,
+    #[deserr(default, error = DeserrJsonError<InvalidApiKeyIndexGroups>)]
+    pub index_groups: Vec<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidApiKeyDefaultFilters>, try_from(BTreeMap<String, String>) = parse_default_filters -> InvalidDefaultFilterError)]
+    pub default_filters: BTreeMap<IndexUidPattern, String>,
     #[deserr(error = DeserrJsonError<InvalidApiKeyExpiresAt>, try_from(Option<String>) = parse_expiration_date -> ParseOffsetDateTimeError, missing_field_error = DeserrJsonError::missing_api_key_expires_at)]
     pub expires_at: Option<OffsetDateTime>,
 }
 
 impl CreateApiKey {
     pub fn to_key(self) -> Key {
-        let CreateApiKey { description, name, uid, actions, indexes, expires_at } = self;
+        let CreateApiKey {
+            description,
+            name,
+            uid,
+            actions,
+            indexes,
+            index_groups,
+            default_filters,
+            expires_at,
+        } = self;
         let now = OffsetDateTime::now_utc();
         Key {
             description,
@@ -59,6 +73,8 @@ impl CreateApiKey {
             uid,
             actions,
             indexes,
+            index_groups,
+            default_filters,
             expires_at,
             created_at: now,
             updated_at: now,
@@ -66,6 +82,65 @@ impl CreateApiKey {
     }
 }
 
+/// An API key of a bulk import, in the format of the keys listed by `GET /keys`.
+///
+/// The `uid` is mandatory, so that the value of the key derived from the master key is
+/// the same on every instance. The `key`, `createdAt`, and `updatedAt` fields are ignored.
+#[derive(Debug, Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct ImportApiKey {
+    #[deserr(default, error = DeserrJsonError<InvalidApiKeyDescription>)]
+    pub description: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidApiKeyName>)]
+    pub name: Option<String>,
+    #[deserr(error = DeserrJsonError<InvalidApiKeyUid>, try_from(&String) = Uuid::from_str -> uuid::Error, missing_field_error = DeserrJsonError::missing_api_key_uid)]
+    pub uid: KeyId,
+    #[deserr(error = DeserrJsonError<InvalidApiKeyActions>, missing_field_error = DeserrJsonError::missing_api_key_actions)]
+    pub actions: Vec<Action>,
+    #[deserr(error = DeserrJsonError<InvalidApiKeyIndexes>, missing_field_error = DeserrJsonError::missing_api_key_indexes)]
+    pub indexes: Vec<IndexUidPattern>,
+    #[deserr(default, error = DeserrJsonError<InvalidApiKeyIndexGroups>)]
+    pub index_groups: Vec<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidApiKeyDefaultFilters>, try_from(BTreeMap<String, String>) = parse_default_filters -> InvalidDefaultFilterError)]
+    pub default_filters: BTreeMap<IndexUidPattern, String>,
+    #[deserr(error = DeserrJsonError<InvalidApiKeyExpiresAt>, try_from(Option<String>) = parse_expiration_date -> ParseOffsetDateTimeError, missing_field_error = DeserrJsonError::missing_api_key_expires_at)]
+    pub expires_at: Option<OffsetDateTime>,
+    #[deserr(default)]
+    pub key: Option<String>,
+    #[deserr(default)]
+    pub created_at: Option<String>,
+    #[deserr(default)]
+    pub updated_at: Option<String>,
+}
+
+impl From<ImportApiKey> for CreateApiKey {
+    fn from(import: ImportApiKey) -> Self {
+        let ImportApiKey {
+            description,
+            name,
+            uid,
+            actions,
+            indexes,
+            index_groups,
+            default_filters,
+            expires_at,
+            key: _,
+            created_at: _,
+            updated_at: _,
+        } = import;
+        CreateApiKey {
+            description,
+            name,
+            uid,
+            actions,
+            indexes,
+            index_groups,
+            default_filters,
+            expires_at,
+        }
+    }
+}
+
 fn deny_immutable_fields_api_key(
     field: &str,
     accepted: &[&str],
@@ -75,6 +150,10 @@ fn deny_immutable_fields_api_key(
         "uid" => immutable_field_error(field, accepted, Code::ImmutableApiKeyUid),
         "actions" => immutable_field_error(field, accepted, Code::ImmutableApiKeyActions),
         "indexes" => immutable_field_error(field, accepted, Code::ImmutableApiKeyIndexes),
+        "indexGroups" => immutable_field_error(field, accepted, Code::ImmutableApiKeyIndexGroups),
+        "defaultFilters" => {
+            immutable_field_error(field, accepted, Code::ImmutableApiKeyDefaultFilters)
+        }
         "expiresAt" => immutable_field_error(field, accepted, Code::ImmutableApiKeyExpiresAt),
         "createdAt" => immutable_field_error(field, accepted, Code::ImmutableApiKeyCreatedAt),
         "updatedAt" => immutable_field_error(field, accepted, Code::ImmutableApiKeyUpdatedAt),
@@ -104,6 +183,13 @@ pub struct Key {
     pub uid: KeyId,
     pub actions: Vec<Action>,
     pub indexes: Vec<IndexUidPattern>,
+    /// The index groups whose patterns are added to the `indexes`, resolved when the key is used.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub index_groups: Vec<String>,
+    /// The filters AND-ed to the filter of every search and documents fetch made with the key,
+    /// by index uid pattern.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub default_filters: BTreeMap<IndexUidPattern, String>,
     #[serde(with = "time::serde::rfc3339::option")]
     pub expires_at: Option<OffsetDateTime>,
     #[serde(with = "time::serde::rfc3339")]
@@ -122,6 +208,8 @@ impl Key {
             uid,
             actions: vec![Action::All],
             indexes: vec![IndexUidPattern::all()],
+            index_groups: Vec::new(),
+            default_filters: BTreeMap::new(),
             expires_at: None,
             created_at: now,
             updated_at: now,
@@ -137,6 +225,8 @@ impl Key {
             uid,
             actions: vec![Action::Search],
             indexes: vec![IndexUidPattern::all()],
+            index_groups: Vec::new(),
+            default_filters: BTreeMap::new(),
             expires_at: None,
             created_at: now,
             updated_at: now,
@@ -144,6 +234,31 @@ impl Key {
     }
 }
 
+/// Checks the syntax of the default filters, the existence of the filtered attributes can't be
+/// checked since the indexes can change after the creation of the key.
+fn parse_default_filters(
+    filters: BTreeMap<String, String>,
+) -> std::result::Result<BTreeMap<IndexUidPattern, String>, InvalidDefaultFilterError> {
+    filters
+        .into_iter()
+        .map(|(pattern, filter)| {
+            let pattern = IndexUidPattern::from_str(&pattern).map_err(|error| {
+                InvalidDefaultFilterError::IndexUidPattern(pattern.clone(), error.to_string())
+            })?;
+            match milli::FilterCondition::parse(&filter) {
+                Ok(Some(_)) => Ok((pattern, filter)),
+                Ok(None) => Err(InvalidDefaultFilterError::Filter(
+                    pattern.to_string(),
+                    String::from("the filter is empty."),
+                )),
+                Err(error) => {
+                    Err(InvalidDefaultFilterError::Filter(pattern.to_string(), error.to_string()))
+                }
+            }
+        })
+        .collect()
+}
+
 fn parse_expiration_date(
     string: Option<String>,
 ) -> std::result::Result<Option<OffsetDateTime>, ParseOffsetDateTimeError> {
@@ -284,6 +399,52 @@ pub enum Action {
     #[serde(rename = "experimental.update")]
     #[deserr(rename = "experimental.update")]
     ExperimentalFeaturesUpdate,
+    #[serde(rename = "presets.*")]
+    #[deserr(rename = "presets.*")]
+    PresetsAll,
+    #[serde(rename = "presets.get")]
+    #[deserr(rename = "presets.get")]
+    PresetsGet,
+    #[serde(rename = "presets.update")]
+    #[deserr(rename = "presets.update")]
+    PresetsUpdate,
+    #[serde(rename = "indexGroups.get")]
+    #[deserr(rename = "indexGroups.get")]
+    IndexGroupsGet,
+    #[serde(rename = "indexGroups.update")]
+    #[deserr(rename = "indexGroups.update")]
+    IndexGroupsUpdate,
+    #[serde(rename = "relevance.check")]
+    #[deserr(rename = "relevance.check")]
+    RelevanceCheck,
+    #[serde(rename = "search.debug")]
+    #[deserr(rename = "search.debug")]
+    SearchDebug,
+    #[serde(rename = "wordFrequency.get")]
+    #[deserr(rename = "wordFrequency.get")]
+    WordFrequencyGet,
+    #[serde(rename = "logs.*")]
+    #[deserr(rename = "logs.*")]
+    LogsAll,
+    #[serde(rename = "logs.get")]
+    #[deserr(rename = "logs.get")]
+    LogsGet,
+    #[serde(rename = "logs.create")]
+    #[deserr(rename = "logs.create")]
+    LogsCreate,
+    #[serde(rename = "logs.delete")]
+    #[deserr(rename = "logs.delete")]
+    LogsDelete,
+    #[serde(rename = "recentSearches.get")]
+    #[deserr(rename = "recentSearches.get")]
+    RecentSearchesGet,
+    #[serde(rename = "webhooks.update")]
+    #[deserr(rename = "webhooks.update")]
+    WebhooksUpdate,
+    /// Downloads the whole database, it is not part of `snapshots.*`.
+    #[serde(rename = "snapshots.download")]
+    #[deserr(rename = "snapshots.download")]
+    SnapshotsDownload,
 }
 
 impl Action {
@@ -323,6 +484,21 @@ impl Action {
             KEYS_DELETE => Some(Self::KeysDelete),
             EXPERIMENTAL_FEATURES_GET => Some(Self::ExperimentalFeaturesGet),
             EXPERIMENTAL_FEATURES_UPDATE => Some(Self::ExperimentalFeaturesUpdate),
+            PRESETS_ALL => Some(Self::PresetsAll),
+            PRESETS_GET => Some(Self::PresetsGet),
+            PRESETS_UPDATE => Some(Self::PresetsUpdate),
+            INDEX_GROUPS_GET => Some(Self::IndexGroupsGet),
+            INDEX_GROUPS_UPDATE => Some(Self::IndexGroupsUpdate),
+            RELEVANCE_CHECK => Some(Self::RelevanceCheck),
+            SEARCH_DEBUG => Some(Self::SearchDebug),
+            WORD_FREQUENCY_GET => Some(Self::WordFrequencyGet),
+            LOGS_ALL => Some(Self::LogsAll),
+            LOGS_GET => Some(Self::LogsGet),
+            LOGS_CREATE => Some(Self::LogsCreate),
+            LOGS_DELETE => Some(Self::LogsDelete),
+            RECENT_SEARCHES_GET => Some(Self::RecentSearchesGet),
+            WEBHOOKS_UPDATE => Some(Self::WebhooksUpdate),
+            SNAPSHOTS_DOWNLOAD => Some(Self::SnapshotsDownload),
             _otherwise => None,
         }
     }
@@ -368,4 +544,19 @@ pub mod actions {
     pub const KEYS_DELETE: u8 = KeysDelete.repr();
     pub const EXPERIMENTAL_FEATURES_GET: u8 = ExperimentalFeaturesGet.repr();
     pub const EXPERIMENTAL_FEATURES_UPDATE: u8 = ExperimentalFeaturesUpdate.repr();
+    pub const PRESETS_ALL: u8 = PresetsAll.repr();
+    pub const PRESETS_GET: u8 = PresetsGet.repr();
+    pub const PRESETS_UPDATE: u8 = PresetsUpdate.repr();
+    pub const INDEX_GROUPS_GET: u8 = IndexGroupsGet.repr();
+    pub const INDEX_GROUPS_UPDATE: u8 = IndexGroupsUpdate.repr();
+    pub const RELEVANCE_CHECK: u8 = RelevanceCheck.repr();
+    pub const SEARCH_DEBUG: u8 = SearchDebug.repr();
+    pub const WORD_FREQUENCY_GET: u8 = WordFrequencyGet.repr();
+    pub const LOGS_ALL: u8 = LogsAll.repr();
+    pub const LOGS_GET: u8 = LogsGet.repr();
+    pub const LOGS_CREATE: u8 = LogsCreate.repr();
+    pub const LOGS_DELETE: u8 = LogsDelete.repr();
+    pub const RECENT_SEARCHES_GET: u8 = RecentSearchesGet.repr();
+    pub const WEBHOOKS_UPDATE: u8 = WebhooksUpdate.repr();
+    pub const SNAPSHOTS_DOWNLOAD: u8 = SnapshotsDownload.repr();
 }
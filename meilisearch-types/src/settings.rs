@@ -8,9 +8,14 @@ use std::str::FromStr;
 
 use deserr::{DeserializeError, Deserr, ErrorKind, MergeWithError, ValuePointerRef};
 use fst::IntoStreamer;
+use milli::collation::Locale;
+use milli::facet::FacetTypeHint;
 use milli::proximity::ProximityPrecision;
 use milli::update::Setting;
-use milli::{Criterion, CriterionError, Index, DEFAULT_VALUES_PER_FACET};
+use milli::{
+    Criterion, CriterionError, Index, LatLng, LocalizedAttributesRule, RandomSeed,
+    DEFAULT_VALUES_PER_FACET,
+};
 use serde::{Deserialize, Serialize, Serializer};
 
 use crate::deserr::DeserrJsonError;
@@ -118,6 +123,46 @@ pub struct PaginationSettings {
     pub max_total_hits: Setting<usize>,
 }
 
+fn validate_stemming_settings<E: DeserializeError>(
+    s: StemmingSettings,
+    location: ValuePointerRef,
+) -> Result<StemmingSettings, E> {
+    let name = |locale: &Locale| serde_json::json!(locale).as_str().unwrap_or_default().to_owned();
+    if let Setting::Set(locales) = &s.locales {
+        if let Some(locale) = locales.iter().find(|locale| !milli::stemming::is_supported(**locale))
+        {
+            let supported: Vec<_> = milli::stemming::SUPPORTED_LOCALES
+                .iter()
+                .map(|locale| format!("`{}`", name(locale)))
+                .collect();
+            return Err(deserr::take_cf_content(E::error::<Infallible>(
+                None,
+                ErrorKind::Unexpected {
+                    msg: format!(
+                        "the words of the `{}` locale can't be stemmed, expected one of {}",
+                        name(locale),
+                        supported.join(", "),
+                    ),
+                },
+                location,
+            )));
+        }
+    }
+    Ok(s)
+}
+
+/// The experimental stemming of the words of the searchable attributes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Deserr)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields, validate = validate_stemming_settings -> DeserrJsonError<InvalidSettingsStemming>)]
+pub struct StemmingSettings {
+    /// The locales the words of the attributes are stemmed in, the attributes localized by a
+    /// localized attributes rule are only stemmed in its locales.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub locales: Setting<BTreeSet<Locale>>,
+}
+
 impl MergeWithError<milli::CriterionError> for DeserrJsonError<InvalidSettingsRankingRules> {
     fn merge(
         _self_: Option<Self>,
@@ -132,6 +177,20 @@ impl MergeWithError<milli::CriterionError> for DeserrJsonError<InvalidSettingsRa
     }
 }
 
+impl MergeWithError<milli::CriterionError> for DeserrJsonError<InvalidSearchRankingRules> {
+    fn merge(
+        _self_: Option<Self>,
+        other: milli::CriterionError,
+        merge_location: ValuePointerRef,
+    ) -> ControlFlow<Self, Self> {
+        Self::error::<Infallible>(
+            None,
+            ErrorKind::Unexpected { msg: other.to_string() },
+            merge_location,
+        )
+    }
+}
+
 /// Holds all the settings for an index. `T` can either be `Checked` if they represents settings
 /// whose validity is guaranteed, or `Unchecked` if they need to be validated. In the later case, a
 /// call to `check` will return a `Settings<Checked>` from a `Settings<Unchecked>`.
@@ -167,7 +226,7 @@ pub struct Settings<T> {
     pub sortable_attributes: Setting<BTreeSet<String>>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default, error = DeserrJsonError<InvalidSettingsRankingRules>)]
-    pub ranking_rules: Setting<Vec<RankingRuleView>>,
+    pub ranking_rules: Setting<Vec<RankingRuleSetting>>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default, error = DeserrJsonError<InvalidSettingsStopWords>)]
     pub stop_words: Setting<BTreeSet<String>>,
@@ -205,6 +264,48 @@ pub struct Settings<T> {
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default, error = DeserrJsonError<InvalidSettingsSearchCutoffMs>)]
     pub search_cutoff_ms: Setting<u64>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsSearchDefaults>)]
+    pub search_defaults: Setting<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsMinNumericPrefixLength>)]
+    pub min_numeric_prefix_length: Setting<u8>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsAllowUnderscoreFields>)]
+    pub allow_underscore_fields: Setting<bool>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsLocalizedAttributes>)]
+    pub localized_attributes: Setting<Vec<LocalizedAttributesRule>>,
+    /// Experimental: indexes and searches the formatted numbers under a canonical form.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsNumberNormalization>)]
+    pub number_normalization: Setting<bool>,
+    /// Experimental: lets the phrases match words spanning two adjacent searchable attributes.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsPhraseAcrossFields>)]
+    pub phrase_across_fields: Setting<bool>,
+    /// Attributes only matching the query words as they are typed, without typos, prefixes,
+    /// nor splitting, whatever the typo tolerance settings.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsExactAttributes>)]
+    pub exact_attributes: Setting<BTreeSet<String>>,
+    /// Experimental: the locales whose compound words are split into the words of the index in
+    /// the queries made on the attributes localized in one of them.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsDecompoundingLocales>)]
+    pub decompounding_locales: Setting<BTreeSet<Locale>>,
+    /// The types hinted for the values of the attributes, the dates of the attributes hinted
+    /// as `date` can be filtered by range and have facet stats.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsFacetTypes>)]
+    pub facet_types: Setting<BTreeMap<String, FacetTypeHint>>,
+    /// Experimental: the words of the attributes are also indexed and searched under their stem.
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsStemming>)]
+    pub stemming: Setting<StemmingSettings>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsPresets>)]
+    pub presets: Setting<BTreeMap<String, Setting<serde_json::Value>>>,
 
     #[serde(skip)]
     #[deserr(skip)]
@@ -268,6 +369,17 @@ impl Settings<Checked> {
             pagination: Setting::Reset,
             embedders: Setting::Reset,
             search_cutoff_ms: Setting::Reset,
+            search_defaults: Setting::Reset,
+            min_numeric_prefix_length: Setting::Reset,
+            allow_underscore_fields: Setting::Reset,
+            localized_attributes: Setting::Reset,
+            number_normalization: Setting::Reset,
+            phrase_across_fields: Setting::Reset,
+            exact_attributes: Setting::Reset,
+            decompounding_locales: Setting::Reset,
+            facet_types: Setting::Reset,
+            stemming: Setting::Reset,
+            presets: Setting::Reset,
             _kind: PhantomData,
         }
     }
@@ -291,6 +403,17 @@ impl Settings<Checked> {
             pagination,
             embedders,
             search_cutoff_ms,
+            search_defaults,
+            min_numeric_prefix_length,
+            allow_underscore_fields,
+            localized_attributes,
+            number_normalization,
+            phrase_across_fields,
+            exact_attributes,
+            decompounding_locales,
+            facet_types,
+            stemming,
+            presets,
             ..
         } = self;
 
@@ -312,9 +435,88 @@ impl Settings<Checked> {
             pagination,
             embedders,
             search_cutoff_ms,
+            search_defaults,
+            min_numeric_prefix_length,
+            allow_underscore_fields,
+            localized_attributes,
+            number_normalization,
+            phrase_across_fields,
+            exact_attributes,
+            decompounding_locales,
+            facet_types,
+            stemming,
+            presets,
             _kind: PhantomData,
         }
     }
+
+    /// Returns these settings in a canonical form, such that two indexes with the same
+    /// settings always give byte-identical JSON, whatever the order they were sent in.
+    ///
+    /// The defaults are made explicit, and everything that doesn't hold a meaningful order is
+    /// sorted. The ranking rules and the searchable attributes keep their order as it matters.
+    pub fn into_canonical(self) -> Settings<Checked> {
+        let wildcard = || vec!["*".to_string()];
+        Settings {
+            displayed_attributes: Setting::Set(
+                self.displayed_attributes.set().unwrap_or_else(wildcard),
+            ),
+            searchable_attributes: Setting::Set(
+                self.searchable_attributes.set().unwrap_or_else(wildcard),
+            ),
+            synonyms: Setting::Set(
+                self.synonyms
+                    .set()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(word, mut synonyms)| {
+                        synonyms.sort_unstable();
+                        synonyms.dedup();
+                        (word, synonyms)
+                    })
+                    .collect(),
+            ),
+            embedders: Setting::Set(self.embedders.set().unwrap_or_default()),
+            search_defaults: match self.search_defaults {
+                Setting::Set(mut defaults) => {
+                    sort_json_keys(&mut defaults);
+                    Setting::Set(defaults)
+                }
+                setting => setting,
+            },
+            presets: Setting::Set(
+                self.presets
+                    .set()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(name, preset)| match preset {
+                        Setting::Set(mut preset) => {
+                            sort_json_keys(&mut preset);
+                            (name, Setting::Set(preset))
+                        }
+                        preset => (name, preset),
+                    })
+                    .collect(),
+            ),
+            ..self
+        }
+    }
+}
+
+/// Recursively sorts the keys of the objects of this value.
+fn sort_json_keys(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(object) => {
+            let mut entries: Vec<_> = std::mem::take(object).into_iter().collect();
+            entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+            for (key, mut value) in entries {
+                sort_json_keys(&mut value);
+                object.insert(key, value);
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(sort_json_keys),
+        _ => (),
+    }
 }
 
 impl Settings<Unchecked> {
@@ -359,12 +561,39 @@ impl Settings<Unchecked> {
             pagination: self.pagination,
             embedders: self.embedders,
             search_cutoff_ms: self.search_cutoff_ms,
+            search_defaults: self.search_defaults,
+            min_numeric_prefix_length: self.min_numeric_prefix_length,
+            allow_underscore_fields: self.allow_underscore_fields,
+            localized_attributes: self.localized_attributes,
+            number_normalization: self.number_normalization,
+            phrase_across_fields: self.phrase_across_fields,
+            exact_attributes: self.exact_attributes,
+            decompounding_locales: self.decompounding_locales,
+            facet_types: self.facet_types,
+            stemming: self.stemming,
+            presets: self.presets,
             _kind: PhantomData,
         }
     }
 
     pub fn validate(self) -> Result<Self, milli::Error> {
-        self.validate_embedding_settings()
+        self.validate_ranking_rules()?.validate_embedding_settings()?.validate_search_defaults()
+    }
+
+    fn validate_ranking_rules(self) -> Result<Self, milli::Error> {
+        if let Setting::Set(ranking_rules) = &self.ranking_rules {
+            let criteria: Vec<_> =
+                ranking_rules.iter().map(|r| Criterion::from(r.rule.clone())).collect();
+            milli::validate_criteria(&criteria)?;
+        }
+        Ok(self)
+    }
+
+    fn validate_search_defaults(self) -> Result<Self, milli::Error> {
+        if let Setting::Set(defaults) = &self.search_defaults {
+            milli::update::validate_search_defaults(defaults)?;
+        }
+        Ok(self)
     }
 
     fn validate_embedding_settings(mut self) -> Result<Self, milli::Error> {
@@ -409,6 +638,17 @@ pub fn apply_settings_to_builder(
         pagination,
         embedders,
         search_cutoff_ms,
+        search_defaults,
+        min_numeric_prefix_length,
+        allow_underscore_fields,
+        localized_attributes,
+        number_normalization,
+        phrase_across_fields,
+        exact_attributes,
+        decompounding_locales,
+        facet_types,
+        stemming,
+        presets,
         _kind,
     } = settings;
 
@@ -439,10 +679,20 @@ pub fn apply_settings_to_builder(
     }
 
     match ranking_rules {
-        Setting::Set(ref criteria) => {
-            builder.set_criteria(criteria.iter().map(|c| c.clone().into()).collect())
+        Setting::Set(ref ranking_rules) => {
+            let criteria: Vec<Criterion> =
+                ranking_rules.iter().map(|r| r.rule.clone().into()).collect();
+            let max_buckets = ranking_rules
+                .iter()
+                .filter_map(|r| Some((Criterion::from(r.rule.clone()).to_string(), r.max_buckets?)))
+                .collect();
+            builder.set_criteria(criteria);
+            builder.set_criteria_max_buckets(max_buckets);
+        }
+        Setting::Reset => {
+            builder.reset_criteria();
+            builder.reset_criteria_max_buckets();
         }
-        Setting::Reset => builder.reset_criteria(),
         Setting::NotSet => (),
     }
 
@@ -590,6 +840,76 @@ pub fn apply_settings_to_builder(
         Setting::Reset => builder.reset_search_cutoff(),
         Setting::NotSet => (),
     }
+
+    match search_defaults {
+        Setting::Set(defaults) => builder.set_search_defaults(defaults.clone()),
+        Setting::Reset => builder.reset_search_defaults(),
+        Setting::NotSet => (),
+    }
+
+    match min_numeric_prefix_length {
+        Setting::Set(length) => builder.set_min_numeric_prefix_length(*length),
+        Setting::Reset => builder.reset_min_numeric_prefix_length(),
+        Setting::NotSet => (),
+    }
+
+    match allow_underscore_fields {
+        Setting::Set(allow) => builder.set_allow_underscore_fields(*allow),
+        Setting::Reset => builder.reset_allow_underscore_fields(),
+        Setting::NotSet => (),
+    }
+
+    match localized_attributes {
+        Setting::Set(rules) => builder.set_localized_attributes_rules(rules.clone()),
+        Setting::Reset => builder.reset_localized_attributes_rules(),
+        Setting::NotSet => (),
+    }
+
+    match number_normalization {
+        Setting::Set(enabled) => builder.set_number_normalization(*enabled),
+        Setting::Reset => builder.reset_number_normalization(),
+        Setting::NotSet => (),
+    }
+
+    match phrase_across_fields {
+        Setting::Set(enabled) => builder.set_phrase_across_fields(*enabled),
+        Setting::Reset => builder.reset_phrase_across_fields(),
+        Setting::NotSet => (),
+    }
+
+    match exact_attributes {
+        Setting::Set(attributes) => builder.set_strict_attributes(attributes.clone()),
+        Setting::Reset => builder.reset_strict_attributes(),
+        Setting::NotSet => (),
+    }
+
+    match decompounding_locales {
+        Setting::Set(locales) => builder.set_decompounding_locales(locales.clone()),
+        Setting::Reset => builder.reset_decompounding_locales(),
+        Setting::NotSet => (),
+    }
+
+    match facet_types {
+        Setting::Set(facet_types) => builder.set_facet_types(facet_types.clone()),
+        Setting::Reset => builder.reset_facet_types(),
+        Setting::NotSet => (),
+    }
+
+    match stemming {
+        Setting::Set(StemmingSettings { locales: Setting::Set(locales) }) => {
+            builder.set_stemming_locales(locales.clone())
+        }
+        Setting::Set(StemmingSettings { locales: Setting::Reset }) | Setting::Reset => {
+            builder.reset_stemming_locales()
+        }
+        Setting::Set(StemmingSettings { locales: Setting::NotSet }) | Setting::NotSet => (),
+    }
+
+    match presets {
+        Setting::Set(presets) => builder.set_search_presets(presets.clone()),
+        Setting::Reset => builder.reset_search_presets(),
+        Setting::NotSet => (),
+    }
 }
 
 pub enum SecretPolicy {
@@ -684,8 +1004,37 @@ pub fn settings(
         .collect();
     let embedders = if embedders.is_empty() { Setting::NotSet } else { Setting::Set(embedders) };
 
+    let criteria_max_buckets = index.criteria_max_buckets(rtxn)?;
+
     let search_cutoff_ms = index.search_cutoff(rtxn)?;
 
+    let search_defaults = index.search_defaults(rtxn)?;
+
+    let min_numeric_prefix_length = index.min_numeric_prefix_length(rtxn)?;
+
+    let allow_underscore_fields = index.allow_underscore_fields(rtxn)?;
+
+    let localized_attributes = index.localized_attributes_rules(rtxn)?;
+
+    let number_normalization = index.number_normalization(rtxn)?;
+
+    let phrase_across_fields = index.phrase_across_fields(rtxn)?;
+
+    let exact_attributes = index.strict_attributes(rtxn)?.into_iter().map(String::from).collect();
+
+    let decompounding_locales = index.decompounding_locales(rtxn)?;
+
+    let facet_types = index.facet_types(rtxn)?;
+
+    let stemming = StemmingSettings { locales: Setting::Set(index.stemming_locales(rtxn)?) };
+
+    let presets: BTreeMap<_, _> = index
+        .search_presets(rtxn)?
+        .into_iter()
+        .map(|(name, preset)| (name, Setting::Set(preset)))
+        .collect();
+    let presets = if presets.is_empty() { Setting::NotSet } else { Setting::Set(presets) };
+
     let mut settings = Settings {
         displayed_attributes: match displayed_attributes {
             Some(attrs) => Setting::Set(attrs),
@@ -697,7 +1046,15 @@ pub fn settings(
         },
         filterable_attributes: Setting::Set(filterable_attributes),
         sortable_attributes: Setting::Set(sortable_attributes),
-        ranking_rules: Setting::Set(criteria.iter().map(|c| c.clone().into()).collect()),
+        ranking_rules: Setting::Set(
+            criteria
+                .iter()
+                .map(|c| RankingRuleSetting {
+                    rule: c.clone().into(),
+                    max_buckets: criteria_max_buckets.get(&c.to_string()).copied(),
+                })
+                .collect(),
+        ),
         stop_words: Setting::Set(stop_words),
         non_separator_tokens: Setting::Set(non_separator_tokens),
         separator_tokens: Setting::Set(separator_tokens),
@@ -716,6 +1073,23 @@ pub fn settings(
             Some(cutoff) => Setting::Set(cutoff),
             None => Setting::Reset,
         },
+        search_defaults: match search_defaults {
+            Some(defaults) => Setting::Set(defaults),
+            None => Setting::Reset,
+        },
+        min_numeric_prefix_length: Setting::Set(min_numeric_prefix_length),
+        allow_underscore_fields: Setting::Set(allow_underscore_fields),
+        localized_attributes: match localized_attributes {
+            Some(rules) => Setting::Set(rules),
+            None => Setting::Reset,
+        },
+        number_normalization: Setting::Set(number_normalization),
+        phrase_across_fields: Setting::Set(phrase_across_fields),
+        exact_attributes: Setting::Set(exact_attributes),
+        decompounding_locales: Setting::Set(decompounding_locales),
+        facet_types: Setting::Set(facet_types),
+        stemming: Setting::Set(stemming),
+        presets,
         _kind: PhantomData,
     };
 
@@ -748,6 +1122,10 @@ pub enum RankingRuleView {
     Asc(String),
     /// Sorted by the decreasing value of the field specified.
     Desc(String),
+    /// Shuffled in buckets of `bucket_size` documents, in an order only depending on the seed.
+    Random { seed: RandomSeed, bucket_size: u32 },
+    /// Sorted by increasing distance to the point, in buckets of `bucket_size` documents.
+    GeoPoint { point: LatLng, bucket_size: u32 },
 }
 impl Serialize for RankingRuleView {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -804,6 +1182,12 @@ impl From<Criterion> for RankingRuleView {
             Criterion::Exactness => RankingRuleView::Exactness,
             Criterion::Asc(x) => RankingRuleView::Asc(x),
             Criterion::Desc(x) => RankingRuleView::Desc(x),
+            Criterion::Random { seed, bucket_size } => {
+                RankingRuleView::Random { seed, bucket_size }
+            }
+            Criterion::GeoPoint { point, bucket_size } => {
+                RankingRuleView::GeoPoint { point, bucket_size }
+            }
         }
     }
 }
@@ -818,6 +1202,106 @@ impl From<RankingRuleView> for Criterion {
             RankingRuleView::Exactness => Criterion::Exactness,
             RankingRuleView::Asc(x) => Criterion::Asc(x),
             RankingRuleView::Desc(x) => Criterion::Desc(x),
+            RankingRuleView::Random { seed, bucket_size } => {
+                Criterion::Random { seed, bucket_size }
+            }
+            RankingRuleView::GeoPoint { point, bucket_size } => {
+                Criterion::GeoPoint { point, bucket_size }
+            }
+        }
+    }
+}
+
+/// A ranking rule of the settings, written either as the name of the rule or, to cap
+/// the number of buckets it returns, as `{ "rule": "proximity", "maxBuckets": 100 }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "RankingRuleSettingSerializable", into = "RankingRuleSettingSerializable")]
+pub struct RankingRuleSetting {
+    pub rule: RankingRuleView,
+    /// Past this number of buckets, the remaining documents are returned unsorted by the rule.
+    pub max_buckets: Option<usize>,
+}
+
+impl From<RankingRuleView> for RankingRuleSetting {
+    fn from(rule: RankingRuleView) -> Self {
+        Self { rule, max_buckets: None }
+    }
+}
+
+impl<E> Deserr<E> for RankingRuleSetting
+where
+    E: DeserializeError + MergeWithError<CriterionError>,
+{
+    fn deserialize_from_value<V: deserr::IntoValue>(
+        value: deserr::Value<V>,
+        location: ValuePointerRef,
+    ) -> Result<Self, E> {
+        match value {
+            deserr::Value::String(_) => {
+                RankingRuleView::deserialize_from_value(value, location).map(Self::from)
+            }
+            deserr::Value::Map(_) => {
+                let LimitedRankingRule { rule, max_buckets } =
+                    LimitedRankingRule::deserialize_from_value(value, location)?;
+                if max_buckets == 0 {
+                    return Err(deserr::take_cf_content(E::error::<Infallible>(
+                        None,
+                        ErrorKind::Unexpected {
+                            msg: format!(
+                                "the maximum number of buckets of the `{rule}` ranking rule must be greater than 0"
+                            ),
+                        },
+                        location,
+                    )));
+                }
+                Ok(Self { rule, max_buckets: Some(max_buckets) })
+            }
+            value => Err(deserr::take_cf_content(E::error::<V>(
+                None,
+                ErrorKind::IncorrectValueKind {
+                    actual: value,
+                    accepted: &[deserr::ValueKind::String, deserr::ValueKind::Map],
+                },
+                location,
+            ))),
+        }
+    }
+}
+
+#[derive(Deserr)]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+struct LimitedRankingRule {
+    rule: RankingRuleView,
+    max_buckets: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RankingRuleSettingSerializable {
+    Rule(RankingRuleView),
+    #[serde(rename_all = "camelCase")]
+    Limited {
+        rule: RankingRuleView,
+        max_buckets: usize,
+    },
+}
+
+impl From<RankingRuleSetting> for RankingRuleSettingSerializable {
+    fn from(RankingRuleSetting { rule, max_buckets }: RankingRuleSetting) -> Self {
+        match max_buckets {
+            Some(max_buckets) => Self::Limited { rule, max_buckets },
+            None => Self::Rule(rule),
+        }
+    }
+}
+
+impl From<RankingRuleSettingSerializable> for RankingRuleSetting {
+    fn from(value: RankingRuleSettingSerializable) -> Self {
+        match value {
+            RankingRuleSettingSerializable::Rule(rule) => Self { rule, max_buckets: None },
+            RankingRuleSettingSerializable::Limited { rule, max_buckets } => {
+                Self { rule, max_buckets: Some(max_buckets) }
+            }
         }
     }
 }
@@ -873,6 +1357,17 @@ pub(crate) mod test {
             pagination: Setting::NotSet,
             embedders: Setting::NotSet,
             search_cutoff_ms: Setting::NotSet,
+            search_defaults: Setting::NotSet,
+            min_numeric_prefix_length: Setting::NotSet,
+            allow_underscore_fields: Setting::NotSet,
+            localized_attributes: Setting::NotSet,
+            number_normalization: Setting::NotSet,
+            phrase_across_fields: Setting::NotSet,
+            exact_attributes: Setting::NotSet,
+            decompounding_locales: Setting::NotSet,
+            facet_types: Setting::NotSet,
+            stemming: Setting::NotSet,
+            presets: Setting::NotSet,
             _kind: PhantomData::<Unchecked>,
         };
 
@@ -900,6 +1395,17 @@ pub(crate) mod test {
             pagination: Setting::NotSet,
             embedders: Setting::NotSet,
             search_cutoff_ms: Setting::NotSet,
+            search_defaults: Setting::NotSet,
+            min_numeric_prefix_length: Setting::NotSet,
+            allow_underscore_fields: Setting::NotSet,
+            localized_attributes: Setting::NotSet,
+            number_normalization: Setting::NotSet,
+            phrase_across_fields: Setting::NotSet,
+            exact_attributes: Setting::NotSet,
+            decompounding_locales: Setting::NotSet,
+            facet_types: Setting::NotSet,
+            stemming: Setting::NotSet,
+            presets: Setting::NotSet,
             _kind: PhantomData::<Unchecked>,
         };
 
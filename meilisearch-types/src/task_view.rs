@@ -1,9 +1,14 @@
+use std::collections::BTreeMap;
+
+use milli::update::{FacetUpdateStrategy, IntegrityViolation, IntegrityViolationReport};
 use serde::Serialize;
 use time::{Duration, OffsetDateTime};
 
 use crate::error::ResponseError;
 use crate::settings::{Settings, Unchecked};
-use crate::tasks::{serialize_duration, Details, IndexSwap, Kind, Status, Task, TaskId};
+use crate::tasks::{
+    serialize_duration, Details, IndexSwap, Kind, Status, Task, TaskAttempt, TaskId,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -26,26 +31,53 @@ pub struct TaskView {
     pub started_at: Option<OffsetDateTime>,
     #[serde(with = "time::serde::rfc3339::option", default)]
     pub finished_at: Option<OffsetDateTime>,
+    /// The steps the task went through, only known while it is processing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<ProgressView>,
 }
 
 impl TaskView {
     pub fn from_task(task: &Task) -> TaskView {
+        let mut details = task.details.clone().map(DetailsView::from);
+        if !task.attempts.is_empty() {
+            details.get_or_insert_with(DetailsView::default).attempts = Some(task.attempts.clone());
+        }
+
         TaskView {
             uid: task.uid,
             index_uid: task.index_uid().map(ToOwned::to_owned),
             status: task.status,
             kind: task.kind.as_kind(),
             canceled_by: task.canceled_by,
-            details: task.details.clone().map(DetailsView::from),
+            details,
             error: task.error.clone(),
             duration: task.started_at.zip(task.finished_at).map(|(start, end)| end - start),
             enqueued_at: task.enqueued_at,
             started_at: task.started_at,
             finished_at: task.finished_at,
+            progress: None,
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressView {
+    /// The steps in the order they were started, the last one being the current one.
+    pub steps: Vec<ProgressStepView>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressStepView {
+    pub name: String,
+    /// What is being counted, e.g. `documents` or `bytes`.
+    pub unit: String,
+    pub finished: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+}
+
 #[derive(Default, Debug, PartialEq, Eq, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DetailsView {
@@ -54,6 +86,14 @@ pub struct DetailsView {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub indexed_documents: Option<Option<u64>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_documents: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invalid_utf8_replacements: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indexing_errors: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_update_strategies: Option<BTreeMap<String, FacetUpdateStrategy>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub primary_key: Option<Option<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub provided_ids: Option<usize>,
@@ -74,22 +114,59 @@ pub struct DetailsView {
     pub settings: Option<Box<Settings<Unchecked>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub swaps: Option<Vec<IndexSwap>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_index_uid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restored_documents: Option<Option<u64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repair: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub violations: Option<Option<BTreeMap<IntegrityViolation, IntegrityViolationReport>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub imported_indexes: Option<Option<u64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefixes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub populate_cache: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warmed_entries: Option<Option<u64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warnings: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<Vec<TaskAttempt>>,
 }
 
 impl From<Details> for DetailsView {
     fn from(details: Details) -> Self {
         match details {
-            Details::DocumentAdditionOrUpdate { received_documents, indexed_documents } => {
+            Details::DocumentAdditionOrUpdate {
+                received_documents,
+                indexed_documents,
+                duplicate_documents,
+                invalid_utf8_replacements,
+                indexing_errors,
+                facet_update_strategies,
+            } => DetailsView {
+                received_documents: Some(received_documents),
+                indexed_documents: Some(indexed_documents),
+                duplicate_documents: (duplicate_documents != 0).then_some(duplicate_documents),
+                invalid_utf8_replacements: (invalid_utf8_replacements != 0)
+                    .then_some(invalid_utf8_replacements),
+                indexing_errors: (!indexing_errors.is_empty()).then_some(indexing_errors),
+                facet_update_strategies: (!facet_update_strategies.is_empty())
+                    .then_some(facet_update_strategies),
+                ..DetailsView::default()
+            },
+            Details::SettingsUpdate { mut settings, warnings } => {
+                settings.hide_secrets();
                 DetailsView {
-                    received_documents: Some(received_documents),
-                    indexed_documents: Some(indexed_documents),
+                    settings: Some(settings),
+                    warnings: (!warnings.is_empty()).then_some(warnings),
                     ..DetailsView::default()
                 }
             }
-            Details::SettingsUpdate { mut settings } => {
-                settings.hide_secrets();
-                DetailsView { settings: Some(settings), ..DetailsView::default() }
-            }
             Details::IndexInfo { primary_key } => {
                 DetailsView { primary_key: Some(primary_key), ..DetailsView::default() }
             }
@@ -135,6 +212,28 @@ impl From<Details> for DetailsView {
             Details::IndexSwap { swaps } => {
                 DetailsView { swaps: Some(swaps), ..Default::default() }
             }
+            Details::IndexRestore { snapshot_path, snapshot_index_uid, restored_documents } => {
+                DetailsView {
+                    snapshot_path: Some(snapshot_path),
+                    snapshot_index_uid: Some(snapshot_index_uid),
+                    restored_documents: Some(restored_documents),
+                    ..DetailsView::default()
+                }
+            }
+            Details::IntegrityCheck { repair, violations } => DetailsView {
+                repair: Some(repair),
+                violations: Some(violations),
+                ..DetailsView::default()
+            },
+            Details::SnapshotImport { imported_indexes } => {
+                DetailsView { imported_indexes: Some(imported_indexes), ..DetailsView::default() }
+            }
+            Details::IndexWarmup { prefixes, populate_cache, warmed_entries } => DetailsView {
+                prefixes: Some(prefixes),
+                populate_cache: Some(populate_cache),
+                warmed_entries: Some(warmed_entries),
+                ..DetailsView::default()
+            },
         }
     }
 }
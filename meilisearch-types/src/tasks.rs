@@ -1,10 +1,12 @@
 use core::fmt;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::{Display, Write};
 use std::str::FromStr;
 
 use enum_iterator::Sequence;
-use milli::update::IndexDocumentsMethod;
+use milli::update::{
+    FacetUpdateStrategy, IndexDocumentsMethod, IntegrityViolation, IntegrityViolationReport,
+};
 use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize, Serializer};
 use time::{Duration, OffsetDateTime};
@@ -35,6 +37,21 @@ pub struct Task {
 
     pub status: Status,
     pub kind: KindWithContent,
+
+    /// The previous attempts at processing the task that failed because of a transient error.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<TaskAttempt>,
+}
+
+/// A failed attempt at processing a task.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskAttempt {
+    #[serde(with = "time::serde::rfc3339")]
+    pub started_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub finished_at: OffsetDateTime,
+    pub error: ResponseError,
 }
 
 impl Task {
@@ -44,6 +61,7 @@ impl Task {
         match &self.kind {
             DumpCreation { .. }
             | SnapshotCreation
+            | SnapshotImportIncremental
             | TaskCancelation { .. }
             | TaskDeletion { .. }
             | IndexSwap { .. } => None,
@@ -54,7 +72,10 @@ impl Task {
             | SettingsUpdate { index_uid, .. }
             | IndexCreation { index_uid, .. }
             | IndexUpdate { index_uid, .. }
-            | IndexDeletion { index_uid } => Some(index_uid),
+            | IndexDeletion { index_uid }
+            | IndexRestore { index_uid, .. }
+            | IntegrityCheck { index_uid, .. }
+            | IndexWarmup { index_uid, .. } => Some(index_uid),
         }
     }
 
@@ -75,10 +96,14 @@ impl Task {
             | KindWithContent::IndexCreation { .. }
             | KindWithContent::IndexUpdate { .. }
             | KindWithContent::IndexSwap { .. }
+            | KindWithContent::IndexRestore { .. }
+            | KindWithContent::IntegrityCheck { .. }
+            | KindWithContent::IndexWarmup { .. }
             | KindWithContent::TaskCancelation { .. }
             | KindWithContent::TaskDeletion { .. }
             | KindWithContent::DumpCreation { .. }
-            | KindWithContent::SnapshotCreation => None,
+            | KindWithContent::SnapshotCreation
+            | KindWithContent::SnapshotImportIncremental => None,
         }
     }
 }
@@ -125,6 +150,20 @@ pub enum KindWithContent {
     IndexSwap {
         swaps: Vec<IndexSwap>,
     },
+    IndexRestore {
+        index_uid: String,
+        snapshot_path: String,
+        snapshot_index_uid: String,
+    },
+    IntegrityCheck {
+        index_uid: String,
+        repair: bool,
+    },
+    IndexWarmup {
+        index_uid: String,
+        prefixes: Vec<String>,
+        populate_cache: bool,
+    },
     TaskCancelation {
         query: String,
         tasks: RoaringBitmap,
@@ -138,6 +177,8 @@ pub enum KindWithContent {
         instance_uid: Option<InstanceUid>,
     },
     SnapshotCreation,
+    /// Replaces the indexes of a replica by the ones of a snapshot of its primary.
+    SnapshotImportIncremental,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -158,10 +199,14 @@ impl KindWithContent {
             KindWithContent::IndexDeletion { .. } => Kind::IndexDeletion,
             KindWithContent::IndexUpdate { .. } => Kind::IndexUpdate,
             KindWithContent::IndexSwap { .. } => Kind::IndexSwap,
+            KindWithContent::IndexRestore { .. } => Kind::IndexRestore,
+            KindWithContent::IntegrityCheck { .. } => Kind::IntegrityCheck,
+            KindWithContent::IndexWarmup { .. } => Kind::IndexWarmup,
             KindWithContent::TaskCancelation { .. } => Kind::TaskCancelation,
             KindWithContent::TaskDeletion { .. } => Kind::TaskDeletion,
             KindWithContent::DumpCreation { .. } => Kind::DumpCreation,
             KindWithContent::SnapshotCreation => Kind::SnapshotCreation,
+            KindWithContent::SnapshotImportIncremental => Kind::SnapshotImportIncremental,
         }
     }
 
@@ -171,6 +216,7 @@ impl KindWithContent {
         match self {
             DumpCreation { .. }
             | SnapshotCreation
+            | SnapshotImportIncremental
             | TaskCancelation { .. }
             | TaskDeletion { .. } => vec![],
             DocumentAdditionOrUpdate { index_uid, .. }
@@ -180,7 +226,10 @@ impl KindWithContent {
             | SettingsUpdate { index_uid, .. }
             | IndexCreation { index_uid, .. }
             | IndexUpdate { index_uid, .. }
-            | IndexDeletion { index_uid } => vec![index_uid],
+            | IndexDeletion { index_uid }
+            | IndexRestore { index_uid, .. }
+            | IntegrityCheck { index_uid, .. }
+            | IndexWarmup { index_uid, .. } => vec![index_uid],
             IndexSwap { swaps } => {
                 let mut indexes = HashSet::<&str>::default();
                 for swap in swaps {
@@ -200,6 +249,10 @@ impl KindWithContent {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: None,
+                    duplicate_documents: 0,
+                    invalid_utf8_replacements: 0,
+                    indexing_errors: Vec::new(),
+                    facet_update_strategies: BTreeMap::new(),
                 })
             }
             KindWithContent::DocumentDeletion { index_uid: _, documents_ids } => {
@@ -217,9 +270,10 @@ impl KindWithContent {
             KindWithContent::DocumentClear { .. } | KindWithContent::IndexDeletion { .. } => {
                 Some(Details::ClearAll { deleted_documents: None })
             }
-            KindWithContent::SettingsUpdate { new_settings, .. } => {
-                Some(Details::SettingsUpdate { settings: new_settings.clone() })
-            }
+            KindWithContent::SettingsUpdate { new_settings, .. } => Some(Details::SettingsUpdate {
+                settings: new_settings.clone(),
+                warnings: Vec::new(),
+            }),
             KindWithContent::IndexCreation { primary_key, .. }
             | KindWithContent::IndexUpdate { primary_key, .. } => {
                 Some(Details::IndexInfo { primary_key: primary_key.clone() })
@@ -227,6 +281,23 @@ impl KindWithContent {
             KindWithContent::IndexSwap { swaps } => {
                 Some(Details::IndexSwap { swaps: swaps.clone() })
             }
+            KindWithContent::IndexRestore { snapshot_path, snapshot_index_uid, .. } => {
+                Some(Details::IndexRestore {
+                    snapshot_path: snapshot_path.clone(),
+                    snapshot_index_uid: snapshot_index_uid.clone(),
+                    restored_documents: None,
+                })
+            }
+            KindWithContent::IntegrityCheck { repair, .. } => {
+                Some(Details::IntegrityCheck { repair: *repair, violations: None })
+            }
+            KindWithContent::IndexWarmup { prefixes, populate_cache, .. } => {
+                Some(Details::IndexWarmup {
+                    prefixes: prefixes.clone(),
+                    populate_cache: *populate_cache,
+                    warmed_entries: None,
+                })
+            }
             KindWithContent::TaskCancelation { query, tasks } => Some(Details::TaskCancelation {
                 matched_tasks: tasks.len(),
                 canceled_tasks: None,
@@ -239,6 +310,9 @@ impl KindWithContent {
             }),
             KindWithContent::DumpCreation { .. } => Some(Details::Dump { dump_uid: None }),
             KindWithContent::SnapshotCreation => None,
+            KindWithContent::SnapshotImportIncremental => {
+                Some(Details::SnapshotImport { imported_indexes: None })
+            }
         }
     }
 
@@ -248,6 +322,10 @@ impl KindWithContent {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: Some(0),
+                    duplicate_documents: 0,
+                    invalid_utf8_replacements: 0,
+                    indexing_errors: Vec::new(),
+                    facet_update_strategies: BTreeMap::new(),
                 })
             }
             KindWithContent::DocumentDeletion { index_uid: _, documents_ids } => {
@@ -265,9 +343,10 @@ impl KindWithContent {
             KindWithContent::DocumentClear { .. } => {
                 Some(Details::ClearAll { deleted_documents: None })
             }
-            KindWithContent::SettingsUpdate { new_settings, .. } => {
-                Some(Details::SettingsUpdate { settings: new_settings.clone() })
-            }
+            KindWithContent::SettingsUpdate { new_settings, .. } => Some(Details::SettingsUpdate {
+                settings: new_settings.clone(),
+                warnings: Vec::new(),
+            }),
             KindWithContent::IndexDeletion { .. } => None,
             KindWithContent::IndexCreation { primary_key, .. }
             | KindWithContent::IndexUpdate { primary_key, .. } => {
@@ -276,6 +355,23 @@ impl KindWithContent {
             KindWithContent::IndexSwap { .. } => {
                 todo!()
             }
+            KindWithContent::IndexRestore { snapshot_path, snapshot_index_uid, .. } => {
+                Some(Details::IndexRestore {
+                    snapshot_path: snapshot_path.clone(),
+                    snapshot_index_uid: snapshot_index_uid.clone(),
+                    restored_documents: Some(0),
+                })
+            }
+            KindWithContent::IntegrityCheck { repair, .. } => {
+                Some(Details::IntegrityCheck { repair: *repair, violations: Some(BTreeMap::new()) })
+            }
+            KindWithContent::IndexWarmup { prefixes, populate_cache, .. } => {
+                Some(Details::IndexWarmup {
+                    prefixes: prefixes.clone(),
+                    populate_cache: *populate_cache,
+                    warmed_entries: Some(0),
+                })
+            }
             KindWithContent::TaskCancelation { query, tasks } => Some(Details::TaskCancelation {
                 matched_tasks: tasks.len(),
                 canceled_tasks: Some(0),
@@ -288,6 +384,9 @@ impl KindWithContent {
             }),
             KindWithContent::DumpCreation { .. } => Some(Details::Dump { dump_uid: None }),
             KindWithContent::SnapshotCreation => None,
+            KindWithContent::SnapshotImportIncremental => {
+                Some(Details::SnapshotImport { imported_indexes: Some(0) })
+            }
         }
     }
 }
@@ -299,14 +398,19 @@ impl From<&KindWithContent> for Option<Details> {
                 Some(Details::DocumentAdditionOrUpdate {
                     received_documents: *documents_count,
                     indexed_documents: None,
+                    duplicate_documents: 0,
+                    invalid_utf8_replacements: 0,
+                    indexing_errors: Vec::new(),
+                    facet_update_strategies: BTreeMap::new(),
                 })
             }
             KindWithContent::DocumentDeletion { .. } => None,
             KindWithContent::DocumentDeletionByFilter { .. } => None,
             KindWithContent::DocumentClear { .. } => None,
-            KindWithContent::SettingsUpdate { new_settings, .. } => {
-                Some(Details::SettingsUpdate { settings: new_settings.clone() })
-            }
+            KindWithContent::SettingsUpdate { new_settings, .. } => Some(Details::SettingsUpdate {
+                settings: new_settings.clone(),
+                warnings: Vec::new(),
+            }),
             KindWithContent::IndexDeletion { .. } => None,
             KindWithContent::IndexCreation { primary_key, .. } => {
                 Some(Details::IndexInfo { primary_key: primary_key.clone() })
@@ -315,6 +419,23 @@ impl From<&KindWithContent> for Option<Details> {
                 Some(Details::IndexInfo { primary_key: primary_key.clone() })
             }
             KindWithContent::IndexSwap { .. } => None,
+            KindWithContent::IndexRestore { snapshot_path, snapshot_index_uid, .. } => {
+                Some(Details::IndexRestore {
+                    snapshot_path: snapshot_path.clone(),
+                    snapshot_index_uid: snapshot_index_uid.clone(),
+                    restored_documents: None,
+                })
+            }
+            KindWithContent::IntegrityCheck { repair, .. } => {
+                Some(Details::IntegrityCheck { repair: *repair, violations: None })
+            }
+            KindWithContent::IndexWarmup { prefixes, populate_cache, .. } => {
+                Some(Details::IndexWarmup {
+                    prefixes: prefixes.clone(),
+                    populate_cache: *populate_cache,
+                    warmed_entries: None,
+                })
+            }
             KindWithContent::TaskCancelation { query, tasks } => Some(Details::TaskCancelation {
                 matched_tasks: tasks.len(),
                 canceled_tasks: None,
@@ -327,6 +448,9 @@ impl From<&KindWithContent> for Option<Details> {
             }),
             KindWithContent::DumpCreation { .. } => Some(Details::Dump { dump_uid: None }),
             KindWithContent::SnapshotCreation => None,
+            KindWithContent::SnapshotImportIncremental => {
+                Some(Details::SnapshotImport { imported_indexes: None })
+            }
         }
     }
 }
@@ -404,6 +528,11 @@ pub enum Kind {
     TaskDeletion,
     DumpCreation,
     SnapshotCreation,
+    // Kinds are stored with bincode in the task queue, new ones must be appended.
+    IndexRestore,
+    IntegrityCheck,
+    SnapshotImportIncremental,
+    IndexWarmup,
 }
 
 impl Kind {
@@ -414,12 +543,16 @@ impl Kind {
             | Kind::SettingsUpdate
             | Kind::IndexCreation
             | Kind::IndexDeletion
-            | Kind::IndexUpdate => true,
+            | Kind::IndexUpdate
+            | Kind::IndexRestore
+            | Kind::IntegrityCheck
+            | Kind::IndexWarmup => true,
             Kind::IndexSwap
             | Kind::TaskCancelation
             | Kind::TaskDeletion
             | Kind::DumpCreation
-            | Kind::SnapshotCreation => false,
+            | Kind::SnapshotCreation
+            | Kind::SnapshotImportIncremental => false,
         }
     }
 }
@@ -433,10 +566,14 @@ impl Display for Kind {
             Kind::IndexDeletion => write!(f, "indexDeletion"),
             Kind::IndexUpdate => write!(f, "indexUpdate"),
             Kind::IndexSwap => write!(f, "indexSwap"),
+            Kind::IndexRestore => write!(f, "indexRestore"),
+            Kind::IntegrityCheck => write!(f, "integrityCheck"),
+            Kind::IndexWarmup => write!(f, "indexWarmup"),
             Kind::TaskCancelation => write!(f, "taskCancelation"),
             Kind::TaskDeletion => write!(f, "taskDeletion"),
             Kind::DumpCreation => write!(f, "dumpCreation"),
             Kind::SnapshotCreation => write!(f, "snapshotCreation"),
+            Kind::SnapshotImportIncremental => write!(f, "snapshotImportIncremental"),
         }
     }
 }
@@ -450,6 +587,12 @@ impl FromStr for Kind {
             Ok(Kind::IndexUpdate)
         } else if kind.eq_ignore_ascii_case("indexSwap") {
             Ok(Kind::IndexSwap)
+        } else if kind.eq_ignore_ascii_case("indexRestore") {
+            Ok(Kind::IndexRestore)
+        } else if kind.eq_ignore_ascii_case("integrityCheck") {
+            Ok(Kind::IntegrityCheck)
+        } else if kind.eq_ignore_ascii_case("indexWarmup") {
+            Ok(Kind::IndexWarmup)
         } else if kind.eq_ignore_ascii_case("indexDeletion") {
             Ok(Kind::IndexDeletion)
         } else if kind.eq_ignore_ascii_case("documentAdditionOrUpdate") {
@@ -466,6 +609,8 @@ impl FromStr for Kind {
             Ok(Kind::DumpCreation)
         } else if kind.eq_ignore_ascii_case("snapshotCreation") {
             Ok(Kind::SnapshotCreation)
+        } else if kind.eq_ignore_ascii_case("snapshotImportIncremental") {
+            Ok(Kind::SnapshotImportIncremental)
         } else {
             Err(ParseTaskKindError(kind.to_owned()))
         }
@@ -495,16 +640,83 @@ impl std::error::Error for ParseTaskKindError {}
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum Details {
-    DocumentAdditionOrUpdate { received_documents: u64, indexed_documents: Option<u64> },
-    SettingsUpdate { settings: Box<Settings<Unchecked>> },
-    IndexInfo { primary_key: Option<String> },
-    DocumentDeletion { provided_ids: usize, deleted_documents: Option<u64> },
-    DocumentDeletionByFilter { original_filter: String, deleted_documents: Option<u64> },
-    ClearAll { deleted_documents: Option<u64> },
-    TaskCancelation { matched_tasks: u64, canceled_tasks: Option<u64>, original_filter: String },
-    TaskDeletion { matched_tasks: u64, deleted_tasks: Option<u64>, original_filter: String },
-    Dump { dump_uid: Option<String> },
-    IndexSwap { swaps: Vec<IndexSwap> },
+    DocumentAdditionOrUpdate {
+        received_documents: u64,
+        indexed_documents: Option<u64>,
+        /// The documents sharing their primary key with an earlier document of the same payload.
+        #[serde(default, skip_serializing_if = "is_zero")]
+        duplicate_documents: u64,
+        /// The invalid UTF-8 sequences of the payload replaced with U+FFFD.
+        #[serde(default, skip_serializing_if = "is_zero")]
+        invalid_utf8_replacements: u64,
+        /// The distinct errors of the invalid documents of a refused payload, the first one being the error of the task.
+        ///
+        /// Only set when several documents were invalid.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        indexing_errors: Vec<String>,
+        /// How the facet databases of each filterable field were updated.
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        facet_update_strategies: BTreeMap<String, FacetUpdateStrategy>,
+    },
+    SettingsUpdate {
+        settings: Box<Settings<Unchecked>>,
+        /// Problems noticed while applying the settings that didn't prevent the task from succeeding.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        warnings: Vec<String>,
+    },
+    IndexInfo {
+        primary_key: Option<String>,
+    },
+    DocumentDeletion {
+        provided_ids: usize,
+        deleted_documents: Option<u64>,
+    },
+    DocumentDeletionByFilter {
+        original_filter: String,
+        deleted_documents: Option<u64>,
+    },
+    ClearAll {
+        deleted_documents: Option<u64>,
+    },
+    TaskCancelation {
+        matched_tasks: u64,
+        canceled_tasks: Option<u64>,
+        original_filter: String,
+    },
+    TaskDeletion {
+        matched_tasks: u64,
+        deleted_tasks: Option<u64>,
+        original_filter: String,
+    },
+    Dump {
+        dump_uid: Option<String>,
+    },
+    IndexSwap {
+        swaps: Vec<IndexSwap>,
+    },
+    IndexRestore {
+        snapshot_path: String,
+        snapshot_index_uid: String,
+        restored_documents: Option<u64>,
+    },
+    IntegrityCheck {
+        repair: bool,
+        /// The violations found by the check, `None` until the task is processed.
+        violations: Option<BTreeMap<IntegrityViolation, IntegrityViolationReport>>,
+    },
+    SnapshotImport {
+        imported_indexes: Option<u64>,
+    },
+    IndexWarmup {
+        prefixes: Vec<String>,
+        populate_cache: bool,
+        /// The number of entries of the databases read by the warm-up, `None` until the task is processed.
+        warmed_entries: Option<u64>,
+    },
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
 }
 
 impl Details {
@@ -521,10 +733,14 @@ impl Details {
             Self::ClearAll { deleted_documents } => *deleted_documents = Some(0),
             Self::TaskCancelation { canceled_tasks, .. } => *canceled_tasks = Some(0),
             Self::TaskDeletion { deleted_tasks, .. } => *deleted_tasks = Some(0),
+            Self::IndexRestore { restored_documents, .. } => *restored_documents = Some(0),
+            Self::SnapshotImport { imported_indexes } => *imported_indexes = Some(0),
+            Self::IndexWarmup { warmed_entries, .. } => *warmed_entries = Some(0),
             Self::SettingsUpdate { .. }
             | Self::IndexInfo { .. }
             | Self::Dump { .. }
-            | Self::IndexSwap { .. } => (),
+            | Self::IndexSwap { .. }
+            | Self::IntegrityCheck { .. } => (),
         }
 
         details
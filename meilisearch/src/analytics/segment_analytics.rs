@@ -1,6 +1,7 @@
 use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::fs;
 use std::mem::take;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -34,7 +35,7 @@ use crate::option::{
 use crate::routes::indexes::documents::UpdateDocumentsQuery;
 use crate::routes::indexes::facet_search::FacetSearchQuery;
 use crate::routes::tasks::TasksFilterQuery;
-use crate::routes::{create_all_stats, Stats};
+use crate::routes::{create_all_stats, Stats, VersionResponse};
 use crate::search::{
     FacetSearchResult, MatchingStrategy, SearchQuery, SearchQueryWithIndex, SearchResult,
     DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG,
@@ -256,8 +257,24 @@ struct Infos {
     experimental_logs_mode: LogMode,
     experimental_replication_parameters: bool,
     experimental_enable_logs_route: bool,
+    experimental_ranking_rules_override: bool,
     experimental_reduce_indexing_memory_usage: bool,
     experimental_max_number_of_batched_tasks: usize,
+    experimental_auto_cleanup_tasks: bool,
+    experimental_attributes_soft_limit: usize,
+    experimental_attributes_hard_limit: Option<usize>,
+    experimental_max_enqueued_tasks: Option<u64>,
+    experimental_max_task_attempts: u32,
+    experimental_redact_payload_errors: bool,
+    experimental_reenqueue_crashed_tasks: bool,
+    experimental_index_encryption: bool,
+    experimental_replica: bool,
+    experimental_replica_sync_interval_sec: u64,
+    experimental_ephemeral_storage: bool,
+    experimental_outbound_allowlist: bool,
+    experimental_outbound_allow_private_network: bool,
+    experimental_search_trace: bool,
+    experimental_max_buckets_per_rule: Option<usize>,
     gpu_enabled: bool,
     db_path: bool,
     import_dump: bool,
@@ -273,6 +290,8 @@ struct Infos {
     http_payload_size_limit: Byte,
     task_queue_webhook: bool,
     task_webhook_authorization_header: bool,
+    task_retention_days: Option<u64>,
+    update_file_retention_days: Option<u64>,
     log_level: String,
     max_indexing_memory: MaxMemory,
     max_indexing_threads: MaxThreads,
@@ -298,13 +317,38 @@ impl From<Opt> for Infos {
             experimental_logs_mode,
             experimental_replication_parameters,
             experimental_enable_logs_route,
+            experimental_logs_dir: _,
             experimental_reduce_indexing_memory_usage,
             experimental_max_number_of_batched_tasks,
+            experimental_auto_cleanup_tasks,
+            experimental_attributes_soft_limit,
+            experimental_attributes_hard_limit,
+            experimental_max_enqueued_tasks,
+            experimental_max_task_attempts,
+            experimental_redact_payload_errors,
+            experimental_reenqueue_crashed_tasks,
+            experimental_index_encryption_key,
+            experimental_replica_of,
+            experimental_replica_api_key: _,
+            experimental_replica_sync_interval_sec,
+            experimental_ephemeral_storage,
+            experimental_outbound_allowlist,
+            experimental_outbound_allow_private_network,
+            experimental_search_trace_dir,
+            experimental_search_trace_max_files: _,
+            experimental_query_preprocessor: _,
+            experimental_document_tasks_history_size: _,
+            experimental_max_buckets_per_rule,
+            experimental_recent_searches_size: _,
+            experimental_recent_searches_with_query: _,
+            experimental_ranking_rules_override,
             http_addr,
             master_key: _,
             env,
             task_webhook_url,
             task_webhook_authorization_header,
+            task_retention_days,
+            update_file_retention_days,
             max_index_size: _,
             max_task_db_size: _,
             http_payload_size_limit,
@@ -336,8 +380,12 @@ impl From<Opt> for Infos {
             ScheduleSnapshot::Enabled(interval) => Some(interval),
         };
 
-        let IndexerOpts { max_indexing_memory, max_indexing_threads, skip_index_budget: _ } =
-            indexer_options;
+        let IndexerOpts {
+            max_indexing_memory,
+            max_indexing_threads,
+            experimental_transform_chunk_size: _,
+            skip_index_budget: _,
+        } = indexer_options;
 
         // We're going to override every sensible information.
         // We consider information sensible if it contains a path, an address, or a key.
@@ -348,6 +396,7 @@ impl From<Opt> for Infos {
             experimental_logs_mode,
             experimental_replication_parameters,
             experimental_enable_logs_route,
+            experimental_ranking_rules_override,
             experimental_reduce_indexing_memory_usage,
             gpu_enabled: meilisearch_types::milli::vector::is_cuda_enabled(),
             db_path: db_path != PathBuf::from("./data.ms"),
@@ -363,8 +412,26 @@ impl From<Opt> for Infos {
             http_addr: http_addr != default_http_addr(),
             http_payload_size_limit,
             experimental_max_number_of_batched_tasks,
+            experimental_auto_cleanup_tasks,
+            experimental_attributes_soft_limit,
+            experimental_attributes_hard_limit,
+            experimental_max_enqueued_tasks,
+            experimental_max_task_attempts,
+            experimental_redact_payload_errors,
+            experimental_reenqueue_crashed_tasks,
+            experimental_index_encryption: experimental_index_encryption_key.is_some(),
+            experimental_replica: experimental_replica_of.is_some(),
+            experimental_replica_sync_interval_sec,
+            experimental_ephemeral_storage,
+            experimental_outbound_allowlist: !experimental_outbound_allowlist.is_empty(),
+            experimental_outbound_allow_private_network,
+            experimental_search_trace: experimental_search_trace_dir.is_some(),
+            experimental_max_buckets_per_rule: experimental_max_buckets_per_rule
+                .map(NonZeroUsize::get),
             task_queue_webhook: task_webhook_url.is_some(),
             task_webhook_authorization_header: task_webhook_authorization_header.is_some(),
+            task_retention_days,
+            update_file_retention_days,
             log_level: log_level.to_string(),
             max_indexing_memory,
             max_indexing_threads,
@@ -399,7 +466,7 @@ pub struct Segment {
 }
 
 impl Segment {
-    fn compute_traits(opt: &Opt, stats: Stats) -> Value {
+    fn compute_traits(opt: &Opt, stats: Stats, version: VersionResponse) -> Value {
         static FIRST_START_TIMESTAMP: Lazy<Instant> = Lazy::new(Instant::now);
         static SYSTEM: Lazy<Value> = Lazy::new(|| {
             let disks = Disks::new_with_refreshed_list();
@@ -428,6 +495,7 @@ impl Segment {
                 "documents_number": number_of_documents,
             },
             "infos": Infos::from(opt.clone()),
+            "version": version,
         })
     }
 
@@ -472,9 +540,13 @@ impl Segment {
         index_scheduler: Arc<IndexScheduler>,
         auth_controller: Arc<AuthController>,
     ) {
-        if let Ok(stats) =
-            create_all_stats(index_scheduler.into(), auth_controller.into(), &AuthFilter::default())
-        {
+        let features = index_scheduler.features().runtime_features();
+        if let Ok(stats) = create_all_stats(
+            index_scheduler.into(),
+            auth_controller.into(),
+            &AuthFilter::default(),
+            None,
+        ) {
             // Replace the version number with the prototype name if any.
             let version = if let Some(prototype) = build_info::DescribeResult::from_build()
                 .and_then(|describe| describe.as_prototype())
@@ -493,7 +565,7 @@ impl Segment {
                         },
                     })),
                     user: self.user.clone(),
-                    traits: Self::compute_traits(&self.opt, stats),
+                    traits: Self::compute_traits(&self.opt, stats, VersionResponse::new(features)),
                     ..Default::default()
                 })
                 .await;
@@ -606,6 +678,14 @@ pub struct SearchAggregator {
     // every time a search is done using attributes_to_search_on
     attributes_to_search_on_total_number_of_uses: usize,
 
+    // preset
+    // every time a search is done using a preset
+    preset_total_number_of_uses: usize,
+
+    // synonyms
+    // every time a search is done with synonyms
+    synonyms_total_number_of_uses: usize,
+
     // q
     // The maximum number of terms in a q request
     max_terms_number: usize,
@@ -626,12 +706,15 @@ pub struct SearchAggregator {
     max_limit: usize,
     max_offset: usize,
     finite_pagination: usize,
+    // every time a search is done with `searchAfter`
+    search_after_total_number_of_uses: usize,
 
     // formatting
     max_attributes_to_retrieve: usize,
     max_attributes_to_highlight: usize,
     highlight_pre_tag: bool,
     highlight_post_tag: bool,
+    highlight_typo_tags: bool,
     max_attributes_to_crop: usize,
     crop_marker: bool,
     show_matches_position: bool,
@@ -656,6 +739,7 @@ impl SearchAggregator {
             limit,
             page,
             hits_per_page,
+            search_after,
             attributes_to_retrieve: _,
             attributes_to_crop: _,
             crop_length,
@@ -663,15 +747,31 @@ impl SearchAggregator {
             show_matches_position,
             show_ranking_score,
             show_ranking_score_details,
+            show_processing_breakdown: _,
+            show_query_tokens: _,
+            show_search_metrics: _,
+            show_ranking_bucket_path: _,
+            ranking_score_threshold: _,
             filter,
             sort,
             facets: _,
+            facet_distribution_mode: _,
+            facet_approximation_threshold: _,
             highlight_pre_tag,
             highlight_post_tag,
+            highlight_typo_pre_tag,
+            highlight_typo_post_tag,
             crop_marker,
             matching_strategy,
+            tie_break: _,
+            distinct: _,
             attributes_to_search_on,
             hybrid,
+            preset,
+            synonyms,
+            typo_tolerance: _,
+            ranking_rules_override: _,
+            debug: _,
         } = query;
 
         let mut ret = Self::default();
@@ -715,6 +815,16 @@ impl SearchAggregator {
             ret.attributes_to_search_on_total_number_of_uses = 1;
         }
 
+        // preset
+        if preset.is_some() {
+            ret.preset_total_number_of_uses = 1;
+        }
+
+        // synonyms
+        if synonyms.is_some() {
+            ret.synonyms_total_number_of_uses = 1;
+        }
+
         if let Some(ref q) = q {
             ret.max_terms_number = q.split_whitespace().count();
         }
@@ -733,11 +843,16 @@ impl SearchAggregator {
             ret.max_offset = *offset;
             ret.finite_pagination = 0;
         }
+        if search_after.is_some() {
+            ret.search_after_total_number_of_uses = 1;
+        }
 
         ret.matching_strategy.insert(format!("{:?}", matching_strategy), 1);
 
         ret.highlight_pre_tag = *highlight_pre_tag != DEFAULT_HIGHLIGHT_PRE_TAG();
         ret.highlight_post_tag = *highlight_post_tag != DEFAULT_HIGHLIGHT_POST_TAG();
+        ret.highlight_typo_tags =
+            highlight_typo_pre_tag.is_some() || highlight_typo_post_tag.is_some();
         ret.crop_marker = *crop_marker != DEFAULT_CROP_MARKER();
         ret.crop_length = *crop_length != DEFAULT_CROP_LENGTH();
         ret.show_matches_position = *show_matches_position;
@@ -761,10 +876,19 @@ impl SearchAggregator {
             processing_time_ms,
             hits_info: _,
             semantic_hit_count: _,
+            processing_breakdown: _,
+            query_tokens: _,
+            metrics: _,
+            skipped_ranking_rules: _,
+            debug: _,
             facet_distribution: _,
             facet_stats: _,
+            facet_distribution_mode: _,
+            candidates: _,
             degraded,
+            query_truncated: _,
             used_negative_operator,
+            trace: _,
         } = result;
 
         self.total_succeeded = self.total_succeeded.saturating_add(1);
@@ -794,16 +918,20 @@ impl SearchAggregator {
             filter_total_number_of_criteria,
             used_syntax,
             attributes_to_search_on_total_number_of_uses,
+            preset_total_number_of_uses,
+            synonyms_total_number_of_uses,
             max_terms_number,
             max_vector_size,
             matching_strategy,
             max_limit,
             max_offset,
             finite_pagination,
+            search_after_total_number_of_uses,
             max_attributes_to_retrieve,
             max_attributes_to_highlight,
             highlight_pre_tag,
             highlight_post_tag,
+            highlight_typo_tags,
             max_attributes_to_crop,
             crop_marker,
             show_matches_position,
@@ -860,6 +988,14 @@ impl SearchAggregator {
             .attributes_to_search_on_total_number_of_uses
             .saturating_add(attributes_to_search_on_total_number_of_uses);
 
+        // preset
+        self.preset_total_number_of_uses =
+            self.preset_total_number_of_uses.saturating_add(preset_total_number_of_uses);
+
+        // synonyms
+        self.synonyms_total_number_of_uses =
+            self.synonyms_total_number_of_uses.saturating_add(synonyms_total_number_of_uses);
+
         // q
         self.max_terms_number = self.max_terms_number.max(max_terms_number);
 
@@ -873,6 +1009,9 @@ impl SearchAggregator {
         self.max_limit = self.max_limit.max(max_limit);
         self.max_offset = self.max_offset.max(max_offset);
         self.finite_pagination += finite_pagination;
+        self.search_after_total_number_of_uses = self
+            .search_after_total_number_of_uses
+            .saturating_add(search_after_total_number_of_uses);
 
         // formatting
         self.max_attributes_to_retrieve =
@@ -881,6 +1020,7 @@ impl SearchAggregator {
             self.max_attributes_to_highlight.max(max_attributes_to_highlight);
         self.highlight_pre_tag |= highlight_pre_tag;
         self.highlight_post_tag |= highlight_post_tag;
+        self.highlight_typo_tags |= highlight_typo_tags;
         self.max_attributes_to_crop = self.max_attributes_to_crop.max(max_attributes_to_crop);
         self.crop_marker |= crop_marker;
         self.show_matches_position |= show_matches_position;
@@ -918,16 +1058,20 @@ impl SearchAggregator {
             filter_total_number_of_criteria,
             used_syntax,
             attributes_to_search_on_total_number_of_uses,
+            preset_total_number_of_uses,
+            synonyms_total_number_of_uses,
             max_terms_number,
             max_vector_size,
             matching_strategy,
             max_limit,
             max_offset,
             finite_pagination,
+            search_after_total_number_of_uses,
             max_attributes_to_retrieve,
             max_attributes_to_highlight,
             highlight_pre_tag,
             highlight_post_tag,
+            highlight_typo_tags,
             max_attributes_to_crop,
             crop_marker,
             show_matches_position,
@@ -976,6 +1120,12 @@ impl SearchAggregator {
                 "attributes_to_search_on": {
                    "total_number_of_uses": attributes_to_search_on_total_number_of_uses,
                 },
+                "preset": {
+                   "total_number_of_uses": preset_total_number_of_uses,
+                },
+                "synonyms": {
+                   "total_number_of_uses": synonyms_total_number_of_uses,
+                },
                 "q": {
                    "max_terms_number": max_terms_number,
                 },
@@ -991,12 +1141,14 @@ impl SearchAggregator {
                    "max_limit": max_limit,
                    "max_offset": max_offset,
                    "most_used_navigation": if finite_pagination > (total_received / 2) { "exhaustive" } else { "estimated" },
+                   "search_after_total_number_of_uses": search_after_total_number_of_uses,
                 },
                 "formatting": {
                     "max_attributes_to_retrieve": max_attributes_to_retrieve,
                     "max_attributes_to_highlight": max_attributes_to_highlight,
                     "highlight_pre_tag": highlight_pre_tag,
                     "highlight_post_tag": highlight_post_tag,
+                    "highlight_typo_tags": highlight_typo_tags,
                     "max_attributes_to_crop": max_attributes_to_crop,
                     "crop_marker": crop_marker,
                     "show_matches_position": show_matches_position,
@@ -1067,22 +1219,39 @@ impl MultiSearchAggregator {
                     limit: _,
                     page: _,
                     hits_per_page: _,
+                    search_after: _,
                     attributes_to_retrieve: _,
                     attributes_to_crop: _,
                     crop_length: _,
                     attributes_to_highlight: _,
                     show_ranking_score: _,
                     show_ranking_score_details: _,
+                    ranking_score_threshold: _,
+                    show_processing_breakdown: _,
+                    show_query_tokens: _,
+                    show_search_metrics: _,
+                    show_ranking_bucket_path: _,
                     show_matches_position: _,
                     filter: _,
                     sort: _,
                     facets: _,
+                    facet_distribution_mode: _,
+                    facet_approximation_threshold: _,
                     highlight_pre_tag: _,
                     highlight_post_tag: _,
+                    highlight_typo_pre_tag: _,
+                    highlight_typo_post_tag: _,
                     crop_marker: _,
                     matching_strategy: _,
+                    tie_break: _,
+                    distinct: _,
                     attributes_to_search_on: _,
+                    preset: _,
                     hybrid: _,
+                    synonyms: _,
+                    typo_tolerance: _,
+                    ranking_rules_override: _,
+                    federation_options: _,
                 } = query;
 
                 index_uid.as_str()
@@ -1250,7 +1419,8 @@ impl FacetSearchAggregator {
     }
 
     pub fn succeed(&mut self, result: &FacetSearchResult) {
-        let FacetSearchResult { facet_hits: _, facet_query: _, processing_time_ms } = result;
+        let FacetSearchResult { facet_hits: _, facet_query: _, truncated: _, processing_time_ms } =
+            result;
         self.total_succeeded = self.total_succeeded.saturating_add(1);
         self.time_spent.push(*processing_time_ms as usize);
     }
@@ -1357,7 +1527,7 @@ impl DocumentsAggregator {
         index_creation: bool,
         request: &HttpRequest,
     ) -> Self {
-        let UpdateDocumentsQuery { primary_key, csv_delimiter: _ } = documents_query;
+        let UpdateDocumentsQuery { primary_key, csv_delimiter: _, lossy_utf8: _ } = documents_query;
 
         let mut primary_keys = HashSet::new();
         if let Some(primary_key) = primary_key.clone() {
@@ -1514,6 +1684,7 @@ pub struct TasksAggregator {
 
     filtered_by_uid: bool,
     filtered_by_index_uid: bool,
+    filtered_by_index_group: bool,
     filtered_by_type: bool,
     filtered_by_status: bool,
     filtered_by_canceled_by: bool,
@@ -1533,6 +1704,7 @@ impl TasksAggregator {
             from: _,
             uids,
             index_uids,
+            index_groups,
             types,
             statuses,
             canceled_by,
@@ -1549,6 +1721,7 @@ impl TasksAggregator {
             user_agents: extract_user_agents(request).into_iter().collect(),
             filtered_by_uid: uids.is_some(),
             filtered_by_index_uid: index_uids.is_some(),
+            filtered_by_index_group: index_groups.is_some(),
             filtered_by_type: types.is_some(),
             filtered_by_status: statuses.is_some(),
             filtered_by_canceled_by: canceled_by.is_some(),
@@ -1570,6 +1743,7 @@ impl TasksAggregator {
             total_received,
             filtered_by_uid,
             filtered_by_index_uid,
+            filtered_by_index_group,
             filtered_by_type,
             filtered_by_status,
             filtered_by_canceled_by,
@@ -1592,6 +1766,7 @@ impl TasksAggregator {
 
         self.filtered_by_uid |= filtered_by_uid;
         self.filtered_by_index_uid |= filtered_by_index_uid;
+        self.filtered_by_index_group |= filtered_by_index_group;
         self.filtered_by_type |= filtered_by_type;
         self.filtered_by_status |= filtered_by_status;
         self.filtered_by_canceled_by |= filtered_by_canceled_by;
@@ -12,8 +12,12 @@ pub enum MeilisearchHttpError {
     #[error("A Content-Type header is missing. Accepted values for the Content-Type header are: {}",
             .0.iter().map(|s| format!("`{}`", s)).collect::<Vec<_>>().join(", "))]
     MissingContentType(Vec<String>),
-    #[error("The `/logs/stream` route is currently in use by someone else.")]
-    AlreadyUsedLogRoute,
+    #[error("The `/logs/stream` route is currently in use by someone else. Send `\"force\": true` to close their stream and open a new one.")]
+    LogStreamAlreadyExists,
+    #[error("There is no stream on the `/logs/stream` route.")]
+    LogStreamNotFound,
+    #[error("The logs can't be written into `{0}`: {1}.")]
+    InvalidLogFile(String, String),
     #[error("The Content-Type `{0}` does not support the use of a csv delimiter. The csv delimiter can only be used with the Content-Type `text/csv`.")]
     CsvDelimiterWithWrongContentType(String),
     #[error(
@@ -23,10 +27,16 @@ pub enum MeilisearchHttpError {
     InvalidContentType(String, Vec<String>),
     #[error("Document `{0}` not found.")]
     DocumentNotFound(String),
+    #[error("Preset `{0}` not found.")]
+    PresetNotFound(String),
     #[error("Sending an empty filter is forbidden.")]
     EmptyFilter,
     #[error("Invalid syntax for the filter parameter: `expected {}, found: {1}`.", .0.join(", "))]
     InvalidExpression(&'static [&'static str], Value),
+    #[error(
+        "The filter of the search rules of the tenant token for the index `{0}` is invalid: {1}"
+    )]
+    InvalidSearchRulesFilter(String, String),
     #[error("A {0} payload is missing.")]
     MissingPayload(PayloadType),
     #[error("Too many search requests running at the same time: {0}. Retry after 10s.")]
@@ -35,6 +45,10 @@ pub enum MeilisearchHttpError {
     SearchLimiterIsDown,
     #[error("The provided payload reached the size limit. The maximum accepted payload size is {}.",  Byte::from_bytes(*.0 as u64).get_appropriate_unit(true))]
     PayloadTooLarge(usize),
+    #[error("The payload does not match its `X-Content-Sha256` header: its SHA-256 digest is `{computed}` but `{expected}` was expected.")]
+    PayloadChecksumMismatch { expected: String, computed: String },
+    #[error("The settings of the index were modified since they were read: the `If-Match` header is `{expected}` but their current ETag is `{current}`.")]
+    SettingsVersionMismatch { expected: String, current: String },
     #[error("Two indexes must be given for each swap. The list `[{}]` contains {} indexes.",
         .0.iter().map(|uid| format!("\"{uid}\"")).collect::<Vec<_>>().join(", "), .0.len()
     )]
@@ -59,20 +73,51 @@ pub enum MeilisearchHttpError {
     Join(#[from] JoinError),
     #[error("Invalid request: missing `hybrid` parameter when both `q` and `vector` are present.")]
     MissingSearchHybrid,
+    #[error("Invalid value at `.synonyms`: the search synonyms contain {0} synonyms but at most {1} synonyms can be given.")]
+    TooManySearchSynonyms(usize, usize),
+    #[error("Invalid value type at `.searchAfter`: expected a document id, a string or an integer, but found `{0}`.")]
+    InvalidSearchAfterType(Value),
+    #[error("Invalid value at `.searchAfter`: `searchAfter` cannot be used with {0}.")]
+    SearchAfterWith(&'static str),
+    #[error("Invalid value at `.{0}`: the pagination of a federated search is set with `federation.offset` and `federation.limit`, not in its queries.")]
+    FederatedQueryPagination(&'static str),
+    #[error("Invalid value at `.facets`: the facets of a federated search are computed for each index with `federation.facetsByIndex`, they can't be requested in its queries.")]
+    FederatedQueryFacets,
+    #[error("Invalid value at `.federation.facetsByIndex.{0}`: the index `{0}` is not searched by any query of the federated search.")]
+    FederatedFacetsIndexNotSearched(String),
+    #[error("Invalid value at `.federationOptions`: the `federationOptions` of a query can only be set when the `federation` of the request is set.")]
+    FederationOptionsWithoutFederation,
+    #[error("The provided API key is not allowed to use the `debug` search parameter, it requires the `search.debug` action.")]
+    SearchDebugNotAllowed,
+    #[error("The settings {} can't be previewed as they only apply once the documents are reindexed. Only the `rankingRules`, the `searchableAttributes` that are already searchable, and the `enabled` and `minWordSizeForTypos` fields of the `typoTolerance` can be previewed.",
+            .0.iter().map(|s| format!("`{}`", s)).collect::<Vec<_>>().join(", "))]
+    SettingsNotPreviewable(Vec<String>),
+    #[error("Invalid value at `.search`: only the keyword searches can be explained, the search can't be semantic or hybrid.")]
+    SearchNotExplainable,
+    #[error("Only the keyword searches can be streamed as newline-delimited JSON, the search can't be semantic or hybrid.")]
+    SearchNotStreamable,
+    #[error("Invalid value at `.rankingRulesOverride`: {0}")]
+    InvalidRankingRulesOverride(String),
 }
 
 impl ErrorCode for MeilisearchHttpError {
     fn error_code(&self) -> Code {
         match self {
             MeilisearchHttpError::MissingContentType(_) => Code::MissingContentType,
-            MeilisearchHttpError::AlreadyUsedLogRoute => Code::BadRequest,
+            MeilisearchHttpError::LogStreamAlreadyExists => Code::LogStreamAlreadyExists,
+            MeilisearchHttpError::LogStreamNotFound => Code::LogStreamNotFound,
+            MeilisearchHttpError::InvalidLogFile(_, _) => Code::InvalidLogFile,
             MeilisearchHttpError::CsvDelimiterWithWrongContentType(_) => Code::InvalidContentType,
             MeilisearchHttpError::MissingPayload(_) => Code::MissingPayload,
             MeilisearchHttpError::InvalidContentType(_, _) => Code::InvalidContentType,
             MeilisearchHttpError::DocumentNotFound(_) => Code::DocumentNotFound,
+            MeilisearchHttpError::PresetNotFound(_) => Code::PresetNotFound,
             MeilisearchHttpError::EmptyFilter => Code::InvalidDocumentFilter,
             MeilisearchHttpError::InvalidExpression(_, _) => Code::InvalidSearchFilter,
+            MeilisearchHttpError::InvalidSearchRulesFilter(_, _) => Code::InvalidSearchFilter,
             MeilisearchHttpError::PayloadTooLarge(_) => Code::PayloadTooLarge,
+            MeilisearchHttpError::PayloadChecksumMismatch { .. } => Code::PayloadChecksumMismatch,
+            MeilisearchHttpError::SettingsVersionMismatch { .. } => Code::SettingsVersionMismatch,
             MeilisearchHttpError::TooManySearchRequests(_) => Code::TooManySearchRequests,
             MeilisearchHttpError::SearchLimiterIsDown => Code::Internal,
             MeilisearchHttpError::SwapIndexPayloadWrongLength(_) => Code::InvalidSwapIndexes,
@@ -86,6 +131,31 @@ impl ErrorCode for MeilisearchHttpError {
             MeilisearchHttpError::DocumentFormat(e) => e.error_code(),
             MeilisearchHttpError::Join(_) => Code::Internal,
             MeilisearchHttpError::MissingSearchHybrid => Code::MissingSearchHybrid,
+            MeilisearchHttpError::TooManySearchSynonyms(_, _) => Code::InvalidSearchSynonyms,
+            MeilisearchHttpError::InvalidSearchAfterType(_)
+            | MeilisearchHttpError::SearchAfterWith(_) => Code::InvalidSearchSearchAfter,
+            MeilisearchHttpError::FederatedQueryPagination(_) => {
+                Code::InvalidMultiSearchQueryPagination
+            }
+            MeilisearchHttpError::FederatedQueryFacets => Code::InvalidMultiSearchQueryFacets,
+            MeilisearchHttpError::FederatedFacetsIndexNotSearched(_) => {
+                Code::InvalidMultiSearchFacetsByIndex
+            }
+            MeilisearchHttpError::FederationOptionsWithoutFederation => {
+                Code::InvalidMultiSearchFederationOptions
+            }
+            MeilisearchHttpError::SearchDebugNotAllowed => Code::InvalidApiKey,
+            MeilisearchHttpError::SettingsNotPreviewable(_) => Code::InvalidSearchPreviewSettings,
+            MeilisearchHttpError::SearchNotExplainable => Code::InvalidSearchExplain,
+            MeilisearchHttpError::SearchNotStreamable => Code::InvalidSearchStreaming,
+            MeilisearchHttpError::InvalidRankingRulesOverride(_) => Code::InvalidSearchRankingRules,
+        }
+    }
+
+    fn retry_after(&self) -> Option<u64> {
+        match self {
+            MeilisearchHttpError::IndexScheduler(e) => e.retry_after(),
+            _ => None,
         }
     }
 }
@@ -1,17 +1,67 @@
+use std::cell::RefCell;
 use std::pin::Pin;
+use std::rc::Rc;
 use std::task::{Context, Poll};
 
 use actix_http::encoding::Decoder as Decompress;
+use actix_web::error::PayloadError;
 use actix_web::{dev, web, FromRequest, HttpRequest};
 use futures::future::{ready, Ready};
 use futures::Stream;
+use sha2::{Digest, Sha256};
 
 use crate::error::MeilisearchHttpError;
 
+/// The header giving the SHA-256 digest, in hexadecimal, of the payload as it is sent,
+/// i.e. before it is decompressed.
+pub const CONTENT_SHA256_HEADER: &str = "X-Content-Sha256";
+
 pub struct Payload {
-    payload: Decompress<dev::Payload>,
+    payload: Decompress<HashedPayload>,
     limit: usize,
     remaining: usize,
+    checksum: Option<Checksum>,
+}
+
+struct Checksum {
+    expected: String,
+    hasher: Rc<RefCell<Sha256>>,
+}
+
+/// The payload as it is received, hashing its bytes when a checksum is given.
+struct HashedPayload {
+    payload: dev::Payload,
+    hasher: Option<Rc<RefCell<Sha256>>>,
+}
+
+impl Stream for HashedPayload {
+    type Item = Result<web::Bytes, PayloadError>;
+
+    #[inline]
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = Pin::new(&mut self.payload).poll_next(cx);
+        if let (Poll::Ready(Some(Ok(bytes))), Some(hasher)) = (&poll, &self.hasher) {
+            hasher.borrow_mut().update(bytes);
+        }
+        poll
+    }
+}
+
+impl Payload {
+    /// Checks the digest of the bytes received so far against the one given in the
+    /// [`CONTENT_SHA256_HEADER`] header, if any. It must be called once the payload is consumed.
+    pub fn verify_checksum(&self) -> Result<(), MeilisearchHttpError> {
+        let Some(Checksum { expected, hasher }) = &self.checksum else { return Ok(()) };
+        let computed = format!("{:x}", hasher.borrow().clone().finalize());
+        if computed == *expected {
+            Ok(())
+        } else {
+            Err(MeilisearchHttpError::PayloadChecksumMismatch {
+                expected: expected.clone(),
+                computed,
+            })
+        }
+    }
 }
 
 pub struct PayloadConfig {
@@ -41,10 +91,19 @@ impl FromRequest for Payload {
             .app_data::<PayloadConfig>()
             .map(|c| c.limit)
             .unwrap_or(PayloadConfig::default().limit);
+        let checksum = req.headers().get(CONTENT_SHA256_HEADER).map(|value| Checksum {
+            expected: String::from_utf8_lossy(value.as_bytes()).trim().to_ascii_lowercase(),
+            hasher: Rc::default(),
+        });
+        let payload = HashedPayload {
+            payload: payload.take(),
+            hasher: checksum.as_ref().map(|checksum| checksum.hasher.clone()),
+        };
         ready(Ok(Payload {
-            payload: Decompress::from_headers(payload.take(), req.headers()),
+            payload: Decompress::from_headers(payload, req.headers()),
             limit,
             remaining: limit,
+            checksum,
         }))
     }
 }
@@ -96,7 +155,11 @@ mod tests {
         let payload = Payload {
             limit: 3,
             remaining: 3,
-            payload: Decompress::new(actix_payload, actix_http::ContentEncoding::Identity),
+            payload: Decompress::new(
+                HashedPayload { payload: actix_payload, hasher: None },
+                actix_http::ContentEncoding::Identity,
+            ),
+            checksum: None,
         };
 
         let mut enumerated_payload_stream = payload.enumerate();
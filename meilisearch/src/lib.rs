@@ -7,16 +7,22 @@ pub mod extractors;
 pub mod metrics;
 pub mod middleware;
 pub mod option;
+pub mod query_preprocessor;
+pub mod recent_searches;
+pub mod relevance_check;
 pub mod routes;
 pub mod search;
+pub mod search_latency;
 pub mod search_queue;
+pub mod search_trace;
+pub mod tls;
 
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufReader, BufWriter};
-use std::num::NonZeroUsize;
 use std::path::Path;
 use std::sync::Arc;
-use std::thread::{self, available_parallelism};
+use std::thread;
 use std::time::Duration;
 
 use actix_cors::Cors;
@@ -30,17 +36,24 @@ use anyhow::bail;
 use error::PayloadError;
 use extractors::payload::PayloadConfig;
 use http::header::CONTENT_TYPE;
-use index_scheduler::{IndexScheduler, IndexSchedulerOptions};
+use index_scheduler::{IndexScheduler, IndexSchedulerOptions, DEFAULT_RETENTION_INTERVAL};
 use meilisearch_auth::AuthController;
+use meilisearch_types::index_uid::{sanitize_index_uid, validate_index_uid, MAX_INDEX_UID_LENGTH};
+use meilisearch_types::index_uid_pattern::IndexUidPattern;
 use meilisearch_types::milli::documents::{DocumentsBatchBuilder, DocumentsBatchReader};
-use meilisearch_types::milli::update::{IndexDocumentsConfig, IndexDocumentsMethod};
+use meilisearch_types::milli::update::{IndexDocumentsConfig, IndexDocumentsMethod, IndexerConfig};
 use meilisearch_types::settings::apply_settings_to_builder;
 use meilisearch_types::tasks::KindWithContent;
 use meilisearch_types::versioning::{check_version_file, create_version_file};
 use meilisearch_types::{compression, milli, VERSION_FILE_NAME};
 pub use option::Opt;
 use option::ScheduleSnapshot;
+use query_preprocessor::QueryPreprocessor;
+use recent_searches::RecentSearches;
+use routes::indexes::related_terms::RelatedTermsCache;
+use search_latency::SearchLatencies;
 use search_queue::SearchQueue;
+use search_trace::SearchTraces;
 use tracing::{error, info_span};
 use tracing_subscriber::filter::Targets;
 
@@ -114,12 +127,26 @@ pub type LogStderrType = tracing_subscriber::filter::Filtered<
     SubscriberForSecondLayer,
 >;
 
+/// The stream served by the `/logs/stream` route. Like the `LogRouteHandle`, it must be shared
+/// by all the workers so that only one stream is served at a time.
+#[derive(Debug, Default)]
+pub struct LogStreamState {
+    /// The id of the current stream, `None` if there is no stream.
+    pub(crate) active: std::sync::Mutex<Option<u64>>,
+    pub(crate) next_id: std::sync::atomic::AtomicU64,
+    /// The file the `/logs/stderr` route writes the logs into instead of stderr, if any.
+    pub(crate) file: std::sync::Mutex<Option<routes::logs::LogFile>>,
+}
+
 pub fn create_app(
     index_scheduler: Data<IndexScheduler>,
     auth_controller: Data<AuthController>,
+    search_queue: Data<SearchQueue>,
+    search_latencies: Data<SearchLatencies>,
     opt: Opt,
-    logs: (LogRouteHandle, LogStderrHandle),
+    logs: (LogRouteHandle, LogStderrHandle, Arc<LogStreamState>),
     analytics: Arc<dyn Analytics>,
+    query_preprocessor: Arc<dyn QueryPreprocessor>,
     enable_dashboard: bool,
 ) -> actix_web::App<
     impl ServiceFactory<
@@ -136,9 +163,12 @@ pub fn create_app(
                 s,
                 index_scheduler.clone(),
                 auth_controller.clone(),
+                search_queue.clone(),
+                search_latencies.clone(),
                 &opt,
                 logs,
                 analytics.clone(),
+                query_preprocessor.clone(),
             )
         })
         .configure(routes::configure)
@@ -201,7 +231,25 @@ enum OnFailure {
     KeepDb,
 }
 
+/// Moves the database of an instance started with `--experimental-ephemeral-storage` to a
+/// temporary directory, on a tmpfs when `/dev/shm` is available.
+///
+/// The directory is deleted when the returned value is dropped.
+pub fn use_ephemeral_db_path(opt: &mut Opt) -> anyhow::Result<tempfile::TempDir> {
+    let mut builder = tempfile::Builder::new();
+    builder.prefix("meilisearch-");
+    let shm = Path::new("/dev/shm");
+    let dir = match shm.is_dir().then(|| builder.tempdir_in(shm)) {
+        Some(Ok(dir)) => dir,
+        // the tmpfs is not writable, let's use the default temporary directory
+        Some(Err(_)) | None => builder.tempdir()?,
+    };
+    opt.db_path = dir.path().join("data.ms");
+    Ok(dir)
+}
+
 pub fn setup_meilisearch(opt: &Opt) -> anyhow::Result<(Arc<IndexScheduler>, Arc<AuthController>)> {
+    search::set_max_buckets_per_rule(opt.experimental_max_buckets_per_rule);
     let empty_db = is_empty_db(&opt.db_path);
     let (index_scheduler, auth_controller) = if let Some(ref snapshot_path) = opt.import_snapshot {
         let snapshot_path_exists = snapshot_path.exists();
@@ -258,6 +306,9 @@ pub fn setup_meilisearch(opt: &Opt) -> anyhow::Result<(Arc<IndexScheduler>, Arc<
         open_or_create_database(opt, empty_db)?
     };
 
+    // The keys resolve their index groups through the auth controller.
+    auth_controller.set_index_groups(index_scheduler.index_groups()?);
+
     // We create a loop in a thread that registers snapshotCreation tasks
     let index_scheduler = Arc::new(index_scheduler);
     let auth_controller = Arc::new(auth_controller);
@@ -277,6 +328,25 @@ pub fn setup_meilisearch(opt: &Opt) -> anyhow::Result<(Arc<IndexScheduler>, Arc<
             .unwrap();
     }
 
+    // And another one that registers the snapshot imports of a read-only replica
+    if opt.experimental_replica_of.is_some() {
+        let sync_interval = Duration::from_secs(opt.experimental_replica_sync_interval_sec);
+        let index_scheduler = index_scheduler.clone();
+        thread::Builder::new()
+            .name(String::from("register-snapshot-import-tasks"))
+            .spawn(move || loop {
+                thread::sleep(sync_interval);
+                if let Err(e) = index_scheduler.register(
+                    KindWithContent::SnapshotImportIncremental,
+                    None,
+                    false,
+                ) {
+                    error!("Error while registering snapshot import: {}", e);
+                }
+            })
+            .unwrap();
+    }
+
     Ok((index_scheduler, auth_controller))
 }
 
@@ -300,13 +370,36 @@ fn open_or_create_database_unchecked(
             dumps_path: opt.dump_dir.clone(),
             webhook_url: opt.task_webhook_url.as_ref().map(|url| url.to_string()),
             webhook_authorization_header: opt.task_webhook_authorization_header.clone(),
+            replica_primary_url: opt.experimental_replica_of.as_ref().map(|url| url.to_string()),
+            replica_primary_api_key: opt.experimental_replica_api_key.clone(),
             task_db_size: opt.max_task_db_size.get_bytes() as usize,
             index_base_map_size: opt.max_index_size.get_bytes() as usize,
             enable_mdb_writemap: opt.experimental_reduce_indexing_memory_usage,
-            indexer_config: (&opt.indexer_options).try_into()?,
+            indexer_config: IndexerConfig {
+                ephemeral_storage: opt.experimental_ephemeral_storage,
+                outbound_policy: opt.outbound_policy(),
+                ..(&opt.indexer_options).try_into()?
+            },
             autobatching_enabled: true,
             cleanup_enabled: !opt.experimental_replication_parameters,
             max_number_of_tasks: 1_000_000,
+            auto_cleanup_tasks: opt.experimental_auto_cleanup_tasks,
+            attributes_soft_limit: opt.experimental_attributes_soft_limit,
+            attributes_hard_limit: opt.experimental_attributes_hard_limit,
+            max_enqueued_tasks: opt.experimental_max_enqueued_tasks,
+            max_task_attempts: opt.experimental_max_task_attempts,
+            redact_payload_errors: opt.experimental_redact_payload_errors,
+            reenqueue_crashed_tasks: opt.experimental_reenqueue_crashed_tasks,
+            document_tasks_history_size: opt.experimental_document_tasks_history_size,
+            recent_searches_size: opt.experimental_recent_searches_size,
+            encryption_key: opt
+                .experimental_index_encryption_key
+                .as_deref()
+                .map(str::parse::<milli::EncryptionKey>)
+                .transpose()?,
+            task_retention: opt.task_retention_days.map(days_to_duration),
+            update_file_retention: opt.update_file_retention_days.map(days_to_duration),
+            retention_interval: DEFAULT_RETENTION_INTERVAL,
             max_number_of_batched_tasks: opt.experimental_max_number_of_batched_tasks,
             index_growth_amount: byte_unit::Byte::from_str("10GiB").unwrap().get_bytes() as usize,
             index_count: DEFAULT_INDEX_COUNT,
@@ -329,6 +422,10 @@ fn open_or_create_database_unchecked(
     }
 }
 
+fn days_to_duration(days: u64) -> Duration {
+    Duration::from_secs(days.saturating_mul(24 * 60 * 60))
+}
+
 /// Ensure you're in a valid state and open the IndexScheduler + AuthController for you.
 fn open_or_create_database(
     opt: &Opt,
@@ -371,11 +468,29 @@ fn import_dump(
         let _ = std::fs::write(db_path.join("instance-uid"), instance_uid.to_string().as_bytes());
     };
 
+    // Older versions accepted index uids that are not valid anymore, these indexes are renamed
+    // and the keys and tasks referring to them follow.
+    let index_uids = dump_reader
+        .indexes()?
+        .map(|index_reader| Ok(index_reader?.metadata().uid.clone()))
+        .collect::<Result<Vec<_>, dump::Error>>()?;
+    let renamed_indexes = rename_invalid_index_uids(&index_uids);
+    for (uid, new_uid) in &renamed_indexes {
+        tracing::warn!(
+            "The index uid `{uid}` is not valid anymore, the index is imported as `{new_uid}`."
+        );
+    }
+
     // 2. Import the `Key`s.
     let mut keys = Vec::new();
     auth.raw_delete_all_keys()?;
     for key in dump_reader.keys()? {
-        let key = key?;
+        let mut key = key?;
+        for pattern in key.indexes.iter_mut() {
+            if let Some(new_uid) = renamed_indexes.get(&**pattern) {
+                *pattern = IndexUidPattern::new_unchecked(new_uid);
+            }
+        }
         auth.raw_insert_key(key.clone())?;
         keys.push(key);
     }
@@ -393,10 +508,11 @@ fn import_dump(
     for index_reader in dump_reader.indexes()? {
         let mut index_reader = index_reader?;
         let metadata = index_reader.metadata();
-        tracing::info!("Importing index `{}`.", metadata.uid);
+        let uid = renamed_indexes.get(&metadata.uid).unwrap_or(&metadata.uid);
+        tracing::info!("Importing index `{}`.", uid);
 
         let date = Some((metadata.created_at, metadata.updated_at));
-        let index = index_scheduler.create_raw_index(&metadata.uid, date)?;
+        let index = index_scheduler.create_raw_index(uid, date)?;
 
         let mut wtxn = index.write_txn()?;
 
@@ -458,32 +574,62 @@ fn import_dump(
 
     // 5. Import the tasks.
     for ret in dump_reader.tasks()? {
-        let (task, file) = ret?;
+        let (mut task, file) = ret?;
+        if let Some(new_uid) = task.index_uid.as_ref().and_then(|uid| renamed_indexes.get(uid)) {
+            task.index_uid = Some(new_uid.clone());
+        }
         index_scheduler_dump.register_dumped_task(task, file)?;
     }
     Ok(index_scheduler_dump.finish()?)
 }
 
+/// Returns the new uid of the indexes whose uid is not valid, by sanitizing it
+/// and suffixing it with a number when it is already used by another index.
+fn rename_invalid_index_uids(uids: &[String]) -> HashMap<String, String> {
+    let used: HashSet<&str> =
+        uids.iter().map(String::as_str).filter(|uid| validate_index_uid(uid).is_ok()).collect();
+    let mut renamed = HashMap::new();
+    for uid in uids.iter().filter(|uid| validate_index_uid(uid).is_err()) {
+        let sanitized = sanitize_index_uid(uid).unwrap_or_else(|| "index".to_string());
+        let mut new_uid = sanitized.clone();
+        let mut suffix = 0;
+        while used.contains(new_uid.as_str()) || renamed.values().any(|other| other == &new_uid) {
+            suffix += 1;
+            let suffix = format!("_{suffix}");
+            // the sanitized uid only contains ascii characters
+            let prefix_len = sanitized.len().min(MAX_INDEX_UID_LENGTH - suffix.len());
+            new_uid = format!("{}{suffix}", &sanitized[..prefix_len]);
+        }
+        renamed.insert(uid.clone(), new_uid);
+    }
+    renamed
+}
+
 pub fn configure_data(
     config: &mut web::ServiceConfig,
     index_scheduler: Data<IndexScheduler>,
     auth: Data<AuthController>,
+    search_queue: Data<SearchQueue>,
+    search_latencies: Data<SearchLatencies>,
     opt: &Opt,
-    (logs_route, logs_stderr): (LogRouteHandle, LogStderrHandle),
+    (logs_route, logs_stderr, logs_stream): (LogRouteHandle, LogStderrHandle, Arc<LogStreamState>),
     analytics: Arc<dyn Analytics>,
+    query_preprocessor: Arc<dyn QueryPreprocessor>,
 ) {
-    let search_queue = SearchQueue::new(
-        opt.experimental_search_queue_size,
-        available_parallelism().unwrap_or(NonZeroUsize::new(2).unwrap()),
-    );
     let http_payload_size_limit = opt.http_payload_size_limit.get_bytes() as usize;
     config
         .app_data(index_scheduler)
         .app_data(auth)
-        .app_data(web::Data::new(search_queue))
+        .app_data(search_queue)
+        .app_data(search_latencies)
+        .app_data(web::Data::new(SearchTraces::new(opt)))
+        .app_data(web::Data::new(RecentSearches::new(opt)))
+        .app_data(web::Data::new(RelatedTermsCache::default()))
         .app_data(web::Data::from(analytics))
+        .app_data(web::Data::from(query_preprocessor))
         .app_data(web::Data::new(logs_route))
         .app_data(web::Data::new(logs_stderr))
+        .app_data(web::Data::from(logs_stream))
         .app_data(web::Data::new(opt.clone()))
         .app_data(
             web::JsonConfig::default()
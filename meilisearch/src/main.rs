@@ -1,8 +1,10 @@
 use std::env;
 use std::io::{stderr, LineWriter, Write};
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::thread::available_parallelism;
 
 use actix_web::http::KeepAlive;
 use actix_web::web::Data;
@@ -11,9 +13,11 @@ use index_scheduler::IndexScheduler;
 use is_terminal::IsTerminal;
 use meilisearch::analytics::Analytics;
 use meilisearch::option::LogMode;
+use meilisearch::search_latency::SearchLatencies;
+use meilisearch::search_queue::SearchQueue;
 use meilisearch::{
-    analytics, create_app, setup_meilisearch, LogRouteHandle, LogRouteType, LogStderrHandle,
-    LogStderrType, Opt, SubscriberForSecondLayer,
+    analytics, create_app, setup_meilisearch, use_ephemeral_db_path, LogRouteHandle, LogRouteType,
+    LogStderrHandle, LogStderrType, LogStreamState, Opt, SubscriberForSecondLayer,
 };
 use meilisearch_auth::{generate_master_key, AuthController, MASTER_KEY_MIN_SIZE};
 use mimalloc::MiMalloc;
@@ -72,7 +76,7 @@ fn on_panic(info: &std::panic::PanicInfo) {
 
 #[actix_web::main]
 async fn main() -> anyhow::Result<()> {
-    let (opt, config_read_from) = Opt::try_build()?;
+    let (mut opt, config_read_from) = Opt::try_build()?;
 
     std::panic::set_hook(Box::new(on_panic));
 
@@ -105,6 +109,17 @@ async fn main() -> anyhow::Result<()> {
         _ => (),
     }
 
+    anyhow::ensure!(
+        !(opt.env == "production" && opt.experimental_ephemeral_storage),
+        "The `experimental-ephemeral-storage` flag can't be used in a production environment, the data is lost when Meilisearch stops"
+    );
+    // kept alive until Meilisearch stops, the database is deleted when it is dropped
+    let _ephemeral_db_path = if opt.experimental_ephemeral_storage {
+        Some(use_ephemeral_db_path(&mut opt)?)
+    } else {
+        None
+    };
+
     let (index_scheduler, auth_controller) = setup_meilisearch(&opt)?;
 
     #[cfg(all(not(debug_assertions), feature = "analytics"))]
@@ -128,29 +143,51 @@ async fn run_http(
     index_scheduler: Arc<IndexScheduler>,
     auth_controller: Arc<AuthController>,
     opt: Opt,
-    logs: (LogRouteHandle, LogStderrHandle),
+    (logs_route, logs_stderr): (LogRouteHandle, LogStderrHandle),
     analytics: Arc<dyn Analytics>,
 ) -> anyhow::Result<()> {
     let enable_dashboard = &opt.env == "development";
     let opt_clone = opt.clone();
     let index_scheduler = Data::from(index_scheduler);
     let auth_controller = Data::from(auth_controller);
+    let search_latencies = Data::new(SearchLatencies::default());
+    let query_preprocessor = opt.experimental_query_preprocessor.build();
+    // The search queue is shared by all the workers to limit the number of searches of the whole instance.
+    let search_queue = Data::new(SearchQueue::new(
+        opt.experimental_search_queue_size,
+        available_parallelism().unwrap_or(NonZeroUsize::new(2).unwrap()),
+    ));
+
+    // The stream of the logs route is shared by all the workers, like the route layer it feeds.
+    let logs_stream = Arc::new(LogStreamState::default());
+    // The certificate is shared by all the workers, and reloaded in the background when it changes.
+    let tls_certificate = opt.get_ssl_certificate()?.map(Arc::new);
+    let tls_certificate_data = tls_certificate.clone().map(Data::from);
 
     let http_server = HttpServer::new(move || {
-        create_app(
+        let app = create_app(
             index_scheduler.clone(),
             auth_controller.clone(),
+            search_queue.clone(),
+            search_latencies.clone(),
             opt.clone(),
-            logs.clone(),
+            (logs_route.clone(), logs_stderr.clone(), logs_stream.clone()),
             analytics.clone(),
+            query_preprocessor.clone(),
             enable_dashboard,
-        )
+        );
+        match &tls_certificate_data {
+            Some(tls_certificate) => app.app_data(tls_certificate.clone()),
+            None => app,
+        }
     })
     // Disable signals allows the server to terminate immediately when a user enter CTRL-C
     .disable_signals()
     .keep_alive(KeepAlive::Os);
 
-    if let Some(config) = opt_clone.get_ssl_config()? {
+    if let Some(tls_certificate) = tls_certificate {
+        let config = opt_clone.get_ssl_config(tls_certificate.clone())?;
+        tls_certificate.watch()?;
         http_server.bind_rustls_021(opt_clone.http_addr, config)?.run().await?;
     } else {
         http_server.bind(&opt_clone.http_addr)?.run().await?;
@@ -1,7 +1,8 @@
 use lazy_static::lazy_static;
 use prometheus::{
-    opts, register_histogram_vec, register_int_counter_vec, register_int_gauge,
-    register_int_gauge_vec, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec,
+    opts, register_gauge, register_gauge_vec, register_histogram_vec, register_int_counter,
+    register_int_counter_vec, register_int_gauge, register_int_gauge_vec, Gauge, GaugeVec,
+    HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
 
 lazy_static! {
@@ -15,6 +16,18 @@ lazy_static! {
         "Meilisearch number of degraded search requests"
     ))
     .expect("Can't create a metric");
+    pub static ref MEILISEARCH_QUERY_EMBEDDING_CACHE_HITS: IntCounter =
+        register_int_counter!(opts!(
+            "meilisearch_query_embedding_cache_hits",
+            "Meilisearch number of search queries whose embedding was found in the cache"
+        ))
+        .expect("Can't create a metric");
+    pub static ref MEILISEARCH_QUERY_EMBEDDING_CACHE_MISSES: IntCounter =
+        register_int_counter!(opts!(
+            "meilisearch_query_embedding_cache_misses",
+            "Meilisearch number of search queries that had to be sent to the embedder"
+        ))
+        .expect("Can't create a metric");
     pub static ref MEILISEARCH_DB_SIZE_BYTES: IntGauge =
         register_int_gauge!(opts!("meilisearch_db_size_bytes", "Meilisearch DB Size In Bytes"))
             .expect("Can't create a metric");
@@ -31,6 +44,26 @@ lazy_static! {
         &["index"]
     )
     .expect("Can't create a metric");
+    pub static ref MEILISEARCH_INDEX_DB_PAGES: IntGaugeVec = register_int_gauge_vec!(
+        opts!("meilisearch_index_db_pages", "Meilisearch Index DB Pages"),
+        &["index", "database", "kind"]
+    )
+    .expect("Can't create a metric");
+    pub static ref MEILISEARCH_INDEX_DB_ENTRIES: IntGaugeVec = register_int_gauge_vec!(
+        opts!("meilisearch_index_db_entries", "Meilisearch Index DB Entries"),
+        &["index", "database"]
+    )
+    .expect("Can't create a metric");
+    pub static ref MEILISEARCH_INDEX_DB_DEPTH: IntGaugeVec = register_int_gauge_vec!(
+        opts!("meilisearch_index_db_depth", "Meilisearch Index DB B-Tree Depth"),
+        &["index", "database"]
+    )
+    .expect("Can't create a metric");
+    pub static ref MEILISEARCH_INDEX_FRAGMENTATION: GaugeVec = register_gauge_vec!(
+        opts!("meilisearch_index_fragmentation", "Meilisearch Index Proportion Of Free Pages"),
+        &["index"]
+    )
+    .expect("Can't create a metric");
     pub static ref MEILISEARCH_HTTP_RESPONSE_TIME_SECONDS: HistogramVec = register_histogram_vec!(
         "meilisearch_http_response_time_seconds",
         "Meilisearch HTTP response times",
@@ -49,4 +82,38 @@ lazy_static! {
     pub static ref MEILISEARCH_IS_INDEXING: IntGauge =
         register_int_gauge!(opts!("meilisearch_is_indexing", "Meilisearch Is Indexing"))
             .expect("Can't create a metric");
+    pub static ref MEILISEARCH_OLDEST_ENQUEUED_TASK_AGE_SECONDS: Gauge = register_gauge!(opts!(
+        "meilisearch_oldest_enqueued_task_age_seconds",
+        "Meilisearch age in seconds of the oldest task waiting to be processed"
+    ))
+    .expect("Can't create a metric");
+    pub static ref MEILISEARCH_FINISHED_TASKS_PER_MINUTE: IntGauge = register_int_gauge!(opts!(
+        "meilisearch_finished_tasks_per_minute",
+        "Meilisearch number of tasks finished during the last minute"
+    ))
+    .expect("Can't create a metric");
+    pub static ref MEILISEARCH_AVERAGE_BATCH_DURATION_SECONDS: Gauge = register_gauge!(opts!(
+        "meilisearch_average_batch_duration_seconds",
+        "Meilisearch average processing duration in seconds of the batches of tasks"
+    ))
+    .expect("Can't create a metric");
+    pub static ref MEILISEARCH_SEARCHES_RUNNING: IntGauge =
+        register_int_gauge!(opts!("meilisearch_searches_running", "Meilisearch Searches Running"))
+            .expect("Can't create a metric");
+    pub static ref MEILISEARCH_SEARCHES_WAITING_TO_BE_PROCESSED: IntGauge =
+        register_int_gauge!(opts!(
+            "meilisearch_searches_waiting_to_be_processed",
+            "Meilisearch Searches Waiting To Be Processed"
+        ))
+        .expect("Can't create a metric");
+    pub static ref MEILISEARCH_SEARCH_REQUESTS_REJECTED: IntCounter = register_int_counter!(opts!(
+        "meilisearch_search_requests_rejected",
+        "Meilisearch number of search requests refused because the search queue is full"
+    ))
+    .expect("Can't create a metric");
+    pub static ref MEILISEARCH_CANCELED_SEARCH_REQUESTS: IntCounter = register_int_counter!(opts!(
+        "meilisearch_canceled_search_requests",
+        "Meilisearch number of searches stopped because their client disconnected"
+    ))
+    .expect("Can't create a metric");
 }
@@ -2,7 +2,7 @@ use std::env::VarError;
 use std::ffi::OsStr;
 use std::fmt::Display;
 use std::io::{BufReader, Read};
-use std::num::ParseIntError;
+use std::num::{NonZeroUsize, ParseIntError};
 use std::ops::Deref;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -12,6 +12,7 @@ use std::{env, fmt, fs};
 use byte_unit::{Byte, ByteError};
 use clap::Parser;
 use meilisearch_types::features::InstanceTogglableFeatures;
+use meilisearch_types::milli::outbound::OutboundPolicy;
 use meilisearch_types::milli::update::IndexerConfig;
 use rustls::server::{
     AllowAnyAnonymousOrAuthenticatedClient, AllowAnyAuthenticatedClient, ServerSessionMemoryCache,
@@ -22,6 +23,9 @@ use serde::{Deserialize, Serialize};
 use sysinfo::{MemoryRefreshKind, RefreshKind, System};
 use url::Url;
 
+use crate::query_preprocessor::QueryPreprocessorKind;
+use crate::tls::TlsCertificate;
+
 const POSSIBLE_ENV: [&str; 2] = ["development", "production"];
 
 const MEILI_DB_PATH: &str = "MEILI_DB_PATH";
@@ -30,6 +34,8 @@ const MEILI_MASTER_KEY: &str = "MEILI_MASTER_KEY";
 const MEILI_ENV: &str = "MEILI_ENV";
 const MEILI_TASK_WEBHOOK_URL: &str = "MEILI_TASK_WEBHOOK_URL";
 const MEILI_TASK_WEBHOOK_AUTHORIZATION_HEADER: &str = "MEILI_TASK_WEBHOOK_AUTHORIZATION_HEADER";
+const MEILI_TASK_RETENTION_DAYS: &str = "MEILI_TASK_RETENTION_DAYS";
+const MEILI_UPDATE_FILE_RETENTION_DAYS: &str = "MEILI_UPDATE_FILE_RETENTION_DAYS";
 #[cfg(feature = "analytics")]
 const MEILI_NO_ANALYTICS: &str = "MEILI_NO_ANALYTICS";
 const MEILI_HTTP_PAYLOAD_SIZE_LIMIT: &str = "MEILI_HTTP_PAYLOAD_SIZE_LIMIT";
@@ -53,15 +59,49 @@ const MEILI_LOG_LEVEL: &str = "MEILI_LOG_LEVEL";
 const MEILI_EXPERIMENTAL_LOGS_MODE: &str = "MEILI_EXPERIMENTAL_LOGS_MODE";
 const MEILI_EXPERIMENTAL_REPLICATION_PARAMETERS: &str = "MEILI_EXPERIMENTAL_REPLICATION_PARAMETERS";
 const MEILI_EXPERIMENTAL_ENABLE_LOGS_ROUTE: &str = "MEILI_EXPERIMENTAL_ENABLE_LOGS_ROUTE";
+const MEILI_EXPERIMENTAL_LOGS_DIR: &str = "MEILI_EXPERIMENTAL_LOGS_DIR";
 const MEILI_EXPERIMENTAL_ENABLE_METRICS: &str = "MEILI_EXPERIMENTAL_ENABLE_METRICS";
 const MEILI_EXPERIMENTAL_SEARCH_QUEUE_SIZE: &str = "MEILI_EXPERIMENTAL_SEARCH_QUEUE_SIZE";
 const MEILI_EXPERIMENTAL_REDUCE_INDEXING_MEMORY_USAGE: &str =
     "MEILI_EXPERIMENTAL_REDUCE_INDEXING_MEMORY_USAGE";
 const MEILI_EXPERIMENTAL_MAX_NUMBER_OF_BATCHED_TASKS: &str =
     "MEILI_EXPERIMENTAL_MAX_NUMBER_OF_BATCHED_TASKS";
+const MEILI_EXPERIMENTAL_AUTO_CLEANUP_TASKS: &str = "MEILI_EXPERIMENTAL_AUTO_CLEANUP_TASKS";
+const MEILI_EXPERIMENTAL_ATTRIBUTES_SOFT_LIMIT: &str = "MEILI_EXPERIMENTAL_ATTRIBUTES_SOFT_LIMIT";
+const MEILI_EXPERIMENTAL_ATTRIBUTES_HARD_LIMIT: &str = "MEILI_EXPERIMENTAL_ATTRIBUTES_HARD_LIMIT";
+const MEILI_EXPERIMENTAL_MAX_ENQUEUED_TASKS: &str = "MEILI_EXPERIMENTAL_MAX_ENQUEUED_TASKS";
+const MEILI_EXPERIMENTAL_MAX_TASK_ATTEMPTS: &str = "MEILI_EXPERIMENTAL_MAX_TASK_ATTEMPTS";
+const MEILI_EXPERIMENTAL_REDACT_PAYLOAD_ERRORS: &str = "MEILI_EXPERIMENTAL_REDACT_PAYLOAD_ERRORS";
+const MEILI_EXPERIMENTAL_REENQUEUE_CRASHED_TASKS: &str =
+    "MEILI_EXPERIMENTAL_REENQUEUE_CRASHED_TASKS";
+const MEILI_EXPERIMENTAL_INDEX_ENCRYPTION_KEY: &str = "MEILI_EXPERIMENTAL_INDEX_ENCRYPTION_KEY";
+const MEILI_EXPERIMENTAL_REPLICA_OF: &str = "MEILI_EXPERIMENTAL_REPLICA_OF";
+const MEILI_EXPERIMENTAL_REPLICA_API_KEY: &str = "MEILI_EXPERIMENTAL_REPLICA_API_KEY";
+const MEILI_EXPERIMENTAL_REPLICA_SYNC_INTERVAL_SEC: &str =
+    "MEILI_EXPERIMENTAL_REPLICA_SYNC_INTERVAL_SEC";
+const MEILI_EXPERIMENTAL_EPHEMERAL_STORAGE: &str = "MEILI_EXPERIMENTAL_EPHEMERAL_STORAGE";
+const MEILI_EXPERIMENTAL_OUTBOUND_ALLOWLIST: &str = "MEILI_EXPERIMENTAL_OUTBOUND_ALLOWLIST";
+const MEILI_EXPERIMENTAL_OUTBOUND_ALLOW_PRIVATE_NETWORK: &str =
+    "MEILI_EXPERIMENTAL_OUTBOUND_ALLOW_PRIVATE_NETWORK";
+const MEILI_EXPERIMENTAL_SEARCH_TRACE_DIR: &str = "MEILI_EXPERIMENTAL_SEARCH_TRACE_DIR";
+const MEILI_EXPERIMENTAL_SEARCH_TRACE_MAX_FILES: &str = "MEILI_EXPERIMENTAL_SEARCH_TRACE_MAX_FILES";
+const MEILI_EXPERIMENTAL_QUERY_PREPROCESSOR: &str = "MEILI_EXPERIMENTAL_QUERY_PREPROCESSOR";
+const MEILI_EXPERIMENTAL_DOCUMENT_TASKS_HISTORY_SIZE: &str =
+    "MEILI_EXPERIMENTAL_DOCUMENT_TASKS_HISTORY_SIZE";
+const MEILI_EXPERIMENTAL_MAX_BUCKETS_PER_RULE: &str = "MEILI_EXPERIMENTAL_MAX_BUCKETS_PER_RULE";
+const MEILI_EXPERIMENTAL_RECENT_SEARCHES_SIZE: &str = "MEILI_EXPERIMENTAL_RECENT_SEARCHES_SIZE";
+const MEILI_EXPERIMENTAL_RECENT_SEARCHES_WITH_QUERY: &str =
+    "MEILI_EXPERIMENTAL_RECENT_SEARCHES_WITH_QUERY";
+const MEILI_EXPERIMENTAL_RANKING_RULES_OVERRIDE: &str = "MEILI_EXPERIMENTAL_RANKING_RULES_OVERRIDE";
 
 const DEFAULT_CONFIG_FILE_PATH: &str = "./config.toml";
 const DEFAULT_DB_PATH: &str = "./data.ms";
+const DEFAULT_ATTRIBUTES_SOFT_LIMIT: usize = 100;
+const DEFAULT_MAX_TASK_ATTEMPTS: u32 = 3;
+const DEFAULT_REPLICA_SYNC_INTERVAL_SEC: u64 = 60;
+const DEFAULT_SEARCH_TRACE_MAX_FILES: usize = 1000;
+const DEFAULT_DOCUMENT_TASKS_HISTORY_SIZE: usize = 5;
+const DEFAULT_RECENT_SEARCHES_SIZE: usize = 100;
 const DEFAULT_HTTP_ADDR: &str = "localhost:7700";
 const DEFAULT_ENV: &str = "development";
 const DEFAULT_HTTP_PAYLOAD_SIZE_LIMIT: &str = "100 MB";
@@ -72,6 +112,7 @@ const DEFAULT_DUMP_DIR: &str = "dumps/";
 
 const MEILI_MAX_INDEXING_MEMORY: &str = "MEILI_MAX_INDEXING_MEMORY";
 const MEILI_MAX_INDEXING_THREADS: &str = "MEILI_MAX_INDEXING_THREADS";
+const MEILI_EXPERIMENTAL_TRANSFORM_CHUNK_SIZE: &str = "MEILI_EXPERIMENTAL_TRANSFORM_CHUNK_SIZE";
 const DEFAULT_LOG_EVERY_N: usize = 100_000;
 
 // Each environment (index and task-db) is taking space in the virtual address space.
@@ -196,6 +237,8 @@ pub struct Opt {
     pub env: String,
 
     /// Called whenever a task finishes so a third party can be notified.
+    ///
+    /// Ignored once the webhook is configured at runtime through the `PATCH /webhook` route.
     #[clap(long, env = MEILI_TASK_WEBHOOK_URL)]
     pub task_webhook_url: Option<Url>,
 
@@ -203,6 +246,16 @@ pub struct Opt {
     #[clap(long, env = MEILI_TASK_WEBHOOK_AUTHORIZATION_HEADER)]
     pub task_webhook_authorization_header: Option<String>,
 
+    /// Deletes the succeeded, failed, and canceled tasks that finished more than this number of days ago.
+    /// The finished tasks are kept forever by default.
+    #[clap(long, env = MEILI_TASK_RETENTION_DAYS)]
+    pub task_retention_days: Option<u64>,
+
+    /// Deletes the update files that no enqueued task refers to, left by a crash for example, once
+    /// they are older than this number of days. They are kept forever by default.
+    #[clap(long, env = MEILI_UPDATE_FILE_RETENTION_DAYS)]
+    pub update_file_retention_days: Option<u64>,
+
     /// Deactivates Meilisearch's built-in telemetry when provided.
     ///
     /// Meilisearch automatically collects data from all instances that do not opt out using this flag.
@@ -231,6 +284,8 @@ pub struct Opt {
     pub http_payload_size_limit: Byte,
 
     /// Sets the server's SSL certificates.
+    ///
+    /// The certificates and the key are reloaded without a restart when their files change.
     #[clap(long, env = MEILI_SSL_CERT_PATH, value_parser)]
     pub ssl_cert_path: Option<PathBuf>,
 
@@ -347,8 +402,9 @@ pub struct Opt {
 
     /// Experimental search queue size. For more information, see: <https://github.com/orgs/meilisearch/discussions/729>
     ///
-    /// Lets you customize the size of the search queue. Meilisearch processes your search requests as fast as possible but once the
-    /// queue is full it starts returning HTTP 503, Service Unavailable.
+    /// Lets you customize the size of the search queue. Meilisearch processes as many search requests in parallel as it has cores,
+    /// the other ones wait in the queue in the order they were received. Once the queue is full it starts returning
+    /// HTTP 503, Service Unavailable. A multi-search counts for as many searches as it has queries.
     /// The default value is 1000.
     #[clap(long, env = MEILI_EXPERIMENTAL_SEARCH_QUEUE_SIZE, default_value_t = 1000)]
     #[serde(default)]
@@ -368,6 +424,13 @@ pub struct Opt {
     #[serde(default)]
     pub experimental_enable_logs_route: bool,
 
+    /// The directory the `POST /logs/stderr` route can write the logs into.
+    ///
+    /// The `path` given to the route must be relative to this directory, the route can't write
+    /// the logs into a file when it is not set.
+    #[clap(long, env = MEILI_EXPERIMENTAL_LOGS_DIR)]
+    pub experimental_logs_dir: Option<PathBuf>,
+
     /// Enable multiple features that helps you to run meilisearch in a replicated context.
     /// For more information, see: <https://github.com/orgs/meilisearch/discussions/725>
     ///
@@ -388,6 +451,153 @@ pub struct Opt {
     #[serde(default = "default_limit_batched_tasks")]
     pub experimental_max_number_of_batched_tasks: usize,
 
+    /// Experimentally enqueues a deletion of the oldest succeeded tasks when the task database is full.
+    ///
+    /// The write request that hit the limit is still rejected, but the following ones will be accepted
+    /// once the task deletion has been processed.
+    #[clap(long, env = MEILI_EXPERIMENTAL_AUTO_CLEANUP_TASKS)]
+    #[serde(default)]
+    pub experimental_auto_cleanup_tasks: bool,
+
+    /// Experimentally warns in the details of a settings task when an index ends up with more
+    /// filterable, sortable, or searchable attributes than this limit.
+    #[clap(long, env = MEILI_EXPERIMENTAL_ATTRIBUTES_SOFT_LIMIT, default_value_t = default_attributes_soft_limit())]
+    #[serde(default = "default_attributes_soft_limit")]
+    pub experimental_attributes_soft_limit: usize,
+
+    /// Experimentally fails the settings tasks that would give an index more filterable, sortable,
+    /// or searchable attributes than this limit. No limit is enforced by default.
+    #[clap(long, env = MEILI_EXPERIMENTAL_ATTRIBUTES_HARD_LIMIT)]
+    #[serde(default)]
+    pub experimental_attributes_hard_limit: Option<usize>,
+
+    /// Experimentally rejects the write requests with a `429 Too Many Requests` when more than this
+    /// number of tasks are enqueued. The task cancelations, task deletions, and dumps are never rejected.
+    /// No limit is enforced by default.
+    #[clap(long, env = MEILI_EXPERIMENTAL_MAX_ENQUEUED_TASKS)]
+    #[serde(default)]
+    pub experimental_max_enqueued_tasks: Option<u64>,
+
+    /// Experimentally attempts again, with an exponential delay, the tasks failing because of a transient
+    /// error such as an embedder that is temporarily unavailable. A task is marked as failed after this
+    /// number of attempts. Set it to `1` to never attempt a task again.
+    #[clap(long, env = MEILI_EXPERIMENTAL_MAX_TASK_ATTEMPTS, default_value_t = default_max_task_attempts())]
+    #[serde(default = "default_max_task_attempts")]
+    pub experimental_max_task_attempts: u32,
+
+    /// Experimentally truncates the document values longer than 256 characters echoed in the errors
+    /// of the tasks, keeping their first characters and a hash, so that the task details and the logs
+    /// don't contain large chunks of the documents.
+    #[clap(long, env = MEILI_EXPERIMENTAL_REDACT_PAYLOAD_ERRORS)]
+    #[serde(default)]
+    pub experimental_redact_payload_errors: bool,
+
+    /// Experimentally enqueues again the tasks of the batch that was processing when Meilisearch stopped
+    /// unexpectedly. By default, these tasks are marked as failed with the `crashed_during_processing` error.
+    #[clap(long, env = MEILI_EXPERIMENTAL_REENQUEUE_CRASHED_TASKS)]
+    #[serde(default)]
+    pub experimental_reenqueue_crashed_tasks: bool,
+
+    /// Experimentally encrypts the content of the documents and the update files with this 32 bytes key,
+    /// encoded in base64. The inverted indexes, the facets, and the vectors are not encrypted.
+    ///
+    /// An instance created with a key can't be started without it, or with another key. Use a dump
+    /// to encrypt an existing instance, or to change its key.
+    #[clap(long, env = MEILI_EXPERIMENTAL_INDEX_ENCRYPTION_KEY)]
+    pub experimental_index_encryption_key: Option<String>,
+
+    /// Experimentally makes this instance a read-only replica of the Meilisearch instance at this URL.
+    ///
+    /// The replica periodically imports a snapshot of its primary without restarting, its indexes
+    /// being replaced by the ones of the primary. All the tasks modifying the indexes are refused.
+    #[clap(long, env = MEILI_EXPERIMENTAL_REPLICA_OF)]
+    pub experimental_replica_of: Option<Url>,
+
+    /// The API key the replica sends to its primary, it must allow the `snapshots.create`,
+    /// `snapshots.download` and `tasks.get` actions.
+    #[clap(long, env = MEILI_EXPERIMENTAL_REPLICA_API_KEY)]
+    pub experimental_replica_api_key: Option<String>,
+
+    /// The interval, in seconds, at which a replica imports a snapshot of its primary.
+    #[clap(long, env = MEILI_EXPERIMENTAL_REPLICA_SYNC_INTERVAL_SEC, default_value_t = default_replica_sync_interval_sec())]
+    #[serde(default = "default_replica_sync_interval_sec")]
+    pub experimental_replica_sync_interval_sec: u64,
+
+    /// Experimentally stores the database in a temporary directory, on a tmpfs when `/dev/shm` is
+    /// available, that is deleted when Meilisearch stops. The databases are not flushed to disk
+    /// after each write. Meant for the CI and the tests: the data is lost when Meilisearch stops
+    /// or crashes, thus it can't be used in a production environment.
+    #[clap(long, env = MEILI_EXPERIMENTAL_EPHEMERAL_STORAGE)]
+    #[serde(default)]
+    pub experimental_ephemeral_storage: bool,
+
+    /// Experimentally restricts the hosts Meilisearch sends requests to, i.e. the REST embedders,
+    /// the task webhook and the primary of a replica, to the ones matching this comma-separated list.
+    /// A pattern is either a host, or `*.` followed by a domain to allow all its subdomains.
+    ///
+    /// The requests to the other hosts fail with an `outbound_host_not_allowed` error, without being sent.
+    #[clap(long, env = MEILI_EXPERIMENTAL_OUTBOUND_ALLOWLIST, value_delimiter = ',')]
+    #[serde(default)]
+    pub experimental_outbound_allowlist: Vec<String>,
+
+    /// Allows the requests to the loopback and private addresses matching the outbound allow-list.
+    /// They are denied by default when an allow-list is set.
+    #[clap(long, env = MEILI_EXPERIMENTAL_OUTBOUND_ALLOW_PRIVATE_NETWORK)]
+    #[serde(default)]
+    pub experimental_outbound_allow_private_network: bool,
+
+    /// Experimentally writes a JSON file in this directory for every search made on a single index,
+    /// with the index, the query, and the trace of the ranking rules: the buckets they returned and
+    /// the documents added to the results. The query of a `GET` search is its query string.
+    ///
+    /// Meant to debug the relevancy, writing the traces slows down the searches.
+    #[clap(long, env = MEILI_EXPERIMENTAL_SEARCH_TRACE_DIR)]
+    pub experimental_search_trace_dir: Option<PathBuf>,
+
+    /// The maximum number of search traces kept in the `--experimental-search-trace-dir` directory,
+    /// the oldest ones are deleted when a new one is written.
+    #[clap(long, env = MEILI_EXPERIMENTAL_SEARCH_TRACE_MAX_FILES, default_value_t = default_search_trace_max_files())]
+    #[serde(default = "default_search_trace_max_files")]
+    pub experimental_search_trace_max_files: usize,
+
+    /// Experimentally rewrites the `q` of the searches made on a single index before they are searched,
+    /// with one of the preprocessors compiled in Meilisearch. The response still contains the `q` of the request.
+    #[clap(long, env = MEILI_EXPERIMENTAL_QUERY_PREPROCESSOR, default_value_t)]
+    #[serde(default)]
+    pub experimental_query_preprocessor: QueryPreprocessorKind,
+
+    /// The number of tasks kept in the history of each document when the `documentTasks` experimental
+    /// feature is enabled, the oldest ones are forgotten when a new task modifies the document.
+    #[clap(long, env = MEILI_EXPERIMENTAL_DOCUMENT_TASKS_HISTORY_SIZE, default_value_t = default_document_tasks_history_size())]
+    #[serde(default = "default_document_tasks_history_size")]
+    pub experimental_document_tasks_history_size: usize,
+
+    /// Experimentally caps the number of buckets of the ranking rules without a `maxBuckets` in the
+    /// settings of their index. The documents past the last bucket of a rule are not sorted by it.
+    #[clap(long, env = MEILI_EXPERIMENTAL_MAX_BUCKETS_PER_RULE)]
+    #[serde(default)]
+    pub experimental_max_buckets_per_rule: Option<NonZeroUsize>,
+
+    /// The number of searches kept for each index when the `recentSearches` experimental feature
+    /// is enabled, the oldest ones are forgotten when a new search is made on the index.
+    #[clap(long, env = MEILI_EXPERIMENTAL_RECENT_SEARCHES_SIZE, default_value_t = default_recent_searches_size())]
+    #[serde(default = "default_recent_searches_size")]
+    pub experimental_recent_searches_size: usize,
+
+    /// Also keeps the `q` of the recent searches of the indexes. By default, only the names and
+    /// the types of the search parameters are kept.
+    #[clap(long, env = MEILI_EXPERIMENTAL_RECENT_SEARCHES_WITH_QUERY)]
+    #[serde(default)]
+    pub experimental_recent_searches_with_query: bool,
+
+    /// Experimental ranking rules override feature.
+    ///
+    /// Enables the `rankingRulesOverride` search parameter, ranking the documents of a search with
+    /// its own ranking rules instead of the ones of the index.
+    #[clap(long, env = MEILI_EXPERIMENTAL_RANKING_RULES_OVERRIDE)]
+    #[serde(default)]
+    pub experimental_ranking_rules_override: bool,
+
     #[serde(flatten)]
     #[clap(flatten)]
     pub indexer_options: IndexerOpts,
@@ -457,10 +667,34 @@ impl Opt {
             env,
             task_webhook_url,
             task_webhook_authorization_header,
+            task_retention_days,
+            update_file_retention_days,
             max_index_size: _,
             max_task_db_size: _,
             http_payload_size_limit,
             experimental_max_number_of_batched_tasks,
+            experimental_auto_cleanup_tasks,
+            experimental_attributes_soft_limit,
+            experimental_attributes_hard_limit,
+            experimental_max_enqueued_tasks,
+            experimental_max_task_attempts,
+            experimental_redact_payload_errors,
+            experimental_reenqueue_crashed_tasks,
+            experimental_index_encryption_key,
+            experimental_replica_of,
+            experimental_replica_api_key,
+            experimental_replica_sync_interval_sec,
+            experimental_ephemeral_storage,
+            experimental_outbound_allowlist,
+            experimental_outbound_allow_private_network,
+            experimental_search_trace_dir,
+            experimental_search_trace_max_files,
+            experimental_query_preprocessor,
+            experimental_document_tasks_history_size,
+            experimental_max_buckets_per_rule,
+            experimental_recent_searches_size,
+            experimental_recent_searches_with_query,
+            experimental_ranking_rules_override,
             ssl_cert_path,
             ssl_key_path,
             ssl_auth_path,
@@ -486,6 +720,7 @@ impl Opt {
             experimental_search_queue_size,
             experimental_logs_mode,
             experimental_enable_logs_route,
+            experimental_logs_dir,
             experimental_replication_parameters,
             experimental_reduce_indexing_memory_usage,
         } = self;
@@ -504,6 +739,18 @@ impl Opt {
                 task_webhook_authorization_header,
             );
         }
+        if let Some(task_retention_days) = task_retention_days {
+            export_to_env_if_not_present(
+                MEILI_TASK_RETENTION_DAYS,
+                task_retention_days.to_string(),
+            );
+        }
+        if let Some(update_file_retention_days) = update_file_retention_days {
+            export_to_env_if_not_present(
+                MEILI_UPDATE_FILE_RETENTION_DAYS,
+                update_file_retention_days.to_string(),
+            );
+        }
 
         #[cfg(feature = "analytics")]
         {
@@ -517,6 +764,110 @@ impl Opt {
             MEILI_EXPERIMENTAL_MAX_NUMBER_OF_BATCHED_TASKS,
             experimental_max_number_of_batched_tasks.to_string(),
         );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_AUTO_CLEANUP_TASKS,
+            experimental_auto_cleanup_tasks.to_string(),
+        );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_ATTRIBUTES_SOFT_LIMIT,
+            experimental_attributes_soft_limit.to_string(),
+        );
+        if let Some(experimental_attributes_hard_limit) = experimental_attributes_hard_limit {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_ATTRIBUTES_HARD_LIMIT,
+                experimental_attributes_hard_limit.to_string(),
+            );
+        }
+        if let Some(experimental_max_enqueued_tasks) = experimental_max_enqueued_tasks {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_MAX_ENQUEUED_TASKS,
+                experimental_max_enqueued_tasks.to_string(),
+            );
+        }
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_MAX_TASK_ATTEMPTS,
+            experimental_max_task_attempts.to_string(),
+        );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_REDACT_PAYLOAD_ERRORS,
+            experimental_redact_payload_errors.to_string(),
+        );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_REENQUEUE_CRASHED_TASKS,
+            experimental_reenqueue_crashed_tasks.to_string(),
+        );
+        if let Some(experimental_index_encryption_key) = experimental_index_encryption_key {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_INDEX_ENCRYPTION_KEY,
+                experimental_index_encryption_key,
+            );
+        }
+        if let Some(experimental_replica_of) = experimental_replica_of {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_REPLICA_OF,
+                experimental_replica_of.to_string(),
+            );
+        }
+        if let Some(experimental_replica_api_key) = experimental_replica_api_key {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_REPLICA_API_KEY,
+                experimental_replica_api_key,
+            );
+        }
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_REPLICA_SYNC_INTERVAL_SEC,
+            experimental_replica_sync_interval_sec.to_string(),
+        );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_EPHEMERAL_STORAGE,
+            experimental_ephemeral_storage.to_string(),
+        );
+        if !experimental_outbound_allowlist.is_empty() {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_OUTBOUND_ALLOWLIST,
+                experimental_outbound_allowlist.join(","),
+            );
+        }
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_OUTBOUND_ALLOW_PRIVATE_NETWORK,
+            experimental_outbound_allow_private_network.to_string(),
+        );
+        if let Some(experimental_search_trace_dir) = experimental_search_trace_dir {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_SEARCH_TRACE_DIR,
+                experimental_search_trace_dir,
+            );
+        }
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_SEARCH_TRACE_MAX_FILES,
+            experimental_search_trace_max_files.to_string(),
+        );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_QUERY_PREPROCESSOR,
+            experimental_query_preprocessor.to_string(),
+        );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_DOCUMENT_TASKS_HISTORY_SIZE,
+            experimental_document_tasks_history_size.to_string(),
+        );
+        if let Some(experimental_max_buckets_per_rule) = experimental_max_buckets_per_rule {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_MAX_BUCKETS_PER_RULE,
+                experimental_max_buckets_per_rule.to_string(),
+            );
+        }
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_RECENT_SEARCHES_SIZE,
+            experimental_recent_searches_size.to_string(),
+        );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_RECENT_SEARCHES_WITH_QUERY,
+            experimental_recent_searches_with_query.to_string(),
+        );
+        export_to_env_if_not_present(
+            MEILI_EXPERIMENTAL_RANKING_RULES_OVERRIDE,
+            experimental_ranking_rules_override.to_string(),
+        );
         if let Some(ssl_cert_path) = ssl_cert_path {
             export_to_env_if_not_present(MEILI_SSL_CERT_PATH, ssl_cert_path);
         }
@@ -559,6 +910,9 @@ impl Opt {
             MEILI_EXPERIMENTAL_ENABLE_LOGS_ROUTE,
             experimental_enable_logs_route.to_string(),
         );
+        if let Some(experimental_logs_dir) = experimental_logs_dir {
+            export_to_env_if_not_present(MEILI_EXPERIMENTAL_LOGS_DIR, experimental_logs_dir);
+        }
         export_to_env_if_not_present(
             MEILI_EXPERIMENTAL_REDUCE_INDEXING_MEMORY_USAGE,
             experimental_reduce_indexing_memory_usage.to_string(),
@@ -566,56 +920,73 @@ impl Opt {
         indexer_options.export_to_env();
     }
 
-    pub fn get_ssl_config(&self) -> anyhow::Result<Option<rustls::ServerConfig>> {
+    /// The hosts the outbound requests are allowed to reach, every host when no allow-list is set.
+    pub fn outbound_policy(&self) -> OutboundPolicy {
+        if self.experimental_outbound_allowlist.is_empty() {
+            OutboundPolicy::default()
+        } else {
+            OutboundPolicy::allowlist(
+                self.experimental_outbound_allowlist.clone(),
+                self.experimental_outbound_allow_private_network,
+            )
+        }
+    }
+
+    /// Loads the certificate of the server, when SSL is enabled.
+    pub fn get_ssl_certificate(&self) -> anyhow::Result<Option<TlsCertificate>> {
         if let (Some(cert_path), Some(key_path)) = (&self.ssl_cert_path, &self.ssl_key_path) {
-            let config = rustls::ServerConfig::builder().with_safe_defaults();
-
-            let config = match &self.ssl_auth_path {
-                Some(auth_path) => {
-                    let roots = load_certs(auth_path.to_path_buf())?;
-                    let mut client_auth_roots = RootCertStore::empty();
-                    for root in roots {
-                        client_auth_roots.add(&root).unwrap();
-                    }
-                    if self.ssl_require_auth {
-                        let verifier = AllowAnyAuthenticatedClient::new(client_auth_roots);
-                        config.with_client_cert_verifier(Arc::from(verifier))
-                    } else {
-                        let verifier =
-                            AllowAnyAnonymousOrAuthenticatedClient::new(client_auth_roots);
-                        config.with_client_cert_verifier(Arc::from(verifier))
-                    }
+            TlsCertificate::load(cert_path.clone(), key_path.clone(), self.ssl_ocsp_path.clone())
+                .map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn get_ssl_config(
+        &self,
+        certificate: Arc<TlsCertificate>,
+    ) -> anyhow::Result<rustls::ServerConfig> {
+        let config = rustls::ServerConfig::builder().with_safe_defaults();
+
+        let config = match &self.ssl_auth_path {
+            Some(auth_path) => {
+                let roots = load_certs(auth_path.to_path_buf())?;
+                let mut client_auth_roots = RootCertStore::empty();
+                for root in roots {
+                    client_auth_roots.add(&root).unwrap();
+                }
+                if self.ssl_require_auth {
+                    let verifier = AllowAnyAuthenticatedClient::new(client_auth_roots);
+                    config.with_client_cert_verifier(Arc::from(verifier))
+                } else {
+                    let verifier = AllowAnyAnonymousOrAuthenticatedClient::new(client_auth_roots);
+                    config.with_client_cert_verifier(Arc::from(verifier))
                 }
-                None => config.with_no_client_auth(),
-            };
+            }
+            None => config.with_no_client_auth(),
+        };
 
-            let certs = load_certs(cert_path.to_path_buf())?;
-            let privkey = load_private_key(key_path.to_path_buf())?;
-            let ocsp = load_ocsp(&self.ssl_ocsp_path)?;
-            let mut config = config
-                .with_single_cert_with_ocsp_and_sct(certs, privkey, ocsp, vec![])
-                .map_err(|_| anyhow::anyhow!("bad certificates/private key"))?;
+        // The certificate is resolved on every handshake to present the last one reloaded.
+        let mut config = config.with_cert_resolver(certificate);
 
-            config.key_log = Arc::new(rustls::KeyLogFile::new());
+        config.key_log = Arc::new(rustls::KeyLogFile::new());
 
-            if self.ssl_resumption {
-                config.session_storage = ServerSessionMemoryCache::new(256);
-            }
-
-            if self.ssl_tickets {
-                config.ticketer = rustls::Ticketer::new().unwrap();
-            }
+        if self.ssl_resumption {
+            config.session_storage = ServerSessionMemoryCache::new(256);
+        }
 
-            Ok(Some(config))
-        } else {
-            Ok(None)
+        if self.ssl_tickets {
+            config.ticketer = rustls::Ticketer::new().unwrap();
         }
+
+        Ok(config)
     }
 
     pub(crate) fn to_instance_features(&self) -> InstanceTogglableFeatures {
         InstanceTogglableFeatures {
             metrics: self.experimental_enable_metrics,
             logs_route: self.experimental_enable_logs_route,
+            ranking_rules_override: self.experimental_ranking_rules_override,
         }
     }
 }
@@ -635,6 +1006,13 @@ pub struct IndexerOpts {
     #[serde(default)]
     pub max_indexing_threads: MaxThreads,
 
+    /// Experimental: the documents of an indexation weighing more than this size are extracted and
+    /// merged by chunks of this size, one after the other in the same transaction, to bound the
+    /// memory used when indexing very large payloads. By default, the chunks weigh 512 MiB.
+    #[clap(long, env = MEILI_EXPERIMENTAL_TRANSFORM_CHUNK_SIZE)]
+    #[serde(default)]
+    pub experimental_transform_chunk_size: Option<Byte>,
+
     /// Whether or not we want to determine the budget of virtual memory address space we have available dynamically
     /// (the default), or statically.
     ///
@@ -648,7 +1026,12 @@ pub struct IndexerOpts {
 impl IndexerOpts {
     /// Exports the values to their corresponding env vars if they are not set.
     pub fn export_to_env(self) {
-        let IndexerOpts { max_indexing_memory, max_indexing_threads, skip_index_budget: _ } = self;
+        let IndexerOpts {
+            max_indexing_memory,
+            max_indexing_threads,
+            experimental_transform_chunk_size,
+            skip_index_budget: _,
+        } = self;
         if let Some(max_indexing_memory) = max_indexing_memory.0 {
             export_to_env_if_not_present(
                 MEILI_MAX_INDEXING_MEMORY,
@@ -659,6 +1042,12 @@ impl IndexerOpts {
             MEILI_MAX_INDEXING_THREADS,
             max_indexing_threads.0.to_string(),
         );
+        if let Some(experimental_transform_chunk_size) = experimental_transform_chunk_size {
+            export_to_env_if_not_present(
+                MEILI_EXPERIMENTAL_TRANSFORM_CHUNK_SIZE,
+                experimental_transform_chunk_size.to_string(),
+            );
+        }
     }
 }
 
@@ -674,6 +1063,9 @@ impl TryFrom<&IndexerOpts> for IndexerConfig {
         Ok(Self {
             log_every_n: Some(DEFAULT_LOG_EVERY_N),
             max_memory: other.max_indexing_memory.map(|b| b.get_bytes() as usize),
+            transform_chunk_size: other
+                .experimental_transform_chunk_size
+                .map(|size| size.get_bytes() as usize),
             thread_pool: Some(thread_pool),
             max_positions_per_attributes: None,
             skip_index_budget: other.skip_index_budget,
@@ -766,7 +1158,7 @@ impl Deref for MaxThreads {
     }
 }
 
-fn load_certs(filename: PathBuf) -> anyhow::Result<Vec<rustls::Certificate>> {
+pub(crate) fn load_certs(filename: PathBuf) -> anyhow::Result<Vec<rustls::Certificate>> {
     let certfile =
         fs::File::open(filename).map_err(|_| anyhow::anyhow!("cannot open certificate file"))?;
     let mut reader = BufReader::new(certfile);
@@ -775,7 +1167,7 @@ fn load_certs(filename: PathBuf) -> anyhow::Result<Vec<rustls::Certificate>> {
         .map_err(|_| anyhow::anyhow!("cannot read certificate file"))
 }
 
-fn load_private_key(filename: PathBuf) -> anyhow::Result<rustls::PrivateKey> {
+pub(crate) fn load_private_key(filename: PathBuf) -> anyhow::Result<rustls::PrivateKey> {
     let rsa_keys = {
         let keyfile = fs::File::open(filename.clone())
             .map_err(|_| anyhow::anyhow!("cannot open private key file"))?;
@@ -796,15 +1188,13 @@ fn load_private_key(filename: PathBuf) -> anyhow::Result<rustls::PrivateKey> {
     };
 
     // prefer to load pkcs8 keys
-    if !pkcs8_keys.is_empty() {
-        Ok(rustls::PrivateKey(pkcs8_keys[0].clone()))
-    } else {
-        assert!(!rsa_keys.is_empty());
-        Ok(rustls::PrivateKey(rsa_keys[0].clone()))
+    match pkcs8_keys.first().or(rsa_keys.first()) {
+        Some(key) => Ok(rustls::PrivateKey(key.clone())),
+        None => Err(anyhow::anyhow!("the private key file doesn't contain any private key")),
     }
 }
 
-fn load_ocsp(filename: &Option<PathBuf>) -> anyhow::Result<Vec<u8>> {
+pub(crate) fn load_ocsp(filename: &Option<PathBuf>) -> anyhow::Result<Vec<u8>> {
     let mut ret = Vec::new();
 
     if let Some(ref name) = filename {
@@ -858,6 +1248,30 @@ fn default_limit_batched_tasks() -> usize {
     usize::MAX
 }
 
+fn default_attributes_soft_limit() -> usize {
+    DEFAULT_ATTRIBUTES_SOFT_LIMIT
+}
+
+fn default_max_task_attempts() -> u32 {
+    DEFAULT_MAX_TASK_ATTEMPTS
+}
+
+fn default_replica_sync_interval_sec() -> u64 {
+    DEFAULT_REPLICA_SYNC_INTERVAL_SEC
+}
+
+fn default_search_trace_max_files() -> usize {
+    DEFAULT_SEARCH_TRACE_MAX_FILES
+}
+
+fn default_document_tasks_history_size() -> usize {
+    DEFAULT_DOCUMENT_TASKS_HISTORY_SIZE
+}
+
+fn default_recent_searches_size() -> usize {
+    DEFAULT_RECENT_SEARCHES_SIZE
+}
+
 fn default_snapshot_dir() -> PathBuf {
     PathBuf::from(DEFAULT_SNAPSHOT_DIR)
 }
@@ -0,0 +1,100 @@
+//! The hooks rewriting the `q` of the searches before it is split into terms.
+//!
+//! The preprocessors are compiled in Meilisearch, the one used by an instance is selected with
+//! `--experimental-query-preprocessor`. A preprocessor receives the raw `q` of the searches made on
+//! the `/indexes/{indexUid}/search` route and returns the `q` to search, and optionally filters the
+//! documents must also match. The response still contains the `q` sent by the client.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use meilisearch_auth::and_filters;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::search::SearchQuery;
+
+/// Rewrites the queries of the search route before they are searched.
+pub trait QueryPreprocessor: Send + Sync {
+    /// Returns the query to search instead of the raw `q` of a search on the given index.
+    fn preprocess(&self, index_uid: &str, q: &str) -> PreprocessedQuery;
+}
+
+/// A query rewritten by a [`QueryPreprocessor`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PreprocessedQuery {
+    pub q: String,
+    /// A filter, with the syntax of the `filter` search parameter, added to the one of the search.
+    pub filter: Option<Value>,
+}
+
+impl PreprocessedQuery {
+    /// The query searched as it was received.
+    pub fn unchanged(q: &str) -> Self {
+        Self { q: q.to_string(), filter: None }
+    }
+}
+
+/// The default preprocessor, searching the queries as they are received.
+pub struct IdentityPreprocessor;
+
+impl QueryPreprocessor for IdentityPreprocessor {
+    fn preprocess(&self, _index_uid: &str, q: &str) -> PreprocessedQuery {
+        PreprocessedQuery::unchanged(q)
+    }
+}
+
+/// Rewrites the `q` of the search with the preprocessor, and adds its filter to the one of the search.
+///
+/// Returns the `q` that was received, to be returned in the response in place of the rewritten one.
+pub fn preprocess_query(
+    preprocessor: &dyn QueryPreprocessor,
+    index_uid: &str,
+    query: &mut SearchQuery,
+) -> Option<String> {
+    let original_q = query.q.take()?;
+    let PreprocessedQuery { q, filter } = preprocessor.preprocess(index_uid, &original_q);
+    query.q = Some(q);
+    query.filter = and_filters(query.filter.take(), filter);
+    Some(original_q)
+}
+
+/// The preprocessors compiled in Meilisearch.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryPreprocessorKind {
+    #[default]
+    Identity,
+}
+
+impl QueryPreprocessorKind {
+    pub fn build(self) -> Arc<dyn QueryPreprocessor> {
+        match self {
+            QueryPreprocessorKind::Identity => Arc::new(IdentityPreprocessor),
+        }
+    }
+}
+
+impl Display for QueryPreprocessorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryPreprocessorKind::Identity => Display::fmt("identity", f),
+        }
+    }
+}
+
+impl FromStr for QueryPreprocessorKind {
+    type Err = QueryPreprocessorKindError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "identity" => Ok(QueryPreprocessorKind::Identity),
+            _ => Err(QueryPreprocessorKindError(s.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unsupported query preprocessor `{0}`. Supported values are `identity`.")]
+pub struct QueryPreprocessorKindError(String);
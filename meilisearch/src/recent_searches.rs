@@ -0,0 +1,99 @@
+//! This file implements the searches recorded by the `recentSearches` experimental feature.
+//!
+//! Only the shape of a search is recorded: the names of its parameters and the types of their
+//! values, its number of hits and its processing time. The `q` may contain personal data, it is
+//! only recorded with `--experimental-recent-searches-with-query`. The searches themselves are
+//! kept by the index scheduler, which only keeps the last ones of every index.
+
+use actix_web::web::Data;
+use index_scheduler::IndexScheduler;
+use serde_json::{json, Map, Value};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::option::Opt;
+use crate::search::{HitsInfo, SearchResult};
+
+/// The maximum length in bytes of a recorded `q`, the longer ones are truncated.
+const MAX_RECORDED_QUERY_LEN: usize = 512;
+
+#[derive(Debug, Clone)]
+pub struct RecentSearches {
+    with_query: bool,
+}
+
+impl RecentSearches {
+    pub fn new(opt: &Opt) -> Self {
+        Self { with_query: opt.experimental_recent_searches_with_query }
+    }
+
+    /// Returns the recorded form of a search, from the names of its parameters and the types of their values.
+    pub fn sanitize<'a>(
+        &self,
+        parameters: impl IntoIterator<Item = (&'a str, &'static str)>,
+        search_result: &SearchResult,
+    ) -> Value {
+        let parameters: Map<String, Value> = parameters
+            .into_iter()
+            .map(|(name, kind)| (name.to_string(), Value::from(kind)))
+            .collect();
+        let total_hits = match search_result.hits_info {
+            HitsInfo::Pagination { total_hits, .. } => total_hits,
+            HitsInfo::OffsetLimit { estimated_total_hits, .. } => estimated_total_hits,
+        };
+        let searched_at = OffsetDateTime::now_utc().format(&Rfc3339).unwrap_or_default();
+
+        let mut search = json!({
+            "searchedAt": searched_at,
+            "parameters": parameters,
+            "totalHits": total_hits,
+            "processingTimeMs": search_result.processing_time_ms,
+        });
+        if self.with_query {
+            search["q"] = Value::from(truncate(&search_result.query, MAX_RECORDED_QUERY_LEN));
+        }
+        search
+    }
+
+    /// Records a search of the index, a failure is only logged as it must not fail the search.
+    pub async fn record(
+        &self,
+        index_scheduler: Data<IndexScheduler>,
+        index_uid: &str,
+        search: Value,
+    ) {
+        let index_uid = index_uid.to_string();
+        let result = tokio::task::spawn_blocking(move || {
+            index_scheduler.record_recent_search(&index_uid, search)
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => (),
+            Ok(Err(error)) => tracing::warn!(%error, "Could not record the search"),
+            Err(error) => tracing::warn!(%error, "Could not record the search"),
+        }
+    }
+}
+
+/// The name of the type of a JSON value, as recorded in the parameters of a search.
+pub fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn truncate(s: &str, max_len: usize) -> &str {
+    if s.len() <= max_len {
+        return s;
+    }
+    let mut end = max_len;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
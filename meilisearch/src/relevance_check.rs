@@ -0,0 +1,149 @@
+//! Runs a suite of golden queries against an index to find the relevance regressions,
+//! typically after a settings change and before swapping the index with the one being searched.
+
+use std::collections::BTreeSet;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use index_scheduler::{IndexScheduler, RoFeatures};
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::milli::Index;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::routes::indexes::search::search_kind;
+use crate::search::{perform_search, SearchQuery};
+
+/// A search whose top hits are known to be the relevant ones.
+#[derive(Debug, Clone)]
+pub struct GoldenQuery {
+    pub name: String,
+    /// The search to perform, its pagination and the attributes it retrieves are replaced
+    /// to only fetch the external ids of the hits to compare.
+    pub search: SearchQuery,
+    /// The expected top hits, in order.
+    pub expected: Vec<ExpectedHit>,
+    /// The number of positions an expected hit can lose before being a regression.
+    pub tolerance: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExpectedHit {
+    /// The external id of the document.
+    pub id: String,
+    /// The ranking score of the hit when the query was validated, to report how much it changed.
+    pub ranking_score: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelevanceReport {
+    /// The names of the queries in which an expected hit is missing or lost too many positions.
+    pub regressions: Vec<String>,
+    pub queries: Vec<QueryReport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryReport {
+    pub name: String,
+    pub regressed: bool,
+    /// The external ids of the hits, as many as the expected ones plus the tolerance.
+    pub ids: Vec<String>,
+    pub expected: Vec<HitReport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HitReport {
+    pub id: String,
+    pub expected_position: usize,
+    /// `None` when the hit is not in the retrieved ones.
+    pub position: Option<usize>,
+    pub regressed: bool,
+    pub expected_ranking_score: Option<f64>,
+    pub ranking_score: Option<f64>,
+    /// The ranking score minus the expected one.
+    pub ranking_score_diff: Option<f64>,
+}
+
+/// Performs the golden queries on the index like the search route would, and reports the
+/// expected hits that are not found in their position, within the tolerance of their query.
+pub fn check_relevance(
+    index_scheduler: &IndexScheduler,
+    index: &Index,
+    features: RoFeatures,
+    queries: Vec<GoldenQuery>,
+    canceled: Arc<AtomicBool>,
+) -> Result<RelevanceReport, ResponseError> {
+    let rtxn = index.read_txn()?;
+    let primary_key = index.primary_key(&rtxn)?.map(String::from);
+    drop(rtxn);
+
+    let mut regressions = Vec::new();
+    let mut reports = Vec::with_capacity(queries.len());
+    for GoldenQuery { name, mut search, expected, tolerance } in queries {
+        let search_kind = search_kind(&search, index_scheduler, index, features)?;
+
+        // Only the hits are compared, the parameters changing the way they are ranked are kept.
+        search.offset = 0;
+        search.limit = expected.len() + tolerance;
+        search.page = None;
+        search.hits_per_page = None;
+        search.show_ranking_score = true;
+        search.attributes_to_retrieve = primary_key.clone().map(|pk| BTreeSet::from([pk]));
+        search.attributes_to_highlight = None;
+        search.attributes_to_crop = None;
+        search.facets = None;
+
+        let result = perform_search(index, search, search_kind, canceled.clone(), false, None)?;
+        let hits: Vec<_> = result
+            .hits
+            .iter()
+            .map(|hit| {
+                let id = primary_key.as_ref().and_then(|pk| hit.document.get(pk));
+                (id.and_then(external_id).unwrap_or_default(), hit.ranking_score)
+            })
+            .collect();
+
+        let expected: Vec<_> = expected
+            .into_iter()
+            .enumerate()
+            .map(
+                |(expected_position, ExpectedHit { id, ranking_score: expected_ranking_score })| {
+                    let position = hits.iter().position(|(hit_id, _)| *hit_id == id);
+                    let ranking_score = position.and_then(|position| hits[position].1);
+                    HitReport {
+                        regressed: position.map_or(true, |p| p > expected_position + tolerance),
+                        ranking_score_diff: ranking_score
+                            .zip(expected_ranking_score)
+                            .map(|(score, expected)| score - expected),
+                        id,
+                        expected_position,
+                        position,
+                        expected_ranking_score,
+                        ranking_score,
+                    }
+                },
+            )
+            .collect();
+
+        let regressed = expected.iter().any(|hit| hit.regressed);
+        if regressed {
+            regressions.push(name.clone());
+        }
+        let ids = hits.into_iter().map(|(id, _)| id).collect();
+        reports.push(QueryReport { name, regressed, ids, expected });
+    }
+
+    Ok(RelevanceReport { regressions, queries: reports })
+}
+
+/// Returns the external id of a document from the value of its primary key.
+pub fn external_id(value: &Value) -> Option<String> {
+    match value {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) if id.is_u64() || id.is_i64() => Some(id.to_string()),
+        _ => None,
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::str;
 
 use actix_web::web::Data;
@@ -10,8 +11,9 @@ use meilisearch_types::deserr::query_params::Param;
 use meilisearch_types::deserr::{DeserrJsonError, DeserrQueryParamError};
 use meilisearch_types::error::deserr_codes::*;
 use meilisearch_types::error::{Code, ResponseError};
-use meilisearch_types::keys::{CreateApiKey, Key, PatchApiKey};
+use meilisearch_types::keys::{CreateApiKey, ImportApiKey, Key, PatchApiKey};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
@@ -27,6 +29,7 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route(web::post().to(SeqHandler(create_api_key)))
             .route(web::get().to(SeqHandler(list_api_keys))),
     )
+    .service(web::resource("/import").route(web::post().to(SeqHandler(import_api_keys))))
     .service(
         web::resource("/{key}")
             .route(web::get().to(SeqHandler(get_api_key)))
@@ -51,6 +54,110 @@ pub async fn create_api_key(
     Ok(HttpResponse::Created().json(res))
 }
 
+/// What to do with an imported key whose uid is the one of an existing key.
+#[derive(Deserr, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[deserr(rename_all = camelCase)]
+pub enum OnConflict {
+    /// Keep the existing key.
+    #[default]
+    Skip,
+    /// Replace the name and description of the existing key with the ones of the imported key.
+    ///
+    /// The import of a key whose actions, indexes or expiration date differ fails.
+    Overwrite,
+}
+
+#[derive(Deserr, Debug, Clone, Copy)]
+#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
+pub struct ImportApiKeys {
+    #[deserr(default, error = DeserrQueryParamError<InvalidApiKeyOnConflict>)]
+    pub on_conflict: OnConflict,
+}
+
+/// Creates the keys of the payload with their uids, so that their values are the same as
+/// on the instance they were exported from if it has the same master key.
+///
+/// Each key is validated and imported independently, the response reports the outcome of
+/// each of them in the order of the payload.
+pub async fn import_api_keys(
+    auth_controller: GuardedData<ActionPolicy<{ actions::KEYS_CREATE }>, Data<AuthController>>,
+    params: AwebQueryParameter<ImportApiKeys, DeserrQueryParamError>,
+    body: AwebJson<Vec<Value>, DeserrJsonError>,
+) -> Result<HttpResponse, ResponseError> {
+    let overwrite = params.into_inner().on_conflict == OnConflict::Overwrite;
+    let keys = body.into_inner();
+    let results = tokio::task::spawn_blocking(move || {
+        keys.into_iter()
+            .map(|key| import_api_key(&auth_controller, key, overwrite))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| ResponseError::from_msg(e.to_string(), Code::Internal))?;
+
+    Ok(HttpResponse::Ok().json(KeysImportView { results }))
+}
+
+fn import_api_key(auth_controller: &AuthController, key: Value, overwrite: bool) -> KeyImportView {
+    let key = match deserr::deserialize::<ImportApiKey, _, DeserrJsonError>(key) {
+        Ok(key) => CreateApiKey::from(key),
+        Err(error) => return KeyImportView::failed(None, error.into()),
+    };
+    let uid = key.uid;
+
+    match auth_controller.import_key(key, overwrite) {
+        Ok((key, overwritten)) => KeyImportView {
+            uid: Some(uid),
+            status: if overwritten {
+                KeyImportStatus::Overwritten
+            } else {
+                KeyImportStatus::Created
+            },
+            key: Some(KeyView::from_key(key, auth_controller)),
+            error: None,
+        },
+        Err(AuthControllerError::ApiKeyAlreadyExists(_)) => KeyImportView {
+            uid: Some(uid),
+            status: KeyImportStatus::Skipped,
+            key: None,
+            error: None,
+        },
+        Err(error) => KeyImportView::failed(Some(uid), error.into()),
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct KeysImportView {
+    results: Vec<KeyImportView>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct KeyImportView {
+    /// The uid of the key, unknown when the key could not be deserialized.
+    uid: Option<Uuid>,
+    status: KeyImportStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<KeyView>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ResponseError>,
+}
+
+impl KeyImportView {
+    fn failed(uid: Option<Uuid>, error: ResponseError) -> Self {
+        KeyImportView { uid, status: KeyImportStatus::Failed, key: None, error: Some(error) }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum KeyImportStatus {
+    Created,
+    Overwritten,
+    Skipped,
+    Failed,
+}
+
 #[derive(Deserr, Debug, Clone, Copy)]
 #[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
 pub struct ListApiKeys {
@@ -153,6 +260,10 @@ struct KeyView {
     uid: Uuid,
     actions: Vec<Action>,
     indexes: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    index_groups: Vec<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    default_filters: BTreeMap<String, String>,
     #[serde(serialize_with = "time::serde::rfc3339::option::serialize")]
     expires_at: Option<OffsetDateTime>,
     #[serde(serialize_with = "time::serde::rfc3339::serialize")]
@@ -172,6 +283,12 @@ impl KeyView {
             uid: key.uid,
             actions: key.actions,
             indexes: key.indexes.into_iter().map(|x| x.to_string()).collect(),
+            index_groups: key.index_groups,
+            default_filters: key
+                .default_filters
+                .into_iter()
+                .map(|(pattern, filter)| (pattern.to_string(), filter))
+                .collect(),
             expires_at: key.expires_at,
             created_at: key.created_at,
             updated_at: key.updated_at,
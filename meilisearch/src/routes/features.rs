@@ -49,6 +49,18 @@ pub struct RuntimeTogglableFeatures {
     pub logs_route: Option<bool>,
     #[deserr(default)]
     pub export_puffin_reports: Option<bool>,
+    #[deserr(default)]
+    pub debug_routes: Option<bool>,
+    #[deserr(default)]
+    pub relevance_check: Option<bool>,
+    #[deserr(default)]
+    pub word_frequency: Option<bool>,
+    #[deserr(default)]
+    pub document_tasks: Option<bool>,
+    #[deserr(default)]
+    pub recent_searches: Option<bool>,
+    #[deserr(default)]
+    pub ranking_rules_override: Option<bool>,
 }
 
 async fn patch_features(
@@ -72,6 +84,15 @@ async fn patch_features(
             .0
             .export_puffin_reports
             .unwrap_or(old_features.export_puffin_reports),
+        debug_routes: new_features.0.debug_routes.unwrap_or(old_features.debug_routes),
+        relevance_check: new_features.0.relevance_check.unwrap_or(old_features.relevance_check),
+        word_frequency: new_features.0.word_frequency.unwrap_or(old_features.word_frequency),
+        document_tasks: new_features.0.document_tasks.unwrap_or(old_features.document_tasks),
+        recent_searches: new_features.0.recent_searches.unwrap_or(old_features.recent_searches),
+        ranking_rules_override: new_features
+            .0
+            .ranking_rules_override
+            .unwrap_or(old_features.ranking_rules_override),
     };
 
     // explicitly destructure for analytics rather than using the `Serialize` implementation, because
@@ -82,6 +103,12 @@ async fn patch_features(
         metrics,
         logs_route,
         export_puffin_reports,
+        debug_routes,
+        relevance_check,
+        word_frequency,
+        document_tasks,
+        recent_searches,
+        ranking_rules_override,
     } = new_features;
 
     analytics.publish(
@@ -91,6 +118,12 @@ async fn patch_features(
             "metrics": metrics,
             "logs_route": logs_route,
             "export_puffin_reports": export_puffin_reports,
+            "debug_routes": debug_routes,
+            "relevance_check": relevance_check,
+            "word_frequency": word_frequency,
+            "document_tasks": document_tasks,
+            "recent_searches": recent_searches,
+            "ranking_rules_override": ranking_rules_override,
         }),
         Some(&req),
     );
@@ -0,0 +1,147 @@
+use actix_web::web::{self, Data};
+use actix_web::{HttpRequest, HttpResponse};
+use deserr::actix_web::AwebJson;
+use deserr::Deserr;
+use index_scheduler::IndexScheduler;
+use meilisearch_auth::AuthController;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::{Code, ResponseError};
+use meilisearch_types::index_uid_pattern::IndexUidPattern;
+use meilisearch_types::keys::actions;
+use serde::Serialize;
+use serde_json::json;
+use tracing::debug;
+
+use crate::analytics::Analytics;
+use crate::extractors::authentication::policies::ActionPolicy;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::get().to(SeqHandler(list_index_groups)))).service(
+        web::resource("/{group}")
+            .route(web::get().to(SeqHandler(get_index_group)))
+            .route(web::put().to(SeqHandler(put_index_group)))
+            .route(web::delete().to(SeqHandler(delete_index_group))),
+    );
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexGroupView {
+    pub name: String,
+    pub indexes: Vec<IndexUidPattern>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndexGroupsView {
+    pub results: Vec<IndexGroupView>,
+}
+
+async fn list_index_groups(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::INDEX_GROUPS_GET }>, Data<IndexScheduler>>,
+    req: HttpRequest,
+    analytics: Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    analytics.publish("Index Groups Seen".to_string(), json!(null), Some(&req));
+
+    let results = index_scheduler
+        .index_groups()?
+        .into_iter()
+        .map(|(name, indexes)| IndexGroupView { name, indexes })
+        .collect();
+    let groups = IndexGroupsView { results };
+
+    debug!(returns = ?groups, "List index groups");
+    Ok(HttpResponse::Ok().json(groups))
+}
+
+async fn get_index_group(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::INDEX_GROUPS_GET }>, Data<IndexScheduler>>,
+    group: web::Path<String>,
+) -> Result<HttpResponse, ResponseError> {
+    let name = group.into_inner();
+    let indexes = index_scheduler.index_group(&name)?;
+    let group = IndexGroupView { name, indexes };
+
+    debug!(returns = ?group, "Get index group");
+    Ok(HttpResponse::Ok().json(group))
+}
+
+#[derive(Debug, Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct IndexGroupPayload {
+    #[deserr(error = DeserrJsonError<InvalidIndexGroupIndexes>, missing_field_error = DeserrJsonError::missing_index_group_indexes)]
+    pub indexes: Vec<IndexUidPattern>,
+}
+
+/// Creates the index group or replaces its patterns.
+///
+/// The keys referencing the group are scoped to its new patterns as soon as it is modified.
+async fn put_index_group(
+    index_scheduler: GuardedData<
+        ActionPolicy<{ actions::INDEX_GROUPS_UPDATE }>,
+        Data<IndexScheduler>,
+    >,
+    auth_controller: Data<AuthController>,
+    group: web::Path<String>,
+    body: AwebJson<IndexGroupPayload, DeserrJsonError>,
+    req: HttpRequest,
+    analytics: Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let name = group.into_inner();
+    let IndexGroupPayload { indexes } = body.into_inner();
+    debug!(parameters = ?indexes, "Put index group");
+    validate_index_group_name(&name)?;
+
+    analytics.publish(
+        "Index Group Updated".to_string(),
+        json!({ "indexes": indexes.len() }),
+        Some(&req),
+    );
+
+    index_scheduler.put_index_group(&name, indexes.clone())?;
+    auth_controller.set_index_groups(index_scheduler.index_groups()?);
+
+    let group = IndexGroupView { name, indexes };
+    debug!(returns = ?group, "Put index group");
+    Ok(HttpResponse::Ok().json(group))
+}
+
+/// Deletes the index group, the keys referencing it lose the access it granted.
+async fn delete_index_group(
+    index_scheduler: GuardedData<
+        ActionPolicy<{ actions::INDEX_GROUPS_UPDATE }>,
+        Data<IndexScheduler>,
+    >,
+    auth_controller: Data<AuthController>,
+    group: web::Path<String>,
+    req: HttpRequest,
+    analytics: Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    analytics.publish("Index Group Deleted".to_string(), json!(null), Some(&req));
+
+    index_scheduler.delete_index_group(&group)?;
+    auth_controller.set_index_groups(index_scheduler.index_groups()?);
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// An index group name follows the same rules as an index uid.
+fn validate_index_group_name(name: &str) -> Result<(), ResponseError> {
+    if name.is_empty()
+        || name.len() > 400
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(ResponseError::from_msg(
+            format!(
+                "`{name}` is not a valid index group name. Index group name can be an \
+                integer or a string containing only alphanumeric \
+                characters, hyphens (-) and underscores (_)."
+            ),
+            Code::InvalidIndexGroupName,
+        ));
+    }
+    Ok(())
+}
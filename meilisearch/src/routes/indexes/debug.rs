@@ -0,0 +1,85 @@
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse};
+use index_scheduler::IndexScheduler;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::milli::DocumentId;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::error::MeilisearchHttpError;
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/docid/{internal_id}").route(web::get().to(SeqHandler(get_external_id))),
+    )
+    .service(
+        web::resource("/external-id/{external_id}")
+            .route(web::get().to(SeqHandler(get_internal_id))),
+    );
+}
+
+#[derive(Deserialize)]
+pub struct InternalIdParam {
+    index_uid: String,
+    internal_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct ExternalIdParam {
+    index_uid: String,
+    external_id: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentIds {
+    internal_id: DocumentId,
+    external_id: String,
+}
+
+pub async fn get_external_id(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::DOCUMENTS_GET }>, Data<IndexScheduler>>,
+    path: web::Path<InternalIdParam>,
+) -> Result<HttpResponse, ResponseError> {
+    index_scheduler.features().check_debug_routes()?;
+
+    let InternalIdParam { index_uid, internal_id } = path.into_inner();
+    let index_uid = IndexUid::try_from(index_uid)?;
+
+    let index = index_scheduler.index(&index_uid)?;
+    let rtxn = index.read_txn()?;
+    let documents_ids = index.documents_ids(&rtxn)?;
+    let internal_id = match internal_id.parse::<DocumentId>() {
+        Ok(id) if documents_ids.contains(id) => id,
+        _ => return Err(MeilisearchHttpError::DocumentNotFound(internal_id).into()),
+    };
+    let external_id = index.external_ids_of(&rtxn, [internal_id])?.remove(0);
+
+    let ids = DocumentIds { internal_id, external_id };
+    debug!(returns = ?ids, "Get external id");
+    Ok(HttpResponse::Ok().json(ids))
+}
+
+pub async fn get_internal_id(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::DOCUMENTS_GET }>, Data<IndexScheduler>>,
+    path: web::Path<ExternalIdParam>,
+) -> Result<HttpResponse, ResponseError> {
+    index_scheduler.features().check_debug_routes()?;
+
+    let ExternalIdParam { index_uid, external_id } = path.into_inner();
+    let index_uid = IndexUid::try_from(index_uid)?;
+
+    let index = index_scheduler.index(&index_uid)?;
+    let rtxn = index.read_txn()?;
+    let internal_id = index
+        .internal_id_of(&rtxn, &external_id)?
+        .ok_or_else(|| MeilisearchHttpError::DocumentNotFound(external_id.clone()))?;
+
+    let ids = DocumentIds { internal_id, external_id };
+    debug!(returns = ?ids, "Get internal id");
+    Ok(HttpResponse::Ok().json(ids))
+}
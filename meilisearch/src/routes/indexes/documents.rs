@@ -1,16 +1,19 @@
 use std::io::ErrorKind;
 
 use actix_web::http::header::CONTENT_TYPE;
-use actix_web::web::Data;
+use actix_web::web::{Bytes, Data};
 use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
 use bstr::ByteSlice as _;
 use deserr::actix_web::{AwebJson, AwebQueryParameter};
 use deserr::Deserr;
 use futures::StreamExt;
 use index_scheduler::{IndexScheduler, TaskId};
+use meilisearch_auth::and_filters;
 use meilisearch_types::deserr::query_params::Param;
 use meilisearch_types::deserr::{DeserrJsonError, DeserrQueryParamError};
-use meilisearch_types::document_formats::{read_csv, read_json, read_ndjson, PayloadType};
+use meilisearch_types::document_formats::{
+    read_csv, read_json, read_ndjson, PayloadType, Utf8Decoder,
+};
 use meilisearch_types::error::deserr_codes::*;
 use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::heed::RoTxn;
@@ -18,15 +21,17 @@ use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::milli::update::IndexDocumentsMethod;
 use meilisearch_types::milli::DocumentId;
 use meilisearch_types::star_or::OptionStarOrList;
-use meilisearch_types::tasks::KindWithContent;
+use meilisearch_types::tasks::{Details, KindWithContent};
 use meilisearch_types::{milli, Document, Index};
 use mime::Mime;
 use once_cell::sync::Lazy;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tempfile::tempfile;
 use tokio::fs::File;
 use tokio::io::{AsyncSeekExt, AsyncWriteExt, BufWriter};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::ReceiverStream;
 use tracing::debug;
 
 use crate::analytics::{Analytics, DocumentDeletionKind, DocumentFetchKind};
@@ -36,12 +41,15 @@ use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
 use crate::extractors::payload::Payload;
 use crate::extractors::sequential_extractor::SeqHandler;
-use crate::routes::{
-    get_task_id, is_dry_run, PaginationView, SummarizedTaskView, PAGINATION_DEFAULT_LIMIT,
-};
+use crate::routes::{get_task_id, is_dry_run, SummarizedTaskView, PAGINATION_DEFAULT_LIMIT};
 use crate::search::parse_filter;
 use crate::Opt;
 
+/// The number of bytes of serialized documents buffered before being sent to the client.
+const DOCUMENTS_CHUNK_SIZE: usize = 64 * 1024;
+/// The number of chunks the thread reading the documents can get ahead of the client.
+const DOCUMENTS_CHANNEL_CAPACITY: usize = 4;
+
 static ACCEPTED_CONTENT_TYPE: Lazy<Vec<String>> = Lazy::new(|| {
     vec!["application/json".to_string(), "application/x-ndjson".to_string(), "text/csv".to_string()]
 });
@@ -86,6 +94,9 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         web::resource("/{document_id}")
             .route(web::get().to(SeqHandler(get_document)))
             .route(web::delete().to(SeqHandler(delete_document))),
+    )
+    .service(
+        web::resource("/{document_id}/tasks").route(web::get().to(SeqHandler(get_document_tasks))),
     );
 }
 
@@ -112,12 +123,57 @@ pub async fn get_document(
     let GetDocument { fields } = params.into_inner();
     let attributes_to_retrieve = fields.merge_star_and_none();
 
+    // actix keeps some characters of the path percent-encoded, like `%2F`
+    let document_id = match urlencoding::decode(&document_id) {
+        Ok(decoded) => decoded.into_owned(),
+        Err(_) => document_id,
+    };
+
     let index = index_scheduler.index(&index_uid)?;
-    let document = retrieve_document(&index, &document_id, attributes_to_retrieve)?;
+    let filter =
+        index_scheduler.filters().get_index_search_rules(&index_uid).and_then(|rules| rules.filter);
+    let document = retrieve_document(&index, document_id.trim(), attributes_to_retrieve, filter)?;
     debug!(returns = ?document, "Get document");
     Ok(HttpResponse::Ok().json(document))
 }
 
+/// The uids of the last tasks that added, updated, or deleted a document, newest first.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentTasksView {
+    document_id: String,
+    task_uids: Vec<TaskId>,
+}
+
+pub async fn get_document_tasks(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::DOCUMENTS_GET }>, Data<IndexScheduler>>,
+    document_param: web::Path<DocumentParam>,
+) -> Result<HttpResponse, ResponseError> {
+    index_scheduler.features().check_document_tasks()?;
+
+    let DocumentParam { index_uid, document_id } = document_param.into_inner();
+    let index_uid = IndexUid::try_from(index_uid)?;
+
+    // actix keeps some characters of the path percent-encoded, like `%2F`
+    let document_id = match urlencoding::decode(&document_id) {
+        Ok(decoded) => decoded.trim().to_string(),
+        Err(_) => document_id.trim().to_string(),
+    };
+
+    let index = index_scheduler.index(&index_uid)?;
+    let filter =
+        index_scheduler.filters().get_index_search_rules(&index_uid).and_then(|rules| rules.filter);
+    // The history of the documents the filter of the key hides is hidden too.
+    if filter.is_some() {
+        retrieve_document(&index, &document_id, Some(Vec::<String>::new()), filter)?;
+    }
+
+    let rtxn = index.read_txn()?;
+    let task_uids = index.document_tasks(&rtxn, &document_id)?;
+    debug!(returns = ?task_uids, "Get document tasks");
+    Ok(HttpResponse::Ok().json(DocumentTasksView { document_id, task_uids }))
+}
+
 pub async fn delete_document(
     index_scheduler: GuardedData<ActionPolicy<{ actions::DOCUMENTS_DELETE }>, Data<IndexScheduler>>,
     path: web::Path<DocumentParam>,
@@ -189,7 +245,7 @@ pub async fn documents_by_query_post(
         &req,
     );
 
-    documents_by_query(&index_scheduler, index_uid, body)
+    documents_by_query(&index_scheduler, index_uid, body).await
 }
 
 pub async fn get_documents(
@@ -227,33 +283,130 @@ pub async fn get_documents(
         &req,
     );
 
-    documents_by_query(&index_scheduler, index_uid, query)
+    documents_by_query(&index_scheduler, index_uid, query).await
 }
 
-fn documents_by_query(
-    index_scheduler: &IndexScheduler,
+/// Streams the page of documents, so that only a few chunks of it are in memory at once
+/// whatever its size.
+async fn documents_by_query(
+    index_scheduler: &GuardedData<ActionPolicy<{ actions::DOCUMENTS_GET }>, Data<IndexScheduler>>,
     index_uid: web::Path<String>,
-    query: BrowseQuery,
+    mut query: BrowseQuery,
 ) -> Result<HttpResponse, ResponseError> {
     let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    // The default filter of the API key.
+    if let Some(rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
+        query.filter = and_filters(query.filter.take(), rules.filter);
+    }
+    let index = index_scheduler.index(&index_uid)?;
+
+    let (total_sender, total_receiver) = oneshot::channel();
+    let (sender, receiver) = mpsc::channel(DOCUMENTS_CHANNEL_CAPACITY);
+    tokio::task::spawn_blocking(move || stream_documents(index, query, total_sender, sender));
+    let total = match total_receiver.await {
+        Ok(total) => total?,
+        // the reading thread panicked
+        Err(_) => {
+            return Err(ResponseError::from_msg(
+                String::from("The documents could not be read."),
+                Code::Internal,
+            ))
+        }
+    };
+
+    debug!(total, "Get documents");
+    Ok(HttpResponse::Ok()
+        .content_type(mime::APPLICATION_JSON)
+        .streaming(ReceiverStream::new(receiver)))
+}
+
+/// Reads the page of documents in a single read transaction, held while the page is streamed.
+///
+/// The total number of documents, or the error preventing to read them, is sent before any chunk
+/// so that such an error is returned as a regular error response.
+fn stream_documents(
+    index: Index,
+    query: BrowseQuery,
+    total_sender: oneshot::Sender<Result<u64, ResponseError>>,
+    sender: mpsc::Sender<Result<Bytes, ResponseError>>,
+) {
     let BrowseQuery { offset, limit, fields, filter } = query;
 
-    let index = index_scheduler.index(&index_uid)?;
-    let (total, documents) = retrieve_documents(&index, offset, limit, filter, fields)?;
+    let rtxn = match index.read_txn() {
+        Ok(rtxn) => rtxn,
+        Err(e) => {
+            let _ = total_sender.send(Err(e.into()));
+            return;
+        }
+    };
+
+    match retrieve_documents(&index, &rtxn, offset, limit, filter, fields) {
+        Ok((total, documents)) => {
+            // the request was dropped, there is no one to stream the documents to
+            if total_sender.send(Ok(total)).is_err() {
+                return;
+            }
+            write_documents_page(&sender, documents, offset, limit, total);
+        }
+        Err(e) => {
+            let _ = total_sender.send(Err(e));
+        }
+    }
+}
 
-    let ret = PaginationView::new(offset, limit, total as usize, documents);
+/// Serializes the documents as the JSON body of a [`PaginationView`](crate::routes::PaginationView)
+/// and sends it in chunks of about [`DOCUMENTS_CHUNK_SIZE`] bytes.
+///
+/// A document that can't be read aborts the response: what was serialized so far is sent, then the
+/// error, on which actix closes the connection. We don't write an error in the body, the status
+/// code being already sent, but the truncated body is never a valid JSON, so a client can't mistake
+/// it for a complete page.
+fn write_documents_page(
+    sender: &mpsc::Sender<Result<Bytes, ResponseError>>,
+    documents: impl Iterator<Item = Result<Document, ResponseError>>,
+    offset: usize,
+    limit: usize,
+    total: u64,
+) {
+    let mut buffer = br#"{"results":["#.to_vec();
+    for (i, document) in documents.enumerate() {
+        let written = document.and_then(|document| {
+            if i != 0 {
+                buffer.push(b',');
+            }
+            serde_json::to_writer(&mut buffer, &document)
+                .map_err(|e| ResponseError::from_msg(e.to_string(), Code::Internal))
+        });
+        if let Err(error) = written {
+            let _ = sender.blocking_send(Ok(buffer.into()));
+            let _ = sender.blocking_send(Err(error));
+            return;
+        }
+
+        // the client is gone when the receiver is dropped
+        if buffer.len() >= DOCUMENTS_CHUNK_SIZE
+            && sender.blocking_send(Ok(std::mem::take(&mut buffer).into())).is_err()
+        {
+            return;
+        }
+    }
 
-    debug!(returns = ?ret, "Get documents");
-    Ok(HttpResponse::Ok().json(ret))
+    buffer.extend_from_slice(
+        format!(r#"],"offset":{offset},"limit":{limit},"total":{total}}}"#).as_bytes(),
+    );
+    let _ = sender.blocking_send(Ok(buffer.into()));
 }
 
-#[derive(Deserialize, Debug, Deserr)]
+#[derive(Debug, Deserr)]
 #[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
 pub struct UpdateDocumentsQuery {
     #[deserr(default, error = DeserrQueryParamError<InvalidIndexPrimaryKey>)]
     pub primary_key: Option<String>,
     #[deserr(default, try_from(char) = from_char_csv_delimiter -> DeserrQueryParamError<InvalidDocumentCsvDelimiter>, error = DeserrQueryParamError<InvalidDocumentCsvDelimiter>)]
     pub csv_delimiter: Option<u8>,
+    /// Replace the invalid UTF-8 sequences of the payload with U+FFFD instead of refusing it.
+    #[deserr(default, error = DeserrQueryParamError<InvalidDocumentLossyUtf8>)]
+    pub lossy_utf8: Param<bool>,
 }
 
 fn from_char_csv_delimiter(
@@ -294,6 +447,7 @@ pub async fn replace_documents(
         index_uid,
         params.primary_key,
         params.csv_delimiter,
+        params.lossy_utf8.0,
         body,
         IndexDocumentsMethod::ReplaceDocuments,
         uid,
@@ -331,6 +485,7 @@ pub async fn update_documents(
         index_uid,
         params.primary_key,
         params.csv_delimiter,
+        params.lossy_utf8.0,
         body,
         IndexDocumentsMethod::UpdateDocuments,
         uid,
@@ -350,6 +505,7 @@ async fn document_addition(
     index_uid: IndexUid,
     primary_key: Option<String>,
     csv_delimiter: Option<u8>,
+    lossy_utf8: bool,
     mut body: Payload,
     method: IndexDocumentsMethod,
     task_id: Option<TaskId>,
@@ -396,6 +552,9 @@ async fn document_addition(
     let async_file = File::from_std(temp_file);
     let mut buffer = BufWriter::new(async_file);
 
+    // the encoding is validated while the payload is received, before it is parsed
+    let mut decoder = Utf8Decoder::new(format, lossy_utf8);
+    let mut decoded = Vec::new();
     let mut buffer_write_size: usize = 0;
     while let Some(result) = body.next().await {
         let byte = result?;
@@ -404,12 +563,20 @@ async fn document_addition(
             return Err(MeilisearchHttpError::MissingPayload(format));
         }
 
-        match buffer.write_all(&byte).await {
+        decoded.clear();
+        decoder.decode(&byte, &mut decoded)?;
+        match buffer.write_all(&decoded).await {
             Ok(()) => buffer_write_size += 1,
             Err(e) => return Err(MeilisearchHttpError::Payload(ReceivePayload(Box::new(e)))),
         }
     }
 
+    decoded.clear();
+    let invalid_utf8_replacements = decoder.finish(&mut decoded)?;
+    if let Err(e) = buffer.write_all(&decoded).await {
+        return Err(MeilisearchHttpError::Payload(ReceivePayload(Box::new(e))));
+    }
+
     if let Err(e) = buffer.flush().await {
         return Err(MeilisearchHttpError::Payload(ReceivePayload(Box::new(e))));
     }
@@ -418,6 +585,9 @@ async fn document_addition(
         return Err(MeilisearchHttpError::MissingPayload(format));
     }
 
+    // the payload was hashed while it was written, nothing is enqueued if it was corrupted
+    body.verify_checksum()?;
+
     if let Err(e) = buffer.seek(std::io::SeekFrom::Start(0)).await {
         return Err(MeilisearchHttpError::Payload(ReceivePayload(Box::new(e))));
     }
@@ -467,9 +637,20 @@ async fn document_addition(
         index_uid: index_uid.to_string(),
     };
 
+    let details = task.default_details().map(|mut details| {
+        if let Details::DocumentAdditionOrUpdate { invalid_utf8_replacements: count, .. } =
+            &mut details
+        {
+            *count = invalid_utf8_replacements;
+        }
+        details
+    });
+
     let scheduler = index_scheduler.clone();
-    let task = match tokio::task::spawn_blocking(move || scheduler.register(task, task_id, dry_run))
-        .await?
+    let task = match tokio::task::spawn_blocking(move || {
+        scheduler.register_with_details(task, details, task_id, dry_run)
+    })
+    .await?
     {
         Ok(task) => task,
         Err(e) => {
@@ -585,19 +766,19 @@ fn some_documents<'a, 't: 'a>(
 
     Ok(index.iter_documents(rtxn, doc_ids)?.map(move |ret| {
         ret.map_err(ResponseError::from).and_then(|(_key, document)| -> Result<_, ResponseError> {
-            Ok(milli::obkv_to_json(&all_fields, &fields_ids_map, document)?)
+            Ok(milli::obkv_to_json(&all_fields, &fields_ids_map, document.reader())?)
         })
     }))
 }
 
-fn retrieve_documents<S: AsRef<str>>(
-    index: &Index,
+fn retrieve_documents<'a, 't: 'a, S: AsRef<str> + 'a>(
+    index: &'a Index,
+    rtxn: &'t RoTxn,
     offset: usize,
     limit: usize,
     filter: Option<Value>,
     attributes_to_retrieve: Option<Vec<S>>,
-) -> Result<(u64, Vec<Document>), ResponseError> {
-    let rtxn = index.read_txn()?;
+) -> Result<(u64, impl Iterator<Item = Result<Document, ResponseError>> + 'a), ResponseError> {
     let filter = &filter;
     let filter = if let Some(filter) = filter {
         parse_filter(filter)
@@ -607,26 +788,14 @@ fn retrieve_documents<S: AsRef<str>>(
     };
 
     let candidates = if let Some(filter) = filter {
-        filter.evaluate(&rtxn, index).map_err(|err| match err {
-            milli::Error::UserError(milli::UserError::InvalidFilter(_)) => {
-                ResponseError::from_msg(err.to_string(), Code::InvalidDocumentFilter)
-            }
-            e => e.into(),
-        })?
+        filter.evaluate(rtxn, index).map_err(invalid_document_filter)?
     } else {
-        index.documents_ids(&rtxn)?
+        index.documents_ids(rtxn)?
     };
 
-    let (it, number_of_documents) = {
-        let number_of_documents = candidates.len();
-        (
-            some_documents(index, &rtxn, candidates.into_iter().skip(offset).take(limit))?,
-            number_of_documents,
-        )
-    };
-
-    let documents: Result<Vec<_>, ResponseError> = it
-        .map(|document| {
+    let number_of_documents = candidates.len();
+    let documents = some_documents(index, rtxn, candidates.into_iter().skip(offset).take(limit))?
+        .map(move |document| -> Result<_, ResponseError> {
             Ok(match &attributes_to_retrieve {
                 Some(attributes_to_retrieve) => permissive_json_pointer::select_values(
                     &document?,
@@ -634,16 +803,26 @@ fn retrieve_documents<S: AsRef<str>>(
                 ),
                 None => document?,
             })
-        })
-        .collect();
+        });
+
+    Ok((number_of_documents, documents))
+}
 
-    Ok((number_of_documents, documents?))
+fn invalid_document_filter(err: milli::Error) -> ResponseError {
+    match err {
+        milli::Error::UserError(milli::UserError::InvalidFilter(_)) => {
+            ResponseError::from_msg(err.to_string(), Code::InvalidDocumentFilter)
+        }
+        e => e.into(),
+    }
 }
 
+/// Retrieves a document, as if it didn't exist when it doesn't match the filter.
 fn retrieve_document<S: AsRef<str>>(
     index: &Index,
     doc_id: &str,
     attributes_to_retrieve: Option<Vec<S>>,
+    filter: Option<Value>,
 ) -> Result<Document, ResponseError> {
     let txn = index.read_txn()?;
 
@@ -655,6 +834,17 @@ fn retrieve_document<S: AsRef<str>>(
         .get(&txn, doc_id)?
         .ok_or_else(|| MeilisearchHttpError::DocumentNotFound(doc_id.to_string()))?;
 
+    let filter = match &filter {
+        Some(filter) => parse_filter(filter)
+            .map_err(|err| ResponseError::from_msg(err.to_string(), Code::InvalidDocumentFilter))?,
+        None => None,
+    };
+    if let Some(filter) = filter {
+        if !filter.evaluate(&txn, index).map_err(invalid_document_filter)?.contains(internal_id) {
+            return Err(MeilisearchHttpError::DocumentNotFound(doc_id.to_string()).into());
+        }
+    }
+
     let document = index
         .documents(&txn, std::iter::once(internal_id))?
         .into_iter()
@@ -662,7 +852,8 @@ fn retrieve_document<S: AsRef<str>>(
         .map(|(_, d)| d)
         .ok_or_else(|| MeilisearchHttpError::DocumentNotFound(doc_id.to_string()))?;
 
-    let document = meilisearch_types::milli::obkv_to_json(&all_fields, &fields_ids_map, document)?;
+    let document =
+        meilisearch_types::milli::obkv_to_json(&all_fields, &fields_ids_map, document.reader())?;
     let document = match &attributes_to_retrieve {
         Some(attributes_to_retrieve) => permissive_json_pointer::select_values(
             &document,
@@ -673,3 +864,61 @@ fn retrieve_document<S: AsRef<str>>(
 
     Ok(document)
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn document(value: Value) -> Result<Document, ResponseError> {
+        Ok(serde_json::from_value(value).unwrap())
+    }
+
+    fn write_chunks(
+        documents: Vec<Result<Document, ResponseError>>,
+    ) -> Vec<Result<Bytes, ResponseError>> {
+        let (sender, mut receiver) = mpsc::channel(DOCUMENTS_CHANNEL_CAPACITY);
+        let writer = std::thread::spawn(move || {
+            write_documents_page(&sender, documents.into_iter(), 10, 1000, 2000)
+        });
+        let mut chunks = Vec::new();
+        while let Some(chunk) = receiver.blocking_recv() {
+            chunks.push(chunk);
+        }
+        writer.join().unwrap();
+        chunks
+    }
+
+    #[test]
+    fn documents_page_streamed_in_chunks() {
+        let documents: Vec<_> =
+            (0..1000).map(|id| document(json!({ "id": id, "text": "a".repeat(100) }))).collect();
+        let chunks = write_chunks(documents);
+        assert!(chunks.len() > 1, "{}", chunks.len());
+
+        let body: Vec<u8> = chunks.into_iter().flat_map(|chunk| chunk.unwrap()).collect();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["results"].as_array().unwrap().len(), 1000);
+        assert_eq!(body["results"][999], json!({ "id": 999, "text": "a".repeat(100) }));
+        assert_eq!(body["offset"], json!(10));
+        assert_eq!(body["limit"], json!(1000));
+        assert_eq!(body["total"], json!(2000));
+    }
+
+    #[test]
+    fn documents_page_aborted_on_error() {
+        let error = ResponseError::from_msg(String::from("doggo"), Code::Internal);
+        let chunks = write_chunks(vec![
+            document(json!({ "id": 1 })),
+            Err(error),
+            document(json!({ "id": 3 })),
+        ]);
+
+        // the documents read before the error are sent, then the error ends the stream
+        let mut chunks = chunks.into_iter();
+        assert_eq!(chunks.next().unwrap().unwrap(), &br#"{"results":[{"id":1}"#[..]);
+        assert_eq!(chunks.next().unwrap().unwrap_err().to_string(), "doggo");
+        assert!(chunks.next().is_none());
+    }
+}
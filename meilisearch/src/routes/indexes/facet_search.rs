@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
 use deserr::actix_web::AwebJson;
@@ -6,18 +8,21 @@ use meilisearch_types::deserr::DeserrJsonError;
 use meilisearch_types::error::deserr_codes::*;
 use meilisearch_types::error::ResponseError;
 use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::milli::{self, UserError, MAX_FACET_QUERY_LENGTH};
 use serde_json::Value;
 use tracing::debug;
 
 use crate::analytics::{Analytics, FacetSearchAggregator};
+use crate::error::MeilisearchHttpError;
 use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
 use crate::routes::indexes::search::search_kind;
 use crate::search::{
-    add_search_rules, perform_facet_search, HybridQuery, MatchingStrategy, SearchQuery,
-    DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG,
+    add_search_rules, perform_facet_search, CancelSearchOnDrop, HybridQuery, MatchingStrategy,
+    SearchQuery, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG,
     DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT, DEFAULT_SEARCH_OFFSET,
 };
+use crate::search_latency::{LatencyKind, SearchLatencies};
 use crate::search_queue::SearchQueue;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -51,6 +56,7 @@ pub struct FacetSearchQuery {
 pub async fn search(
     index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
     search_queue: Data<SearchQueue>,
+    search_latencies: Data<SearchLatencies>,
     index_uid: web::Path<String>,
     params: AwebJson<FacetSearchQuery, DeserrJsonError>,
     req: HttpRequest,
@@ -65,23 +71,36 @@ pub async fn search(
 
     let facet_query = query.facet_query.clone();
     let facet_name = query.facet_name.clone();
+
+    // Reject the queries that can't match any facet value before waiting for a search permit.
+    if let Some(length) = facet_query.as_ref().map(String::len) {
+        if length > MAX_FACET_QUERY_LENGTH {
+            let error = UserError::FacetSearchQueryTooLong { length, max: MAX_FACET_QUERY_LENGTH };
+            return Err(MeilisearchHttpError::from(milli::Error::from(error)).into());
+        }
+    }
+
     let mut search_query = SearchQuery::from(query);
 
     // Tenant token search_rules.
     if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
-        add_search_rules(&mut search_query, search_rules);
+        add_search_rules(&mut search_query, &index_uid, search_rules)?;
     }
 
     let index = index_scheduler.index(&index_uid)?;
     let features = index_scheduler.features();
     let search_kind = search_kind(&search_query, &index_scheduler, &index, features)?;
     let _permit = search_queue.try_get_search_permit().await?;
+    let cancel_on_drop = CancelSearchOnDrop::default();
+    let canceled = cancel_on_drop.token();
+    let started_at = Instant::now();
     let search_result = tokio::task::spawn_blocking(move || {
-        perform_facet_search(&index, search_query, facet_query, facet_name, search_kind)
+        perform_facet_search(&index, search_query, facet_query, facet_name, search_kind, canceled)
     })
     .await?;
 
     if let Ok(ref search_result) = search_result {
+        search_latencies.record(&index_uid, LatencyKind::FacetSearch, started_at.elapsed());
         aggregate.succeed(search_result);
     }
     analytics.post_facet_search(aggregate);
@@ -111,6 +130,7 @@ impl From<FacetSearchQuery> for SearchQuery {
             limit: DEFAULT_SEARCH_LIMIT(),
             page: None,
             hits_per_page: None,
+            search_after: None,
             attributes_to_retrieve: None,
             attributes_to_crop: None,
             crop_length: DEFAULT_CROP_LENGTH(),
@@ -118,16 +138,32 @@ impl From<FacetSearchQuery> for SearchQuery {
             show_matches_position: false,
             show_ranking_score: false,
             show_ranking_score_details: false,
+            ranking_score_threshold: None,
+            show_ranking_bucket_path: false,
+            show_processing_breakdown: false,
+            show_query_tokens: false,
+            show_search_metrics: false,
             filter,
             sort: None,
             facets: None,
+            facet_distribution_mode: None,
+            facet_approximation_threshold: None,
             highlight_pre_tag: DEFAULT_HIGHLIGHT_PRE_TAG(),
             highlight_post_tag: DEFAULT_HIGHLIGHT_POST_TAG(),
+            highlight_typo_pre_tag: None,
+            highlight_typo_post_tag: None,
             crop_marker: DEFAULT_CROP_MARKER(),
             matching_strategy,
+            tie_break: Default::default(),
+            distinct: None,
             vector,
             attributes_to_search_on,
             hybrid,
+            preset: None,
+            synonyms: None,
+            typo_tolerance: None,
+            ranking_rules_override: None,
+            debug: None,
         }
     }
 }
@@ -0,0 +1,96 @@
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse};
+use deserr::actix_web::AwebJson;
+use deserr::Deserr;
+use index_scheduler::IndexScheduler;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::milli::{
+    FilterAssist, FilterSuggestion, FilterTokenKind, DEFAULT_FILTER_SUGGESTIONS_LIMIT,
+};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::error::MeilisearchHttpError;
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+use crate::search::{parse_filter, search_rules_filter};
+
+/// The maximum number of suggestions returned, whatever the requested limit.
+pub const MAX_FILTER_SUGGESTIONS_LIMIT: usize = 100;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::post().to(SeqHandler(filter_assist))));
+}
+
+#[derive(Debug, Clone, Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct FilterAssistQuery {
+    #[deserr(error = DeserrJsonError<InvalidFilterAssistFilter>, missing_field_error = DeserrJsonError::missing_filter_assist_filter)]
+    filter: String,
+    /// The position of the cursor in characters, the end of the filter by default.
+    #[deserr(default, error = DeserrJsonError<InvalidFilterAssistCursor>)]
+    cursor: Option<usize>,
+    #[deserr(default = DEFAULT_FILTER_SUGGESTIONS_LIMIT, error = DeserrJsonError<InvalidFilterAssistLimit>)]
+    limit: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterAssistResult {
+    expected: Option<FilterTokenKind>,
+    /// The position in characters of the beginning of the text replaced by a suggestion.
+    start: usize,
+    /// The position in characters of the end of the text replaced by a suggestion.
+    end: usize,
+    suggestions: Vec<FilterSuggestion>,
+}
+
+pub async fn filter_assist(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    params: AwebJson<FilterAssistQuery, DeserrJsonError>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let FilterAssistQuery { filter, cursor, limit } = params.into_inner();
+    debug!(filter = %filter, ?cursor, limit, "Filter assist");
+
+    // Tenant token search_rules.
+    let rules_filter = match index_scheduler.filters().get_index_search_rules(&index_uid) {
+        Some(rules) => search_rules_filter(&index_uid, rules)?,
+        None => None,
+    };
+
+    let index = index_scheduler.index(&index_uid)?;
+    // milli works with byte offsets
+    let byte_cursor = match cursor {
+        Some(cursor) => filter.char_indices().nth(cursor).map_or(filter.len(), |(i, _)| i),
+        None => filter.len(),
+    };
+    let limit = limit.min(MAX_FILTER_SUGGESTIONS_LIMIT);
+
+    let assisted = filter.clone();
+    let suggestions = tokio::task::spawn_blocking(move || -> Result<_, MeilisearchHttpError> {
+        let rtxn = index.read_txn()?;
+        let mut assist = FilterAssist::new(assisted, byte_cursor, &rtxn, &index);
+        assist.limit(limit);
+        // only the facet values of the documents the caller can search are suggested
+        if let Some(filter) = rules_filter.as_ref().map(parse_filter).transpose()?.flatten() {
+            assist.filter(filter);
+        }
+        Ok(assist.execute()?)
+    })
+    .await??;
+
+    let result = FilterAssistResult {
+        expected: suggestions.expected,
+        start: filter[..suggestions.start].chars().count(),
+        end: filter[..suggestions.end].chars().count(),
+        suggestions: suggestions.suggestions,
+    };
+    debug!(returns = ?result, "Filter assist");
+    Ok(HttpResponse::Ok().json(result))
+}
@@ -1,16 +1,18 @@
+use std::collections::BTreeMap;
 use std::convert::Infallible;
+use std::path::{Path, PathBuf};
 
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
 use deserr::actix_web::{AwebJson, AwebQueryParameter};
 use deserr::{DeserializeError, Deserr, ValuePointerRef};
-use index_scheduler::IndexScheduler;
+use index_scheduler::{IndexScheduler, ParkedIndex};
 use meilisearch_types::deserr::query_params::Param;
 use meilisearch_types::deserr::{immutable_field_error, DeserrJsonError, DeserrQueryParamError};
 use meilisearch_types::error::deserr_codes::*;
 use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::index_uid::IndexUid;
-use meilisearch_types::milli::{self, FieldDistribution, Index};
+use meilisearch_types::milli::{self, DatabaseStats, FieldDistribution, Index};
 use meilisearch_types::tasks::KindWithContent;
 use serde::Serialize;
 use serde_json::json;
@@ -23,12 +25,21 @@ use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::{AuthenticationError, GuardedData};
 use crate::extractors::sequential_extractor::SeqHandler;
 use crate::routes::is_dry_run;
+use crate::search_latency::{SearchLatencies, SearchLatencyStats};
 use crate::Opt;
 
+pub mod debug;
 pub mod documents;
 pub mod facet_search;
+pub mod filter_assist;
+pub mod presets;
+pub mod recent_searches;
+pub mod related_terms;
+pub mod relevance_check;
 pub mod search;
 pub mod settings;
+pub mod timeline;
+pub mod word_frequency;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -45,10 +56,22 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
                     .route(web::delete().to(SeqHandler(delete_index))),
             )
             .service(web::resource("/stats").route(web::get().to(SeqHandler(get_index_stats))))
+            .service(web::resource("/unpark").route(web::post().to(SeqHandler(unpark_index))))
+            .service(web::resource("/restore").route(web::post().to(SeqHandler(restore_index))))
+            .service(web::resource("/check").route(web::post().to(SeqHandler(check_index))))
+            .service(web::resource("/warmup").route(web::post().to(SeqHandler(warm_up_index))))
             .service(web::scope("/documents").configure(documents::configure))
             .service(web::scope("/search").configure(search::configure))
             .service(web::scope("/facet-search").configure(facet_search::configure))
-            .service(web::scope("/settings").configure(settings::configure)),
+            .service(web::scope("/filter-assist").configure(filter_assist::configure))
+            .service(web::scope("/presets").configure(presets::configure))
+            .service(web::scope("/recent-searches").configure(recent_searches::configure))
+            .service(web::scope("/relevance-check").configure(relevance_check::configure))
+            .service(web::scope("/related-terms").configure(related_terms::configure))
+            .service(web::scope("/settings").configure(settings::configure))
+            .service(web::scope("/timeline").configure(timeline::configure))
+            .service(web::scope("/word-frequency").configure(word_frequency::configure))
+            .service(web::scope("/_debug").configure(debug::configure)),
     );
 }
 
@@ -61,10 +84,17 @@ pub struct IndexView {
     #[serde(with = "time::serde::rfc3339")]
     pub updated_at: OffsetDateTime,
     pub primary_key: Option<String>,
+    /// Only returned when the index is parked because its batches kept panicking.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parked: Option<ParkedIndexView>,
 }
 
 impl IndexView {
-    fn new(uid: String, index: &Index) -> Result<IndexView, milli::Error> {
+    fn new(
+        uid: String,
+        index: &Index,
+        parked: Option<ParkedIndex>,
+    ) -> Result<IndexView, milli::Error> {
         // It is important that this function does not keep the Index handle or a clone of it, because
         // `list_indexes` relies on this property to avoid opening all indexes at once.
         let rtxn = index.read_txn()?;
@@ -73,10 +103,26 @@ impl IndexView {
             created_at: index.created_at(&rtxn)?,
             updated_at: index.updated_at(&rtxn)?,
             primary_key: index.primary_key(&rtxn)?.map(String::from),
+            parked: parked.map(ParkedIndexView::from),
         })
     }
 }
 
+/// Why and since when an index isn't processed anymore, its tasks stay enqueued until it's unparked.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ParkedIndexView {
+    #[serde(with = "time::serde::rfc3339")]
+    pub parked_at: OffsetDateTime,
+    pub reason: String,
+}
+
+impl From<ParkedIndex> for ParkedIndexView {
+    fn from(ParkedIndex { parked_at, reason }: ParkedIndex) -> Self {
+        ParkedIndexView { parked_at, reason }
+    }
+}
+
 #[derive(Deserr, Debug, Clone, Copy)]
 #[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
 pub struct ListIndexes {
@@ -102,7 +148,7 @@ pub async fn list_indexes(
             if !filters.is_index_authorized(uid) {
                 return Ok(None);
             }
-            Ok(Some(IndexView::new(uid.to_string(), index)?))
+            Ok(Some(IndexView::new(uid.to_string(), index, index_scheduler.parked_index(uid))?))
         })?;
     // Won't cause to open all indexes because IndexView doesn't keep the `Index` opened.
     let indexes: Vec<IndexView> = indexes.into_iter().flatten().collect();
@@ -185,7 +231,8 @@ pub async fn get_index(
     let index_uid = IndexUid::try_from(index_uid.into_inner())?;
 
     let index = index_scheduler.index(&index_uid)?;
-    let index_view = IndexView::new(index_uid.into_inner(), &index)?;
+    let parked = index_scheduler.parked_index(&index_uid);
+    let index_view = IndexView::new(index_uid.into_inner(), &index, parked)?;
 
     debug!(returns = ?index_view, "Get index");
 
@@ -252,8 +299,23 @@ pub struct IndexStats {
     pub number_of_documents: u64,
     /// Whether the index is currently performing indexation, according to the scheduler.
     pub is_indexing: bool,
+    /// Only returned when the index is parked because its batches kept panicking.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parked: Option<ParkedIndexView>,
     /// Association of every field name with the number of times it occurs in the documents.
     pub field_distribution: FieldDistribution,
+    /// The LMDB statistics of every database of the index.
+    pub databases: BTreeMap<String, DatabaseStats>,
+    /// The proportion of free pages in the index' DB, that a compaction would reclaim.
+    pub fragmentation: f64,
+    /// The percentiles of the latencies of the last searches made on the index.
+    /// Only returned by the stats route of the index.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_latency: Option<SearchLatencyStats>,
+    /// The errors of the invalid documents of the last refused payload of documents.
+    /// Only returned by the stats route of the index, once a payload was refused.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_indexing_errors: Option<Vec<String>>,
 }
 
 impl From<index_scheduler::IndexStats> for IndexStats {
@@ -261,13 +323,19 @@ impl From<index_scheduler::IndexStats> for IndexStats {
         IndexStats {
             number_of_documents: stats.inner_stats.number_of_documents,
             is_indexing: stats.is_indexing,
+            parked: stats.parked.map(ParkedIndexView::from),
+            fragmentation: stats.inner_stats.fragmentation(),
             field_distribution: stats.inner_stats.field_distribution,
+            databases: stats.inner_stats.database_stats,
+            search_latency: None,
+            last_indexing_errors: None,
         }
     }
 }
 
 pub async fn get_index_stats(
     index_scheduler: GuardedData<ActionPolicy<{ actions::STATS_GET }>, Data<IndexScheduler>>,
+    search_latencies: Data<SearchLatencies>,
     index_uid: web::Path<String>,
     req: HttpRequest,
     analytics: web::Data<dyn Analytics>,
@@ -275,8 +343,165 @@ pub async fn get_index_stats(
     let index_uid = IndexUid::try_from(index_uid.into_inner())?;
     analytics.publish("Stats Seen".to_string(), json!({ "per_index_uid": true }), Some(&req));
 
-    let stats = IndexStats::from(index_scheduler.index_stats(&index_uid)?);
+    let stats = index_scheduler.index_stats(&index_uid)?;
+    let last_indexing_errors = stats.inner_stats.last_indexing_errors.clone();
+    let mut stats = IndexStats::from(stats);
+    stats.search_latency = Some(search_latencies.stats(&index_uid));
+    stats.last_indexing_errors = (!last_indexing_errors.is_empty()).then_some(last_indexing_errors);
 
     debug!(returns = ?stats, "Get index stats");
     Ok(HttpResponse::Ok().json(stats))
 }
+
+pub async fn unpark_index(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::INDEXES_UPDATE }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+
+    // An index can be parked before its creation succeeded, so it may not exist yet
+    if !index_scheduler.unpark_index(&index_uid) {
+        index_scheduler.index(&index_uid)?;
+    }
+
+    debug!(index_uid = %index_uid, "Unpark index");
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Deserr, Debug)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct RestoreIndexRequest {
+    /// The path of the snapshot, relative to the snapshot directory.
+    #[deserr(error = DeserrJsonError<InvalidSnapshotPath>, missing_field_error = DeserrJsonError::missing_snapshot_path)]
+    snapshot_path: String,
+    /// The uid of the index in the snapshot, the uid of the restored index by default.
+    #[deserr(default, error = DeserrJsonError<InvalidSnapshotIndexUid>)]
+    snapshot_index_uid: Option<IndexUid>,
+}
+
+pub async fn restore_index(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SNAPSHOTS_CREATE }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    body: AwebJson<RestoreIndexRequest, DeserrJsonError>,
+    req: HttpRequest,
+    opt: web::Data<Opt>,
+    analytics: web::Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    debug!(parameters = ?body, "Restore index");
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let RestoreIndexRequest { snapshot_path, snapshot_index_uid } = body.into_inner();
+    let snapshot_path = resolve_snapshot_path(&opt.snapshot_dir, &snapshot_path)?;
+    analytics.publish("Index Restored".to_string(), json!({}), Some(&req));
+
+    let task = KindWithContent::IndexRestore {
+        snapshot_index_uid: snapshot_index_uid.unwrap_or_else(|| index_uid.clone()).into_inner(),
+        index_uid: index_uid.into_inner(),
+        snapshot_path: snapshot_path.display().to_string(),
+    };
+    let uid = get_task_id(&req, &opt)?;
+    let dry_run = is_dry_run(&req, &opt)?;
+    let task: SummarizedTaskView =
+        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+            .await??
+            .into();
+
+    debug!(returns = ?task, "Restore index");
+    Ok(HttpResponse::Accepted().json(task))
+}
+
+/// Resolves the path of a snapshot against the snapshot directory, it must not escape it.
+fn resolve_snapshot_path(
+    snapshot_dir: &Path,
+    snapshot_path: &str,
+) -> Result<PathBuf, ResponseError> {
+    let invalid_path = || {
+        ResponseError::from_msg(
+            format!("`{snapshot_path}` is not a snapshot file of the snapshot directory."),
+            Code::InvalidSnapshotPath,
+        )
+    };
+    let snapshot_dir = snapshot_dir.canonicalize().map_err(|_| invalid_path())?;
+    let path = snapshot_dir.join(snapshot_path).canonicalize().map_err(|_| invalid_path())?;
+    if path.starts_with(&snapshot_dir) && path.is_file() {
+        Ok(path)
+    } else {
+        Err(invalid_path())
+    }
+}
+
+#[derive(Deserr, Debug)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct IntegrityCheckRequest {
+    /// Whether the entries referencing missing documents must be removed.
+    #[deserr(default, error = DeserrJsonError<InvalidIntegrityCheckRepair>)]
+    repair: bool,
+}
+
+pub async fn check_index(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::INDEXES_UPDATE }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    body: AwebJson<IntegrityCheckRequest, DeserrJsonError>,
+    req: HttpRequest,
+    opt: web::Data<Opt>,
+    analytics: web::Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    debug!(parameters = ?body, "Check index");
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let IntegrityCheckRequest { repair } = body.into_inner();
+    analytics.publish("Index Checked".to_string(), json!({ "repair": repair }), Some(&req));
+
+    let task = KindWithContent::IntegrityCheck { index_uid: index_uid.into_inner(), repair };
+    let uid = get_task_id(&req, &opt)?;
+    let dry_run = is_dry_run(&req, &opt)?;
+    let task: SummarizedTaskView =
+        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+            .await??
+            .into();
+
+    debug!(returns = ?task, "Check index");
+    Ok(HttpResponse::Accepted().json(task))
+}
+
+#[derive(Deserr, Debug)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct IndexWarmupRequest {
+    /// The prefixes of the words whose docids are read.
+    #[deserr(error = DeserrJsonError<InvalidIndexWarmupPrefixes>, missing_field_error = DeserrJsonError::missing_index_warmup_prefixes)]
+    prefixes: Vec<String>,
+    /// Whether the docids read are kept in memory for the searches.
+    #[deserr(default, error = DeserrJsonError<InvalidIndexWarmupPopulateCache>)]
+    populate_cache: bool,
+}
+
+pub async fn warm_up_index(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::INDEXES_UPDATE }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    body: AwebJson<IndexWarmupRequest, DeserrJsonError>,
+    req: HttpRequest,
+    opt: web::Data<Opt>,
+    analytics: web::Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    debug!(parameters = ?body, "Warm up index");
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let IndexWarmupRequest { prefixes, populate_cache } = body.into_inner();
+    analytics.publish(
+        "Index Warmed Up".to_string(),
+        json!({ "prefixes": prefixes.len(), "populate_cache": populate_cache }),
+        Some(&req),
+    );
+
+    let task = KindWithContent::IndexWarmup {
+        index_uid: index_uid.into_inner(),
+        prefixes,
+        populate_cache,
+    };
+    let uid = get_task_id(&req, &opt)?;
+    let dry_run = is_dry_run(&req, &opt)?;
+    let task: SummarizedTaskView =
+        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+            .await??
+            .into();
+
+    debug!(returns = ?task, "Warm up index");
+    Ok(HttpResponse::Accepted().json(task))
+}
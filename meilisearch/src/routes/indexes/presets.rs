@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use actix_web::web::Data;
+use actix_web::{web, HttpRequest, HttpResponse};
+use deserr::actix_web::AwebJson;
+use index_scheduler::IndexScheduler;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::milli::update::Setting;
+use meilisearch_types::settings::Settings;
+use meilisearch_types::tasks::KindWithContent;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::debug;
+
+use crate::analytics::Analytics;
+use crate::error::MeilisearchHttpError;
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+use crate::routes::{get_task_id, is_dry_run, SummarizedTaskView};
+use crate::search::validate_search_preset;
+use crate::Opt;
+
+#[derive(Deserialize)]
+pub struct PresetParam {
+    index_uid: String,
+    preset_name: String,
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::get().to(SeqHandler(get_presets)))).service(
+        web::resource("/{preset_name}")
+            .route(web::get().to(SeqHandler(get_preset)))
+            .route(web::put().to(SeqHandler(update_preset)))
+            .route(web::delete().to(SeqHandler(delete_preset))),
+    );
+}
+
+pub async fn get_presets(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::PRESETS_GET }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+
+    let index = index_scheduler.index(&index_uid)?;
+    let rtxn = index.read_txn()?;
+    let presets = index.search_presets(&rtxn)?;
+
+    debug!(returns = ?presets, "Get presets");
+    Ok(HttpResponse::Ok().json(presets))
+}
+
+pub async fn get_preset(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::PRESETS_GET }>, Data<IndexScheduler>>,
+    path: web::Path<PresetParam>,
+) -> Result<HttpResponse, ResponseError> {
+    let PresetParam { index_uid, preset_name } = path.into_inner();
+    let index_uid = IndexUid::try_from(index_uid)?;
+
+    let index = index_scheduler.index(&index_uid)?;
+    let rtxn = index.read_txn()?;
+    let preset = index
+        .search_presets(&rtxn)?
+        .remove(&preset_name)
+        .ok_or(MeilisearchHttpError::PresetNotFound(preset_name))?;
+
+    debug!(returns = ?preset, "Get preset");
+    Ok(HttpResponse::Ok().json(preset))
+}
+
+pub async fn update_preset(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::PRESETS_UPDATE }>, Data<IndexScheduler>>,
+    path: web::Path<PresetParam>,
+    body: AwebJson<Value, DeserrJsonError>,
+    req: HttpRequest,
+    opt: web::Data<Opt>,
+    analytics: web::Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let PresetParam { index_uid, preset_name } = path.into_inner();
+    let index_uid = IndexUid::try_from(index_uid)?;
+    let preset = body.into_inner();
+    debug!(parameters = ?preset, "Update preset");
+
+    validate_search_preset(&preset_name, &preset)?;
+
+    analytics.publish(
+        "Search Preset Updated".to_string(),
+        json!({ "total_parameters": preset.as_object().map(|preset| preset.len()) }),
+        Some(&req),
+    );
+
+    let presets = BTreeMap::from([(preset_name, Setting::Set(preset))]);
+    let allow_index_creation = index_scheduler.filters().allow_index_creation(&index_uid);
+    let task = presets_update_task(index_uid, presets, allow_index_creation);
+    let uid = get_task_id(&req, &opt)?;
+    let dry_run = is_dry_run(&req, &opt)?;
+    let task: SummarizedTaskView =
+        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+            .await??
+            .into();
+
+    debug!(returns = ?task, "Update preset");
+    Ok(HttpResponse::Accepted().json(task))
+}
+
+pub async fn delete_preset(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::PRESETS_UPDATE }>, Data<IndexScheduler>>,
+    path: web::Path<PresetParam>,
+    req: HttpRequest,
+    opt: web::Data<Opt>,
+    analytics: web::Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let PresetParam { index_uid, preset_name } = path.into_inner();
+    let index_uid = IndexUid::try_from(index_uid)?;
+
+    analytics.publish("Search Preset Deleted".to_string(), json!({}), Some(&req));
+
+    let presets = BTreeMap::from([(preset_name, Setting::Reset)]);
+    let allow_index_creation = index_scheduler.filters().allow_index_creation(&index_uid);
+    let task = presets_update_task(index_uid, presets, allow_index_creation);
+    let uid = get_task_id(&req, &opt)?;
+    let dry_run = is_dry_run(&req, &opt)?;
+    let task: SummarizedTaskView =
+        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+            .await??
+            .into();
+
+    debug!(returns = ?task, "Delete preset");
+    Ok(HttpResponse::Accepted().json(task))
+}
+
+/// The presets are stored with the settings of the index,
+/// so they are updated by a settings update only containing them.
+fn presets_update_task(
+    index_uid: IndexUid,
+    presets: BTreeMap<String, Setting<Value>>,
+    allow_index_creation: bool,
+) -> KindWithContent {
+    let new_settings = Settings { presets: Setting::Set(presets), ..Default::default() };
+    KindWithContent::SettingsUpdate {
+        index_uid: index_uid.into_inner(),
+        new_settings: Box::new(new_settings),
+        is_deletion: false,
+        allow_index_creation,
+    }
+}
@@ -0,0 +1,36 @@
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse};
+use index_scheduler::IndexScheduler;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::index_uid::IndexUid;
+use serde_json::json;
+use tracing::debug;
+
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::get().to(SeqHandler(get_recent_searches))));
+}
+
+/// Returns the last searches made on the index, newest first.
+///
+/// Only the searches made while the `recentSearches` experimental feature was enabled are returned.
+pub async fn get_recent_searches(
+    index_scheduler: GuardedData<
+        ActionPolicy<{ actions::RECENT_SEARCHES_GET }>,
+        Data<IndexScheduler>,
+    >,
+    index_uid: web::Path<String>,
+) -> Result<HttpResponse, ResponseError> {
+    index_scheduler.features().check_recent_searches()?;
+
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    // the searches of an index that doesn't exist anymore are deleted with it
+    index_scheduler.index(&index_uid)?;
+
+    let results = index_scheduler.recent_searches(&index_uid)?;
+    debug!(returns = results.len(), "Get recent searches");
+    Ok(HttpResponse::Ok().json(json!({ "results": results })))
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse};
+use deserr::actix_web::AwebJson;
+use deserr::Deserr;
+use index_scheduler::IndexScheduler;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::milli::{RelatedTerm, RelatedTerms, DEFAULT_RELATED_TERMS_LIMIT};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::error::MeilisearchHttpError;
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+use crate::search::{parse_filter, search_rules_filter};
+
+/// The maximum number of related terms returned, whatever the requested limit.
+pub const MAX_RELATED_TERMS_LIMIT: usize = 100;
+/// How long the related terms of a word are reused before being computed again.
+const CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::post().to(SeqHandler(related_terms))));
+}
+
+#[derive(Debug, Clone, Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct RelatedTermsQuery {
+    #[deserr(error = DeserrJsonError<InvalidRelatedTermsWord>, missing_field_error = DeserrJsonError::missing_related_terms_word)]
+    word: String,
+    #[deserr(default, error = DeserrJsonError<InvalidRelatedTermsAttributes>)]
+    attributes: Option<Vec<String>>,
+    #[deserr(default = DEFAULT_RELATED_TERMS_LIMIT, error = DeserrJsonError<InvalidRelatedTermsLimit>)]
+    limit: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatedTermsResult {
+    word: String,
+    related_terms: Vec<RelatedTerm>,
+}
+
+/// The related terms recently computed, by index, word, attributes, limit, and filter of the
+/// search rules of the caller.
+///
+/// The entries are only dropped when they are requested again after having expired,
+/// every worker keeps its own cache.
+#[derive(Debug, Default)]
+pub struct RelatedTermsCache {
+    entries: Mutex<HashMap<CacheKey, (Instant, Vec<RelatedTerm>)>>,
+}
+
+type CacheKey = (String, String, Option<Vec<String>>, usize, Option<String>);
+
+impl RelatedTermsCache {
+    fn get(&self, key: &CacheKey) -> Option<Vec<RelatedTerm>> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((computed_at, terms)) if computed_at.elapsed() < CACHE_TTL => Some(terms.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: CacheKey, terms: Vec<RelatedTerm>) {
+        self.entries.lock().unwrap().insert(key, (Instant::now(), terms));
+    }
+}
+
+pub async fn related_terms(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
+    cache: Data<RelatedTermsCache>,
+    index_uid: web::Path<String>,
+    params: AwebJson<RelatedTermsQuery, DeserrJsonError>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let RelatedTermsQuery { word, attributes, limit } = params.into_inner();
+    debug!(word = %word, ?attributes, limit, "Related terms");
+
+    // Tenant token search_rules.
+    let rules_filter = match index_scheduler.filters().get_index_search_rules(&index_uid) {
+        Some(rules) => search_rules_filter(&index_uid, rules)?,
+        None => None,
+    };
+
+    let index = index_scheduler.index(&index_uid)?;
+    let limit = limit.min(MAX_RELATED_TERMS_LIMIT);
+    let key = (
+        index_uid.to_string(),
+        word.clone(),
+        attributes.clone(),
+        limit,
+        rules_filter.as_ref().map(|filter| filter.to_string()),
+    );
+    let related_terms = match cache.get(&key) {
+        Some(related_terms) => related_terms,
+        None => {
+            let searched = word.clone();
+            let related_terms =
+                tokio::task::spawn_blocking(move || -> Result<_, MeilisearchHttpError> {
+                    let rtxn = index.read_txn()?;
+                    let mut related_terms = RelatedTerms::new(searched, &rtxn, &index);
+                    related_terms.limit(limit);
+                    if let Some(attributes) = attributes {
+                        related_terms.attributes(attributes);
+                    }
+                    // only the documents the caller can search are counted
+                    if let Some(filter) =
+                        rules_filter.as_ref().map(parse_filter).transpose()?.flatten()
+                    {
+                        related_terms.filter(filter);
+                    }
+                    Ok(related_terms.execute()?)
+                })
+                .await??;
+            cache.insert(key, related_terms.clone());
+            related_terms
+        }
+    };
+
+    let result = RelatedTermsResult { word, related_terms };
+    debug!(returns = ?result, "Related terms");
+    Ok(HttpResponse::Ok().json(result))
+}
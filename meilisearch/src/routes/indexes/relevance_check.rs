@@ -0,0 +1,128 @@
+use actix_web::web::Data;
+use actix_web::{web, HttpRequest, HttpResponse};
+use deserr::actix_web::AwebJson;
+use deserr::Deserr;
+use index_scheduler::IndexScheduler;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::{Code, ResponseError};
+use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::milli;
+use serde_json::{json, Map, Value};
+use tracing::debug;
+
+use crate::analytics::Analytics;
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+use crate::relevance_check::{check_relevance, external_id, ExpectedHit, GoldenQuery};
+use crate::routes::indexes::search::apply_preset_and_search_defaults;
+use crate::search::{add_search_rules, CancelSearchOnDrop, SearchQuery};
+use crate::search_queue::SearchQueue;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::post().to(SeqHandler(relevance_check))));
+}
+
+#[derive(Debug, Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct RelevanceCheck {
+    #[deserr(error = DeserrJsonError<InvalidRelevanceCheckQueries>)]
+    queries: Vec<GoldenQueryView>,
+}
+
+#[derive(Debug, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidRelevanceCheckQueries>, rename_all = camelCase, deny_unknown_fields)]
+pub struct GoldenQueryView {
+    name: String,
+    /// The parameters of the search, as they are sent to the search route.
+    #[deserr(default = Value::Object(Map::new()))]
+    search: Value,
+    expected: Vec<ExpectedHitView>,
+    #[deserr(default)]
+    tolerance: usize,
+}
+
+#[derive(Debug, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidRelevanceCheckQueries>, rename_all = camelCase, deny_unknown_fields)]
+pub struct ExpectedHitView {
+    id: Value,
+    #[deserr(default)]
+    ranking_score: Option<f64>,
+}
+
+pub async fn relevance_check(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::RELEVANCE_CHECK }>, Data<IndexScheduler>>,
+    search_queue: web::Data<SearchQueue>,
+    index_uid: web::Path<String>,
+    params: AwebJson<RelevanceCheck, DeserrJsonError>,
+    req: HttpRequest,
+    analytics: web::Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let features = index_scheduler.features();
+    features.check_relevance_check()?;
+
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let RelevanceCheck { queries } = params.into_inner();
+    debug!(parameters = ?queries, "Relevance check");
+
+    let index = index_scheduler.index(&index_uid)?;
+    let queries = queries
+        .into_iter()
+        .map(|query| golden_query(&index_scheduler, &index_uid, &index, query))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    analytics.publish(
+        "Relevance Checked".to_string(),
+        json!({ "total_queries": queries.len() }),
+        Some(&req),
+    );
+
+    let _permit = search_queue.try_get_search_permit().await?;
+    let cancel_on_drop = CancelSearchOnDrop::default();
+    let canceled = cancel_on_drop.token();
+    let report = tokio::task::spawn_blocking(move || {
+        check_relevance(index_scheduler.get_ref(), &index, features, queries, canceled)
+    })
+    .await??;
+
+    debug!(returns = ?report, "Relevance check");
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Prepares the search of a golden query exactly like the search route prepares its query.
+fn golden_query(
+    index_scheduler: &GuardedData<ActionPolicy<{ actions::RELEVANCE_CHECK }>, Data<IndexScheduler>>,
+    index_uid: &IndexUid,
+    index: &milli::Index,
+    query: GoldenQueryView,
+) -> Result<GoldenQuery, ResponseError> {
+    let GoldenQueryView { name, search, expected, tolerance } = query;
+
+    let provided: Vec<String> =
+        search.as_object().map(|params| params.keys().cloned().collect()).unwrap_or_default();
+    let mut search: SearchQuery = deserr::deserialize::<_, _, DeserrJsonError>(search)?;
+    apply_preset_and_search_defaults(index, &mut search, |name| {
+        provided.iter().any(|parameter| parameter == name)
+    })?;
+
+    // Tenant token search_rules.
+    if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(index_uid) {
+        add_search_rules(&mut search, index_uid, search_rules)?;
+    }
+
+    let expected = expected
+        .into_iter()
+        .map(|ExpectedHitView { id, ranking_score }| match external_id(&id) {
+            Some(id) => Ok(ExpectedHit { id, ranking_score }),
+            None => Err(ResponseError::from_msg(
+                format!(
+                    "Invalid expected hit id `{id}` in the `{name}` query: expected a string or an integer."
+                ),
+                Code::InvalidRelevanceCheckQueries,
+            )),
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(GoldenQuery { name, search, expected, tolerance })
+}
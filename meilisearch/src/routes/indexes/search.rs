@@ -1,3 +1,6 @@
+use std::time::Instant;
+
+use actix_web::http::header::ACCEPT;
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
 use deserr::actix_web::{AwebJson, AwebQueryParameter};
@@ -5,11 +8,19 @@ use index_scheduler::{IndexScheduler, RoFeatures};
 use meilisearch_types::deserr::query_params::Param;
 use meilisearch_types::deserr::{DeserrJsonError, DeserrQueryParamError};
 use meilisearch_types::error::deserr_codes::*;
-use meilisearch_types::error::ResponseError;
+use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::milli;
+use meilisearch_types::milli::index::{
+    DEFAULT_MIN_WORD_LEN_ONE_TYPO, DEFAULT_MIN_WORD_LEN_TWO_TYPOS,
+};
+use meilisearch_types::milli::update::Setting;
 use meilisearch_types::serde_cs::vec::CS;
-use serde_json::Value;
+use meilisearch_types::settings::{Settings, Unchecked};
+use serde_json::{json, Map, Value};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
 use tracing::debug;
 
 use crate::analytics::{Analytics, SearchAggregator};
@@ -18,19 +29,33 @@ use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
 use crate::extractors::sequential_extractor::SeqHandler;
 use crate::metrics::MEILISEARCH_DEGRADED_SEARCH_REQUESTS;
+use crate::query_preprocessor::{preprocess_query, QueryPreprocessor};
+use crate::recent_searches::{json_type, RecentSearches};
 use crate::search::{
-    add_search_rules, perform_search, HybridQuery, MatchingStrategy, SearchKind, SearchQuery,
-    SemanticRatio, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER, DEFAULT_HIGHLIGHT_POST_TAG,
-    DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT, DEFAULT_SEARCH_OFFSET, DEFAULT_SEMANTIC_RATIO,
+    add_search_rules, apply_search_defaults, perform_search, perform_search_explain,
+    perform_search_stream, validate_search_defaults, validate_search_preset, CancelSearchOnDrop,
+    FacetDistributionMode, HybridQuery, MatchingStrategy, RankingScoreThreshold, SearchKind,
+    SearchQuery, SemanticRatio, TieBreak, DEFAULT_CROP_LENGTH, DEFAULT_CROP_MARKER,
+    DEFAULT_HIGHLIGHT_POST_TAG, DEFAULT_HIGHLIGHT_PRE_TAG, DEFAULT_SEARCH_LIMIT,
+    DEFAULT_SEARCH_OFFSET, DEFAULT_SEMANTIC_RATIO,
 };
-use crate::search_queue::SearchQueue;
+use crate::search_latency::{LatencyKind, SearchLatencies};
+use crate::search_queue::{Permit, SearchQueue};
+use crate::search_trace::SearchTraces;
+
+/// The content type of the hits streamed while they are ranked, one document per line.
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+/// The number of batches of hits that are ranked in advance of the ones sent to the client.
+const SEARCH_STREAM_CHANNEL_CAPACITY: usize = 4;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::resource("")
             .route(web::get().to(SeqHandler(search_with_url_query)))
             .route(web::post().to(SeqHandler(search_with_post))),
-    );
+    )
+    .service(web::resource("/preview").route(web::post().to(SeqHandler(search_preview))))
+    .service(web::resource("/explain").route(web::post().to(SeqHandler(search_explain))));
 }
 
 #[derive(Debug, deserr::Deserr)]
@@ -48,6 +73,8 @@ pub struct SearchQueryGet {
     page: Option<Param<usize>>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchHitsPerPage>)]
     hits_per_page: Option<Param<usize>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchSearchAfter>)]
+    search_after: Option<String>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchAttributesToRetrieve>)]
     attributes_to_retrieve: Option<CS<String>>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchAttributesToCrop>)]
@@ -66,22 +93,46 @@ pub struct SearchQueryGet {
     show_ranking_score: Param<bool>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchShowRankingScoreDetails>)]
     show_ranking_score_details: Param<bool>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchRankingScoreThreshold>)]
+    ranking_score_threshold: Option<RankingScoreThresholdGet>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchShowRankingBucketPath>)]
+    show_ranking_bucket_path: Param<bool>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchShowProcessingBreakdown>)]
+    show_processing_breakdown: Param<bool>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchShowQueryTokens>)]
+    show_query_tokens: Param<bool>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchShowSearchMetrics>)]
+    show_search_metrics: Param<bool>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchFacets>)]
     facets: Option<CS<String>>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchFacetDistributionMode>)]
+    facet_distribution_mode: Option<FacetDistributionMode>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchFacetApproximationThreshold>)]
+    facet_approximation_threshold: Option<Param<u64>>,
     #[deserr( default = DEFAULT_HIGHLIGHT_PRE_TAG(), error = DeserrQueryParamError<InvalidSearchHighlightPreTag>)]
     highlight_pre_tag: String,
     #[deserr( default = DEFAULT_HIGHLIGHT_POST_TAG(), error = DeserrQueryParamError<InvalidSearchHighlightPostTag>)]
     highlight_post_tag: String,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchHighlightTypoPreTag>)]
+    highlight_typo_pre_tag: Option<String>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchHighlightTypoPostTag>)]
+    highlight_typo_post_tag: Option<String>,
     #[deserr(default = DEFAULT_CROP_MARKER(), error = DeserrQueryParamError<InvalidSearchCropMarker>)]
     crop_marker: String,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchMatchingStrategy>)]
     matching_strategy: MatchingStrategy,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchTieBreak>)]
+    tie_break: TieBreak,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchDistinct>)]
+    distinct: Option<String>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchAttributesToSearchOn>)]
     pub attributes_to_search_on: Option<CS<String>>,
     #[deserr(default, error = DeserrQueryParamError<InvalidEmbedder>)]
     pub hybrid_embedder: Option<String>,
     #[deserr(default, error = DeserrQueryParamError<InvalidSearchSemanticRatio>)]
     pub hybrid_semantic_ratio: Option<SemanticRatioGet>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidSearchPreset>)]
+    pub preset: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, deserr::Deserr)]
@@ -105,6 +156,19 @@ impl std::ops::Deref for SemanticRatioGet {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, deserr::Deserr)]
+#[deserr(try_from(String) = TryFrom::try_from -> InvalidSearchRankingScoreThreshold)]
+pub struct RankingScoreThresholdGet(RankingScoreThreshold);
+
+impl std::convert::TryFrom<String> for RankingScoreThresholdGet {
+    type Error = InvalidSearchRankingScoreThreshold;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let f: f64 = s.parse().map_err(|_| InvalidSearchRankingScoreThreshold)?;
+        Ok(RankingScoreThresholdGet(RankingScoreThreshold::try_from(f)?))
+    }
+}
+
 impl From<SearchQueryGet> for SearchQuery {
     fn from(other: SearchQueryGet) -> Self {
         let filter = match other.filter {
@@ -136,6 +200,7 @@ impl From<SearchQueryGet> for SearchQuery {
             limit: other.limit.0,
             page: other.page.as_deref().copied(),
             hits_per_page: other.hits_per_page.as_deref().copied(),
+            search_after: other.search_after.map(Value::String),
             attributes_to_retrieve: other.attributes_to_retrieve.map(|o| o.into_iter().collect()),
             attributes_to_crop: other.attributes_to_crop.map(|o| o.into_iter().collect()),
             crop_length: other.crop_length.0,
@@ -145,13 +210,33 @@ impl From<SearchQueryGet> for SearchQuery {
             show_matches_position: other.show_matches_position.0,
             show_ranking_score: other.show_ranking_score.0,
             show_ranking_score_details: other.show_ranking_score_details.0,
+            ranking_score_threshold: other.ranking_score_threshold.map(|o| o.0),
+            show_ranking_bucket_path: other.show_ranking_bucket_path.0,
+            show_processing_breakdown: other.show_processing_breakdown.0,
+            show_query_tokens: other.show_query_tokens.0,
+            show_search_metrics: other.show_search_metrics.0,
             facets: other.facets.map(|o| o.into_iter().collect()),
+            facet_distribution_mode: other.facet_distribution_mode,
+            facet_approximation_threshold: other.facet_approximation_threshold.map(|t| t.0),
             highlight_pre_tag: other.highlight_pre_tag,
             highlight_post_tag: other.highlight_post_tag,
+            highlight_typo_pre_tag: other.highlight_typo_pre_tag,
+            highlight_typo_post_tag: other.highlight_typo_post_tag,
             crop_marker: other.crop_marker,
             matching_strategy: other.matching_strategy,
+            tie_break: other.tie_break,
+            distinct: other.distinct,
             attributes_to_search_on: other.attributes_to_search_on.map(|o| o.into_iter().collect()),
             hybrid,
+            preset: other.preset,
+            // a map of synonyms can only be given in the body of a POST request
+            synonyms: None,
+            // the typo tolerance can only be overridden in the body of a POST request
+            typo_tolerance: None,
+            // the ranking rules can only be overridden in the body of a POST request
+            ranking_rules_override: None,
+            // the debug artifacts can only be asked in the body of a POST request
+            debug: None,
         }
     }
 }
@@ -184,33 +269,73 @@ fn fix_sort_query_parameters(sort_query: &str) -> Vec<String> {
 pub async fn search_with_url_query(
     index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
     search_queue: web::Data<SearchQueue>,
+    search_latencies: web::Data<SearchLatencies>,
+    search_traces: web::Data<SearchTraces>,
+    recent_searches: web::Data<RecentSearches>,
     index_uid: web::Path<String>,
     params: AwebQueryParameter<SearchQueryGet, DeserrQueryParamError>,
     req: HttpRequest,
     analytics: web::Data<dyn Analytics>,
+    query_preprocessor: web::Data<dyn QueryPreprocessor>,
 ) -> Result<HttpResponse, ResponseError> {
     debug!(parameters = ?params, "Search get");
     let index_uid = IndexUid::try_from(index_uid.into_inner())?;
 
     let mut query: SearchQuery = params.into_inner().into();
 
+    let index = index_scheduler.index(&index_uid)?;
+
+    // The query string was already parsed the same way by the extractor, but the parameters it
+    // defaulted can't be told apart from the provided ones anymore.
+    let provided: Map<String, Value> = serde_urlencoded::from_str(req.query_string())
+        .map_err(|e| ResponseError::from_msg(e.to_string(), Code::BadRequest))?;
+    apply_preset_and_search_defaults(&index, &mut query, |name| provided.contains_key(name))?;
+
     // Tenant token search_rules.
     if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
-        add_search_rules(&mut query, search_rules);
+        add_search_rules(&mut query, &index_uid, search_rules)?;
     }
 
+    let original_q = preprocess_query(query_preprocessor.get_ref(), &index_uid, &mut query);
+
     let mut aggregate = SearchAggregator::from_query(&query, &req);
 
-    let index = index_scheduler.index(&index_uid)?;
     let features = index_scheduler.features();
 
     let search_kind = search_kind(&query, index_scheduler.get_ref(), &index, features)?;
 
     let _permit = search_queue.try_get_search_permit().await?;
-    let search_result =
-        tokio::task::spawn_blocking(move || perform_search(&index, query, search_kind)).await?;
+    let cancel_on_drop = CancelSearchOnDrop::default();
+    let canceled = cancel_on_drop.token();
+    let started_at = Instant::now();
+    let traced_query =
+        search_traces.enabled().then(|| Value::String(req.query_string().to_string()));
+    let mut search_result = tokio::task::spawn_blocking({
+        let index_uid = index_uid.to_string();
+        move || {
+            let trace = traced_query.is_some();
+            let mut search_result =
+                perform_search(&index, query, search_kind, canceled, trace, None);
+            if let (Some(query), Ok(search_result)) = (traced_query, &mut search_result) {
+                search_traces.write(&index_uid, query, search_result.trace.take());
+            }
+            search_result
+        }
+    })
+    .await?;
+    // the response contains the query that was received, not the one that was searched
+    if let (Some(original_q), Ok(search_result)) = (original_q, &mut search_result) {
+        search_result.query = original_q;
+    }
     if let Ok(ref search_result) = search_result {
+        search_latencies.record(&index_uid, LatencyKind::Search, started_at.elapsed());
         aggregate.succeed(search_result);
+        if features.runtime_features().recent_searches {
+            // the values of the query parameters are all strings
+            let parameters = provided.iter().map(|(name, _)| (name.as_str(), "string"));
+            let search = recent_searches.sanitize(parameters, search_result);
+            recent_searches.record((*index_scheduler).clone(), &index_uid, search).await;
+        }
     }
     analytics.get_search(aggregate);
 
@@ -223,37 +348,90 @@ pub async fn search_with_url_query(
 pub async fn search_with_post(
     index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
     search_queue: web::Data<SearchQueue>,
+    search_latencies: web::Data<SearchLatencies>,
+    search_traces: web::Data<SearchTraces>,
+    recent_searches: web::Data<RecentSearches>,
     index_uid: web::Path<String>,
-    params: AwebJson<SearchQuery, DeserrJsonError>,
+    params: AwebJson<Value, DeserrJsonError>,
     req: HttpRequest,
     analytics: web::Data<dyn Analytics>,
+    query_preprocessor: web::Data<dyn QueryPreprocessor>,
 ) -> Result<HttpResponse, ResponseError> {
     let index_uid = IndexUid::try_from(index_uid.into_inner())?;
 
-    let mut query = params.into_inner();
+    // The parameters are kept as JSON until now to know which ones were explicitly provided.
+    let params = params.into_inner();
+    let traced_query = search_traces.enabled().then(|| params.clone());
+    let provided: Vec<(String, &'static str)> = params
+        .as_object()
+        .map(|params| params.iter().map(|(name, value)| (name.clone(), json_type(value))).collect())
+        .unwrap_or_default();
+    let mut query: SearchQuery = deserr::deserialize::<_, _, DeserrJsonError>(params)?;
     debug!(parameters = ?query, "Search post");
 
+    if query.debug.is_some() && !index_scheduler.filters().allow_search_debug() {
+        return Err(MeilisearchHttpError::SearchDebugNotAllowed.into());
+    }
+
+    let index = index_scheduler.index(&index_uid)?;
+
+    apply_preset_and_search_defaults(&index, &mut query, |name| {
+        provided.iter().any(|(parameter, _)| parameter == name)
+    })?;
+
     // Tenant token search_rules.
     if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
-        add_search_rules(&mut query, search_rules);
+        add_search_rules(&mut query, &index_uid, search_rules)?;
     }
 
-    let mut aggregate = SearchAggregator::from_query(&query, &req);
+    let original_q = preprocess_query(query_preprocessor.get_ref(), &index_uid, &mut query);
 
-    let index = index_scheduler.index(&index_uid)?;
+    let mut aggregate = SearchAggregator::from_query(&query, &req);
 
     let features = index_scheduler.features();
 
     let search_kind = search_kind(&query, index_scheduler.get_ref(), &index, features)?;
 
+    if accepts_ndjson(&req) {
+        if !matches!(search_kind, SearchKind::KeywordOnly) {
+            return Err(MeilisearchHttpError::SearchNotStreamable.into());
+        }
+        let permit = search_queue.try_get_search_permit().await?;
+        return stream_search(index, query, permit).await;
+    }
+
     let _permit = search_queue.try_get_search_permit().await?;
-    let search_result =
-        tokio::task::spawn_blocking(move || perform_search(&index, query, search_kind)).await?;
+    let cancel_on_drop = CancelSearchOnDrop::default();
+    let canceled = cancel_on_drop.token();
+    let started_at = Instant::now();
+    let mut search_result = tokio::task::spawn_blocking({
+        let index_uid = index_uid.to_string();
+        move || {
+            let trace = traced_query.is_some();
+            let mut search_result =
+                perform_search(&index, query, search_kind, canceled, trace, None);
+            if let (Some(query), Ok(search_result)) = (traced_query, &mut search_result) {
+                search_traces.write(&index_uid, query, search_result.trace.take());
+            }
+            search_result
+        }
+    })
+    .await?;
+    // the response contains the query that was received, not the one that was searched
+    if let (Some(original_q), Ok(search_result)) = (original_q, &mut search_result) {
+        search_result.query = original_q;
+    }
     if let Ok(ref search_result) = search_result {
+        search_latencies.record(&index_uid, LatencyKind::Search, started_at.elapsed());
         aggregate.succeed(search_result);
         if search_result.degraded {
             MEILISEARCH_DEGRADED_SEARCH_REQUESTS.inc();
         }
+        if features.runtime_features().recent_searches {
+            let parameters = provided.iter().map(|(name, kind)| (name.as_str(), *kind));
+            let search = recent_searches.sanitize(parameters, search_result);
+            recent_searches.record((*index_scheduler).clone(), &index_uid, search).await;
+        }
     }
     analytics.post_search(aggregate);
 
@@ -263,6 +441,338 @@ pub async fn search_with_post(
     Ok(HttpResponse::Ok().json(search_result))
 }
 
+/// Whether the client asked for the hits alone as newline-delimited JSON, streamed while they
+/// are ranked.
+fn accepts_ndjson(req: &HttpRequest) -> bool {
+    req.headers().get(ACCEPT).and_then(|accept| accept.to_str().ok()).map_or(false, |accept| {
+        accept.split(',').any(|mime| mime.trim().starts_with(NDJSON_CONTENT_TYPE))
+    })
+}
+
+/// Streams the hits of the keyword search as newline-delimited JSON.
+///
+/// The search keeps its permit until it ends. It is canceled when the client disconnects before
+/// the response starts, like any search, and then when the body of the response is dropped.
+async fn stream_search(
+    index: milli::Index,
+    query: SearchQuery,
+    permit: Permit,
+) -> Result<HttpResponse, ResponseError> {
+    let cancel_on_drop = CancelSearchOnDrop::default();
+    let canceled = cancel_on_drop.token();
+    let (started_sender, started_receiver) = oneshot::channel();
+    let (sender, receiver) = mpsc::channel(SEARCH_STREAM_CHANNEL_CAPACITY);
+    tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        perform_search_stream(&index, query, canceled, started_sender, sender)
+    });
+    match started_receiver.await {
+        Ok(started) => started?,
+        // the search thread panicked
+        Err(_) => {
+            return Err(ResponseError::from_msg(
+                String::from("The search could not be performed."),
+                Code::Internal,
+            ))
+        }
+    }
+
+    let body = ReceiverStream::new(receiver).map(move |hits| {
+        let _cancel_on_drop = &cancel_on_drop;
+        hits
+    });
+    Ok(HttpResponse::Ok().content_type(NDJSON_CONTENT_TYPE).streaming(body))
+}
+
+#[derive(Debug, deserr::Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct SearchPreview {
+    /// The settings to search with, as they are sent to the settings route.
+    #[deserr(default = Value::Object(Map::new()))]
+    settings: Value,
+    /// The parameters of the search, as they are sent to the search route.
+    #[deserr(default = Value::Object(Map::new()))]
+    search: Value,
+}
+
+/// The settings that are only read at search time, the other ones need the documents to be reindexed.
+const PREVIEWABLE_SETTINGS: &[&str] = &["rankingRules", "searchableAttributes", "typoTolerance"];
+/// The fields of the typo tolerance that are only read at search time.
+const PREVIEWABLE_TYPO_TOLERANCE: &[&str] = &["enabled", "minWordSizeForTypos"];
+
+/// Searches the index with some settings, without changing the settings of the index.
+pub async fn search_preview(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
+    search_queue: web::Data<SearchQueue>,
+    index_uid: web::Path<String>,
+    params: AwebJson<SearchPreview, DeserrJsonError>,
+    req: HttpRequest,
+    analytics: web::Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let SearchPreview { settings: raw_settings, search } = params.into_inner();
+    debug!(settings = ?raw_settings, parameters = ?search, "Search preview");
+
+    let settings: Settings<Unchecked> =
+        deserr::deserialize::<_, _, DeserrJsonError>(raw_settings.clone())?;
+    let provided: Vec<String> =
+        search.as_object().map(|params| params.keys().cloned().collect()).unwrap_or_default();
+    let mut query: SearchQuery = deserr::deserialize::<_, _, DeserrJsonError>(search)?;
+
+    if query.debug.is_some() && !index_scheduler.filters().allow_search_debug() {
+        return Err(MeilisearchHttpError::SearchDebugNotAllowed.into());
+    }
+
+    let index = index_scheduler.index(&index_uid)?;
+
+    apply_preset_and_search_defaults(&index, &mut query, |name| {
+        provided.iter().any(|parameter| parameter == name)
+    })?;
+
+    // Tenant token search_rules.
+    if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
+        add_search_rules(&mut query, &index_uid, search_rules)?;
+    }
+
+    let settings_overlay = settings_overlay(&index, &raw_settings, settings, &mut query)?;
+
+    analytics.publish(
+        "Search Previewed".to_string(),
+        json!({
+            "settings": raw_settings.as_object().map(|settings| settings.keys().collect::<Vec<_>>()),
+        }),
+        Some(&req),
+    );
+
+    let features = index_scheduler.features();
+    let search_kind = search_kind(&query, index_scheduler.get_ref(), &index, features)?;
+
+    let _permit = search_queue.try_get_search_permit().await?;
+    let cancel_on_drop = CancelSearchOnDrop::default();
+    let canceled = cancel_on_drop.token();
+    let search_result = tokio::task::spawn_blocking(move || {
+        perform_search(&index, query, search_kind, canceled, false, Some(settings_overlay))
+    })
+    .await??;
+
+    debug!(returns = ?search_result, "Search preview");
+    Ok(HttpResponse::Ok().json(search_result))
+}
+
+#[derive(Debug, deserr::Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct SearchExplain {
+    /// The external id of the document to explain.
+    #[deserr(error = DeserrJsonError<InvalidDocumentId>, missing_field_error = DeserrJsonError::missing_document_id)]
+    document_id: String,
+    /// The parameters of the search, as they are sent to the search route.
+    #[deserr(default = Value::Object(Map::new()))]
+    search: Value,
+}
+
+/// Reports how a search ranks a document: the bucket of every ranking rule it falls into,
+/// why it isn't returned, or its position among the hits.
+pub async fn search_explain(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
+    search_queue: web::Data<SearchQueue>,
+    index_uid: web::Path<String>,
+    params: AwebJson<SearchExplain, DeserrJsonError>,
+    req: HttpRequest,
+    analytics: web::Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let SearchExplain { document_id, search } = params.into_inner();
+    debug!(document_id = %document_id, parameters = ?search, "Search explain");
+
+    let provided: Vec<String> =
+        search.as_object().map(|params| params.keys().cloned().collect()).unwrap_or_default();
+    let mut query: SearchQuery = deserr::deserialize::<_, _, DeserrJsonError>(search)?;
+
+    if query.debug.is_some() && !index_scheduler.filters().allow_search_debug() {
+        return Err(MeilisearchHttpError::SearchDebugNotAllowed.into());
+    }
+
+    let index = index_scheduler.index(&index_uid)?;
+
+    apply_preset_and_search_defaults(&index, &mut query, |name| {
+        provided.iter().any(|parameter| parameter == name)
+    })?;
+
+    // Tenant token search_rules.
+    if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid) {
+        add_search_rules(&mut query, &index_uid, search_rules)?;
+    }
+
+    let features = index_scheduler.features();
+    let search_kind = search_kind(&query, index_scheduler.get_ref(), &index, features)?;
+    if !matches!(search_kind, SearchKind::KeywordOnly) {
+        return Err(MeilisearchHttpError::SearchNotExplainable.into());
+    }
+
+    analytics.publish(
+        "Search Explained".to_string(),
+        json!({ "filter": query.filter.is_some(), "distinct": query.distinct.is_some() }),
+        Some(&req),
+    );
+
+    let _permit = search_queue.try_get_search_permit().await?;
+    let cancel_on_drop = CancelSearchOnDrop::default();
+    let canceled = cancel_on_drop.token();
+    let explanation = tokio::task::spawn_blocking(move || {
+        perform_search_explain(&index, query, document_id, canceled)
+    })
+    .await??;
+
+    debug!(returns = ?explanation, "Search explain");
+    Ok(HttpResponse::Ok().json(explanation))
+}
+
+/// Converts the previewed settings into the overlay the search is made with.
+///
+/// The searchable attributes restrict the attributes searched by the query, like `attributesToSearchOn`.
+/// All the settings that can't be previewed are reported at once.
+fn settings_overlay(
+    index: &milli::Index,
+    raw_settings: &Value,
+    settings: Settings<Unchecked>,
+    query: &mut SearchQuery,
+) -> Result<milli::SettingsOverlay, ResponseError> {
+    let rtxn = index.read_txn()?;
+    let mut not_previewable = Vec::new();
+    for (name, value) in raw_settings.as_object().into_iter().flatten() {
+        if !PREVIEWABLE_SETTINGS.contains(&name.as_str()) {
+            not_previewable.push(name.clone());
+        } else if name == "typoTolerance" {
+            for field in value.as_object().into_iter().flat_map(|fields| fields.keys()) {
+                if !PREVIEWABLE_TYPO_TOLERANCE.contains(&field.as_str()) {
+                    not_previewable.push(format!("typoTolerance.{field}"));
+                }
+            }
+        }
+    }
+
+    let mut overlay = milli::SettingsOverlay::default();
+    let criteria = match settings.ranking_rules {
+        // the maximum numbers of buckets of the ranking rules are not previewed
+        Setting::Set(rules) => {
+            Some(rules.into_iter().map(|r| milli::Criterion::from(r.rule)).collect())
+        }
+        Setting::Reset => Some(milli::default_criteria()),
+        Setting::NotSet => None,
+    };
+    if let Some(criteria) = criteria {
+        // the documents are only sorted on the fields that are already faceted
+        let faceted_fields = index.faceted_fields(&rtxn)?;
+        let unfaceted_field = criteria.iter().any(|criterion: &milli::Criterion| match criterion {
+            milli::Criterion::Asc(field)
+            | milli::Criterion::Desc(field)
+            | milli::Criterion::Random { seed: milli::RandomSeed::Field(field), .. } => {
+                !faceted_fields.contains(field)
+            }
+            _otherwise => false,
+        });
+        if unfaceted_field {
+            not_previewable.push("rankingRules".to_string());
+        }
+        overlay.criteria = Some(criteria);
+    }
+
+    match settings.searchable_attributes {
+        Setting::Set(attributes) if !attributes.iter().any(|attribute| attribute == "*") => {
+            let searchable = index.user_defined_searchable_fields(&rtxn)?;
+            let all_searchable = searchable.as_ref().map_or(true, |searchable| {
+                attributes.iter().all(|attribute| searchable.contains(&attribute.as_str()))
+            });
+            if !all_searchable {
+                not_previewable.push("searchableAttributes".to_string());
+            }
+            query.attributes_to_search_on = Some(match query.attributes_to_search_on.take() {
+                Some(searched) if !searched.iter().any(|attribute| attribute == "*") => searched
+                    .into_iter()
+                    .filter(|attribute| attributes.contains(attribute))
+                    .collect(),
+                _ => attributes,
+            });
+        }
+        // all the attributes are searchable, which can only be previewed when they already are
+        Setting::Set(_) | Setting::Reset => {
+            if index.user_defined_searchable_fields(&rtxn)?.is_some() {
+                not_previewable.push("searchableAttributes".to_string());
+            }
+        }
+        Setting::NotSet => (),
+    }
+
+    if let Setting::Set(typo_tolerance) = settings.typo_tolerance {
+        overlay.authorize_typos = typo_tolerance.enabled.or_reset(true).set();
+        if let Setting::Set(min_word_size) = typo_tolerance.min_word_size_for_typos {
+            overlay.min_word_len_one_typo =
+                min_word_size.one_typo.or_reset(DEFAULT_MIN_WORD_LEN_ONE_TYPO).set();
+            overlay.min_word_len_two_typos =
+                min_word_size.two_typos.or_reset(DEFAULT_MIN_WORD_LEN_TWO_TYPOS).set();
+        }
+    } else if let Setting::Reset = settings.typo_tolerance {
+        overlay.authorize_typos = Some(true);
+        overlay.min_word_len_one_typo = Some(DEFAULT_MIN_WORD_LEN_ONE_TYPO);
+        overlay.min_word_len_two_typos = Some(DEFAULT_MIN_WORD_LEN_TWO_TYPOS);
+    }
+
+    if !not_previewable.is_empty() {
+        return Err(MeilisearchHttpError::SettingsNotPreviewable(not_previewable).into());
+    }
+
+    let one_typo = match overlay.min_word_len_one_typo {
+        Some(one_typo) => one_typo,
+        None => index.min_word_len_one_typo(&rtxn)?,
+    };
+    let two_typos = match overlay.min_word_len_two_typos {
+        Some(two_typos) => two_typos,
+        None => index.min_word_len_two_typos(&rtxn)?,
+    };
+    if one_typo > two_typos {
+        return Err(milli::Error::from(milli::UserError::InvalidMinTypoWordLenSetting(
+            one_typo, two_typos,
+        ))
+        .into());
+    }
+
+    Ok(overlay)
+}
+
+/// Fills the parameters that were not provided in the request with the preset it references,
+/// and then with the `searchDefaults` of the index.
+///
+/// `is_provided` is called with the camelCase name of the parameters.
+pub(crate) fn apply_preset_and_search_defaults(
+    index: &milli::Index,
+    query: &mut SearchQuery,
+    is_provided: impl Fn(&str) -> bool,
+) -> Result<(), ResponseError> {
+    let rtxn = index.read_txn()?;
+
+    let mut preset_parameters = Vec::new();
+    if let Some(name) = query.preset.clone() {
+        let preset = index
+            .search_presets(&rtxn)?
+            .remove(&name)
+            .ok_or_else(|| MeilisearchHttpError::PresetNotFound(name.clone()))?;
+        if let Some(parameters) = preset.as_object() {
+            preset_parameters.extend(parameters.keys().cloned());
+        }
+        let preset = validate_search_preset(&name, &preset)?;
+        apply_search_defaults(query, preset, &is_provided);
+    }
+
+    if let Some(defaults) = index.search_defaults(&rtxn)? {
+        let defaults = validate_search_defaults(&defaults)?;
+        apply_search_defaults(query, defaults, |name| {
+            is_provided(name) || preset_parameters.iter().any(|parameter| parameter == name)
+        });
+    }
+
+    Ok(())
+}
+
 pub fn search_kind(
     query: &SearchQuery,
     index_scheduler: &IndexScheduler,
@@ -277,10 +787,16 @@ pub fn search_kind(
         features.check_vector("Passing `hybrid` as a query parameter")?;
     }
 
-    // regardless of anything, always do a keyword search when we don't have a vector and the query is whitespace or missing
+    if query.ranking_rules_override.is_some() {
+        features.check_ranking_rules_override()?;
+    }
+
+    // regardless of anything, always do a keyword search when we don't have a vector and the query is whitespace, `*` or missing
     if query.vector.is_none() {
         match &query.q {
-            Some(q) if q.trim().is_empty() => return Ok(SearchKind::KeywordOnly),
+            Some(q) if q.trim().is_empty() || milli::is_wildcard_query(q) => {
+                return Ok(SearchKind::KeywordOnly)
+            }
             None => return Ok(SearchKind::KeywordOnly),
             _ => {}
         }
@@ -1,27 +1,37 @@
+use actix_web::http::header::{ETAG, IF_MATCH};
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
-use deserr::actix_web::AwebJson;
+use deserr::actix_web::{AwebJson, AwebQueryParameter};
+use deserr::Deserr;
 use index_scheduler::IndexScheduler;
-use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::deserr::query_params::Param;
+use meilisearch_types::deserr::{DeserrJsonError, DeserrQueryParamError};
+use meilisearch_types::error::deserr_codes::InvalidSettingsCanonical;
 use meilisearch_types::error::ResponseError;
 use meilisearch_types::facet_values_sort::FacetValuesSort;
+use meilisearch_types::heed::RoTxn;
 use meilisearch_types::index_uid::IndexUid;
 use meilisearch_types::milli::update::Setting;
+use meilisearch_types::milli::Index;
 use meilisearch_types::settings::{settings, RankingRuleView, SecretPolicy, Settings, Unchecked};
 use meilisearch_types::tasks::KindWithContent;
 use serde_json::json;
+use sha2::{Digest, Sha256};
 use tracing::debug;
 
 use crate::analytics::Analytics;
+use crate::error::MeilisearchHttpError;
 use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
 use crate::routes::{get_task_id, is_dry_run, SummarizedTaskView};
+use crate::search::{validate_search_defaults, validate_search_preset};
 use crate::Opt;
 
 #[macro_export]
 macro_rules! make_setting_route {
     ($route:literal, $update_verb:ident, $type:ty, $err_ty:ty, $attr:ident, $camelcase_attr:literal, $analytics_var:ident, $analytics:expr) => {
         pub mod $attr {
+            use actix_web::http::header::ETAG;
             use actix_web::web::Data;
             use actix_web::{web, HttpRequest, HttpResponse, Resource};
             use index_scheduler::IndexScheduler;
@@ -48,6 +58,7 @@ macro_rules! make_setting_route {
                 opt: web::Data<Opt>,
             ) -> Result<HttpResponse, ResponseError> {
                 let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+                $crate::routes::indexes::settings::check_if_match(&req, &index_scheduler, &index_uid)?;
 
                 let new_settings = Settings { $attr: Setting::Reset.into(), ..Default::default() };
 
@@ -102,6 +113,7 @@ macro_rules! make_setting_route {
                     new_settings,
                     &index_scheduler,
                 )?;
+                $crate::routes::indexes::settings::check_if_match(&req, &index_scheduler, &index_uid)?;
 
                 let allow_index_creation =
                     index_scheduler.filters().allow_index_creation(&index_uid);
@@ -135,12 +147,13 @@ macro_rules! make_setting_route {
                 let index = index_scheduler.index(&index_uid)?;
                 let rtxn = index.read_txn()?;
                 let settings = settings(&index, &rtxn, meilisearch_types::settings::SecretPolicy::HideSecrets)?;
+                let etag = $crate::routes::indexes::settings::settings_etag(&index, &rtxn)?;
 
                 debug!(returns = ?settings, "Update settings");
                 let mut json = serde_json::json!(&settings);
                 let val = json[$camelcase_attr].take();
 
-                Ok(HttpResponse::Ok().json(val))
+                Ok(HttpResponse::Ok().insert_header((ETAG, etag)).json(val))
             }
 
             pub fn resources() -> Resource {
@@ -479,27 +492,29 @@ make_setting_route!(
 make_setting_route!(
     "/ranking-rules",
     put,
-    Vec<meilisearch_types::settings::RankingRuleView>,
+    Vec<meilisearch_types::settings::RankingRuleSetting>,
     meilisearch_types::deserr::DeserrJsonError<
         meilisearch_types::error::deserr_codes::InvalidSettingsRankingRules,
     >,
     ranking_rules,
     "rankingRules",
     analytics,
-    |setting: &Option<Vec<meilisearch_types::settings::RankingRuleView>>, req: &HttpRequest| {
+    |setting: &Option<Vec<meilisearch_types::settings::RankingRuleSetting>>, req: &HttpRequest| {
         use serde_json::json;
 
         analytics.publish(
             "RankingRules Updated".to_string(),
             json!({
                 "ranking_rules": {
-                    "words_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s, meilisearch_types::settings::RankingRuleView::Words))),
-                    "typo_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s, meilisearch_types::settings::RankingRuleView::Typo))),
-                    "proximity_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s, meilisearch_types::settings::RankingRuleView::Proximity))),
-                    "attribute_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s, meilisearch_types::settings::RankingRuleView::Attribute))),
-                    "sort_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s, meilisearch_types::settings::RankingRuleView::Sort))),
-                    "exactness_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s, meilisearch_types::settings::RankingRuleView::Exactness))),
-                    "values": setting.as_ref().map(|rr| rr.iter().filter(|s| matches!(s, meilisearch_types::settings::RankingRuleView::Asc(_) | meilisearch_types::settings::RankingRuleView::Desc(_)) ).map(|x| x.to_string()).collect::<Vec<_>>().join(", ")),
+                    "words_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s.rule, meilisearch_types::settings::RankingRuleView::Words))),
+                    "typo_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s.rule, meilisearch_types::settings::RankingRuleView::Typo))),
+                    "proximity_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s.rule, meilisearch_types::settings::RankingRuleView::Proximity))),
+                    "attribute_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s.rule, meilisearch_types::settings::RankingRuleView::Attribute))),
+                    "sort_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s.rule, meilisearch_types::settings::RankingRuleView::Sort))),
+                    "exactness_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s.rule, meilisearch_types::settings::RankingRuleView::Exactness))),
+                    "random_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s.rule, meilisearch_types::settings::RankingRuleView::Random { .. }))),
+                    "geo_point_position": setting.as_ref().map(|rr| rr.iter().position(|s| matches!(s.rule, meilisearch_types::settings::RankingRuleView::GeoPoint { .. }))),
+                    "values": setting.as_ref().map(|rr| rr.iter().filter(|s| matches!(s.rule, meilisearch_types::settings::RankingRuleView::Asc(_) | meilisearch_types::settings::RankingRuleView::Desc(_)) ).map(|x| x.rule.to_string()).collect::<Vec<_>>().join(", ")),
                 }
             }),
             Some(req),
@@ -644,6 +659,221 @@ make_setting_route!(
     }
 );
 
+make_setting_route!(
+    "/search-defaults",
+    put,
+    serde_json::Value,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsSearchDefaults,
+    >,
+    search_defaults,
+    "searchDefaults",
+    analytics,
+    |setting: &Option<serde_json::Value>, req: &HttpRequest| {
+        analytics.publish(
+            "Search Defaults Updated".to_string(),
+            serde_json::json!({"search_defaults": crate::routes::indexes::settings::search_defaults_analytics(setting.as_ref())}),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/min-numeric-prefix-length",
+    put,
+    u8,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsMinNumericPrefixLength,
+    >,
+    min_numeric_prefix_length,
+    "minNumericPrefixLength",
+    analytics,
+    |setting: &Option<u8>, req: &HttpRequest| {
+        analytics.publish(
+            "Min Numeric Prefix Length Updated".to_string(),
+            serde_json::json!({"min_numeric_prefix_length": setting }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/allow-underscore-fields",
+    put,
+    bool,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsAllowUnderscoreFields,
+    >,
+    allow_underscore_fields,
+    "allowUnderscoreFields",
+    analytics,
+    |setting: &Option<bool>, req: &HttpRequest| {
+        analytics.publish(
+            "Allow Underscore Fields Updated".to_string(),
+            serde_json::json!({"allow_underscore_fields": setting }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/localized-attributes",
+    put,
+    Vec<meilisearch_types::milli::LocalizedAttributesRule>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsLocalizedAttributes,
+    >,
+    localized_attributes,
+    "localizedAttributes",
+    analytics,
+    |rules: &Option<Vec<meilisearch_types::milli::LocalizedAttributesRule>>, req: &HttpRequest| {
+        analytics.publish(
+            "Localized Attributes Updated".to_string(),
+            serde_json::json!({
+                "localized_attributes": {
+                    "total": rules.as_ref().map(|rules| rules.len()),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/number-normalization",
+    put,
+    bool,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsNumberNormalization,
+    >,
+    number_normalization,
+    "numberNormalization",
+    analytics,
+    |setting: &Option<bool>, req: &HttpRequest| {
+        analytics.publish(
+            "Number Normalization Updated".to_string(),
+            serde_json::json!({"number_normalization": setting }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/phrase-across-fields",
+    put,
+    bool,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsPhraseAcrossFields,
+    >,
+    phrase_across_fields,
+    "phraseAcrossFields",
+    analytics,
+    |setting: &Option<bool>, req: &HttpRequest| {
+        analytics.publish(
+            "Phrase Across Fields Updated".to_string(),
+            serde_json::json!({"phrase_across_fields": setting }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/exact-attributes",
+    put,
+    std::collections::BTreeSet<String>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsExactAttributes,
+    >,
+    exact_attributes,
+    "exactAttributes",
+    analytics,
+    |setting: &Option<std::collections::BTreeSet<String>>, req: &HttpRequest| {
+        analytics.publish(
+            "Exact Attributes Updated".to_string(),
+            serde_json::json!({
+                "exact_attributes": {
+                    "total": setting.as_ref().map(|attributes| attributes.len()),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/decompounding-locales",
+    put,
+    std::collections::BTreeSet<meilisearch_types::milli::collation::Locale>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsDecompoundingLocales,
+    >,
+    decompounding_locales,
+    "decompoundingLocales",
+    analytics,
+    |setting: &Option<std::collections::BTreeSet<meilisearch_types::milli::collation::Locale>>, req: &HttpRequest| {
+        analytics.publish(
+            "Decompounding Locales Updated".to_string(),
+            serde_json::json!({"decompounding_locales": setting }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/facet-types",
+    put,
+    std::collections::BTreeMap<String, meilisearch_types::milli::facet::FacetTypeHint>,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsFacetTypes,
+    >,
+    facet_types,
+    "facetTypes",
+    analytics,
+    |setting: &Option<std::collections::BTreeMap<String, meilisearch_types::milli::facet::FacetTypeHint>>, req: &HttpRequest| {
+        analytics.publish(
+            "Facet Types Updated".to_string(),
+            serde_json::json!({
+                "facet_types": {
+                    "total": setting.as_ref().map(|facet_types| facet_types.len()),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
+make_setting_route!(
+    "/stemming",
+    patch,
+    meilisearch_types::settings::StemmingSettings,
+    meilisearch_types::deserr::DeserrJsonError<
+        meilisearch_types::error::deserr_codes::InvalidSettingsStemming,
+    >,
+    stemming,
+    "stemming",
+    analytics,
+    |setting: &Option<meilisearch_types::settings::StemmingSettings>, req: &HttpRequest| {
+        analytics.publish(
+            "Stemming Updated".to_string(),
+            serde_json::json!({
+                "stemming": {
+                    "locales": setting.as_ref().and_then(|s| s.locales.as_ref().set()),
+                },
+            }),
+            Some(req),
+        );
+    }
+);
+
+fn search_defaults_analytics(setting: Option<&serde_json::Value>) -> serde_json::Value {
+    json!(
+        {
+            "set": setting.is_some(),
+            "total": setting.and_then(|defaults| defaults.as_object()).map(|defaults| defaults.len()),
+        }
+    )
+}
+
 macro_rules! generate_configure {
     ($($mod:ident),*) => {
         pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -675,7 +905,17 @@ generate_configure!(
     pagination,
     faceting,
     embedders,
-    search_cutoff_ms
+    search_cutoff_ms,
+    search_defaults,
+    min_numeric_prefix_length,
+    allow_underscore_fields,
+    localized_attributes,
+    number_normalization,
+    phrase_across_fields,
+    exact_attributes,
+    decompounding_locales,
+    facet_types,
+    stemming
 );
 
 pub async fn update_all(
@@ -691,18 +931,21 @@ pub async fn update_all(
     let new_settings = body.into_inner();
     debug!(parameters = ?new_settings, "Update all settings");
     let new_settings = validate_settings(new_settings, &index_scheduler)?;
+    check_if_match(&req, &index_scheduler, &index_uid)?;
 
     analytics.publish(
         "Settings Updated".to_string(),
         json!({
            "ranking_rules": {
-                "words_position": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().position(|s| matches!(s, RankingRuleView::Words))),
-                "typo_position": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().position(|s| matches!(s, RankingRuleView::Typo))),
-                "proximity_position": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().position(|s| matches!(s, RankingRuleView::Proximity))),
-                "attribute_position": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().position(|s| matches!(s, RankingRuleView::Attribute))),
-                "sort_position": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().position(|s| matches!(s, RankingRuleView::Sort))),
-                "exactness_position": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().position(|s| matches!(s, RankingRuleView::Exactness))),
-                "values": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().filter(|s| !matches!(s, RankingRuleView::Asc(_) | RankingRuleView::Desc(_)) ).map(|x| x.to_string()).collect::<Vec<_>>().join(", ")),
+                "words_position": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().position(|s| matches!(s.rule, RankingRuleView::Words))),
+                "typo_position": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().position(|s| matches!(s.rule, RankingRuleView::Typo))),
+                "proximity_position": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().position(|s| matches!(s.rule, RankingRuleView::Proximity))),
+                "attribute_position": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().position(|s| matches!(s.rule, RankingRuleView::Attribute))),
+                "sort_position": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().position(|s| matches!(s.rule, RankingRuleView::Sort))),
+                "exactness_position": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().position(|s| matches!(s.rule, RankingRuleView::Exactness))),
+                "random_position": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().position(|s| matches!(s.rule, RankingRuleView::Random { .. }))),
+                "geo_point_position": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().position(|s| matches!(s.rule, RankingRuleView::GeoPoint { .. }))),
+                "values": new_settings.ranking_rules.as_ref().set().map(|rr| rr.iter().filter(|s| !matches!(s.rule, RankingRuleView::Asc(_) | RankingRuleView::Desc(_)) ).map(|x| x.rule.to_string()).collect::<Vec<_>>().join(", ")),
             },
             "searchable_attributes": {
                 "total": new_settings.searchable_attributes.as_ref().set().map(|searchable| searchable.len()),
@@ -788,6 +1031,27 @@ pub async fn update_all(
             },
             "embedders": crate::routes::indexes::settings::embedder_analytics(new_settings.embedders.as_ref().set()),
             "search_cutoff_ms": new_settings.search_cutoff_ms.as_ref().set(),
+            "search_defaults": crate::routes::indexes::settings::search_defaults_analytics(new_settings.search_defaults.as_ref().set()),
+            "min_numeric_prefix_length": new_settings.min_numeric_prefix_length.as_ref().set(),
+            "allow_underscore_fields": new_settings.allow_underscore_fields.as_ref().set(),
+            "localized_attributes": {
+                "total": new_settings.localized_attributes.as_ref().set().map(|rules| rules.len()),
+            },
+            "number_normalization": new_settings.number_normalization.as_ref().set(),
+            "phrase_across_fields": new_settings.phrase_across_fields.as_ref().set(),
+            "exact_attributes": {
+                "total": new_settings.exact_attributes.as_ref().set().map(|attributes| attributes.len()),
+            },
+            "decompounding_locales": new_settings.decompounding_locales.as_ref().set(),
+            "facet_types": {
+                "total": new_settings.facet_types.as_ref().set().map(|facet_types| facet_types.len()),
+            },
+            "stemming": {
+                "locales": new_settings.stemming.as_ref().set().and_then(|s| s.locales.as_ref().set()),
+            },
+            "presets": {
+                "total": new_settings.presets.as_ref().set().map(|presets| presets.len()),
+            },
         }),
         Some(&req),
     );
@@ -811,17 +1075,31 @@ pub async fn update_all(
     Ok(HttpResponse::Accepted().json(task))
 }
 
+#[derive(Debug, Deserr)]
+#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
+pub struct GetSettingsQuery {
+    /// Returns the settings with all the defaults made explicit and in a stable order.
+    #[deserr(default, error = DeserrQueryParamError<InvalidSettingsCanonical>)]
+    canonical: Param<bool>,
+}
+
 pub async fn get_all(
     index_scheduler: GuardedData<ActionPolicy<{ actions::SETTINGS_GET }>, Data<IndexScheduler>>,
     index_uid: web::Path<String>,
+    params: AwebQueryParameter<GetSettingsQuery, DeserrQueryParamError>,
 ) -> Result<HttpResponse, ResponseError> {
     let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let GetSettingsQuery { canonical } = params.into_inner();
 
     let index = index_scheduler.index(&index_uid)?;
     let rtxn = index.read_txn()?;
-    let new_settings = settings(&index, &rtxn, SecretPolicy::HideSecrets)?;
+    let mut new_settings = settings(&index, &rtxn, SecretPolicy::HideSecrets)?;
+    if canonical.0 {
+        new_settings = new_settings.into_canonical();
+    }
+    let etag = settings_etag(&index, &rtxn)?;
     debug!(returns = ?new_settings, "Get all settings");
-    Ok(HttpResponse::Ok().json(new_settings))
+    Ok(HttpResponse::Ok().insert_header((ETAG, etag)).json(new_settings))
 }
 
 pub async fn delete_all(
@@ -831,6 +1109,7 @@ pub async fn delete_all(
     opt: web::Data<Opt>,
 ) -> Result<HttpResponse, ResponseError> {
     let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    check_if_match(&req, &index_scheduler, &index_uid)?;
 
     let new_settings = Settings::cleared().into_unchecked();
 
@@ -860,5 +1139,48 @@ fn validate_settings(
     if matches!(settings.embedders, Setting::Set(_)) {
         index_scheduler.features().check_vector("Passing `embedders` in settings")?
     }
+    if let Setting::Set(defaults) = &settings.search_defaults {
+        validate_search_defaults(defaults)?;
+    }
+    if let Setting::Set(presets) = &settings.presets {
+        for (name, preset) in presets {
+            if let Setting::Set(preset) = preset {
+                validate_search_preset(name, preset)?;
+            }
+        }
+    }
     Ok(settings.validate()?)
 }
+
+/// Returns the ETag of the settings of the index, the SHA-256 digest of their canonical serialization.
+pub fn settings_etag(index: &Index, rtxn: &RoTxn) -> Result<String, ResponseError> {
+    let settings = settings(index, rtxn, SecretPolicy::HideSecrets)?.into_canonical();
+    let serialized = serde_json::to_vec(&settings).map_err(MeilisearchHttpError::from)?;
+    Ok(format!("\"{:x}\"", Sha256::digest(serialized)))
+}
+
+/// Rejects a settings update whose `If-Match` header doesn't match the current ETag of the settings
+/// of the index, i.e. when the settings were modified since the client read them.
+///
+/// The updates without an `If-Match` header are always accepted.
+pub fn check_if_match(
+    req: &HttpRequest,
+    index_scheduler: &IndexScheduler,
+    index_uid: &str,
+) -> Result<(), ResponseError> {
+    let Some(if_match) = req.headers().get(IF_MATCH) else { return Ok(()) };
+    let if_match = if_match.to_str().unwrap_or_default();
+
+    let index = index_scheduler.index(index_uid)?;
+    let rtxn = index.read_txn()?;
+    let current = settings_etag(&index, &rtxn)?;
+    if if_match.split(',').map(str::trim).any(|etag| etag == "*" || etag == current) {
+        Ok(())
+    } else {
+        Err(MeilisearchHttpError::SettingsVersionMismatch {
+            expected: if_match.to_string(),
+            current,
+        }
+        .into())
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse};
+use deserr::actix_web::AwebQueryParameter;
+use deserr::Deserr;
+use index_scheduler::{IndexScheduler, IndexStatsSample, Query};
+use meilisearch_types::deserr::DeserrQueryParamError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::{InvalidTaskDateError, ResponseError};
+use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::settings::{Settings, Unchecked};
+use meilisearch_types::star_or::OptionStarOr;
+use meilisearch_types::tasks::{Details, Status, TaskId};
+use serde::Serialize;
+use serde_json::{json, Value};
+use time::{Duration, OffsetDateTime};
+use tracing::debug;
+
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+use crate::routes::tasks::{deserialize_date_after, deserialize_date_before};
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::get().to(SeqHandler(get_timeline))));
+}
+
+#[derive(Debug, Deserr)]
+#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
+pub struct TimelineQuery {
+    /// The inclusive start of the timeline.
+    #[deserr(default, error = DeserrQueryParamError<InvalidTimelineFrom>, try_from(OptionStarOr<String>) = deserialize_date_before -> InvalidTaskDateError)]
+    from: OptionStarOr<OffsetDateTime>,
+    /// The exclusive end of the timeline, a date alone includes the whole day.
+    #[deserr(default, error = DeserrQueryParamError<InvalidTimelineTo>, try_from(OptionStarOr<String>) = deserialize_date_after -> InvalidTaskDateError)]
+    to: OptionStarOr<OffsetDateTime>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidTimelineInterval>)]
+    interval: TimelineInterval,
+}
+
+/// The duration of the buckets of the timeline, they are aligned on the UTC time.
+#[derive(Debug, Default, Clone, Copy, Deserr, Serialize)]
+#[deserr(rename_all = camelCase)]
+#[serde(rename_all = "camelCase")]
+pub enum TimelineInterval {
+    Minute,
+    #[default]
+    Hour,
+    Day,
+}
+
+impl TimelineInterval {
+    fn seconds(self) -> i64 {
+        match self {
+            TimelineInterval::Minute => 60,
+            TimelineInterval::Hour => 60 * 60,
+            TimelineInterval::Day => 24 * 60 * 60,
+        }
+    }
+
+    /// The start of the bucket containing the date.
+    fn bucket_of(self, date: OffsetDateTime) -> OffsetDateTime {
+        let timestamp = date.unix_timestamp();
+        let start = timestamp - timestamp.rem_euclid(self.seconds());
+        OffsetDateTime::from_unix_timestamp(start).unwrap_or(date)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineBucket {
+    #[serde(with = "time::serde::rfc3339")]
+    start: OffsetDateTime,
+    /// The documents indexed by the succeeded tasks of the bucket.
+    documents_added: u64,
+    /// The documents deleted by the succeeded tasks of the bucket.
+    documents_deleted: u64,
+    failed_tasks: u64,
+    settings_changes: Vec<SettingsChange>,
+    /// The sizes of the index recorded after the batches of the bucket.
+    size_samples: Vec<IndexStatsSample>,
+}
+
+impl TimelineBucket {
+    fn new(start: OffsetDateTime) -> Self {
+        Self {
+            start,
+            documents_added: 0,
+            documents_deleted: 0,
+            failed_tasks: 0,
+            settings_changes: Vec::new(),
+            size_samples: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsChange {
+    task_uid: TaskId,
+    #[serde(with = "time::serde::rfc3339")]
+    finished_at: OffsetDateTime,
+    /// The names of the settings set or reset by the task.
+    changed_settings: Vec<String>,
+}
+
+/// Returns the activity of the index over time, from the history of its tasks and the samples of
+/// its stats recorded after each batch.
+///
+/// Only the buckets with some activity are returned, oldest first. The tasks deleted from the
+/// queue are not counted anymore.
+pub async fn get_timeline(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::STATS_GET }>, Data<IndexScheduler>>,
+    index_uid: web::Path<String>,
+    params: AwebQueryParameter<TimelineQuery, DeserrQueryParamError>,
+) -> Result<HttpResponse, ResponseError> {
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let TimelineQuery { from, to, interval } = params.into_inner();
+    let from = from.merge_star_and_none();
+    let to = to.merge_star_and_none();
+    let in_timeline = |date: OffsetDateTime| {
+        from.map_or(true, |from| date >= from) && to.map_or(true, |to| date < to)
+    };
+
+    // the history of the stats is deleted with the index
+    let samples = index_scheduler.index_stats_history(&index_uid)?;
+
+    let query = Query {
+        statuses: Some(vec![Status::Succeeded, Status::Failed]),
+        index_uids: Some(vec![index_uid.to_string()]),
+        // the bounds of the query are exclusive
+        after_finished_at: from.map(|from| from - Duration::nanoseconds(1)),
+        before_finished_at: to,
+        ..Query::default()
+    };
+    let (tasks, _) =
+        index_scheduler.get_tasks_from_authorized_indexes(query, index_scheduler.filters())?;
+
+    let mut buckets: BTreeMap<OffsetDateTime, TimelineBucket> = BTreeMap::new();
+    // the tasks are returned newest first
+    for task in tasks.into_iter().rev() {
+        let Some(finished_at) = task.finished_at.filter(|&date| in_timeline(date)) else {
+            continue;
+        };
+        let start = interval.bucket_of(finished_at);
+        let bucket = buckets.entry(start).or_insert_with(|| TimelineBucket::new(start));
+        if task.status == Status::Failed {
+            bucket.failed_tasks += 1;
+            continue;
+        }
+        match task.details {
+            Some(Details::DocumentAdditionOrUpdate { indexed_documents, .. }) => {
+                bucket.documents_added += indexed_documents.unwrap_or_default();
+            }
+            Some(
+                Details::DocumentDeletion { deleted_documents, .. }
+                | Details::DocumentDeletionByFilter { deleted_documents, .. }
+                | Details::ClearAll { deleted_documents },
+            ) => {
+                bucket.documents_deleted += deleted_documents.unwrap_or_default();
+            }
+            Some(Details::SettingsUpdate { settings, .. }) => {
+                bucket.settings_changes.push(SettingsChange {
+                    task_uid: task.uid,
+                    finished_at,
+                    changed_settings: changed_settings(&settings),
+                });
+            }
+            _ => (),
+        }
+    }
+    for sample in samples.into_iter().filter(|sample| in_timeline(sample.recorded_at)) {
+        let start = interval.bucket_of(sample.recorded_at);
+        buckets
+            .entry(start)
+            .or_insert_with(|| TimelineBucket::new(start))
+            .size_samples
+            .push(sample);
+    }
+
+    let buckets: Vec<_> = buckets.into_values().collect();
+    debug!(returns = buckets.len(), "Get index timeline");
+    Ok(HttpResponse::Ok().json(json!({ "interval": interval, "buckets": buckets })))
+}
+
+/// The names of the settings set or reset by a settings update, as they are sent to the settings route.
+fn changed_settings(settings: &Settings<Unchecked>) -> Vec<String> {
+    match serde_json::to_value(settings) {
+        Ok(Value::Object(settings)) => settings.into_iter().map(|(name, _)| name).collect(),
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,81 @@
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse};
+use deserr::actix_web::AwebQueryParameter;
+use deserr::Deserr;
+use index_scheduler::IndexScheduler;
+use meilisearch_types::deserr::query_params::Param;
+use meilisearch_types::deserr::DeserrQueryParamError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::ResponseError;
+use meilisearch_types::index_uid::IndexUid;
+use serde::Serialize;
+use tracing::debug;
+
+use crate::extractors::authentication::policies::*;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+
+/// The number of words returned when no limit is requested.
+pub const DEFAULT_WORD_FREQUENCY_LIMIT: usize = 20;
+/// The maximum number of words returned, whatever the requested limit.
+pub const MAX_WORD_FREQUENCY_LIMIT: usize = 1000;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("").route(web::get().to(SeqHandler(word_frequency))));
+}
+
+#[derive(Debug, Deserr)]
+#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
+pub struct WordFrequencyQuery {
+    #[deserr(default, error = DeserrQueryParamError<InvalidWordFrequencyPrefix>)]
+    prefix: Option<String>,
+    #[deserr(default = Param(DEFAULT_WORD_FREQUENCY_LIMIT), error = DeserrQueryParamError<InvalidWordFrequencyLimit>)]
+    limit: Param<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordFrequency {
+    word: String,
+    /// The number of documents containing the word.
+    documents: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordFrequencyResult {
+    prefix: Option<String>,
+    words: Vec<WordFrequency>,
+}
+
+/// Returns the indexed words starting with the prefix, the ones found in the most documents first.
+///
+/// The prefix is lowercased to match the words as they are indexed.
+pub async fn word_frequency(
+    index_scheduler: GuardedData<
+        ActionPolicy<{ actions::WORD_FREQUENCY_GET }>,
+        Data<IndexScheduler>,
+    >,
+    index_uid: web::Path<String>,
+    params: AwebQueryParameter<WordFrequencyQuery, DeserrQueryParamError>,
+) -> Result<HttpResponse, ResponseError> {
+    index_scheduler.features().check_word_frequency()?;
+
+    let index_uid = IndexUid::try_from(index_uid.into_inner())?;
+    let WordFrequencyQuery { prefix, limit } = params.into_inner();
+    debug!(?prefix, limit = limit.0, "Word frequency");
+
+    let index = index_scheduler.index(&index_uid)?;
+    let limit = limit.0.min(MAX_WORD_FREQUENCY_LIMIT);
+    let searched = prefix.as_deref().map(str::to_lowercase);
+    let words = tokio::task::spawn_blocking(move || {
+        let rtxn = index.read_txn()?;
+        index.word_frequencies(&rtxn, searched.as_deref(), limit)
+    })
+    .await??;
+
+    let words = words.into_iter().map(|(word, documents)| WordFrequency { word, documents });
+    let result = WordFrequencyResult { prefix, words: words.collect() };
+    debug!(returns = ?result, "Word frequency");
+    Ok(HttpResponse::Ok().json(result))
+}
@@ -1,9 +1,13 @@
 use std::convert::Infallible;
-use std::io::Write;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, LineWriter, Write};
 use std::ops::ControlFlow;
+use std::path::{Component, Path, PathBuf};
 use std::pin::Pin;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, PoisonError};
+use std::time::Duration;
 
 use actix_web::web::{Bytes, Data};
 use actix_web::{web, HttpResponse};
@@ -17,20 +21,26 @@ use meilisearch_types::error::{Code, ResponseError};
 use tokio::sync::mpsc;
 use tracing_subscriber::filter::Targets;
 use tracing_subscriber::Layer;
+use tracing_trace::processor::span_stats::LiveSpanStats;
 
 use crate::error::MeilisearchHttpError;
 use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
 use crate::extractors::sequential_extractor::SeqHandler;
-use crate::{LogRouteHandle, LogStderrHandle};
+use crate::{LogRouteHandle, LogStderrHandle, LogStreamState, Opt, SubscriberForSecondLayer};
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::resource("stream")
             .route(web::post().to(SeqHandler(get_logs)))
+            .route(web::patch().to(SeqHandler(update_logs_target)))
             .route(web::delete().to(SeqHandler(cancel_logs))),
     )
-    .service(web::resource("stderr").route(web::post().to(SeqHandler(update_stderr_target))));
+    .service(
+        web::resource("stderr")
+            .route(web::post().to(SeqHandler(update_stderr_target)))
+            .route(web::delete().to(SeqHandler(reset_stderr_target))),
+    );
 }
 
 #[derive(Debug, Default, Clone, Copy, Deserr, PartialEq, Eq)]
@@ -40,6 +50,8 @@ pub enum LogMode {
     Human,
     Json,
     Profile,
+    /// Streams the number of calls, the total and the longest durations of each span.
+    SpanStats,
 }
 
 /// Simple wrapper around the `Targets` from `tracing_subscriber` to implement `MergeWithError` on it.
@@ -94,8 +106,19 @@ pub struct GetLogs {
 
     #[deserr(default = false, error = DeserrJsonError<BadRequest>)]
     profile_memory: bool,
+
+    /// Closes the current stream, if any, instead of returning an error.
+    #[deserr(default = false, error = DeserrJsonError<BadRequest>)]
+    force: bool,
+
+    /// The interval, in milliseconds, of the heartbeats sent in the `human` and `json` modes
+    /// while nothing is logged, for the proxies not to close the connection.
+    #[deserr(default = DEFAULT_HEARTBEAT_INTERVAL_MS, error = DeserrJsonError<BadRequest>)]
+    heartbeat_interval_ms: u64,
 }
 
+const DEFAULT_HEARTBEAT_INTERVAL_MS: u64 = 30_000;
+
 fn validate_get_logs<E: DeserializeError>(
     logs: GetLogs,
     location: ValuePointerRef,
@@ -108,6 +131,14 @@ fn validate_get_logs<E: DeserializeError>(
             },
             location,
         )))
+    } else if logs.heartbeat_interval_ms == 0 {
+        Err(deserr::take_cf_content(E::error::<Infallible>(
+            None,
+            ErrorKind::Unexpected {
+                msg: String::from("`heartbeatIntervalMs` must be greater than 0."),
+            },
+            location,
+        )))
     } else {
         Ok(logs)
     }
@@ -128,27 +159,47 @@ impl Write for LogWriter {
     }
 }
 
+/// Uninstalls the layer of a stream when the stream is dropped, either because it was closed by
+/// the `DELETE` route or because its client disconnected, which the heartbeats make noticed even
+/// when nothing is logged.
 struct HandleGuard {
     /// We need to keep an handle on the logs to make it available again when the streamer is dropped
     logs: Arc<LogRouteHandle>,
+    state: Arc<LogStreamState>,
+    /// The id of the stream, to not free the route if another stream took it over in the meantime.
+    id: u64,
 }
 
 impl Drop for HandleGuard {
     fn drop(&mut self) {
+        let mut active = self.state.active.lock().unwrap_or_else(PoisonError::into_inner);
+        if *active != Some(self.id) {
+            return;
+        }
+
+        *active = None;
         if let Err(e) = self.logs.modify(|layer| *layer.inner_mut() = None) {
             tracing::error!("Could not free the logs route: {e}");
         }
     }
 }
 
+/// Streams the logs, and the `heartbeat` line whenever nothing was logged during the interval.
+///
+/// Writing the heartbeats fails once the client disconnected, which drops the stream.
 fn byte_stream(
     receiver: mpsc::UnboundedReceiver<Vec<u8>>,
+    heartbeat_interval: Duration,
+    heartbeat: &'static [u8],
     guard: HandleGuard,
 ) -> impl futures_util::Stream<Item = Result<Bytes, ResponseError>> {
     futures_util::stream::unfold((receiver, guard), move |(mut receiver, guard)| async move {
-        let vec = receiver.recv().await;
+        let bytes = match tokio::time::timeout(heartbeat_interval, receiver.recv()).await {
+            Ok(vec) => Bytes::from(vec?),
+            Err(_elapsed) => Bytes::from_static(heartbeat),
+        };
 
-        vec.map(From::from).map(Ok).map(|a| (a, (receiver, guard)))
+        Some((Ok(bytes), (receiver, guard)))
     })
 }
 
@@ -158,9 +209,9 @@ fn make_layer<
     S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
 >(
     opt: &GetLogs,
-    logs: Data<LogRouteHandle>,
+    guard: HandleGuard,
 ) -> (Box<dyn Layer<S> + Send + Sync>, PinnedByteStream) {
-    let guard = HandleGuard { logs: logs.into_inner() };
+    let heartbeat_interval = Duration::from_millis(opt.heartbeat_interval_ms);
     match opt.mode {
         LogMode::Human => {
             let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
@@ -169,7 +220,7 @@ fn make_layer<
                 .with_writer(move || LogWriter { sender: sender.clone() })
                 .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE);
 
-            let stream = byte_stream(receiver, guard);
+            let stream = byte_stream(receiver, heartbeat_interval, b"# heartbeat\n", guard);
             (Box::new(fmt_layer) as Box<dyn Layer<S> + Send + Sync>, Box::pin(stream))
         }
         LogMode::Json => {
@@ -180,7 +231,8 @@ fn make_layer<
                 .json()
                 .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE);
 
-            let stream = byte_stream(receiver, guard);
+            let stream =
+                byte_stream(receiver, heartbeat_interval, b"{\"type\":\"heartbeat\"}\n", guard);
             (Box::new(fmt_layer) as Box<dyn Layer<S> + Send + Sync>, Box::pin(stream))
         }
         LogMode::Profile => {
@@ -188,6 +240,13 @@ fn make_layer<
 
             let stream = entry_stream(trace, guard);
 
+            (Box::new(layer) as Box<dyn Layer<S> + Send + Sync>, Box::pin(stream))
+        }
+        LogMode::SpanStats => {
+            let (trace, layer) = tracing_trace::Trace::new(false);
+
+            let stream = span_stats_stream(trace, guard);
+
             (Box::new(layer) as Box<dyn Layer<S> + Send + Sync>, Box::pin(stream))
         }
     }
@@ -248,44 +307,104 @@ fn entry_stream(
     )
 }
 
+/// Streams the statistics of the spans as a JSON object per line, every second
+/// when spans were called in the meantime and once more when the stream is closed.
+fn span_stats_stream(
+    trace: tracing_trace::Trace,
+    guard: HandleGuard,
+) -> impl Stream<Item = Result<Bytes, ResponseError>> {
+    let receiver = trace.into_receiver();
+    let stats = LiveSpanStats::new();
+
+    futures_util::stream::unfold(
+        (Some(receiver), stats, guard),
+        move |(receiver, mut stats, guard)| async move {
+            let mut receiver = receiver?;
+            let mut entries = Vec::new();
+            let mut closed = false;
+            let mut updated = false;
+
+            let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(1);
+            loop {
+                entries.clear();
+                let Ok(count) =
+                    tokio::time::timeout_at(deadline, receiver.recv_many(&mut entries, 100)).await
+                else {
+                    break;
+                };
+                if count == 0 {
+                    // channel closed, the last statistics are sent
+                    closed = true;
+                    break;
+                }
+                for entry in &entries {
+                    stats.process(entry);
+                }
+                updated = true;
+            }
+
+            let mut bytes = Vec::new();
+            if updated || closed {
+                if let Err(error) = serde_json::to_writer(&mut bytes, stats.stats()) {
+                    return Some((
+                        Err(ResponseError::from_msg(
+                            format!("error serializing the span statistics: {error}"),
+                            Code::Internal,
+                        )),
+                        (None, stats, guard),
+                    ));
+                }
+                bytes.push(b'\n');
+            }
+
+            let receiver = if closed { None } else { Some(receiver) };
+            Some((Ok(bytes.into()), (receiver, stats, guard)))
+        },
+    )
+}
+
 pub async fn get_logs(
-    index_scheduler: GuardedData<ActionPolicy<{ actions::METRICS_GET }>, Data<IndexScheduler>>,
+    index_scheduler: GuardedData<ActionPolicy<{ actions::LOGS_GET }>, Data<IndexScheduler>>,
     logs: Data<LogRouteHandle>,
+    state: Data<LogStreamState>,
     body: AwebJson<GetLogs, DeserrJsonError>,
 ) -> Result<HttpResponse, ResponseError> {
     index_scheduler.features().check_logs_route()?;
 
     let opt = body.into_inner();
-    let mut stream = None;
 
-    logs.modify(|layer| match layer.inner_mut() {
-        None => {
-            // there is no one getting logs
-            *layer.filter_mut() = opt.target.0.clone();
-            let (new_layer, new_stream) = make_layer(&opt, logs.clone());
+    let mut active = state.active.lock().unwrap_or_else(PoisonError::into_inner);
+    if active.is_some() && !opt.force {
+        // there is already someone getting logs
+        return Err(MeilisearchHttpError::LogStreamAlreadyExists.into());
+    }
 
-            *layer.inner_mut() = Some(new_layer);
-            stream = Some(new_stream);
-        }
-        Some(_) => {
-            // there is already someone getting logs
-        }
+    let id = state.next_id.fetch_add(1, Ordering::Relaxed);
+    let guard =
+        HandleGuard { logs: logs.clone().into_inner(), state: state.clone().into_inner(), id };
+    let (new_layer, stream) = make_layer(&opt, guard);
+
+    logs.modify(|layer| {
+        // dropping the layer of the current stream closes its channel, which terminates its response
+        *layer.inner_mut() = None;
+        *layer.filter_mut() = opt.target.0.clone();
+        *layer.inner_mut() = Some(new_layer);
     })
     .unwrap();
+    *active = Some(id);
 
-    if let Some(stream) = stream {
-        Ok(HttpResponse::Ok().streaming(stream))
-    } else {
-        Err(MeilisearchHttpError::AlreadyUsedLogRoute.into())
-    }
+    Ok(HttpResponse::Ok().streaming(stream))
 }
 
 pub async fn cancel_logs(
-    index_scheduler: GuardedData<ActionPolicy<{ actions::METRICS_GET }>, Data<IndexScheduler>>,
+    index_scheduler: GuardedData<ActionPolicy<{ actions::LOGS_DELETE }>, Data<IndexScheduler>>,
     logs: Data<LogRouteHandle>,
+    state: Data<LogStreamState>,
 ) -> Result<HttpResponse, ResponseError> {
     index_scheduler.features().check_logs_route()?;
 
+    let mut active = state.active.lock().unwrap_or_else(PoisonError::into_inner);
+    *active = None;
     if let Err(e) = logs.modify(|layer| *layer.inner_mut() = None) {
         tracing::error!("Could not free the logs route: {e}");
     }
@@ -295,24 +414,284 @@ pub async fn cancel_logs(
 
 #[derive(Debug, Deserr)]
 #[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct UpdateStreamLogs {
+    #[deserr(default = "info".parse().unwrap(), try_from(&String) = MyTargets::from_str -> DeserrJsonError<BadRequest>)]
+    target: MyTargets,
+}
+
+/// Swaps the filter of the current stream, without disconnecting it.
+pub async fn update_logs_target(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::LOGS_CREATE }>, Data<IndexScheduler>>,
+    logs: Data<LogRouteHandle>,
+    state: Data<LogStreamState>,
+    body: AwebJson<UpdateStreamLogs, DeserrJsonError>,
+) -> Result<HttpResponse, ResponseError> {
+    index_scheduler.features().check_logs_route()?;
+
+    let opt = body.into_inner();
+
+    // the stream is checked and its filter swapped under the same lock that the
+    // creation and the deletion of the stream take
+    let active = state.active.lock().unwrap_or_else(PoisonError::into_inner);
+    if active.is_none() {
+        return Err(MeilisearchHttpError::LogStreamNotFound.into());
+    }
+
+    logs.modify(|layer| *layer.filter_mut() = opt.target.0.clone()).unwrap();
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserr, PartialEq, Eq)]
+#[deserr(rename_all = camelCase)]
+pub enum LogFileMode {
+    #[default]
+    Human,
+    Json,
+}
+
+#[derive(Debug, Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields, validate = validate_update_stderr_logs -> DeserrJsonError<BadRequest>)]
 pub struct UpdateStderrLogs {
     #[deserr(default = "info".parse().unwrap(), try_from(&String) = MyTargets::from_str -> DeserrJsonError<BadRequest>)]
     target: MyTargets,
+
+    /// Writes the logs into this file instead of stderr, until the route is called with `DELETE`.
+    ///
+    /// The path is relative to the `--experimental-logs-dir` directory.
+    #[deserr(default, error = DeserrJsonError<BadRequest>)]
+    path: Option<String>,
+
+    #[deserr(default, error = DeserrJsonError<BadRequest>)]
+    mode: Option<LogFileMode>,
+
+    /// Creates the missing parent directories of the `path`.
+    #[deserr(default = false, error = DeserrJsonError<BadRequest>)]
+    create_dirs: bool,
 }
 
+fn validate_update_stderr_logs<E: DeserializeError>(
+    logs: UpdateStderrLogs,
+    location: ValuePointerRef,
+) -> Result<UpdateStderrLogs, E> {
+    if logs.path.is_none() && (logs.mode.is_some() || logs.create_dirs) {
+        Err(deserr::take_cf_content(E::error::<Infallible>(
+            None,
+            ErrorKind::Unexpected {
+                msg: String::from(
+                    "`mode` and `createDirs` can only be used to write the logs into a `path`.",
+                ),
+            },
+            location,
+        )))
+    } else {
+        Ok(logs)
+    }
+}
+
+/// The file the logs are written into instead of stderr.
+///
+/// The layer sends the logs to a dedicated thread writing them, so that logging never waits
+/// on the disk. The thread stops once the layer, and thus the sender, is dropped.
+#[derive(Debug)]
+pub(crate) struct LogFile {
+    path: PathBuf,
+    writer: std::thread::JoinHandle<io::Result<()>>,
+}
+
+impl LogFile {
+    /// Opens the file at this path, relative to the logs directory configured at launch.
+    ///
+    /// The path can't leave the logs directory, neither with `..` nor through a symbolic link.
+    fn open(
+        logs_dir: Option<&Path>,
+        relative_path: &str,
+        mode: LogFileMode,
+        create_dirs: bool,
+    ) -> Result<(Box<dyn Layer<SubscriberForSecondLayer> + Send + Sync>, Self), MeilisearchHttpError>
+    {
+        let invalid = |reason: String| {
+            MeilisearchHttpError::InvalidLogFile(relative_path.to_string(), reason)
+        };
+
+        let Some(logs_dir) = logs_dir else {
+            return Err(invalid(String::from(
+                "the logs can only be written into a file when a directory is configured with `--experimental-logs-dir`",
+            )));
+        };
+        let relative = Path::new(relative_path);
+        let mut components = relative.components().filter(|c| *c != Component::CurDir).peekable();
+        if components.peek().is_none()
+            || !components.all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(invalid(String::from(
+                "the path must be relative to the logs directory and can't contain `..`",
+            )));
+        }
+
+        let path = logs_dir.join(relative);
+        let error = |error: io::Error| {
+            MeilisearchHttpError::InvalidLogFile(relative_path.to_string(), error.to_string())
+        };
+
+        // The closest existing ancestor is resolved to make sure no symbolic link leaves the
+        // directory. The links are not followed to find it, so that a dangling link is resolved
+        // too instead of being created through.
+        let logs_dir = logs_dir.canonicalize().map_err(error)?;
+        let existing = path
+            .ancestors()
+            .find(|ancestor| ancestor.symlink_metadata().is_ok())
+            .unwrap_or(logs_dir.as_path());
+        match existing.canonicalize() {
+            Ok(existing) if existing.starts_with(&logs_dir) => (),
+            Ok(_) => return Err(invalid(String::from("the path leaves the logs directory"))),
+            Err(_) => {
+                return Err(invalid(String::from("the path goes through a broken symbolic link")))
+            }
+        }
+
+        if path.is_dir() {
+            return Err(error(io::Error::other("it is a directory")));
+        }
+        if create_dirs {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(error)?;
+            }
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path).map_err(error)?;
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let writer = std::thread::Builder::new()
+            .name(String::from("log-file-writer"))
+            .spawn(move || write_log_file(receiver, file))
+            .map_err(error)?;
+
+        let layer = tracing_subscriber::fmt::layer()
+            .with_writer(move || LogWriter { sender: sender.clone() })
+            .with_ansi(false)
+            .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE);
+        let layer = match mode {
+            LogFileMode::Human => {
+                Box::new(layer) as Box<dyn Layer<SubscriberForSecondLayer> + Send + Sync>
+            }
+            LogFileMode::Json => Box::new(layer.json()),
+        };
+
+        Ok((layer, Self { path, writer }))
+    }
+
+    /// Waits for the remaining logs to be written and the file to be closed.
+    ///
+    /// The layer writing into this file must have been dropped beforehand.
+    fn close(self) {
+        match self.writer.join() {
+            Ok(Ok(())) => (),
+            Ok(Err(error)) => {
+                tracing::error!(%error, path = %self.path.display(), "Could not write the logs")
+            }
+            Err(_) => tracing::error!(path = %self.path.display(), "The log writer panicked"),
+        }
+    }
+}
+
+/// Writes the logs until the channel is closed, flushing them whenever no other log is waiting
+/// so that the file can be followed while it is written.
+fn write_log_file(mut receiver: mpsc::UnboundedReceiver<Vec<u8>>, file: File) -> io::Result<()> {
+    let mut writer = BufWriter::new(file);
+    while let Some(log) = receiver.blocking_recv() {
+        writer.write_all(&log)?;
+        while let Ok(log) = receiver.try_recv() {
+            writer.write_all(&log)?;
+        }
+        writer.flush()?;
+    }
+    writer.flush()?;
+    writer.get_ref().sync_all()
+}
+
+/// The layer writing the logs into stderr, in the mode the instance was launched with.
+fn stderr_layer(
+    mode: crate::option::LogMode,
+) -> Box<dyn Layer<SubscriberForSecondLayer> + Send + Sync> {
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(|| LineWriter::new(std::io::stderr()))
+        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE);
+
+    match mode {
+        crate::option::LogMode::Human => {
+            Box::new(layer) as Box<dyn Layer<SubscriberForSecondLayer> + Send + Sync>
+        }
+        crate::option::LogMode::Json => Box::new(layer.json()),
+    }
+}
+
+/// Swaps the filter of the logs written into stderr, or into a file if a `path` is given.
 pub async fn update_stderr_target(
-    index_scheduler: GuardedData<ActionPolicy<{ actions::METRICS_GET }>, Data<IndexScheduler>>,
+    index_scheduler: GuardedData<ActionPolicy<{ actions::LOGS_CREATE }>, Data<IndexScheduler>>,
     logs: Data<LogStderrHandle>,
+    state: Data<LogStreamState>,
     body: AwebJson<UpdateStderrLogs, DeserrJsonError>,
+    options: Data<Opt>,
 ) -> Result<HttpResponse, ResponseError> {
     index_scheduler.features().check_logs_route()?;
 
+    let logs_dir = options.experimental_logs_dir.as_deref();
     let opt = body.into_inner();
 
-    logs.modify(|layer| {
-        *layer.filter_mut() = opt.target.0.clone();
-    })
-    .unwrap();
+    let Some(path) = opt.path else {
+        logs.modify(|layer| {
+            *layer.filter_mut() = opt.target.0.clone();
+        })
+        .unwrap();
+
+        return Ok(HttpResponse::NoContent().finish());
+    };
+
+    let (new_layer, new_file) =
+        LogFile::open(logs_dir, &path, opt.mode.unwrap_or_default(), opt.create_dirs)?;
+
+    // the layer is swapped under the lock so that the file it writes into is always the current one
+    let previous_file = {
+        let mut file = state.file.lock().unwrap_or_else(PoisonError::into_inner);
+        logs.modify(|layer| {
+            *layer.filter_mut() = opt.target.0.clone();
+            *layer.inner_mut() = new_layer;
+        })
+        .unwrap();
+        file.replace(new_file)
+    };
+    if let Some(previous_file) = previous_file {
+        tokio::task::spawn_blocking(move || previous_file.close())
+            .await
+            .map_err(MeilisearchHttpError::from)?;
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Flushes and closes the file the logs are written into, if any, and writes them into stderr
+/// again with the same filter.
+pub async fn reset_stderr_target(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::LOGS_DELETE }>, Data<IndexScheduler>>,
+    logs: Data<LogStderrHandle>,
+    state: Data<LogStreamState>,
+    opt: Data<Opt>,
+) -> Result<HttpResponse, ResponseError> {
+    index_scheduler.features().check_logs_route()?;
+
+    let file = {
+        let mut file = state.file.lock().unwrap_or_else(PoisonError::into_inner);
+        if file.is_some() {
+            logs.modify(|layer| *layer.inner_mut() = stderr_layer(opt.experimental_logs_mode))
+                .unwrap();
+        }
+        file.take()
+    };
+    if let Some(file) = file {
+        tokio::task::spawn_blocking(move || file.close())
+            .await
+            .map_err(MeilisearchHttpError::from)?;
+    }
 
     Ok(HttpResponse::NoContent().finish())
 }
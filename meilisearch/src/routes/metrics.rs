@@ -29,7 +29,8 @@ pub async fn get_metrics(
         return Err(error);
     }
 
-    let response = create_all_stats((*index_scheduler).clone(), auth_controller, auth_filters)?;
+    let response =
+        create_all_stats((*index_scheduler).clone(), auth_controller, auth_filters, None)?;
 
     crate::metrics::MEILISEARCH_DB_SIZE_BYTES.set(response.database_size as i64);
     crate::metrics::MEILISEARCH_USED_DB_SIZE_BYTES.set(response.used_database_size as i64);
@@ -39,6 +40,27 @@ pub async fn get_metrics(
         crate::metrics::MEILISEARCH_INDEX_DOCS_COUNT
             .with_label_values(&[index])
             .set(value.number_of_documents as i64);
+        crate::metrics::MEILISEARCH_INDEX_FRAGMENTATION
+            .with_label_values(&[index])
+            .set(value.fragmentation);
+
+        for (database, stats) in value.databases.iter() {
+            for (kind, pages) in [
+                ("branch", stats.branch_pages),
+                ("leaf", stats.leaf_pages),
+                ("overflow", stats.overflow_pages),
+            ] {
+                crate::metrics::MEILISEARCH_INDEX_DB_PAGES
+                    .with_label_values(&[index, database, kind])
+                    .set(pages as i64);
+            }
+            crate::metrics::MEILISEARCH_INDEX_DB_ENTRIES
+                .with_label_values(&[index, database])
+                .set(stats.entries as i64);
+            crate::metrics::MEILISEARCH_INDEX_DB_DEPTH
+                .with_label_values(&[index, database])
+                .set(stats.depth as i64);
+        }
     }
 
     for (kind, value) in index_scheduler.get_stats()? {
@@ -54,6 +76,14 @@ pub async fn get_metrics(
     }
     crate::metrics::MEILISEARCH_IS_INDEXING.set(index_scheduler.is_task_processing()? as i64);
 
+    let task_queue = index_scheduler.task_queue_stats()?;
+    crate::metrics::MEILISEARCH_OLDEST_ENQUEUED_TASK_AGE_SECONDS
+        .set(task_queue.oldest_enqueued_task_age_seconds.unwrap_or_default());
+    crate::metrics::MEILISEARCH_FINISHED_TASKS_PER_MINUTE
+        .set(task_queue.finished_tasks_per_minute as i64);
+    crate::metrics::MEILISEARCH_AVERAGE_BATCH_DURATION_SECONDS
+        .set(task_queue.average_batch_duration_seconds.unwrap_or_default());
+
     let encoder = TextEncoder::new();
     let mut buffer = vec![];
     encoder.encode(&prometheus::gather(), &mut buffer).expect("Failed to encode metrics");
@@ -2,9 +2,15 @@ use std::collections::BTreeMap;
 
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
-use index_scheduler::IndexScheduler;
+use deserr::actix_web::AwebQueryParameter;
+use deserr::Deserr;
+use index_scheduler::{CrashRecoveryReport, IndexScheduler, ReplicationStatus, TaskQueueStats};
 use meilisearch_auth::AuthController;
+use meilisearch_types::deserr::DeserrQueryParamError;
+use meilisearch_types::error::deserr_codes::*;
 use meilisearch_types::error::{Code, ResponseError};
+use meilisearch_types::features::RuntimeTogglableFeatures;
+use meilisearch_types::index_uid_pattern::IndexUidPattern;
 use meilisearch_types::settings::{Settings, Unchecked};
 use meilisearch_types::tasks::{Kind, Status, Task, TaskId};
 use serde::{Deserialize, Serialize};
@@ -16,6 +22,7 @@ use crate::analytics::Analytics;
 use crate::extractors::authentication::policies::*;
 use crate::extractors::authentication::GuardedData;
 use crate::search_queue::SearchQueue;
+use crate::tls::TlsCertificate;
 use crate::Opt;
 
 const PAGINATION_DEFAULT_LIMIT: usize = 20;
@@ -23,13 +30,15 @@ const PAGINATION_DEFAULT_LIMIT: usize = 20;
 mod api_key;
 mod dump;
 pub mod features;
+mod index_groups;
 pub mod indexes;
-mod logs;
+pub(crate) mod logs;
 mod metrics;
 mod multi_search;
 mod snapshot;
 mod swap_indexes;
 pub mod tasks;
+mod webhook;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(web::scope("/tasks").configure(tasks::configure))
@@ -41,10 +50,12 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         .service(web::resource("/stats").route(web::get().to(get_stats)))
         .service(web::resource("/version").route(web::get().to(get_version)))
         .service(web::scope("/indexes").configure(indexes::configure))
+        .service(web::scope("/index-groups").configure(index_groups::configure))
         .service(web::scope("/multi-search").configure(multi_search::configure))
         .service(web::scope("/swap-indexes").configure(swap_indexes::configure))
         .service(web::scope("/metrics").configure(metrics::configure))
-        .service(web::scope("/experimental-features").configure(features::configure));
+        .service(web::scope("/experimental-features").configure(features::configure))
+        .service(web::scope("/webhook").configure(webhook::configure));
 }
 
 pub fn get_task_id(req: &HttpRequest, opt: &Opt) -> Result<Option<TaskId>, ResponseError> {
@@ -291,27 +302,75 @@ pub struct Stats {
     #[serde(serialize_with = "time::serde::rfc3339::option::serialize")]
     pub last_update: Option<OffsetDateTime>,
     pub indexes: BTreeMap<String, indexes::IndexStats>,
+    /// Only returned when the stats are restricted to an index group.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<IndexGroupStats>,
+    /// Only returned when the instance is a read-only replica.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replication: Option<ReplicationStatus>,
+    /// Only returned when the instance ever stopped while processing a batch of tasks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_crash_recovery: Option<CrashRecoveryReport>,
+    /// Only returned when the API key can access all the indexes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_queue: Option<TaskQueueStats>,
+}
+
+/// The stats aggregated over the indexes of an index group.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexGroupStats {
+    pub name: String,
+    pub number_of_indexes: usize,
+    pub number_of_documents: u64,
+}
+
+#[derive(Deserr, Debug)]
+#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
+pub struct StatsQuery {
+    #[deserr(default, error = DeserrQueryParamError<InvalidStatsGroup>)]
+    pub group: Option<String>,
 }
 
 async fn get_stats(
     index_scheduler: GuardedData<ActionPolicy<{ actions::STATS_GET }>, Data<IndexScheduler>>,
     auth_controller: GuardedData<ActionPolicy<{ actions::STATS_GET }>, Data<AuthController>>,
+    params: AwebQueryParameter<StatsQuery, DeserrQueryParamError>,
     req: HttpRequest,
     analytics: web::Data<dyn Analytics>,
 ) -> Result<HttpResponse, ResponseError> {
-    analytics.publish("Stats Seen".to_string(), json!({ "per_index_uid": false }), Some(&req));
+    let StatsQuery { group } = params.into_inner();
+    analytics.publish(
+        "Stats Seen".to_string(),
+        json!({ "per_index_uid": false, "per_index_group": group.is_some() }),
+        Some(&req),
+    );
     let filters = index_scheduler.filters();
 
-    let stats = create_all_stats((*index_scheduler).clone(), (*auth_controller).clone(), filters)?;
+    let patterns = group.as_deref().map(|name| index_scheduler.index_group(name)).transpose()?;
+    let mut stats = create_all_stats(
+        (*index_scheduler).clone(),
+        (*auth_controller).clone(),
+        filters,
+        patterns.as_deref(),
+    )?;
+    stats.group = group.map(|name| IndexGroupStats {
+        name,
+        number_of_indexes: stats.indexes.len(),
+        number_of_documents: stats.indexes.values().map(|stats| stats.number_of_documents).sum(),
+    });
 
     debug!(returns = ?stats, "Get stats");
     Ok(HttpResponse::Ok().json(stats))
 }
 
+/// Returns the stats of the authorized indexes, restricted to the ones matching
+/// one of the `index_patterns` if any.
 pub fn create_all_stats(
     index_scheduler: Data<IndexScheduler>,
     auth_controller: Data<AuthController>,
     filters: &meilisearch_auth::AuthFilter,
+    index_patterns: Option<&[IndexUidPattern]>,
 ) -> Result<Stats, ResponseError> {
     let mut last_task: Option<OffsetDateTime> = None;
     let mut indexes = BTreeMap::new();
@@ -329,6 +388,11 @@ pub fn create_all_stats(
         if !filters.is_index_authorized(&index_uid) {
             continue;
         }
+        if index_patterns
+            .map_or(false, |patterns| !patterns.iter().any(|p| p.matches_str(&index_uid)))
+        {
+            continue;
+        }
 
         last_task = last_task.map_or(Some(stats.inner_stats.updated_at), |last| {
             Some(last.max(stats.inner_stats.updated_at))
@@ -341,39 +405,69 @@ pub fn create_all_stats(
     database_size += auth_controller.size()?;
     used_database_size += auth_controller.used_size()?;
 
-    let stats = Stats { database_size, used_database_size, last_update: last_task, indexes };
+    let stats = Stats {
+        database_size,
+        used_database_size,
+        last_update: last_task,
+        indexes,
+        group: None,
+        replication: index_scheduler.replication_status(),
+        last_crash_recovery: index_scheduler.last_crash_recovery()?,
+        task_queue: if filters.all_indexes_authorized() {
+            Some(index_scheduler.task_queue_stats()?)
+        } else {
+            None
+        },
+    };
     Ok(stats)
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct VersionResponse {
+pub struct VersionResponse {
     commit_sha: String,
     commit_date: String,
     pkg_version: String,
+    experimental_features: Vec<&'static str>,
+    heed_version: String,
+    lmdb_master_sys_version: String,
+}
+
+impl VersionResponse {
+    /// The versions this binary was built with and the experimental features currently enabled.
+    pub fn new(features: RuntimeTogglableFeatures) -> Self {
+        let build_info = build_info::BuildInfo::from_build();
+
+        VersionResponse {
+            commit_sha: build_info.commit_sha1.unwrap_or("unknown").to_string(),
+            commit_date: build_info
+                .commit_timestamp
+                .and_then(|commit_timestamp| {
+                    commit_timestamp
+                        .format(&time::format_description::well_known::Iso8601::DEFAULT)
+                        .ok()
+                })
+                .unwrap_or("unknown".into()),
+            pkg_version: env!("CARGO_PKG_VERSION").to_string(),
+            experimental_features: features.enabled_features(),
+            heed_version: build_info.heed_version.unwrap_or("unknown").to_string(),
+            lmdb_master_sys_version: build_info
+                .lmdb_master_sys_version
+                .unwrap_or("unknown")
+                .to_string(),
+        }
+    }
 }
 
 async fn get_version(
-    _index_scheduler: GuardedData<ActionPolicy<{ actions::VERSION }>, Data<IndexScheduler>>,
+    index_scheduler: GuardedData<ActionPolicy<{ actions::VERSION }>, Data<IndexScheduler>>,
     req: HttpRequest,
     analytics: web::Data<dyn Analytics>,
 ) -> HttpResponse {
     analytics.publish("Version Seen".to_string(), json!(null), Some(&req));
 
-    let build_info = build_info::BuildInfo::from_build();
-
-    HttpResponse::Ok().json(VersionResponse {
-        commit_sha: build_info.commit_sha1.unwrap_or("unknown").to_string(),
-        commit_date: build_info
-            .commit_timestamp
-            .and_then(|commit_timestamp| {
-                commit_timestamp
-                    .format(&time::format_description::well_known::Iso8601::DEFAULT)
-                    .ok()
-            })
-            .unwrap_or("unknown".into()),
-        pkg_version: env!("CARGO_PKG_VERSION").to_string(),
-    })
+    let features = index_scheduler.features().runtime_features();
+    HttpResponse::Ok().json(VersionResponse::new(features))
 }
 
 #[derive(Serialize)]
@@ -387,6 +481,7 @@ pub async fn get_health(
     index_scheduler: Data<IndexScheduler>,
     auth_controller: Data<AuthController>,
     search_queue: Data<SearchQueue>,
+    tls_certificate: Option<Data<TlsCertificate>>,
     analytics: web::Data<dyn Analytics>,
 ) -> Result<HttpResponse, ResponseError> {
     analytics.health_seen(&req);
@@ -395,5 +490,14 @@ pub async fn get_health(
     index_scheduler.health().unwrap();
     auth_controller.health().unwrap();
 
-    Ok(HttpResponse::Ok().json(serde_json::json!({ "status": "available" })))
+    // the expiration of the certificate lets the monitoring check that it is renewed in time
+    let mut health = serde_json::json!({ "status": "available" });
+    if let Some(tls_certificate) = tls_certificate {
+        health["tlsCertificateNotAfter"] = serde_json::json!(tls_certificate
+            .not_after()
+            .format(&time::format_description::well_known::Rfc3339)
+            .unwrap());
+    }
+
+    Ok(HttpResponse::Ok().json(health))
 }
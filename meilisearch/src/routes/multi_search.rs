@@ -1,22 +1,34 @@
+use std::collections::{BTreeMap, HashSet};
+use std::time::Instant;
+
 use actix_http::StatusCode;
 use actix_web::web::{self, Data};
 use actix_web::{HttpRequest, HttpResponse};
 use deserr::actix_web::AwebJson;
 use index_scheduler::IndexScheduler;
 use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::{
+    InvalidMultiSearchContinueOnError, InvalidMultiSearchFederated,
+};
 use meilisearch_types::error::ResponseError;
 use meilisearch_types::keys::actions;
+use roaring::RoaringBitmap;
 use serde::Serialize;
+use serde_json::Value;
 use tracing::debug;
 
 use crate::analytics::{Analytics, MultiSearchAggregator};
+use crate::error::MeilisearchHttpError;
 use crate::extractors::authentication::policies::ActionPolicy;
 use crate::extractors::authentication::{AuthenticationError, GuardedData};
 use crate::extractors::sequential_extractor::SeqHandler;
-use crate::routes::indexes::search::search_kind;
+use crate::routes::indexes::search::{apply_preset_and_search_defaults, search_kind};
 use crate::search::{
-    add_search_rules, perform_search, SearchQueryWithIndex, SearchResultWithIndex,
+    add_search_rules, perform_federated_facets, perform_search, CancelSearchOnDrop, ComputedFacets,
+    FederatedSearchHit, FederatedSearchResult, Federation, HitFederation, HitsInfo, MergeFacets,
+    MergedFacets, SearchQueryWithIndex, SearchResultWithIndex,
 };
+use crate::search_latency::{LatencyKind, SearchLatencies};
 use crate::search_queue::SearchQueue;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -24,81 +36,194 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
 }
 
 #[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
 struct SearchResults {
-    results: Vec<SearchResultWithIndex>,
+    results: Vec<SearchResultOrError>,
+    /// The wall time of the whole request, only returned when `continueOnError` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    processing_time_ms: Option<u128>,
+}
+
+/// The outcome of a query of a multi-search, at the position of the query.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum SearchResultOrError {
+    Result(SearchResultWithIndex),
+    Error(SearchErrorWithIndex),
+}
+
+/// A query that failed in a multi-search made with `continueOnError`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchErrorWithIndex {
+    index_uid: String,
+    error: ResponseError,
+    processing_time_ms: u128,
 }
 
 #[derive(Debug, deserr::Deserr)]
 #[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
 pub struct SearchQueries {
     queries: Vec<SearchQueryWithIndex>,
+    #[deserr(default, error = DeserrJsonError<InvalidMultiSearchFederated>)]
+    federation: Option<Federation>,
+    /// Returns the error of a failing query at its position instead of failing the whole request.
+    /// A federated search still fails as a whole.
+    #[deserr(default, error = DeserrJsonError<InvalidMultiSearchContinueOnError>)]
+    continue_on_error: bool,
+}
+
+/// How the results of a query are merged into the ones of a federated search.
+struct FederatedQuery {
+    weight: f64,
+    show_ranking_score: bool,
 }
 
 pub async fn multi_search_with_post(
     index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
     search_queue: Data<SearchQueue>,
-    params: AwebJson<SearchQueries, DeserrJsonError>,
+    search_latencies: Data<SearchLatencies>,
+    params: AwebJson<Value, DeserrJsonError>,
     req: HttpRequest,
     analytics: web::Data<dyn Analytics>,
 ) -> Result<HttpResponse, ResponseError> {
-    let queries = params.into_inner().queries;
+    let before_search = Instant::now();
+    // The queries are kept as JSON until now to know which parameters were explicitly provided.
+    let params = params.into_inner();
+    let provided: Vec<Vec<String>> = match params.get("queries") {
+        Some(Value::Array(queries)) => queries
+            .iter()
+            .map(|query| query.as_object().map(|q| q.keys().cloned().collect()).unwrap_or_default())
+            .collect(),
+        _ => Vec::new(),
+    };
+    let SearchQueries { queries, federation, continue_on_error } =
+        deserr::deserialize::<_, _, DeserrJsonError>(params)?;
+    // the results of a federated search can't be merged without the results of all its queries
+    let continue_on_error = continue_on_error && federation.is_none();
+    if let Some(federation) = &federation {
+        let searched = |uid: &String| queries.iter().any(|query| query.index_uid.as_str() == uid);
+        if let Some(uid) = federation.facets_by_index.keys().find(|uid| !searched(uid)) {
+            return Err(MeilisearchHttpError::FederatedFacetsIndexNotSearched(uid.clone()).into());
+        }
+    }
 
     let mut multi_aggregate = MultiSearchAggregator::from_queries(&queries, &req);
     let features = index_scheduler.features();
 
     // Since we don't want to process half of the search requests and then get a permit refused
-    // we're going to get one permit for the whole duration of the multi-search request.
-    let _permit = search_queue.try_get_search_permit().await?;
+    // we're going to get one permit, counting for all the queries, for the whole duration of the multi-search request.
+    let _permit = search_queue.try_get_search_permits(queries.len()).await?;
+    // Cancels all the searches of the request if the client disconnects.
+    let cancel_on_drop = CancelSearchOnDrop::default();
 
     // Explicitly expect a `(ResponseError, usize)` for the error type rather than `ResponseError` only,
     // so that `?` doesn't work if it doesn't use `with_index`, ensuring that it is not forgotten in case of code
     // changes.
+    let mut federated_queries = Vec::new();
     let search_results: Result<_, (ResponseError, usize)> = async {
         let mut search_results = Vec::with_capacity(queries.len());
-        for (query_index, (index_uid, mut query)) in
-            queries.into_iter().map(SearchQueryWithIndex::into_index_query).enumerate()
-        {
-            debug!(on_index = query_index, parameters = ?query, "Multi-search");
-
-            // Check index from API key
-            if !index_scheduler.filters().is_index_authorized(&index_uid) {
-                return Err(AuthenticationError::InvalidToken).with_index(query_index);
-            }
-            // Apply search rules from tenant token
-            if let Some(search_rules) = index_scheduler.filters().get_index_search_rules(&index_uid)
-            {
-                add_search_rules(&mut query, search_rules);
-            }
+        for (query_index, query) in queries.into_iter().enumerate() {
+            let query_started_at = Instant::now();
+            let query_index_uid = query.index_uid.to_string();
+            let search_result: Result<_, (ResponseError, usize)> = async {
+                let weight = match &federation {
+                    Some(_) => {
+                        check_federated_query(&query).with_index(query_index)?;
+                        Some(*query.federation_options.unwrap_or_default().weight)
+                    }
+                    None if query.federation_options.is_some() => {
+                        return Err(MeilisearchHttpError::FederationOptionsWithoutFederation)
+                            .with_index(query_index);
+                    }
+                    None => None,
+                };
+                let (index_uid, mut query) = query.into_index_query();
+                debug!(on_index = query_index, parameters = ?query, "Multi-search");
 
-            let index = index_scheduler
-                .index(&index_uid)
-                .map_err(|err| {
-                    let mut err = ResponseError::from(err);
-                    // Patch the HTTP status code to 400 as it defaults to 404 for `index_not_found`, but
-                    // here the resource not found is not part of the URL.
-                    err.code = StatusCode::BAD_REQUEST;
-                    err
+                // Check index from API key
+                if !index_scheduler.filters().is_index_authorized(&index_uid) {
+                    return Err(AuthenticationError::InvalidToken).with_index(query_index);
+                }
+
+                let index = index_scheduler
+                    .index(&index_uid)
+                    .map_err(|err| {
+                        let mut err = ResponseError::from(err);
+                        // Patch the HTTP status code to 400 as it defaults to 404 for `index_not_found`, but
+                        // here the resource not found is not part of the URL.
+                        err.code = StatusCode::BAD_REQUEST;
+                        err
+                    })
+                    .with_index(query_index)?;
+
+                let provided = &provided[query_index];
+                apply_preset_and_search_defaults(&index, &mut query, |name| {
+                    provided.iter().any(|parameter| parameter == name)
                 })
                 .with_index(query_index)?;
 
-            let search_kind = search_kind(&query, index_scheduler.get_ref(), &index, features)
-                .with_index(query_index)?;
+                if let (Some(federation), Some(weight)) = (&federation, weight) {
+                    federated_queries.push(FederatedQuery {
+                        weight,
+                        show_ranking_score: query.show_ranking_score,
+                    });
+                    // Every query returns all the hits up to the end of the merged page,
+                    // with the ranking scores they are merged with. The defaults of the index
+                    // can't paginate the query nor compute facets that would be dropped.
+                    query.offset = 0;
+                    query.limit = federation.offset.saturating_add(federation.limit);
+                    query.page = None;
+                    query.hits_per_page = None;
+                    query.facets = None;
+                    query.show_ranking_score = true;
+                }
 
-            let search_result =
-                tokio::task::spawn_blocking(move || perform_search(&index, query, search_kind))
-                    .await
+                // Apply search rules from tenant token
+                if let Some(search_rules) =
+                    index_scheduler.filters().get_index_search_rules(&index_uid)
+                {
+                    add_search_rules(&mut query, &index_uid, search_rules)
+                        .with_index(query_index)?;
+                }
+
+                let search_kind = search_kind(&query, index_scheduler.get_ref(), &index, features)
                     .with_index(query_index)?;
 
-            search_results.push(SearchResultWithIndex {
-                index_uid: index_uid.into_inner(),
-                result: search_result.with_index(query_index)?,
-            });
+                let canceled = cancel_on_drop.token();
+                let started_at = Instant::now();
+                let search_result = tokio::task::spawn_blocking(move || {
+                    perform_search(&index, query, search_kind, canceled, false, None)
+                })
+                .await
+                .with_index(query_index)?;
+                let result = search_result.with_index(query_index)?;
+                search_latencies.record(&index_uid, LatencyKind::MultiSearch, started_at.elapsed());
+
+                Ok(SearchResultWithIndex { index_uid: index_uid.into_inner(), result })
+            }
+            .await;
+
+            match search_result {
+                Ok(result) => search_results.push(SearchResultOrError::Result(result)),
+                Err((error, _)) if continue_on_error => {
+                    search_results.push(SearchResultOrError::Error(SearchErrorWithIndex {
+                        index_uid: query_index_uid,
+                        error,
+                        processing_time_ms: query_started_at.elapsed().as_millis(),
+                    }))
+                }
+                Err(error) => return Err(error),
+            }
         }
         Ok(search_results)
     }
     .await;
 
-    if search_results.is_ok() {
+    let all_succeeded = search_results.as_ref().is_ok_and(|results| {
+        results.iter().all(|result| matches!(result, SearchResultOrError::Result(_)))
+    });
+    if all_succeeded {
         multi_aggregate.succeed();
     }
     analytics.post_multi_search(multi_aggregate);
@@ -113,7 +238,149 @@ pub async fn multi_search_with_post(
 
     debug!(returns = ?search_results, "Multi-search");
 
-    Ok(HttpResponse::Ok().json(SearchResults { results: search_results }))
+    match federation {
+        Some(federation) => {
+            // a federated search fails as soon as one of its queries fails
+            let mut search_results: Vec<_> = search_results
+                .into_iter()
+                .filter_map(|result| match result {
+                    SearchResultOrError::Result(result) => Some(result),
+                    SearchResultOrError::Error(_) => None,
+                })
+                .collect();
+
+            // the facets of an index are computed on the documents matched by all its queries
+            let mut facets_by_index = BTreeMap::new();
+            for (index_uid, fields) in &federation.facets_by_index {
+                let mut candidates = RoaringBitmap::new();
+                for result in search_results.iter_mut().filter(|r| &r.index_uid == index_uid) {
+                    candidates |= std::mem::take(&mut result.result.candidates);
+                }
+                let facets: Result<_, ResponseError> = async {
+                    let index = index_scheduler.index(index_uid)?;
+                    let fields = fields.clone();
+                    let canceled = cancel_on_drop.token();
+                    let facets = tokio::task::spawn_blocking(move || {
+                        perform_federated_facets(&index, &fields, candidates, canceled)
+                    })
+                    .await??;
+                    Ok(facets)
+                }
+                .await;
+                let facets = facets.map_err(|mut err| {
+                    err.message =
+                        format!("Inside `.federation.facetsByIndex.{index_uid}`: {}", err.message);
+                    err
+                })?;
+                facets_by_index.insert(index_uid.clone(), facets);
+            }
+
+            let result = merge_federated_results(
+                search_results,
+                federated_queries,
+                facets_by_index,
+                federation,
+                before_search,
+            );
+            Ok(HttpResponse::Ok().json(result))
+        }
+        None => Ok(HttpResponse::Ok().json(SearchResults {
+            results: search_results,
+            processing_time_ms: continue_on_error.then(|| before_search.elapsed().as_millis()),
+        })),
+    }
+}
+
+/// Rejects the parameters of a query that have no meaning once its hits are merged.
+fn check_federated_query(query: &SearchQueryWithIndex) -> Result<(), MeilisearchHttpError> {
+    let pagination = [
+        ("offset", query.offset.is_some()),
+        ("limit", query.limit.is_some()),
+        ("page", query.page.is_some()),
+        ("hitsPerPage", query.hits_per_page.is_some()),
+        ("searchAfter", query.search_after.is_some()),
+    ];
+    if let Some((name, _)) = pagination.into_iter().find(|(_, is_set)| *is_set) {
+        return Err(MeilisearchHttpError::FederatedQueryPagination(name));
+    }
+    if query.facets.is_some() {
+        return Err(MeilisearchHttpError::FederatedQueryFacets);
+    }
+    Ok(())
+}
+
+/// Merges the hits of the queries into a single list ranked by their weighted ranking scores.
+///
+/// The hits with the same weighted score stay in the order of their queries, and then of their
+/// ranks. A document of an index matched by several queries is only kept at its best position.
+///
+/// The facets of the indexes are also merged by name when `federation.mergeFacets` is set.
+fn merge_federated_results(
+    results: Vec<SearchResultWithIndex>,
+    queries: Vec<FederatedQuery>,
+    facets_by_index: BTreeMap<String, ComputedFacets>,
+    federation: Federation,
+    before_search: Instant,
+) -> FederatedSearchResult {
+    let mut estimated_total_hits = 0;
+    let mut degraded = false;
+    let mut hits = Vec::new();
+    for (queries_position, (SearchResultWithIndex { index_uid, result }, query)) in
+        results.into_iter().zip(queries).enumerate()
+    {
+        estimated_total_hits += match result.hits_info {
+            HitsInfo::OffsetLimit { estimated_total_hits: hits, .. } => hits,
+            HitsInfo::Pagination { total_hits: hits, .. } => hits,
+        };
+        degraded |= result.degraded;
+        for mut hit in result.hits {
+            let ranking_score = hit.ranking_score.unwrap_or(1.0);
+            if !query.show_ranking_score {
+                hit.ranking_score = None;
+            }
+            let federation = HitFederation {
+                index_uid: index_uid.clone(),
+                queries_position,
+                weighted_ranking_score: ranking_score * query.weight,
+            };
+            hits.push(FederatedSearchHit { hit, federation });
+        }
+    }
+
+    // the sort is stable, keeping the order of the queries and of the ranks on ties
+    hits.sort_by(|left, right| {
+        let (left, right) = (&left.federation, &right.federation);
+        right.weighted_ranking_score.total_cmp(&left.weighted_ranking_score)
+    });
+    let mut seen = HashSet::new();
+    hits.retain(|FederatedSearchHit { hit, federation }| {
+        seen.insert((federation.index_uid.clone(), hit.docid))
+    });
+    let hits = hits.into_iter().skip(federation.offset).take(federation.limit).collect();
+
+    let (facet_distribution, facet_stats, facet_warnings) = match federation.merge_facets {
+        Some(MergeFacets { max_values_per_facet }) => {
+            let MergedFacets { distribution, stats, warnings } =
+                MergedFacets::new(&facets_by_index, max_values_per_facet);
+            (Some(distribution), Some(stats), warnings)
+        }
+        None => (None, None, BTreeMap::new()),
+    };
+
+    FederatedSearchResult {
+        hits,
+        processing_time_ms: before_search.elapsed().as_millis(),
+        hits_info: HitsInfo::OffsetLimit {
+            limit: federation.limit,
+            offset: federation.offset,
+            estimated_total_hits,
+        },
+        degraded,
+        facets_by_index,
+        facet_distribution,
+        facet_stats,
+        facet_warnings,
+    }
 }
 
 /// Local `Result` extension trait to avoid `map_err` boilerplate.
@@ -1,9 +1,13 @@
-use actix_web::web::Data;
+use std::path::PathBuf;
+
+use actix_web::web::{Bytes, Data};
 use actix_web::{web, HttpRequest, HttpResponse};
+use futures::Stream;
 use index_scheduler::IndexScheduler;
-use meilisearch_types::error::ResponseError;
+use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::tasks::KindWithContent;
 use serde_json::json;
+use tokio::io::AsyncReadExt;
 use tracing::debug;
 
 use crate::analytics::Analytics;
@@ -14,7 +18,9 @@ use crate::routes::{get_task_id, is_dry_run, SummarizedTaskView};
 use crate::Opt;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.service(web::resource("").route(web::post().to(SeqHandler(create_snapshot))));
+    cfg.service(web::resource("").route(web::post().to(SeqHandler(create_snapshot))))
+        .service(web::resource("/latest").route(web::get().to(SeqHandler(get_latest_snapshot))))
+        .service(web::resource("/import").route(web::post().to(SeqHandler(import_snapshot))));
 }
 
 pub async fn create_snapshot(
@@ -36,3 +42,69 @@ pub async fn create_snapshot(
     debug!(returns = ?task, "Create snapshot");
     Ok(HttpResponse::Accepted().json(task))
 }
+
+/// Downloads the last snapshot created by this instance, the replicas import it from their primary.
+///
+/// The snapshot contains the documents of every index, the tasks and the API keys, so it has its
+/// own action that `snapshots.*` doesn't grant.
+pub async fn get_latest_snapshot(
+    index_scheduler: GuardedData<
+        ActionPolicy<{ actions::SNAPSHOTS_DOWNLOAD }>,
+        Data<IndexScheduler>,
+    >,
+) -> Result<HttpResponse, ResponseError> {
+    let snapshot_path = index_scheduler.snapshot_path();
+    let file = match tokio::fs::File::open(&snapshot_path).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(ResponseError::from_msg(
+                String::from("No snapshot was created on this instance."),
+                Code::SnapshotNotFound,
+            ))
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    debug!(path = %snapshot_path.display(), "Download snapshot");
+    Ok(HttpResponse::Ok()
+        .content_type("application/gzip")
+        .streaming(read_snapshot(file, snapshot_path)))
+}
+
+fn read_snapshot(
+    mut file: tokio::fs::File,
+    path: PathBuf,
+) -> impl Stream<Item = Result<Bytes, std::io::Error>> {
+    async_stream::try_stream! {
+        let mut buffer = vec![0; 64 * 1024];
+        loop {
+            let read = file.read(&mut buffer).await?;
+            if read == 0 {
+                debug!(path = %path.display(), "Snapshot downloaded");
+                break;
+            }
+            yield Bytes::copy_from_slice(&buffer[..read]);
+        }
+    }
+}
+
+/// Enqueues the import of a snapshot of the primary, on a read-only replica.
+pub async fn import_snapshot(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::SNAPSHOTS_CREATE }>, Data<IndexScheduler>>,
+    req: HttpRequest,
+    opt: web::Data<Opt>,
+    analytics: web::Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    analytics.publish("Snapshot Imported".to_string(), json!({}), Some(&req));
+
+    let task = KindWithContent::SnapshotImportIncremental;
+    let uid = get_task_id(&req, &opt)?;
+    let dry_run = is_dry_run(&req, &opt)?;
+    let task: SummarizedTaskView =
+        tokio::task::spawn_blocking(move || index_scheduler.register(task, uid, dry_run))
+            .await??
+            .into();
+
+    debug!(returns = ?task, "Import snapshot");
+    Ok(HttpResponse::Accepted().json(task))
+}
@@ -26,6 +26,10 @@ use crate::extractors::sequential_extractor::SeqHandler;
 use crate::Opt;
 
 const DEFAULT_LIMIT: u32 = 20;
+/// The time a request waits for a task to finish by default, in milliseconds.
+const DEFAULT_WAIT_TIMEOUT_MS: u64 = 30_000;
+/// The longest time a request can wait for a task to finish, in milliseconds.
+const MAX_WAIT_TIMEOUT_MS: u64 = 5 * 60 * 1000;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -34,7 +38,8 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route(web::delete().to(SeqHandler(delete_tasks))),
     )
     .service(web::resource("/cancel").route(web::post().to(SeqHandler(cancel_tasks))))
-    .service(web::resource("/{task_id}").route(web::get().to(SeqHandler(get_task))));
+    .service(web::resource("/{task_id}").route(web::get().to(SeqHandler(get_task))))
+    .service(web::resource("/{task_id}/wait").route(web::get().to(SeqHandler(wait_task))));
 }
 #[derive(Debug, Deserr)]
 #[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
@@ -54,6 +59,8 @@ pub struct TasksFilterQuery {
     pub statuses: OptionStarOrList<Status>,
     #[deserr(default, error = DeserrQueryParamError<InvalidIndexUid>)]
     pub index_uids: OptionStarOrList<IndexUid>,
+    #[deserr(default, error = DeserrQueryParamError<InvalidTaskIndexGroups>)]
+    pub index_groups: OptionStarOrList<String>,
 
     #[deserr(default, error = DeserrQueryParamError<InvalidTaskAfterEnqueuedAt>, try_from(OptionStarOr<String>) = deserialize_date_after -> InvalidTaskDateError)]
     pub after_enqueued_at: OptionStarOr<OffsetDateTime>,
@@ -77,6 +84,7 @@ impl TasksFilterQuery {
             statuses: self.statuses.merge_star_and_none(),
             types: self.types.merge_star_and_none(),
             index_uids: self.index_uids.map(|x| x.to_string()).merge_star_and_none(),
+            index_groups: self.index_groups.merge_star_and_none(),
             uids: self.uids.merge_star_and_none(),
             canceled_by: self.canceled_by.merge_star_and_none(),
             before_enqueued_at: self.before_enqueued_at.merge_star_and_none(),
@@ -146,6 +154,7 @@ impl TaskDeletionOrCancelationQuery {
             statuses: self.statuses.merge_star_and_none(),
             types: self.types.merge_star_and_none(),
             index_uids: self.index_uids.map(|x| x.to_string()).merge_star_and_none(),
+            index_groups: None,
             uids: self.uids.merge_star_and_none(),
             canceled_by: self.canceled_by.merge_star_and_none(),
             before_enqueued_at: self.before_enqueued_at.merge_star_and_none(),
@@ -284,9 +293,13 @@ async fn get_tasks(
     let filters = index_scheduler.filters();
     let (tasks, total) = index_scheduler.get_tasks_from_authorized_indexes(query, filters)?;
     let mut results: Vec<_> = tasks.iter().map(TaskView::from_task).collect();
+    fill_progress(&index_scheduler, &mut results);
 
     // If we were able to fetch the number +1 tasks we asked
     // it means that there is more to come.
+    // As `from` is inclusive and tasks are returned from the newest to the oldest, the extra
+    // task is the one the next page starts with. Tasks enqueued in the meantime have a higher
+    // uid and can't shift the following pages.
     let next = if results.len() == limit as usize { results.pop().map(|t| t.uid) } else { None };
 
     let from = results.first().map(|t| t.uid);
@@ -317,13 +330,93 @@ async fn get_task(
     let (tasks, _) = index_scheduler.get_tasks_from_authorized_indexes(query, filters)?;
 
     if let Some(task) = tasks.first() {
-        let task_view = TaskView::from_task(task);
+        let mut task_view = TaskView::from_task(task);
+        fill_progress(&index_scheduler, std::slice::from_mut(&mut task_view));
         Ok(HttpResponse::Ok().json(task_view))
     } else {
         Err(index_scheduler::Error::TaskNotFound(task_uid).into())
     }
 }
 
+/// Shows the steps reported by the batch being processed on the tasks it contains.
+fn fill_progress(index_scheduler: &IndexScheduler, task_views: &mut [TaskView]) {
+    if let Some((processing, progress)) = index_scheduler.processing_progress() {
+        for task_view in task_views.iter_mut().filter(|view| processing.contains(view.uid)) {
+            task_view.progress = Some(progress.clone());
+        }
+    }
+}
+
+#[derive(Debug, Deserr)]
+#[deserr(error = DeserrQueryParamError, rename_all = camelCase, deny_unknown_fields)]
+pub struct WaitTaskQuery {
+    #[deserr(default = Param(DEFAULT_WAIT_TIMEOUT_MS), error = DeserrQueryParamError<InvalidTaskWaitTimeout>)]
+    pub timeout_ms: Param<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WaitedTaskView {
+    #[serde(flatten)]
+    task: TaskView,
+    /// `true` if the task was not finished when the timeout elapsed.
+    timed_out: bool,
+}
+
+async fn wait_task(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::TASKS_GET }>, Data<IndexScheduler>>,
+    task_uid: web::Path<String>,
+    params: AwebQueryParameter<WaitTaskQuery, DeserrQueryParamError>,
+    req: HttpRequest,
+    analytics: web::Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let task_uid_string = task_uid.into_inner();
+
+    let task_uid: TaskId = match task_uid_string.parse() {
+        Ok(id) => id,
+        Err(_e) => {
+            return Err(index_scheduler::Error::InvalidTaskUids { task_uid: task_uid_string }.into())
+        }
+    };
+    let timeout_ms = params.into_inner().timeout_ms.0.min(MAX_WAIT_TIMEOUT_MS);
+
+    analytics.publish("Tasks Waited".to_string(), json!({ "timeout_ms": timeout_ms }), Some(&req));
+
+    // the task must be visible with the key before it is waited for
+    let query = index_scheduler::Query { uids: Some(vec![task_uid]), ..Query::default() };
+    let filters = index_scheduler.filters();
+    let (tasks, _) = index_scheduler.get_tasks_from_authorized_indexes(query.clone(), filters)?;
+    if tasks.is_empty() {
+        return Err(index_scheduler::Error::TaskNotFound(task_uid).into());
+    }
+
+    // the request is woken up every time a batch is processed, until the task is finished
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(timeout_ms);
+    let mut processed_batches = index_scheduler.processed_batches();
+    let finished = loop {
+        processed_batches.borrow_and_update();
+        if index_scheduler.is_task_finished(task_uid)? {
+            break true;
+        }
+        match tokio::time::timeout_at(deadline, processed_batches.changed()).await {
+            Ok(Ok(())) => continue,
+            // the timeout elapsed or the scheduler was dropped
+            Ok(Err(_)) | Err(_) => break false,
+        }
+    };
+
+    let (tasks, _) = index_scheduler.get_tasks_from_authorized_indexes(query, filters)?;
+    match tasks.first() {
+        Some(task) => {
+            let mut task = TaskView::from_task(task);
+            fill_progress(&index_scheduler, std::slice::from_mut(&mut task));
+            Ok(HttpResponse::Ok().json(WaitedTaskView { task, timed_out: !finished }))
+        }
+        // the task was deleted while it was waited for
+        None => Err(index_scheduler::Error::TaskNotFound(task_uid).into()),
+    }
+}
+
 pub enum DeserializeDateOption {
     Before,
     After,
@@ -599,7 +692,7 @@ mod tests {
             let err = deserr_query_params::<TaskDeletionOrCancelationQuery>(params).unwrap_err();
             snapshot!(meili_snap::json_string!(err), @r###"
             {
-              "message": "Invalid value in parameter `types`: `createIndex` is not a valid task type. Available types are `documentAdditionOrUpdate`, `documentDeletion`, `settingsUpdate`, `indexCreation`, `indexDeletion`, `indexUpdate`, `indexSwap`, `taskCancelation`, `taskDeletion`, `dumpCreation`, `snapshotCreation`.",
+              "message": "Invalid value in parameter `types`: `createIndex` is not a valid task type. Available types are `documentAdditionOrUpdate`, `documentDeletion`, `settingsUpdate`, `indexCreation`, `indexDeletion`, `indexUpdate`, `indexSwap`, `taskCancelation`, `taskDeletion`, `dumpCreation`, `snapshotCreation`, `indexRestore`, `integrityCheck`, `snapshotImportIncremental`, `indexWarmup`.",
               "code": "invalid_task_types",
               "type": "invalid_request",
               "link": "https://docs.meilisearch.com/errors#invalid_task_types"
@@ -624,7 +717,7 @@ mod tests {
             let err = deserr_query_params::<TaskDeletionOrCancelationQuery>(params).unwrap_err();
             snapshot!(meili_snap::json_string!(err), @r###"
             {
-              "message": "Invalid value in parameter `indexUids[1]`: `hé` is not a valid index uid. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_).",
+              "message": "Invalid value in parameter `indexUids[1]`: `hé` is not a valid index uid: the character `é` at position 1 is not allowed. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_), of at most 400 bytes. Try `h_` instead.",
               "code": "invalid_index_uid",
               "type": "invalid_request",
               "link": "https://docs.meilisearch.com/errors#invalid_index_uid"
@@ -636,7 +729,7 @@ mod tests {
             let err = deserr_query_params::<TaskDeletionOrCancelationQuery>(params).unwrap_err();
             snapshot!(meili_snap::json_string!(err), @r###"
             {
-              "message": "Invalid value in parameter `indexUids`: `hé` is not a valid index uid. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_).",
+              "message": "Invalid value in parameter `indexUids`: `hé` is not a valid index uid: the character `é` at position 1 is not allowed. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_), of at most 400 bytes. Try `h_` instead.",
               "code": "invalid_index_uid",
               "type": "invalid_request",
               "link": "https://docs.meilisearch.com/errors#invalid_index_uid"
@@ -650,14 +743,14 @@ mod tests {
         {
             let params = "from=12&limit=15&indexUids=toto,tata-78&statuses=succeeded,enqueued&afterEnqueuedAt=2012-04-23&uids=1,2,3";
             let query = deserr_query_params::<TasksFilterQuery>(params).unwrap();
-            snapshot!(format!("{:?}", query), @r###"TasksFilterQuery { limit: Param(15), from: Some(Param(12)), uids: List([1, 2, 3]), canceled_by: None, types: None, statuses: List([Succeeded, Enqueued]), index_uids: List([IndexUid("toto"), IndexUid("tata-78")]), after_enqueued_at: Other(2012-04-24 0:00:00.0 +00:00:00), before_enqueued_at: None, after_started_at: None, before_started_at: None, after_finished_at: None, before_finished_at: None }"###);
+            snapshot!(format!("{:?}", query), @r###"TasksFilterQuery { limit: Param(15), from: Some(Param(12)), uids: List([1, 2, 3]), canceled_by: None, types: None, statuses: List([Succeeded, Enqueued]), index_uids: List([IndexUid("toto"), IndexUid("tata-78")]), index_groups: None, after_enqueued_at: Other(2012-04-24 0:00:00.0 +00:00:00), before_enqueued_at: None, after_started_at: None, before_started_at: None, after_finished_at: None, before_finished_at: None }"###);
         }
         {
             // Stars should translate to `None` in the query
             // Verify value of the default limit
             let params = "indexUids=*&statuses=succeeded,*&afterEnqueuedAt=2012-04-23&uids=1,2,3";
             let query = deserr_query_params::<TasksFilterQuery>(params).unwrap();
-            snapshot!(format!("{:?}", query), @"TasksFilterQuery { limit: Param(20), from: None, uids: List([1, 2, 3]), canceled_by: None, types: None, statuses: Star, index_uids: Star, after_enqueued_at: Other(2012-04-24 0:00:00.0 +00:00:00), before_enqueued_at: None, after_started_at: None, before_started_at: None, after_finished_at: None, before_finished_at: None }");
+            snapshot!(format!("{:?}", query), @"TasksFilterQuery { limit: Param(20), from: None, uids: List([1, 2, 3]), canceled_by: None, types: None, statuses: Star, index_uids: Star, index_groups: None, after_enqueued_at: Other(2012-04-24 0:00:00.0 +00:00:00), before_enqueued_at: None, after_started_at: None, before_started_at: None, after_finished_at: None, before_finished_at: None }");
         }
         {
             // Stars should also translate to `None` in task deletion/cancelation queries
@@ -0,0 +1,104 @@
+use actix_web::web::{self, Data};
+use actix_web::{HttpRequest, HttpResponse};
+use deserr::actix_web::AwebJson;
+use deserr::Deserr;
+use index_scheduler::IndexScheduler;
+use meilisearch_types::deserr::DeserrJsonError;
+use meilisearch_types::error::deserr_codes::*;
+use meilisearch_types::error::{Code, ResponseError};
+use meilisearch_types::keys::actions;
+use meilisearch_types::settings::Setting;
+use serde_json::json;
+use tracing::debug;
+use url::Url;
+
+use crate::analytics::Analytics;
+use crate::extractors::authentication::policies::ActionPolicy;
+use crate::extractors::authentication::GuardedData;
+use crate::extractors::sequential_extractor::SeqHandler;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("")
+            .route(web::get().to(SeqHandler(get_webhook)))
+            .route(web::patch().to(SeqHandler(patch_webhook))),
+    );
+}
+
+async fn get_webhook(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::TASKS_GET }>, Data<IndexScheduler>>,
+    req: HttpRequest,
+    analytics: Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    analytics.publish("Webhook Seen".to_string(), json!(null), Some(&req));
+
+    let status =
+        index_scheduler.webhook_status()?.ok_or(index_scheduler::Error::WebhookNotFound)?;
+
+    debug!(returns = ?status, "Get webhook");
+    Ok(HttpResponse::Ok().json(status))
+}
+
+#[derive(Debug, Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct PatchWebhook {
+    #[deserr(default, error = DeserrJsonError<InvalidWebhookUrl>)]
+    pub url: Setting<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidWebhookAuthorizationHeader>)]
+    pub authorization_header: Setting<String>,
+}
+
+/// Changes where the payloads of the webhook are sent, `null` resets a field and a webhook
+/// without a URL is disabled.
+///
+/// The settings take precedence over the launch options, even after a restart.
+async fn patch_webhook(
+    index_scheduler: GuardedData<ActionPolicy<{ actions::WEBHOOKS_UPDATE }>, Data<IndexScheduler>>,
+    body: AwebJson<PatchWebhook, DeserrJsonError>,
+    req: HttpRequest,
+    analytics: Data<dyn Analytics>,
+) -> Result<HttpResponse, ResponseError> {
+    let PatchWebhook { url, authorization_header } = body.into_inner();
+    // the authorization header is a secret, it is never logged
+    debug!(parameters = ?url, "Patch webhook");
+
+    let mut settings = index_scheduler.webhook_settings();
+    match url {
+        Setting::Set(url) => settings.url = Some(parse_webhook_url(&url)?.to_string()),
+        Setting::Reset => settings.url = None,
+        Setting::NotSet => (),
+    }
+    match authorization_header {
+        Setting::Set(header) => settings.authorization_header = Some(header),
+        Setting::Reset => settings.authorization_header = None,
+        Setting::NotSet => (),
+    }
+
+    analytics.publish(
+        "Webhook Updated".to_string(),
+        json!({
+            "url": settings.url.is_some(),
+            "authorization_header": settings.authorization_header.is_some(),
+        }),
+        Some(&req),
+    );
+    index_scheduler.put_webhook_settings(settings)?;
+
+    let status = index_scheduler.webhook_status()?.unwrap_or_default();
+    debug!(returns = ?status, "Patch webhook");
+    Ok(HttpResponse::Ok().json(status))
+}
+
+fn parse_webhook_url(url: &str) -> Result<Url, ResponseError> {
+    let invalid = |reason: String| {
+        ResponseError::from_msg(
+            format!("Invalid value at `.url`: `{url}` is not a valid webhook URL: {reason}."),
+            Code::InvalidWebhookUrl,
+        )
+    };
+    let parsed = Url::parse(url).map_err(|e| invalid(e.to_string()))?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(parsed),
+        scheme => Err(invalid(format!("the scheme `{scheme}` is not `http` nor `https`"))),
+    }
+}
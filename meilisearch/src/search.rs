@@ -1,33 +1,58 @@
 use std::cmp::min;
 use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::num::NonZeroUsize;
+use std::ops::ControlFlow;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use deserr::Deserr;
+use base64::Engine;
+use bytes::Bytes;
+use deserr::{Deserr, IntoValue, ValuePointerRef};
 use either::Either;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use index_scheduler::QueryEmbeddingCache;
 use indexmap::IndexMap;
-use meilisearch_auth::IndexSearchRules;
+use meilisearch_auth::{and_filters, IndexSearchRules};
 use meilisearch_types::deserr::DeserrJsonError;
 use meilisearch_types::error::deserr_codes::*;
-use meilisearch_types::error::ResponseError;
+use meilisearch_types::error::{Code, ResponseError};
 use meilisearch_types::heed::RoTxn;
 use meilisearch_types::index_uid::IndexUid;
+use meilisearch_types::milli::index::{
+    DEFAULT_MIN_WORD_LEN_ONE_TYPO, DEFAULT_MIN_WORD_LEN_TWO_TYPOS,
+};
 use meilisearch_types::milli::score_details::{ScoreDetails, ScoringStrategy};
 use meilisearch_types::milli::vector::Embedder;
-use meilisearch_types::milli::{FacetValueHit, OrderBy, SearchForFacetValues, TimeBudget};
-use meilisearch_types::settings::DEFAULT_PAGINATION_MAX_TOTAL_HITS;
+use meilisearch_types::milli::{
+    FacetValueHit, FacetValueHits, OrderBy, SearchForFacetValues, TimeBudget,
+    DEFAULT_APPROXIMATION_THRESHOLD,
+};
+use meilisearch_types::settings::{
+    MinWordSizeTyposSetting, RankingRuleView, DEFAULT_PAGINATION_MAX_TOTAL_HITS,
+};
 use meilisearch_types::{milli, Document};
+use milli::facet::{format_facet_date, parse_facet_date, FacetTypeHint, FacetValue};
 use milli::tokenizer::TokenizerBuilder;
 use milli::{
-    AscDesc, FieldId, FieldsIdsMap, Filter, FormatOptions, Index, MatchBounds, MatcherBuilder,
-    SortError, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
+    AscDesc, BucketStep, DocumentId, FieldId, FieldsIdsMap, Filter, FormatOptions, Index,
+    MatchBounds, MatcherBuilder, QueryGraphDescription, QueryToken, RankingRuleBucketStats,
+    SearchMetrics, SkippedRankingRule, SortError, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
 };
+use ordered_float::OrderedFloat;
 use regex::Regex;
+use roaring::RoaringBitmap;
 use serde::Serialize;
 use serde_json::{json, Value};
+use tokio::sync::{mpsc, oneshot};
 
 use crate::error::MeilisearchHttpError;
+use crate::metrics::{
+    MEILISEARCH_CANCELED_SEARCH_REQUESTS, MEILISEARCH_QUERY_EMBEDDING_CACHE_HITS,
+    MEILISEARCH_QUERY_EMBEDDING_CACHE_MISSES,
+};
 
 type MatchesPosition = BTreeMap<String, Vec<MatchBounds>>;
 
@@ -39,6 +64,9 @@ pub const DEFAULT_HIGHLIGHT_PRE_TAG: fn() -> String = || "<em>".to_string();
 pub const DEFAULT_HIGHLIGHT_POST_TAG: fn() -> String = || "</em>".to_string();
 pub const DEFAULT_SEMANTIC_RATIO: fn() -> SemanticRatio = || SemanticRatio(0.5);
 
+/// The maximum number of synonyms, summed over all the words, given with a search request.
+pub const MAX_SEARCH_SYNONYMS: usize = 100;
+
 #[derive(Debug, Clone, Default, PartialEq, Deserr)]
 #[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
 pub struct SearchQuery {
@@ -56,6 +84,8 @@ pub struct SearchQuery {
     pub page: Option<usize>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHitsPerPage>)]
     pub hits_per_page: Option<usize>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSearchAfter>)]
+    pub search_after: Option<Value>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToRetrieve>)]
     pub attributes_to_retrieve: Option<BTreeSet<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToCrop>)]
@@ -70,22 +100,80 @@ pub struct SearchQuery {
     pub show_ranking_score: bool,
     #[deserr(default, error = DeserrJsonError<InvalidSearchShowRankingScoreDetails>, default)]
     pub show_ranking_score_details: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRankingScoreThreshold>)]
+    pub ranking_score_threshold: Option<RankingScoreThreshold>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchShowRankingBucketPath>, default)]
+    pub show_ranking_bucket_path: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchShowProcessingBreakdown>, default)]
+    pub show_processing_breakdown: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchShowQueryTokens>, default)]
+    pub show_query_tokens: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchShowSearchMetrics>, default)]
+    pub show_search_metrics: bool,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFilter>)]
     pub filter: Option<Value>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchSort>)]
     pub sort: Option<Vec<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFacets>)]
     pub facets: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetDistributionMode>)]
+    pub facet_distribution_mode: Option<FacetDistributionMode>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetApproximationThreshold>)]
+    pub facet_approximation_threshold: Option<u64>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPreTag>, default = DEFAULT_HIGHLIGHT_PRE_TAG())]
     pub highlight_pre_tag: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPostTag>, default = DEFAULT_HIGHLIGHT_POST_TAG())]
     pub highlight_post_tag: String,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightTypoPreTag>)]
+    pub highlight_typo_pre_tag: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightTypoPostTag>)]
+    pub highlight_typo_post_tag: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchCropMarker>, default = DEFAULT_CROP_MARKER())]
     pub crop_marker: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchMatchingStrategy>, default)]
     pub matching_strategy: MatchingStrategy,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchTieBreak>, default)]
+    pub tie_break: TieBreak,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchDistinct>)]
+    pub distinct: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToSearchOn>, default)]
     pub attributes_to_search_on: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchPreset>)]
+    pub preset: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSynonyms>)]
+    pub synonyms: Option<BTreeMap<String, Vec<String>>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsTypoTolerance>)]
+    pub typo_tolerance: Option<SearchTypoTolerance>,
+    /// The ranking rules used instead of the ones of the index, behind the `rankingRulesOverride`
+    /// experimental feature.
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRankingRules>)]
+    pub ranking_rules_override: Option<Vec<RankingRuleView>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchDebug>)]
+    pub debug: Option<SearchDebug>,
+}
+
+/// The debug artifacts of the search to return, only allowed to the keys with the `search.debug` action.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidSearchDebug>, rename_all = camelCase, deny_unknown_fields)]
+pub struct SearchDebug {
+    #[deserr(default, error = DeserrJsonError<InvalidSearchDebug>, default)]
+    pub query_graph: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchDebug>, default)]
+    pub bucket_stats: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchDebug>, default)]
+    pub dump_search_state: bool,
+}
+
+/// Overrides the typo tolerance of the index for a single search.
+///
+/// It is validated like the `typoTolerance` setting, and merged over the settings of the index.
+#[derive(Debug, Clone, Default, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError<InvalidSettingsTypoTolerance>, rename_all = camelCase, deny_unknown_fields)]
+pub struct SearchTypoTolerance {
+    #[deserr(default)]
+    pub enabled: Option<bool>,
+    #[deserr(default)]
+    pub min_word_size_for_typos: Option<MinWordSizeTyposSetting>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Deserr)]
@@ -99,8 +187,17 @@ pub struct HybridQuery {
 
 pub enum SearchKind {
     KeywordOnly,
-    SemanticOnly { embedder_name: String, embedder: Arc<Embedder> },
-    Hybrid { embedder_name: String, embedder: Arc<Embedder>, semantic_ratio: f32 },
+    SemanticOnly {
+        embedder_name: String,
+        embedder: Arc<Embedder>,
+        query_embeddings: QueryEmbeddingCache,
+    },
+    Hybrid {
+        embedder_name: String,
+        embedder: Arc<Embedder>,
+        query_embeddings: QueryEmbeddingCache,
+        semantic_ratio: f32,
+    },
 }
 impl SearchKind {
     pub(crate) fn semantic(
@@ -109,9 +206,9 @@ impl SearchKind {
         embedder_name: Option<&str>,
         vector_len: Option<usize>,
     ) -> Result<Self, ResponseError> {
-        let (embedder_name, embedder) =
+        let (embedder_name, embedder, query_embeddings) =
             Self::embedder(index_scheduler, index, embedder_name, vector_len)?;
-        Ok(Self::SemanticOnly { embedder_name, embedder })
+        Ok(Self::SemanticOnly { embedder_name, embedder, query_embeddings })
     }
 
     pub(crate) fn hybrid(
@@ -121,9 +218,9 @@ impl SearchKind {
         semantic_ratio: f32,
         vector_len: Option<usize>,
     ) -> Result<Self, ResponseError> {
-        let (embedder_name, embedder) =
+        let (embedder_name, embedder, query_embeddings) =
             Self::embedder(index_scheduler, index, embedder_name, vector_len)?;
-        Ok(Self::Hybrid { embedder_name, embedder, semantic_ratio })
+        Ok(Self::Hybrid { embedder_name, embedder, query_embeddings, semantic_ratio })
     }
 
     fn embedder(
@@ -131,9 +228,9 @@ impl SearchKind {
         index: &Index,
         embedder_name: Option<&str>,
         vector_len: Option<usize>,
-    ) -> Result<(String, Arc<Embedder>), ResponseError> {
+    ) -> Result<(String, Arc<Embedder>, QueryEmbeddingCache), ResponseError> {
         let embedder_configs = index.embedding_configs(&index.read_txn()?)?;
-        let embedders = index_scheduler.embedders(embedder_configs)?;
+        let embedders = index_scheduler.embedders(embedder_configs.clone())?;
 
         let embedder_name = embedder_name.unwrap_or_else(|| embedders.get_default_embedder_name());
 
@@ -156,8 +253,29 @@ impl SearchKind {
             }
         }
 
-        Ok((embedder_name.to_owned(), embedder))
+        let query_embeddings = embedder_configs
+            .iter()
+            .find(|(name, _)| name == embedder_name)
+            .map(|(_, config)| index_scheduler.query_embedding_cache(&config.embedder_options))
+            .expect("the embedder was found in the same configs");
+
+        Ok((embedder_name.to_owned(), embedder, query_embeddings))
+    }
+}
+
+/// Embeds the query text, going through the query embeddings cache and recording its hits and misses.
+fn embed_query(
+    query_embeddings: &QueryEmbeddingCache,
+    embedder: &Embedder,
+    text: &str,
+) -> Result<Vec<f32>, milli::vector::Error> {
+    let (embedding, hit) = query_embeddings.embed(embedder, text)?;
+    if hit {
+        MEILISEARCH_QUERY_EMBEDDING_CACHE_HITS.inc();
+    } else {
+        MEILISEARCH_QUERY_EMBEDDING_CACHE_MISSES.inc();
     }
+    Ok(embedding)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Deserr)]
@@ -192,6 +310,32 @@ impl std::ops::Deref for SemanticRatio {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Deserr)]
+#[deserr(try_from(f64) = TryFrom::try_from -> InvalidSearchRankingScoreThreshold)]
+pub struct RankingScoreThreshold(f64);
+
+impl std::convert::TryFrom<f64> for RankingScoreThreshold {
+    type Error = InvalidSearchRankingScoreThreshold;
+
+    fn try_from(f: f64) -> Result<Self, Self::Error> {
+        // the suggested "fix" is: `!(0.0..=1.0).contains(&f)`` which is allegedly less readable
+        #[allow(clippy::manual_range_contains)]
+        if f > 1.0 || f < 0.0 {
+            Err(InvalidSearchRankingScoreThreshold)
+        } else {
+            Ok(RankingScoreThreshold(f))
+        }
+    }
+}
+
+impl std::ops::Deref for RankingScoreThreshold {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 impl SearchQuery {
     pub fn is_finite_pagination(&self) -> bool {
         self.page.or(self.hits_per_page).is_some()
@@ -213,14 +357,16 @@ pub struct SearchQueryWithIndex {
     pub vector: Option<Vec<f32>>,
     #[deserr(default, error = DeserrJsonError<InvalidHybridQuery>)]
     pub hybrid: Option<HybridQuery>,
-    #[deserr(default = DEFAULT_SEARCH_OFFSET(), error = DeserrJsonError<InvalidSearchOffset>)]
-    pub offset: usize,
-    #[deserr(default = DEFAULT_SEARCH_LIMIT(), error = DeserrJsonError<InvalidSearchLimit>)]
-    pub limit: usize,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchOffset>)]
+    pub offset: Option<usize>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchLimit>)]
+    pub limit: Option<usize>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchPage>)]
     pub page: Option<usize>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHitsPerPage>)]
     pub hits_per_page: Option<usize>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSearchAfter>)]
+    pub search_after: Option<Value>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToRetrieve>)]
     pub attributes_to_retrieve: Option<BTreeSet<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToCrop>)]
@@ -233,6 +379,16 @@ pub struct SearchQueryWithIndex {
     pub show_ranking_score: bool,
     #[deserr(default, error = DeserrJsonError<InvalidSearchShowRankingScoreDetails>, default)]
     pub show_ranking_score_details: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRankingScoreThreshold>)]
+    pub ranking_score_threshold: Option<RankingScoreThreshold>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchShowRankingBucketPath>, default)]
+    pub show_ranking_bucket_path: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchShowProcessingBreakdown>, default)]
+    pub show_processing_breakdown: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchShowQueryTokens>, default)]
+    pub show_query_tokens: bool,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchShowSearchMetrics>, default)]
+    pub show_search_metrics: bool,
     #[deserr(default, error = DeserrJsonError<InvalidSearchShowMatchesPosition>, default)]
     pub show_matches_position: bool,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFilter>)]
@@ -241,16 +397,40 @@ pub struct SearchQueryWithIndex {
     pub sort: Option<Vec<String>>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchFacets>)]
     pub facets: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetDistributionMode>)]
+    pub facet_distribution_mode: Option<FacetDistributionMode>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchFacetApproximationThreshold>)]
+    pub facet_approximation_threshold: Option<u64>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPreTag>, default = DEFAULT_HIGHLIGHT_PRE_TAG())]
     pub highlight_pre_tag: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightPostTag>, default = DEFAULT_HIGHLIGHT_POST_TAG())]
     pub highlight_post_tag: String,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightTypoPreTag>)]
+    pub highlight_typo_pre_tag: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchHighlightTypoPostTag>)]
+    pub highlight_typo_post_tag: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchCropMarker>, default = DEFAULT_CROP_MARKER())]
     pub crop_marker: String,
     #[deserr(default, error = DeserrJsonError<InvalidSearchMatchingStrategy>, default)]
     pub matching_strategy: MatchingStrategy,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchTieBreak>, default)]
+    pub tie_break: TieBreak,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchDistinct>)]
+    pub distinct: Option<String>,
     #[deserr(default, error = DeserrJsonError<InvalidSearchAttributesToSearchOn>, default)]
     pub attributes_to_search_on: Option<Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchPreset>)]
+    pub preset: Option<String>,
+    #[deserr(default, error = DeserrJsonError<InvalidSearchSynonyms>)]
+    pub synonyms: Option<BTreeMap<String, Vec<String>>>,
+    #[deserr(default, error = DeserrJsonError<InvalidSettingsTypoTolerance>)]
+    pub typo_tolerance: Option<SearchTypoTolerance>,
+    /// The ranking rules used instead of the ones of the index, behind the `rankingRulesOverride`
+    /// experimental feature.
+    #[deserr(default, error = DeserrJsonError<InvalidSearchRankingRules>)]
+    pub ranking_rules_override: Option<Vec<RankingRuleView>>,
+    #[deserr(default, error = DeserrJsonError<InvalidMultiSearchFederationOptions>)]
+    pub federation_options: Option<FederationOptions>,
 }
 
 impl SearchQueryWithIndex {
@@ -263,48 +443,84 @@ impl SearchQueryWithIndex {
             limit,
             page,
             hits_per_page,
+            search_after,
             attributes_to_retrieve,
             attributes_to_crop,
             crop_length,
             attributes_to_highlight,
             show_ranking_score,
             show_ranking_score_details,
+            ranking_score_threshold,
+            show_ranking_bucket_path,
+            show_processing_breakdown,
+            show_query_tokens,
+            show_search_metrics,
             show_matches_position,
             filter,
             sort,
             facets,
+            facet_distribution_mode,
+            facet_approximation_threshold,
             highlight_pre_tag,
             highlight_post_tag,
+            highlight_typo_pre_tag,
+            highlight_typo_post_tag,
             crop_marker,
             matching_strategy,
+            tie_break,
+            distinct,
             attributes_to_search_on,
+            preset,
             hybrid,
+            synonyms,
+            typo_tolerance,
+            ranking_rules_override,
+            // the options are only used to merge the results of a federated search
+            federation_options: _,
         } = self;
         (
             index_uid,
             SearchQuery {
                 q,
                 vector,
-                offset,
-                limit,
+                offset: offset.unwrap_or_else(DEFAULT_SEARCH_OFFSET),
+                limit: limit.unwrap_or_else(DEFAULT_SEARCH_LIMIT),
                 page,
                 hits_per_page,
+                search_after,
                 attributes_to_retrieve,
                 attributes_to_crop,
                 crop_length,
                 attributes_to_highlight,
                 show_ranking_score,
                 show_ranking_score_details,
+                ranking_score_threshold,
+                show_ranking_bucket_path,
+                show_processing_breakdown,
+                show_query_tokens,
+                show_search_metrics,
                 show_matches_position,
                 filter,
                 sort,
                 facets,
+                facet_distribution_mode,
+                facet_approximation_threshold,
                 highlight_pre_tag,
                 highlight_post_tag,
+                highlight_typo_pre_tag,
+                highlight_typo_post_tag,
                 crop_marker,
                 matching_strategy,
+                tie_break,
+                distinct,
                 attributes_to_search_on,
                 hybrid,
+                synonyms,
+                typo_tolerance,
+                ranking_rules_override,
+                preset,
+                // the debug artifacts are only available on the search route of an index
+                debug: None,
                 // do not use ..Default::default() here,
                 // rather add any missing field from `SearchQuery` to `SearchQueryWithIndex`
             },
@@ -312,6 +528,68 @@ impl SearchQueryWithIndex {
     }
 }
 
+/// Merges the results of the queries of a multi-search into a single list of hits.
+#[derive(Debug, Clone, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct Federation {
+    #[deserr(default = DEFAULT_SEARCH_LIMIT(), error = DeserrJsonError<InvalidSearchLimit>)]
+    pub limit: usize,
+    #[deserr(default = DEFAULT_SEARCH_OFFSET(), error = DeserrJsonError<InvalidSearchOffset>)]
+    pub offset: usize,
+    /// The facets to compute for each index, on all the documents of the index matched by the queries.
+    #[deserr(default, error = DeserrJsonError<InvalidMultiSearchFacetsByIndex>)]
+    pub facets_by_index: BTreeMap<String, Vec<String>>,
+    #[deserr(default, error = DeserrJsonError<InvalidMultiSearchMergeFacets>)]
+    pub merge_facets: Option<MergeFacets>,
+}
+
+/// Merges the facets of the indexes of a federated search by name.
+#[derive(Debug, Clone, Copy, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct MergeFacets {
+    #[deserr(default = DEFAULT_VALUES_PER_FACET, error = DeserrJsonError<InvalidMultiSearchMergeFacets>)]
+    pub max_values_per_facet: usize,
+}
+
+/// How the hits of a query are merged into the results of a federated search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserr)]
+#[deserr(error = DeserrJsonError, rename_all = camelCase, deny_unknown_fields)]
+pub struct FederationOptions {
+    #[deserr(default, error = DeserrJsonError<InvalidMultiSearchWeight>)]
+    pub weight: Weight,
+}
+
+/// The factor applied to the ranking scores of the hits of a query before they are merged.
+#[derive(Debug, Clone, Copy, PartialEq, Deserr)]
+#[deserr(try_from(f64) = TryFrom::try_from -> InvalidMultiSearchWeight)]
+pub struct Weight(f64);
+
+impl Default for Weight {
+    fn default() -> Self {
+        Weight(1.0)
+    }
+}
+
+impl std::convert::TryFrom<f64> for Weight {
+    type Error = InvalidMultiSearchWeight;
+
+    fn try_from(f: f64) -> Result<Self, Self::Error> {
+        if f.is_finite() && f >= 0.0 {
+            Ok(Weight(f))
+        } else {
+            Err(InvalidMultiSearchWeight)
+        }
+    }
+}
+
+impl std::ops::Deref for Weight {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Deserr)]
 #[deserr(rename_all = camelCase)]
 pub enum MatchingStrategy {
@@ -319,6 +597,8 @@ pub enum MatchingStrategy {
     Last,
     /// All query words are mandatory
     All,
+    /// Remove query words from the one matching the fewest documents to the one matching the most
+    Frequency,
 }
 
 impl Default for MatchingStrategy {
@@ -332,10 +612,52 @@ impl From<MatchingStrategy> for TermsMatchingStrategy {
         match other {
             MatchingStrategy::Last => Self::Last,
             MatchingStrategy::All => Self::All,
+            MatchingStrategy::Frequency => Self::Frequency,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserr)]
+#[deserr(rename_all = camelCase)]
+pub enum TieBreak {
+    /// The documents ranked the same are returned in the order they were indexed in.
+    #[default]
+    InternalId,
+    /// The documents ranked the same are returned in the order of their document ids.
+    DocumentId,
+}
+
+impl From<TieBreak> for milli::TieBreak {
+    fn from(other: TieBreak) -> Self {
+        match other {
+            TieBreak::InternalId => Self::InternalId,
+            TieBreak::DocumentId => Self::DocumentId,
         }
     }
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserr)]
+#[deserr(rename_all = camelCase)]
+pub enum FacetDistributionMode {
+    /// The facet values of all the candidates are counted.
+    #[default]
+    Exact,
+    /// When there are too many candidates, the facet values are counted on a sample of them
+    /// and the counts are extrapolated to all the candidates.
+    Approximate,
+    /// When there are too many candidates, the facet distribution is not computed.
+    SkipOnLargeUniverse,
+}
+
+/// The way the facet distribution was actually computed, returned in the search response.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AppliedFacetDistributionMode {
+    Exact,
+    Approximate,
+    Skipped,
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, Deserr)]
 #[deserr(rename_all = camelCase)]
 pub enum FacetValuesSort {
@@ -368,6 +690,9 @@ pub struct SearchHit {
     pub ranking_score: Option<f64>,
     #[serde(rename = "_rankingScoreDetails", skip_serializing_if = "Option::is_none")]
     pub ranking_score_details: Option<serde_json::Map<String, serde_json::Value>>,
+    /// The internal id of the document, used to deduplicate the hits of a federated search.
+    #[serde(skip)]
+    pub docid: DocumentId,
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
@@ -378,19 +703,90 @@ pub struct SearchResult {
     pub processing_time_ms: u128,
     #[serde(flatten)]
     pub hits_info: HitsInfo,
+    /// `Some(None)` when the facet distribution was skipped, and returned as `null`.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub facet_distribution: Option<BTreeMap<String, IndexMap<String, u64>>>,
+    pub facet_distribution: Option<Option<BTreeMap<String, FacetValuesDistribution>>>,
+    /// `Some(None)` when the facet distribution was skipped, and returned as `null`.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub facet_stats: Option<BTreeMap<String, FacetStats>>,
+    pub facet_stats: Option<Option<BTreeMap<String, FacetStats>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_distribution_mode: Option<AppliedFacetDistributionMode>,
+    /// The documents matched by the query, used to compute the facets of a federated search.
+    #[serde(skip)]
+    pub candidates: RoaringBitmap,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub semantic_hit_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub processing_breakdown: Option<ProcessingBreakdown>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_tokens: Option<Vec<QueryToken>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<SearchMetrics>,
+    /// The ranking rules that had no effect on the search, only reported with the ranking score details.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped_ranking_rules: Option<Vec<SkippedRankingRuleView>>,
+    /// Set when the search cutoff was reached and the hits were not entirely sorted.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub degraded: bool,
+    /// Set when the query has too many words and only its first ones were searched.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub query_truncated: bool,
+    /// The debug artifacts asked with the `debug` parameter.
+    #[serde(rename = "_debug", skip_serializing_if = "Option::is_none")]
+    pub debug: Option<SearchDebugView>,
 
     // These fields are only used for analytics purposes
     #[serde(skip)]
-    pub degraded: bool,
-    #[serde(skip)]
     pub used_negative_operator: bool,
+    /// The trace of the bucket sort, only recorded when it is written to the search trace directory.
+    #[serde(skip)]
+    pub trace: Option<milli::SearchTrace>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchDebugView {
+    /// Only returned for the searches with a query.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_graph: Option<QueryGraphDescription>,
+    /// The sizes of the first buckets of every ranking rule, in the order of the ranking rules.
+    /// Only returned when the documents were sorted with the ranking rules.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket_stats: Option<Vec<RankingRuleBucketStats>>,
+    /// The query graph and the trace of the bucket sort as a gzipped JSON, encoded in base64,
+    /// to be attached to the bug reports.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_state: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedRankingRuleView {
+    pub ranking_rule: String,
+    pub reason: String,
+}
+
+impl From<SkippedRankingRule> for SkippedRankingRuleView {
+    fn from(SkippedRankingRule { ranking_rule, reason }: SkippedRankingRule) -> Self {
+        Self { ranking_rule, reason: reason.to_string() }
+    }
+}
+
+/// The time spent in each step of a search, in milliseconds, and how its hits were counted.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessingBreakdown {
+    /// Whether the number of hits returned is the exact number of matching documents.
+    pub exhaustive_nb_hits: bool,
+    pub query_parsing: f64,
+    /// Only reported for the semantic and hybrid searches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<f64>,
+    pub universe: f64,
+    pub bucket_sort: f64,
+    pub facet_distribution: f64,
+    pub formatting: f64,
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
@@ -401,6 +797,174 @@ pub struct SearchResultWithIndex {
     pub result: SearchResult,
 }
 
+/// The hits of the queries of a federated search, merged into a single list.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FederatedSearchResult {
+    pub hits: Vec<FederatedSearchHit>,
+    pub processing_time_ms: u128,
+    /// The estimated total is the sum of the ones of the queries, in which the documents
+    /// matched by several queries are counted several times.
+    #[serde(flatten)]
+    pub hits_info: HitsInfo,
+    /// Set when the search cutoff was reached by any of the queries.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub degraded: bool,
+    /// The facets of each index of `federation.facetsByIndex`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub facets_by_index: BTreeMap<String, ComputedFacets>,
+    /// The facets of all the indexes merged by name, only returned with `federation.mergeFacets`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_distribution: Option<BTreeMap<String, FacetValuesDistribution>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facet_stats: Option<BTreeMap<String, FacetStats>>,
+    /// The merged facets whose values don't have the same type in all the indexes.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub facet_warnings: BTreeMap<String, String>,
+}
+
+/// The facet distribution and stats computed on the documents matched by a search.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ComputedFacets {
+    pub distribution: BTreeMap<String, FacetValuesDistribution>,
+    pub stats: BTreeMap<String, FacetStats>,
+}
+
+/// The facets of the indexes of a federated search merged by name.
+pub struct MergedFacets {
+    pub distribution: BTreeMap<String, FacetValuesDistribution>,
+    pub stats: BTreeMap<String, FacetStats>,
+    pub warnings: BTreeMap<String, String>,
+}
+
+impl MergedFacets {
+    /// Sums the counts of the values of the facets with the same name, and keeps the smallest
+    /// and biggest numbers of their stats.
+    ///
+    /// The merged numbers are sorted by value, before the strings sorted in lexicographic order,
+    /// up to `max_values_per_facet` values. A facet only containing strings in an index and only
+    /// numbers in another one is still merged, with a warning.
+    pub fn new(
+        facets_by_index: &BTreeMap<String, ComputedFacets>,
+        max_values_per_facet: usize,
+    ) -> Self {
+        let mut strings: BTreeMap<&str, BTreeMap<&str, u64>> = BTreeMap::new();
+        let mut numbers: BTreeMap<&str, BTreeMap<OrderedFloat<f64>, u64>> = BTreeMap::new();
+        // the indexes in which each facet only contains strings, and only contains numbers
+        let mut types: BTreeMap<&str, (Vec<&str>, Vec<&str>)> = BTreeMap::new();
+        let mut merged_stats: BTreeMap<&str, (f64, f64, bool)> = BTreeMap::new();
+
+        for (index_uid, ComputedFacets { distribution, stats }) in facets_by_index {
+            for (name, values) in distribution {
+                let (index_strings, index_numbers) = match values {
+                    FacetValuesDistribution::Strings(index_strings) => {
+                        types.entry(name.as_str()).or_default().0.push(index_uid);
+                        (Some(index_strings), None)
+                    }
+                    FacetValuesDistribution::Numbers(index_numbers) => {
+                        types.entry(name.as_str()).or_default().1.push(index_uid);
+                        (None, Some(index_numbers))
+                    }
+                    FacetValuesDistribution::Mixed { strings, numbers } => {
+                        (Some(strings), Some(numbers))
+                    }
+                };
+                let strings = strings.entry(name.as_str()).or_default();
+                for (value, count) in index_strings.into_iter().flatten() {
+                    *strings.entry(value.as_str()).or_default() += count;
+                }
+                let numbers = numbers.entry(name.as_str()).or_default();
+                for FacetNumberCount { value, count } in index_numbers.into_iter().flatten() {
+                    *numbers.entry(OrderedFloat(*value)).or_default() += count;
+                }
+            }
+
+            for (name, stats) in stats {
+                let (min, max, dates) = match stats {
+                    FacetStats::Numbers { min, max } => (*min, *max, false),
+                    // the dates were formatted from timestamps, they can always be parsed back
+                    FacetStats::Dates { min, max } => (
+                        parse_facet_date(min).unwrap_or(f64::NAN),
+                        parse_facet_date(max).unwrap_or(f64::NAN),
+                        true,
+                    ),
+                };
+                merged_stats
+                    .entry(name.as_str())
+                    .and_modify(|(merged_min, merged_max, merged_dates)| {
+                        *merged_min = merged_min.min(min);
+                        *merged_max = merged_max.max(max);
+                        *merged_dates &= dates;
+                    })
+                    .or_insert((min, max, dates));
+            }
+        }
+
+        let distribution = strings
+            .into_iter()
+            .zip(numbers)
+            .map(|((name, strings), (_, numbers))| {
+                let numbers: Vec<_> = numbers
+                    .into_iter()
+                    .take(max_values_per_facet)
+                    .map(|(value, count)| FacetNumberCount { value: value.0, count })
+                    .collect();
+                let strings: IndexMap<_, _> = strings
+                    .into_iter()
+                    .take(max_values_per_facet - numbers.len())
+                    .map(|(value, count)| (value.to_string(), count))
+                    .collect();
+                let values = match (strings.is_empty(), numbers.is_empty()) {
+                    (_, true) => FacetValuesDistribution::Strings(strings),
+                    (true, false) => FacetValuesDistribution::Numbers(numbers),
+                    (false, false) => FacetValuesDistribution::Mixed { strings, numbers },
+                };
+                (name.to_string(), values)
+            })
+            .collect();
+
+        let stats = merged_stats
+            .into_iter()
+            .map(|(name, (min, max, dates))| (name.to_string(), FacetStats::new(min, max, dates)))
+            .collect();
+
+        let warnings = types
+            .into_iter()
+            .filter(|(_, (strings, numbers))| !strings.is_empty() && !numbers.is_empty())
+            .map(|(name, (strings, numbers))| {
+                let list = |uids: Vec<&str>| {
+                    uids.iter().map(|uid| format!("`{uid}`")).collect::<Vec<_>>().join(", ")
+                };
+                let message = format!(
+                    "The facet `{name}` only contains strings in {} and only numbers in {}, its values have been merged anyway.",
+                    list(strings),
+                    list(numbers),
+                );
+                (name.to_string(), message)
+            })
+            .collect();
+
+        MergedFacets { distribution, stats, warnings }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FederatedSearchHit {
+    #[serde(flatten)]
+    pub hit: SearchHit,
+    #[serde(rename = "_federation")]
+    pub federation: HitFederation,
+}
+
+/// Where a hit of a federated search comes from, and the score it was merged with.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HitFederation {
+    pub index_uid: String,
+    pub queries_position: usize,
+    pub weighted_ranking_score: f64,
+}
+
 #[derive(Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum HitsInfo {
@@ -410,10 +974,95 @@ pub enum HitsInfo {
     OffsetLimit { limit: usize, offset: usize, estimated_total_hits: usize },
 }
 
+/// The smallest and biggest facet numbers of an attribute.
+///
+/// The facet numbers of the attributes hinted as dates are timestamps, they are returned as
+/// the RFC3339 datetimes they have been parsed from.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum FacetStats {
+    Numbers {
+        #[serde(serialize_with = "serialize_facet_number")]
+        min: f64,
+        #[serde(serialize_with = "serialize_facet_number")]
+        max: f64,
+    },
+    Dates {
+        min: String,
+        max: String,
+    },
+}
+
+impl FacetStats {
+    fn new(min: f64, max: f64, date_attribute: bool) -> Self {
+        // the numbers of a date attribute that can't be formatted can't be timestamps of dates
+        let dates =
+            date_attribute.then(|| format_facet_date(min).zip(format_facet_date(max))).flatten();
+        match dates {
+            Some((min, max)) => FacetStats::Dates { min, max },
+            None => FacetStats::Numbers { min, max },
+        }
+    }
+}
+
+/// The number of documents of each value of a facet.
+///
+/// JSON object keys can only be strings, so the numbers are returned in an array along with
+/// their count, with the same JSON type they must be written with in a filter:
+/// - the facets only containing strings are returned as a `{ "red": 2 }` object, like before,
+/// - the facets only containing numbers are returned as a `[{ "value": 10, "count": 2 }]` array,
+/// - the facets containing both are returned as a `{ "strings": {..}, "numbers": [..] }` object.
+///
+/// Compatibility note: the numbers used to be returned as string keys, like the strings.
 #[derive(Serialize, Debug, Clone, PartialEq)]
-pub struct FacetStats {
-    pub min: f64,
-    pub max: f64,
+#[serde(untagged)]
+pub enum FacetValuesDistribution {
+    Strings(IndexMap<String, u64>),
+    Numbers(Vec<FacetNumberCount>),
+    Mixed { strings: IndexMap<String, u64>, numbers: Vec<FacetNumberCount> },
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FacetNumberCount {
+    #[serde(serialize_with = "serialize_facet_number")]
+    pub value: f64,
+    pub count: u64,
+}
+
+impl FacetValuesDistribution {
+    fn new(values: IndexMap<FacetValue, u64>) -> Self {
+        let mut strings = IndexMap::new();
+        let mut numbers = Vec::new();
+        for (value, count) in values {
+            match value {
+                FacetValue::String(string) => {
+                    strings.insert(string, count);
+                }
+                FacetValue::Number(number) => {
+                    numbers.push(FacetNumberCount { value: number.0, count })
+                }
+            }
+        }
+
+        match (strings.is_empty(), numbers.is_empty()) {
+            (_, true) => FacetValuesDistribution::Strings(strings),
+            (true, false) => FacetValuesDistribution::Numbers(numbers),
+            (false, false) => FacetValuesDistribution::Mixed { strings, numbers },
+        }
+    }
+}
+
+/// Serializes the whole numbers as JSON integers, `10.0` is returned as `10`.
+fn serialize_facet_number<S: serde::Serializer>(
+    number: &f64,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    // above 2^53 the integers can't all be represented and are kept as floats
+    if number.fract() == 0.0 && number.abs() < 9_007_199_254_740_992.0 {
+        serializer.serialize_i64(*number as i64)
+    } else {
+        serializer.serialize_f64(*number)
+    }
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
@@ -421,27 +1070,207 @@ pub struct FacetStats {
 pub struct FacetSearchResult {
     pub facet_hits: Vec<FacetValueHit>,
     pub facet_query: Option<String>,
+    /// Whether too many facet values were examined, some matching values may be missing.
+    pub truncated: bool,
     pub processing_time_ms: u128,
 }
 
 /// Incorporate search rules in search query
-pub fn add_search_rules(query: &mut SearchQuery, rules: IndexSearchRules) {
-    query.filter = match (query.filter.take(), rules.filter) {
-        (None, rules_filter) => rules_filter,
-        (filter, None) => filter,
-        (Some(filter), Some(rules_filter)) => {
-            let filter = match filter {
-                Value::Array(filter) => filter,
-                filter => vec![filter],
-            };
-            let rules_filter = match rules_filter {
-                Value::Array(rules_filter) => rules_filter,
-                rules_filter => vec![rules_filter],
-            };
+pub fn add_search_rules(
+    query: &mut SearchQuery,
+    index_uid: &str,
+    rules: IndexSearchRules,
+) -> Result<(), MeilisearchHttpError> {
+    query.filter = and_filters(query.filter.take(), search_rules_filter(index_uid, rules)?);
+
+    Ok(())
+}
 
-            Some(Value::Array([filter, rules_filter].concat()))
+/// The filter restricting the documents of the index to the ones allowed by the search rules.
+///
+/// The filter of the rules is checked beforehand, so that an invalid filter in a tenant token
+/// is not reported as an error of the filter of the query.
+pub fn search_rules_filter(
+    index_uid: &str,
+    rules: IndexSearchRules,
+) -> Result<Option<Value>, MeilisearchHttpError> {
+    if let Some(rules_filter) = &rules.filter {
+        if let Err(error) = parse_filter(rules_filter) {
+            return Err(MeilisearchHttpError::InvalidSearchRulesFilter(
+                index_uid.to_owned(),
+                error.to_string(),
+            ));
         }
     }
+
+    Ok(rules.filter)
+}
+
+/// Checks that the `searchDefaults` setting of an index only contains valid search parameters,
+/// following the exact same rules as the body of a search request.
+pub fn validate_search_defaults(defaults: &Value) -> Result<SearchQuery, ResponseError> {
+    validate_stored_search_parameters(
+        defaults,
+        ValuePointerRef::Origin.push_key("searchDefaults"),
+        ".searchDefaults",
+        Code::InvalidSettingsSearchDefaults,
+    )
+}
+
+/// Checks that the search preset `name` only contains valid search parameters,
+/// following the exact same rules as the body of a search request.
+pub fn validate_search_preset(name: &str, preset: &Value) -> Result<SearchQuery, ResponseError> {
+    let presets = ValuePointerRef::Origin.push_key("presets");
+    validate_stored_search_parameters(
+        preset,
+        presets.push_key(name),
+        &format!(".presets.{name}"),
+        Code::InvalidSettingsPresets,
+    )
+}
+
+fn validate_stored_search_parameters(
+    parameters: &Value,
+    location: ValuePointerRef,
+    location_name: &str,
+    code: Code,
+) -> Result<SearchQuery, ResponseError> {
+    let query = <SearchQuery as Deserr<DeserrJsonError>>::deserialize_from_value(
+        parameters.clone().into_value(),
+        location,
+    )
+    .map_err(|error| match error.code {
+        // errors that are not specific to a search parameter, e.g. an unknown parameter
+        Code::BadRequest => ResponseError::from_msg(error.msg, code),
+        _ => error.into(),
+    })?;
+
+    if query.preset.is_some() {
+        return Err(ResponseError::from_msg(
+            format!("Invalid value at `{location_name}.preset`: stored search parameters cannot reference a preset."),
+            code,
+        ));
+    }
+
+    Ok(query)
+}
+
+/// Fills the parameters that were not explicitly provided in a search request with the
+/// `searchDefaults` of the index, so that the parameters of the request always take precedence.
+///
+/// `is_provided` is called with the camelCase name of the parameters.
+pub fn apply_search_defaults(
+    query: &mut SearchQuery,
+    defaults: SearchQuery,
+    is_provided: impl Fn(&str) -> bool,
+) {
+    let SearchQuery {
+        q,
+        vector,
+        hybrid,
+        offset,
+        limit,
+        page,
+        hits_per_page,
+        // the documents of a preset can't be the same for all the pages
+        search_after: _,
+        attributes_to_retrieve,
+        attributes_to_crop,
+        crop_length,
+        attributes_to_highlight,
+        show_matches_position,
+        show_ranking_score,
+        show_ranking_score_details,
+        ranking_score_threshold,
+        show_ranking_bucket_path,
+        show_processing_breakdown,
+        show_query_tokens,
+        show_search_metrics,
+        filter,
+        sort,
+        facets,
+        facet_distribution_mode,
+        facet_approximation_threshold,
+        highlight_pre_tag,
+        highlight_post_tag,
+        highlight_typo_pre_tag,
+        highlight_typo_post_tag,
+        crop_marker,
+        matching_strategy,
+        tie_break,
+        distinct,
+        attributes_to_search_on,
+        preset: _,
+        synonyms,
+        typo_tolerance,
+        ranking_rules_override,
+        // the debug artifacts are only returned when the request itself asks for them
+        debug: _,
+    } = defaults;
+
+    macro_rules! apply {
+        ($($field:ident: $name:literal),* $(,)?) => {
+            $(if !is_provided($name) {
+                query.$field = $field;
+            })*
+        };
+    }
+
+    apply!(
+        q: "q",
+        vector: "vector",
+        attributes_to_retrieve: "attributesToRetrieve",
+        attributes_to_crop: "attributesToCrop",
+        crop_length: "cropLength",
+        attributes_to_highlight: "attributesToHighlight",
+        show_matches_position: "showMatchesPosition",
+        show_ranking_score: "showRankingScore",
+        show_ranking_score_details: "showRankingScoreDetails",
+        ranking_score_threshold: "rankingScoreThreshold",
+        show_ranking_bucket_path: "showRankingBucketPath",
+        show_processing_breakdown: "showProcessingBreakdown",
+        show_query_tokens: "showQueryTokens",
+        show_search_metrics: "showSearchMetrics",
+        filter: "filter",
+        sort: "sort",
+        facets: "facets",
+        facet_distribution_mode: "facetDistributionMode",
+        facet_approximation_threshold: "facetApproximationThreshold",
+        highlight_pre_tag: "highlightPreTag",
+        highlight_post_tag: "highlightPostTag",
+        highlight_typo_pre_tag: "highlightTypoPreTag",
+        highlight_typo_post_tag: "highlightTypoPostTag",
+        crop_marker: "cropMarker",
+        matching_strategy: "matchingStrategy",
+        tie_break: "tieBreak",
+        distinct: "distinct",
+        attributes_to_search_on: "attributesToSearchOn",
+        synonyms: "synonyms",
+        typo_tolerance: "typoTolerance",
+        ranking_rules_override: "rankingRulesOverride",
+    );
+
+    // The GET route splits the hybrid parameter in two.
+    if !["hybrid", "hybridEmbedder", "hybridSemanticRatio"].into_iter().any(&is_provided) {
+        query.hybrid = hybrid;
+    }
+
+    // Mixing a default pagination with the one of the request would be confusing,
+    // e.g. a default `hitsPerPage` would silently switch a request using `offset` to pages.
+    if !["offset", "limit", "page", "hitsPerPage"].into_iter().any(&is_provided) {
+        query.offset = offset;
+        query.limit = limit;
+        query.page = page;
+        query.hits_per_page = hits_per_page;
+    }
+}
+
+/// The maximum number of buckets of the ranking rules without a `maxBuckets` in the settings
+/// of their index, set from the options of the instance. `0` when they are not capped.
+static MAX_BUCKETS_PER_RULE: AtomicUsize = AtomicUsize::new(0);
+
+pub fn set_max_buckets_per_rule(max_buckets: Option<NonZeroUsize>) {
+    MAX_BUCKETS_PER_RULE.store(max_buckets.map_or(0, NonZeroUsize::get), Ordering::Relaxed);
 }
 
 fn prepare_search<'t>(
@@ -450,9 +1279,14 @@ fn prepare_search<'t>(
     query: &'t SearchQuery,
     search_kind: &SearchKind,
     time_budget: TimeBudget,
-) -> Result<(milli::Search<'t>, bool, usize, usize), MeilisearchHttpError> {
+    settings_overlay: Option<milli::SettingsOverlay>,
+) -> Result<(milli::Search<'t>, bool, usize, usize, Duration), MeilisearchHttpError> {
     let mut search = index.search(rtxn);
     search.time_budget(time_budget);
+    if let Some(max_buckets) = NonZeroUsize::new(MAX_BUCKETS_PER_RULE.load(Ordering::Relaxed)) {
+        search.max_buckets_per_rule(max_buckets.get());
+    }
+    let mut embedding_time = Duration::ZERO;
 
     match search_kind {
         SearchKind::KeywordOnly => {
@@ -460,23 +1294,36 @@ fn prepare_search<'t>(
                 search.query(q);
             }
         }
-        SearchKind::SemanticOnly { embedder_name, embedder } => {
+        SearchKind::SemanticOnly { embedder_name, embedder, query_embeddings } => {
             let vector = match query.vector.clone() {
                 Some(vector) => vector,
-                None => embedder
-                    .embed_one(query.q.clone().unwrap())
-                    .map_err(milli::vector::Error::from)
-                    .map_err(milli::Error::from)?,
+                None => {
+                    let before_embedding = Instant::now();
+                    let vector =
+                        embed_query(query_embeddings, embedder, query.q.as_deref().unwrap())
+                            .map_err(milli::Error::from)?;
+                    embedding_time = before_embedding.elapsed();
+                    vector
+                }
             };
 
             search.semantic(embedder_name.clone(), embedder.clone(), Some(vector));
         }
-        SearchKind::Hybrid { embedder_name, embedder, semantic_ratio: _ } => {
+        SearchKind::Hybrid { embedder_name, embedder, query_embeddings, semantic_ratio: _ } => {
+            let mut vector = query.vector.clone();
             if let Some(q) = &query.q {
                 search.query(q);
+                // Embedding the query up front lets the next identical queries skip the embedder,
+                // if it fails the hybrid search will try again and fall back to a keyword search.
+                if vector.is_none() {
+                    let before_embedding = Instant::now();
+                    vector = embed_query(query_embeddings, embedder, q)
+                        .map_err(|error| tracing::error!(error=%error, "Embedding failed"))
+                        .ok();
+                    embedding_time = before_embedding.elapsed();
+                }
             }
-            // will be embedded in hybrid search if necessary
-            search.semantic(embedder_name.clone(), embedder.clone(), query.vector.clone());
+            search.semantic(embedder_name.clone(), embedder.clone(), vector);
         }
     }
 
@@ -484,8 +1331,34 @@ fn prepare_search<'t>(
         search.searchable_attributes(searchable);
     }
 
+    if let Some(ref synonyms) = query.synonyms {
+        let count = synonyms.values().map(Vec::len).sum();
+        if count > MAX_SEARCH_SYNONYMS {
+            return Err(MeilisearchHttpError::TooManySearchSynonyms(count, MAX_SEARCH_SYNONYMS));
+        }
+        search.synonyms(synonyms);
+    }
+
+    let mut settings_overlay = settings_overlay.unwrap_or_default();
+    if let Some(ref typo_tolerance) = query.typo_tolerance {
+        apply_typo_tolerance(index, rtxn, typo_tolerance, &mut settings_overlay)?;
+    }
+    if let Some(ref ranking_rules) = query.ranking_rules_override {
+        apply_ranking_rules_override(index, rtxn, ranking_rules, &mut settings_overlay)?;
+    }
+    search.settings_overlay(settings_overlay);
+
     let is_finite_pagination = query.is_finite_pagination();
     search.terms_matching_strategy(query.matching_strategy.into());
+    search.tie_break(query.tie_break.into());
+
+    if let Some(ref distinct) = query.distinct {
+        search.distinct(distinct.clone());
+    }
+
+    if let Some(ranking_score_threshold) = query.ranking_score_threshold {
+        search.ranking_score_threshold(*ranking_score_threshold);
+    }
 
     let max_total_hits = index
         .pagination_max_total_hits(rtxn)
@@ -494,11 +1367,26 @@ fn prepare_search<'t>(
         .unwrap_or(DEFAULT_PAGINATION_MAX_TOTAL_HITS);
 
     search.exhaustive_number_hits(is_finite_pagination);
-    search.scoring_strategy(if query.show_ranking_score || query.show_ranking_score_details {
-        ScoringStrategy::Detailed
-    } else {
-        ScoringStrategy::Skip
-    });
+    search.scoring_strategy(
+        if query.show_ranking_score
+            || query.show_ranking_score_details
+            || query.show_ranking_bucket_path
+        {
+            ScoringStrategy::Detailed
+        } else {
+            ScoringStrategy::Skip
+        },
+    );
+    search.bucket_paths(query.show_ranking_bucket_path);
+    search.matched_derivations(
+        query.attributes_to_highlight.is_some()
+            || query.attributes_to_crop.is_some()
+            || query.show_matches_position,
+    );
+    search.debug_query_graph(
+        query.debug.map_or(false, |debug| debug.query_graph || debug.dump_search_state),
+    );
+    search.bucket_stats(query.debug.map_or(false, |debug| debug.bucket_stats));
 
     // compute the offset on the limit depending on the pagination mode.
     let (offset, limit) = if is_finite_pagination {
@@ -519,6 +1407,24 @@ fn prepare_search<'t>(
     search.offset(offset);
     search.limit(limit);
 
+    if let Some(ref search_after) = query.search_after {
+        let document_id = match search_after {
+            Value::String(document_id) => document_id.clone(),
+            Value::Number(number) if number.is_i64() || number.is_u64() => number.to_string(),
+            value => return Err(MeilisearchHttpError::InvalidSearchAfterType(value.clone())),
+        };
+        if is_finite_pagination {
+            return Err(MeilisearchHttpError::SearchAfterWith("`page` or `hitsPerPage`"));
+        }
+        if query.offset != 0 {
+            return Err(MeilisearchHttpError::SearchAfterWith("`offset`"));
+        }
+        if let SearchKind::Hybrid { .. } = search_kind {
+            return Err(MeilisearchHttpError::SearchAfterWith("a hybrid search"));
+        }
+        search.search_after(document_id);
+    }
+
     if let Some(ref filter) = query.filter {
         if let Some(facets) = parse_filter(filter)? {
             search.filter(facets);
@@ -536,23 +1442,245 @@ fn prepare_search<'t>(
         search.sort_criteria(sort);
     }
 
-    Ok((search, is_finite_pagination, max_total_hits, offset))
+    Ok((search, is_finite_pagination, max_total_hits, offset, embedding_time))
+}
+
+/// Merges the typo tolerance of the search request over the overlay, the settings of the index
+/// filling what is neither overridden by the request nor by the overlay.
+///
+/// The minimum word sizes are validated once merged, like the `typoTolerance` setting.
+fn apply_typo_tolerance(
+    index: &Index,
+    rtxn: &RoTxn,
+    typo_tolerance: &SearchTypoTolerance,
+    overlay: &mut milli::SettingsOverlay,
+) -> Result<(), MeilisearchHttpError> {
+    let SearchTypoTolerance { enabled, min_word_size_for_typos } = typo_tolerance;
+    if let Some(enabled) = enabled {
+        overlay.authorize_typos = Some(*enabled);
+    }
+    if let Some(MinWordSizeTyposSetting { one_typo, two_typos }) = min_word_size_for_typos {
+        if let Some(one_typo) = one_typo.clone().or_reset(DEFAULT_MIN_WORD_LEN_ONE_TYPO).set() {
+            overlay.min_word_len_one_typo = Some(one_typo);
+        }
+        if let Some(two_typos) = two_typos.clone().or_reset(DEFAULT_MIN_WORD_LEN_TWO_TYPOS).set() {
+            overlay.min_word_len_two_typos = Some(two_typos);
+        }
+    }
+
+    let one_typo = match overlay.min_word_len_one_typo {
+        Some(one_typo) => one_typo,
+        None => index.min_word_len_one_typo(rtxn)?,
+    };
+    let two_typos = match overlay.min_word_len_two_typos {
+        Some(two_typos) => two_typos,
+        None => index.min_word_len_two_typos(rtxn)?,
+    };
+    if one_typo > two_typos {
+        return Err(milli::Error::from(milli::UserError::InvalidMinTypoWordLenSetting(
+            one_typo, two_typos,
+        ))
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Ranks the documents of the search with its own ranking rules, checked like the ones of the settings.
+///
+/// The documents are only sorted on the attributes that are already sortable.
+fn apply_ranking_rules_override(
+    index: &Index,
+    rtxn: &RoTxn,
+    ranking_rules: &[RankingRuleView],
+    overlay: &mut milli::SettingsOverlay,
+) -> Result<(), MeilisearchHttpError> {
+    let criteria: Vec<_> = ranking_rules.iter().cloned().map(milli::Criterion::from).collect();
+    milli::validate_criteria(&criteria)
+        .map_err(|error| MeilisearchHttpError::InvalidRankingRulesOverride(error.to_string()))?;
+
+    let sortable_fields = index.sortable_fields(rtxn)?;
+    for criterion in &criteria {
+        let field = match criterion {
+            milli::Criterion::Asc(field)
+            | milli::Criterion::Desc(field)
+            | milli::Criterion::Random { seed: milli::RandomSeed::Field(field), .. } => field,
+            _otherwise => continue,
+        };
+        if !sortable_fields.contains(field) {
+            return Err(MeilisearchHttpError::InvalidRankingRulesOverride(format!(
+                "the `{criterion}` ranking rule sorts on the attribute `{field}`, which is not a sortable attribute of the index."
+            )));
+        }
+    }
+
+    overlay.criteria = Some(criteria);
+    Ok(())
+}
+
+/// Cancels the searches of a request when dropped.
+///
+/// The guard must be kept in the future of the route handler: actix drops this future when the
+/// client disconnects, which makes the blocking search tasks stop with a
+/// [`milli::Error::SearchCancelled`] at their next check instead of computing a response that
+/// nobody will read.
+#[derive(Debug, Default)]
+pub struct CancelSearchOnDrop {
+    canceled: Arc<AtomicBool>,
+}
+
+impl CancelSearchOnDrop {
+    /// The token to give to the searches canceled by this guard.
+    pub fn token(&self) -> Arc<AtomicBool> {
+        self.canceled.clone()
+    }
+}
+
+impl Drop for CancelSearchOnDrop {
+    fn drop(&mut self) {
+        self.canceled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Computes the distribution and the stats of the given facets on the candidates, on a sample
+/// of `approximation` candidates when it is set.
+fn compute_facets(
+    index: &Index,
+    rtxn: &RoTxn,
+    fields: &[String],
+    candidates: RoaringBitmap,
+    approximation: Option<u64>,
+    time_budget: TimeBudget,
+) -> Result<ComputedFacets, MeilisearchHttpError> {
+    let mut facet_distribution = index.facets_distribution(rtxn);
+    if let Some(threshold) = approximation {
+        facet_distribution.approximate(threshold);
+    }
+
+    let max_values_by_facet = index
+        .max_values_per_facet(rtxn)
+        .map_err(milli::Error::from)?
+        .map(|x| x as usize)
+        .unwrap_or(DEFAULT_VALUES_PER_FACET);
+    facet_distribution.max_values_per_facet(max_values_by_facet);
+
+    let sort_facet_values_by = index.sort_facet_values_by(rtxn).map_err(milli::Error::from)?;
+
+    if fields.iter().all(|f| f != "*") {
+        let fields: Vec<_> = fields.iter().map(|n| (n, sort_facet_values_by.get(n))).collect();
+        facet_distribution.facets(fields);
+    }
+
+    let distribution = facet_distribution
+        .candidates(candidates)
+        .default_order_by(sort_facet_values_by.get("*"))
+        .time_budget(time_budget)
+        .execute_typed()?
+        .into_iter()
+        .map(|(name, values)| (name, FacetValuesDistribution::new(values)))
+        .collect();
+
+    let date_attributes: Vec<_> = index
+        .facet_types(rtxn)?
+        .into_iter()
+        .filter_map(|(attribute, hint)| (hint == FacetTypeHint::Date).then_some(attribute))
+        .collect();
+    let stats = facet_distribution
+        .compute_stats()?
+        .into_iter()
+        .map(|(name, (min, max))| {
+            let date_attribute = milli::is_faceted(&name, &date_attributes);
+            (name, FacetStats::new(min, max, date_attribute))
+        })
+        .collect();
+
+    Ok(ComputedFacets { distribution, stats })
+}
+
+/// Computes the exact facets of a federated search on the documents of the index matched by
+/// its queries.
+pub fn perform_federated_facets(
+    index: &Index,
+    fields: &[String],
+    candidates: RoaringBitmap,
+    canceled: Arc<AtomicBool>,
+) -> Result<ComputedFacets, MeilisearchHttpError> {
+    let rtxn = index.read_txn()?;
+    let time_budget = search_time_budget(index, &rtxn, canceled)?;
+    compute_facets(index, &rtxn, fields, candidates, None, time_budget)
+}
+
+fn search_time_budget(
+    index: &Index,
+    rtxn: &RoTxn,
+    canceled: Arc<AtomicBool>,
+) -> Result<TimeBudget, MeilisearchHttpError> {
+    let time_budget = match index.search_cutoff(rtxn)? {
+        Some(cutoff) => TimeBudget::new(Duration::from_millis(cutoff)),
+        None => TimeBudget::default(),
+    };
+    Ok(time_budget.with_cancellation(canceled))
+}
+
+/// Counts the searches stopped because their client disconnected.
+///
+/// The error is never sent back, the request future that would have returned it is already dropped.
+/// The attributes to retrieve are the ones explicitly marked as to retrieve (all by default),
+/// but these attributes must be also be present
+/// - in the fields_ids_map
+/// - in the displayed attributes
+fn retrieved_fields_ids(
+    attributes_to_retrieve: Option<&BTreeSet<String>>,
+    fields_ids_map: &FieldsIdsMap,
+    displayed_ids: &BTreeSet<FieldId>,
+) -> BTreeSet<FieldId> {
+    let fids = |attrs: &BTreeSet<String>| {
+        let mut ids = BTreeSet::new();
+        for attr in attrs {
+            if attr == "*" {
+                ids = displayed_ids.clone();
+                break;
+            }
+
+            if let Some(id) = fields_ids_map.id(attr) {
+                ids.insert(id);
+            }
+        }
+        ids
+    };
+
+    attributes_to_retrieve
+        .map(fids)
+        .unwrap_or_else(|| displayed_ids.clone())
+        .intersection(displayed_ids)
+        .cloned()
+        .collect()
+}
+
+fn record_canceled_search(error: milli::Error) -> milli::Error {
+    if let milli::Error::SearchCancelled = error {
+        MEILISEARCH_CANCELED_SEARCH_REQUESTS.inc();
+    }
+    error
 }
 
 pub fn perform_search(
     index: &Index,
     query: SearchQuery,
     search_kind: SearchKind,
+    canceled: Arc<AtomicBool>,
+    trace: bool,
+    settings_overlay: Option<milli::SettingsOverlay>,
 ) -> Result<SearchResult, MeilisearchHttpError> {
     let before_search = Instant::now();
     let rtxn = index.read_txn()?;
-    let time_budget = match index.search_cutoff(&rtxn)? {
-        Some(cutoff) => TimeBudget::new(Duration::from_millis(cutoff)),
-        None => TimeBudget::default(),
-    };
+    let time_budget = search_time_budget(index, &rtxn, canceled)?;
 
-    let (search, is_finite_pagination, max_total_hits, offset) =
-        prepare_search(index, &rtxn, &query, &search_kind, time_budget)?;
+    let (mut search, is_finite_pagination, max_total_hits, offset, prepare_embedding_time) =
+        prepare_search(index, &rtxn, &query, &search_kind, time_budget.clone(), settings_overlay)?;
+    let dump_search_state = query.debug.map_or(false, |debug| debug.dump_search_state);
+    search.trace(trace || dump_search_state);
+    let query_parsing_time = before_search.elapsed().saturating_sub(prepare_embedding_time);
 
     let (
         milli::SearchResult {
@@ -560,20 +1688,32 @@ pub fn perform_search(
             matching_words,
             candidates,
             document_scores,
+            document_bucket_paths,
             degraded,
             used_negative_operator,
+            numeric_prefix_disabled,
+            query_truncated,
+            timings,
+            metrics,
+            skipped_ranking_rules,
+            query_tokens,
+            query_graph,
+            trace: search_trace,
+            bucket_stats,
+            explanation: _,
         },
         semantic_hit_count,
     ) = match &search_kind {
-        SearchKind::KeywordOnly => (search.execute()?, None),
-        SearchKind::SemanticOnly { .. } => {
-            let results = search.execute()?;
+        SearchKind::KeywordOnly => search.execute().map(|results| (results, None)),
+        SearchKind::SemanticOnly { .. } => search.execute().map(|results| {
             let semantic_hit_count = results.document_scores.len() as u32;
             (results, Some(semantic_hit_count))
-        }
-        SearchKind::Hybrid { semantic_ratio, .. } => search.execute_hybrid(*semantic_ratio)?,
-    };
+        }),
+        SearchKind::Hybrid { semantic_ratio, .. } => search.execute_hybrid(*semantic_ratio),
+    }
+    .map_err(record_canceled_search)?;
 
+    let before_formatting = Instant::now();
     let fields_ids_map = index.fields_ids_map(&rtxn).unwrap();
 
     let displayed_ids = index
@@ -581,48 +1721,31 @@ pub fn perform_search(
         .map(|fields| fields.into_iter().collect::<BTreeSet<_>>())
         .unwrap_or_else(|| fields_ids_map.iter().map(|(id, _)| id).collect());
 
-    let fids = |attrs: &BTreeSet<String>| {
-        let mut ids = BTreeSet::new();
-        for attr in attrs {
-            if attr == "*" {
-                ids = displayed_ids.clone();
-                break;
-            }
-
-            if let Some(id) = fields_ids_map.id(attr) {
-                ids.insert(id);
-            }
-        }
-        ids
-    };
-
-    // The attributes to retrieve are the ones explicitly marked as to retrieve (all by default),
-    // but these attributes must be also be present
-    // - in the fields_ids_map
-    // - in the displayed attributes
-    let to_retrieve_ids: BTreeSet<_> = query
-        .attributes_to_retrieve
-        .as_ref()
-        .map(fids)
-        .unwrap_or_else(|| displayed_ids.clone())
-        .intersection(&displayed_ids)
-        .cloned()
-        .collect();
+    let to_retrieve_ids = retrieved_fields_ids(
+        query.attributes_to_retrieve.as_ref(),
+        &fields_ids_map,
+        &displayed_ids,
+    );
 
     let attr_to_highlight = query.attributes_to_highlight.unwrap_or_default();
 
     let attr_to_crop = query.attributes_to_crop.unwrap_or_default();
 
-    // Attributes in `formatted_options` correspond to the attributes that will be in `_formatted`
-    // These attributes are:
-    // - the attributes asked to be highlighted or cropped (with `attributesToCrop` or `attributesToHighlight`)
-    // - the attributes asked to be retrieved: these attributes will not be highlighted/cropped
+    // The patterns like `attributes.*` can only be expanded against the fields of each document.
+    let attribute_patterns = AttributePatterns::new(
+        query.attributes_to_retrieve.as_ref(),
+        &attr_to_highlight,
+        &attr_to_crop,
+        query.crop_length,
+    );
+
+    // Attributes in `formatted_options` correspond to the attributes explicitly asked to be
+    // highlighted or cropped (with `attributesToCrop` or `attributesToHighlight`).
     // But these attributes must be also present in displayed attributes
     let formatted_options = compute_formatted_options(
         &attr_to_highlight,
         &attr_to_crop,
         query.crop_length,
-        &to_retrieve_ids,
         &fields_ids_map,
         &displayed_ids,
     );
@@ -653,26 +1776,58 @@ pub fn perform_search(
     formatter_builder.crop_marker(query.crop_marker);
     formatter_builder.highlight_prefix(query.highlight_pre_tag);
     formatter_builder.highlight_suffix(query.highlight_post_tag);
+    if let Some(highlight_typo_pre_tag) = query.highlight_typo_pre_tag {
+        formatter_builder.highlight_typo_prefix(highlight_typo_pre_tag);
+    }
+    if let Some(highlight_typo_post_tag) = query.highlight_typo_post_tag {
+        formatter_builder.highlight_typo_suffix(highlight_typo_post_tag);
+    }
 
     let mut documents = Vec::new();
     let documents_iter = index.documents(&rtxn, documents_ids)?;
 
-    for ((_id, obkv), score) in documents_iter.into_iter().zip(document_scores.into_iter()) {
+    // the paths are only collected when they are requested
+    let mut document_bucket_paths = document_bucket_paths.into_iter();
+    for ((docid, obkv), score) in documents_iter.into_iter().zip(document_scores.into_iter()) {
+        let bucket_path = document_bucket_paths.next();
         // First generate a document with all the displayed fields
-        let displayed_document = make_document(&displayed_ids, &fields_ids_map, obkv)?;
+        let displayed_document = make_document(&displayed_ids, &fields_ids_map, obkv.reader())?;
+
+        let document_fields = if attribute_patterns.is_empty() {
+            BTreeSet::new()
+        } else {
+            document_fields(&displayed_document)
+        };
 
         // select the attributes to retrieve
-        let attributes_to_retrieve = to_retrieve_ids
+        let attributes_to_retrieve: Vec<_> = to_retrieve_ids
             .iter()
-            .map(|&fid| fields_ids_map.name(fid).expect("Missing field name"));
-        let mut document =
-            permissive_json_pointer::select_values(&displayed_document, attributes_to_retrieve);
+            .map(|&fid| fields_ids_map.name(fid).expect("Missing field name"))
+            .chain(attribute_patterns.retrieved_fields(&document_fields))
+            .collect();
+        let mut document = permissive_json_pointer::select_values(
+            &displayed_document,
+            attributes_to_retrieve.iter().copied(),
+        );
+
+        // Attributes in `document_formatted_options` correspond to the attributes that will be in `_formatted`
+        // These attributes are:
+        // - the attributes asked to be highlighted or cropped, by name or by pattern
+        // - the attributes asked to be retrieved: these attributes will not be highlighted/cropped
+        let document_formatted_options = document_formatted_options(
+            &formatted_options,
+            &attribute_patterns,
+            &document_fields,
+            &attributes_to_retrieve,
+            &fields_ids_map,
+        );
 
+        formatter_builder.document(docid);
         let (matches_position, formatted) = format_fields(
             &displayed_document,
             &fields_ids_map,
             &formatter_builder,
-            &formatted_options,
+            &document_formatted_options,
             query.show_matches_position,
             &displayed_ids,
         )?;
@@ -683,8 +1838,30 @@ pub fn perform_search(
 
         let ranking_score =
             query.show_ranking_score.then(|| ScoreDetails::global_score(score.iter()));
-        let ranking_score_details =
-            query.show_ranking_score_details.then(|| ScoreDetails::to_json_map(score.iter()));
+        let show_ranking_score_details =
+            query.show_ranking_score_details || query.show_ranking_bucket_path;
+        let ranking_score_details = show_ranking_score_details.then(|| {
+            let mut details = ScoreDetails::to_json_map(score.iter());
+            if numeric_prefix_disabled {
+                if let Some(Value::Object(words)) = details.get_mut("words") {
+                    words.insert("prefixDisabled".to_string(), Value::Bool(true));
+                }
+            }
+            if let Some(bucket_path) = bucket_path {
+                let bucket_path = bucket_path
+                    .into_iter()
+                    .map(|BucketStep { ranking_rule, bucket, bucket_size }| {
+                        json!({
+                            "rankingRule": ranking_rule,
+                            "bucket": bucket,
+                            "bucketSize": bucket_size,
+                        })
+                    })
+                    .collect();
+                details.insert("bucketPath".to_string(), Value::Array(bucket_path));
+            }
+            details
+        });
 
         let hit = SearchHit {
             document,
@@ -692,11 +1869,17 @@ pub fn perform_search(
             matches_position,
             ranking_score_details,
             ranking_score,
+            docid,
         };
         documents.push(hit);
     }
 
     let number_of_hits = min(candidates.len() as usize, max_total_hits);
+    // The candidates are only an estimation when the distinct attribute is not applied to all of
+    // them, which is only done when counting the pages.
+    let exhaustive_nb_hits = candidates.len() as usize <= max_total_hits
+        && (is_finite_pagination
+            || (query.distinct.is_none() && index.distinct_field(&rtxn)?.is_none()));
     let hits_info = if is_finite_pagination {
         let hits_per_page = query.hits_per_page.unwrap_or_else(DEFAULT_SEARCH_LIMIT);
         // If hit_per_page is 0, then pages can't be computed and so we respond 0.
@@ -713,41 +1896,69 @@ pub fn perform_search(
     } else {
         HitsInfo::OffsetLimit { limit: query.limit, offset, estimated_total_hits: number_of_hits }
     };
-
-    let (facet_distribution, facet_stats) = match query.facets {
+    let formatting_time = before_formatting.elapsed();
+
+    let before_facet_distribution = Instant::now();
+    // a sample must contain at least one candidate to be extrapolated
+    let approximation_threshold =
+        query.facet_approximation_threshold.unwrap_or(DEFAULT_APPROXIMATION_THRESHOLD).max(1);
+    let is_large_universe = candidates.len() > approximation_threshold;
+    let (facet_distribution, facet_stats, applied_facet_distribution_mode) = match query.facets {
+        Some(_)
+            if is_large_universe
+                && query.facet_distribution_mode
+                    == Some(FacetDistributionMode::SkipOnLargeUniverse) =>
+        {
+            (Some(None), Some(None), AppliedFacetDistributionMode::Skipped)
+        }
         Some(ref fields) => {
-            let mut facet_distribution = index.facets_distribution(&rtxn);
-
-            let max_values_by_facet = index
-                .max_values_per_facet(&rtxn)
-                .map_err(milli::Error::from)?
-                .map(|x| x as usize)
-                .unwrap_or(DEFAULT_VALUES_PER_FACET);
-            facet_distribution.max_values_per_facet(max_values_by_facet);
-
-            let sort_facet_values_by =
-                index.sort_facet_values_by(&rtxn).map_err(milli::Error::from)?;
-
-            if fields.iter().all(|f| f != "*") {
-                let fields: Vec<_> =
-                    fields.iter().map(|n| (n, sort_facet_values_by.get(n))).collect();
-                facet_distribution.facets(fields);
-            }
-
-            let distribution = facet_distribution
-                .candidates(candidates)
-                .default_order_by(sort_facet_values_by.get("*"))
-                .execute()?;
-            let stats = facet_distribution.compute_stats()?;
-            (Some(distribution), Some(stats))
+            let (approximation, applied_mode) = match query.facet_distribution_mode {
+                Some(FacetDistributionMode::Approximate) if is_large_universe => {
+                    (Some(approximation_threshold), AppliedFacetDistributionMode::Approximate)
+                }
+                _ => (None, AppliedFacetDistributionMode::Exact),
+            };
+            let ComputedFacets { distribution, stats } = compute_facets(
+                index,
+                &rtxn,
+                fields,
+                candidates.clone(),
+                approximation,
+                time_budget,
+            )?;
+            (Some(Some(distribution)), Some(Some(stats)), applied_mode)
         }
-        None => (None, None),
+        None => (None, None, AppliedFacetDistributionMode::Exact),
     };
 
-    let facet_stats = facet_stats.map(|stats| {
-        stats.into_iter().map(|(k, (min, max))| (k, FacetStats { min, max })).collect()
+    // The mode is only reported to the users who explicitly asked for one.
+    let facet_distribution_mode = match (&query.facets, query.facet_distribution_mode) {
+        (Some(_), Some(_)) => Some(applied_facet_distribution_mode),
+        _ => None,
+    };
+    let facet_distribution_time = before_facet_distribution.elapsed();
+
+    let processing_breakdown = query.show_processing_breakdown.then(|| ProcessingBreakdown {
+        exhaustive_nb_hits,
+        query_parsing: duration_as_ms(query_parsing_time),
+        embedding: match search_kind {
+            SearchKind::KeywordOnly => None,
+            SearchKind::SemanticOnly { .. } | SearchKind::Hybrid { .. } => {
+                Some(duration_as_ms(prepare_embedding_time + timings.embedding))
+            }
+        },
+        universe: duration_as_ms(timings.universe),
+        bucket_sort: duration_as_ms(timings.bucket_sort),
+        facet_distribution: duration_as_ms(facet_distribution_time),
+        formatting: duration_as_ms(formatting_time),
     });
 
+    let search_state = if dump_search_state {
+        Some(dump_search_state_blob(query_graph.as_ref(), search_trace.as_ref())?)
+    } else {
+        None
+    };
+
     let result = SearchResult {
         hits: documents,
         hits_info,
@@ -755,28 +1966,246 @@ pub fn perform_search(
         processing_time_ms: before_search.elapsed().as_millis(),
         facet_distribution,
         facet_stats,
+        facet_distribution_mode,
+        candidates,
         degraded,
+        query_truncated,
         used_negative_operator,
         semantic_hit_count,
+        processing_breakdown,
+        query_tokens: query.show_query_tokens.then_some(query_tokens),
+        metrics: query.show_search_metrics.then_some(metrics),
+        skipped_ranking_rules: (query.show_ranking_score_details
+            && !skipped_ranking_rules.is_empty())
+        .then(|| skipped_ranking_rules.into_iter().map(SkippedRankingRuleView::from).collect()),
+        debug: query.debug.map(|debug| SearchDebugView {
+            query_graph: query_graph.filter(|_| debug.query_graph),
+            bucket_stats,
+            search_state,
+        }),
+        trace: search_trace.filter(|_| trace),
     };
     Ok(result)
 }
 
+/// How a search ranked a document of the index, returned by the search explain route.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchExplanationView {
+    pub document_id: String,
+    /// The position of the document among all the hits of the search, starting at `0`,
+    /// regardless of the offset and the limit. `null` when the document is not returned.
+    pub position: Option<usize>,
+    /// Why the document is not returned, `null` when it is.
+    pub excluded: Option<ExclusionView>,
+    /// The buckets the document fell into, from the first ranking rule to the last one sorting it.
+    pub bucket_path: Vec<BucketStepView>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExclusionView {
+    pub reason: &'static str,
+    /// The document returned instead of this one by the distinct attribute.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketStepView {
+    pub ranking_rule: String,
+    pub bucket: usize,
+    pub bucket_size: u64,
+}
+
+impl From<BucketStep> for BucketStepView {
+    fn from(BucketStep { ranking_rule, bucket, bucket_size }: BucketStep) -> Self {
+        Self { ranking_rule, bucket, bucket_size }
+    }
+}
+
+/// Makes the keyword search of the query and follows the document through its ranking rules.
+///
+/// The offset and the limit of the query are ignored, the documents are ranked until the
+/// explained one is returned or discarded.
+pub fn perform_search_explain(
+    index: &Index,
+    query: SearchQuery,
+    document_id: String,
+    canceled: Arc<AtomicBool>,
+) -> Result<SearchExplanationView, MeilisearchHttpError> {
+    let rtxn = index.read_txn()?;
+    let docid = index
+        .internal_id_of(&rtxn, &document_id)?
+        .ok_or_else(|| MeilisearchHttpError::DocumentNotFound(document_id.clone()))?;
+    let time_budget = search_time_budget(index, &rtxn, canceled)?;
+
+    let (mut search, _, _, _, _) =
+        prepare_search(index, &rtxn, &query, &SearchKind::KeywordOnly, time_budget, None)?;
+    search.explain(docid);
+    let milli::SearchResult { explanation, .. } =
+        search.execute().map_err(record_canceled_search)?;
+    let milli::SearchExplanation { bucket_path, exclusion, position } =
+        explanation.unwrap_or_default();
+
+    let excluded = match exclusion {
+        Some(reason) => {
+            let (reason, by) = match reason {
+                milli::ExclusionReason::FilteredOut => ("filteredOut", None),
+                milli::ExclusionReason::NegativeQuery => ("negativeQuery", None),
+                milli::ExclusionReason::DroppedByWordsRelaxation => {
+                    ("droppedByWordsRelaxation", None)
+                }
+                milli::ExclusionReason::NotMatchingQuery => ("notMatchingQuery", None),
+                milli::ExclusionReason::Distinct { kept } => {
+                    ("distinct", index.external_ids_of(&rtxn, [kept])?.pop())
+                }
+                milli::ExclusionReason::BelowRankingScoreThreshold => {
+                    ("belowRankingScoreThreshold", None)
+                }
+            };
+            Some(ExclusionView { reason, by })
+        }
+        None => None,
+    };
+
+    Ok(SearchExplanationView {
+        document_id,
+        position,
+        excluded,
+        bucket_path: bucket_path.into_iter().map(BucketStepView::from).collect(),
+    })
+}
+
+/// Makes the keyword search of the query and streams its hits as newline-delimited JSON, one
+/// document per line, each batch of documents being sent as soon as the bucket sort ranks it.
+///
+/// The documents only contain their retrieved attributes: they are neither formatted nor scored,
+/// and the facets are not computed. The search stops once the receiver of the hits is dropped.
+///
+/// Like for the documents route, an error happening before the first batch is sent to `started`,
+/// to be returned as a regular error response, while a later one aborts the response.
+pub fn perform_search_stream(
+    index: &Index,
+    query: SearchQuery,
+    canceled: Arc<AtomicBool>,
+    started: oneshot::Sender<Result<(), ResponseError>>,
+    sender: mpsc::Sender<Result<Bytes, ResponseError>>,
+) {
+    let mut started = Some(started);
+    let result = stream_search_hits(index, &query, canceled, &mut started, &sender);
+    match (result, started) {
+        // no document was sent, the search found none or failed before
+        (result, Some(started)) => {
+            let _ = started.send(result.map_err(ResponseError::from));
+        }
+        (Err(error), None) => {
+            let _ = sender.blocking_send(Err(error.into()));
+        }
+        (Ok(()), None) => (),
+    }
+}
+
+fn stream_search_hits(
+    index: &Index,
+    query: &SearchQuery,
+    canceled: Arc<AtomicBool>,
+    started: &mut Option<oneshot::Sender<Result<(), ResponseError>>>,
+    sender: &mpsc::Sender<Result<Bytes, ResponseError>>,
+) -> Result<(), MeilisearchHttpError> {
+    let rtxn = index.read_txn()?;
+    let fields_ids_map = index.fields_ids_map(&rtxn)?;
+    let displayed_ids = index
+        .displayed_fields_ids(&rtxn)?
+        .map(|fields| fields.into_iter().collect::<BTreeSet<_>>())
+        .unwrap_or_else(|| fields_ids_map.iter().map(|(id, _)| id).collect());
+    let to_retrieve_ids = retrieved_fields_ids(
+        query.attributes_to_retrieve.as_ref(),
+        &fields_ids_map,
+        &displayed_ids,
+    );
+    let time_budget = search_time_budget(index, &rtxn, canceled)?;
+
+    let mut error = None;
+    let result = {
+        let (search, _, _, _, _) =
+            prepare_search(index, &rtxn, query, &SearchKind::KeywordOnly, time_budget, None)?;
+        search.execute_streaming(|docids| {
+            // the response starts with the first batch of documents
+            if let Some(started) = started.take() {
+                if started.send(Ok(())).is_err() {
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+            let hits = match write_hits(index, &rtxn, &fields_ids_map, &to_retrieve_ids, docids) {
+                Ok(hits) => hits,
+                Err(e) => {
+                    error = Some(e);
+                    return Ok(ControlFlow::Break(()));
+                }
+            };
+            // the client is gone when the receiver is dropped
+            match sender.blocking_send(Ok(hits.into())) {
+                Ok(()) => Ok(ControlFlow::Continue(())),
+                Err(_) => Ok(ControlFlow::Break(())),
+            }
+        })
+    };
+    result.map_err(record_canceled_search)?;
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Writes the retrieved attributes of the documents as newline-delimited JSON.
+fn write_hits(
+    index: &Index,
+    rtxn: &RoTxn,
+    fields_ids_map: &FieldsIdsMap,
+    to_retrieve_ids: &BTreeSet<FieldId>,
+    docids: &[DocumentId],
+) -> Result<Vec<u8>, MeilisearchHttpError> {
+    let mut buffer = Vec::new();
+    for (_docid, obkv) in index.documents(rtxn, docids.iter().copied())? {
+        let document = make_document(to_retrieve_ids, fields_ids_map, obkv.reader())?;
+        serde_json::to_writer(&mut buffer, &document)?;
+        buffer.push(b'\n');
+    }
+    Ok(buffer)
+}
+
+/// Serializes the query graph and the trace of the bucket sort of a search into a gzipped JSON,
+/// encoded in base64.
+fn dump_search_state_blob(
+    query_graph: Option<&QueryGraphDescription>,
+    trace: Option<&milli::SearchTrace>,
+) -> Result<String, MeilisearchHttpError> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    serde_json::to_writer(&mut encoder, &json!({ "queryGraph": query_graph, "trace": trace }))?;
+    let compressed = encoder.finish().map_err(serde_json::Error::io)?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(compressed))
+}
+
+fn duration_as_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
 pub fn perform_facet_search(
     index: &Index,
     search_query: SearchQuery,
     facet_query: Option<String>,
     facet_name: String,
     search_kind: SearchKind,
+    canceled: Arc<AtomicBool>,
 ) -> Result<FacetSearchResult, MeilisearchHttpError> {
     let before_search = Instant::now();
     let rtxn = index.read_txn()?;
-    let time_budget = match index.search_cutoff(&rtxn)? {
-        Some(cutoff) => TimeBudget::new(Duration::from_millis(cutoff)),
-        None => TimeBudget::default(),
-    };
+    let time_budget = search_time_budget(index, &rtxn, canceled)?;
 
-    let (search, _, _, _) = prepare_search(index, &rtxn, &search_query, &search_kind, time_budget)?;
+    let (search, _, _, _, _) =
+        prepare_search(index, &rtxn, &search_query, &search_kind, time_budget, None)?;
     let mut facet_search = SearchForFacetValues::new(
         facet_name,
         search,
@@ -789,9 +2218,12 @@ pub fn perform_facet_search(
         facet_search.max_values(max_facets as usize);
     }
 
+    let FacetValueHits { hits, truncated } = facet_search.execute()?;
+
     Ok(FacetSearchResult {
-        facet_hits: facet_search.execute()?,
+        facet_hits: hits,
         facet_query,
+        truncated,
         processing_time_ms: before_search.elapsed().as_millis(),
     })
 }
@@ -816,7 +2248,6 @@ fn compute_formatted_options(
     attr_to_highlight: &HashSet<String>,
     attr_to_crop: &[String],
     query_crop_length: usize,
-    to_retrieve_ids: &BTreeSet<FieldId>,
     fields_ids_map: &FieldsIdsMap,
     displayed_ids: &BTreeSet<FieldId>,
 ) -> BTreeMap<FieldId, FormatOptions> {
@@ -837,11 +2268,6 @@ fn compute_formatted_options(
         displayed_ids,
     );
 
-    // Should not return `_formatted` if no valid attributes to highlight/crop
-    if !formatted_options.is_empty() {
-        add_non_formatted_ids_to_formatted_options(&mut formatted_options, to_retrieve_ids);
-    }
-
     formatted_options
 }
 
@@ -877,14 +2303,7 @@ fn add_crop_to_formatted_options(
     displayed_ids: &BTreeSet<FieldId>,
 ) {
     for attr in attr_to_crop {
-        let mut split = attr.rsplitn(2, ':');
-        let (attr_name, attr_len) = match split.next().zip(split.next()) {
-            Some((len, name)) => {
-                let crop_len = len.parse::<usize>().unwrap_or(crop_length);
-                (name, crop_len)
-            }
-            None => (attr.as_str(), crop_length),
-        };
+        let (attr_name, attr_len) = parse_crop_attribute(attr, crop_length);
 
         if attr_name == "*" {
             for id in displayed_ids {
@@ -906,15 +2325,172 @@ fn add_crop_to_formatted_options(
     }
 }
 
-fn add_non_formatted_ids_to_formatted_options(
-    formatted_options: &mut BTreeMap<FieldId, FormatOptions>,
-    to_retrieve_ids: &BTreeSet<FieldId>,
-) {
-    for id in to_retrieve_ids {
-        formatted_options.entry(*id).or_insert(FormatOptions { highlight: false, crop: None });
+/// Splits an attribute of `attributesToCrop` into its name and its crop length, e.g. `title:5`.
+fn parse_crop_attribute(attr: &str, crop_length: usize) -> (&str, usize) {
+    let mut split = attr.rsplitn(2, ':');
+    match split.next().zip(split.next()) {
+        Some((len, name)) => (name, len.parse::<usize>().unwrap_or(crop_length)),
+        None => (attr, crop_length),
+    }
+}
+
+/// Returns `true` if the attribute is a pattern like `attributes.*` or `*_title`.
+/// The single `*` is not a pattern as it already designates all the displayed attributes.
+fn is_attribute_pattern(attr: &str) -> bool {
+    attr != "*" && attr.contains('*')
+}
+
+/// Returns `true` if the field matches the pattern, where a `*` matches any sequence of
+/// characters, dots included: `attributes.*` matches `attributes.color` and `attributes.size.eu`.
+fn matches_attribute_pattern(pattern: &str, field: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = field.strip_prefix(first) else { return false };
+    let mut parts: Vec<_> = parts.collect();
+    let last = parts.pop().unwrap_or_default();
+    for part in parts {
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// The patterns of `attributesToRetrieve`, `attributesToHighlight` and `attributesToCrop`.
+///
+/// They are expanded against the fields of each document rather than against the fields ids map,
+/// as the fields, and thus the displayed fields, vary from one document to another.
+#[derive(Debug, Default)]
+struct AttributePatterns<'a> {
+    retrieve: Vec<&'a str>,
+    highlight: Vec<&'a str>,
+    crop: Vec<(&'a str, usize)>,
+}
+
+impl<'a> AttributePatterns<'a> {
+    fn new(
+        attr_to_retrieve: Option<&'a BTreeSet<String>>,
+        attr_to_highlight: &'a HashSet<String>,
+        attr_to_crop: &'a [String],
+        crop_length: usize,
+    ) -> Self {
+        // retrieving `*` already retrieves all the displayed attributes
+        let retrieve = match attr_to_retrieve {
+            Some(attrs) if !attrs.contains("*") => {
+                attrs.iter().map(String::as_str).filter(|attr| is_attribute_pattern(attr)).collect()
+            }
+            _ => Vec::new(),
+        };
+        let highlight = attr_to_highlight
+            .iter()
+            .map(String::as_str)
+            .filter(|attr| is_attribute_pattern(attr))
+            .collect();
+        let crop = attr_to_crop
+            .iter()
+            .map(|attr| parse_crop_attribute(attr, crop_length))
+            .filter(|(attr, _)| is_attribute_pattern(attr))
+            .collect();
+        Self { retrieve, highlight, crop }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.retrieve.is_empty() && self.highlight.is_empty() && self.crop.is_empty()
+    }
+
+    /// Returns the fields of the document matched by the patterns of `attributesToRetrieve`.
+    fn retrieved_fields<'d>(&self, document_fields: &'d BTreeSet<String>) -> Vec<&'d str> {
+        document_fields
+            .iter()
+            .filter(|field| {
+                self.retrieve.iter().any(|pattern| matches_attribute_pattern(pattern, field))
+            })
+            .map(String::as_str)
+            .collect()
     }
 }
 
+/// Returns all the fields of the document, the nested ones included, e.g. `doggo` and `doggo.name`.
+fn document_fields(document: &Document) -> BTreeSet<String> {
+    fn insert_fields(prefix: Option<&str>, value: &Value, fields: &mut BTreeSet<String>) {
+        match value {
+            Value::Object(object) => {
+                for (key, value) in object {
+                    let field = match prefix {
+                        Some(prefix) => format!("{prefix}.{key}"),
+                        None => key.clone(),
+                    };
+                    insert_fields(Some(&field), value, fields);
+                    fields.insert(field);
+                }
+            }
+            Value::Array(values) => {
+                values.iter().for_each(|value| insert_fields(prefix, value, fields))
+            }
+            _ => (),
+        }
+    }
+
+    let mut fields = BTreeSet::new();
+    for (key, value) in document {
+        insert_fields(Some(key), value, &mut fields);
+        fields.insert(key.clone());
+    }
+    fields
+}
+
+/// Returns the options to format the fields of this document with, by name.
+///
+/// The patterns are expanded against the fields of the document. The crop length of an attribute
+/// given by name in `attributesToCrop` takes precedence over the one of a pattern matching it.
+fn document_formatted_options(
+    formatted_options: &BTreeMap<FieldId, FormatOptions>,
+    attribute_patterns: &AttributePatterns,
+    document_fields: &BTreeSet<String>,
+    attributes_to_retrieve: &[&str],
+    fields_ids_map: &FieldsIdsMap,
+) -> BTreeMap<String, FormatOptions> {
+    let mut document_options: BTreeMap<String, FormatOptions> = formatted_options
+        .iter()
+        .map(|(&fid, &option)| {
+            (fields_ids_map.name(fid).expect("Missing field name").to_string(), option)
+        })
+        .collect();
+
+    for field in document_fields {
+        let highlight = attribute_patterns
+            .highlight
+            .iter()
+            .any(|pattern| matches_attribute_pattern(pattern, field));
+        let crop = attribute_patterns
+            .crop
+            .iter()
+            .find(|(pattern, _)| matches_attribute_pattern(pattern, field))
+            .map(|&(_, crop_length)| crop_length);
+        if !highlight && crop.is_none() {
+            continue;
+        }
+
+        let option = document_options
+            .entry(field.clone())
+            .or_insert(FormatOptions { highlight: false, crop: None });
+        option.highlight |= highlight;
+        option.crop = option.crop.or(crop);
+    }
+
+    // Should not return `_formatted` if no valid attributes to highlight/crop
+    if !document_options.is_empty() {
+        for field in attributes_to_retrieve {
+            document_options
+                .entry(field.to_string())
+                .or_insert(FormatOptions { highlight: false, crop: None });
+        }
+    }
+
+    document_options
+}
+
 fn make_document(
     displayed_attributes: &BTreeSet<FieldId>,
     field_ids_map: &FieldsIdsMap,
@@ -943,7 +2519,7 @@ fn format_fields<'a>(
     document: &Document,
     field_ids_map: &FieldsIdsMap,
     builder: &'a MatcherBuilder<'a>,
-    formatted_options: &BTreeMap<FieldId, FormatOptions>,
+    formatted_options: &BTreeMap<String, FormatOptions>,
     compute_matches: bool,
     displayable_ids: &BTreeSet<FieldId>,
 ) -> Result<(Option<MatchesPosition>, Document), MeilisearchHttpError> {
@@ -955,7 +2531,7 @@ fn format_fields<'a>(
     let formatting_fields_options: Vec<_> = formatted_options
         .iter()
         .filter(|(_, option)| option.should_format())
-        .map(|(fid, option)| (field_ids_map.name(*fid).unwrap(), option))
+        .map(|(name, option)| (name.as_str(), option))
         .collect();
 
     // select the attributes to retrieve
@@ -987,11 +2563,7 @@ fn format_fields<'a>(
         }
     });
 
-    let selectors = formatted_options
-        .keys()
-        // This unwrap must be safe since we got the ids from the fields_ids_map just
-        // before.
-        .map(|&fid| field_ids_map.name(fid).unwrap());
+    let selectors = formatted_options.keys().map(String::as_str);
     let document = permissive_json_pointer::select_values(&document, selectors);
 
     Ok((matches_position, document))
@@ -1185,4 +2757,21 @@ mod test {
         insert_geo_distance(sorters, &mut document);
         assert_eq!(document.get("_geoDistance"), None);
     }
+
+    #[test]
+    fn test_matches_attribute_pattern() {
+        assert!(matches_attribute_pattern("attributes.*", "attributes.color"));
+        assert!(matches_attribute_pattern("attributes.*", "attributes.size.eu"));
+        assert!(!matches_attribute_pattern("attributes.*", "attributes"));
+        assert!(!matches_attribute_pattern("attributes.*", "other.attributes.color"));
+
+        assert!(matches_attribute_pattern("*_title", "sub_title"));
+        assert!(matches_attribute_pattern("*_title", "_title"));
+        assert!(!matches_attribute_pattern("*_title", "title"));
+        assert!(!matches_attribute_pattern("*_title", "sub_title_fr"));
+
+        assert!(matches_attribute_pattern("doggo.*.name", "doggo.friend.name"));
+        assert!(!matches_attribute_pattern("doggo.*.name", "doggo.name"));
+        assert!(matches_attribute_pattern("*name*", "name"));
+    }
 }
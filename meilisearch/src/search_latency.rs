@@ -0,0 +1,201 @@
+//! This file implements the histograms of the search latencies exposed by the stats of every index.
+//!
+//! Every index gets one histogram per kind of search (search, multi-search, and facet-search).
+//! A histogram only keeps the latencies of the last few minutes: it is made of one slot per minute,
+//! and a slot is reset the first time it's used again once the window has moved past it.
+//! Every slot is an array of atomic counters, one per bucket of latency, so recording a latency never
+//! waits on a lock. The histograms are only kept in memory and are thus reset on restart.
+//!
+//! The buckets follow the idea of the HDR histograms: every power of two of microseconds is split
+//! into a few sub-buckets, which gives the same relative precision for the short and long latencies.
+
+use std::collections::HashMap;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// The number of minutes over which the percentiles are computed.
+pub const WINDOW_MINUTES: u64 = 5;
+/// The number of sub-buckets every power of two is split into, the precision is about 25%.
+const SUB_BUCKETS: usize = 4;
+/// The latencies are recorded in microseconds, up to 2^33µs (more than two hours).
+const MAX_EXPONENT: usize = 32;
+const BUCKETS: usize = (MAX_EXPONENT + 1) * SUB_BUCKETS;
+/// The minute of a slot that has never been used.
+const UNUSED_SLOT: u64 = u64::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatencyKind {
+    Search,
+    MultiSearch,
+    FacetSearch,
+}
+
+/// The latencies of the searches made on every index since the start of Meilisearch.
+#[derive(Debug)]
+pub struct SearchLatencies {
+    started_at: Instant,
+    indexes: RwLock<HashMap<String, Arc<IndexLatencies>>>,
+}
+
+impl Default for SearchLatencies {
+    fn default() -> Self {
+        Self { started_at: Instant::now(), indexes: RwLock::default() }
+    }
+}
+
+impl SearchLatencies {
+    pub fn record(&self, index_uid: &str, kind: LatencyKind, latency: Duration) {
+        let minute = self.current_minute();
+        let index = self.indexes.read().unwrap().get(index_uid).cloned();
+        let index = match index {
+            Some(index) => index,
+            None => self.indexes.write().unwrap().entry(index_uid.to_string()).or_default().clone(),
+        };
+        index.histogram(kind).record(minute, latency);
+    }
+
+    /// Returns the percentiles of the latencies of the searches made
+    /// on this index during the last [`WINDOW_MINUTES`] minutes.
+    pub fn stats(&self, index_uid: &str) -> SearchLatencyStats {
+        let minute = self.current_minute();
+        match self.indexes.read().unwrap().get(index_uid) {
+            Some(index) => SearchLatencyStats {
+                search: index.search.percentiles(minute),
+                multi_search: index.multi_search.percentiles(minute),
+                facet_search: index.facet_search.percentiles(minute),
+            },
+            None => SearchLatencyStats::default(),
+        }
+    }
+
+    fn current_minute(&self) -> u64 {
+        self.started_at.elapsed().as_secs() / 60
+    }
+}
+
+#[derive(Debug, Default)]
+struct IndexLatencies {
+    search: Histogram,
+    multi_search: Histogram,
+    facet_search: Histogram,
+}
+
+impl IndexLatencies {
+    fn histogram(&self, kind: LatencyKind) -> &Histogram {
+        match kind {
+            LatencyKind::Search => &self.search,
+            LatencyKind::MultiSearch => &self.multi_search,
+            LatencyKind::FacetSearch => &self.facet_search,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Histogram {
+    slots: [Slot; WINDOW_MINUTES as usize],
+}
+
+#[derive(Debug)]
+struct Slot {
+    minute: AtomicU64,
+    buckets: [AtomicU64; BUCKETS],
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        let slot = || Slot {
+            minute: AtomicU64::new(UNUSED_SLOT),
+            buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+        };
+        Self { slots: std::array::from_fn(|_| slot()) }
+    }
+}
+
+impl Histogram {
+    fn record(&self, minute: u64, latency: Duration) {
+        let slot = &self.slots[(minute % WINDOW_MINUTES) as usize];
+        let slot_minute = slot.minute.load(Relaxed);
+        if slot_minute != minute
+            && slot.minute.compare_exchange(slot_minute, minute, Relaxed, Relaxed).is_ok()
+        {
+            // The slot contains the latencies of an older minute. A concurrent search could
+            // record its latency before we reset the slot, but an approximation is fine here.
+            slot.buckets.iter().for_each(|bucket| bucket.store(0, Relaxed));
+        }
+        slot.buckets[bucket_of(latency)].fetch_add(1, Relaxed);
+    }
+
+    fn percentiles(&self, minute: u64) -> LatencyPercentiles {
+        let mut buckets = [0; BUCKETS];
+        for slot in &self.slots {
+            let slot_minute = slot.minute.load(Relaxed);
+            if slot_minute != UNUSED_SLOT && minute.saturating_sub(slot_minute) < WINDOW_MINUTES {
+                for (count, bucket) in buckets.iter_mut().zip(&slot.buckets) {
+                    *count += bucket.load(Relaxed);
+                }
+            }
+        }
+
+        let count = buckets.iter().sum();
+        LatencyPercentiles {
+            count,
+            p50: percentile(&buckets, count, 0.50),
+            p90: percentile(&buckets, count, 0.90),
+            p99: percentile(&buckets, count, 0.99),
+        }
+    }
+}
+
+/// Returns the index of the bucket of this latency.
+fn bucket_of(latency: Duration) -> usize {
+    let max_micros = (1u128 << (MAX_EXPONENT + 1)) - 1;
+    let micros = latency.as_micros().clamp(1, max_micros) as u64;
+    let exponent = micros.ilog2() as usize;
+    let sub_bucket = ((micros << 2) >> exponent) as usize % SUB_BUCKETS;
+    exponent * SUB_BUCKETS + sub_bucket
+}
+
+/// Returns the upper bound, in milliseconds, of the bucket at this index.
+fn upper_bound_of(bucket: usize) -> f64 {
+    let exponent = (bucket / SUB_BUCKETS) as i32;
+    let sub_bucket = (bucket % SUB_BUCKETS) as f64;
+    let micros = 2f64.powi(exponent) * (1.0 + (sub_bucket + 1.0) / SUB_BUCKETS as f64);
+    micros / 1000.0
+}
+
+fn percentile(buckets: &[u64; BUCKETS], count: u64, quantile: f64) -> Option<f64> {
+    if count == 0 {
+        return None;
+    }
+    let rank = ((count as f64 * quantile).ceil() as u64).max(1);
+    let mut seen = 0;
+    buckets
+        .iter()
+        .position(|bucket| {
+            seen += bucket;
+            seen >= rank
+        })
+        .map(upper_bound_of)
+}
+
+/// The percentiles of the latencies of the searches of an index, as known to the stats route.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchLatencyStats {
+    pub search: LatencyPercentiles,
+    pub multi_search: LatencyPercentiles,
+    pub facet_search: LatencyPercentiles,
+}
+
+/// The percentiles are expressed in milliseconds, and are `null` when no search was made.
+#[derive(Debug, Default, Serialize)]
+pub struct LatencyPercentiles {
+    pub count: u64,
+    pub p50: Option<f64>,
+    pub p90: Option<f64>,
+    pub p99: Option<f64>,
+}
@@ -16,32 +16,48 @@
 //!                                You should exit and free all the RAM you use ASAP.
 //! - Sends you a Permit => that will unlock the method, and you will be able to process your search.
 //!                         And should drop the Permit only once you have freed all the RAM consumed by the method.
+//!
+//! The search requests that can't run straight away wait in a FIFO queue, they're processed in the order they
+//! were received. A request that runs several searches, like a multi-search, takes one slot per search with
+//! `SearchQueue::try_get_search_permits`.
 
+use std::collections::VecDeque;
 use std::num::NonZeroUsize;
 
-use rand::rngs::StdRng;
-use rand::{Rng, SeedableRng};
 use tokio::sync::{mpsc, oneshot};
 
 use crate::error::MeilisearchHttpError;
+use crate::metrics::{
+    MEILISEARCH_SEARCHES_RUNNING, MEILISEARCH_SEARCHES_WAITING_TO_BE_PROCESSED,
+    MEILISEARCH_SEARCH_REQUESTS_REJECTED,
+};
 
 #[derive(Debug)]
 pub struct SearchQueue {
-    sender: mpsc::Sender<oneshot::Sender<Permit>>,
+    sender: mpsc::Sender<SearchRequest>,
     capacity: usize,
+    parallelism: NonZeroUsize,
+}
+
+/// A search request waiting for the number of slots it needs to run.
+#[derive(Debug)]
+struct SearchRequest {
+    slots: usize,
+    permit: oneshot::Sender<Permit>,
 }
 
 /// You should only run search requests while holding this permit.
 /// Once it's dropped, a new search request will be able to process.
 #[derive(Debug)]
 pub struct Permit {
-    sender: mpsc::Sender<()>,
+    slots: usize,
+    sender: mpsc::Sender<usize>,
 }
 
 impl Drop for Permit {
     fn drop(&mut self) {
         // if the channel is closed then the whole instance is down
-        let _ = futures::executor::block_on(self.sender.send(()));
+        let _ = futures::executor::block_on(self.sender.send(self.slots));
     }
 }
 
@@ -52,7 +68,7 @@ impl SearchQueue {
         let (sender, receiver) = mpsc::channel(1);
 
         tokio::task::spawn(Self::run(capacity, paralellism, receiver));
-        Self { sender, capacity }
+        Self { sender, capacity, parallelism: paralellism }
     }
 
     /// This function is the main loop, it's in charge on scheduling which search request should execute first and
@@ -62,11 +78,10 @@ impl SearchQueue {
     async fn run(
         capacity: usize,
         parallelism: NonZeroUsize,
-        mut receive_new_searches: mpsc::Receiver<oneshot::Sender<Permit>>,
+        mut receive_new_searches: mpsc::Receiver<SearchRequest>,
     ) {
-        let mut queue: Vec<oneshot::Sender<Permit>> = Default::default();
-        let mut rng: StdRng = StdRng::from_entropy();
-        let mut searches_running: usize = 0;
+        let mut queue: VecDeque<SearchRequest> = Default::default();
+        let mut slots_used: usize = 0;
         // By having a capacity of parallelism we ensures that every time a search finish it can release its RAM asap
         let (sender, mut search_finished) = mpsc::channel(parallelism.into());
 
@@ -74,47 +89,66 @@ impl SearchQueue {
             tokio::select! {
                 // biased select because we wants to free up space before trying to register new tasks
                 biased;
-                _ = search_finished.recv() => {
-                    searches_running = searches_running.saturating_sub(1);
-                    if !queue.is_empty() {
-                        // Can't panic: the queue wasn't empty thus the range isn't empty.
-                        let remove = rng.gen_range(0..queue.len());
-                        let channel = queue.swap_remove(remove);
-                        let _ = channel.send(Permit { sender: sender.clone() });
-                    }
+                slots = search_finished.recv() => {
+                    // this unwrap is safe because we hold a sender of the channel
+                    slots_used = slots_used.saturating_sub(slots.unwrap());
                 },
 
                 search_request = receive_new_searches.recv() => {
                     // this unwrap is safe because we're sure the `SearchQueue` still lives somewhere in actix-web
                     let search_request = search_request.unwrap();
-                    if searches_running < usize::from(parallelism) && queue.is_empty() {
-                        searches_running += 1;
-                        // if the search requests die it's not a hard error on our side
-                        let _ = search_request.send(Permit { sender: sender.clone() });
-                        continue;
-                    } else if capacity == 0 {
-                        // in the very specific case where we have a capacity of zero
-                        // we must refuse the request straight away without going through
-                        // the queue stuff.
-                        drop(search_request);
-                        continue;
-
+                    // The clients that gave up on their search don't need to keep their place in the queue.
+                    queue.retain(|request| !request.permit.is_closed());
+                    if queue.is_empty() && slots_used + search_request.slots <= usize::from(parallelism) {
+                        // it will be processed straight away, even with a capacity of zero
+                        queue.push_back(search_request);
                     } else if queue.len() >= capacity {
-                        let remove = rng.gen_range(0..queue.len());
-                        let thing = queue.swap_remove(remove); // this will drop the channel and notify the search that it won't be processed
-                        drop(thing);
+                        // this will drop the channel and notify the search that it won't be processed
+                        drop(search_request);
+                        MEILISEARCH_SEARCH_REQUESTS_REJECTED.inc();
+                    } else {
+                        queue.push_back(search_request);
                     }
-                    queue.push(search_request);
                 },
             }
+
+            // The requests are processed in order: a request that needs more slots than the available
+            // ones blocks the ones behind it, so it's never starved by the requests needing fewer slots.
+            while let Some(request) = queue.front() {
+                if slots_used + request.slots > usize::from(parallelism) {
+                    break;
+                }
+                // Can't panic: we just checked that the queue isn't empty.
+                let request = queue.pop_front().unwrap();
+                slots_used += request.slots;
+                // if the search request died, the permit is dropped and sends back its slots
+                let _ =
+                    request.permit.send(Permit { slots: request.slots, sender: sender.clone() });
+            }
+
+            MEILISEARCH_SEARCHES_RUNNING.set(slots_used as i64);
+            MEILISEARCH_SEARCHES_WAITING_TO_BE_PROCESSED.set(queue.len() as i64);
         }
     }
 
     /// Returns a search `Permit`.
     /// It should be dropped as soon as you've freed all the RAM associated with the search request being processed.
     pub async fn try_get_search_permit(&self) -> Result<Permit, MeilisearchHttpError> {
+        self.try_get_search_permits(1).await
+    }
+
+    /// Returns a `Permit` counting for `searches` searches, for the requests that run several searches.
+    /// It can't count for more searches than the number of searches that can run in parallel.
+    pub async fn try_get_search_permits(
+        &self,
+        searches: usize,
+    ) -> Result<Permit, MeilisearchHttpError> {
+        let slots = searches.clamp(1, self.parallelism.get());
         let (sender, receiver) = oneshot::channel();
-        self.sender.send(sender).await.map_err(|_| MeilisearchHttpError::SearchLimiterIsDown)?;
+        self.sender
+            .send(SearchRequest { slots, permit: sender })
+            .await
+            .map_err(|_| MeilisearchHttpError::SearchLimiterIsDown)?;
         receiver.await.map_err(|_| MeilisearchHttpError::TooManySearchRequests(self.capacity))
     }
 
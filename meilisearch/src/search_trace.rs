@@ -0,0 +1,89 @@
+//! This file implements the search traces written in the `--experimental-search-trace-dir` directory.
+//!
+//! Every search made on a single index writes a JSON file with the index, the query, and the events of
+//! the bucket sort recorded by milli. The files are named after the time of the search, the oldest ones
+//! are deleted once there are more than `--experimental-search-trace-max-files` of them.
+//! Nothing is kept in memory, so that the workers writing the traces at the same time don't have to
+//! share anything but the directory.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use meilisearch_types::milli::SearchTrace;
+use serde_json::{json, Value};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::option::Opt;
+
+#[derive(Debug, Clone)]
+pub struct SearchTraces {
+    dir: Option<PathBuf>,
+    max_files: usize,
+}
+
+impl SearchTraces {
+    pub fn new(opt: &Opt) -> Self {
+        Self {
+            dir: opt.experimental_search_trace_dir.clone(),
+            max_files: opt.experimental_search_trace_max_files,
+        }
+    }
+
+    /// Whether the searches must record their trace.
+    pub fn enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    /// Writes the trace of a search, a failure is only logged as it must not fail the search.
+    pub fn write(&self, index_uid: &str, query: Value, trace: Option<SearchTrace>) {
+        let (Some(dir), Some(trace)) = (&self.dir, trace) else { return };
+        if let Err(error) = self.try_write(dir, index_uid, query, trace) {
+            tracing::warn!(%error, dir = %dir.display(), "Could not write the search trace");
+        }
+    }
+
+    fn try_write(
+        &self,
+        dir: &Path,
+        index_uid: &str,
+        query: Value,
+        trace: SearchTrace,
+    ) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let now = OffsetDateTime::now_utc().unix_timestamp_nanos();
+        let path = dir.join(format!("{now:020}-{}.json", Uuid::new_v4()));
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(
+            &mut writer,
+            &json!({ "indexUid": index_uid, "query": query, "trace": trace }),
+        )?;
+        writer.flush()?;
+        self.delete_oldest(dir)
+    }
+
+    /// Deletes the oldest traces of the directory until there are at most `max_files` of them.
+    fn delete_oldest(&self, dir: &Path) -> io::Result<()> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(false, |extension| extension == "json") {
+                paths.push(path);
+            }
+        }
+        if paths.len() <= self.max_files {
+            return Ok(());
+        }
+
+        paths.sort_unstable();
+        for path in &paths[..paths.len() - self.max_files] {
+            match fs::remove_file(path) {
+                // another worker already deleted it
+                Err(error) if error.kind() == ErrorKind::NotFound => (),
+                result => result?,
+            }
+        }
+        Ok(())
+    }
+}
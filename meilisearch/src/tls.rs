@@ -0,0 +1,266 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use parking_lot::RwLock;
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use time::OffsetDateTime;
+
+use crate::option::{load_certs, load_ocsp, load_private_key};
+
+/// How often the certificate files are checked for a change.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The certificate presented by the server, reloaded when its files change on disk.
+///
+/// The certificate and its key are swapped together, the connections already established
+/// keep the certificate they were opened with and the new ones use the reloaded one.
+pub struct TlsCertificate {
+    files: CertificateFiles,
+    loaded: RwLock<LoadedCertificate>,
+}
+
+struct CertificateFiles {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    ocsp_path: Option<PathBuf>,
+}
+
+struct LoadedCertificate {
+    key: Arc<CertifiedKey>,
+    not_after: OffsetDateTime,
+    /// The modification times of the files the certificate was read from.
+    modified: Vec<Option<SystemTime>>,
+}
+
+impl TlsCertificate {
+    pub fn load(
+        cert_path: PathBuf,
+        key_path: PathBuf,
+        ocsp_path: Option<PathBuf>,
+    ) -> anyhow::Result<Self> {
+        let files = CertificateFiles { cert_path, key_path, ocsp_path };
+        let loaded = files.read(files.modified())?;
+        Ok(Self { files, loaded: RwLock::new(loaded) })
+    }
+
+    /// The expiration date of the certificate currently presented.
+    pub fn not_after(&self) -> OffsetDateTime {
+        self.loaded.read().not_after
+    }
+
+    /// Reloads the certificate if one of its files was modified since it was last read.
+    ///
+    /// A replacement that can't be read is logged and the current certificate is kept,
+    /// the files are read again on their next modification.
+    pub fn reload_if_modified(&self) -> bool {
+        let modified = self.files.modified();
+        if self.loaded.read().modified == modified {
+            return false;
+        }
+
+        match self.files.read(modified.clone()) {
+            Ok(loaded) => {
+                tracing::info!(
+                    not_after = %loaded.not_after,
+                    "Reloaded the TLS certificate from {}",
+                    self.files.cert_path.display()
+                );
+                *self.loaded.write() = loaded;
+                true
+            }
+            Err(error) => {
+                tracing::error!(
+                    %error,
+                    "Could not reload the TLS certificate from {}, keeping the current one",
+                    self.files.cert_path.display()
+                );
+                self.loaded.write().modified = modified;
+                false
+            }
+        }
+    }
+
+    /// Polls the certificate files in the background for the lifetime of the process.
+    pub fn watch(self: Arc<Self>) -> std::io::Result<()> {
+        std::thread::Builder::new().name(String::from("tls-certificate-reloader")).spawn(
+            move || loop {
+                std::thread::sleep(RELOAD_INTERVAL);
+                self.reload_if_modified();
+            },
+        )?;
+        Ok(())
+    }
+}
+
+impl CertificateFiles {
+    fn read(&self, modified: Vec<Option<SystemTime>>) -> anyhow::Result<LoadedCertificate> {
+        let certs = load_certs(self.cert_path.clone())?;
+        let Some(leaf) = certs.first() else {
+            anyhow::bail!("the certificate file doesn't contain any certificate");
+        };
+        let not_after = not_after(&leaf.0)?;
+        let key = load_private_key(self.key_path.clone())?;
+        let key = rustls::sign::any_supported_type(&key)
+            .map_err(|_| anyhow::anyhow!("bad certificates/private key"))?;
+        let ocsp = load_ocsp(&self.ocsp_path)?;
+
+        let mut key = CertifiedKey::new(certs, key);
+        if !ocsp.is_empty() {
+            key.ocsp = Some(ocsp);
+        }
+
+        Ok(LoadedCertificate { key: Arc::new(key), not_after, modified })
+    }
+
+    fn modified(&self) -> Vec<Option<SystemTime>> {
+        fn modified(path: &Path) -> Option<SystemTime> {
+            fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+        }
+
+        let mut paths = vec![self.cert_path.as_path(), self.key_path.as_path()];
+        paths.extend(self.ocsp_path.as_deref());
+        paths.into_iter().map(modified).collect()
+    }
+}
+
+impl ResolvesServerCert for TlsCertificate {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        Some(self.loaded.read().key.clone())
+    }
+}
+
+fn not_after(der: &[u8]) -> anyhow::Result<OffsetDateTime> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der)
+        .map_err(|_| anyhow::anyhow!("cannot parse the certificate"))?;
+    Ok(cert.validity().not_after.to_datetime())
+}
+
+#[cfg(test)]
+mod test {
+    use rustls::{
+        Certificate, ClientConfig, ClientConnection, Connection, RootCertStore, ServerConfig,
+        ServerConnection,
+    };
+
+    use super::*;
+
+    struct SelfSigned {
+        der: Vec<u8>,
+        cert_pem: String,
+        key_pem: String,
+    }
+
+    fn self_signed() -> SelfSigned {
+        let cert = rcgen::generate_simple_self_signed(vec![String::from("localhost")]).unwrap();
+        SelfSigned {
+            der: cert.serialize_der().unwrap(),
+            cert_pem: cert.serialize_pem().unwrap(),
+            key_pem: cert.serialize_private_key_pem(),
+        }
+    }
+
+    /// Runs a handshake in memory with a client only trusting the given certificate,
+    /// and returns the certificates presented by the server.
+    fn handshake(
+        certificate: Arc<TlsCertificate>,
+        trusted: &SelfSigned,
+    ) -> Result<Vec<Certificate>, rustls::Error> {
+        let server_config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_cert_resolver(certificate);
+        let mut roots = RootCertStore::empty();
+        roots.add(&Certificate(trusted.der.clone())).unwrap();
+        let client_config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+        let mut client = Connection::from(ClientConnection::new(
+            Arc::new(client_config),
+            "localhost".try_into().unwrap(),
+        )?);
+        let mut server = Connection::from(ServerConnection::new(Arc::new(server_config))?);
+        while client.is_handshaking() || server.is_handshaking() {
+            transfer(&mut client, &mut server)?;
+            transfer(&mut server, &mut client)?;
+        }
+
+        let Connection::Client(client) = client else { unreachable!() };
+        Ok(client.peer_certificates().unwrap().to_vec())
+    }
+
+    fn transfer(from: &mut Connection, to: &mut Connection) -> Result<(), rustls::Error> {
+        let mut buffer = Vec::new();
+        while from.wants_write() {
+            from.write_tls(&mut buffer).unwrap();
+        }
+        let mut buffer = buffer.as_slice();
+        while !buffer.is_empty() {
+            to.read_tls(&mut buffer).unwrap();
+            to.process_new_packets()?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn reload_the_modified_certificate() {
+        let dir = tempfile::tempdir().unwrap();
+        let cert_path = dir.path().join("cert.pem");
+        let key_path = dir.path().join("key.pem");
+
+        let first = self_signed();
+        fs::write(&cert_path, &first.cert_pem).unwrap();
+        fs::write(&key_path, &first.key_pem).unwrap();
+        let certificate =
+            Arc::new(TlsCertificate::load(cert_path.clone(), key_path.clone(), None).unwrap());
+        let first_not_after = certificate.not_after();
+
+        let presented = handshake(certificate.clone(), &first).unwrap();
+        assert_eq!(presented, vec![Certificate(first.der.clone())]);
+        assert!(!certificate.reload_if_modified());
+
+        // swap the files for another pair, the new handshakes present the new certificate
+        let second = self_signed();
+        fs::write(&cert_path, &second.cert_pem).unwrap();
+        fs::write(&key_path, &second.key_pem).unwrap();
+        assert!(certificate.reload_if_modified());
+
+        let presented = handshake(certificate.clone(), &second).unwrap();
+        assert_eq!(presented, vec![Certificate(second.der.clone())]);
+        handshake(certificate.clone(), &first).unwrap_err();
+        assert_eq!(certificate.not_after(), not_after(&second.der).unwrap());
+        assert_eq!(first_not_after, not_after(&first.der).unwrap());
+    }
+
+    #[test]
+    fn keep_the_certificate_when_the_replacement_is_malformed() {
+        let dir = tempfile::tempdir().unwrap();
+        let cert_path = dir.path().join("cert.pem");
+        let key_path = dir.path().join("key.pem");
+
+        let first = self_signed();
+        fs::write(&cert_path, &first.cert_pem).unwrap();
+        fs::write(&key_path, &first.key_pem).unwrap();
+        let certificate =
+            Arc::new(TlsCertificate::load(cert_path.clone(), key_path.clone(), None).unwrap());
+
+        fs::write(&cert_path, "not a certificate").unwrap();
+        fs::write(&key_path, "not a key").unwrap();
+        assert!(!certificate.reload_if_modified());
+
+        let presented = handshake(certificate.clone(), &first).unwrap();
+        assert_eq!(presented, vec![Certificate(first.der.clone())]);
+
+        // the next valid replacement is picked up
+        let second = self_signed();
+        fs::write(&cert_path, &second.cert_pem).unwrap();
+        fs::write(&key_path, &second.key_pem).unwrap();
+        assert!(certificate.reload_if_modified());
+        let presented = handshake(certificate, &second).unwrap();
+        assert_eq!(presented, vec![Certificate(second.der)]);
+    }
+}
@@ -376,7 +376,7 @@ async fn error_add_api_key_invalid_index_uids() {
     meili_snap::snapshot!(code, @"400 Bad Request");
     meili_snap::snapshot!(meili_snap::json_string!(response, { ".createdAt" => "[ignored]", ".updatedAt" => "[ignored]" }), @r###"
     {
-      "message": "Invalid value at `.indexes[0]`: `invalid index # / \\name with spaces` is not a valid index uid pattern. Index uid patterns can be an integer or a string containing only alphanumeric characters, hyphens (-), underscores (_), and optionally end with a star (*).",
+      "message": "Invalid value at `.indexes[0]`: `invalid index # / \\name with spaces` is not a valid index uid pattern: the character ` ` at position 7 is not allowed. Index uid patterns can be an integer or a string containing only alphanumeric characters, hyphens (-), underscores (_), of at most 400 bytes, and optionally end with a star (*). Try `invalid_index______name_with_spaces` instead.",
       "code": "invalid_api_key_indexes",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_api_key_indexes"
@@ -421,7 +421,7 @@ async fn error_add_api_key_invalid_parameters_actions() {
     meili_snap::snapshot!(code, @"400 Bad Request");
     meili_snap::snapshot!(meili_snap::json_string!(response, { ".createdAt" => "[ignored]", ".updatedAt" => "[ignored]" }), @r###"
     {
-      "message": "Unknown value `doc.add` at `.actions[0]`: expected one of `*`, `search`, `documents.*`, `documents.add`, `documents.get`, `documents.delete`, `indexes.*`, `indexes.create`, `indexes.get`, `indexes.update`, `indexes.delete`, `indexes.swap`, `tasks.*`, `tasks.cancel`, `tasks.delete`, `tasks.get`, `settings.*`, `settings.get`, `settings.update`, `stats.*`, `stats.get`, `metrics.*`, `metrics.get`, `dumps.*`, `dumps.create`, `snapshots.*`, `snapshots.create`, `version`, `keys.create`, `keys.get`, `keys.update`, `keys.delete`, `experimental.get`, `experimental.update`",
+      "message": "Unknown value `doc.add` at `.actions[0]`: expected one of `*`, `search`, `documents.*`, `documents.add`, `documents.get`, `documents.delete`, `indexes.*`, `indexes.create`, `indexes.get`, `indexes.update`, `indexes.delete`, `indexes.swap`, `tasks.*`, `tasks.cancel`, `tasks.delete`, `tasks.get`, `settings.*`, `settings.get`, `settings.update`, `stats.*`, `stats.get`, `metrics.*`, `metrics.get`, `dumps.*`, `dumps.create`, `snapshots.*`, `snapshots.create`, `version`, `keys.create`, `keys.get`, `keys.update`, `keys.delete`, `experimental.get`, `experimental.update`, `presets.*`, `presets.get`, `presets.update`, `indexGroups.get`, `indexGroups.update`, `relevance.check`, `search.debug`, `wordFrequency.get`, `logs.*`, `logs.get`, `logs.create`, `logs.delete`, `recentSearches.get`, `webhooks.update`, `snapshots.download`",
       "code": "invalid_api_key_actions",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_api_key_actions"
@@ -1779,3 +1779,205 @@ async fn error_access_api_key_routes_no_master_key_set() {
     "###);
     meili_snap::snapshot!(code, @"401 Unauthorized");
 }
+
+#[actix_rt::test]
+async fn import_exported_api_keys() {
+    let mut source = Server::new_auth().await;
+    source.use_api_key("MASTER_KEY");
+    for (uid, name) in [
+        ("4bc0887a-0e41-4f3b-935d-0c451dcee9c8", "indexing-key"),
+        ("9a6d3b25-7b1f-4d5e-8f0c-2d1b0e4a7c61", "search-key"),
+    ] {
+        let content = json!({
+            "name": name,
+            "uid": uid,
+            "indexes": ["products"],
+            "actions": ["search", "documents.add"],
+            "expiresAt": "2050-11-13T00:00:00Z"
+        });
+        let (response, code) = source.add_api_key(content).await;
+        assert_eq!(201, code, "{:?}", response);
+    }
+
+    let (exported, code) = source.list_api_keys("?limit=100").await;
+    assert_eq!(200, code, "{:?}", exported);
+    let exported = exported["results"].as_array().unwrap().clone();
+    assert_eq!(exported.len(), 4);
+
+    // the destination has the same master key
+    let mut destination = Server::new_auth().await;
+    destination.use_api_key("MASTER_KEY");
+    let (response, code) = destination.import_api_keys("", json!(exported)).await;
+    meili_snap::snapshot!(code, @"200 OK");
+    let results = response["results"].as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    for (result, key) in results.iter().zip(&exported) {
+        assert_eq!(result["status"], json!("created"), "{:?}", result);
+        assert_eq!(result["uid"], key["uid"]);
+        assert_eq!(result["key"]["key"], key["key"]);
+    }
+
+    // the derived values are the same, the clients keep working
+    for key in &exported {
+        let (response, code) = destination.get_api_key(key["uid"].as_str().unwrap()).await;
+        assert_eq!(200, code, "{:?}", response);
+        assert_eq!(response["key"], key["key"]);
+        assert_eq!(response["name"], key["name"]);
+        assert_eq!(response["actions"], key["actions"]);
+        assert_eq!(response["indexes"], key["indexes"]);
+        assert_eq!(response["expiresAt"], key["expiresAt"]);
+    }
+    let exported_key = |name: &str| {
+        let key = exported.iter().find(|key| key["name"] == json!(name)).unwrap();
+        key["key"].as_str().unwrap().to_string()
+    };
+    destination.use_api_key(exported_key("search-key"));
+    let (response, code) = destination.list_api_keys("").await;
+    assert_eq!(403, code, "{:?}", response);
+    destination.use_api_key(exported_key("Default Admin API Key"));
+    let (response, code) = destination.list_api_keys("").await;
+    assert_eq!(200, code, "{:?}", response);
+}
+
+#[actix_rt::test]
+async fn import_api_keys_conflicts_and_errors() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key("MASTER_KEY");
+
+    let key = json!({
+        "name": "indexing-key",
+        "uid": "4bc0887a-0e41-4f3b-935d-0c451dcee9c8",
+        "indexes": ["products"],
+        "actions": ["documents.add"],
+        "expiresAt": null
+    });
+    let (response, code) = server.add_api_key(key).await;
+    assert_eq!(201, code, "{:?}", response);
+
+    let content = json!([
+        {
+            "name": "renamed-key",
+            "uid": "4bc0887a-0e41-4f3b-935d-0c451dcee9c8",
+            "indexes": ["products"],
+            "actions": ["documents.add"],
+            "expiresAt": null
+        },
+        { "uid": "9a6d3b25-7b1f-4d5e-8f0c-2d1b0e4a7c61", "indexes": ["products"], "expiresAt": null },
+        { "indexes": ["products"], "actions": ["search"], "expiresAt": null },
+        { "uid": "2d1b0e4a-7b1f-4d5e-8f0c-9a6d3b257c61", "indexes": ["products"], "actions": ["search"], "expiresAt": null },
+    ]);
+
+    // the conflicting keys are skipped by default, the invalid ones don't abort the import
+    let (response, code) = server.import_api_keys("", content.clone()).await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response, { ".results[3].key.key" => "[key]", ".results[3].key.createdAt" => "[ignored]", ".results[3].key.updatedAt" => "[ignored]" }), @r###"
+    {
+      "results": [
+        {
+          "uid": "4bc0887a-0e41-4f3b-935d-0c451dcee9c8",
+          "status": "skipped"
+        },
+        {
+          "uid": null,
+          "status": "failed",
+          "error": {
+            "message": "Missing field `actions`",
+            "code": "missing_api_key_actions",
+            "type": "invalid_request",
+            "link": "https://docs.meilisearch.com/errors#missing_api_key_actions"
+          }
+        },
+        {
+          "uid": null,
+          "status": "failed",
+          "error": {
+            "message": "Missing field `uid`",
+            "code": "missing_api_key_uid",
+            "type": "invalid_request",
+            "link": "https://docs.meilisearch.com/errors#missing_api_key_uid"
+          }
+        },
+        {
+          "uid": "2d1b0e4a-7b1f-4d5e-8f0c-9a6d3b257c61",
+          "status": "created",
+          "key": {
+            "name": null,
+            "description": null,
+            "key": "[key]",
+            "uid": "2d1b0e4a-7b1f-4d5e-8f0c-9a6d3b257c61",
+            "actions": [
+              "search"
+            ],
+            "indexes": [
+              "products"
+            ],
+            "expiresAt": null,
+            "createdAt": "[ignored]",
+            "updatedAt": "[ignored]"
+          }
+        }
+      ]
+    }
+    "###);
+    let (response, _) = server.get_api_key("4bc0887a-0e41-4f3b-935d-0c451dcee9c8").await;
+    assert_eq!(response["name"], json!("indexing-key"));
+
+    // or overwritten
+    let (response, code) = server.import_api_keys("?onConflict=overwrite", content).await;
+    meili_snap::snapshot!(code, @"200 OK");
+    assert_eq!(response["results"][0]["status"], json!("overwritten"), "{:?}", response);
+    assert_eq!(response["results"][3]["status"], json!("overwritten"), "{:?}", response);
+    let (response, _) = server.get_api_key("4bc0887a-0e41-4f3b-935d-0c451dcee9c8").await;
+    assert_eq!(response["name"], json!("renamed-key"));
+
+    // but the overwrite can't change what the key is allowed to do
+    let content = json!([
+        {
+            "uid": "4bc0887a-0e41-4f3b-935d-0c451dcee9c8",
+            "indexes": ["*"],
+            "actions": ["*"],
+            "expiresAt": null
+        },
+        {
+            "uid": "4bc0887a-0e41-4f3b-935d-0c451dcee9c8",
+            "indexes": ["products"],
+            "actions": ["documents.add"],
+            "expiresAt": "2050-11-13T00:00:00Z"
+        },
+    ]);
+    let (response, code) = server.import_api_keys("?onConflict=overwrite", content).await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
+    {
+      "results": [
+        {
+          "uid": "4bc0887a-0e41-4f3b-935d-0c451dcee9c8",
+          "status": "failed",
+          "error": {
+            "message": "The API key `4bc0887a-0e41-4f3b-935d-0c451dcee9c8` can't be overwritten because its immutable field `actions` differs from the one of the imported key.",
+            "code": "immutable_api_key_actions",
+            "type": "invalid_request",
+            "link": "https://docs.meilisearch.com/errors#immutable_api_key_actions"
+          }
+        },
+        {
+          "uid": "4bc0887a-0e41-4f3b-935d-0c451dcee9c8",
+          "status": "failed",
+          "error": {
+            "message": "The API key `4bc0887a-0e41-4f3b-935d-0c451dcee9c8` can't be overwritten because its immutable field `expiresAt` differs from the one of the imported key.",
+            "code": "immutable_api_key_expires_at",
+            "type": "invalid_request",
+            "link": "https://docs.meilisearch.com/errors#immutable_api_key_expires_at"
+          }
+        }
+      ]
+    }
+    "###);
+    let (response, _) = server.get_api_key("4bc0887a-0e41-4f3b-935d-0c451dcee9c8").await;
+    assert_eq!(response["actions"], json!(["documents.add"]));
+    assert_eq!(response["expiresAt"], json!(null));
+
+    let (response, code) = server.import_api_keys("?onConflict=merge", json!([])).await;
+    meili_snap::snapshot!(code, @"400 Bad Request");
+    assert_eq!(response["code"], json!("invalid_api_key_on_conflict"), "{:?}", response);
+}
@@ -16,11 +16,16 @@ pub static AUTHORIZATIONS: Lazy<HashMap<(&'static str, &'static str), HashSet<&'
             ("POST",    "/multi-search") =>                                    hashset!{"search", "*"},
             ("POST",    "/indexes/products/search") =>                         hashset!{"search", "*"},
             ("GET",     "/indexes/products/search") =>                         hashset!{"search", "*"},
+            ("POST",    "/indexes/products/search/preview") =>                 hashset!{"search", "*"},
+            ("POST",    "/indexes/products/search/explain") =>                 hashset!{"search", "*"},
             ("POST",    "/indexes/products/documents") =>                      hashset!{"documents.add", "documents.*", "*"},
             ("GET",     "/indexes/products/documents") =>                      hashset!{"documents.get", "documents.*", "*"},
+            ("GET",     "/indexes/products/_debug/docid/0") =>                 hashset!{"documents.get", "documents.*", "*"},
+            ("GET",     "/indexes/products/_debug/external-id/0") =>           hashset!{"documents.get", "documents.*", "*"},
             ("POST",    "/indexes/products/documents/fetch") =>                hashset!{"documents.get", "documents.*", "*"},
             ("GET",     "/indexes/products/documents/0") =>                    hashset!{"documents.get", "documents.*", "*"},
             ("DELETE",  "/indexes/products/documents/0") =>                    hashset!{"documents.delete", "documents.*", "*"},
+            ("GET",     "/indexes/products/documents/0/tasks") =>              hashset!{"documents.get", "documents.*", "*"},
             ("POST",    "/indexes/products/documents/delete-batch") =>         hashset!{"documents.delete", "documents.*", "*"},
             ("POST",    "/indexes/products/documents/delete") =>               hashset!{"documents.delete", "documents.*", "*"},
             ("GET",     "/tasks") =>                                           hashset!{"tasks.get", "tasks.*", "*"},
@@ -53,21 +58,47 @@ pub static AUTHORIZATIONS: Lazy<HashMap<(&'static str, &'static str), HashSet<&'
             ("PUT",     "/indexes/products/settings/sortable-attributes") =>   hashset!{"settings.update", "settings.*", "*"},
             ("PUT",     "/indexes/products/settings/stop-words") =>            hashset!{"settings.update", "settings.*", "*"},
             ("PUT",     "/indexes/products/settings/synonyms") =>              hashset!{"settings.update", "settings.*", "*"},
+            ("GET",     "/indexes/products/presets") =>                        hashset!{"presets.get", "presets.*", "*"},
+            ("GET",     "/indexes/products/presets/storefront") =>             hashset!{"presets.get", "presets.*", "*"},
+            ("PUT",     "/indexes/products/presets/storefront") =>             hashset!{"presets.update", "presets.*", "*"},
+            ("DELETE",  "/indexes/products/presets/storefront") =>             hashset!{"presets.update", "presets.*", "*"},
+            ("POST",    "/indexes/products/unpark") =>                         hashset!{"indexes.update", "indexes.*", "*"},
+            ("POST",    "/indexes/products/restore") =>                        hashset!{"snapshots.create", "snapshots.*", "*"},
+            ("POST",    "/indexes/products/relevance-check") =>                hashset!{"relevance.check", "*"},
+            ("POST",    "/indexes/products/related-terms") =>                  hashset!{"search", "*"},
+            ("GET",     "/indexes/products/word-frequency") =>                 hashset!{"wordFrequency.get", "*"},
+            ("GET",     "/indexes/products/recent-searches") =>                hashset!{"recentSearches.get", "*"},
+            ("POST",    "/indexes/products/filter-assist") =>                  hashset!{"search", "*"},
+            ("POST",    "/indexes/products/check") =>                          hashset!{"indexes.update", "indexes.*", "*"},
+            ("POST",    "/indexes/products/warmup") =>                         hashset!{"indexes.update", "indexes.*", "*"},
             ("GET",     "/indexes/products/stats") =>                          hashset!{"stats.get", "stats.*", "*"},
+            ("GET",     "/indexes/products/timeline") =>                       hashset!{"stats.get", "stats.*", "*"},
             ("GET",     "/stats") =>                                           hashset!{"stats.get", "stats.*", "*"},
             ("POST",    "/dumps") =>                                           hashset!{"dumps.create", "dumps.*", "*"},
             ("POST",    "/snapshots") =>                                       hashset!{"snapshots.create", "snapshots.*", "*"},
+            ("GET",     "/snapshots/latest") =>                                hashset!{"snapshots.download", "*"},
+            ("POST",    "/snapshots/import") =>                                hashset!{"snapshots.create", "snapshots.*", "*"},
             ("GET",     "/version") =>                                         hashset!{"version", "*"},
             ("GET",     "/metrics") =>                                         hashset!{"metrics.get", "metrics.*", "*"},
-            ("POST",    "/logs/stream") =>                                     hashset!{"metrics.get", "metrics.*", "*"},
-            ("DELETE",  "/logs/stream") =>                                     hashset!{"metrics.get", "metrics.*", "*"},
+            ("POST",    "/logs/stream") =>                                     hashset!{"logs.get", "logs.*", "*"},
+            ("PATCH",   "/logs/stream") =>                                     hashset!{"logs.create", "logs.*", "*"},
+            ("DELETE",  "/logs/stream") =>                                     hashset!{"logs.delete", "logs.*", "*"},
+            ("POST",    "/logs/stderr") =>                                     hashset!{"logs.create", "logs.*", "*"},
+            ("DELETE",  "/logs/stderr") =>                                     hashset!{"logs.delete", "logs.*", "*"},
             ("PATCH",   "/keys/mykey/") =>                                     hashset!{"keys.update", "*"},
             ("GET",     "/keys/mykey/") =>                                     hashset!{"keys.get", "*"},
             ("DELETE",  "/keys/mykey/") =>                                     hashset!{"keys.delete", "*"},
             ("POST",    "/keys") =>                                            hashset!{"keys.create", "*"},
+            ("POST",    "/keys/import") =>                                     hashset!{"keys.create", "*"},
             ("GET",     "/keys") =>                                            hashset!{"keys.get", "*"},
             ("GET",     "/experimental-features") =>                           hashset!{"experimental.get", "*"},
             ("PATCH",   "/experimental-features") =>                           hashset!{"experimental.update", "*"},
+            ("GET",     "/webhook") =>                                         hashset!{"tasks.get", "tasks.*", "*"},
+            ("PATCH",   "/webhook") =>                                         hashset!{"webhooks.update", "*"},
+            ("GET",     "/index-groups") =>                                    hashset!{"indexGroups.get", "*"},
+            ("GET",     "/index-groups/tenants") =>                            hashset!{"indexGroups.get", "*"},
+            ("PUT",     "/index-groups/tenants") =>                            hashset!{"indexGroups.update", "*"},
+            ("DELETE",  "/index-groups/tenants") =>                            hashset!{"indexGroups.update", "*"},
         };
 
         authorizations
@@ -1035,3 +1066,164 @@ async fn error_creating_index_without_index() {
     let (response, code) = index.create(None).await;
     assert_eq!(403, code, "{:?}", &response);
 }
+
+#[actix_rt::test]
+async fn search_debug_requires_the_action() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key(MASTER_KEY);
+
+    let index = server.index("products");
+    let (response, code) =
+        index.add_documents(json!([{ "id": 1, "title": "hidden world" }]), None).await;
+    assert_eq!(202, code, "{:?}", &response);
+    index.wait_task(response.uid()).await;
+
+    let content = json!({
+        "indexes": ["products"],
+        "actions": ["search"],
+        "expiresAt": "2050-11-13T00:00:00Z"
+    });
+    let (response, code) = server.add_api_key(content).await;
+    assert_eq!(201, code, "{:?}", &response);
+    let search_key = response["key"].as_str().unwrap().to_string();
+
+    let content = json!({
+        "indexes": ["products"],
+        "actions": ["search", "search.debug"],
+        "expiresAt": "2050-11-13T00:00:00Z"
+    });
+    let (response, code) = server.add_api_key(content).await;
+    assert_eq!(201, code, "{:?}", &response);
+    let debug_key = response["key"].as_str().unwrap().to_string();
+
+    server.use_api_key(&search_key);
+    let index = server.index("products");
+    let (response, code) =
+        index.search_post(json!({ "q": "world", "debug": { "queryGraph": true } })).await;
+    meili_snap::snapshot!(code, @"403 Forbidden");
+    meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
+    {
+      "message": "The provided API key is not allowed to use the `debug` search parameter, it requires the `search.debug` action.",
+      "code": "invalid_api_key",
+      "type": "auth",
+      "link": "https://docs.meilisearch.com/errors#invalid_api_key"
+    }
+    "###);
+    let (response, code) =
+        index.search_post(json!({ "q": "world", "debug": { "bucketStats": true } })).await;
+    assert_eq!(403, code, "{:?}", &response);
+    let (response, code) = index.search_post(json!({ "q": "world" })).await;
+    assert_eq!(200, code, "{:?}", &response);
+    assert!(response.get("_debug").is_none(), "{:?}", &response);
+
+    server.use_api_key(&debug_key);
+    let index = server.index("products");
+    let (response, code) =
+        index.search_post(json!({ "q": "world", "debug": { "queryGraph": true } })).await;
+    assert_eq!(200, code, "{:?}", &response);
+    assert!(response["_debug"]["queryGraph"]["graphviz"].is_string(), "{:?}", &response);
+
+    let (response, code) =
+        index.search_post(json!({ "q": "world", "debug": { "bucketStats": true } })).await;
+    assert_eq!(200, code, "{:?}", &response);
+    assert!(response["_debug"]["bucketStats"].is_array(), "{:?}", &response);
+
+    server.use_api_key(MASTER_KEY);
+    let index = server.index("products");
+    let (response, code) =
+        index.search_post(json!({ "q": "world", "debug": { "queryGraph": true } })).await;
+    assert_eq!(200, code, "{:?}", &response);
+    assert!(response["_debug"]["queryGraph"]["graphviz"].is_string(), "{:?}", &response);
+}
+
+#[actix_rt::test]
+async fn word_frequency_requires_the_action() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key(MASTER_KEY);
+    let (_response, code) = server.set_features(json!({ "wordFrequency": true })).await;
+    assert_eq!(200, code);
+
+    let index = server.index("products");
+    let (response, code) =
+        index.add_documents(json!([{ "id": 1, "title": "hidden world" }]), None).await;
+    assert_eq!(202, code, "{:?}", &response);
+    index.wait_task(response.uid()).await;
+
+    let content = json!({
+        "indexes": ["products"],
+        "actions": ["search"],
+        "expiresAt": "2050-11-13T00:00:00Z"
+    });
+    let (response, code) = server.add_api_key(content).await;
+    assert_eq!(201, code, "{:?}", &response);
+    let search_key = response["key"].as_str().unwrap().to_string();
+
+    let content = json!({
+        "indexes": ["products"],
+        "actions": ["wordFrequency.get"],
+        "expiresAt": "2050-11-13T00:00:00Z"
+    });
+    let (response, code) = server.add_api_key(content).await;
+    assert_eq!(201, code, "{:?}", &response);
+    let word_frequency_key = response["key"].as_str().unwrap().to_string();
+
+    server.use_api_key(&search_key);
+    let (response, code) = server.service.get("/indexes/products/word-frequency").await;
+    assert_eq!(403, code, "{:?}", &response);
+
+    server.use_api_key(&word_frequency_key);
+    let (response, code) = server.service.get("/indexes/products/word-frequency?prefix=wor").await;
+    assert_eq!(200, code, "{:?}", &response);
+    assert_eq!(response["words"], json!([{ "word": "world", "documents": 1 }]));
+
+    // the key is restricted to its indexes
+    let (response, code) = server.service.get("/indexes/doggos/word-frequency").await;
+    assert_eq!(403, code, "{:?}", &response);
+}
+
+#[actix_rt::test]
+async fn logs_require_the_logs_actions() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key(MASTER_KEY);
+    let (_response, code) = server.set_features(json!({ "logsRoute": true })).await;
+    assert_eq!(200, code);
+
+    let content = json!({
+        "indexes": ["*"],
+        "actions": ["metrics.get"],
+        "expiresAt": "2050-11-13T00:00:00Z"
+    });
+    let (response, code) = server.add_api_key(content).await;
+    assert_eq!(201, code, "{:?}", &response);
+    let metrics_key = response["key"].as_str().unwrap().to_string();
+
+    let content = json!({
+        "indexes": ["*"],
+        "actions": ["logs.*"],
+        "expiresAt": "2050-11-13T00:00:00Z"
+    });
+    let (response, code) = server.add_api_key(content).await;
+    assert_eq!(201, code, "{:?}", &response);
+    let logs_key = response["key"].as_str().unwrap().to_string();
+
+    server.use_api_key(&metrics_key);
+    let (response, code) = server.service.post("/logs/stderr", json!({})).await;
+    assert_eq!(403, code, "{:?}", &response);
+    assert_eq!(response["code"], "invalid_api_key", "{:?}", &response);
+
+    server.use_api_key(&logs_key);
+    let (response, code) = server.service.post("/logs/stderr", json!({ "target": "debug" })).await;
+    assert_eq!(204, code, "{:?}", &response);
+    let (response, code) = server.service.delete("/logs/stderr").await;
+    assert_eq!(204, code, "{:?}", &response);
+
+    // the key can't read the documents
+    let (response, code) = server.service.get("/indexes").await;
+    assert_eq!(403, code, "{:?}", &response);
+    let (response, code) = server.service.get("/indexes/products/documents").await;
+    assert_eq!(403, code, "{:?}", &response);
+
+    server.use_api_key(MASTER_KEY);
+    let (response, code) = server.service.post("/logs/stderr", json!({})).await;
+    assert_eq!(204, code, "{:?}", &response);
+}
@@ -0,0 +1,262 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::{Server, Value};
+use crate::json;
+
+const MASTER_KEY: &str = "MASTER_KEY";
+
+fn ids(hits: &Value) -> Vec<u64> {
+    let mut ids: Vec<_> =
+        hits.as_array().unwrap().iter().map(|hit| hit["id"].as_u64().unwrap()).collect();
+    ids.sort_unstable();
+    ids
+}
+
+async fn server_with_tenant_key() -> (Server, String) {
+    let mut server = Server::new_auth().await;
+    server.use_api_key(MASTER_KEY);
+
+    let index = server.index("products");
+    let (task, code) =
+        index.update_settings(json!({ "filterableAttributes": ["tenant", "color"] })).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(task.uid()).await;
+    let documents = json!([
+        { "id": 1, "tenant": 42, "color": "red" },
+        { "id": 2, "tenant": 42, "color": "blue" },
+        { "id": 3, "tenant": 7, "color": "red" },
+    ]);
+    let (task, code) = index.add_documents(documents, Some("id")).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(task.uid()).await;
+
+    let content = json!({
+        "indexes": ["products"],
+        "actions": ["search", "documents.get"],
+        "defaultFilters": { "products": "tenant = 42" },
+        "expiresAt": null,
+    });
+    let (response, code) = server.add_api_key(content).await;
+    snapshot!(code, @"201 Created");
+    snapshot!(json_string!(response["defaultFilters"]), @r###"
+    {
+      "products": "tenant = 42"
+    }
+    "###);
+    let key = response["key"].as_str().unwrap().to_string();
+
+    (server, key)
+}
+
+#[actix_rt::test]
+async fn default_filter_restricts_the_searches() {
+    let (mut server, key) = server_with_tenant_key().await;
+    server.use_api_key(&key);
+    let index = server.index("products");
+
+    let (response, code) = index.search_post(json!({})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response["hits"])), @"[1, 2]");
+
+    // the filter of the request is AND-ed to the default filter, not replacing it
+    let (response, code) = index.search_post(json!({ "filter": "color = red" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response["hits"])), @"[1]");
+    let (response, code) = index.search_post(json!({ "filter": "tenant = 7" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response["hits"])), @"[]");
+
+    let (response, code) = index.facet_search(json!({ "facetName": "color" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetHits"]), @r###"
+    [
+      {
+        "value": "blue",
+        "count": 1
+      },
+      {
+        "value": "red",
+        "count": 1
+      }
+    ]
+    "###);
+
+    let (response, code) = server
+        .multi_search(json!({ "queries": [{ "indexUid": "products", "filter": "color = red" }] }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response["results"][0]["hits"])), @"[1]");
+}
+
+#[actix_rt::test]
+async fn default_filter_restricts_the_documents() {
+    let (mut server, key) = server_with_tenant_key().await;
+    server.use_api_key(&key);
+    let index = server.index("products");
+
+    let (response, code) = index.get_all_documents_raw("").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response["results"])), @"[1, 2]");
+    snapshot!(response["total"], @"2");
+
+    let (response, code) = index.get_document_by_filter(json!({ "filter": "color = red" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response["results"])), @"[1]");
+
+    let (_response, code) = index.get_document(1, None).await;
+    snapshot!(code, @"200 OK");
+    // a document of another tenant is as if it didn't exist
+    let (response, code) = index.get_document(3, None).await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(json_string!(response["code"]), @r###""document_not_found""###);
+
+    // the master key isn't restricted
+    server.use_api_key(MASTER_KEY);
+    let (response, code) = index.get_all_documents_raw("").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response["results"])), @"[1, 2, 3]");
+}
+
+#[actix_rt::test]
+async fn default_filter_restricts_the_filter_suggestions() {
+    let (mut server, key) = server_with_tenant_key().await;
+    server.use_api_key(&key);
+
+    let (response, code) = server
+        .service
+        .post("/indexes/products/filter-assist", json!({ "filter": "color = " }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["suggestions"]), @r###"
+    [
+      {
+        "text": "blue",
+        "count": 1
+      },
+      {
+        "text": "red",
+        "count": 1
+      }
+    ]
+    "###);
+
+    // the master key isn't restricted
+    server.use_api_key(MASTER_KEY);
+    let (response, code) = server
+        .service
+        .post("/indexes/products/filter-assist", json!({ "filter": "color = r" }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["suggestions"]), @r###"
+    [
+      {
+        "text": "red",
+        "count": 2
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn default_filter_restricts_the_related_terms() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key(MASTER_KEY);
+
+    let index = server.index("articles");
+    let (task, _code) = index.update_settings(json!({ "filterableAttributes": ["tenant"] })).await;
+    index.wait_task(task.uid()).await;
+    let documents = json!([
+        { "id": 1, "tenant": 42, "title": "running shoes" },
+        { "id": 2, "tenant": 42, "title": "running shoes" },
+        { "id": 3, "tenant": 7, "title": "running socks" },
+    ]);
+    let (task, _code) = index.add_documents(documents, Some("id")).await;
+    index.wait_task(task.uid()).await;
+
+    let content = json!({
+        "indexes": ["articles"],
+        "actions": ["search"],
+        "defaultFilters": { "articles": "tenant = 42" },
+        "expiresAt": null,
+    });
+    let (response, code) = server.add_api_key(content).await;
+    snapshot!(code, @"201 Created");
+    let key = response["key"].as_str().unwrap().to_string();
+
+    server.use_api_key(&key);
+    let (response, code) =
+        server.service.post("/indexes/articles/related-terms", json!({ "word": "running" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["relatedTerms"]), @r###"
+    [
+      {
+        "term": "shoes",
+        "documents": 2
+      }
+    ]
+    "###);
+
+    // the terms cached for the key are not returned to the master key
+    server.use_api_key(MASTER_KEY);
+    let (response, code) =
+        server.service.post("/indexes/articles/related-terms", json!({ "word": "running" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["relatedTerms"]), @r###"
+    [
+      {
+        "term": "shoes",
+        "documents": 2
+      },
+      {
+        "term": "socks",
+        "documents": 1
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn invalid_default_filters() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key(MASTER_KEY);
+
+    let content = json!({
+        "indexes": ["products"],
+        "actions": ["search"],
+        "defaultFilters": { "products": "tenant = " },
+        "expiresAt": null,
+    });
+    let (response, code) = server.add_api_key(content).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response["code"]), @r###""invalid_api_key_default_filters""###);
+
+    let content = json!({
+        "indexes": ["products"],
+        "actions": ["search"],
+        "defaultFilters": { "products": 42 },
+        "expiresAt": null,
+    });
+    let (response, code) = server.add_api_key(content).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response["code"]), @r###""invalid_api_key_default_filters""###);
+
+    let content = json!({
+        "indexes": ["products"],
+        "actions": ["search"],
+        "expiresAt": null,
+    });
+    let (response, code) = server.add_api_key(content).await;
+    snapshot!(code, @"201 Created");
+    let uid = response["uid"].as_str().unwrap();
+    let (response, code) =
+        server.patch_api_key(uid, json!({ "defaultFilters": { "products": "tenant = 42" } })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Immutable field `defaultFilters`: expected one of `description`, `name`",
+      "code": "immutable_api_key_default_filters",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#immutable_api_key_default_filters"
+    }
+    "###);
+}
@@ -0,0 +1,229 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::Server;
+use crate::json;
+
+const MASTER_KEY: &str = "MASTER_KEY";
+
+async fn server_with_tenants() -> Server {
+    let mut server = Server::new_auth().await;
+    server.use_api_key(MASTER_KEY);
+
+    for (uid, documents) in [
+        ("tenant_1_products", json!([{ "id": 1 }, { "id": 2 }])),
+        ("tenant_2_products", json!([{ "id": 1 }])),
+        ("products", json!([{ "id": 1 }])),
+    ] {
+        let index = server.index(uid);
+        let (task, code) = index.add_documents(documents, Some("id")).await;
+        snapshot!(code, @"202 Accepted");
+        index.wait_task(task.uid()).await;
+    }
+
+    let (response, code) =
+        server.service.put("/index-groups/tenants", json!({ "indexes": ["tenant_*"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "name": "tenants",
+      "indexes": [
+        "tenant_*"
+      ]
+    }
+    "###);
+
+    server
+}
+
+#[actix_rt::test]
+async fn key_scoped_to_index_group() {
+    let mut server = server_with_tenants().await;
+
+    let content = json!({
+        "indexes": [],
+        "indexGroups": ["tenants"],
+        "actions": ["search", "stats.get", "tasks.get"],
+        "expiresAt": null,
+    });
+    let (response, code) = server.add_api_key(content).await;
+    snapshot!(code, @"201 Created");
+    snapshot!(json_string!(response["indexGroups"]), @r###"
+    [
+      "tenants"
+    ]
+    "###);
+    let key = response["key"].as_str().unwrap().to_string();
+    server.use_api_key(&key);
+
+    let (_response, code) = server.index("tenant_1_products").search_post(json!({})).await;
+    snapshot!(code, @"200 OK");
+    let (_response, code) = server.index("tenant_2_products").search_post(json!({})).await;
+    snapshot!(code, @"200 OK");
+    let (response, code) = server.index("products").search_post(json!({})).await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The provided API key is invalid.",
+      "code": "invalid_api_key",
+      "type": "auth",
+      "link": "https://docs.meilisearch.com/errors#invalid_api_key"
+    }
+    "###);
+
+    // the stats only contain the indexes of the group
+    let (response, code) = server.stats().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["indexes"].as_object().unwrap().keys().collect::<Vec<_>>()), @r###"
+    [
+      "tenant_1_products",
+      "tenant_2_products"
+    ]
+    "###);
+
+    let (response, code) = server.tasks_filter("indexGroups=tenants").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["results"].as_array().unwrap().iter().map(|task| &task["indexUid"]).collect::<Vec<_>>()), @r###"
+    [
+      "tenant_2_products",
+      "tenant_1_products"
+    ]
+    "###);
+
+    // once the group is modified, the key follows its new patterns
+    server.use_api_key(MASTER_KEY);
+    let (_response, code) = server
+        .service
+        .put("/index-groups/tenants", json!({ "indexes": ["tenant_1_products"] }))
+        .await;
+    snapshot!(code, @"200 OK");
+    server.use_api_key(&key);
+    let (_response, code) = server.index("tenant_1_products").search_post(json!({})).await;
+    snapshot!(code, @"200 OK");
+    let (_response, code) = server.index("tenant_2_products").search_post(json!({})).await;
+    snapshot!(code, @"403 Forbidden");
+
+    // and loses the access the group granted once it is deleted
+    server.use_api_key(MASTER_KEY);
+    let (_response, code) = server.service.delete("/index-groups/tenants").await;
+    snapshot!(code, @"204 No Content");
+    server.use_api_key(&key);
+    let (_response, code) = server.index("tenant_1_products").search_post(json!({})).await;
+    snapshot!(code, @"403 Forbidden");
+}
+
+#[actix_rt::test]
+async fn stats_of_index_group() {
+    let server = server_with_tenants().await;
+
+    let (response, code) = server.service.get("/stats?group=tenants").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["group"]), @r###"
+    {
+      "name": "tenants",
+      "numberOfIndexes": 2,
+      "numberOfDocuments": 3
+    }
+    "###);
+    snapshot!(json_string!(response["indexes"].as_object().unwrap().keys().collect::<Vec<_>>()), @r###"
+    [
+      "tenant_1_products",
+      "tenant_2_products"
+    ]
+    "###);
+
+    // without a group the stats are not aggregated
+    let (response, code) = server.stats().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["group"]), @"null");
+    snapshot!(response["indexes"].as_object().unwrap().len(), @"3");
+
+    let (response, code) = server.service.get("/index-groups").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "results": [
+        {
+          "name": "tenants",
+          "indexes": [
+            "tenant_*"
+          ]
+        }
+      ]
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn error_index_group_not_found() {
+    let server = server_with_tenants().await;
+
+    let (response, code) = server.service.get("/index-groups/doggos").await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Index group `doggos` not found.",
+      "code": "index_group_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#index_group_not_found"
+    }
+    "###);
+
+    let (response, code) = server.service.get("/stats?group=doggos").await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(response["code"], @r###""index_group_not_found""###);
+
+    let (response, code) = server.tasks_filter("indexGroups=doggos").await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(response["code"], @r###""index_group_not_found""###);
+
+    let content = json!({
+        "indexes": [],
+        "indexGroups": ["doggos"],
+        "actions": ["search"],
+        "expiresAt": null,
+    });
+    let (response, code) = server.add_api_key(content).await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Index group `doggos` not found.",
+      "code": "index_group_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#index_group_not_found"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn error_bad_index_group() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key(MASTER_KEY);
+
+    let (response, code) =
+        server.service.put("/index-groups/doggo%20s", json!({ "indexes": ["doggos"] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`doggo s` is not a valid index group name. Index group name can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_).",
+      "code": "invalid_index_group_name",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_index_group_name"
+    }
+    "###);
+
+    let (response, code) = server.service.put("/index-groups/doggos", json!({})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `indexes`",
+      "code": "missing_index_group_indexes",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#missing_index_group_indexes"
+    }
+    "###);
+
+    let (response, code) =
+        server.service.put("/index-groups/doggos", json!({ "indexes": ["doggo s"] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_index_group_indexes""###);
+}
@@ -1,6 +1,8 @@
 mod api_keys;
 mod authorization;
+mod default_filters;
 mod errors;
+mod index_groups;
 mod payload;
 mod tenant_token;
 
@@ -45,6 +47,11 @@ impl Server {
         self.service.get(url).await
     }
 
+    pub async fn import_api_keys(&self, params: &str, content: Value) -> (Value, StatusCode) {
+        let url = format!("/keys/import{params}");
+        self.service.post(url, content).await
+    }
+
     pub async fn delete_api_key(&self, key: impl AsRef<str>) -> (Value, StatusCode) {
         let url = format!("/keys/{}", key.as_ref());
         self.service.delete(url).await
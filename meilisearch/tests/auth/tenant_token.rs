@@ -468,6 +468,42 @@ async fn error_search_forbidden_token() {
     compute_forbidden_search!(tenant_tokens, ACCEPTED_KEYS);
 }
 
+#[actix_rt::test]
+async fn error_search_invalid_filter_token() {
+    let mut server = Server::new_auth().await;
+    server.use_api_key("MASTER_KEY");
+
+    let content = json!({
+        "indexes": ["*"],
+        "actions": ["search"],
+        "expiresAt": (OffsetDateTime::now_utc() + Duration::hours(1)).format(&Rfc3339).unwrap(),
+    });
+
+    let (response, code) = server.add_api_key(content).await;
+    assert_eq!(code, 201);
+    let key = response["key"].as_str().unwrap();
+    let uid = response["uid"].as_str().unwrap();
+
+    let tenant_token = hashmap! {
+        "searchRules" => json!({"sales": {"filter": "color = 'blue"}}),
+        "exp" => json!((OffsetDateTime::now_utc() + Duration::hours(1)).unix_timestamp())
+    };
+    let web_token = generate_tenant_token(uid, key, tenant_token);
+    server.use_api_key(&web_token);
+
+    let index = server.index("sales");
+    let (response, code) = index.search_post(json!({})).await;
+    meili_snap::snapshot!(code, @"400 Bad Request");
+    meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
+    {
+      "message": "The filter of the search rules of the tenant token for the index `sales` is invalid: Expression `\\'blue` is missing the following closing delimiter: `'`.\n9:14 color = 'blue\n             ^^^^^",
+      "code": "invalid_search_filter",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_filter"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn error_access_forbidden_routes() {
     let mut server = Server::new_auth().await;
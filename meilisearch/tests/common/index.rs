@@ -1,9 +1,7 @@
 use std::fmt::Write;
 use std::panic::{catch_unwind, resume_unwind, UnwindSafe};
-use std::time::Duration;
 
 use actix_web::http::StatusCode;
-use tokio::time::sleep;
 use urlencoding::encode as urlencode;
 
 use super::encoder::Encoder;
@@ -137,20 +135,14 @@ impl Index<'_> {
     }
 
     pub async fn wait_task(&self, update_id: u64) -> Value {
-        // try several times to get status, or panic to not wait forever
-        let url = format!("/tasks/{}", update_id);
-        for _ in 0..100 {
-            let (response, status_code) = self.service.get(&url).await;
-            assert_eq!(200, status_code, "response: {}", response);
-
-            if response["status"] == "succeeded" || response["status"] == "failed" {
-                return response;
-            }
+        // wait for the task to be finished, or panic to not wait forever
+        let url = format!("/tasks/{}/wait?timeoutMs=50000", update_id);
+        let (mut response, status_code) = self.service.get(&url).await;
+        assert_eq!(200, status_code, "response: {}", response);
+        assert_eq!(response["timedOut"], false, "Timeout waiting for update id: {}", response);
 
-            // wait 0.5 second.
-            sleep(Duration::from_millis(500)).await;
-        }
-        panic!("Timeout waiting for update id");
+        response.0.as_object_mut().unwrap().remove("timedOut");
+        response
     }
 
     pub async fn get_task(&self, update_id: u64) -> (Value, StatusCode) {
@@ -333,6 +325,31 @@ impl Index<'_> {
         self.service.put_encoded(url, settings, self.encoder).await
     }
 
+    pub async fn update_settings_search_defaults(&self, settings: Value) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/settings/search-defaults", urlencode(self.uid.as_ref()));
+        self.service.put_encoded(url, settings, self.encoder).await
+    }
+
+    pub async fn presets(&self) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/presets", urlencode(self.uid.as_ref()));
+        self.service.get(url).await
+    }
+
+    pub async fn get_preset(&self, name: &str) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/presets/{}", urlencode(self.uid.as_ref()), urlencode(name));
+        self.service.get(url).await
+    }
+
+    pub async fn update_preset(&self, name: &str, preset: Value) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/presets/{}", urlencode(self.uid.as_ref()), urlencode(name));
+        self.service.put_encoded(url, preset, self.encoder).await
+    }
+
+    pub async fn delete_preset(&self, name: &str) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/presets/{}", urlencode(self.uid.as_ref()), urlencode(name));
+        self.service.delete(url).await
+    }
+
     pub async fn delete_settings(&self) -> (Value, StatusCode) {
         let url = format!("/indexes/{}/settings", urlencode(self.uid.as_ref()));
         self.service.delete(url).await
@@ -1,7 +1,8 @@
 #![allow(dead_code)]
 
+use std::num::NonZeroUsize;
 use std::path::Path;
-use std::time::Duration;
+use std::sync::Arc;
 
 use actix_http::body::MessageBody;
 use actix_web::dev::ServiceResponse;
@@ -9,10 +10,12 @@ use actix_web::http::StatusCode;
 use byte_unit::{Byte, ByteUnit};
 use clap::Parser;
 use meilisearch::option::{IndexerOpts, MaxMemory, Opt};
-use meilisearch::{analytics, create_app, setup_meilisearch, SubscriberForSecondLayer};
+use meilisearch::search_queue::SearchQueue;
+use meilisearch::{
+    analytics, create_app, setup_meilisearch, LogStreamState, SubscriberForSecondLayer,
+};
 use once_cell::sync::Lazy;
 use tempfile::TempDir;
-use tokio::time::sleep;
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::Layer;
 
@@ -43,7 +46,15 @@ impl Server {
         let options = default_settings(dir.path());
 
         let (index_scheduler, auth) = setup_meilisearch(&options).unwrap();
-        let service = Service { index_scheduler, auth, options, api_key: None };
+        let service = Service {
+            index_scheduler,
+            auth,
+            search_queue: search_queue(&options),
+            search_latencies: Default::default(),
+            query_preprocessor: options.experimental_query_preprocessor.build(),
+            options,
+            api_key: None,
+        };
 
         Server { service, _dir: Some(dir) }
     }
@@ -58,7 +69,15 @@ impl Server {
         options.master_key = Some("MASTER_KEY".to_string());
 
         let (index_scheduler, auth) = setup_meilisearch(&options).unwrap();
-        let service = Service { index_scheduler, auth, options, api_key: None };
+        let service = Service {
+            index_scheduler,
+            auth,
+            search_queue: search_queue(&options),
+            search_latencies: Default::default(),
+            query_preprocessor: options.experimental_query_preprocessor.build(),
+            options,
+            api_key: None,
+        };
 
         Server { service, _dir: Some(dir) }
     }
@@ -71,7 +90,15 @@ impl Server {
 
     pub async fn new_with_options(options: Opt) -> Result<Self, anyhow::Error> {
         let (index_scheduler, auth) = setup_meilisearch(&options)?;
-        let service = Service { index_scheduler, auth, options, api_key: None };
+        let service = Service {
+            index_scheduler,
+            auth,
+            search_queue: search_queue(&options),
+            search_latencies: Default::default(),
+            query_preprocessor: options.experimental_query_preprocessor.build(),
+            options,
+            api_key: None,
+        };
 
         Ok(Server { service, _dir: None })
     }
@@ -99,14 +126,60 @@ impl Server {
         actix_web::test::init_service(create_app(
             self.service.index_scheduler.clone().into(),
             self.service.auth.clone().into(),
+            self.service.search_queue.clone().into(),
+            self.service.search_latencies.clone().into(),
             self.service.options.clone(),
-            (route_layer_handle, stderr_layer_handle),
+            (route_layer_handle, stderr_layer_handle, Default::default()),
             analytics::MockAnalytics::new(&self.service.options),
+            self.service.query_preprocessor.clone(),
             true,
         ))
         .await
     }
 
+    /// Serves this server on a port chosen by the system, for the tests in which another
+    /// instance must reach it over HTTP. Returns the URL of the server.
+    pub fn listen(&self) -> String {
+        let (_route_layer, route_layer_handle) =
+            tracing_subscriber::reload::Layer::new(None.with_filter(
+                tracing_subscriber::filter::Targets::new().with_target("", LevelFilter::OFF),
+            ));
+        let (_stderr_layer, stderr_layer_handle) = tracing_subscriber::reload::Layer::new(
+            (Box::new(tracing_subscriber::fmt::layer())
+                as Box<dyn tracing_subscriber::Layer<SubscriberForSecondLayer> + Send + Sync>)
+                .with_filter(tracing_subscriber::filter::Targets::new()),
+        );
+
+        let service = &self.service;
+        let index_scheduler = service.index_scheduler.clone();
+        let auth = service.auth.clone();
+        let search_queue = service.search_queue.clone();
+        let search_latencies = service.search_latencies.clone();
+        let query_preprocessor = service.query_preprocessor.clone();
+        let options = service.options.clone();
+        let logs_stream = Arc::new(LogStreamState::default());
+        let server = actix_web::HttpServer::new(move || {
+            create_app(
+                index_scheduler.clone().into(),
+                auth.clone().into(),
+                search_queue.clone().into(),
+                search_latencies.clone().into(),
+                options.clone(),
+                (route_layer_handle.clone(), stderr_layer_handle.clone(), logs_stream.clone()),
+                analytics::MockAnalytics::new(&options),
+                query_preprocessor.clone(),
+                true,
+            )
+        })
+        .workers(1)
+        // By listening on the port 0, the system will give us any available port.
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let (ip, scheme) = server.addrs_with_scheme()[0];
+        tokio::spawn(server.run());
+        format!("{scheme}://{ip}")
+    }
+
     /// Returns a view to an index. There is no guarantee that the index exists.
     pub fn index(&self, uid: impl AsRef<str>) -> Index<'_> {
         self.index_with_encoder(uid, Encoder::Plain)
@@ -190,20 +263,14 @@ impl Server {
     }
 
     pub async fn wait_task(&self, update_id: u64) -> Value {
-        // try several times to get status, or panic to not wait forever
-        let url = format!("/tasks/{}", update_id);
-        for _ in 0..100 {
-            let (response, status_code) = self.service.get(&url).await;
-            assert_eq!(200, status_code, "response: {}", response);
-
-            if response["status"] == "succeeded" || response["status"] == "failed" {
-                return response;
-            }
-
-            // wait 0.5 second.
-            sleep(Duration::from_millis(500)).await;
-        }
-        panic!("Timeout waiting for update id");
+        // wait for the task to be finished, or panic to not wait forever
+        let url = format!("/tasks/{}/wait?timeoutMs=50000", update_id);
+        let (mut response, status_code) = self.service.get(&url).await;
+        assert_eq!(200, status_code, "response: {}", response);
+        assert_eq!(response["timedOut"], false, "Timeout waiting for update id: {}", response);
+
+        response.0.as_object_mut().unwrap().remove("timedOut");
+        response
     }
 
     pub async fn get_task(&self, update_id: u64) -> (Value, StatusCode) {
@@ -224,6 +291,12 @@ impl Server {
     }
 }
 
+/// The search queue shared by all the requests sent to a server, like the workers of an instance share it.
+pub fn search_queue(options: &Opt) -> Arc<SearchQueue> {
+    let parallelism = std::thread::available_parallelism().unwrap_or(NonZeroUsize::new(2).unwrap());
+    Arc::new(SearchQueue::new(options.experimental_search_queue_size, parallelism))
+}
+
 pub fn default_settings(dir: impl AsRef<Path>) -> Opt {
     Opt {
         db_path: dir.as_ref().join("db"),
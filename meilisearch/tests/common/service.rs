@@ -5,6 +5,9 @@ use actix_web::http::StatusCode;
 use actix_web::test;
 use actix_web::test::TestRequest;
 use index_scheduler::IndexScheduler;
+use meilisearch::query_preprocessor::QueryPreprocessor;
+use meilisearch::search_latency::SearchLatencies;
+use meilisearch::search_queue::SearchQueue;
 use meilisearch::{analytics, create_app, Opt, SubscriberForSecondLayer};
 use meilisearch_auth::AuthController;
 use tracing::level_filters::LevelFilter;
@@ -16,6 +19,9 @@ use crate::common::Value;
 pub struct Service {
     pub index_scheduler: Arc<IndexScheduler>,
     pub auth: Arc<AuthController>,
+    pub search_queue: Arc<SearchQueue>,
+    pub search_latencies: Arc<SearchLatencies>,
+    pub query_preprocessor: Arc<dyn QueryPreprocessor>,
     pub options: Opt,
     pub api_key: Option<String>,
 }
@@ -123,9 +129,12 @@ impl Service {
         let app = test::init_service(create_app(
             self.index_scheduler.clone().into(),
             self.auth.clone().into(),
+            self.search_queue.clone().into(),
+            self.search_latencies.clone().into(),
             self.options.clone(),
-            (route_layer_handle, stderr_layer_handle),
+            (route_layer_handle, stderr_layer_handle, Default::default()),
             analytics::MockAnalytics::new(&self.options),
+            self.query_preprocessor.clone(),
             true,
         ))
         .await;
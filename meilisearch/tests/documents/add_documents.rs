@@ -1,6 +1,9 @@
+use actix_web::http::StatusCode;
 use actix_web::test;
+use bytes::Bytes;
 use meili_snap::{json_string, snapshot};
 use meilisearch::Opt;
+use sha2::{Digest, Sha256};
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
 
@@ -217,6 +220,75 @@ async fn add_single_document_with_every_encoding() {
     }
 }
 
+/// Here we send a checksum of the payload, as it is sent, along with the documents
+#[actix_rt::test]
+async fn add_documents_with_checksum() {
+    let document = r#"[{"id": 1, "content": "Bouvier Bernois"}]"#;
+    let server = Server::new().await;
+    let app = server.init_web_app().await;
+
+    // a wrong checksum is rejected and nothing is enqueued
+    let req = test::TestRequest::post()
+        .uri("/indexes/dog/documents")
+        .set_payload(document)
+        .insert_header(("content-type", "application/json"))
+        .insert_header(("X-Content-Sha256", "0".repeat(64)))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    let status_code = res.status();
+    let body = test::read_body(res).await;
+    let response: Value = serde_json::from_slice(&body).unwrap_or_default();
+    snapshot!(status_code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The payload does not match its `X-Content-Sha256` header: its SHA-256 digest is `5523253b2d2d1739c5adb17842d83d5f3d306868b4eeefcfffb68933c4798663` but `0000000000000000000000000000000000000000000000000000000000000000` was expected.",
+      "code": "payload_checksum_mismatch",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#payload_checksum_mismatch"
+    }
+    "###);
+
+    // the checksum is the one of the payload as it is sent, compressed or not
+    for (task_uid, encoder) in Encoder::iterator().enumerate() {
+        let payload: Bytes = encoder.encode(document).into();
+        let checksum = format!("{:x}", Sha256::digest(&payload));
+        let mut req = test::TestRequest::put()
+            .uri("/indexes/dog/documents")
+            .set_payload(payload)
+            .insert_header(("content-type", "application/json"))
+            .insert_header(("X-Content-Sha256", checksum.to_uppercase()));
+        req = match encoder.header() {
+            Some(header) => req.insert_header(header),
+            None => req,
+        };
+        let res = test::call_service(&app, req.to_request()).await;
+        let status_code = res.status();
+        let body = test::read_body(res).await;
+        let response: Value = serde_json::from_slice(&body).unwrap_or_default();
+        assert_eq!(status_code, 202, "{response}");
+        assert_eq!(response["taskUid"], task_uid);
+    }
+
+    // the checksum of the decompressed payload is not the one of the payload as it is sent
+    let encoder = Encoder::Gzip;
+    let req = test::TestRequest::post()
+        .uri("/indexes/dog/documents")
+        .set_payload(encoder.encode(document))
+        .insert_header(("content-type", "application/json"))
+        .insert_header(encoder.header().unwrap())
+        .insert_header((
+            "X-Content-Sha256",
+            "5523253b2d2d1739c5adb17842d83d5f3d306868b4eeefcfffb68933c4798663",
+        ))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    let status_code = res.status();
+    let body = test::read_body(res).await;
+    let response: Value = serde_json::from_slice(&body).unwrap_or_default();
+    snapshot!(status_code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""payload_checksum_mismatch""###);
+}
+
 #[actix_rt::test]
 async fn add_csv_document() {
     let server = Server::new().await;
@@ -971,7 +1043,7 @@ async fn error_document_add_create_index_bad_uid() {
     snapshot!(json_string!(response),
         @r###"
     {
-      "message": "`883  fj!` is not a valid index uid. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_).",
+      "message": "`883  fj!` is not a valid index uid: the character ` ` at position 3 is not allowed. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_), of at most 400 bytes. Try `883__fj_` instead.",
       "code": "invalid_index_uid",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_index_uid"
@@ -1112,6 +1184,173 @@ async fn replace_document() {
     "###);
 }
 
+/// Three occurrences of the same document in one NDJSON payload.
+const DUPLICATE_DOCUMENTS: &str = r#"{ "age": 2, "id": 1, "name": "kefir" }
+{ "age": 3, "id": 1 }
+{ "id": 1, "zone": "north" }"#;
+
+#[actix_rt::test]
+async fn replace_duplicate_documents_in_one_payload() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index
+        .raw_add_documents(DUPLICATE_DOCUMENTS, vec![("Content-Type", "application/x-ndjson")], "")
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index.get_task(response.uid()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "receivedDocuments": 3,
+      "indexedDocuments": 3,
+      "duplicateDocuments": 2
+    }
+    "###);
+
+    // the last occurrence wins
+    let (response, code) = index.get_document(1, None).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "id": 1,
+      "zone": "north"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn update_duplicate_documents_in_one_payload() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) =
+        index.raw_update_documents(DUPLICATE_DOCUMENTS, Some("application/x-ndjson"), "").await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index.get_task(response.uid()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "receivedDocuments": 3,
+      "indexedDocuments": 3,
+      "duplicateDocuments": 2
+    }
+    "###);
+
+    // the occurrences are merged in order
+    let (response, code) = index.get_document(1, None).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "age": 3,
+      "id": 1,
+      "name": "kefir",
+      "zone": "north"
+    }
+    "###);
+}
+
+/// A JSON payload with a lone 0xFF byte in a field value.
+const INVALID_UTF8_JSON: &[u8] = b"[{\"id\": 1, \"name\": \"ke\xfffir\"}]";
+/// A CSV payload with a lone 0xFF byte in a field value.
+const INVALID_UTF8_CSV: &[u8] = b"id,name\n1,ke\xfffir\n";
+
+async fn add_raw_bytes(
+    server: &Server,
+    payload: &'static [u8],
+    content_type: &str,
+    query_parameter: &str,
+) -> (Value, StatusCode) {
+    let req = test::TestRequest::post()
+        .uri(&format!("/indexes/test/documents{query_parameter}"))
+        .set_payload(payload)
+        .insert_header(("content-type", content_type));
+    server.service.request(req).await
+}
+
+#[actix_rt::test]
+async fn add_documents_with_invalid_utf8() {
+    let server = Server::new().await;
+
+    let (response, code) = add_raw_bytes(&server, INVALID_UTF8_JSON, "application/json", "").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The `json` payload provided contains an invalid UTF-8 sequence at byte 22: `6d 65 22 3a 20 22 6b 65 ff 66 69 72 22 7d 5d`. Use the `lossyUtf8=true` query parameter to replace the invalid sequences with U+FFFD.",
+      "code": "invalid_document_format",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_document_format"
+    }
+    "###);
+
+    let (response, code) = add_raw_bytes(&server, INVALID_UTF8_CSV, "text/csv", "").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The `csv` payload provided contains an invalid UTF-8 sequence at byte 12: `61 6d 65 0a 31 2c 6b 65 ff 66 69 72 0a`. Use the `lossyUtf8=true` query parameter to replace the invalid sequences with U+FFFD.",
+      "code": "invalid_document_format",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_document_format"
+    }
+    "###);
+
+    // nothing was enqueued
+    let (response, code) = server.tasks().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["total"], @"0");
+}
+
+#[actix_rt::test]
+async fn add_documents_with_lossy_utf8() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) =
+        add_raw_bytes(&server, INVALID_UTF8_JSON, "application/json", "?lossyUtf8=true").await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index.get_task(response.uid()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "receivedDocuments": 1,
+      "indexedDocuments": 1,
+      "invalidUtf8Replacements": 1
+    }
+    "###);
+
+    let (response, code) = index.get_document(1, None).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "id": 1,
+      "name": "ke�fir"
+    }
+    "###);
+
+    let (response, code) =
+        add_raw_bytes(&server, INVALID_UTF8_CSV, "text/csv", "?lossyUtf8=true").await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index.get_task(response.uid()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "receivedDocuments": 1,
+      "indexedDocuments": 1,
+      "invalidUtf8Replacements": 1
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn add_no_documents() {
     let server = Server::new().await;
@@ -1530,6 +1769,39 @@ async fn add_documents_with_geo_field() {
     "###);
 }
 
+#[actix_rt::test]
+async fn add_documents_redact_payload_errors() {
+    let temp = tempfile::tempdir().unwrap();
+    let options = Opt { experimental_redact_payload_errors: true, ..default_settings(temp.path()) };
+    let server = Server::new_with_options(options).await.unwrap();
+    let index = server.index("test");
+    index.create(Some("id")).await;
+    index.update_settings(json!({"sortableAttributes": ["_geo"]})).await;
+
+    // a 10KB value in a broken `_geo` field
+    let documents = json!([
+        {
+            "id": "11",
+            "_geo": "a".repeat(10_000),
+        }
+    ]);
+
+    index.add_documents(documents, None).await;
+    index.wait_task(2).await;
+    let (response, code) = index.get_task(2).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["status"], @r###""failed""###);
+    snapshot!(response["error"]["code"], @r###""invalid_document_geo_field""###);
+    let message = response["error"]["message"].as_str().unwrap();
+    assert!(message.len() < 1024, "{message}");
+    assert!(
+        message.starts_with("The `_geo` field in the document with the id: `11` is not an object.")
+    );
+    assert!(
+        message.contains(&format!("instead got `\"{}… (10000 characters, hash: ", "a".repeat(32)))
+    );
+}
+
 #[actix_rt::test]
 async fn add_documents_invalid_geo_field() {
     let server = Server::new().await;
@@ -2423,3 +2695,159 @@ async fn dry_register_file() {
     "###);
     snapshot!(code, @"404 Not Found");
 }
+
+#[actix_rt::test]
+async fn error_add_documents_with_reserved_field() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    index.create(Some("id")).await;
+
+    let documents = json!([
+        { "id": 1, "_geo": { "lat": 1, "lng": 1 }, "nested": { "_score": 1 } },
+        { "id": 2, "_score": 12 },
+    ]);
+    let (task, _code) = index.add_documents(documents, None).await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(json_string!(response, { ".duration" => "[duration]", ".enqueuedAt" => "[date]", ".startedAt" => "[date]", ".finishedAt" => "[date]" }),
+        @r###"
+    {
+      "uid": 1,
+      "indexUid": "test",
+      "status": "failed",
+      "type": "documentAdditionOrUpdate",
+      "canceledBy": null,
+      "details": {
+        "receivedDocuments": 2,
+        "indexedDocuments": 0
+      },
+      "error": {
+        "message": "The document with id: `\"2\"` contains the field `_score`, but the fields starting with `_` are reserved. Only `_geo` and `_vectors` are accepted, unless the `allowUnderscoreFields` setting is enabled.",
+        "code": "invalid_document_fields",
+        "type": "invalid_request",
+        "link": "https://docs.meilisearch.com/errors#invalid_document_fields"
+      },
+      "duration": "[duration]",
+      "enqueuedAt": "[date]",
+      "startedAt": "[date]",
+      "finishedAt": "[date]"
+    }
+    "###);
+
+    let (response, code) = index.update_settings(json!({ "allowUnderscoreFields": true })).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let documents = json!([{ "id": 2, "_score": 12 }]);
+    let (task, _code) = index.add_documents(documents, None).await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    let (response, code) = index.get_document(2, None).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response, @r###"
+    {
+      "id": 2,
+      "_score": 12
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn error_add_documents_reports_all_the_invalid_documents() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (_, code) = server.set_features(json!({ "vectorStore": true })).await;
+    snapshot!(code, @"200 OK");
+    let (task, code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["_geo"],
+            "embedders": { "default": { "source": "userProvided", "dimensions": 2 } },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(task.uid()).await;
+
+    let documents = json!([
+        { "id": 0, "name": "valid" },
+        { "id": "invalid id" },
+        { "name": "no id" },
+        { "id": 3, "_geo": { "lat": "north", "lng": 2 } },
+        { "id": 4, "_vectors": { "default": "not a vector" } },
+        { "id": 5, "_private": true },
+        { "id": "invalid id" },
+    ]);
+    let (task, code) = index.add_documents(documents, Some("id")).await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+    snapshot!(response["details"]["indexedDocuments"], @"0");
+
+    // the same error is only reported once
+    let errors = response["details"]["indexingErrors"].as_array().unwrap().clone();
+    assert_eq!(errors.len(), 5, "{errors:?}");
+    let errors: Vec<_> = errors.iter().map(|error| error.as_str().unwrap()).collect();
+    assert!(
+        errors[0].starts_with("Document identifier `\"invalid id\"` is invalid."),
+        "{errors:?}"
+    );
+    assert!(errors[1].starts_with("Document doesn't have a `id` attribute"), "{errors:?}");
+    assert!(errors[2].starts_with("Could not parse latitude"), "{errors:?}");
+    assert!(errors[3].starts_with("The `_vectors.default` field"), "{errors:?}");
+    assert!(errors[4].contains("contains the field `_private`"), "{errors:?}");
+    // the task fails with the error of the first invalid document
+    assert_eq!(response["error"]["message"], errors[0]);
+
+    let (stats, code) = index.stats().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(stats["numberOfDocuments"], @"0");
+    assert_eq!(stats["lastIndexingErrors"], response["details"]["indexingErrors"]);
+
+    // the errors of the last refused payload are kept after a successful one
+    let (task, _code) = index.add_documents(json!([{ "id": 0, "name": "valid" }]), None).await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    let (stats, _code) = index.stats().await;
+    assert_eq!(stats["lastIndexingErrors"].as_array().unwrap().len(), 5);
+}
+
+#[actix_rt::test]
+async fn add_documents_reports_the_facet_update_strategies() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _) = index.update_settings(json!({ "filterableAttributes": ["price"] })).await;
+    index.wait_task(task.uid()).await;
+
+    let documents: Vec<_> = (0..100).map(|id| json!({ "id": id, "price": id })).collect();
+    let (task, _) = index.add_documents(json!(documents), Some("id")).await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(json_string!(response["details"]["facetUpdateStrategies"]), @r###"
+    {
+      "price": "bulk"
+    }
+    "###);
+
+    // a single new price is inserted in the existing facet levels
+    let (task, _) = index.add_documents(json!([{ "id": 100, "price": 100 }]), None).await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(json_string!(response["details"]["facetUpdateStrategies"]), @r###"
+    {
+      "price": "incremental"
+    }
+    "###);
+
+    // two new prices out of a hundred make the facet levels to be rebuilt
+    let (task, _) = index
+        .add_documents(json!([{ "id": 101, "price": 101 }, { "id": 102, "price": 102 }]), None)
+        .await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(json_string!(response["details"]["facetUpdateStrategies"]), @r###"
+    {
+      "price": "bulk"
+    }
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({ "filter": "price >= 99", "attributesToRetrieve": ["id"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["estimatedTotalHits"], @"4");
+}
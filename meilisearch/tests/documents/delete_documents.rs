@@ -156,14 +156,17 @@ async fn delete_document_by_filter() {
     index.wait_task(1).await;
 
     let (stats, _) = index.stats().await;
-    snapshot!(json_string!(stats), @r###"
+    snapshot!(json_string!(stats, { ".databases" => "[databases]", ".fragmentation" => "[fragmentation]", ".searchLatency" => "[searchLatency]" }), @r###"
     {
       "numberOfDocuments": 4,
       "isIndexing": false,
       "fieldDistribution": {
         "color": 3,
         "id": 4
-      }
+      },
+      "databases": "[databases]",
+      "fragmentation": "[fragmentation]",
+      "searchLatency": "[searchLatency]"
     }
     "###);
 
@@ -202,14 +205,17 @@ async fn delete_document_by_filter() {
     "###);
 
     let (stats, _) = index.stats().await;
-    snapshot!(json_string!(stats), @r###"
+    snapshot!(json_string!(stats, { ".databases" => "[databases]", ".fragmentation" => "[fragmentation]", ".searchLatency" => "[searchLatency]" }), @r###"
     {
       "numberOfDocuments": 2,
       "isIndexing": false,
       "fieldDistribution": {
         "color": 1,
         "id": 2
-      }
+      },
+      "databases": "[databases]",
+      "fragmentation": "[fragmentation]",
+      "searchLatency": "[searchLatency]"
     }
     "###);
 
@@ -267,14 +273,17 @@ async fn delete_document_by_filter() {
     "###);
 
     let (stats, _) = index.stats().await;
-    snapshot!(json_string!(stats), @r###"
+    snapshot!(json_string!(stats, { ".databases" => "[databases]", ".fragmentation" => "[fragmentation]", ".searchLatency" => "[searchLatency]" }), @r###"
     {
       "numberOfDocuments": 1,
       "isIndexing": false,
       "fieldDistribution": {
         "color": 1,
         "id": 1
-      }
+      },
+      "databases": "[databases]",
+      "fragmentation": "[fragmentation]",
+      "searchLatency": "[searchLatency]"
     }
     "###);
 
@@ -0,0 +1,109 @@
+use meili_snap::{json_string, snapshot};
+use meilisearch::Opt;
+
+use crate::common::{default_settings, Server};
+use crate::json;
+
+#[actix_rt::test]
+async fn document_tasks_requires_the_feature() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.add_documents(json!([{ "id": 1, "name": "kefir" }]), None).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = server.service.get("/indexes/test/documents/1/tasks").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Using the `/indexes/{indexUid}/documents/{documentId}/tasks` route requires enabling the `document tasks` experimental feature. See https://www.meilisearch.com/docs/learn/experimental/overview",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn document_tasks_newest_first() {
+    let server = Server::new().await;
+    let (_response, code) = server.set_features(json!({ "documentTasks": true })).await;
+    snapshot!(code, @"200 OK");
+    let index = server.index("test");
+
+    let (task, _code) = index.add_documents(json!([{ "id": 1, "name": "kefir" }]), None).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.update_documents(json!([{ "id": 1, "age": 4 }]), None).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.add_documents(json!([{ "id": 2, "name": "intel" }]), None).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.add_documents(json!([{ "id": 1, "name": "kefirounet" }]), None).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = server.service.get("/indexes/test/documents/1/tasks").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "documentId": "1",
+      "taskUids": [
+        3,
+        1,
+        0
+      ]
+    }
+    "###);
+
+    // the deletions are recorded too, and the history outlives the document
+    let (task, _code) = index.delete_document(1).await;
+    index.wait_task(task.uid()).await;
+    let (response, code) = server.service.get("/indexes/test/documents/1/tasks").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["taskUids"], @"[4,3,1,0]");
+
+    let (response, code) = server.service.get("/indexes/test/documents/2/tasks").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["taskUids"], @"[2]");
+
+    let (response, code) = server.service.get("/indexes/test/documents/3/tasks").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["taskUids"], @"[]");
+}
+
+#[actix_rt::test]
+async fn document_tasks_history_is_bounded() {
+    let temp = tempfile::tempdir().unwrap();
+    let options =
+        Opt { experimental_document_tasks_history_size: 2, ..default_settings(temp.path()) };
+    let server = Server::new_with_options(options).await.unwrap();
+    server.set_features(json!({ "documentTasks": true })).await;
+    let index = server.index("test");
+
+    for age in 0..3 {
+        let (task, _code) = index.add_documents(json!([{ "id": 1, "age": age }]), None).await;
+        index.wait_task(task.uid()).await;
+    }
+
+    let (response, code) = server.service.get("/indexes/test/documents/1/tasks").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["taskUids"], @"[2,1]");
+}
+
+#[actix_rt::test]
+async fn document_tasks_not_recorded_when_disabled() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (task, _code) = index.add_documents(json!([{ "id": 1, "name": "kefir" }]), None).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.update_documents(json!([{ "id": 1, "age": 4 }]), None).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = index.stats().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["databases"]["document-tasks"]["entries"], @"0");
+
+    // enabling the feature afterward doesn't reveal a history that was not recorded
+    server.set_features(json!({ "documentTasks": true })).await;
+    let (response, code) = server.service.get("/indexes/test/documents/1/tasks").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["taskUids"], @"[]");
+}
@@ -203,6 +203,28 @@ async fn test_get_all_documents_offset() {
     assert_eq!(response["total"], json!(77));
 }
 
+#[actix_rt::test]
+async fn get_all_documents_large_page() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let documents: Vec<_> = (0..10_000)
+        .map(|id| json!({ "id": id, "text": format!("the document number {id}") }))
+        .collect();
+    let (task, _code) = index.add_documents(json!(documents), None).await;
+    index.wait_task(task.uid()).await;
+
+    // the page is streamed in several chunks, the body must still be a single JSON object
+    let (response, code) = index.get_all_documents_raw("?limit=10000&offset=1").await;
+    assert_eq!(code, 200, "{response}");
+    let results = response["results"].as_array().unwrap();
+    assert_eq!(results.len(), 9_999);
+    assert_eq!(results[0], json!({ "id": 1, "text": "the document number 1" }));
+    assert_eq!(results[9_998], json!({ "id": 9_999, "text": "the document number 9999" }));
+    assert_eq!(response["offset"], json!(1));
+    assert_eq!(response["limit"], json!(10_000));
+    assert_eq!(response["total"], json!(10_000));
+}
+
 #[actix_rt::test]
 async fn test_get_all_documents_attributes_to_retrieve() {
     let server = Server::new().await;
@@ -540,3 +562,89 @@ async fn get_document_by_filter() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn get_document_by_encoded_id_with_nested_fields() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let documents = json!([
+        {
+            "id": "doc-1",
+            "title": "Kefir",
+            "nested": { "content": "foobar", "other": "baz" },
+        }
+    ]);
+    let (task, _code) = index.add_documents(documents, None).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) =
+        index.service.get("/indexes/test/documents/doc%2D1?fields=id,nested.content").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "id": "doc-1",
+      "nested": {
+        "content": "foobar"
+      }
+    }
+    "###);
+
+    let (response, code) = index.service.get("/indexes/test/documents/%20doc%5F2").await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Document `doc_2` not found.",
+      "code": "document_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#document_not_found"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn debug_routes_translate_document_ids() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index
+        .add_documents(json!([{ "id": "kefir" }, { "id": "bobby" }, { "id": "echo" }]), None)
+        .await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = index.service.get("/indexes/test/_debug/external-id/bobby").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Using the `/indexes/{indexUid}/_debug/*` routes requires enabling the `debug routes` experimental feature. See https://www.meilisearch.com/docs/learn/experimental/overview",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+    let (response, code) = index.service.get("/indexes/test/_debug/docid/1").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""feature_not_enabled""###);
+
+    let (_response, code) = server.set_features(json!({ "debugRoutes": true })).await;
+    snapshot!(code, @"200 OK");
+
+    for external_id in ["kefir", "bobby", "echo"] {
+        let (response, code) =
+            index.service.get(format!("/indexes/test/_debug/external-id/{external_id}")).await;
+        assert_eq!(code, 200, "{response}");
+        assert_eq!(response["externalId"], external_id);
+
+        let internal_id = response["internalId"].as_u64().unwrap();
+        let (response, code) =
+            index.service.get(format!("/indexes/test/_debug/docid/{internal_id}")).await;
+        assert_eq!(code, 200, "{response}");
+        assert_eq!(response["internalId"], internal_id);
+        assert_eq!(response["externalId"], external_id);
+    }
+
+    let (response, code) = index.service.get("/indexes/test/_debug/external-id/doggo").await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(response["code"], @r###""document_not_found""###);
+    let (response, code) = index.service.get("/indexes/test/_debug/docid/42").await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(response["code"], @r###""document_not_found""###);
+}
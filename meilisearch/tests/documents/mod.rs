@@ -1,5 +1,6 @@
 mod add_documents;
 mod delete_documents;
+mod document_tasks;
 mod errors;
 mod get_documents;
 mod update_documents;
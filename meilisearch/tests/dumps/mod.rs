@@ -4,9 +4,20 @@ use meili_snap::{json_string, snapshot};
 use meilisearch::Opt;
 
 use self::data::GetDump;
-use crate::common::{default_settings, GetAllDocumentsOptions, Server};
+use crate::common::{default_settings, GetAllDocumentsOptions, Server, Value};
 use crate::json;
 
+/// Removes the LMDB statistics of the databases from the stats of an index,
+/// as they depend on how the index was built and not only on its content.
+fn without_runtime_stats(mut stats: Value) -> Value {
+    if let Some(stats) = stats.0.as_object_mut() {
+        stats.remove("databases");
+        stats.remove("fragmentation");
+        stats.remove("searchLatency");
+    }
+    stats
+}
+
 // all the following test are ignored on windows. See #2364
 #[actix_rt::test]
 #[cfg_attr(target_os = "windows", ignore)]
@@ -26,6 +37,8 @@ async fn import_dump_v1_movie_raw() {
     let index = server.index("indexUID");
 
     let (stats, code) = index.stats().await;
+
+    let stats = without_runtime_stats(stats);
     snapshot!(code, @"200 OK");
     assert_eq!(
         stats,
@@ -78,7 +91,19 @@ async fn import_dump_v1_movie_raw() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "searchDefaults": null,
+      "minNumericPrefixLength": 4,
+      "allowUnderscoreFields": false,
+      "localizedAttributes": null,
+      "numberNormalization": false,
+      "phraseAcrossFields": false,
+      "exactAttributes": [],
+      "decompoundingLocales": [],
+      "facetTypes": {},
+      "stemming": {
+        "locales": []
+      }
     }
     "###
     );
@@ -162,6 +187,8 @@ async fn import_dump_v1_movie_with_settings() {
     let index = server.index("indexUID");
 
     let (stats, code) = index.stats().await;
+
+    let stats = without_runtime_stats(stats);
     snapshot!(code, @"200 OK");
     snapshot!(
         json_string!(stats),
@@ -240,7 +267,19 @@ async fn import_dump_v1_movie_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "searchDefaults": null,
+      "minNumericPrefixLength": 4,
+      "allowUnderscoreFields": false,
+      "localizedAttributes": null,
+      "numberNormalization": false,
+      "phraseAcrossFields": false,
+      "exactAttributes": [],
+      "decompoundingLocales": [],
+      "facetTypes": {},
+      "stemming": {
+        "locales": []
+      }
     }
     "###
     );
@@ -324,6 +363,8 @@ async fn import_dump_v1_rubygems_with_settings() {
     let index = server.index("rubygems");
 
     let (stats, code) = index.stats().await;
+
+    let stats = without_runtime_stats(stats);
     snapshot!(code, @"200 OK");
     assert_eq!(
         stats,
@@ -388,7 +429,19 @@ async fn import_dump_v1_rubygems_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "searchDefaults": null,
+      "minNumericPrefixLength": 4,
+      "allowUnderscoreFields": false,
+      "localizedAttributes": null,
+      "numberNormalization": false,
+      "phraseAcrossFields": false,
+      "exactAttributes": [],
+      "decompoundingLocales": [],
+      "facetTypes": {},
+      "stemming": {
+        "locales": []
+      }
     }
     "###
     );
@@ -470,6 +523,8 @@ async fn import_dump_v2_movie_raw() {
     let index = server.index("indexUID");
 
     let (stats, code) = index.stats().await;
+
+    let stats = without_runtime_stats(stats);
     snapshot!(code, @"200 OK");
     assert_eq!(
         stats,
@@ -522,7 +577,19 @@ async fn import_dump_v2_movie_raw() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "searchDefaults": null,
+      "minNumericPrefixLength": 4,
+      "allowUnderscoreFields": false,
+      "localizedAttributes": null,
+      "numberNormalization": false,
+      "phraseAcrossFields": false,
+      "exactAttributes": [],
+      "decompoundingLocales": [],
+      "facetTypes": {},
+      "stemming": {
+        "locales": []
+      }
     }
     "###
     );
@@ -606,6 +673,8 @@ async fn import_dump_v2_movie_with_settings() {
     let index = server.index("indexUID");
 
     let (stats, code) = index.stats().await;
+
+    let stats = without_runtime_stats(stats);
     snapshot!(code, @"200 OK");
     assert_eq!(
         stats,
@@ -668,7 +737,19 @@ async fn import_dump_v2_movie_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "searchDefaults": null,
+      "minNumericPrefixLength": 4,
+      "allowUnderscoreFields": false,
+      "localizedAttributes": null,
+      "numberNormalization": false,
+      "phraseAcrossFields": false,
+      "exactAttributes": [],
+      "decompoundingLocales": [],
+      "facetTypes": {},
+      "stemming": {
+        "locales": []
+      }
     }
     "###
     );
@@ -752,6 +833,8 @@ async fn import_dump_v2_rubygems_with_settings() {
     let index = server.index("rubygems");
 
     let (stats, code) = index.stats().await;
+
+    let stats = without_runtime_stats(stats);
     snapshot!(code, @"200 OK");
     assert_eq!(
         stats,
@@ -813,7 +896,19 @@ async fn import_dump_v2_rubygems_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "searchDefaults": null,
+      "minNumericPrefixLength": 4,
+      "allowUnderscoreFields": false,
+      "localizedAttributes": null,
+      "numberNormalization": false,
+      "phraseAcrossFields": false,
+      "exactAttributes": [],
+      "decompoundingLocales": [],
+      "facetTypes": {},
+      "stemming": {
+        "locales": []
+      }
     }
     "###
     );
@@ -895,6 +990,8 @@ async fn import_dump_v3_movie_raw() {
     let index = server.index("indexUID");
 
     let (stats, code) = index.stats().await;
+
+    let stats = without_runtime_stats(stats);
     snapshot!(code, @"200 OK");
     assert_eq!(
         stats,
@@ -947,7 +1044,19 @@ async fn import_dump_v3_movie_raw() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "searchDefaults": null,
+      "minNumericPrefixLength": 4,
+      "allowUnderscoreFields": false,
+      "localizedAttributes": null,
+      "numberNormalization": false,
+      "phraseAcrossFields": false,
+      "exactAttributes": [],
+      "decompoundingLocales": [],
+      "facetTypes": {},
+      "stemming": {
+        "locales": []
+      }
     }
     "###
     );
@@ -1031,6 +1140,8 @@ async fn import_dump_v3_movie_with_settings() {
     let index = server.index("indexUID");
 
     let (stats, code) = index.stats().await;
+
+    let stats = without_runtime_stats(stats);
     snapshot!(code, @"200 OK");
     assert_eq!(
         stats,
@@ -1093,7 +1204,19 @@ async fn import_dump_v3_movie_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "searchDefaults": null,
+      "minNumericPrefixLength": 4,
+      "allowUnderscoreFields": false,
+      "localizedAttributes": null,
+      "numberNormalization": false,
+      "phraseAcrossFields": false,
+      "exactAttributes": [],
+      "decompoundingLocales": [],
+      "facetTypes": {},
+      "stemming": {
+        "locales": []
+      }
     }
     "###
     );
@@ -1177,6 +1300,8 @@ async fn import_dump_v3_rubygems_with_settings() {
     let index = server.index("rubygems");
 
     let (stats, code) = index.stats().await;
+
+    let stats = without_runtime_stats(stats);
     snapshot!(code, @"200 OK");
     assert_eq!(
         stats,
@@ -1238,7 +1363,19 @@ async fn import_dump_v3_rubygems_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "searchDefaults": null,
+      "minNumericPrefixLength": 4,
+      "allowUnderscoreFields": false,
+      "localizedAttributes": null,
+      "numberNormalization": false,
+      "phraseAcrossFields": false,
+      "exactAttributes": [],
+      "decompoundingLocales": [],
+      "facetTypes": {},
+      "stemming": {
+        "locales": []
+      }
     }
     "###
     );
@@ -1320,6 +1457,8 @@ async fn import_dump_v4_movie_raw() {
     let index = server.index("indexUID");
 
     let (stats, code) = index.stats().await;
+
+    let stats = without_runtime_stats(stats);
     snapshot!(code, @"200 OK");
     assert_eq!(
         stats,
@@ -1372,7 +1511,19 @@ async fn import_dump_v4_movie_raw() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "searchDefaults": null,
+      "minNumericPrefixLength": 4,
+      "allowUnderscoreFields": false,
+      "localizedAttributes": null,
+      "numberNormalization": false,
+      "phraseAcrossFields": false,
+      "exactAttributes": [],
+      "decompoundingLocales": [],
+      "facetTypes": {},
+      "stemming": {
+        "locales": []
+      }
     }
     "###
     );
@@ -1456,6 +1607,8 @@ async fn import_dump_v4_movie_with_settings() {
     let index = server.index("indexUID");
 
     let (stats, code) = index.stats().await;
+
+    let stats = without_runtime_stats(stats);
     snapshot!(code, @"200 OK");
     assert_eq!(
         stats,
@@ -1518,7 +1671,19 @@ async fn import_dump_v4_movie_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "searchDefaults": null,
+      "minNumericPrefixLength": 4,
+      "allowUnderscoreFields": false,
+      "localizedAttributes": null,
+      "numberNormalization": false,
+      "phraseAcrossFields": false,
+      "exactAttributes": [],
+      "decompoundingLocales": [],
+      "facetTypes": {},
+      "stemming": {
+        "locales": []
+      }
     }
     "###
     );
@@ -1602,6 +1767,8 @@ async fn import_dump_v4_rubygems_with_settings() {
     let index = server.index("rubygems");
 
     let (stats, code) = index.stats().await;
+
+    let stats = without_runtime_stats(stats);
     snapshot!(code, @"200 OK");
     assert_eq!(
         stats,
@@ -1663,7 +1830,19 @@ async fn import_dump_v4_rubygems_with_settings() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "searchDefaults": null,
+      "minNumericPrefixLength": 4,
+      "allowUnderscoreFields": false,
+      "localizedAttributes": null,
+      "numberNormalization": false,
+      "phraseAcrossFields": false,
+      "exactAttributes": [],
+      "decompoundingLocales": [],
+      "facetTypes": {},
+      "stemming": {
+        "locales": []
+      }
     }
     "###
     );
@@ -1775,6 +1954,8 @@ async fn import_dump_v5() {
     let index2 = server.index("test2");
 
     let (stats, code) = index1.stats().await;
+
+    let stats = without_runtime_stats(stats);
     snapshot!(code, @"200 OK");
     assert_eq!(stats, expected_stats);
 
@@ -1786,6 +1967,8 @@ async fn import_dump_v5() {
     assert_eq!(docs["results"].as_array().unwrap().len(), 10);
 
     let (stats, code) = index2.stats().await;
+
+    let stats = without_runtime_stats(stats);
     snapshot!(code, @"200 OK");
     assert_eq!(stats, expected_stats);
 
@@ -1860,7 +2043,13 @@ async fn import_dump_v6_containing_experimental_features() {
       "vectorStore": false,
       "metrics": false,
       "logsRoute": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "debugRoutes": false,
+      "relevanceCheck": false,
+      "wordFrequency": false,
+      "documentTasks": false,
+      "recentSearches": false,
+      "rankingRulesOverride": false
     }
     "###);
 
@@ -1908,7 +2097,19 @@ async fn import_dump_v6_containing_experimental_features() {
       "pagination": {
         "maxTotalHits": 1000
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "searchDefaults": null,
+      "minNumericPrefixLength": 4,
+      "allowUnderscoreFields": false,
+      "localizedAttributes": null,
+      "numberNormalization": false,
+      "phraseAcrossFields": false,
+      "exactAttributes": [],
+      "decompoundingLocales": [],
+      "facetTypes": {},
+      "stemming": {
+        "locales": []
+      }
     }
     "###);
 
@@ -1939,3 +2140,156 @@ async fn import_dump_v6_containing_experimental_features() {
         })
         .await;
 }
+
+#[actix_rt::test]
+async fn dump_of_an_encrypted_instance_is_decrypted() {
+    let temp = tempfile::tempdir().unwrap();
+    let options = Opt {
+        experimental_index_encryption_key: Some(
+            "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=".to_string(),
+        ),
+        ..default_settings(temp.path())
+    };
+    let server = Server::new_with_options(options).await.unwrap();
+    let index = server.index("test");
+    index.update_settings(json!({ "searchableAttributes": ["title"] })).await;
+    let (task, _) = index
+        .add_documents(
+            json!([{ "id": 1, "title": "Carol", "secret": "correct horse battery staple" }]),
+            Some("id"),
+        )
+        .await;
+    index.wait_task(task.uid()).await;
+
+    let (documents, code) = index.get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(documents["results"]), @r###"
+    [
+      {
+        "id": 1,
+        "title": "Carol",
+        "secret": "correct horse battery staple"
+      }
+    ]
+    "###);
+
+    // the content of the documents is not stored in plain text
+    for entry in walkdir::WalkDir::new(temp.path().join("db").join("indexes")) {
+        let entry = entry.unwrap();
+        if entry.file_type().is_file() {
+            let content = std::fs::read(entry.path()).unwrap();
+            assert!(
+                !content.windows(28).any(|w| w == b"correct horse battery staple"),
+                "{} contains the secret",
+                entry.path().display()
+            );
+        }
+    }
+
+    let (task, code) = server.create_dump().await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(task.uid()).await;
+    snapshot!(task["status"], @r###""succeeded""###);
+    let dump_uid = task["details"]["dumpUid"].as_str().unwrap();
+    let dump_path = temp.path().join("dumps").join(format!("{dump_uid}.dump"));
+
+    // the dump can be imported by an instance that doesn't have the key
+    let temp = tempfile::tempdir().unwrap();
+    let options = Opt { import_dump: Some(dump_path), ..default_settings(temp.path()) };
+    let server = Server::new_with_options(options).await.unwrap();
+    let (documents, code) =
+        server.index("test").get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(documents["results"]), @r###"
+    [
+      {
+        "id": 1,
+        "title": "Carol",
+        "secret": "correct horse battery staple"
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+#[cfg_attr(target_os = "windows", ignore)]
+async fn import_dump_with_invalid_index_uids() {
+    use meilisearch_types::tasks::Status;
+
+    let temp = tempfile::tempdir().unwrap();
+    let dump_path = temp.path().join("invalid-index-uids.dump");
+
+    // older versions accepted index uids that are not valid anymore
+    let dump = dump::DumpWriter::new(None).unwrap();
+    let now = time::OffsetDateTime::now_utc();
+    for uid in ["my movies", "my_movies"] {
+        let metadata = dump::IndexMetadata {
+            uid: uid.to_string(),
+            primary_key: Some("id".to_string()),
+            created_at: now,
+            updated_at: now,
+        };
+        let mut index = dump.create_index(uid, &metadata).unwrap();
+        let document = serde_json::json!({ "id": 1, "title": uid });
+        index.push_document(document.as_object().unwrap()).unwrap();
+        index.flush().unwrap();
+        index.settings(&Default::default()).unwrap();
+    }
+    let mut tasks = dump.create_tasks_queue().unwrap();
+    tasks
+        .push_task(&dump::TaskDump {
+            uid: 0,
+            index_uid: Some("my movies".to_string()),
+            status: Status::Succeeded,
+            kind: dump::KindDump::IndexCreation { primary_key: Some("id".to_string()) },
+            canceled_by: None,
+            details: None,
+            error: None,
+            enqueued_at: now,
+            started_at: Some(now),
+            finished_at: Some(now),
+            attempts: Vec::new(),
+        })
+        .unwrap();
+    tasks.flush().unwrap();
+    dump.create_keys().unwrap().flush().unwrap();
+    dump.persist_to(std::fs::File::create(&dump_path).unwrap()).unwrap();
+
+    let options = Opt { import_dump: Some(dump_path), ..default_settings(temp.path()) };
+    let server = Server::new_with_options(options).await.unwrap();
+
+    // the invalid uid is sanitized without taking the uid of the other index
+    let (indexes, code) = server.list_indexes(None, None).await;
+    snapshot!(code, @"200 OK");
+    let uids: Vec<_> =
+        indexes["results"].as_array().unwrap().iter().map(|index| &index["uid"]).collect();
+    snapshot!(format!("{uids:?}"), @r###"[String("my_movies"), String("my_movies_1")]"###);
+
+    let (documents, code) =
+        server.index("my_movies_1").get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(documents["results"]), @r###"
+    [
+      {
+        "id": 1,
+        "title": "my movies"
+      }
+    ]
+    "###);
+    let (documents, code) =
+        server.index("my_movies").get_all_documents(GetAllDocumentsOptions::default()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(documents["results"]), @r###"
+    [
+      {
+        "id": 1,
+        "title": "my_movies"
+      }
+    ]
+    "###);
+
+    // and the tasks follow their index
+    let (task, code) = server.get_task(0).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(task["indexUid"], @r###""my_movies_1""###);
+}
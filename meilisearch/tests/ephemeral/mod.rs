@@ -0,0 +1,34 @@
+use meilisearch::{use_ephemeral_db_path, Opt};
+
+use crate::common::{default_settings, Server};
+use crate::json;
+
+#[actix_rt::test]
+async fn ephemeral_storage_is_deleted_on_shutdown() {
+    let temp = tempfile::tempdir().unwrap();
+    let mut options = Opt { experimental_ephemeral_storage: true, ..default_settings(temp.path()) };
+    let ephemeral_dir = use_ephemeral_db_path(&mut options).unwrap();
+    let db_path = options.db_path.clone();
+    assert!(db_path.starts_with(ephemeral_dir.path()));
+
+    let server = Server::new_with_options(options).await.unwrap();
+    let index = server.index("test");
+    let documents = json!([
+        { "id": 1, "kind": "doggo" },
+        { "id": 2, "kind": "catto" },
+    ]);
+    let (task, _code) = index.add_documents(documents, Some("id")).await;
+    index.wait_task(task.uid()).await;
+
+    index
+        .search(json!({"q": "doggo"}), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert_eq!(response["hits"], json!([{ "id": 1, "kind": "doggo" }]));
+        })
+        .await;
+    assert!(db_path.join("indexes").exists());
+
+    drop(server);
+    drop(ephemeral_dir);
+    assert!(!db_path.exists());
+}
@@ -21,7 +21,13 @@ async fn experimental_features() {
       "vectorStore": false,
       "metrics": false,
       "logsRoute": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "debugRoutes": false,
+      "relevanceCheck": false,
+      "wordFrequency": false,
+      "documentTasks": false,
+      "recentSearches": false,
+      "rankingRulesOverride": false
     }
     "###);
 
@@ -33,7 +39,13 @@ async fn experimental_features() {
       "vectorStore": true,
       "metrics": false,
       "logsRoute": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "debugRoutes": false,
+      "relevanceCheck": false,
+      "wordFrequency": false,
+      "documentTasks": false,
+      "recentSearches": false,
+      "rankingRulesOverride": false
     }
     "###);
 
@@ -45,7 +57,13 @@ async fn experimental_features() {
       "vectorStore": true,
       "metrics": false,
       "logsRoute": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "debugRoutes": false,
+      "relevanceCheck": false,
+      "wordFrequency": false,
+      "documentTasks": false,
+      "recentSearches": false,
+      "rankingRulesOverride": false
     }
     "###);
 
@@ -58,7 +76,13 @@ async fn experimental_features() {
       "vectorStore": true,
       "metrics": false,
       "logsRoute": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "debugRoutes": false,
+      "relevanceCheck": false,
+      "wordFrequency": false,
+      "documentTasks": false,
+      "recentSearches": false,
+      "rankingRulesOverride": false
     }
     "###);
 
@@ -71,7 +95,13 @@ async fn experimental_features() {
       "vectorStore": true,
       "metrics": false,
       "logsRoute": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "debugRoutes": false,
+      "relevanceCheck": false,
+      "wordFrequency": false,
+      "documentTasks": false,
+      "recentSearches": false,
+      "rankingRulesOverride": false
     }
     "###);
 }
@@ -91,7 +121,13 @@ async fn experimental_feature_metrics() {
       "vectorStore": false,
       "metrics": true,
       "logsRoute": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "debugRoutes": false,
+      "relevanceCheck": false,
+      "wordFrequency": false,
+      "documentTasks": false,
+      "recentSearches": false,
+      "rankingRulesOverride": false
     }
     "###);
 
@@ -146,7 +182,7 @@ async fn errors() {
     meili_snap::snapshot!(code, @"400 Bad Request");
     meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
     {
-      "message": "Unknown field `NotAFeature`: expected one of `vectorStore`, `metrics`, `logsRoute`, `exportPuffinReports`",
+      "message": "Unknown field `NotAFeature`: expected one of `vectorStore`, `metrics`, `logsRoute`, `exportPuffinReports`, `debugRoutes`, `relevanceCheck`, `wordFrequency`, `documentTasks`, `recentSearches`, `rankingRulesOverride`",
       "code": "bad_request",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#bad_request"
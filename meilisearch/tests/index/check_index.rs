@@ -0,0 +1,63 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::Server;
+use crate::json;
+
+#[actix_rt::test]
+async fn check_index() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index
+        .add_documents(json!([{ "id": 1, "name": "kefir" }, { "id": 2, "name": "intel" }]), None)
+        .await;
+    index.wait_task(task.uid()).await;
+
+    let (task, code) = server.service.post("/indexes/test/check", json!({})).await;
+    snapshot!(code, @"202 Accepted");
+    let task = index.wait_task(task.uid()).await;
+    snapshot!(task["status"], @r###""succeeded""###);
+    snapshot!(task["type"], @r###""integrityCheck""###);
+    snapshot!(json_string!(task["details"]), @r###"
+    {
+      "repair": false,
+      "violations": {}
+    }
+    "###);
+
+    let (task, code) = server.service.post("/indexes/test/check", json!({ "repair": true })).await;
+    snapshot!(code, @"202 Accepted");
+    let task = index.wait_task(task.uid()).await;
+    snapshot!(json_string!(task["details"]), @r###"
+    {
+      "repair": true,
+      "violations": {}
+    }
+    "###);
+
+    // the documents are left untouched
+    let (response, _code) = index.get_all_documents_raw("").await;
+    snapshot!(response["total"], @"2");
+}
+
+#[actix_rt::test]
+async fn check_index_errors() {
+    let server = Server::new().await;
+
+    let (task, code) = server.service.post("/indexes/doggo/check", json!({})).await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(task.uid()).await;
+    snapshot!(task["status"], @r###""failed""###);
+    snapshot!(task["error"]["code"], @r###""index_not_found""###);
+
+    let (response, code) =
+        server.service.post("/indexes/doggo/check", json!({ "repair": "yes" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.repair`: expected a boolean, but found a string: `\"yes\"`",
+      "code": "invalid_integrity_check_repair",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_integrity_check_repair"
+    }
+    "###);
+}
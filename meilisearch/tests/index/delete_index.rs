@@ -62,3 +62,36 @@ async fn loop_delete_add_documents() {
         assert_eq!(response["status"], "succeeded", "{}", response);
     }
 }
+
+#[actix_rt::test]
+async fn search_while_deleting_index() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let documents = json!([{"id": 1, "field1": "hello"}, {"id": 2, "field1": "world"}]);
+
+    for _ in 0..10 {
+        let (response, code) = index.add_documents(documents.clone(), None).await;
+        assert_eq!(code, 202, "{}", response);
+        index.wait_task(response["taskUid"].as_u64().unwrap()).await;
+
+        let searches: Vec<_> = (0..20).map(|_| index.search_post(json!({"q": "hello"}))).collect();
+        let deletion = async {
+            let (response, code) = index.delete().await;
+            assert_eq!(code, 202, "{}", response);
+            index.wait_task(response["taskUid"].as_u64().unwrap()).await
+        };
+        let (responses, deletion) = futures::join!(futures::future::join_all(searches), deletion);
+        assert_eq!(deletion["status"], "succeeded", "{}", deletion);
+
+        for (response, code) in responses {
+            match code.as_u16() {
+                200 => (),
+                404 => assert_eq!(response["code"], "index_not_found", "{}", response),
+                _ => panic!("unexpected search failure: {code} {response}"),
+            }
+        }
+
+        let (response, code) = index.search_post(json!({"q": "hello"})).await;
+        assert_eq!(code, 404, "{}", response);
+    }
+}
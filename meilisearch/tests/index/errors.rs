@@ -75,7 +75,7 @@ async fn create_index_bad_uid() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(json_string!(response), @r###"
     {
-      "message": "Invalid value at `.uid`: `the best doggo` is not a valid index uid. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_).",
+      "message": "Invalid value at `.uid`: `the best doggo` is not a valid index uid: the character ` ` at position 3 is not allowed. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_), of at most 400 bytes. Try `the_best_doggo` instead.",
       "code": "invalid_index_uid",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_index_uid"
@@ -137,7 +137,7 @@ async fn get_index_bad_uid() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(json_string!(response), @r###"
     {
-      "message": "`the good doggo` is not a valid index uid. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_).",
+      "message": "`the good doggo` is not a valid index uid: the character ` ` at position 3 is not allowed. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_), of at most 400 bytes. Try `the_good_doggo` instead.",
       "code": "invalid_index_uid",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_index_uid"
@@ -239,7 +239,7 @@ async fn update_index_bad_uid() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(json_string!(response), @r###"
     {
-      "message": "`the good doggo` is not a valid index uid. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_).",
+      "message": "`the good doggo` is not a valid index uid: the character ` ` at position 3 is not allowed. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_), of at most 400 bytes. Try `the_good_doggo` instead.",
       "code": "invalid_index_uid",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_index_uid"
@@ -256,7 +256,7 @@ async fn delete_index_bad_uid() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(json_string!(response), @r###"
     {
-      "message": "`the good doggo` is not a valid index uid. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_).",
+      "message": "`the good doggo` is not a valid index uid: the character ` ` at position 3 is not allowed. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_), of at most 400 bytes. Try `the_good_doggo` instead.",
       "code": "invalid_index_uid",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_index_uid"
@@ -186,10 +186,36 @@ async fn get_invalid_index_uid() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(json_string!(response), @r###"
     {
-      "message": "`this is not a valid index name` is not a valid index uid. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_).",
+      "message": "`this is not a valid index name` is not a valid index uid: the character ` ` at position 4 is not allowed. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_), of at most 400 bytes. Try `this_is_not_a_valid_index_name` instead.",
       "code": "invalid_index_uid",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_index_uid"
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn unpark_index() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.create(None).await;
+    index.wait_task(task.uid()).await;
+
+    // unparking an index that isn't parked is a no-op
+    let (_response, code) = server.service.post("/indexes/test/unpark", crate::json!(null)).await;
+    snapshot!(code, @"204 No Content");
+    let (response, code) = index.get().await;
+    snapshot!(code, @"200 OK");
+    assert!(response.get("parked").is_none(), "{response}");
+
+    let (response, code) = server.service.post("/indexes/doggo/unpark", crate::json!(null)).await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Index `doggo` not found.",
+      "code": "index_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#index_not_found"
+    }
+    "###);
+}
@@ -1,6 +1,9 @@
+mod check_index;
 mod create_index;
 mod delete_index;
 mod errors;
 mod get_index;
 mod stats;
+mod timeline;
 mod update_index;
+mod warm_up_index;
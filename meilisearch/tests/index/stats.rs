@@ -45,6 +45,92 @@ async fn stats() {
     assert_eq!(response["fieldDistribution"]["age"], 1);
 }
 
+#[actix_rt::test]
+async fn stats_of_the_databases() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 1, "name": "kefir" },
+        { "id": 2, "name": "intel" },
+        { "id": 3, "name": "bobby" },
+    ]);
+    let (response, code) = index.add_documents(documents, None).await;
+    assert_eq!(code, 202);
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index.stats().await;
+    assert_eq!(code, 200);
+
+    let databases = response["databases"].as_object().unwrap();
+    assert!(databases.contains_key("main"));
+    assert!(databases.contains_key("word-docids"));
+    for (name, stats) in databases {
+        for key in ["pageSize", "depth", "branchPages", "leafPages", "overflowPages", "entries"] {
+            assert!(stats[key].is_u64(), "missing `{key}` in the stats of `{name}`: {stats}");
+        }
+    }
+    assert_eq!(databases["documents"]["entries"], response["numberOfDocuments"]);
+    assert_eq!(databases["external-documents-ids"]["entries"], 3);
+
+    let fragmentation = response["fragmentation"].as_f64().unwrap();
+    assert!((0.0..=1.0).contains(&fragmentation), "{fragmentation}");
+}
+
+#[actix_rt::test]
+async fn stats_of_the_search_latencies() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, _code) =
+        index.update_settings(json!({ "filterableAttributes": ["color"] })).await;
+    index.wait_task(response.uid()).await;
+    let documents = json!([
+        { "id": 1, "name": "kefir", "color": "blue" },
+        { "id": 2, "name": "intel", "color": "red" },
+    ]);
+    let (response, _code) = index.add_documents(documents, None).await;
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index.stats().await;
+    assert_eq!(code, 200);
+    assert_eq!(
+        response["searchLatency"]["search"],
+        json!({ "count": 0, "p50": null, "p90": null, "p99": null })
+    );
+
+    for q in ["kefir", "intel", "bobby", "", "kef"] {
+        let (_response, code) = index.search_post(json!({ "q": q })).await;
+        assert_eq!(code, 200);
+    }
+    let (_response, code) = server
+        .multi_search(
+            json!({ "queries": [{ "indexUid": "test", "q": "kefir" }, { "indexUid": "test" }] }),
+        )
+        .await;
+    assert_eq!(code, 200);
+    let (_response, code) = index.facet_search(json!({ "facetName": "color" })).await;
+    assert_eq!(code, 200);
+
+    let (response, code) = index.stats().await;
+    assert_eq!(code, 200);
+    let latencies = &response["searchLatency"];
+    for (kind, count) in [("search", 5), ("multiSearch", 2), ("facetSearch", 1)] {
+        let stats = &latencies[kind];
+        assert_eq!(stats["count"], count, "{kind}: {stats}");
+        let p50 = stats["p50"].as_f64().unwrap();
+        let p90 = stats["p90"].as_f64().unwrap();
+        let p99 = stats["p99"].as_f64().unwrap();
+        assert!(0.0 < p50 && p50 <= p90 && p90 <= p99, "{kind}: {stats}");
+    }
+
+    // the latencies of the other indexes are tracked separately
+    let (response, _code) = server.index("other").create(None).await;
+    server.wait_task(response.uid()).await;
+    let (response, _code) = server.index("other").stats().await;
+    assert_eq!(response["searchLatency"]["search"]["count"], 0);
+}
+
 #[actix_rt::test]
 async fn error_get_stats_unexisting_index() {
     let server = Server::new().await;
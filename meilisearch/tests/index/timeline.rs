@@ -0,0 +1,127 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::{Server, Value};
+use crate::json;
+
+/// Sums a counter of the buckets of the timeline.
+fn sum(timeline: &Value, counter: &str) -> u64 {
+    timeline["buckets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|bucket| bucket[counter].as_u64().unwrap())
+        .sum()
+}
+
+/// Concatenates a list of the buckets of the timeline.
+fn concat(timeline: &Value, list: &str) -> Vec<serde_json::Value> {
+    timeline["buckets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|bucket| bucket[list].as_array().unwrap().clone())
+        .collect()
+}
+
+#[actix_rt::test]
+async fn timeline_of_a_few_batches() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 1, "title": "kefir" },
+        { "id": 2, "title": "intel" },
+        { "id": 3, "title": "bobby" },
+    ]);
+    let (task, _code) = index.add_documents(documents, Some("id")).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.delete_document(2).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.update_settings(json!({ "rankingRules": ["words"] })).await;
+    let settings_task = index.wait_task(task.uid()).await;
+    // an invalid document id fails its task
+    let (task, _code) = index.add_documents(json!([{ "id": "a b" }]), None).await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+
+    let (timeline, code) = server.service.get("/indexes/test/timeline?interval=day").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(timeline["interval"], @r###""day""###);
+    snapshot!(sum(&timeline, "documentsAdded"), @"3");
+    snapshot!(sum(&timeline, "documentsDeleted"), @"1");
+    snapshot!(sum(&timeline, "failedTasks"), @"1");
+
+    let settings_changes = concat(&timeline, "settingsChanges");
+    snapshot!(json_string!(settings_changes, { "[].finishedAt" => "[date]" }), @r###"
+    [
+      {
+        "taskUid": 2,
+        "finishedAt": "[date]",
+        "changedSettings": [
+          "rankingRules"
+        ]
+      }
+    ]
+    "###);
+    assert_eq!(settings_changes[0]["finishedAt"], settings_task["finishedAt"]);
+
+    // the size of the index is recorded after each batch
+    let size_samples = concat(&timeline, "sizeSamples");
+    assert!(!size_samples.is_empty());
+    let last = size_samples.last().unwrap();
+    snapshot!(last["numberOfDocuments"], @"2");
+    assert!(last["databaseSize"].as_u64().unwrap() > 0);
+    assert!(last["usedDatabaseSize"].as_u64().unwrap() > 0);
+    assert!(last["recordedAt"].is_string());
+}
+
+#[actix_rt::test]
+async fn timeline_between_dates() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.add_documents(json!([{ "id": 1 }]), Some("id")).await;
+    index.wait_task(task.uid()).await;
+
+    // nothing happened before the year 2000
+    let (timeline, code) = server.service.get("/indexes/test/timeline?to=1999-12-31").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(timeline), @r###"
+    {
+      "interval": "hour",
+      "buckets": []
+    }
+    "###);
+
+    let (timeline, code) =
+        server.service.get("/indexes/test/timeline?from=2000-01-01&interval=minute").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(sum(&timeline, "documentsAdded"), @"1");
+}
+
+#[actix_rt::test]
+async fn timeline_errors() {
+    let server = Server::new().await;
+
+    let (response, code) = server.service.get("/indexes/test/timeline").await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(response["code"], @r###""index_not_found""###);
+
+    let index = server.index("test");
+    let (task, _code) = index.create(None).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = server.service.get("/indexes/test/timeline?interval=week").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_timeline_interval""###);
+
+    let (response, code) = server.service.get("/indexes/test/timeline?from=yesterday").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `from`: `yesterday` is an invalid date-time. It should follow the YYYY-MM-DD or RFC 3339 date-time format.",
+      "code": "invalid_timeline_from",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_timeline_from"
+    }
+    "###);
+}
@@ -95,15 +95,83 @@ async fn error_update_existing_primary_key() {
     let response = index.wait_task(2).await;
 
     let expected_response = json!({
-        "message": "Index already has a primary key: `id`.",
-        "code": "index_primary_key_already_exists",
+        "message": "The primary key cannot be changed to `primary` because it is missing or invalid in the following documents: `11`.",
+        "code": "invalid_index_primary_key",
         "type": "invalid_request",
-        "link": "https://docs.meilisearch.com/errors#index_primary_key_already_exists"
+        "link": "https://docs.meilisearch.com/errors#invalid_index_primary_key"
     });
 
     assert_eq!(response["error"], expected_response);
 }
 
+#[actix_rt::test]
+async fn update_primary_key_of_non_empty_index() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (_response, code) = index.create(Some("id")).await;
+    assert_eq!(code, 202);
+
+    let documents = json!([
+        { "id": 1, "sku": "kefir-01", "name": "kefir" },
+        { "id": 2, "sku": "intel-02", "name": "intel" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (response, code) = index.update(Some("sku")).await;
+    assert_eq!(code, 202);
+
+    // the index stays searchable while its primary key is being changed
+    let (search, code) = index.search_post(json!({ "q": "kefir" })).await;
+    assert_eq!(code, 200);
+    assert_eq!(search["hits"].as_array().unwrap().len(), 1);
+
+    let response = index.wait_task(response["taskUid"].as_u64().unwrap()).await;
+    assert_eq!(response["status"], "succeeded");
+
+    let (response, _code) = index.get().await;
+    assert_eq!(response["primaryKey"], "sku");
+
+    // the documents can now be fetched with their new external id
+    let (response, code) = server.service.get("/indexes/test/documents/kefir-01").await;
+    assert_eq!(code, 200);
+    assert_eq!(response, json!({ "id": 1, "sku": "kefir-01", "name": "kefir" }));
+    let (_response, code) = index.get_document(1, None).await;
+    assert_eq!(code, 404);
+}
+
+#[actix_rt::test]
+async fn error_update_primary_key_with_duplicate_values() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (_response, code) = index.create(Some("id")).await;
+    assert_eq!(code, 202);
+
+    let documents = json!([
+        { "id": 1, "color": "blue" },
+        { "id": 2, "color": "red" },
+        { "id": 3, "color": "blue" },
+    ]);
+    index.add_documents(documents, None).await;
+
+    let (response, code) = index.update(Some("color")).await;
+    assert_eq!(code, 202);
+
+    let response = index.wait_task(response["taskUid"].as_u64().unwrap()).await;
+
+    let expected_response = json!({
+        "message": "The primary key cannot be changed to `color` because the following documents share the same value for it: `1`, `3`.",
+        "code": "invalid_index_primary_key",
+        "type": "invalid_request",
+        "link": "https://docs.meilisearch.com/errors#invalid_index_primary_key"
+    });
+
+    assert_eq!(response["error"], expected_response);
+
+    let (response, _code) = index.get().await;
+    assert_eq!(response["primaryKey"], "id");
+}
+
 #[actix_rt::test]
 async fn error_update_unexisting_index() {
     let server = Server::new().await;
@@ -0,0 +1,82 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::Server;
+use crate::json;
+
+#[actix_rt::test]
+async fn warm_up_index() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index
+        .add_documents(
+            json!([
+                { "id": 1, "name": "kefir" },
+                { "id": 2, "name": "kefirounet" },
+                { "id": 3, "name": "intel" },
+            ]),
+            None,
+        )
+        .await;
+    index.wait_task(task.uid()).await;
+
+    let (task, code) =
+        server.service.post("/indexes/test/warmup", json!({ "prefixes": ["kef"] })).await;
+    snapshot!(code, @"202 Accepted");
+    let task = index.wait_task(task.uid()).await;
+    snapshot!(task["status"], @r###""succeeded""###);
+    snapshot!(task["type"], @r###""indexWarmup""###);
+    snapshot!(json_string!(task["details"]), @r###"
+    {
+      "prefixes": [
+        "kef"
+      ],
+      "populateCache": false,
+      "warmedEntries": 2
+    }
+    "###);
+
+    let (task, code) = server
+        .service
+        .post("/indexes/test/warmup", json!({ "prefixes": ["kef", "int"], "populateCache": true }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let task = index.wait_task(task.uid()).await;
+    snapshot!(task["status"], @r###""succeeded""###);
+    snapshot!(task["details"]["warmedEntries"], @"3");
+
+    // the searches are answered the same from the shared cache
+    index
+        .search(json!({ "q": "kef", "attributesToRetrieve": ["id"] }), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 1
+              },
+              {
+                "id": 2
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    let (response, code) = server.service.get("/tasks?types=indexWarmup").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["total"], @"2");
+}
+
+#[actix_rt::test]
+async fn warm_up_index_requires_prefixes() {
+    let server = Server::new().await;
+    let (response, code) = server.service.post("/indexes/test/warmup", json!({})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `prefixes`",
+      "code": "missing_index_warmup_prefixes",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#missing_index_warmup_prefixes"
+    }
+    "###);
+}
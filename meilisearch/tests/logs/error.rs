@@ -89,7 +89,7 @@ async fn logs_stream_bad_mode() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(response, @r###"
     {
-      "message": "Unknown value `tamo` at `.mode`: expected one of `human`, `json`, `profile`",
+      "message": "Unknown value `tamo` at `.mode`: expected one of `human`, `json`, `profile`, `spanStats`",
       "code": "bad_request",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#bad_request"
@@ -146,7 +146,77 @@ async fn logs_stream_bad_profile_memory() {
     snapshot!(code, @"400 Bad Request");
     snapshot!(response, @r###"
     {
-      "message": "Unknown value `fmt` at `.mode`: expected one of `human`, `json`, `profile`",
+      "message": "Unknown value `fmt` at `.mode`: expected one of `human`, `json`, `profile`, `spanStats`",
+      "code": "bad_request",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#bad_request"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn logs_stream_bad_heartbeat_interval() {
+    let server = Server::new().await;
+
+    let (response, code) =
+        server.service.post("/logs/stream", json!({ "heartbeatIntervalMs": "30s" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid value type at `.heartbeatIntervalMs`: expected a positive integer, but found a string: `\"30s\"`",
+      "code": "bad_request",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#bad_request"
+    }
+    "###);
+
+    let (response, code) =
+        server.service.post("/logs/stream", json!({ "heartbeatIntervalMs": 0 })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid value: `heartbeatIntervalMs` must be greater than 0.",
+      "code": "invalid_settings_typo_tolerance",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_typo_tolerance"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn logs_stderr_bad_file() {
+    let server = Server::new().await;
+
+    // Wrong type
+    let (response, code) = server.service.post("/logs/stderr", json!({ "path": true })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid value type at `.path`: expected a string, but found a boolean: `true`",
+      "code": "bad_request",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#bad_request"
+    }
+    "###);
+
+    let (response, code) =
+        server.service.post("/logs/stderr", json!({ "path": "logs.txt", "mode": "profile" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Unknown value `profile` at `.mode`: expected one of `human`, `json`",
+      "code": "bad_request",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#bad_request"
+    }
+    "###);
+
+    // Used without a path
+    let (response, code) = server.service.post("/logs/stderr", json!({ "mode": "json" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid value: `mode` and `createDirs` can only be used to write the logs into a `path`.",
       "code": "bad_request",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#bad_request"
@@ -180,6 +250,17 @@ async fn logs_stream_without_enabling_the_route() {
     }
     "###);
 
+    let (response, code) = server.service.patch("/logs/stream", json!({})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Modifying logs through the `/logs/*` routes requires enabling the `logs route` experimental feature. See https://github.com/orgs/meilisearch/discussions/721",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+
     let (response, code) = server.service.post("/logs/stderr", json!({})).await;
     snapshot!(code, @"400 Bad Request");
     snapshot!(response, @r###"
@@ -191,3 +272,47 @@ async fn logs_stream_without_enabling_the_route() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn logs_stream_patch_bad_target() {
+    let server = Server::new().await;
+
+    let (response, code) = server.service.patch("/logs/stream", json!({ "target": true })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid value type at `.target`: expected a string, but found a boolean: `true`",
+      "code": "bad_request",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#bad_request"
+    }
+    "###);
+
+    let (response, code) = server.service.patch("/logs/stream", json!({ "target": "==" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "Invalid value at `.target`: invalid filter directive: too many '=' in filter directive, expected 0 or 1",
+      "code": "bad_request",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#bad_request"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn logs_stream_patch_without_stream() {
+    let server = Server::new().await;
+    server.set_features(json!({ "logsRoute": true })).await;
+
+    let (response, code) = server.service.patch("/logs/stream", json!({ "target": "info" })).await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(response, @r###"
+    {
+      "message": "There is no stream on the `/logs/stream` route.",
+      "code": "log_stream_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#log_stream_not_found"
+    }
+    "###);
+}
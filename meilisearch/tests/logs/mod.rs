@@ -2,10 +2,16 @@ mod error;
 
 use std::rc::Rc;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use actix_web::http::header::ContentType;
-use meili_snap::snapshot;
-use meilisearch::{analytics, create_app, Opt, SubscriberForSecondLayer};
+use actix_web::web::Bytes;
+use meili_snap::{json_string, snapshot};
+use meilisearch::{
+    analytics, create_app, LogRouteHandle, LogStderrHandle, LogStreamState, Opt,
+    SubscriberForSecondLayer,
+};
+use once_cell::sync::Lazy;
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::Layer;
@@ -13,6 +19,33 @@ use tracing_subscriber::Layer;
 use crate::common::{default_settings, Server};
 use crate::json;
 
+/// The global subscriber can only be set once per process, the tests logging from other threads
+/// than their own share its route and stderr layers, and take turns with the lock.
+#[allow(clippy::type_complexity)]
+static GLOBAL_ROUTE_LAYER: Lazy<(
+    LogRouteHandle,
+    LogStderrHandle,
+    Arc<LogStreamState>,
+    tokio::sync::Mutex<()>,
+)> = Lazy::new(|| {
+    let (route_layer, route_layer_handle) =
+        tracing_subscriber::reload::Layer::new(None.with_filter(
+            tracing_subscriber::filter::Targets::new().with_target("", LevelFilter::OFF),
+        ));
+    let (stderr_layer, stderr_layer_handle) = tracing_subscriber::reload::Layer::new(
+        (Box::new(
+            tracing_subscriber::fmt::layer()
+                .with_span_events(tracing_subscriber::fmt::format::FmtSpan::ACTIVE),
+        ) as Box<dyn tracing_subscriber::Layer<SubscriberForSecondLayer> + Send + Sync>)
+            .with_filter(tracing_subscriber::filter::Targets::from_str("OFF").unwrap()),
+    );
+
+    let subscriber = tracing_subscriber::registry().with(route_layer).with(stderr_layer);
+    tracing::subscriber::set_global_default(subscriber).unwrap();
+
+    (route_layer_handle, stderr_layer_handle, Default::default(), tokio::sync::Mutex::new(()))
+});
+
 #[actix_web::test]
 async fn basic_test_log_stream_route() {
     let db_path = tempfile::tempdir().unwrap();
@@ -23,10 +56,10 @@ async fn basic_test_log_stream_route() {
     .await
     .unwrap();
 
-    let (route_layer, route_layer_handle) =
-        tracing_subscriber::reload::Layer::new(None.with_filter(
-            tracing_subscriber::filter::Targets::new().with_target("", LevelFilter::OFF),
-        ));
+    // the global subscriber is shared by the tests that need it, they take turns
+    let (route_layer_handle, _, logs_stream, lock) = &*GLOBAL_ROUTE_LAYER;
+    let _lock = lock.lock().await;
+
     let (_stderr_layer, stderr_layer_handle) = tracing_subscriber::reload::Layer::new(
         (Box::new(
             tracing_subscriber::fmt::layer()
@@ -35,25 +68,19 @@ async fn basic_test_log_stream_route() {
             .with_filter(tracing_subscriber::filter::Targets::new()),
     );
 
-    let subscriber = tracing_subscriber::registry().with(route_layer).with(
-        tracing_subscriber::fmt::layer()
-            .with_span_events(tracing_subscriber::fmt::format::FmtSpan::ACTIVE)
-            .with_filter(tracing_subscriber::filter::LevelFilter::from_str("OFF").unwrap()),
-    );
-
     let app = actix_web::test::init_service(create_app(
         server.service.index_scheduler.clone().into(),
         server.service.auth.clone().into(),
+        server.service.search_queue.clone().into(),
+        server.service.search_latencies.clone().into(),
         server.service.options.clone(),
-        (route_layer_handle, stderr_layer_handle),
+        (route_layer_handle.clone(), stderr_layer_handle, logs_stream.clone()),
         analytics::MockAnalytics::new(&server.service.options),
+        server.service.query_preprocessor.clone(),
         true,
     ))
     .await;
 
-    // set the subscriber as the default for the application
-    tracing::subscriber::set_global_default(subscriber).unwrap();
-
     let app = Rc::new(app);
 
     // First, we start listening on the `/logs/stream` route
@@ -97,3 +124,615 @@ async fn basic_test_log_stream_route() {
     let logs = String::from_utf8(logs.to_vec()).unwrap();
     assert!(logs.contains("INFO"), "{logs}");
 }
+
+#[actix_web::test]
+async fn json_log_stream_route() {
+    let db_path = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_enable_logs_route: true,
+        ..default_settings(db_path.path())
+    })
+    .await
+    .unwrap();
+
+    let (route_layer, route_layer_handle) =
+        tracing_subscriber::reload::Layer::new(None.with_filter(
+            tracing_subscriber::filter::Targets::new().with_target("", LevelFilter::OFF),
+        ));
+    let (_stderr_layer, stderr_layer_handle) = tracing_subscriber::reload::Layer::new(
+        (Box::new(tracing_subscriber::fmt::layer())
+            as Box<dyn tracing_subscriber::Layer<SubscriberForSecondLayer> + Send + Sync>)
+            .with_filter(tracing_subscriber::filter::Targets::new()),
+    );
+
+    let subscriber = tracing_subscriber::registry().with(route_layer);
+
+    let app = actix_web::test::init_service(create_app(
+        server.service.index_scheduler.clone().into(),
+        server.service.auth.clone().into(),
+        server.service.search_queue.clone().into(),
+        server.service.search_latencies.clone().into(),
+        server.service.options.clone(),
+        (route_layer_handle, stderr_layer_handle, Default::default()),
+        analytics::MockAnalytics::new(&server.service.options),
+        server.service.query_preprocessor.clone(),
+        true,
+    ))
+    .await;
+
+    // The global subscriber can only be set once per process, the spans of the HTTP requests
+    // are all created on this thread though, so the subscriber is only set for this thread.
+    let _subscriber_guard = tracing::subscriber::set_default(subscriber);
+
+    let app = Rc::new(app);
+
+    let handle_app = app.clone();
+    let handle = tokio::task::spawn_local(async move {
+        let req = actix_web::test::TestRequest::post()
+            .uri("/logs/stream")
+            .insert_header(ContentType::json())
+            .set_payload(
+                serde_json::to_vec(&json!({
+                    "mode": "json",
+                    "target": "info",
+                }))
+                .unwrap(),
+            );
+        let req = req.to_request();
+        let ret = actix_web::test::call_service(&*handle_app, req).await;
+        actix_web::test::read_body(ret).await
+    });
+
+    // The span of the index creation request is logged when it closes
+    let (ret, _code) = server.create_index(json!({ "uid": "tamo" })).await;
+    server.wait_task(ret.uid()).await;
+
+    let req = actix_web::test::TestRequest::delete().uri("/logs/stream");
+    let req = req.to_request();
+    let ret = actix_web::test::call_service(&*app, req).await;
+    let code = ret.status();
+    snapshot!(code, @"204 No Content");
+
+    let logs = handle.await.unwrap();
+    let logs = String::from_utf8(logs.to_vec()).unwrap();
+    assert!(!logs.is_empty());
+    for line in logs.lines() {
+        let event: serde_json::Value = serde_json::from_str(line)
+            .unwrap_or_else(|e| panic!("the line `{line}` is not JSON: {e}"));
+        assert!(event.get("level").is_some(), "{line}");
+    }
+}
+
+#[actix_web::test]
+async fn patch_log_stream_target() {
+    let db_path = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_enable_logs_route: true,
+        ..default_settings(db_path.path())
+    })
+    .await
+    .unwrap();
+
+    let (route_layer, route_layer_handle) =
+        tracing_subscriber::reload::Layer::new(None.with_filter(
+            tracing_subscriber::filter::Targets::new().with_target("", LevelFilter::OFF),
+        ));
+    let (_stderr_layer, stderr_layer_handle) = tracing_subscriber::reload::Layer::new(
+        (Box::new(tracing_subscriber::fmt::layer())
+            as Box<dyn tracing_subscriber::Layer<SubscriberForSecondLayer> + Send + Sync>)
+            .with_filter(tracing_subscriber::filter::Targets::new()),
+    );
+
+    let subscriber = tracing_subscriber::registry().with(route_layer);
+
+    let app = actix_web::test::init_service(create_app(
+        server.service.index_scheduler.clone().into(),
+        server.service.auth.clone().into(),
+        server.service.search_queue.clone().into(),
+        server.service.search_latencies.clone().into(),
+        server.service.options.clone(),
+        (route_layer_handle, stderr_layer_handle, Default::default()),
+        analytics::MockAnalytics::new(&server.service.options),
+        server.service.query_preprocessor.clone(),
+        true,
+    ))
+    .await;
+
+    // The global subscriber can only be set once per process, the spans of the HTTP requests
+    // are all created on this thread though, so the subscriber is only set for this thread.
+    let _subscriber_guard = tracing::subscriber::set_default(subscriber);
+
+    let app = Rc::new(app);
+
+    let handle_app = app.clone();
+    let handle = tokio::task::spawn_local(async move {
+        let req = actix_web::test::TestRequest::post()
+            .uri("/logs/stream")
+            .insert_header(ContentType::json())
+            .set_payload(
+                serde_json::to_vec(&json!({
+                    "mode": "human",
+                    "target": "error",
+                }))
+                .unwrap(),
+            );
+        let req = req.to_request();
+        let ret = actix_web::test::call_service(&*handle_app, req).await;
+        actix_web::test::read_body(ret).await
+    });
+    // let the stream be created before patching it
+    tokio::task::yield_now().await;
+
+    // The span of this request is at the INFO level and is filtered out
+    let (_response, code) = server.index("before_patch").get().await;
+    snapshot!(code, @"404 Not Found");
+
+    let req = actix_web::test::TestRequest::patch()
+        .uri("/logs/stream")
+        .insert_header(ContentType::json())
+        .set_payload(serde_json::to_vec(&json!({ "target": "info" })).unwrap());
+    let ret = actix_web::test::call_service(&*app, req.to_request()).await;
+    snapshot!(ret.status(), @"204 No Content");
+
+    let (_response, code) = server.index("after_patch").get().await;
+    snapshot!(code, @"404 Not Found");
+
+    let req = actix_web::test::TestRequest::delete().uri("/logs/stream");
+    let ret = actix_web::test::call_service(&*app, req.to_request()).await;
+    snapshot!(ret.status(), @"204 No Content");
+
+    let logs = handle.await.unwrap();
+    let logs = String::from_utf8(logs.to_vec()).unwrap();
+    assert!(logs.contains("INFO"), "{logs}");
+    assert!(logs.contains("/indexes/after_patch"), "{logs}");
+    assert!(!logs.contains("/indexes/before_patch"), "{logs}");
+}
+
+#[actix_web::test]
+async fn span_stats_log_stream_route() {
+    let db_path = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_enable_logs_route: true,
+        ..default_settings(db_path.path())
+    })
+    .await
+    .unwrap();
+
+    // the search is processed on another thread, the global subscriber is needed
+    let (route_layer_handle, _, logs_stream, lock) = &*GLOBAL_ROUTE_LAYER;
+    let _lock = lock.lock().await;
+
+    let (_stderr_layer, stderr_layer_handle) = tracing_subscriber::reload::Layer::new(
+        (Box::new(tracing_subscriber::fmt::layer())
+            as Box<dyn tracing_subscriber::Layer<SubscriberForSecondLayer> + Send + Sync>)
+            .with_filter(tracing_subscriber::filter::Targets::new()),
+    );
+
+    let app = actix_web::test::init_service(create_app(
+        server.service.index_scheduler.clone().into(),
+        server.service.auth.clone().into(),
+        server.service.search_queue.clone().into(),
+        server.service.search_latencies.clone().into(),
+        server.service.options.clone(),
+        (route_layer_handle.clone(), stderr_layer_handle, logs_stream.clone()),
+        analytics::MockAnalytics::new(&server.service.options),
+        server.service.query_preprocessor.clone(),
+        true,
+    ))
+    .await;
+
+    let index = server.index("tamo");
+    let (task, _code) = index.add_documents(json!([{ "id": 1, "title": "kefir" }]), None).await;
+    server.wait_task(task.uid()).await;
+
+    let app = Rc::new(app);
+
+    let handle_app = app.clone();
+    let handle = tokio::task::spawn_local(async move {
+        let req = actix_web::test::TestRequest::post()
+            .uri("/logs/stream")
+            .insert_header(ContentType::json())
+            .set_payload(
+                serde_json::to_vec(&json!({
+                    "mode": "spanStats",
+                    "target": "search=trace",
+                }))
+                .unwrap(),
+            );
+        let req = req.to_request();
+        let ret = actix_web::test::call_service(&*handle_app, req).await;
+        actix_web::test::read_body(ret).await
+    });
+    // let the stream be created before searching
+    tokio::task::yield_now().await;
+
+    let (response, code) = index.search_post(json!({ "q": "kefir" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"].as_array().unwrap().len(), @"1");
+
+    let req = actix_web::test::TestRequest::delete().uri("/logs/stream");
+    let ret = actix_web::test::call_service(&*app, req.to_request()).await;
+    snapshot!(ret.status(), @"204 No Content");
+
+    let logs = handle.await.unwrap();
+    let logs = String::from_utf8(logs.to_vec()).unwrap();
+    // the statistics sent when the stream is closed include all the previous ones
+    let last_stats = logs.lines().last().unwrap_or_else(|| panic!("no statistics: {logs}"));
+    let stats: serde_json::Value = serde_json::from_str(last_stats).unwrap();
+    let bucket_sort = &stats["search::bucket_sort::bucket_sort"];
+    assert!(bucket_sort["callCount"].as_u64().unwrap() >= 1, "{logs}");
+    assert!(bucket_sort["maxTime"].as_u64().unwrap() <= bucket_sort["totalTime"].as_u64().unwrap());
+}
+
+/// Opens a stream on the `/logs/stream` route with the given parameters and returns its body.
+fn open_log_stream<S, B>(app: Rc<S>, body: serde_json::Value) -> tokio::task::JoinHandle<Bytes>
+where
+    S: actix_web::dev::Service<
+            actix_http::Request,
+            Response = actix_web::dev::ServiceResponse<B>,
+            Error = actix_web::Error,
+        > + 'static,
+    B: actix_web::body::MessageBody + 'static,
+{
+    tokio::task::spawn_local(async move {
+        let req = actix_web::test::TestRequest::post()
+            .uri("/logs/stream")
+            .insert_header(ContentType::json())
+            .set_payload(serde_json::to_vec(&body).unwrap());
+        let ret = actix_web::test::call_service(&*app, req.to_request()).await;
+        actix_web::test::read_body(ret).await
+    })
+}
+
+#[actix_web::test]
+async fn log_stream_conflict_and_force() {
+    let db_path = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_enable_logs_route: true,
+        ..default_settings(db_path.path())
+    })
+    .await
+    .unwrap();
+
+    let (route_layer, route_layer_handle) =
+        tracing_subscriber::reload::Layer::new(None.with_filter(
+            tracing_subscriber::filter::Targets::new().with_target("", LevelFilter::OFF),
+        ));
+    let (_stderr_layer, stderr_layer_handle) = tracing_subscriber::reload::Layer::new(
+        (Box::new(tracing_subscriber::fmt::layer())
+            as Box<dyn tracing_subscriber::Layer<SubscriberForSecondLayer> + Send + Sync>)
+            .with_filter(tracing_subscriber::filter::Targets::new()),
+    );
+
+    let subscriber = tracing_subscriber::registry().with(route_layer);
+
+    let app = actix_web::test::init_service(create_app(
+        server.service.index_scheduler.clone().into(),
+        server.service.auth.clone().into(),
+        server.service.search_queue.clone().into(),
+        server.service.search_latencies.clone().into(),
+        server.service.options.clone(),
+        (route_layer_handle, stderr_layer_handle, Default::default()),
+        analytics::MockAnalytics::new(&server.service.options),
+        server.service.query_preprocessor.clone(),
+        true,
+    ))
+    .await;
+
+    let _subscriber_guard = tracing::subscriber::set_default(subscriber);
+
+    let app = Rc::new(app);
+
+    let first = open_log_stream(app.clone(), json!({ "target": "info" }));
+    tokio::task::yield_now().await;
+
+    // A second stream is refused while the first one is open
+    let req = actix_web::test::TestRequest::post()
+        .uri("/logs/stream")
+        .insert_header(ContentType::json())
+        .set_payload(serde_json::to_vec(&json!({ "target": "info" })).unwrap());
+    let ret = actix_web::test::call_service(&*app, req.to_request()).await;
+    snapshot!(ret.status(), @"409 Conflict");
+    let response: serde_json::Value = actix_web::test::read_body_json(ret).await;
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The `/logs/stream` route is currently in use by someone else. Send `\"force\": true` to close their stream and open a new one.",
+      "code": "log_stream_already_exists",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#log_stream_already_exists"
+    }
+    "###);
+
+    // Forcing it closes the first stream
+    let second = open_log_stream(app.clone(), json!({ "target": "info", "force": true }));
+    tokio::task::yield_now().await;
+    first.await.unwrap();
+
+    // The first stream being closed must not free the route of the second one
+    let (_response, code) = server.index("after_force").get().await;
+    snapshot!(code, @"404 Not Found");
+
+    let req = actix_web::test::TestRequest::delete().uri("/logs/stream");
+    let ret = actix_web::test::call_service(&*app, req.to_request()).await;
+    snapshot!(ret.status(), @"204 No Content");
+
+    let logs = second.await.unwrap();
+    let logs = String::from_utf8(logs.to_vec()).unwrap();
+    assert!(logs.contains("/indexes/after_force"), "{logs}");
+}
+
+#[actix_web::test]
+async fn log_stream_delete_then_post() {
+    let db_path = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_enable_logs_route: true,
+        ..default_settings(db_path.path())
+    })
+    .await
+    .unwrap();
+
+    let (route_layer, route_layer_handle) =
+        tracing_subscriber::reload::Layer::new(None.with_filter(
+            tracing_subscriber::filter::Targets::new().with_target("", LevelFilter::OFF),
+        ));
+    let (_stderr_layer, stderr_layer_handle) = tracing_subscriber::reload::Layer::new(
+        (Box::new(tracing_subscriber::fmt::layer())
+            as Box<dyn tracing_subscriber::Layer<SubscriberForSecondLayer> + Send + Sync>)
+            .with_filter(tracing_subscriber::filter::Targets::new()),
+    );
+
+    let subscriber = tracing_subscriber::registry().with(route_layer);
+
+    let app = actix_web::test::init_service(create_app(
+        server.service.index_scheduler.clone().into(),
+        server.service.auth.clone().into(),
+        server.service.search_queue.clone().into(),
+        server.service.search_latencies.clone().into(),
+        server.service.options.clone(),
+        (route_layer_handle, stderr_layer_handle, Default::default()),
+        analytics::MockAnalytics::new(&server.service.options),
+        server.service.query_preprocessor.clone(),
+        true,
+    ))
+    .await;
+
+    let _subscriber_guard = tracing::subscriber::set_default(subscriber);
+
+    let app = Rc::new(app);
+
+    let first = open_log_stream(app.clone(), json!({ "target": "info" }));
+    tokio::task::yield_now().await;
+
+    let req = actix_web::test::TestRequest::delete().uri("/logs/stream");
+    let ret = actix_web::test::call_service(&*app, req.to_request()).await;
+    snapshot!(ret.status(), @"204 No Content");
+
+    // A new stream is accepted as soon as the previous one is deleted
+    let second = open_log_stream(app.clone(), json!({ "target": "info" }));
+    tokio::task::yield_now().await;
+    first.await.unwrap();
+
+    let (_response, code) = server.index("after_delete").get().await;
+    snapshot!(code, @"404 Not Found");
+
+    let req = actix_web::test::TestRequest::delete().uri("/logs/stream");
+    let ret = actix_web::test::call_service(&*app, req.to_request()).await;
+    snapshot!(ret.status(), @"204 No Content");
+
+    let logs = second.await.unwrap();
+    let logs = String::from_utf8(logs.to_vec()).unwrap();
+    assert!(logs.contains("/indexes/after_delete"), "{logs}");
+}
+
+#[actix_web::test]
+async fn log_stream_heartbeat_and_disconnect() {
+    let db_path = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_enable_logs_route: true,
+        ..default_settings(db_path.path())
+    })
+    .await
+    .unwrap();
+
+    let (route_layer, route_layer_handle) =
+        tracing_subscriber::reload::Layer::new(None.with_filter(
+            tracing_subscriber::filter::Targets::new().with_target("", LevelFilter::OFF),
+        ));
+    let (_stderr_layer, stderr_layer_handle) = tracing_subscriber::reload::Layer::new(
+        (Box::new(tracing_subscriber::fmt::layer())
+            as Box<dyn tracing_subscriber::Layer<SubscriberForSecondLayer> + Send + Sync>)
+            .with_filter(tracing_subscriber::filter::Targets::new()),
+    );
+
+    let subscriber = tracing_subscriber::registry().with(route_layer);
+
+    let app = actix_web::test::init_service(create_app(
+        server.service.index_scheduler.clone().into(),
+        server.service.auth.clone().into(),
+        server.service.search_queue.clone().into(),
+        server.service.search_latencies.clone().into(),
+        server.service.options.clone(),
+        (route_layer_handle, stderr_layer_handle, Default::default()),
+        analytics::MockAnalytics::new(&server.service.options),
+        server.service.query_preprocessor.clone(),
+        true,
+    ))
+    .await;
+
+    let _subscriber_guard = tracing::subscriber::set_default(subscriber);
+
+    let app = Rc::new(app);
+
+    // The client of the first stream disappears without calling the `DELETE` route
+    let first = open_log_stream(app.clone(), json!({ "target": "info" }));
+    tokio::task::yield_now().await;
+    first.abort();
+    assert!(first.await.unwrap_err().is_cancelled());
+
+    // The route was freed when its stream was dropped
+    let second = open_log_stream(
+        app.clone(),
+        json!({ "target": "OFF", "mode": "json", "heartbeatIntervalMs": 10 }),
+    );
+    tokio::task::yield_now().await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let req = actix_web::test::TestRequest::delete().uri("/logs/stream");
+    let ret = actix_web::test::call_service(&*app, req.to_request()).await;
+    snapshot!(ret.status(), @"204 No Content");
+
+    // Nothing was logged, only the heartbeats were sent
+    let logs = second.await.unwrap();
+    let logs = String::from_utf8(logs.to_vec()).unwrap();
+    assert!(!logs.is_empty());
+    assert!(logs.lines().all(|line| line == r#"{"type":"heartbeat"}"#), "{logs}");
+}
+
+#[actix_web::test]
+async fn log_file_route() {
+    let db_path = tempfile::tempdir().unwrap();
+    let logs_dir = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_enable_logs_route: true,
+        experimental_logs_dir: Some(logs_dir.path().to_path_buf()),
+        ..default_settings(db_path.path())
+    })
+    .await
+    .unwrap();
+
+    // the tasks are processed on another thread, the global subscriber is needed
+    let (route_layer_handle, stderr_layer_handle, logs_stream, lock) = &*GLOBAL_ROUTE_LAYER;
+    let _lock = lock.lock().await;
+
+    let app = actix_web::test::init_service(create_app(
+        server.service.index_scheduler.clone().into(),
+        server.service.auth.clone().into(),
+        server.service.search_queue.clone().into(),
+        server.service.search_latencies.clone().into(),
+        server.service.options.clone(),
+        (route_layer_handle.clone(), stderr_layer_handle.clone(), logs_stream.clone()),
+        analytics::MockAnalytics::new(&server.service.options),
+        server.service.query_preprocessor.clone(),
+        true,
+    ))
+    .await;
+
+    let path = logs_dir.path().join("logs").join("meilisearch.log");
+
+    // a directory can't be written into
+    let req =
+        actix_web::test::TestRequest::post().uri("/logs/stderr").set_json(json!({ "path": "." }));
+    let ret = actix_web::test::call_service(&app, req.to_request()).await;
+    snapshot!(ret.status(), @"400 Bad Request");
+    let body: serde_json::Value = actix_web::test::read_body_json(ret).await;
+    snapshot!(body["code"], @r###""invalid_log_file""###);
+
+    // the parent directory is missing
+    let req = actix_web::test::TestRequest::post()
+        .uri("/logs/stderr")
+        .set_json(json!({ "path": "logs/meilisearch.log", "target": "index_scheduler=info" }));
+    let ret = actix_web::test::call_service(&app, req.to_request()).await;
+    snapshot!(ret.status(), @"400 Bad Request");
+
+    let req = actix_web::test::TestRequest::post().uri("/logs/stderr").set_json(
+        json!({ "path": "logs/meilisearch.log", "target": "index_scheduler=info", "createDirs": true }),
+    );
+    let ret = actix_web::test::call_service(&app, req.to_request()).await;
+    snapshot!(ret.status(), @"204 No Content");
+
+    let (ret, _code) = server.create_index(json!({ "uid": "tamo" })).await;
+    server.wait_task(ret.uid()).await;
+
+    let req = actix_web::test::TestRequest::delete().uri("/logs/stderr");
+    let ret = actix_web::test::call_service(&app, req.to_request()).await;
+    snapshot!(ret.status(), @"204 No Content");
+
+    // the file is flushed and closed once the route is deleted
+    let logs = std::fs::read_to_string(&path).unwrap();
+    assert!(logs.contains("INFO"), "{logs}");
+    assert!(logs.contains("index_scheduler"), "{logs}");
+    // no color codes are written into a file
+    assert!(!logs.contains('\u{1b}'), "{logs}");
+
+    // nothing is written into the file anymore, the logs are written into stderr again
+    stderr_layer_handle
+        .modify(|layer| {
+            *layer.filter_mut() = tracing_subscriber::filter::Targets::from_str("OFF").unwrap()
+        })
+        .unwrap();
+    let (ret, _code) = server.create_index(json!({ "uid": "kefir" })).await;
+    server.wait_task(ret.uid()).await;
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), logs);
+}
+
+#[actix_web::test]
+async fn log_file_route_stays_in_the_logs_dir() {
+    let db_path = tempfile::tempdir().unwrap();
+    let logs_dir = tempfile::tempdir().unwrap();
+    let outside_dir = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_enable_logs_route: true,
+        experimental_logs_dir: Some(logs_dir.path().to_path_buf()),
+        ..default_settings(db_path.path())
+    })
+    .await
+    .unwrap();
+
+    let outside = outside_dir.path().join("meilisearch.log");
+    let (response, code) =
+        server.service.post("/logs/stderr", json!({ "path": outside, "createDirs": true })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_log_file""###);
+
+    let (response, code) = server
+        .service
+        .post("/logs/stderr", json!({ "path": "logs/../../meilisearch.log", "createDirs": true }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["message"], @r###""The logs can't be written into `logs/../../meilisearch.log`: the path must be relative to the logs directory and can't contain `..`.""###);
+
+    // a symbolic link can't lead out of the logs directory
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(outside_dir.path(), logs_dir.path().join("outside")).unwrap();
+        let (response, code) =
+            server.service.post("/logs/stderr", json!({ "path": "outside/meilisearch.log" })).await;
+        snapshot!(code, @"400 Bad Request");
+        snapshot!(response["code"], @r###""invalid_log_file""###);
+
+        // nor a dangling one, which would create the file it points to
+        std::os::unix::fs::symlink(&outside, logs_dir.path().join("dangling.log")).unwrap();
+        let (response, code) =
+            server.service.post("/logs/stderr", json!({ "path": "dangling.log" })).await;
+        snapshot!(code, @"400 Bad Request");
+        snapshot!(response["message"], @r###""The logs can't be written into `dangling.log`: the path goes through a broken symbolic link.""###);
+        let (response, code) = server
+            .service
+            .post(
+                "/logs/stderr",
+                json!({ "path": "dangling.log/meilisearch.log", "createDirs": true }),
+            )
+            .await;
+        snapshot!(code, @"400 Bad Request");
+        snapshot!(response["code"], @r###""invalid_log_file""###);
+    }
+
+    assert!(!outside.exists());
+    assert_eq!(std::fs::read_dir(outside_dir.path()).unwrap().count(), 0);
+}
+
+#[actix_web::test]
+async fn log_file_route_without_logs_dir() {
+    let db_path = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_enable_logs_route: true,
+        ..default_settings(db_path.path())
+    })
+    .await
+    .unwrap();
+
+    let (response, code) =
+        server.service.post("/logs/stderr", json!({ "path": "meilisearch.log" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_log_file""###);
+}
@@ -0,0 +1,149 @@
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        { "id": 1, "published": "2024-01-05" },
+        { "id": 2, "published": "2024-01-10T12:30:00Z" },
+        { "id": 3, "published": "2024-01-20T08:00:00+02:00" },
+        { "id": 4, "published": ["2024-02-01", "2023-12-24T18:00:00.5Z"] },
+        { "id": 5, "published": "soon" },
+    ])
+});
+
+async fn index_with_dates(server: &Server) -> crate::common::Index<'_> {
+    let index = server.index("test");
+    let (task, code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["published"],
+            "facetTypes": { "published": "date" },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(task.uid()).await;
+    let (task, code) = index.add_documents(DOCUMENTS.clone(), Some("id")).await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    index
+}
+
+fn ids(hits: &Value) -> Vec<u64> {
+    let mut ids: Vec<_> =
+        hits.as_array().unwrap().iter().map(|hit| hit["id"].as_u64().unwrap()).collect();
+    ids.sort_unstable();
+    ids
+}
+
+#[actix_rt::test]
+async fn filter_date_ranges() {
+    let server = Server::new().await;
+    let index = index_with_dates(&server).await;
+
+    // a date is taken at midnight UTC, the datetime of the 20th of January is after it
+    let (response, code) =
+        index.search_post(json!({ "filter": "published 2024-01-05 TO 2024-01-20" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response["hits"])), @"[1, 2]");
+
+    let (response, code) =
+        index.search_post(json!({ "filter": "published > \"2024-01-10T12:00:00Z\"" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response["hits"])), @"[2, 3, 4]");
+
+    // the datetimes are compared in UTC
+    let (response, code) =
+        index.search_post(json!({ "filter": "published >= \"2024-01-20T07:00:00+01:00\"" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response["hits"])), @"[3, 4]");
+
+    let (response, code) = index.search_post(json!({ "filter": "published < 2024-01-01" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response["hits"])), @"[4]");
+
+    // a date is equal to the datetime at midnight of that day
+    let (response, code) =
+        index.search_post(json!({ "filter": "published = \"2024-01-05T00:00:00Z\"" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response["hits"])), @"[1]");
+
+    // the strings that aren't dates are still filtered as strings
+    let (response, code) = index.search_post(json!({ "filter": "published = soon" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response["hits"])), @"[5]");
+}
+
+#[actix_rt::test]
+async fn date_facet_stats() {
+    let server = Server::new().await;
+    let index = index_with_dates(&server).await;
+
+    let (response, code) = index.search_post(json!({ "facets": ["published"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetStats"]), @r###"
+    {
+      "published": {
+        "min": "2023-12-24T18:00:00.5Z",
+        "max": "2024-02-01T00:00:00Z"
+      }
+    }
+    "###);
+    // the timestamps are not counted in the distribution
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "published": {
+        "2023-12-24T18:00:00.5Z": 1,
+        "2024-01-05": 1,
+        "2024-01-10T12:30:00Z": 1,
+        "2024-01-20T08:00:00+02:00": 1,
+        "2024-02-01": 1,
+        "soon": 1
+      }
+    }
+    "###);
+
+    // the stats are the ones of the candidates, formatted in UTC
+    let (response, code) = index
+        .search_post(json!({
+            "facets": ["published"],
+            "filter": "published 2024-01-06 TO 2024-01-25",
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetStats"]), @r###"
+    {
+      "published": {
+        "min": "2024-01-10T12:30:00Z",
+        "max": "2024-01-20T06:00:00Z"
+      }
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn date_facet_type_reindexes_the_documents() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) =
+        index.update_settings(json!({ "filterableAttributes": ["published"] })).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), Some("id")).await;
+    index.wait_task(task.uid()).await;
+
+    // without the hint the dates are only strings
+    let (response, code) = index.search_post(json!({ "filter": "published > 2024-01-15" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response["code"]), @r###""invalid_search_filter""###);
+
+    let (task, code) =
+        index.update_settings(json!({ "facetTypes": { "published": "date" } })).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = index.search_post(json!({ "filter": "published > 2024-01-15" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response["hits"])), @"[3, 4]");
+}
@@ -239,3 +239,68 @@ async fn distinct_search_with_pagination_no_ranking() {
     snapshot!(response["totalPages"], @"2");
     snapshot!(response["totalHits"], @"6");
 }
+
+#[actix_rt::test]
+async fn distinct_search_parameter() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, Some(DOCUMENT_PRIMARY_KEY)).await;
+    let (task, _code) = index
+        .update_settings(json!({ "filterableAttributes": [DOCUMENT_DISTINCT_KEY, "color"] }))
+        .await;
+    index.wait_task(task.uid()).await;
+
+    fn get_ids(response: &Value) -> Vec<u64> {
+        let hits_array = response["hits"].as_array().unwrap();
+        hits_array.iter().map(|h| h[DOCUMENT_PRIMARY_KEY].as_u64().unwrap()).collect::<Vec<_>>()
+    }
+
+    // a placeholder search without ranking rules to apply
+    let (response, code) = index.search_post(json!({"hitsPerPage": 20})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["totalHits"], @"14");
+
+    let (response, code) =
+        index.search_post(json!({"hitsPerPage": 20, "distinct": DOCUMENT_DISTINCT_KEY})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", get_ids(&response)), @"[1, 4, 6, 8, 9, 12]");
+    snapshot!(response["totalHits"], @"6");
+
+    // a keyword search, ranked by the ranking rules
+    let (response, code) = index.search_post(json!({"q": "jeans", "hitsPerPage": 20})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["totalHits"], @"6");
+
+    let (response, code) = index
+        .search_post(json!({"q": "jeans", "hitsPerPage": 20, "distinct": DOCUMENT_DISTINCT_KEY}))
+        .await;
+    snapshot!(code, @"200 OK");
+    let mut ids = get_ids(&response);
+    ids.sort_unstable();
+    snapshot!(format!("{:?}", ids), @"[1, 12]");
+    snapshot!(response["totalHits"], @"2");
+
+    let (response, code) =
+        index.search_get(&format!("q=jeans&hitsPerPage=20&distinct={DOCUMENT_DISTINCT_KEY}")).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["totalHits"], @"2");
+
+    // the parameter overrides the distinct attribute of the index, which is used otherwise
+    let (task, _code) = index.update_distinct_attribute(json!(DOCUMENT_DISTINCT_KEY)).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = index.search_post(json!({"hitsPerPage": 20})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["totalHits"], @"6");
+
+    let (response, code) = index.search_post(json!({"hitsPerPage": 20, "distinct": null})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["totalHits"], @"6");
+
+    let (response, code) = index.search_post(json!({"hitsPerPage": 20, "distinct": "color"})).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", get_ids(&response)), @"[1, 2, 3, 4, 7, 8, 9, 12, 14]");
+    snapshot!(response["totalHits"], @"9");
+}
@@ -0,0 +1,120 @@
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        { "id": 1, "title": "red shoes", "brand": "a", "rank": 1 },
+        { "id": 2, "title": "red shoes", "brand": "a", "rank": 2 },
+        { "id": 3, "title": "red shoes", "brand": "b", "rank": 3 },
+        { "id": 4, "title": "red hat", "brand": "c", "rank": 0 },
+        { "id": 5, "title": "red cap", "brand": "d", "rank": 4 },
+    ])
+});
+
+async fn server_with_documents() -> Server {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index
+        .update_settings(json!({
+            "sortableAttributes": ["rank"],
+            "rankingRules": ["words", "rank:asc"],
+            "distinctAttribute": "brand",
+        }))
+        .await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    server
+}
+
+#[actix_rt::test]
+async fn explain_a_distinct_excluded_document() {
+    let server = server_with_documents().await;
+
+    let (response, code) = server
+        .service
+        .post(
+            "/indexes/test/search/explain",
+            json!({ "documentId": "2", "search": { "q": "red shoes" } }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    // the document ranks right after the document 1 of the same brand
+    snapshot!(json_string!(response), @r###"
+    {
+      "documentId": "2",
+      "position": null,
+      "excluded": {
+        "reason": "distinct",
+        "by": "1"
+      },
+      "bucketPath": [
+        {
+          "rankingRule": "words",
+          "bucket": 0,
+          "bucketSize": 3
+        }
+      ]
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn explain_the_third_document() {
+    let server = server_with_documents().await;
+    let index = server.index("test");
+
+    let (response, code) = index.search_post(json!({ "q": "red shoes" })).await;
+    snapshot!(code, @"200 OK");
+    let ids: Vec<_> =
+        response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].clone()).collect();
+    snapshot!(json_string!(ids), @"[1, 3, 4, 5]");
+
+    let (response, code) = server
+        .service
+        .post(
+            "/indexes/test/search/explain",
+            json!({ "documentId": "4", "search": { "q": "red shoes", "offset": 1, "limit": 1 } }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    // the offset and the limit of the search are ignored
+    snapshot!(json_string!(response), @r###"
+    {
+      "documentId": "4",
+      "position": 2,
+      "excluded": null,
+      "bucketPath": [
+        {
+          "rankingRule": "words",
+          "bucket": 1,
+          "bucketSize": 2
+        },
+        {
+          "rankingRule": "rank:asc",
+          "bucket": 0,
+          "bucketSize": 1
+        }
+      ]
+    }
+    "###);
+
+    // with all the words required, the document is discarded before being ranked
+    let (response, code) = server
+        .service
+        .post(
+            "/indexes/test/search/explain",
+            json!({ "documentId": "4", "search": { "q": "red shoes", "matchingStrategy": "all" } }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["excluded"]), @r###"
+    {
+      "reason": "droppedByWordsRelaxation"
+    }
+    "###);
+}
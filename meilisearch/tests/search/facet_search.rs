@@ -200,3 +200,56 @@ async fn simple_facet_search_with_sort_by_count() {
     assert_eq!(hits[0], json!({ "value": "Action", "count": 3 }));
     assert_eq!(hits[1], json!({ "value": "Adventure", "count": 2 }));
 }
+
+#[actix_rt::test]
+async fn facet_search_with_a_too_long_query() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let query = "a".repeat(10 * 1024);
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": query})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "The facet query is 10240 bytes long, but it can't be longer than 512 bytes.",
+      "code": "facet_search_query_too_long",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#facet_search_query_too_long"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn facet_search_with_only_separators() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.update_settings_filterable_attributes(json!(["genres"])).await;
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "?!,;. -"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response, @r###"
+    {
+      "message": "The facet query `?!,;. -` must contain at least one word or number, not only separators.",
+      "code": "facet_search_query_without_words",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#facet_search_query_without_words"
+    }
+    "###);
+
+    let (response, code) =
+        index.facet_search(json!({"facetName": "genres", "facetQuery": "adv"})).await;
+    assert_eq!(code, 200, "{}", response);
+    assert_eq!(response["facetHits"].as_array().unwrap().len(), 1);
+    assert_eq!(response["truncated"], false);
+}
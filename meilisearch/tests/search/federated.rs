@@ -0,0 +1,449 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::{Server, Value};
+use crate::json;
+
+async fn server_with_two_indexes() -> Server {
+    let server = Server::new().await;
+
+    let movies = server.index("movies");
+    let (task, _code) = movies.update_settings(json!({ "filterableAttributes": ["genre"] })).await;
+    movies.wait_task(task.uid()).await;
+    let documents = json!([
+        { "id": 1, "title": "Shazam!", "genre": "action" },
+        { "id": 2, "title": "Captain Marvel", "genre": "action" },
+        { "id": 3, "title": "Escape Room", "genre": "horror" },
+        { "id": 4, "title": "Glass", "genre": "thriller" },
+    ]);
+    let (task, _code) = movies.add_documents(documents, Some("id")).await;
+    let response = movies.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    let books = server.index("books");
+    let documents = json!([
+        { "id": 1, "title": "Dune" },
+        { "id": 2, "title": "Neuromancer" },
+    ]);
+    let (task, _code) = books.add_documents(documents, Some("id")).await;
+    let response = books.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    server
+}
+
+fn hit_ids(response: &Value) -> Vec<String> {
+    response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| format!("{}:{}", hit["_federation"]["indexUid"].as_str().unwrap(), hit["id"]))
+        .collect()
+}
+
+#[actix_rt::test]
+async fn federated_search_merges_the_hits() {
+    let server = server_with_two_indexes().await;
+
+    // the placeholder searches rank all their hits with a score of 1.0, only the weights matter
+    let (response, code) = server
+        .multi_search(json!({
+            "federation": {},
+            "queries": [
+                { "indexUid": "movies", "filter": "genre = action", "federationOptions": { "weight": 0.9 } },
+                { "indexUid": "books", "federationOptions": { "weight": 0.85 } },
+                { "indexUid": "movies", "federationOptions": { "weight": 0.5 } },
+            ]
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    // the action movies are matched by the first and the last queries, they are only kept once
+    snapshot!(json_string!(response, { ".processingTimeMs" => "[time]" }), @r###"
+    {
+      "hits": [
+        {
+          "id": 1,
+          "title": "Shazam!",
+          "genre": "action",
+          "_federation": {
+            "indexUid": "movies",
+            "queriesPosition": 0,
+            "weightedRankingScore": 0.9
+          }
+        },
+        {
+          "id": 2,
+          "title": "Captain Marvel",
+          "genre": "action",
+          "_federation": {
+            "indexUid": "movies",
+            "queriesPosition": 0,
+            "weightedRankingScore": 0.9
+          }
+        },
+        {
+          "id": 1,
+          "title": "Dune",
+          "_federation": {
+            "indexUid": "books",
+            "queriesPosition": 1,
+            "weightedRankingScore": 0.85
+          }
+        },
+        {
+          "id": 2,
+          "title": "Neuromancer",
+          "_federation": {
+            "indexUid": "books",
+            "queriesPosition": 1,
+            "weightedRankingScore": 0.85
+          }
+        },
+        {
+          "id": 3,
+          "title": "Escape Room",
+          "genre": "horror",
+          "_federation": {
+            "indexUid": "movies",
+            "queriesPosition": 2,
+            "weightedRankingScore": 0.5
+          }
+        },
+        {
+          "id": 4,
+          "title": "Glass",
+          "genre": "thriller",
+          "_federation": {
+            "indexUid": "movies",
+            "queriesPosition": 2,
+            "weightedRankingScore": 0.5
+          }
+        }
+      ],
+      "processingTimeMs": "[time]",
+      "limit": 20,
+      "offset": 0,
+      "estimatedTotalHits": 8
+    }
+    "###);
+
+    // the best ranked occurrence of a document is kept, whatever the order of the queries
+    let (response, code) = server
+        .multi_search(json!({
+            "federation": {},
+            "queries": [
+                { "indexUid": "movies", "federationOptions": { "weight": 0.5 } },
+                { "indexUid": "movies", "filter": "genre = horror", "showRankingScore": true },
+            ]
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", hit_ids(&response)), @r###"["movies:3", "movies:1", "movies:2", "movies:4"]"###);
+    snapshot!(json_string!(response["hits"][0]["_rankingScore"]), @"1.0");
+    snapshot!(json_string!(response["hits"][1]["_rankingScore"]), @"null");
+}
+
+#[actix_rt::test]
+async fn federated_search_pagination() {
+    let server = server_with_two_indexes().await;
+
+    let queries = json!([
+        { "indexUid": "movies", "filter": "genre = action" },
+        { "indexUid": "books", "federationOptions": { "weight": 0.5 } },
+    ]);
+
+    let (response, code) = server
+        .multi_search(json!({ "federation": { "offset": 1, "limit": 2 }, "queries": queries }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", hit_ids(&response)), @r###"["movies:2", "books:1"]"###);
+    snapshot!(response["offset"], @"1");
+    snapshot!(response["limit"], @"2");
+    snapshot!(response["estimatedTotalHits"], @"4");
+
+    let (response, code) = server
+        .multi_search(json!({ "federation": { "offset": 3, "limit": 2 }, "queries": queries }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", hit_ids(&response)), @r###"["books:2"]"###);
+}
+
+#[actix_rt::test]
+async fn federated_search_errors() {
+    let server = server_with_two_indexes().await;
+
+    let (response, code) = server
+        .multi_search(json!({
+            "federation": {},
+            "queries": [{ "indexUid": "movies" }, { "indexUid": "books", "limit": 5 }]
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Inside `.queries[1]`: Invalid value at `.limit`: the pagination of a federated search is set with `federation.offset` and `federation.limit`, not in its queries.",
+      "code": "invalid_multi_search_query_pagination",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_multi_search_query_pagination"
+    }
+    "###);
+
+    let (response, code) = server
+        .multi_search(json!({
+            "federation": {},
+            "queries": [{ "indexUid": "movies", "facets": ["genre"] }]
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response["code"]), @r###""invalid_multi_search_query_facets""###);
+
+    let (response, code) = server
+        .multi_search(json!({
+            "queries": [{ "indexUid": "movies", "federationOptions": { "weight": 2.0 } }]
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Inside `.queries[0]`: Invalid value at `.federationOptions`: the `federationOptions` of a query can only be set when the `federation` of the request is set.",
+      "code": "invalid_multi_search_federation_options",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_multi_search_federation_options"
+    }
+    "###);
+
+    let (response, code) = server
+        .multi_search(json!({
+            "federation": {},
+            "queries": [{ "indexUid": "movies", "federationOptions": { "weight": -1.0 } }]
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response["code"]), @r###""invalid_multi_search_weight""###);
+
+    let (response, code) = server
+        .multi_search(json!({ "federation": 42, "queries": [{ "indexUid": "movies" }] }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response["code"]), @r###""invalid_multi_search_federated""###);
+
+    // a failing query fails the whole request
+    let (response, code) = server
+        .multi_search(json!({
+            "federation": {},
+            "queries": [
+                { "indexUid": "movies" },
+                { "indexUid": "books", "filter": "genre = action" },
+            ]
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Inside `.queries[1]`: Attribute `genre` is not filterable. This index does not have configured filterable attributes.\n1:6 genre = action\n    ^^^^^",
+      "code": "invalid_search_filter",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_filter"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn federated_search_facets() {
+    let server = Server::new().await;
+
+    let movies = server.index("movies");
+    let (task, _code) =
+        movies.update_settings(json!({ "filterableAttributes": ["genre", "year"] })).await;
+    movies.wait_task(task.uid()).await;
+    let documents = json!([
+        { "id": 1, "title": "Gravity", "genre": "science fiction", "year": 2013 },
+        { "id": 2, "title": "Shazam!", "genre": "action", "year": 2019 },
+        { "id": 3, "title": "Captain Marvel", "genre": "action", "year": 2019 },
+        { "id": 4, "title": "Escape Room", "genre": "horror", "year": 2019 },
+    ]);
+    let (task, _code) = movies.add_documents(documents, Some("id")).await;
+    let response = movies.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    // the years of the books are strings
+    let books = server.index("books");
+    let (task, _code) =
+        books.update_settings(json!({ "filterableAttributes": ["genre", "year"] })).await;
+    books.wait_task(task.uid()).await;
+    let documents = json!([
+        { "id": 1, "title": "Dune", "genre": "science fiction", "year": "1965" },
+        { "id": 2, "title": "Neuromancer", "genre": "science fiction", "year": "1984" },
+        { "id": 3, "title": "Carrie", "genre": "horror", "year": "1974" },
+        { "id": 4, "title": "Emma", "genre": "romance", "year": "1815" },
+    ]);
+    let (task, _code) = books.add_documents(documents, Some("id")).await;
+    let response = books.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    // the facets of the movies are computed on the documents matched by both their queries
+    let queries = json!([
+        { "indexUid": "movies", "filter": "year = 2019 AND genre = action" },
+        { "indexUid": "movies", "filter": "year = 2013" },
+        { "indexUid": "books", "filter": "genre != romance" },
+    ]);
+    let (response, code) = server
+        .multi_search(json!({
+            "federation": { "facetsByIndex": { "movies": ["genre", "year"], "books": ["genre", "year"] } },
+            "queries": queries,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetsByIndex"]), @r###"
+    {
+      "books": {
+        "distribution": {
+          "genre": {
+            "horror": 1,
+            "science fiction": 2
+          },
+          "year": {
+            "1965": 1,
+            "1974": 1,
+            "1984": 1
+          }
+        },
+        "stats": {}
+      },
+      "movies": {
+        "distribution": {
+          "genre": {
+            "action": 2,
+            "science fiction": 1
+          },
+          "year": [
+            {
+              "value": 2013,
+              "count": 1
+            },
+            {
+              "value": 2019,
+              "count": 2
+            }
+          ]
+        },
+        "stats": {
+          "year": {
+            "min": 2013,
+            "max": 2019
+          }
+        }
+      }
+    }
+    "###);
+    assert!(response.get("facetDistribution").is_none(), "{}", response);
+    assert!(response.get("facetStats").is_none(), "{}", response);
+
+    // the merged facets sum the counts of the values of both indexes
+    let (response, code) = server
+        .multi_search(json!({
+            "federation": {
+                "facetsByIndex": { "movies": ["genre", "year"], "books": ["genre", "year"] },
+                "mergeFacets": {},
+            },
+            "queries": queries,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "genre": {
+        "action": 2,
+        "horror": 1,
+        "science fiction": 3
+      },
+      "year": {
+        "strings": {
+          "1965": 1,
+          "1974": 1,
+          "1984": 1
+        },
+        "numbers": [
+          {
+            "value": 2013,
+            "count": 1
+          },
+          {
+            "value": 2019,
+            "count": 2
+          }
+        ]
+      }
+    }
+    "###);
+    snapshot!(json_string!(response["facetStats"]), @r###"
+    {
+      "year": {
+        "min": 2013,
+        "max": 2019
+      }
+    }
+    "###);
+    // the conflicting types are reported, without failing the request
+    snapshot!(json_string!(response["facetWarnings"]), @r###"
+    {
+      "year": "The facet `year` only contains strings in `books` and only numbers in `movies`, its values have been merged anyway."
+    }
+    "###);
+    assert!(response["facetsByIndex"]["books"].is_object(), "{}", response);
+
+    // the numbers are kept before the strings
+    let (response, code) = server
+        .multi_search(json!({
+            "federation": {
+                "facetsByIndex": { "movies": ["year"], "books": ["year"] },
+                "mergeFacets": { "maxValuesPerFacet": 3 },
+            },
+            "queries": queries,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["facetDistribution"]), @r###"
+    {
+      "year": {
+        "strings": {
+          "1965": 1
+        },
+        "numbers": [
+          {
+            "value": 2013,
+            "count": 1
+          },
+          {
+            "value": 2019,
+            "count": 2
+          }
+        ]
+      }
+    }
+    "###);
+
+    let (response, code) = server
+        .multi_search(json!({
+            "federation": { "facetsByIndex": { "shows": ["genre"] } },
+            "queries": queries,
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.federation.facetsByIndex.shows`: the index `shows` is not searched by any query of the federated search.",
+      "code": "invalid_multi_search_facets_by_index",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_multi_search_facets_by_index"
+    }
+    "###);
+
+    let (response, code) = server
+        .multi_search(json!({
+            "federation": { "facetsByIndex": { "movies": ["title"] } },
+            "queries": queries,
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response["code"]), @r###""invalid_search_facets""###);
+}
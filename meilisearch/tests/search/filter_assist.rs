@@ -0,0 +1,237 @@
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        { "id": 1, "genre": "horror", "price": 12, "available": true },
+        { "id": 2, "genre": "horror", "price": 8, "available": false },
+        { "id": 3, "genre": "science fiction", "price": 20 },
+        { "id": 4, "genre": "romance", "price": 15, "available": true },
+    ])
+});
+
+async fn index_with_documents(server: &Server) -> crate::common::Index<'_> {
+    let index = server.index("test");
+    let (task, _code) = index
+        .update_settings(json!({ "filterableAttributes": ["genre", "price", "available"] }))
+        .await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(task.uid()).await;
+    index
+}
+
+#[actix_rt::test]
+async fn filter_assist_after_attribute() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server).await;
+
+    let (response, code) = index
+        .service
+        .post("/indexes/test/filter-assist", json!({ "filter": "genre ", "cursor": 6 }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "expected": "operator",
+      "start": 6,
+      "end": 6,
+      "suggestions": [
+        {
+          "text": "="
+        },
+        {
+          "text": "!="
+        },
+        {
+          "text": "IN"
+        },
+        {
+          "text": "NOT IN"
+        },
+        {
+          "text": "EXISTS"
+        },
+        {
+          "text": "NOT EXISTS"
+        },
+        {
+          "text": "IS NULL"
+        },
+        {
+          "text": "IS NOT NULL"
+        },
+        {
+          "text": "IS EMPTY"
+        },
+        {
+          "text": "IS NOT EMPTY"
+        }
+      ]
+    }
+    "###);
+
+    // the comparison operators are only suggested for the numbers
+    let (response, code) =
+        index.service.post("/indexes/test/filter-assist", json!({ "filter": "price >" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "expected": "operator",
+      "start": 6,
+      "end": 7,
+      "suggestions": [
+        {
+          "text": ">"
+        },
+        {
+          "text": ">="
+        }
+      ]
+    }
+    "###);
+
+    let (response, code) = index
+        .service
+        .post("/indexes/test/filter-assist", json!({ "filter": "available n", "limit": 1 }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["suggestions"]), @r###"
+    [
+      {
+        "text": "NOT EXISTS"
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn filter_assist_after_equal() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server).await;
+
+    let (response, code) = index
+        .service
+        .post("/indexes/test/filter-assist", json!({ "filter": "genre = ", "cursor": 8 }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "expected": "value",
+      "start": 8,
+      "end": 8,
+      "suggestions": [
+        {
+          "text": "horror",
+          "count": 2
+        },
+        {
+          "text": "romance",
+          "count": 1
+        },
+        {
+          "text": "\"science fiction\"",
+          "count": 1
+        }
+      ]
+    }
+    "###);
+
+    // the cursor is counted in characters and the rest of the filter is ignored
+    let (response, code) = index
+        .service
+        .post(
+            "/indexes/test/filter-assist",
+            json!({ "filter": "genre = \"ho AND price > 10", "cursor": 11 }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "expected": "value",
+      "start": 8,
+      "end": 11,
+      "suggestions": [
+        {
+          "text": "horror",
+          "count": 2
+        }
+      ]
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn filter_assist_attributes_and_combinators() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server).await;
+
+    let (response, code) = index
+        .service
+        .post("/indexes/test/filter-assist", json!({ "filter": "genre = horror AND pr" }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "expected": "attribute",
+      "start": 19,
+      "end": 21,
+      "suggestions": [
+        {
+          "text": "price"
+        }
+      ]
+    }
+    "###);
+
+    let (response, code) = index
+        .service
+        .post("/indexes/test/filter-assist", json!({ "filter": "genre = horror " }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["suggestions"]), @r###"
+    [
+      {
+        "text": "AND"
+      },
+      {
+        "text": "OR"
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn filter_assist_bad_request() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server).await;
+
+    let (response, code) =
+        index.service.post("/indexes/test/filter-assist", json!({ "cursor": 3 })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `filter`",
+      "code": "missing_filter_assist_filter",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#missing_filter_assist_filter"
+    }
+    "###);
+
+    let (response, code) = index
+        .service
+        .post("/indexes/test/filter-assist", json!({ "filter": "genre", "cursor": -1 }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.cursor`: expected a positive integer, but found a negative integer: `-1`",
+      "code": "invalid_filter_assist_cursor",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_filter_assist_cursor"
+    }
+    "###);
+}
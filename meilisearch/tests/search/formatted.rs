@@ -568,3 +568,276 @@ async fn test_cjk_highlight() {
         })
         .await;
 }
+
+#[actix_rt::test]
+async fn formatted_with_search_defaults() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 0, "title": "Captain Marvel" },
+        { "id": 1, "title": "Escape Room" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    let (response, code) = index
+        .update_settings(json!({ "searchDefaults": {
+            "attributesToRetrieve": ["title"],
+            "attributesToCrop": ["title"],
+            "attributesToHighlight": ["title"],
+            "cropLength": 1,
+            "cropMarker": "[…]",
+            "highlightPreTag": "<b>",
+            "highlightPostTag": "</b>",
+        }}))
+        .await;
+    assert_eq!(code, 202, "{}", response);
+    let response = index.wait_task(response.uid()).await;
+    assert_eq!(response["status"], "succeeded", "{}", response);
+
+    index
+        .search(json!({ "q": "marvel" }), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert_eq!(
+                response["hits"][0],
+                json!({ "title": "Captain Marvel", "_formatted": { "title": "[…]<b>Marvel</b>" } })
+            );
+        })
+        .await;
+
+    // the parameters of the request take precedence over the defaults
+    index
+        .search(
+            json!({ "q": "marvel", "cropLength": 5, "highlightPreTag": "<i>", "highlightPostTag": "</i>" }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                assert_eq!(
+                    response["hits"][0],
+                    json!({ "title": "Captain Marvel", "_formatted": { "title": "Captain <i>Marvel</i>" } })
+                );
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn formatted_with_typo_tags() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([{ "id": 0, "title": "An apple pie, or an aple pie" }]);
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    // without typo tags, all the matches are highlighted the same way
+    index
+        .search(json!({ "q": "apple", "attributesToHighlight": ["title"] }), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            allow_duplicates! {
+                assert_json_snapshot!(response["hits"][0]["_formatted"], @r###"
+                {
+                  "id": "0",
+                  "title": "An <em>apple</em> pie, or an <em>aple</em> pie"
+                }
+                "###)
+            }
+        })
+        .await;
+
+    index
+        .search(
+            json!({
+                "q": "apple",
+                "attributesToHighlight": ["title"],
+                "highlightTypoPreTag": "<em class=\"typo\">",
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                allow_duplicates! {
+                    assert_json_snapshot!(response["hits"][0]["_formatted"], @r###"
+                    {
+                      "id": "0",
+                      "title": "An <em>apple</em> pie, or an <em class=\"typo\">aple</em> pie"
+                    }
+                    "###)
+                }
+            },
+        )
+        .await;
+
+    index
+        .search(
+            json!({
+                "q": "apple",
+                "attributesToHighlight": ["title"],
+                "highlightPreTag": "<b>",
+                "highlightPostTag": "</b>",
+                "highlightTypoPreTag": "<i>",
+                "highlightTypoPostTag": "</i>",
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                allow_duplicates! {
+                    assert_json_snapshot!(response["hits"][0]["_formatted"], @r###"
+                    {
+                      "id": "0",
+                      "title": "An <b>apple</b> pie, or an <i>aple</i> pie"
+                    }
+                    "###)
+                }
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn formatted_with_typo_derivations() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 0, "title": "A search engine" },
+        { "id": 1, "title": "Looking for engines" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    // the words each document was found with are highlighted, even when they were derived with a typo
+    index
+        .search(
+            json!({ "q": "serach", "attributesToHighlight": ["title"], "showMatchesPosition": true }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                allow_duplicates! {
+                    assert_json_snapshot!(response["hits"], @r###"
+                    [
+                      {
+                        "id": 0,
+                        "title": "A search engine",
+                        "_formatted": {
+                          "id": "0",
+                          "title": "A <em>search</em> engine"
+                        },
+                        "_matchesPosition": {
+                          "title": [
+                            {
+                              "start": 2,
+                              "length": 6
+                            }
+                          ]
+                        }
+                      }
+                    ]
+                    "###)
+                }
+            },
+        )
+        .await;
+
+    // the completions of the last word are highlighted up to the length of the prefix
+    index
+        .search(json!({ "q": "engi", "attributesToHighlight": ["title"] }), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            allow_duplicates! {
+                assert_json_snapshot!(response["hits"], @r###"
+                [
+                  {
+                    "id": 0,
+                    "title": "A search engine",
+                    "_formatted": {
+                      "id": "0",
+                      "title": "A search <em>engi</em>ne"
+                    }
+                  },
+                  {
+                    "id": 1,
+                    "title": "Looking for engines",
+                    "_formatted": {
+                      "id": "1",
+                      "title": "Looking for <em>engi</em>nes"
+                    }
+                  }
+                ]
+                "###)
+            }
+        })
+        .await;
+}
+
+#[actix_rt::test]
+async fn formatted_with_attribute_patterns() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({ "sortableAttributes": ["id"] })).await;
+    let documents = json!([
+        {
+            "id": 1,
+            "title": "Captain Marvel",
+            "sub_title": "the first avenger ever",
+            "attributes": { "color": "red", "size": "large" },
+        },
+        {
+            "id": 2,
+            "title": "Captain America",
+            "main_title": "the captain of the avengers",
+            "attributes": { "power": "shield" },
+        },
+    ]);
+    let (response, _code) = index.add_documents(documents, None).await;
+    index.wait_task(response.uid()).await;
+
+    // The patterns are expanded against the fields of every document, and the crop length
+    // of `title`, given by name, takes precedence over the one of the `*title` pattern.
+    index
+        .search(
+            json!({
+                "q": "captain",
+                "sort": ["id:asc"],
+                "attributesToRetrieve": ["id", "attributes.*"],
+                "attributesToHighlight": ["*_title"],
+                "attributesToCrop": ["title:1", "*title:10"],
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                allow_duplicates! {
+                    assert_json_snapshot!(response["hits"], @r###"
+                    [
+                      {
+                        "id": 1,
+                        "attributes": {
+                          "color": "red",
+                          "size": "large"
+                        },
+                        "_formatted": {
+                          "id": "1",
+                          "title": "Captain…",
+                          "sub_title": "the first avenger ever",
+                          "attributes": {
+                            "color": "red",
+                            "size": "large"
+                          }
+                        }
+                      },
+                      {
+                        "id": 2,
+                        "attributes": {
+                          "power": "shield"
+                        },
+                        "_formatted": {
+                          "id": "2",
+                          "title": "Captain…",
+                          "main_title": "the <em>captain</em> of the avengers",
+                          "attributes": {
+                            "power": "shield"
+                          }
+                        }
+                      }
+                    ]
+                    "###)
+                }
+            },
+        )
+        .await;
+}
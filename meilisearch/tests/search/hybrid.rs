@@ -16,7 +16,13 @@ async fn index_with_documents<'a>(server: &'a Server, documents: &Value) -> Inde
       "vectorStore": true,
       "metrics": false,
       "logsRoute": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "debugRoutes": false,
+      "relevanceCheck": false,
+      "wordFrequency": false,
+      "documentTasks": false,
+      "recentSearches": false,
+      "rankingRulesOverride": false
     }
     "###);
 
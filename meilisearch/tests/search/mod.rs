@@ -1,16 +1,32 @@
 // This modules contains all the test concerning search. Each particular feature of the search
 // should be tested in its own module to isolate tests and keep the tests readable.
 
+mod dates;
 mod distinct;
 mod errors;
+mod explain;
 mod facet_search;
+mod federated;
+mod filter_assist;
 mod formatted;
 mod geo;
 mod hybrid;
 mod multi;
 mod pagination;
+mod presets;
+mod preview;
+mod query_preprocessor;
+mod ranking_rules_override;
+mod ranking_score_threshold;
+mod recent_searches;
+mod related_terms;
+mod relevance_check;
 mod restrict_searchable;
 mod search_queue;
+mod search_trace;
+mod stream;
+mod typo_tolerance;
+mod word_frequency;
 
 use once_cell::sync::Lazy;
 
@@ -135,6 +151,56 @@ async fn simple_placeholder_search() {
         .await;
 }
 
+#[actix_rt::test]
+async fn wildcard_query_is_a_placeholder_search() {
+    let server = Server::new().await;
+    let index = server.index("nested");
+
+    index.update_settings(json!({"filterableAttributes": ["father", "mother"]})).await;
+
+    let documents = NESTED_DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (placeholder, code) =
+        index.search_post(json!({"filter": "mother = michelle", "facets": ["father"]})).await;
+    assert_eq!(code, 200, "{}", placeholder);
+    assert_eq!(placeholder["hits"].as_array().unwrap().len(), 2);
+
+    for q in ["*", " * "] {
+        index
+            .search(
+                json!({"q": q, "filter": "mother = michelle", "facets": ["father"]}),
+                |response, code| {
+                    assert_eq!(code, 200, "{}", response);
+                    assert_eq!(response["hits"], placeholder["hits"]);
+                    assert_eq!(response["estimatedTotalHits"], placeholder["estimatedTotalHits"]);
+                    assert_eq!(response["facetDistribution"], placeholder["facetDistribution"]);
+                },
+            )
+            .await;
+    }
+}
+
+#[actix_rt::test]
+async fn trailing_star_is_not_a_wildcard() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    // the last word of the query is already a prefix, the `*` doesn't match everything
+    index
+        .search(json!({"q": "capt*"}), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert_eq!(response["hits"].as_array().unwrap().len(), 1);
+            assert_eq!(response["hits"][0]["title"], "Captain Marvel");
+        })
+        .await;
+}
+
 #[actix_rt::test]
 async fn simple_search() {
     let server = Server::new().await;
@@ -157,13 +223,54 @@ async fn simple_search() {
     index.wait_task(1).await;
 
     index
-        .search(json!({"q": "pésti"}), |response, code| {
+        .search(json!({"q": "glass"}), |response, code| {
             assert_eq!(code, 200, "{}", response);
             assert_eq!(response["hits"].as_array().unwrap().len(), 2);
         })
         .await;
 }
 
+#[actix_rt::test]
+async fn search_with_nonsense_word_and_matching_strategy() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    for strategy in ["last", "frequency"] {
+        index
+            .search(
+                json!({"q": "captain zzzzzzzz", "matchingStrategy": strategy}),
+                |response, code| {
+                    assert_eq!(code, 200, "{}", response);
+                    assert_eq!(response["hits"].as_array().unwrap().len(), 1, "{}", response);
+                    meili_snap::snapshot!(response["hits"][0]["title"], @r###""Captain Marvel""###);
+                },
+            )
+            .await;
+    }
+
+    // the nonsense word is the least frequent one, removed first even at the start of the query
+    index
+        .search(
+            json!({"q": "zzzzzzzz captain", "matchingStrategy": "frequency"}),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                assert_eq!(response["hits"].as_array().unwrap().len(), 1, "{}", response);
+            },
+        )
+        .await;
+
+    index
+        .search(json!({"q": "captain zzzzzzzz", "matchingStrategy": "all"}), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            meili_snap::snapshot!(response["hits"], @"[]");
+        })
+        .await;
+}
+
 #[actix_rt::test]
 async fn phrase_search_with_stop_word() {
     // related to https://github.com/meilisearch/meilisearch/issues/3521
@@ -289,6 +396,63 @@ async fn negative_special_cases_search() {
         .await;
 }
 
+#[actix_rt::test]
+async fn search_with_synonyms_parameter() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    index.update_settings(json!({"synonyms": { "escape": ["glass"] }})).await;
+    index.wait_task(1).await;
+
+    // the synonyms of the request are merged with the ones of the settings
+    index
+        .search(
+            json!({"q": "hero", "synonyms": { "Hero": ["MARVEL"] }, "attributesToRetrieve": ["id"]}),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": "299537"
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+
+    index
+        .search(
+            json!({"q": "escape", "synonyms": { "hero": ["marvel"] }, "attributesToRetrieve": ["id"]}),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+                [
+                  {
+                    "id": "522681"
+                  },
+                  {
+                    "id": "450465"
+                  }
+                ]
+                "###);
+            },
+        )
+        .await;
+
+    // they only apply to the request they were given with
+    index
+        .search(json!({"q": "hero", "attributesToRetrieve": ["id"]}), |response, code| {
+            meili_snap::snapshot!(code, @"200 OK");
+            meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @"[]");
+        })
+        .await;
+}
+
 #[cfg(feature = "default")]
 #[actix_rt::test]
 async fn test_kanji_language_detection() {
@@ -369,7 +533,7 @@ async fn search_multiple_params() {
     index
         .search(
             json!({
-                "q": "pésti",
+                "q": "glass",
                 "attributesToCrop": ["catto:2"],
                 "attributesToHighlight": ["catto"],
                 "limit": 2,
@@ -383,6 +547,413 @@ async fn search_multiple_params() {
         .await;
 }
 
+#[actix_rt::test]
+async fn search_processing_breakdown() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (_, code) = index.update_settings(json!({"filterableAttributes": ["id"]})).await;
+    meili_snap::snapshot!(code, @"202 Accepted");
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    index
+        .search(json!({"q": "glass"}), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert!(response.get("processingBreakdown").is_none(), "{}", response);
+        })
+        .await;
+
+    index
+        .search(
+            json!({
+                "q": "glass",
+                "facets": ["id"],
+                "showProcessingBreakdown": true,
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                let mut breakdown = response["processingBreakdown"].as_object().unwrap().clone();
+                assert_eq!(breakdown.remove("exhaustiveNbHits"), Some(serde_json::Value::Bool(true)));
+                // the embedding is only reported for the semantic and hybrid searches
+                let mut keys: Vec<_> = breakdown.keys().collect();
+                keys.sort();
+                meili_snap::snapshot!(format!("{keys:?}"), @r###"["bucketSort", "facetDistribution", "formatting", "queryParsing", "universe"]"###);
+                for (key, value) in breakdown {
+                    let value = value.as_f64().unwrap_or_else(|| panic!("{key} is not a number"));
+                    assert!(value >= 0.0, "{key} is negative: {value}");
+                }
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_query_tokens() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    index
+        .search(json!({"q": "dragon"}), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert!(response.get("queryTokens").is_none(), "{}", response);
+        })
+        .await;
+
+    let query = "Train \"Your Dragon\" 🐶 world";
+    index
+        .search(json!({"q": query, "showQueryTokens": true}), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            let tokens = response["queryTokens"].as_array().unwrap();
+            let located: Vec<_> = tokens
+                .iter()
+                .filter(|token| token["kind"] != "separatorSkipped")
+                .map(|token| {
+                    let start = token["start"].as_u64().unwrap() as usize;
+                    let end = token["end"].as_u64().unwrap() as usize;
+                    format!(
+                        "{} {start}..{end} {:?} {}",
+                        token["kind"],
+                        &query[start..end],
+                        token["token"]
+                    )
+                })
+                .collect();
+            meili_snap::snapshot!(located.join("\n"), @r###"
+            "word" 0..5 "Train" "train"
+            "phrase" 7..11 "Your" "your"
+            "phrase" 12..18 "Dragon" "dragon"
+            "word" 20..24 "🐶" "🐶"
+            "word" 25..30 "world" "world"
+            "###);
+        })
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_metrics() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    index
+        .search(json!({"q": "dragon"}), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert!(response.get("metrics").is_none(), "{}", response);
+        })
+        .await;
+
+    index
+        .search(json!({"q": "dragon", "showSearchMetrics": true}), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            let metrics = response["metrics"].as_object().unwrap();
+            let mut keys: Vec<_> = metrics.keys().collect();
+            keys.sort();
+            meili_snap::snapshot!(format!("{keys:?}"), @r###"["bitmapDecodes", "bitmapDifferences", "bitmapIntersections", "bitmapUnions", "databaseCacheHits", "databaseCacheMisses", "distinctSeenValuesChecks", "distinctValueDocidsLookups", "prefixDocidsFallbacks", "prefixDocidsLookups", "sharedCacheHits", "termCacheHits", "termCacheMisses", "wordDocidsLookups"]"###);
+            assert!(metrics["wordDocidsLookups"].as_u64().unwrap() > 0, "{}", response);
+        })
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_facets_reuse_the_search_candidates() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (_, code) = index.update_settings(json!({"filterableAttributes": ["title"]})).await;
+    meili_snap::snapshot!(code, @"202 Accepted");
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (without_facets, code) =
+        index.search_post(json!({"q": "the world", "showSearchMetrics": true})).await;
+    meili_snap::snapshot!(code, @"200 OK");
+    let (with_facets, code) = index
+        .search_post(json!({"q": "the world", "facets": ["title"], "showSearchMetrics": true}))
+        .await;
+    meili_snap::snapshot!(code, @"200 OK");
+
+    // the facet distribution is computed from the candidates of the search,
+    // the query is not resolved a second time.
+    assert_eq!(with_facets["metrics"], without_facets["metrics"]);
+    assert_eq!(with_facets["estimatedTotalHits"], without_facets["estimatedTotalHits"]);
+    meili_snap::snapshot!(meili_snap::json_string!(with_facets["facetDistribution"]), @r###"
+    {
+      "title": {
+        "How to Train Your Dragon: The Hidden World": 1
+      }
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn search_debug_query_graph() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    index
+        .search(json!({"q": "hidden world"}), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert!(response.get("_debug").is_none(), "{}", response);
+        })
+        .await;
+
+    let (response, code) =
+        index.search_post(json!({"q": "hidden world", "debug": {"queryGraph": true}})).await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["_debug"]), @r###"
+    {
+      "queryGraph": {
+        "graphviz": "digraph {\n  0 [label=\"START\"];\n  0 -> 2;\n  0 -> 4;\n  1 [label=\"END\"];\n  2 [label=\"hidden\"];\n  2 -> 3;\n  3 [label=\"world\"];\n  3 -> 1;\n  4 [label=\"hiddenworld\"];\n  4 -> 1;\n}",
+        "nodes": [
+          {
+            "id": 0,
+            "type": "start"
+          },
+          {
+            "id": 1,
+            "type": "end"
+          },
+          {
+            "id": 2,
+            "type": "term",
+            "term": "hidden",
+            "positions": [
+              0,
+              0
+            ],
+            "derivations": {
+              "words": 1,
+              "phrases": 0,
+              "prefixDb": false
+            }
+          },
+          {
+            "id": 3,
+            "type": "term",
+            "term": "world",
+            "positions": [
+              1,
+              1
+            ],
+            "derivations": {
+              "words": 1,
+              "phrases": 0,
+              "prefixDb": false
+            }
+          },
+          {
+            "id": 4,
+            "type": "term",
+            "term": "hiddenworld",
+            "positions": [
+              0,
+              1
+            ],
+            "derivations": {
+              "words": 0,
+              "phrases": 1,
+              "prefixDb": false
+            }
+          }
+        ],
+        "edges": [
+          {
+            "from": 0,
+            "to": 2
+          },
+          {
+            "from": 0,
+            "to": 4
+          },
+          {
+            "from": 2,
+            "to": 3
+          },
+          {
+            "from": 3,
+            "to": 1
+          },
+          {
+            "from": 4,
+            "to": 1
+          }
+        ]
+      }
+    }
+    "###);
+
+    // a placeholder search has no query graph
+    let (response, code) = index.search_post(json!({"debug": {"queryGraph": true}})).await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["_debug"]), @"{}");
+}
+
+#[actix_rt::test]
+async fn search_debug_bucket_stats() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 1, "title": "the red dragon" },
+        { "id": 2, "title": "the dragon of the hidden world" },
+        { "id": 3, "title": "a dragon" },
+        { "id": 4, "title": "the knight" },
+    ]);
+    let (task, _code) = index.add_documents(documents, None).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) =
+        index.search_post(json!({"q": "dragon", "debug": {"bucketStats": true}})).await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(response["estimatedTotalHits"], @"3");
+    let words = &response["_debug"]["bucketStats"][0];
+    assert_eq!(words["rankingRule"], json!("words"), "{response}");
+    // a single word query has a single words bucket with all the candidates
+    assert_eq!(words["bucketSizes"], json!([3]), "{response}");
+    assert_eq!(words["buckets"], json!(1), "{response}");
+
+    // the stats are only returned when they are asked
+    let (response, code) =
+        index.search_post(json!({"q": "dragon", "debug": {"queryGraph": true}})).await;
+    meili_snap::snapshot!(code, @"200 OK");
+    assert!(response["_debug"].get("bucketStats").is_none(), "{response}");
+}
+
+#[actix_rt::test]
+async fn search_debug_dump_search_state() {
+    use std::io::Read;
+
+    use base64::Engine;
+    use flate2::read::GzDecoder;
+
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 1, "title": "the dragon of the hidden world" },
+        { "id": 2, "title": "hidden dragons" },
+    ]);
+    let (task, _code) = index.update_settings(json!({ "rankingRules": ["words", "typo"] })).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.add_documents(documents, None).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) =
+        index.search_post(json!({"q": "hidden world", "debug": {"dumpSearchState": true}})).await;
+    meili_snap::snapshot!(code, @"200 OK");
+    // the other debug artifacts are only returned when they are asked
+    assert!(response["_debug"].get("queryGraph").is_none(), "{response}");
+
+    let blob = response["_debug"]["searchState"].as_str().unwrap();
+    let compressed = base64::engine::general_purpose::STANDARD.decode(blob).unwrap();
+    let mut state = String::new();
+    GzDecoder::new(compressed.as_slice()).read_to_string(&mut state).unwrap();
+    let state: serde_json::Value = serde_json::from_str(&state).unwrap();
+
+    let query_graph = &state["queryGraph"];
+    let graphviz = query_graph["graphviz"].as_str().unwrap();
+    let graphviz_nodes = graphviz.lines().filter(|line| line.contains("[label=")).count();
+    let nodes = query_graph["nodes"].as_array().unwrap();
+    assert_eq!(nodes.len(), graphviz_nodes, "{state}");
+
+    let trace = &state["trace"];
+    meili_snap::snapshot!(trace["initialUniverse"], @"2");
+    meili_snap::snapshot!(meili_snap::json_string!(trace["rankingRules"]), @r###"
+    [
+      "words",
+      "typo"
+    ]
+    "###);
+    assert!(!trace["events"].as_array().unwrap().is_empty(), "{state}");
+}
+
+#[actix_rt::test]
+async fn search_reports_skipped_ranking_rules() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    let (response, _code) = index.update_settings(json!({ "sortableAttributes": ["id"] })).await;
+    index.wait_task(response.uid()).await;
+
+    index
+        .search(json!({"q": "dragon", "showRankingScoreDetails": true}), |response, code| {
+            meili_snap::snapshot!(code, @"200 OK");
+            meili_snap::snapshot!(meili_snap::json_string!(response["skippedRankingRules"]), @r###"
+            [
+              {
+                "rankingRule": "sort",
+                "reason": "the search has no `sort` parameter"
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    index
+        .search(json!({"q": "dragon"}), |response, code| {
+            meili_snap::snapshot!(code, @"200 OK");
+            meili_snap::snapshot!(meili_snap::json_string!(response["skippedRankingRules"]), @"null");
+        })
+        .await;
+
+    index
+        .search(
+            json!({"q": "dragon", "sort": ["id:asc"], "showRankingScoreDetails": true}),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["skippedRankingRules"]), @"null");
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_with_ranking_bucket_path() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    index
+        .search(json!({"q": "dragon", "showRankingScoreDetails": true}), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            let details = response["hits"][0]["_rankingScoreDetails"].as_object().unwrap();
+            assert!(details.get("bucketPath").is_none(), "{}", response);
+        })
+        .await;
+
+    index
+        .search(json!({"q": "dragon", "showRankingBucketPath": true}), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            let hits = response["hits"].as_array().unwrap();
+            assert!(!hits.is_empty(), "{}", response);
+            for hit in hits {
+                let path = hit["_rankingScoreDetails"]["bucketPath"].as_array().unwrap();
+                assert_eq!(path[0]["rankingRule"], json!("words"), "{}", response);
+                assert!(path.iter().all(|step| step["bucketSize"].as_u64().unwrap() > 0));
+            }
+        })
+        .await;
+}
+
 #[actix_rt::test]
 async fn search_with_filter_string_notation() {
     let server = Server::new().await;
@@ -565,6 +1136,146 @@ async fn search_with_sort_on_strings() {
         .await;
 }
 
+#[actix_rt::test]
+async fn search_with_sort_on_accented_strings() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({"sortableAttributes": ["word"]})).await;
+
+    let documents = json!([
+        { "id": 0, "word": "eux" },
+        { "id": 1, "word": "été" },
+        { "id": 2, "word": "zèbre" },
+        { "id": 3, "word": "école" },
+        { "id": 4, "word": "Élan" },
+        { "id": 5, "word": "abricot" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    index
+        .search(
+            json!({ "sort": ["word:asc"], "attributesToRetrieve": ["word"] }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+            [
+              {
+                "word": "abricot"
+              },
+              {
+                "word": "école"
+              },
+              {
+                "word": "Élan"
+              },
+              {
+                "word": "été"
+              },
+              {
+                "word": "eux"
+              },
+              {
+                "word": "zèbre"
+              }
+            ]
+            "###);
+            },
+        )
+        .await;
+
+    index
+        .search(
+            json!({ "sort": ["word:desc"], "attributesToRetrieve": ["word"] }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+            [
+              {
+                "word": "zèbre"
+              },
+              {
+                "word": "eux"
+              },
+              {
+                "word": "été"
+              },
+              {
+                "word": "Élan"
+              },
+              {
+                "word": "école"
+              },
+              {
+                "word": "abricot"
+              }
+            ]
+            "###);
+            },
+        )
+        .await;
+
+    // in swedish the letters with a diaeresis come after the `z`
+    let (response, _) = index
+        .update_settings(json!({
+            "localizedAttributes": [{ "attributePatterns": ["wo*"], "locales": ["swe"] }]
+        }))
+        .await;
+    index.wait_task(response.uid()).await;
+    let (response, code) = index.settings().await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["localizedAttributes"]), @r###"
+    [
+      {
+        "attributePatterns": [
+          "wo*"
+        ],
+        "locales": [
+          "swe"
+        ]
+      }
+    ]
+    "###);
+
+    index.add_documents(json!([{ "id": 6, "word": "öl" }]), None).await;
+    index.wait_task(3).await;
+
+    index
+        .search(
+            json!({ "sort": ["word:asc"], "attributesToRetrieve": ["word"] }),
+            |response, code| {
+                meili_snap::snapshot!(code, @"200 OK");
+                meili_snap::snapshot!(meili_snap::json_string!(response["hits"]), @r###"
+            [
+              {
+                "word": "abricot"
+              },
+              {
+                "word": "école"
+              },
+              {
+                "word": "Élan"
+              },
+              {
+                "word": "été"
+              },
+              {
+                "word": "eux"
+              },
+              {
+                "word": "zèbre"
+              },
+              {
+                "word": "öl"
+              }
+            ]
+            "###);
+            },
+        )
+        .await;
+}
+
 #[actix_rt::test]
 async fn search_with_multiple_sort() {
     let server = Server::new().await;
@@ -676,10 +1387,110 @@ async fn search_facet_distribution() {
                     dist["doggos.name"],
                     json!({ "bobby": 1, "buddy": 1, "gros bill": 1, "turbo": 1, "fast": 1})
                 );
-                assert_eq!(dist["doggos.age"], json!({ "2": 1, "4": 1, "5": 1, "6": 1, "8": 1}));
+                assert_eq!(
+                    dist["doggos.age"],
+                    json!([
+                        { "value": 2, "count": 1 },
+                        { "value": 4, "count": 1 },
+                        { "value": 5, "count": 1 },
+                        { "value": 6, "count": 1 },
+                        { "value": 8, "count": 1 },
+                    ])
+                );
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_facet_distribution_mode() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({"filterableAttributes": ["title"]})).await;
+
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    // without any mode the response doesn't report how the distribution was computed
+    index
+        .search(json!({ "facets": ["title"] }), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert_eq!(response["facetDistribution"]["title"].as_object().unwrap().len(), 5);
+            assert!(response.get("facetDistributionMode").is_none());
+        })
+        .await;
+
+    // the universe is too small to be approximated or skipped
+    for mode in ["exact", "approximate", "skipOnLargeUniverse"] {
+        index
+            .search(
+                json!({ "facets": ["title"], "facetDistributionMode": mode }),
+                |response, code| {
+                    assert_eq!(code, 200, "{}", response);
+                    assert_eq!(
+                        response["facetDistribution"]["title"].as_object().unwrap().len(),
+                        5
+                    );
+                    assert_eq!(response["facetDistributionMode"], json!("exact"));
+                },
+            )
+            .await;
+    }
+
+    // above the approximation threshold the distribution is approximated or skipped
+    index
+        .search(
+            json!({
+                "facets": ["title"],
+                "facetDistributionMode": "approximate",
+                "facetApproximationThreshold": 2
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                assert!(response["facetDistribution"]["title"].is_object(), "{}", response);
+                assert_eq!(response["facetDistributionMode"], json!("approximate"));
+            },
+        )
+        .await;
+    index
+        .search(
+            json!({
+                "facets": ["title"],
+                "facetDistributionMode": "skipOnLargeUniverse",
+                "facetApproximationThreshold": 2
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                assert_eq!(response.get("facetDistribution"), Some(&json!(null)), "{}", response);
+                assert_eq!(response.get("facetStats"), Some(&json!(null)), "{}", response);
+                assert_eq!(response["facetDistributionMode"], json!("skipped"));
+            },
+        )
+        .await;
+    index
+        .search(
+            json!({
+                "facets": ["title"],
+                "facetDistributionMode": "skipOnLargeUniverse",
+                "facetApproximationThreshold": 5
+            }),
+            |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                assert_eq!(response["facetDistributionMode"], json!("exact"));
             },
         )
         .await;
+
+    // the mode is not reported when no facets are requested
+    index
+        .search(json!({ "facetDistributionMode": "approximate" }), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert!(response.get("facetDistribution").is_none());
+            assert!(response.get("facetDistributionMode").is_none());
+        })
+        .await;
 }
 
 #[actix_rt::test]
@@ -847,7 +1658,7 @@ async fn faceting_max_values_per_facet() {
             }),
             |response, code| {
                 assert_eq!(code, 200, "{}", response);
-                let numbers = response["facetDistribution"]["number"].as_object().unwrap();
+                let numbers = response["facetDistribution"]["number"].as_array().unwrap();
                 assert_eq!(numbers.len(), 100);
             },
         )
@@ -863,13 +1674,95 @@ async fn faceting_max_values_per_facet() {
             }),
             |response, code| {
                 assert_eq!(code, 200, "{}", response);
-                let numbers = &response["facetDistribution"]["number"].as_object().unwrap();
+                let numbers = &response["facetDistribution"]["number"].as_array().unwrap();
                 assert_eq!(numbers.len(), 10_000);
             },
         )
         .await;
 }
 
+#[actix_rt::test]
+async fn facet_distribution_value_types() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (task, _code) = index
+        .update_settings(json!({ "filterableAttributes": ["integer", "float", "mixed"] }))
+        .await;
+    index.wait_task(task.uid()).await;
+
+    let documents = json!([
+        { "id": 1, "integer": 10, "float": 1.5, "mixed": 10 },
+        { "id": 2, "integer": 20, "float": 2.0, "mixed": "10" },
+        { "id": 3, "integer": 10, "float": 2.25, "mixed": "red" },
+    ]);
+    let (task, _code) = index.add_documents(documents, None).await;
+    index.wait_task(task.uid()).await;
+
+    index
+        .search(json!({ "facets": ["integer", "float", "mixed"] }), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            // the numbers are JSON numbers, a number and a string are distinct values
+            meili_snap::snapshot!(meili_snap::json_string!(response["facetDistribution"]), @r###"
+            {
+              "float": [
+                {
+                  "value": 1.5,
+                  "count": 1
+                },
+                {
+                  "value": 2,
+                  "count": 1
+                },
+                {
+                  "value": 2.25,
+                  "count": 1
+                }
+              ],
+              "integer": [
+                {
+                  "value": 10,
+                  "count": 2
+                },
+                {
+                  "value": 20,
+                  "count": 1
+                }
+              ],
+              "mixed": {
+                "strings": {
+                  "10": 1,
+                  "red": 1
+                },
+                "numbers": [
+                  {
+                    "value": 10,
+                    "count": 1
+                  }
+                ]
+              }
+            }
+            "###);
+            meili_snap::snapshot!(meili_snap::json_string!(response["facetStats"]), @r###"
+            {
+              "float": {
+                "min": 1.5,
+                "max": 2.25
+              },
+              "integer": {
+                "min": 10,
+                "max": 20
+              },
+              "mixed": {
+                "min": 10,
+                "max": 10
+              }
+            }
+            "###);
+        })
+        .await;
+}
+
 #[actix_rt::test]
 async fn test_score_details() {
     let server = Server::new().await;
@@ -921,6 +1814,7 @@ async fn test_score_details() {
                         "order": 3,
                         "attributeRankingOrderScore": 1.0,
                         "queryWordDistanceScore": 0.8095238095238095,
+                        "queryWordDistanceBandSum": 4,
                         "score": 0.9727891156462584
                       },
                       "exactness": {
@@ -1019,7 +1913,14 @@ async fn test_degraded_score_details() {
                   "processingTimeMs": "[duration]",
                   "limit": 20,
                   "offset": 0,
-                  "estimatedTotalHits": 3
+                  "estimatedTotalHits": 3,
+                  "skippedRankingRules": [
+                    {
+                      "rankingRule": "sort",
+                      "reason": "the index has no sortable attributes"
+                    }
+                  ],
+                  "degraded": true
                 }
                 "###);
             },
@@ -1458,3 +2359,70 @@ async fn simple_search_with_strange_synonyms() {
         })
         .await;
 }
+
+#[actix_rt::test]
+async fn number_normalization() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 0, "price": "1000" },
+        { "id": 1, "price": "1,000" },
+        { "id": 2, "price": "1.000" },
+        { "id": 3, "price": "$1,000.50" },
+        { "id": 4, "price": "1.000,50€" },
+        { "id": 5, "price": "1.5" },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    // the documents already indexed are reindexed with their numbers normalized.
+    let (response, _code) = index.update_settings(json!({ "numberNormalization": true })).await;
+    index.wait_task(response.uid()).await;
+
+    // the trailing space prevents the last word from matching as a prefix, like `1000.5`.
+    for query in ["1000 ", "1,000 ", "1.000 ", "$1000 "] {
+        index
+            .search(json!({ "q": query, "attributesToRetrieve": ["id"] }), |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                let mut ids: Vec<_> = response["hits"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|hit| hit["id"].clone())
+                    .collect();
+                ids.sort_by_key(|id| id.as_u64());
+                assert_eq!(json!(ids), json!([0, 1, 2]), "{query}");
+            })
+            .await;
+    }
+
+    for query in ["1000.5 ", "1,000.50 ", "1.000,5 "] {
+        index
+            .search(json!({ "q": query, "attributesToRetrieve": ["id"] }), |response, code| {
+                assert_eq!(code, 200, "{}", response);
+                let mut ids: Vec<_> = response["hits"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|hit| hit["id"].clone())
+                    .collect();
+                ids.sort_by_key(|id| id.as_u64());
+                assert_eq!(json!(ids), json!([3, 4]), "{query}");
+            })
+            .await;
+    }
+
+    // once disabled, the numbers only match the way they are written.
+    let (response, _code) = index.update_settings(json!({ "numberNormalization": false })).await;
+    index.wait_task(response.uid()).await;
+
+    index
+        .search(json!({ "q": "1.000,50€", "attributesToRetrieve": ["id"] }), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            let ids: Vec<_> =
+                response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].clone()).collect();
+            assert!(!ids.contains(&json!(3)), "{}", response);
+        })
+        .await;
+}
@@ -113,6 +113,31 @@ async fn simple_search_single_index() {
     "###);
 }
 
+#[actix_rt::test]
+async fn search_facet_approximation_threshold() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    index.update_settings(json!({"filterableAttributes": ["title"]})).await;
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(1).await;
+
+    let (response, code) = server
+        .multi_search(json!({"queries": [
+        {"indexUid": "test", "facets": ["title"], "facetDistributionMode": "approximate", "facetApproximationThreshold": 2},
+        {"indexUid": "test", "facets": ["title"], "facetDistributionMode": "skipOnLargeUniverse", "facetApproximationThreshold": 2},
+        {"indexUid": "test", "facets": ["title"], "facetDistributionMode": "skipOnLargeUniverse"},
+        ]}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["results"][0]["facetDistributionMode"]), @r###""approximate""###);
+    snapshot!(json_string!(response["results"][1]["facetDistributionMode"]), @r###""skipped""###);
+    assert_eq!(response["results"][1].get("facetDistribution"), Some(&json!(null)), "{}", response);
+    assert_eq!(response["results"][1].get("facetStats"), Some(&json!(null)), "{}", response);
+    snapshot!(json_string!(response["results"][2]["facetDistributionMode"]), @r###""exact""###);
+}
+
 #[actix_rt::test]
 async fn simple_search_missing_index_uid() {
     let server = Server::new().await;
@@ -155,7 +180,7 @@ async fn simple_search_illegal_index_uid() {
     snapshot!(code, @"400 Bad Request");
     insta::assert_json_snapshot!(response, @r###"
     {
-      "message": "Invalid value at `.queries[0].indexUid`: `hé` is not a valid index uid. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_).",
+      "message": "Invalid value at `.queries[0].indexUid`: `hé` is not a valid index uid: the character `é` at position 1 is not allowed. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_), of at most 400 bytes. Try `h_` instead.",
       "code": "invalid_index_uid",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_index_uid"
@@ -376,3 +401,97 @@ async fn search_multiple_query_errors() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn search_continue_on_error() {
+    let server = Server::new().await;
+
+    let index = server.index("test");
+    let documents = DOCUMENTS.clone();
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    let queries = json!([
+        {"indexUid" : "test", "q": "glass", "attributesToRetrieve": ["id"]},
+        {"indexUid": "nested", "q": "pésti"},
+        {"indexUid" : "test", "q": "shazam", "attributesToRetrieve": ["id"]},
+    ]);
+
+    // without the flag the whole request fails
+    let (response, code) = server.multi_search(json!({"queries": queries})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""index_not_found""###);
+
+    let (response, code) =
+        server.multi_search(json!({"queries": queries, "continueOnError": true})).await;
+    snapshot!(code, @"200 OK");
+    insta::assert_json_snapshot!(response, { ".processingTimeMs" => "[time]", ".results[].processingTimeMs" => "[time]" }, @r###"
+    {
+      "results": [
+        {
+          "indexUid": "test",
+          "hits": [
+            {
+              "id": "450465"
+            }
+          ],
+          "query": "glass",
+          "processingTimeMs": "[time]",
+          "limit": 20,
+          "offset": 0,
+          "estimatedTotalHits": 1
+        },
+        {
+          "indexUid": "nested",
+          "error": {
+            "message": "Index `nested` not found.",
+            "code": "index_not_found",
+            "type": "invalid_request",
+            "link": "https://docs.meilisearch.com/errors#index_not_found"
+          },
+          "processingTimeMs": "[time]"
+        },
+        {
+          "indexUid": "test",
+          "hits": [
+            {
+              "id": "287947"
+            }
+          ],
+          "query": "shazam",
+          "processingTimeMs": "[time]",
+          "limit": 20,
+          "offset": 0,
+          "estimatedTotalHits": 1
+        }
+      ],
+      "processingTimeMs": "[time]"
+    }
+    "###);
+
+    // a federated search keeps failing as a whole
+    let (response, code) = server
+        .multi_search(json!({
+            "federation": {},
+            "queries": [
+                {"indexUid" : "test", "q": "glass"},
+                {"indexUid": "nested", "q": "pésti"},
+            ],
+            "continueOnError": true,
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Inside `.queries[1]`: Index `nested` not found.",
+      "code": "index_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#index_not_found"
+    }
+    "###);
+
+    let (response, code) =
+        server.multi_search(json!({"queries": [], "continueOnError": "yes"})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_multi_search_continue_on_error""###);
+}
@@ -1,4 +1,6 @@
-use crate::common::Server;
+use meili_snap::*;
+
+use crate::common::{Server, Value};
 use crate::json;
 use crate::search::DOCUMENTS;
 
@@ -163,3 +165,139 @@ async fn ensure_placeholder_search_hit_count_valid() {
             .await;
     }
 }
+
+#[actix_rt::test]
+async fn search_after_returns_the_same_pages_as_offset() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let documents = json!([
+        { "id": 0, "color": "red", "rank": 2, "score": 10 },
+        { "id": 1, "color": "blue", "rank": 1, "score": 5 },
+        { "id": 2, "color": "green", "rank": 1, "score": 7 },
+        { "id": 3, "color": "red", "rank": 1, "score": 1 },
+        { "id": 4, "color": "pink", "rank": 3, "score": 3 },
+        { "id": 5, "color": "cyan", "rank": 2, "score": 3 },
+        { "id": 6, "color": "blue", "rank": 3, "score": 9 },
+        { "id": 7, "color": "white", "rank": 2, "score": 3 },
+        { "id": 8, "color": "black", "rank": 1, "score": 5 },
+        { "id": 9, "color": "gray", "rank": 3, "score": 8 },
+    ]);
+    index.add_documents(documents, None).await;
+    index.wait_task(0).await;
+
+    let (response, _code) = index
+        .update_settings(json!({
+            "rankingRules": ["rank:asc", "score:desc"],
+            "distinctAttribute": "color",
+        }))
+        .await;
+    index.wait_task(response.uid()).await;
+
+    let ids = |response: &Value| -> Vec<serde_json::Value> {
+        response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].clone()).collect()
+    };
+
+    let mut offset_pages = vec![];
+    for page in 0..3 {
+        let (response, code) =
+            index.search_post(json!({ "offset": page * 3, "limit": 3, "q": "" })).await;
+        snapshot!(code, @"200 OK");
+        offset_pages.push(ids(&response));
+    }
+    snapshot!(json_string!(offset_pages), @r###"
+    [
+      [
+        2,
+        1,
+        8
+      ],
+      [
+        3,
+        5,
+        7
+      ],
+      [
+        9,
+        4
+      ]
+    ]
+    "###);
+
+    let mut search_after_pages: Vec<Vec<serde_json::Value>> = vec![];
+    for _ in 0..3 {
+        let search_after = search_after_pages.last().and_then(|page| page.last()).cloned();
+        let (response, code) =
+            index.search_post(json!({ "searchAfter": search_after, "limit": 3, "q": "" })).await;
+        snapshot!(code, @"200 OK");
+        search_after_pages.push(ids(&response));
+    }
+    assert_eq!(search_after_pages, offset_pages);
+
+    // the GET route takes the id as a string
+    let (response, code) = index.search_get("searchAfter=8&limit=3").await;
+    snapshot!(code, @"200 OK");
+    assert_eq!(ids(&response), offset_pages[1]);
+}
+
+#[actix_rt::test]
+async fn exhaustive_nb_hits_depends_on_pagination_and_distinct() {
+    let server = Server::new().await;
+    let documents = json!([
+        { "id": 0, "color": "red" },
+        { "id": 1, "color": "red" },
+        { "id": 2, "color": "blue" },
+        { "id": 3, "color": "blue" },
+        { "id": 4, "color": "green" },
+        { "id": 5, "color": "green" },
+    ]);
+
+    let index = server.index("plain");
+    index.add_documents(documents.clone(), None).await;
+    index.wait_task(0).await;
+
+    let distinct = server.index("distinct");
+    distinct.update_settings(json!({ "distinctAttribute": "color" })).await;
+    distinct.add_documents(documents, None).await;
+    distinct.wait_task(2).await;
+
+    // without distinct attribute all the hits are counted, whatever the pagination
+    index
+        .search(json!({ "limit": 1, "showProcessingBreakdown": true }), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert_eq!(response["estimatedTotalHits"], 6);
+            assert!(response.get("totalHits").is_none());
+            assert!(response.get("totalPages").is_none());
+            assert_eq!(response["processingBreakdown"]["exhaustiveNbHits"], true);
+        })
+        .await;
+    index
+        .search(json!({ "hitsPerPage": 1, "showProcessingBreakdown": true }), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert_eq!(response["totalHits"], 6);
+            assert_eq!(response["totalPages"], 6);
+            assert!(response.get("estimatedTotalHits").is_none());
+            assert_eq!(response["processingBreakdown"]["exhaustiveNbHits"], true);
+        })
+        .await;
+
+    // with a distinct attribute the hits are only counted when paginating by pages
+    distinct
+        .search(json!({ "limit": 1, "showProcessingBreakdown": true }), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert!(response.get("estimatedTotalHits").is_some());
+            assert!(response.get("totalHits").is_none());
+            assert!(response.get("totalPages").is_none());
+            assert_eq!(response["processingBreakdown"]["exhaustiveNbHits"], false);
+        })
+        .await;
+    distinct
+        .search(json!({ "hitsPerPage": 1, "showProcessingBreakdown": true }), |response, code| {
+            assert_eq!(code, 200, "{}", response);
+            assert_eq!(response["totalHits"], 3);
+            assert_eq!(response["totalPages"], 3);
+            assert!(response.get("estimatedTotalHits").is_none());
+            assert_eq!(response["processingBreakdown"]["exhaustiveNbHits"], true);
+        })
+        .await;
+}
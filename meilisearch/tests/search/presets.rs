@@ -0,0 +1,243 @@
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        { "id": 1, "name": "kefir", "color": "blue", "price": 30 },
+        { "id": 2, "name": "intel", "color": "blue", "price": 10 },
+        { "id": 3, "name": "bobby", "color": "red", "price": 5 },
+        { "id": 4, "name": "echo", "color": "blue", "price": 20 },
+    ])
+});
+
+#[actix_rt::test]
+async fn search_with_preset() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, _code) = index
+        .update_settings(
+            json!({ "filterableAttributes": ["color"], "sortableAttributes": ["price"] }),
+        )
+        .await;
+    index.wait_task(response.uid()).await;
+    let (response, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index
+        .update_preset(
+            "storefront",
+            json!({ "filter": "color = blue", "sort": ["price:asc"], "attributesToRetrieve": ["id"] }),
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    let (response, code) = index.get_preset("storefront").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "attributesToRetrieve": [
+        "id"
+      ],
+      "filter": "color = blue",
+      "sort": [
+        "price:asc"
+      ]
+    }
+    "###);
+
+    index
+        .search(json!({ "preset": "storefront" }), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 2
+              },
+              {
+                "id": 4
+              },
+              {
+                "id": 1
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    // the parameters of the request override the ones of the preset
+    index
+        .search(json!({ "preset": "storefront", "sort": ["price:desc"] }), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 1
+              },
+              {
+                "id": 4
+              },
+              {
+                "id": 2
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    let (response, code) = index.delete_preset("storefront").await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+    let (response, code) = index.presets().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @"{}");
+}
+
+#[actix_rt::test]
+async fn search_with_unknown_preset() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (response, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(response.uid()).await;
+
+    index
+        .search(json!({ "preset": "storefront" }), |response, code| {
+            snapshot!(code, @"404 Not Found");
+            snapshot!(json_string!(response), @r###"
+            {
+              "message": "Preset `storefront` not found.",
+              "code": "preset_not_found",
+              "type": "invalid_request",
+              "link": "https://docs.meilisearch.com/errors#preset_not_found"
+            }
+            "###);
+        })
+        .await;
+
+    let (response, code) = index.get_preset("storefront").await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(response["code"], @r###""preset_not_found""###);
+}
+
+#[actix_rt::test]
+async fn invalid_preset() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.update_preset("storefront", json!({ "limit": "doggo" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.presets.storefront.limit`: expected a positive integer, but found a string: `\"doggo\"`",
+      "code": "invalid_search_limit",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_limit"
+    }
+    "###);
+
+    let (response, code) = index.update_preset("storefront", json!({ "doggo": true })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_settings_presets""###);
+
+    let (response, code) = index.update_preset("storefront", json!({ "preset": "other" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.presets.storefront.preset`: stored search parameters cannot reference a preset.",
+      "code": "invalid_settings_presets",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_presets"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn multi_search_with_preset_and_search_defaults() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, _code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["color"],
+            "sortableAttributes": ["price"],
+            "searchDefaults": { "sort": ["price:asc"], "attributesToRetrieve": ["id"] },
+        }))
+        .await;
+    index.wait_task(response.uid()).await;
+    let (response, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(response.uid()).await;
+    let (response, _code) = index.update_preset("blue", json!({ "filter": "color = blue" })).await;
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = server
+        .multi_search(json!({"queries": [
+            { "indexUid": "test" },
+            { "indexUid": "test", "preset": "blue" },
+            { "indexUid": "test", "preset": "blue", "sort": ["price:desc"] },
+        ]}))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["results"][0]["hits"]), @r###"
+    [
+      {
+        "id": 3
+      },
+      {
+        "id": 2
+      },
+      {
+        "id": 4
+      },
+      {
+        "id": 1
+      }
+    ]
+    "###);
+    snapshot!(json_string!(response["results"][1]["hits"]), @r###"
+    [
+      {
+        "id": 2
+      },
+      {
+        "id": 4
+      },
+      {
+        "id": 1
+      }
+    ]
+    "###);
+    snapshot!(json_string!(response["results"][2]["hits"]), @r###"
+    [
+      {
+        "id": 1
+      },
+      {
+        "id": 4
+      },
+      {
+        "id": 2
+      }
+    ]
+    "###);
+
+    // the preset and the defaults also apply to the queries of a federated search
+    let (response, code) = server
+        .multi_search(json!({"federation": {}, "queries": [
+            { "indexUid": "test", "preset": "blue" },
+        ]}))
+        .await;
+    snapshot!(code, @"200 OK");
+    let ids: Vec<_> = response["hits"].as_array().unwrap().iter().map(|hit| &hit["id"]).collect();
+    snapshot!(json_string!(ids), @r###"
+    [
+      2,
+      4,
+      1
+    ]
+    "###);
+}
@@ -0,0 +1,129 @@
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        { "id": 1, "title": "hello", "description": "cheap", "rank": 1 },
+        { "id": 2, "title": "hello there", "description": "expensive", "rank": 2 },
+        { "id": 3, "title": "hello world", "description": "hello", "rank": 3 },
+        { "id": 4, "title": "goodbye", "description": "free", "rank": 0 },
+    ])
+});
+
+fn ids(response: &Value) -> Vec<i64> {
+    response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].as_i64().unwrap()).collect()
+}
+
+#[actix_rt::test]
+async fn preview_ranking_rules_without_changing_the_index() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index
+        .update_settings(json!({
+            "sortableAttributes": ["rank"],
+            "rankingRules": ["words", "rank:asc"],
+        }))
+        .await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = index.search_post(json!({ "q": "hello" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[1, 2, 3]");
+
+    let (response, code) = index
+        .service
+        .post(
+            "/indexes/test/search/preview",
+            json!({
+                "settings": { "rankingRules": ["words", "rank:desc"] },
+                "search": { "q": "hello" },
+            }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[3, 2, 1]");
+
+    // the index is untouched
+    let (response, _code) = index.settings().await;
+    snapshot!(json_string!(response["rankingRules"]), @r###"
+    [
+      "words",
+      "rank:asc"
+    ]
+    "###);
+    let (response, code) = index.search_post(json!({ "q": "hello" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[1, 2, 3]");
+}
+
+#[actix_rt::test]
+async fn preview_searchable_attributes_and_typo_tolerance() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = index
+        .service
+        .post(
+            "/indexes/test/search/preview",
+            json!({
+                "settings": { "searchableAttributes": ["description"] },
+                "search": { "q": "hello" },
+            }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[3]");
+
+    let (response, code) = index
+        .service
+        .post(
+            "/indexes/test/search/preview",
+            json!({
+                "settings": { "typoTolerance": { "enabled": false } },
+                "search": { "q": "hellp" },
+            }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[]");
+}
+
+#[actix_rt::test]
+async fn preview_rejects_the_settings_requiring_reindexing() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = index
+        .service
+        .post(
+            "/indexes/test/search/preview",
+            json!({
+                "settings": {
+                    "filterableAttributes": ["rank"],
+                    "stopWords": ["hello"],
+                    "rankingRules": ["words", "rank:asc"],
+                    "typoTolerance": { "enabled": false, "disableOnAttributes": ["title"] },
+                },
+                "search": { "q": "hello" },
+            }),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "The settings `filterableAttributes`, `stopWords`, `typoTolerance.disableOnAttributes`, `rankingRules` can't be previewed as they only apply once the documents are reindexed. Only the `rankingRules`, the `searchableAttributes` that are already searchable, and the `enabled` and `minWordSizeForTypos` fields of the `typoTolerance` can be previewed.",
+      "code": "invalid_search_preview_settings",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_preview_settings"
+    }
+    "###);
+}
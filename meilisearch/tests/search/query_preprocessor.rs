@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use meili_snap::snapshot;
+use meilisearch::query_preprocessor::{PreprocessedQuery, QueryPreprocessor};
+
+use crate::common::{Server, Value};
+use crate::json;
+
+/// Removes the uppercase store code prefixing the queries, and only searches the documents of that store.
+struct StoreCodePreprocessor;
+
+impl QueryPreprocessor for StoreCodePreprocessor {
+    fn preprocess(&self, _index_uid: &str, q: &str) -> PreprocessedQuery {
+        match q.split_once(':') {
+            Some((code, rest))
+                if !code.is_empty() && code.chars().all(|c| c.is_ascii_uppercase()) =>
+            {
+                PreprocessedQuery {
+                    q: rest.trim().to_string(),
+                    filter: Some(serde_json::json!(format!("store = {code}"))),
+                }
+            }
+            _ => PreprocessedQuery::unchanged(q),
+        }
+    }
+}
+
+fn ids(response: &Value) -> Vec<u64> {
+    response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].as_u64().unwrap()).collect()
+}
+
+async fn server_with_preprocessor() -> Server {
+    let mut server = Server::new().await;
+    server.service.query_preprocessor = Arc::new(StoreCodePreprocessor);
+
+    let index = server.index("test");
+    let (task, _code) = index
+        .update_settings(json!({
+            "searchableAttributes": ["title"],
+            "filterableAttributes": ["store", "id"],
+        }))
+        .await;
+    index.wait_task(task.uid()).await;
+    let documents = json!([
+        { "id": 1, "title": "red shoes", "store": "PARIS" },
+        { "id": 2, "title": "blue shoes", "store": "LYON" },
+        { "id": 3, "title": "paris souvenir shoes", "store": "LYON" },
+        { "id": 4, "title": "red hat", "store": "PARIS" },
+    ]);
+    let (task, _code) = index.add_documents(documents, Some("id")).await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    server
+}
+
+#[actix_rt::test]
+async fn preprocessed_query_is_searched() {
+    let server = server_with_preprocessor().await;
+    let index = server.index("test");
+
+    // the store code is not searched, it only restricts the documents to its store
+    let (response, code) = index.search_post(json!({ "q": "PARIS: shoes" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[1]");
+    snapshot!(response["query"], @r###""PARIS: shoes""###);
+
+    let (response, code) = index.search_get("q=LYON%3A%20shoes").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[2, 3]");
+    snapshot!(response["query"], @r###""LYON: shoes""###);
+
+    // the forced filter is combined with the filter of the search
+    let (response, code) =
+        index.search_post(json!({ "q": "LYON: shoes", "filter": "id != 2" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[3]");
+
+    // the queries the preprocessor doesn't rewrite are searched as they are
+    let (response, code) = index.search_post(json!({ "q": "paris shoes" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[3]");
+    snapshot!(response["query"], @r###""paris shoes""###);
+}
+
+#[actix_rt::test]
+async fn default_preprocessor_keeps_the_query() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) =
+        index.add_documents(json!([{ "id": 1, "title": "PARIS: shoes" }]), None).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = index.search_post(json!({ "q": "PARIS: shoes" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[1]");
+    snapshot!(response["query"], @r###""PARIS: shoes""###);
+}
@@ -0,0 +1,142 @@
+use meili_snap::{json_string, snapshot};
+use meilisearch::Opt;
+
+use crate::common::{default_settings, Server};
+use crate::json;
+
+async fn server_with_index(options: Opt) -> Server {
+    let server = Server::new_with_options(options).await.unwrap();
+    let index = server.index("test");
+    let (task, _code) = index.update_settings_sortable_attributes(json!(["price"])).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index
+        .add_documents(
+            json!([
+                { "id": 1, "title": "red shoes", "price": 20 },
+                { "id": 2, "title": "red hat", "price": 35 },
+                { "id": 3, "title": "blue shoes", "price": 10 },
+            ]),
+            None,
+        )
+        .await;
+    index.wait_task(task.uid()).await;
+    server
+}
+
+#[actix_rt::test]
+async fn ranking_rules_override_requires_the_feature() {
+    let temp = tempfile::tempdir().unwrap();
+    let server = server_with_index(default_settings(temp.path())).await;
+    let index = server.index("test");
+
+    let (response, code) =
+        index.search_post(json!({ "q": "red", "rankingRulesOverride": ["price:desc"] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Passing `rankingRulesOverride` as a search parameter requires enabling the `ranking rules override` experimental feature. See https://www.meilisearch.com/docs/learn/experimental/overview",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+
+    let (_response, code) = server.set_features(json!({ "rankingRulesOverride": true })).await;
+    snapshot!(code, @"200 OK");
+    let (_response, code) =
+        index.search_post(json!({ "q": "red", "rankingRulesOverride": ["price:desc"] })).await;
+    snapshot!(code, @"200 OK");
+}
+
+#[actix_rt::test]
+async fn ranking_rules_override_replaces_the_ranking_rules_of_the_index() {
+    let temp = tempfile::tempdir().unwrap();
+    let server = server_with_index(Opt {
+        experimental_ranking_rules_override: true,
+        ..default_settings(temp.path())
+    })
+    .await;
+    let index = server.index("test");
+
+    let (response, code) =
+        index.search_post(json!({ "q": "shoes", "attributesToRetrieve": ["id"] })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1
+      },
+      {
+        "id": 3
+      }
+    ]
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "shoes",
+            "attributesToRetrieve": ["id"],
+            "rankingRulesOverride": ["words", "price:asc"]
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 3
+      },
+      {
+        "id": 1
+      }
+    ]
+    "###);
+
+    // the ranking rules of the index are left untouched
+    let (response, code) = index.settings().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["rankingRules"]), @r###"
+    [
+      "words",
+      "typo",
+      "proximity",
+      "attribute",
+      "sort",
+      "exactness"
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn invalid_ranking_rules_override() {
+    let temp = tempfile::tempdir().unwrap();
+    let server = server_with_index(Opt {
+        experimental_ranking_rules_override: true,
+        ..default_settings(temp.path())
+    })
+    .await;
+    let index = server.index("test");
+
+    let (response, code) =
+        index.search_post(json!({ "q": "red", "rankingRulesOverride": ["manyTheFish"] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.rankingRulesOverride[0]`: `manyTheFish` ranking rule is invalid. Valid ranking rules are words, typo, sort, proximity, attribute, exactness and custom ranking rules.",
+      "code": "invalid_search_ranking_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_ranking_rules"
+    }
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({ "q": "red", "rankingRulesOverride": ["title:asc"] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.rankingRulesOverride`: the `title:asc` ranking rule sorts on the attribute `title`, which is not a sortable attribute of the index.",
+      "code": "invalid_search_ranking_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_ranking_rules"
+    }
+    "###);
+}
@@ -0,0 +1,108 @@
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        { "id": 1, "title": "the quick brown fox" },
+        { "id": 2, "title": "the quick brown dog" },
+        { "id": 3, "title": "the quick cat" },
+        { "id": 4, "title": "a lazy cat" },
+    ])
+});
+
+fn ids(response: &Value) -> Vec<i64> {
+    response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].as_i64().unwrap()).collect()
+}
+
+async fn server_with_documents() -> Server {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    server
+}
+
+#[actix_rt::test]
+async fn ranking_score_threshold_drops_the_hits_below_it() {
+    let server = server_with_documents().await;
+    let index = server.index("test");
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "quick brown fox",
+            "matchingStrategy": "last",
+            "showRankingScore": true,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[1, 2, 3]");
+    snapshot!(response["estimatedTotalHits"], @"3");
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "quick brown fox",
+            "matchingStrategy": "last",
+            "showRankingScore": true,
+            "rankingScoreThreshold": 0.4,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[1, 2]");
+    snapshot!(response["estimatedTotalHits"], @"2");
+    for hit in response["hits"].as_array().unwrap() {
+        assert!(hit["_rankingScore"].as_f64().unwrap() >= 0.4, "{hit}");
+    }
+
+    // the hits below the threshold don't count in the offset
+    let (response, code) = index
+        .search_get(
+            "?q=quick%20brown%20fox&matchingStrategy=last&rankingScoreThreshold=0.4&offset=1",
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[2]");
+
+    // a threshold of 0 keeps every hit
+    let (response, code) = index
+        .search_post(json!({
+            "q": "quick brown fox",
+            "matchingStrategy": "last",
+            "rankingScoreThreshold": 0.0,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[1, 2, 3]");
+}
+
+#[actix_rt::test]
+async fn invalid_ranking_score_threshold() {
+    let server = server_with_documents().await;
+    let index = server.index("test");
+
+    let (response, code) =
+        index.search_post(json!({ "q": "fox", "rankingScoreThreshold": 1.5 })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.rankingScoreThreshold`: the value of `rankingScoreThreshold` is invalid, expected a float between `0.0` and `1.0`.",
+      "code": "invalid_search_ranking_score_threshold",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_ranking_score_threshold"
+    }
+    "###);
+
+    let (response, code) = index.search_get("?q=fox&rankingScoreThreshold=-0.1").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `rankingScoreThreshold`: the value of `rankingScoreThreshold` is invalid, expected a float between `0.0` and `1.0`.",
+      "code": "invalid_search_ranking_score_threshold",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_ranking_score_threshold"
+    }
+    "###);
+}
@@ -0,0 +1,142 @@
+use meili_snap::{json_string, snapshot};
+use meilisearch::Opt;
+
+use crate::common::{default_settings, Server};
+use crate::json;
+
+async fn server_with_index(options: Opt) -> Server {
+    let server = Server::new_with_options(options).await.unwrap();
+    let (_response, code) = server.set_features(json!({ "recentSearches": true })).await;
+    snapshot!(code, @"200 OK");
+    let index = server.index("test");
+    let (task, _code) = index
+        .add_documents(
+            json!([{ "id": 1, "title": "red shoes" }, { "id": 2, "title": "blue hat" }]),
+            None,
+        )
+        .await;
+    index.wait_task(task.uid()).await;
+    server
+}
+
+#[actix_rt::test]
+async fn recent_searches_requires_the_feature() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.create(None).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = server.service.get("/indexes/test/recent-searches").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Using the `/indexes/{indexUid}/recent-searches` route requires enabling the `recent searches` experimental feature. See https://www.meilisearch.com/docs/learn/experimental/overview",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn recent_searches_without_query() {
+    let temp = tempfile::tempdir().unwrap();
+    let server = server_with_index(default_settings(temp.path())).await;
+    let index = server.index("test");
+
+    let (_response, code) = index.search_post(json!({ "q": "red", "limit": 1 })).await;
+    snapshot!(code, @"200 OK");
+    let (_response, code) = index.search_get("q=blue&attributesToRetrieve=title").await;
+    snapshot!(code, @"200 OK");
+
+    // the `q` is not recorded, only the names and the types of the parameters
+    let (response, code) = server.service.get("/indexes/test/recent-searches").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response, { ".results[].searchedAt" => "[date]", ".results[].processingTimeMs" => "[duration]" }), @r###"
+    {
+      "results": [
+        {
+          "searchedAt": "[date]",
+          "parameters": {
+            "q": "string",
+            "attributesToRetrieve": "string"
+          },
+          "totalHits": 1,
+          "processingTimeMs": "[duration]"
+        },
+        {
+          "searchedAt": "[date]",
+          "parameters": {
+            "q": "string",
+            "limit": "number"
+          },
+          "totalHits": 1,
+          "processingTimeMs": "[duration]"
+        }
+      ]
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn recent_searches_with_query() {
+    let temp = tempfile::tempdir().unwrap();
+    let options =
+        Opt { experimental_recent_searches_with_query: true, ..default_settings(temp.path()) };
+    let server = server_with_index(options).await;
+    let index = server.index("test");
+
+    let (_response, code) = index.search_post(json!({ "q": "red shoes" })).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = server.service.get("/indexes/test/recent-searches").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["results"][0]["q"]), @r###""red shoes""###);
+}
+
+#[actix_rt::test]
+async fn recent_searches_are_bounded() {
+    let temp = tempfile::tempdir().unwrap();
+    let options = Opt {
+        experimental_recent_searches_size: 2,
+        experimental_recent_searches_with_query: true,
+        ..default_settings(temp.path())
+    };
+    let server = server_with_index(options).await;
+    let index = server.index("test");
+
+    for q in ["red", "blue", "shoes"] {
+        let (_response, code) = index.search_post(json!({ "q": q })).await;
+        snapshot!(code, @"200 OK");
+    }
+
+    // the oldest search is forgotten
+    let (response, code) = server.service.get("/indexes/test/recent-searches").await;
+    snapshot!(code, @"200 OK");
+    let queries: Vec<_> =
+        response["results"].as_array().unwrap().iter().map(|search| search["q"].clone()).collect();
+    snapshot!(json_string!(queries), @r###"["shoes", "blue"]"###);
+}
+
+#[actix_rt::test]
+async fn recent_searches_not_recorded_when_disabled() {
+    let temp = tempfile::tempdir().unwrap();
+    let server = server_with_index(default_settings(temp.path())).await;
+    let index = server.index("test");
+
+    let (_response, code) = server.set_features(json!({ "recentSearches": false })).await;
+    snapshot!(code, @"200 OK");
+    let (_response, code) = index.search_post(json!({ "q": "red" })).await;
+    snapshot!(code, @"200 OK");
+
+    // enabling the feature afterward doesn't reveal the searches that were not recorded
+    let (_response, code) = server.set_features(json!({ "recentSearches": true })).await;
+    snapshot!(code, @"200 OK");
+    let (response, code) = server.service.get("/indexes/test/recent-searches").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "results": []
+    }
+    "###);
+}
@@ -0,0 +1,122 @@
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        { "id": 1, "title": "running shoes for the trail", "description": "light" },
+        { "id": 2, "title": "running shoes and socks", "description": "cotton" },
+        { "id": 3, "title": "the best running shoes", "description": "cushioned" },
+        { "id": 4, "title": "running late for the meeting", "description": "a story" },
+        { "id": 5, "title": "wool hat", "description": "the running socks" },
+    ])
+});
+
+async fn index_with_documents(server: &Server) -> crate::common::Index<'_> {
+    let index = server.index("test");
+    let (task, _code) = index.update_settings(json!({ "stopWords": ["the", "a", "for"] })).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(task.uid()).await;
+    index
+}
+
+#[actix_rt::test]
+async fn related_terms_ranked_by_documents() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server).await;
+
+    let (response, code) =
+        index.service.post("/indexes/test/related-terms", json!({ "word": "Running" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["word"]), @r###""Running""###);
+    let terms = response["relatedTerms"].as_array().unwrap();
+    snapshot!(json_string!(terms[0]), @r###"
+    {
+      "term": "shoes",
+      "documents": 3
+    }
+    "###);
+    assert_eq!(terms[1], json!({ "term": "socks", "documents": 2 }), "{response}");
+    for stop_word in ["the", "a", "for"] {
+        assert!(terms.iter().all(|term| term["term"] != stop_word), "{response}");
+    }
+    assert!(terms.iter().all(|term| term["term"] != "running"), "{response}");
+
+    let (response, code) = index
+        .service
+        .post("/indexes/test/related-terms", json!({ "word": "running", "limit": 1 }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["relatedTerms"]), @r###"
+    [
+      {
+        "term": "shoes",
+        "documents": 3
+      }
+    ]
+    "###);
+
+    // a stop word has no related terms
+    let (response, code) =
+        index.service.post("/indexes/test/related-terms", json!({ "word": "the" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["relatedTerms"]), @"[]");
+}
+
+#[actix_rt::test]
+async fn related_terms_restricted_to_attributes() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server).await;
+
+    let (response, code) = index
+        .service
+        .post(
+            "/indexes/test/related-terms",
+            json!({ "word": "running", "attributes": ["description"] }),
+        )
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["relatedTerms"]), @r###"
+    [
+      {
+        "term": "socks",
+        "documents": 1
+      }
+    ]
+    "###);
+}
+
+#[actix_rt::test]
+async fn related_terms_bad_request() {
+    let server = Server::new().await;
+    let index = index_with_documents(&server).await;
+
+    let (response, code) =
+        index.service.post("/indexes/test/related-terms", json!({ "limit": 3 })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `word`",
+      "code": "missing_related_terms_word",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#missing_related_terms_word"
+    }
+    "###);
+
+    let (response, code) = index
+        .service
+        .post("/indexes/test/related-terms", json!({ "word": "running", "limit": "ten" }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.limit`: expected a positive integer, but found a string: `\"ten\"`",
+      "code": "invalid_related_terms_limit",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_related_terms_limit"
+    }
+    "###);
+}
@@ -0,0 +1,152 @@
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        { "id": 1, "title": "red running shoes", "description": "lightweight" },
+        { "id": 2, "title": "blue running shoes", "description": "red laces" },
+        { "id": 3, "title": "wool winter hat", "description": "warm" },
+        { "id": 4, "title": "leather boots", "description": "dark red" },
+    ])
+});
+
+static GOLDEN_QUERIES: Lazy<Value> = Lazy::new(|| {
+    json!({
+        "queries": [
+            { "name": "hat", "search": { "q": "hat" }, "expected": [{ "id": 3, "rankingScore": 1.0 }] },
+            { "name": "boots", "search": { "q": "boots" }, "expected": [{ "id": "4" }] },
+            { "name": "red", "search": { "q": "red" }, "expected": [{ "id": 1 }] },
+        ]
+    })
+});
+
+#[actix_rt::test]
+async fn relevance_check_flags_the_regressed_query() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) =
+        index.service.post("/indexes/test/relevance-check", GOLDEN_QUERIES.clone()).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Using the `/indexes/{indexUid}/relevance-check` route requires enabling the `relevance check` experimental feature. See https://www.meilisearch.com/docs/learn/experimental/overview",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+
+    let (_response, code) = server.set_features(json!({ "relevanceCheck": true })).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) =
+        index.service.post("/indexes/test/relevance-check", GOLDEN_QUERIES.clone()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["regressions"]), @"[]");
+    let hat = &response["queries"][0];
+    assert_eq!(hat["ids"], json!(["3"]));
+    assert!(hat["expected"][0]["rankingScoreDiff"].is_f64(), "{response}");
+    assert_eq!(response["queries"][2]["ids"], json!(["1"]));
+
+    // the descriptions are now more important than the titles.
+    let (task, _code) =
+        index.update_settings(json!({ "searchableAttributes": ["description", "title"] })).await;
+    index.wait_task(task.uid()).await;
+
+    let (response, code) =
+        index.service.post("/indexes/test/relevance-check", GOLDEN_QUERIES.clone()).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["regressions"]), @r###"
+    [
+      "red"
+    ]
+    "###);
+    let red = &response["queries"][2];
+    assert_eq!(red["regressed"], json!(true));
+    assert_ne!(red["expected"][0]["position"], json!(0), "{response}");
+    for query in &response["queries"].as_array().unwrap()[..2] {
+        assert_eq!(query["regressed"], json!(false), "{response}");
+    }
+}
+
+#[actix_rt::test]
+async fn relevance_check_tolerance() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(task.uid()).await;
+    server.set_features(json!({ "relevanceCheck": true })).await;
+
+    // the shoes are both ranked before the boots, which must then tolerate two positions.
+    let golden_query = |tolerance: usize| {
+        json!({
+            "queries": [{
+                "name": "red",
+                "search": { "q": "red" },
+                "expected": [{ "id": 4 }],
+                "tolerance": tolerance,
+            }]
+        })
+    };
+
+    let (response, code) =
+        index.service.post("/indexes/test/relevance-check", golden_query(1)).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["regressions"]), @r###"
+    [
+      "red"
+    ]
+    "###);
+
+    let (response, code) =
+        index.service.post("/indexes/test/relevance-check", golden_query(2)).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["regressions"]), @"[]");
+    snapshot!(json_string!(response["queries"][0]["expected"][0]["position"]), @"2");
+}
+
+#[actix_rt::test]
+async fn relevance_check_bad_queries() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    index.wait_task(task.uid()).await;
+    server.set_features(json!({ "relevanceCheck": true })).await;
+
+    let (response, code) = index
+        .service
+        .post("/indexes/test/relevance-check", json!({ "queries": [{ "name": "red" }] }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `expected` inside `.queries[0]`",
+      "code": "invalid_relevance_check_queries",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_relevance_check_queries"
+    }
+    "###);
+
+    let (response, code) = index
+        .service
+        .post(
+            "/indexes/test/relevance-check",
+            json!({ "queries": [{ "name": "red", "expected": [{ "id": true }] }] }),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid expected hit id `true` in the `red` query: expected a string or an integer.",
+      "code": "invalid_relevance_check_queries",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_relevance_check_queries"
+    }
+    "###);
+}
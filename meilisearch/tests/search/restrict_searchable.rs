@@ -367,3 +367,161 @@ async fn search_on_exact_field() {
         })
         .await;
 }
+
+#[actix_rt::test]
+async fn exact_attributes_only_match_the_query_words() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (task, _) = index.update_settings(json!({ "exactAttributes": ["sku"] })).await;
+    index.wait_task(task.uid()).await;
+    let (task, _) = index
+        .add_documents(
+            json!([
+                { "id": 1, "title": "sunflower", "sku": "rose" },
+                { "id": 2, "title": "rose", "sku": "sunflower" },
+                { "id": 3, "title": "tulip", "sku": "sunflowers" },
+            ]),
+            None,
+        )
+        .await;
+    index.wait_task(task.uid()).await;
+
+    // a one-typo query matches the title, but never the sku
+    index
+        .search(json!({"q": "sunflowr"}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 1,
+                "title": "sunflower",
+                "sku": "rose"
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    // neither does a prefix
+    index
+        .search(json!({"q": "sunflower"}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 1,
+                "title": "sunflower",
+                "sku": "rose"
+              },
+              {
+                "id": 2,
+                "title": "rose",
+                "sku": "sunflower"
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    index
+        .search(json!({"q": "sunflowr", "attributesToSearchOn": ["sku"]}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @"[]");
+        })
+        .await;
+}
+
+#[actix_rt::test]
+async fn search_on_attribute_patterns() {
+    let server = Server::new().await;
+    let index = index_with_documents(
+        &server,
+        &json!([
+        {
+            "title": "Shazam!",
+            "desc": "a Captain Marvel ersatz",
+            "id": "1",
+        },
+        {
+            "title": "Captain Planet",
+            "description": "He's not part of the Marvel Cinematic Universe",
+            "id": "2",
+        },
+        {
+            "title": "Shazam!",
+            "footer": "The story of Captain Marvel",
+            "id": "3",
+        }]),
+    )
+    .await;
+
+    // the documents only matching in the footer are not returned
+    index
+        .search(json!({"q": "Captain Marvel", "attributesToSearchOn": ["title", "desc*"], "attributesToRetrieve": ["id"]}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": "1"
+              },
+              {
+                "id": "2"
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    // a pattern matching no attribute restricts the search to nothing
+    index
+        .search(
+            json!({"q": "Captain Marvel", "attributesToSearchOn": ["head*"]}),
+            |response, code| {
+                snapshot!(code, @"200 OK");
+                snapshot!(json_string!(response["hits"]), @"[]");
+            },
+        )
+        .await;
+}
+
+#[actix_rt::test]
+async fn proximity_ranking_rule_restricted() {
+    let server = Server::new().await;
+    let index = index_with_documents(
+        &server,
+        &json!([
+        {
+            "title": "Captain Planet",
+            "desc": "not part of the Marvel Cinematic Universe",
+            "footer": "Captain Marvel",
+            "id": "1",
+        },
+        {
+            "title": "Captain Marvel",
+            "desc": "a Shazam ersatz",
+            "footer": "A super hero",
+            "id": "2",
+        }]),
+    )
+    .await;
+    let (task, _) = index.update_settings(json!({ "rankingRules": ["words", "proximity"] })).await;
+    index.wait_task(task.uid()).await;
+
+    // Document 2 should appear before document 1, the words of document 1 are only next to each other in the footer.
+    index
+        .search(json!({"q": "Captain Marvel", "attributesToSearchOn": ["title", "desc"], "attributesToRetrieve": ["id"]}), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": "2"
+              },
+              {
+                "id": "1"
+              }
+            ]
+            "###);
+        })
+        .await;
+}
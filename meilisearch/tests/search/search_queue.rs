@@ -6,6 +6,9 @@ use actix_web::ResponseError;
 use meili_snap::snapshot;
 use meilisearch::search_queue::SearchQueue;
 
+use crate::common::Server;
+use crate::json;
+
 #[actix_rt::test]
 async fn search_queue_register() {
     let queue = SearchQueue::new(4, NonZeroUsize::new(2).unwrap());
@@ -63,27 +66,25 @@ async fn wait_till_cores_are_available() {
 
 #[actix_rt::test]
 async fn refuse_search_requests_when_queue_is_full() {
-    let queue = Arc::new(SearchQueue::new(1, NonZeroUsize::new(1).unwrap()));
+    let queue = SearchQueue::new(1, NonZeroUsize::new(1).unwrap());
 
     // First, use the whole capacity of the
-    let _permit1 = tokio::time::timeout(Duration::from_secs(1), queue.try_get_search_permit())
+    let permit1 = tokio::time::timeout(Duration::from_secs(1), queue.try_get_search_permit())
         .await
         .expect("I should get a permit straight away")
         .unwrap();
 
-    let q = queue.clone();
-    let permit2 = tokio::task::spawn(async move { q.try_get_search_permit().await });
-
-    // Here the queue is full. By registering two new search requests the permit 2 and 3 should be thrown out
-    let q = queue.clone();
-    let _permit3 = tokio::task::spawn(async move { q.try_get_search_permit().await });
+    let mut permit2 = Box::pin(queue.try_get_search_permit());
+    tokio::time::timeout(Duration::from_millis(100), &mut permit2)
+        .await
+        .expect_err("The second search should wait in the queue");
 
-    let permit2 = tokio::time::timeout(Duration::from_secs(1), permit2)
+    // Here the queue is full. The new search request should be refused, and the second one keep its place
+    let permit3 = tokio::time::timeout(Duration::from_secs(1), queue.try_get_search_permit())
         .await
-        .expect("I should get a result straight away")
-        .unwrap(); // task should end successfully
+        .expect("I should get a result straight away");
 
-    let err = meilisearch_types::error::ResponseError::from(permit2.unwrap_err());
+    let err = meilisearch_types::error::ResponseError::from(permit3.unwrap_err());
     let http_response = err.error_response();
     let mut headers: Vec<_> = http_response
         .headers()
@@ -102,6 +103,141 @@ async fn refuse_search_requests_when_queue_is_full() {
       "link": "https://docs.meilisearch.com/errors#too_many_search_requests"
     }
     "###);
+
+    drop(permit1);
+    let _permit2 = tokio::time::timeout(Duration::from_secs(1), permit2)
+        .await
+        .expect("I should get a permit straight away")
+        .unwrap();
+}
+
+#[actix_rt::test]
+async fn search_requests_are_processed_in_order() {
+    let queue = SearchQueue::new(3, NonZeroUsize::new(1).unwrap());
+
+    let permit1 = tokio::time::timeout(Duration::from_secs(1), queue.try_get_search_permit())
+        .await
+        .expect("I should get a permit straight away")
+        .unwrap();
+
+    // Register the three next searches in order
+    let mut permit2 = Box::pin(queue.try_get_search_permit());
+    let mut permit3 = Box::pin(queue.try_get_search_permit());
+    let mut permit4 = Box::pin(queue.try_get_search_permit());
+    for permit in [&mut permit2, &mut permit3, &mut permit4] {
+        tokio::time::timeout(Duration::from_millis(100), permit)
+            .await
+            .expect_err("The search should wait in the queue");
+    }
+
+    // Only the oldest search can run once the first one is finished
+    drop(permit1);
+    tokio::time::timeout(Duration::from_millis(100), &mut permit4)
+        .await
+        .expect_err("The fourth search must wait for the second and third ones");
+    tokio::time::timeout(Duration::from_millis(100), &mut permit3)
+        .await
+        .expect_err("The third search must wait for the second one");
+    let permit2 = tokio::time::timeout(Duration::from_secs(1), permit2)
+        .await
+        .expect("I should get a permit straight away")
+        .unwrap();
+
+    drop(permit2);
+    tokio::time::timeout(Duration::from_millis(100), &mut permit4)
+        .await
+        .expect_err("The fourth search must wait for the third one");
+    let permit3 = tokio::time::timeout(Duration::from_secs(1), permit3)
+        .await
+        .expect("I should get a permit straight away")
+        .unwrap();
+
+    drop(permit3);
+    let _permit4 = tokio::time::timeout(Duration::from_secs(1), permit4)
+        .await
+        .expect("I should get a permit straight away")
+        .unwrap();
+}
+
+#[actix_rt::test]
+async fn permits_count_for_several_searches() {
+    let queue = SearchQueue::new(2, NonZeroUsize::new(2).unwrap());
+
+    let permit1 = tokio::time::timeout(Duration::from_secs(1), queue.try_get_search_permit())
+        .await
+        .expect("I should get a permit straight away")
+        .unwrap();
+
+    // A multi-search of two queries needs the two cores
+    let mut permit2 = Box::pin(queue.try_get_search_permits(2));
+    tokio::time::timeout(Duration::from_millis(100), &mut permit2)
+        .await
+        .expect_err("The multi-search should wait for the first search");
+
+    // A core is available, but the new search must not overtake the multi-search
+    let mut permit3 = Box::pin(queue.try_get_search_permit());
+    tokio::time::timeout(Duration::from_millis(100), &mut permit3)
+        .await
+        .expect_err("The search should wait for the multi-search");
+
+    drop(permit1);
+    let permit2 = tokio::time::timeout(Duration::from_secs(1), permit2)
+        .await
+        .expect("I should get a permit straight away")
+        .unwrap();
+    tokio::time::timeout(Duration::from_millis(100), &mut permit3)
+        .await
+        .expect_err("The search should wait for the multi-search");
+
+    drop(permit2);
+    let permit3 = tokio::time::timeout(Duration::from_secs(1), permit3)
+        .await
+        .expect("I should get a permit straight away")
+        .unwrap();
+    drop(permit3);
+
+    // A multi-search with more queries than cores can still run
+    let _permit4 = tokio::time::timeout(Duration::from_secs(1), queue.try_get_search_permits(10))
+        .await
+        .expect("I should get a permit straight away")
+        .unwrap();
+}
+
+#[actix_rt::test]
+async fn search_routes_go_through_the_search_queue() {
+    let mut server = Server::new().await;
+    server.service.search_queue = Arc::new(SearchQueue::new(1, NonZeroUsize::new(1).unwrap()));
+    let index = server.index("test");
+    let (task, _code) = index.add_documents(json!([{ "id": 1, "title": "kefir" }]), None).await;
+    index.wait_task(task.uid()).await;
+
+    // This permit plays the role of a very slow search that takes the only core
+    let slow_search = server.service.search_queue.try_get_search_permit().await.unwrap();
+
+    let mut search = Box::pin(index.search_post(json!({ "q": "kefir" })));
+    tokio::time::timeout(Duration::from_millis(100), &mut search)
+        .await
+        .expect_err("The search should wait in the queue");
+
+    // The queue is full, the other search requests are refused
+    let (response, code) = index.search_post(json!({ "q": "kefir" })).await;
+    snapshot!(code, @"503 Service Unavailable");
+    snapshot!(response["code"], @r###""too_many_search_requests""###);
+    let (response, code) = index.facet_search(json!({ "facetName": "title" })).await;
+    snapshot!(code, @"503 Service Unavailable");
+    snapshot!(response["code"], @r###""too_many_search_requests""###);
+    let (response, code) =
+        server.multi_search(json!({"queries": [{ "indexUid": "test", "q": "kefir" }]})).await;
+    snapshot!(code, @"503 Service Unavailable");
+    snapshot!(response["code"], @r###""too_many_search_requests""###);
+
+    // Once the slow search is finished, the waiting search is processed
+    drop(slow_search);
+    let (response, code) = tokio::time::timeout(Duration::from_secs(5), search)
+        .await
+        .expect("The search should be processed once the slow search is finished");
+    snapshot!(code, @"200 OK");
+    snapshot!(response["hits"], @r###"[{"id":1,"title":"kefir"}]"###);
 }
 
 #[actix_rt::test]
@@ -0,0 +1,92 @@
+use meili_snap::{json_string, snapshot};
+use meilisearch::Opt;
+
+use crate::common::{default_settings, Server, Value};
+use crate::json;
+
+fn read_traces(dir: &std::path::Path) -> Vec<Value> {
+    let mut paths: Vec<_> =
+        std::fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+    paths.sort_unstable();
+    paths
+        .into_iter()
+        .map(|path| Value(serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap()))
+        .collect()
+}
+
+#[actix_rt::test]
+async fn search_trace_written_and_capped() {
+    let temp = tempfile::tempdir().unwrap();
+    let trace_dir = temp.path().join("search-traces");
+    let options = Opt {
+        experimental_search_trace_dir: Some(trace_dir.clone()),
+        experimental_search_trace_max_files: 2,
+        ..default_settings(temp.path())
+    };
+    let server = Server::new_with_options(options).await.unwrap();
+    let index = server.index("test");
+    index
+        .add_documents(
+            json!([{ "id": 1, "text": "hello world" }, { "id": 2, "text": "hello" }]),
+            None,
+        )
+        .await;
+    index.wait_task(0).await;
+
+    let (response, code) = index.search_post(json!({ "q": "hello", "limit": 1 })).await;
+    snapshot!(code, @"200 OK");
+    // the trace is written in a file, not returned
+    assert!(response.get("trace").is_none(), "{response}");
+
+    let traces = read_traces(&trace_dir);
+    assert_eq!(traces.len(), 1);
+    snapshot!(traces[0]["indexUid"], @r###""test""###);
+    snapshot!(json_string!(traces[0]["query"]), @r###"
+    {
+      "q": "hello",
+      "limit": 1
+    }
+    "###);
+    snapshot!(traces[0]["trace"]["initialUniverse"], @"2");
+    let events = traces[0]["trace"]["events"].as_array().unwrap();
+    snapshot!(events[0]["event"], @r###""startIteration""###);
+    snapshot!(events[0]["rankingRule"], @r###""words""###);
+    let added: Vec<_> =
+        events.iter().filter(|event| event["event"] == json!("addToResults")).collect();
+    snapshot!(json_string!(added), @r###"
+    [
+      {
+        "event": "addToResults",
+        "docids": [
+          1
+        ]
+      }
+    ]
+    "###);
+
+    // only the two most recent traces are kept
+    index.search_get("q=world").await;
+    index.search_get("q=hello%20world").await;
+    let traces = read_traces(&trace_dir);
+    assert_eq!(traces.len(), 2);
+    snapshot!(traces[0]["query"], @r###""q=world""###);
+    snapshot!(traces[1]["query"], @r###""q=hello%20world""###);
+}
+
+#[actix_rt::test]
+async fn search_trace_not_written_by_default() {
+    let temp = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(default_settings(temp.path())).await.unwrap();
+    let index = server.index("test");
+    index.add_documents(json!([{ "id": 1, "text": "hello world" }]), None).await;
+    index.wait_task(0).await;
+
+    let (_, code) = index.search_post(json!({ "q": "hello" })).await;
+    snapshot!(code, @"200 OK");
+    let entries: Vec<_> = std::fs::read_dir(temp.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.contains("trace"))
+        .collect();
+    assert!(entries.is_empty(), "{entries:?}");
+}
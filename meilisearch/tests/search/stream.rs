@@ -0,0 +1,78 @@
+use actix_web::http::header::{ACCEPT, CONTENT_TYPE};
+use actix_web::test;
+use meili_snap::{json_string, snapshot};
+use serde_json::Value;
+
+use crate::common::Server;
+use crate::json;
+
+async fn server_with_documents() -> Server {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index
+        .update_settings(json!({ "rankingRules": ["words", "typo", "rank:asc"], "sortableAttributes": ["rank"] }))
+        .await;
+    index.wait_task(task.uid()).await;
+    let documents: Vec<_> = (0..50)
+        .map(|id| {
+            let title = match id % 3 {
+                0 => "red shoes",
+                1 => "red hat",
+                _ => "blue shoes",
+            };
+            json!({ "id": id, "title": title, "rank": (id * 7) % 50 })
+        })
+        .collect();
+    let (task, _code) = index.add_documents(documents.into(), None).await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    server
+}
+
+#[actix_rt::test]
+async fn streamed_hits_are_the_hits_of_the_search() {
+    let server = server_with_documents().await;
+    let index = server.index("test");
+    let app = server.init_web_app().await;
+
+    for search in [
+        json!({ "q": "red shoes", "limit": 100 }),
+        json!({ "q": "shoes", "offset": 5, "limit": 10, "attributesToRetrieve": ["id"] }),
+        json!({ "limit": 30 }),
+    ] {
+        let (response, code) = index.search_post(search.clone()).await;
+        snapshot!(code, @"200 OK");
+
+        let req = test::TestRequest::post()
+            .uri("/indexes/test/search")
+            .insert_header((ACCEPT, "application/x-ndjson"))
+            .set_json(&search.0);
+        let res = test::call_service(&app, req.to_request()).await;
+        snapshot!(res.status(), @"200 OK");
+        snapshot!(res.headers().get(CONTENT_TYPE).unwrap().to_str().unwrap(), @"application/x-ndjson");
+        let body = test::read_body(res).await;
+        let streamed: Vec<Value> = std::str::from_utf8(&body)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(Value::from(streamed), response["hits"], "{search}");
+    }
+}
+
+#[actix_rt::test]
+async fn stream_errors_before_the_first_hit() {
+    let server = server_with_documents().await;
+    let app = server.init_web_app().await;
+
+    // the errors found before streaming any hit are returned as usual
+    let req = test::TestRequest::post()
+        .uri("/indexes/test/search")
+        .insert_header((ACCEPT, "application/x-ndjson"))
+        .set_json(serde_json::json!({ "q": "red", "filter": "title = red" }));
+    let res = test::call_service(&app, req.to_request()).await;
+    snapshot!(res.status(), @"400 Bad Request");
+    let body: Value = test::read_body_json(res).await;
+    snapshot!(json_string!(body["code"]), @r###""invalid_search_filter""###);
+}
@@ -0,0 +1,149 @@
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        { "id": 1, "reference": "bearing 62012" },
+        { "id": 2, "reference": "bearings 62012" },
+        { "id": 3, "reference": "washer" },
+    ])
+});
+
+fn ids(response: &Value) -> Vec<i64> {
+    response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].as_i64().unwrap()).collect()
+}
+
+async fn server_with_documents() -> Server {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    server
+}
+
+#[actix_rt::test]
+async fn disable_typo_tolerance_for_a_query() {
+    let server = server_with_documents().await;
+    let index = server.index("test");
+
+    let (response, code) =
+        index.search_post(json!({ "q": "bearinh", "showRankingScoreDetails": true })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[1, 2]");
+    snapshot!(json_string!(response["hits"][0]["_rankingScoreDetails"]["typo"]), @r###"
+    {
+      "order": 1,
+      "typoCount": 1,
+      "maxTypoCount": 1,
+      "score": 0.5
+    }
+    "###);
+
+    let (response, code) =
+        index.search_post(json!({ "q": "bearinh", "typoTolerance": { "enabled": false } })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[]");
+
+    // the score details reflect the typo tolerance the search was made with
+    let (response, code) = index
+        .search_post(json!({
+            "q": "bearing",
+            "typoTolerance": { "enabled": false },
+            "showRankingScoreDetails": true,
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[1, 2]");
+    snapshot!(json_string!(response["hits"][0]["_rankingScoreDetails"]["typo"]), @r###"
+    {
+      "order": 1,
+      "typoCount": 0,
+      "maxTypoCount": 0,
+      "score": 1.0
+    }
+    "###);
+
+    // the settings of the index are untouched
+    let (response, _code) = index.settings().await;
+    snapshot!(json_string!(response["typoTolerance"]["enabled"]), @"true");
+}
+
+#[actix_rt::test]
+async fn override_the_min_word_size_for_typos_for_a_query() {
+    let server = server_with_documents().await;
+    let index = server.index("test");
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "bearinh",
+            "typoTolerance": { "minWordSizeForTypos": { "oneTypo": 8 } },
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[]");
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "bearinh",
+            "typoTolerance": { "minWordSizeForTypos": { "oneTypo": 7 } },
+        }))
+        .await;
+    snapshot!(code, @"200 OK");
+    snapshot!(format!("{:?}", ids(&response)), @"[1, 2]");
+}
+
+#[actix_rt::test]
+async fn invalid_typo_tolerance_for_a_query() {
+    let server = server_with_documents().await;
+    let index = server.index("test");
+
+    let (response, code) = index
+        .search_post(json!({
+            "q": "bearinh",
+            "typoTolerance": { "minWordSizeForTypos": { "oneTypo": 8, "twoTypos": 5 } },
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.typoTolerance.minWordSizeForTypos`: `minWordSizeForTypos` setting is invalid. `oneTypo` and `twoTypos` fields should be between `0` and `255`, and `twoTypos` should be greater or equals to `oneTypo` but found `oneTypo: 8` and twoTypos: 5`.",
+      "code": "invalid_settings_typo_tolerance",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_typo_tolerance"
+    }
+    "###);
+
+    // the two typos minimum of the index is 9
+    let (response, code) = index
+        .search_post(json!({
+            "q": "bearinh",
+            "typoTolerance": { "minWordSizeForTypos": { "oneTypo": 10 } },
+        }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`minWordSizeForTypos` setting is invalid. `oneTypo` and `twoTypos` fields should be between `0` and `255`, and `twoTypos` should be greater or equals to `oneTypo` but found `oneTypo: 10` and twoTypos: 9`.",
+      "code": "invalid_settings_typo_tolerance",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_typo_tolerance"
+    }
+    "###);
+
+    let (response, code) = index
+        .search_post(json!({ "q": "bearinh", "typoTolerance": { "disableOnWords": [] } }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown field `disableOnWords` inside `.typoTolerance`: expected one of `enabled`, `minWordSizeForTypos`",
+      "code": "invalid_settings_typo_tolerance",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_typo_tolerance"
+    }
+    "###);
+}
@@ -0,0 +1,129 @@
+use meili_snap::{json_string, snapshot};
+use once_cell::sync::Lazy;
+
+use crate::common::{Server, Value};
+use crate::json;
+
+static DOCUMENTS: Lazy<Value> = Lazy::new(|| {
+    json!([
+        { "id": 1, "text": "one two three" },
+        { "id": 2, "text": "two three" },
+        { "id": 3, "text": "three" },
+        { "id": 4, "text": "three thirteen" },
+    ])
+});
+
+async fn server_with_documents() -> Server {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.update_settings(json!({ "searchableAttributes": ["text"] })).await;
+    index.wait_task(task.uid()).await;
+    let (task, _code) = index.add_documents(DOCUMENTS.clone(), None).await;
+    let response = index.wait_task(task.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    server
+}
+
+#[actix_rt::test]
+async fn word_frequency_requires_the_feature() {
+    let server = server_with_documents().await;
+
+    let (response, code) = server.service.get("/indexes/test/word-frequency").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Using the `/indexes/{indexUid}/word-frequency` route requires enabling the `word frequency` experimental feature. See https://www.meilisearch.com/docs/learn/experimental/overview",
+      "code": "feature_not_enabled",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#feature_not_enabled"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn word_frequency() {
+    let server = server_with_documents().await;
+    let (_response, code) = server.set_features(json!({ "wordFrequency": true })).await;
+    snapshot!(code, @"200 OK");
+
+    let (response, code) = server.service.get("/indexes/test/word-frequency").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "prefix": null,
+      "words": [
+        {
+          "word": "three",
+          "documents": 4
+        },
+        {
+          "word": "two",
+          "documents": 2
+        },
+        {
+          "word": "one",
+          "documents": 1
+        },
+        {
+          "word": "thirteen",
+          "documents": 1
+        }
+      ]
+    }
+    "###);
+
+    let (response, code) = server.service.get("/indexes/test/word-frequency?prefix=Th").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response), @r###"
+    {
+      "prefix": "Th",
+      "words": [
+        {
+          "word": "three",
+          "documents": 4
+        },
+        {
+          "word": "thirteen",
+          "documents": 1
+        }
+      ]
+    }
+    "###);
+
+    let (response, code) = server.service.get("/indexes/test/word-frequency?limit=2").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["words"]), @r###"
+    [
+      {
+        "word": "three",
+        "documents": 4
+      },
+      {
+        "word": "two",
+        "documents": 2
+      }
+    ]
+    "###);
+
+    let (response, code) =
+        server.service.get("/indexes/test/word-frequency?prefix=four&limit=10").await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["words"]), @"[]");
+}
+
+#[actix_rt::test]
+async fn word_frequency_bad_limit() {
+    let server = server_with_documents().await;
+    server.set_features(json!({ "wordFrequency": true })).await;
+
+    let (response, code) = server.service.get("/indexes/test/word-frequency?limit=many").await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `limit`: could not parse `many` as a positive integer",
+      "code": "invalid_word_frequency_limit",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_word_frequency_limit"
+    }
+    "###);
+}
@@ -0,0 +1,91 @@
+use meili_snap::{json_string, snapshot};
+use meilisearch::Opt;
+
+use crate::common::server::default_settings;
+use crate::common::Server;
+use crate::json;
+
+#[actix_rt::test]
+async fn attributes_limits_are_checked_after_expansion() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_attributes_soft_limit: 3,
+        experimental_attributes_hard_limit: Some(5),
+        ..default_settings(dir.path())
+    })
+    .await
+    .unwrap();
+    let index = server.index("test");
+
+    let (response, code) = index
+        .add_documents(
+            json!([{
+                "id": 1,
+                "title": "Bobby",
+                "doggo": { "name": "bobby", "age": 2, "race": "bernese mountain" },
+            }]),
+            None,
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (response, _code) =
+        index.update_settings(json!({ "filterableAttributes": ["title"] })).await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "filterableAttributes": [
+        "title"
+      ]
+    }
+    "###);
+
+    // `doggo` counts for itself and its three nested fields
+    let (response, _code) =
+        index.update_settings(json!({ "filterableAttributes": ["doggo"] })).await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "filterableAttributes": [
+        "doggo"
+      ],
+      "warnings": [
+        "The index has 4 filterable attributes, which exceeds the recommended limit of 3."
+      ]
+    }
+    "###);
+
+    // `*` counts for all the fields of the index
+    let (response, _code) = index.update_settings(json!({ "searchableAttributes": ["*"] })).await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+    snapshot!(json_string!(response["error"]), @r###"
+    {
+      "message": "The index would have 6 searchable attributes, which exceeds the limit of 5 searchable attributes.",
+      "code": "invalid_settings_searchable_attributes",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_searchable_attributes"
+    }
+    "###);
+
+    // attributes that don't match any field yet count for one each
+    let (response, _code) = index
+        .update_settings(json!({ "sortableAttributes": ["a", "b", "c", "d", "e", "f", "g"] }))
+        .await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""failed""###);
+    snapshot!(response["error"]["message"], @r###""The index would have 7 sortable attributes, which exceeds the limit of 5 sortable attributes.""###);
+
+    // the failed tasks didn't change anything
+    let (response, code) = index.settings().await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["searchableAttributes"]), @r###"
+    [
+      "*"
+    ]
+    "###);
+    snapshot!(json_string!(response["sortableAttributes"]), @"[]");
+}
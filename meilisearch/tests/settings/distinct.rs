@@ -1,3 +1,5 @@
+use meili_snap::{json_string, snapshot};
+
 use crate::common::Server;
 use crate::json;
 
@@ -42,3 +44,64 @@ async fn set_and_reset_distinct_attribute_with_dedicated_route() {
 
     assert_eq!(response, json!(null));
 }
+
+#[actix_rt::test]
+async fn distinct_attribute_is_implicitly_faceted() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, _code) = index.update_settings(json!({ "distinctAttribute": "color" })).await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "distinctAttribute": "color",
+      "warnings": [
+        "The distinct attribute `color` is not filterable, its values were implicitly indexed as facets."
+      ]
+    }
+    "###);
+
+    let documents = json!([
+        { "id": 0, "color": "red" },
+        { "id": 1, "color": "blue" },
+        { "id": 2, "color": "red" },
+        { "id": 3, "color": "green" },
+        { "id": 4, "color": "blue" },
+    ]);
+    let (response, _code) = index.add_documents(documents, None).await;
+    index.wait_task(response.uid()).await;
+
+    index
+        .search(json!({ "attributesToRetrieve": ["id"] }), |response, code| {
+            snapshot!(code, @"200 OK");
+            snapshot!(json_string!(response["hits"]), @r###"
+            [
+              {
+                "id": 0
+              },
+              {
+                "id": 1
+              },
+              {
+                "id": 3
+              }
+            ]
+            "###);
+        })
+        .await;
+
+    // no note when the distinct attribute is also filterable
+    let (response, _code) = index
+        .update_settings(json!({ "distinctAttribute": "color", "filterableAttributes": ["color"] }))
+        .await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "filterableAttributes": [
+        "color"
+      ],
+      "distinctAttribute": "color"
+    }
+    "###);
+}
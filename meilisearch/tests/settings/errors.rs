@@ -143,6 +143,37 @@ async fn settings_bad_ranking_rules() {
     "###);
 }
 
+#[actix_rt::test]
+async fn settings_repeated_ranking_rules() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index
+        .update_settings(json!({ "rankingRules": ["words", "price:asc", "typo", "price:desc"] }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`price:desc` ranking rule at index 3 is sorting the `price` attribute, already sorted by the `price:asc` ranking rule at index 1. An attribute can only be sorted by a single ranking rule.",
+      "code": "invalid_settings_ranking_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_ranking_rules"
+    }
+    "###);
+
+    let (response, code) =
+        index.update_settings_ranking_rules(json!(["sort", "words", "sort", "typo"])).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "`sort` ranking rule at index 2 is repeating the `sort` ranking rule at index 0. The `sort` ranking rule can only be used once.",
+      "code": "invalid_settings_ranking_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_ranking_rules"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn settings_bad_stop_words() {
     let server = Server::new().await;
@@ -365,3 +396,225 @@ async fn settings_bad_search_cutoff_ms() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn settings_bad_search_defaults() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) =
+        index.update_settings(json!({ "searchDefaults": { "cropLength": "doggo" } })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.searchDefaults.cropLength`: expected a positive integer, but found a string: `\"doggo\"`",
+      "code": "invalid_search_crop_length",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_crop_length"
+    }
+    "###);
+
+    let (response, code) =
+        index.update_settings_search_defaults(json!({ "highlightPreTag": ["doggo"] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.searchDefaults.highlightPreTag`: expected a string, but found an array: `[\"doggo\"]`",
+      "code": "invalid_search_highlight_pre_tag",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_search_highlight_pre_tag"
+    }
+    "###);
+
+    let (response, code) = index.update_settings_search_defaults(json!("doggo")).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.searchDefaults`: expected an object, but found a string: `\"doggo\"`",
+      "code": "invalid_settings_search_defaults",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_search_defaults"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_min_numeric_prefix_length() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) =
+        index.update_settings(json!({ "minNumericPrefixLength": "doggo" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.minNumericPrefixLength`: expected a positive integer, but found a string: `\"doggo\"`",
+      "code": "invalid_settings_min_numeric_prefix_length",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_min_numeric_prefix_length"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_allow_underscore_fields() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.update_settings(json!({ "allowUnderscoreFields": "doggo" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.allowUnderscoreFields`: expected a boolean, but found a string: `\"doggo\"`",
+      "code": "invalid_settings_allow_underscore_fields",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_allow_underscore_fields"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_localized_attributes() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index
+        .update_settings(json!({ "localizedAttributes": [{ "attributePatterns": ["title"], "locales": ["klingon"] }] }))
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown value `klingon` at `.localizedAttributes[0].locales[0]`: expected one of `ara`, `ces`, `cmn`, `dan`, `deu`, `ell`, `eng`, `est`, `fin`, `fra`, `heb`, `hin`, `hrv`, `hun`, `isl`, `ita`, `jpn`, `kor`, `lav`, `lit`, `nld`, `nob`, `pol`, `por`, `ron`, `rus`, `slk`, `slv`, `spa`, `swe`, `tha`, `tur`, `ukr`, `vie`",
+      "code": "invalid_settings_localized_attributes",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_localized_attributes"
+    }
+    "###);
+
+    let (response, code) =
+        index.update_settings(json!({ "localizedAttributes": [{ "locales": ["fra"] }] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Missing field `attributePatterns` inside `.localizedAttributes[0]`",
+      "code": "invalid_settings_localized_attributes",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_localized_attributes"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_number_normalization() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.update_settings(json!({ "numberNormalization": "doggo" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.numberNormalization`: expected a boolean, but found a string: `\"doggo\"`",
+      "code": "invalid_settings_number_normalization",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_number_normalization"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_phrase_across_fields() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.update_settings(json!({ "phraseAcrossFields": "doggo" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.phraseAcrossFields`: expected a boolean, but found a string: `\"doggo\"`",
+      "code": "invalid_settings_phrase_across_fields",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_phrase_across_fields"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_exact_attributes() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.update_settings(json!({ "exactAttributes": "doggo" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.exactAttributes`: expected an array, but found a string: `\"doggo\"`",
+      "code": "invalid_settings_exact_attributes",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_exact_attributes"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_decompounding_locales() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.update_settings(json!({ "decompoundingLocales": "deu" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.decompoundingLocales`: expected an array, but found a string: `\"deu\"`",
+      "code": "invalid_settings_decompounding_locales",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_decompounding_locales"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_facet_types() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index.update_settings(json!({ "facetTypes": ["published"] })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.facetTypes`: expected an object, but found an array: `[\"published\"]`",
+      "code": "invalid_settings_facet_types",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_facet_types"
+    }
+    "###);
+}
+
+#[actix_rt::test]
+async fn settings_bad_stemming() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) =
+        index.update_settings(json!({ "stemming": { "locales": ["fra"] } })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.stemming`: the words of the `fra` locale can't be stemmed, expected one of `eng`",
+      "code": "invalid_settings_stemming",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_stemming"
+    }
+    "###);
+
+    let (response, code) =
+        index.update_settings(json!({ "stemming": { "languages": ["eng"] } })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Unknown field `languages` inside `.stemming`: expected one of `locales`",
+      "code": "invalid_settings_stemming",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_stemming"
+    }
+    "###);
+}
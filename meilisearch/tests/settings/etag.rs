@@ -0,0 +1,92 @@
+use actix_web::http::header::{ContentType, ETAG, IF_MATCH};
+use actix_web::http::StatusCode;
+use actix_web::test;
+use meili_snap::snapshot;
+use serde_json::{json, Value};
+
+use crate::common::Server;
+
+/// Sends the request and returns the body, the status and the ETag of the response.
+async fn call<S, B>(app: &S, req: test::TestRequest) -> (Value, StatusCode, Option<String>)
+where
+    S: actix_web::dev::Service<
+        actix_http::Request,
+        Response = actix_web::dev::ServiceResponse<B>,
+        Error = actix_web::Error,
+    >,
+    B: actix_web::body::MessageBody,
+{
+    let res = test::call_service(app, req.to_request()).await;
+    let status = res.status();
+    let etag = res.headers().get(ETAG).map(|etag| etag.to_str().unwrap().to_string());
+    let body = test::read_body(res).await;
+    (serde_json::from_slice(&body).unwrap_or_default(), status, etag)
+}
+
+fn patch_settings(body: Value, if_match: &str) -> test::TestRequest {
+    test::TestRequest::patch()
+        .uri("/indexes/test/settings")
+        .insert_header(ContentType::json())
+        .insert_header((IF_MATCH, if_match))
+        .set_payload(body.to_string())
+}
+
+#[actix_rt::test]
+async fn stale_etag_is_refused() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    let (task, _code) = index.create(None).await;
+    index.wait_task(task.uid()).await;
+    let app = server.init_web_app().await;
+
+    // an operator reads the settings
+    let (_response, code, stale) =
+        call(&app, test::TestRequest::get().uri("/indexes/test/settings")).await;
+    snapshot!(code, @"200 OK");
+    let stale = stale.unwrap();
+
+    // the sub-routes return the ETag of all the settings
+    let (_response, code, etag) =
+        call(&app, test::TestRequest::get().uri("/indexes/test/settings/ranking-rules")).await;
+    snapshot!(code, @"200 OK");
+    assert_eq!(etag.as_ref(), Some(&stale));
+
+    // another operator changes them in the meantime, without `If-Match` nothing is checked
+    let (task, code) = index.update_settings(crate::json!({ "rankingRules": ["words"] })).await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(task.uid()).await;
+
+    // the change of the first operator would clobber the one of the second
+    let (response, code, _etag) =
+        call(&app, patch_settings(json!({ "searchableAttributes": ["title"] }), &stale)).await;
+    snapshot!(code, @"412 Precondition Failed");
+    snapshot!(response["code"], @r###""settings_version_mismatch""###);
+    let (response, code, _etag) = call(
+        &app,
+        test::TestRequest::delete()
+            .uri("/indexes/test/settings/ranking-rules")
+            .insert_header((IF_MATCH, stale.as_str())),
+    )
+    .await;
+    snapshot!(code, @"412 Precondition Failed");
+    snapshot!(response["code"], @r###""settings_version_mismatch""###);
+
+    // once the settings are read again, the change is accepted
+    let (response, code, fresh) =
+        call(&app, test::TestRequest::get().uri("/indexes/test/settings")).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["rankingRules"], @r###"["words"]"###);
+    let fresh = fresh.unwrap();
+    assert_ne!(fresh, stale);
+
+    let (response, code, _etag) =
+        call(&app, patch_settings(json!({ "searchableAttributes": ["title"] }), &fresh)).await;
+    snapshot!(code, @"202 Accepted");
+    let response = index.wait_task(response["taskUid"].as_u64().unwrap()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+
+    // `*` matches any version of the settings
+    let (_response, code, _etag) =
+        call(&app, patch_settings(json!({ "searchableAttributes": ["*"] }), "*")).await;
+    snapshot!(code, @"202 Accepted");
+}
@@ -36,6 +36,16 @@ static DEFAULT_SETTINGS_VALUES: Lazy<HashMap<&'static str, Value>> = Lazy::new(|
         }),
     );
     map.insert("search_cutoff_ms", json!(null));
+    map.insert("search_defaults", json!(null));
+    map.insert("min_numeric_prefix_length", json!(4));
+    map.insert("allow_underscore_fields", json!(false));
+    map.insert("localized_attributes", json!(null));
+    map.insert("number_normalization", json!(false));
+    map.insert("phrase_across_fields", json!(false));
+    map.insert("exact_attributes", json!([]));
+    map.insert("decompounding_locales", json!([]));
+    map.insert("facet_types", json!({}));
+    map.insert("stemming", json!({ "locales": [] }));
     map
 });
 
@@ -55,7 +65,7 @@ async fn get_settings() {
     let (response, code) = index.settings().await;
     assert_eq!(code, 200);
     let settings = response.as_object().unwrap();
-    assert_eq!(settings.keys().len(), 16);
+    assert_eq!(settings.keys().len(), 26);
     assert_eq!(settings["displayedAttributes"], json!(["*"]));
     assert_eq!(settings["searchableAttributes"], json!(["*"]));
     assert_eq!(settings["filterableAttributes"], json!([]));
@@ -86,6 +96,145 @@ async fn get_settings() {
     );
     assert_eq!(settings["proximityPrecision"], json!("byWord"));
     assert_eq!(settings["searchCutoffMs"], json!(null));
+    assert_eq!(settings["searchDefaults"], json!(null));
+    assert_eq!(settings["minNumericPrefixLength"], json!(4));
+    assert_eq!(settings["allowUnderscoreFields"], json!(false));
+    assert_eq!(settings["localizedAttributes"], json!(null));
+    assert_eq!(settings["numberNormalization"], json!(false));
+    assert_eq!(settings["phraseAcrossFields"], json!(false));
+    assert_eq!(settings["exactAttributes"], json!([]));
+    assert_eq!(settings["decompoundingLocales"], json!([]));
+    assert_eq!(settings["facetTypes"], json!({}));
+    assert_eq!(settings["stemming"], json!({ "locales": [] }));
+}
+
+#[actix_rt::test]
+async fn get_canonical_settings() {
+    let first_server = Server::new().await;
+    let index = first_server.index("test");
+    let (response, _code) = index
+        .update_settings(json!({
+            "filterableAttributes": ["genre", "color"],
+            "sortableAttributes": ["price", "date"],
+            "rankingRules": ["sort", "words", "typo", "proximity", "attribute", "exactness"],
+            "searchableAttributes": ["title", "description"],
+            "synonyms": { "wolverine": ["xmen", "logan"], "logan": ["wolverine"] },
+            "searchDefaults": { "sort": ["price:asc"], "filter": "color = blue" },
+        }))
+        .await;
+    index.wait_task(response.uid()).await;
+
+    let second_server = Server::new().await;
+    let index = second_server.index("test");
+    let (response, _code) = index
+        .update_settings(json!({
+            "searchDefaults": { "filter": "color = blue", "sort": ["price:asc"] },
+            "synonyms": { "logan": ["wolverine"], "wolverine": ["logan", "xmen"] },
+            "searchableAttributes": ["title", "description"],
+            "rankingRules": ["sort", "words", "typo", "proximity", "attribute", "exactness"],
+            "sortableAttributes": ["date", "price"],
+            "filterableAttributes": ["color", "genre"],
+        }))
+        .await;
+    index.wait_task(response.uid()).await;
+
+    let (first, code) = first_server.service.get("/indexes/test/settings?canonical=true").await;
+    assert_eq!(code, 200, "{}", first);
+    let (second, code) = second_server.service.get("/indexes/test/settings?canonical=true").await;
+    assert_eq!(code, 200, "{}", second);
+    assert_eq!(first.to_string(), second.to_string());
+
+    meili_snap::snapshot!(meili_snap::json_string!(first), @r###"
+    {
+      "displayedAttributes": [
+        "*"
+      ],
+      "searchableAttributes": [
+        "title",
+        "description"
+      ],
+      "filterableAttributes": [
+        "color",
+        "genre"
+      ],
+      "sortableAttributes": [
+        "date",
+        "price"
+      ],
+      "rankingRules": [
+        "sort",
+        "words",
+        "typo",
+        "proximity",
+        "attribute",
+        "exactness"
+      ],
+      "stopWords": [],
+      "nonSeparatorTokens": [],
+      "separatorTokens": [],
+      "dictionary": [],
+      "synonyms": {
+        "logan": [
+          "wolverine"
+        ],
+        "wolverine": [
+          "logan",
+          "xmen"
+        ]
+      },
+      "distinctAttribute": null,
+      "proximityPrecision": "byWord",
+      "typoTolerance": {
+        "enabled": true,
+        "minWordSizeForTypos": {
+          "oneTypo": 5,
+          "twoTypos": 9
+        },
+        "disableOnWords": [],
+        "disableOnAttributes": []
+      },
+      "faceting": {
+        "maxValuesPerFacet": 100,
+        "sortFacetValuesBy": {
+          "*": "alpha"
+        }
+      },
+      "pagination": {
+        "maxTotalHits": 1000
+      },
+      "embedders": {},
+      "searchCutoffMs": null,
+      "searchDefaults": {
+        "filter": "color = blue",
+        "sort": [
+          "price:asc"
+        ]
+      },
+      "minNumericPrefixLength": 4,
+      "allowUnderscoreFields": false,
+      "localizedAttributes": null,
+      "numberNormalization": false,
+      "phraseAcrossFields": false,
+      "exactAttributes": [],
+      "decompoundingLocales": [],
+      "facetTypes": {},
+      "stemming": {
+        "locales": []
+      },
+      "presets": {}
+    }
+    "###);
+
+    let (response, code) = first_server.service.get("/indexes/test/settings?canonical=yes").await;
+    meili_snap::snapshot!(code, @"400 Bad Request");
+    meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
+    {
+      "message": "Invalid value in parameter `canonical`: could not parse `yes` as a boolean, expected either `true` or `false`",
+      "code": "invalid_settings_canonical",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_canonical"
+    }
+    "###);
 }
 
 #[actix_rt::test]
@@ -99,7 +248,13 @@ async fn secrets_are_hidden_in_settings() {
       "vectorStore": true,
       "metrics": false,
       "logsRoute": false,
-      "exportPuffinReports": false
+      "exportPuffinReports": false,
+      "debugRoutes": false,
+      "relevanceCheck": false,
+      "wordFrequency": false,
+      "documentTasks": false,
+      "recentSearches": false,
+      "rankingRulesOverride": false
     }
     "###);
 
@@ -199,7 +354,19 @@ async fn secrets_are_hidden_in_settings() {
           "inputType": "text"
         }
       },
-      "searchCutoffMs": null
+      "searchCutoffMs": null,
+      "searchDefaults": null,
+      "minNumericPrefixLength": 4,
+      "allowUnderscoreFields": false,
+      "localizedAttributes": null,
+      "numberNormalization": false,
+      "phraseAcrossFields": false,
+      "exactAttributes": [],
+      "decompoundingLocales": [],
+      "facetTypes": {},
+      "stemming": {
+        "locales": []
+      }
     }
     "###);
 
@@ -327,7 +494,7 @@ async fn error_update_setting_unexisting_index_invalid_uid() {
     meili_snap::snapshot!(code, @"400 Bad Request");
     meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
     {
-      "message": "`test##!  ` is not a valid index uid. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_).",
+      "message": "`test##!  ` is not a valid index uid: the character `#` at position 4 is not allowed. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_), of at most 400 bytes. Try `test_____` instead.",
       "code": "invalid_index_uid",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#invalid_index_uid"
@@ -418,7 +585,17 @@ test_setting_routes!(
     synonyms put,
     pagination patch,
     faceting patch,
-    search_cutoff_ms put
+    search_cutoff_ms put,
+    search_defaults put,
+    min_numeric_prefix_length put,
+    allow_underscore_fields put,
+    localized_attributes put,
+    number_normalization put,
+    phrase_across_fields put,
+    exact_attributes put,
+    decompounding_locales put,
+    facet_types put,
+    stemming patch
 );
 
 #[actix_rt::test]
@@ -439,6 +616,46 @@ async fn error_set_invalid_ranking_rules() {
     "###);
 }
 
+#[actix_rt::test]
+async fn set_ranking_rules_with_max_buckets() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index
+        .update_settings(json!({
+            "rankingRules": ["words", { "rule": "proximity", "maxBuckets": 10 }, "typo"]
+        }))
+        .await;
+    meili_snap::snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (response, code) = index.settings().await;
+    meili_snap::snapshot!(code, @"200 OK");
+    meili_snap::snapshot!(meili_snap::json_string!(response["rankingRules"]), @r###"
+    [
+      "words",
+      {
+        "rule": "proximity",
+        "maxBuckets": 10
+      },
+      "typo"
+    ]
+    "###);
+
+    let (response, code) = index
+        .update_settings(json!({ "rankingRules": [{ "rule": "proximity", "maxBuckets": 0 }] }))
+        .await;
+    meili_snap::snapshot!(code, @"400 Bad Request");
+    meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.rankingRules[0]`: the maximum number of buckets of the `proximity` ranking rule must be greater than 0",
+      "code": "invalid_settings_ranking_rules",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_settings_ranking_rules"
+    }
+    "###);
+}
+
 #[actix_rt::test]
 async fn set_and_reset_distinct_attribute_with_dedicated_route() {
     let server = Server::new().await;
@@ -1,5 +1,8 @@
+mod attributes_limits;
 mod distinct;
 mod errors;
+mod etag;
 mod get_settings;
 mod proximity_settings;
+mod search_defaults;
 mod tokenizer_customization;
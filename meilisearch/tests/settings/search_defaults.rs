@@ -0,0 +1,53 @@
+use meili_snap::{json_string, snapshot};
+
+use crate::common::Server;
+use crate::json;
+
+#[actix_rt::test]
+async fn removing_attributes_used_by_search_defaults_warns() {
+    let server = Server::new().await;
+    let index = server.index("test");
+
+    let (response, code) = index
+        .update_settings(json!({
+            "sortableAttributes": ["price"],
+            "filterableAttributes": ["color"],
+            "searchDefaults": { "sort": ["price:asc"], "filter": "color = blue" },
+        }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    index.wait_task(response.uid()).await;
+
+    let (response, _code) = index.update_settings(json!({ "sortableAttributes": [] })).await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "sortableAttributes": [],
+      "warnings": [
+        "The `sort` of the `searchDefaults` references `price`, which is no longer a sortable attribute."
+      ]
+    }
+    "###);
+
+    let (response, _code) = index.update_settings(json!({ "filterableAttributes": [] })).await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(response["status"], @r###""succeeded""###);
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "filterableAttributes": [],
+      "warnings": [
+        "The `filter` of the `searchDefaults` references `color`, which is no longer a filterable attribute."
+      ]
+    }
+    "###);
+
+    // the attributes are already gone, there is nothing left to report
+    let (response, _code) = index.update_settings(json!({ "sortableAttributes": [] })).await;
+    let response = index.wait_task(response.uid()).await;
+    snapshot!(json_string!(response["details"]), @r###"
+    {
+      "sortableAttributes": []
+    }
+    "###);
+}
@@ -184,3 +184,314 @@ async fn perform_on_demand_snapshot() {
         server.index("doggo").settings(),
     );
 }
+
+#[actix_rt::test]
+async fn restore_index_from_snapshot() {
+    let temp = tempfile::tempdir().unwrap();
+    let snapshot_dir = tempfile::tempdir().unwrap();
+
+    let options =
+        Opt { snapshot_dir: snapshot_dir.path().to_owned(), ..default_settings(temp.path()) };
+
+    let server = Server::new_with_options(options).await.unwrap();
+
+    let catto = server.index("catto");
+    catto
+        .add_documents(json!([{ "id": 1, "name": "kefir" }, { "id": 2, "name": "intel" }]), None)
+        .await;
+    let doggo = server.index("doggo");
+    let (task, _) = doggo.add_documents(json!([{ "id": 1, "name": "bork" }]), None).await;
+    doggo.wait_task(task.uid()).await;
+
+    let (task, code) = server.create_snapshot().await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(task.uid()).await;
+
+    let (task, _) = catto.delete().await;
+    catto.wait_task(task.uid()).await;
+    let (_, code) = catto.get().await;
+    snapshot!(code, @"404 Not Found");
+
+    let (task, code) = server
+        .service
+        .post("/indexes/catto/restore", json!({ "snapshotPath": "db.snapshot" }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let task = catto.wait_task(task.uid()).await;
+    snapshot!(json_string!(task, { ".details.snapshotPath" => "[path]", ".enqueuedAt" => "[date]", ".startedAt" => "[date]", ".finishedAt" => "[date]", ".duration" => "[duration]" }), @r###"
+    {
+      "uid": 4,
+      "indexUid": "catto",
+      "status": "succeeded",
+      "type": "indexRestore",
+      "canceledBy": null,
+      "details": {
+        "snapshotPath": "[path]",
+        "snapshotIndexUid": "catto",
+        "restoredDocuments": 2
+      },
+      "error": null,
+      "duration": "[duration]",
+      "enqueuedAt": "[date]",
+      "startedAt": "[date]",
+      "finishedAt": "[date]"
+    }
+    "###);
+
+    let (response, code) = catto.search_post(json!({ "q": "kefir" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1,
+        "name": "kefir"
+      }
+    ]
+    "###);
+
+    // an index of the snapshot can be restored under another uid, next to the current one
+    let (task, code) = server
+        .service
+        .post(
+            "/indexes/doggo_backup/restore",
+            json!({ "snapshotPath": "db.snapshot", "snapshotIndexUid": "doggo" }),
+        )
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let task = server.wait_task(task.uid()).await;
+    snapshot!(task["status"], @r###""succeeded""###);
+    let (response, _) = server.index("doggo_backup").search_post(json!({ "q": "bork" })).await;
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1,
+        "name": "bork"
+      }
+    ]
+    "###);
+    let (response, _) = doggo.search_post(json!({ "q": "bork" })).await;
+    snapshot!(response["estimatedTotalHits"], @"1");
+
+    // the index must exist in the snapshot
+    let (task, _) = server
+        .service
+        .post("/indexes/whalo/restore", json!({ "snapshotPath": "db.snapshot" }))
+        .await;
+    let task = server.wait_task(task.uid()).await;
+    snapshot!(task["error"]["code"], @r###""index_not_found""###);
+}
+
+#[actix_rt::test]
+async fn failed_restore_keeps_the_current_index() {
+    let temp = tempfile::tempdir().unwrap();
+    let snapshot_dir = tempfile::tempdir().unwrap();
+
+    let options =
+        Opt { snapshot_dir: snapshot_dir.path().to_owned(), ..default_settings(temp.path()) };
+
+    let server = Server::new_with_options(options).await.unwrap();
+
+    let catto = server.index("catto");
+    let (task, _) = catto
+        .add_documents(json!([{ "id": 1, "name": "kefir" }, { "id": 2, "name": "intel" }]), None)
+        .await;
+    catto.wait_task(task.uid()).await;
+
+    let (task, code) = server.create_snapshot().await;
+    snapshot!(code, @"202 Accepted");
+    server.wait_task(task.uid()).await;
+
+    // the environments of the indexes of the snapshot are not LMDB files anymore
+    let extracted = tempfile::tempdir().unwrap();
+    meilisearch_types::compression::from_tar_gz(
+        snapshot_dir.path().join("db.snapshot"),
+        &extracted,
+    )
+    .unwrap();
+    for entry in std::fs::read_dir(extracted.path().join("indexes")).unwrap() {
+        std::fs::write(entry.unwrap().path().join("data.mdb"), b"not an index").unwrap();
+    }
+    meilisearch_types::compression::to_tar_gz(
+        &extracted,
+        snapshot_dir.path().join("corrupted.snapshot"),
+    )
+    .unwrap();
+
+    let (task, code) = server
+        .service
+        .post("/indexes/catto/restore", json!({ "snapshotPath": "corrupted.snapshot" }))
+        .await;
+    snapshot!(code, @"202 Accepted");
+    let task = catto.wait_task(task.uid()).await;
+    snapshot!(task["status"], @r###""failed""###);
+
+    // the current index is left untouched
+    let (response, code) = catto.search_post(json!({ "q": "kefir" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1,
+        "name": "kefir"
+      }
+    ]
+    "###);
+    let (response, _) = catto.stats().await;
+    snapshot!(response["numberOfDocuments"], @"2");
+}
+
+#[actix_rt::test]
+async fn restore_index_outside_of_the_snapshot_dir() {
+    let temp = tempfile::tempdir().unwrap();
+    let snapshot_dir = tempfile::tempdir().unwrap();
+
+    let options =
+        Opt { snapshot_dir: snapshot_dir.path().to_owned(), ..default_settings(temp.path()) };
+
+    let server = Server::new_with_options(options).await.unwrap();
+
+    let outside = tempfile::NamedTempFile::new_in(temp.path()).unwrap();
+    let (response, code) = server
+        .service
+        .post(
+            "/indexes/catto/restore",
+            json!({ "snapshotPath": outside.path().display().to_string() }),
+        )
+        .await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response, { ".message" => "[message]" }), @r###"
+    {
+      "message": "[message]",
+      "code": "invalid_snapshot_path",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_snapshot_path"
+    }
+    "###);
+
+    let (response, code) =
+        server.service.post("/indexes/catto/restore", json!({ "snapshotPath": "../db" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""invalid_snapshot_path""###);
+
+    let (response, code) = server.service.post("/indexes/catto/restore", json!({})).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(response["code"], @r###""missing_snapshot_path""###);
+}
+
+#[actix_rt::test]
+async fn replica_imports_the_snapshots_of_its_primary() {
+    let temp = tempfile::tempdir().unwrap();
+    let snapshot_dir = tempfile::tempdir().unwrap();
+    let options =
+        Opt { snapshot_dir: snapshot_dir.path().to_owned(), ..default_settings(temp.path()) };
+    let primary = Server::new_with_options(options).await.unwrap();
+    let primary_url = primary.listen();
+
+    let replica_temp = tempfile::tempdir().unwrap();
+    let options = Opt {
+        experimental_replica_of: Some(primary_url.parse().unwrap()),
+        ..default_settings(replica_temp.path())
+    };
+    let replica = Server::new_with_options(options).await.unwrap();
+
+    let (task, _) = primary
+        .index("catto")
+        .add_documents(json!([{ "id": 1, "name": "kefir" }, { "id": 2, "name": "intel" }]), None)
+        .await;
+    primary.wait_task(task.uid()).await;
+    let (task, _) = primary.index("doggo").create(None).await;
+    primary.wait_task(task.uid()).await;
+
+    // the replica is read-only
+    let (response, code) =
+        replica.index("catto").add_documents(json!([{ "id": 3, "name": "bork" }]), None).await;
+    snapshot!(code, @"403 Forbidden");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "This instance is a read-only replica, `documentAdditionOrUpdate` tasks cannot be enqueued on it.",
+      "code": "read_only_replica",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#read_only_replica"
+    }
+    "###);
+    let (stats, _) = replica.stats().await;
+    snapshot!(json_string!(stats["replication"]), @r###"
+    {
+      "lastSuccessfulSync": null,
+      "lagSeconds": null
+    }
+    "###);
+
+    let (task, code) = replica.service.post("/snapshots/import", json!(null)).await;
+    snapshot!(code, @"202 Accepted");
+    let task = replica.wait_task(task.uid()).await;
+    snapshot!(json_string!(task, { ".enqueuedAt" => "[date]", ".startedAt" => "[date]", ".finishedAt" => "[date]", ".duration" => "[duration]" }), @r###"
+    {
+      "uid": 0,
+      "indexUid": null,
+      "status": "succeeded",
+      "type": "snapshotImportIncremental",
+      "canceledBy": null,
+      "details": {
+        "importedIndexes": 2
+      },
+      "error": null,
+      "duration": "[duration]",
+      "enqueuedAt": "[date]",
+      "startedAt": "[date]",
+      "finishedAt": "[date]"
+    }
+    "###);
+
+    let (response, code) = replica.index("catto").search_post(json!({ "q": "kefir" })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(json_string!(response["hits"]), @r###"
+    [
+      {
+        "id": 1,
+        "name": "kefir"
+      }
+    ]
+    "###);
+    let (stats, _) = replica.stats().await;
+    snapshot!(json_string!(stats["replication"], { ".lastSuccessfulSync" => "[date]", ".lagSeconds" => "[lag]" }), @r###"
+    {
+      "lastSuccessfulSync": "[date]",
+      "lagSeconds": "[lag]"
+    }
+    "###);
+    snapshot!(json_string!(stats["indexes"]["catto"]["numberOfDocuments"]), @"2");
+
+    // the next sync brings the deletions of the primary too
+    let (task, _) = primary.index("doggo").delete().await;
+    primary.wait_task(task.uid()).await;
+    let (task, _) = primary.index("catto").delete_document(1).await;
+    primary.wait_task(task.uid()).await;
+
+    let (task, _) = replica.service.post("/snapshots/import", json!(null)).await;
+    let task = replica.wait_task(task.uid()).await;
+    snapshot!(task["details"]["importedIndexes"], @"1");
+    let (_, code) = replica.index("doggo").get().await;
+    snapshot!(code, @"404 Not Found");
+    let (response, _) = replica.index("catto").search_post(json!({ "q": "kefir" })).await;
+    snapshot!(response["estimatedTotalHits"], @"0");
+}
+
+#[actix_rt::test]
+async fn import_snapshot_without_primary() {
+    let server = Server::new().await;
+
+    let (response, code) = server.service.post("/snapshots/import", json!(null)).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "This instance is not a replica, there is no primary instance to import a snapshot from.",
+      "code": "replication_not_configured",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#replication_not_configured"
+    }
+    "###);
+
+    let (stats, _) = server.stats().await;
+    snapshot!(stats["replication"], @"null");
+}
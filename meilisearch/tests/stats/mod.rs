@@ -1,3 +1,4 @@
+use meili_snap::{json_string, snapshot};
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
 
@@ -13,6 +14,26 @@ async fn get_settings_unexisting_index() {
     assert!(version.get("commitSha").is_some());
     assert!(version.get("commitDate").is_some());
     assert!(version.get("pkgVersion").is_some());
+    assert!(version.get("heedVersion").is_some());
+    assert!(version.get("lmdbMasterSysVersion").is_some());
+    assert_eq!(response["experimentalFeatures"], json!([]));
+}
+
+#[actix_rt::test]
+async fn version_reports_the_enabled_experimental_features() {
+    let server = Server::new().await;
+
+    let (_, code) = server.set_features(json!({ "vectorStore": true, "metrics": true })).await;
+    assert_eq!(code, 200);
+    let (response, code) = server.version().await;
+    assert_eq!(code, 200);
+    assert_eq!(response["experimentalFeatures"], json!(["vectorStore", "metrics"]));
+
+    let (_, code) = server.set_features(json!({ "vectorStore": false })).await;
+    assert_eq!(code, 200);
+    let (response, code) = server.version().await;
+    assert_eq!(code, 200);
+    assert_eq!(response["experimentalFeatures"], json!(["metrics"]));
 }
 
 #[actix_rt::test]
@@ -74,3 +95,31 @@ async fn stats() {
     assert_eq!(response["indexes"]["test"]["fieldDistribution"]["name"], 1);
     assert_eq!(response["indexes"]["test"]["fieldDistribution"]["age"], 1);
 }
+
+#[actix_rt::test]
+async fn stats_task_queue() {
+    let server = Server::new().await;
+
+    let (response, code) = server.stats().await;
+    assert_eq!(code, 200);
+    snapshot!(json_string!(response["taskQueue"]), @r###"
+    {
+      "oldestEnqueuedTaskAgeSeconds": null,
+      "finishedTasksPerMinute": 0,
+      "averageBatchDurationSeconds": null,
+      "tasksDeletedByRetention": 0,
+      "updateFilesDeletedByRetention": 0
+    }
+    "###);
+
+    let index = server.index("test");
+    let (task, code) = index.create(Some("id")).await;
+    assert_eq!(code, 202);
+    index.wait_task(task.uid()).await;
+
+    let (response, code) = server.stats().await;
+    assert_eq!(code, 200);
+    assert_eq!(response["taskQueue"]["oldestEnqueuedTaskAgeSeconds"], json!(null));
+    assert_eq!(response["taskQueue"]["finishedTasksPerMinute"], 1);
+    assert!(response["taskQueue"]["averageBatchDurationSeconds"].as_f64().is_some());
+}
@@ -92,3 +92,20 @@ async fn swap_indexes_bad_indexes() {
     }
     "###);
 }
+
+#[actix_rt::test]
+async fn swap_indexes_bad_index_uid() {
+    let server = Server::new().await;
+
+    let (response, code) =
+        server.index_swap(json!([{ "indexes": ["doggo", "the good doggo"]}])).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `[0].indexes[1]`: `the good doggo` is not a valid index uid: the character ` ` at position 3 is not allowed. Index uid can be an integer or a string containing only alphanumeric characters, hyphens (-) and underscores (_), of at most 400 bytes. Try `the_good_doggo` instead.",
+      "code": "invalid_swap_indexes",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_swap_indexes"
+    }
+    "###);
+}
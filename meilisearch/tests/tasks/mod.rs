@@ -2,9 +2,11 @@ mod errors;
 mod webhook;
 
 use meili_snap::insta::assert_json_snapshot;
+use meilisearch::Opt;
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
 
+use crate::common::server::default_settings;
 use crate::common::Server;
 use crate::json;
 
@@ -47,6 +49,56 @@ async fn get_task_status() {
     // TODO check response format, as per #48
 }
 
+#[actix_rt::test]
+async fn wait_task_finished_before_timeout() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    index.create(None).await;
+    index.add_documents(json!([{ "id": 1, "content": "foobar" }]), None).await;
+
+    let (response, code) = server.service.get("/tasks/1/wait?timeoutMs=50000").await;
+    assert_eq!(code, 200, "{response}");
+    assert_eq!(response["uid"], 1);
+    assert_eq!(response["status"], "succeeded");
+    assert_eq!(response["timedOut"], false);
+}
+
+#[actix_rt::test]
+async fn wait_task_already_finished() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    index.create(None).await;
+    index.wait_task(0).await;
+
+    let (response, code) = server.service.get("/tasks/0/wait?timeoutMs=0").await;
+    assert_eq!(code, 200, "{response}");
+    assert_eq!(response["status"], "succeeded");
+    assert_eq!(response["timedOut"], false);
+}
+
+#[actix_rt::test]
+async fn wait_task_timeout_expired() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    index.create(None).await;
+
+    // the task may or may not be processed already, but a task that is not finished
+    // must always be reported as timed out
+    let (response, code) = server.service.get("/tasks/0/wait?timeoutMs=0").await;
+    assert_eq!(code, 200, "{response}");
+    let finished = response["status"] == "succeeded" || response["status"] == "failed";
+    assert_eq!(response["timedOut"], !finished, "{response}");
+}
+
+#[actix_rt::test]
+async fn wait_unexisting_task() {
+    let server = Server::new().await;
+
+    let (response, code) = server.service.get("/tasks/1/wait").await;
+    assert_eq!(code, 404, "{response}");
+    assert_eq!(response["code"], "task_not_found");
+}
+
 #[actix_rt::test]
 async fn list_tasks() {
     let server = Server::new().await;
@@ -106,6 +158,37 @@ async fn list_tasks_with_star_filters() {
     assert_eq!(response["results"].as_array().unwrap().len(), 2);
 }
 
+#[actix_rt::test]
+async fn list_tasks_pagination_with_concurrent_writes() {
+    let server = Server::new().await;
+    let index = server.index("test");
+    for id in 0..100 {
+        index.add_documents(json!([{ "id": id }]), None).await;
+    }
+
+    let (mut response, code) = server.tasks_filter("limit=10").await;
+    assert_eq!(code, 200, "{}", response);
+
+    let mut uids = Vec::new();
+    loop {
+        let results = response["results"].as_array().unwrap();
+        uids.extend(results.iter().map(|task| task["uid"].as_u64().unwrap()));
+
+        // enqueue a new task between each page
+        index.add_documents(json!([{ "id": "new" }]), None).await;
+
+        let Some(next) = response["next"].as_u64() else { break };
+        let (page, code) = server.tasks_filter(&format!("from={next}&limit=10")).await;
+        assert_eq!(code, 200, "{}", page);
+        // `from` is inclusive
+        assert_eq!(page["from"], json!(next));
+        response = page;
+    }
+
+    // no duplicates and no gaps among the tasks enqueued before the pagination started
+    assert_eq!(uids, (0..100).rev().collect::<Vec<u64>>());
+}
+
 #[actix_rt::test]
 async fn list_tasks_status_filtered() {
     let server = Server::new().await;
@@ -200,7 +283,7 @@ async fn get_task_filter_error() {
     assert_eq!(code, 400, "{}", response);
     meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
     {
-      "message": "Unknown parameter `lol`: expected one of `limit`, `from`, `uids`, `canceledBy`, `types`, `statuses`, `indexUids`, `afterEnqueuedAt`, `beforeEnqueuedAt`, `afterStartedAt`, `beforeStartedAt`, `afterFinishedAt`, `beforeFinishedAt`",
+      "message": "Unknown parameter `lol`: expected one of `limit`, `from`, `uids`, `canceledBy`, `types`, `statuses`, `indexUids`, `indexGroups`, `afterEnqueuedAt`, `beforeEnqueuedAt`, `afterStartedAt`, `beforeStartedAt`, `afterFinishedAt`, `beforeFinishedAt`",
       "code": "bad_request",
       "type": "invalid_request",
       "link": "https://docs.meilisearch.com/errors#bad_request"
@@ -528,7 +611,7 @@ async fn test_summarized_delete_documents_by_filter() {
         "originalFilter": "\"doggo = bernese\""
       },
       "error": {
-        "message": "Attribute `doggo` is not filterable. This index does not have configured filterable attributes.\n1:6 doggo = bernese",
+        "message": "Attribute `doggo` is not filterable. This index does not have configured filterable attributes.\n1:6 doggo = bernese\n    ^^^^^",
         "code": "invalid_document_filter",
         "type": "invalid_request",
         "link": "https://docs.meilisearch.com/errors#invalid_document_filter"
@@ -1098,3 +1181,30 @@ async fn test_summarized_dump_creation() {
     }
     "###);
 }
+
+#[actix_web::test]
+async fn test_too_many_enqueued_tasks() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(Opt {
+        experimental_max_enqueued_tasks: Some(0),
+        ..default_settings(dir.path())
+    })
+    .await
+    .unwrap();
+    let index = server.index("test");
+
+    let (response, code) = index.add_documents(json!([{ "id": 1 }]), None).await;
+    meili_snap::snapshot!(code, @"429 Too Many Requests");
+    meili_snap::snapshot!(meili_snap::json_string!(response), @r###"
+    {
+      "message": "Meilisearch cannot receive write operations because 0 tasks are enqueued, which reaches the limit of 0 enqueued tasks. Please retry in 1 seconds.",
+      "code": "too_many_enqueued_tasks",
+      "type": "system",
+      "link": "https://docs.meilisearch.com/errors#too_many_enqueued_tasks"
+    }
+    "###);
+
+    // the task deletions are never refused
+    let (_response, code) = server.delete_tasks("uids=0").await;
+    meili_snap::snapshot!(code, @"202 Accepted");
+}
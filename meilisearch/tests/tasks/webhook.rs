@@ -2,7 +2,9 @@
 //! post requests. The webhook handle starts a server and forwards all the
 //! received requests into a channel for you to handle.
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use actix_http::body::MessageBody;
 use actix_web::dev::{ServiceFactory, ServiceResponse};
@@ -13,7 +15,7 @@ use meilisearch::Opt;
 use tokio::sync::mpsc;
 use url::Url;
 
-use crate::common::{default_settings, Server};
+use crate::common::{default_settings, Server, Value};
 use crate::json;
 
 #[post("/")]
@@ -127,3 +129,343 @@ async fn test_basic_webhook() {
 
     server_handle.abort();
 }
+
+/// A webhook refusing the payloads until it is told to accept them.
+struct FlakyWebhook {
+    accept: AtomicBool,
+    /// The sequence number of every payload received, whether it was accepted, and its body.
+    sender: mpsc::UnboundedSender<(u64, bool, Vec<u8>)>,
+}
+
+#[post("/")]
+async fn flaky_forward_body(
+    req: HttpRequest,
+    webhook: Data<FlakyWebhook>,
+    body: Bytes,
+) -> HttpResponse {
+    let sequence = req.headers().get("x-meilisearch-webhook-sequence").unwrap();
+    let sequence = sequence.to_str().unwrap().parse().unwrap();
+    let accepted = webhook.accept.load(Ordering::Relaxed);
+    webhook.sender.send((sequence, accepted, body.to_vec())).unwrap();
+    if accepted {
+        HttpResponse::Ok().into()
+    } else {
+        HttpResponse::InternalServerError().into()
+    }
+}
+
+/// Returns the uids of the tasks contained in a webhook payload.
+fn payload_task_uids(payload: &[u8]) -> Vec<u64> {
+    let payload = std::str::from_utf8(payload).unwrap();
+    payload
+        .lines()
+        .map(|line| {
+            serde_json::from_str::<serde_json::Value>(line).unwrap()["uid"].as_u64().unwrap()
+        })
+        .collect()
+}
+
+/// Waits for the webhook status to report the expected lag.
+async fn wait_webhook_lag(server: &Server, lag: u64) -> Value {
+    for _ in 0..100 {
+        let (response, code) = server.service.get("/webhook").await;
+        assert_eq!(code, 200, "{response}");
+        if response["lag"] == lag {
+            return response;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("The webhook lag never reached {lag}");
+}
+
+/// Waits for the webhook status to report an error, starting from the given status.
+async fn wait_webhook_error(server: &Server, mut response: Value) -> Value {
+    for _ in 0..100 {
+        if !response["lastError"].is_null() {
+            return response;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        response = server.service.get("/webhook").await.0;
+    }
+    panic!("The webhook never reported an error: {response}");
+}
+
+#[actix_web::test]
+async fn test_webhook_delivers_in_order() {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let webhook = Data::new(FlakyWebhook { accept: AtomicBool::new(false), sender });
+    let app_webhook = webhook.clone();
+    let http_server = HttpServer::new(move || {
+        App::new().service(flaky_forward_body).app_data(app_webhook.clone())
+    })
+    .bind(("127.0.0.1", 0))
+    .unwrap();
+    let (ip, scheme) = http_server.addrs_with_scheme()[0];
+    let url = format!("{scheme}://{ip}/");
+    let server_handle = tokio::spawn(http_server.run());
+
+    let db_path = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(Opt {
+        task_webhook_url: Some(Url::parse(&url).unwrap()),
+        ..default_settings(db_path.path())
+    })
+    .await
+    .unwrap();
+
+    let (response, code) = server.service.get("/webhook").await;
+    snapshot!(code, @"200 OK");
+    assert_eq!(response["url"], url.as_str());
+    snapshot!(json_string!(response, { ".url" => "[url]" }), @r###"
+    {
+      "url": "[url]",
+      "lastAcknowledgedSequence": null,
+      "lag": 0,
+      "dropped": 0,
+      "lastError": null,
+      "lastErrorAt": null
+    }
+    "###);
+
+    let index = server.index("tamo");
+    let (first, _) = index.add_documents(json!({ "id": 0 }), None).await;
+
+    // the first delivery fails and the payload is kept
+    let (sequence, accepted, _) = receiver.recv().await.unwrap();
+    assert_eq!((sequence, accepted), (1, false));
+    let response = wait_webhook_lag(&server, 1).await;
+    let response = wait_webhook_error(&server, response).await;
+    snapshot!(json_string!(response, { ".url" => "[url]", ".lastError.message" => "[message]", ".lastErrorAt" => "[date]" }), @r###"
+    {
+      "url": "[url]",
+      "lastAcknowledgedSequence": null,
+      "lag": 1,
+      "dropped": 0,
+      "lastError": {
+        "message": "[message]",
+        "code": "webhook_delivery_failed",
+        "type": "system",
+        "link": "https://docs.meilisearch.com/errors#webhook_delivery_failed"
+      },
+      "lastErrorAt": "[date]"
+    }
+    "###);
+    snapshot!(response["lastError"]["message"].as_str().unwrap().replace(&url, "[url]"), @"The task webhook `[url]` did not receive the payload: it answered with the status 500.");
+
+    // the next payload is not sent before the first one is delivered
+    let (second, _) = index.add_documents(json!({ "id": 1 }), None).await;
+    index.wait_task(second.uid()).await;
+    wait_webhook_lag(&server, 2).await;
+    while let Ok((sequence, accepted, _)) = receiver.try_recv() {
+        assert_eq!((sequence, accepted), (1, false));
+    }
+
+    webhook.accept.store(true, Ordering::Relaxed);
+    let mut delivered = Vec::new();
+    while delivered.len() < 2 {
+        let (sequence, accepted, payload) = receiver.recv().await.unwrap();
+        if accepted {
+            delivered.push((sequence, payload_task_uids(&payload)));
+        }
+    }
+    assert_eq!(delivered, vec![(1, vec![first.uid()]), (2, vec![second.uid()])]);
+
+    let response = wait_webhook_lag(&server, 0).await;
+    snapshot!(json_string!(response, { ".url" => "[url]" }), @r###"
+    {
+      "url": "[url]",
+      "lastAcknowledgedSequence": 2,
+      "lag": 0,
+      "dropped": 0,
+      "lastError": null,
+      "lastErrorAt": null
+    }
+    "###);
+
+    server_handle.abort();
+}
+
+#[actix_web::test]
+async fn webhook_not_in_outbound_allowlist() {
+    let WebhookHandle { server_handle, url, mut receiver } = create_webhook_server().await;
+
+    let db_path = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(Opt {
+        task_webhook_url: Some(Url::parse(&url).unwrap()),
+        experimental_outbound_allowlist: vec!["hooks.example.com".to_string()],
+        ..default_settings(db_path.path())
+    })
+    .await
+    .unwrap();
+
+    let index = server.index("tamo");
+    let (task, _) = index.add_documents(json!({ "id": 0 }), None).await;
+    index.wait_task(task.uid()).await;
+
+    let response = wait_webhook_lag(&server, 1).await;
+    let response = wait_webhook_error(&server, response).await;
+    snapshot!(json_string!(response, { ".url" => "[url]", ".lastErrorAt" => "[date]" }), @r###"
+    {
+      "url": "[url]",
+      "lastAcknowledgedSequence": null,
+      "lag": 1,
+      "dropped": 0,
+      "lastError": {
+        "message": "The host `127.0.0.1` is not allowed by the outbound allow-list: `hooks.example.com`.",
+        "code": "outbound_host_not_allowed",
+        "type": "invalid_request",
+        "link": "https://docs.meilisearch.com/errors#outbound_host_not_allowed"
+      },
+      "lastErrorAt": "[date]"
+    }
+    "###);
+
+    // the payload was never sent
+    assert!(receiver.try_recv().is_err());
+
+    server_handle.abort();
+}
+
+#[actix_web::test]
+async fn webhook_on_private_network_in_outbound_allowlist() {
+    let WebhookHandle { server_handle, url, mut receiver } = create_webhook_server().await;
+
+    // the loopback address matches the allow-list, but the private addresses are denied by default
+    let db_path = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(Opt {
+        task_webhook_url: Some(Url::parse(&url).unwrap()),
+        experimental_outbound_allowlist: vec!["127.0.0.1".to_string()],
+        ..default_settings(db_path.path())
+    })
+    .await
+    .unwrap();
+
+    let index = server.index("tamo");
+    let (task, _) = index.add_documents(json!({ "id": 0 }), None).await;
+    index.wait_task(task.uid()).await;
+
+    let response = wait_webhook_lag(&server, 1).await;
+    let response = wait_webhook_error(&server, response).await;
+    snapshot!(response["lastError"]["message"], @r###""The host `127.0.0.1` is a local or private address, which is denied by the outbound allow-list: `127.0.0.1`.""###);
+    assert!(receiver.try_recv().is_err());
+
+    // once the private addresses are allowed, the payloads are delivered
+    let db_path = tempfile::tempdir().unwrap();
+    let server = Server::new_with_options(Opt {
+        task_webhook_url: Some(Url::parse(&url).unwrap()),
+        experimental_outbound_allowlist: vec!["127.0.0.1".to_string()],
+        experimental_outbound_allow_private_network: true,
+        ..default_settings(db_path.path())
+    })
+    .await
+    .unwrap();
+
+    let index = server.index("tamo");
+    let (task, _) = index.add_documents(json!({ "id": 0 }), None).await;
+    let payload = receiver.recv().await.unwrap();
+    assert_eq!(payload_task_uids(&payload), vec![task.uid()]);
+    let response = wait_webhook_lag(&server, 0).await;
+    assert!(response["lastError"].is_null(), "{response}");
+
+    server_handle.abort();
+}
+
+#[actix_web::test]
+async fn get_webhook_without_webhook() {
+    let server = Server::new().await;
+    let (response, code) = server.service.get("/webhook").await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(response, @r###"
+    {
+      "message": "No task webhook is configured on this instance.",
+      "code": "webhook_not_found",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#webhook_not_found"
+    }
+    "###);
+}
+
+#[actix_web::test]
+async fn webhook_configured_at_runtime() {
+    let WebhookHandle { server_handle, url, mut receiver } = create_webhook_server().await;
+    let server = Server::new().await;
+
+    let (response, code) = server
+        .service
+        .patch("/webhook", json!({ "url": url, "authorizationHeader": "Bearer doggo" }))
+        .await;
+    snapshot!(code, @"200 OK");
+    assert_eq!(response["url"], url.as_str());
+    snapshot!(response["lag"], @"0");
+
+    let index = server.index("tamo");
+    let (task, _) = index.create(None).await;
+    index.wait_task(task.uid()).await;
+
+    let payload = receiver.recv().await.unwrap();
+    let payload = String::from_utf8(payload).unwrap();
+    let tasks: Vec<serde_json::Value> =
+        payload.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    snapshot!(json_string!(tasks, { "[].duration" => "[duration]", "[].enqueuedAt" => "[date]", "[].startedAt" => "[date]", "[].finishedAt" => "[date]" }), @r###"
+    [
+      {
+        "uid": 0,
+        "indexUid": "tamo",
+        "status": "succeeded",
+        "type": "indexCreation",
+        "canceledBy": null,
+        "details": {
+          "primaryKey": null
+        },
+        "error": null,
+        "duration": "[duration]",
+        "enqueuedAt": "[date]",
+        "startedAt": "[date]",
+        "finishedAt": "[date]"
+      }
+    ]
+    "###);
+    wait_webhook_lag(&server, 0).await;
+
+    // without a URL the webhook is disabled
+    let (response, code) = server.service.patch("/webhook", json!({ "url": null })).await;
+    snapshot!(code, @"200 OK");
+    snapshot!(response["url"], @"null");
+    let (response, code) = server.service.get("/webhook").await;
+    snapshot!(code, @"404 Not Found");
+    snapshot!(response["code"], @r###""webhook_not_found""###);
+
+    let (task, _) = index.delete().await;
+    index.wait_task(task.uid()).await;
+    assert!(receiver.try_recv().is_err());
+
+    server_handle.abort();
+}
+
+#[actix_web::test]
+async fn patch_webhook_errors() {
+    let server = Server::new().await;
+
+    let (response, code) =
+        server.service.patch("/webhook", json!({ "url": "ftp://hooks.example.com" })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value at `.url`: `ftp://hooks.example.com` is not a valid webhook URL: the scheme `ftp` is not `http` nor `https`.",
+      "code": "invalid_webhook_url",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_webhook_url"
+    }
+    "###);
+
+    let (response, code) =
+        server.service.patch("/webhook", json!({ "authorizationHeader": 42 })).await;
+    snapshot!(code, @"400 Bad Request");
+    snapshot!(json_string!(response), @r###"
+    {
+      "message": "Invalid value type at `.authorizationHeader`: expected a string, but found a positive integer: `42`",
+      "code": "invalid_webhook_authorization_header",
+      "type": "invalid_request",
+      "link": "https://docs.meilisearch.com/errors#invalid_webhook_authorization_header"
+    }
+    "###);
+}
@@ -286,7 +286,7 @@ fn export_a_dump(
         // 4.1. Dump the documents
         for ret in index.all_documents(&rtxn)? {
             let (_id, doc) = ret?;
-            let document = obkv_to_json(&all_fields, &fields_ids_map, doc)?;
+            let document = obkv_to_json(&all_fields, &fields_ids_map, doc.reader())?;
             index_dumper.push_document(&document)?;
         }
 
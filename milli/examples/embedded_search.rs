@@ -0,0 +1,57 @@
+//! Indexes the documents of a JSON file and searches them, without the HTTP server.
+//!
+//! ```bash
+//! cargo run --example embedded_search -- <index directory> <documents.json> <query> [filter]
+//! ```
+//!
+//! The JSON file must contain an array of objects with an `id` field.
+
+use std::fs::File;
+use std::io::BufReader;
+
+use milli::heed::EnvOpenOptions;
+use milli::{Index, IndexerBuilder, Object, SearchBuilder};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let usage = "usage: embedded_search <index directory> <documents.json> <query> [filter]";
+    let index_path = args.next().ok_or(usage)?;
+    let documents_path = args.next().ok_or(usage)?;
+    let query = args.next().ok_or(usage)?;
+    let filter = args.next();
+
+    std::fs::create_dir_all(&index_path)?;
+    let mut options = EnvOpenOptions::new();
+    options.map_size(1024 * 1024 * 1024); // 1 GB
+    let index = Index::new(options, &index_path)?;
+
+    let documents: Vec<Object> =
+        serde_json::from_reader(BufReader::new(File::open(documents_path)?))?;
+
+    let mut wtxn = index.write_txn()?;
+    let indexed = IndexerBuilder::new(&index)
+        .primary_key("id")
+        .add_documents(documents)
+        .execute(&mut wtxn)?;
+    wtxn.commit()?;
+    println!("{indexed} documents indexed");
+
+    let rtxn = index.read_txn()?;
+    let mut search = SearchBuilder::new(&index);
+    search.query(query).limit(10);
+    if let Some(filter) = filter {
+        search.filter(filter);
+    }
+    let hits = search.execute(&rtxn)?;
+    println!("{} documents match", hits.candidates.len());
+
+    let documents = index.documents(&rtxn, hits.documents_ids.iter().copied())?;
+    let fields_ids_map = index.fields_ids_map(&rtxn)?;
+    let fields: Vec<_> = fields_ids_map.ids().collect();
+    for ((_, obkv), score) in documents.into_iter().zip(hits.scores) {
+        let document = milli::obkv_to_json(&fields, &fields_ids_map, obkv.reader())?;
+        println!("{score:.4} {}", serde_json::to_string(&document)?);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+//! The normalization of the string facet values.
+//!
+//! The values are normalized once when the documents are indexed and once more when they are
+//! compared to the value of a filter, both sides must then go through [`normalize_facet`] with
+//! the [`CaseFolding`] of the index so that a filter value matches the values it was indexed from.
+
+use charabia::normalizer::{CharNormalizer, CompatibilityDecompositionNormalizer};
+
+/// How the letters of the string facet values are folded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CaseFolding {
+    /// The letters are only lowercased.
+    ///
+    /// It is the folding of the indexes created before the full case folding, they keep it
+    /// until all their documents are cleared, which happens when they are reindexed.
+    Lowercase,
+    /// The letters are folded like the Unicode full case folding, in which `ß` is `ss`,
+    /// and the dotted and dotless `i` are both folded to `i`.
+    #[default]
+    Full,
+}
+
+/// Returns the normalized form of a string facet value, in which it is stored and compared.
+pub fn normalize_facet(original: &str, case_folding: CaseFolding) -> String {
+    let lowercased =
+        CompatibilityDecompositionNormalizer.normalize_str(original.trim()).to_lowercase();
+    match case_folding {
+        CaseFolding::Lowercase => lowercased,
+        CaseFolding::Full => fold_case(&lowercased),
+    }
+}
+
+/// Folds the lowercased and decomposed letters that the full case folding maps to other letters.
+///
+/// The compatibility decomposition already maps most of them, like the ligatures
+/// or the `ſ`, only the ones remaining after it are folded here.
+fn fold_case(lowercased: &str) -> String {
+    let mut folded = String::with_capacity(lowercased.len());
+    let mut chars = lowercased.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            'ß' => folded.push_str("ss"),
+            'ς' => folded.push('σ'),
+            // the iota subscript of the Greek vowels
+            '\u{345}' => folded.push('ι'),
+            'ı' => folded.push('i'),
+            // the capital dotted `İ` is lowercased to an `i` followed by a combining dot above
+            'i' => {
+                folded.push('i');
+                if chars.peek() == Some(&'\u{307}') {
+                    chars.next();
+                }
+            }
+            c => folded.push(c),
+        }
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_case_folding() {
+        let cases = [
+            ("été", "ÉTÉ"),
+            ("straße", "STRASSE"),
+            ("STRAẞE", "strasse"),
+            ("İstanbul", "istanbul"),
+            ("ıspanak", "ISPANAK"),
+            ("ΣΟΦΟΣ", "σοφοσ"),
+            ("ᾳ", "ΑΙ"),
+            ("ﬁn", "FIN"),
+        ];
+        for (value, filter) in cases {
+            assert_eq!(
+                normalize_facet(value, CaseFolding::Full),
+                normalize_facet(filter, CaseFolding::Full),
+                "{value} and {filter}"
+            );
+        }
+    }
+
+    #[test]
+    fn lowercase_folding() {
+        assert_eq!(
+            normalize_facet("été", CaseFolding::Lowercase),
+            normalize_facet("ÉTÉ", CaseFolding::Lowercase)
+        );
+        assert_ne!(
+            normalize_facet("straße", CaseFolding::Lowercase),
+            normalize_facet("STRASSE", CaseFolding::Lowercase)
+        );
+        assert_ne!(
+            normalize_facet("İstanbul", CaseFolding::Lowercase),
+            normalize_facet("istanbul", CaseFolding::Lowercase)
+        );
+    }
+}
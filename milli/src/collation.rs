@@ -0,0 +1,272 @@
+//! The alphabetical order in which the string facet values are sorted.
+//!
+//! The facet string databases are ordered by the bytes of the normalized values, which puts
+//! the accented letters after the `z`. The [`Collator`] computes, from a normalized value, a
+//! [`CollationKey`] that compares the values like a dictionary of the locale would:
+//! 1. by their letters without the diacritics,
+//! 2. then by their diacritics,
+//! 3. then by their bytes, so that two different values never compare equal.
+
+use deserr::Deserr;
+use serde::{Deserialize, Serialize};
+
+/// The locales whose alphabetical order can be used to sort the string facet values.
+///
+/// They are identified by their ISO 639-3 code.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Deserr,
+)]
+#[serde(rename_all = "lowercase")]
+#[deserr(rename_all = lowercase)]
+pub enum Locale {
+    Ara,
+    Ces,
+    Cmn,
+    Dan,
+    Deu,
+    Ell,
+    Eng,
+    Est,
+    Fin,
+    Fra,
+    Heb,
+    Hin,
+    Hrv,
+    Hun,
+    Isl,
+    Ita,
+    Jpn,
+    Kor,
+    Lav,
+    Lit,
+    Nld,
+    Nob,
+    Pol,
+    Por,
+    Ron,
+    Rus,
+    Slk,
+    Slv,
+    Spa,
+    Swe,
+    Tha,
+    Tur,
+    Ukr,
+    Vie,
+}
+
+/// A letter that a locale sorts as a distinct letter placed after `after`,
+/// instead of sorting it as its base letter with a diacritic.
+#[derive(Debug)]
+struct Tailoring {
+    base: char,
+    /// The combining mark following the base letter once the value is normalized, if any.
+    mark: Option<char>,
+    after: char,
+    /// The rank of the letter among the ones placed after the same letter, starting at 1.
+    rank: u32,
+}
+
+const fn tailoring(base: char, mark: Option<char>, after: char, rank: u32) -> Tailoring {
+    Tailoring { base, mark, after, rank }
+}
+
+const RING_ABOVE: char = '\u{30A}';
+const DIAERESIS: char = '\u{308}';
+const TILDE: char = '\u{303}';
+
+/// Å, Ä, and Ö come after Z.
+const SWEDISH: &[Tailoring] = &[
+    tailoring('a', Some(RING_ABOVE), 'z', 1),
+    tailoring('a', Some(DIAERESIS), 'z', 2),
+    tailoring('æ', None, 'z', 2),
+    tailoring('o', Some(DIAERESIS), 'z', 3),
+    tailoring('ø', None, 'z', 3),
+];
+
+/// Æ, Ø, and Å come after Z.
+const DANISH: &[Tailoring] = &[
+    tailoring('æ', None, 'z', 1),
+    tailoring('a', Some(DIAERESIS), 'z', 1),
+    tailoring('ø', None, 'z', 2),
+    tailoring('o', Some(DIAERESIS), 'z', 2),
+    tailoring('a', Some(RING_ABOVE), 'z', 3),
+];
+
+/// Ñ comes after N.
+const SPANISH: &[Tailoring] = &[tailoring('n', Some(TILDE), 'n', 1)];
+
+/// Compares the normalized string facet values in the alphabetical order of a locale.
+///
+/// The values are expected to be normalized with [`crate::normalize_facet`], so that
+/// the accented letters are decomposed into a base letter followed by combining marks.
+#[derive(Debug, Clone, Copy)]
+pub struct Collator {
+    tailorings: &'static [Tailoring],
+}
+
+impl Default for Collator {
+    /// The unicode collation order, shared by most locales.
+    fn default() -> Self {
+        Collator { tailorings: &[] }
+    }
+}
+
+impl Collator {
+    /// Returns the collator of the first locale with a specific alphabetical order,
+    /// or the unicode collation order if none has one.
+    pub fn for_locales(locales: &[Locale]) -> Self {
+        let tailorings = locales.iter().find_map(|locale| match locale {
+            Locale::Swe | Locale::Fin => Some(SWEDISH),
+            Locale::Dan | Locale::Nob => Some(DANISH),
+            Locale::Spa => Some(SPANISH),
+            _ => None,
+        });
+        Collator { tailorings: tailorings.unwrap_or_default() }
+    }
+
+    /// Computes the key of the normalized value, the values must be sorted by their keys.
+    pub fn key(&self, normalized: &str) -> CollationKey {
+        let mut primary = Vec::with_capacity(normalized.len());
+        let mut secondary = Vec::with_capacity(normalized.len());
+
+        let mut chars = normalized.chars().peekable();
+        while let Some(c) = chars.next() {
+            if is_combining_mark(c) {
+                // a mark without a base letter only matters as a diacritic
+                secondary.push(c as u32);
+                continue;
+            }
+            let mark = chars.peek().copied().filter(|&next| is_combining_mark(next));
+
+            let tailoring = self
+                .tailorings
+                .iter()
+                .find(|t| t.base == c && (t.mark.is_none() || t.mark == mark));
+            if let Some(tailoring) = tailoring {
+                if tailoring.mark.is_some() {
+                    chars.next();
+                }
+                // sorted after the letter it follows but before the next one
+                primary.push(primary_weight(tailoring.after) + tailoring.rank);
+                secondary.push(0);
+            } else {
+                let expansion = expand(c);
+                let diacritic = match expansion {
+                    Some(_) => c as u32,
+                    None => 0,
+                };
+                for base in expansion.unwrap_or(std::slice::from_ref(&c)) {
+                    primary.push(primary_weight(*base));
+                    secondary.push(diacritic);
+                }
+            }
+
+            // the combining marks following the letter are its diacritics
+            while let Some(mark) = chars.next_if(|&next| is_combining_mark(next)) {
+                if let Some(last) = secondary.last_mut() {
+                    *last = last.saturating_mul(0x1_0000).saturating_add(mark as u32);
+                }
+            }
+        }
+
+        CollationKey { primary, secondary, bytes: normalized.as_bytes().to_vec() }
+    }
+}
+
+/// The key by which a normalized string facet value is sorted, see [`Collator::key`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CollationKey {
+    primary: Vec<u32>,
+    secondary: Vec<u32>,
+    bytes: Vec<u8>,
+}
+
+impl CollationKey {
+    /// The normalized value this key was computed from.
+    pub fn value(&self) -> &str {
+        // the bytes come from a `&str`
+        std::str::from_utf8(&self.bytes).unwrap()
+    }
+}
+
+/// Leaves room between two letters for the letters a locale sorts after the first one.
+fn primary_weight(c: char) -> u32 {
+    (c as u32) << 2
+}
+
+/// The letters that are not decomposed by the normalization but sort like other letters.
+fn expand(c: char) -> Option<&'static [char]> {
+    let expansion: &'static [char] = match c {
+        'æ' => &['a', 'e'],
+        'œ' => &['o', 'e'],
+        'ß' => &['s', 's'],
+        'þ' => &['t', 'h'],
+        'ø' => &['o'],
+        'đ' | 'ð' => &['d'],
+        'ł' => &['l'],
+        'ı' => &['i'],
+        _ => return None,
+    };
+    Some(expansion)
+}
+
+/// The combining diacritical marks, that follow their base letter once decomposed.
+fn is_combining_mark(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}'
+        | '\u{1AB0}'..='\u{1AFF}'
+        | '\u{1DC0}'..='\u{1DFF}'
+        | '\u{20D0}'..='\u{20FF}'
+        | '\u{FE20}'..='\u{FE2F}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{normalize_facet, CaseFolding};
+
+    fn sort(collator: Collator, words: &[&str]) -> Vec<String> {
+        let mut keys: Vec<_> = words
+            .iter()
+            .map(|word| collator.key(&normalize_facet(word, CaseFolding::Full)))
+            .collect();
+        keys.sort();
+        keys.iter().map(|key| key.value().to_string()).collect()
+    }
+
+    #[test]
+    fn accented_letters_sort_like_their_base_letter() {
+        let sorted = sort(Collator::default(), &["eux", "été", "zèbre", "ete", "Écrire", "école"]);
+        let expected: Vec<_> = ["école", "écrire", "ete", "été", "eux", "zèbre"]
+            .iter()
+            .map(|w| normalize_facet(w, CaseFolding::Full))
+            .collect();
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn locales_with_their_own_letters() {
+        let words = ["zebra", "ängel", "ost", "öl", "apa"];
+
+        let sorted = sort(Collator::default(), &words);
+        let expected: Vec<_> = ["ängel", "apa", "öl", "ost", "zebra"]
+            .iter()
+            .map(|w| normalize_facet(w, CaseFolding::Full))
+            .collect();
+        assert_eq!(sorted, expected);
+
+        let sorted = sort(Collator::for_locales(&[Locale::Swe]), &words);
+        let expected: Vec<_> = ["apa", "ost", "zebra", "ängel", "öl"]
+            .iter()
+            .map(|w| normalize_facet(w, CaseFolding::Full))
+            .collect();
+        assert_eq!(sorted, expected);
+
+        let sorted = sort(Collator::for_locales(&[Locale::Spa]), &["ñu", "oso", "nube"]);
+        let expected: Vec<_> =
+            ["nube", "ñu", "oso"].iter().map(|w| normalize_facet(w, CaseFolding::Full)).collect();
+        assert_eq!(sorted, expected);
+    }
+}
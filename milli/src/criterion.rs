@@ -22,6 +22,35 @@ pub enum CriterionError {
 `{name}` can only be used for filtering at search time"
     )]
     ReservedNameForFilter { name: String },
+    #[error(
+        "`{name}` ranking rule is invalid. The random ranking rule must be written `random(seed)` or \
+`random(seed, bucketSize)`, where the seed is an integer or the name of an attribute, \
+and the bucket size is a positive integer."
+    )]
+    InvalidRandom { name: String },
+    #[error(
+        "`{name}` ranking rule is invalid. The geo ranking rule must be written `_geoPoint(latitude, longitude)` or \
+`_geoPoint(latitude, longitude, bucketSize)`, where the latitude is between -90 and 90, the longitude \
+between -180 and 180, and the bucket size is a positive integer."
+    )]
+    InvalidGeoPoint { name: String },
+    #[error(
+        "`sort` ranking rule at index {second} is repeating the `sort` ranking rule at index {first}. \
+The `sort` ranking rule can only be used once."
+    )]
+    RepeatedSort { first: usize, second: usize },
+    #[error(
+        "`{second_name}` ranking rule at index {second} is sorting the `{field}` attribute, \
+already sorted by the `{first_name}` ranking rule at index {first}. \
+An attribute can only be sorted by a single ranking rule."
+    )]
+    RepeatedField {
+        field: String,
+        first_name: String,
+        first: usize,
+        second_name: String,
+        second: usize,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -45,6 +74,36 @@ pub enum Criterion {
     Asc(String),
     /// Sorted by the decreasing value of the field specified.
     Desc(String),
+    /// Shuffled in buckets of `bucket_size` documents, in an order only depending on the seed.
+    /// The subsequent ranking rules sort the documents within each bucket.
+    Random { seed: RandomSeed, bucket_size: u32 },
+    /// Sorted by increasing distance to the point, in buckets of `bucket_size` documents.
+    /// The documents without a geo point are ranked last.
+    GeoPoint { point: LatLng, bucket_size: u32 },
+}
+
+/// The default number of documents in each bucket of the [`Criterion::GeoPoint`] ranking rule.
+pub const DEFAULT_GEO_POINT_BUCKET_SIZE: u32 = 1000;
+
+/// The target of the [`Criterion::GeoPoint`] ranking rule.
+///
+/// Its coordinates are in range once parsed, they are never NaN.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct LatLng {
+    pub lat: f64,
+    pub lng: f64,
+}
+
+impl Eq for LatLng {}
+
+/// The seed of the [`Criterion::Random`] ranking rule.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum RandomSeed {
+    /// All the documents are shuffled with the same seed.
+    Literal(u64),
+    /// Each document is shuffled with the value of this field, documents without
+    /// a value are ranked last.
+    Field(String),
 }
 
 impl Criterion {
@@ -52,9 +111,53 @@ impl Criterion {
     pub fn field_name(&self) -> Option<&str> {
         match self {
             Criterion::Asc(name) | Criterion::Desc(name) => Some(name),
+            Criterion::Random { seed: RandomSeed::Field(name), .. } => Some(name),
             _otherwise => None,
         }
     }
+
+    fn parse_random(text: &str) -> Result<Criterion, CriterionError> {
+        let invalid = || CriterionError::InvalidRandom { name: text.to_string() };
+        let args = text
+            .strip_prefix("random(")
+            .and_then(|args| args.strip_suffix(')'))
+            .ok_or_else(invalid)?;
+        let (seed, bucket_size) = match args.rsplit_once(',') {
+            Some((seed, bucket_size)) => {
+                let bucket_size = bucket_size.trim().parse().map_err(|_| invalid())?;
+                (seed.trim(), bucket_size)
+            }
+            None => (args.trim(), 1),
+        };
+        if seed.is_empty() || bucket_size == 0 {
+            return Err(invalid());
+        }
+        let seed = match seed.parse() {
+            Ok(seed) => RandomSeed::Literal(seed),
+            Err(_) => RandomSeed::Field(seed.to_string()),
+        };
+        Ok(Criterion::Random { seed, bucket_size })
+    }
+
+    fn parse_geo_point(text: &str) -> Result<Criterion, CriterionError> {
+        let invalid = || CriterionError::InvalidGeoPoint { name: text.to_string() };
+        let args = text
+            .strip_prefix("_geoPoint(")
+            .and_then(|args| args.strip_suffix(')'))
+            .ok_or_else(invalid)?;
+        let args: Vec<_> = args.split(',').map(str::trim).collect();
+        let (lat, lng, bucket_size) = match args.as_slice() {
+            [lat, lng] => (lat, lng, DEFAULT_GEO_POINT_BUCKET_SIZE),
+            [lat, lng, bucket_size] => (lat, lng, bucket_size.parse().map_err(|_| invalid())?),
+            _ => return Err(invalid()),
+        };
+        let lat: f64 = lat.parse().map_err(|_| invalid())?;
+        let lng: f64 = lng.parse().map_err(|_| invalid())?;
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) || bucket_size == 0 {
+            return Err(invalid());
+        }
+        Ok(Criterion::GeoPoint { point: LatLng { lat, lng }, bucket_size })
+    }
 }
 
 impl FromStr for Criterion {
@@ -68,6 +171,18 @@ impl FromStr for Criterion {
             "attribute" => Ok(Criterion::Attribute),
             "sort" => Ok(Criterion::Sort),
             "exactness" => Ok(Criterion::Exactness),
+            text if text.starts_with("random(")
+                && !text.ends_with(":asc")
+                && !text.ends_with(":desc") =>
+            {
+                Criterion::parse_random(text)
+            }
+            text if text.starts_with("_geoPoint(")
+                && !text.ends_with(":asc")
+                && !text.ends_with(":desc") =>
+            {
+                Criterion::parse_geo_point(text)
+            }
             text => match AscDesc::from_str(text)? {
                 AscDesc::Asc(Member::Field(field)) => Ok(Criterion::Asc(field)),
                 AscDesc::Desc(Member::Field(field)) => Ok(Criterion::Desc(field)),
@@ -79,6 +194,58 @@ impl FromStr for Criterion {
     }
 }
 
+/// Returns the index of a ranking rule and of the first ranking rule repeating it: a second
+/// `sort`, or a second custom ranking rule on the same attribute.
+fn find_repeated_criterion(criteria: &[Criterion]) -> Option<(usize, usize)> {
+    criteria.iter().enumerate().find_map(|(second, criterion)| {
+        let first = criteria[..second].iter().position(|previous| match (previous, criterion) {
+            (Criterion::Sort, Criterion::Sort) => true,
+            (
+                Criterion::Asc(previous) | Criterion::Desc(previous),
+                Criterion::Asc(field) | Criterion::Desc(field),
+            ) => previous == field,
+            _otherwise => false,
+        })?;
+        Some((first, second))
+    })
+}
+
+/// Checks that the ranking rules use `sort` at most once, and sort each attribute at most once.
+///
+/// The errors point at both ranking rules by their index in the list.
+pub fn validate_criteria(criteria: &[Criterion]) -> Result<(), CriterionError> {
+    match find_repeated_criterion(criteria) {
+        None => Ok(()),
+        Some((first, second)) => match &criteria[second] {
+            Criterion::Sort => Err(CriterionError::RepeatedSort { first, second }),
+            criterion => Err(CriterionError::RepeatedField {
+                field: criterion.field_name().unwrap_or_default().to_string(),
+                first_name: criteria[first].to_string(),
+                first,
+                second_name: criterion.to_string(),
+                second,
+            }),
+        },
+    }
+}
+
+/// Removes the ranking rules repeating a previous one, that [`validate_criteria`] rejects but
+/// that older versions and dumps could store.
+///
+/// The documents of a bucket all share the value sorted by the previous ranking rule, so the
+/// removed ranking rules never changed the ranking.
+pub fn normalize_criteria(mut criteria: Vec<Criterion>) -> Vec<Criterion> {
+    while let Some((first, second)) = find_repeated_criterion(&criteria) {
+        tracing::warn!(
+            "Ignoring the `{}` ranking rule at index {second}, repeating the `{}` ranking rule at index {first}",
+            criteria[second],
+            criteria[first],
+        );
+        criteria.remove(second);
+    }
+    criteria
+}
+
 pub fn default_criteria() -> Vec<Criterion> {
     vec![
         Criterion::Words,
@@ -103,6 +270,20 @@ impl fmt::Display for Criterion {
             Exactness => f.write_str("exactness"),
             Asc(attr) => write!(f, "{}:asc", attr),
             Desc(attr) => write!(f, "{}:desc", attr),
+            Random { seed, bucket_size } => {
+                let seed = match seed {
+                    RandomSeed::Literal(seed) => seed.to_string(),
+                    RandomSeed::Field(field) => field.clone(),
+                };
+                match bucket_size {
+                    1 => write!(f, "random({seed})"),
+                    bucket_size => write!(f, "random({seed}, {bucket_size})"),
+                }
+            }
+            GeoPoint { point: LatLng { lat, lng }, bucket_size } => match *bucket_size {
+                DEFAULT_GEO_POINT_BUCKET_SIZE => write!(f, "_geoPoint({lat}, {lng})"),
+                bucket_size => write!(f, "_geoPoint({lat}, {lng}, {bucket_size})"),
+            },
         }
     }
 }
@@ -129,6 +310,24 @@ mod tests {
             ("truc:machin:desc", Criterion::Desc(S("truc:machin"))),
             ("hello-world!:desc", Criterion::Desc(S("hello-world!"))),
             ("it's spacy over there:asc", Criterion::Asc(S("it's spacy over there"))),
+            ("random(42)", Criterion::Random { seed: RandomSeed::Literal(42), bucket_size: 1 }),
+            (
+                "random(42, 10)",
+                Criterion::Random { seed: RandomSeed::Literal(42), bucket_size: 10 },
+            ),
+            (
+                "random(shuffle.seed)",
+                Criterion::Random { seed: RandomSeed::Field(S("shuffle.seed")), bucket_size: 1 },
+            ),
+            ("random(42):asc", Criterion::Asc(S("random(42)"))),
+            (
+                "_geoPoint(48.85, 2.35)",
+                Criterion::GeoPoint { point: LatLng { lat: 48.85, lng: 2.35 }, bucket_size: 1000 },
+            ),
+            (
+                "_geoPoint(-90, 180, 10)",
+                Criterion::GeoPoint { point: LatLng { lat: -90., lng: 180. }, bucket_size: 10 },
+            ),
         ];
 
         for (input, expected) in valid_criteria {
@@ -153,6 +352,14 @@ mod tests {
             ("price:aasc", InvalidName { name: S("price:aasc") }),
             ("price:asc and desc", InvalidName { name: S("price:asc and desc") }),
             ("price:asc:truc", InvalidName { name: S("price:asc:truc") }),
+            ("random()", InvalidRandom { name: S("random()") }),
+            ("random(42, 0)", InvalidRandom { name: S("random(42, 0)") }),
+            ("random(42, ten)", InvalidRandom { name: S("random(42, ten)") }),
+            ("random(42", InvalidRandom { name: S("random(42") }),
+            ("_geoPoint(42)", InvalidGeoPoint { name: S("_geoPoint(42)") }),
+            ("_geoPoint(91, 75)", InvalidGeoPoint { name: S("_geoPoint(91, 75)") }),
+            ("_geoPoint(42, 75, 0)", InvalidGeoPoint { name: S("_geoPoint(42, 75, 0)") }),
+            ("_geoPoint(42, 75", InvalidGeoPoint { name: S("_geoPoint(42, 75") }),
             ("_geo:asc", ReservedName { name: S("_geo") }),
             ("_geoDistance:asc", ReservedName { name: S("_geoDistance") }),
             ("_geoPoint:asc", ReservedNameForSort { name: S("_geoPoint") }),
@@ -185,4 +392,23 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn repeated_criteria() {
+        let criteria = |rules: &[&str]| -> Vec<Criterion> {
+            rules.iter().map(|rule| rule.parse().unwrap()).collect()
+        };
+
+        let valid = criteria(&["words", "price:asc", "sort", "typo", "rank:desc", "random(price)"]);
+        assert!(validate_criteria(&valid).is_ok());
+        assert_eq!(normalize_criteria(valid.clone()), valid);
+
+        let repeated_field = criteria(&["words", "price:asc", "typo", "price:desc"]);
+        insta::assert_snapshot!(validate_criteria(&repeated_field).unwrap_err(), @"`price:desc` ranking rule at index 3 is sorting the `price` attribute, already sorted by the `price:asc` ranking rule at index 1. An attribute can only be sorted by a single ranking rule.");
+        assert_eq!(normalize_criteria(repeated_field), criteria(&["words", "price:asc", "typo"]));
+
+        let repeated_sort = criteria(&["sort", "words", "rank:asc", "sort", "rank:asc"]);
+        insta::assert_snapshot!(validate_criteria(&repeated_sort).unwrap_err(), @"`sort` ranking rule at index 3 is repeating the `sort` ranking rule at index 0. The `sort` ranking rule can only be used once.");
+        assert_eq!(normalize_criteria(repeated_sort), criteria(&["sort", "words", "rank:asc"]));
+    }
 }
@@ -0,0 +1,192 @@
+//! Encryption at rest of the content of the documents.
+//!
+//! The documents are encrypted with AES-256-GCM, each value being prefixed by its random nonce.
+//! Only the content of the documents is encrypted: the inverted indexes, the facet databases
+//! and the vectors are stored in plain text, as the search needs to read their keys.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use thiserror::Error;
+
+/// The length, in bytes, of an encryption key.
+pub const ENCRYPTION_KEY_LEN: usize = 32;
+
+/// What is encrypted in the key checks, to tell a wrong key from a corrupted value.
+const KEY_CHECK_PLAINTEXT: &[u8] = b"meilisearch-encryption-key-check";
+const KEY_CHECK_AAD: &[u8] = b"key-check";
+
+#[derive(Error, Debug)]
+pub enum EncryptionError {
+    #[error("The encryption key must be {ENCRYPTION_KEY_LEN} bytes encoded in base64.")]
+    InvalidKey,
+    #[error("The data was encrypted with another encryption key.")]
+    WrongKey,
+    #[error("The data is encrypted, but no encryption key was provided.")]
+    MissingKey,
+    #[error("The data was written without encryption, it can't be opened with an encryption key. Import a dump to encrypt it.")]
+    UnencryptedData,
+    #[error("Could not encrypt a value.")]
+    Encryption,
+    #[error("Could not decrypt a value, it is corrupted.")]
+    Decryption,
+}
+
+/// A 256 bits key with which the documents and the update files are encrypted.
+#[derive(Clone)]
+pub struct EncryptionKey(Arc<LessSafeKey>);
+
+impl EncryptionKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EncryptionError> {
+        if bytes.len() != ENCRYPTION_KEY_LEN {
+            return Err(EncryptionError::InvalidKey);
+        }
+        let key = UnboundKey::new(&AES_256_GCM, bytes).map_err(|_| EncryptionError::InvalidKey)?;
+        Ok(EncryptionKey(Arc::new(LessSafeKey::new(key))))
+    }
+
+    /// Encrypts the value, the `aad` is authenticated but not encrypted, and must
+    /// be the same to decrypt it. It binds the value to where it is stored.
+    pub fn encrypt(&self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let mut nonce = [0; NONCE_LEN];
+        SystemRandom::new().fill(&mut nonce).map_err(|_| EncryptionError::Encryption)?;
+
+        let tag_len = self.0.algorithm().tag_len();
+        let mut output = Vec::with_capacity(NONCE_LEN + plaintext.len() + tag_len);
+        output.extend_from_slice(&nonce);
+        output.extend_from_slice(plaintext);
+        let tag = self
+            .0
+            .seal_in_place_separate_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(aad),
+                &mut output[NONCE_LEN..],
+            )
+            .map_err(|_| EncryptionError::Encryption)?;
+        output.extend_from_slice(tag.as_ref());
+        Ok(output)
+    }
+
+    pub fn decrypt(&self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        if ciphertext.len() < NONCE_LEN + self.0.algorithm().tag_len() {
+            return Err(EncryptionError::Decryption);
+        }
+        let (nonce, sealed) = ciphertext.split_at(NONCE_LEN);
+        let nonce =
+            Nonce::try_assume_unique_for_key(nonce).map_err(|_| EncryptionError::Decryption)?;
+
+        let mut buffer = sealed.to_vec();
+        let len = self
+            .0
+            .open_in_place(nonce, Aad::from(aad), &mut buffer)
+            .map_err(|_| EncryptionError::Decryption)?
+            .len();
+        buffer.truncate(len);
+        Ok(buffer)
+    }
+
+    /// A value that only this key can decrypt, to store next to the data it encrypts.
+    pub fn key_check(&self) -> Result<Vec<u8>, EncryptionError> {
+        self.encrypt(KEY_CHECK_AAD, KEY_CHECK_PLAINTEXT)
+    }
+
+    pub fn verify_key_check(&self, key_check: &[u8]) -> Result<(), EncryptionError> {
+        match self.decrypt(KEY_CHECK_AAD, key_check) {
+            Ok(plaintext) if plaintext == KEY_CHECK_PLAINTEXT => Ok(()),
+            _ => Err(EncryptionError::WrongKey),
+        }
+    }
+}
+
+impl FromStr for EncryptionKey {
+    type Err = EncryptionError;
+
+    /// Parses a base64 encoded key.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(s.trim())
+            .map_err(|_| EncryptionError::InvalidKey)?;
+        EncryptionKey::from_bytes(&bytes)
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptionKey(..)")
+    }
+}
+
+/// Checks that some stored data can be read with the given key, before reading any of it.
+///
+/// Returns the key check to store next to the data when it starts to be encrypted,
+/// only empty data can start to be encrypted.
+pub fn check_encryption_key(
+    key: Option<&EncryptionKey>,
+    stored_key_check: Option<&[u8]>,
+    has_data: bool,
+) -> Result<Option<Vec<u8>>, EncryptionError> {
+    match (key, stored_key_check) {
+        (Some(key), Some(key_check)) => key.verify_key_check(key_check).map(|()| None),
+        (None, Some(_)) => Err(EncryptionError::MissingKey),
+        (Some(_), None) if has_data => Err(EncryptionError::UnencryptedData),
+        (Some(key), None) => key.key_check().map(Some),
+        (None, None) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+    const OTHER_KEY: &str = "Hx4dHBsaGRgXFhUUExIREA8ODQwLCgkIBwYFBAMCAQA=";
+
+    #[test]
+    fn encrypt_decrypt() {
+        let key: EncryptionKey = KEY.parse().unwrap();
+        let ciphertext = key.encrypt(b"doc-1", b"hello world").unwrap();
+        assert!(!ciphertext.windows(11).any(|w| w == b"hello world"));
+        assert_eq!(key.decrypt(b"doc-1", &ciphertext).unwrap(), b"hello world");
+
+        // the nonce is random, the same value is never encrypted the same way
+        assert_ne!(ciphertext, key.encrypt(b"doc-1", b"hello world").unwrap());
+        // a value can't be moved to another place
+        assert!(key.decrypt(b"doc-2", &ciphertext).is_err());
+
+        let other_key: EncryptionKey = OTHER_KEY.parse().unwrap();
+        assert!(other_key.decrypt(b"doc-1", &ciphertext).is_err());
+    }
+
+    #[test]
+    fn invalid_keys() {
+        assert!("not base64!".parse::<EncryptionKey>().is_err());
+        // 16 bytes
+        assert!("AAECAwQFBgcICQoLDA0ODw==".parse::<EncryptionKey>().is_err());
+    }
+
+    #[test]
+    fn key_checks() {
+        let key: EncryptionKey = KEY.parse().unwrap();
+        let other_key: EncryptionKey = OTHER_KEY.parse().unwrap();
+
+        let key_check = check_encryption_key(Some(&key), None, false).unwrap().unwrap();
+        assert!(check_encryption_key(Some(&key), Some(&key_check), true).unwrap().is_none());
+        assert!(matches!(
+            check_encryption_key(Some(&other_key), Some(&key_check), true),
+            Err(EncryptionError::WrongKey)
+        ));
+        assert!(matches!(
+            check_encryption_key(None, Some(&key_check), true),
+            Err(EncryptionError::MissingKey)
+        ));
+        assert!(matches!(
+            check_encryption_key(Some(&key), None, true),
+            Err(EncryptionError::UnencryptedData)
+        ));
+        assert!(check_encryption_key(None, None, true).unwrap().is_none());
+    }
+}
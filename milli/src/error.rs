@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::collections::BTreeSet;
 use std::convert::Infallible;
 use std::fmt::Write;
@@ -9,6 +10,7 @@ use serde_json::Value;
 use thiserror::Error;
 
 use crate::documents::{self, DocumentsBatchCursorError};
+use crate::encryption::EncryptionError;
 use crate::{CriterionError, DocumentId, FieldId, Object, SortError};
 
 pub fn is_reserved_keyword(keyword: &str) -> bool {
@@ -23,6 +25,8 @@ pub enum Error {
     IoError(#[from] io::Error),
     #[error(transparent)]
     UserError(#[from] UserError),
+    #[error("The search was canceled.")]
+    SearchCancelled,
 }
 
 #[derive(Error, Debug)]
@@ -36,6 +40,8 @@ pub enum InternalError {
     #[error("Missing {key} in the field id mapping.")]
     FieldIdMappingMissingEntry { key: FieldId },
     #[error(transparent)]
+    Encryption(#[from] EncryptionError),
+    #[error(transparent)]
     Fst(#[from] fst::Error),
     #[error(transparent)]
     DocumentsError(#[from] documents::Error),
@@ -118,6 +124,8 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
     InvalidVectorsType { document_id: Value, value: Value, subfield: String },
     #[error("The `_vectors` field in the document with id: `{document_id}` is not an object. Was expecting an object with a key for each embedder with manually provided vectors, but instead got `{value}`")]
     InvalidVectorsMapType { document_id: Value, value: Value },
+    #[error("The document with id: `{document_id}` contains the field `{field}`, but the fields starting with `_` are reserved. Only `_geo` and `_vectors` are accepted, unless the `allowUnderscoreFields` setting is enabled.")]
+    ReservedDocumentField { document_id: Value, field: String },
     #[error("{0}")]
     InvalidFilter(String),
     #[error("Invalid type for filter subexpression: expected: {}, found: {1}.", .0.join(", "))]
@@ -133,6 +141,17 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
         }
     )]
     InvalidSortableAttribute { field: String, valid_fields: BTreeSet<String>, hidden_fields: bool },
+    #[error("Attribute `{}` is not filterable and can't be used as the distinct attribute of the search. {}",
+        .field,
+        match .valid_fields.is_empty() {
+            true => "This index does not have configured filterable attributes.".to_string(),
+            false => format!("Available filterable attributes are: `{}{}`.",
+                    valid_fields.iter().map(AsRef::as_ref).collect::<Vec<&str>>().join(", "),
+                    .hidden_fields.then_some(", <..hidden-attributes>").unwrap_or(""),
+                ),
+        }
+    )]
+    InvalidDistinctAttribute { field: String, valid_fields: BTreeSet<String>, hidden_fields: bool },
     #[error("Attribute `{}` is not facet-searchable. {}",
         .field,
         match .valid_fields.is_empty() {
@@ -148,6 +167,12 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
         valid_fields: BTreeSet<String>,
         hidden_fields: bool,
     },
+    #[error("The facet query is {length} bytes long, but it can't be longer than {max} bytes.")]
+    FacetSearchQueryTooLong { length: usize, max: usize },
+    #[error(
+        "The facet query `{0}` must contain at least one word or number, not only separators."
+    )]
+    FacetSearchQueryWithoutWords(String),
     #[error("Attribute `{}` is not searchable. Available searchable attributes are: `{}{}`.",
         .field,
         .valid_fields.iter().map(AsRef::as_ref).collect::<Vec<&str>>().join(", "),
@@ -158,6 +183,10 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
         valid_fields: BTreeSet<String>,
         hidden_fields: bool,
     },
+    #[error("The document `{document_id}` given in `searchAfter` is not part of the results of the search.")]
+    InvalidSearchAfter { document_id: String },
+    #[error("Invalid value at `.searchDefaults`: {0}")]
+    InvalidSearchDefaults(String),
     #[error("an environment is already opened with different options")]
     InvalidLmdbOpenOptions,
     #[error("You must specify where `sort` is listed in the rankingRules setting to use the sort parameter at search time.")]
@@ -178,6 +207,10 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
     NoSpaceLeftOnDevice,
     #[error("Index already has a primary key: `{0}`.")]
     PrimaryKeyCannotBeChanged(String),
+    #[error("The primary key cannot be changed to `{}` because it is missing or invalid in the following documents: {}.", .primary_key, .document_ids.iter().map(|id| format!("`{id}`")).collect::<Vec<_>>().join(", "))]
+    PrimaryKeyMissingInDocuments { primary_key: String, document_ids: Vec<String> },
+    #[error("The primary key cannot be changed to `{}` because the following documents share the same value for it: {}.", .primary_key, .document_ids.iter().map(|id| format!("`{id}`")).collect::<Vec<_>>().join(", "))]
+    PrimaryKeyValueNotUnique { primary_key: String, document_ids: Vec<String> },
     #[error(transparent)]
     SerdeJson(serde_json::Error),
     #[error(transparent)]
@@ -247,6 +280,135 @@ only composed of alphanumeric characters (a-z A-Z 0-9), hyphens (-) and undersco
     InvalidUrl { embedder_name: String, inner_error: url::ParseError, url: String },
 }
 
+impl Error {
+    /// Whether the operation failed because of a condition that may not happen again,
+    /// such as an IO error or an embedder that is temporarily unavailable.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::IoError(_) => true,
+            Error::InternalError(InternalError::VectorEmbeddingError(error))
+            | Error::UserError(UserError::VectorEmbeddingError(error)) => error.is_transient(),
+            Error::InternalError(_) | Error::UserError(_) | Error::SearchCancelled => false,
+        }
+    }
+
+    /// Truncates the long document values echoed in the message of this error, see [`redact_payload`].
+    pub fn redact_payloads(self) -> Self {
+        match self {
+            Error::UserError(error) => Error::UserError(error.redact_payloads()),
+            error => error,
+        }
+    }
+}
+
+impl UserError {
+    /// Truncates the long document values echoed in the message of this error, see [`redact_payload`].
+    pub fn redact_payloads(self) -> Self {
+        match self {
+            UserError::InvalidDocumentId { document_id } => {
+                UserError::InvalidDocumentId { document_id: redact_value(document_id) }
+            }
+            UserError::InvalidVectorsType { document_id, value, subfield } => {
+                UserError::InvalidVectorsType { document_id, value: redact_value(value), subfield }
+            }
+            UserError::InvalidVectorsMapType { document_id, value } => {
+                UserError::InvalidVectorsMapType { document_id, value: redact_value(value) }
+            }
+            UserError::ReservedDocumentField { document_id, field } => {
+                let field = redact_payload(&field).into_owned();
+                UserError::ReservedDocumentField { document_id, field }
+            }
+            UserError::InvalidGeoField(error) => {
+                UserError::InvalidGeoField(error.redact_payloads())
+            }
+            // the message of a filter error echoes the filter on its own line
+            UserError::InvalidFilter(message) => UserError::InvalidFilter(
+                message.lines().map(redact_payload).collect::<Vec<_>>().join("\n"),
+            ),
+            UserError::InvalidFilterExpression(expected, value) => {
+                UserError::InvalidFilterExpression(expected, redact_value(value))
+            }
+            UserError::MissingDocumentId { primary_key, document } => {
+                UserError::MissingDocumentId { primary_key, document: redact_object(document) }
+            }
+            UserError::TooManyDocumentIds { primary_key, document } => {
+                UserError::TooManyDocumentIds { primary_key, document: redact_object(document) }
+            }
+            error => error,
+        }
+    }
+}
+
+impl GeoError {
+    /// Truncates the long document values echoed in the message of this error, see [`redact_payload`].
+    pub fn redact_payloads(self) -> Self {
+        match self {
+            GeoError::NotAnObject { document_id, value } => {
+                GeoError::NotAnObject { document_id, value: redact_value(value) }
+            }
+            GeoError::UnexpectedExtraFields { document_id, value } => {
+                GeoError::UnexpectedExtraFields { document_id, value: redact_value(value) }
+            }
+            GeoError::BadLatitudeAndLongitude { document_id, lat, lng } => {
+                GeoError::BadLatitudeAndLongitude {
+                    document_id,
+                    lat: redact_value(lat),
+                    lng: redact_value(lng),
+                }
+            }
+            GeoError::BadLatitude { document_id, value } => {
+                GeoError::BadLatitude { document_id, value: redact_value(value) }
+            }
+            GeoError::BadLongitude { document_id, value } => {
+                GeoError::BadLongitude { document_id, value: redact_value(value) }
+            }
+            error @ (GeoError::MissingLatitudeAndLongitude { .. }
+            | GeoError::MissingLatitude { .. }
+            | GeoError::MissingLongitude { .. }) => error,
+        }
+    }
+}
+
+/// The number of characters above which a payload echoed in an error message is redacted.
+pub const REDACTED_PAYLOAD_MAX_LENGTH: usize = 256;
+
+/// The number of characters kept at the start of a redacted payload.
+const REDACTED_PAYLOAD_KEPT_LENGTH: usize = 32;
+
+/// Truncates a payload longer than [`REDACTED_PAYLOAD_MAX_LENGTH`] characters to its first
+/// characters followed by an ellipsis, its length, and a hash of the full payload,
+/// so that the occurrences of the same payload can still be told apart in the logs.
+pub fn redact_payload(payload: &str) -> Cow<'_, str> {
+    let length = payload.chars().count();
+    if length <= REDACTED_PAYLOAD_MAX_LENGTH {
+        return Cow::Borrowed(payload);
+    }
+    let kept: String = payload.chars().take(REDACTED_PAYLOAD_KEPT_LENGTH).collect();
+    let hash = fxhash::hash64(payload);
+    Cow::Owned(format!("{kept}… ({length} characters, hash: {hash:016x})"))
+}
+
+/// Replaces the value by its redacted representation if it is too long, see [`redact_payload`].
+fn redact_value(value: Value) -> Value {
+    let json;
+    let payload = match &value {
+        Value::String(string) => string.as_str(),
+        value => {
+            json = value.to_string();
+            json.as_str()
+        }
+    };
+    let redacted = match redact_payload(payload) {
+        Cow::Borrowed(_) => None,
+        Cow::Owned(redacted) => Some(redacted),
+    };
+    redacted.map_or(value, Value::String)
+}
+
+fn redact_object(object: Object) -> Object {
+    object.into_iter().map(|(key, value)| (key, redact_value(value))).collect()
+}
+
 impl From<crate::vector::Error> for Error {
     fn from(value: crate::vector::Error) -> Self {
         match value.fault() {
@@ -367,6 +529,7 @@ macro_rules! error_from_sub_error {
 
 error_from_sub_error! {
     FieldIdMapMissingEntry => InternalError,
+    EncryptionError => InternalError,
     fst::Error => InternalError,
     documents::Error => InternalError,
     str::Utf8Error => InternalError,
@@ -469,3 +632,16 @@ fn conditionally_lookup_for_error_message() {
         assert_eq!(err.to_string(), format!("{} {}", prefix, suffix));
     }
 }
+
+#[test]
+fn redact_long_payloads() {
+    let document_id = Value::from("1");
+    let err = GeoError::NotAnObject { document_id, value: Value::from("a".repeat(10_000)) };
+    let message = UserError::InvalidGeoField(err).redact_payloads().to_string();
+    assert!(message.len() < 1024, "{message}");
+    assert!(message.contains("with the id: `\"1\"`"), "{message}");
+    assert!(message.contains("… (10000 characters, hash: "), "{message}");
+
+    let err = UserError::InvalidFilterExpression(&["String"], Value::from(42));
+    assert_eq!(err.to_string(), err.redact_payloads().to_string());
+}
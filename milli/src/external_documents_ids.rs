@@ -68,6 +68,11 @@ impl ExternalDocumentsIds {
         Ok(())
     }
 
+    /// Removes all the external ids of the mapping.
+    pub fn clear(&self, wtxn: &mut RwTxn) -> heed::Result<()> {
+        self.0.clear(wtxn)
+    }
+
     /// Returns an iterator over all the external ids.
     pub fn iter<'t>(&self, rtxn: &'t RoTxn) -> heed::Result<RoIter<'t, Str, BEU32>> {
         self.0.iter(rtxn)
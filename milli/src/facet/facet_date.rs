@@ -0,0 +1,82 @@
+//! The dates of the attributes hinted as dates by the `facetTypes` setting.
+//!
+//! Their RFC3339 and `YYYY-MM-DD` strings are indexed as facet strings, like any other string,
+//! and additionally as facet numbers: the number of seconds elapsed since the Unix epoch.
+//! The dates can then be filtered by range and have facet stats, in which the timestamps are
+//! formatted back into RFC3339 strings.
+
+use deserr::Deserr;
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::{Date, OffsetDateTime};
+
+/// The type hinted for the values of an attribute, changing how its strings are faceted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Deserr)]
+#[serde(rename_all = "camelCase")]
+#[deserr(rename_all = camelCase)]
+pub enum FacetTypeHint {
+    /// The RFC3339 and `YYYY-MM-DD` strings are also indexed as timestamps.
+    Date,
+}
+
+/// Parses an RFC3339 datetime or a `YYYY-MM-DD` date, taken at midnight UTC, into the number
+/// of seconds elapsed since the Unix epoch.
+pub fn parse_facet_date(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let datetime = match OffsetDateTime::parse(value, &Rfc3339) {
+        Ok(datetime) => datetime,
+        Err(_) => Date::parse(value, format_description!("[year]-[month]-[day]"))
+            .ok()?
+            .midnight()
+            .assume_utc(),
+    };
+    let nanoseconds = datetime.nanosecond() as f64 / 1_000_000_000.0;
+    Some(datetime.unix_timestamp() as f64 + nanoseconds)
+}
+
+/// Formats a timestamp in seconds into an RFC3339 datetime in UTC.
+///
+/// Returns `None` when the timestamp is out of the range of the RFC3339 years.
+pub fn format_facet_date(timestamp: f64) -> Option<String> {
+    if !timestamp.is_finite() {
+        return None;
+    }
+    let seconds = timestamp.floor();
+    let nanoseconds = (((timestamp - seconds) * 1_000_000_000.0).round() as u32).min(999_999_999);
+    let datetime = OffsetDateTime::from_unix_timestamp(seconds as i64)
+        .ok()?
+        .replace_nanosecond(nanoseconds)
+        .ok()?;
+    datetime.format(&Rfc3339).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dates() {
+        assert_eq!(parse_facet_date("1970-01-02"), Some(86_400.0));
+        assert_eq!(parse_facet_date("1970-01-02T00:00:00Z"), Some(86_400.0));
+        assert_eq!(parse_facet_date("1970-01-02T01:00:00+01:00"), Some(86_400.0));
+        assert_eq!(parse_facet_date(" 2024-02-29T12:30:00.5Z "), Some(1_709_209_800.5));
+        assert_eq!(parse_facet_date("1969-12-31"), Some(-86_400.0));
+
+        assert_eq!(parse_facet_date("2024-02-30"), None);
+        assert_eq!(parse_facet_date("2024-02"), None);
+        assert_eq!(parse_facet_date("2024-02-01 12:00"), None);
+        assert_eq!(parse_facet_date("yesterday"), None);
+    }
+
+    #[test]
+    fn format_dates() {
+        assert_eq!(format_facet_date(86_400.0).as_deref(), Some("1970-01-02T00:00:00Z"));
+        assert_eq!(format_facet_date(1_709_209_800.5).as_deref(), Some("2024-02-29T12:30:00.5Z"));
+        assert_eq!(format_facet_date(-86_400.0).as_deref(), Some("1969-12-31T00:00:00Z"));
+        assert_eq!(format_facet_date(1e300), None);
+
+        let timestamp = parse_facet_date("2021-07-14T08:15:30.25Z").unwrap();
+        assert_eq!(format_facet_date(timestamp).as_deref(), Some("2021-07-14T08:15:30.25Z"));
+    }
+}
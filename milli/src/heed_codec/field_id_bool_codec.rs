@@ -0,0 +1,31 @@
+use std::borrow::Cow;
+
+use heed::BoxedError;
+
+use super::SliceTooShortError;
+use crate::{try_split_array_at, FieldId};
+
+/// Encodes a field id followed by a boolean stored as a single byte, `0` for `false` and `1` for `true`.
+pub struct FieldIdBoolCodec;
+
+impl<'a> heed::BytesDecode<'a> for FieldIdBoolCodec {
+    type DItem = (FieldId, bool);
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
+        let (field_id_bytes, bytes) = try_split_array_at(bytes).ok_or(SliceTooShortError)?;
+        let field_id = u16::from_be_bytes(field_id_bytes);
+        let ([boolean], _nothing) = try_split_array_at(bytes).ok_or(SliceTooShortError)?;
+        Ok((field_id, boolean != 0))
+    }
+}
+
+impl<'a> heed::BytesEncode<'a> for FieldIdBoolCodec {
+    type EItem = (FieldId, bool);
+
+    fn bytes_encode((field_id, boolean): &Self::EItem) -> Result<Cow<[u8]>, BoxedError> {
+        let mut bytes = Vec::with_capacity(2 + 1);
+        bytes.extend_from_slice(&field_id.to_be_bytes());
+        bytes.push(*boolean as u8);
+        Ok(Cow::Owned(bytes))
+    }
+}
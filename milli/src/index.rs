@@ -1,17 +1,24 @@
 use std::borrow::Cow;
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
 use std::fs::File;
 use std::path::Path;
 
 use charabia::{Language, Script};
+use fst::{Automaton, IntoStreamer, Streamer};
 use heed::types::*;
 use heed::{CompactionOption, Database, RoTxn, RwTxn, Unspecified};
 use roaring::RoaringBitmap;
 use rstar::RTree;
+use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
+use crate::case_folding::CaseFolding;
+use crate::collation::Locale;
 use crate::documents::PrimaryKey;
+use crate::encryption::{check_encryption_key, EncryptionKey};
 use crate::error::{InternalError, UserError};
+use crate::facet::{FacetType, FacetTypeHint};
 use crate::fields_ids_map::FieldsIdsMap;
 use crate::heed_codec::facet::{
     FacetGroupKeyCodec, FacetGroupValueCodec, FieldDocIdFacetF64Codec, FieldDocIdFacetStringCodec,
@@ -22,18 +29,24 @@ use crate::heed_codec::{
 };
 use crate::order_by_map::OrderByMap;
 use crate::proximity::ProximityPrecision;
+use crate::search::new::{SharedSearchCache, WarmUpStats};
+use crate::update::FacetUpdateStrategy;
 use crate::vector::EmbeddingConfig;
 use crate::{
-    default_criteria, CboRoaringBitmapCodec, Criterion, DocumentId, ExternalDocumentsIds,
-    FacetDistribution, FieldDistribution, FieldId, FieldIdWordCountCodec, GeoPoint, ObkvCodec,
-    Result, RoaringBitmapCodec, RoaringBitmapLenCodec, Search, U8StrStrCodec, BEU16, BEU32, BEU64,
+    default_criteria, normalize_criteria, CboRoaringBitmapCodec, CboRoaringBitmapLenCodec,
+    Criterion, DocumentId, ExternalDocumentsIds, FacetDistribution, FieldDistribution, FieldId,
+    FieldIdBoolCodec, FieldIdWordCountCodec, GeoPoint, LocalizedAttributesRule, ObkvCodec,
+    RandomSeed, Result, RoaringBitmapCodec, RoaringBitmapLenCodec, Search, U8StrStrCodec, BEU16,
+    BEU32, BEU64,
 };
 
 pub const DEFAULT_MIN_WORD_LEN_ONE_TYPO: u8 = 5;
 pub const DEFAULT_MIN_WORD_LEN_TWO_TYPOS: u8 = 9;
+pub const DEFAULT_MIN_NUMERIC_PREFIX_LENGTH: u8 = 4;
 
 pub mod main_key {
     pub const CRITERIA_KEY: &str = "criteria";
+    pub const CRITERIA_MAX_BUCKETS_KEY: &str = "criteria-max-buckets";
     pub const DISPLAYED_FIELDS_KEY: &str = "displayed-fields";
     pub const DISTINCT_FIELD_KEY: &str = "distinct-field-key";
     pub const DOCUMENTS_IDS_KEY: &str = "documents-ids";
@@ -62,12 +75,31 @@ pub mod main_key {
     pub const TWO_TYPOS_WORD_LEN: &str = "two-typos-word-len";
     pub const EXACT_WORDS: &str = "exact-words";
     pub const EXACT_ATTRIBUTES: &str = "exact-attributes";
+    pub const STRICT_ATTRIBUTES: &str = "strict-attributes";
     pub const MAX_VALUES_PER_FACET: &str = "max-values-per-facet";
     pub const SORT_FACET_VALUES_BY: &str = "sort-facet-values-by";
     pub const PAGINATION_MAX_TOTAL_HITS: &str = "pagination-max-total-hits";
     pub const PROXIMITY_PRECISION: &str = "proximity-precision";
     pub const EMBEDDING_CONFIGS: &str = "embedding_configs";
     pub const SEARCH_CUTOFF: &str = "search_cutoff";
+    pub const SEARCH_DEFAULTS: &str = "search_defaults";
+    pub const MIN_NUMERIC_PREFIX_LENGTH: &str = "min_numeric_prefix_length";
+    pub const SEARCH_PRESETS: &str = "search_presets";
+    pub const ALLOW_UNDERSCORE_FIELDS: &str = "allow_underscore_fields";
+    pub const BOOLEAN_FACETS: &str = "boolean-facets";
+    pub const FULL_CASE_FOLDING: &str = "full-case-folding";
+    pub const LOCALIZED_ATTRIBUTES_RULES: &str = "localized_attributes_rules";
+    pub const NUMBER_NORMALIZATION: &str = "number_normalization";
+    pub const PHRASE_ACROSS_FIELDS: &str = "phrase_across_fields";
+    pub const DECOMPOUNDING_LOCALES: &str = "decompounding_locales";
+    pub const FACET_TYPES: &str = "facet_types";
+    pub const STEMMING_LOCALES: &str = "stemming_locales";
+    pub const ENCRYPTION_KEY_CHECK: &str = "encryption-key-check";
+    pub const LAST_INDEXING_ERRORS: &str = "last-indexing-errors";
+    pub const FACET_NUMBER_ENTRIES_COUNTS: &str = "facet-number-entries-counts";
+    pub const FACET_STRING_ENTRIES_COUNTS: &str = "facet-string-entries-counts";
+    pub const LAST_FACET_UPDATE_STRATEGIES: &str = "last-facet-update-strategies";
+    pub const SEARCH_CACHE_STAMP_KEY: &str = "search-cache-stamp";
 }
 
 pub mod db_name {
@@ -88,6 +120,7 @@ pub mod db_name {
     pub const FACET_ID_EXISTS_DOCIDS: &str = "facet-id-exists-docids";
     pub const FACET_ID_IS_NULL_DOCIDS: &str = "facet-id-is-null-docids";
     pub const FACET_ID_IS_EMPTY_DOCIDS: &str = "facet-id-is-empty-docids";
+    pub const FACET_ID_BOOL_DOCIDS: &str = "facet-id-bool-docids";
     pub const FACET_ID_STRING_DOCIDS: &str = "facet-id-string-docids";
     pub const FACET_ID_NORMALIZED_STRING_STRINGS: &str = "facet-id-normalized-string-strings";
     pub const FACET_ID_STRING_FST: &str = "facet-id-string-fst";
@@ -97,6 +130,98 @@ pub mod db_name {
     pub const VECTOR_ARROY: &str = "vector-arroy";
     pub const DOCUMENTS: &str = "documents";
     pub const SCRIPT_LANGUAGE_DOCIDS: &str = "script_language_docids";
+    pub const DOCUMENT_TASKS: &str = "document-tasks";
+
+    /// The names of all the databases of an index.
+    pub const ALL: &[&str] = &[
+        MAIN,
+        WORD_DOCIDS,
+        EXACT_WORD_DOCIDS,
+        WORD_PREFIX_DOCIDS,
+        EXACT_WORD_PREFIX_DOCIDS,
+        EXTERNAL_DOCUMENTS_IDS,
+        WORD_PAIR_PROXIMITY_DOCIDS,
+        WORD_POSITION_DOCIDS,
+        WORD_FIELD_ID_DOCIDS,
+        WORD_PREFIX_POSITION_DOCIDS,
+        WORD_PREFIX_FIELD_ID_DOCIDS,
+        FIELD_ID_WORD_COUNT_DOCIDS,
+        FACET_ID_F64_DOCIDS,
+        FACET_ID_EXISTS_DOCIDS,
+        FACET_ID_IS_NULL_DOCIDS,
+        FACET_ID_IS_EMPTY_DOCIDS,
+        FACET_ID_BOOL_DOCIDS,
+        FACET_ID_STRING_DOCIDS,
+        FACET_ID_NORMALIZED_STRING_STRINGS,
+        FACET_ID_STRING_FST,
+        FIELD_ID_DOCID_FACET_F64S,
+        FIELD_ID_DOCID_FACET_STRINGS,
+        VECTOR_EMBEDDER_CATEGORY_ID,
+        VECTOR_ARROY,
+        DOCUMENTS,
+        SCRIPT_LANGUAGE_DOCIDS,
+        DOCUMENT_TASKS,
+    ];
+}
+
+/// The LMDB statistics of one of the databases of an index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseStats {
+    /// Size of a page, in bytes.
+    pub page_size: u32,
+    /// Depth of the B-tree.
+    pub depth: u32,
+    /// Number of internal pages.
+    pub branch_pages: usize,
+    /// Number of leaf pages.
+    pub leaf_pages: usize,
+    /// Number of overflow pages, used by the values that do not fit in a leaf page.
+    pub overflow_pages: usize,
+    /// Number of entries.
+    pub entries: usize,
+}
+
+impl From<heed::DatabaseStat> for DatabaseStats {
+    fn from(stat: heed::DatabaseStat) -> Self {
+        let heed::DatabaseStat {
+            page_size,
+            depth,
+            branch_pages,
+            leaf_pages,
+            overflow_pages,
+            entries,
+        } = stat;
+        DatabaseStats { page_size, depth, branch_pages, leaf_pages, overflow_pages, entries }
+    }
+}
+
+/// A document of an index, as an obkv of its fields.
+///
+/// It borrows the database when the index is not encrypted, and owns its decrypted content otherwise.
+#[derive(Debug, Clone)]
+pub struct StoredDocument<'t>(Cow<'t, [u8]>);
+
+impl<'t> StoredDocument<'t> {
+    pub fn reader(&self) -> obkv::KvReaderU16<'_> {
+        obkv::KvReaderU16::new(&self.0)
+    }
+
+    pub fn get(&self, field_id: FieldId) -> Option<&[u8]> {
+        self.reader().get(field_id)
+    }
+
+    pub fn iter(&self) -> obkv::KvIter<'_, FieldId> {
+        self.reader().iter()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_owned(self) -> StoredDocument<'static> {
+        StoredDocument(Cow::Owned(self.0.into_owned()))
+    }
 }
 
 #[derive(Clone)]
@@ -146,6 +271,8 @@ pub struct Index {
     pub facet_id_is_null_docids: Database<FieldIdCodec, CboRoaringBitmapCodec>,
     /// Maps the facet field id and the docids for which this field is considered empty
     pub facet_id_is_empty_docids: Database<FieldIdCodec, CboRoaringBitmapCodec>,
+    /// Maps the facet field id and a boolean with the docids for which this field contains this boolean
+    pub facet_id_bool_docids: Database<FieldIdBoolCodec, CboRoaringBitmapCodec>,
 
     /// Maps the facet field id and ranges of numbers with the docids that corresponds to them.
     pub facet_id_f64_docids: Database<FacetGroupKeyCodec<OrderedF64Codec>, FacetGroupValueCodec>,
@@ -168,18 +295,39 @@ pub struct Index {
 
     /// Maps the document id to the document as an obkv store.
     pub(crate) documents: Database<BEU32, ObkvCodec>,
+
+    /// Maps the external document ids with the uids of the last tasks that modified them, newest first.
+    pub(crate) document_tasks: Database<Str, SerdeJson<Vec<u32>>>,
+
+    /// The key the documents are encrypted with, if any.
+    encryption_key: Option<EncryptionKey>,
 }
 
 impl Index {
     pub fn new_with_creation_dates<P: AsRef<Path>>(
+        options: heed::EnvOpenOptions,
+        path: P,
+        created_at: OffsetDateTime,
+        updated_at: OffsetDateTime,
+    ) -> Result<Index> {
+        Self::new_with_encryption_key(options, path, created_at, updated_at, None)
+    }
+
+    /// Opens an index whose documents are encrypted with the given key.
+    ///
+    /// Fails if the documents were encrypted with another key, or if the index already
+    /// contains documents that were not encrypted. Opening an encrypted index without
+    /// a key fails too.
+    pub fn new_with_encryption_key<P: AsRef<Path>>(
         mut options: heed::EnvOpenOptions,
         path: P,
         created_at: OffsetDateTime,
         updated_at: OffsetDateTime,
+        encryption_key: Option<EncryptionKey>,
     ) -> Result<Index> {
         use db_name::*;
 
-        options.max_dbs(25);
+        options.max_dbs(27);
 
         let env = options.open(path)?;
         let mut wtxn = env.write_txn()?;
@@ -215,6 +363,7 @@ impl Index {
             env.create_database(&mut wtxn, Some(FACET_ID_IS_NULL_DOCIDS))?;
         let facet_id_is_empty_docids =
             env.create_database(&mut wtxn, Some(FACET_ID_IS_EMPTY_DOCIDS))?;
+        let facet_id_bool_docids = env.create_database(&mut wtxn, Some(FACET_ID_BOOL_DOCIDS))?;
         let field_id_docid_facet_f64s =
             env.create_database(&mut wtxn, Some(FIELD_ID_DOCID_FACET_F64S))?;
         let field_id_docid_facet_strings =
@@ -225,9 +374,11 @@ impl Index {
         let vector_arroy = env.create_database(&mut wtxn, Some(VECTOR_ARROY))?;
 
         let documents = env.create_database(&mut wtxn, Some(DOCUMENTS))?;
+        let document_tasks = env.create_database(&mut wtxn, Some(DOCUMENT_TASKS))?;
         wtxn.commit()?;
 
         Index::set_creation_dates(&env, main, created_at, updated_at)?;
+        Index::check_encryption_key(&env, main, documents, encryption_key.as_ref())?;
 
         Ok(Index {
             env,
@@ -251,11 +402,14 @@ impl Index {
             facet_id_exists_docids,
             facet_id_is_null_docids,
             facet_id_is_empty_docids,
+            facet_id_bool_docids,
             field_id_docid_facet_f64s,
             field_id_docid_facet_strings,
             vector_arroy,
             embedder_category_id,
             documents,
+            document_tasks,
+            encryption_key,
         })
     }
 
@@ -276,14 +430,47 @@ impl Index {
         if main.get(&txn, main_key::CREATED_AT_KEY)?.is_none() {
             main.put(&mut txn, main_key::UPDATED_AT_KEY, &updated_at)?;
             main.put(&mut txn, main_key::CREATED_AT_KEY, &created_at)?;
+            // A new index stores its booleans in the dedicated facet database from the start.
+            main.remap_types::<Str, U8>().put(&mut txn, main_key::BOOLEAN_FACETS, &1)?;
+            main.remap_types::<Str, U8>().put(&mut txn, main_key::FULL_CASE_FOLDING, &1)?;
             txn.commit()?;
         }
         Ok(())
     }
 
+    fn check_encryption_key(
+        env: &heed::Env,
+        main: Database<Unspecified, Unspecified>,
+        documents: Database<BEU32, ObkvCodec>,
+        encryption_key: Option<&EncryptionKey>,
+    ) -> Result<()> {
+        let mut wtxn = env.write_txn()?;
+        let main = main.remap_types::<Str, Bytes>();
+        let key_check = main.get(&wtxn, main_key::ENCRYPTION_KEY_CHECK)?;
+        let has_documents = !documents.is_empty(&wtxn)?;
+        if let Some(key_check) = check_encryption_key(encryption_key, key_check, has_documents)? {
+            main.put(&mut wtxn, main_key::ENCRYPTION_KEY_CHECK, &key_check)?;
+            wtxn.commit()?;
+        }
+        Ok(())
+    }
+
+    /// Whether the documents of this index are encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption_key.is_some()
+    }
+
     /// Create a write transaction to be able to write into the index.
+    ///
+    /// Any write may change the docids of the words, the searches stop using the
+    /// shared search cache of the index once the transaction is committed.
     pub fn write_txn(&self) -> heed::Result<RwTxn> {
-        self.env.write_txn()
+        let mut wtxn = self.env.write_txn()?;
+        self.main
+            .remap_types::<Str, Bytes>()
+            .delete(&mut wtxn, main_key::SEARCH_CACHE_STAMP_KEY)?;
+        SharedSearchCache::invalidate(self);
+        Ok(wtxn)
     }
 
     /// Create a read transaction to be able to read the index.
@@ -296,6 +483,20 @@ impl Index {
         self.env.path()
     }
 
+    /// Reads the docids of the words and of the prefixes starting with the given prefixes,
+    /// for the first searches after a restart not to wait for the pages to be read from the disk.
+    ///
+    /// When `populate_shared_cache` is set, the docids are also kept in memory and used by the
+    /// searches of the process until the next write to the index, see [`SharedSearchCache`].
+    pub fn warm_up_search_cache(
+        &self,
+        wtxn: &mut RwTxn,
+        prefixes: &[String],
+        populate_shared_cache: bool,
+    ) -> Result<WarmUpStats> {
+        SharedSearchCache::warm_up(self, wtxn, prefixes, populate_shared_cache)
+    }
+
     /// Returns the size used by the index without the cached pages.
     pub fn used_size(&self) -> Result<u64> {
         Ok(self.env.non_free_pages_size()?)
@@ -306,6 +507,18 @@ impl Index {
         Ok(self.env.real_disk_size()?)
     }
 
+    /// Returns the LMDB statistics of every database of the index, by database name.
+    pub fn database_stats(&self, rtxn: &RoTxn) -> Result<BTreeMap<String, DatabaseStats>> {
+        let mut stats = BTreeMap::new();
+        for name in db_name::ALL {
+            let database = self.env.open_database::<Unspecified, Unspecified>(rtxn, Some(name))?;
+            if let Some(database) = database {
+                stats.insert(name.to_string(), database.stat(rtxn)?.into());
+            }
+        }
+        Ok(stats)
+    }
+
     /// Returns the map size the underlying environment was opened with, in bytes.
     ///
     /// This value does not represent the current on-disk size of the index.
@@ -388,6 +601,32 @@ impl Index {
         ExternalDocumentsIds::new(self.external_documents_ids)
     }
 
+    /* document tasks */
+
+    /// Records that the task with the given uid added, updated, or deleted the document,
+    /// only keeping the `history_size` last tasks of the document.
+    pub(crate) fn push_document_task(
+        &self,
+        wtxn: &mut RwTxn,
+        external_id: &str,
+        task_uid: u32,
+        history_size: usize,
+    ) -> heed::Result<()> {
+        let mut tasks = self.document_tasks.get(wtxn, external_id)?.unwrap_or_default();
+        if tasks.first() == Some(&task_uid) {
+            return Ok(());
+        }
+        tasks.insert(0, task_uid);
+        tasks.truncate(history_size);
+        self.document_tasks.put(wtxn, external_id, &tasks)
+    }
+
+    /// Returns the uids of the last tasks that added, updated, or deleted the document,
+    /// newest first. Only the tasks processed while the history was recorded are returned.
+    pub fn document_tasks(&self, rtxn: &RoTxn, external_id: &str) -> heed::Result<Vec<u32>> {
+        Ok(self.document_tasks.get(rtxn, external_id)?.unwrap_or_default())
+    }
+
     /* fields ids map */
 
     /// Writes the fields ids map which associate the documents keys with an internal field id
@@ -801,7 +1040,8 @@ impl Index {
 
     /// Returns the user defined faceted fields names.
     ///
-    /// The user faceted fields are the union of all the filterable, sortable, distinct, and Asc/Desc fields.
+    /// The user faceted fields are the union of all the filterable, sortable, distinct, Asc/Desc,
+    /// and random seed fields.
     pub fn user_defined_faceted_fields(&self, rtxn: &RoTxn) -> Result<HashSet<String>> {
         let filterable_fields = self.filterable_fields(rtxn)?;
         let sortable_fields = self.sortable_fields(rtxn)?;
@@ -809,6 +1049,7 @@ impl Index {
         let asc_desc_fields =
             self.criteria(rtxn)?.into_iter().filter_map(|criterion| match criterion {
                 Criterion::Asc(field) | Criterion::Desc(field) => Some(field),
+                Criterion::Random { seed: RandomSeed::Field(field), .. } => Some(field),
                 _otherwise => None,
             });
 
@@ -851,6 +1092,19 @@ impl Index {
         }
     }
 
+    /// Retrieve all the documents which contain this boolean for this field id
+    pub fn bool_faceted_documents_ids(
+        &self,
+        rtxn: &RoTxn,
+        field_id: FieldId,
+        value: bool,
+    ) -> heed::Result<RoaringBitmap> {
+        match self.facet_id_bool_docids.get(rtxn, &(field_id, value))? {
+            Some(docids) => Ok(docids),
+            None => Ok(RoaringBitmap::new()),
+        }
+    }
+
     /// Retrieve all the documents which contain this field id and that is considered empty
     pub fn empty_faceted_documents_ids(
         &self,
@@ -917,11 +1171,38 @@ impl Index {
             .remap_types::<Str, SerdeJson<Vec<Criterion>>>()
             .get(rtxn, main_key::CRITERIA_KEY)?
         {
-            Some(criteria) => Ok(criteria),
+            // older versions and dumps could store repeated ranking rules
+            Some(criteria) => Ok(normalize_criteria(criteria)),
             None => Ok(default_criteria()),
         }
     }
 
+    pub(crate) fn put_criteria_max_buckets(
+        &self,
+        wtxn: &mut RwTxn,
+        max_buckets: &BTreeMap<String, usize>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<&BTreeMap<String, usize>>>().put(
+            wtxn,
+            main_key::CRITERIA_MAX_BUCKETS_KEY,
+            &max_buckets,
+        )
+    }
+
+    pub(crate) fn delete_criteria_max_buckets(&self, wtxn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::CRITERIA_MAX_BUCKETS_KEY)
+    }
+
+    /// The maximum number of buckets of the ranking rules, by the criterion they are created from
+    /// as written by its `Display` implementation.
+    pub fn criteria_max_buckets(&self, rtxn: &RoTxn) -> heed::Result<BTreeMap<String, usize>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<BTreeMap<String, usize>>>()
+            .get(rtxn, main_key::CRITERIA_MAX_BUCKETS_KEY)?
+            .unwrap_or_default())
+    }
+
     /* words fst */
 
     /// Writes the FST which is the words dictionary of the engine.
@@ -1145,20 +1426,108 @@ impl Index {
         self.word_docids.remap_data_type::<RoaringBitmapLenCodec>().get(rtxn, word)
     }
 
+    /// Returns the words of the dictionary starting with the prefix, or all of them, along with
+    /// the number of documents containing them, the most frequent words first.
+    ///
+    /// The numbers of documents are read from the headers of the bitmaps, which are only decoded
+    /// for the words found in both the exact and the non-exact attributes.
+    pub fn word_frequencies(
+        &self,
+        rtxn: &RoTxn,
+        prefix: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<(String, u64)>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let fst = self.words_fst(rtxn)?;
+        let automaton = fst::automaton::Str::new(prefix.unwrap_or_default()).starts_with();
+        let mut stream = fst.search(automaton).into_stream();
+
+        // the least frequent of the kept words is on top, the greatest word among the ties
+        let mut kept = BinaryHeap::new();
+        while let Some(word) = stream.next() {
+            let word = std::str::from_utf8(word)?;
+            let count = self.word_frequency(rtxn, word)?;
+            if count == 0 {
+                continue;
+            }
+            if kept.len() == limit {
+                let Some(Reverse((min_count, Reverse(max_word)))) = kept.peek() else { break };
+                if (count, Reverse(word)) <= (*min_count, Reverse(max_word.as_str())) {
+                    continue;
+                }
+                kept.pop();
+            }
+            kept.push(Reverse((count, Reverse(word.to_string()))));
+        }
+
+        let mut frequencies: Vec<_> =
+            kept.into_iter().map(|Reverse((count, Reverse(word)))| (word, count)).collect();
+        frequencies.sort_unstable_by(|(aw, ac), (bw, bc)| bc.cmp(ac).then(aw.cmp(bw)));
+        Ok(frequencies)
+    }
+
+    /// The number of documents containing the word, in any attribute.
+    fn word_frequency(&self, rtxn: &RoTxn, word: &str) -> Result<u64> {
+        let len_db = |db: Database<Str, CboRoaringBitmapCodec>| {
+            db.remap_data_type::<CboRoaringBitmapLenCodec>().get(rtxn, word)
+        };
+        match (len_db(self.word_docids)?, len_db(self.exact_word_docids)?) {
+            (Some(count), None) | (None, Some(count)) => Ok(count),
+            (None, None) => Ok(0),
+            (Some(_), Some(_)) => {
+                let docids = self.word_docids.get(rtxn, word)?.unwrap_or_default();
+                let exact_docids = self.exact_word_docids.get(rtxn, word)?.unwrap_or_default();
+                Ok((docids | exact_docids).len())
+            }
+        }
+    }
+
     /* documents */
 
+    /// Returns the obkv of a document, decrypted if the index is encrypted.
+    pub(crate) fn document_bytes<'t>(
+        &self,
+        rtxn: &'t RoTxn,
+        docid: DocumentId,
+    ) -> Result<Option<Cow<'t, [u8]>>> {
+        let Some(bytes) = self.documents.remap_data_type::<Bytes>().get(rtxn, &docid)? else {
+            return Ok(None);
+        };
+        match &self.encryption_key {
+            Some(key) => Ok(Some(Cow::Owned(key.decrypt(&docid.to_be_bytes(), bytes)?))),
+            None => Ok(Some(Cow::Borrowed(bytes))),
+        }
+    }
+
+    /// Writes the obkv of a document, encrypted if the index is encrypted.
+    pub(crate) fn put_document_bytes(
+        &self,
+        wtxn: &mut RwTxn,
+        docid: DocumentId,
+        obkv: &[u8],
+    ) -> Result<()> {
+        let documents = self.documents.remap_data_type::<Bytes>();
+        match &self.encryption_key {
+            Some(key) => documents.put(wtxn, &docid, &key.encrypt(&docid.to_be_bytes(), obkv)?)?,
+            None => documents.put(wtxn, &docid, obkv)?,
+        }
+        Ok(())
+    }
+
     /// Returns an iterator over the requested documents. The next item will be an error if a document is missing.
     pub fn iter_documents<'a, 't: 'a>(
         &'a self,
         rtxn: &'t RoTxn,
         ids: impl IntoIterator<Item = DocumentId> + 'a,
-    ) -> Result<impl Iterator<Item = Result<(DocumentId, obkv::KvReaderU16<'t>)>> + 'a> {
+    ) -> Result<impl Iterator<Item = Result<(DocumentId, StoredDocument<'t>)>> + 'a> {
         Ok(ids.into_iter().map(move |id| {
-            let kv = self
-                .documents
-                .get(rtxn, &id)?
+            let bytes = self
+                .document_bytes(rtxn, id)?
                 .ok_or(UserError::UnknownInternalDocumentId { document_id: id })?;
-            Ok((id, kv))
+            Ok((id, StoredDocument(bytes)))
         }))
     }
 
@@ -1167,7 +1536,7 @@ impl Index {
         &self,
         rtxn: &'t RoTxn,
         ids: impl IntoIterator<Item = DocumentId>,
-    ) -> Result<Vec<(DocumentId, obkv::KvReaderU16<'t>)>> {
+    ) -> Result<Vec<(DocumentId, StoredDocument<'t>)>> {
         self.iter_documents(rtxn, ids)?.collect()
     }
 
@@ -1175,7 +1544,7 @@ impl Index {
     pub fn all_documents<'a, 't: 'a>(
         &'a self,
         rtxn: &'t RoTxn,
-    ) -> Result<impl Iterator<Item = Result<(DocumentId, obkv::KvReaderU16<'t>)>> + 'a> {
+    ) -> Result<impl Iterator<Item = Result<(DocumentId, StoredDocument<'t>)>> + 'a> {
         self.iter_documents(rtxn, self.documents_ids(rtxn)?)
     }
 
@@ -1199,7 +1568,7 @@ impl Index {
         })?;
         Ok(self.iter_documents(rtxn, ids)?.map(move |entry| -> Result<_> {
             let (_docid, obkv) = entry?;
-            match primary_key.document_id(&obkv, &fields)? {
+            match primary_key.document_id(&obkv.reader(), &fields)? {
                 Ok(document_id) => Ok(document_id),
                 Err(_) => Err(InternalError::DocumentsError(
                     crate::documents::Error::InvalidDocumentFormat,
@@ -1209,6 +1578,21 @@ impl Index {
         }))
     }
 
+    /// Returns the external ids of the requested documents, in the same order.
+    /// Returns an error if a document is missing.
+    pub fn external_ids_of(
+        &self,
+        rtxn: &RoTxn,
+        ids: impl IntoIterator<Item = DocumentId>,
+    ) -> Result<Vec<String>> {
+        self.external_id_of(rtxn, ids)?.into_iter().collect()
+    }
+
+    /// Returns the internal id of the document with the given external id, if it exists.
+    pub fn internal_id_of(&self, rtxn: &RoTxn, external_id: &str) -> Result<Option<DocumentId>> {
+        Ok(self.external_documents_ids().get(rtxn, external_id)?)
+    }
+
     pub fn facets_distribution<'a>(&'a self, rtxn: &'a RoTxn) -> FacetDistribution<'a> {
         FacetDistribution::new(rtxn, self)
     }
@@ -1263,6 +1647,33 @@ impl Index {
         }
     }
 
+    /// Whether the booleans of this index are stored in the `facet_id_bool_docids` database.
+    ///
+    /// The indexes created before this database existed only know their booleans as the
+    /// `"true"` and `"false"` facet strings, until all their documents are cleared.
+    pub fn boolean_facets(&self, txn: &RoTxn) -> heed::Result<bool> {
+        Ok(self.main.remap_types::<Str, U8>().get(txn, main_key::BOOLEAN_FACETS)?.is_some())
+    }
+
+    pub(crate) fn put_boolean_facets(&self, txn: &mut RwTxn) -> heed::Result<()> {
+        self.main.remap_types::<Str, U8>().put(txn, main_key::BOOLEAN_FACETS, &1)
+    }
+
+    /// How the string facet values of this index are folded.
+    ///
+    /// The indexes created before the full case folding only lowercase their values,
+    /// until all their documents are cleared.
+    pub fn case_folding(&self, txn: &RoTxn) -> heed::Result<CaseFolding> {
+        match self.main.remap_types::<Str, U8>().get(txn, main_key::FULL_CASE_FOLDING)? {
+            Some(_) => Ok(CaseFolding::Full),
+            None => Ok(CaseFolding::Lowercase),
+        }
+    }
+
+    pub(crate) fn put_full_case_folding(&self, txn: &mut RwTxn) -> heed::Result<()> {
+        self.main.remap_types::<Str, U8>().put(txn, main_key::FULL_CASE_FOLDING, &1)
+    }
+
     pub(crate) fn put_authorize_typos(&self, txn: &mut RwTxn, flag: bool) -> heed::Result<()> {
         // It is not possible to put a bool in heed with OwnedType, so we put a u8 instead. We
         // identify 0 as being false, and anything else as true. The absence of a value is true,
@@ -1362,6 +1773,38 @@ impl Index {
         self.main.remap_key_type::<Str>().delete(txn, main_key::EXACT_ATTRIBUTES)
     }
 
+    /// Returns the strict attributes: attributes only matching the query words as they are
+    /// typed, without typos, prefixes, nor splitting.
+    pub fn strict_attributes<'t>(&self, txn: &'t RoTxn) -> Result<Vec<&'t str>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeBincode<Vec<&str>>>()
+            .get(txn, main_key::STRICT_ATTRIBUTES)?
+            .unwrap_or_default())
+    }
+
+    /// Returns the list of strict attributes field ids.
+    pub fn strict_attributes_ids(&self, txn: &RoTxn) -> Result<HashSet<FieldId>> {
+        let attrs = self.strict_attributes(txn)?;
+        let fid_map = self.fields_ids_map(txn)?;
+        Ok(attrs.iter().filter_map(|attr| fid_map.id(attr)).collect())
+    }
+
+    /// Writes the strict attributes to the database.
+    pub(crate) fn put_strict_attributes(&self, txn: &mut RwTxn, attrs: &[&str]) -> Result<()> {
+        self.main.remap_types::<Str, SerdeBincode<&[&str]>>().put(
+            txn,
+            main_key::STRICT_ATTRIBUTES,
+            &attrs,
+        )?;
+        Ok(())
+    }
+
+    /// Clears the strict attributes from the store.
+    pub(crate) fn delete_strict_attributes(&self, txn: &mut RwTxn) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(txn, main_key::STRICT_ATTRIBUTES)
+    }
+
     pub fn max_values_per_facet(&self, txn: &RoTxn) -> heed::Result<Option<u64>> {
         self.main.remap_types::<Str, BEU64>().get(txn, main_key::MAX_VALUES_PER_FACET)
     }
@@ -1510,6 +1953,431 @@ impl Index {
     pub(crate) fn delete_search_cutoff(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
         self.main.remap_key_type::<Str>().delete(wtxn, main_key::SEARCH_CUTOFF)
     }
+
+    /// Stores the search parameters used when a search request doesn't specify them.
+    ///
+    /// The engine doesn't interpret them: they are kept as provided by the user.
+    pub(crate) fn put_search_defaults(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        defaults: &serde_json::Value,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<serde_json::Value>>().put(
+            wtxn,
+            main_key::SEARCH_DEFAULTS,
+            defaults,
+        )
+    }
+
+    pub fn search_defaults(&self, rtxn: &RoTxn<'_>) -> Result<Option<serde_json::Value>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<serde_json::Value>>()
+            .get(rtxn, main_key::SEARCH_DEFAULTS)?)
+    }
+
+    pub(crate) fn delete_search_defaults(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::SEARCH_DEFAULTS)
+    }
+
+    /// The length under which a number ending the query is not considered as a prefix.
+    ///
+    /// `0` means that numbers are always used as prefixes, like any other word.
+    pub fn min_numeric_prefix_length(&self, rtxn: &RoTxn<'_>) -> heed::Result<u8> {
+        Ok(self
+            .main
+            .remap_types::<Str, U8>()
+            .get(rtxn, main_key::MIN_NUMERIC_PREFIX_LENGTH)?
+            .unwrap_or(DEFAULT_MIN_NUMERIC_PREFIX_LENGTH))
+    }
+
+    pub(crate) fn put_min_numeric_prefix_length(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        val: u8,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, U8>().put(wtxn, main_key::MIN_NUMERIC_PREFIX_LENGTH, &val)
+    }
+
+    pub(crate) fn delete_min_numeric_prefix_length(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+    ) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::MIN_NUMERIC_PREFIX_LENGTH)
+    }
+
+    /// Stores the named sets of search parameters a search request can reference.
+    ///
+    /// Like the search defaults, the engine doesn't interpret them.
+    pub(crate) fn put_search_presets(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        presets: &BTreeMap<String, serde_json::Value>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<BTreeMap<String, serde_json::Value>>>().put(
+            wtxn,
+            main_key::SEARCH_PRESETS,
+            presets,
+        )
+    }
+
+    pub fn search_presets(
+        &self,
+        rtxn: &RoTxn<'_>,
+    ) -> heed::Result<BTreeMap<String, serde_json::Value>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<BTreeMap<String, serde_json::Value>>>()
+            .get(rtxn, main_key::SEARCH_PRESETS)?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn delete_search_presets(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::SEARCH_PRESETS)
+    }
+
+    /// Whether the documents can contain top-level fields starting with `_` other than
+    /// `_geo` and `_vectors`, which are otherwise reserved.
+    pub fn allow_underscore_fields(&self, rtxn: &RoTxn<'_>) -> heed::Result<bool> {
+        // The bool is stored as a u8, the absence of a value means the fields are reserved.
+        match self.main.remap_types::<Str, U8>().get(rtxn, main_key::ALLOW_UNDERSCORE_FIELDS)? {
+            Some(0) | None => Ok(false),
+            Some(_) => Ok(true),
+        }
+    }
+
+    pub(crate) fn put_allow_underscore_fields(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        allow: bool,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, U8>().put(
+            wtxn,
+            main_key::ALLOW_UNDERSCORE_FIELDS,
+            &(allow as u8),
+        )
+    }
+
+    pub(crate) fn delete_allow_underscore_fields(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+    ) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::ALLOW_UNDERSCORE_FIELDS)
+    }
+
+    pub(crate) fn put_localized_attributes_rules(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        rules: &[LocalizedAttributesRule],
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::LOCALIZED_ATTRIBUTES_RULES,
+            &rules,
+        )
+    }
+
+    /// The locales of the attributes, the first rule matching an attribute applies.
+    pub fn localized_attributes_rules(
+        &self,
+        rtxn: &RoTxn<'_>,
+    ) -> heed::Result<Option<Vec<LocalizedAttributesRule>>> {
+        self.main
+            .remap_types::<Str, SerdeJson<Vec<LocalizedAttributesRule>>>()
+            .get(rtxn, main_key::LOCALIZED_ATTRIBUTES_RULES)
+    }
+
+    pub(crate) fn delete_localized_attributes_rules(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+    ) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::LOCALIZED_ATTRIBUTES_RULES)
+    }
+
+    /// Whether the formatted numbers, like `1,000.50`, are indexed and searched
+    /// under their canonical form, like `1000.5`.
+    pub fn number_normalization(&self, rtxn: &RoTxn<'_>) -> heed::Result<bool> {
+        // The bool is stored as a u8, the absence of a value means the numbers are kept as is.
+        match self.main.remap_types::<Str, U8>().get(rtxn, main_key::NUMBER_NORMALIZATION)? {
+            Some(0) | None => Ok(false),
+            Some(_) => Ok(true),
+        }
+    }
+
+    pub(crate) fn put_number_normalization(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        enabled: bool,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, U8>().put(
+            wtxn,
+            main_key::NUMBER_NORMALIZATION,
+            &(enabled as u8),
+        )
+    }
+
+    pub(crate) fn delete_number_normalization(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::NUMBER_NORMALIZATION)
+    }
+
+    /// Whether the phrases can match words spanning the boundary between two attributes
+    /// that are adjacent in the searchable attributes order.
+    pub fn phrase_across_fields(&self, rtxn: &RoTxn<'_>) -> heed::Result<bool> {
+        // The bool is stored as a u8, the absence of a value means the phrases stay within a field.
+        match self.main.remap_types::<Str, U8>().get(rtxn, main_key::PHRASE_ACROSS_FIELDS)? {
+            Some(0) | None => Ok(false),
+            Some(_) => Ok(true),
+        }
+    }
+
+    pub(crate) fn put_phrase_across_fields(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        enabled: bool,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, U8>().put(
+            wtxn,
+            main_key::PHRASE_ACROSS_FIELDS,
+            &(enabled as u8),
+        )
+    }
+
+    pub(crate) fn delete_phrase_across_fields(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::PHRASE_ACROSS_FIELDS)
+    }
+
+    /// The locales whose compound words are split into the words of the index at search time.
+    ///
+    /// They only apply to the attributes localized in one of them by the localized attributes rules.
+    pub fn decompounding_locales(&self, rtxn: &RoTxn<'_>) -> heed::Result<BTreeSet<Locale>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<BTreeSet<Locale>>>()
+            .get(rtxn, main_key::DECOMPOUNDING_LOCALES)?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn put_decompounding_locales(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        locales: &BTreeSet<Locale>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<BTreeSet<Locale>>>().put(
+            wtxn,
+            main_key::DECOMPOUNDING_LOCALES,
+            locales,
+        )
+    }
+
+    pub(crate) fn delete_decompounding_locales(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::DECOMPOUNDING_LOCALES)
+    }
+
+    /// The types hinted for the values of the attributes, changing how their strings are faceted.
+    pub fn facet_types(&self, rtxn: &RoTxn<'_>) -> heed::Result<BTreeMap<String, FacetTypeHint>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<BTreeMap<String, FacetTypeHint>>>()
+            .get(rtxn, main_key::FACET_TYPES)?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn put_facet_types(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        facet_types: &BTreeMap<String, FacetTypeHint>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<BTreeMap<String, FacetTypeHint>>>().put(
+            wtxn,
+            main_key::FACET_TYPES,
+            facet_types,
+        )
+    }
+
+    pub(crate) fn delete_facet_types(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::FACET_TYPES)
+    }
+
+    /// The ids of the fields whose strings are also faceted as dates, including the nested fields
+    /// of the attributes hinted as dates.
+    pub fn date_fields_ids(&self, rtxn: &RoTxn<'_>) -> Result<HashSet<FieldId>> {
+        let date_attributes: Vec<_> = self
+            .facet_types(rtxn)?
+            .into_iter()
+            .filter_map(|(attribute, hint)| (hint == FacetTypeHint::Date).then_some(attribute))
+            .collect();
+        // most indexes don't have any date attribute, the fields ids map is then never read
+        if date_attributes.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let fields_ids_map = self.fields_ids_map(rtxn)?;
+        Ok(fields_ids_map
+            .iter()
+            .filter(|(_, name)| crate::is_faceted(name, &date_attributes))
+            .map(|(field_id, _)| field_id)
+            .collect())
+    }
+
+    /// The locales whose words are also indexed and searched under their stem.
+    pub fn stemming_locales(&self, rtxn: &RoTxn<'_>) -> heed::Result<BTreeSet<Locale>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<BTreeSet<Locale>>>()
+            .get(rtxn, main_key::STEMMING_LOCALES)?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn put_stemming_locales(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        locales: &BTreeSet<Locale>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<BTreeSet<Locale>>>().put(
+            wtxn,
+            main_key::STEMMING_LOCALES,
+            locales,
+        )
+    }
+
+    pub(crate) fn delete_stemming_locales(&self, wtxn: &mut RwTxn<'_>) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::STEMMING_LOCALES)
+    }
+
+    /// The stemming locales of the words of each field, the fields without any are not stemmed.
+    ///
+    /// A field localized by a localized attributes rule is stemmed in the stemming locales of
+    /// the rule, any other in all the stemming locales. The exact and strict attributes are
+    /// never stemmed.
+    pub fn stemmed_fields_ids(&self, rtxn: &RoTxn<'_>) -> Result<HashMap<FieldId, Vec<Locale>>> {
+        let stemming_locales = self.stemming_locales(rtxn)?;
+        // most indexes don't stem their words, the fields ids map is then never read
+        if stemming_locales.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rules = self.localized_attributes_rules(rtxn)?.unwrap_or_default();
+        let mut exact_fields_ids = self.exact_attributes_ids(rtxn)?;
+        exact_fields_ids.extend(self.strict_attributes_ids(rtxn)?);
+        let fields_ids_map = self.fields_ids_map(rtxn)?;
+        Ok(fields_ids_map
+            .iter()
+            .filter(|(field_id, _)| !exact_fields_ids.contains(field_id))
+            .filter_map(|(field_id, name)| {
+                // like for the collation, the first rule matching the attribute gives its locales
+                let locales: Vec<_> = match rules.iter().find(|rule| rule.match_str(name)) {
+                    Some(rule) => rule
+                        .locales
+                        .iter()
+                        .filter(|locale| stemming_locales.contains(locale))
+                        .copied()
+                        .collect(),
+                    None => stemming_locales.iter().copied().collect(),
+                };
+                (!locales.is_empty()).then_some((field_id, locales))
+            })
+            .collect())
+    }
+
+    /// Stores the errors of the invalid documents of the last refused payload of documents.
+    pub fn put_last_indexing_errors(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        errors: &[String],
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::LAST_INDEXING_ERRORS,
+            &errors,
+        )
+    }
+
+    /// The errors of the invalid documents of the last refused payload of documents.
+    pub fn last_indexing_errors(&self, rtxn: &RoTxn<'_>) -> heed::Result<Vec<String>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<Vec<String>>>()
+            .get(rtxn, main_key::LAST_INDEXING_ERRORS)?
+            .unwrap_or_default())
+    }
+
+    /* facet entries counts */
+
+    fn facet_entries_counts_key(facet_type: FacetType) -> &'static str {
+        match facet_type {
+            FacetType::Number => main_key::FACET_NUMBER_ENTRIES_COUNTS,
+            FacetType::String => main_key::FACET_STRING_ENTRIES_COUNTS,
+        }
+    }
+
+    /// Writes the number of facet values of each field, i.e. its number of level 0 facet entries.
+    pub(crate) fn put_facet_entries_counts(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        facet_type: FacetType,
+        counts: &BTreeMap<FieldId, u64>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<BTreeMap<FieldId, u64>>>().put(
+            wtxn,
+            Self::facet_entries_counts_key(facet_type),
+            counts,
+        )
+    }
+
+    /// Returns the number of facet values of each field, the fields whose facet values
+    /// have never been counted are missing.
+    pub fn facet_entries_counts(
+        &self,
+        rtxn: &RoTxn<'_>,
+        facet_type: FacetType,
+    ) -> heed::Result<BTreeMap<FieldId, u64>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<BTreeMap<FieldId, u64>>>()
+            .get(rtxn, Self::facet_entries_counts_key(facet_type))?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn delete_facet_entries_counts(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        facet_type: FacetType,
+    ) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, Self::facet_entries_counts_key(facet_type))
+    }
+
+    /* last facet update strategies */
+
+    pub(crate) fn put_last_facet_update_strategies(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+        strategies: &BTreeMap<String, FacetUpdateStrategy>,
+    ) -> heed::Result<()> {
+        self.main.remap_types::<Str, SerdeJson<_>>().put(
+            wtxn,
+            main_key::LAST_FACET_UPDATE_STRATEGIES,
+            strategies,
+        )
+    }
+
+    /// How the facet databases of each field were updated by the last indexing of documents.
+    pub fn last_facet_update_strategies(
+        &self,
+        rtxn: &RoTxn<'_>,
+    ) -> heed::Result<BTreeMap<String, FacetUpdateStrategy>> {
+        Ok(self
+            .main
+            .remap_types::<Str, SerdeJson<BTreeMap<String, FacetUpdateStrategy>>>()
+            .get(rtxn, main_key::LAST_FACET_UPDATE_STRATEGIES)?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn delete_last_facet_update_strategies(
+        &self,
+        wtxn: &mut RwTxn<'_>,
+    ) -> heed::Result<bool> {
+        self.main.remap_key_type::<Str>().delete(wtxn, main_key::LAST_FACET_UPDATE_STRATEGIES)
+    }
 }
 
 #[cfg(test)]
@@ -1524,11 +2392,11 @@ pub(crate) mod tests {
 
     use crate::documents::DocumentsBatchReader;
     use crate::error::{Error, InternalError};
-    use crate::index::{DEFAULT_MIN_WORD_LEN_ONE_TYPO, DEFAULT_MIN_WORD_LEN_TWO_TYPOS};
+    use crate::index::{db_name, DEFAULT_MIN_WORD_LEN_ONE_TYPO, DEFAULT_MIN_WORD_LEN_TWO_TYPOS};
     use crate::update::{
         self, IndexDocuments, IndexDocumentsConfig, IndexDocumentsMethod, IndexerConfig, Settings,
     };
-    use crate::{db_snap, obkv_to_json, Filter, Index, Search, SearchResult};
+    use crate::{db_snap, obkv_to_json, Criterion, Filter, Index, Search, SearchResult};
 
     pub(crate) struct TempIndex {
         pub inner: Index,
@@ -1682,6 +2550,82 @@ pub(crate) mod tests {
         assert!(matches!(err, Error::InternalError(InternalError::AbortedIndexation)));
     }
 
+    #[test]
+    fn database_stats() {
+        let index = TempIndex::new();
+        index
+            .add_documents(documents!([
+                { "id": 1, "name": "kevin" },
+                { "id": 2, "name": "bob", "age": 20 },
+                { "id": 3, "name": "kefir", "age": 4 },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let stats = index.database_stats(&rtxn).unwrap();
+        assert_eq!(stats.len(), db_name::ALL.len());
+        assert_eq!(stats[db_name::DOCUMENTS].entries, 3);
+        assert_eq!(stats[db_name::EXTERNAL_DOCUMENTS_IDS].entries, 3);
+        assert_eq!(stats[db_name::VECTOR_ARROY].entries, 0);
+    }
+
+    #[test]
+    fn word_frequencies() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|s| {
+                s.set_searchable_fields(vec![S("text"), S("title")]);
+                s.set_exact_attributes(hashset! { S("title") });
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 1, "text": "one two three", "title": "one" },
+                { "id": 2, "text": "two three" },
+                { "id": 3, "text": "three", "title": "two" },
+                { "id": 4, "text": "three thirteen" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let frequencies =
+            |prefix: Option<&str>, limit| index.word_frequencies(&rtxn, prefix, limit).unwrap();
+
+        // the exact and non-exact documents of a word are counted once
+        assert_eq!(
+            frequencies(None, 10),
+            vec![(S("three"), 4), (S("two"), 3), (S("one"), 1), (S("thirteen"), 1)]
+        );
+        assert_eq!(frequencies(None, 3), vec![(S("three"), 4), (S("two"), 3), (S("one"), 1)]);
+        assert_eq!(frequencies(Some("th"), 10), vec![(S("three"), 4), (S("thirteen"), 1)]);
+        assert!(frequencies(Some("four"), 10).is_empty());
+        assert!(frequencies(None, 0).is_empty());
+    }
+
+    #[test]
+    fn internal_and_external_ids() {
+        let index = TempIndex::new();
+        index
+            .add_documents(documents!([
+                { "id": "kevin", "name": "kevin" },
+                { "id": "bob", "name": "bob" },
+                { "id": "kefir", "name": "kefir" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        for external_id in ["kevin", "bob", "kefir"] {
+            let internal_id = index.internal_id_of(&rtxn, external_id).unwrap().unwrap();
+            let external_ids = index.external_ids_of(&rtxn, [internal_id]).unwrap();
+            assert_eq!(external_ids, vec![external_id.to_string()]);
+        }
+        assert_eq!(index.internal_id_of(&rtxn, "doggo").unwrap(), None);
+
+        let external_ids = index.external_ids_of(&rtxn, [2, 0, 1]).unwrap();
+        assert_eq!(external_ids, vec!["kefir", "kevin", "bob"]);
+        assert!(index.external_ids_of(&rtxn, [3]).is_err());
+    }
+
     #[test]
     fn initial_field_distribution() {
         let index = TempIndex::new();
@@ -1766,6 +2710,30 @@ pub(crate) mod tests {
         assert!(!index.authorize_typos(&txn).unwrap());
     }
 
+    #[test]
+    fn normalize_repeated_criteria() {
+        let index = TempIndex::new();
+        // older versions and dumps could store repeated ranking rules, they are ignored when read
+        index
+            .update_settings(|settings| {
+                settings.set_criteria(vec![
+                    Criterion::Sort,
+                    Criterion::Words,
+                    Criterion::Asc(S("price")),
+                    Criterion::Typo,
+                    Criterion::Desc(S("price")),
+                    Criterion::Sort,
+                ]);
+            })
+            .unwrap();
+
+        let txn = index.read_txn().unwrap();
+        assert_eq!(
+            index.criteria(&txn).unwrap(),
+            vec![Criterion::Sort, Criterion::Words, Criterion::Asc(S("price")), Criterion::Typo]
+        );
+    }
+
     #[test]
     fn set_min_word_len_for_typos() {
         let index = TempIndex::new();
@@ -2253,8 +3221,9 @@ pub(crate) mod tests {
         "###);
 
         let rtxn = index.read_txn().unwrap();
-        let (_docid, obkv) = index.documents(&rtxn, [0]).unwrap()[0];
-        let json = obkv_to_json(&[0, 1, 2], &index.fields_ids_map(&rtxn).unwrap(), obkv).unwrap();
+        let (_docid, obkv) = index.documents(&rtxn, [0]).unwrap().remove(0);
+        let json =
+            obkv_to_json(&[0, 1, 2], &index.fields_ids_map(&rtxn).unwrap(), obkv.reader()).unwrap();
         insta::assert_debug_snapshot!(json, @r###"
         {
             "primary_key": Number(30),
@@ -2262,8 +3231,9 @@ pub(crate) mod tests {
         "###);
 
         // Furthermore, when we retrieve document 34, it is not the result of merging 35 with 34
-        let (_docid, obkv) = index.documents(&rtxn, [2]).unwrap()[0];
-        let json = obkv_to_json(&[0, 1, 2], &index.fields_ids_map(&rtxn).unwrap(), obkv).unwrap();
+        let (_docid, obkv) = index.documents(&rtxn, [2]).unwrap().remove(0);
+        let json =
+            obkv_to_json(&[0, 1, 2], &index.fields_ids_map(&rtxn).unwrap(), obkv.reader()).unwrap();
         insta::assert_debug_snapshot!(json, @r###"
         {
             "primary_key": Number(34),
@@ -2425,15 +3395,26 @@ pub(crate) mod tests {
             matching_words: _,
             candidates: _,
             document_scores: _,
+            document_bucket_paths: _,
             mut documents_ids,
             degraded: _,
             used_negative_operator: _,
+            numeric_prefix_disabled: _,
+            query_truncated: _,
+            timings: _,
+            metrics: _,
+            skipped_ranking_rules: _,
+            query_tokens: _,
+            query_graph: _,
+            trace: _,
+            bucket_stats: _,
+            explanation: _,
         } = search.execute().unwrap();
         let primary_key_id = index.fields_ids_map(&rtxn).unwrap().id("primary_key").unwrap();
         documents_ids.sort_unstable();
         let docs = index.documents(&rtxn, documents_ids).unwrap();
         let mut all_ids = HashSet::new();
-        for (_docid, obkv) in docs {
+        for (_docid, obkv) in &docs {
             let id = obkv.get(primary_key_id).unwrap();
             assert!(all_ids.insert(id));
         }
@@ -2503,4 +3484,57 @@ pub(crate) mod tests {
 
         db_snap!(index, geo_faceted_documents_ids); // ensure that no documents were inserted
     }
+
+    #[test]
+    fn encrypted_documents() {
+        use crate::encryption::EncryptionError;
+        use crate::EncryptionKey;
+
+        let key: EncryptionKey = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=".parse().unwrap();
+        let other_key: EncryptionKey =
+            "Hx4dHBsaGRgXFhUUExIREA8ODQwLCgkIBwYFBAMCAQA=".parse().unwrap();
+        let dir = TempDir::new_in(".").unwrap();
+        let open = |key: Option<EncryptionKey>| {
+            let mut options = EnvOpenOptions::new();
+            options.map_size(4096 * 2000);
+            let now = time::OffsetDateTime::now_utc();
+            Index::new_with_encryption_key(options, dir.path(), now, now, key)
+        };
+
+        let index = open(Some(key.clone())).unwrap();
+        assert!(index.is_encrypted());
+        let mut wtxn = index.write_txn().unwrap();
+        let document = serde_json::json!({ "id": 1, "title": "kefir", "secret": "correct horse battery staple" });
+        crate::IndexerBuilder::new(&index)
+            .primary_key("id")
+            .searchable_fields(["title"])
+            .add_documents([document.as_object().unwrap().clone()])
+            .execute(&mut wtxn)
+            .unwrap();
+        wtxn.commit().unwrap();
+        index.prepare_for_closing().wait();
+
+        // the documents are decrypted when the index is reopened with the same key
+        let index = open(Some(key)).unwrap();
+        let rtxn = index.read_txn().unwrap();
+        let fields_ids_map = index.fields_ids_map(&rtxn).unwrap();
+        let fields: Vec<_> = fields_ids_map.ids().collect();
+        let docs = index.documents(&rtxn, [0]).unwrap();
+        let document = obkv_to_json(&fields, &fields_ids_map, docs[0].1.reader()).unwrap();
+        assert_eq!(document["secret"], "correct horse battery staple");
+        drop(rtxn);
+        index.prepare_for_closing().wait();
+
+        assert!(matches!(
+            open(Some(other_key)),
+            Err(Error::InternalError(InternalError::Encryption(EncryptionError::WrongKey)))
+        ));
+        assert!(matches!(
+            open(None),
+            Err(Error::InternalError(InternalError::Encryption(EncryptionError::MissingKey)))
+        ));
+
+        let data = std::fs::read(dir.path().join("data.mdb")).unwrap();
+        assert!(!data.windows(28).any(|w| w == b"correct horse battery staple"));
+    }
 }
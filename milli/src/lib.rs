@@ -9,18 +9,29 @@ pub static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
 pub mod documents;
 
 mod asc_desc;
+pub mod case_folding;
+pub mod collation;
 mod criterion;
+pub mod encryption;
 mod error;
 mod external_documents_ids;
+#[doc(hidden)]
 pub mod facet;
 mod fields_ids_map;
+#[doc(hidden)]
 pub mod heed_codec;
 pub mod index;
+mod localized_attributes_rules;
+pub mod number_normalization;
 pub mod order_by_map;
+pub mod outbound;
 pub mod prompt;
 pub mod proximity;
 pub mod score_details;
 mod search;
+pub mod stemming;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 pub mod update;
 pub mod vector;
 
@@ -33,19 +44,31 @@ use std::convert::{TryFrom, TryInto};
 use std::fmt;
 use std::hash::BuildHasherDefault;
 
-use charabia::normalizer::{CharNormalizer, CompatibilityDecompositionNormalizer};
 pub use filter_parser::{Condition, FilterCondition, Span, Token};
 use fxhash::{FxHasher32, FxHasher64};
 pub use grenad::CompressionType;
 pub use search::new::{
-    execute_search, filtered_universe, DefaultSearchLogger, GeoSortStrategy, SearchContext,
-    SearchLogger, VisualSearchLogger,
+    BucketStep, ExclusionReason, GeoSortStrategy, QueryEdgeDescription, QueryGraphDescription,
+    QueryNodeDescription, QueryNodeKind, QueryTermDerivations, QueryToken, QueryTokenKind,
+    RankingRuleBucketStats, SearchExplanation, SearchTrace, SearchTraceEvent, SkipReason,
+    SkippedRankingRule, WarmUpStats, MAX_RECORDED_BUCKETS,
+};
+// The internals of the search, prefer the `SearchBuilder` to search without depending on them.
+#[doc(hidden)]
+pub use search::new::{
+    execute_search, filtered_universe, BucketStatsLogger, DefaultSearchLogger, QueryGraph,
+    SearchContext, SearchLogger, StructuredSearchLogger, VisualSearchLogger,
 };
 use serde_json::Value;
 pub use {charabia as tokenizer, heed};
 
 pub use self::asc_desc::{AscDesc, AscDescError, Member, SortError};
-pub use self::criterion::{default_criteria, Criterion, CriterionError};
+pub use self::case_folding::{normalize_facet, CaseFolding};
+pub use self::criterion::{
+    default_criteria, normalize_criteria, validate_criteria, Criterion, CriterionError, LatLng,
+    RandomSeed,
+};
+pub use self::encryption::{EncryptionError, EncryptionKey};
 pub use self::error::{
     Error, FieldIdMapMissingEntry, InternalError, SerializationError, UserError,
 };
@@ -53,16 +76,24 @@ pub use self::external_documents_ids::ExternalDocumentsIds;
 pub use self::fields_ids_map::FieldsIdsMap;
 pub use self::heed_codec::{
     BEU16StrCodec, BEU32StrCodec, BoRoaringBitmapCodec, BoRoaringBitmapLenCodec,
-    CboRoaringBitmapCodec, CboRoaringBitmapLenCodec, FieldIdWordCountCodec, ObkvCodec,
-    RoaringBitmapCodec, RoaringBitmapLenCodec, StrBEU32Codec, U8StrStrCodec,
+    CboRoaringBitmapCodec, CboRoaringBitmapLenCodec, FieldIdBoolCodec, FieldIdWordCountCodec,
+    ObkvCodec, RoaringBitmapCodec, RoaringBitmapLenCodec, StrBEU32Codec, U8StrStrCodec,
     UncheckedU8StrStrCodec,
 };
-pub use self::index::Index;
-pub use self::search::facet::{FacetValueHit, SearchForFacetValues};
+pub use self::index::{DatabaseStats, Index, StoredDocument};
+pub use self::localized_attributes_rules::LocalizedAttributesRule;
+pub use self::search::facet::{
+    FacetValueHit, FacetValueHits, SearchForFacetValues, MAX_FACET_QUERY_LENGTH,
+};
 pub use self::search::{
-    FacetDistribution, Filter, FormatOptions, MatchBounds, MatcherBuilder, MatchingWords, OrderBy,
-    Search, SearchResult, SemanticSearch, TermsMatchingStrategy, DEFAULT_VALUES_PER_FACET,
+    is_wildcard_query, FacetDistribution, Filter, FilterAssist, FilterSuggestion,
+    FilterSuggestions, FilterTokenKind, FormatOptions, MatchBounds, MatcherBuilder, MatchingWords,
+    OrderBy, RelatedTerm, RelatedTerms, Search, SearchBuilder, SearchHits, SearchMetrics,
+    SearchResult, SearchTimings, SemanticSearch, SettingsOverlay, TermsMatchingStrategy, TieBreak,
+    DEFAULT_APPROXIMATION_THRESHOLD, DEFAULT_FILTER_SUGGESTIONS_LIMIT, DEFAULT_RELATED_TERMS_LIMIT,
+    DEFAULT_VALUES_PER_FACET,
 };
+pub use self::update::IndexerBuilder;
 
 pub type Result<T> = std::result::Result<T, error::Error>;
 
@@ -109,6 +140,8 @@ pub const MAX_POSITION_PER_ATTRIBUTE: u32 = u16::MAX as u32 + 1;
 pub struct TimeBudget {
     started_at: std::time::Instant,
     budget: std::time::Duration,
+    /// Set when the search must stop as soon as possible, e.g. because the client that asked for it disconnected.
+    canceled: Option<std::sync::Arc<std::sync::atomic::AtomicBool>>,
 
     /// When testing the time budget, ensuring we did more than iteration of the bucket sort can be useful.
     /// But to avoid being flaky, the only option is to add the ability to stop after a specific number of calls instead of a `Duration`.
@@ -122,6 +155,7 @@ impl fmt::Debug for TimeBudget {
             .field("started_at", &self.started_at)
             .field("budget", &self.budget)
             .field("left", &(self.budget - self.started_at.elapsed()))
+            .field("canceled", &self.is_canceled())
             .finish()
     }
 }
@@ -137,6 +171,7 @@ impl TimeBudget {
         Self {
             started_at: std::time::Instant::now(),
             budget,
+            canceled: None,
 
             #[cfg(test)]
             stop_after: None,
@@ -156,7 +191,32 @@ impl TimeBudget {
         self
     }
 
+    /// Makes the budget exceeded as soon as `canceled` is set to `true`.
+    pub fn with_cancellation(
+        mut self,
+        canceled: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    ) -> Self {
+        self.canceled = Some(canceled);
+        self
+    }
+
+    /// The flag set when the search is canceled, if the budget was given one.
+    pub fn cancellation(&self) -> Option<std::sync::Arc<std::sync::atomic::AtomicBool>> {
+        self.canceled.clone()
+    }
+
+    /// Whether the search has been canceled, regardless of the time spent.
+    pub fn is_canceled(&self) -> bool {
+        self.canceled
+            .as_ref()
+            .map_or(false, |canceled| canceled.load(std::sync::atomic::Ordering::Relaxed))
+    }
+
     pub fn exceeded(&self) -> bool {
+        if self.is_canceled() {
+            return true;
+        }
+
         #[cfg(test)]
         if let Some((current, stop_after)) = &self.stop_after {
             let current = current.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
@@ -353,8 +413,22 @@ pub fn is_faceted_by(field: &str, facet: &str) -> bool {
         && field[facet.len()..].chars().next().map(|c| c == '.').unwrap_or(true)
 }
 
-pub fn normalize_facet(original: &str) -> String {
-    CompatibilityDecompositionNormalizer.normalize_str(original.trim()).to_lowercase()
+/// The biggest integer that an `f64` can represent exactly.
+///
+/// Bigger integers are rounded to the nearest `f64` when they are stored as facet numbers,
+/// so documents with close but different huge integers can share the same facet value.
+pub const MAX_EXACT_FACET_INTEGER: u64 = 1 << 53;
+
+/// Returns the canonical representation of a facet number.
+///
+/// The facet numbers are stored with their bytes, the numbers that compare equal must
+/// then be stored in the same way: `-0.0` is stored as `0.0`.
+pub fn normalize_facet_number(number: f64) -> f64 {
+    if number == 0.0 {
+        0.0
+    } else {
+        number
+    }
 }
 
 /// Represents either a vector or an array of multiple vectors.
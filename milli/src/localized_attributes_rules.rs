@@ -0,0 +1,58 @@
+use deserr::Deserr;
+use serde::{Deserialize, Serialize};
+
+use crate::collation::{Collator, Locale};
+
+/// The locales of the attributes matching one of the patterns.
+///
+/// A pattern is either an attribute name, or an attribute name starting
+/// and/or ending with a `*` that matches any prefix and/or suffix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Deserr)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub struct LocalizedAttributesRule {
+    pub attribute_patterns: Vec<String>,
+    pub locales: Vec<Locale>,
+}
+
+impl LocalizedAttributesRule {
+    pub fn match_str(&self, attribute: &str) -> bool {
+        self.attribute_patterns.iter().any(|pattern| match_pattern(pattern, attribute))
+    }
+
+    /// The collator of the first rule matching the attribute, the default one if none matches.
+    pub fn collator_for(rules: &[LocalizedAttributesRule], attribute: &str) -> Collator {
+        rules
+            .iter()
+            .find(|rule| rule.match_str(attribute))
+            .map_or_else(Collator::default, |rule| Collator::for_locales(&rule.locales))
+    }
+}
+
+/// Whether the attribute matches the pattern, see [`LocalizedAttributesRule`].
+pub(crate) fn match_pattern(pattern: &str, attribute: &str) -> bool {
+    match (pattern.strip_prefix('*'), pattern.strip_suffix('*')) {
+        _ if pattern == "*" => true,
+        (Some(_), Some(_)) => attribute.contains(&pattern[1..pattern.len() - 1]),
+        (Some(suffix), None) => attribute.ends_with(suffix),
+        (None, Some(prefix)) => attribute.starts_with(prefix),
+        (None, None) => attribute == pattern,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attribute_patterns() {
+        assert!(match_pattern("*", "title"));
+        assert!(match_pattern("title", "title"));
+        assert!(!match_pattern("title", "subtitle"));
+        assert!(match_pattern("*title", "subtitle"));
+        assert!(match_pattern("title_*", "title_fr"));
+        assert!(!match_pattern("title_*", "subtitle_fr"));
+        assert!(match_pattern("*_fr*", "title_fr.short"));
+        assert!(!match_pattern("*_fr*", "title_en"));
+    }
+}
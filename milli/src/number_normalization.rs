@@ -0,0 +1,122 @@
+//! The normalization of the formatted numbers of the searchable attributes.
+//!
+//! When the `numberNormalization` setting is enabled, the words that are formatted numbers, like
+//! `1,000`, `1.000` or `$1,000.00`, are indexed and searched under a canonical form, like `1000`,
+//! so that a query matches a number whatever the thousands and decimal separators it is written with.
+
+use std::borrow::Cow;
+
+use charabia::{Token, TokenKind};
+
+/// The currency symbols removed around a number before it is normalized.
+const CURRENCY_SYMBOLS: &[char] = &['$', '€', '£', '¥', '₹'];
+
+/// Replaces the lemma of a word that is a formatted number by its canonical form.
+pub fn normalize_number(token: Token<'_>) -> Token<'_> {
+    if token.kind != TokenKind::Word {
+        return token;
+    }
+    match canonical_number(token.lemma()) {
+        Some(canonical) => Token { lemma: Cow::Owned(canonical), ..token },
+        None => token,
+    }
+}
+
+/// Returns the canonical form of a formatted number, `None` if the string is not a formatted
+/// number or if it is already in its canonical form.
+///
+/// The separators are guessed from the number itself:
+/// - when both `,` and `.` are used, the last one is the decimal separator,
+/// - when only one of them is used several times, it is the thousands separator,
+/// - when only one of them is used once, it is the thousands separator if it is followed by
+///   exactly three digits, and the decimal separator otherwise.
+///
+/// The canonical form only contains the digits of the integer part, followed by a `.` and the
+/// digits of the fractional part without its trailing zeros, if any remains.
+pub fn canonical_number(s: &str) -> Option<String> {
+    let number = s.trim_matches(CURRENCY_SYMBOLS);
+    let bytes = number.as_bytes();
+    if !bytes.first()?.is_ascii_digit() || !bytes.last()?.is_ascii_digit() {
+        return None;
+    }
+    if !bytes.iter().all(|b| b.is_ascii_digit() || *b == b',' || *b == b'.') {
+        return None;
+    }
+    if bytes.windows(2).any(|w| !w[0].is_ascii_digit() && !w[1].is_ascii_digit()) {
+        return None;
+    }
+
+    let commas = number.matches(',').count();
+    let dots = number.matches('.').count();
+    let (integer, fraction) = match (commas, dots) {
+        (0, 0) => (number, ""),
+        (_, 0) | (0, _) => {
+            let separator = if commas > 0 { ',' } else { '.' };
+            let (head, last_group) = number.rsplit_once(separator)?;
+            if commas + dots == 1 && last_group.len() != 3 {
+                (head, last_group)
+            } else {
+                (number, "")
+            }
+        }
+        _ => {
+            let decimal = if number.rfind(',') > number.rfind('.') { ',' } else { '.' };
+            let (integer, fraction) = number.rsplit_once(decimal)?;
+            if fraction.contains(decimal) || integer.contains(decimal) {
+                return None;
+            }
+            (integer, fraction)
+        }
+    };
+
+    let mut groups = integer.split([',', '.']);
+    let first_group = groups.next()?;
+    let grouped = integer.contains([',', '.']);
+    if grouped && (first_group.len() > 3 || groups.any(|group| group.len() != 3)) {
+        return None;
+    }
+
+    let mut canonical: String = integer.chars().filter(char::is_ascii_digit).collect();
+    let fraction = fraction.trim_end_matches('0');
+    if !fraction.is_empty() {
+        canonical.push('.');
+        canonical.push_str(fraction);
+    }
+
+    (canonical != s).then_some(canonical)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(s: &str) -> String {
+        canonical_number(s).unwrap_or_else(|| s.to_string())
+    }
+
+    #[test]
+    fn thousands_separators() {
+        for number in ["1000", "1,000", "1.000", "$1,000", "1000€", "1,000.00", "1.000,0"] {
+            assert_eq!(canonical(number), "1000", "{number}");
+        }
+        assert_eq!(canonical("1,234,567"), "1234567");
+        assert_eq!(canonical("1.234.567"), "1234567");
+    }
+
+    #[test]
+    fn decimal_separators() {
+        assert_eq!(canonical("1,000.50"), "1000.5");
+        assert_eq!(canonical("1.000,50"), "1000.5");
+        assert_eq!(canonical("3.5"), "3.5");
+        assert_eq!(canonical("3,5"), "3.5");
+        assert_eq!(canonical("12.25"), "12.25");
+    }
+
+    #[test]
+    fn not_formatted_numbers() {
+        for word in ["hello", "1,0000,000", "1,,000", "1.000.50", "1,000,5", ",100", "100.", "v1.2"]
+        {
+            assert_eq!(canonical_number(word), None, "{word}");
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Restriction of the hosts the engine may send requests to.
+//!
+//! Every outbound HTTP client (the REST embedders, the webhook, the replication) is built from an
+//! [`OutboundPolicy`]. When an allow-list is configured, the requests to hosts not matching any of
+//! its patterns fail before any connection is attempted, and so do the requests to the loopback
+//! and private addresses, unless they are explicitly allowed.
+
+use std::io;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::sync::Arc;
+
+use thiserror::Error;
+use url::{Host, Url};
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum OutboundHostNotAllowed {
+    #[error("The host `{host}` is not allowed by the outbound allow-list: {}.", format_allowlist(.allowlist))]
+    NotInAllowlist { host: String, allowlist: Vec<String> },
+    #[error("The host `{host}` is a local or private address, which is denied by the outbound allow-list: {}.", format_allowlist(.allowlist))]
+    PrivateNetwork { host: String, allowlist: Vec<String> },
+    #[error("The URL `{url}` does not contain a host, it is denied by the outbound allow-list: {}.", format_allowlist(.allowlist))]
+    MissingHost { url: String, allowlist: Vec<String> },
+}
+
+fn format_allowlist(allowlist: &[String]) -> String {
+    allowlist.iter().map(|pattern| format!("`{pattern}`")).collect::<Vec<_>>().join(", ")
+}
+
+/// The hosts the outbound requests are allowed to reach.
+///
+/// The default policy allows every host.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutboundPolicy(Option<Arc<Allowlist>>);
+
+#[derive(Debug, PartialEq, Eq)]
+struct Allowlist {
+    /// Either an exact host, or `*.` followed by a domain to allow all its subdomains.
+    patterns: Vec<String>,
+    allow_private_network: bool,
+}
+
+impl OutboundPolicy {
+    /// Restricts the outbound requests to the hosts matching one of the `patterns`.
+    ///
+    /// The loopback and private addresses are denied unless `allow_private_network` is set,
+    /// even if they match a pattern.
+    pub fn allowlist(patterns: Vec<String>, allow_private_network: bool) -> Self {
+        let patterns = patterns.into_iter().map(|pattern| pattern.trim().to_lowercase()).collect();
+        Self(Some(Arc::new(Allowlist { patterns, allow_private_network })))
+    }
+
+    /// Returns an error if the requests to this URL are not allowed.
+    pub fn check_url(&self, url: &str) -> Result<(), OutboundHostNotAllowed> {
+        let Some(allowlist) = &self.0 else { return Ok(()) };
+
+        let host = Url::parse(url).ok().and_then(|url| url.host().map(|host| host.to_owned()));
+        let Some(host) = host else {
+            return Err(OutboundHostNotAllowed::MissingHost {
+                url: url.to_string(),
+                allowlist: allowlist.patterns.clone(),
+            });
+        };
+
+        let name = match &host {
+            Host::Domain(domain) => domain.to_lowercase(),
+            Host::Ipv4(ip) => ip.to_string(),
+            Host::Ipv6(ip) => ip.to_string(),
+        };
+        if !allowlist.patterns.iter().any(|pattern| matches_pattern(pattern, &name)) {
+            return Err(OutboundHostNotAllowed::NotInAllowlist {
+                host: name,
+                allowlist: allowlist.patterns.clone(),
+            });
+        }
+
+        let private = match host {
+            Host::Domain(domain) => is_localhost(&domain),
+            Host::Ipv4(ip) => is_private_ip(IpAddr::V4(ip)),
+            Host::Ipv6(ip) => is_private_ip(IpAddr::V6(ip)),
+        };
+        if private && !allowlist.allow_private_network {
+            return Err(OutboundHostNotAllowed::PrivateNetwork {
+                host: name,
+                allowlist: allowlist.patterns.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns a builder of HTTP clients enforcing the policy when resolving the hosts,
+    /// so that an allowed domain can't resolve to a private address.
+    ///
+    /// The URLs must still be checked with [`Self::check_url`] before sending the requests.
+    pub fn agent_builder(&self) -> ureq::AgentBuilder {
+        let builder = ureq::AgentBuilder::new();
+        match &self.0 {
+            Some(allowlist) if !allowlist.allow_private_network => {
+                builder.resolver(|netloc: &str| -> io::Result<Vec<SocketAddr>> {
+                    let addrs: Vec<_> = netloc.to_socket_addrs()?.collect();
+                    if addrs.iter().any(|addr| is_private_ip(addr.ip())) {
+                        return Err(io::Error::new(
+                            io::ErrorKind::PermissionDenied,
+                            format!("`{netloc}` resolves to a local or private address"),
+                        ));
+                    }
+                    Ok(addrs)
+                })
+            }
+            _ => builder,
+        }
+    }
+
+    /// Returns an HTTP client enforcing the policy.
+    pub fn agent(&self) -> ureq::Agent {
+        self.agent_builder().build()
+    }
+}
+
+fn matches_pattern(pattern: &str, host: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(domain)
+            .map_or(false, |subdomain| subdomain.len() > 1 && subdomain.ends_with('.')),
+        None => pattern == host,
+    }
+}
+
+fn is_localhost(domain: &str) -> bool {
+    let domain = domain.trim_end_matches('.');
+    domain.eq_ignore_ascii_case("localhost") || domain.to_lowercase().ends_with(".localhost")
+}
+
+fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                // the shared address space of the carrier-grade NATs, 100.64.0.0/10
+                || (ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_private_ip(IpAddr::V4(ip)),
+            None => {
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    // unique local addresses, fc00::/7
+                    || (ip.segments()[0] & 0xfe00) == 0xfc00
+                    // link-local addresses, fe80::/10
+                    || (ip.segments()[0] & 0xffc0) == 0xfe80
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_policy_allows_everything() {
+        let policy = OutboundPolicy::default();
+        assert!(policy.check_url("http://localhost:7700/").is_ok());
+        assert!(policy.check_url("https://example.com/embed").is_ok());
+        assert!(policy.check_url("not a url").is_ok());
+    }
+
+    #[test]
+    fn patterns() {
+        let policy = OutboundPolicy::allowlist(
+            vec!["api.openai.com".to_string(), "*.Example.com".to_string()],
+            false,
+        );
+        assert!(policy.check_url("https://api.openai.com/v1/embeddings").is_ok());
+        assert!(policy.check_url("https://API.openai.com:443/").is_ok());
+        assert!(policy.check_url("https://embed.example.com/").is_ok());
+        assert!(policy.check_url("https://a.b.example.com/").is_ok());
+
+        insta::assert_snapshot!(policy.check_url("https://example.com/").unwrap_err(), @"The host `example.com` is not allowed by the outbound allow-list: `api.openai.com`, `*.example.com`.");
+        insta::assert_snapshot!(policy.check_url("https://notexample.com/").unwrap_err(), @"The host `notexample.com` is not allowed by the outbound allow-list: `api.openai.com`, `*.example.com`.");
+        insta::assert_snapshot!(policy.check_url("https://openai.com/").unwrap_err(), @"The host `openai.com` is not allowed by the outbound allow-list: `api.openai.com`, `*.example.com`.");
+        insta::assert_snapshot!(policy.check_url("/relative").unwrap_err(), @"The URL `/relative` does not contain a host, it is denied by the outbound allow-list: `api.openai.com`, `*.example.com`.");
+    }
+
+    #[test]
+    fn private_network() {
+        let patterns = vec![
+            "localhost".to_string(),
+            "127.0.0.1".to_string(),
+            "192.168.1.12".to_string(),
+            "::1".to_string(),
+            "8.8.8.8".to_string(),
+        ];
+
+        let policy = OutboundPolicy::allowlist(patterns.clone(), false);
+        insta::assert_snapshot!(policy.check_url("http://localhost:11434/").unwrap_err(), @"The host `localhost` is a local or private address, which is denied by the outbound allow-list: `localhost`, `127.0.0.1`, `192.168.1.12`, `::1`, `8.8.8.8`.");
+        assert!(policy.check_url("http://127.0.0.1/").is_err());
+        assert!(policy.check_url("http://192.168.1.12/").is_err());
+        assert!(policy.check_url("http://[::1]:7700/").is_err());
+        assert!(policy.check_url("http://8.8.8.8/").is_ok());
+
+        let policy = OutboundPolicy::allowlist(patterns, true);
+        assert!(policy.check_url("http://localhost:11434/").is_ok());
+        assert!(policy.check_url("http://127.0.0.1/").is_ok());
+        assert!(policy.check_url("http://192.168.1.12/").is_ok());
+        assert!(policy.check_url("http://[::1]:7700/").is_ok());
+        // the allow-list still applies to the private addresses
+        assert!(policy.check_url("http://10.0.0.1/").is_err());
+    }
+
+    #[test]
+    fn private_ips() {
+        for ip in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.0.1", "169.254.1.1", "0.0.0.0"] {
+            assert!(is_private_ip(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in ["100.64.0.1", "::1", "fd00::1", "fe80::1", "::ffff:127.0.0.1"] {
+            assert!(is_private_ip(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in ["8.8.8.8", "100.128.0.1", "2001:4860:4860::8888", "::ffff:8.8.8.8"] {
+            assert!(!is_private_ip(ip.parse().unwrap()), "{ip}");
+        }
+    }
+}
@@ -17,9 +17,14 @@ pub enum ScoreDetails {
     Sort(Sort),
     Vector(Vector),
     GeoSort(GeoSort),
+    Random(Random),
 
     /// Returned when we don't have the time to finish applying all the subsequent ranking-rules
     Skipped,
+
+    /// Returned after the ranking rules when the query graph was too large and some
+    /// of the alternatives of the query were not searched
+    QueryGraphDegraded(QueryGraphDegradation),
 }
 
 #[derive(Clone, Copy)]
@@ -53,7 +58,10 @@ impl ScoreDetails {
             ScoreDetails::Sort(_) => None,
             ScoreDetails::GeoSort(_) => None,
             ScoreDetails::Vector(_) => None,
+            // the shuffled buckets are all equally relevant
+            ScoreDetails::Random(_) => Some(Rank { rank: 1, max_rank: 1 }),
             ScoreDetails::Skipped => Some(Rank { rank: 0, max_rank: 1 }),
+            ScoreDetails::QueryGraphDegraded(_) => Some(Rank { rank: 1, max_rank: 1 }),
         }
     }
 
@@ -101,7 +109,10 @@ impl ScoreDetails {
             ScoreDetails::Vector(vector) => {
                 RankOrValue::Score(vector.similarity.as_ref().map(|s| *s as f64).unwrap_or(0.0f64))
             }
+            ScoreDetails::Random(_) => RankOrValue::Rank(Rank { rank: 1, max_rank: 1 }),
             ScoreDetails::Skipped => RankOrValue::Rank(Rank { rank: 0, max_rank: 1 }),
+            // does not change the score, the documents are ranked among the searched alternatives
+            ScoreDetails::QueryGraphDegraded(_) => RankOrValue::Rank(Rank { rank: 1, max_rank: 1 }),
         }
     }
 
@@ -170,6 +181,12 @@ impl ScoreDetails {
 
                     attribute_details
                         .insert("queryWordDistanceScore".into(), position.local_score().into());
+                    // the logarithmic distance bands of the query words added together,
+                    // 0 when all of them are in place
+                    attribute_details.insert(
+                        "queryWordDistanceBandSum".into(),
+                        (position.max_rank - position.rank).into(),
+                    );
                     let score = Rank::global_score([fid_details, *position].iter().copied());
                     attribute_details.insert("score".into(), score.into());
 
@@ -258,11 +275,27 @@ impl ScoreDetails {
                     details_map.insert("vectorSort".into(), details);
                     order += 1;
                 }
+                ScoreDetails::Random(random) => {
+                    let details = serde_json::json!({
+                        "order": order,
+                        "bucket": random.bucket,
+                    });
+                    details_map.insert("random".into(), details);
+                    order += 1;
+                }
                 ScoreDetails::Skipped => {
                     details_map
                         .insert("skipped".to_string(), serde_json::json!({ "order": order }));
                     order += 1;
                 }
+                ScoreDetails::QueryGraphDegraded(degradation) => {
+                    // not a ranking rule, the order is not updated
+                    let degradation_details = serde_json::json!({
+                        "droppedNgrams": degradation.dropped_ngrams,
+                        "droppedSynonymTerms": degradation.dropped_synonym_terms,
+                    });
+                    details_map.insert("queryGraphDegraded".into(), degradation_details);
+                }
             }
         }
         details_map
@@ -287,6 +320,15 @@ pub enum ScoringStrategy {
     Detailed,
 }
 
+/// The alternatives of a query that were dropped because its query graph was too large.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct QueryGraphDegradation {
+    /// The number of ngrams that were not searched.
+    pub dropped_ngrams: u32,
+    /// The number of terms whose synonyms were not searched.
+    pub dropped_synonym_terms: u32,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Words {
     pub matching_words: u32,
@@ -494,6 +536,12 @@ pub struct Vector {
     pub similarity: Option<f32>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Random {
+    /// The index of the shuffled bucket containing the document.
+    pub bucket: u32,
+}
+
 impl GeoSort {
     pub fn distance(&self) -> Option<f64> {
         self.value.map(|value| distance_between_two_points(&self.target_point, &value))
@@ -0,0 +1,142 @@
+use std::str::FromStr;
+
+use heed::RoTxn;
+use roaring::RoaringBitmap;
+
+use crate::score_details::{ScoreDetails, ScoringStrategy};
+use crate::{
+    AscDesc, DocumentId, Filter, Index, Result, Search, SearchResult, SortError,
+    TermsMatchingStrategy,
+};
+
+/// Searches the documents of an index without depending on the internals of the search.
+///
+/// The ranking rules, the distinct attribute and the synonyms of the settings of the index
+/// are applied like in a search of the HTTP API.
+///
+/// ```no_run
+/// # fn search(index: &milli::Index) -> milli::Result<()> {
+/// let rtxn = index.read_txn()?;
+/// let hits = milli::SearchBuilder::new(index)
+///     .query("hello world")
+///     .filter("genre = horror")
+///     .sort(["released:desc"])
+///     .limit(10)
+///     .execute(&rtxn)?;
+/// println!("{} documents match", hits.candidates.len());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SearchBuilder<'a> {
+    index: &'a Index,
+    query: Option<String>,
+    filter: Option<String>,
+    sort: Vec<String>,
+    searchable_attributes: Option<Vec<String>>,
+    terms_matching_strategy: TermsMatchingStrategy,
+    offset: usize,
+    limit: usize,
+}
+
+/// The documents found by a [`SearchBuilder`].
+#[derive(Debug, Clone, Default)]
+pub struct SearchHits {
+    /// The ids of the returned documents, in the order of their ranking.
+    pub documents_ids: Vec<DocumentId>,
+    /// All the documents matching the search, regardless of the pagination.
+    pub candidates: RoaringBitmap,
+    /// The ranking score, between 0 and 1, of each returned document.
+    pub scores: Vec<f64>,
+}
+
+impl<'a> SearchBuilder<'a> {
+    pub fn new(index: &'a Index) -> Self {
+        SearchBuilder {
+            index,
+            query: None,
+            filter: None,
+            sort: Vec::new(),
+            searchable_attributes: None,
+            terms_matching_strategy: TermsMatchingStrategy::default(),
+            offset: 0,
+            limit: 20,
+        }
+    }
+
+    /// The words to search for, a search without a query returns all the documents.
+    pub fn query(&mut self, query: impl Into<String>) -> &mut Self {
+        self.query = Some(query.into());
+        self
+    }
+
+    /// A filter expression on the filterable attributes, e.g. `genre = horror AND year > 2000`.
+    pub fn filter(&mut self, filter: impl Into<String>) -> &mut Self {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    /// The sortable attributes to sort the documents by, e.g. `year:desc`.
+    pub fn sort(&mut self, sort: impl IntoIterator<Item = impl Into<String>>) -> &mut Self {
+        self.sort = sort.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Restricts the search to these searchable attributes.
+    pub fn searchable_attributes(
+        &mut self,
+        attributes: impl IntoIterator<Item = impl Into<String>>,
+    ) -> &mut Self {
+        self.searchable_attributes = Some(attributes.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn terms_matching_strategy(&mut self, strategy: TermsMatchingStrategy) -> &mut Self {
+        self.terms_matching_strategy = strategy;
+        self
+    }
+
+    /// The number of documents to skip.
+    pub fn offset(&mut self, offset: usize) -> &mut Self {
+        self.offset = offset;
+        self
+    }
+
+    /// The maximum number of documents to return.
+    pub fn limit(&mut self, limit: usize) -> &mut Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn execute(&self, rtxn: &RoTxn) -> Result<SearchHits> {
+        let mut search = Search::new(rtxn, self.index);
+        if let Some(query) = &self.query {
+            search.query(query);
+        }
+        if let Some(filter) = &self.filter {
+            if let Some(filter) = Filter::from_str(filter)? {
+                search.filter(filter);
+            }
+        }
+        if !self.sort.is_empty() {
+            let sort: std::result::Result<Vec<_>, _> =
+                self.sort.iter().map(|s| AscDesc::from_str(s)).collect();
+            search.sort_criteria(sort.map_err(SortError::from)?);
+        }
+        if let Some(attributes) = &self.searchable_attributes {
+            search.searchable_attributes(attributes);
+        }
+        search.terms_matching_strategy(self.terms_matching_strategy);
+        search.scoring_strategy(ScoringStrategy::Detailed);
+        search.offset(self.offset);
+        search.limit(self.limit);
+
+        let SearchResult { documents_ids, candidates, document_scores, .. } = search.execute()?;
+        let scores = document_scores
+            .iter()
+            .map(|details| ScoreDetails::global_score(details.iter()))
+            .collect();
+
+        Ok(SearchHits { documents_ids, candidates, scores })
+    }
+}
@@ -5,11 +5,12 @@ use std::{fmt, mem};
 use heed::types::Bytes;
 use heed::BytesDecode;
 use indexmap::IndexMap;
+use ordered_float::OrderedFloat;
 use roaring::RoaringBitmap;
 use serde::{Deserialize, Serialize};
 
 use crate::error::UserError;
-use crate::facet::FacetType;
+use crate::facet::{FacetType, FacetValue};
 use crate::heed_codec::facet::{
     FacetGroupKeyCodec, FieldDocIdFacetF64Codec, FieldDocIdFacetStringCodec, OrderedF64Codec,
 };
@@ -17,7 +18,7 @@ use crate::heed_codec::{BytesRefCodec, StrRefCodec};
 use crate::search::facet::facet_distribution_iter::{
     count_iterate_over_facet_distribution, lexicographically_iterate_over_facet_distribution,
 };
-use crate::{FieldId, Index, Result};
+use crate::{FieldId, Index, Result, TimeBudget};
 
 /// The default number of values by facets that will
 /// be fetched from the key-value store.
@@ -27,6 +28,10 @@ pub const DEFAULT_VALUES_PER_FACET: usize = 100;
 /// the system to choose between one algorithm or another.
 const CANDIDATES_THRESHOLD: u64 = 3000;
 
+/// The default number of candidates above which an approximated facet
+/// distribution is computed on a sample of the candidates.
+pub const DEFAULT_APPROXIMATION_THRESHOLD: u64 = 100_000;
+
 /// How should we fetch the facets?
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OrderBy {
@@ -42,6 +47,8 @@ pub struct FacetDistribution<'a> {
     candidates: Option<RoaringBitmap>,
     max_values_per_facet: usize,
     default_order_by: OrderBy,
+    approximation_threshold: Option<u64>,
+    time_budget: TimeBudget,
     rtxn: &'a heed::RoTxn<'a>,
     index: &'a Index,
 }
@@ -53,6 +60,8 @@ impl<'a> FacetDistribution<'a> {
             candidates: None,
             max_values_per_facet: DEFAULT_VALUES_PER_FACET,
             default_order_by: OrderBy::default(),
+            approximation_threshold: None,
+            time_budget: TimeBudget::max(),
             rtxn,
             index,
         }
@@ -81,11 +90,34 @@ impl<'a> FacetDistribution<'a> {
         self
     }
 
+    /// Restricts the distribution to these documents, usually the candidates returned by a search,
+    /// so that the query is not resolved a second time.
     pub fn candidates(&mut self, candidates: RoaringBitmap) -> &mut Self {
         self.candidates = Some(candidates);
         self
     }
 
+    /// Approximate the facet distribution when there are more than `threshold` candidates.
+    ///
+    /// The counts are computed on a sample of around `threshold` candidates and then
+    /// extrapolated to the whole set of candidates. For a facet value contained in a
+    /// proportion `p` of the candidates, the relative standard error of its count is
+    /// around `sqrt((1 - p) / (threshold * p))`, e.g. ~3% for a value contained in 1%
+    /// of the candidates with a threshold of 100 000.
+    pub fn approximate(&mut self, threshold: u64) -> &mut Self {
+        self.approximation_threshold = Some(threshold);
+        self
+    }
+
+    /// Stop computing the distribution as soon as the search is canceled.
+    ///
+    /// Only the cancellation is checked: the facet distribution is never degraded because of the
+    /// time spent, the values returned after a cancellation are incomplete and must be discarded.
+    pub fn time_budget(&mut self, time_budget: TimeBudget) -> &mut Self {
+        self.time_budget = time_budget;
+        self
+    }
+
     /// There is a small amount of candidates OR we ask for facet string values so we
     /// decide to iterate over the facet values of each one of them, one by one.
     fn facet_distribution_from_documents(
@@ -93,7 +125,7 @@ impl<'a> FacetDistribution<'a> {
         field_id: FieldId,
         facet_type: FacetType,
         candidates: &RoaringBitmap,
-        distribution: &mut IndexMap<String, u64>,
+        distribution: &mut IndexMap<FacetValue, u64>,
     ) -> heed::Result<()> {
         match facet_type {
             FacetType::Number => {
@@ -102,6 +134,9 @@ impl<'a> FacetDistribution<'a> {
                 let distribution_prelength = distribution.len();
                 let db = self.index.field_id_docid_facet_f64s;
                 for docid in candidates {
+                    if self.time_budget.is_canceled() {
+                        break;
+                    }
                     key_buffer.truncate(mem::size_of::<FieldId>());
                     key_buffer.extend_from_slice(&docid.to_be_bytes());
                     let iter = db
@@ -111,7 +146,9 @@ impl<'a> FacetDistribution<'a> {
 
                     for result in iter {
                         let ((_, _, value), ()) = result?;
-                        *distribution.entry(value.to_string()).or_insert(0) += 1;
+                        *distribution
+                            .entry(FacetValue::Number(OrderedFloat(value)))
+                            .or_insert(0) += 1;
 
                         if distribution.len() - distribution_prelength == self.max_values_per_facet
                         {
@@ -126,6 +163,9 @@ impl<'a> FacetDistribution<'a> {
 
                 let db = self.index.field_id_docid_facet_strings;
                 'outer: for docid in candidates {
+                    if self.time_budget.is_canceled() {
+                        break;
+                    }
                     key_buffer.truncate(mem::size_of::<FieldId>());
                     key_buffer.extend_from_slice(&docid.to_be_bytes());
                     let iter = db
@@ -148,7 +188,7 @@ impl<'a> FacetDistribution<'a> {
 
                 let iter = normalized_distribution
                     .into_iter()
-                    .map(|(_normalized, (original, count))| (original.to_string(), count));
+                    .map(|(_normalized, (original, count))| (FacetValue::from(original), count));
                 distribution.extend(iter);
             }
         }
@@ -163,7 +203,7 @@ impl<'a> FacetDistribution<'a> {
         field_id: FieldId,
         candidates: &RoaringBitmap,
         order_by: OrderBy,
-        distribution: &mut IndexMap<String, u64>,
+        distribution: &mut IndexMap<FacetValue, u64>,
     ) -> heed::Result<()> {
         let search_function = match order_by {
             OrderBy::Lexicographic => lexicographically_iterate_over_facet_distribution,
@@ -177,8 +217,9 @@ impl<'a> FacetDistribution<'a> {
             candidates,
             |facet_key, nbr_docids, _| {
                 let facet_key = OrderedF64Codec::bytes_decode(facet_key).unwrap();
-                distribution.insert(facet_key.to_string(), nbr_docids);
-                if distribution.len() == self.max_values_per_facet {
+                distribution.insert(FacetValue::Number(OrderedFloat(facet_key)), nbr_docids);
+                if distribution.len() == self.max_values_per_facet || self.time_budget.is_canceled()
+                {
                     Ok(ControlFlow::Break(()))
                 } else {
                     Ok(ControlFlow::Continue(()))
@@ -192,7 +233,7 @@ impl<'a> FacetDistribution<'a> {
         field_id: FieldId,
         candidates: &RoaringBitmap,
         order_by: OrderBy,
-        distribution: &mut IndexMap<String, u64>,
+        distribution: &mut IndexMap<FacetValue, u64>,
     ) -> heed::Result<()> {
         let search_function = match order_by {
             OrderBy::Lexicographic => lexicographically_iterate_over_facet_distribution,
@@ -215,8 +256,9 @@ impl<'a> FacetDistribution<'a> {
                     .unwrap()
                     .to_owned();
 
-                distribution.insert(original_string, nbr_docids);
-                if distribution.len() == self.max_values_per_facet {
+                distribution.insert(FacetValue::String(original_string), nbr_docids);
+                if distribution.len() == self.max_values_per_facet || self.time_budget.is_canceled()
+                {
                     Ok(ControlFlow::Break(()))
                 } else {
                     Ok(ControlFlow::Continue(()))
@@ -225,24 +267,55 @@ impl<'a> FacetDistribution<'a> {
         )
     }
 
+    /// The timestamps of the date fields are not returned, their dates are already counted as strings.
     fn facet_values(
         &self,
         field_id: FieldId,
         order_by: OrderBy,
-    ) -> heed::Result<IndexMap<String, u64>> {
+        date_field: bool,
+    ) -> heed::Result<IndexMap<FacetValue, u64>> {
         use FacetType::{Number, String};
 
+        let sample;
+        let (candidates, scale) = match self.approximation_threshold {
+            Some(threshold) => {
+                let universe;
+                let candidates = match &self.candidates {
+                    Some(cnd) => cnd,
+                    None => {
+                        universe = self.index.documents_ids(self.rtxn)?;
+                        &universe
+                    }
+                };
+
+                if candidates.len() > threshold {
+                    sample = sample_candidates(candidates, threshold);
+                    (Some(&sample), Some(candidates.len() as f64 / sample.len() as f64))
+                } else {
+                    (self.candidates.as_ref(), None)
+                }
+            }
+            None => (self.candidates.as_ref(), None),
+        };
+
         let mut distribution = IndexMap::new();
-        match (order_by, &self.candidates) {
+        match (order_by, candidates) {
             (OrderBy::Lexicographic, Some(cnd)) if cnd.len() <= CANDIDATES_THRESHOLD => {
                 // Classic search, candidates were specified, we must return facet values only related
                 // to those candidates. We also enter here for facet strings for performance reasons.
-                self.facet_distribution_from_documents(field_id, Number, cnd, &mut distribution)?;
+                if !date_field {
+                    self.facet_distribution_from_documents(
+                        field_id,
+                        Number,
+                        cnd,
+                        &mut distribution,
+                    )?;
+                }
                 self.facet_distribution_from_documents(field_id, String, cnd, &mut distribution)?;
             }
             _ => {
                 let universe;
-                let candidates = match &self.candidates {
+                let candidates = match candidates {
                     Some(cnd) => cnd,
                     None => {
                         universe = self.index.documents_ids(self.rtxn)?;
@@ -250,12 +323,14 @@ impl<'a> FacetDistribution<'a> {
                     }
                 };
 
-                self.facet_numbers_distribution_from_facet_levels(
-                    field_id,
-                    candidates,
-                    order_by,
-                    &mut distribution,
-                )?;
+                if !date_field {
+                    self.facet_numbers_distribution_from_facet_levels(
+                        field_id,
+                        candidates,
+                        order_by,
+                        &mut distribution,
+                    )?;
+                }
                 self.facet_strings_distribution_from_facet_levels(
                     field_id,
                     candidates,
@@ -265,6 +340,13 @@ impl<'a> FacetDistribution<'a> {
             }
         };
 
+        // The counts have been computed on a sample, we extrapolate them to all the candidates.
+        if let Some(scale) = scale {
+            for count in distribution.values_mut() {
+                *count = (*count as f64 * scale).round() as u64;
+            }
+        }
+
         Ok(distribution)
     }
 
@@ -329,6 +411,25 @@ impl<'a> FacetDistribution<'a> {
     }
 
     pub fn execute(&self) -> Result<BTreeMap<String, IndexMap<String, u64>>> {
+        let distribution = self.execute_typed()?;
+        Ok(distribution
+            .into_iter()
+            .map(|(name, values)| {
+                let values = values
+                    .into_iter()
+                    .map(|(value, count)| match value {
+                        FacetValue::String(string) => (string, count),
+                        FacetValue::Number(number) => (number.to_string(), count),
+                    })
+                    .collect();
+                (name, values)
+            })
+            .collect())
+    }
+
+    /// Like [`Self::execute`], but the values keep their type instead of all being converted
+    /// to strings, a number and a string with the same representation are then distinct values.
+    pub fn execute_typed(&self) -> Result<BTreeMap<String, IndexMap<FacetValue, u64>>> {
         let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
         let filterable_fields = self.index.filterable_fields(self.rtxn)?;
 
@@ -352,15 +453,19 @@ impl<'a> FacetDistribution<'a> {
             None => filterable_fields,
         };
 
+        let date_fields_ids = self.index.date_fields_ids(self.rtxn)?;
         let mut distribution = BTreeMap::new();
         for (fid, name) in fields_ids_map.iter() {
+            if self.time_budget.is_canceled() {
+                break;
+            }
             if crate::is_faceted(name, &fields) {
                 let order_by = self
                     .facets
                     .as_ref()
                     .and_then(|facets| facets.get(name).copied())
                     .unwrap_or(self.default_order_by);
-                let values = self.facet_values(fid, order_by)?;
+                let values = self.facet_values(fid, order_by, date_fields_ids.contains(&fid))?;
                 distribution.insert(name.to_string(), values);
             }
         }
@@ -376,6 +481,8 @@ impl fmt::Debug for FacetDistribution<'_> {
             candidates,
             max_values_per_facet,
             default_order_by,
+            approximation_threshold,
+            time_budget,
             rtxn: _,
             index: _,
         } = self;
@@ -385,17 +492,32 @@ impl fmt::Debug for FacetDistribution<'_> {
             .field("candidates", candidates)
             .field("max_values_per_facet", max_values_per_facet)
             .field("default_order_by", default_order_by)
+            .field("approximation_threshold", approximation_threshold)
+            .field("time_budget", time_budget)
             .finish()
     }
 }
 
+/// Returns a sample of around `size` documents evenly spread among the candidates.
+fn sample_candidates(candidates: &RoaringBitmap, size: u64) -> RoaringBitmap {
+    // A document is kept when its hash falls in the first `size / len` part of the hashes, so
+    // that the sampling rate is not rounded to an integer when there are less than twice as
+    // many candidates as requested.
+    let limit = (size.min(candidates.len()) << 32) / candidates.len().max(1);
+    // We select the documents by hashing their ids instead of taking one document every few
+    // documents, to avoid sampling a biased subset when the documents were indexed in a periodic order.
+    candidates.iter().filter(|docid| (docid.wrapping_mul(0x9E37_79B9) as u64) < limit).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::iter;
 
     use big_s::S;
     use maplit::hashset;
+    use roaring::RoaringBitmap;
 
+    use super::sample_candidates;
     use crate::documents::documents_batch_reader_from_objects;
     use crate::index::tests::TempIndex;
     use crate::{milli_snap, FacetDistribution, OrderBy};
@@ -561,6 +683,88 @@ mod tests {
         milli_snap!(format!("{map:?}"), @r###"{"colour": {"Red": 3000}}"###);
     }
 
+    #[test]
+    fn many_candidates_approximated_facet_values() {
+        let mut index = TempIndex::new_with_map_size(4096 * 10_000);
+        index.index_documents_config.autogenerate_docids = true;
+
+        index
+            .update_settings(|settings| settings.set_filterable_fields(hashset! { S("colour") }))
+            .unwrap();
+
+        let facet_values = ["Red", "RED", " red ", "Blue", "BLUE"];
+
+        let mut documents = vec![];
+        for i in 0..10_000 {
+            let document = serde_json::json!({
+                "colour": facet_values[i % 5],
+            })
+            .as_object()
+            .unwrap()
+            .clone();
+            documents.push(document);
+        }
+
+        let documents = documents_batch_reader_from_objects(documents);
+
+        index.add_documents(documents).unwrap();
+
+        let txn = index.read_txn().unwrap();
+
+        let exact = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::default())))
+            .candidates((0..10_000).collect())
+            .execute()
+            .unwrap();
+
+        milli_snap!(format!("{exact:?}"), @r###"{"colour": {"Blue": 4000, "Red": 6000}}"###);
+
+        for order_by in [OrderBy::Lexicographic, OrderBy::Count] {
+            let approximated = FacetDistribution::new(&txn, &index)
+                .facets(iter::once(("colour", order_by)))
+                .candidates((0..10_000).collect())
+                .approximate(1_000)
+                .execute()
+                .unwrap();
+
+            for (value, exact_count) in &exact["colour"] {
+                let approximated_count = approximated["colour"][value];
+                // 5% of tolerance is more than three times the standard error for a sample of 1000 documents
+                assert!(
+                    exact_count.abs_diff(approximated_count) * 20 <= *exact_count,
+                    "{value}: approximated {approximated_count} too far from {exact_count}"
+                );
+            }
+        }
+
+        // below the threshold the distribution is exact
+        let approximated = FacetDistribution::new(&txn, &index)
+            .facets(iter::once(("colour", OrderBy::default())))
+            .candidates((0..5_000).collect())
+            .approximate(5_000)
+            .execute()
+            .unwrap();
+
+        milli_snap!(format!("{approximated:?}"), @r###"{"colour": {"Blue": 2000, "Red": 3000}}"###);
+    }
+
+    #[test]
+    fn sample_between_once_and_twice_the_threshold() {
+        let candidates: RoaringBitmap = (0..15_000).collect();
+
+        // the sample is not twice smaller than requested when the rate is between 1 and 2
+        let sample = sample_candidates(&candidates, 10_000);
+        assert!(
+            sample.len().abs_diff(10_000) * 20 <= 10_000,
+            "sampled {} documents instead of around 10000",
+            sample.len()
+        );
+        assert!(sample.is_subset(&candidates));
+
+        let sample = sample_candidates(&candidates, 15_000);
+        assert_eq!(sample, candidates);
+    }
+
     #[test]
     fn many_candidates_many_facet_values() {
         let mut index = TempIndex::new_with_map_size(4096 * 10_000);
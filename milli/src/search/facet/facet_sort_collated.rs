@@ -0,0 +1,205 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+use heed::types::Bytes;
+use heed::Result;
+use roaring::RoaringBitmap;
+
+use crate::collation::{CollationKey, Collator};
+use crate::heed_codec::facet::{FacetGroupKey, FacetGroupKeyCodec, FacetGroupValueCodec};
+use crate::heed_codec::BytesRefCodec;
+
+/// The number of string facet values sorted at once.
+const COLLATED_CHUNK_SIZE: usize = 1000;
+
+/// Return an iterator which iterates over the given candidate documents in
+/// the order of the [`Collator`] of their string facet value for the given field id.
+///
+/// Like [`super::ascending_facet_sort`], the documents are grouped by the facet value
+/// that determined their rank and are never returned twice.
+///
+/// The facet values are stored in the byte order of their normalized value, which isn't
+/// the alphabetical order. To keep the memory bounded, the iterator only sorts the next
+/// [`COLLATED_CHUNK_SIZE`] facet values of the candidates, by scanning all the values
+/// of the field again every time it runs out of values.
+pub fn collated_facet_sort<'t>(
+    rtxn: &'t heed::RoTxn<'t>,
+    db: heed::Database<FacetGroupKeyCodec<BytesRefCodec>, FacetGroupValueCodec>,
+    field_id: u16,
+    candidates: RoaringBitmap,
+    collator: Collator,
+    is_ascending: bool,
+) -> CollatedFacetSort<'t> {
+    CollatedFacetSort {
+        rtxn,
+        db,
+        field_id,
+        candidates,
+        collator,
+        is_ascending,
+        chunk: VecDeque::new(),
+        last_key: None,
+        exhausted: false,
+    }
+}
+
+pub struct CollatedFacetSort<'t> {
+    rtxn: &'t heed::RoTxn<'t>,
+    db: heed::Database<FacetGroupKeyCodec<BytesRefCodec>, FacetGroupValueCodec>,
+    field_id: u16,
+    candidates: RoaringBitmap,
+    collator: Collator,
+    is_ascending: bool,
+    /// The next facet values to return, in order.
+    chunk: VecDeque<CollationKey>,
+    /// The key of the last facet value of the previous chunk.
+    last_key: Option<CollationKey>,
+    exhausted: bool,
+}
+
+impl<'t> CollatedFacetSort<'t> {
+    fn cmp(&self, a: &CollationKey, b: &CollationKey) -> Ordering {
+        if self.is_ascending {
+            a.cmp(b)
+        } else {
+            b.cmp(a)
+        }
+    }
+
+    /// Fills the chunk with the next facet values of the candidates following the last chunk.
+    fn fill_chunk(&mut self) -> Result<()> {
+        let mut prefix = self.field_id.to_be_bytes().to_vec();
+        // level 0 contains the facet values themselves
+        prefix.push(0);
+
+        let mut keys = Vec::new();
+        let iter = self.db.remap_key_type::<Bytes>().prefix_iter(self.rtxn, &prefix)?;
+        for result in iter {
+            let (key, value) = result?;
+            if value.bitmap.is_disjoint(&self.candidates) {
+                continue;
+            }
+            let Ok(normalized) = std::str::from_utf8(&key[prefix.len()..]) else { continue };
+            let key = self.collator.key(normalized);
+            if let Some(last_key) = &self.last_key {
+                if self.cmp(&key, last_key) != Ordering::Greater {
+                    continue;
+                }
+            }
+            keys.push(key);
+            if keys.len() >= COLLATED_CHUNK_SIZE * 2 {
+                keys.sort_unstable_by(|a, b| self.cmp(a, b));
+                keys.truncate(COLLATED_CHUNK_SIZE);
+            }
+        }
+        keys.sort_unstable_by(|a, b| self.cmp(a, b));
+        keys.truncate(COLLATED_CHUNK_SIZE);
+
+        self.exhausted = keys.len() < COLLATED_CHUNK_SIZE;
+        self.last_key = keys.last().cloned();
+        self.chunk = keys.into();
+        Ok(())
+    }
+}
+
+impl<'t> Iterator for CollatedFacetSort<'t> {
+    type Item = Result<(RoaringBitmap, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.candidates.is_empty() {
+                return None;
+            }
+            if self.chunk.is_empty() {
+                if self.exhausted {
+                    return None;
+                }
+                if let Err(e) = self.fill_chunk() {
+                    return Some(Err(e));
+                }
+                continue;
+            }
+
+            let key = self.chunk.pop_front()?;
+            let group_key = FacetGroupKey {
+                field_id: self.field_id,
+                level: 0,
+                left_bound: key.value().as_bytes(),
+            };
+            let docids = match self.db.get(self.rtxn, &group_key) {
+                Ok(Some(value)) => value.bitmap & &self.candidates,
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            };
+            // the documents having several values were already returned with a previous one
+            if docids.is_empty() {
+                continue;
+            }
+            self.candidates -= &docids;
+            return Some(Ok((docids, key.value().to_owned())));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use roaring::RoaringBitmap;
+
+    use crate::collation::Collator;
+    use crate::documents::documents_batch_reader_from_objects;
+    use crate::heed_codec::facet::FacetGroupKeyCodec;
+    use crate::heed_codec::BytesRefCodec;
+    use crate::index::tests::TempIndex;
+    use crate::search::facet::collated_facet_sort;
+    use crate::update::Settings;
+
+    #[test]
+    fn collated_sort_across_chunks() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings: &mut Settings| {
+                settings.set_sortable_fields(["word".to_string()].into_iter().collect());
+            })
+            .unwrap();
+
+        // more values than a chunk, with an accented variant of each of them
+        let documents = (0..1200).flat_map(|i| {
+            [
+                serde_json::json!({ "id": i * 2, "word": format!("e{i:04}") }),
+                serde_json::json!({ "id": i * 2 + 1, "word": format!("é{i:04}") }),
+            ]
+        });
+        let documents = documents.filter_map(|json| match json {
+            serde_json::Value::Object(object) => Some(object),
+            _ => None,
+        });
+        index.add_documents(documents_batch_reader_from_objects(documents)).unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let field_id = index.fields_ids_map(&rtxn).unwrap().id("word").unwrap();
+        let db = index.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        let candidates: RoaringBitmap = (0..2400).collect();
+
+        for is_ascending in [true, false] {
+            let docids: Vec<u32> = collated_facet_sort(
+                &rtxn,
+                db,
+                field_id,
+                candidates.clone(),
+                Collator::default(),
+                is_ascending,
+            )
+            .map(|r| {
+                let (docids, _) = r.unwrap();
+                assert_eq!(docids.len(), 1);
+                docids.min().unwrap()
+            })
+            .collect();
+            let mut expected: Vec<u32> = (0..2400).collect();
+            if !is_ascending {
+                expected.reverse();
+            }
+            assert_eq!(docids, expected);
+        }
+    }
+}
@@ -9,10 +9,13 @@ use serde_json::Value;
 
 use super::facet_range_search;
 use crate::error::{Error, UserError};
+use crate::facet::parse_facet_date;
 use crate::heed_codec::facet::{
     FacetGroupKey, FacetGroupKeyCodec, FacetGroupValueCodec, OrderedF64Codec,
 };
-use crate::{distance_between_two_points, lat_lng_to_xyz, FieldId, Index, Result};
+use crate::{
+    distance_between_two_points, lat_lng_to_xyz, normalize_facet_number, FieldId, Index, Result,
+};
 
 /// The maximum number of filters the filter AST can process.
 const MAX_FILTER_DEPTH: usize = 2000;
@@ -236,24 +239,22 @@ impl<'a> Filter<'a> {
     ) -> Result<RoaringBitmap> {
         let numbers_db = index.facet_id_f64_docids;
         let strings_db = index.facet_id_string_docids;
+        let date_field = index.date_fields_ids(rtxn)?.contains(&field_id);
+        let parse_number = |val: &Token| Self::parse_facet_number(val, date_field);
 
         // Make sure we always bound the ranges with the field id and the level,
         // as the facets values are all in the same database and prefixed by the
         // field id and the level.
 
         let (left, right) = match operator {
-            Condition::GreaterThan(val) => {
-                (Excluded(val.parse_finite_float()?), Included(f64::MAX))
-            }
+            Condition::GreaterThan(val) => (Excluded(parse_number(val)?), Included(f64::MAX)),
             Condition::GreaterThanOrEqual(val) => {
-                (Included(val.parse_finite_float()?), Included(f64::MAX))
-            }
-            Condition::LowerThan(val) => (Included(f64::MIN), Excluded(val.parse_finite_float()?)),
-            Condition::LowerThanOrEqual(val) => {
-                (Included(f64::MIN), Included(val.parse_finite_float()?))
+                (Included(parse_number(val)?), Included(f64::MAX))
             }
+            Condition::LowerThan(val) => (Included(f64::MIN), Excluded(parse_number(val)?)),
+            Condition::LowerThanOrEqual(val) => (Included(f64::MIN), Included(parse_number(val)?)),
             Condition::Between { from, to } => {
-                (Included(from.parse_finite_float()?), Included(to.parse_finite_float()?))
+                (Included(parse_number(from)?), Included(parse_number(to)?))
             }
             Condition::Null => {
                 let is_null = index.null_faceted_documents_ids(rtxn, field_id)?;
@@ -268,18 +269,32 @@ impl<'a> Filter<'a> {
                 return Ok(exist);
             }
             Condition::Equal(val) => {
+                let normalized = crate::normalize_facet(val.value(), index.case_folding(rtxn)?);
                 let string_docids = strings_db
-                    .get(
-                        rtxn,
-                        &FacetGroupKey {
-                            field_id,
-                            level: 0,
-                            left_bound: &crate::normalize_facet(val.value()),
-                        },
-                    )?
+                    .get(rtxn, &FacetGroupKey { field_id, level: 0, left_bound: &normalized })?
                     .map(|v| v.bitmap)
                     .unwrap_or_default();
-                let number = val.parse_finite_float().ok();
+                // The booleans are also stored as the "true" and "false" facet strings, the indexes
+                // that don't keep track of them separately can only match both kinds at once.
+                let boolean = match normalized.as_str() {
+                    "true" => Some(true),
+                    "false" => Some(false),
+                    _ => None,
+                };
+                if let Some(boolean) = boolean {
+                    if index.boolean_facets(rtxn)? {
+                        let bool_docids =
+                            index.bool_faceted_documents_ids(rtxn, field_id, boolean)?;
+                        // A document containing both the boolean and the string in an array
+                        // is only matched by the unquoted boolean.
+                        return Ok(if val.as_bool() == Some(boolean) {
+                            bool_docids
+                        } else {
+                            string_docids - bool_docids
+                        });
+                    }
+                }
+                let number = parse_number(val).ok();
                 let number_docids = match number {
                     Some(n) => numbers_db
                         .get(rtxn, &FacetGroupKey { field_id, level: 0, left_bound: n })?
@@ -302,6 +317,17 @@ impl<'a> Filter<'a> {
         Ok(output)
     }
 
+    /// Parses the number a facet value is compared to.
+    ///
+    /// The dates compared to the values of a date field are parsed into the timestamps
+    /// the dates of the field are indexed as.
+    fn parse_facet_number(val: &Token, date_field: bool) -> Result<f64> {
+        match date_field.then(|| parse_facet_date(val.value())).flatten() {
+            Some(timestamp) => Ok(normalize_facet_number(timestamp)),
+            None => Ok(val.parse_finite_float()?),
+        }
+    }
+
     /// Aggregates the documents ids that are part of the specified range automatically
     /// going deeper through the levels.
     fn explore_facet_number_levels(
@@ -601,7 +627,7 @@ mod tests {
     use roaring::RoaringBitmap;
 
     use crate::index::tests::TempIndex;
-    use crate::Filter;
+    use crate::{AscDesc, CaseFolding, Filter, Member, Search};
 
     #[test]
     fn empty_db() {
@@ -1128,4 +1154,200 @@ mod tests {
         let result = filter.evaluate(&rtxn, &index).unwrap();
         assert_eq!(result, RoaringBitmap::from_iter((0..100).filter(|x| x % 10 != 0)));
     }
+
+    #[test]
+    fn integer_and_float_facet_numbers_are_equivalent() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_primary_key("id".to_owned());
+                settings.set_filterable_fields(hashset! { S("price"), S("big") });
+                settings.set_sortable_fields(hashset! { S("price") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "price": 10 },
+                { "id": 1, "price": 10.0 },
+                { "id": 2, "price": 1e1 },
+                { "id": 3, "price": 11 },
+                { "id": 4, "price": 0 },
+                { "id": 5, "price": -0.0 },
+                { "id": 6, "big": 9007199254740992_u64 },
+                { "id": 7, "big": 9007199254740993_u64 },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        for filter in
+            ["price = 10", "price = 10.0", "price = 1e1", "price = '10.00'", "price 1e1 TO 10"]
+        {
+            let filter = Filter::from_str(filter).unwrap().unwrap();
+            let result = filter.evaluate(&rtxn, &index).unwrap();
+            assert_eq!(result, RoaringBitmap::from_iter([0, 1, 2]), "{filter:?}");
+        }
+        for filter in ["price = 0", "price = -0", "price = 0.0", "price = -0.0", "price -0 TO 0"] {
+            let filter = Filter::from_str(filter).unwrap().unwrap();
+            let result = filter.evaluate(&rtxn, &index).unwrap();
+            assert_eq!(result, RoaringBitmap::from_iter([4, 5]), "{filter:?}");
+        }
+        let filter = Filter::from_str("price >= -0").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter(0..6));
+
+        // the integers bigger than 2^53 are rounded to the nearest float
+        let filter = Filter::from_str("big = 9007199254740993").unwrap().unwrap();
+        let result = filter.evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([6, 7]));
+
+        // the equivalent numbers are sorted together
+        let mut search = Search::new(&rtxn, &index);
+        search.sort_criteria(vec![AscDesc::Asc(Member::Field(S("price")))]);
+        search.limit(6);
+        let result = search.execute().unwrap();
+        assert_eq!(result.documents_ids, vec![4, 5, 0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn filter_booleans() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_primary_key("id".to_owned());
+                settings.set_filterable_fields(hashset! { S("active") });
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "active": true },
+                { "id": 1, "active": "true" },
+                { "id": 2, "active": false },
+                { "id": 3, "active": "False" },
+                { "id": 4 },
+                { "id": 5, "active": [true, "false"] },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let cases: &[(&str, &[u32])] = &[
+            ("active = true", &[0, 5]),
+            ("active = 'true'", &[1]),
+            ("active = \"true\"", &[1]),
+            ("active = false", &[2]),
+            ("active = 'false'", &[3, 5]),
+            ("active = TRUE", &[1]),
+            ("active != true", &[1, 2, 3, 4]),
+            ("active IN [true, 'false']", &[0, 3, 5]),
+            ("active EXISTS", &[0, 1, 2, 3, 5]),
+        ];
+        for (filter, expected) in cases {
+            let result =
+                Filter::from_str(filter).unwrap().unwrap().evaluate(&rtxn, &index).unwrap();
+            assert_eq!(result, RoaringBitmap::from_iter(expected.iter().copied()), "{filter}");
+        }
+        drop(rtxn);
+
+        // the booleans are kept up to date when the documents are updated
+        index.add_documents(documents!([{ "id": 0, "active": false }])).unwrap();
+        let rtxn = index.read_txn().unwrap();
+        let result =
+            Filter::from_str("active = false").unwrap().unwrap().evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([0, 2]));
+        let result =
+            Filter::from_str("active = true").unwrap().unwrap().evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([5]));
+        drop(rtxn);
+
+        // the indexes created before the booleans were stored separately match both kinds
+        let mut wtxn = index.write_txn().unwrap();
+        index
+            .main
+            .remap_key_type::<heed::types::Str>()
+            .delete(&mut wtxn, crate::index::main_key::BOOLEAN_FACETS)
+            .unwrap();
+        wtxn.commit().unwrap();
+        let rtxn = index.read_txn().unwrap();
+        let result =
+            Filter::from_str("active = true").unwrap().unwrap().evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([1, 5]));
+        let result =
+            Filter::from_str("active = 'false'").unwrap().unwrap().evaluate(&rtxn, &index).unwrap();
+        assert_eq!(result, RoaringBitmap::from_iter([0, 2, 3, 5]));
+    }
+
+    #[test]
+    fn filter_with_case_folding() {
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_primary_key("id".to_owned());
+                settings.set_filterable_fields(hashset! { S("userId") });
+            })
+            .unwrap();
+
+        // the index only lowercases its values, like the ones created before the full case folding
+        let mut wtxn = index.write_txn().unwrap();
+        index
+            .main
+            .remap_key_type::<heed::types::Str>()
+            .delete(&mut wtxn, crate::index::main_key::FULL_CASE_FOLDING)
+            .unwrap();
+        wtxn.commit().unwrap();
+
+        // the documents values and the same values written differently
+        let cases = [
+            ("été", "ÉTÉ"),
+            ("straße", "STRASSE"),
+            ("STRAẞE", "strasse"),
+            ("İstanbul", "istanbul"),
+            ("ıspanak", "ISPANAK"),
+            ("ΣΟΦΟΣ", "σοφοσ"),
+            ("ᾳ", "ΑΙ"),
+        ];
+        let documents: Vec<_> = cases
+            .iter()
+            .enumerate()
+            .map(|(id, (value, _))| serde_json::json!({ "id": id, "userId": value }))
+            .collect();
+        index.add_documents(documents!(documents)).unwrap();
+
+        let evaluate = |filter: &str| {
+            let rtxn = index.read_txn().unwrap();
+            let filter = format!("userId = \"{filter}\"");
+            Filter::from_str(&filter).unwrap().unwrap().evaluate(&rtxn, &index).unwrap()
+        };
+
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.case_folding(&rtxn).unwrap(), CaseFolding::Lowercase);
+        drop(rtxn);
+        for (id, (value, other)) in cases.iter().enumerate() {
+            let id = id as u32;
+            // a value always matches the documents it was indexed from
+            assert_eq!(evaluate(value), RoaringBitmap::from_iter([id]), "{value}");
+            // only the values that differ by their case match once lowercased
+            let expected = if *value == "été" { vec![id] } else { vec![] };
+            assert_eq!(evaluate(other), RoaringBitmap::from_iter(expected), "{other}");
+        }
+
+        // reindexing the documents clears them and folds their values with the full case folding
+        index
+            .update_settings(|settings| {
+                settings.set_searchable_fields(vec![S("userId")]);
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.case_folding(&rtxn).unwrap(), CaseFolding::Full);
+        drop(rtxn);
+        for (id, (value, other)) in cases.iter().enumerate() {
+            let id = id as u32;
+            assert_eq!(evaluate(value), RoaringBitmap::from_iter([id]), "{value}");
+            assert_eq!(evaluate(other), RoaringBitmap::from_iter([id]), "{other}");
+        }
+    }
 }
@@ -1,12 +1,17 @@
 pub use facet_sort_ascending::ascending_facet_sort;
+pub use facet_sort_collated::{collated_facet_sort, CollatedFacetSort};
 pub use facet_sort_descending::descending_facet_sort;
 use heed::types::{Bytes, DecodeIgnore};
 use heed::{BytesDecode, RoTxn};
 use roaring::RoaringBitmap;
 
-pub use self::facet_distribution::{FacetDistribution, OrderBy, DEFAULT_VALUES_PER_FACET};
+pub use self::facet_distribution::{
+    FacetDistribution, OrderBy, DEFAULT_APPROXIMATION_THRESHOLD, DEFAULT_VALUES_PER_FACET,
+};
 pub use self::filter::{BadGeoError, Filter};
-pub use self::search::{FacetValueHit, SearchForFacetValues};
+pub use self::search::{
+    FacetValueHit, FacetValueHits, SearchForFacetValues, MAX_FACET_QUERY_LENGTH,
+};
 use crate::heed_codec::facet::{FacetGroupKeyCodec, FacetGroupValueCodec, OrderedF64Codec};
 use crate::heed_codec::BytesRefCodec;
 use crate::{Index, Result};
@@ -15,6 +20,7 @@ mod facet_distribution;
 mod facet_distribution_iter;
 mod facet_range_search;
 mod facet_sort_ascending;
+mod facet_sort_collated;
 mod facet_sort_descending;
 mod filter;
 mod search;
@@ -3,7 +3,7 @@ use std::collections::BinaryHeap;
 use std::ops::ControlFlow;
 
 use charabia::normalizer::NormalizerOption;
-use charabia::Normalize;
+use charabia::{Normalize, TokenizerBuilder};
 use fst::automaton::{Automaton, Str};
 use fst::{IntoStreamer, Streamer};
 use roaring::RoaringBitmap;
@@ -17,14 +17,33 @@ use crate::{DocumentId, FieldId, OrderBy, Result, Search};
 /// The maximum number of values per facet returned by the facet search route.
 const DEFAULT_MAX_NUMBER_OF_VALUES_PER_FACET: usize = 100;
 
+/// The maximum length, in bytes, of a facet query.
+///
+/// The facet values are truncated before being stored, a longer query can't match any of them.
+pub const MAX_FACET_QUERY_LENGTH: usize = 512;
+
+/// The maximum number of facet values examined by a facet search before returning
+/// the values found so far.
+const DEFAULT_MAX_EXAMINED_FACET_VALUES: usize = 10_000;
+
 pub struct SearchForFacetValues<'a> {
     query: Option<String>,
     facet: String,
     search_query: Search<'a>,
     max_values: usize,
+    max_examined_values: usize,
     is_hybrid: bool,
 }
 
+/// The facet values found by a facet search.
+#[derive(Debug, Clone, Default)]
+pub struct FacetValueHits {
+    pub hits: Vec<FacetValueHit>,
+    /// Whether the search stopped after examining too many facet values,
+    /// some matching values may be missing.
+    pub truncated: bool,
+}
+
 impl<'a> SearchForFacetValues<'a> {
     pub fn new(
         facet: String,
@@ -36,6 +55,7 @@ impl<'a> SearchForFacetValues<'a> {
             facet,
             search_query,
             max_values: DEFAULT_MAX_NUMBER_OF_VALUES_PER_FACET,
+            max_examined_values: DEFAULT_MAX_EXAMINED_FACET_VALUES,
             is_hybrid,
         }
     }
@@ -50,6 +70,50 @@ impl<'a> SearchForFacetValues<'a> {
         self
     }
 
+    /// The number of facet values to examine before returning the values found so far.
+    pub fn max_examined_values(&mut self, max: usize) -> &mut Self {
+        self.max_examined_values = max;
+        self
+    }
+
+    /// Rejects the queries that would examine many facet values without being able to match any.
+    fn check_query(&self, query: &str) -> Result<()> {
+        if query.len() > MAX_FACET_QUERY_LENGTH {
+            return Err(UserError::FacetSearchQueryTooLong {
+                length: query.len(),
+                max: MAX_FACET_QUERY_LENGTH,
+            }
+            .into());
+        }
+        // An empty query matches all the facet values, like no query.
+        if query.is_empty() {
+            return Ok(());
+        }
+
+        let index = self.search_query.index;
+        let rtxn = self.search_query.rtxn;
+        let mut builder = TokenizerBuilder::new();
+        let separators = index.allowed_separators(rtxn)?;
+        let separators: Option<Vec<_>> =
+            separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
+        if let Some(ref separators) = separators {
+            builder.separators(separators);
+        }
+        let dictionary = index.dictionary(rtxn)?;
+        let dictionary: Option<Vec<_>> =
+            dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
+        if let Some(ref dictionary) = dictionary {
+            builder.words_dict(dictionary);
+        }
+
+        let tokenizer = builder.build();
+        if tokenizer.tokenize(query).any(|token| token.is_word()) {
+            Ok(())
+        } else {
+            Err(UserError::FacetSearchQueryWithoutWords(query.to_string()).into())
+        }
+    }
+
     fn one_original_value_of(
         &self,
         field_id: FieldId,
@@ -62,10 +126,14 @@ impl<'a> SearchForFacetValues<'a> {
         Ok(index.field_id_docid_facet_strings.get(rtxn, &key)?.map(|v| v.to_owned()))
     }
 
-    pub fn execute(&self) -> Result<Vec<FacetValueHit>> {
+    pub fn execute(&self) -> Result<FacetValueHits> {
         let index = self.search_query.index;
         let rtxn = self.search_query.rtxn;
 
+        if let Some(query) = &self.query {
+            self.check_query(query)?;
+        }
+
         let filterable_fields = index.filterable_fields(rtxn)?;
         if !filterable_fields.contains(&self.facet) {
             let (valid_fields, hidden_fields) =
@@ -84,12 +152,12 @@ impl<'a> SearchForFacetValues<'a> {
             Some(fid) => fid,
             // we return an empty list of results when the attribute has been
             // set as filterable but no document contains this field (yet).
-            None => return Ok(Vec::new()),
+            None => return Ok(FacetValueHits::default()),
         };
 
         let fst = match self.search_query.index.facet_id_string_fst.get(rtxn, &fid)? {
             Some(fst) => fst,
-            None => return Ok(Vec::new()),
+            None => return Ok(FacetValueHits::default()),
         };
 
         let search_candidates = self.search_query.execute_for_candidates(
@@ -106,6 +174,8 @@ impl<'a> SearchForFacetValues<'a> {
             OrderBy::Lexicographic => ValuesCollection::by_lexicographic(self.max_values),
             OrderBy::Count => ValuesCollection::by_count(self.max_values),
         };
+        let mut examined = 0;
+        let mut truncated = false;
 
         match self.query.as_ref() {
             Some(query) => {
@@ -144,6 +214,14 @@ impl<'a> SearchForFacetValues<'a> {
 
                         let mut stream = fst.search(automaton).into_stream();
                         while let Some(facet_value) = stream.next() {
+                            if self.search_query.time_budget.is_canceled() {
+                                break;
+                            }
+                            if examined == self.max_examined_values {
+                                truncated = true;
+                                break;
+                            }
+                            examined += 1;
                             let value = std::str::from_utf8(facet_value)?;
                             if self
                                 .fetch_original_facets_using_normalized(
@@ -163,6 +241,14 @@ impl<'a> SearchForFacetValues<'a> {
                     let automaton = Str::new(query).starts_with();
                     let mut stream = fst.search(automaton).into_stream();
                     while let Some(facet_value) = stream.next() {
+                        if self.search_query.time_budget.is_canceled() {
+                            break;
+                        }
+                        if examined == self.max_examined_values {
+                            truncated = true;
+                            break;
+                        }
+                        examined += 1;
                         let value = std::str::from_utf8(facet_value)?;
                         if self
                             .fetch_original_facets_using_normalized(
@@ -182,6 +268,14 @@ impl<'a> SearchForFacetValues<'a> {
             None => {
                 let prefix = FacetGroupKey { field_id: fid, level: 0, left_bound: "" };
                 for result in index.facet_id_string_docids.prefix_iter(rtxn, &prefix)? {
+                    if self.search_query.time_budget.is_canceled() {
+                        break;
+                    }
+                    if examined == self.max_examined_values {
+                        truncated = true;
+                        break;
+                    }
+                    examined += 1;
                     let (FacetGroupKey { left_bound, .. }, FacetGroupValue { bitmap, .. }) =
                         result?;
                     let count = search_candidates.intersection_len(&bitmap);
@@ -197,7 +291,7 @@ impl<'a> SearchForFacetValues<'a> {
             }
         }
 
-        Ok(results.into_sorted_vec())
+        Ok(FacetValueHits { hits: results.into_sorted_vec(), truncated })
     }
 
     fn fetch_original_facets_using_normalized(
@@ -330,3 +424,71 @@ impl ValuesCollection {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+    use maplit::hashset;
+
+    use super::*;
+    use crate::index::tests::TempIndex;
+    use crate::Error;
+
+    fn index_with_genres() -> TempIndex {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_primary_key(S("id"));
+                settings.set_filterable_fields(hashset! { S("genre") });
+            })
+            .unwrap();
+        let documents: Vec<_> = (0..20)
+            .map(|id| serde_json::json!({ "id": id, "genre": format!("genre{id:02}") }))
+            .collect();
+        index
+            .add_documents(crate::documents::documents_batch_reader_from_objects(
+                documents.into_iter().map(|document| document.as_object().unwrap().clone()),
+            ))
+            .unwrap();
+        index
+    }
+
+    #[test]
+    fn facet_search_examines_a_limited_number_of_values() {
+        let index = index_with_genres();
+        let rtxn = index.read_txn().unwrap();
+
+        let mut search = SearchForFacetValues::new(S("genre"), Search::new(&rtxn, &index), false);
+        search.query("genre");
+        let FacetValueHits { hits, truncated } = search.execute().unwrap();
+        assert_eq!(hits.len(), 20);
+        assert!(!truncated);
+
+        search.max_examined_values(5);
+        let FacetValueHits { hits, truncated } = search.execute().unwrap();
+        assert_eq!(hits.len(), 5);
+        assert!(truncated);
+    }
+
+    #[test]
+    fn facet_search_rejects_unmatchable_queries() {
+        let index = index_with_genres();
+        let rtxn = index.read_txn().unwrap();
+
+        let mut search = SearchForFacetValues::new(S("genre"), Search::new(&rtxn, &index), false);
+        search.query("a".repeat(10 * 1024));
+        assert!(matches!(
+            search.execute(),
+            Err(Error::UserError(UserError::FacetSearchQueryTooLong { length: 10240, .. }))
+        ));
+
+        search.query("  ,;. -!? ");
+        assert!(matches!(
+            search.execute(),
+            Err(Error::UserError(UserError::FacetSearchQueryWithoutWords(_)))
+        ));
+
+        search.query("");
+        assert_eq!(search.execute().unwrap().hits.len(), 20);
+    }
+}
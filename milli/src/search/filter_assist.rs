@@ -0,0 +1,388 @@
+use filter_parser::{expected_at, quote_if_needed, Expected};
+use heed::RoTxn;
+use serde::Serialize;
+
+use crate::heed_codec::facet::{FacetGroupKeyCodec, OrderedF64Codec};
+use crate::heed_codec::{BytesRefCodec, StrRefCodec};
+use crate::search::facet::{get_first_facet_value, get_last_facet_value};
+use crate::{Error, FieldId, Filter, Index, Result, Search, SearchForFacetValues, UserError};
+
+/// The default number of suggestions returned.
+pub const DEFAULT_FILTER_SUGGESTIONS_LIMIT: usize = 20;
+
+/// The operators suggested for the attributes containing numbers.
+const NUMBER_OPERATORS: &[&str] = &[
+    "=",
+    "!=",
+    ">",
+    ">=",
+    "<",
+    "<=",
+    "IN",
+    "NOT IN",
+    "EXISTS",
+    "NOT EXISTS",
+    "IS NULL",
+    "IS NOT NULL",
+    "IS EMPTY",
+    "IS NOT EMPTY",
+];
+/// The operators suggested for the attributes containing strings.
+const STRING_OPERATORS: &[&str] = &[
+    "=",
+    "!=",
+    "IN",
+    "NOT IN",
+    "EXISTS",
+    "NOT EXISTS",
+    "IS NULL",
+    "IS NOT NULL",
+    "IS EMPTY",
+    "IS NOT EMPTY",
+];
+/// The operators suggested for the attributes only containing booleans.
+const BOOLEAN_OPERATORS: &[&str] = &["=", "!=", "EXISTS", "NOT EXISTS", "IS NULL", "IS NOT NULL"];
+/// The operators for which the facet values of the attribute are suggested.
+const VALUE_OPERATORS: &[&str] = &["=", "!=", "IN"];
+const COMBINATORS: &[&str] = &["AND", "OR"];
+
+/// The kind of token expected at the cursor of a filter being written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FilterTokenKind {
+    Attribute,
+    Operator,
+    Value,
+    Combinator,
+}
+
+/// A text that can replace the token being written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FilterSuggestion {
+    pub text: String,
+    /// The number of documents containing the suggested value, only known for the values.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+}
+
+/// What can be written at the cursor of a filter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterSuggestions {
+    /// `None` when the filter written before the cursor is invalid.
+    pub expected: Option<FilterTokenKind>,
+    /// The byte offset of the beginning of the token replaced by a suggestion.
+    pub start: usize,
+    /// The byte offset of the end of the token replaced by a suggestion, the cursor.
+    pub end: usize,
+    pub suggestions: Vec<FilterSuggestion>,
+}
+
+/// Suggests how to complete a filter at a cursor, according to the settings and the facet
+/// values of the index.
+///
+/// Only the displayed filterable attributes are suggested, the facet values are only
+/// suggested after the `=`, `!=`, and `IN` operators, and are found by a facet search.
+pub struct FilterAssist<'a> {
+    filter: String,
+    cursor: usize,
+    rtxn: &'a RoTxn<'a>,
+    index: &'a Index,
+    limit: usize,
+    filter: Option<Filter<'a>>,
+}
+
+impl<'a> FilterAssist<'a> {
+    /// The cursor is a byte offset in the filter.
+    pub fn new(
+        filter: impl Into<String>,
+        cursor: usize,
+        rtxn: &'a RoTxn<'a>,
+        index: &'a Index,
+    ) -> Self {
+        FilterAssist {
+            filter: filter.into(),
+            cursor,
+            rtxn,
+            index,
+            limit: DEFAULT_FILTER_SUGGESTIONS_LIMIT,
+            filter: None,
+        }
+    }
+
+    pub fn limit(&mut self, limit: usize) -> &mut Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Only suggests and counts the facet values of the documents matching this filter.
+    pub fn filter(&mut self, filter: Filter<'a>) -> &mut Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    pub fn execute(&self) -> Result<FilterSuggestions> {
+        let cursor = self.cursor.min(self.filter.len());
+        let Some(expectation) = expected_at(&self.filter, cursor) else {
+            return Ok(FilterSuggestions {
+                expected: None,
+                start: cursor,
+                end: cursor,
+                suggestions: Vec::new(),
+            });
+        };
+
+        let span = expectation.prefix.original_span();
+        let start = span.location_offset();
+        let end = start + span.fragment().len();
+        let prefix = expectation.prefix.value();
+
+        let (expected, mut suggestions) = match expectation.expected {
+            Expected::Attribute => (FilterTokenKind::Attribute, self.attributes(prefix)?),
+            Expected::Operator { attribute } => {
+                (FilterTokenKind::Operator, self.operators(attribute.value(), prefix)?)
+            }
+            Expected::Value { attribute, operator } => {
+                (FilterTokenKind::Value, self.values(attribute.value(), operator, prefix)?)
+            }
+            Expected::Combinator => (FilterTokenKind::Combinator, keywords(COMBINATORS, prefix)),
+        };
+        suggestions.truncate(self.limit);
+
+        Ok(FilterSuggestions { expected: Some(expected), start, end, suggestions })
+    }
+
+    /// The displayed filterable attributes starting with the prefix, in alphabetical order.
+    fn attributes(&self, prefix: &str) -> Result<Vec<FilterSuggestion>> {
+        let filterable_fields = self.index.filterable_fields(self.rtxn)?;
+        let (displayed_filterable_fields, _) =
+            self.index.remove_hidden_fields(self.rtxn, filterable_fields)?;
+
+        Ok(displayed_filterable_fields
+            .iter()
+            // the geo filters are written with functions
+            .filter(|field| field.as_str() != "_geo" && field.starts_with(prefix))
+            .map(|field| FilterSuggestion {
+                text: quote_if_needed(field).into_owned(),
+                count: None,
+            })
+            .collect())
+    }
+
+    /// The operators applicable on the values of the attribute, starting with the prefix.
+    fn operators(&self, attribute: &str, prefix: &str) -> Result<Vec<FilterSuggestion>> {
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        let operators = match fields_ids_map.id(attribute) {
+            Some(fid) => {
+                let kinds = self.value_kinds(fid)?;
+                NUMBER_OPERATORS
+                    .iter()
+                    .copied()
+                    .filter(|operator| {
+                        // an attribute without any value could contain anything
+                        kinds == ValueKinds::default()
+                            || (kinds.number && NUMBER_OPERATORS.contains(operator))
+                            || (kinds.string && STRING_OPERATORS.contains(operator))
+                            || (kinds.boolean && BOOLEAN_OPERATORS.contains(operator))
+                    })
+                    .collect()
+            }
+            None => NUMBER_OPERATORS.to_vec(),
+        };
+
+        Ok(keywords(&operators, prefix))
+    }
+
+    /// The kinds of values stored in the facet databases for this field.
+    fn value_kinds(&self, fid: FieldId) -> Result<ValueKinds> {
+        let number = get_first_facet_value::<OrderedF64Codec>(
+            self.rtxn,
+            self.index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+            fid,
+        )?
+        .is_some();
+
+        let strings_db =
+            self.index.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        let first = get_first_facet_value::<StrRefCodec>(self.rtxn, strings_db, fid)?;
+        let last = get_last_facet_value::<StrRefCodec>(self.rtxn, strings_db, fid)?;
+        // the booleans are also stored as the `false` and `true` strings
+        let (string, boolean) = match (first, last) {
+            (Some(first), Some(last)) => {
+                let is_bool = |value: &str| value == "false" || value == "true";
+                (!(is_bool(first) && is_bool(last)), is_bool(first) || is_bool(last))
+            }
+            _ => (false, false),
+        };
+
+        Ok(ValueKinds { number, string, boolean })
+    }
+
+    /// The facet values of the attribute matching the prefix, for the operators comparing strings.
+    fn values(
+        &self,
+        attribute: &str,
+        operator: &str,
+        prefix: &str,
+    ) -> Result<Vec<FilterSuggestion>> {
+        if !VALUE_OPERATORS.contains(&operator)
+            || !self.index.filterable_fields(self.rtxn)?.contains(attribute)
+        {
+            return Ok(Vec::new());
+        }
+
+        let mut search = Search::new(self.rtxn, self.index);
+        if let Some(filter) = &self.filter {
+            search.filter(filter.clone());
+        }
+        let mut search = SearchForFacetValues::new(attribute.to_string(), search, false);
+        search.max_values(self.limit);
+        if !prefix.is_empty() {
+            search.query(prefix);
+        }
+
+        match search.execute() {
+            Ok(values) => Ok(values
+                .hits
+                .into_iter()
+                .map(|hit| FilterSuggestion {
+                    text: quote_if_needed(&hit.value).into_owned(),
+                    count: Some(hit.count),
+                })
+                .collect()),
+            // a prefix made of separators can't match any value
+            Err(Error::UserError(UserError::FacetSearchQueryWithoutWords(_))) => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Which kinds of values a field contains, a field can contain several kinds of values.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ValueKinds {
+    number: bool,
+    string: bool,
+    boolean: bool,
+}
+
+/// The keywords starting with the prefix, ignoring the case.
+fn keywords(keywords: &[&str], prefix: &str) -> Vec<FilterSuggestion> {
+    let prefix = prefix.to_uppercase();
+    keywords
+        .iter()
+        .filter(|keyword| keyword.starts_with(&prefix))
+        .map(|keyword| FilterSuggestion { text: keyword.to_string(), count: None })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+    use maplit::hashset;
+
+    use super::*;
+    use crate::index::tests::TempIndex;
+
+    fn index() -> TempIndex {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(
+                    hashset! { S("genre"), S("price"), S("available"), S("release date") },
+                );
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "id": 0, "genre": "horror", "price": 12, "available": true, "release date": 1990 },
+                { "id": 1, "genre": "horror", "price": 8, "available": false },
+                { "id": 2, "genre": "science fiction", "price": 20, "available": true },
+                { "id": 3, "genre": "romance" },
+            ]))
+            .unwrap();
+        index
+    }
+
+    fn texts(suggestions: &FilterSuggestions) -> Vec<&str> {
+        suggestions.suggestions.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn suggests_attributes() {
+        let index = index();
+        let rtxn = index.read_txn().unwrap();
+
+        let suggestions = FilterAssist::new("", 0, &rtxn, &index).execute().unwrap();
+        assert_eq!(suggestions.expected, Some(FilterTokenKind::Attribute));
+        assert_eq!(texts(&suggestions), vec!["available", "genre", "price", "\"release date\""]);
+
+        let suggestions =
+            FilterAssist::new("genre = horror AND pr", 21, &rtxn, &index).execute().unwrap();
+        assert_eq!((suggestions.start, suggestions.end), (19, 21));
+        assert_eq!(texts(&suggestions), vec!["price"]);
+    }
+
+    #[test]
+    fn suggests_operators_by_value_kind() {
+        let index = index();
+        let rtxn = index.read_txn().unwrap();
+
+        let suggestions = FilterAssist::new("price ", 6, &rtxn, &index).execute().unwrap();
+        assert_eq!(suggestions.expected, Some(FilterTokenKind::Operator));
+        assert_eq!(texts(&suggestions), NUMBER_OPERATORS);
+
+        let suggestions = FilterAssist::new("genre ", 6, &rtxn, &index).execute().unwrap();
+        assert_eq!(texts(&suggestions), STRING_OPERATORS);
+
+        let suggestions = FilterAssist::new("available IS", 12, &rtxn, &index).execute().unwrap();
+        assert_eq!((suggestions.start, suggestions.end), (10, 12));
+        assert_eq!(texts(&suggestions), vec!["IS NULL", "IS NOT NULL"]);
+    }
+
+    #[test]
+    fn suggests_values() {
+        let index = index();
+        let rtxn = index.read_txn().unwrap();
+
+        let suggestions = FilterAssist::new("genre = ", 8, &rtxn, &index).execute().unwrap();
+        assert_eq!(suggestions.expected, Some(FilterTokenKind::Value));
+        assert_eq!(texts(&suggestions), vec!["horror", "romance", "\"science fiction\""]);
+        assert_eq!(suggestions.suggestions[0].count, Some(2));
+
+        let suggestions =
+            FilterAssist::new("genre IN [horror, sci", 21, &rtxn, &index).execute().unwrap();
+        assert_eq!(texts(&suggestions), vec!["\"science fiction\""]);
+
+        // no values are suggested for the comparisons of numbers
+        let suggestions = FilterAssist::new("price > ", 8, &rtxn, &index).execute().unwrap();
+        assert_eq!(suggestions.expected, Some(FilterTokenKind::Value));
+        assert!(suggestions.suggestions.is_empty());
+    }
+
+    #[test]
+    fn suggests_the_values_of_the_filtered_documents() {
+        let index = index();
+        let rtxn = index.read_txn().unwrap();
+
+        let filter = Filter::from_str("price < 10").unwrap().unwrap();
+        let suggestions =
+            FilterAssist::new("genre = ", 8, &rtxn, &index).filter(filter).execute().unwrap();
+        assert_eq!(texts(&suggestions), vec!["horror"]);
+        assert_eq!(suggestions.suggestions[0].count, Some(1));
+    }
+
+    #[test]
+    fn suggests_combinators() {
+        let index = index();
+        let rtxn = index.read_txn().unwrap();
+
+        let suggestions =
+            FilterAssist::new("genre = horror o", 16, &rtxn, &index).execute().unwrap();
+        assert_eq!(suggestions.expected, Some(FilterTokenKind::Combinator));
+        assert_eq!(texts(&suggestions), vec!["OR"]);
+
+        let suggestions = FilterAssist::new("genre = = ", 10, &rtxn, &index).execute().unwrap();
+        assert_eq!(suggestions.expected, None);
+        assert!(suggestions.suggestions.is_empty());
+    }
+}
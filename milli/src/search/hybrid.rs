@@ -1,18 +1,34 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::time::Instant;
 
 use itertools::Itertools;
 use roaring::RoaringBitmap;
 
 use crate::score_details::{ScoreDetails, ScoreValue, ScoringStrategy};
 use crate::search::SemanticSearch;
-use crate::{MatchingWords, Result, Search, SearchResult};
+use crate::{
+    BucketStep, MatchingWords, QueryGraphDescription, QueryToken, RankingRuleBucketStats, Result,
+    Search, SearchMetrics, SearchResult, SearchTimings, SearchTrace, SkippedRankingRule,
+};
 
 struct ScoreWithRatioResult {
     matching_words: MatchingWords,
     candidates: RoaringBitmap,
     document_scores: Vec<(u32, ScoreWithRatio)>,
+    /// The buckets each document went through, empty if they were not collected.
+    bucket_paths: HashMap<u32, Vec<BucketStep>>,
     degraded: bool,
     used_negative_operator: bool,
+    numeric_prefix_disabled: bool,
+    query_truncated: bool,
+    timings: SearchTimings,
+    metrics: SearchMetrics,
+    skipped_ranking_rules: Vec<SkippedRankingRule>,
+    query_tokens: Vec<QueryToken>,
+    query_graph: Option<QueryGraphDescription>,
+    trace: Option<SearchTrace>,
+    bucket_stats: Option<Vec<RankingRuleBucketStats>>,
 }
 
 type ScoreWithRatio = (Vec<ScoreDetails>, f32);
@@ -69,6 +85,8 @@ fn compare_scores(
 
 impl ScoreWithRatioResult {
     fn new(results: SearchResult, ratio: f32) -> Self {
+        let bucket_paths =
+            results.documents_ids.iter().copied().zip(results.document_bucket_paths).collect();
         let document_scores = results
             .documents_ids
             .into_iter()
@@ -79,8 +97,18 @@ impl ScoreWithRatioResult {
             matching_words: results.matching_words,
             candidates: results.candidates,
             document_scores,
+            bucket_paths,
             degraded: results.degraded,
             used_negative_operator: results.used_negative_operator,
+            numeric_prefix_disabled: results.numeric_prefix_disabled,
+            query_truncated: results.query_truncated,
+            timings: results.timings,
+            metrics: results.metrics,
+            skipped_ranking_rules: results.skipped_ranking_rules,
+            query_tokens: results.query_tokens,
+            query_graph: results.query_graph,
+            trace: results.trace,
+            bucket_stats: results.bucket_stats,
         }
     }
 
@@ -103,6 +131,12 @@ impl ScoreWithRatioResult {
         let mut document_scores = Vec::with_capacity(
             vector_results.document_scores.len() + keyword_results.document_scores.len(),
         );
+        // the paths were collected by both searches or by none of them
+        let collect_bucket_paths =
+            !vector_results.bucket_paths.is_empty() || !keyword_results.bucket_paths.is_empty();
+        let mut vector_bucket_paths = vector_results.bucket_paths;
+        let mut keyword_bucket_paths = keyword_results.bucket_paths;
+        let mut document_bucket_paths = Vec::new();
 
         let mut documents_seen = RoaringBitmap::new();
         for ((docid, (main_score, _sub_score)), source) in vector_results
@@ -129,6 +163,13 @@ impl ScoreWithRatioResult {
             if let ResultSource::Semantic = source {
                 semantic_hit_count += 1;
             }
+            if collect_bucket_paths {
+                let bucket_paths = match source {
+                    ResultSource::Semantic => &mut vector_bucket_paths,
+                    ResultSource::Keyword => &mut keyword_bucket_paths,
+                };
+                document_bucket_paths.push(bucket_paths.remove(&docid).unwrap_or_default());
+            }
             documents_ids.push(docid);
             // TODO: pass both scores to documents_score in some way?
             document_scores.push(main_score);
@@ -140,9 +181,21 @@ impl ScoreWithRatioResult {
                 candidates: vector_results.candidates | keyword_results.candidates,
                 documents_ids,
                 document_scores,
+                document_bucket_paths,
                 degraded: vector_results.degraded | keyword_results.degraded,
                 used_negative_operator: vector_results.used_negative_operator
                     | keyword_results.used_negative_operator,
+                numeric_prefix_disabled: keyword_results.numeric_prefix_disabled,
+                query_truncated: keyword_results.query_truncated,
+                timings: vector_results.timings + keyword_results.timings,
+                metrics: vector_results.metrics + keyword_results.metrics,
+                skipped_ranking_rules: keyword_results.skipped_ranking_rules,
+                query_tokens: keyword_results.query_tokens,
+                query_graph: keyword_results.query_graph,
+                trace: keyword_results.trace,
+                bucket_stats: keyword_results.bucket_stats,
+                // the documents are not explained by a hybrid search
+                explanation: None,
             },
             semantic_hit_count,
         )
@@ -160,9 +213,27 @@ impl<'a> Search<'a> {
             limit: self.limit + self.offset,
             sort_criteria: self.sort_criteria.clone(),
             searchable_attributes: self.searchable_attributes,
+            synonyms: self.synonyms,
+            settings_overlay: self.settings_overlay.clone(),
             geo_strategy: self.geo_strategy,
             terms_matching_strategy: self.terms_matching_strategy,
             scoring_strategy: ScoringStrategy::Detailed,
+            ranking_score_threshold: self.ranking_score_threshold,
+            max_buckets_per_rule: self.max_buckets_per_rule,
+            max_query_terms: self.max_query_terms,
+            explained_document: None,
+            tie_break: self.tie_break,
+            distinct: self.distinct.clone(),
+            bucket_paths: self.bucket_paths,
+            matched_derivations: self.matched_derivations,
+            debug_query_graph: self.debug_query_graph,
+            trace: self.trace,
+            bucket_stats: self.bucket_stats,
+            parallel_term_resolution: self.parallel_term_resolution,
+            small_universe_threshold: self.small_universe_threshold,
+            distinct_strategy: self.distinct_strategy,
+            // the results of the two searches are merged with the offset, not after a document
+            search_after: None,
             words_limit: self.words_limit,
             exhaustive_number_hits: self.exhaustive_number_hits,
             rtxn: self.rtxn,
@@ -188,6 +259,7 @@ impl<'a> Search<'a> {
             return Ok((keyword_results, Some(0)));
         };
 
+        let before_embedding = Instant::now();
         let vector_query = match vector {
             Some(vector_query) => vector_query,
             None => {
@@ -201,12 +273,14 @@ impl<'a> Search<'a> {
                 }
             }
         };
+        let embedding = before_embedding.elapsed();
 
         search.semantic =
             Some(SemanticSearch { vector: Some(vector_query), embedder_name, embedder });
 
         // TODO: would be better to have two distinct functions at this point
-        let vector_results = search.execute()?;
+        let mut vector_results = search.execute()?;
+        vector_results.timings.embedding += embedding;
 
         let keyword_results = ScoreWithRatioResult::new(keyword_results, 1.0 - semantic_ratio);
         let vector_results = ScoreWithRatioResult::new(vector_results, semantic_ratio);
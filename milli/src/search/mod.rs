@@ -1,18 +1,35 @@
+use std::collections::BTreeMap;
 use std::fmt;
+use std::ops::ControlFlow;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use levenshtein_automata::{LevenshteinAutomatonBuilder as LevBuilder, DFA};
 use once_cell::sync::Lazy;
 use roaring::bitmap::RoaringBitmap;
 
-pub use self::facet::{FacetDistribution, Filter, OrderBy, DEFAULT_VALUES_PER_FACET};
+pub use self::builder::{SearchBuilder, SearchHits};
+pub use self::facet::{
+    FacetDistribution, Filter, OrderBy, DEFAULT_APPROXIMATION_THRESHOLD, DEFAULT_VALUES_PER_FACET,
+};
+pub use self::filter_assist::{
+    FilterAssist, FilterSuggestion, FilterSuggestions, FilterTokenKind,
+    DEFAULT_FILTER_SUGGESTIONS_LIMIT,
+};
 pub use self::new::matches::{FormatOptions, MatchBounds, MatcherBuilder, MatchingWords};
-use self::new::{execute_vector_search, PartialSearchResult};
+pub use self::new::SearchMetrics;
+use self::new::{
+    execute_vector_search, explain_document, BucketStatsLogger, BucketStep, ExclusionReason,
+    ExplainLogger, HitsCallback, PartialSearchResult, QueryGraphDescription, QueryGraphLogger,
+    RankingRuleBucketStats, SearchExplanation, SearchTrace, SkippedRankingRule,
+    StructuredSearchLogger,
+};
+pub use self::related_terms::{RelatedTerm, RelatedTerms, DEFAULT_RELATED_TERMS_LIMIT};
 use crate::score_details::{ScoreDetails, ScoringStrategy};
 use crate::vector::Embedder;
 use crate::{
-    execute_search, filtered_universe, AscDesc, DefaultSearchLogger, DocumentId, Index, Result,
-    SearchContext, TimeBudget,
+    execute_search, filtered_universe, AscDesc, Criterion, DocumentId, Index, QueryToken, Result,
+    SearchContext, TimeBudget, UserError,
 };
 
 // Building these factories is not free.
@@ -20,10 +37,13 @@ static LEVDIST0: Lazy<LevBuilder> = Lazy::new(|| LevBuilder::new(0, true));
 static LEVDIST1: Lazy<LevBuilder> = Lazy::new(|| LevBuilder::new(1, true));
 static LEVDIST2: Lazy<LevBuilder> = Lazy::new(|| LevBuilder::new(2, true));
 
+mod builder;
 pub mod facet;
+mod filter_assist;
 mod fst_utils;
 pub mod hybrid;
 pub mod new;
+mod related_terms;
 
 #[derive(Debug, Clone)]
 pub struct SemanticSearch {
@@ -40,9 +60,26 @@ pub struct Search<'a> {
     limit: usize,
     sort_criteria: Option<Vec<AscDesc>>,
     searchable_attributes: Option<&'a [String]>,
+    synonyms: Option<&'a BTreeMap<String, Vec<String>>>,
+    settings_overlay: SettingsOverlay,
     geo_strategy: new::GeoSortStrategy,
     terms_matching_strategy: TermsMatchingStrategy,
     scoring_strategy: ScoringStrategy,
+    ranking_score_threshold: Option<f64>,
+    max_buckets_per_rule: Option<usize>,
+    max_query_terms: Option<usize>,
+    explained_document: Option<DocumentId>,
+    tie_break: TieBreak,
+    distinct: Option<String>,
+    bucket_paths: bool,
+    matched_derivations: bool,
+    debug_query_graph: bool,
+    trace: bool,
+    bucket_stats: bool,
+    parallel_term_resolution: bool,
+    small_universe_threshold: u64,
+    distinct_strategy: Option<new::DistinctStrategy>,
+    search_after: Option<String>,
     words_limit: usize,
     exhaustive_number_hits: bool,
     rtxn: &'a heed::RoTxn<'a>,
@@ -60,9 +97,26 @@ impl<'a> Search<'a> {
             limit: 20,
             sort_criteria: None,
             searchable_attributes: None,
+            synonyms: None,
+            settings_overlay: SettingsOverlay::default(),
             geo_strategy: new::GeoSortStrategy::default(),
             terms_matching_strategy: TermsMatchingStrategy::default(),
             scoring_strategy: Default::default(),
+            ranking_score_threshold: None,
+            max_buckets_per_rule: None,
+            max_query_terms: None,
+            explained_document: None,
+            tie_break: TieBreak::default(),
+            distinct: None,
+            bucket_paths: false,
+            matched_derivations: false,
+            debug_query_graph: false,
+            trace: false,
+            bucket_stats: false,
+            parallel_term_resolution: false,
+            small_universe_threshold: new::DEFAULT_SMALL_UNIVERSE_THRESHOLD,
+            distinct_strategy: None,
+            search_after: None,
             exhaustive_number_hits: false,
             words_limit: 10,
             rtxn,
@@ -72,8 +126,13 @@ impl<'a> Search<'a> {
         }
     }
 
+    /// Sets the query of the search.
+    ///
+    /// A query consisting solely of `*` is a placeholder search, exactly like an empty query.
+    /// A trailing `*` on a word has no special meaning: the last word is already a prefix.
     pub fn query(&mut self, query: impl Into<String>) -> &mut Search<'a> {
-        self.query = Some(query.into());
+        let query = query.into();
+        self.query = if is_wildcard_query(&query) { None } else { Some(query) };
         self
     }
 
@@ -107,6 +166,18 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Expands the query with these synonyms, on top of the synonyms of the index.
+    pub fn synonyms(&mut self, synonyms: &'a BTreeMap<String, Vec<String>>) -> &mut Search<'a> {
+        self.synonyms = Some(synonyms);
+        self
+    }
+
+    /// Searches with these settings instead of the ones of the index.
+    pub fn settings_overlay(&mut self, overlay: SettingsOverlay) -> &mut Search<'a> {
+        self.settings_overlay = overlay;
+        self
+    }
+
     pub fn terms_matching_strategy(&mut self, value: TermsMatchingStrategy) -> &mut Search<'a> {
         self.terms_matching_strategy = value;
         self
@@ -117,11 +188,115 @@ impl<'a> Search<'a> {
         self
     }
 
+    /// Orders the documents ranked the same by all the ranking rules.
+    pub fn tie_break(&mut self, value: TieBreak) -> &mut Search<'a> {
+        self.tie_break = value;
+        self
+    }
+
+    /// Deduplicates the documents on this attribute instead of the distinct attribute of the index.
+    ///
+    /// The attribute must be filterable.
+    pub fn distinct(&mut self, attribute: String) -> &mut Search<'a> {
+        self.distinct = Some(attribute);
+        self
+    }
+
+    /// Only returns the documents whose ranking score is at least this threshold, between `0.0` and `1.0`.
+    ///
+    /// The documents below the threshold are not counted in the candidates, nor in the offset.
+    pub fn ranking_score_threshold(&mut self, value: f64) -> &mut Search<'a> {
+        self.ranking_score_threshold = Some(value);
+        self
+    }
+
+    /// Caps the number of buckets of the ranking rules without a maximum in the settings,
+    /// the documents past the last bucket are returned unsorted by the ranking rule.
+    pub fn max_buckets_per_rule(&mut self, value: usize) -> &mut Search<'a> {
+        self.max_buckets_per_rule = Some(value);
+        self
+    }
+
+    /// Records the buckets of the ranking rules each returned document went through.
+    pub fn bucket_paths(&mut self, value: bool) -> &mut Search<'a> {
+        self.bucket_paths = value;
+        self
+    }
+
+    /// Records the derivations of the query words matched by each returned document,
+    /// the matching words then highlight the words the documents were actually found with.
+    pub fn matched_derivations(&mut self, value: bool) -> &mut Search<'a> {
+        self.matched_derivations = value;
+        self
+    }
+
+    /// Decodes the docids of the terms of long queries in parallel before resolving the query graphs.
+    pub fn parallel_term_resolution(&mut self, value: bool) -> &mut Search<'a> {
+        self.parallel_term_resolution = value;
+        self
+    }
+
+    /// Sets the maximum number of documents of a universe that is verified against the docids
+    /// of each term instead of resolving them, e.g. with a restrictive filter.
+    pub fn small_universe_threshold(&mut self, value: u64) -> &mut Search<'a> {
+        self.small_universe_threshold = value;
+        self
+    }
+
+    /// Forces how the documents sharing a distinct value are discarded, instead of choosing
+    /// the cheapest strategy for the distinct attribute.
+    pub fn distinct_strategy(&mut self, value: new::DistinctStrategy) -> &mut Search<'a> {
+        self.distinct_strategy = Some(value);
+        self
+    }
+
+    /// Returns the query graph the documents were ranked with, to debug the search.
+    pub fn debug_query_graph(&mut self, value: bool) -> &mut Search<'a> {
+        self.debug_query_graph = value;
+        self
+    }
+
+    /// Records the events of the bucket sort, to debug the ranking of the documents.
+    pub fn trace(&mut self, value: bool) -> &mut Search<'a> {
+        self.trace = value;
+        self
+    }
+
+    /// Follows the document through the ranking rules and returns how it was ranked, or why it
+    /// was discarded, in [`SearchResult::explanation`].
+    ///
+    /// The offset and the limit are ignored, all the documents are ranked until this one is.
+    pub fn explain(&mut self, docid: DocumentId) -> &mut Search<'a> {
+        self.explained_document = Some(docid);
+        self
+    }
+
+    /// Returns the sizes of the first buckets of every ranking rule, to tune the ranking rules.
+    pub fn bucket_stats(&mut self, value: bool) -> &mut Search<'a> {
+        self.bucket_stats = value;
+        self
+    }
+
+    /// Returns the documents ranked after the one with this external id, instead of
+    /// skipping the first `offset` ones, which is faster on the deep pages.
+    pub fn search_after(&mut self, document_id: impl Into<String>) -> &mut Search<'a> {
+        self.search_after = Some(document_id.into());
+        self
+    }
+
     pub fn words_limit(&mut self, value: usize) -> &mut Search<'a> {
         self.words_limit = value;
         self
     }
 
+    /// The maximum number of terms of the query whatever the words limit, 150 by default.
+    ///
+    /// The next words are ignored and [`SearchResult::query_truncated`] is set.
+    pub fn max_query_terms(&mut self, value: usize) -> &mut Search<'a> {
+        self.max_query_terms = Some(value);
+        self
+    }
+
     pub fn filter(&mut self, condition: Filter<'a>) -> &mut Search<'a> {
         self.filter = Some(condition);
         self
@@ -155,31 +330,124 @@ impl<'a> Search<'a> {
     }
 
     pub fn execute(&self) -> Result<SearchResult> {
+        self.execute_with(None)
+    }
+
+    /// Executes the search like [`Self::execute`], but also gives the ids of the documents to
+    /// `on_hits` batch by batch, in their ranking order, as soon as the bucket sort ranks them.
+    ///
+    /// When `on_hits` returns [`ControlFlow::Break`] the search stops and only returns the
+    /// documents given so far, its candidates are then incomplete.
+    pub fn execute_streaming(
+        &self,
+        on_hits: impl FnMut(&[DocumentId]) -> Result<ControlFlow<()>> + 'a,
+    ) -> Result<SearchResult> {
+        self.execute_with(Some(Box::new(on_hits)))
+    }
+
+    fn execute_with(&self, hits_callback: Option<HitsCallback<'a>>) -> Result<SearchResult> {
         let mut ctx = SearchContext::new(self.index, self.rtxn);
+        ctx.hits_callback = hits_callback;
+        ctx.collect_bucket_paths = self.bucket_paths;
+        ctx.ranking_score_threshold = self.ranking_score_threshold;
+        ctx.max_buckets_per_rule = self.max_buckets_per_rule;
+        if let Some(max_query_terms) = self.max_query_terms {
+            ctx.max_query_terms = max_query_terms;
+        }
+        ctx.explained_docid = self.explained_document;
+        ctx.parallel_term_resolution = self.parallel_term_resolution;
+        ctx.small_universe_threshold = self.small_universe_threshold;
+        ctx.distinct_strategy = self.distinct_strategy;
+        ctx.settings_overlay = self.settings_overlay.clone();
+        ctx.tie_break = self.tie_break;
+        ctx.canceled = self.time_budget.cancellation();
+
+        if let Some(distinct) = &self.distinct {
+            let filterable_fields = ctx.index.filterable_fields(ctx.txn)?;
+            if !crate::is_faceted(distinct, &filterable_fields) {
+                let (valid_fields, hidden_fields) =
+                    ctx.index.remove_hidden_fields(ctx.txn, filterable_fields)?;
+                return Err(UserError::InvalidDistinctAttribute {
+                    field: distinct.clone(),
+                    valid_fields,
+                    hidden_fields,
+                }
+                .into());
+            }
+            ctx.distinct = Some(distinct.clone());
+        }
 
         if let Some(searchable_attributes) = self.searchable_attributes {
             ctx.searchable_attributes(searchable_attributes)?;
         }
+        ctx.restrict_to_strict_attributes()?;
 
+        if let Some(synonyms) = self.synonyms {
+            ctx.synonyms(synonyms)?;
+        }
+
+        if let Some(document_id) = &self.search_after {
+            let docid = self.index.internal_id_of(self.rtxn, document_id)?.ok_or_else(|| {
+                UserError::InvalidSearchAfter { document_id: document_id.clone() }
+            })?;
+            ctx.search_after = Some(docid);
+        }
+
+        // only one of the placeholder and query loggers is used, depending on the query
+        let mut placeholder_logger = (
+            (
+                self.trace.then(StructuredSearchLogger::default),
+                self.bucket_stats.then(BucketStatsLogger::default),
+            ),
+            self.explained_document.map(ExplainLogger::new),
+        );
+        let mut query_logger = (
+            (
+                (
+                    self.debug_query_graph.then(QueryGraphLogger::default),
+                    self.trace.then(StructuredSearchLogger::default),
+                ),
+                self.bucket_stats.then(BucketStatsLogger::default),
+            ),
+            self.explained_document.map(ExplainLogger::new),
+        );
+
+        let before_universe = Instant::now();
         let universe = filtered_universe(&ctx, &self.filter)?;
+        // an explained search ranks all the documents until the explained one
+        let (offset, limit) = match self.explained_document {
+            Some(docid) => {
+                if !universe.contains(docid) {
+                    ctx.explained_exclusion = Some(ExclusionReason::FilteredOut);
+                }
+                (0, universe.len() as usize)
+            }
+            None => (self.offset, self.limit),
+        };
+        let before_bucket_sort = Instant::now();
         let PartialSearchResult {
             located_query_terms,
             candidates,
             documents_ids,
             document_scores,
+            document_bucket_paths,
             degraded,
             used_negative_operator,
+            numeric_prefix_disabled,
+            query_truncated,
+            query_tokens,
         } = match self.semantic.as_ref() {
             Some(SemanticSearch { vector: Some(vector), embedder_name, embedder }) => {
                 execute_vector_search(
                     &mut ctx,
                     vector,
                     self.scoring_strategy,
+                    self.exhaustive_number_hits,
                     universe,
                     &self.sort_criteria,
                     self.geo_strategy,
-                    self.offset,
-                    self.limit,
+                    offset,
+                    limit,
                     embedder_name,
                     embedder,
                     self.time_budget.clone(),
@@ -194,17 +462,59 @@ impl<'a> Search<'a> {
                 universe,
                 &self.sort_criteria,
                 self.geo_strategy,
-                self.offset,
-                self.limit,
+                offset,
+                limit,
                 Some(self.words_limit),
-                &mut DefaultSearchLogger,
-                &mut DefaultSearchLogger,
+                &mut placeholder_logger,
+                &mut query_logger,
                 self.time_budget.clone(),
             )?,
         };
 
+        let timings = SearchTimings {
+            universe: before_bucket_sort - before_universe,
+            bucket_sort: before_bucket_sort.elapsed(),
+            embedding: Duration::ZERO,
+        };
+
+        let metrics = ctx.metrics;
+        let skipped_ranking_rules = std::mem::take(&mut ctx.skipped_ranking_rules);
+        let (
+            ((query_graph_logger, query_trace_logger), query_bucket_stats_logger),
+            query_explain_logger,
+        ) = query_logger;
+        let (
+            (placeholder_trace_logger, placeholder_bucket_stats_logger),
+            placeholder_explain_logger,
+        ) = placeholder_logger;
+        let query_graph = match query_graph_logger.and_then(|logger| logger.query_graph) {
+            Some(query_graph) => Some(query_graph.description(&mut ctx)?),
+            None => None,
+        };
+        let trace = query_trace_logger
+            .and_then(StructuredSearchLogger::into_trace)
+            .or_else(|| placeholder_trace_logger.and_then(StructuredSearchLogger::into_trace));
+        let bucket_stats = query_bucket_stats_logger
+            .and_then(BucketStatsLogger::into_stats)
+            .or_else(|| placeholder_bucket_stats_logger.and_then(BucketStatsLogger::into_stats));
+
+        let explanation = match self.explained_document {
+            Some(docid) => {
+                let buckets = query_explain_logger
+                    .and_then(ExplainLogger::into_buckets)
+                    .or_else(|| placeholder_explain_logger.and_then(ExplainLogger::into_buckets))
+                    .unwrap_or_default();
+                Some(explain_document(&mut ctx, docid, buckets, &documents_ids)?)
+            }
+            None => None,
+        };
+
         // consume context and located_query_terms to build MatchingWords.
         let matching_words = match located_query_terms {
+            Some(located_query_terms) if self.matched_derivations => {
+                let docids: RoaringBitmap = documents_ids.iter().copied().collect();
+                MatchingWords::with_matched_derivations(ctx, located_query_terms, &docids)?
+            }
             Some(located_query_terms) => MatchingWords::new(ctx, located_query_terms),
             None => MatchingWords::default(),
         };
@@ -213,9 +523,20 @@ impl<'a> Search<'a> {
             matching_words,
             candidates,
             document_scores,
+            document_bucket_paths,
             documents_ids,
             degraded,
             used_negative_operator,
+            numeric_prefix_disabled,
+            query_truncated,
+            timings,
+            metrics,
+            skipped_ranking_rules,
+            query_tokens,
+            query_graph,
+            trace,
+            bucket_stats,
+            explanation,
         })
     }
 }
@@ -229,9 +550,26 @@ impl fmt::Debug for Search<'_> {
             limit,
             sort_criteria,
             searchable_attributes,
+            synonyms,
+            settings_overlay,
             geo_strategy: _,
             terms_matching_strategy,
             scoring_strategy,
+            ranking_score_threshold,
+            max_buckets_per_rule,
+            max_query_terms,
+            explained_document,
+            tie_break,
+            distinct,
+            bucket_paths,
+            matched_derivations,
+            debug_query_graph,
+            trace,
+            bucket_stats,
+            parallel_term_resolution,
+            small_universe_threshold,
+            distinct_strategy,
+            search_after,
             words_limit,
             exhaustive_number_hits,
             rtxn: _,
@@ -247,8 +585,25 @@ impl fmt::Debug for Search<'_> {
             .field("limit", limit)
             .field("sort_criteria", sort_criteria)
             .field("searchable_attributes", searchable_attributes)
+            .field("synonyms", synonyms)
+            .field("settings_overlay", settings_overlay)
             .field("terms_matching_strategy", terms_matching_strategy)
             .field("scoring_strategy", scoring_strategy)
+            .field("ranking_score_threshold", ranking_score_threshold)
+            .field("max_buckets_per_rule", max_buckets_per_rule)
+            .field("max_query_terms", max_query_terms)
+            .field("explained_document", explained_document)
+            .field("tie_break", tie_break)
+            .field("distinct", distinct)
+            .field("bucket_paths", bucket_paths)
+            .field("matched_derivations", matched_derivations)
+            .field("debug_query_graph", debug_query_graph)
+            .field("trace", trace)
+            .field("bucket_stats", bucket_stats)
+            .field("parallel_term_resolution", parallel_term_resolution)
+            .field("small_universe_threshold", small_universe_threshold)
+            .field("distinct_strategy", distinct_strategy)
+            .field("search_after", search_after)
             .field("exhaustive_number_hits", exhaustive_number_hits)
             .field("words_limit", words_limit)
             .field(
@@ -266,8 +621,68 @@ pub struct SearchResult {
     pub candidates: RoaringBitmap,
     pub documents_ids: Vec<DocumentId>,
     pub document_scores: Vec<Vec<ScoreDetails>>,
+    /// The buckets of the ranking rules each document went through,
+    /// empty unless [`Search::bucket_paths`] is enabled.
+    pub document_bucket_paths: Vec<Vec<BucketStep>>,
     pub degraded: bool,
     pub used_negative_operator: bool,
+    /// The last word of the query is a short number that was not used as a prefix.
+    pub numeric_prefix_disabled: bool,
+    /// Words of the query were ignored because it has more terms than the words limit
+    /// or [`Search::max_query_terms`].
+    pub query_truncated: bool,
+    pub timings: SearchTimings,
+    pub metrics: SearchMetrics,
+    /// The ranking rules of the settings that had no effect on the search, and why.
+    pub skipped_ranking_rules: Vec<SkippedRankingRule>,
+    /// The tokens of the query, located in the original query.
+    pub query_tokens: Vec<QueryToken>,
+    /// The query graph the documents were ranked with, `None` unless
+    /// [`Search::debug_query_graph`] is enabled and the search had a query.
+    pub query_graph: Option<QueryGraphDescription>,
+    /// The events of the bucket sort, `None` unless [`Search::trace`] is enabled
+    /// and the documents were sorted with the ranking rules.
+    pub trace: Option<SearchTrace>,
+    /// The sizes of the first buckets of every ranking rule, `None` unless
+    /// [`Search::bucket_stats`] is enabled and the documents were sorted with the ranking rules.
+    pub bucket_stats: Option<Vec<RankingRuleBucketStats>>,
+    /// How the document given to [`Search::explain`] was ranked, `None` unless it is set.
+    pub explanation: Option<SearchExplanation>,
+}
+
+/// The settings a search uses instead of the ones of the index, to preview their effect.
+///
+/// Only the settings read at search time can be overlaid, the other ones
+/// only have an effect once the documents are indexed with them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SettingsOverlay {
+    pub criteria: Option<Vec<Criterion>>,
+    pub authorize_typos: Option<bool>,
+    pub min_word_len_one_typo: Option<u8>,
+    pub min_word_len_two_typos: Option<u8>,
+}
+
+/// The time spent in the main steps of a search.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SearchTimings {
+    /// Computing the documents matching the filter.
+    pub universe: Duration,
+    /// Ranking the documents, with the ranking rules or the vector store.
+    pub bucket_sort: Duration,
+    /// Embedding the query, when a hybrid search had to do it itself.
+    pub embedding: Duration,
+}
+
+impl std::ops::Add for SearchTimings {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        SearchTimings {
+            universe: self.universe + other.universe,
+            bucket_sort: self.bucket_sort + other.bucket_sort,
+            embedding: self.embedding + other.embedding,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -276,6 +691,8 @@ pub enum TermsMatchingStrategy {
     Last,
     // all words are mandatory
     All,
+    // remove the word matching the fewest documents first
+    Frequency,
 }
 
 impl Default for TermsMatchingStrategy {
@@ -284,6 +701,22 @@ impl Default for TermsMatchingStrategy {
     }
 }
 
+/// How the documents ranked the same by all the ranking rules are ordered.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// By internal document id, the order in which the documents were first indexed.
+    #[default]
+    InternalId,
+    /// By external document id, which doesn't depend on the order in which the documents were indexed.
+    /// The ids are compared as numbers when both are, the numbers being ranked before the strings.
+    DocumentId,
+}
+
+/// Returns `true` if the query must be handled as a placeholder search, i.e. it is only made of `*`.
+pub fn is_wildcard_query(query: &str) -> bool {
+    query.trim() == "*"
+}
+
 fn get_first(s: &str) -> &str {
     match s.chars().next() {
         Some(c) => &s[..c.len_utf8()],
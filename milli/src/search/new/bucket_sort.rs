@@ -1,20 +1,36 @@
+use std::cmp::Ordering;
+
 use roaring::RoaringBitmap;
 
 use super::logger::SearchLogger;
-use super::ranking_rules::{BoxRankingRule, RankingRuleQueryTrait};
+use super::ranking_rules::{BoxRankingRule, RankingRuleOutput, RankingRuleQueryTrait};
 use super::SearchContext;
 use crate::score_details::{ScoreDetails, ScoringStrategy};
-use crate::search::new::distinct::{apply_distinct_rule, distinct_single_docid, DistinctOutput};
-use crate::{Result, TimeBudget};
+use crate::search::new::distinct::{apply_distinct_rule, DistinctOutput, DistinctRule};
+use crate::{Result, TieBreak, TimeBudget, UserError};
 
 pub struct BucketSortOutput {
     pub docids: Vec<u32>,
     pub scores: Vec<Vec<ScoreDetails>>,
+    /// The buckets each document went through, only collected when
+    /// [`SearchContext::collect_bucket_paths`] is set, empty otherwise.
+    pub bucket_paths: Vec<Vec<BucketStep>>,
     pub all_candidates: RoaringBitmap,
 
     pub degraded: bool,
 }
 
+/// A bucket of a ranking rule that a document went through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BucketStep {
+    /// The id of the ranking rule.
+    pub ranking_rule: String,
+    /// The index of the bucket among the ones returned by the ranking rule for its parent bucket.
+    pub bucket: usize,
+    /// The number of documents in the bucket.
+    pub bucket_size: u64,
+}
+
 // TODO: would probably be good to regroup some of these inside of a struct?
 #[allow(clippy::too_many_arguments)]
 #[tracing::instrument(level = "trace", skip_all, target = "search::bucket_sort")]
@@ -33,34 +49,45 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
     logger.ranking_rules(&ranking_rules);
     logger.initial_universe(universe);
 
-    let distinct_fid = if let Some(field) = ctx.index.distinct_field(ctx.txn)? {
-        ctx.index.fields_ids_map(ctx.txn)?.id(field)
-    } else {
-        None
+    let mut distinct = match ctx.distinct_fid()? {
+        Some(distinct_fid) => Some(DistinctRule::new(ctx, distinct_fid)?),
+        None => None,
+    };
+
+    let collect_bucket_paths = ctx.collect_bucket_paths;
+    // the documents alone in their bucket must be scored to be compared to the threshold
+    let scoring_strategy = match ctx.ranking_score_threshold {
+        Some(_) => ScoringStrategy::Detailed,
+        None => scoring_strategy,
     };
 
     if universe.len() < from as u64 {
         return Ok(BucketSortOutput {
             docids: vec![],
             scores: vec![],
+            bucket_paths: vec![],
             all_candidates: universe.clone(),
             degraded: false,
         });
     }
     if ranking_rules.is_empty() {
-        if let Some(distinct_fid) = distinct_fid {
+        // without ranking rules all the documents are ranked the same
+        let mut ordered = vec![];
+        let is_ordered = tie_break_order(ctx, universe, &mut ordered)?;
+        if let Some(mut distinct) = distinct {
+            let docids: Box<dyn Iterator<Item = u32>> = match is_ordered {
+                true => Box::new(ordered.into_iter()),
+                false => Box::new(universe.iter()),
+            };
             let mut excluded = RoaringBitmap::new();
             let mut results = vec![];
-            for docid in universe.iter() {
+            for docid in docids {
                 if results.len() >= from + length {
                     break;
                 }
-                if excluded.contains(docid) {
-                    continue;
+                if distinct.apply_single(ctx, docid, &mut excluded)? {
+                    results.push(docid);
                 }
-
-                distinct_single_docid(ctx.index, ctx.txn, distinct_fid, docid, &mut excluded)?;
-                results.push(docid);
             }
 
             let mut all_candidates = universe - excluded;
@@ -74,16 +101,28 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
                 results.clear();
             }
 
+            send_hits(ctx, &results)?;
+            // without ranking rules the documents are not sorted and go through no bucket
+            let bucket_paths =
+                if collect_bucket_paths { vec![vec![]; results.len()] } else { vec![] };
             return Ok(BucketSortOutput {
                 scores: vec![Default::default(); results.len()],
+                bucket_paths,
                 docids: results,
                 all_candidates,
                 degraded: false,
             });
         } else {
-            let docids: Vec<u32> = universe.iter().skip(from).take(length).collect();
+            let docids: Vec<u32> = match is_ordered {
+                true => ordered.into_iter().skip(from).take(length).collect(),
+                false => universe.iter().skip(from).take(length).collect(),
+            };
+            send_hits(ctx, &docids)?;
+            let bucket_paths =
+                if collect_bucket_paths { vec![vec![]; docids.len()] } else { vec![] };
             return Ok(BucketSortOutput {
                 scores: vec![Default::default(); docids.len()],
+                bucket_paths,
                 docids,
                 all_candidates: universe.clone(),
                 degraded: false,
@@ -98,10 +137,16 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
     ranking_rules[0].start_iteration(ctx, logger, universe, query)?;
 
     let mut ranking_rule_scores: Vec<ScoreDetails> = vec![];
+    // Like the scores, the steps of the current bucket of each ranking rule,
+    // along with the number of buckets each ranking rule returned so far.
+    let mut ranking_rule_bucket_steps: Vec<BucketStep> = vec![];
+    let mut ranking_rule_bucket_counts: Vec<usize> = vec![0; ranking_rules_len];
 
     let mut ranking_rule_universes: Vec<RoaringBitmap> =
         vec![RoaringBitmap::default(); ranking_rules_len];
     ranking_rule_universes[0] = universe.clone();
+    // The query each ranking rule was started with, given to its collapsed last bucket.
+    let mut ranking_rule_queries: Vec<Q> = vec![query.clone(); ranking_rules_len];
     let mut cur_ranking_rule_index = 0;
 
     /// Finish iterating over the current ranking rule, yielding
@@ -130,42 +175,50 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
             if ranking_rule_scores.len() > cur_ranking_rule_index {
                 ranking_rule_scores.pop();
             }
+            ranking_rule_bucket_steps.truncate(cur_ranking_rule_index);
         };
     }
 
-    let mut all_candidates = universe.clone();
-    let mut valid_docids = vec![];
-    let mut valid_scores = vec![];
-    let mut cur_offset = 0usize;
-
-    macro_rules! maybe_add_to_results {
-        ($candidates:expr) => {
-            maybe_add_to_results(
-                ctx,
-                from,
-                length,
-                logger,
-                &mut valid_docids,
-                &mut valid_scores,
-                &mut all_candidates,
-                &mut ranking_rule_universes,
-                &mut ranking_rules,
-                cur_ranking_rule_index,
-                &mut cur_offset,
-                distinct_fid,
-                &ranking_rule_scores,
-                $candidates,
-            )?;
-        };
-    }
+    let mut results = BucketSortResults::new(universe, length);
 
-    while valid_docids.len() < length {
+    // the search stops early when the hits callback doesn't want more documents
+    while results.docids.len() < length && !results.stopped {
+        // a canceled search is stopped with an error rather than degraded, nobody reads its results
+        ctx.check_canceled()?;
+        // an explained search stops once its document is returned or discarded
+        if let Some(docid) = ctx.explained_docid {
+            if !ranking_rule_universes.iter().any(|universe| universe.contains(docid)) {
+                break;
+            }
+        }
         if time_budget.exceeded() {
             loop {
                 let bucket = std::mem::take(&mut ranking_rule_universes[cur_ranking_rule_index]);
                 ranking_rule_scores.push(ScoreDetails::Skipped);
-                maybe_add_to_results!(bucket);
+                // the rest of the universe of the ranking rule is its last, unsorted, bucket
+                if collect_bucket_paths {
+                    ranking_rule_bucket_steps.push(BucketStep {
+                        ranking_rule: ranking_rules[cur_ranking_rule_index].id(),
+                        bucket: ranking_rule_bucket_counts[cur_ranking_rule_index],
+                        bucket_size: bucket.len(),
+                    });
+                }
+                add_to_results(
+                    ctx,
+                    from,
+                    length,
+                    logger,
+                    &mut results,
+                    &mut ranking_rule_universes,
+                    &mut ranking_rules,
+                    cur_ranking_rule_index,
+                    distinct.as_mut(),
+                    &ranking_rule_scores,
+                    collect_bucket_paths.then_some(ranking_rule_bucket_steps.as_slice()),
+                    bucket,
+                )?;
                 ranking_rule_scores.pop();
+                ranking_rule_bucket_steps.truncate(cur_ranking_rule_index);
 
                 if cur_ranking_rule_index == 0 {
                     break;
@@ -174,12 +227,7 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
                 back!();
             }
 
-            return Ok(BucketSortOutput {
-                scores: valid_scores,
-                docids: valid_docids,
-                all_candidates,
-                degraded: true,
-            });
+            return Ok(results.into_output(true));
         }
 
         // The universe for this bucket is zero, so we don't need to sort
@@ -189,7 +237,20 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
                 && ranking_rule_universes[cur_ranking_rule_index].len() == 1)
         {
             let bucket = std::mem::take(&mut ranking_rule_universes[cur_ranking_rule_index]);
-            maybe_add_to_results!(bucket);
+            add_to_results(
+                ctx,
+                from,
+                length,
+                logger,
+                &mut results,
+                &mut ranking_rule_universes,
+                &mut ranking_rules,
+                cur_ranking_rule_index,
+                distinct.as_mut(),
+                &ranking_rule_scores,
+                collect_bucket_paths.then_some(ranking_rule_bucket_steps.as_slice()),
+                bucket,
+            )?;
             back!();
             continue;
         }
@@ -197,18 +258,40 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
         let span = tracing::trace_span!(target: "search::bucket_sort", "next_bucket", id = ranking_rules[cur_ranking_rule_index].id());
         let entered = span.enter();
 
-        let Some(next_bucket) = ranking_rules[cur_ranking_rule_index].next_bucket(
-            ctx,
-            logger,
-            &ranking_rule_universes[cur_ranking_rule_index],
-        )?
-        else {
-            back!();
-            continue;
+        let max_buckets =
+            ranking_rules[cur_ranking_rule_index].max_buckets().or(ctx.max_buckets_per_rule);
+        let next_bucket = if max_buckets.map_or(false, |max_buckets| {
+            ranking_rule_bucket_counts[cur_ranking_rule_index] + 1 >= max_buckets
+        }) {
+            // the rest of the universe of the ranking rule is its last, unsorted, bucket
+            RankingRuleOutput {
+                query: ranking_rule_queries[cur_ranking_rule_index].clone(),
+                candidates: ranking_rule_universes[cur_ranking_rule_index].clone(),
+                score: ScoreDetails::Skipped,
+            }
+        } else {
+            let Some(next_bucket) = ranking_rules[cur_ranking_rule_index].next_bucket(
+                ctx,
+                logger,
+                &ranking_rule_universes[cur_ranking_rule_index],
+            )?
+            else {
+                back!();
+                continue;
+            };
+            next_bucket
         };
         drop(entered);
 
         ranking_rule_scores.push(next_bucket.score);
+        if collect_bucket_paths {
+            ranking_rule_bucket_steps.push(BucketStep {
+                ranking_rule: ranking_rules[cur_ranking_rule_index].id(),
+                bucket: ranking_rule_bucket_counts[cur_ranking_rule_index],
+                bucket_size: next_bucket.candidates.len(),
+            });
+        }
+        ranking_rule_bucket_counts[cur_ranking_rule_index] += 1;
 
         logger.next_bucket_ranking_rule(
             cur_ranking_rule_index,
@@ -221,18 +304,45 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
             ranking_rule_universes[cur_ranking_rule_index].is_superset(&next_bucket.candidates)
         );
         ranking_rule_universes[cur_ranking_rule_index] -= &next_bucket.candidates;
+        ctx.metrics.bitmap_differences += 1;
+
+        // the documents of a bucket below the threshold are neither sorted by the next ranking rules
+        // nor returned, and they don't count in the offset
+        if below_ranking_score_threshold(ctx, &ranking_rule_scores) {
+            results.all_candidates -= &next_bucket.candidates;
+            ctx.metrics.bitmap_differences += 1;
+            ranking_rule_scores.pop();
+            ranking_rule_bucket_steps.truncate(cur_ranking_rule_index);
+            continue;
+        }
 
         if cur_ranking_rule_index == ranking_rules_len - 1
             || (scoring_strategy == ScoringStrategy::Skip && next_bucket.candidates.len() <= 1)
-            || cur_offset + (next_bucket.candidates.len() as usize) < from
+            || results.cur_offset + (next_bucket.candidates.len() as usize) < from
         {
-            maybe_add_to_results!(next_bucket.candidates);
+            add_to_results(
+                ctx,
+                from,
+                length,
+                logger,
+                &mut results,
+                &mut ranking_rule_universes,
+                &mut ranking_rules,
+                cur_ranking_rule_index,
+                distinct.as_mut(),
+                &ranking_rule_scores,
+                collect_bucket_paths.then_some(ranking_rule_bucket_steps.as_slice()),
+                next_bucket.candidates,
+            )?;
             ranking_rule_scores.pop();
+            ranking_rule_bucket_steps.truncate(cur_ranking_rule_index);
             continue;
         }
 
         cur_ranking_rule_index += 1;
+        ranking_rule_bucket_counts[cur_ranking_rule_index] = 0;
         ranking_rule_universes[cur_ranking_rule_index] = next_bucket.candidates.clone();
+        ranking_rule_queries[cur_ranking_rule_index] = next_bucket.query.clone();
         logger.start_iteration_ranking_rule(
             cur_ranking_rule_index,
             ranking_rules[cur_ranking_rule_index].as_ref(),
@@ -247,93 +357,308 @@ pub fn bucket_sort<'ctx, Q: RankingRuleQueryTrait>(
         )?;
     }
 
-    Ok(BucketSortOutput {
-        docids: valid_docids,
-        scores: valid_scores,
-        all_candidates,
-        degraded: false,
-    })
+    Ok(results.into_output(false))
 }
 
-/// Add the candidates to the results. Take `distinct`, `from`, `length`, and `cur_offset`
-/// into account and inform the logger.
+/// Returns the `length` documents ranked right after `after_docid`, like [`bucket_sort`] would
+/// return them with the offset of the page following `after_docid`, without walking
+/// the buckets of all the documents ranked before it.
+///
+/// The bucket of `after_docid` is resolved first with the same ranking rules, only following
+/// the path leading to it, and the documents ranked at or before it, along with the ones
+/// discarded by the distinct attribute because of them, are removed from the universe.
+/// The remaining documents are then sorted normally.
 #[allow(clippy::too_many_arguments)]
-fn maybe_add_to_results<'ctx, Q: RankingRuleQueryTrait>(
+#[tracing::instrument(level = "trace", skip_all, target = "search::bucket_sort")]
+pub fn bucket_sort_after<'ctx, Q: RankingRuleQueryTrait>(
+    ctx: &mut SearchContext<'ctx>,
+    mut ranking_rules: Vec<BoxRankingRule<'ctx, Q>>,
+    query: &Q,
+    universe: &RoaringBitmap,
+    after_docid: u32,
+    length: usize,
+    scoring_strategy: ScoringStrategy,
+    logger: &mut dyn SearchLogger<Q>,
+    time_budget: TimeBudget,
+) -> Result<BucketSortOutput> {
+    if !universe.contains(after_docid) {
+        let document_id = ctx.index.external_ids_of(ctx.txn, [after_docid])?.remove(0);
+        return Err(UserError::InvalidSearchAfter { document_id }.into());
+    }
+
+    let mut ranked_before = RoaringBitmap::new();
+    let mut bucket = universe.clone();
+    let mut bucket_query = query.clone();
+    for ranking_rule in ranking_rules.iter_mut() {
+        if bucket.len() <= 1 {
+            break;
+        }
+
+        ranking_rule.start_iteration(ctx, logger, &bucket, &bucket_query)?;
+        let max_buckets = ranking_rule.max_buckets().or(ctx.max_buckets_per_rule);
+        let mut remaining = bucket.clone();
+        let mut found = None;
+        let mut bucket_count = 0;
+        while !remaining.is_empty() {
+            // like in the bucket sort, the documents past the maximum number of buckets
+            // of the ranking rule are left to its last bucket
+            if max_buckets.map_or(false, |max_buckets| bucket_count + 1 >= max_buckets) {
+                break;
+            }
+            let Some(next_bucket) = ranking_rule.next_bucket(ctx, logger, &remaining)? else {
+                break;
+            };
+            bucket_count += 1;
+            remaining -= &next_bucket.candidates;
+            if next_bucket.candidates.contains(after_docid) {
+                found = Some(next_bucket);
+                break;
+            }
+            ranked_before |= next_bucket.candidates;
+        }
+        ranking_rule.end_iteration(ctx, logger);
+
+        match found {
+            Some(next_bucket) => {
+                bucket = next_bucket.candidates;
+                bucket_query = next_bucket.query;
+            }
+            // the documents not returned by a ranking rule are its last bucket
+            None => bucket = remaining,
+        }
+    }
+    // the documents of the last bucket are returned in the tie break order
+    let mut ordered = vec![];
+    if tie_break_order(ctx, &bucket, &mut ordered)? {
+        for docid in ordered {
+            ranked_before.insert(docid);
+            if docid == after_docid {
+                break;
+            }
+        }
+    } else {
+        let mut bucket_before = bucket;
+        bucket_before.remove_range(after_docid + 1..);
+        ranked_before |= bucket_before;
+    }
+
+    let mut universe = universe - &ranked_before;
+    let mut ranked_before_candidates = ranked_before.clone();
+    if let Some(distinct_fid) = ctx.distinct_fid()? {
+        // whatever the strategy of the distinct rule, the documents sharing a value with the ones
+        // ranked before must be removed from the universe, as the bucket sort starts over
+        let DistinctOutput { remaining, excluded } =
+            apply_distinct_rule(ctx, distinct_fid, &ranked_before)?;
+        universe -= excluded;
+        ranked_before_candidates = remaining;
+    }
+
+    let mut output = bucket_sort(
+        ctx,
+        ranking_rules,
+        query,
+        &universe,
+        0,
+        length,
+        scoring_strategy,
+        logger,
+        time_budget,
+    )?;
+    output.all_candidates |= ranked_before_candidates;
+    Ok(output)
+}
+
+/// Gives the documents just added to the results to the hits callback of the search, if any,
+/// and returns whether it asked to stop the search.
+fn send_hits(ctx: &mut SearchContext, docids: &[u32]) -> Result<bool> {
+    match &mut ctx.hits_callback {
+        Some(on_hits) if !docids.is_empty() => Ok(on_hits(docids)?.is_break()),
+        _ => Ok(false),
+    }
+}
+
+/// Writes the candidates in the order they must be returned in when they are ranked the same
+/// by all the ranking rules into `ordered`, or returns `false` when it is the order of their ids.
+fn tie_break_order(
+    ctx: &SearchContext,
+    candidates: &RoaringBitmap,
+    ordered: &mut Vec<u32>,
+) -> Result<bool> {
+    match ctx.tie_break {
+        TieBreak::DocumentId if candidates.len() > 1 => {
+            let external_ids = ctx.index.external_ids_of(ctx.txn, candidates)?;
+            let mut docids: Vec<_> = candidates.iter().zip(external_ids).collect();
+            docids.sort_unstable_by(|(_, left), (_, right)| compare_document_ids(left, right));
+            ordered.clear();
+            ordered.extend(docids.into_iter().map(|(docid, _)| docid));
+            Ok(true)
+        }
+        TieBreak::DocumentId | TieBreak::InternalId => Ok(false),
+    }
+}
+
+/// Compares the external ids as numbers when both are, the numbers being ordered before the strings.
+fn compare_document_ids(left: &str, right: &str) -> Ordering {
+    match (left.parse::<u64>(), right.parse::<u64>()) {
+        (Ok(l), Ok(r)) => l.cmp(&r).then_with(|| left.cmp(right)),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => left.cmp(right),
+    }
+}
+
+/// Whether the documents of a bucket with these scores rank below the ranking score threshold
+/// of the search, if any.
+///
+/// The buckets of the next ranking rules can only lower the score of the documents,
+/// the bucket can thus be dropped before them.
+fn below_ranking_score_threshold(
+    ctx: &SearchContext,
+    ranking_rule_scores: &[ScoreDetails],
+) -> bool {
+    ctx.ranking_score_threshold
+        .is_some_and(|threshold| ScoreDetails::global_score(ranking_rule_scores.iter()) < threshold)
+}
+
+/// The documents returned by the bucket sort so far, along with their scores and bucket paths.
+struct BucketSortResults {
+    docids: Vec<u32>,
+    scores: Vec<Vec<ScoreDetails>>,
+    bucket_paths: Vec<Vec<BucketStep>>,
+    all_candidates: RoaringBitmap,
+    /// The number of documents ranked so far, the skipped ones included.
+    cur_offset: usize,
+    /// Whether the hits callback asked to stop the search.
+    stopped: bool,
+}
+
+impl BucketSortResults {
+    fn new(universe: &RoaringBitmap, length: usize) -> Self {
+        // no more than the documents of the universe can be returned, whatever the length
+        let capacity = length.min(universe.len() as usize);
+        Self {
+            docids: Vec::with_capacity(capacity),
+            scores: Vec::with_capacity(capacity),
+            bucket_paths: Vec::new(),
+            all_candidates: universe.clone(),
+            cur_offset: 0,
+            stopped: false,
+        }
+    }
+
+    fn into_output(self, degraded: bool) -> BucketSortOutput {
+        let Self { docids, scores, bucket_paths, all_candidates, cur_offset: _, stopped: _ } = self;
+        BucketSortOutput { docids, scores, bucket_paths, all_candidates, degraded }
+    }
+}
+
+/// Add the candidates to the results. Take `distinct`, `from`, `length`, and the current
+/// offset of the results into account and inform the logger.
+///
+/// The steps of the buckets are only given when the bucket paths are collected.
+#[allow(clippy::too_many_arguments)]
+fn add_to_results<'ctx, Q: RankingRuleQueryTrait>(
     ctx: &mut SearchContext<'ctx>,
     from: usize,
     length: usize,
     logger: &mut dyn SearchLogger<Q>,
 
-    valid_docids: &mut Vec<u32>,
-    valid_scores: &mut Vec<Vec<ScoreDetails>>,
-    all_candidates: &mut RoaringBitmap,
+    results: &mut BucketSortResults,
 
     ranking_rule_universes: &mut [RoaringBitmap],
     ranking_rules: &mut [BoxRankingRule<'ctx, Q>],
 
     cur_ranking_rule_index: usize,
 
-    cur_offset: &mut usize,
-
-    distinct_fid: Option<u16>,
+    distinct: Option<&mut DistinctRule<'ctx>>,
     ranking_rule_scores: &[ScoreDetails],
+    bucket_steps: Option<&[BucketStep]>,
     candidates: RoaringBitmap,
 ) -> Result<()> {
+    // a stopped search, degraded or not, doesn't return more documents
+    if results.stopped {
+        return Ok(());
+    }
+
+    // The documents below the threshold are dropped before the distinct rule and the offset apply.
+    if below_ranking_score_threshold(ctx, ranking_rule_scores) {
+        results.all_candidates -= &candidates;
+        ctx.metrics.bitmap_differences += 1;
+        return Ok(());
+    }
+
+    // The ties are broken first, for the distinct rule to keep the first document of each value
+    // in this order, and for the documents to be skipped in this order too.
+    // The order is written in the buffer of the context, reused from one bucket to the next.
+    let mut ordered = std::mem::take(&mut ctx.tie_break_buffer);
+    let is_ordered = tie_break_order(ctx, &candidates, &mut ordered)?;
+
     // First apply the distinct rule on the candidates, reducing the universes if necessary
-    let candidates = if let Some(distinct_fid) = distinct_fid {
-        let DistinctOutput { remaining, excluded } =
-            apply_distinct_rule(ctx, distinct_fid, &candidates)?;
+    let candidates = if let Some(distinct) = distinct {
+        let DistinctOutput { remaining, excluded } = if is_ordered {
+            distinct.apply(ctx, ordered.iter().copied())?
+        } else {
+            distinct.apply(ctx, &candidates)?
+        };
+        logger.distinct_excluded(&excluded, &remaining);
         for universe in ranking_rule_universes.iter_mut() {
             *universe -= &excluded;
-            *all_candidates -= &excluded;
+            results.all_candidates -= &excluded;
+            ctx.metrics.bitmap_differences += 2;
         }
         remaining
     } else {
-        candidates.clone()
+        candidates
     };
-    *all_candidates |= &candidates;
+    results.all_candidates |= &candidates;
+    ctx.metrics.bitmap_unions += 1;
 
     // if the candidates are empty, there is nothing to do;
-    if candidates.is_empty() {
-        return Ok(());
-    }
+    if !candidates.is_empty() {
+        let mut docids: Box<dyn Iterator<Item = u32> + '_> = if is_ordered {
+            Box::new(ordered.iter().copied().filter(|docid| candidates.contains(*docid)))
+        } else {
+            Box::new(candidates.iter())
+        };
 
-    // if we still haven't reached the first document to return
-    if *cur_offset < from {
-        // and if no document from this bucket can be returned
-        if *cur_offset + (candidates.len() as usize) < from {
-            // then just skip the bucket
+        // if no document from this bucket can be returned, then just skip the bucket
+        if results.cur_offset + (candidates.len() as usize) < from {
             logger.skip_bucket_ranking_rule(
                 cur_ranking_rule_index,
                 ranking_rules[cur_ranking_rule_index].as_ref(),
                 &candidates,
             );
         } else {
-            // otherwise, skip some of the documents and add some of the rest, in order of ids
-            let candidates_vec = candidates.iter().collect::<Vec<_>>();
-            let (skipped_candidates, candidates) = candidates_vec.split_at(from - *cur_offset);
-
-            logger.skip_bucket_ranking_rule(
-                cur_ranking_rule_index,
-                ranking_rules[cur_ranking_rule_index].as_ref(),
-                &skipped_candidates.iter().collect(),
-            );
-            let candidates =
-                candidates.iter().take(length - valid_docids.len()).copied().collect::<Vec<_>>();
-            logger.add_to_results(&candidates);
-            valid_docids.extend_from_slice(&candidates);
-            valid_scores
-                .extend(std::iter::repeat(ranking_rule_scores.to_owned()).take(candidates.len()));
+            // otherwise, skip the documents before the first one to return, in the tie break order
+            if results.cur_offset < from {
+                let skipped: RoaringBitmap =
+                    docids.by_ref().take(from - results.cur_offset).collect();
+                logger.skip_bucket_ranking_rule(
+                    cur_ranking_rule_index,
+                    ranking_rules[cur_ranking_rule_index].as_ref(),
+                    &skipped,
+                );
+            }
+            // and add some of the rest (up to the limit)
+            let previous_len = results.docids.len();
+            results.docids.extend(docids.take(length - previous_len));
+            let added = &results.docids[previous_len..];
+            logger.add_to_results(added);
+            results
+                .scores
+                .extend(std::iter::repeat(ranking_rule_scores.to_owned()).take(added.len()));
+            if let Some(bucket_steps) = bucket_steps {
+                results
+                    .bucket_paths
+                    .extend(std::iter::repeat(bucket_steps.to_vec()).take(added.len()));
+            }
+            results.stopped = send_hits(ctx, &results.docids[previous_len..])?;
         }
-    } else {
-        // if we have passed the offset already, add some of the documents (up to the limit)
-        let candidates = candidates.iter().take(length - valid_docids.len()).collect::<Vec<u32>>();
-        logger.add_to_results(&candidates);
-        valid_docids.extend_from_slice(&candidates);
-        valid_scores
-            .extend(std::iter::repeat(ranking_rule_scores.to_owned()).take(candidates.len()));
+
+        results.cur_offset += candidates.len() as usize;
     }
 
-    *cur_offset += candidates.len() as usize;
+    ordered.clear();
+    ctx.tie_break_buffer = ordered;
     Ok(())
 }
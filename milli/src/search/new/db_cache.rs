@@ -1,14 +1,20 @@
 use std::borrow::Cow;
 use std::collections::hash_map::Entry;
+use std::collections::BTreeMap;
 use std::hash::Hash;
 
+use fst::automaton::Str;
+use fst::{Automaton, IntoStreamer, Streamer};
 use fxhash::FxHashMap;
 use heed::types::Bytes;
 use heed::{BytesEncode, Database, RoTxn};
 use roaring::RoaringBitmap;
 
+use super::distinct::DistinctValue;
 use super::interner::Interned;
-use super::Word;
+use super::metrics::SearchMetrics;
+use super::shared_cache::SharedDocids;
+use super::{limits, Word};
 use crate::heed_codec::{BytesDecodeOwned, StrBEU16Codec};
 use crate::proximity::ProximityPrecision;
 use crate::update::{merge_cbo_roaring_bitmaps, MergeFn};
@@ -28,13 +34,15 @@ pub struct DatabaseCache<'ctx> {
     pub word_prefix_pair_proximity_docids:
         FxHashMap<(u8, Interned<String>, Interned<String>), Option<RoaringBitmap>>,
     pub prefix_word_pair_proximity_docids:
-        FxHashMap<(u8, Interned<String>, Interned<String>), Option<Cow<'ctx, [u8]>>>,
-    pub word_docids: FxHashMap<Interned<String>, Option<Cow<'ctx, [u8]>>>,
-    pub exact_word_docids: FxHashMap<Interned<String>, Option<Cow<'ctx, [u8]>>>,
-    pub word_prefix_docids: FxHashMap<Interned<String>, Option<Cow<'ctx, [u8]>>>,
-    pub exact_word_prefix_docids: FxHashMap<Interned<String>, Option<Cow<'ctx, [u8]>>>,
+        FxHashMap<(u8, Interned<String>, Interned<String>), Option<RoaringBitmap>>,
+    pub word_docids: WordDocidsCache<'ctx>,
+    pub exact_word_docids: WordDocidsCache<'ctx>,
+    pub strict_word_docids: WordDocidsCache<'ctx>,
+    pub word_prefix_docids: WordDocidsCache<'ctx>,
+    pub exact_word_prefix_docids: WordDocidsCache<'ctx>,
 
     pub words_fst: Option<fst::Set<Cow<'ctx, [u8]>>>,
+    pub prefix_completions: FxHashMap<Interned<String>, Vec<Interned<String>>>,
     pub word_position_docids: FxHashMap<(Interned<String>, u16), Option<Cow<'ctx, [u8]>>>,
     pub word_prefix_position_docids: FxHashMap<(Interned<String>, u16), Option<Cow<'ctx, [u8]>>>,
     pub word_positions: FxHashMap<Interned<String>, Vec<u16>>,
@@ -44,13 +52,137 @@ pub struct DatabaseCache<'ctx> {
     pub word_prefix_fid_docids: FxHashMap<(Interned<String>, u16), Option<Cow<'ctx, [u8]>>>,
     pub word_fids: FxHashMap<Interned<String>, Vec<u16>>,
     pub word_prefix_fids: FxHashMap<Interned<String>, Vec<u16>>,
+
+    pub distinct_values: FxHashMap<(u16, u32), Vec<DistinctValue<'ctx>>>,
+    pub distinct_value_docids: FxHashMap<(u16, DistinctValue<'ctx>), Option<RoaringBitmap>>,
+}
+
+/// A map of the [`DatabaseCache`], storing the values of a database by key.
+trait BytesCache<'ctx, K> {
+    /// Returns whether the key is cached, marking it as used.
+    fn touch(&mut self, key: &K) -> bool;
+
+    fn cached(&self, key: &K) -> Option<&Option<Cow<'ctx, [u8]>>>;
+
+    fn store(&mut self, key: K, value: Option<Cow<'ctx, [u8]>>);
+
+    /// The values read by the warm-up of the index, consulted before the database.
+    fn shared(&self) -> Option<&SharedDocids> {
+        None
+    }
+}
+
+impl<'ctx, K: Eq + Hash> BytesCache<'ctx, K> for FxHashMap<K, Option<Cow<'ctx, [u8]>>> {
+    fn touch(&mut self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+
+    fn cached(&self, key: &K) -> Option<&Option<Cow<'ctx, [u8]>>> {
+        self.get(key)
+    }
+
+    fn store(&mut self, key: K, value: Option<Cow<'ctx, [u8]>>) {
+        self.insert(key, value);
+    }
+}
+
+/// The cache of the docids of the words or of the prefixes.
+///
+/// A query with many derivations, such as the prefixes of a short word on an index with
+/// many words, may look up the docids of tens of thousands of words. The cache is unbounded
+/// by default, but it can be given a capacity, see [`SearchContext::database_cache_capacity`],
+/// over which the least recently used docids are evicted.
+#[derive(Default)]
+pub struct WordDocidsCache<'ctx> {
+    /// The docids of the words along with the time of their last use.
+    entries: FxHashMap<Interned<String>, (Option<Cow<'ctx, [u8]>>, u64)>,
+    /// The words by the time of their last use, only maintained when there is a capacity.
+    recency: BTreeMap<u64, Interned<String>>,
+    capacity: Option<usize>,
+    clock: u64,
+    shared: Option<SharedDocids>,
+}
+
+impl<'ctx> WordDocidsCache<'ctx> {
+    /// Bounds the number of cached docids, `None` to never evict them.
+    pub fn set_capacity(&mut self, capacity: Option<usize>) {
+        self.capacity = capacity.map(|capacity| capacity.max(1));
+        self.recency = match self.capacity {
+            Some(_) => {
+                self.entries.iter().map(|(word, (_, last_use))| (*last_use, *word)).collect()
+            }
+            None => BTreeMap::new(),
+        };
+        self.evict();
+    }
+
+    /// Consults the docids read by the warm-up of the index before the database.
+    pub fn set_shared(&mut self, shared: Option<SharedDocids>) {
+        self.shared = shared;
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    fn evict(&mut self) {
+        let Some(capacity) = self.capacity else { return };
+        while self.entries.len() > capacity {
+            let Some((_, word)) = self.recency.pop_first() else { break };
+            self.entries.remove(&word);
+        }
+    }
 }
+
+impl<'ctx> BytesCache<'ctx, Interned<String>> for WordDocidsCache<'ctx> {
+    fn touch(&mut self, word: &Interned<String>) -> bool {
+        let Some((_, last_use)) = self.entries.get_mut(word) else { return false };
+        self.clock += 1;
+        if self.capacity.is_some() {
+            self.recency.remove(last_use);
+            self.recency.insert(self.clock, *word);
+        }
+        *last_use = self.clock;
+        true
+    }
+
+    fn cached(&self, word: &Interned<String>) -> Option<&Option<Cow<'ctx, [u8]>>> {
+        self.entries.get(word).map(|(value, _)| value)
+    }
+
+    fn store(&mut self, word: Interned<String>, value: Option<Cow<'ctx, [u8]>>) {
+        self.clock += 1;
+        let previous = self.entries.insert(word, (value, self.clock));
+        if self.capacity.is_some() {
+            if let Some((_, last_use)) = previous {
+                self.recency.remove(&last_use);
+            }
+            self.recency.insert(self.clock, word);
+            self.evict();
+        }
+    }
+
+    fn shared(&self) -> Option<&SharedDocids> {
+        self.shared.as_ref()
+    }
+}
+
 impl<'ctx> DatabaseCache<'ctx> {
     fn get_value<'v, K1, KC, DC>(
         txn: &'ctx RoTxn,
         cache_key: K1,
         db_key: &'v KC::EItem,
         cache: &mut FxHashMap<K1, Option<Cow<'ctx, [u8]>>>,
+        metrics: &mut SearchMetrics,
         db: Database<KC, Bytes>,
     ) -> Result<Option<DC::DItem>>
     where
@@ -58,22 +190,8 @@ impl<'ctx> DatabaseCache<'ctx> {
         KC: BytesEncode<'v>,
         DC: BytesDecodeOwned,
     {
-        if let Entry::Vacant(entry) = cache.entry(cache_key) {
-            let bitmap_ptr = db.get(txn, db_key)?.map(Cow::Borrowed);
-            entry.insert(bitmap_ptr);
-        }
-
-        match cache.get(&cache_key).unwrap() {
-            Some(Cow::Borrowed(bytes)) => DC::bytes_decode_owned(bytes)
-                .map(Some)
-                .map_err(heed::Error::Decoding)
-                .map_err(Into::into),
-            Some(Cow::Owned(bytes)) => DC::bytes_decode_owned(bytes)
-                .map(Some)
-                .map_err(heed::Error::Decoding)
-                .map_err(Into::into),
-            None => Ok(None),
-        }
+        let value = Self::get_bytes(txn, cache_key, db_key, cache, metrics, db)?;
+        Self::decode_value::<DC>(value, metrics)
     }
 
     fn get_value_from_keys<'v, K1, KC, DC>(
@@ -81,6 +199,7 @@ impl<'ctx> DatabaseCache<'ctx> {
         cache_key: K1,
         db_keys: &'v [KC::EItem],
         cache: &mut FxHashMap<K1, Option<Cow<'ctx, [u8]>>>,
+        metrics: &mut SearchMetrics,
         db: Database<KC, Bytes>,
         merger: MergeFn,
     ) -> Result<Option<DC::DItem>>
@@ -90,7 +209,124 @@ impl<'ctx> DatabaseCache<'ctx> {
         DC: BytesDecodeOwned,
         KC::EItem: Sized,
     {
-        if let Entry::Vacant(entry) = cache.entry(cache_key) {
+        let value = Self::get_bytes_from_keys(txn, cache_key, db_keys, cache, metrics, db, merger)?;
+        Self::decode_value::<DC>(value, metrics)
+    }
+
+    /// Retrieve or insert the given value in the cache, without decoding it.
+    fn get_bytes<'c, 'v, K1, KC, C>(
+        txn: &'ctx RoTxn,
+        cache_key: K1,
+        db_key: &'v KC::EItem,
+        cache: &'c mut C,
+        metrics: &mut SearchMetrics,
+        db: Database<KC, Bytes>,
+    ) -> Result<Option<&'c Cow<'ctx, [u8]>>>
+    where
+        K1: Copy,
+        KC: BytesEncode<'v>,
+        C: BytesCache<'ctx, K1>,
+    {
+        if cache.touch(&cache_key) {
+            metrics.database_cache_hits += 1;
+        } else {
+            metrics.database_cache_misses += 1;
+            let bitmap_ptr = match cache.shared() {
+                Some(shared) => {
+                    let db_key = KC::bytes_encode(db_key).map_err(heed::Error::Encoding)?;
+                    let db = db.remap_key_type::<Bytes>();
+                    Self::get_shared_or_db(txn, &db_key, shared, metrics, db)?
+                }
+                None => db.get(txn, db_key)?.map(Cow::Borrowed),
+            };
+            cache.store(cache_key, bitmap_ptr);
+        }
+
+        Ok(cache.cached(&cache_key).unwrap().as_ref())
+    }
+
+    /// Retrieve the value from the shared cache of the index, or else from the database.
+    fn get_shared_or_db(
+        txn: &'ctx RoTxn,
+        db_key: &[u8],
+        shared: &SharedDocids,
+        metrics: &mut SearchMetrics,
+        db: Database<Bytes, Bytes>,
+    ) -> Result<Option<Cow<'ctx, [u8]>>> {
+        match shared.get(db_key) {
+            Some(bytes) => {
+                metrics.shared_cache_hits += 1;
+                Ok(Some(Cow::Owned(bytes.to_vec())))
+            }
+            None => Ok(db.get(txn, db_key)?.map(Cow::Borrowed)),
+        }
+    }
+
+    /// Retrieve or insert the values of the given keys in the cache, without decoding them.
+    ///
+    /// The keys missing from the cache are looked up in the order of the database, so that the
+    /// lookups of the many derivations of a term go through the same pages of the database.
+    fn get_many_bytes<'v, K1, KC, C>(
+        txn: &'ctx RoTxn,
+        keys: &[(K1, &'v KC::EItem)],
+        cache: &mut C,
+        metrics: &mut SearchMetrics,
+        db: Database<KC, Bytes>,
+    ) -> Result<Vec<Option<Cow<'ctx, [u8]>>>>
+    where
+        K1: Copy,
+        KC: BytesEncode<'v>,
+        C: BytesCache<'ctx, K1>,
+    {
+        let mut values = Vec::with_capacity(keys.len());
+        let mut missing = Vec::new();
+        for (index, &(cache_key, db_key)) in keys.iter().enumerate() {
+            if cache.touch(&cache_key) {
+                metrics.database_cache_hits += 1;
+                values.push(cache.cached(&cache_key).unwrap().clone());
+            } else {
+                metrics.database_cache_misses += 1;
+                let db_key = KC::bytes_encode(db_key).map_err(heed::Error::Encoding)?;
+                missing.push((db_key, index));
+                values.push(None);
+            }
+        }
+
+        missing.sort_unstable();
+        let db = db.remap_key_type::<Bytes>();
+        for (db_key, index) in missing {
+            let bitmap_ptr = match cache.shared() {
+                Some(shared) => Self::get_shared_or_db(txn, &db_key, shared, metrics, db)?,
+                None => db.get(txn, &db_key)?.map(Cow::Borrowed),
+            };
+            // a bounded cache may evict the values of this batch, they are returned nonetheless
+            cache.store(keys[index].0, bitmap_ptr.clone());
+            values[index] = bitmap_ptr;
+        }
+
+        Ok(values)
+    }
+
+    /// Retrieve or insert the merge of the values of the given keys in the cache, without decoding it.
+    fn get_bytes_from_keys<'c, 'v, K1, KC, C>(
+        txn: &'ctx RoTxn,
+        cache_key: K1,
+        db_keys: &'v [KC::EItem],
+        cache: &'c mut C,
+        metrics: &mut SearchMetrics,
+        db: Database<KC, Bytes>,
+        merger: MergeFn,
+    ) -> Result<Option<&'c Cow<'ctx, [u8]>>>
+    where
+        K1: Copy,
+        KC: BytesEncode<'v>,
+        KC::EItem: Sized,
+        C: BytesCache<'ctx, K1>,
+    {
+        if cache.touch(&cache_key) {
+            metrics.database_cache_hits += 1;
+        } else {
+            metrics.database_cache_misses += 1;
             let bitmap_ptr: Option<Cow<'ctx, [u8]>> = match db_keys {
                 [] => None,
                 [key] => db.get(txn, key)?.map(Cow::Borrowed),
@@ -109,24 +345,55 @@ impl<'ctx> DatabaseCache<'ctx> {
                 }
             };
 
-            entry.insert(bitmap_ptr);
+            cache.store(cache_key, bitmap_ptr);
         }
 
-        match cache.get(&cache_key).unwrap() {
-            Some(Cow::Borrowed(bytes)) => DC::bytes_decode_owned(bytes)
-                .map(Some)
-                .map_err(heed::Error::Decoding)
-                .map_err(Into::into),
-            Some(Cow::Owned(bytes)) => DC::bytes_decode_owned(bytes)
-                .map(Some)
-                .map_err(heed::Error::Decoding)
-                .map_err(Into::into),
+        Ok(cache.cached(&cache_key).unwrap().as_ref())
+    }
+
+    fn decode_value<DC>(
+        value: Option<&Cow<'ctx, [u8]>>,
+        metrics: &mut SearchMetrics,
+    ) -> Result<Option<DC::DItem>>
+    where
+        DC: BytesDecodeOwned,
+    {
+        match value {
+            Some(bytes) => {
+                metrics.bitmap_decodes += 1;
+                DC::bytes_decode_owned(bytes)
+                    .map(Some)
+                    .map_err(heed::Error::Decoding)
+                    .map_err(Into::into)
+            }
             None => Ok(None),
         }
     }
 }
 
 impl<'ctx> SearchContext<'ctx> {
+    /// Bounds the number of docids of words and of prefixes kept in the database cache, the least
+    /// recently used ones being evicted. They are all kept by default, or when it is `None`.
+    pub fn database_cache_capacity(&mut self, capacity: Option<usize>) {
+        let DatabaseCache {
+            word_docids,
+            exact_word_docids,
+            strict_word_docids,
+            word_prefix_docids,
+            exact_word_prefix_docids,
+            ..
+        } = &mut self.db_cache;
+        for cache in [
+            word_docids,
+            exact_word_docids,
+            strict_word_docids,
+            word_prefix_docids,
+            exact_word_prefix_docids,
+        ] {
+            cache.set_capacity(capacity);
+        }
+    }
+
     pub fn get_words_fst(&mut self) -> Result<fst::Set<Cow<'ctx, [u8]>>> {
         if let Some(fst) = self.db_cache.words_fst.clone() {
             Ok(fst)
@@ -137,85 +404,260 @@ impl<'ctx> SearchContext<'ctx> {
         }
     }
 
+    /// Returns the words of the index starting with the given prefix, up to
+    /// [`MAX_PREFIX_COUNT`](super::limits::MAX_PREFIX_COUNT) of them.
+    pub fn get_prefix_completions(
+        &mut self,
+        prefix: Interned<String>,
+    ) -> Result<&[Interned<String>]> {
+        if !self.db_cache.prefix_completions.contains_key(&prefix) {
+            let fst = self.get_words_fst()?;
+            let prefix_str = self.word_interner.get(prefix).to_owned();
+            let mut stream = fst.search(Str::new(&prefix_str).starts_with()).into_stream();
+            let mut completions = Vec::new();
+            while let Some(word) = stream.next() {
+                if completions.len() == limits::MAX_PREFIX_COUNT {
+                    break;
+                }
+                let word = std::str::from_utf8(word)?.to_owned();
+                completions.push(self.word_interner.insert(word));
+            }
+            self.db_cache.prefix_completions.insert(prefix, completions);
+        }
+        Ok(&self.db_cache.prefix_completions[&prefix])
+    }
+
     pub fn word_docids(&mut self, word: Word) -> Result<Option<RoaringBitmap>> {
+        let docids = match word {
+            Word::Original(word) => vec![
+                self.get_db_strict_word_docids(word)?,
+                self.get_db_exact_word_docids(word)?,
+                self.get_db_word_docids(word)?,
+            ],
+            Word::ZeroTypoDerived(word) => {
+                vec![self.get_db_exact_word_docids(word)?, self.get_db_word_docids(word)?]
+            }
+            Word::Derived(word) => return self.get_db_word_docids(word),
+        };
+        Ok(docids.into_iter().flatten().reduce(|left, right| left | right))
+    }
+
+    /// Returns the serialized docids of the given word, the strict, exact and tolerant
+    /// docids of a word are not merged.
+    pub fn word_docids_bytes(&mut self, word: Word) -> Result<Vec<Cow<'ctx, [u8]>>> {
         match word {
             Word::Original(word) => {
-                let exact = self.get_db_exact_word_docids(word)?;
-                let tolerant = self.get_db_word_docids(word)?;
-                Ok(match (exact, tolerant) {
-                    (None, None) => None,
-                    (None, Some(tolerant)) => Some(tolerant),
-                    (Some(exact), None) => Some(exact),
-                    (Some(exact), Some(tolerant)) => {
-                        let mut both = exact;
-                        both |= tolerant;
-                        Some(both)
-                    }
-                })
+                let strict = self.get_db_strict_word_docids_bytes(word)?;
+                let exact = self.get_db_exact_word_docids_bytes(word)?;
+                let tolerant = self.get_db_word_docids_bytes(word)?;
+                Ok(strict.into_iter().chain(exact).chain(tolerant).collect())
+            }
+            Word::ZeroTypoDerived(word) => {
+                let exact = self.get_db_exact_word_docids_bytes(word)?;
+                let tolerant = self.get_db_word_docids_bytes(word)?;
+                Ok(exact.into_iter().chain(tolerant).collect())
             }
-            Word::Derived(word) => self.get_db_word_docids(word),
+            Word::Derived(word) => Ok(self.get_db_word_docids_bytes(word)?.into_iter().collect()),
         }
     }
 
-    /// Retrieve or insert the given value in the `word_docids` database.
+    /// Returns the serialized docids of the given words, like [`Self::word_docids_bytes`] does
+    /// for each of them, but looking up the words missing from the cache in one batch per database.
+    pub fn words_docids_bytes(&mut self, words: &[Word]) -> Result<Vec<Cow<'ctx, [u8]>>> {
+        let mut strict = Vec::new();
+        let mut exact = Vec::new();
+        let mut tolerant = Vec::new();
+        for word in words {
+            match *word {
+                Word::Original(word) => {
+                    strict.push(word);
+                    exact.push(word);
+                }
+                Word::ZeroTypoDerived(word) => exact.push(word),
+                Word::Derived(_) => (),
+            }
+            tolerant.push(word.interned());
+        }
+
+        let strict = self.get_db_strict_words_docids_bytes(&strict)?;
+        let exact = self.get_db_exact_words_docids_bytes(&exact)?;
+        let tolerant = self.get_db_words_docids_bytes(&tolerant)?;
+        Ok(strict.into_iter().chain(exact).chain(tolerant).flatten().collect())
+    }
+
     fn get_db_word_docids(&mut self, word: Interned<String>) -> Result<Option<RoaringBitmap>> {
-        match &self.restricted_fids {
+        let bytes = self.get_db_word_docids_bytes(word)?;
+        DatabaseCache::decode_value::<CboRoaringBitmapCodec>(bytes.as_ref(), &mut self.metrics)
+    }
+
+    /// Retrieve or insert the given value in the `word_docids` database.
+    fn get_db_word_docids_bytes(
+        &mut self,
+        word: Interned<String>,
+    ) -> Result<Option<Cow<'ctx, [u8]>>> {
+        self.metrics.word_docids_lookups += 1;
+        let bytes = match &self.restricted_fids {
             Some(restricted_fids) => {
                 let interned = self.word_interner.get(word).as_str();
                 let keys: Vec<_> =
                     restricted_fids.tolerant.iter().map(|fid| (interned, *fid)).collect();
 
-                DatabaseCache::get_value_from_keys::<_, _, CboRoaringBitmapCodec>(
+                DatabaseCache::get_bytes_from_keys(
                     self.txn,
                     word,
                     &keys[..],
                     &mut self.db_cache.word_docids,
+                    &mut self.metrics,
                     self.index.word_fid_docids.remap_data_type::<Bytes>(),
                     merge_cbo_roaring_bitmaps,
-                )
+                )?
             }
-            None => DatabaseCache::get_value::<_, _, CboRoaringBitmapCodec>(
+            None => DatabaseCache::get_bytes(
                 self.txn,
                 word,
                 self.word_interner.get(word).as_str(),
                 &mut self.db_cache.word_docids,
+                &mut self.metrics,
                 self.index.word_docids.remap_data_type::<Bytes>(),
-            ),
+            )?,
+        };
+        Ok(bytes.cloned())
+    }
+
+    /// Retrieve or insert the values of the given words in the `word_docids` database,
+    /// in the order of the words.
+    fn get_db_words_docids_bytes(
+        &mut self,
+        words: &[Interned<String>],
+    ) -> Result<Vec<Option<Cow<'ctx, [u8]>>>> {
+        if self.restricted_fids.is_some() {
+            // the docids of a word are merged from the ones of its fields
+            return words.iter().map(|word| self.get_db_word_docids_bytes(*word)).collect();
         }
+        self.metrics.word_docids_lookups += words.len() as u64;
+        let keys: Vec<_> =
+            words.iter().map(|word| (*word, self.word_interner.get(*word).as_str())).collect();
+        DatabaseCache::get_many_bytes(
+            self.txn,
+            &keys,
+            &mut self.db_cache.word_docids,
+            &mut self.metrics,
+            self.index.word_docids.remap_data_type::<Bytes>(),
+        )
     }
 
     fn get_db_exact_word_docids(
         &mut self,
         word: Interned<String>,
     ) -> Result<Option<RoaringBitmap>> {
-        match &self.restricted_fids {
+        let bytes = self.get_db_exact_word_docids_bytes(word)?;
+        DatabaseCache::decode_value::<CboRoaringBitmapCodec>(bytes.as_ref(), &mut self.metrics)
+    }
+
+    fn get_db_exact_word_docids_bytes(
+        &mut self,
+        word: Interned<String>,
+    ) -> Result<Option<Cow<'ctx, [u8]>>> {
+        self.metrics.word_docids_lookups += 1;
+        let bytes = match &self.restricted_fids {
             Some(restricted_fids) => {
                 let interned = self.word_interner.get(word).as_str();
                 let keys: Vec<_> =
                     restricted_fids.exact.iter().map(|fid| (interned, *fid)).collect();
 
-                DatabaseCache::get_value_from_keys::<_, _, CboRoaringBitmapCodec>(
+                DatabaseCache::get_bytes_from_keys(
                     self.txn,
                     word,
                     &keys[..],
                     &mut self.db_cache.exact_word_docids,
+                    &mut self.metrics,
                     self.index.word_fid_docids.remap_data_type::<Bytes>(),
                     merge_cbo_roaring_bitmaps,
-                )
+                )?
             }
-            None => DatabaseCache::get_value::<_, _, CboRoaringBitmapCodec>(
+            None => DatabaseCache::get_bytes(
                 self.txn,
                 word,
                 self.word_interner.get(word).as_str(),
                 &mut self.db_cache.exact_word_docids,
+                &mut self.metrics,
                 self.index.exact_word_docids.remap_data_type::<Bytes>(),
-            ),
+            )?,
+        };
+        Ok(bytes.cloned())
+    }
+
+    /// Retrieve or insert the values of the given words in the `exact_word_docids` database,
+    /// in the order of the words.
+    fn get_db_exact_words_docids_bytes(
+        &mut self,
+        words: &[Interned<String>],
+    ) -> Result<Vec<Option<Cow<'ctx, [u8]>>>> {
+        if self.restricted_fids.is_some() {
+            // the docids of a word are merged from the ones of its fields
+            return words.iter().map(|word| self.get_db_exact_word_docids_bytes(*word)).collect();
+        }
+        self.metrics.word_docids_lookups += words.len() as u64;
+        let keys: Vec<_> =
+            words.iter().map(|word| (*word, self.word_interner.get(*word).as_str())).collect();
+        DatabaseCache::get_many_bytes(
+            self.txn,
+            &keys,
+            &mut self.db_cache.exact_word_docids,
+            &mut self.metrics,
+            self.index.exact_word_docids.remap_data_type::<Bytes>(),
+        )
+    }
+
+    fn get_db_strict_word_docids(
+        &mut self,
+        word: Interned<String>,
+    ) -> Result<Option<RoaringBitmap>> {
+        let bytes = self.get_db_strict_word_docids_bytes(word)?;
+        DatabaseCache::decode_value::<CboRoaringBitmapCodec>(bytes.as_ref(), &mut self.metrics)
+    }
+
+    /// The strict attributes are only searched by field, see
+    /// [`SearchContext::restrict_to_strict_attributes`].
+    fn get_db_strict_word_docids_bytes(
+        &mut self,
+        word: Interned<String>,
+    ) -> Result<Option<Cow<'ctx, [u8]>>> {
+        let Some(restricted_fids) = &self.restricted_fids else { return Ok(None) };
+        if restricted_fids.strict.is_empty() {
+            return Ok(None);
         }
+        self.metrics.word_docids_lookups += 1;
+        let interned = self.word_interner.get(word).as_str();
+        let keys: Vec<_> = restricted_fids.strict.iter().map(|fid| (interned, *fid)).collect();
+
+        let bytes = DatabaseCache::get_bytes_from_keys(
+            self.txn,
+            word,
+            &keys[..],
+            &mut self.db_cache.strict_word_docids,
+            &mut self.metrics,
+            self.index.word_fid_docids.remap_data_type::<Bytes>(),
+            merge_cbo_roaring_bitmaps,
+        )?;
+        Ok(bytes.cloned())
     }
 
+    /// The strict docids of a word are always merged from the ones of its fields.
+    fn get_db_strict_words_docids_bytes(
+        &mut self,
+        words: &[Interned<String>],
+    ) -> Result<Vec<Option<Cow<'ctx, [u8]>>>> {
+        words.iter().map(|word| self.get_db_strict_word_docids_bytes(*word)).collect()
+    }
+
+    /// The strict attributes never match a prefix.
+    ///
+    /// A prefix missing from the prefix databases, like in the ones of the restricted attributes
+    /// when they lag behind the words, is resolved from the words starting with it.
     pub fn word_prefix_docids(&mut self, prefix: Word) -> Result<Option<RoaringBitmap>> {
-        match prefix {
-            Word::Original(prefix) => {
+        let docids = match prefix {
+            Word::Original(prefix) | Word::ZeroTypoDerived(prefix) => {
                 let exact = self.get_db_exact_word_prefix_docids(prefix)?;
                 let tolerant = self.get_db_word_prefix_docids(prefix)?;
                 Ok(match (exact, tolerant) {
@@ -229,69 +671,148 @@ impl<'ctx> SearchContext<'ctx> {
                     }
                 })
             }
-            Word::Derived(prefix) => self.get_db_word_prefix_docids(prefix),
+            Word::Derived(prefix) => self.get_db_word_prefix_docids(prefix)?,
+        };
+        match docids {
+            Some(docids) => Ok(Some(docids)),
+            None => {
+                let mut docids = RoaringBitmap::new();
+                for bytes in self.word_prefix_completions_docids_bytes(prefix)? {
+                    docids |= DatabaseCache::decode_value::<CboRoaringBitmapCodec>(
+                        Some(&bytes),
+                        &mut self.metrics,
+                    )?
+                    .unwrap_or_default();
+                }
+                Ok(Some(docids).filter(|docids| !docids.is_empty()))
+            }
         }
     }
 
-    /// Retrieve or insert the given value in the `word_prefix_docids` database.
+    /// Returns the serialized docids of the given prefix, the exact and the tolerant
+    /// docids of an original prefix are not merged.
+    pub fn word_prefix_docids_bytes(&mut self, prefix: Word) -> Result<Vec<Cow<'ctx, [u8]>>> {
+        let bytes: Vec<_> = match prefix {
+            Word::Original(prefix) | Word::ZeroTypoDerived(prefix) => {
+                let exact = self.get_db_exact_word_prefix_docids_bytes(prefix)?;
+                let tolerant = self.get_db_word_prefix_docids_bytes(prefix)?;
+                exact.into_iter().chain(tolerant).collect()
+            }
+            Word::Derived(prefix) => {
+                self.get_db_word_prefix_docids_bytes(prefix)?.into_iter().collect()
+            }
+        };
+        if bytes.is_empty() {
+            self.word_prefix_completions_docids_bytes(prefix)
+        } else {
+            Ok(bytes)
+        }
+    }
+
+    /// Returns the serialized docids of the words starting with a prefix missing from the
+    /// prefix databases, up to [`MAX_PREFIX_COUNT`](super::limits::MAX_PREFIX_COUNT) of them.
+    fn word_prefix_completions_docids_bytes(
+        &mut self,
+        prefix: Word,
+    ) -> Result<Vec<Cow<'ctx, [u8]>>> {
+        let completions = self.get_prefix_completions(prefix.interned())?;
+        if completions.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.metrics.prefix_docids_fallbacks += 1;
+        let words: Vec<_> = completions
+            .iter()
+            .map(|&word| match prefix {
+                // like the prefix, its completions don't match the strict attributes
+                Word::Original(_) | Word::ZeroTypoDerived(_) => Word::ZeroTypoDerived(word),
+                Word::Derived(_) => Word::Derived(word),
+            })
+            .collect();
+        self.words_docids_bytes(&words)
+    }
+
     fn get_db_word_prefix_docids(
         &mut self,
         prefix: Interned<String>,
     ) -> Result<Option<RoaringBitmap>> {
-        match &self.restricted_fids {
+        let bytes = self.get_db_word_prefix_docids_bytes(prefix)?;
+        DatabaseCache::decode_value::<CboRoaringBitmapCodec>(bytes.as_ref(), &mut self.metrics)
+    }
+
+    /// Retrieve or insert the given value in the `word_prefix_docids` database.
+    fn get_db_word_prefix_docids_bytes(
+        &mut self,
+        prefix: Interned<String>,
+    ) -> Result<Option<Cow<'ctx, [u8]>>> {
+        self.metrics.prefix_docids_lookups += 1;
+        let bytes = match &self.restricted_fids {
             Some(restricted_fids) => {
                 let interned = self.word_interner.get(prefix).as_str();
                 let keys: Vec<_> =
                     restricted_fids.tolerant.iter().map(|fid| (interned, *fid)).collect();
 
-                DatabaseCache::get_value_from_keys::<_, _, CboRoaringBitmapCodec>(
+                DatabaseCache::get_bytes_from_keys(
                     self.txn,
                     prefix,
                     &keys[..],
                     &mut self.db_cache.word_prefix_docids,
+                    &mut self.metrics,
                     self.index.word_prefix_fid_docids.remap_data_type::<Bytes>(),
                     merge_cbo_roaring_bitmaps,
-                )
+                )?
             }
-            None => DatabaseCache::get_value::<_, _, CboRoaringBitmapCodec>(
+            None => DatabaseCache::get_bytes(
                 self.txn,
                 prefix,
                 self.word_interner.get(prefix).as_str(),
                 &mut self.db_cache.word_prefix_docids,
+                &mut self.metrics,
                 self.index.word_prefix_docids.remap_data_type::<Bytes>(),
-            ),
-        }
+            )?,
+        };
+        Ok(bytes.cloned())
     }
 
     fn get_db_exact_word_prefix_docids(
         &mut self,
         prefix: Interned<String>,
     ) -> Result<Option<RoaringBitmap>> {
-        match &self.restricted_fids {
+        let bytes = self.get_db_exact_word_prefix_docids_bytes(prefix)?;
+        DatabaseCache::decode_value::<CboRoaringBitmapCodec>(bytes.as_ref(), &mut self.metrics)
+    }
+
+    fn get_db_exact_word_prefix_docids_bytes(
+        &mut self,
+        prefix: Interned<String>,
+    ) -> Result<Option<Cow<'ctx, [u8]>>> {
+        self.metrics.prefix_docids_lookups += 1;
+        let bytes = match &self.restricted_fids {
             Some(restricted_fids) => {
                 let interned = self.word_interner.get(prefix).as_str();
                 let keys: Vec<_> =
                     restricted_fids.exact.iter().map(|fid| (interned, *fid)).collect();
 
-                DatabaseCache::get_value_from_keys::<_, _, CboRoaringBitmapCodec>(
+                DatabaseCache::get_bytes_from_keys(
                     self.txn,
                     prefix,
                     &keys[..],
                     &mut self.db_cache.exact_word_prefix_docids,
+                    &mut self.metrics,
                     self.index.word_prefix_fid_docids.remap_data_type::<Bytes>(),
                     merge_cbo_roaring_bitmaps,
-                )
+                )?
             }
-            None => DatabaseCache::get_value::<_, _, CboRoaringBitmapCodec>(
+            None => DatabaseCache::get_bytes(
                 self.txn,
                 prefix,
                 self.word_interner.get(prefix).as_str(),
                 &mut self.db_cache.exact_word_prefix_docids,
+                &mut self.metrics,
                 self.index.exact_word_prefix_docids.remap_data_type::<Bytes>(),
-            ),
-        }
+            )?,
+        };
+        Ok(bytes.cloned())
     }
-
     pub fn get_db_word_pair_proximity_docids(
         &mut self,
         word1: Interned<String>,
@@ -345,18 +866,54 @@ impl<'ctx> SearchContext<'ctx> {
 
                 Ok(docids)
             }
-            ProximityPrecision::ByWord => DatabaseCache::get_value::<_, _, CboRoaringBitmapCodec>(
-                self.txn,
-                (proximity, word1, word2),
-                &(
-                    proximity,
-                    self.word_interner.get(word1).as_str(),
-                    self.word_interner.get(word2).as_str(),
-                ),
-                &mut self.db_cache.word_pair_proximity_docids,
-                self.index.word_pair_proximity_docids.remap_data_type::<Bytes>(),
-            ),
+            ProximityPrecision::ByWord => {
+                let docids = DatabaseCache::get_value::<_, _, CboRoaringBitmapCodec>(
+                    self.txn,
+                    (proximity, word1, word2),
+                    &(
+                        proximity,
+                        self.word_interner.get(word1).as_str(),
+                        self.word_interner.get(word2).as_str(),
+                    ),
+                    &mut self.db_cache.word_pair_proximity_docids,
+                    &mut self.metrics,
+                    self.index.word_pair_proximity_docids.remap_data_type::<Bytes>(),
+                )?;
+                match docids {
+                    Some(docids) if self.restricted_fids.is_some() => {
+                        Ok(Some(docids & self.get_restricted_word_pair_docids(word1, word2)?))
+                    }
+                    docids => Ok(docids),
+                }
+            }
+        }
+    }
+
+    /// Returns the documents in which both words are in one of the attributes the search is
+    /// restricted to.
+    ///
+    /// The word pair proximity database doesn't tell the attributes apart, the pairs found in
+    /// the other attributes are filtered out with it.
+    fn get_restricted_word_pair_docids(
+        &mut self,
+        word1: Interned<String>,
+        word2: Interned<String>,
+    ) -> Result<RoaringBitmap> {
+        let fids: Vec<_> = match &self.restricted_fids {
+            Some(fids) => {
+                fids.tolerant.iter().chain(&fids.exact).chain(&fids.strict).copied().collect()
+            }
+            None => Vec::new(),
+        };
+        let mut docids = RoaringBitmap::new();
+        for fid in fids {
+            let word1_docids = self.get_db_word_fid_docids(word1, fid)?;
+            let word2_docids = self.get_db_word_fid_docids(word2, fid)?;
+            if let (Some(word1_docids), Some(word2_docids)) = (word1_docids, word2_docids) {
+                docids |= word1_docids & word2_docids;
+            }
         }
+        Ok(docids)
     }
 
     pub fn get_db_word_pair_proximity_docids_len(
@@ -366,10 +923,8 @@ impl<'ctx> SearchContext<'ctx> {
         proximity: u8,
     ) -> Result<Option<u64>> {
         match self.index.proximity_precision(self.txn)?.unwrap_or_default() {
-            ProximityPrecision::ByAttribute => Ok(self
-                .get_db_word_pair_proximity_docids(word1, word2, proximity)?
-                .map(|d| d.len())),
-            ProximityPrecision::ByWord => {
+            // the pairs of the attributes the search isn't restricted to must be filtered out
+            ProximityPrecision::ByWord if self.restricted_fids.is_none() => {
                 DatabaseCache::get_value::<_, _, CboRoaringBitmapLenCodec>(
                     self.txn,
                     (proximity, word1, word2),
@@ -379,9 +934,13 @@ impl<'ctx> SearchContext<'ctx> {
                         self.word_interner.get(word2).as_str(),
                     ),
                     &mut self.db_cache.word_pair_proximity_docids,
+                    &mut self.metrics,
                     self.index.word_pair_proximity_docids.remap_data_type::<Bytes>(),
                 )
             }
+            ProximityPrecision::ByAttribute | ProximityPrecision::ByWord => Ok(self
+                .get_db_word_pair_proximity_docids(word1, word2, proximity)?
+                .map(|d| d.len())),
         }
     }
 
@@ -464,8 +1023,32 @@ impl<'ctx> SearchContext<'ctx> {
         right: Interned<String>,
         proximity: u8,
     ) -> Result<Option<RoaringBitmap>> {
-        // only accept exact matches on reverted positions
-        self.get_db_word_pair_proximity_docids(left_prefix, right, proximity)
+        let proximity_precision = self.index.proximity_precision(self.txn)?.unwrap_or_default();
+        if proximity_precision == ProximityPrecision::ByAttribute {
+            // The distance at the attribute level doesn't depend on the order of the words.
+            return self.get_db_word_prefix_pair_proximity_docids(right, left_prefix, proximity);
+        }
+
+        if let Some(docids) =
+            self.db_cache.prefix_word_pair_proximity_docids.get(&(proximity, left_prefix, right))
+        {
+            return Ok(docids.clone());
+        }
+
+        // The pairs are stored by their first word, so the words starting with the prefix
+        // can't be found with a single iteration: the pairs of each of them are fetched instead.
+        let completions = self.get_prefix_completions(left_prefix)?.to_vec();
+        let mut prefix_docids = RoaringBitmap::new();
+        for left in completions {
+            if let Some(docids) = self.get_db_word_pair_proximity_docids(left, right, proximity)? {
+                prefix_docids |= docids;
+            }
+        }
+        let prefix_docids = Some(prefix_docids).filter(|docids| !docids.is_empty());
+        self.db_cache
+            .prefix_word_pair_proximity_docids
+            .insert((proximity, left_prefix, right), prefix_docids.clone());
+        Ok(prefix_docids)
     }
 
     pub fn get_db_word_fid_docids(
@@ -483,6 +1066,7 @@ impl<'ctx> SearchContext<'ctx> {
             (word, fid),
             &(self.word_interner.get(word).as_str(), fid),
             &mut self.db_cache.word_fid_docids,
+            &mut self.metrics,
             self.index.word_fid_docids.remap_data_type::<Bytes>(),
         )
     }
@@ -502,6 +1086,7 @@ impl<'ctx> SearchContext<'ctx> {
             (word_prefix, fid),
             &(self.word_interner.get(word_prefix).as_str(), fid),
             &mut self.db_cache.word_prefix_fid_docids,
+            &mut self.metrics,
             self.index.word_prefix_fid_docids.remap_data_type::<Bytes>(),
         )
     }
@@ -570,6 +1155,7 @@ impl<'ctx> SearchContext<'ctx> {
             (word, position),
             &(self.word_interner.get(word).as_str(), position),
             &mut self.db_cache.word_position_docids,
+            &mut self.metrics,
             self.index.word_position_docids.remap_data_type::<Bytes>(),
         )
     }
@@ -584,6 +1170,7 @@ impl<'ctx> SearchContext<'ctx> {
             (word_prefix, position),
             &(self.word_interner.get(word_prefix).as_str(), position),
             &mut self.db_cache.word_prefix_position_docids,
+            &mut self.metrics,
             self.index.word_prefix_position_docids.remap_data_type::<Bytes>(),
         )
     }
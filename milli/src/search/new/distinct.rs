@@ -1,3 +1,6 @@
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashSet};
+
 use heed::types::{Bytes, Str, Unit};
 use heed::{Database, RoPrefix, RoTxn};
 use roaring::RoaringBitmap;
@@ -5,17 +8,152 @@ use roaring::RoaringBitmap;
 const FID_SIZE: usize = 2;
 const DOCID_SIZE: usize = 4;
 
+use crate::facet::FacetType;
 use crate::heed_codec::facet::{
     FacetGroupKey, FacetGroupKeyCodec, FacetGroupValueCodec, FieldDocIdFacetCodec,
 };
 use crate::heed_codec::BytesRefCodec;
-use crate::{Index, Result, SearchContext};
+use crate::{FieldId, Index, Result, SearchContext};
+
+/// The minimum number of distinct values per document above which the documents are
+/// deduplicated with [`DistinctStrategy::SeenValues`].
+pub const SEEN_VALUES_MIN_VALUES_PER_DOCUMENT: f64 = 0.5;
 
 pub struct DistinctOutput {
     pub remaining: RoaringBitmap,
     pub excluded: RoaringBitmap,
 }
 
+/// A value of the distinct field of a document, as it is stored in the facet databases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DistinctValue<'ctx> {
+    String(&'ctx [u8]),
+    Number(&'ctx [u8]),
+}
+
+/// How the documents sharing a distinct value with a document kept by the search are discarded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistinctStrategy {
+    /// The documents of the values of each kept document are read from the facet databases
+    /// and removed from the universes of the ranking rules at once.
+    FacetDocids,
+    /// The values of each candidate are read and compared to the values of the documents kept
+    /// before it. When almost every document has its own value, the documents of a value are
+    /// only the document itself and reading them from the facet databases is wasted.
+    SeenValues,
+}
+
+impl DistinctStrategy {
+    /// Chooses the cheapest strategy for the distinct field, according to the number of its
+    /// values per document.
+    ///
+    /// The number of values is read from the statistics of the facet databases, the values
+    /// that were never counted are ignored, falling back to [`DistinctStrategy::FacetDocids`].
+    pub fn choose(index: &Index, rtxn: &RoTxn, field_id: FieldId) -> Result<Self> {
+        let mut values = 0;
+        for facet_type in [FacetType::String, FacetType::Number] {
+            let counts = index.facet_entries_counts(rtxn, facet_type)?;
+            values += counts.get(&field_id).copied().unwrap_or_default();
+        }
+        let documents = index.number_of_documents(rtxn)?;
+        if documents > 0 && values as f64 / documents as f64 >= SEEN_VALUES_MIN_VALUES_PER_DOCUMENT
+        {
+            Ok(DistinctStrategy::SeenValues)
+        } else {
+            Ok(DistinctStrategy::FacetDocids)
+        }
+    }
+}
+
+/// The distinct rule applied by a search to the documents it returns, one bucket after the other.
+///
+/// With [`DistinctStrategy::SeenValues`], the documents sharing a value with a kept document are
+/// only discarded when they are given to the rule, so they stay in the universes of the ranking
+/// rules until then.
+pub struct DistinctRule<'ctx> {
+    field_id: FieldId,
+    strategy: DistinctStrategy,
+    /// The values of the documents kept so far, with [`DistinctStrategy::SeenValues`].
+    seen_values: HashSet<DistinctValue<'ctx>>,
+}
+
+impl<'ctx> DistinctRule<'ctx> {
+    /// Uses the strategy of the search context, or the cheapest one for the field.
+    pub fn new(ctx: &SearchContext<'ctx>, field_id: FieldId) -> Result<Self> {
+        let strategy = match ctx.distinct_strategy {
+            Some(strategy) => strategy,
+            None => DistinctStrategy::choose(ctx.index, ctx.txn, field_id)?,
+        };
+        Ok(DistinctRule { field_id, strategy, seen_values: HashSet::new() })
+    }
+
+    /// Keeps the first document of each distinct value in the order of the candidates, like
+    /// [`apply_distinct_rule_in_order`], the documents kept before included.
+    ///
+    /// With [`DistinctStrategy::SeenValues`], `excluded` only contains the discarded candidates.
+    pub fn apply(
+        &mut self,
+        ctx: &mut SearchContext<'ctx>,
+        candidates: impl IntoIterator<Item = u32>,
+    ) -> Result<DistinctOutput> {
+        match self.strategy {
+            DistinctStrategy::FacetDocids => {
+                apply_distinct_rule_in_order(ctx, self.field_id, candidates)
+            }
+            DistinctStrategy::SeenValues => {
+                let mut remaining = RoaringBitmap::new();
+                let mut excluded = RoaringBitmap::new();
+                for docid in candidates {
+                    if self.keep_unseen(ctx, docid)? {
+                        remaining.insert(docid);
+                    } else {
+                        excluded.insert(docid);
+                    }
+                }
+                Ok(DistinctOutput { remaining, excluded })
+            }
+        }
+    }
+
+    /// Returns whether the document is kept, adding the documents it discards to `excluded`,
+    /// like [`distinct_single_docid`].
+    pub fn apply_single(
+        &mut self,
+        ctx: &mut SearchContext<'ctx>,
+        docid: u32,
+        excluded: &mut RoaringBitmap,
+    ) -> Result<bool> {
+        if excluded.contains(docid) {
+            return Ok(false);
+        }
+        match self.strategy {
+            DistinctStrategy::FacetDocids => {
+                distinct_single_docid(ctx, self.field_id, docid, excluded)?;
+                Ok(true)
+            }
+            DistinctStrategy::SeenValues => {
+                let kept = self.keep_unseen(ctx, docid)?;
+                if !kept {
+                    excluded.insert(docid);
+                }
+                Ok(kept)
+            }
+        }
+    }
+
+    /// Keeps the document if none of its values was seen before, and marks them as seen.
+    fn keep_unseen(&mut self, ctx: &mut SearchContext<'ctx>, docid: u32) -> Result<bool> {
+        // each document is only read once, its values are not cached
+        ctx.metrics.distinct_seen_values_checks += 1;
+        let values = document_distinct_values(ctx.index, ctx.txn, self.field_id, docid)?;
+        if values.iter().any(|value| self.seen_values.contains(value)) {
+            return Ok(false);
+        }
+        self.seen_values.extend(values);
+        Ok(true)
+    }
+}
+
 /// Return a [`DistinctOutput`] containing:
 /// - `remaining`: a set of docids built such that exactly one element from `candidates`
 /// is kept for each distinct value inside the given field. If the field does not exist, it
@@ -27,46 +165,156 @@ pub fn apply_distinct_rule(
     field_id: u16,
     candidates: &RoaringBitmap,
 ) -> Result<DistinctOutput> {
+    apply_distinct_rule_in_order(ctx, field_id, candidates)
+}
+
+/// Like [`apply_distinct_rule`], but keeps the first document of each distinct value in the
+/// order of the candidates instead of the one with the smallest id.
+pub fn apply_distinct_rule_in_order(
+    ctx: &mut SearchContext,
+    field_id: u16,
+    candidates: impl IntoIterator<Item = u32>,
+) -> Result<DistinctOutput> {
+    // The values of the whole bucket are read at once: first the values of the candidates
+    // not read yet by the search, in the database order, and then the documents of the values
+    // not seen yet, in the database order too.
+    let candidates: Vec<u32> = candidates.into_iter().collect();
+    let mut unread: Vec<u32> = candidates
+        .iter()
+        .copied()
+        .filter(|docid| !ctx.db_cache.distinct_values.contains_key(&(field_id, *docid)))
+        .collect();
+    unread.sort_unstable();
+    for docid in unread {
+        distinct_values(ctx, field_id, docid)?;
+    }
+
+    let mut candidates_values = Vec::with_capacity(candidates.len());
+    let mut values = BTreeSet::new();
+    for docid in candidates {
+        let docid_values = distinct_values(ctx, field_id, docid)?;
+        values.extend(docid_values.iter().copied());
+        candidates_values.push((docid, docid_values));
+    }
+    for value in values {
+        distinct_value_docids(ctx, field_id, value)?;
+    }
+
     let mut excluded = RoaringBitmap::new();
     let mut remaining = RoaringBitmap::new();
-    for docid in candidates {
+    for (docid, values) in candidates_values {
         if excluded.contains(docid) {
             continue;
         }
-        distinct_single_docid(ctx.index, ctx.txn, field_id, docid, &mut excluded)?;
-        remaining.push(docid);
+        for value in values {
+            if let Some(Some(docids)) = ctx.db_cache.distinct_value_docids.get(&(field_id, value)) {
+                excluded |= docids;
+            }
+        }
+        remaining.insert(docid);
     }
     Ok(DistinctOutput { remaining, excluded })
 }
 
 /// Apply the distinct rule defined by [`apply_distinct_rule`] for a single document id.
+///
+/// The values are read lazily, for the searches that stop before the end of their bucket.
 pub fn distinct_single_docid(
-    index: &Index,
-    txn: &RoTxn,
+    ctx: &mut SearchContext,
     field_id: u16,
     docid: u32,
     excluded: &mut RoaringBitmap,
 ) -> Result<()> {
-    for item in facet_string_values(docid, field_id, index, txn)? {
-        let ((_, _, facet_value), _) = item?;
-        if let Some(facet_docids) = facet_value_docids(
-            index.facet_id_string_docids.remap_types(),
-            txn,
-            field_id,
-            facet_value,
-        )? {
+    for value in distinct_values(ctx, field_id, docid)? {
+        if let Some(facet_docids) = distinct_value_docids(ctx, field_id, value)? {
             *excluded |= facet_docids;
         }
     }
+    Ok(())
+}
+
+/// Return the first of the `kept` documents sharing a value of the given field with the document,
+/// i.e. the document that made the distinct rule discard it.
+pub fn distinct_kept_docid(
+    ctx: &mut SearchContext,
+    field_id: u16,
+    docid: u32,
+    kept: &RoaringBitmap,
+) -> Result<Option<u32>> {
+    for value in distinct_values(ctx, field_id, docid)? {
+        if let Some(docids) = distinct_value_docids(ctx, field_id, value)? {
+            if let Some(kept_docid) = (docids & kept).min() {
+                return Ok(Some(kept_docid));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Return the values of the given field of the given document, reading them from the
+/// cache of the search context, or from the database the first time.
+fn distinct_values<'ctx>(
+    ctx: &mut SearchContext<'ctx>,
+    field_id: u16,
+    docid: u32,
+) -> Result<Vec<DistinctValue<'ctx>>> {
+    let values = match ctx.db_cache.distinct_values.entry((field_id, docid)) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => {
+            entry.insert(document_distinct_values(ctx.index, ctx.txn, field_id, docid)?)
+        }
+    };
+    Ok(values.clone())
+}
+
+/// Return the values of the given field of the given document, read from the database.
+fn document_distinct_values<'ctx>(
+    index: &Index,
+    txn: &'ctx RoTxn,
+    field_id: u16,
+    docid: u32,
+) -> Result<Vec<DistinctValue<'ctx>>> {
+    let mut values = Vec::new();
+    for item in facet_string_values(docid, field_id, index, txn)? {
+        let ((_, _, facet_value), _) = item?;
+        values.push(DistinctValue::String(facet_value));
+    }
     for item in facet_number_values(docid, field_id, index, txn)? {
         let ((_, _, facet_value), _) = item?;
-        if let Some(facet_docids) =
-            facet_value_docids(index.facet_id_f64_docids.remap_types(), txn, field_id, facet_value)?
-        {
-            *excluded |= facet_docids;
-        }
+        values.push(DistinctValue::Number(facet_value));
     }
-    Ok(())
+    Ok(values)
+}
+
+/// Return all the docids containing the given distinct value in the given field, reading them
+/// from the cache of the search context, or from the facet databases the first time.
+fn distinct_value_docids<'ctx, 'search>(
+    ctx: &'search mut SearchContext<'ctx>,
+    field_id: u16,
+    value: DistinctValue<'ctx>,
+) -> Result<Option<&'search RoaringBitmap>> {
+    let docids = match ctx.db_cache.distinct_value_docids.entry((field_id, value)) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => {
+            ctx.metrics.distinct_value_docids_lookups += 1;
+            let docids = match value {
+                DistinctValue::String(facet_value) => facet_value_docids(
+                    ctx.index.facet_id_string_docids.remap_types(),
+                    ctx.txn,
+                    field_id,
+                    facet_value,
+                )?,
+                DistinctValue::Number(facet_value) => facet_value_docids(
+                    ctx.index.facet_id_f64_docids.remap_types(),
+                    ctx.txn,
+                    field_id,
+                    facet_value,
+                )?,
+            };
+            entry.insert(docids)
+        }
+    };
+    Ok(docids.as_ref())
 }
 
 /// Return all the docids containing the given value in the given field
@@ -82,7 +330,7 @@ fn facet_value_docids(
 }
 
 /// Return an iterator over each number value in the given field of the given document.
-fn facet_number_values<'a>(
+pub fn facet_number_values<'a>(
     docid: u32,
     field_id: u16,
     index: &Index,
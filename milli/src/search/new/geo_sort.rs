@@ -1,5 +1,4 @@
 use std::collections::VecDeque;
-use std::iter::FromIterator;
 
 use heed::types::{Bytes, Unit};
 use heed::{RoPrefix, RoTxn};
@@ -79,6 +78,8 @@ pub struct GeoSort<Q: RankingRuleQueryTrait> {
     strategy: Strategy,
     ascending: bool,
     point: [f64; 2],
+    /// The maximum number of documents of each bucket, `1` when sorting.
+    bucket_size: u64,
     field_ids: Option<[u16; 2]>,
     rtree: Option<RTree<GeoPoint>>,
 
@@ -92,12 +93,14 @@ impl<Q: RankingRuleQueryTrait> GeoSort<Q> {
         geo_faceted_docids: RoaringBitmap,
         point: [f64; 2],
         ascending: bool,
+        bucket_size: u32,
     ) -> Result<Self> {
         Ok(Self {
             query: None,
             strategy,
             ascending,
             point,
+            bucket_size: bucket_size.max(1) as u64,
             geo_candidates: geo_faceted_docids,
             field_ids: None,
             rtree: None,
@@ -234,11 +237,10 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for GeoSort<Q> {
         Ok(())
     }
 
-    #[allow(clippy::only_used_in_recursion)]
     fn next_bucket(
         &mut self,
         ctx: &mut SearchContext<'ctx>,
-        logger: &mut dyn SearchLogger<Q>,
+        _logger: &mut dyn SearchLogger<Q>,
         universe: &RoaringBitmap,
     ) -> Result<Option<RankingRuleOutput<Q>>> {
         let query = self.query.as_ref().unwrap().clone();
@@ -265,24 +267,39 @@ impl<'ctx, Q: RankingRuleQueryTrait> RankingRule<'ctx, Q> for GeoSort<Q> {
                 cache.pop_back()
             }
         };
-        while let Some((id, point)) = next(&mut self.cached_sorted_docids) {
-            if geo_candidates.contains(id) {
-                return Ok(Some(RankingRuleOutput {
-                    query,
-                    candidates: RoaringBitmap::from_iter([id]),
-                    score: ScoreDetails::GeoSort(score_details::GeoSort {
-                        target_point: self.point,
-                        ascending: self.ascending,
-                        value: Some(point),
-                    }),
-                }));
+
+        // the bucket is scored with the point of its best ranked document
+        let mut candidates = RoaringBitmap::new();
+        let mut best_point = None;
+        'bucket: loop {
+            while let Some((id, point)) = next(&mut self.cached_sorted_docids) {
+                if geo_candidates.contains(id) {
+                    best_point.get_or_insert(point);
+                    candidates.insert(id);
+                    if candidates.len() >= self.bucket_size {
+                        break 'bucket;
+                    }
+                }
             }
+
+            // if we got out of this loop it means we've exhausted our cache.
+            // we need to refill it with the documents that are not in the bucket yet.
+            let remaining = &geo_candidates - &candidates;
+            if remaining.is_empty() {
+                break;
+            }
+            self.fill_buffer(ctx, &remaining)?;
         }
 
-        // if we got out of this loop it means we've exhausted our cache.
-        // we need to refill it and run the function again.
-        self.fill_buffer(ctx, &geo_candidates)?;
-        self.next_bucket(ctx, logger, universe)
+        Ok(Some(RankingRuleOutput {
+            query,
+            candidates,
+            score: ScoreDetails::GeoSort(score_details::GeoSort {
+                target_point: self.point,
+                ascending: self.ascending,
+                value: best_point,
+            }),
+        }))
     }
 
     fn end_iteration(&mut self, _ctx: &mut SearchContext<'ctx>, _logger: &mut dyn SearchLogger<Q>) {
@@ -148,24 +148,18 @@ impl<'ctx, G: RankingRuleGraphTrait> RankingRule<'ctx, QueryGraph> for GraphBase
                 // where only the phrase is matching, and none of the non-phrase words.
                 // With the `1` that `next_max_cost` is initialized with, this gets counted twice.
                 .saturating_sub(1) as u64;
-            match terms_matching_strategy {
-                TermsMatchingStrategy::Last => {
-                    let removal_order =
-                        query_graph.removal_order_for_terms_matching_strategy_last(ctx);
-                    let mut forbidden_nodes =
-                        SmallBitmap::for_interned_values_in(&query_graph.nodes);
-                    let mut costs = query_graph.nodes.map(|_| None);
-                    // FIXME: this works because only words uses termsmatchingstrategy at the moment.
-                    for ns in removal_order {
-                        for n in ns.iter() {
-                            *costs.get_mut(n) = Some((1, forbidden_nodes.clone()));
-                        }
-                        forbidden_nodes.union(&ns);
-                    }
-                    costs
+            let removal_order = query_graph
+                .removal_order_for_terms_matching_strategy(ctx, terms_matching_strategy)?;
+            let mut forbidden_nodes = SmallBitmap::for_interned_values_in(&query_graph.nodes);
+            let mut costs = query_graph.nodes.map(|_| None);
+            // FIXME: this works because only words uses termsmatchingstrategy at the moment.
+            for ns in removal_order {
+                for n in ns.iter() {
+                    *costs.get_mut(n) = Some((1, forbidden_nodes.clone()));
                 }
-                TermsMatchingStrategy::All => query_graph.nodes.map(|_| None),
+                forbidden_nodes.union(&ns);
             }
+            costs
         } else {
             query_graph.nodes.map(|_| None)
         };
@@ -1,6 +1,9 @@
 /// Maximum number of tokens we consider in a single search.
 pub const MAX_TOKEN_COUNT: usize = 1_000;
 
+/// Default maximum number of terms of a search query, the words after them are not searched.
+pub const DEFAULT_MAX_QUERY_TERMS: usize = 150;
+
 /// Maximum number of prefixes that can be derived from a single word.
 pub const MAX_PREFIX_COUNT: usize = 1_000;
 /// Maximum number of words that can be derived from a single word with a distance of one to that word.
@@ -8,6 +11,13 @@ pub const MAX_ONE_TYPO_COUNT: usize = 150;
 /// Maximum number of words that can be derived from a single word with a distance of two to that word.
 pub const MAX_TWO_TYPOS_COUNT: usize = 50;
 
+/// Minimum number of characters of a word for it to be decompounded.
+pub const MIN_DECOMPOUNDED_WORD_LEN: usize = 8;
+/// Minimum number of characters of each of the words a compound word is split into.
+pub const MIN_DECOMPOUNDED_PART_LEN: usize = 3;
+/// Maximum number of words a compound word can be split into.
+pub const MAX_DECOMPOUNDED_PARTS: usize = 4;
+
 /// Maximum amount of synonym phrases that can be derived from a single word.
 pub const MAX_SYNONYM_PHRASE_COUNT: usize = 50;
 
@@ -15,3 +25,14 @@ pub const MAX_SYNONYM_PHRASE_COUNT: usize = 50;
 ///
 /// This limit is meant to gracefully handle the case where a word would have very long phrases as synonyms.
 pub const MAX_SYNONYM_WORD_COUNT: usize = 100;
+
+/// Maximum amount of alternatives in the query graph of a single search.
+///
+/// Each term node of the graph is an alternative, and so is each synonym of its term.
+/// Above this limit, the ngrams of the query are not searched, then the synonyms of its least frequent terms.
+pub const MAX_QUERY_GRAPH_ALTERNATIVES: usize = 1_024;
+
+/// Maximum number of words derived from the terms of a single search, as prefixes or with typos.
+///
+/// Once it is reached, the next terms are only searched as they are written.
+pub const MAX_QUERY_DERIVATIONS: usize = 10_000;
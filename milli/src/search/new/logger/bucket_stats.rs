@@ -0,0 +1,79 @@
+use std::any::Any;
+
+use roaring::RoaringBitmap;
+use serde::Serialize;
+
+use super::SearchLogger;
+use crate::search::new::ranking_rules::BoxRankingRule;
+use crate::search::new::{RankingRule, RankingRuleQueryTrait};
+
+/// The maximum number of bucket sizes recorded for every ranking rule.
+pub const MAX_RECORDED_BUCKETS: usize = 20;
+
+/// The sizes of the buckets returned by every ranking rule, as recorded by the [`BucketStatsLogger`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RankingRuleBucketStats {
+    pub ranking_rule: String,
+    /// The sizes of the first buckets, in the order they were returned.
+    pub bucket_sizes: Vec<u64>,
+    /// The number of buckets returned, including the ones whose size wasn't recorded.
+    pub buckets: u64,
+}
+
+/// A [`SearchLogger`] recording the sizes of the first buckets returned by every ranking rule.
+///
+/// A ranking rule returns buckets for every bucket of its parent it sorts,
+/// they are all recorded one after the other.
+#[derive(Debug, Default)]
+pub struct BucketStatsLogger {
+    stats: Option<Vec<RankingRuleBucketStats>>,
+}
+
+impl BucketStatsLogger {
+    /// The recorded stats, `None` if no bucket sort was made with this logger.
+    pub fn into_stats(self) -> Option<Vec<RankingRuleBucketStats>> {
+        self.stats
+    }
+}
+
+impl<Q: RankingRuleQueryTrait> SearchLogger<Q> for BucketStatsLogger {
+    fn initial_query(&mut self, _query: &Q) {}
+
+    fn initial_universe(&mut self, _universe: &RoaringBitmap) {}
+
+    fn query_for_initial_universe(&mut self, _query: &Q) {}
+
+    fn ranking_rules(&mut self, rr: &[BoxRankingRule<Q>]) {
+        self.stats = Some(
+            rr.iter()
+                .map(|rr| RankingRuleBucketStats {
+                    ranking_rule: rr.id(),
+                    bucket_sizes: Vec::new(),
+                    buckets: 0,
+                })
+                .collect(),
+        );
+    }
+
+    fn next_bucket_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        _ranking_rule: &dyn RankingRule<Q>,
+        _universe: &RoaringBitmap,
+        candidates: &RoaringBitmap,
+    ) {
+        let Some(stats) = self.stats.as_mut().and_then(|stats| stats.get_mut(ranking_rule_idx))
+        else {
+            return;
+        };
+        if stats.bucket_sizes.len() < MAX_RECORDED_BUCKETS {
+            stats.bucket_sizes.push(candidates.len());
+        }
+        stats.buckets += 1;
+    }
+
+    fn add_to_results(&mut self, _docids: &[u32]) {}
+
+    fn log_internal_state(&mut self, _rr: &dyn Any) {}
+}
@@ -0,0 +1,139 @@
+use std::any::Any;
+
+use roaring::RoaringBitmap;
+
+use super::SearchLogger;
+use crate::search::new::ranking_rules::BoxRankingRule;
+use crate::search::new::{BucketStep, RankingRule, RankingRuleQueryTrait};
+use crate::DocumentId;
+
+/// Why an explained document is not returned by a search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExclusionReason {
+    /// The document doesn't match the filter of the search.
+    FilteredOut,
+    /// The document contains a word or a phrase excluded by the query.
+    NegativeQuery,
+    /// The document matches some words of the query, but the terms matching strategy
+    /// doesn't remove enough words from the query for it to match.
+    DroppedByWordsRelaxation,
+    /// The document doesn't match any word of the query.
+    NotMatchingQuery,
+    /// The document has the same value of the distinct attribute as a document ranked before it.
+    Distinct { kept: DocumentId },
+    /// The ranking score of the document is below the threshold of the search.
+    BelowRankingScoreThreshold,
+}
+
+/// How a search ranked a single document, returned when [`Search::explain`](crate::Search::explain) is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchExplanation {
+    /// The buckets of the ranking rules the document fell into, from the first ranking rule
+    /// to the last one that sorted it.
+    pub bucket_path: Vec<BucketStep>,
+    /// Why the document is not returned, `None` when it is.
+    pub exclusion: Option<ExclusionReason>,
+    /// The position of the document among all the documents returned by the search,
+    /// starting at `0` and ignoring the offset and the limit.
+    pub position: Option<usize>,
+}
+
+/// A [`SearchLogger`] following a single document through the buckets of the ranking rules.
+///
+/// The buckets are recorded as they are returned, the path of the document is truncated
+/// every time it falls into a new bucket of a ranking rule.
+#[derive(Debug)]
+pub struct ExplainLogger {
+    docid: DocumentId,
+    ranking_rules: Vec<String>,
+    /// The number of buckets returned by each ranking rule since its iteration started.
+    bucket_counts: Vec<usize>,
+    bucket_path: Vec<BucketStep>,
+    /// The documents kept by the distinct rule when it discarded the explained document.
+    distinct_kept: Option<RoaringBitmap>,
+    sorted: bool,
+}
+
+/// What the [`ExplainLogger`] recorded about the explained document.
+#[derive(Debug, Default)]
+pub struct ExplainedBuckets {
+    pub bucket_path: Vec<BucketStep>,
+    pub distinct_kept: Option<RoaringBitmap>,
+}
+
+impl ExplainLogger {
+    pub fn new(docid: DocumentId) -> Self {
+        Self {
+            docid,
+            ranking_rules: Vec::new(),
+            bucket_counts: Vec::new(),
+            bucket_path: Vec::new(),
+            distinct_kept: None,
+            sorted: false,
+        }
+    }
+
+    /// The recorded buckets, `None` if no bucket sort was made with this logger.
+    pub fn into_buckets(self) -> Option<ExplainedBuckets> {
+        let Self { bucket_path, distinct_kept, sorted, .. } = self;
+        sorted.then_some(ExplainedBuckets { bucket_path, distinct_kept })
+    }
+}
+
+impl<Q: RankingRuleQueryTrait> SearchLogger<Q> for ExplainLogger {
+    fn initial_query(&mut self, _query: &Q) {}
+
+    fn initial_universe(&mut self, _universe: &RoaringBitmap) {}
+
+    fn query_for_initial_universe(&mut self, _query: &Q) {}
+
+    fn ranking_rules(&mut self, rr: &[BoxRankingRule<Q>]) {
+        self.ranking_rules = rr.iter().map(|rr| rr.id()).collect();
+        self.bucket_counts = vec![0; rr.len()];
+        self.sorted = true;
+    }
+
+    fn start_iteration_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        _ranking_rule: &dyn RankingRule<Q>,
+        _query: &Q,
+        _universe: &RoaringBitmap,
+    ) {
+        if let Some(count) = self.bucket_counts.get_mut(ranking_rule_idx) {
+            *count = 0;
+        }
+    }
+
+    fn next_bucket_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        _ranking_rule: &dyn RankingRule<Q>,
+        _universe: &RoaringBitmap,
+        candidates: &RoaringBitmap,
+    ) {
+        let Some(count) = self.bucket_counts.get_mut(ranking_rule_idx) else { return };
+        if candidates.contains(self.docid) {
+            self.bucket_path.truncate(ranking_rule_idx);
+            self.bucket_path.push(BucketStep {
+                ranking_rule: self.ranking_rules[ranking_rule_idx].clone(),
+                bucket: *count,
+                bucket_size: candidates.len(),
+            });
+        }
+        *count += 1;
+    }
+
+    fn distinct_excluded(&mut self, excluded: &RoaringBitmap, kept: &RoaringBitmap) {
+        if self.distinct_kept.is_none()
+            && excluded.contains(self.docid)
+            && !kept.contains(self.docid)
+        {
+            self.distinct_kept = Some(kept.clone());
+        }
+    }
+
+    fn add_to_results(&mut self, _docids: &[u32]) {}
+
+    fn log_internal_state(&mut self, _rr: &dyn Any) {}
+}
@@ -1,3 +1,6 @@
+pub mod bucket_stats;
+pub mod explain;
+pub mod structured;
 // #[cfg(test)]
 pub mod visual;
 
@@ -6,7 +9,7 @@ use std::any::Any;
 use roaring::RoaringBitmap;
 
 use super::ranking_rules::BoxRankingRule;
-use super::{RankingRule, RankingRuleQueryTrait};
+use super::{QueryGraph, RankingRule, RankingRuleQueryTrait};
 
 /// Trait for structure logging the execution of a search query.
 pub trait SearchLogger<Q: RankingRuleQueryTrait> {
@@ -19,6 +22,9 @@ pub trait SearchLogger<Q: RankingRuleQueryTrait> {
     /// Logs the query that was used to compute the set of all candidates
     fn query_for_initial_universe(&mut self, _query: &Q);
 
+    /// Logs the truncation of a search query, only its first terms are searched
+    fn query_truncated(&mut self, _kept_terms: usize) {}
+
     /// Logs the ranking rules used to perform the search query
     fn ranking_rules(&mut self, _rr: &[BoxRankingRule<Q>]);
 
@@ -56,6 +62,9 @@ pub trait SearchLogger<Q: RankingRuleQueryTrait> {
         _universe: &RoaringBitmap,
     ) {
     }
+    /// Logs the documents discarded by the distinct rule, along with the documents kept instead
+    fn distinct_excluded(&mut self, _excluded: &RoaringBitmap, _kept: &RoaringBitmap) {}
+
     /// Logs the addition of document ids to the final results
     fn add_to_results(&mut self, _docids: &[u32]);
 
@@ -79,3 +88,207 @@ impl<Q: RankingRuleQueryTrait> SearchLogger<Q> for DefaultSearchLogger {
 
     fn log_internal_state(&mut self, _rr: &dyn Any) {}
 }
+
+/// A [`SearchLogger`] keeping the query graph given to the ranking rules.
+#[derive(Default)]
+pub struct QueryGraphLogger {
+    pub query_graph: Option<QueryGraph>,
+}
+
+impl SearchLogger<QueryGraph> for QueryGraphLogger {
+    fn initial_query(&mut self, query: &QueryGraph) {
+        self.query_graph = Some(query.clone());
+    }
+
+    fn initial_universe(&mut self, _universe: &RoaringBitmap) {}
+
+    fn query_for_initial_universe(&mut self, _query: &QueryGraph) {}
+
+    fn ranking_rules(&mut self, _rr: &[BoxRankingRule<QueryGraph>]) {}
+
+    fn add_to_results(&mut self, _docids: &[u32]) {}
+
+    fn log_internal_state(&mut self, _rr: &dyn Any) {}
+}
+
+/// An optional [`SearchLogger`], doing nothing when it is `None`.
+impl<Q: RankingRuleQueryTrait, L: SearchLogger<Q>> SearchLogger<Q> for Option<L> {
+    fn initial_query(&mut self, query: &Q) {
+        if let Some(logger) = self {
+            logger.initial_query(query);
+        }
+    }
+
+    fn initial_universe(&mut self, universe: &RoaringBitmap) {
+        if let Some(logger) = self {
+            logger.initial_universe(universe);
+        }
+    }
+
+    fn query_for_initial_universe(&mut self, query: &Q) {
+        if let Some(logger) = self {
+            logger.query_for_initial_universe(query);
+        }
+    }
+
+    fn ranking_rules(&mut self, rr: &[BoxRankingRule<Q>]) {
+        if let Some(logger) = self {
+            logger.ranking_rules(rr);
+        }
+    }
+
+    fn start_iteration_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        ranking_rule: &dyn RankingRule<Q>,
+        query: &Q,
+        universe: &RoaringBitmap,
+    ) {
+        if let Some(logger) = self {
+            logger.start_iteration_ranking_rule(ranking_rule_idx, ranking_rule, query, universe);
+        }
+    }
+
+    fn next_bucket_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        ranking_rule: &dyn RankingRule<Q>,
+        universe: &RoaringBitmap,
+        candidates: &RoaringBitmap,
+    ) {
+        if let Some(logger) = self {
+            logger.next_bucket_ranking_rule(ranking_rule_idx, ranking_rule, universe, candidates);
+        }
+    }
+
+    fn skip_bucket_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        ranking_rule: &dyn RankingRule<Q>,
+        candidates: &RoaringBitmap,
+    ) {
+        if let Some(logger) = self {
+            logger.skip_bucket_ranking_rule(ranking_rule_idx, ranking_rule, candidates);
+        }
+    }
+
+    fn end_iteration_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        ranking_rule: &dyn RankingRule<Q>,
+        universe: &RoaringBitmap,
+    ) {
+        if let Some(logger) = self {
+            logger.end_iteration_ranking_rule(ranking_rule_idx, ranking_rule, universe);
+        }
+    }
+
+    fn distinct_excluded(&mut self, excluded: &RoaringBitmap, kept: &RoaringBitmap) {
+        if let Some(logger) = self {
+            logger.distinct_excluded(excluded, kept);
+        }
+    }
+
+    fn query_truncated(&mut self, kept_terms: usize) {
+        if let Some(logger) = self {
+            logger.query_truncated(kept_terms);
+        }
+    }
+
+    fn add_to_results(&mut self, docids: &[u32]) {
+        if let Some(logger) = self {
+            logger.add_to_results(docids);
+        }
+    }
+
+    fn log_internal_state(&mut self, rr: &dyn Any) {
+        if let Some(logger) = self {
+            logger.log_internal_state(rr);
+        }
+    }
+}
+
+/// Two [`SearchLogger`]s logging the same search.
+impl<Q: RankingRuleQueryTrait, A: SearchLogger<Q>, B: SearchLogger<Q>> SearchLogger<Q> for (A, B) {
+    fn initial_query(&mut self, query: &Q) {
+        self.0.initial_query(query);
+        self.1.initial_query(query);
+    }
+
+    fn initial_universe(&mut self, universe: &RoaringBitmap) {
+        self.0.initial_universe(universe);
+        self.1.initial_universe(universe);
+    }
+
+    fn query_for_initial_universe(&mut self, query: &Q) {
+        self.0.query_for_initial_universe(query);
+        self.1.query_for_initial_universe(query);
+    }
+
+    fn ranking_rules(&mut self, rr: &[BoxRankingRule<Q>]) {
+        self.0.ranking_rules(rr);
+        self.1.ranking_rules(rr);
+    }
+
+    fn start_iteration_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        ranking_rule: &dyn RankingRule<Q>,
+        query: &Q,
+        universe: &RoaringBitmap,
+    ) {
+        self.0.start_iteration_ranking_rule(ranking_rule_idx, ranking_rule, query, universe);
+        self.1.start_iteration_ranking_rule(ranking_rule_idx, ranking_rule, query, universe);
+    }
+
+    fn next_bucket_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        ranking_rule: &dyn RankingRule<Q>,
+        universe: &RoaringBitmap,
+        candidates: &RoaringBitmap,
+    ) {
+        self.0.next_bucket_ranking_rule(ranking_rule_idx, ranking_rule, universe, candidates);
+        self.1.next_bucket_ranking_rule(ranking_rule_idx, ranking_rule, universe, candidates);
+    }
+
+    fn skip_bucket_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        ranking_rule: &dyn RankingRule<Q>,
+        candidates: &RoaringBitmap,
+    ) {
+        self.0.skip_bucket_ranking_rule(ranking_rule_idx, ranking_rule, candidates);
+        self.1.skip_bucket_ranking_rule(ranking_rule_idx, ranking_rule, candidates);
+    }
+
+    fn end_iteration_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        ranking_rule: &dyn RankingRule<Q>,
+        universe: &RoaringBitmap,
+    ) {
+        self.0.end_iteration_ranking_rule(ranking_rule_idx, ranking_rule, universe);
+        self.1.end_iteration_ranking_rule(ranking_rule_idx, ranking_rule, universe);
+    }
+
+    fn distinct_excluded(&mut self, excluded: &RoaringBitmap, kept: &RoaringBitmap) {
+        self.0.distinct_excluded(excluded, kept);
+        self.1.distinct_excluded(excluded, kept);
+    }
+
+    fn query_truncated(&mut self, kept_terms: usize) {
+        self.0.query_truncated(kept_terms);
+        self.1.query_truncated(kept_terms);
+    }
+
+    fn add_to_results(&mut self, docids: &[u32]) {
+        self.0.add_to_results(docids);
+        self.1.add_to_results(docids);
+    }
+
+    fn log_internal_state(&mut self, rr: &dyn Any) {
+        self.0.log_internal_state(rr);
+        self.1.log_internal_state(rr);
+    }
+}
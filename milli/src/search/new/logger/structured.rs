@@ -0,0 +1,136 @@
+use std::any::Any;
+
+use roaring::RoaringBitmap;
+use serde::Serialize;
+
+use super::SearchLogger;
+use crate::search::new::ranking_rules::BoxRankingRule;
+use crate::search::new::{RankingRule, RankingRuleQueryTrait};
+
+/// The sequence of the events of a bucket sort, as recorded by the [`StructuredSearchLogger`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchTrace {
+    /// The ids of the ranking rules, in the order they are applied.
+    pub ranking_rules: Vec<String>,
+    /// The number of documents sorted by the ranking rules.
+    pub initial_universe: u64,
+    pub events: Vec<SearchTraceEvent>,
+}
+
+/// An event of the bucket sort.
+///
+/// The universes and buckets are reported by their number of documents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub enum SearchTraceEvent {
+    /// A ranking rule starts sorting a bucket of its parent.
+    #[serde(rename_all = "camelCase")]
+    StartIteration { ranking_rule_index: usize, ranking_rule: String, universe: u64 },
+    /// A ranking rule returned a bucket out of what remains of its universe.
+    #[serde(rename_all = "camelCase")]
+    NextBucket { ranking_rule_index: usize, ranking_rule: String, universe: u64, bucket: u64 },
+    /// The documents of a bucket are skipped because of the offset.
+    #[serde(rename_all = "camelCase")]
+    SkipBucket { ranking_rule_index: usize, ranking_rule: String, bucket: u64 },
+    /// A ranking rule is done sorting its universe, with this number of documents left unsorted.
+    #[serde(rename_all = "camelCase")]
+    EndIteration { ranking_rule_index: usize, ranking_rule: String, universe: u64 },
+    /// Documents are added to the results.
+    AddToResults { docids: Vec<u32> },
+}
+
+/// A [`SearchLogger`] recording the events of the bucket sort in a [`SearchTrace`].
+#[derive(Debug, Default)]
+pub struct StructuredSearchLogger {
+    trace: Option<SearchTrace>,
+}
+
+impl StructuredSearchLogger {
+    /// The recorded trace, `None` if no bucket sort was made with this logger.
+    pub fn into_trace(self) -> Option<SearchTrace> {
+        self.trace
+    }
+
+    fn push(&mut self, event: SearchTraceEvent) {
+        self.trace.get_or_insert_with(SearchTrace::default).events.push(event);
+    }
+}
+
+impl<Q: RankingRuleQueryTrait> SearchLogger<Q> for StructuredSearchLogger {
+    fn initial_query(&mut self, _query: &Q) {
+        self.trace = Some(SearchTrace::default());
+    }
+
+    fn initial_universe(&mut self, universe: &RoaringBitmap) {
+        self.trace.get_or_insert_with(SearchTrace::default).initial_universe = universe.len();
+    }
+
+    fn query_for_initial_universe(&mut self, _query: &Q) {}
+
+    fn ranking_rules(&mut self, rr: &[BoxRankingRule<Q>]) {
+        self.trace.get_or_insert_with(SearchTrace::default).ranking_rules =
+            rr.iter().map(|rr| rr.id()).collect();
+    }
+
+    fn start_iteration_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        ranking_rule: &dyn RankingRule<Q>,
+        _query: &Q,
+        universe: &RoaringBitmap,
+    ) {
+        self.push(SearchTraceEvent::StartIteration {
+            ranking_rule_index: ranking_rule_idx,
+            ranking_rule: ranking_rule.id(),
+            universe: universe.len(),
+        });
+    }
+
+    fn next_bucket_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        ranking_rule: &dyn RankingRule<Q>,
+        universe: &RoaringBitmap,
+        candidates: &RoaringBitmap,
+    ) {
+        self.push(SearchTraceEvent::NextBucket {
+            ranking_rule_index: ranking_rule_idx,
+            ranking_rule: ranking_rule.id(),
+            universe: universe.len(),
+            bucket: candidates.len(),
+        });
+    }
+
+    fn skip_bucket_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        ranking_rule: &dyn RankingRule<Q>,
+        candidates: &RoaringBitmap,
+    ) {
+        self.push(SearchTraceEvent::SkipBucket {
+            ranking_rule_index: ranking_rule_idx,
+            ranking_rule: ranking_rule.id(),
+            bucket: candidates.len(),
+        });
+    }
+
+    fn end_iteration_ranking_rule(
+        &mut self,
+        ranking_rule_idx: usize,
+        ranking_rule: &dyn RankingRule<Q>,
+        universe: &RoaringBitmap,
+    ) {
+        self.push(SearchTraceEvent::EndIteration {
+            ranking_rule_index: ranking_rule_idx,
+            ranking_rule: ranking_rule.id(),
+            universe: universe.len(),
+        });
+    }
+
+    fn add_to_results(&mut self, docids: &[u32]) {
+        self.push(SearchTraceEvent::AddToResults { docids: docids.to_vec() });
+    }
+
+    fn log_internal_state(&mut self, _rr: &dyn Any) {}
+}
@@ -424,14 +424,15 @@ fill: \"#D8A7B1\"
 
         let cur_action_id = self.id_of_last_rr_action();
         let results_id = self.id_of_extend_results();
-        let docids = new.iter().collect::<Vec<_>>();
+        // the external ids are shown as they are the ones known by the users
+        let docids = self.ctx.index.external_ids_of(self.ctx.txn, new.iter().copied())?.join(", ");
         let len = new.len();
 
         writeln!(
             &mut self.index_file,
             "{cur_action_id} -> {results_id} : \"add {len}\"
 {results_id} {{
-tooltip: \"{docids:?}\"
+tooltip: \"[{docids}]\"
 style {{
 fill: \"#B6E2D3\"
 }}
@@ -3,11 +3,13 @@ use std::fmt;
 use std::ops::RangeInclusive;
 
 use charabia::Token;
+use roaring::RoaringBitmap;
 
 use super::super::interner::Interned;
 use super::super::query_term::LocatedQueryTerm;
+use super::super::resolve_query_graph::{compute_matched_derivations, MatchedDerivations};
 use super::super::{DedupInterner, Phrase};
-use crate::SearchContext;
+use crate::{DocumentId, Result, SearchContext};
 
 pub struct LocatedMatchingPhrase {
     pub value: Interned<Phrase>,
@@ -15,7 +17,11 @@ pub struct LocatedMatchingPhrase {
 }
 
 pub struct LocatedMatchingWords {
-    pub value: Vec<Interned<String>>,
+    /// The matching words with the number of typos that derived them from the query word.
+    pub value: Vec<(Interned<String>, u8)>,
+    /// The derivations of the query word found in each returned document, they are matched
+    /// before the other derivations when the document being formatted is known.
+    pub document_words: MatchedDerivations,
     pub positions: RangeInclusive<WordId>,
     pub is_prefix: bool,
     pub original_char_count: usize,
@@ -33,12 +39,35 @@ pub struct MatchingWords {
 
 impl MatchingWords {
     pub fn new(ctx: SearchContext, located_terms: Vec<LocatedQueryTerm>) -> Self {
+        let matched_derivations = vec![MatchedDerivations::default(); located_terms.len()];
+        Self::from_located_terms(ctx, located_terms, matched_derivations)
+    }
+
+    /// Creates the matching words and records the derivations of the terms
+    /// found in each of the given documents, usually the returned ones.
+    ///
+    /// The words matched by a document are the ones the query graph was resolved with,
+    /// including the typo derivations and the completions of the prefixes.
+    pub fn with_matched_derivations(
+        mut ctx: SearchContext,
+        located_terms: Vec<LocatedQueryTerm>,
+        docids: &RoaringBitmap,
+    ) -> Result<Self> {
+        let matched_derivations = compute_matched_derivations(&mut ctx, &located_terms, docids)?;
+        Ok(Self::from_located_terms(ctx, located_terms, matched_derivations))
+    }
+
+    fn from_located_terms(
+        ctx: SearchContext,
+        located_terms: Vec<LocatedQueryTerm>,
+        matched_derivations: Vec<MatchedDerivations>,
+    ) -> Self {
         let mut phrases = Vec::new();
         let mut words = Vec::new();
 
         // Extract and centralize the different phrases and words to match stored in a QueryTerm
         // and wrap them in dedicated structures.
-        for located_term in located_terms {
+        for (located_term, document_words) in located_terms.into_iter().zip(matched_derivations) {
             let term = ctx.term_interner.get(located_term.value);
             let (matching_words, matching_phrases) = term.all_computed_derivations();
 
@@ -51,6 +80,7 @@ impl MatchingWords {
 
             words.push(LocatedMatchingWords {
                 value: matching_words,
+                document_words,
                 positions: located_term.positions.clone(),
                 is_prefix: term.is_prefix(),
                 original_char_count: term.original_word(&ctx).chars().count(),
@@ -70,14 +100,36 @@ impl MatchingWords {
 
     /// Returns an iterator over terms that match or partially match the given token.
     pub fn match_token<'a, 'b>(&'a self, token: &'b Token<'b>) -> MatchesIter<'a, 'b> {
-        MatchesIter { matching_words: self, phrases: Box::new(self.phrases.iter()), token }
+        self.match_token_in_document(token, None)
+    }
+
+    /// Returns an iterator over terms that match or partially match the given token,
+    /// the derivations found in the given document are tried first.
+    pub fn match_token_in_document<'a, 'b>(
+        &'a self,
+        token: &'b Token<'b>,
+        document: Option<DocumentId>,
+    ) -> MatchesIter<'a, 'b> {
+        MatchesIter {
+            matching_words: self,
+            phrases: Box::new(self.phrases.iter()),
+            token,
+            document,
+        }
     }
 
     /// Try to match the token with one of the located_words.
-    fn match_unique_words<'a>(&'a self, token: &Token) -> Option<MatchType<'a>> {
+    fn match_unique_words<'a>(
+        &'a self,
+        token: &Token,
+        document: Option<DocumentId>,
+    ) -> Option<MatchType<'a>> {
         for located_words in &self.words {
-            for word in &located_words.value {
-                let word = self.word_interner.get(*word);
+            let document_words = document
+                .and_then(|docid| located_words.document_words.get(&docid))
+                .map_or(&[][..], Vec::as_slice);
+            for &(word, typos) in document_words.iter().chain(&located_words.value) {
+                let word = self.word_interner.get(word);
                 // if the word is a prefix we match using starts_with.
                 if located_words.is_prefix && token.lemma().starts_with(word) {
                     let Some((char_index, c)) =
@@ -88,12 +140,12 @@ impl MatchingWords {
                     let prefix_length = char_index + c.len_utf8();
                     let char_len = token.original_lengths(prefix_length).0;
                     let ids = &located_words.positions;
-                    return Some(MatchType::Full { char_len, ids });
+                    return Some(MatchType::Full { char_len, ids, typos });
                 // else we exact match the token.
                 } else if token.lemma() == word {
                     let char_len = token.char_end - token.char_start;
                     let ids = &located_words.positions;
-                    return Some(MatchType::Full { char_len, ids });
+                    return Some(MatchType::Full { char_len, ids, typos });
                 }
             }
         }
@@ -108,6 +160,7 @@ pub struct MatchesIter<'a, 'b> {
     matching_words: &'a MatchingWords,
     phrases: Box<dyn Iterator<Item = &'a LocatedMatchingPhrase> + 'a>,
     token: &'b Token<'b>,
+    document: Option<DocumentId>,
 }
 
 impl<'a> Iterator for MatchesIter<'a, '_> {
@@ -130,12 +183,17 @@ impl<'a> Iterator for MatchesIter<'a, '_> {
                         word.map(|word| self.matching_words.word_interner.get(word).as_str())
                     })
                     .collect();
-                let partial = PartialMatch { matching_words: words, ids, char_len: 0 };
+                let partial = PartialMatch {
+                    matching_words: words,
+                    ids,
+                    char_len: 0,
+                    is_prefix: phrase.prefix,
+                };
 
                 partial.match_token(self.token).or_else(|| self.next())
             }
             // If no phrases matches, try to match uiques words.
-            None => self.matching_words.match_unique_words(self.token),
+            None => self.matching_words.match_unique_words(self.token, self.document),
         }
     }
 }
@@ -147,9 +205,11 @@ pub type WordId = u16;
 /// - split words
 /// - multi-word synonyms
 /// In these cases we need to match consecutively several tokens to consider that the match is full.
+///
+/// A full match also tells the number of typos of the matched word, phrases are always matched without typos.
 #[derive(Debug, PartialEq)]
 pub enum MatchType<'a> {
-    Full { char_len: usize, ids: &'a RangeInclusive<WordId> },
+    Full { char_len: usize, ids: &'a RangeInclusive<WordId>, typos: u8 },
     Partial(PartialMatch<'a>),
 }
 
@@ -159,6 +219,8 @@ pub struct PartialMatch<'a> {
     matching_words: Vec<Option<&'a str>>,
     ids: &'a RangeInclusive<WordId>,
     char_len: usize,
+    /// Whether the last word of the phrase is matched as a prefix.
+    is_prefix: bool,
 }
 
 impl<'a> PartialMatch<'a> {
@@ -167,25 +229,30 @@ impl<'a> PartialMatch<'a> {
     /// - Partial if the given token matches the partial match but doesn't complete it
     /// - Full if the given token completes the partial match
     pub fn match_token(self, token: &Token) -> Option<MatchType<'a>> {
-        let Self { mut matching_words, ids, .. } = self;
-
-        let is_matching = match matching_words.first()? {
-            Some(word) => &token.lemma() == word,
+        let Self { mut matching_words, ids, is_prefix, .. } = self;
+
+        let last_is_prefix = is_prefix && matching_words.len() == 1;
+        let (is_matching, char_len) = match matching_words.first()? {
+            // only the part of the token matching the prefix is highlighted
+            Some(word) if last_is_prefix => match token.lemma().starts_with(word) {
+                true => (true, token.original_lengths(word.len()).0),
+                false => (false, 0),
+            },
+            Some(word) => (&token.lemma() == word, token.char_end - token.char_start),
             // a None value in the phrase corresponds to a stop word,
             // the walue is considered a match if the current token is categorized as a stop word.
-            None => token.is_stopword(),
+            None => (token.is_stopword(), token.char_end - token.char_start),
         };
 
-        let char_len = token.char_end - token.char_start;
         // if there are remaining words to match in the phrase and the current token is matching,
         // return a new Partial match allowing the highlighter to continue.
         if is_matching && matching_words.len() > 1 {
             matching_words.remove(0);
-            Some(MatchType::Partial(PartialMatch { matching_words, ids, char_len }))
+            Some(MatchType::Partial(PartialMatch { matching_words, ids, char_len, is_prefix }))
         // if there is no remaining word to match in the phrase and the current token is matching,
         // return a Full match.
         } else if is_matching {
-            Some(MatchType::Full { char_len, ids })
+            Some(MatchType::Full { char_len, ids, typos: 0 })
         // if the current token doesn't match, return None to break the match sequence.
         } else {
             None
@@ -222,7 +289,7 @@ impl fmt::Debug for MatchingWords {
             .flat_map(|w| {
                 w.value
                     .iter()
-                    .map(|s| (word_interner.get(*s), w.positions.clone(), w.is_prefix))
+                    .map(|(s, _)| (word_interner.get(*s), w.positions.clone(), w.is_prefix))
                     .collect::<Vec<_>>()
             })
             .collect();
@@ -276,7 +343,7 @@ pub(crate) mod tests {
                     ..Default::default()
                 })
                 .next(),
-            Some(MatchType::Full { char_len: 5, ids: &(0..=0) })
+            Some(MatchType::Full { char_len: 5, ids: &(0..=0), typos: 0 })
         );
         assert_eq!(
             matching_words
@@ -300,7 +367,7 @@ pub(crate) mod tests {
                     ..Default::default()
                 })
                 .next(),
-            Some(MatchType::Full { char_len: 5, ids: &(2..=2) })
+            Some(MatchType::Full { char_len: 5, ids: &(2..=2), typos: 0 })
         );
         assert_eq!(
             matching_words
@@ -312,7 +379,7 @@ pub(crate) mod tests {
                     ..Default::default()
                 })
                 .next(),
-            Some(MatchType::Full { char_len: 5, ids: &(2..=2) })
+            Some(MatchType::Full { char_len: 5, ids: &(2..=2), typos: 0 })
         );
         assert_eq!(
             matching_words
@@ -5,6 +5,8 @@ pub use matching_words::MatchingWords;
 use matching_words::{MatchType, PartialMatch, WordId};
 use serde::Serialize;
 
+use crate::DocumentId;
+
 pub mod matching_words;
 
 const DEFAULT_CROP_MARKER: &str = "…";
@@ -18,6 +20,9 @@ pub struct MatcherBuilder<'m> {
     crop_marker: Option<String>,
     highlight_prefix: Option<String>,
     highlight_suffix: Option<String>,
+    highlight_typo_prefix: Option<String>,
+    highlight_typo_suffix: Option<String>,
+    document: Option<DocumentId>,
 }
 
 impl<'m> MatcherBuilder<'m> {
@@ -28,6 +33,9 @@ impl<'m> MatcherBuilder<'m> {
             crop_marker: None,
             highlight_prefix: None,
             highlight_suffix: None,
+            highlight_typo_prefix: None,
+            highlight_typo_suffix: None,
+            document: None,
         }
     }
 
@@ -46,6 +54,24 @@ impl<'m> MatcherBuilder<'m> {
         self
     }
 
+    /// Sets the prefix of the words matched with typos, defaults to the highlight prefix.
+    pub fn highlight_typo_prefix(&mut self, prefix: String) -> &Self {
+        self.highlight_typo_prefix = Some(prefix);
+        self
+    }
+
+    /// Sets the suffix of the words matched with typos, defaults to the highlight suffix.
+    pub fn highlight_typo_suffix(&mut self, suffix: String) -> &Self {
+        self.highlight_typo_suffix = Some(suffix);
+        self
+    }
+
+    /// Sets the document the built matchers format, its matched derivations are highlighted.
+    pub fn document(&mut self, docid: DocumentId) -> &Self {
+        self.document = Some(docid);
+        self
+    }
+
     pub fn build<'t>(&'m self, text: &'t str) -> Matcher<'t, 'm> {
         let crop_marker = match &self.crop_marker {
             Some(marker) => marker.as_str(),
@@ -60,6 +86,14 @@ impl<'m> MatcherBuilder<'m> {
             Some(marker) => marker.as_str(),
             None => DEFAULT_HIGHLIGHT_SUFFIX,
         };
+        let highlight_typo_prefix = match &self.highlight_typo_prefix {
+            Some(marker) => marker.as_str(),
+            None => highlight_prefix,
+        };
+        let highlight_typo_suffix = match &self.highlight_typo_suffix {
+            Some(marker) => marker.as_str(),
+            None => highlight_suffix,
+        };
         Matcher {
             text,
             matching_words: &self.matching_words,
@@ -67,6 +101,9 @@ impl<'m> MatcherBuilder<'m> {
             crop_marker,
             highlight_prefix,
             highlight_suffix,
+            highlight_typo_prefix,
+            highlight_typo_suffix,
+            document: self.document,
             matches: None,
         }
     }
@@ -97,6 +134,8 @@ pub struct Match {
     word_position: usize,
     // position of the token in the whole text.
     token_position: usize,
+    // number of typos of the matched word.
+    typos: u8,
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq, Eq)]
@@ -114,6 +153,9 @@ pub struct Matcher<'t, 'm> {
     crop_marker: &'m str,
     highlight_prefix: &'m str,
     highlight_suffix: &'m str,
+    highlight_typo_prefix: &'m str,
+    highlight_typo_suffix: &'m str,
+    document: Option<DocumentId>,
     matches: Option<(Vec<Token<'t>>, Vec<Match>)>,
 }
 
@@ -140,7 +182,7 @@ impl<'t> Matcher<'t, '_> {
                         partial
                     }
                     // partial match is now full, we keep this matches and we advance positions
-                    Some(MatchType::Full { char_len, ids }) => {
+                    Some(MatchType::Full { char_len, ids, typos }) => {
                         let ids: Vec<_> = ids.clone().collect();
                         // save previously matched tokens as matches.
                         let iter = potential_matches.into_iter().map(
@@ -149,6 +191,7 @@ impl<'t> Matcher<'t, '_> {
                                 ids: ids.clone(),
                                 word_position,
                                 token_position,
+                                typos,
                             },
                         );
                         matches.extend(iter);
@@ -159,6 +202,7 @@ impl<'t> Matcher<'t, '_> {
                             ids,
                             word_position,
                             token_position,
+                            typos,
                         });
 
                         // the match is complete, we return true.
@@ -191,17 +235,18 @@ impl<'t> Matcher<'t, '_> {
             .filter(|(_, _, token)| !token.is_separator());
 
         while let Some((token_position, word_position, word)) = words_positions.next() {
-            for match_type in self.matching_words.match_token(word) {
+            for match_type in self.matching_words.match_token_in_document(word, self.document) {
                 match match_type {
                     // we match, we save the current token as a match,
                     // then we continue the rest of the tokens.
-                    MatchType::Full { char_len, ids } => {
+                    MatchType::Full { char_len, ids, typos } => {
                         let ids: Vec<_> = ids.clone().collect();
                         matches.push(Match {
                             match_len: char_len,
                             ids,
                             word_position,
                             token_position,
+                            typos,
                         });
                         break;
                     }
@@ -460,9 +505,15 @@ impl<'t> Matcher<'t, '_> {
                                 .enumerate()
                                 .find(|(i, _)| *i == m.match_len)
                                 .map_or(token.byte_end, |(_, (i, _))| i + token.byte_start);
-                            formatted.push(self.highlight_prefix);
+                            // the words matched with typos can be highlighted differently.
+                            let (highlight_prefix, highlight_suffix) = if m.typos > 0 {
+                                (self.highlight_typo_prefix, self.highlight_typo_suffix)
+                            } else {
+                                (self.highlight_prefix, self.highlight_suffix)
+                            };
+                            formatted.push(highlight_prefix);
                             formatted.push(&self.text[token.byte_start..highlight_byte_index]);
-                            formatted.push(self.highlight_suffix);
+                            formatted.push(highlight_suffix);
                             // if it's a prefix highlight, we put the end of the word after the highlight marker.
                             if highlight_byte_index < token.byte_end {
                                 formatted.push(&self.text[highlight_byte_index..token.byte_end]);
@@ -888,4 +939,74 @@ mod tests {
             @"_the_ _do_ _or_ die can't be he do and or isn'_t_ _he_"
         );
     }
+
+    #[test]
+    fn highlight_typos() {
+        let temp_index = temp_index_with_documents();
+        let rtxn = temp_index.read_txn().unwrap();
+        let mut builder = MatcherBuilder::new_test(&rtxn, &temp_index, "westfalia");
+
+        let format_options = FormatOptions { highlight: true, crop: None };
+        let text = "westfalia westfali";
+
+        // without typo tags, the words matched with typos are highlighted like the others.
+        let mut matcher = builder.build(text);
+        insta::assert_snapshot!(
+            matcher.format(format_options),
+            @"<em>westfalia</em> <em>westfali</em>"
+        );
+
+        builder.highlight_typo_prefix("<em class=\"typo\">".to_string());
+        let mut matcher = builder.build(text);
+        insta::assert_snapshot!(
+            matcher.format(format_options),
+            @r###"<em>westfalia</em> <em class="typo">westfali</em>"###
+        );
+    }
+
+    #[test]
+    fn highlight_matched_derivations() {
+        let temp_index = TempIndex::new();
+        temp_index
+            .add_documents(documents!([
+                { "id": 0, "name": "a search engine" },
+                { "id": 1, "name": "looking for engines" },
+            ]))
+            .unwrap();
+        let rtxn = temp_index.read_txn().unwrap();
+
+        let mut ctx = SearchContext::new(&temp_index, &rtxn);
+        let universe = filtered_universe(&ctx, &None).unwrap();
+        let crate::search::PartialSearchResult { located_query_terms, documents_ids, .. } =
+            execute_search(
+                &mut ctx,
+                Some("serach"),
+                crate::TermsMatchingStrategy::default(),
+                crate::score_details::ScoringStrategy::Skip,
+                false,
+                universe,
+                &None,
+                crate::search::new::GeoSortStrategy::default(),
+                0,
+                100,
+                Some(10),
+                &mut crate::DefaultSearchLogger,
+                &mut crate::DefaultSearchLogger,
+                TimeBudget::max(),
+            )
+            .unwrap();
+        assert_eq!(documents_ids, vec![0]);
+
+        let docids = documents_ids.iter().copied().collect();
+        let matching_words =
+            MatchingWords::with_matched_derivations(ctx, located_query_terms.unwrap(), &docids)
+                .unwrap();
+        let mut builder =
+            MatcherBuilder::new(matching_words, TokenizerBuilder::default().into_tokenizer());
+        builder.document(0);
+
+        let format_options = FormatOptions { highlight: true, crop: None };
+        let mut matcher = builder.build("a search engine");
+        insta::assert_snapshot!(matcher.format(format_options), @"a <em>search</em> engine");
+    }
 }
@@ -0,0 +1,63 @@
+use serde::Serialize;
+
+/// The work done by a search on the databases and the bitmaps, collected by its [`SearchContext`].
+///
+/// The bitmap operations are only counted when resolving the query graph and sorting the buckets,
+/// the ones done inside of the ranking rules are not.
+///
+/// [`SearchContext`]: super::SearchContext
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchMetrics {
+    /// The docids of a word asked to the database cache.
+    pub word_docids_lookups: u64,
+    /// The docids of a prefix asked to the database cache.
+    pub prefix_docids_lookups: u64,
+    /// The prefixes missing from the prefix databases, resolved from the words starting with them.
+    pub prefix_docids_fallbacks: u64,
+    /// The docids of a distinct value fetched from the facet databases.
+    pub distinct_value_docids_lookups: u64,
+    /// The documents whose distinct values were compared to the ones of the documents kept before,
+    /// instead of fetching the documents of the values.
+    pub distinct_seen_values_checks: u64,
+    /// The values found in the database cache.
+    pub database_cache_hits: u64,
+    /// The values fetched from the databases because they were not in the database cache.
+    pub database_cache_misses: u64,
+    /// The values of the missed ones found in the cache filled by the warm-up of the index.
+    pub shared_cache_hits: u64,
+    /// The docids of a term found in the cache of the query graph resolution.
+    pub term_cache_hits: u64,
+    /// The docids of a term computed because they were not in the cache of the query graph resolution.
+    pub term_cache_misses: u64,
+    /// The bitmaps decoded from the bytes stored in the databases.
+    pub bitmap_decodes: u64,
+    pub bitmap_unions: u64,
+    pub bitmap_intersections: u64,
+    pub bitmap_differences: u64,
+}
+
+impl std::ops::Add for SearchMetrics {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        SearchMetrics {
+            word_docids_lookups: self.word_docids_lookups + other.word_docids_lookups,
+            prefix_docids_lookups: self.prefix_docids_lookups + other.prefix_docids_lookups,
+            prefix_docids_fallbacks: self.prefix_docids_fallbacks + other.prefix_docids_fallbacks,
+            distinct_value_docids_lookups: self.distinct_value_docids_lookups
+                + other.distinct_value_docids_lookups,
+            distinct_seen_values_checks: self.distinct_seen_values_checks
+                + other.distinct_seen_values_checks,
+            database_cache_hits: self.database_cache_hits + other.database_cache_hits,
+            database_cache_misses: self.database_cache_misses + other.database_cache_misses,
+            shared_cache_hits: self.shared_cache_hits + other.shared_cache_hits,
+            term_cache_hits: self.term_cache_hits + other.term_cache_hits,
+            term_cache_misses: self.term_cache_misses + other.term_cache_misses,
+            bitmap_decodes: self.bitmap_decodes + other.bitmap_decodes,
+            bitmap_unions: self.bitmap_unions + other.bitmap_unions,
+            bitmap_intersections: self.bitmap_intersections + other.bitmap_intersections,
+            bitmap_differences: self.bitmap_differences + other.bitmap_differences,
+        }
+    }
+}
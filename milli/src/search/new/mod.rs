@@ -7,57 +7,92 @@ mod interner;
 mod limits;
 mod logger;
 pub mod matches;
+mod metrics;
 mod query_graph;
 mod query_term;
 mod ranking_rule_graph;
 mod ranking_rules;
 mod resolve_query_graph;
+mod shared_cache;
 mod small_bitmap;
 
 mod exact_attribute;
+mod random_sort;
 mod sort;
 mod vector_sort;
 
 #[cfg(test)]
 mod tests;
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
-use bucket_sort::{bucket_sort, BucketSortOutput};
+pub use bucket_sort::BucketStep;
+use bucket_sort::{bucket_sort, bucket_sort_after, BucketSortOutput};
 use charabia::TokenizerBuilder;
 use db_cache::DatabaseCache;
 use exact_attribute::ExactAttribute;
 use graph_based_ranking_rule::{Exactness, Fid, Position, Proximity, Typo};
 use heed::RoTxn;
 use interner::{DedupInterner, Interner};
+pub use logger::bucket_stats::{BucketStatsLogger, RankingRuleBucketStats, MAX_RECORDED_BUCKETS};
+pub use logger::explain::{ExclusionReason, ExplainLogger, ExplainedBuckets, SearchExplanation};
+pub use logger::structured::{SearchTrace, SearchTraceEvent, StructuredSearchLogger};
 pub use logger::visual::VisualSearchLogger;
-pub use logger::{DefaultSearchLogger, SearchLogger};
-use query_graph::{QueryGraph, QueryNode};
+pub use logger::{DefaultSearchLogger, QueryGraphLogger, SearchLogger};
+pub use metrics::SearchMetrics;
+pub use query_graph::{
+    QueryEdgeDescription, QueryGraph, QueryGraphDescription, QueryNodeDescription, QueryNodeKind,
+    QueryTermDerivations,
+};
+use query_graph::{QueryNode, QueryNodeData};
 use query_term::{
     located_query_terms_from_tokens, ExtractedTokens, LocatedQueryTerm, Phrase, QueryTerm,
 };
+pub use query_term::{QueryToken, QueryTokenKind};
+use random_sort::RandomSort;
 use ranking_rules::{
-    BoxRankingRule, PlaceholderQuery, RankingRule, RankingRuleOutput, RankingRuleQueryTrait,
+    BoxRankingRule, MaxBuckets, PlaceholderQuery, RankingRule, RankingRuleOutput,
+    RankingRuleQueryTrait,
+};
+pub use ranking_rules::{SkipReason, SkippedRankingRule};
+use resolve_query_graph::{
+    compute_query_graph_docids, compute_query_term_subset_docids, PhraseDocIdsCache,
+    QueryGraphDocIdsCache,
 };
-use resolve_query_graph::{compute_query_graph_docids, PhraseDocIdsCache};
 use roaring::RoaringBitmap;
+pub use shared_cache::{SharedSearchCache, WarmUpStats};
 use sort::Sort;
 
 use self::distinct::facet_string_values;
+pub use self::distinct::DistinctStrategy;
 use self::geo_sort::GeoSort;
 pub use self::geo_sort::Strategy as GeoSortStrategy;
 use self::graph_based_ranking_rule::Words;
 use self::interner::Interned;
 use self::vector_sort::VectorSort;
+use crate::collation::Locale;
 use crate::error::FieldIdMapMissingEntry;
+use crate::localized_attributes_rules::match_pattern;
+use crate::number_normalization::normalize_number;
 use crate::score_details::{ScoreDetails, ScoringStrategy};
-use crate::search::new::distinct::apply_distinct_rule;
+use crate::search::new::distinct::DistinctRule;
+use crate::update::normalize_synonyms;
 use crate::vector::Embedder;
 use crate::{
-    AscDesc, DocumentId, FieldId, Filter, Index, Member, Result, TermsMatchingStrategy, TimeBudget,
-    UserError,
+    AscDesc, Criterion, DocumentId, Error, FieldId, Filter, Index, Member, Result, SettingsOverlay,
+    TermsMatchingStrategy, TieBreak, TimeBudget, UserError,
 };
 
+/// The default maximum number of documents of a universe that is verified against the docids
+/// of each term of the query graph instead of resolving them.
+pub const DEFAULT_SMALL_UNIVERSE_THRESHOLD: u64 = 1000;
+
+/// Receives the documents of a search batch by batch, as soon as the bucket sort ranks them.
+pub type HitsCallback<'ctx> = Box<dyn FnMut(&[DocumentId]) -> Result<ControlFlow<()>> + 'ctx>;
+
 /// A structure used throughout the execution of a search query.
 pub struct SearchContext<'ctx> {
     pub index: &'ctx Index,
@@ -67,20 +102,131 @@ pub struct SearchContext<'ctx> {
     pub phrase_interner: DedupInterner<Phrase>,
     pub term_interner: Interner<QueryTerm>,
     pub phrase_docids: PhraseDocIdsCache,
+    pub query_graph_docids: QueryGraphDocIdsCache<'ctx>,
     pub restricted_fids: Option<RestrictedFids>,
+    /// The normalized synonyms given with the query, on top of the ones of the index.
+    pub query_synonyms: HashMap<Vec<String>, Vec<Vec<String>>>,
+    /// The work done by the search so far.
+    pub metrics: SearchMetrics,
+    /// Whether the bucket sort records the buckets each returned document went through.
+    pub collect_bucket_paths: bool,
+    /// When set, the bucket sort drops the documents whose ranking score is below it.
+    pub ranking_score_threshold: Option<f64>,
+    /// The maximum number of buckets of the ranking rules without a maximum of their own.
+    pub max_buckets_per_rule: Option<usize>,
+    /// Whether the docids of the terms of a large query graph are decoded in parallel
+    /// before the graph is resolved.
+    pub parallel_term_resolution: bool,
+    /// The maximum number of documents of a universe that is verified against the serialized
+    /// docids of each term of the query graph, above it the docids of the terms are resolved.
+    pub small_universe_threshold: u64,
+    /// When set, the bucket sort returns the documents ranked after this one
+    /// instead of skipping the first `from` ones.
+    pub search_after: Option<DocumentId>,
+    /// The ranking rules of the settings that have no effect on the search, and why.
+    pub skipped_ranking_rules: Vec<SkippedRankingRule>,
+    /// The maximum number of terms of the query, its next words are ignored.
+    pub max_query_terms: usize,
+    /// The number of words derived from the terms of the query so far,
+    /// up to [`MAX_QUERY_DERIVATIONS`](limits::MAX_QUERY_DERIVATIONS).
+    pub derivation_count: usize,
+    /// The document followed through the search, the bucket sort stops once it is ranked.
+    pub explained_docid: Option<DocumentId>,
+    /// Why the explained document was discarded before the bucket sort.
+    pub explained_exclusion: Option<ExclusionReason>,
+    /// The settings used instead of the ones of the index.
+    pub settings_overlay: SettingsOverlay,
+    /// How the documents ranked the same by all the ranking rules are ordered.
+    pub tie_break: TieBreak,
+    /// The distinct attribute used instead of the one of the index.
+    pub distinct: Option<String>,
+    /// How the documents sharing a distinct value are discarded, the cheapest strategy for the
+    /// distinct attribute when `None`.
+    pub distinct_strategy: Option<DistinctStrategy>,
+    /// The documents of a bucket in their tie break order, reused by the bucket sort
+    /// from one bucket to the next.
+    pub tie_break_buffer: Vec<u32>,
+    /// Set when the client of the search disconnected, the search then stops
+    /// with a [`Error::SearchCancelled`].
+    pub canceled: Option<Arc<AtomicBool>>,
+    /// Called by the bucket sort with every batch of documents added to the results, in their
+    /// ranking order, the search stops when it returns [`ControlFlow::Break`].
+    pub hits_callback: Option<HitsCallback<'ctx>>,
 }
 
 impl<'ctx> SearchContext<'ctx> {
     pub fn new(index: &'ctx Index, txn: &'ctx RoTxn<'ctx>) -> Self {
+        let mut db_cache = DatabaseCache::default();
+        // the cache is only an optimization, the databases are read when it can't be found
+        if let Ok(Some(shared)) = SharedSearchCache::of(index, txn) {
+            db_cache.word_docids.set_shared(Some(shared.word_docids.clone()));
+            db_cache.exact_word_docids.set_shared(Some(shared.exact_word_docids.clone()));
+            db_cache.word_prefix_docids.set_shared(Some(shared.word_prefix_docids.clone()));
+            db_cache
+                .exact_word_prefix_docids
+                .set_shared(Some(shared.exact_word_prefix_docids.clone()));
+        }
+
         Self {
             index,
             txn,
-            db_cache: <_>::default(),
+            db_cache,
             word_interner: <_>::default(),
             phrase_interner: <_>::default(),
             term_interner: <_>::default(),
             phrase_docids: <_>::default(),
+            query_graph_docids: <_>::default(),
             restricted_fids: None,
+            query_synonyms: HashMap::new(),
+            metrics: SearchMetrics::default(),
+            collect_bucket_paths: false,
+            ranking_score_threshold: None,
+            max_buckets_per_rule: None,
+            parallel_term_resolution: false,
+            small_universe_threshold: DEFAULT_SMALL_UNIVERSE_THRESHOLD,
+            search_after: None,
+            skipped_ranking_rules: Vec::new(),
+            max_query_terms: limits::DEFAULT_MAX_QUERY_TERMS,
+            derivation_count: 0,
+            explained_docid: None,
+            explained_exclusion: None,
+            settings_overlay: SettingsOverlay::default(),
+            tie_break: TieBreak::default(),
+            distinct: None,
+            distinct_strategy: None,
+            tie_break_buffer: Vec::new(),
+            canceled: None,
+            hits_callback: None,
+        }
+    }
+
+    /// Returns an error if the search was canceled.
+    ///
+    /// It is called at the boundaries of the loops of the search and must stay cheap.
+    pub fn check_canceled(&self) -> Result<()> {
+        match &self.canceled {
+            Some(canceled) if canceled.load(Ordering::Relaxed) => Err(Error::SearchCancelled),
+            _ => Ok(()),
+        }
+    }
+
+    /// The id of the distinct attribute of the search, or else of the index.
+    pub fn distinct_fid(&self) -> Result<Option<FieldId>> {
+        let field = match &self.distinct {
+            Some(field) => Some(field.as_str()),
+            None => self.index.distinct_field(self.txn)?,
+        };
+        Ok(match field {
+            Some(field) => self.index.fields_ids_map(self.txn)?.id(field),
+            None => None,
+        })
+    }
+
+    /// The ranking rules of the settings overlay, or else the ones of the index.
+    pub fn criteria(&self) -> Result<Vec<Criterion>> {
+        match &self.settings_overlay.criteria {
+            Some(criteria) => Ok(criteria.clone()),
+            None => Ok(self.index.criteria(self.txn)?),
         }
     }
 
@@ -88,6 +234,7 @@ impl<'ctx> SearchContext<'ctx> {
         let fids_map = self.index.fields_ids_map(self.txn)?;
         let searchable_names = self.index.searchable_fields(self.txn)?;
         let exact_attributes_ids = self.index.exact_attributes_ids(self.txn)?;
+        let strict_attributes_ids = self.index.strict_attributes_ids(self.txn)?;
 
         let mut restricted_fids = RestrictedFids::default();
         let mut contains_wildcard = false;
@@ -96,6 +243,19 @@ impl<'ctx> SearchContext<'ctx> {
                 contains_wildcard = true;
                 continue;
             }
+            // A pattern is expanded to the searchable attributes matching it, if any.
+            if field_name.starts_with('*') || field_name.ends_with('*') {
+                let names: Vec<&str> = match &searchable_names {
+                    Some(names) => names.clone(),
+                    None => fids_map.names().collect(),
+                };
+                for name in names.into_iter().filter(|name| match_pattern(field_name, name)) {
+                    if let Some(fid) = fids_map.id(name) {
+                        restricted_fids.push(fid, &exact_attributes_ids, &strict_attributes_ids);
+                    }
+                }
+                continue;
+            }
             let searchable_contains_name =
                 searchable_names.as_ref().map(|sn| sn.iter().any(|name| name == field_name));
             let fid = match (fids_map.id(field_name), searchable_contains_name) {
@@ -128,22 +288,190 @@ impl<'ctx> SearchContext<'ctx> {
                 }
             };
 
-            if exact_attributes_ids.contains(&fid) {
-                restricted_fids.exact.push(fid);
-            } else {
-                restricted_fids.tolerant.push(fid);
-            };
+            restricted_fids.push(fid, &exact_attributes_ids, &strict_attributes_ids);
         }
 
         self.restricted_fids = (!contains_wildcard).then_some(restricted_fids);
 
         Ok(())
     }
+
+    /// Restricts the search to all the searchable attributes when some of them are strict,
+    /// the words of the strict attributes can only be told apart in the databases by field.
+    pub fn restrict_to_strict_attributes(&mut self) -> Result<()> {
+        if self.restricted_fids.is_some() {
+            return Ok(());
+        }
+        let strict_attributes_ids = self.index.strict_attributes_ids(self.txn)?;
+        if strict_attributes_ids.is_empty() {
+            return Ok(());
+        }
+        let exact_attributes_ids = self.index.exact_attributes_ids(self.txn)?;
+        let searchable_fields_ids: Vec<_> = match self.index.searchable_fields_ids(self.txn)? {
+            Some(fids) => fids,
+            None => self.index.fields_ids_map(self.txn)?.ids().collect(),
+        };
+
+        let mut restricted_fids = RestrictedFids::default();
+        for fid in searchable_fields_ids {
+            restricted_fids.push(fid, &exact_attributes_ids, &strict_attributes_ids);
+        }
+        self.restricted_fids = Some(restricted_fids);
+
+        Ok(())
+    }
+
+    /// Whether the compound words of the query are split into the words of the index, which is
+    /// the case when one of the searched attributes is localized in a decompounding locale.
+    pub fn decompounds(&self) -> Result<bool> {
+        let locales = self.index.decompounding_locales(self.txn)?;
+        if locales.is_empty() {
+            return Ok(false);
+        }
+        let rules = self.index.localized_attributes_rules(self.txn)?.unwrap_or_default();
+        let fids_map = self.index.fields_ids_map(self.txn)?;
+        let searched_fids: Vec<_> = match &self.restricted_fids {
+            Some(RestrictedFids { tolerant, exact, strict }) => {
+                tolerant.iter().chain(exact).chain(strict).copied().collect()
+            }
+            None => match self.index.searchable_fields_ids(self.txn)? {
+                Some(fids) => fids,
+                None => fids_map.ids().collect(),
+            },
+        };
+
+        Ok(searched_fids.into_iter().filter_map(|fid| fids_map.name(fid)).any(|name| {
+            // like for the collation, the first rule matching the attribute gives its locales
+            rules
+                .iter()
+                .find(|rule| rule.match_str(name))
+                .map_or(false, |rule| rule.locales.iter().any(|locale| locales.contains(locale)))
+        }))
+    }
+
+    /// The locales the words of the query are stemmed in, those of the searched attributes.
+    pub fn stemming_locales(&self) -> Result<BTreeSet<Locale>> {
+        let stemmed_fields_ids = self.index.stemmed_fields_ids(self.txn)?;
+        let is_searched = |fid: &FieldId| match &self.restricted_fids {
+            Some(RestrictedFids { tolerant, exact, strict }) => {
+                tolerant.contains(fid) || exact.contains(fid) || strict.contains(fid)
+            }
+            None => true,
+        };
+
+        Ok(stemmed_fields_ids
+            .into_iter()
+            .filter(|(fid, _)| is_searched(fid))
+            .flat_map(|(_, locales)| locales)
+            .collect())
+    }
+
+    /// Normalizes the synonyms given with the query like the synonyms of the settings.
+    pub fn synonyms(&mut self, synonyms: &BTreeMap<String, Vec<String>>) -> Result<()> {
+        let mut builder = TokenizerBuilder::new();
+        let stop_words = self.index.stop_words(self.txn)?;
+        if let Some(ref stop_words) = stop_words {
+            builder.stop_words(stop_words);
+        }
+
+        let separators = self.index.allowed_separators(self.txn)?;
+        let separators: Option<Vec<_>> =
+            separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
+        if let Some(ref separators) = separators {
+            builder.separators(separators);
+        }
+
+        let dictionary = self.index.dictionary(self.txn)?;
+        let dictionary: Option<Vec<_>> =
+            dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
+        if let Some(ref dictionary) = dictionary {
+            builder.words_dict(dictionary);
+        }
+
+        let tokenizer = builder.build();
+        self.query_synonyms = normalize_synonyms(&tokenizer, synonyms);
+
+        Ok(())
+    }
+
+    /// The synonyms of the given words, the ones given with the query
+    /// coming before the ones of the index.
+    pub fn word_synonyms(&self, words: &[String]) -> Result<Vec<Vec<String>>> {
+        let mut synonyms = self.query_synonyms.get(words).cloned().unwrap_or_default();
+        if let Some(index_synonyms) = self.index.synonyms(self.txn)?.remove(words) {
+            for synonym in index_synonyms {
+                if !synonyms.contains(&synonym) {
+                    synonyms.push(synonym);
+                }
+            }
+        }
+        Ok(synonyms)
+    }
+
+    /// Parses the query into the graph of its terms and their derivations, ignoring its
+    /// negative words, or returns `None` if there is nothing to search for.
+    ///
+    /// This is the first step of a keyword search, the next ones being
+    /// [`Self::resolve_query_graph`] and [`Self::bucket_sort_query_graph`].
+    /// They are exposed to run, and benchmark, a search step by step.
+    pub fn query_graph(
+        &mut self,
+        query: &str,
+        words_limit: Option<usize>,
+    ) -> Result<Option<QueryGraph>> {
+        let ExtractedTokens { query_terms, .. } = extract_query_tokens(self, query, words_limit)?;
+        if query_terms.is_empty() {
+            return Ok(None);
+        }
+        let (graph, _) = QueryGraph::from_query(self, &query_terms)?;
+        Ok(Some(graph))
+    }
+
+    /// Returns the documents of the universe matching all the terms of the query graph.
+    pub fn resolve_query_graph(
+        &mut self,
+        graph: &QueryGraph,
+        universe: &RoaringBitmap,
+    ) -> Result<RoaringBitmap> {
+        compute_query_graph_docids(self, graph, universe)
+    }
+
+    /// Ranks the documents of the universe with the ranking rules of the index
+    /// and returns the `length` ones following the first `from` ones.
+    pub fn bucket_sort_query_graph(
+        &mut self,
+        graph: &QueryGraph,
+        universe: &RoaringBitmap,
+        from: usize,
+        length: usize,
+    ) -> Result<Vec<DocumentId>> {
+        let ranking_rules = get_ranking_rules_for_query_graph_search(
+            self,
+            &None,
+            geo_sort::Strategy::default(),
+            TermsMatchingStrategy::default(),
+        )?;
+        let BucketSortOutput { docids, .. } = bucket_sort(
+            self,
+            ranking_rules,
+            graph,
+            universe,
+            from,
+            length,
+            ScoringStrategy::Skip,
+            &mut DefaultSearchLogger,
+            TimeBudget::max(),
+        )?;
+        Ok(docids)
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, PartialOrd, Ord, Eq)]
 pub enum Word {
     Original(Interned<String>),
+    /// A word derived from the query without typos, a prefix, a split word or a synonym:
+    /// it matches the attributes without typos, but not the strict ones.
+    ZeroTypoDerived(Interned<String>),
     Derived(Interned<String>),
 }
 
@@ -151,6 +479,7 @@ impl Word {
     pub fn interned(&self) -> Interned<String> {
         match self {
             Word::Original(word) => *word,
+            Word::ZeroTypoDerived(word) => *word,
             Word::Derived(word) => *word,
         }
     }
@@ -160,11 +489,31 @@ impl Word {
 pub struct RestrictedFids {
     pub tolerant: Vec<FieldId>,
     pub exact: Vec<FieldId>,
+    /// The attributes only matching the original words of the query.
+    pub strict: Vec<FieldId>,
 }
 
 impl RestrictedFids {
     pub fn contains(&self, fid: &FieldId) -> bool {
-        self.tolerant.contains(fid) || self.exact.contains(fid)
+        self.tolerant.contains(fid) || self.exact.contains(fid) || self.strict.contains(fid)
+    }
+
+    fn push(
+        &mut self,
+        fid: FieldId,
+        exact_attributes_ids: &HashSet<FieldId>,
+        strict_attributes_ids: &HashSet<FieldId>,
+    ) {
+        if self.contains(&fid) {
+            return;
+        }
+        if strict_attributes_ids.contains(&fid) {
+            self.strict.push(fid);
+        } else if exact_attributes_ids.contains(&fid) {
+            self.exact.push(fid);
+        } else {
+            self.tolerant.push(fid);
+        }
     }
 }
 
@@ -178,14 +527,11 @@ fn resolve_maximally_reduced_query_graph(
 ) -> Result<RoaringBitmap> {
     let mut graph = query_graph.clone();
 
-    let nodes_to_remove = match matching_strategy {
-        TermsMatchingStrategy::Last => query_graph
-            .removal_order_for_terms_matching_strategy_last(ctx)
-            .iter()
-            .flat_map(|x| x.iter())
-            .collect(),
-        TermsMatchingStrategy::All => vec![],
-    };
+    let nodes_to_remove: Vec<_> = query_graph
+        .removal_order_for_terms_matching_strategy(ctx, matching_strategy)?
+        .iter()
+        .flat_map(|x| x.iter())
+        .collect();
     graph.remove_nodes_keep_edges(&nodes_to_remove);
 
     logger.query_for_initial_universe(&graph);
@@ -242,7 +588,7 @@ fn resolve_negative_phrases(
 
 /// Return the list of initialised ranking rules to be used for a placeholder search.
 fn get_ranking_rules_for_placeholder_search<'ctx>(
-    ctx: &SearchContext<'ctx>,
+    ctx: &mut SearchContext<'ctx>,
     sort_criteria: &Option<Vec<AscDesc>>,
     geo_strategy: geo_sort::Strategy,
 ) -> Result<Vec<BoxRankingRule<'ctx, PlaceholderQuery>>> {
@@ -250,19 +596,26 @@ fn get_ranking_rules_for_placeholder_search<'ctx>(
     let mut sorted_fields = HashSet::new();
     let mut geo_sorted = false;
     let mut ranking_rules: Vec<BoxRankingRule<PlaceholderQuery>> = vec![];
-    let settings_ranking_rules = ctx.index.criteria(ctx.txn)?;
+    let settings_ranking_rules = ctx.criteria()?;
+    let criteria_max_buckets = ctx.index.criteria_max_buckets(ctx.txn)?;
     for rr in settings_ranking_rules {
+        let max_buckets = criterion_max_buckets(&criteria_max_buckets, &rr);
+        let first = ranking_rules.len();
         match rr {
             // These rules need a query to have an effect; ignore them in placeholder search
             crate::Criterion::Words
             | crate::Criterion::Typo
             | crate::Criterion::Attribute
             | crate::Criterion::Proximity
-            | crate::Criterion::Exactness => continue,
+            | crate::Criterion::Exactness => {
+                skip_ranking_rule(ctx, &rr, SkipReason::EmptyQuery);
+                continue;
+            }
             crate::Criterion::Sort => {
                 if sort {
                     continue;
                 }
+                skip_sort_without_effect(ctx, sort_criteria)?;
                 resolve_sort_criteria(
                     sort_criteria,
                     ctx,
@@ -277,6 +630,7 @@ fn get_ranking_rules_for_placeholder_search<'ctx>(
                 if sorted_fields.contains(&field_name) {
                     continue;
                 }
+                skip_unknown_attribute(ctx, &crate::Criterion::Asc(field_name.clone()))?;
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, true)?));
             }
@@ -284,16 +638,31 @@ fn get_ranking_rules_for_placeholder_search<'ctx>(
                 if sorted_fields.contains(&field_name) {
                     continue;
                 }
+                skip_unknown_attribute(ctx, &crate::Criterion::Desc(field_name.clone()))?;
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, false)?));
             }
+            crate::Criterion::Random { seed, bucket_size } => {
+                ranking_rules.push(Box::new(RandomSort::new(ctx, seed, bucket_size)?));
+            }
+            crate::Criterion::GeoPoint { point, bucket_size } => {
+                let geo_faceted_docids = ctx.index.geo_faceted_documents_ids(ctx.txn)?;
+                ranking_rules.push(Box::new(GeoSort::new(
+                    geo_strategy,
+                    geo_faceted_docids,
+                    [point.lat, point.lng],
+                    true,
+                    bucket_size,
+                )?));
+            }
         }
+        cap_buckets(&mut ranking_rules, first, max_buckets);
     }
     Ok(ranking_rules)
 }
 
 fn get_ranking_rules_for_vector<'ctx>(
-    ctx: &SearchContext<'ctx>,
+    ctx: &mut SearchContext<'ctx>,
     sort_criteria: &Option<Vec<AscDesc>>,
     geo_strategy: geo_sort::Strategy,
     limit_plus_offset: usize,
@@ -310,8 +679,11 @@ fn get_ranking_rules_for_vector<'ctx>(
     let mut vector = false;
     let mut ranking_rules: Vec<BoxRankingRule<PlaceholderQuery>> = vec![];
 
-    let settings_ranking_rules = ctx.index.criteria(ctx.txn)?;
+    let settings_ranking_rules = ctx.criteria()?;
+    let criteria_max_buckets = ctx.index.criteria_max_buckets(ctx.txn)?;
     for rr in settings_ranking_rules {
+        let max_buckets = criterion_max_buckets(&criteria_max_buckets, &rr);
+        let first = ranking_rules.len();
         match rr {
             crate::Criterion::Words
             | crate::Criterion::Typo
@@ -336,6 +708,7 @@ fn get_ranking_rules_for_vector<'ctx>(
                 if sort {
                     continue;
                 }
+                skip_sort_without_effect(ctx, sort_criteria)?;
                 resolve_sort_criteria(
                     sort_criteria,
                     ctx,
@@ -350,6 +723,7 @@ fn get_ranking_rules_for_vector<'ctx>(
                 if sorted_fields.contains(&field_name) {
                     continue;
                 }
+                skip_unknown_attribute(ctx, &crate::Criterion::Asc(field_name.clone()))?;
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, true)?));
             }
@@ -357,10 +731,25 @@ fn get_ranking_rules_for_vector<'ctx>(
                 if sorted_fields.contains(&field_name) {
                     continue;
                 }
+                skip_unknown_attribute(ctx, &crate::Criterion::Desc(field_name.clone()))?;
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, false)?));
             }
+            crate::Criterion::Random { seed, bucket_size } => {
+                ranking_rules.push(Box::new(RandomSort::new(ctx, seed, bucket_size)?));
+            }
+            crate::Criterion::GeoPoint { point, bucket_size } => {
+                let geo_faceted_docids = ctx.index.geo_faceted_documents_ids(ctx.txn)?;
+                ranking_rules.push(Box::new(GeoSort::new(
+                    geo_strategy,
+                    geo_faceted_docids,
+                    [point.lat, point.lng],
+                    true,
+                    bucket_size,
+                )?));
+            }
         }
+        cap_buckets(&mut ranking_rules, first, max_buckets);
     }
 
     Ok(ranking_rules)
@@ -368,7 +757,7 @@ fn get_ranking_rules_for_vector<'ctx>(
 
 /// Return the list of initialised ranking rules to be used for a query graph search.
 fn get_ranking_rules_for_query_graph_search<'ctx>(
-    ctx: &SearchContext<'ctx>,
+    ctx: &mut SearchContext<'ctx>,
     sort_criteria: &Option<Vec<AscDesc>>,
     geo_strategy: geo_sort::Strategy,
     terms_matching_strategy: TermsMatchingStrategy,
@@ -389,8 +778,10 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
     }
 
     let mut ranking_rules: Vec<BoxRankingRule<QueryGraph>> = vec![];
-    let settings_ranking_rules = ctx.index.criteria(ctx.txn)?;
+    let settings_ranking_rules = ctx.criteria()?;
+    let criteria_max_buckets = ctx.index.criteria_max_buckets(ctx.txn)?;
     for rr in settings_ranking_rules {
+        let first = ranking_rules.len();
         // Add Words before any of: typo, proximity, attribute
         match rr {
             crate::Criterion::Typo
@@ -404,8 +795,17 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
             }
             _ => {}
         }
+        let words_max_buckets =
+            criterion_max_buckets(&criteria_max_buckets, &crate::Criterion::Words);
+        cap_buckets(&mut ranking_rules, first, words_max_buckets);
+
+        let max_buckets = criterion_max_buckets(&criteria_max_buckets, &rr);
+        let first = ranking_rules.len();
         match rr {
             crate::Criterion::Words => {
+                if matches!(terms_matching_strategy, TermsMatchingStrategy::All) {
+                    skip_ranking_rule(ctx, &rr, SkipReason::MatchingStrategyAll);
+                }
                 if words {
                     continue;
                 }
@@ -438,6 +838,7 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
                 if sort {
                     continue;
                 }
+                skip_sort_without_effect(ctx, sort_criteria)?;
                 resolve_sort_criteria(
                     sort_criteria,
                     ctx,
@@ -460,6 +861,7 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
                 if sorted_fields.contains(&field_name) {
                     continue;
                 }
+                skip_unknown_attribute(ctx, &crate::Criterion::Asc(field_name.clone()))?;
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, true)?));
             }
@@ -467,14 +869,83 @@ fn get_ranking_rules_for_query_graph_search<'ctx>(
                 if sorted_fields.contains(&field_name) {
                     continue;
                 }
+                skip_unknown_attribute(ctx, &crate::Criterion::Desc(field_name.clone()))?;
                 sorted_fields.insert(field_name.clone());
                 ranking_rules.push(Box::new(Sort::new(ctx.index, ctx.txn, field_name, false)?));
             }
+            crate::Criterion::Random { seed, bucket_size } => {
+                ranking_rules.push(Box::new(RandomSort::new(ctx, seed, bucket_size)?));
+            }
+            crate::Criterion::GeoPoint { point, bucket_size } => {
+                let geo_faceted_docids = ctx.index.geo_faceted_documents_ids(ctx.txn)?;
+                ranking_rules.push(Box::new(GeoSort::new(
+                    geo_strategy,
+                    geo_faceted_docids,
+                    [point.lat, point.lng],
+                    true,
+                    bucket_size,
+                )?));
+            }
         }
+        cap_buckets(&mut ranking_rules, first, max_buckets);
     }
     Ok(ranking_rules)
 }
 
+/// The maximum number of buckets of the ranking rules created from the criterion.
+fn criterion_max_buckets(
+    criteria_max_buckets: &BTreeMap<String, usize>,
+    criterion: &crate::Criterion,
+) -> Option<usize> {
+    criteria_max_buckets.get(&criterion.to_string()).copied()
+}
+
+/// Caps the number of buckets of the ranking rules created since the `first` one.
+fn cap_buckets<'ctx, Query: RankingRuleQueryTrait>(
+    ranking_rules: &mut Vec<BoxRankingRule<'ctx, Query>>,
+    first: usize,
+    max_buckets: Option<usize>,
+) {
+    let Some(max_buckets) = max_buckets else { return };
+    let capped: Vec<BoxRankingRule<'ctx, Query>> = ranking_rules
+        .drain(first..)
+        .map(|ranking_rule| -> BoxRankingRule<'ctx, Query> {
+            Box::new(MaxBuckets::new(ranking_rule, max_buckets))
+        })
+        .collect();
+    ranking_rules.extend(capped);
+}
+
+fn skip_ranking_rule(ctx: &mut SearchContext, ranking_rule: &crate::Criterion, reason: SkipReason) {
+    ctx.skipped_ranking_rules
+        .push(SkippedRankingRule { ranking_rule: ranking_rule.to_string(), reason });
+}
+
+/// Records the `sort` ranking rule as skipped if the search can't sort by anything.
+fn skip_sort_without_effect(
+    ctx: &mut SearchContext,
+    sort_criteria: &Option<Vec<AscDesc>>,
+) -> Result<()> {
+    if sort_criteria.as_ref().map_or(true, Vec::is_empty) {
+        let reason = if ctx.index.sortable_fields(ctx.txn)?.is_empty() {
+            SkipReason::NoSortableAttributes
+        } else {
+            SkipReason::NoSortParameter
+        };
+        skip_ranking_rule(ctx, &crate::Criterion::Sort, reason);
+    }
+    Ok(())
+}
+
+/// Records an `asc`/`desc` ranking rule as skipped if no document contains its field.
+fn skip_unknown_attribute(ctx: &mut SearchContext, ranking_rule: &crate::Criterion) -> Result<()> {
+    let field_name = ranking_rule.field_name().unwrap_or_default();
+    if ctx.index.fields_ids_map(ctx.txn)?.id(field_name).is_none() {
+        skip_ranking_rule(ctx, ranking_rule, SkipReason::UnknownAttribute);
+    }
+    Ok(())
+}
+
 fn resolve_sort_criteria<'ctx, Query: RankingRuleQueryTrait>(
     sort_criteria: &Option<Vec<AscDesc>>,
     ctx: &SearchContext<'ctx>,
@@ -511,6 +982,7 @@ fn resolve_sort_criteria<'ctx, Query: RankingRuleQueryTrait>(
                     geo_faceted_docids,
                     point,
                     true,
+                    1,
                 )?));
             }
             AscDesc::Desc(Member::Geo(point)) => {
@@ -523,6 +995,7 @@ fn resolve_sort_criteria<'ctx, Query: RankingRuleQueryTrait>(
                     geo_faceted_docids,
                     point,
                     false,
+                    1,
                 )?));
             }
         };
@@ -543,6 +1016,7 @@ pub fn execute_vector_search(
     ctx: &mut SearchContext,
     vector: &[f32],
     scoring_strategy: ScoringStrategy,
+    exhaustive_number_hits: bool,
     universe: RoaringBitmap,
     sort_criteria: &Option<Vec<AscDesc>>,
     geo_strategy: geo_sort::Strategy,
@@ -570,25 +1044,46 @@ pub fn execute_vector_search(
     let placeholder_search_logger: &mut dyn SearchLogger<PlaceholderQuery> =
         &mut placeholder_search_logger;
 
-    let BucketSortOutput { docids, scores, all_candidates, degraded } = bucket_sort(
-        ctx,
-        ranking_rules,
-        &PlaceholderQuery,
-        &universe,
-        from,
-        length,
-        scoring_strategy,
-        placeholder_search_logger,
-        time_budget,
-    )?;
+    let bucket_sort_output = match ctx.search_after {
+        Some(after_docid) => bucket_sort_after(
+            ctx,
+            ranking_rules,
+            &PlaceholderQuery,
+            &universe,
+            after_docid,
+            length,
+            scoring_strategy,
+            placeholder_search_logger,
+            time_budget,
+        )?,
+        None => bucket_sort(
+            ctx,
+            ranking_rules,
+            &PlaceholderQuery,
+            &universe,
+            from,
+            length,
+            scoring_strategy,
+            placeholder_search_logger,
+            time_budget,
+        )?,
+    };
+    let BucketSortOutput { docids, scores, bucket_paths, all_candidates, degraded } =
+        bucket_sort_output;
+
+    let all_candidates = exhaustive_candidates(ctx, all_candidates, exhaustive_number_hits)?;
 
     Ok(PartialSearchResult {
         candidates: all_candidates,
         document_scores: scores,
+        document_bucket_paths: bucket_paths,
         documents_ids: docids,
         located_query_terms: None,
         degraded,
         used_negative_operator: false,
+        numeric_prefix_disabled: false,
+        query_truncated: false,
+        query_tokens: Vec::new(),
     })
 }
 
@@ -613,52 +1108,37 @@ pub fn execute_search(
     check_sort_criteria(ctx, sort_criteria.as_ref())?;
 
     let mut used_negative_operator = false;
+    let mut numeric_prefix_disabled = false;
+    let mut query_truncated = false;
+    let mut query_tokens = Vec::new();
     let mut located_query_terms = None;
+    let mut query_graph_degradation = None;
     let query_terms = if let Some(query) = query {
-        let span = tracing::trace_span!(target: "search::tokens", "tokenizer_builder");
-        let entered = span.enter();
-
-        // We make sure that the analyzer is aware of the stop words
-        // this ensures that the query builder is able to properly remove them.
-        let mut tokbuilder = TokenizerBuilder::new();
-        let stop_words = ctx.index.stop_words(ctx.txn)?;
-        if let Some(ref stop_words) = stop_words {
-            tokbuilder.stop_words(stop_words);
-        }
-
-        let separators = ctx.index.allowed_separators(ctx.txn)?;
-        let separators: Option<Vec<_>> =
-            separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
-        if let Some(ref separators) = separators {
-            tokbuilder.separators(separators);
-        }
-
-        let dictionary = ctx.index.dictionary(ctx.txn)?;
-        let dictionary: Option<Vec<_>> =
-            dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
-        if let Some(ref dictionary) = dictionary {
-            tokbuilder.words_dict(dictionary);
-        }
-
-        let script_lang_map = ctx.index.script_language(ctx.txn)?;
-        if !script_lang_map.is_empty() {
-            tokbuilder.allow_list(&script_lang_map);
-        }
-
-        let tokenizer = tokbuilder.build();
-        drop(entered);
-
-        let span = tracing::trace_span!(target: "search::tokens", "tokenize");
-        let entered = span.enter();
-        let tokens = tokenizer.tokenize(query);
-        drop(entered);
-
-        let ExtractedTokens { query_terms, negative_words, negative_phrases } =
-            located_query_terms_from_tokens(ctx, tokens, words_limit)?;
+        let ExtractedTokens {
+            query_terms,
+            negative_words,
+            negative_phrases,
+            numeric_prefix_disabled: last_number_not_prefix,
+            query_tokens: extracted_query_tokens,
+            query_truncated: truncated,
+        } = extract_query_tokens(ctx, query, words_limit)?;
         used_negative_operator = !negative_words.is_empty() || !negative_phrases.is_empty();
+        numeric_prefix_disabled = last_number_not_prefix;
+        query_tokens = extracted_query_tokens;
+        if truncated {
+            query_graph_logger.query_truncated(query_terms.len());
+            query_truncated = true;
+        }
 
         let ignored_documents = resolve_negative_words(ctx, &negative_words)?;
         let ignored_phrases = resolve_negative_phrases(ctx, &negative_phrases)?;
+        if let Some(docid) = ctx.explained_docid {
+            if universe.contains(docid)
+                && (ignored_documents.contains(docid) || ignored_phrases.contains(docid))
+            {
+                ctx.explained_exclusion = Some(ExclusionReason::NegativeQuery);
+            }
+        }
 
         universe -= ignored_documents;
         universe -= ignored_phrases;
@@ -674,8 +1154,10 @@ pub fn execute_search(
     };
 
     let bucket_sort_output = if let Some(query_terms) = query_terms {
-        let (graph, new_located_query_terms) = QueryGraph::from_query(ctx, &query_terms)?;
+        let (mut graph, new_located_query_terms) = QueryGraph::from_query(ctx, &query_terms)?;
+        graph.prune_empty_terms(ctx)?;
         located_query_terms = Some(new_located_query_terms);
+        query_graph_degradation = graph.degradation;
 
         let ranking_rules = get_ranking_rules_for_query_graph_search(
             ctx,
@@ -684,59 +1166,207 @@ pub fn execute_search(
             terms_matching_strategy,
         )?;
 
-        universe &=
+        let query_universe =
             resolve_universe(ctx, &universe, &graph, terms_matching_strategy, query_graph_logger)?;
-
-        bucket_sort(
-            ctx,
-            ranking_rules,
-            &graph,
-            &universe,
-            from,
-            length,
-            scoring_strategy,
-            query_graph_logger,
-            time_budget,
-        )?
+        if let Some(docid) = ctx.explained_docid {
+            if universe.contains(docid) && !query_universe.contains(docid) {
+                ctx.explained_exclusion = Some(if matches_any_term(ctx, &graph, docid)? {
+                    ExclusionReason::DroppedByWordsRelaxation
+                } else {
+                    ExclusionReason::NotMatchingQuery
+                });
+            }
+        }
+        universe &= query_universe;
+
+        match ctx.search_after {
+            Some(after_docid) => bucket_sort_after(
+                ctx,
+                ranking_rules,
+                &graph,
+                &universe,
+                after_docid,
+                length,
+                scoring_strategy,
+                query_graph_logger,
+                time_budget,
+            )?,
+            None => bucket_sort(
+                ctx,
+                ranking_rules,
+                &graph,
+                &universe,
+                from,
+                length,
+                scoring_strategy,
+                query_graph_logger,
+                time_budget,
+            )?,
+        }
     } else {
         let ranking_rules =
             get_ranking_rules_for_placeholder_search(ctx, sort_criteria, geo_strategy)?;
-        bucket_sort(
-            ctx,
-            ranking_rules,
-            &PlaceholderQuery,
-            &universe,
-            from,
-            length,
-            scoring_strategy,
-            placeholder_search_logger,
-            time_budget,
-        )?
+        match ctx.search_after {
+            Some(after_docid) => bucket_sort_after(
+                ctx,
+                ranking_rules,
+                &PlaceholderQuery,
+                &universe,
+                after_docid,
+                length,
+                scoring_strategy,
+                placeholder_search_logger,
+                time_budget,
+            )?,
+            None => bucket_sort(
+                ctx,
+                ranking_rules,
+                &PlaceholderQuery,
+                &universe,
+                from,
+                length,
+                scoring_strategy,
+                placeholder_search_logger,
+                time_budget,
+            )?,
+        }
     };
 
-    let BucketSortOutput { docids, scores, mut all_candidates, degraded } = bucket_sort_output;
-    let fields_ids_map = ctx.index.fields_ids_map(ctx.txn)?;
-
-    // The candidates is the universe unless the exhaustive number of hits
-    // is requested and a distinct attribute is set.
-    if exhaustive_number_hits {
-        if let Some(f) = ctx.index.distinct_field(ctx.txn)? {
-            if let Some(distinct_fid) = fields_ids_map.id(f) {
-                all_candidates = apply_distinct_rule(ctx, distinct_fid, &all_candidates)?.remaining;
-            }
+    let BucketSortOutput { docids, mut scores, bucket_paths, all_candidates, degraded } =
+        bucket_sort_output;
+    if let Some(degradation) = query_graph_degradation {
+        for document_scores in &mut scores {
+            document_scores.push(ScoreDetails::QueryGraphDegraded(degradation));
         }
     }
+    let all_candidates = exhaustive_candidates(ctx, all_candidates, exhaustive_number_hits)?;
 
     Ok(PartialSearchResult {
         candidates: all_candidates,
         document_scores: scores,
+        document_bucket_paths: bucket_paths,
         documents_ids: docids,
         located_query_terms,
         degraded,
         used_negative_operator,
+        numeric_prefix_disabled,
+        query_truncated,
+        query_tokens,
     })
 }
 
+/// Builds the explanation of a document from what the search recorded about it.
+pub(crate) fn explain_document(
+    ctx: &mut SearchContext,
+    docid: DocumentId,
+    buckets: ExplainedBuckets,
+    documents_ids: &[DocumentId],
+) -> Result<SearchExplanation> {
+    let ExplainedBuckets { bucket_path, distinct_kept } = buckets;
+    let position = documents_ids.iter().position(|&id| id == docid);
+    let exclusion = match (position, ctx.explained_exclusion, distinct_kept) {
+        (Some(_), _, _) => None,
+        (None, Some(reason), _) => Some(reason),
+        (None, None, Some(kept)) => match ctx.distinct_fid()? {
+            Some(distinct_fid) => distinct::distinct_kept_docid(ctx, distinct_fid, docid, &kept)?
+                .map(|kept| ExclusionReason::Distinct { kept }),
+            None => None,
+        },
+        // the documents ranked by the bucket sort are all returned, but the ones below the threshold
+        (None, None, None) => {
+            ctx.ranking_score_threshold.map(|_| ExclusionReason::BelowRankingScoreThreshold)
+        }
+    };
+    Ok(SearchExplanation { bucket_path, exclusion, position })
+}
+
+/// Whether the document contains at least one of the terms of the query graph.
+fn matches_any_term(
+    ctx: &mut SearchContext,
+    graph: &QueryGraph,
+    docid: DocumentId,
+) -> Result<bool> {
+    for (_, node) in graph.nodes.iter() {
+        if let QueryNodeData::Term(term) = &node.data {
+            if compute_query_term_subset_docids(ctx, &term.term_subset)?.contains(docid) {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// The candidates is the universe unless the exhaustive number of hits
+/// is requested and a distinct attribute is set.
+///
+/// The bucket sort only deduplicates the documents it returned, so the distinct rule is applied
+/// to all the remaining candidates to keep a single document per distinct value. The kept
+/// documents may not be the ones the bucket sort would have chosen, but their number is exact.
+fn exhaustive_candidates(
+    ctx: &mut SearchContext,
+    all_candidates: RoaringBitmap,
+    exhaustive_number_hits: bool,
+) -> Result<RoaringBitmap> {
+    if exhaustive_number_hits {
+        if let Some(distinct_fid) = ctx.distinct_fid()? {
+            let mut distinct = DistinctRule::new(ctx, distinct_fid)?;
+            return Ok(distinct.apply(ctx, &all_candidates)?.remaining);
+        }
+    }
+    Ok(all_candidates)
+}
+
+/// Tokenizes the query like the documents were and extracts its terms.
+fn extract_query_tokens(
+    ctx: &mut SearchContext,
+    query: &str,
+    words_limit: Option<usize>,
+) -> Result<ExtractedTokens> {
+    let span = tracing::trace_span!(target: "search::tokens", "tokenizer_builder");
+    let entered = span.enter();
+
+    // We make sure that the analyzer is aware of the stop words
+    // this ensures that the query builder is able to properly remove them.
+    let mut tokbuilder = TokenizerBuilder::new();
+    let stop_words = ctx.index.stop_words(ctx.txn)?;
+    if let Some(ref stop_words) = stop_words {
+        tokbuilder.stop_words(stop_words);
+    }
+
+    let separators = ctx.index.allowed_separators(ctx.txn)?;
+    let separators: Option<Vec<_>> =
+        separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
+    if let Some(ref separators) = separators {
+        tokbuilder.separators(separators);
+    }
+
+    let dictionary = ctx.index.dictionary(ctx.txn)?;
+    let dictionary: Option<Vec<_>> =
+        dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
+    if let Some(ref dictionary) = dictionary {
+        tokbuilder.words_dict(dictionary);
+    }
+
+    let script_lang_map = ctx.index.script_language(ctx.txn)?;
+    if !script_lang_map.is_empty() {
+        tokbuilder.allow_list(&script_lang_map);
+    }
+
+    let tokenizer = tokbuilder.build();
+    drop(entered);
+
+    let span = tracing::trace_span!(target: "search::tokens", "tokenize");
+    let entered = span.enter();
+    let tokens = tokenizer.tokenize(query);
+    drop(entered);
+
+    if ctx.index.number_normalization(ctx.txn)? {
+        located_query_terms_from_tokens(ctx, tokens.map(normalize_number), words_limit)
+    } else {
+        located_query_terms_from_tokens(ctx, tokens, words_limit)
+    }
+}
+
 fn check_sort_criteria(ctx: &SearchContext, sort_criteria: Option<&Vec<AscDesc>>) -> Result<()> {
     let sort_criteria = if let Some(sort_criteria) = sort_criteria {
         sort_criteria
@@ -750,7 +1380,7 @@ fn check_sort_criteria(ctx: &SearchContext, sort_criteria: Option<&Vec<AscDesc>>
 
     // We check that the sort ranking rule exists and throw an
     // error if we try to use it and that it doesn't.
-    let sort_ranking_rule_missing = !ctx.index.criteria(ctx.txn)?.contains(&crate::Criterion::Sort);
+    let sort_ranking_rule_missing = !ctx.criteria()?.contains(&crate::Criterion::Sort);
     if sort_ranking_rule_missing {
         return Err(UserError::SortRankingRuleMissing.into());
     }
@@ -794,7 +1424,15 @@ pub struct PartialSearchResult {
     pub candidates: RoaringBitmap,
     pub documents_ids: Vec<DocumentId>,
     pub document_scores: Vec<Vec<ScoreDetails>>,
+    /// The buckets each document went through, empty if they were not collected.
+    pub document_bucket_paths: Vec<Vec<BucketStep>>,
 
     pub degraded: bool,
     pub used_negative_operator: bool,
+    /// The last word of the query is a short number that was not used as a prefix.
+    pub numeric_prefix_disabled: bool,
+    /// Words of the query were ignored because it has too many terms.
+    pub query_truncated: bool,
+    /// The tokens of the query, located in the original query.
+    pub query_tokens: Vec<QueryToken>,
 }
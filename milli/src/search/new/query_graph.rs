@@ -1,17 +1,22 @@
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
 use std::collections::BTreeMap;
+use std::fmt::Write;
 use std::hash::{Hash, Hasher};
 
 use fxhash::{FxHashMap, FxHasher};
+use roaring::RoaringBitmap;
+use serde::Serialize;
 
 use super::interner::{FixedSizeInterner, Interned};
 use super::query_term::{
     self, number_of_typos_allowed, LocatedQueryTerm, LocatedQueryTermSubset, QueryTermSubset,
 };
+use super::resolve_query_graph::compute_query_term_subset_docids;
 use super::small_bitmap::SmallBitmap;
-use super::SearchContext;
+use super::{limits, SearchContext, Word};
+use crate::score_details::QueryGraphDegradation;
 use crate::search::new::interner::Interner;
-use crate::Result;
+use crate::{Result, TermsMatchingStrategy};
 
 /// A node of the [`QueryGraph`].
 ///
@@ -85,79 +90,63 @@ pub struct QueryGraph {
     pub end_node: Interned<QueryNode>,
     /// The list of all query nodes
     pub nodes: FixedSizeInterner<QueryNode>,
+    /// The alternatives dropped because the graph was too large, if any
+    pub degradation: Option<QueryGraphDegradation>,
 }
 
 impl QueryGraph {
     /// Build the query graph from the parsed user search query, return an updated list of the located query terms
     /// which contains ngrams.
+    ///
+    /// When the graph would have more than [`MAX_QUERY_GRAPH_ALTERNATIVES`](limits::MAX_QUERY_GRAPH_ALTERNATIVES),
+    /// it is degraded by dropping all the ngrams first, then the synonyms of the least frequent terms.
     pub fn from_query(
         ctx: &mut SearchContext,
         // The terms here must be consecutive
         terms: &[LocatedQueryTerm],
     ) -> Result<(QueryGraph, Vec<LocatedQueryTerm>)> {
-        let mut new_located_query_terms = terms.to_vec();
-
         let nbr_typos = number_of_typos_allowed(ctx)?;
 
-        let mut nodes_data: Vec<QueryNodeData> = vec![QueryNodeData::Start, QueryNodeData::End];
-        let root_node = 0;
-        let end_node = 1;
-
-        // Ee could consider generalizing to 4,5,6,7,etc. ngrams
-        let (mut prev2, mut prev1, mut prev0): (Vec<u16>, Vec<u16>, Vec<u16>) =
-            (vec![], vec![], vec![root_node]);
-
-        let original_terms_len = terms.len();
-        for term_idx in 0..original_terms_len {
-            let mut new_nodes = vec![];
-
-            let new_node_idx = add_node(
-                &mut nodes_data,
-                QueryNodeData::Term(LocatedQueryTermSubset {
-                    term_subset: QueryTermSubset::full(terms[term_idx].value),
-                    positions: terms[term_idx].positions.clone(),
-                    term_ids: term_idx as u8..=term_idx as u8,
-                }),
-            );
-            new_nodes.push(new_node_idx);
-
-            if !prev1.is_empty() {
-                if let Some(ngram) =
-                    query_term::make_ngram(ctx, &terms[term_idx - 1..=term_idx], &nbr_typos)?
-                {
-                    new_located_query_terms.push(ngram.clone());
-                    let ngram_idx = add_node(
-                        &mut nodes_data,
-                        QueryNodeData::Term(LocatedQueryTermSubset {
-                            term_subset: QueryTermSubset::full(ngram.value),
-                            positions: ngram.positions,
-                            term_ids: term_idx as u8 - 1..=term_idx as u8,
-                        }),
-                    );
-                    new_nodes.push(ngram_idx);
+        let (mut nodes_data, mut new_located_query_terms) =
+            nodes_data_from_terms(ctx, terms, &nbr_typos, true)?;
+
+        let mut degradation = None;
+        let mut alternatives = number_of_alternatives(ctx, &nodes_data);
+        if alternatives > limits::MAX_QUERY_GRAPH_ALTERNATIVES {
+            let dropped_ngrams = (new_located_query_terms.len() - terms.len()) as u32;
+            (nodes_data, new_located_query_terms) =
+                nodes_data_from_terms(ctx, terms, &nbr_typos, false)?;
+            alternatives = number_of_alternatives(ctx, &nodes_data);
+
+            let mut dropped_synonym_terms = 0;
+            if alternatives > limits::MAX_QUERY_GRAPH_ALTERNATIVES {
+                let mut terms_by_frequency = Vec::with_capacity(terms.len());
+                for term in terms {
+                    let frequency = match term.value.original_single_word(ctx) {
+                        Some(word) => ctx.word_docids(Word::Original(word))?.map_or(0, |d| d.len()),
+                        None => 0,
+                    };
+                    terms_by_frequency.push((frequency, term.value));
                 }
-            }
-            if !prev2.is_empty() {
-                if let Some(ngram) =
-                    query_term::make_ngram(ctx, &terms[term_idx - 2..=term_idx], &nbr_typos)?
-                {
-                    new_located_query_terms.push(ngram.clone());
-                    let ngram_idx = add_node(
-                        &mut nodes_data,
-                        QueryNodeData::Term(LocatedQueryTermSubset {
-                            term_subset: QueryTermSubset::full(ngram.value),
-                            positions: ngram.positions,
-                            term_ids: term_idx as u8 - 2..=term_idx as u8,
-                        }),
-                    );
-                    new_nodes.push(ngram_idx);
+                terms_by_frequency.sort_by_key(|(frequency, _)| *frequency);
+
+                for (_, term) in terms_by_frequency {
+                    if alternatives <= limits::MAX_QUERY_GRAPH_ALTERNATIVES {
+                        break;
+                    }
+                    let term = ctx.term_interner.get_mut(term);
+                    if term.synonyms_len() > 0 {
+                        alternatives -= term.synonyms_len();
+                        term.clear_synonyms();
+                        dropped_synonym_terms += 1;
+                    }
                 }
             }
-            (prev0, prev1, prev2) = (new_nodes, prev0, prev1);
+            degradation = Some(QueryGraphDegradation { dropped_ngrams, dropped_synonym_terms });
         }
 
-        let root_node = Interned::from_raw(root_node);
-        let end_node = Interned::from_raw(end_node);
+        let root_node = Interned::from_raw(ROOT_NODE);
+        let end_node = Interned::from_raw(END_NODE);
         let mut nodes = FixedSizeInterner::new(
             nodes_data.len() as u16,
             QueryNode {
@@ -170,12 +159,17 @@ impl QueryGraph {
             let node = nodes.get_mut(Interned::from_raw(node_idx as u16));
             node.data = node_data;
         }
-        let mut graph = QueryGraph { root_node, end_node, nodes };
+        let mut graph = QueryGraph { root_node, end_node, nodes, degradation };
         graph.build_initial_edges();
 
         Ok((graph, new_located_query_terms))
     }
 
+    /// The number of alternatives of the graph: its term nodes and the synonyms of their terms.
+    pub fn number_of_alternatives(&self, ctx: &SearchContext) -> usize {
+        number_of_alternatives(ctx, self.nodes.iter().map(|(_, node)| &node.data))
+    }
+
     /// Remove the given nodes, connecting all their predecessors to all their successors.
     pub fn remove_nodes_keep_edges(&mut self, nodes: &[Interned<QueryNode>]) {
         for &node_id in nodes {
@@ -241,6 +235,90 @@ impl QueryGraph {
         }
     }
 
+    /// Remove the term nodes matching no documents, so that the ranking rules don't
+    /// resolve the paths going through them.
+    ///
+    /// A node is only removed when it is an alternative to other nodes, e.g. an ngram
+    /// or a split word: removing a node that all the paths go through would disconnect
+    /// the end node, and connecting its neighbours instead would match more documents.
+    /// The documents matching the graph are thus the same before and after the pruning.
+    pub fn prune_empty_terms(&mut self, ctx: &mut SearchContext) -> Result<()> {
+        let mut empty_nodes = vec![];
+        for (node_id, node) in self.nodes.iter() {
+            if let QueryNodeData::Term(LocatedQueryTermSubset { term_subset, .. }) = &node.data {
+                if ctx.query_term_subset_is_empty(term_subset)? {
+                    empty_nodes.push(node_id);
+                }
+            }
+        }
+
+        for node_id in empty_nodes {
+            // the node may have been removed with the nodes it was only connected to
+            if matches!(self.nodes.get(node_id).data, QueryNodeData::Deleted) {
+                continue;
+            }
+            let mut pruned = self.clone();
+            pruned.remove_nodes(&[node_id]);
+            pruned.simplify();
+            if !pruned.nodes.get(pruned.end_node).predecessors.is_empty() {
+                *self = pruned;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the graph in the graphviz `dot` format, without its deleted nodes.
+    pub fn graphviz(&self, ctx: &SearchContext) -> String {
+        let mut dot = String::from("digraph {\n");
+        for (node_id, node) in self.nodes.iter() {
+            let label = match &node.data {
+                QueryNodeData::Term(LocatedQueryTermSubset { term_subset, .. }) => {
+                    term_subset.description(ctx).replace('"', "\\\"")
+                }
+                QueryNodeData::Deleted => continue,
+                QueryNodeData::Start => "START".to_owned(),
+                QueryNodeData::End => "END".to_owned(),
+            };
+            writeln!(dot, "  {node_id} [label=\"{label}\"];").unwrap();
+            for successor in node.successors.iter() {
+                writeln!(dot, "  {node_id} -> {successor};").unwrap();
+            }
+        }
+        dot.push('}');
+        dot
+    }
+
+    /// Returns the nodes and the edges of the graph, without its deleted nodes,
+    /// along with its graphviz representation.
+    pub fn description(&self, ctx: &mut SearchContext) -> Result<QueryGraphDescription> {
+        let mut nodes = vec![];
+        let mut edges = vec![];
+        for (node_id, node) in self.nodes.iter() {
+            let kind = match &node.data {
+                QueryNodeData::Term(LocatedQueryTermSubset { term_subset, positions, .. }) => {
+                    QueryNodeKind::Term {
+                        term: term_subset.description(ctx),
+                        positions: [*positions.start(), *positions.end()],
+                        derivations: QueryTermDerivations {
+                            words: term_subset.all_single_words_except_prefix_db(ctx)?.len(),
+                            phrases: term_subset.all_phrases(ctx)?.len(),
+                            prefix_db: term_subset.use_prefix_db(ctx).is_some(),
+                        },
+                    }
+                }
+                QueryNodeData::Deleted => continue,
+                QueryNodeData::Start => QueryNodeKind::Start,
+                QueryNodeData::End => QueryNodeKind::End,
+            };
+            nodes.push(QueryNodeDescription { id: node_id.into_raw(), kind });
+            edges.extend(node.successors.iter().map(|successor| QueryEdgeDescription {
+                from: node_id.into_raw(),
+                to: successor.into_raw(),
+            }));
+        }
+        Ok(QueryGraphDescription { graphviz: self.graphviz(ctx), nodes, edges })
+    }
+
     fn build_initial_edges(&mut self) {
         for (_, node) in self.nodes.iter_mut() {
             node.successors.clear();
@@ -290,6 +368,25 @@ impl QueryGraph {
         }
     }
 
+    /// The groups of nodes removed one after the other by the terms matching strategy,
+    /// nothing is removed when all the terms are mandatory.
+    pub fn removal_order_for_terms_matching_strategy(
+        &self,
+        ctx: &mut SearchContext,
+        terms_matching_strategy: TermsMatchingStrategy,
+    ) -> Result<Vec<SmallBitmap<QueryNode>>> {
+        match terms_matching_strategy {
+            TermsMatchingStrategy::Last => {
+                Ok(self.removal_order_for_terms_matching_strategy_last(ctx))
+            }
+            TermsMatchingStrategy::Frequency => {
+                self.removal_order_for_terms_matching_strategy_frequency(ctx)
+            }
+            TermsMatchingStrategy::All => Ok(vec![]),
+        }
+    }
+
+    /// Removes the terms from the last one to the first one.
     pub fn removal_order_for_terms_matching_strategy_last(
         &self,
         ctx: &SearchContext,
@@ -319,6 +416,47 @@ impl QueryGraph {
             let rank = 1 + last_term_idx - term_idx;
             rank as u16
         };
+        self.removal_order_by_cost(ctx, cost_of_term_idx)
+    }
+
+    /// Removes the terms matching the fewest documents first, the ones matching no document
+    /// at all being removed before any other.
+    fn removal_order_for_terms_matching_strategy_frequency(
+        &self,
+        ctx: &mut SearchContext,
+    ) -> Result<Vec<SmallBitmap<QueryNode>>> {
+        // the documents of a term are the ones of all the nodes it is part of, ngrams included
+        let mut term_docids = BTreeMap::<u8, RoaringBitmap>::new();
+        for (_, node) in self.nodes.iter() {
+            let QueryNodeData::Term(t) = &node.data else { continue };
+            let docids = compute_query_term_subset_docids(ctx, &t.term_subset)?;
+            for term_idx in t.term_ids.clone() {
+                *term_docids.entry(term_idx).or_default() |= &docids;
+            }
+        }
+        if term_docids.len() < 2 {
+            return Ok(vec![]);
+        }
+
+        let mut frequencies: Vec<_> =
+            term_docids.into_iter().map(|(term_idx, docids)| (docids.len(), term_idx)).collect();
+        // like with the `last` strategy, the last of the terms as frequent as each other goes first
+        frequencies.sort_unstable_by_key(|&(frequency, term_idx)| (frequency, Reverse(term_idx)));
+        let ranks: BTreeMap<u8, u16> = frequencies
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (_, term_idx))| (term_idx, rank as u16 + 1))
+            .collect();
+        Ok(self.removal_order_by_cost(ctx, |term_idx| ranks[&term_idx]))
+    }
+
+    /// Groups the removable nodes by the cost of their terms, the nodes with the lowest cost being
+    /// removed first. A node spanning several terms costs as much as the most costly of them.
+    fn removal_order_by_cost(
+        &self,
+        ctx: &SearchContext,
+        cost_of_term_idx: impl Fn(u8) -> u16,
+    ) -> Vec<SmallBitmap<QueryNode>> {
         let mut nodes_to_remove = BTreeMap::<u16, SmallBitmap<QueryNode>>::new();
         let mut at_least_one_mandatory_term = false;
         for (node_id, node) in self.nodes.iter() {
@@ -360,12 +498,150 @@ impl QueryGraph {
     }
 }
 
+/// The nodes and the edges of a [`QueryGraph`], returned with the search results to debug it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryGraphDescription {
+    /// The graph in the graphviz `dot` format.
+    pub graphviz: String,
+    pub nodes: Vec<QueryNodeDescription>,
+    pub edges: Vec<QueryEdgeDescription>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryNodeDescription {
+    pub id: u16,
+    #[serde(flatten)]
+    pub kind: QueryNodeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum QueryNodeKind {
+    Start,
+    End,
+    Term {
+        /// The original word or ngram of the query.
+        term: String,
+        /// The first and last positions of the term in the query.
+        positions: [u16; 2],
+        derivations: QueryTermDerivations,
+    },
+}
+
+/// The number of derivations searched for a term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryTermDerivations {
+    /// The single words: the original word, its typos and the words it is a prefix of.
+    pub words: usize,
+    /// The phrases: the original phrase, the split words and the synonyms.
+    pub phrases: usize,
+    /// Whether the prefix database is used instead of the words starting with the term.
+    pub prefix_db: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct QueryEdgeDescription {
+    pub from: u16,
+    pub to: u16,
+}
+
 fn add_node(nodes_data: &mut Vec<QueryNodeData>, node_data: QueryNodeData) -> u16 {
     let new_node_idx = nodes_data.len() as u16;
     nodes_data.push(node_data);
     new_node_idx
 }
 
+const ROOT_NODE: u16 = 0;
+const END_NODE: u16 = 1;
+
+/// Returns the data of the nodes of the query graph of the given terms, with or without their ngrams,
+/// and the located query terms updated with the ngrams.
+fn nodes_data_from_terms(
+    ctx: &mut SearchContext,
+    terms: &[LocatedQueryTerm],
+    nbr_typos: &impl Fn(&str) -> u8,
+    with_ngrams: bool,
+) -> Result<(Vec<QueryNodeData>, Vec<LocatedQueryTerm>)> {
+    let mut new_located_query_terms = terms.to_vec();
+
+    let mut nodes_data: Vec<QueryNodeData> = vec![QueryNodeData::Start, QueryNodeData::End];
+
+    // Ee could consider generalizing to 4,5,6,7,etc. ngrams
+    let (mut prev2, mut prev1, mut prev0): (Vec<u16>, Vec<u16>, Vec<u16>) =
+        (vec![], vec![], vec![ROOT_NODE]);
+
+    let original_terms_len = terms.len();
+    for term_idx in 0..original_terms_len {
+        let mut new_nodes = vec![];
+
+        let new_node_idx = add_node(
+            &mut nodes_data,
+            QueryNodeData::Term(LocatedQueryTermSubset {
+                term_subset: QueryTermSubset::full(terms[term_idx].value),
+                positions: terms[term_idx].positions.clone(),
+                term_ids: term_idx as u8..=term_idx as u8,
+            }),
+        );
+        new_nodes.push(new_node_idx);
+
+        if with_ngrams && !prev1.is_empty() {
+            if let Some(ngram) =
+                query_term::make_ngram(ctx, &terms[term_idx - 1..=term_idx], nbr_typos)?
+            {
+                new_located_query_terms.push(ngram.clone());
+                let ngram_idx = add_node(
+                    &mut nodes_data,
+                    QueryNodeData::Term(LocatedQueryTermSubset {
+                        term_subset: QueryTermSubset::full(ngram.value),
+                        positions: ngram.positions,
+                        term_ids: term_idx as u8 - 1..=term_idx as u8,
+                    }),
+                );
+                new_nodes.push(ngram_idx);
+            }
+        }
+        if with_ngrams && !prev2.is_empty() {
+            if let Some(ngram) =
+                query_term::make_ngram(ctx, &terms[term_idx - 2..=term_idx], nbr_typos)?
+            {
+                new_located_query_terms.push(ngram.clone());
+                let ngram_idx = add_node(
+                    &mut nodes_data,
+                    QueryNodeData::Term(LocatedQueryTermSubset {
+                        term_subset: QueryTermSubset::full(ngram.value),
+                        positions: ngram.positions,
+                        term_ids: term_idx as u8 - 2..=term_idx as u8,
+                    }),
+                );
+                new_nodes.push(ngram_idx);
+            }
+        }
+        (prev0, prev1, prev2) = (new_nodes, prev0, prev1);
+    }
+
+    Ok((nodes_data, new_located_query_terms))
+}
+
+/// The number of alternatives of the given nodes: the term nodes and the synonyms of their terms.
+///
+/// The synonyms are not nodes of the graph but derivations of the terms, they are counted
+/// because each of them is resolved like a phrase by the ranking rules.
+fn number_of_alternatives<'a>(
+    ctx: &SearchContext,
+    nodes_data: impl IntoIterator<Item = &'a QueryNodeData>,
+) -> usize {
+    nodes_data
+        .into_iter()
+        .map(|node| match node {
+            QueryNodeData::Term(term) => 1 + term.term_subset.synonyms_len(ctx),
+            QueryNodeData::Deleted | QueryNodeData::Start | QueryNodeData::End => 0,
+        })
+        .sum()
+}
+
 impl QueryGraph {
     /*
     Build a query graph from a list of paths
@@ -477,6 +753,6 @@ impl QueryGraph {
             node.predecessors.insert(prev_node_id);
         }
 
-        QueryGraph { root_node, end_node, nodes }
+        QueryGraph { root_node, end_node, nodes, degradation: None }
     }
 }
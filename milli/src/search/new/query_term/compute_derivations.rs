@@ -12,7 +12,7 @@ use crate::search::new::interner::{DedupInterner, Interned};
 use crate::search::new::query_term::{Lazy, TwoTypoTerm};
 use crate::search::new::{limits, SearchContext};
 use crate::search::{build_dfa, get_first};
-use crate::{Result, MAX_WORD_LENGTH};
+use crate::{stemming, Result, MAX_WORD_LENGTH};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum NumberOfTypos {
@@ -28,7 +28,12 @@ pub enum ZeroOrOneTypo {
 
 impl Interned<QueryTerm> {
     pub fn compute_fully_if_needed(self, ctx: &mut SearchContext) -> Result<()> {
+        let derivations_exhausted = ctx.derivation_count >= limits::MAX_QUERY_DERIVATIONS;
         let s = ctx.term_interner.get_mut(self);
+        // past the derivations of the search, the next terms are searched without typos
+        if derivations_exhausted && s.one_typo.is_uninit() {
+            s.max_levenshtein_distance = 0;
+        }
         if s.max_levenshtein_distance <= 1 && s.one_typo.is_uninit() {
             assert!(s.two_typo.is_uninit());
             // Initialize one_typo subterm even if max_nbr_typo is 0 because of split words
@@ -196,6 +201,15 @@ pub fn partially_initialized_term_from_word(
 
     let fst = ctx.index.words_fst(ctx.txn)?;
 
+    // Past the derivations of the search, the next terms are neither prefixes nor typo tolerant.
+    let (max_typo, is_prefix) = if ctx.derivation_count >= limits::MAX_QUERY_DERIVATIONS {
+        (0, false)
+    } else {
+        (max_typo, is_prefix)
+    };
+    // A short number is most likely complete, and its prefix derivations,
+    // like all the years or zip codes of the index, are as slow as irrelevant.
+    let is_prefix = is_prefix && !is_short_number(ctx, word)?;
     let use_prefix_db = is_prefix
         && (ctx
             .index
@@ -233,13 +247,11 @@ pub fn partially_initialized_term_from_word(
                 }
             },
         )?;
+        ctx.derivation_count += prefix_of.len();
     }
-    let synonyms = ctx.index.synonyms(ctx.txn)?;
+    let synonyms = ctx.word_synonyms(&[word.to_owned()])?;
     let mut synonym_word_count = 0;
     let synonyms = synonyms
-        .get(&vec![word.to_owned()])
-        .cloned()
-        .unwrap_or_default()
         .into_iter()
         .take(limits::MAX_SYNONYM_PHRASE_COUNT)
         .filter_map(|words| {
@@ -248,11 +260,25 @@ pub fn partially_initialized_term_from_word(
             }
             synonym_word_count += words.len();
             let words = words.into_iter().map(|w| Some(ctx.word_interner.insert(w))).collect();
-            Some(ctx.phrase_interner.insert(Phrase { words }))
+            Some(ctx.phrase_interner.insert(Phrase {
+                words,
+                stop_words: Vec::new(),
+                derived: true,
+                prefix: false,
+            }))
         })
         .collect();
+    // the words of the stemmed attributes are indexed along with their stem at the same position
+    let mut stems = BTreeSet::new();
+    for locale in ctx.stemming_locales()? {
+        if let Some(stem) = stemming::stem(word, locale) {
+            if ctx.get_words_fst()?.contains(&stem) {
+                stems.insert(ctx.word_interner.insert(stem));
+            }
+        }
+    }
     let zero_typo =
-        ZeroTypoTerm { phrase: None, exact: zero_typo, prefix_of, synonyms, use_prefix_db };
+        ZeroTypoTerm { phrase: None, exact: zero_typo, prefix_of, synonyms, use_prefix_db, stems };
 
     Ok(QueryTerm {
         original: word_interned,
@@ -265,12 +291,30 @@ pub fn partially_initialized_term_from_word(
     })
 }
 
+/// Returns `true` if the word is only made of digits and is shorter than
+/// the `min_numeric_prefix_length` setting, it must then not be used as a prefix.
+pub fn is_short_number(ctx: &SearchContext, word: &str) -> Result<bool> {
+    if word.is_empty() || !word.bytes().all(|b| b.is_ascii_digit()) {
+        return Ok(false);
+    }
+    let min_numeric_prefix_length = ctx.index.min_numeric_prefix_length(ctx.txn)?;
+    Ok(word.len() < min_numeric_prefix_length as usize)
+}
+
 fn find_split_words(ctx: &mut SearchContext, word: &str) -> Result<Option<Interned<Phrase>>> {
-    if let Some((l, r)) = split_best_frequency(ctx, word)? {
-        Ok(Some(ctx.phrase_interner.insert(Phrase { words: vec![Some(l), Some(r)] })))
+    let words = if let Some((l, r)) = split_best_frequency(ctx, word)? {
+        vec![Some(l), Some(r)]
+    } else if let Some(parts) = decompound(ctx, word)? {
+        parts.into_iter().map(Some).collect()
     } else {
-        Ok(None)
-    }
+        return Ok(None);
+    };
+    Ok(Some(ctx.phrase_interner.insert(Phrase {
+        words,
+        stop_words: Vec::new(),
+        derived: true,
+        prefix: false,
+    })))
 }
 
 impl Interned<QueryTerm> {
@@ -317,6 +361,7 @@ impl Interned<QueryTerm> {
             None
         };
 
+        ctx.derivation_count += one_typo_words.len();
         let self_mut = ctx.term_interner.get_mut(self);
 
         // Only add the split words to the derivations if:
@@ -325,7 +370,7 @@ impl Interned<QueryTerm> {
         let split_words = if let Some((ngram_words, split_words)) =
             self_mut.ngram_words.as_ref().zip(split_words.as_ref())
         {
-            let Phrase { words } = ctx.phrase_interner.get(*split_words);
+            let Phrase { words, .. } = ctx.phrase_interner.get(*split_words);
             if ngram_words.iter().ne(words.iter().flatten()) {
                 Some(*split_words)
             } else {
@@ -387,6 +432,7 @@ impl Interned<QueryTerm> {
             )?;
         }
 
+        ctx.derivation_count += one_typo_words.len() + two_typo_words.len();
         let split_words = find_split_words(ctx, original_str.as_str())?;
         let self_mut = ctx.term_interner.get_mut(self);
 
@@ -401,6 +447,49 @@ impl Interned<QueryTerm> {
     }
 }
 
+/// Split a compound word of the query into the fewest words of the index, using the words FST
+/// as a dictionary, when the search is made on attributes in a decompounding locale.
+///
+/// Among the splits in the fewest words, the one with the longest first words is kept.
+/// Return `None` if the word is too short or cannot be split.
+fn decompound(ctx: &mut SearchContext, word: &str) -> Result<Option<Vec<Interned<String>>>> {
+    let bounds: Vec<_> = word.char_indices().map(|(i, _)| i).chain([word.len()]).collect();
+    let chars = bounds.len() - 1;
+    if chars < limits::MIN_DECOMPOUNDED_WORD_LEN || !ctx.decompounds()? {
+        return Ok(None);
+    }
+    let words_fst = ctx.get_words_fst()?;
+
+    // the fewest words the end of the word, starting at this character, can be split into,
+    // along with the end of the first of them
+    let mut splits: Vec<Option<(usize, usize)>> = vec![None; chars + 1];
+    splits[chars] = Some((0, chars));
+    for start in (0..chars).rev() {
+        for end in (start + limits::MIN_DECOMPOUNDED_PART_LEN..=chars).rev() {
+            // the word as a whole is not a split
+            if start == 0 && end == chars {
+                continue;
+            }
+            let Some((count, _)) = splits[end] else { continue };
+            let shorter = splits[start].map_or(true, |(best, _)| count + 1 < best);
+            if count < limits::MAX_DECOMPOUNDED_PARTS
+                && shorter
+                && words_fst.contains(&word[bounds[start]..bounds[end]])
+            {
+                splits[start] = Some((count + 1, end));
+            }
+        }
+    }
+
+    let mut parts = Vec::new();
+    let mut start = 0;
+    while let Some((_, end)) = splits[start].filter(|_| start < chars) {
+        parts.push(ctx.word_interner.insert(word[bounds[start]..bounds[end]].to_owned()));
+        start = end;
+    }
+    Ok((start == chars && parts.len() > 1).then_some(parts))
+}
+
 /// Split the original word into the two words that appear the
 /// most next to each other in the index.
 ///
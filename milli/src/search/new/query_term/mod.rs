@@ -3,7 +3,7 @@ mod ntypo_subset;
 mod parse_query;
 mod phrase;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::iter::FromIterator;
 use std::ops::RangeInclusive;
 
@@ -11,6 +11,7 @@ use either::Either;
 pub use ntypo_subset::NTypoTermSubset;
 pub use parse_query::{
     located_query_terms_from_tokens, make_ngram, number_of_typos_allowed, ExtractedTokens,
+    QueryToken, QueryTokenKind,
 };
 pub use phrase::Phrase;
 
@@ -66,6 +67,8 @@ struct ZeroTypoTerm {
     synonyms: BTreeSet<Interned<Phrase>>,
     /// A prefix in the prefix databases matching the original word
     use_prefix_db: Option<Interned<String>>,
+    /// The stems of the original word in the stemming locales of the searched attributes
+    stems: BTreeSet<Interned<String>>,
 }
 #[derive(Default, Clone, PartialEq, Eq, Hash)]
 struct OneTypoTerm {
@@ -214,6 +217,7 @@ impl QueryTermSubset {
                     prefix_of,
                     synonyms: _,
                     use_prefix_db: _,
+                    stems,
                 } = &original.zero_typo;
                 result.extend(zero_typo.iter().copied().map(|w| {
                     if original.ngram_words.is_some() {
@@ -226,9 +230,11 @@ impl QueryTermSubset {
                     if original.ngram_words.is_some() {
                         Word::Derived(w)
                     } else {
-                        Word::Original(w)
+                        Word::ZeroTypoDerived(w)
                     }
                 }));
+                // the exact attributes are not stemmed
+                result.extend(stems.iter().copied().map(Word::Derived));
             }
             NTypoTermSubset::Subset { words, phrases: _ } => {
                 let ZeroTypoTerm {
@@ -237,6 +243,7 @@ impl QueryTermSubset {
                     prefix_of,
                     synonyms: _,
                     use_prefix_db: _,
+                    stems,
                 } = &original.zero_typo;
                 if let Some(zero_typo) = zero_typo {
                     if words.contains(zero_typo) {
@@ -251,9 +258,10 @@ impl QueryTermSubset {
                     if original.ngram_words.is_some() {
                         Word::Derived(w)
                     } else {
-                        Word::Original(w)
+                        Word::ZeroTypoDerived(w)
                     }
                 }));
+                result.extend(stems.intersection(words).copied().map(Word::Derived));
             }
             NTypoTermSubset::Nothing => {}
         }
@@ -298,7 +306,7 @@ impl QueryTermSubset {
         }
         let original = ctx.term_interner.get_mut(self.original);
 
-        let ZeroTypoTerm { phrase, exact: _, prefix_of: _, synonyms, use_prefix_db: _ } =
+        let ZeroTypoTerm { phrase, exact: _, prefix_of: _, synonyms, use_prefix_db: _, stems: _ } =
             &original.zero_typo;
         result.extend(phrase.iter().copied());
         result.extend(synonyms.iter().copied());
@@ -328,6 +336,11 @@ impl QueryTermSubset {
         Ok(result)
     }
 
+    /// The number of synonyms of the original term.
+    pub fn synonyms_len(&self, ctx: &SearchContext) -> usize {
+        ctx.term_interner.get(self.original).synonyms_len()
+    }
+
     pub fn original_phrase(&self, ctx: &SearchContext) -> Option<Interned<Phrase>> {
         let t = ctx.term_interner.get(self.original);
         if let Some(p) = t.zero_typo.phrase {
@@ -407,12 +420,14 @@ impl QueryTermSubset {
 
 impl ZeroTypoTerm {
     fn is_empty(&self) -> bool {
-        let ZeroTypoTerm { phrase, exact: zero_typo, prefix_of, synonyms, use_prefix_db } = self;
+        let ZeroTypoTerm { phrase, exact: zero_typo, prefix_of, synonyms, use_prefix_db, stems } =
+            self;
         phrase.is_none()
             && zero_typo.is_none()
             && prefix_of.is_empty()
             && synonyms.is_empty()
             && use_prefix_db.is_none()
+            && stems.is_empty()
     }
 }
 impl OneTypoTerm {
@@ -446,7 +461,7 @@ impl QueryTerm {
 
 impl Interned<QueryTerm> {
     /// Return the original word from the given query term
-    fn original_single_word(self, ctx: &SearchContext) -> Option<Interned<String>> {
+    pub fn original_single_word(self, ctx: &SearchContext) -> Option<Interned<String>> {
         let self_ = ctx.term_interner.get(self);
         if self_.ngram_words.is_some() {
             None
@@ -477,6 +492,17 @@ impl QueryTerm {
     pub fn is_prefix(&self) -> bool {
         self.is_prefix
     }
+    /// The prefix whose completions are searched through the prefix databases, if any.
+    pub fn prefix_db(&self) -> Option<Interned<String>> {
+        self.zero_typo.use_prefix_db
+    }
+    pub fn synonyms_len(&self) -> usize {
+        self.zero_typo.synonyms.len()
+    }
+    /// Forgets the synonyms of the term, they are not searched anymore.
+    pub fn clear_synonyms(&mut self) {
+        self.zero_typo.synonyms.clear();
+    }
     pub fn original_word(&self, ctx: &SearchContext) -> String {
         ctx.word_interner.get(self.original).clone()
     }
@@ -485,24 +511,31 @@ impl QueryTerm {
         self.zero_typo.phrase
     }
 
-    pub fn all_computed_derivations(&self) -> (Vec<Interned<String>>, Vec<Interned<Phrase>>) {
-        let mut words = BTreeSet::new();
+    /// Returns the words and phrases derived from the term so far,
+    /// each word being associated with the smallest number of typos that derives it.
+    pub fn all_computed_derivations(&self) -> (Vec<(Interned<String>, u8)>, Vec<Interned<Phrase>>) {
+        let mut words = BTreeMap::new();
         let mut phrases = BTreeSet::new();
 
-        let ZeroTypoTerm { phrase, exact: zero_typo, prefix_of, synonyms, use_prefix_db: _ } =
+        let ZeroTypoTerm { phrase, exact: zero_typo, prefix_of, synonyms, use_prefix_db: _, stems } =
             &self.zero_typo;
-        words.extend(zero_typo.iter().copied());
-        words.extend(prefix_of.iter().copied());
+        words.extend(zero_typo.iter().map(|word| (*word, 0)));
+        words.extend(prefix_of.iter().map(|word| (*word, 0)));
+        words.extend(stems.iter().map(|word| (*word, 0)));
         phrases.extend(phrase.iter().copied());
         phrases.extend(synonyms.iter().copied());
 
         if let Lazy::Init(OneTypoTerm { split_words, one_typo }) = &self.one_typo {
-            words.extend(one_typo.iter().copied());
+            for word in one_typo {
+                words.entry(*word).or_insert(1);
+            }
             phrases.extend(split_words.iter().copied());
         };
 
         if let Lazy::Init(TwoTypoTerm { two_typos }) = &self.two_typo {
-            words.extend(two_typos.iter().copied());
+            for word in two_typos {
+                words.entry(*word).or_insert(2);
+            }
         };
 
         (words.into_iter().collect(), phrases.into_iter().collect())
@@ -1,9 +1,9 @@
 use std::collections::BTreeSet;
 
-use charabia::normalizer::NormalizedTokenIter;
-use charabia::{SeparatorKind, TokenKind};
+use charabia::{SeparatorKind, Token, TokenKind};
+use serde::Serialize;
 
-use super::compute_derivations::partially_initialized_term_from_word;
+use super::compute_derivations::{is_short_number, partially_initialized_term_from_word};
 use super::{LocatedQueryTerm, ZeroTypoTerm};
 use crate::search::new::query_term::{Lazy, Phrase, QueryTerm};
 use crate::search::new::Word;
@@ -18,13 +18,57 @@ pub struct ExtractedTokens {
     pub negative_words: Vec<Word>,
     /// The phrases that must not appear in the results.
     pub negative_phrases: Vec<LocatedQueryTerm>,
+    /// Whether the last word is a number too short to be used as a prefix.
+    pub numeric_prefix_disabled: bool,
+    /// The tokens of the query, located in the original query.
+    pub query_tokens: Vec<QueryToken>,
+    /// Whether words of the query were ignored because it has too many terms.
+    pub query_truncated: bool,
+}
+
+/// A token of the search query, located by its byte offsets in the original query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueryToken {
+    /// The normalized token.
+    pub token: String,
+    pub start: usize,
+    pub end: usize,
+    pub kind: QueryTokenKind,
+}
+
+impl QueryToken {
+    fn new(token: &Token, kind: QueryTokenKind) -> Self {
+        QueryToken {
+            token: token.lemma().to_string(),
+            start: token.byte_start,
+            end: token.byte_end,
+            kind,
+        }
+    }
+}
+
+/// How a token of the search query was used by the search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QueryTokenKind {
+    /// A word searched on its own.
+    Word,
+    /// A word searched as part of a phrase.
+    Phrase,
+    /// A separator, not searched.
+    SeparatorSkipped,
+    /// A stop word, not searched.
+    StopWordSkipped,
+    /// A stop word of a phrase made only of stop words, not searched as the stop words are not
+    /// indexed and the phrase can't be looked up.
+    PhraseStopWordSkipped,
 }
 
 /// Convert the tokenised search query into a list of located query terms.
 #[tracing::instrument(level = "trace", skip_all, target = "search::query")]
 pub fn located_query_terms_from_tokens(
     ctx: &mut SearchContext,
-    query: NormalizedTokenIter,
+    query: impl Iterator<Item = Token<'_>>,
     words_limit: Option<usize>,
 ) -> Result<ExtractedTokens> {
     let nbr_typos = number_of_typos_allowed(ctx)?;
@@ -34,11 +78,14 @@ pub fn located_query_terms_from_tokens(
     let mut negative_phrase = false;
     let mut phrase: Option<PhraseBuilder> = None;
     let mut encountered_whitespace = true;
+    let mut ends_with_word = false;
     let mut negative_next_token = false;
     let mut negative_words = Vec::new();
     let mut negative_phrases = Vec::new();
+    let mut numeric_prefix_disabled = false;
+    let mut query_tokens = Vec::new();
 
-    let parts_limit = words_limit.unwrap_or(usize::MAX);
+    let parts_limit = words_limit.unwrap_or(usize::MAX).min(ctx.max_query_terms);
 
     // start with the last position as we will wrap around to position 0 at the beginning of the loop below.
     let mut position = u16::MAX;
@@ -51,7 +98,17 @@ pub fn located_query_terms_from_tokens(
 
         // early return if word limit is exceeded
         if query_terms.len() >= parts_limit {
-            return Ok(ExtractedTokens { query_terms, negative_words, negative_phrases });
+            let query_truncated = std::iter::once(token)
+                .chain(peekable)
+                .any(|token| matches!(token.kind, TokenKind::Word | TokenKind::StopWord));
+            return Ok(ExtractedTokens {
+                query_terms,
+                negative_words,
+                negative_phrases,
+                numeric_prefix_disabled,
+                query_tokens,
+                query_truncated,
+            });
         }
 
         match token.kind {
@@ -63,8 +120,10 @@ pub fn located_query_terms_from_tokens(
                 // 2. if the word is not the last token of the query and is not a stop_word we push it as a non-prefix word,
                 // 3. if the word is the last token of the query we push it as a prefix word.
                 if let Some(phrase) = &mut phrase {
-                    phrase.push_word(ctx, &token, position)
+                    query_tokens.push(QueryToken::new(&token, QueryTokenKind::Phrase));
+                    phrase.push_word(ctx, &token, position, query_tokens.len() - 1)
                 } else if negative_next_token {
+                    query_tokens.push(QueryToken::new(&token, QueryTokenKind::Word));
                     let word = token.lemma().to_string();
                     let word = Word::Original(ctx.word_interner.insert(word));
                     negative_words.push(word);
@@ -72,6 +131,7 @@ pub fn located_query_terms_from_tokens(
                 } else if peekable.peek().is_some() {
                     match token.kind {
                         TokenKind::Word => {
+                            query_tokens.push(QueryToken::new(&token, QueryTokenKind::Word));
                             let word = token.lemma();
                             let term = partially_initialized_term_from_word(
                                 ctx,
@@ -86,10 +146,32 @@ pub fn located_query_terms_from_tokens(
                             };
                             query_terms.push(located_term);
                         }
-                        TokenKind::StopWord | TokenKind::Separator(_) | TokenKind::Unknown => (),
+                        // a stop word is only searched when it has synonyms, without typos
+                        TokenKind::StopWord
+                            if !ctx.word_synonyms(&[token.lemma().to_owned()])?.is_empty() =>
+                        {
+                            query_tokens.push(QueryToken::new(&token, QueryTokenKind::Word));
+                            let term = partially_initialized_term_from_word(
+                                ctx,
+                                token.lemma(),
+                                0,
+                                false,
+                                false,
+                            )?;
+                            let located_term = LocatedQueryTerm {
+                                value: ctx.term_interner.push(term),
+                                positions: position..=position,
+                            };
+                            query_terms.push(located_term);
+                        }
+                        TokenKind::StopWord => query_tokens
+                            .push(QueryToken::new(&token, QueryTokenKind::StopWordSkipped)),
+                        TokenKind::Separator(_) | TokenKind::Unknown => (),
                     }
                 } else {
+                    query_tokens.push(QueryToken::new(&token, QueryTokenKind::Word));
                     let word = token.lemma();
+                    numeric_prefix_disabled = is_short_number(ctx, word)?;
                     let term = partially_initialized_term_from_word(
                         ctx,
                         word,
@@ -105,6 +187,8 @@ pub fn located_query_terms_from_tokens(
                 }
             }
             TokenKind::Separator(separator_kind) => {
+                query_tokens.push(QueryToken::new(&token, QueryTokenKind::SeparatorSkipped));
+
                 // add penalty for hard separators
                 if let SeparatorKind::Hard = separator_kind {
                     position = position.wrapping_add(7);
@@ -116,7 +200,7 @@ pub fn located_query_terms_from_tokens(
                     // If we have a hard separator inside a phrase, we immediately start a new phrase
                     let phrase = if separator_kind == SeparatorKind::Hard {
                         if let Some(phrase) = phrase {
-                            if let Some(located_query_term) = phrase.build(ctx) {
+                            if let Some(located_query_term) = phrase.build(ctx, &mut query_tokens) {
                                 // as we are evaluating a negative operator we put the phrase
                                 // in the negative one *but* we don't reset the negative operator
                                 // as we are immediately starting a new negative phrase.
@@ -144,7 +228,7 @@ pub fn located_query_terms_from_tokens(
                     if let Some(phrase) = phrase {
                         // Per the check above, quote_count > 0
                         quote_count -= 1;
-                        if let Some(located_query_term) = phrase.build(ctx) {
+                        if let Some(located_query_term) = phrase.build(ctx, &mut query_tokens) {
                             // we were evaluating a negative operator so we
                             // put the phrase in the negative phrases
                             if negative_phrase {
@@ -173,11 +257,15 @@ pub fn located_query_terms_from_tokens(
 
         encountered_whitespace =
             token.lemma().chars().last().filter(|c| c.is_whitespace()).is_some();
+        ends_with_word = matches!(token.kind, TokenKind::Word | TokenKind::StopWord);
     }
 
     // If a quote is never closed, we consider all of the end of the query as a phrase.
-    if let Some(phrase) = phrase.take() {
-        if let Some(located_query_term) = phrase.build(ctx) {
+    if let Some(mut phrase) = phrase.take() {
+        // Like the last word of the query, the last word of the phrase is a prefix
+        // when nothing follows it, as it is likely still being typed.
+        phrase.prefix = ends_with_word && !phrase.ends_with_short_number(ctx)?;
+        if let Some(located_query_term) = phrase.build(ctx, &mut query_tokens) {
             // put the phrase in the negative set if we are evaluating a negative operator.
             if negative_phrase {
                 negative_phrases.push(located_query_term);
@@ -187,25 +275,47 @@ pub fn located_query_terms_from_tokens(
         }
     }
 
-    Ok(ExtractedTokens { query_terms, negative_words, negative_phrases })
+    Ok(ExtractedTokens {
+        query_terms,
+        negative_words,
+        negative_phrases,
+        numeric_prefix_disabled,
+        query_tokens,
+        query_truncated: false,
+    })
 }
 
+/// Returns the number of typos allowed on a word according to the typo tolerance settings.
+///
+/// The minimum sizes of the words are counted in characters. No typos are allowed when all
+/// the attributes searched are exact, as the typo derivations would never be looked up.
 pub fn number_of_typos_allowed<'ctx>(
     ctx: &SearchContext<'ctx>,
 ) -> Result<impl Fn(&str) -> u8 + 'ctx> {
-    let authorize_typos = ctx.index.authorize_typos(ctx.txn)?;
-    let min_len_one_typo = ctx.index.min_word_len_one_typo(ctx.txn)?;
-    let min_len_two_typos = ctx.index.min_word_len_two_typos(ctx.txn)?;
+    let overlay = &ctx.settings_overlay;
+    let authorize_typos = match overlay.authorize_typos {
+        Some(authorize_typos) => authorize_typos,
+        None => ctx.index.authorize_typos(ctx.txn)?,
+    } && !only_exact_attributes(ctx)?;
+    let min_len_one_typo = match overlay.min_word_len_one_typo {
+        Some(min_len_one_typo) => min_len_one_typo,
+        None => ctx.index.min_word_len_one_typo(ctx.txn)?,
+    };
+    let min_len_two_typos = match overlay.min_word_len_two_typos {
+        Some(min_len_two_typos) => min_len_two_typos,
+        None => ctx.index.min_word_len_two_typos(ctx.txn)?,
+    };
 
     let exact_words = ctx.index.exact_words(ctx.txn)?;
 
     Ok(Box::new(move |word: &str| {
+        let word_len = word.chars().count();
         if !authorize_typos
-            || word.len() < min_len_one_typo as usize
+            || word_len < min_len_one_typo as usize
             || exact_words.as_ref().map_or(false, |fst| fst.contains(word))
         {
             0
-        } else if word.len() < min_len_two_typos as usize {
+        } else if word_len < min_len_two_typos as usize {
             1
         } else {
             2
@@ -213,6 +323,25 @@ pub fn number_of_typos_allowed<'ctx>(
     }))
 }
 
+/// Returns whether typos are disabled on all the attributes searched.
+fn only_exact_attributes(ctx: &SearchContext) -> Result<bool> {
+    if let Some(restricted_fids) = &ctx.restricted_fids {
+        return Ok(restricted_fids.tolerant.is_empty());
+    }
+
+    let exact_attributes_ids = ctx.index.exact_attributes_ids(ctx.txn)?;
+    if exact_attributes_ids.is_empty() {
+        return Ok(false);
+    }
+    let only_exact = match ctx.index.searchable_fields_ids(ctx.txn)? {
+        Some(fids) => fids.iter().all(|fid| exact_attributes_ids.contains(fid)),
+        None => {
+            ctx.index.fields_ids_map(ctx.txn)?.ids().all(|fid| exact_attributes_ids.contains(&fid))
+        }
+    };
+    Ok(only_exact)
+}
+
 pub fn make_ngram(
     ctx: &mut SearchContext,
     terms: &[LocatedQueryTerm],
@@ -257,14 +386,17 @@ pub fn make_ngram(
         partially_initialized_term_from_word(ctx, &ngram_str, max_nbr_typos, is_prefix, true)?;
 
     // Now add the synonyms
-    let index_synonyms = ctx.index.synonyms(ctx.txn)?;
-
-    term.zero_typo.synonyms.extend(
-        index_synonyms.get(&words).cloned().unwrap_or_default().into_iter().map(|words| {
-            let words = words.into_iter().map(|w| Some(ctx.word_interner.insert(w))).collect();
-            ctx.phrase_interner.insert(Phrase { words })
-        }),
-    );
+    let synonyms = ctx.word_synonyms(&words)?;
+
+    term.zero_typo.synonyms.extend(synonyms.into_iter().map(|words| {
+        let words = words.into_iter().map(|w| Some(ctx.word_interner.insert(w))).collect();
+        ctx.phrase_interner.insert(Phrase {
+            words,
+            stop_words: Vec::new(),
+            derived: true,
+            prefix: false,
+        })
+    }));
 
     let term = QueryTerm {
         original: ngram_str_interned,
@@ -283,41 +415,83 @@ pub fn make_ngram(
 
 struct PhraseBuilder {
     words: Vec<Option<crate::search::new::Interned<String>>>,
+    stop_words: Vec<Option<crate::search::new::Interned<String>>>,
+    /// The indexes of the stop words of the phrase in the query tokens.
+    stop_word_tokens: Vec<usize>,
     start: u16,
     end: u16,
+    prefix: bool,
 }
 
 impl PhraseBuilder {
     fn empty() -> Self {
-        Self { words: Default::default(), start: u16::MAX, end: u16::MAX }
+        Self {
+            words: Default::default(),
+            stop_words: Default::default(),
+            stop_word_tokens: Default::default(),
+            start: u16::MAX,
+            end: u16::MAX,
+            prefix: false,
+        }
     }
 
     fn is_empty(&self) -> bool {
         self.words.is_empty() || self.words.iter().all(Option::is_none)
     }
 
+    fn ends_with_short_number(&self, ctx: &SearchContext) -> Result<bool> {
+        match self.words.last() {
+            Some(Some(word)) => is_short_number(ctx, ctx.word_interner.get(*word)),
+            _ => Ok(false),
+        }
+    }
+
     // precondition: token has kind Word or StopWord
-    fn push_word(&mut self, ctx: &mut SearchContext, token: &charabia::Token, position: u16) {
+    fn push_word(
+        &mut self,
+        ctx: &mut SearchContext,
+        token: &charabia::Token,
+        position: u16,
+        query_token: usize,
+    ) {
         if self.is_empty() {
             self.start = position;
         }
         self.end = position;
+        let word = ctx.word_interner.insert(token.lemma().to_string());
         if let TokenKind::StopWord = token.kind {
+            // the stop words are not indexed, they are checked in the documents
             self.words.push(None);
+            self.stop_words.push(Some(word));
+            self.stop_word_tokens.push(query_token);
         } else {
             // token has kind Word
-            let word = ctx.word_interner.insert(token.lemma().to_string());
             self.words.push(Some(word));
+            self.stop_words.push(None);
         }
     }
 
-    fn build(self, ctx: &mut SearchContext) -> Option<LocatedQueryTerm> {
+    fn build(
+        self,
+        ctx: &mut SearchContext,
+        query_tokens: &mut [QueryToken],
+    ) -> Option<LocatedQueryTerm> {
         if self.is_empty() {
+            for index in self.stop_word_tokens {
+                query_tokens[index].kind = QueryTokenKind::PhraseStopWordSkipped;
+            }
             return None;
         }
+        let stop_words =
+            if self.stop_word_tokens.is_empty() { Vec::new() } else { self.stop_words };
         Some(LocatedQueryTerm {
             value: ctx.term_interner.push({
-                let phrase = ctx.phrase_interner.insert(Phrase { words: self.words });
+                let phrase = ctx.phrase_interner.insert(Phrase {
+                    words: self.words,
+                    stop_words,
+                    derived: false,
+                    prefix: self.prefix,
+                });
                 let phrase_desc = phrase.description(ctx);
                 QueryTerm {
                     original: ctx.word_interner.insert(phrase_desc),
@@ -330,6 +504,7 @@ impl PhraseBuilder {
                         prefix_of: BTreeSet::default(),
                         synonyms: BTreeSet::default(),
                         use_prefix_db: None,
+                        stems: BTreeSet::default(),
                     },
                     one_typo: Lazy::Uninit,
                     two_typo: Lazy::Uninit,
@@ -372,6 +547,35 @@ mod tests {
             located_query_terms_from_tokens(&mut ctx, tokens, None)?;
         assert!(query_terms.is_empty());
 
+        Ok(())
+    }
+    #[test]
+    fn query_tokens_are_located_in_the_original_query() -> Result<()> {
+        let query = "Hello \"World of\" 🐶";
+        let mut builder = TokenizerBuilder::default();
+        let tokenizer = builder.build();
+        let tokens = tokenizer.tokenize(query);
+        let index = temp_index_with_documents();
+        let rtxn = index.read_txn()?;
+        let mut ctx = SearchContext::new(&index, &rtxn);
+        let ExtractedTokens { query_tokens, .. } =
+            located_query_terms_from_tokens(&mut ctx, tokens, None)?;
+
+        let located: Vec<_> = query_tokens
+            .iter()
+            .filter(|token| token.kind != QueryTokenKind::SeparatorSkipped)
+            .map(|token| (token.token.as_str(), &query[token.start..token.end], token.kind))
+            .collect();
+        assert_eq!(
+            located,
+            [
+                ("hello", "Hello", QueryTokenKind::Word),
+                ("world", "World", QueryTokenKind::Phrase),
+                ("of", "of", QueryTokenKind::Phrase),
+                ("🐶", "🐶", QueryTokenKind::Word),
+            ]
+        );
+
         Ok(())
     }
 }
@@ -8,6 +8,16 @@ use crate::SearchContext;
 #[derive(Default, Clone, PartialEq, Eq, Hash)]
 pub struct Phrase {
     pub words: Vec<Option<Interned<String>>>,
+    /// The stop words of a phrase written in the query, in place of its `None` words, or
+    /// nothing when it has no stop words. They are not indexed, so the documents matching
+    /// the other words of the phrase are checked to contain them.
+    pub stop_words: Vec<Option<Interned<String>>>,
+    /// Whether the phrase is derived from a query word, as its split words or one of its
+    /// synonyms, rather than written in the query: its words don't match the strict attributes.
+    pub derived: bool,
+    /// Whether the last word of the phrase is matched as a prefix, the query ending inside
+    /// an unclosed phrase that is likely still being typed.
+    pub prefix: bool,
 }
 impl Interned<Phrase> {
     pub fn description(self, ctx: &SearchContext) -> String {
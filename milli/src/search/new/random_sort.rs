@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use roaring::RoaringBitmap;
+
+use super::distinct::{facet_number_values, facet_string_values};
+use super::logger::SearchLogger;
+use super::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait, SearchContext};
+use crate::score_details::{self, ScoreDetails};
+use crate::{DocumentId, FieldId, RandomSeed, Result};
+
+/// A ranking rule shuffling the documents in buckets of `bucket_size` documents.
+///
+/// The shuffled order of a document only depends on the seed and on the document itself,
+/// so the same seed, universe, and index always produce the same permutation, however many
+/// times the iteration is restarted.
+pub struct RandomSort<Query> {
+    seed: RandomSeed,
+    /// The id of the seed field, `None` if the seed is a literal or the field is unknown.
+    field_id: Option<FieldId>,
+    bucket_size: usize,
+    /// The shuffling keys of the documents, computed once for the whole search.
+    keys: HashMap<DocumentId, u64>,
+    /// The documents of the universe given to `start_iteration`, in the shuffled order.
+    shuffled: Vec<DocumentId>,
+    /// The position of the next document of `shuffled` to return.
+    cursor: usize,
+    bucket: u32,
+    query: Option<Query>,
+}
+
+impl<Query> RandomSort<Query> {
+    pub fn new(ctx: &SearchContext, seed: RandomSeed, bucket_size: u32) -> Result<Self> {
+        let field_id = match &seed {
+            RandomSeed::Literal(_) => None,
+            RandomSeed::Field(field) => ctx.index.fields_ids_map(ctx.txn)?.id(field),
+        };
+
+        Ok(Self {
+            seed,
+            field_id,
+            bucket_size: bucket_size.max(1) as usize,
+            keys: HashMap::new(),
+            shuffled: Vec::new(),
+            cursor: 0,
+            bucket: 0,
+            query: None,
+        })
+    }
+
+    /// Returns the shuffling key of the document, the documents are ranked by increasing key
+    /// and then by increasing id.
+    fn key(&mut self, ctx: &SearchContext, docid: DocumentId) -> Result<u64> {
+        if let Some(key) = self.keys.get(&docid) {
+            return Ok(*key);
+        }
+
+        let key = match (&self.seed, self.field_id) {
+            (RandomSeed::Literal(seed), _) => mix(seed ^ mix(docid as u64)),
+            (RandomSeed::Field(_), None) => u64::MAX,
+            (RandomSeed::Field(_), Some(field_id)) => {
+                // the first value of the field seeds the document, numbers before strings
+                let mut value = None;
+                if let Some(item) = facet_number_values(docid, field_id, ctx.index, ctx.txn)?.next()
+                {
+                    let ((_, _, bytes), _) = item?;
+                    value = Some(hash_bytes(bytes));
+                }
+                if value.is_none() {
+                    if let Some(item) =
+                        facet_string_values(docid, field_id, ctx.index, ctx.txn)?.next()
+                    {
+                        let ((_, _, bytes), _) = item?;
+                        value = Some(hash_bytes(bytes));
+                    }
+                }
+                // the documents without a seed are ranked last
+                value.map_or(u64::MAX, |value| mix(value).min(u64::MAX - 1))
+            }
+        };
+
+        self.keys.insert(docid, key);
+        Ok(key)
+    }
+}
+
+impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for RandomSort<Query> {
+    fn id(&self) -> String {
+        match &self.seed {
+            RandomSeed::Literal(seed) => format!("random({seed})"),
+            RandomSeed::Field(field) => format!("random({field})"),
+        }
+    }
+
+    fn start_iteration(
+        &mut self,
+        ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        universe: &RoaringBitmap,
+        query: &Query,
+    ) -> Result<()> {
+        let mut keyed = Vec::with_capacity(universe.len() as usize);
+        for docid in universe {
+            keyed.push((self.key(ctx, docid)?, docid));
+        }
+        keyed.sort_unstable();
+
+        self.shuffled = keyed.into_iter().map(|(_, docid)| docid).collect();
+        self.cursor = 0;
+        self.bucket = 0;
+        self.query = Some(query.clone());
+        Ok(())
+    }
+
+    fn next_bucket(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+        universe: &RoaringBitmap,
+    ) -> Result<Option<RankingRuleOutput<Query>>> {
+        // the documents removed from the universe since the start of the iteration are skipped
+        let mut candidates = RoaringBitmap::new();
+        while candidates.len() < self.bucket_size as u64 && self.cursor < self.shuffled.len() {
+            let docid = self.shuffled[self.cursor];
+            if universe.contains(docid) {
+                candidates.insert(docid);
+            }
+            self.cursor += 1;
+        }
+
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+
+        let score = ScoreDetails::Random(score_details::Random { bucket: self.bucket });
+        self.bucket += 1;
+        Ok(Some(RankingRuleOutput {
+            query: self.query.as_ref().unwrap().clone(),
+            candidates,
+            score,
+        }))
+    }
+
+    fn end_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<Query>,
+    ) {
+        self.shuffled = Vec::new();
+        self.query = None;
+    }
+}
+
+/// The finalizer of the SplitMix64 generator, a stable and well distributed 64 bits mixer.
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// The 64 bits FNV-1a hash, which doesn't depend on the platform nor on the process.
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
@@ -4,7 +4,6 @@ use super::{ComputedCondition, RankingRuleGraphTrait};
 use crate::score_details::{self, Rank, ScoreDetails};
 use crate::search::new::interner::{DedupInterner, Interned};
 use crate::search::new::query_term::{ExactTerm, LocatedQueryTermSubset};
-use crate::search::new::resolve_query_graph::compute_query_term_subset_docids;
 use crate::search::new::Word;
 use crate::{Result, SearchContext};
 
@@ -57,8 +56,7 @@ impl RankingRuleGraphTrait for ExactnessGraph {
                 (compute_docids(ctx, dest_node, universe)?, end_term_subset)
             }
             ExactnessCondition::Any(dest_node) => {
-                let docids =
-                    universe & compute_query_term_subset_docids(ctx, &dest_node.term_subset)?;
+                let docids = universe & ctx.get_query_term_subset_docids(&dest_node.term_subset)?;
                 (docids, dest_node.clone())
             }
         };
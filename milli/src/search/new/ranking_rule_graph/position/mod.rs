@@ -78,7 +78,6 @@ impl RankingRuleGraphTrait for PositionGraph {
         let mut positions_for_costs = FxHashMap::<u32, Vec<u16>>::default();
 
         for position in all_positions {
-            // FIXME: bucketed position???
             let distance = position.abs_diff(*term.positions.start());
             let cost = {
                 let mut cost = 0;
@@ -122,18 +121,11 @@ impl RankingRuleGraphTrait for PositionGraph {
     }
 }
 
+/// Returns the cost of a term found `distance` positions away from its place in the query.
+///
+/// Distances are grouped in logarithmic bands (0, 1, 2-3, 4-7, 8-15, ...) so that the
+/// number of buckets of the rule stays bounded, even on very long attributes.
 fn cost_from_distance(distance: u32) -> u32 {
-    match distance {
-        0 => 0,
-        1 => 1,
-        2..=4 => 2,
-        5..=7 => 3,
-        8..=11 => 4,
-        12..=16 => 5,
-        17..=24 => 6,
-        25..=64 => 7,
-        65..=256 => 8,
-        257..=1024 => 9,
-        _ => 10,
-    }
+    // the band is the number of significant bits of the distance
+    (u32::BITS - distance.leading_zeros()).min(10)
 }
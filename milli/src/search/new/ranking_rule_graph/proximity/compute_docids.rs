@@ -8,7 +8,6 @@ use super::ProximityCondition;
 use crate::search::new::interner::Interned;
 use crate::search::new::query_term::{Phrase, QueryTermSubset};
 use crate::search::new::ranking_rule_graph::ComputedCondition;
-use crate::search::new::resolve_query_graph::compute_query_term_subset_docids;
 use crate::search::new::{SearchContext, Word};
 use crate::Result;
 
@@ -22,8 +21,7 @@ pub fn compute_docids(
             (left_term, right_term, *cost)
         }
         ProximityCondition::Term { term } => {
-            let mut docids = compute_query_term_subset_docids(ctx, &term.term_subset)?;
-            docids &= universe;
+            let docids = universe & ctx.get_query_term_subset_docids(&term.term_subset)?;
             return Ok(ComputedCondition {
                 docids,
                 universe_len: universe.len(),
@@ -147,7 +145,7 @@ fn compute_prefix_edges(
     }
 
     // No swapping when computing the proximity between a phrase and a word
-    if left_phrase.is_none() {
+    if backward_proximity >= 1 && left_phrase.is_none() {
         if let Some(new_docids) = ctx.get_db_prefix_word_pair_proximity_docids(
             right_prefix,
             left_word,
@@ -4,7 +4,6 @@ use super::{ComputedCondition, RankingRuleGraphTrait};
 use crate::score_details::{self, Rank, ScoreDetails};
 use crate::search::new::interner::{DedupInterner, Interned};
 use crate::search::new::query_term::LocatedQueryTermSubset;
-use crate::search::new::resolve_query_graph::compute_query_term_subset_docids;
 use crate::search::new::SearchContext;
 use crate::Result;
 
@@ -25,9 +24,7 @@ impl RankingRuleGraphTrait for TypoGraph {
         universe: &RoaringBitmap,
     ) -> Result<ComputedCondition> {
         let TypoCondition { term, .. } = condition;
-        // maybe compute_query_term_subset_docids should accept a universe as argument
-        let mut docids = compute_query_term_subset_docids(ctx, &term.term_subset)?;
-        docids &= universe;
+        let docids = universe & ctx.get_query_term_subset_docids(&term.term_subset)?;
 
         Ok(ComputedCondition {
             docids,
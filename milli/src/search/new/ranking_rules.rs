@@ -1,3 +1,5 @@
+use std::fmt;
+
 use roaring::RoaringBitmap;
 
 use super::logger::SearchLogger;
@@ -57,6 +59,62 @@ pub trait RankingRule<'ctx, Query: RankingRuleQueryTrait> {
         ctx: &mut SearchContext<'ctx>,
         logger: &mut dyn SearchLogger<Query>,
     );
+
+    /// The maximum number of buckets the bucket sort asks for between a call to
+    /// [`start_iteration`](RankingRule::start_iteration) and the next one, the last of
+    /// them containing all the remaining documents of the universe.
+    fn max_buckets(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// A ranking rule whose number of buckets is capped by the settings.
+pub struct MaxBuckets<'ctx, Query> {
+    inner: BoxRankingRule<'ctx, Query>,
+    max_buckets: usize,
+}
+
+impl<'ctx, Query: RankingRuleQueryTrait> MaxBuckets<'ctx, Query> {
+    pub fn new(inner: BoxRankingRule<'ctx, Query>, max_buckets: usize) -> Self {
+        Self { inner, max_buckets }
+    }
+}
+
+impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for MaxBuckets<'ctx, Query> {
+    fn id(&self) -> String {
+        self.inner.id()
+    }
+
+    fn start_iteration(
+        &mut self,
+        ctx: &mut SearchContext<'ctx>,
+        logger: &mut dyn SearchLogger<Query>,
+        universe: &RoaringBitmap,
+        query: &Query,
+    ) -> Result<()> {
+        self.inner.start_iteration(ctx, logger, universe, query)
+    }
+
+    fn next_bucket(
+        &mut self,
+        ctx: &mut SearchContext<'ctx>,
+        logger: &mut dyn SearchLogger<Query>,
+        universe: &RoaringBitmap,
+    ) -> Result<Option<RankingRuleOutput<Query>>> {
+        self.inner.next_bucket(ctx, logger, universe)
+    }
+
+    fn end_iteration(
+        &mut self,
+        ctx: &mut SearchContext<'ctx>,
+        logger: &mut dyn SearchLogger<Query>,
+    ) {
+        self.inner.end_iteration(ctx, logger)
+    }
+
+    fn max_buckets(&self) -> Option<usize> {
+        Some(self.max_buckets)
+    }
 }
 
 /// Output of a ranking rule, consisting of the query to be used
@@ -70,3 +128,35 @@ pub struct RankingRuleOutput<Q> {
     /// The score for the candidates of the current bucket
     pub score: ScoreDetails,
 }
+
+/// A ranking rule of the settings that had no effect on a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedRankingRule {
+    /// The ranking rule, as written in the settings.
+    pub ranking_rule: String,
+    pub reason: SkipReason,
+}
+
+/// Why a ranking rule of the settings had no effect on a search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    NoSortParameter,
+    NoSortableAttributes,
+    UnknownAttribute,
+    EmptyQuery,
+    MatchingStrategyAll,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SkipReason::NoSortParameter => "the search has no `sort` parameter",
+            SkipReason::NoSortableAttributes => "the index has no sortable attributes",
+            SkipReason::UnknownAttribute => "no document contains the attribute",
+            SkipReason::EmptyQuery => "the search has no query",
+            SkipReason::MatchingStrategyAll => {
+                "the `matchingStrategy` is `all`, so all the query words always match"
+            }
+        })
+    }
+}
@@ -1,21 +1,31 @@
 #![allow(clippy::too_many_arguments)]
 
+use std::borrow::Cow;
 use std::collections::VecDeque;
 
-use fxhash::FxHashMap;
+use charabia::{SeparatorKind, TokenKind, Tokenizer, TokenizerBuilder};
+use fxhash::{FxHashMap, FxHashSet};
+use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
 use roaring::{MultiOps, RoaringBitmap};
 
 use super::interner::Interned;
-use super::query_graph::QueryNodeData;
-use super::query_term::{Phrase, QueryTermSubset};
+use super::metrics::SearchMetrics;
+use super::query_graph::{QueryNode, QueryNodeData};
+use super::query_term::{LocatedQueryTerm, Phrase, QueryTermSubset};
 use super::small_bitmap::SmallBitmap;
 use super::{QueryGraph, SearchContext, Word};
+use crate::heed_codec::BytesDecodeOwned;
+use crate::number_normalization::normalize_number;
 use crate::search::new::query_term::LocatedQueryTermSubset;
-use crate::Result;
+use crate::update::json_to_string;
+use crate::{bucketed_position, obkv_to_json, CboRoaringBitmapCodec, DocumentId, FieldId, Result};
 
 #[derive(Default)]
 pub struct PhraseDocIdsCache {
     pub cache: FxHashMap<Interned<Phrase>, RoaringBitmap>,
+    /// The documents matching the phrase only across two adjacent attributes,
+    /// they are also part of the documents in `cache`.
+    pub across_fields: FxHashMap<Interned<Phrase>, RoaringBitmap>,
 }
 impl<'ctx> SearchContext<'ctx> {
     /// Get the document ids associated with the given phrase
@@ -23,28 +33,258 @@ impl<'ctx> SearchContext<'ctx> {
         if self.phrase_docids.cache.contains_key(&phrase) {
             return Ok(&self.phrase_docids.cache[&phrase]);
         };
-        let docids = compute_phrase_docids(self, phrase)?;
+        let mut docids = compute_phrase_docids(self, phrase)?;
+        let across_fields_docids = if self.index.phrase_across_fields(self.txn)? {
+            compute_phrase_across_fields_docids(self, phrase)? - &docids
+        } else {
+            RoaringBitmap::new()
+        };
+        docids |= &across_fields_docids;
+        let _ = self.phrase_docids.across_fields.insert(phrase, across_fields_docids);
         let _ = self.phrase_docids.cache.insert(phrase, docids);
         let docids = &self.phrase_docids.cache[&phrase];
         Ok(docids)
     }
+
+    /// Get the document ids matching the given phrase only across two adjacent attributes.
+    ///
+    /// It is always empty when the `phraseAcrossFields` setting is disabled.
+    pub fn get_phrase_across_fields_docids(
+        &mut self,
+        phrase: Interned<Phrase>,
+    ) -> Result<&RoaringBitmap> {
+        self.get_phrase_docids(phrase)?;
+        Ok(&self.phrase_docids.across_fields[&phrase])
+    }
+
+    /// Get the document ids associated with the given term subset
+    pub fn get_query_term_subset_docids(
+        &mut self,
+        term: &QueryTermSubset,
+    ) -> Result<&RoaringBitmap> {
+        self.cache_query_term_subset_docids(term)?;
+        self.resolve_query_term_subset_docids(term)
+    }
+
+    /// Get the document ids of the universe associated with the given term subset.
+    ///
+    /// The docids of the term are not resolved when the universe is not larger than the
+    /// `small_universe_threshold` of the context, they are decoded one by one and intersected
+    /// with the universe instead, until all the documents of the universe are found.
+    fn get_query_term_subset_docids_within(
+        &mut self,
+        term: &QueryTermSubset,
+        universe: &RoaringBitmap,
+    ) -> Result<RoaringBitmap> {
+        self.cache_query_term_subset_docids(term)?;
+        let slices = match self.query_graph_docids.term_docids.get(term) {
+            Some(LazyDocids::Raw(slices)) if universe.len() <= self.small_universe_threshold => {
+                slices
+            }
+            _ => {
+                let docids = universe & self.resolve_query_term_subset_docids(term)?;
+                self.metrics.bitmap_intersections += 1;
+                return Ok(docids);
+            }
+        };
+
+        let mut docids = RoaringBitmap::new();
+        for bytes in slices {
+            // all the documents of the universe are found, the remaining docids can't add any
+            if docids.len() == universe.len() {
+                break;
+            }
+            let mut term_docids = decode_docids(bytes, &mut self.metrics)?;
+            term_docids &= universe;
+            docids |= term_docids;
+            self.metrics.bitmap_intersections += 1;
+            self.metrics.bitmap_unions += 1;
+        }
+        Ok(docids)
+    }
+
+    /// Returns whether the given term subset matches no documents.
+    ///
+    /// Only the serialized docids of the term are fetched, they are cached for the
+    /// resolution of the query graph.
+    pub fn query_term_subset_is_empty(&mut self, term: &QueryTermSubset) -> Result<bool> {
+        self.cache_query_term_subset_docids(term)?;
+        Ok(self.query_graph_docids.empty_terms.contains(term))
+    }
+
+    fn cache_query_term_subset_docids(&mut self, term: &QueryTermSubset) -> Result<()> {
+        if self.query_graph_docids.contains(term) {
+            self.metrics.term_cache_hits += 1;
+        } else {
+            self.metrics.term_cache_misses += 1;
+            let docids = compute_query_term_subset_lazy_docids(self, term)?;
+            self.query_graph_docids.insert(term, docids);
+        }
+        Ok(())
+    }
+
+    /// Decodes and merges the serialized docids of the terms of the graph in parallel, so that
+    /// resolving the graph only intersects them.
+    ///
+    /// The serialized docids are fetched with the read transaction of the search, they point into
+    /// the memory map of the index which can be read from any thread while the transaction is
+    /// alive: the workers don't need their own transaction.
+    fn resolve_query_graph_terms_in_parallel(&mut self, q: &QueryGraph) -> Result<()> {
+        let mut terms = Vec::new();
+        for (_, node) in q.nodes.iter() {
+            if let QueryNodeData::Term(LocatedQueryTermSubset { term_subset, .. }) = &node.data {
+                if !terms.contains(&term_subset) {
+                    terms.push(term_subset);
+                }
+            }
+        }
+        if terms.len() < PARALLEL_RESOLUTION_MIN_TERMS {
+            return Ok(());
+        }
+
+        for term in &terms {
+            if !self.query_graph_docids.contains(term) {
+                self.cache_query_term_subset_docids(term)?;
+            }
+        }
+        let (raw_terms, raw_slices): (Vec<_>, Vec<_>) = terms
+            .into_iter()
+            .filter_map(|term| match self.query_graph_docids.term_docids.get(term) {
+                Some(LazyDocids::Raw(slices)) => Some((term, slices.as_slice())),
+                _ => None,
+            })
+            .unzip();
+        let resolved: Vec<_> = raw_slices
+            .into_par_iter()
+            .map(|slices| {
+                let mut union = RoaringBitmap::new();
+                for bytes in slices {
+                    union |= CboRoaringBitmapCodec::bytes_decode_owned(bytes)
+                        .map_err(heed::Error::Decoding)?;
+                }
+                Ok((union, slices.len() as u64))
+            })
+            .collect::<Result<_>>()?;
+
+        for (term, (docids, decodes)) in raw_terms.into_iter().zip(resolved) {
+            self.metrics.bitmap_decodes += decodes;
+            self.metrics.bitmap_unions += decodes;
+            self.query_graph_docids.term_docids.insert(term.clone(), LazyDocids::Resolved(docids));
+        }
+        Ok(())
+    }
+
+    /// Merges the serialized docids of a cached term subset, if they are not already.
+    fn resolve_query_term_subset_docids(
+        &mut self,
+        term: &QueryTermSubset,
+    ) -> Result<&RoaringBitmap> {
+        let cache = &mut self.query_graph_docids;
+        let Some(docids) = cache.term_docids.get_mut(term) else {
+            return Ok(&cache.empty);
+        };
+        if let LazyDocids::Raw(slices) = docids {
+            let mut union = RoaringBitmap::new();
+            for bytes in slices.iter() {
+                union |= decode_docids(bytes, &mut self.metrics)?;
+                self.metrics.bitmap_unions += 1;
+            }
+            *docids = LazyDocids::Resolved(union);
+        }
+        match docids {
+            LazyDocids::Resolved(docids) => Ok(docids),
+            LazyDocids::Raw(_) => unreachable!(),
+        }
+    }
+}
+
+/// The minimum number of distinct terms in a query graph for their docids to be decoded in
+/// parallel, below it dispatching them to the threads costs more than it saves.
+const PARALLEL_RESOLUTION_MIN_TERMS: usize = 4;
+
+/// The docids of a term subset, decoded and merged only when they are needed.
+pub enum LazyDocids<'ctx> {
+    /// The serialized docids of the words and the prefix of the term, the largest first.
+    Raw(Vec<Cow<'ctx, [u8]>>),
+    Resolved(RoaringBitmap),
+}
+
+/// What is kept between the resolutions of the query graphs of a search.
+///
+/// The docids are cached by term subset rather than by node, so the cache stays valid when
+/// the nodes of a graph are deleted or when the graph is truncated.
+#[derive(Default)]
+pub struct QueryGraphDocIdsCache<'ctx> {
+    pub term_docids: FxHashMap<QueryTermSubset, LazyDocids<'ctx>>,
+    /// The term subsets matching no documents, they are not stored with the other docids.
+    pub empty_terms: FxHashSet<QueryTermSubset>,
+    empty: RoaringBitmap,
+    /// The docids of the paths leading to each node, reused by every resolution.
+    path_nodes_docids: Vec<RoaringBitmap>,
+}
+
+impl<'ctx> QueryGraphDocIdsCache<'ctx> {
+    fn contains(&self, term: &QueryTermSubset) -> bool {
+        self.term_docids.contains_key(term) || self.empty_terms.contains(term)
+    }
+
+    fn insert(&mut self, term: &QueryTermSubset, docids: LazyDocids<'ctx>) {
+        let is_empty = match &docids {
+            LazyDocids::Raw(slices) => slices.is_empty(),
+            LazyDocids::Resolved(docids) => docids.is_empty(),
+        };
+        if is_empty {
+            self.empty_terms.insert(term.clone());
+        } else {
+            self.term_docids.insert(term.clone(), docids);
+        }
+    }
+}
+
+fn decode_docids(bytes: &[u8], metrics: &mut SearchMetrics) -> Result<RoaringBitmap> {
+    metrics.bitmap_decodes += 1;
+    CboRoaringBitmapCodec::bytes_decode_owned(bytes)
+        .map_err(heed::Error::Decoding)
+        .map_err(Into::into)
+}
+
+/// Returns the serialized docids of the term, or its resolved docids when it contains phrases.
+fn compute_query_term_subset_lazy_docids<'ctx>(
+    ctx: &mut SearchContext<'ctx>,
+    term: &QueryTermSubset,
+) -> Result<LazyDocids<'ctx>> {
+    if !term.all_phrases(ctx)?.is_empty() {
+        return compute_query_term_subset_docids(ctx, term).map(LazyDocids::Resolved);
+    }
+
+    let words: Vec<_> = term.all_single_words_except_prefix_db(ctx)?.into_iter().collect();
+    let mut slices = ctx.words_docids_bytes(&words)?;
+    if let Some(prefix) = term.use_prefix_db(ctx) {
+        slices.extend(ctx.word_prefix_docids_bytes(prefix)?);
+    }
+    // the largest docids are the most likely to contain all the documents of a universe
+    slices.sort_unstable_by_key(|bytes| std::cmp::Reverse(bytes.len()));
+    Ok(LazyDocids::Raw(slices))
 }
+
 pub fn compute_query_term_subset_docids(
     ctx: &mut SearchContext,
     term: &QueryTermSubset,
 ) -> Result<RoaringBitmap> {
     let mut docids = RoaringBitmap::new();
-    for word in term.all_single_words_except_prefix_db(ctx)? {
-        if let Some(word_docids) = ctx.word_docids(word)? {
-            docids |= word_docids;
-        }
+    let words: Vec<_> = term.all_single_words_except_prefix_db(ctx)?.into_iter().collect();
+    for bytes in ctx.words_docids_bytes(&words)? {
+        ctx.metrics.bitmap_unions += 1;
+        docids |= decode_docids(&bytes, &mut ctx.metrics)?;
     }
     for phrase in term.all_phrases(ctx)? {
+        ctx.metrics.bitmap_unions += 1;
         docids |= ctx.get_phrase_docids(phrase)?;
     }
 
     if let Some(prefix) = term.use_prefix_db(ctx) {
         if let Some(prefix_docids) = ctx.word_prefix_docids(prefix)? {
+            ctx.metrics.bitmap_unions += 1;
             docids |= prefix_docids;
         }
     }
@@ -65,11 +305,20 @@ pub fn compute_query_term_subset_docids_within_field_id(
     }
 
     for phrase in term.all_phrases(ctx)? {
+        let across_fields_docids = ctx.get_phrase_across_fields_docids(phrase)?.clone();
+        let within_field_docids = ctx.get_phrase_docids(phrase)? - &across_fields_docids;
         // There may be false positives when resolving a phrase, so we're not
         // guaranteed that all of its words are within a single fid.
         if let Some(word) = phrase.words(ctx).iter().flatten().next() {
             if let Some(word_fid_docids) = ctx.get_db_word_fid_docids(*word, fid)? {
-                docids |= ctx.get_phrase_docids(phrase)? & word_fid_docids;
+                docids |= within_field_docids & word_fid_docids;
+            }
+        }
+        // The phrases spanning two attributes are attributed to the second one, so that
+        // they rank after the phrases found entirely within the first one.
+        if let Some(word) = phrase.words(ctx).iter().flatten().last() {
+            if let Some(word_fid_docids) = ctx.get_db_word_fid_docids(*word, fid)? {
+                docids |= across_fields_docids & word_fid_docids;
             }
         }
     }
@@ -120,47 +369,121 @@ pub fn compute_query_term_subset_docids_within_position(
 }
 
 /// Returns the subset of the input universe that satisfies the contraints of the input query graph.
+#[tracing::instrument(level = "trace", skip_all, target = "search::query")]
 pub fn compute_query_graph_docids(
     ctx: &mut SearchContext,
     q: &QueryGraph,
     universe: &RoaringBitmap,
 ) -> Result<RoaringBitmap> {
+    // the docids of the terms are only intersected with a small universe, decoding them all
+    // upfront would cost more than verifying its documents against each term
+    if ctx.parallel_term_resolution && universe.len() > ctx.small_universe_threshold {
+        ctx.resolve_query_graph_terms_in_parallel(q)?;
+    }
     let mut nodes_resolved = SmallBitmap::for_interned_values_in(&q.nodes);
-    let mut path_nodes_docids = q.nodes.map(|_| RoaringBitmap::new());
+    let mut path_nodes_docids = std::mem::take(&mut ctx.query_graph_docids.path_nodes_docids);
+    path_nodes_docids.iter_mut().for_each(RoaringBitmap::clear);
+    path_nodes_docids.resize(q.nodes.len() as usize, RoaringBitmap::new());
+    let result =
+        resolve_query_graph_paths(ctx, q, universe, &mut nodes_resolved, &mut path_nodes_docids);
+    ctx.query_graph_docids.path_nodes_docids = path_nodes_docids;
+    result
+}
+
+/// The derivations of a query term found in each document, with the number of typos
+/// that derived them from the query word.
+pub type MatchedDerivations = FxHashMap<DocumentId, Vec<(Interned<String>, u8)>>;
+
+/// Returns, for each located term, the derivations of the term contained by each of the given documents.
+///
+/// This is a targeted pass over the returned documents only, once the bucket sort is done,
+/// the derivations of a term are fully computed, and the completions of a prefix are bounded
+/// by [`MAX_PREFIX_COUNT`](super::limits::MAX_PREFIX_COUNT).
+#[tracing::instrument(level = "trace", skip_all, target = "search::query")]
+pub fn compute_matched_derivations(
+    ctx: &mut SearchContext,
+    located_terms: &[LocatedQueryTerm],
+    docids: &RoaringBitmap,
+) -> Result<Vec<MatchedDerivations>> {
+    let mut matched = Vec::with_capacity(located_terms.len());
+    for located_term in located_terms {
+        let mut term_matched = MatchedDerivations::default();
+        if docids.is_empty() {
+            matched.push(term_matched);
+            continue;
+        }
+        located_term.value.compute_fully_if_needed(ctx)?;
+        let term = ctx.term_interner.get(located_term.value);
+        let (mut words, _) = term.all_computed_derivations();
+        if let Some(prefix) = term.prefix_db() {
+            let completions = ctx.get_prefix_completions(prefix)?;
+            words.extend(completions.iter().map(|&word| (word, 0)));
+        }
+
+        for (word, typos) in words {
+            ctx.check_canceled()?;
+            let Some(word_docids) = ctx.word_docids(Word::Original(word))? else { continue };
+            for docid in word_docids & docids {
+                term_matched.entry(docid).or_default().push((word, typos));
+            }
+        }
+        matched.push(term_matched);
+    }
+    Ok(matched)
+}
+
+fn resolve_query_graph_paths(
+    ctx: &mut SearchContext,
+    q: &QueryGraph,
+    universe: &RoaringBitmap,
+    nodes_resolved: &mut SmallBitmap<QueryNode>,
+    path_nodes_docids: &mut [RoaringBitmap],
+) -> Result<RoaringBitmap> {
+    let slot = |node_id: Interned<QueryNode>| node_id.into_raw() as usize;
 
     let mut next_nodes_to_visit = VecDeque::new();
     next_nodes_to_visit.push_back(q.root_node);
+    // The number of nodes visited in a row whose predecessors were not all resolved.
+    let mut postponed_nodes = 0;
 
     while let Some(node_id) = next_nodes_to_visit.pop_front() {
+        ctx.check_canceled()?;
         let node = q.nodes.get(node_id);
         let predecessors = &node.predecessors;
-        if !predecessors.is_subset(&nodes_resolved) {
+        if !predecessors.is_subset(nodes_resolved) {
             next_nodes_to_visit.push_back(node_id);
+            postponed_nodes += 1;
+            // None of the remaining nodes can be resolved, some of their predecessors
+            // are not reachable from the root node, and neither is the end node.
+            if postponed_nodes > next_nodes_to_visit.len() {
+                break;
+            }
             continue;
         }
+        postponed_nodes = 0;
         // Take union of all predecessors
         let predecessors_docids =
-            MultiOps::union(predecessors.iter().map(|p| path_nodes_docids.get(p)));
+            MultiOps::union(predecessors.iter().map(|p| &path_nodes_docids[slot(p)]));
+        ctx.metrics.bitmap_unions += 1;
 
         let node_docids = match &node.data {
+            // No path leads to this node with documents, its term docids are not needed,
+            // and its successors will be skipped as well.
+            QueryNodeData::Term(_) if predecessors_docids.is_empty() => predecessors_docids,
             QueryNodeData::Term(LocatedQueryTermSubset {
                 term_subset,
                 positions: _,
                 term_ids: _,
-            }) => {
-                let node_docids = compute_query_term_subset_docids(ctx, term_subset)?;
-                predecessors_docids & node_docids
-            }
-            QueryNodeData::Deleted => {
-                panic!()
-            }
+            }) => ctx.get_query_term_subset_docids_within(term_subset, &predecessors_docids)?,
+            // A deleted node matches no documents, the paths going through it are ignored.
+            QueryNodeData::Deleted => RoaringBitmap::new(),
             QueryNodeData::Start => universe.clone(),
             QueryNodeData::End => {
                 return Ok(predecessors_docids);
             }
         };
         nodes_resolved.insert(node_id);
-        *path_nodes_docids.get_mut(node_id) = node_docids;
+        path_nodes_docids[slot(node_id)] = node_docids;
 
         for succ in node.successors.iter() {
             if !next_nodes_to_visit.contains(&succ) && !nodes_resolved.contains(succ) {
@@ -169,26 +492,39 @@ pub fn compute_query_graph_docids(
         }
 
         for prec in node.predecessors.iter() {
-            if q.nodes.get(prec).successors.is_subset(&nodes_resolved) {
-                path_nodes_docids.get_mut(prec).clear();
+            if q.nodes.get(prec).successors.is_subset(nodes_resolved) {
+                path_nodes_docids[slot(prec)].clear();
             }
         }
     }
-    panic!()
+    // The end node is not reachable from the root node, no document matches the graph.
+    Ok(RoaringBitmap::new())
 }
 
 pub fn compute_phrase_docids(
     ctx: &mut SearchContext,
     phrase: Interned<Phrase>,
 ) -> Result<RoaringBitmap> {
-    let Phrase { words } = ctx.phrase_interner.get(phrase).clone();
+    let Phrase { words, stop_words, derived, prefix } = ctx.phrase_interner.get(phrase).clone();
 
     if words.is_empty() {
         return Ok(RoaringBitmap::new());
     }
+    let last = words.len() - 1;
     let mut candidates = RoaringBitmap::new();
-    for word in words.iter().flatten().copied() {
-        if let Some(word_docids) = ctx.word_docids(Word::Original(word))? {
+    for (index, word) in words.iter().enumerate() {
+        let Some(word) = *word else { continue };
+        let word_docids = if prefix && index == last {
+            // the pairs ending with the prefix check that the documents contain it
+            if words[..index].iter().any(Option::is_some) {
+                continue;
+            }
+            phrase_prefix_docids(ctx, word)?
+        } else {
+            let word = if derived { Word::ZeroTypoDerived(word) } else { Word::Original(word) };
+            ctx.word_docids(word)?
+        };
+        if let Some(word_docids) = word_docids {
             candidates |= word_docids;
         } else {
             return Ok(RoaringBitmap::new());
@@ -197,7 +533,7 @@ pub fn compute_phrase_docids(
 
     let winsize = words.len().min(3);
 
-    for win in words.windows(winsize) {
+    for (start, win) in words.windows(winsize).enumerate() {
         // Get all the documents with the matching distance for each word pairs.
         let mut bitmaps = Vec::with_capacity(winsize.pow(2));
         for (offset, &s1) in win
@@ -211,8 +547,9 @@ pub fn compute_phrase_docids(
                 .enumerate()
                 .filter_map(|(index, word)| word.as_ref().map(|word| (index, word)))
             {
+                let s2_is_prefix = prefix && start + offset + 1 + dist == last;
                 if dist == 0 {
-                    match ctx.get_db_word_pair_proximity_docids(s1, s2, 1)? {
+                    match phrase_pair_docids(ctx, s1, s2, 1, s2_is_prefix)? {
                         Some(m) => bitmaps.push(m),
                         // If there are no documents for this pair, there will be no
                         // results for the phrase query.
@@ -222,7 +559,7 @@ pub fn compute_phrase_docids(
                     let mut bitmap = RoaringBitmap::new();
                     for dist in 0..=dist {
                         if let Some(m) =
-                            ctx.get_db_word_pair_proximity_docids(s1, s2, dist as u8 + 1)?
+                            phrase_pair_docids(ctx, s1, s2, dist as u8 + 1, s2_is_prefix)?
                         {
                             bitmap |= m;
                         }
@@ -248,5 +585,269 @@ pub fn compute_phrase_docids(
             }
         }
     }
+
+    if !stop_words.is_empty() && !candidates.is_empty() {
+        candidates = phrase_stop_words_docids(ctx, &words, &stop_words, prefix, candidates)?;
+    }
     Ok(candidates)
 }
+
+/// Returns the candidates in which the words of the phrase, its stop words included,
+/// follow each other in one of the searched attributes.
+///
+/// The stop words are not indexed, so the phrase is first resolved from its other words and
+/// the remaining candidates are then checked against their attributes, tokenized with the
+/// settings of the index. A query ending inside the phrase matches its last word as a prefix.
+fn phrase_stop_words_docids(
+    ctx: &mut SearchContext,
+    words: &[Option<Interned<String>>],
+    stop_words: &[Option<Interned<String>>],
+    prefix: bool,
+    candidates: RoaringBitmap,
+) -> Result<RoaringBitmap> {
+    let phrase: Vec<String> = words
+        .iter()
+        .zip(stop_words)
+        .filter_map(|(word, stop_word)| word.or(*stop_word))
+        .map(|word| ctx.word_interner.get(word).to_owned())
+        .collect();
+
+    let mut tokbuilder = TokenizerBuilder::new();
+    let index_stop_words = ctx.index.stop_words(ctx.txn)?;
+    if let Some(ref index_stop_words) = index_stop_words {
+        tokbuilder.stop_words(index_stop_words);
+    }
+
+    let separators = ctx.index.allowed_separators(ctx.txn)?;
+    let separators: Option<Vec<_>> =
+        separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
+    if let Some(ref separators) = separators {
+        tokbuilder.separators(separators);
+    }
+
+    let dictionary = ctx.index.dictionary(ctx.txn)?;
+    let dictionary: Option<Vec<_>> =
+        dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
+    if let Some(ref dictionary) = dictionary {
+        tokbuilder.words_dict(dictionary);
+    }
+
+    let script_lang_map = ctx.index.script_language(ctx.txn)?;
+    if !script_lang_map.is_empty() {
+        tokbuilder.allow_list(&script_lang_map);
+    }
+
+    let tokenizer = tokbuilder.build();
+    let number_normalization = ctx.index.number_normalization(ctx.txn)?;
+
+    let fields_ids_map = ctx.index.fields_ids_map(ctx.txn)?;
+    let all_fields_ids: Vec<_> = fields_ids_map.ids().collect();
+    let searchable_fields_ids = ctx.index.searchable_fields_ids(ctx.txn)?;
+    let is_searched = |fid: FieldId| {
+        searchable_fields_ids.as_ref().map_or(true, |fids| fids.contains(&fid))
+            && ctx.restricted_fids.as_ref().map_or(true, |restricted| restricted.contains(&fid))
+    };
+
+    let mut docids = RoaringBitmap::new();
+    let mut buffer = String::new();
+    for result in ctx.index.iter_documents(ctx.txn, candidates.iter())? {
+        let (docid, document) = result?;
+        let document = obkv_to_json(&all_fields_ids, &fields_ids_map, document.reader())?;
+        for (field, value) in flatten_serde_json::flatten(&document) {
+            if !fields_ids_map.id(&field).map_or(false, &is_searched) {
+                continue;
+            }
+            buffer.clear();
+            let Some(text) = json_to_string(&value, &mut buffer) else { continue };
+            if text_contains_phrase(&tokenizer, text, &phrase, prefix, number_normalization) {
+                docids.insert(docid);
+                break;
+            }
+        }
+    }
+
+    Ok(docids)
+}
+
+/// Returns whether the words of the phrase follow each other in the text, without any hard
+/// separator between them, the last one being possibly a prefix.
+fn text_contains_phrase(
+    tokenizer: &Tokenizer,
+    text: &str,
+    phrase: &[String],
+    prefix: bool,
+    number_normalization: bool,
+) -> bool {
+    let contains_phrase = |text_words: &[String]| {
+        text_words.windows(phrase.len()).any(|window| {
+            window.iter().zip(phrase).enumerate().all(|(index, (text_word, word))| {
+                if prefix && index == phrase.len() - 1 {
+                    text_word.starts_with(word.as_str())
+                } else {
+                    text_word == word
+                }
+            })
+        })
+    };
+
+    let mut text_words = Vec::new();
+    for token in tokenizer.tokenize(text) {
+        let token = if number_normalization { normalize_number(token) } else { token };
+        match token.kind {
+            TokenKind::Word | TokenKind::StopWord if !token.lemma().is_empty() => {
+                text_words.push(token.lemma().to_string());
+            }
+            TokenKind::Separator(SeparatorKind::Hard) => {
+                if contains_phrase(&text_words) {
+                    return true;
+                }
+                text_words.clear();
+            }
+            _ => (),
+        }
+    }
+    contains_phrase(&text_words)
+}
+
+/// Returns the documents in which the second word follows the first one at the given proximity,
+/// the second word being possibly the prefix ending a phrase.
+fn phrase_pair_docids(
+    ctx: &mut SearchContext,
+    word1: Interned<String>,
+    word2: Interned<String>,
+    proximity: u8,
+    word2_is_prefix: bool,
+) -> Result<Option<RoaringBitmap>> {
+    if word2_is_prefix {
+        let docids = ctx.get_db_word_prefix_pair_proximity_docids(word1, word2, proximity)?;
+        Ok(docids.filter(|docids| !docids.is_empty()))
+    } else {
+        ctx.get_db_word_pair_proximity_docids(word1, word2, proximity)
+    }
+}
+
+/// Returns the documents containing a word starting with the prefix ending a phrase.
+fn phrase_prefix_docids(
+    ctx: &mut SearchContext,
+    prefix: Interned<String>,
+) -> Result<Option<RoaringBitmap>> {
+    // the prefixes missing from the prefix databases are resolved from the words FST
+    ctx.word_prefix_docids(Word::Original(prefix))
+}
+
+/// Returns the documents in which the phrase starts at the end of a searchable attribute
+/// and continues at the beginning of the next one, in the searchable attributes order.
+///
+/// The pairs of words are never indexed across attributes, so each side of the boundary is
+/// resolved as its own phrase. The words on each side must belong to their attribute, the
+/// first word after the boundary must be at the relative position 0 of its attribute, and the
+/// last word before it must be at the last relative position of its attribute, i.e. at the
+/// absolute positions `absolute_from_relative_position(fid, word_count - 1)` and
+/// `absolute_from_relative_position(next_fid, 0)`. Like the other phrases, the matches are
+/// approximate: the positions are checked against all the attributes of the document.
+fn compute_phrase_across_fields_docids(
+    ctx: &mut SearchContext,
+    phrase: Interned<Phrase>,
+) -> Result<RoaringBitmap> {
+    let Phrase { words, stop_words, derived, prefix } = ctx.phrase_interner.get(phrase).clone();
+
+    let searchable_fields_ids: Vec<_> = match ctx.index.searchable_fields_ids(ctx.txn)? {
+        Some(fids) => fids,
+        None => ctx.index.fields_ids_map(ctx.txn)?.ids().collect(),
+    };
+
+    let mut docids = RoaringBitmap::new();
+    for boundary in 1..words.len() {
+        let (before, after) = words.split_at(boundary);
+        // the stop words are not indexed, they can't be used to locate the boundary
+        let (Some(last_before), Some(first_after)) = (before[before.len() - 1], after[0]) else {
+            continue;
+        };
+
+        // each side only keeps the stop words it contains
+        let side_stop_words = |side: &[Option<Interned<String>>]| {
+            if side.iter().any(Option::is_some) {
+                side.to_vec()
+            } else {
+                Vec::new()
+            }
+        };
+        let (stop_words_before, stop_words_after) =
+            if stop_words.is_empty() { (&[][..], &[][..]) } else { stop_words.split_at(boundary) };
+
+        let before_phrase = ctx.phrase_interner.insert(Phrase {
+            words: before.to_vec(),
+            stop_words: side_stop_words(stop_words_before),
+            derived,
+            prefix: false,
+        });
+        let after_phrase = ctx.phrase_interner.insert(Phrase {
+            words: after.to_vec(),
+            stop_words: side_stop_words(stop_words_after),
+            derived,
+            prefix,
+        });
+        let mut candidates = compute_phrase_docids(ctx, before_phrase)?;
+        if !candidates.is_empty() {
+            candidates &= compute_phrase_docids(ctx, after_phrase)?;
+        }
+        if !candidates.is_empty() {
+            candidates &= ctx.get_db_word_position_docids(first_after, 0)?.unwrap_or_default();
+        }
+        if candidates.is_empty() {
+            continue;
+        }
+
+        for fids in searchable_fields_ids.windows(2) {
+            let &[fid, next_fid] = fids else { continue };
+
+            let mut pair_docids = candidates.clone();
+            for word in before.iter().flatten() {
+                pair_docids &= ctx.get_db_word_fid_docids(*word, fid)?.unwrap_or_default();
+            }
+            // a prefix ending the phrase is not a word of the attribute
+            let after_words = if prefix { &after[..after.len() - 1] } else { after };
+            for word in after_words.iter().flatten() {
+                pair_docids &= ctx.get_db_word_fid_docids(*word, next_fid)?.unwrap_or_default();
+            }
+            if !pair_docids.is_empty() {
+                pair_docids &= word_at_field_end_docids(ctx, last_before, fid)?;
+            }
+            docids |= pair_docids;
+        }
+    }
+
+    Ok(docids)
+}
+
+/// Returns the documents in which the attribute ends with the word.
+///
+/// The word count of the attributes is only known when they are short, for the long ones
+/// we only check that they contain the word.
+fn word_at_field_end_docids(
+    ctx: &mut SearchContext,
+    word: Interned<String>,
+    fid: u16,
+) -> Result<RoaringBitmap> {
+    let word_fid_docids = ctx.get_db_word_fid_docids(word, fid)?.unwrap_or_default();
+
+    let mut counted_docids = RoaringBitmap::new();
+    let mut docids = RoaringBitmap::new();
+    for result in
+        ctx.index.field_id_word_count_docids.range(ctx.txn, &((fid, 1)..=(fid, u8::MAX)))?
+    {
+        let ((_, count), count_docids) = result?;
+        let count_docids = count_docids & &word_fid_docids;
+        if count_docids.is_empty() {
+            continue;
+        }
+        let last_position = bucketed_position(count as u16 - 1);
+        if let Some(word_position_docids) = ctx.get_db_word_position_docids(word, last_position)? {
+            docids |= &count_docids & word_position_docids;
+        }
+        counted_docids |= count_docids;
+    }
+    docids |= word_fid_docids - counted_docids;
+
+    Ok(docids)
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use fxhash::FxHashMap;
+use heed::types::{Bytes, Str};
+use heed::{RoTxn, RwTxn};
+use once_cell::sync::Lazy;
+
+use super::limits;
+use crate::index::main_key;
+use crate::{Index, Result, BEU64};
+
+/// The size of the memory pages of the usual systems.
+const PAGE_SIZE: usize = 4096;
+
+/// The docids of the words or of the prefixes, by their key in the database.
+pub type SharedDocids = Arc<FxHashMap<Box<[u8]>, Box<[u8]>>>;
+
+/// The shared caches of the warmed up indexes, by the path of their environment.
+static SHARED_SEARCH_CACHES: Lazy<RwLock<HashMap<PathBuf, Arc<SharedSearchCache>>>> =
+    Lazy::new(Default::default);
+
+/// The docids read by the warm-up of an index, consulted by all the searches of the process
+/// before looking up the databases.
+///
+/// A warm-up writes a random stamp in the index along with the cache, and every write
+/// transaction deletes it. The cache is only used by the transactions reading this stamp,
+/// i.e. by the snapshots of the index the cache was read from.
+#[derive(Default)]
+pub struct SharedSearchCache {
+    stamp: u64,
+    pub word_docids: SharedDocids,
+    pub exact_word_docids: SharedDocids,
+    pub word_prefix_docids: SharedDocids,
+    pub exact_word_prefix_docids: SharedDocids,
+}
+
+/// The number of entries read by a warm-up.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WarmUpStats {
+    pub word_entries: u64,
+    pub prefix_entries: u64,
+}
+
+impl SharedSearchCache {
+    /// The cache of the index, if it was read from the snapshot of the transaction.
+    pub fn of(index: &Index, rtxn: &RoTxn) -> Result<Option<Arc<Self>>> {
+        let Some(stamp) =
+            index.main.remap_types::<Str, BEU64>().get(rtxn, main_key::SEARCH_CACHE_STAMP_KEY)?
+        else {
+            return Ok(None);
+        };
+        let caches = SHARED_SEARCH_CACHES.read().unwrap();
+        Ok(caches.get(index.path()).filter(|cache| cache.stamp == stamp).cloned())
+    }
+
+    /// Forgets the cache of the index, its stamp must be deleted in the same transaction.
+    pub(crate) fn invalidate(index: &Index) {
+        SHARED_SEARCH_CACHES.write().unwrap().remove(index.path());
+    }
+
+    pub(crate) fn warm_up(
+        index: &Index,
+        wtxn: &mut RwTxn,
+        prefixes: &[String],
+        populate: bool,
+    ) -> Result<WarmUpStats> {
+        let mut stats = WarmUpStats::default();
+        let mut cache = SharedSearchCache { stamp: rand::random(), ..Default::default() };
+        let SharedSearchCache {
+            stamp: _,
+            word_docids,
+            exact_word_docids,
+            word_prefix_docids,
+            exact_word_prefix_docids,
+        } = &mut cache;
+
+        for prefix in prefixes {
+            for (db, shared) in [
+                (index.word_docids.remap_types::<Bytes, Bytes>(), &mut *word_docids),
+                (index.exact_word_docids.remap_types::<Bytes, Bytes>(), &mut *exact_word_docids),
+            ] {
+                stats.word_entries += read_entries(wtxn, db, prefix, populate, shared)?;
+            }
+            for (db, shared) in [
+                (index.word_prefix_docids.remap_types::<Bytes, Bytes>(), &mut *word_prefix_docids),
+                (
+                    index.exact_word_prefix_docids.remap_types::<Bytes, Bytes>(),
+                    &mut *exact_word_prefix_docids,
+                ),
+            ] {
+                stats.prefix_entries += read_entries(wtxn, db, prefix, populate, shared)?;
+            }
+        }
+
+        if populate {
+            index.main.remap_types::<Str, BEU64>().put(
+                wtxn,
+                main_key::SEARCH_CACHE_STAMP_KEY,
+                &cache.stamp,
+            )?;
+            SHARED_SEARCH_CACHES.write().unwrap().insert(index.path().to_owned(), Arc::new(cache));
+        }
+
+        Ok(stats)
+    }
+}
+
+/// Reads the entries of the database starting with the prefix, as many as the words
+/// a search derives from a prefix, to pull their pages in the memory of the system.
+fn read_entries(
+    rtxn: &RoTxn,
+    db: heed::Database<Bytes, Bytes>,
+    prefix: &str,
+    populate: bool,
+    shared: &mut SharedDocids,
+) -> Result<u64> {
+    let mut count = 0;
+    for result in db.prefix_iter(rtxn, prefix.as_bytes())?.take(limits::MAX_PREFIX_COUNT) {
+        let (key, value) = result?;
+        if populate {
+            Arc::make_mut(shared).insert(key.into(), value.into());
+        } else {
+            // the bytes are only read for the system to load their pages
+            std::hint::black_box(value.iter().step_by(PAGE_SIZE).fold(0, |a, b| a ^ b));
+        }
+        count += 1;
+    }
+    Ok(count)
+}
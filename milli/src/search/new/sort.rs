@@ -3,11 +3,12 @@ use roaring::RoaringBitmap;
 
 use super::logger::SearchLogger;
 use super::{RankingRule, RankingRuleOutput, RankingRuleQueryTrait, SearchContext};
+use crate::collation::Collator;
 use crate::heed_codec::facet::{FacetGroupKeyCodec, OrderedF64Codec};
-use crate::heed_codec::{BytesRefCodec, StrRefCodec};
+use crate::heed_codec::BytesRefCodec;
 use crate::score_details::{self, ScoreDetails};
-use crate::search::facet::{ascending_facet_sort, descending_facet_sort};
-use crate::{FieldId, Index, Result};
+use crate::search::facet::{ascending_facet_sort, collated_facet_sort, descending_facet_sort};
+use crate::{FieldId, Index, LocalizedAttributesRule, Result};
 
 pub trait RankingRuleOutputIter<'ctx, Query> {
     fn next_bucket(&mut self) -> Result<Option<RankingRuleOutput<Query>>>;
@@ -45,10 +46,22 @@ impl<'ctx, Query> RankingRuleOutputIter<'ctx, Query> for RankingRuleOutputIterWr
 // new impl ignores docs removed in (2), which is a missed perf opt issue, see `next_bucket`
 // this perf problem is P2
 // mostly happens when many documents map to the same distinct attribute value.
+/// The ranking rule sorting the documents by the values of a field.
+///
+/// The numbers come before the strings in both directions. A document with several values
+/// is ranked by its lowest value in an ascending sort and by its highest in a descending one:
+/// the facet values are iterated in the order of the sort and a document is only returned
+/// in the bucket of the first of its values. The documents without any value, including the
+/// empty arrays, are returned together in the last bucket.
+///
+/// The fields of nested objects are sorted by their dotted path, e.g. `author.name`, which is
+/// the name of the field in the flattened documents and in the fields ids map.
 pub struct Sort<'ctx, Query> {
     field_name: String,
     field_id: Option<FieldId>,
     is_ascending: bool,
+    /// The alphabetical order of the string facet values.
+    collator: Collator,
     original_query: Option<Query>,
     iter: Option<RankingRuleOutputIterWrapper<'ctx, Query>>,
     must_redact: bool,
@@ -63,11 +76,14 @@ impl<'ctx, Query> Sort<'ctx, Query> {
         let fields_ids_map = index.fields_ids_map(rtxn)?;
         let field_id = fields_ids_map.id(&field_name);
         let must_redact = Self::must_redact(index, rtxn, &field_name)?;
+        let rules = index.localized_attributes_rules(rtxn)?.unwrap_or_default();
+        let collator = LocalizedAttributesRule::collator_for(&rules, &field_name);
 
         Ok(Self {
             field_name,
             field_id,
             is_ascending,
+            collator,
             original_query: None,
             iter: None,
             must_redact,
@@ -106,37 +122,30 @@ impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for Sort<'ctx,
                     .facet_id_string_docids
                     .remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
 
-                let (number_iter, string_iter) = if self.is_ascending {
-                    let number_iter = ascending_facet_sort(
+                let number_iter = if self.is_ascending {
+                    itertools::Either::Left(ascending_facet_sort(
                         ctx.txn,
                         number_db,
                         field_id,
                         parent_candidates.clone(),
-                    )?;
-                    let string_iter = ascending_facet_sort(
-                        ctx.txn,
-                        string_db,
-                        field_id,
-                        parent_candidates.clone(),
-                    )?;
-
-                    (itertools::Either::Left(number_iter), itertools::Either::Left(string_iter))
+                    )?)
                 } else {
-                    let number_iter = descending_facet_sort(
+                    itertools::Either::Right(descending_facet_sort(
                         ctx.txn,
                         number_db,
                         field_id,
                         parent_candidates.clone(),
-                    )?;
-                    let string_iter = descending_facet_sort(
-                        ctx.txn,
-                        string_db,
-                        field_id,
-                        parent_candidates.clone(),
-                    )?;
-
-                    (itertools::Either::Right(number_iter), itertools::Either::Right(string_iter))
+                    )?)
                 };
+                // the string facet values are sorted alphabetically rather than by their bytes
+                let string_iter = collated_facet_sort(
+                    ctx.txn,
+                    string_db,
+                    field_id,
+                    parent_candidates.clone(),
+                    self.collator,
+                    self.is_ascending,
+                );
                 let number_iter = number_iter.map(|r| -> Result<_> {
                     let (docids, bytes) = r?;
                     Ok((
@@ -150,13 +159,8 @@ impl<'ctx, Query: RankingRuleQueryTrait> RankingRule<'ctx, Query> for Sort<'ctx,
                     ))
                 });
                 let string_iter = string_iter.map(|r| -> Result<_> {
-                    let (docids, bytes) = r?;
-                    Ok((
-                        docids,
-                        serde_json::Value::String(
-                            StrRefCodec::bytes_decode(bytes).expect("some string").to_owned(),
-                        ),
-                    ))
+                    let (docids, value) = r?;
+                    Ok((docids, serde_json::Value::String(value)))
                 });
 
                 let query_graph = parent_query.clone();
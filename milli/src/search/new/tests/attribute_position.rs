@@ -194,3 +194,46 @@ fn test_attribute_position_ngrams() {
     let document_ids_scores: Vec<_> = documents_ids.iter().zip(document_scores).collect();
     insta::assert_snapshot!(format!("{document_ids_scores:#?}"));
 }
+
+#[test]
+fn test_attribute_position_logarithmic_bands() {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_criteria(vec![Criterion::Attribute]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            {
+                "id": 0,
+                "text": "a a a a a a a a a a a a a a a a a a a a quick",
+            },
+            {
+                "id": 1,
+                "text": "a a a quick",
+            },
+            {
+                "id": 2,
+                "text": "a a quick",
+            },
+        ]))
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("quick");
+    s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
+
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+
+    // positions 2 and 3 are in the same band, position 20 is in a further one
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 2, 0]");
+    assert_eq!(document_scores[0], document_scores[1]);
+    assert_ne!(document_scores[1], document_scores[2]);
+}
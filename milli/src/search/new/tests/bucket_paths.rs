@@ -0,0 +1,95 @@
+/*!
+This module tests the bucket paths returned by the bucket sort, i.e. the bucket
+of each ranking rule a returned document went through.
+
+1. the paths are empty when they are not requested
+2. each step contains the index and size of the bucket returned by the ranking rule
+3. documents removed by the distinct attribute do not appear in the paths
+4. the paths are empty when there is no ranking rule to apply
+*/
+
+use big_s::S;
+use maplit::hashset;
+
+use crate::index::tests::TempIndex;
+use crate::score_details::ScoringStrategy;
+use crate::{BucketStep, Criterion, Search, SearchResult};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_sortable_fields(hashset! { S("rank"), S("score"), S("letter") });
+            s.set_distinct_field("letter".to_owned());
+            s.set_criteria(vec![Criterion::Asc(S("rank")), Criterion::Desc(S("score"))]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "letter": "A", "rank": 1, "score": 10 },
+            { "id": 1, "letter": "A", "rank": 1, "score": 5 },
+            { "id": 2, "letter": "B", "rank": 1, "score": 7 },
+            { "id": 3, "letter": "C", "rank": 2, "score": 1 },
+            { "id": 4, "letter": "C", "rank": 2, "score": 3 },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn step(ranking_rule: &str, bucket: usize, bucket_size: u64) -> BucketStep {
+    BucketStep { ranking_rule: ranking_rule.to_owned(), bucket, bucket_size }
+}
+
+#[test]
+fn test_bucket_paths_not_requested() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    let SearchResult { documents_ids, document_bucket_paths, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2, 4]");
+    assert!(document_bucket_paths.is_empty());
+}
+
+#[test]
+fn test_bucket_paths_two_rules_with_distinct() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    s.bucket_paths(true);
+    let SearchResult { documents_ids, document_bucket_paths, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2, 4]");
+    assert_eq!(
+        document_bucket_paths,
+        vec![
+            vec![step("rank:asc", 0, 3), step("score:desc", 0, 1)],
+            vec![step("rank:asc", 0, 3), step("score:desc", 1, 1)],
+            vec![step("rank:asc", 1, 2), step("score:desc", 0, 1)],
+        ]
+    );
+}
+
+#[test]
+fn test_bucket_paths_no_ranking_rules() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::Words]);
+        })
+        .unwrap();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    s.bucket_paths(true);
+    let SearchResult { documents_ids, document_bucket_paths, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2, 3]");
+    assert_eq!(document_bucket_paths, vec![Vec::<BucketStep>::new(); 3]);
+}
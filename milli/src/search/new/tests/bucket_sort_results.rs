@@ -0,0 +1,179 @@
+/*!
+This module tests that the pages returned by the bucket sort are the slices of the complete
+ranking of the documents, whatever their offset, their length, and the universe of the search:
+
+1. with and without a query, i.e. with and without ranking rules splitting the buckets
+2. with and without a sort, a distinct attribute, and a filter reducing the universe
+3. with the two tie break orders
+
+and that the documents below a ranking score threshold are dropped before the offset applies.
+*/
+
+use big_s::S;
+use maplit::hashset;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use crate::index::tests::TempIndex;
+use crate::score_details::{ScoreDetails, ScoringStrategy};
+use crate::{AscDesc, Criterion, Filter, Member, Search, SearchResult, TieBreak};
+
+const WORDS: &[&str] = &["the", "quick", "brown", "fox", "jumps", "over", "lazy", "dog"];
+const QUERIES: &[Option<&str>] =
+    &[None, Some("quick fox"), Some("the lazy dog jumps"), Some("brwn"), Some("over the qu")];
+const NB_DOCUMENTS: usize = 300;
+
+fn create_index(rng: &mut SmallRng) -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_filterable_fields(hashset! { S("rank"), S("group") });
+            s.set_sortable_fields(hashset! { S("rank") });
+            s.set_criteria(vec![
+                Criterion::Words,
+                Criterion::Typo,
+                Criterion::Proximity,
+                Criterion::Sort,
+                Criterion::Exactness,
+            ]);
+        })
+        .unwrap();
+
+    let documents: Vec<_> = (0..NB_DOCUMENTS)
+        .map(|id| {
+            let text: Vec<_> =
+                (0..rng.gen_range(1..8)).map(|_| WORDS[rng.gen_range(0..WORDS.len())]).collect();
+            serde_json::json!({
+                "id": id,
+                "text": text.join(" "),
+                "rank": rng.gen_range(0..50),
+                "group": rng.gen_range(0..20),
+            })
+        })
+        .collect();
+    index.add_documents(documents!(documents)).unwrap();
+
+    index
+}
+
+#[derive(Debug)]
+struct Params {
+    query: Option<&'static str>,
+    filter: Option<String>,
+    sort: bool,
+    distinct: bool,
+    tie_break: TieBreak,
+}
+
+impl Params {
+    fn random(rng: &mut SmallRng) -> Self {
+        Self {
+            query: QUERIES[rng.gen_range(0..QUERIES.len())],
+            filter: rng.gen_bool(0.5).then(|| format!("rank < {}", rng.gen_range(0..50))),
+            sort: rng.gen_bool(0.3),
+            distinct: rng.gen_bool(0.5),
+            tie_break: if rng.gen_bool(0.5) { TieBreak::InternalId } else { TieBreak::DocumentId },
+        }
+    }
+}
+
+fn search(index: &TempIndex, params: &Params, from: usize, length: usize) -> SearchResult {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    if let Some(query) = params.query {
+        s.query(query);
+    }
+    if let Some(filter) = &params.filter {
+        s.filter(Filter::from_str(filter).unwrap().unwrap());
+    }
+    if params.sort {
+        s.sort_criteria(vec![AscDesc::Desc(Member::Field(S("rank")))]);
+    }
+    if params.distinct {
+        s.distinct(S("group"));
+    }
+    s.tie_break(params.tie_break);
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    s.offset(from);
+    s.limit(length);
+    s.execute().unwrap()
+}
+
+#[test]
+fn test_pages_are_slices_of_the_complete_ranking() {
+    let mut rng = SmallRng::seed_from_u64(42);
+    let index = create_index(&mut rng);
+
+    for _ in 0..200 {
+        let params = Params::random(&mut rng);
+        let complete = search(&index, &params, 0, NB_DOCUMENTS);
+        let from = rng.gen_range(0..NB_DOCUMENTS + 20);
+        let length = rng.gen_range(0..40);
+        let page = search(&index, &params, from, length);
+
+        let context = format!("{params:?}, from: {from}, length: {length}");
+        let expected_ids: Vec<_> =
+            complete.documents_ids.iter().skip(from).take(length).copied().collect();
+        assert_eq!(page.documents_ids, expected_ids, "{context}");
+        let expected_scores: Vec<_> =
+            complete.document_scores.iter().skip(from).take(length).cloned().collect();
+        assert_eq!(page.document_scores, expected_scores, "{context}");
+        // the candidates are only estimated with a distinct attribute
+        if !params.distinct {
+            assert_eq!(page.candidates, complete.candidates, "{context}");
+        }
+    }
+}
+
+#[test]
+fn test_pages_above_the_ranking_score_threshold() {
+    let mut rng = SmallRng::seed_from_u64(7);
+    let index = create_index(&mut rng);
+
+    for _ in 0..200 {
+        let params = Params::random(&mut rng);
+        let threshold = rng.gen_range(0.0..=1.0);
+        let complete = search(&index, &params, 0, NB_DOCUMENTS);
+        let from = rng.gen_range(0..NB_DOCUMENTS / 4);
+        let length = rng.gen_range(0..40);
+
+        let txn = index.read_txn().unwrap();
+        let mut s = Search::new(&txn, &index);
+        if let Some(query) = params.query {
+            s.query(query);
+        }
+        if let Some(filter) = &params.filter {
+            s.filter(Filter::from_str(filter).unwrap().unwrap());
+        }
+        if params.sort {
+            s.sort_criteria(vec![AscDesc::Desc(Member::Field(S("rank")))]);
+        }
+        if params.distinct {
+            s.distinct(S("group"));
+        }
+        s.tie_break(params.tie_break);
+        s.ranking_score_threshold(threshold);
+        s.offset(from);
+        s.limit(length);
+        let page = s.execute().unwrap();
+
+        // the documents below the threshold don't count in the offset
+        let context = format!("{params:?}, threshold: {threshold}, from: {from}, length: {length}");
+        let above: Vec<_> = complete
+            .documents_ids
+            .iter()
+            .zip(&complete.document_scores)
+            .filter(|(_, scores)| ScoreDetails::global_score(scores.iter()) >= threshold)
+            .map(|(docid, _)| *docid)
+            .collect();
+        let expected_ids: Vec<_> = above.iter().skip(from).take(length).copied().collect();
+        assert_eq!(page.documents_ids, expected_ids, "{context}");
+        // all the buckets were sorted when the page isn't full
+        if !params.distinct && page.documents_ids.len() < length {
+            assert_eq!(page.candidates, above.iter().copied().collect(), "{context}");
+        }
+    }
+}
@@ -0,0 +1,86 @@
+/*!
+This module tests the sizes of the buckets recorded by the bucket stats logger:
+
+1. the stats are only recorded when they are requested
+2. the words ranking rule returns a single bucket with all the candidates for a single word query
+3. only the sizes of the first buckets are recorded, but all the buckets are counted
+*/
+
+use big_s::S;
+use maplit::hashset;
+
+use crate::index::tests::TempIndex;
+use crate::score_details::ScoringStrategy;
+use crate::{Criterion, Search, SearchResult, MAX_RECORDED_BUCKETS};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec![S("text")]);
+            s.set_sortable_fields(hashset! { S("rank") });
+            s.set_criteria(vec![Criterion::Words, Criterion::Asc(S("rank"))]);
+        })
+        .unwrap();
+
+    let documents: Vec<_> = (0..30)
+        .map(|id| {
+            let text = if id % 3 == 0 { "the knight" } else { "the red dragon" };
+            serde_json::json!({ "id": id, "text": text, "rank": id })
+        })
+        .collect();
+    index.add_documents(documents!(documents)).unwrap();
+
+    index
+}
+
+#[test]
+fn test_bucket_stats_not_requested() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("dragon");
+    let SearchResult { bucket_stats, .. } = s.execute().unwrap();
+    assert_eq!(bucket_stats, None);
+}
+
+#[test]
+fn test_bucket_stats_single_word() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("dragon");
+    s.bucket_stats(true);
+    let SearchResult { candidates, bucket_stats, .. } = s.execute().unwrap();
+    let bucket_stats = bucket_stats.unwrap();
+    assert_eq!(bucket_stats[0].ranking_rule, "words");
+    assert_eq!(bucket_stats[0].bucket_sizes, vec![candidates.len()]);
+    assert_eq!(bucket_stats[0].buckets, 1);
+    insta::assert_snapshot!(candidates.len(), @"20");
+}
+
+#[test]
+fn test_bucket_stats_capped() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    s.limit(30);
+    s.bucket_stats(true);
+    let SearchResult { bucket_stats, .. } = s.execute().unwrap();
+    let bucket_stats = bucket_stats.unwrap();
+    let ranking_rules: Vec<_> = bucket_stats.iter().map(|stats| &stats.ranking_rule).collect();
+    insta::assert_debug_snapshot!(ranking_rules, @r###"
+    [
+        "rank:asc",
+    ]
+    "###);
+    // every document has its own rank
+    assert_eq!(bucket_stats[0].bucket_sizes, vec![1; MAX_RECORDED_BUCKETS]);
+    assert_eq!(bucket_stats[0].buckets, 30);
+}
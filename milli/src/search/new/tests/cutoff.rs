@@ -1,17 +1,26 @@
 //! This module test the search cutoff and ensure a few things:
 //! 1. A basic test works and mark the search as degraded
-//! 2. A test that ensure the filters are affectively applied even with a cutoff of 0
-//! 3. A test that ensure the cutoff works well with the ranking scores
+//! 2. A degraded search still returns the requested page of documents
+//! 3. A test that ensure the filters are affectively applied even with a cutoff of 0
+//! 4. A test that ensure the cutoff works well with the ranking scores
+//! 5. A test that ensure a canceled search stops early with an error
 
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 use big_s::S;
 use maplit::hashset;
 use meili_snap::snapshot;
+use roaring::RoaringBitmap;
 
 use crate::index::tests::TempIndex;
 use crate::score_details::{ScoreDetails, ScoringStrategy};
-use crate::{Criterion, Filter, Search, TimeBudget};
+use crate::search::new::bucket_sort::bucket_sort;
+use crate::search::new::ranking_rules::{PlaceholderQuery, RankingRule, RankingRuleOutput};
+use crate::search::new::resolve_query_graph::compute_query_graph_docids;
+use crate::search::new::{DefaultSearchLogger, SearchContext, SearchLogger};
+use crate::{Criterion, Error, Filter, Result, Search, TimeBudget};
 
 fn create_index() -> TempIndex {
     let index = TempIndex::new();
@@ -65,6 +74,30 @@ fn basic_degraded_search() {
 
     let result = search.execute().unwrap();
     assert!(result.degraded);
+    assert_eq!(result.documents_ids.len(), 3);
+}
+
+#[test]
+fn degraded_search_respects_offset_and_limit() {
+    let index = create_index();
+    let rtxn = index.read_txn().unwrap();
+
+    let mut search = Search::new(&rtxn, &index);
+    search.query("hello puppy kefir");
+    search.limit(100);
+    search.time_budget(TimeBudget::new(Duration::from_millis(0)));
+    let all = search.execute().unwrap();
+    assert!(all.degraded);
+
+    let mut search = Search::new(&rtxn, &index);
+    search.query("hello puppy kefir");
+    search.offset(1);
+    search.limit(2);
+    search.time_budget(TimeBudget::new(Duration::from_millis(0)));
+    let page = search.execute().unwrap();
+    assert!(page.degraded);
+    assert_eq!(page.documents_ids, all.documents_ids[1..3]);
+    assert_eq!(page.candidates, all.candidates);
 }
 
 #[test]
@@ -427,3 +460,116 @@ fn degraded_search_and_score_details() {
     ]
     "###);
 }
+
+/// A slow ranking rule returning the documents one by one and counting the buckets it returned.
+struct SlowRankingRule {
+    buckets: Arc<AtomicUsize>,
+}
+
+impl<'ctx> RankingRule<'ctx, PlaceholderQuery> for SlowRankingRule {
+    fn id(&self) -> String {
+        S("slow")
+    }
+
+    fn start_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<PlaceholderQuery>,
+        _universe: &RoaringBitmap,
+        _query: &PlaceholderQuery,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn next_bucket(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<PlaceholderQuery>,
+        universe: &RoaringBitmap,
+    ) -> Result<Option<RankingRuleOutput<PlaceholderQuery>>> {
+        std::thread::sleep(Duration::from_millis(10));
+        self.buckets.fetch_add(1, Ordering::SeqCst);
+        Ok(universe.min().map(|docid| RankingRuleOutput {
+            query: PlaceholderQuery,
+            candidates: RoaringBitmap::from_iter([docid]),
+            score: ScoreDetails::Skipped,
+        }))
+    }
+
+    fn end_iteration(
+        &mut self,
+        _ctx: &mut SearchContext<'ctx>,
+        _logger: &mut dyn SearchLogger<PlaceholderQuery>,
+    ) {
+    }
+}
+
+#[test]
+fn canceled_search_stops_early() {
+    let index = TempIndex::new();
+    let buckets = Arc::new(AtomicUsize::new(0));
+    let canceled = Arc::new(AtomicBool::new(false));
+    let universe: RoaringBitmap = (0..100).collect();
+
+    let output = std::thread::scope(|s| {
+        let search = s.spawn(|| {
+            let rtxn = index.read_txn().unwrap();
+            let mut ctx = SearchContext::new(&index, &rtxn);
+            ctx.canceled = Some(canceled.clone());
+            let ranking_rule = SlowRankingRule { buckets: buckets.clone() };
+            bucket_sort(
+                &mut ctx,
+                vec![Box::new(ranking_rule)],
+                &PlaceholderQuery,
+                &universe,
+                0,
+                100,
+                ScoringStrategy::Skip,
+                &mut DefaultSearchLogger,
+                TimeBudget::max().with_cancellation(canceled.clone()),
+            )
+            .map(|output| output.docids)
+        });
+
+        // the client disconnects while the search is running
+        while buckets.load(Ordering::SeqCst) < 2 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        canceled.store(true, Ordering::SeqCst);
+
+        search.join().unwrap()
+    });
+
+    match output {
+        Err(Error::SearchCancelled) => (),
+        Err(error) => panic!("unexpected error: {error}"),
+        Ok(docids) => panic!("the canceled search returned {} documents", docids.len()),
+    }
+    // the ranking rule was not called for the remaining documents
+    let buckets = buckets.load(Ordering::SeqCst);
+    assert!(buckets < 10, "the search kept running after being canceled: {buckets} buckets");
+}
+
+#[test]
+fn canceled_search_stops_resolving_the_query_graph() {
+    let index = create_index();
+    let rtxn = index.read_txn().unwrap();
+    let canceled = Arc::new(AtomicBool::new(true));
+
+    let mut ctx = SearchContext::new(&index, &rtxn);
+    ctx.canceled = Some(canceled.clone());
+    let graph = ctx.query_graph("hello puppy kefir", None).unwrap().unwrap();
+    let universe = index.documents_ids(&rtxn).unwrap();
+    let bitmap_unions = ctx.metrics.bitmap_unions;
+    let result = compute_query_graph_docids(&mut ctx, &graph, &universe);
+    assert!(matches!(result, Err(Error::SearchCancelled)), "{result:?}");
+    // not a single node of the graph was resolved
+    assert_eq!(ctx.metrics.bitmap_unions, bitmap_unions);
+
+    // the token of the time budget is the one of the search context
+    let mut search = Search::new(&rtxn, &index);
+    search.query("hello puppy kefir");
+    search.time_budget(TimeBudget::max().with_cancellation(canceled));
+    let result = search.execute();
+    assert!(matches!(result, Err(Error::SearchCancelled)), "{:?}", result.map(|r| r.documents_ids));
+}
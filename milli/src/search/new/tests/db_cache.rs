@@ -0,0 +1,179 @@
+/*!
+This module tests the batched lookups of the database cache:
+
+1. the docids of words looked up in one batch are the same as the ones looked up one by one
+2. the number of docids of words kept in the cache does not exceed its capacity
+3. bounding the cache does not change the results of a search
+*/
+
+use std::borrow::Cow;
+
+use big_s::S;
+use maplit::hashset;
+use roaring::RoaringBitmap;
+
+use crate::index::tests::TempIndex;
+use crate::search::new::interner::Interned;
+use crate::search::new::Word;
+use crate::{CboRoaringBitmapCodec, Criterion, SearchContext};
+
+type WordKind = fn(Interned<String>) -> Word;
+
+const WORDS: [(&str, WordKind); 9] = [
+    ("sun", Word::Original),
+    ("sunflower", Word::Derived),
+    ("sunny", Word::ZeroTypoDerived),
+    ("zzz", Word::Original),
+    ("sun", Word::ZeroTypoDerived),
+    ("moon", Word::Derived),
+    ("moonlight", Word::Original),
+    ("sun", Word::Derived),
+    ("monday", Word::ZeroTypoDerived),
+];
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec![S("title"), S("text")]);
+            s.set_exact_attributes(hashset! { S("title") });
+            s.set_criteria(vec![Criterion::Words, Criterion::Typo]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "title": "sun", "text": "the sun is shining" },
+            { "id": 1, "title": "flowers", "text": "a sunflower in the sun" },
+            { "id": 2, "title": "sunny", "text": "sunny monday" },
+            { "id": 3, "title": "night", "text": "moonlight sunset" },
+            { "id": 4, "title": "moon", "text": "the moon and the sun" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn intern(ctx: &mut SearchContext, words: &[(&str, WordKind)]) -> Vec<Word> {
+    words.iter().map(|(word, kind)| kind(ctx.word_interner.insert(word.to_string()))).collect()
+}
+
+fn union(slices: Vec<Cow<[u8]>>) -> RoaringBitmap {
+    slices
+        .iter()
+        .map(|bytes| CboRoaringBitmapCodec::deserialize_from(bytes).unwrap())
+        .fold(RoaringBitmap::new(), |acc, docids| acc | docids)
+}
+
+#[test]
+fn test_batched_words_docids() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+    let searchable = [S("text")];
+
+    for restricted in [false, true] {
+        let mut expected = RoaringBitmap::new();
+        for word in &WORDS {
+            let mut ctx = SearchContext::new(&index, &txn);
+            if restricted {
+                ctx.searchable_attributes(&searchable).unwrap();
+            }
+            let words = intern(&mut ctx, &[*word]);
+            let docids = ctx.word_docids(words[0]).unwrap().unwrap_or_default();
+
+            // a batch of a single word, served by the database then by the cache
+            let mut batch_ctx = SearchContext::new(&index, &txn);
+            if restricted {
+                batch_ctx.searchable_attributes(&searchable).unwrap();
+            }
+            let words = intern(&mut batch_ctx, &[*word]);
+            for _ in 0..2 {
+                let batched = union(batch_ctx.words_docids_bytes(&words).unwrap());
+                assert_eq!(batched, docids, "{} restricted: {restricted}", word.0);
+            }
+            expected |= docids;
+        }
+
+        let mut ctx = SearchContext::new(&index, &txn);
+        if restricted {
+            ctx.searchable_attributes(&searchable).unwrap();
+        }
+        let words = intern(&mut ctx, &WORDS);
+        let docids = union(ctx.words_docids_bytes(&words).unwrap());
+        assert_eq!(docids, expected, "restricted: {restricted}");
+
+        // the second batch is entirely served by the cache
+        let misses = ctx.metrics.database_cache_misses;
+        let docids = union(ctx.words_docids_bytes(&words).unwrap());
+        assert_eq!(docids, expected, "restricted: {restricted}");
+        assert_eq!(ctx.metrics.database_cache_misses, misses);
+    }
+}
+
+#[test]
+fn test_bounded_database_cache() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut ctx = SearchContext::new(&index, &txn);
+    ctx.database_cache_capacity(Some(2));
+    let words = intern(
+        &mut ctx,
+        &[
+            ("sun", Word::Derived),
+            ("sunflower", Word::Derived),
+            ("sunny", Word::Derived),
+            ("moon", Word::Derived),
+            ("zzz", Word::Derived),
+        ],
+    );
+
+    // a batch larger than the capacity still returns the docids of all its words
+    let batched = ctx.words_docids_bytes(&words).unwrap();
+    assert_eq!(batched.len(), 4);
+    assert_eq!(union(batched), RoaringBitmap::from_iter([0, 1, 2, 4]));
+    assert_eq!(ctx.db_cache.word_docids.len(), 2);
+
+    for word in &words {
+        ctx.words_docids_bytes(&[*word]).unwrap();
+        assert!(ctx.db_cache.word_docids.len() <= 2);
+    }
+
+    // the two most recently used words are kept
+    let misses = ctx.metrics.database_cache_misses;
+    ctx.words_docids_bytes(&words[3..]).unwrap();
+    assert_eq!(ctx.metrics.database_cache_misses, misses);
+    // the other ones were evicted
+    let docids = union(ctx.words_docids_bytes(&words[..1]).unwrap());
+    assert_eq!(ctx.metrics.database_cache_misses, misses + 1);
+    assert_eq!(docids, RoaringBitmap::from_iter([0, 1, 4]));
+    assert_eq!(ctx.db_cache.word_docids.len(), 2);
+}
+
+#[test]
+fn test_bounded_database_cache_search() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    for query in ["sun", "sunflower moon", "the sun shines", "moo", "sunny mondey"] {
+        let mut ctx = SearchContext::new(&index, &txn);
+        let universe = index.documents_ids(&txn).unwrap();
+        let graph = ctx.query_graph(query, None).unwrap().unwrap();
+        let docids = ctx.resolve_query_graph(&graph, &universe).unwrap();
+        let ranked = ctx.bucket_sort_query_graph(&graph, &universe, 0, 20).unwrap();
+
+        let mut bounded_ctx = SearchContext::new(&index, &txn);
+        bounded_ctx.database_cache_capacity(Some(1));
+        let graph = bounded_ctx.query_graph(query, None).unwrap().unwrap();
+        assert_eq!(bounded_ctx.resolve_query_graph(&graph, &universe).unwrap(), docids, "{query}");
+        assert_eq!(
+            bounded_ctx.bucket_sort_query_graph(&graph, &universe, 0, 20).unwrap(),
+            ranked,
+            "{query}"
+        );
+        assert!(bounded_ctx.db_cache.word_docids.len() <= 1);
+        assert!(bounded_ctx.db_cache.word_prefix_docids.len() <= 1);
+    }
+}
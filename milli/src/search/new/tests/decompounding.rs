@@ -0,0 +1,96 @@
+/*!
+This module tests the decompounding of the query words:
+
+1. a compound word of the query matches its words written apart, in attributes localized in a
+   decompounding locale
+2. the words of the query are not decompounded when the search is made on attributes in
+   other locales, or when the index has no decompounding locales
+3. the split words keep matching the compound words of the documents, and the reverse
+*/
+
+use std::collections::BTreeSet;
+
+use big_s::S;
+
+use crate::collation::Locale;
+use crate::index::tests::TempIndex;
+use crate::{Criterion, LocalizedAttributesRule, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index(decompounding_locales: BTreeSet<Locale>) -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec![S("text_de"), S("text_en")]);
+            s.set_criteria(vec![Criterion::Words]);
+            s.set_localized_attributes_rules(vec![
+                LocalizedAttributesRule {
+                    attribute_patterns: vec![S("text_de")],
+                    locales: vec![Locale::Deu],
+                },
+                LocalizedAttributesRule {
+                    attribute_patterns: vec![S("text_en")],
+                    locales: vec![Locale::Eng],
+                },
+            ]);
+            s.set_decompounding_locales(decompounding_locales);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text_de": "Der Hund schläft in der Hunde Hütte" },
+            { "id": 1, "text_de": "Die neue Hundehütte steht im Garten" },
+            { "id": 2, "text_de": "Eine Fahrt mit dem Donau Dampf Schiff" },
+            { "id": 3, "text_en": "We slept on the dog house boat" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn search(index: &TempIndex, query: &str, attributes: Option<&[String]>) -> Vec<u32> {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    s.query(query);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    if let Some(attributes) = attributes {
+        s.searchable_attributes(attributes);
+    }
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    documents_ids
+}
+
+#[test]
+fn test_decompounding() {
+    let index = create_index(BTreeSet::from([Locale::Deu]));
+
+    // the three words are found in the words FST, even if they don't follow each other in any pair
+    insta::assert_snapshot!(format!("{:?}", search(&index, "donaudampfschiff", None)), @"[2]");
+    // split in two words like without decompounding, and the reverse
+    insta::assert_snapshot!(format!("{:?}", search(&index, "hundehütte", None)), @"[0, 1]");
+    insta::assert_snapshot!(format!("{:?}", search(&index, "hunde hütte", None)), @"[0, 1]");
+    // too short to be decompounded
+    insta::assert_snapshot!(format!("{:?}", search(&index, "dogboat", None)), @"[]");
+
+    // the english attribute alone is not decompounded
+    let text_en = [S("text_en")];
+    insta::assert_snapshot!(format!("{:?}", search(&index, "doghouseboat", Some(&text_en))), @"[]");
+    // but the words of the german attribute are
+    let text_de = [S("text_de")];
+    insta::assert_snapshot!(format!("{:?}", search(&index, "donaudampfschiff", Some(&text_de))), @"[2]");
+}
+
+#[test]
+fn test_no_decompounding() {
+    let index = create_index(BTreeSet::new());
+
+    insta::assert_snapshot!(format!("{:?}", search(&index, "donaudampfschiff", None)), @"[]");
+    insta::assert_snapshot!(format!("{:?}", search(&index, "doghouseboat", None)), @"[]");
+    // the split words don't depend on decompounding
+    insta::assert_snapshot!(format!("{:?}", search(&index, "hundehütte", None)), @"[0, 1]");
+
+    let index = create_index(BTreeSet::from([Locale::Nld]));
+    insta::assert_snapshot!(format!("{:?}", search(&index, "donaudampfschiff", None)), @"[]");
+}
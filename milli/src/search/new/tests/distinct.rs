@@ -531,6 +531,87 @@ fn test_distinct_all_candidates() {
     insta::assert_snapshot!(format!("{candidates:?}"), @"[1, 4, 7, 8, 14, 17, 19, 20, 23, 24, 25, 26]");
 }
 
+/// Counts the documents of `docids` once per distinct value, the documents without
+/// a distinct value being all counted.
+fn brute_force_distinct_count(index: &Index, txn: &RoTxn, docids: &[u32]) -> u64 {
+    let values =
+        collect_field_values(index, txn, index.distinct_field(txn).unwrap().unwrap(), docids);
+
+    let mut unique = HashSet::new();
+    let mut count = 0;
+    for v in values {
+        if v == "__does_not_exist__" || unique.insert(v) {
+            count += 1;
+        }
+    }
+    count
+}
+
+#[test]
+fn test_distinct_exhaustive_count_no_ranking_rules() {
+    let index = create_index();
+
+    let txn = index.read_txn().unwrap();
+    let all_docids: Vec<_> = index.documents_ids(&txn).unwrap().iter().collect();
+
+    let mut s = Search::new(&txn, &index);
+    s.limit(2);
+    s.exhaustive_number_hits(true);
+
+    let SearchResult { documents_ids, candidates, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2]");
+    assert_eq!(candidates.len(), brute_force_distinct_count(&index, &txn, &all_docids));
+}
+
+#[test]
+fn test_distinct_exhaustive_count_sort() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::Sort]);
+        })
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+    let all_docids: Vec<_> = index.documents_ids(&txn).unwrap().iter().collect();
+
+    let mut s = Search::new(&txn, &index);
+    s.sort_criteria(vec![AscDesc::Desc(Member::Field(S("rank1")))]);
+    s.limit(3);
+    s.exhaustive_number_hits(true);
+
+    let SearchResult { documents_ids, candidates, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids.len(), 3);
+    assert_eq!(candidates.len(), brute_force_distinct_count(&index, &txn, &all_docids));
+
+    // the count doesn't depend on the requested page, even when it is past the last document
+    s.offset(100);
+    let SearchResult { documents_ids, candidates, .. } = s.execute().unwrap();
+    assert!(documents_ids.is_empty());
+    assert_eq!(candidates.len(), brute_force_distinct_count(&index, &txn, &all_docids));
+}
+
+#[test]
+fn test_exhaustive_count_without_distinct() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.reset_distinct_field();
+            s.set_criteria(vec![Criterion::Sort]);
+        })
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.sort_criteria(vec![AscDesc::Desc(Member::Field(S("rank1")))]);
+    s.limit(3);
+    s.exhaustive_number_hits(true);
+
+    let SearchResult { candidates, .. } = s.execute().unwrap();
+    assert_eq!(candidates.len(), index.number_of_documents(&txn).unwrap());
+}
+
 #[test]
 fn test_distinct_typo() {
     let index = create_index();
@@ -585,3 +666,267 @@ fn test_distinct_typo() {
     ]
     "###);
 }
+
+#[test]
+fn test_distinct_batched_exclusion_matches_per_document_exclusion() {
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+    use roaring::RoaringBitmap;
+
+    use crate::search::new::distinct::{apply_distinct_rule, distinct_single_docid};
+    use crate::SearchContext;
+
+    let mut rng = SmallRng::seed_from_u64(42);
+    let index = TempIndex::new();
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_distinct_field("letter".to_owned());
+        })
+        .unwrap();
+
+    // a mix of missing values, strings, numbers and arrays sharing some of their values
+    let documents: Vec<_> = (0..500)
+        .map(|id| {
+            let letter = match rng.gen_range(0..4) {
+                0 => serde_json::Value::Null,
+                1 => serde_json::json!(format!("{}", (b'A' + rng.gen_range(0..20)) as char)),
+                2 => serde_json::json!(rng.gen_range(0..20)),
+                _ => serde_json::json!([
+                    format!("{}", (b'A' + rng.gen_range(0..20)) as char),
+                    rng.gen_range(0..20)
+                ]),
+            };
+            serde_json::json!({ "id": id, "letter": letter }).as_object().unwrap().clone()
+        })
+        .collect();
+    index.add_documents(crate::documents::documents_batch_reader_from_objects(documents)).unwrap();
+
+    let txn = index.read_txn().unwrap();
+    let distinct_fid = index.fields_ids_map(&txn).unwrap().id("letter").unwrap();
+
+    for _ in 0..20 {
+        let candidates: RoaringBitmap =
+            (0..500u32).filter(|_| rng.gen_bool(0.3)).collect::<RoaringBitmap>();
+
+        // the exclusion as it was computed, one document after the other
+        let mut ctx = SearchContext::new(&index, &txn);
+        let mut expected_excluded = RoaringBitmap::new();
+        let mut expected_remaining = RoaringBitmap::new();
+        for docid in &candidates {
+            if expected_excluded.contains(docid) {
+                continue;
+            }
+            distinct_single_docid(&mut ctx, distinct_fid, docid, &mut expected_excluded).unwrap();
+            expected_remaining.push(docid);
+        }
+
+        let mut ctx = SearchContext::new(&index, &txn);
+        let output = apply_distinct_rule(&mut ctx, distinct_fid, &candidates).unwrap();
+        assert_eq!(output.remaining, expected_remaining);
+        assert_eq!(output.excluded, expected_excluded);
+
+        // the values cached by the search context give the same result
+        let output = apply_distinct_rule(&mut ctx, distinct_fid, &candidates).unwrap();
+        assert_eq!(output.remaining, expected_remaining);
+        assert_eq!(output.excluded, expected_excluded);
+    }
+}
+
+#[test]
+fn test_distinct_value_docids_are_fetched_once_per_value() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_sortable_fields(hashset! { S("rank1") });
+            s.set_distinct_field("letter".to_owned());
+            s.set_criteria(vec![Criterion::Sort]);
+        })
+        .unwrap();
+
+    // the documents with two values make the same value appear in the buckets of several ranks
+    let mut documents = vec![
+        serde_json::json!({ "id": 0, "letter": "A", "rank1": 0 }),
+        serde_json::json!({ "id": 1, "letter": ["A", "B"], "rank1": 0 }),
+        serde_json::json!({ "id": 2, "letter": "B", "rank1": 1 }),
+        serde_json::json!({ "id": 3, "letter": ["B", "C"], "rank1": 1 }),
+        serde_json::json!({ "id": 4, "letter": "C", "rank1": 2 }),
+    ];
+    documents
+        .extend((5..100).map(|id| serde_json::json!({ "id": id, "letter": "A", "rank1": id % 3 })));
+    let documents = documents.into_iter().map(|doc| doc.as_object().unwrap().clone()).collect();
+    index.add_documents(crate::documents::documents_batch_reader_from_objects(documents)).unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.sort_criteria(vec![AscDesc::Asc(Member::Field(S("rank1")))]);
+    s.limit(20);
+    s.exhaustive_number_hits(true);
+
+    let SearchResult { documents_ids, candidates, metrics, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2, 4]");
+    assert_eq!(candidates.len(), 3);
+    assert_eq!(metrics.distinct_value_docids_lookups, 3);
+
+    // same without ranking rules, where the distinct rule is applied one document at a time
+    drop(txn);
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::Words]);
+        })
+        .unwrap();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.limit(20);
+    s.exhaustive_number_hits(true);
+
+    let SearchResult { documents_ids, candidates, metrics, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2, 4]");
+    assert_eq!(candidates.len(), 3);
+    assert_eq!(metrics.distinct_value_docids_lookups, 3);
+}
+
+#[test]
+fn test_distinct_search_parameter_placeholder_no_ranking_rules() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.reset_distinct_field();
+            s.set_filterable_fields(hashset! { S("letter"), S("rank1") });
+        })
+        .unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.limit(30);
+    s.exhaustive_number_hits(true);
+    let SearchResult { documents_ids, candidates, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids.len(), 27);
+    assert_eq!(candidates.len(), 27);
+
+    s.distinct(S("letter"));
+    let SearchResult { documents_ids, candidates, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2, 5, 8, 9, 15, 18, 20, 21, 24, 25, 26]");
+    assert_eq!(candidates.len(), 12);
+
+    // the attribute of the search overrides the distinct attribute of the index
+    drop(txn);
+    index
+        .update_settings(|s| {
+            s.set_distinct_field("letter".to_owned());
+        })
+        .unwrap();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.limit(30);
+    s.distinct(S("rank1"));
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 4, 12, 13, 14]");
+
+    // only the filterable attributes can be used
+    let mut s = Search::new(&txn, &index);
+    s.distinct(S("text"));
+    let error = s.execute().unwrap_err();
+    assert!(matches!(
+        error,
+        crate::Error::UserError(crate::error::UserError::InvalidDistinctAttribute { .. })
+    ));
+}
+
+#[test]
+fn test_distinct_seen_values_strategy_on_nearly_unique_values() {
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::score_details::ScoringStrategy;
+    use crate::search::new::DistinctStrategy;
+
+    const WORDS: &[&str] = &["the", "quick", "brown", "fox", "jumps", "over", "lazy", "dog"];
+    const NB_DOCUMENTS: usize = 1000;
+
+    let mut rng = SmallRng::seed_from_u64(42);
+    let index = TempIndex::new();
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec![S("text")]);
+            s.set_sortable_fields(hashset! { S("rank") });
+            s.set_distinct_field("url".to_owned());
+            s.set_criteria(vec![Criterion::Words, Criterion::Sort, Criterion::Exactness]);
+        })
+        .unwrap();
+
+    // almost every document has its own url, a few share the url of another one or have none
+    let documents: Vec<_> = (0..NB_DOCUMENTS)
+        .map(|id| {
+            let text: Vec<_> =
+                (0..rng.gen_range(1..8)).map(|_| WORDS[rng.gen_range(0..WORDS.len())]).collect();
+            let url = match rng.gen_range(0..10) {
+                0 => serde_json::Value::Null,
+                1 => serde_json::json!(format!("https://example.com/{}", rng.gen_range(0..id + 1))),
+                _ => serde_json::json!(format!("https://example.com/{id}")),
+            };
+            serde_json::json!({ "id": id, "text": text.join(" "), "url": url, "rank": rng.gen_range(0..20) })
+                .as_object()
+                .unwrap()
+                .clone()
+        })
+        .collect();
+    index.add_documents(crate::documents::documents_batch_reader_from_objects(documents)).unwrap();
+
+    let txn = index.read_txn().unwrap();
+    let distinct_fid = index.fields_ids_map(&txn).unwrap().id("url").unwrap();
+    assert_eq!(
+        DistinctStrategy::choose(&index, &txn, distinct_fid).unwrap(),
+        DistinctStrategy::SeenValues
+    );
+
+    for _ in 0..50 {
+        let query = if rng.gen_bool(0.3) { None } else { Some("quick brown dog") };
+        let sort = rng.gen_bool(0.5);
+        let exhaustive = rng.gen_bool(0.5);
+        let offset = rng.gen_range(0..NB_DOCUMENTS / 2);
+        let limit = rng.gen_range(1..100);
+        let search = |strategy: Option<DistinctStrategy>| {
+            let mut s = Search::new(&txn, &index);
+            if let Some(query) = query {
+                s.query(query);
+            }
+            if sort {
+                s.sort_criteria(vec![AscDesc::Asc(Member::Field(S("rank")))]);
+            }
+            if let Some(strategy) = strategy {
+                s.distinct_strategy(strategy);
+            }
+            s.exhaustive_number_hits(exhaustive);
+            s.scoring_strategy(ScoringStrategy::Detailed);
+            s.offset(offset);
+            s.limit(limit);
+            s.execute().unwrap()
+        };
+
+        let context = format!("query: {query:?}, sort: {sort}, offset: {offset}, limit: {limit}");
+        let by_facet_docids = search(Some(DistinctStrategy::FacetDocids));
+        assert_eq!(by_facet_docids.metrics.distinct_seen_values_checks, 0, "{context}");
+        // the cheapest strategy is chosen from the number of values of the distinct field
+        let by_seen_values = search(None);
+        assert_eq!(by_seen_values.metrics.distinct_value_docids_lookups, 0, "{context}");
+        assert!(by_seen_values.metrics.distinct_seen_values_checks > 0, "{context}");
+
+        assert_eq!(by_facet_docids.documents_ids, by_seen_values.documents_ids, "{context}");
+        assert_eq!(by_facet_docids.document_scores, by_seen_values.document_scores, "{context}");
+        // the candidates are only estimated, but their number is exact when it is exhaustive
+        if exhaustive {
+            assert_eq!(
+                by_facet_docids.candidates.len(),
+                by_seen_values.candidates.len(),
+                "{context}"
+            );
+        }
+    }
+}
@@ -0,0 +1,278 @@
+/*!
+This module tests the search on the generated corpus shared with the benchmarks:
+
+1. Running the steps of a search one by one ranks the documents like a search does
+2. The documents resolved from the query graph are the ones matching all the terms of the query
+3. Resolving a query graph again reuses the docids of its terms
+4. Resolving a query graph whose end node is not reachable returns no documents
+5. Resolving a query graph ignores the paths going through a reachable deleted node
+6. The terms that only follow a term matching no documents are not fetched from the databases
+7. Searching again in the same context reuses the docids fetched by the first search
+8. Resolving a query graph in a small universe decodes fewer bitmaps, and finds the same documents
+*/
+
+use roaring::RoaringBitmap;
+
+use crate::documents::documents_batch_reader_from_objects;
+use crate::index::tests::TempIndex;
+use crate::search::new::query_graph::QueryNodeData;
+use crate::search::new::query_term::LocatedQueryTermSubset;
+use crate::test_utils::CorpusBuilder;
+use crate::{Search, SearchContext, SearchResult, TermsMatchingStrategy};
+
+fn create_index(corpus: &CorpusBuilder) -> TempIndex {
+    let index = TempIndex::new();
+    index.update_settings(CorpusBuilder::configure).unwrap();
+    index.add_documents(documents_batch_reader_from_objects(corpus.build())).unwrap();
+    index
+}
+
+#[test]
+fn search_steps_rank_like_search() {
+    let mut corpus = CorpusBuilder::new(1000);
+    corpus.vocabulary(500);
+    let index = create_index(&corpus);
+    let txn = index.read_txn().unwrap();
+    let universe = index.documents_ids(&txn).unwrap();
+
+    for terms in [1, 3, 8] {
+        let query = corpus.query(terms);
+
+        let mut s = Search::new(&txn, &index);
+        s.query(&query);
+        let SearchResult { documents_ids, .. } = s.execute().unwrap();
+
+        let mut ctx = SearchContext::new(&index, &txn);
+        let graph = ctx.query_graph(&query, None).unwrap().unwrap();
+        let ranked = ctx.bucket_sort_query_graph(&graph, &universe, 0, 20).unwrap();
+        assert_eq!(ranked, documents_ids, "query: {query}");
+    }
+}
+
+#[test]
+fn resolved_query_graph_is_the_universe_of_the_search() {
+    let mut corpus = CorpusBuilder::new(1000);
+    corpus.vocabulary(500);
+    let index = create_index(&corpus);
+    let txn = index.read_txn().unwrap();
+    let universe = index.documents_ids(&txn).unwrap();
+
+    for terms in [1, 3, 8] {
+        let query = corpus.query(terms);
+
+        let mut s = Search::new(&txn, &index);
+        s.query(&query);
+        s.terms_matching_strategy(TermsMatchingStrategy::All);
+        let SearchResult { candidates, .. } = s.execute().unwrap();
+
+        let mut ctx = SearchContext::new(&index, &txn);
+        let graph = ctx.query_graph(&query, None).unwrap().unwrap();
+        let docids = ctx.resolve_query_graph(&graph, &universe).unwrap();
+        assert_eq!(docids, candidates, "query: {query}");
+    }
+}
+
+#[test]
+fn resolving_a_query_graph_again_reuses_the_term_docids() {
+    let mut corpus = CorpusBuilder::new(1000);
+    corpus.vocabulary(500);
+    let index = create_index(&corpus);
+    let txn = index.read_txn().unwrap();
+    let universe = index.documents_ids(&txn).unwrap();
+    let half_universe: RoaringBitmap = universe.iter().step_by(2).collect();
+
+    for terms in [1, 3, 8] {
+        let query = corpus.query(terms);
+
+        let mut ctx = SearchContext::new(&index, &txn);
+        let graph = ctx.query_graph(&query, None).unwrap().unwrap();
+        let docids = ctx.resolve_query_graph(&graph, &universe).unwrap();
+
+        // the docids fetched from the database are forgotten, they must not be needed anymore
+        ctx.db_cache.word_docids.clear();
+        ctx.db_cache.exact_word_docids.clear();
+        ctx.db_cache.word_prefix_docids.clear();
+        ctx.db_cache.exact_word_prefix_docids.clear();
+
+        let half_docids = ctx.resolve_query_graph(&graph, &half_universe).unwrap();
+        assert_eq!(half_docids, &docids & &half_universe, "query: {query}");
+        assert!(ctx.db_cache.word_docids.is_empty(), "query: {query}");
+        assert!(ctx.db_cache.exact_word_docids.is_empty(), "query: {query}");
+        assert!(ctx.db_cache.word_prefix_docids.is_empty(), "query: {query}");
+        assert!(ctx.db_cache.exact_word_prefix_docids.is_empty(), "query: {query}");
+    }
+}
+
+#[test]
+fn resolving_a_query_graph_of_unknown_words() {
+    let mut corpus = CorpusBuilder::new(100);
+    corpus.vocabulary(50);
+    let index = create_index(&corpus);
+    let txn = index.read_txn().unwrap();
+    let universe = index.documents_ids(&txn).unwrap();
+
+    let mut ctx = SearchContext::new(&index, &txn);
+    let graph = ctx.query_graph("yyyyyyy kkkkkkk", None).unwrap().unwrap();
+    let docids = ctx.resolve_query_graph(&graph, &universe).unwrap();
+    assert!(docids.is_empty());
+}
+
+#[test]
+fn resolving_a_truncated_query_graph() {
+    let mut corpus = CorpusBuilder::new(100);
+    corpus.vocabulary(50);
+    let index = create_index(&corpus);
+    let txn = index.read_txn().unwrap();
+    let universe = index.documents_ids(&txn).unwrap();
+    let query = corpus.query(3);
+
+    let mut ctx = SearchContext::new(&index, &txn);
+    let mut graph = ctx.query_graph(&query, None).unwrap().unwrap();
+    // removing the successors of the root node with their edges disconnects the end node
+    let successors: Vec<_> = graph.nodes.get(graph.root_node).successors.iter().collect();
+    graph.remove_nodes(&successors);
+    let docids = ctx.resolve_query_graph(&graph, &universe).unwrap();
+    assert!(docids.is_empty(), "query: {query}");
+}
+
+#[test]
+fn resolving_a_query_graph_with_a_reachable_deleted_node() {
+    let mut corpus = CorpusBuilder::new(100);
+    corpus.vocabulary(50);
+    let index = create_index(&corpus);
+    let txn = index.read_txn().unwrap();
+    let universe = index.documents_ids(&txn).unwrap();
+    let query = corpus.query(3);
+
+    let mut ctx = SearchContext::new(&index, &txn);
+    let graph = ctx.query_graph(&query, None).unwrap().unwrap();
+    let successor = graph.nodes.get(graph.root_node).successors.iter().next().unwrap();
+
+    // the node is marked as deleted but keeps its edges
+    let mut deleted = graph.clone();
+    deleted.nodes.get_mut(successor).data = QueryNodeData::Deleted;
+    let docids = ctx.resolve_query_graph(&deleted, &universe).unwrap();
+
+    // which matches the same documents as removing the node with its edges
+    let mut removed = graph;
+    removed.remove_nodes(&[successor]);
+    let expected = ctx.resolve_query_graph(&removed, &universe).unwrap();
+    assert_eq!(docids, expected, "query: {query}");
+}
+
+#[test]
+fn resolving_a_query_graph_skips_the_successors_of_an_empty_term() {
+    let mut corpus = CorpusBuilder::new(100);
+    corpus.vocabulary(50);
+    let index = create_index(&corpus);
+    let txn = index.read_txn().unwrap();
+    let universe = index.documents_ids(&txn).unwrap();
+    let query = format!("yyyyyyy {}", corpus.query(1));
+
+    let mut s = Search::new(&txn, &index);
+    s.query(&query);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    let SearchResult { candidates, .. } = s.execute().unwrap();
+
+    let mut ctx = SearchContext::new(&index, &txn);
+    let graph = ctx.query_graph(&query, None).unwrap().unwrap();
+    let docids = ctx.resolve_query_graph(&graph, &universe).unwrap();
+    assert_eq!(docids, candidates, "query: {query}");
+    assert!(docids.is_empty(), "query: {query}");
+    assert!(ctx.metrics.database_cache_misses > 0, "query: {query}");
+
+    // the node of the absent word, and not the ngram starting with it
+    let absent_node = graph
+        .nodes
+        .get(graph.root_node)
+        .successors
+        .iter()
+        .find(|&node| match &graph.nodes.get(node).data {
+            QueryNodeData::Term(LocatedQueryTermSubset { term_ids, .. }) => term_ids == &(0..=0),
+            _ => false,
+        })
+        .unwrap();
+    let mut successors = 0;
+    for node in graph.nodes.get(absent_node).successors.iter() {
+        if let QueryNodeData::Term(LocatedQueryTermSubset { term_subset, .. }) =
+            &graph.nodes.get(node).data
+        {
+            // fetching the docids of the term now requires new lookups,
+            // they were not fetched during the resolution
+            let lookups = ctx.metrics.database_cache_misses;
+            let term_docids = ctx.get_query_term_subset_docids(term_subset).unwrap();
+            assert!(!term_docids.is_empty(), "query: {query}");
+            assert!(ctx.metrics.database_cache_misses > lookups, "query: {query}");
+            successors += 1;
+        }
+    }
+    assert!(successors > 0, "query: {query}");
+}
+
+#[test]
+fn searching_again_in_the_same_context_hits_the_caches() {
+    let mut corpus = CorpusBuilder::new(1000);
+    corpus.vocabulary(500);
+    let index = create_index(&corpus);
+    let txn = index.read_txn().unwrap();
+    let universe = index.documents_ids(&txn).unwrap();
+    let query = corpus.query(3);
+
+    let search = |ctx: &mut SearchContext| {
+        let graph = ctx.query_graph(&query, None).unwrap().unwrap();
+        let docids = ctx.resolve_query_graph(&graph, &universe).unwrap();
+        ctx.bucket_sort_query_graph(&graph, &docids, 0, 20).unwrap()
+    };
+
+    let mut ctx = SearchContext::new(&index, &txn);
+    let first_ranked = search(&mut ctx);
+    let first = std::mem::take(&mut ctx.metrics);
+    let second_ranked = search(&mut ctx);
+    let second = ctx.metrics;
+
+    assert_eq!(first_ranked, second_ranked, "query: {query}");
+    assert!(first.word_docids_lookups > 0, "query: {query}, metrics: {first:?}");
+    assert!(
+        second.database_cache_hits + second.term_cache_hits
+            > first.database_cache_hits + first.term_cache_hits,
+        "query: {query}, metrics: {first:?} then {second:?}"
+    );
+    assert!(
+        second.bitmap_decodes < first.bitmap_decodes,
+        "query: {query}, metrics: {first:?} then {second:?}"
+    );
+}
+
+#[test]
+fn resolving_a_query_graph_in_a_small_universe_decodes_fewer_bitmaps() {
+    let mut corpus = CorpusBuilder::new(5000);
+    corpus.vocabulary(2000);
+    let index = create_index(&corpus);
+    let txn = index.read_txn().unwrap();
+    let universe = index.documents_ids(&txn).unwrap();
+    // the last word is a prefix with many typo derivations
+    let query = corpus.query(3);
+
+    let mut ctx = SearchContext::new(&index, &txn);
+    let graph = ctx.query_graph(&query, None).unwrap().unwrap();
+    let docids = ctx.resolve_query_graph(&graph, &universe).unwrap();
+    let metrics = ctx.metrics;
+    assert!(!docids.is_empty(), "query: {query}");
+
+    let small_universe: RoaringBitmap = docids.iter().take(1).collect();
+    let mut ctx = SearchContext::new(&index, &txn);
+    let graph = ctx.query_graph(&query, None).unwrap().unwrap();
+    let small_docids = ctx.resolve_query_graph(&graph, &small_universe).unwrap();
+    let small_metrics = ctx.metrics;
+    assert_eq!(small_docids, small_universe, "query: {query}");
+    assert!(
+        small_metrics.bitmap_decodes < metrics.bitmap_decodes,
+        "query: {query}, metrics: {metrics:?} then {small_metrics:?}"
+    );
+
+    // the universe of the documents not matching the query is not saturated
+    let other_universe: RoaringBitmap = (&universe - &docids).iter().step_by(7).collect();
+    let other_universe = other_universe | &small_universe;
+    let other_docids = ctx.resolve_query_graph(&graph, &other_universe).unwrap();
+    assert_eq!(other_docids, &docids & &other_universe, "query: {query}");
+}
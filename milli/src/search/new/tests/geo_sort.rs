@@ -9,7 +9,7 @@ use maplit::hashset;
 use crate::index::tests::TempIndex;
 use crate::score_details::ScoreDetails;
 use crate::search::new::tests::collect_field_values;
-use crate::{AscDesc, Criterion, GeoSortStrategy, Member, Search, SearchResult};
+use crate::{AscDesc, Criterion, GeoSortStrategy, LatLng, Member, Search, SearchResult};
 
 fn create_index() -> TempIndex {
     let index = TempIndex::new();
@@ -307,3 +307,59 @@ fn geo_sort_without_any_geo_faceted_documents() {
     insta::assert_snapshot!(format!("{ids:?}"), @"[0, 2, 3]");
     insta::assert_snapshot!(format!("{scores:#?}"));
 }
+
+#[test]
+fn geo_point_ranking_rule_mixed_with_words_and_distinct() {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_filterable_fields(hashset! { S("_geo") });
+            s.set_distinct_field("group".to_owned());
+            s.set_criteria(vec![
+                Criterion::Words,
+                Criterion::GeoPoint { point: LatLng { lat: 0., lng: 0. }, bucket_size: 2 },
+            ]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "doggo": "jean", "group": "a", "_geo": { "lat": 0, "lng": 1 } },
+            { "id": 1, "doggo": "jean", "group": "b", "_geo": { "lat": 0, "lng": 0 } },
+            { "id": 2, "doggo": "jean", "group": "a", "_geo": { "lat": 0, "lng": 2 } },
+            { "id": 3, "doggo": "jean", "group": "c", "_geo": { "lat": 0, "lng": 3 } },
+            { "id": 4, "doggo": "jean", "group": "d" },
+            { "id": 5, "doggo": "jean bob", "group": "e", "_geo": { "lat": 0, "lng": 10 } },
+            { "id": 6, "doggo": "jean bob", "group": "f", "_geo": { "lat": 0, "lng": 5 } },
+            { "id": 7, "doggo": "jean bob", "group": "g", "_geo": { "lat": 0, "lng": 20 } },
+            { "id": 8, "doggo": "jean", "group": "h", "_geo": { "lat": 0, "lng": 4 } },
+        ]))
+        .unwrap();
+
+    let rtxn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&rtxn, &index);
+    s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
+
+    // the documents are sorted by words first, and then by buckets of two documents
+    // sorted by distance; the document 2 is removed by the distinct rule as the
+    // document 0 is in a closer bucket, and the document 4 without a geo point is last.
+    s.query("jean bob");
+    let (ids, scores) = execute_iterative_and_rtree_returns_the_same(&rtxn, &index, &mut s);
+    insta::assert_snapshot!(format!("{ids:?}"), @"[5, 6, 7, 0, 1, 3, 8, 4]");
+    let geo_values: Vec<_> = scores
+        .iter()
+        .map(|score| match &score[1] {
+            ScoreDetails::GeoSort(geo_sort) => geo_sort.value,
+            _ => panic!("expected a geo sort score"),
+        })
+        .collect();
+    insta::assert_snapshot!(format!("{geo_values:?}"), @"[Some([0.0, 5.0]), Some([0.0, 5.0]), Some([0.0, 20.0]), Some([0.0, 0.0]), Some([0.0, 0.0]), Some([0.0, 3.0]), Some([0.0, 3.0]), None]");
+
+    // the universe is restricted by the limit, the buckets must stay the same
+    s.limit(4);
+    let (ids, _) = execute_iterative_and_rtree_returns_the_same(&rtxn, &index, &mut s);
+    insta::assert_snapshot!(format!("{ids:?}"), @"[5, 6, 7, 0]");
+}
@@ -0,0 +1,107 @@
+/*!
+This module tests the maximum number of buckets of the ranking rules:
+1. past its maximum, the rest of the universe of a ranking rule is its last bucket, which is
+only sorted by the next ranking rules
+2. with a maximum of one bucket, a ranking rule doesn't sort the documents at all
+3. the maximum of the search applies to the ranking rules without a maximum in the settings
+*/
+
+use std::collections::BTreeMap;
+
+use big_s::S;
+use maplit::hashset;
+
+use crate::index::tests::TempIndex;
+use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec![S("text")]);
+            s.set_sortable_fields(hashset! { S("rank") });
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "the quick fox", "rank": 3 },
+            { "id": 1, "text": "the quick brown fox", "rank": 1 },
+            { "id": 2, "text": "the quick brown and dark fox", "rank": 2 },
+            { "id": 3, "text": "fox the quick", "rank": 0 },
+            { "id": 4, "text": "quick", "rank": 4 },
+        ]))
+        .unwrap();
+    index
+}
+
+fn search_external_ids(index: &TempIndex, max_buckets_per_rule: Option<usize>) -> Vec<String> {
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, index);
+    s.query("quick fox");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    if let Some(max_buckets) = max_buckets_per_rule {
+        s.max_buckets_per_rule(max_buckets);
+    }
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    index.external_ids_of(&txn, documents_ids).unwrap()
+}
+
+#[test]
+fn test_max_buckets_of_one_ignores_the_ranking_rule() {
+    let index = create_index();
+
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::Words, Criterion::Asc(S("rank"))]);
+        })
+        .unwrap();
+    let without_proximity = search_external_ids(&index, None);
+
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::Words, Criterion::Proximity, Criterion::Asc(S("rank"))]);
+        })
+        .unwrap();
+    let with_proximity = search_external_ids(&index, None);
+    assert_ne!(with_proximity, without_proximity);
+
+    index
+        .update_settings(|s| {
+            s.set_criteria_max_buckets(BTreeMap::from([(S("proximity"), 1)]));
+        })
+        .unwrap();
+    let capped_proximity = search_external_ids(&index, None);
+    assert_eq!(capped_proximity, without_proximity);
+
+    // the documents are sorted by proximity again once the maximum is reset
+    index.update_settings(|s| s.reset_criteria_max_buckets()).unwrap();
+    assert_eq!(search_external_ids(&index, None), with_proximity);
+}
+
+#[test]
+fn test_max_buckets_per_rule_of_the_search() {
+    let index = create_index();
+
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::Words, Criterion::Proximity, Criterion::Asc(S("rank"))]);
+            s.set_criteria_max_buckets(BTreeMap::from([(S("rank:asc"), 10)]));
+        })
+        .unwrap();
+
+    // words and proximity don't sort the documents, rank keeps its own maximum
+    let ids = search_external_ids(&index, Some(1));
+    insta::assert_debug_snapshot!(ids, @r###"
+    [
+        "3",
+        "1",
+        "2",
+        "0",
+        "4",
+    ]
+    "###);
+}
@@ -1,19 +1,45 @@
 pub mod attribute_fid;
 pub mod attribute_position;
+pub mod bucket_paths;
+pub mod bucket_sort_results;
+pub mod bucket_stats;
 pub mod cutoff;
+pub mod db_cache;
+pub mod decompounding;
 pub mod distinct;
 pub mod exactness;
+pub mod generated_corpus;
 pub mod geo_sort;
 pub mod integration;
 #[cfg(feature = "all-tokenizations")]
 pub mod language;
+pub mod max_buckets;
+pub mod negative;
 pub mod ngram_split_words;
+pub mod numeric_prefix;
+pub mod parallel_term_resolution;
+pub mod phrase_across_fields;
+pub mod phrase_prefix;
+pub mod prefix_fallback;
 pub mod proximity;
 pub mod proximity_typo;
+pub mod prune_empty_terms;
+pub mod query_graph_limit;
+pub mod random_sort;
+pub mod search_after;
+pub mod search_trace;
+pub mod settings_overlay;
+pub mod small_universe;
 pub mod sort;
+pub mod stemming;
 pub mod stop_words;
+pub mod streaming;
+pub mod strict_attributes;
+pub mod synonyms;
+pub mod tie_break;
 pub mod typo;
 pub mod typo_proximity;
+pub mod warm_up;
 pub mod words_tms;
 
 fn collect_field_values(
@@ -0,0 +1,96 @@
+/*!
+This module tests the negative operator of the query, `-word` and `-"a phrase"`:
+
+1. the documents containing a negated word are excluded, even the ones ranked first without it
+2. only the exact negated word excludes documents, not its typos nor the words it prefixes
+3. the documents containing a negated phrase are excluded, not the ones only containing its words
+4. a `-` inside a word, or followed by nothing, is a separator as any other
+*/
+
+use crate::index::tests::TempIndex;
+use crate::{Search, SearchResult};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["title".to_owned()]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "title": "laptop refurbished" },
+            { "id": 1, "title": "laptop stand" },
+            { "id": 2, "title": "gaming laptop open box" },
+            { "id": 3, "title": "laptop box open" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+/// Returns the ids of the documents in their ranking order, and whether the negative operator was used.
+fn search(index: &TempIndex, query: &str) -> (Vec<u32>, bool) {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    s.query(query);
+    let SearchResult { documents_ids, used_negative_operator, .. } = s.execute().unwrap();
+    (documents_ids, used_negative_operator)
+}
+
+#[test]
+fn test_negative_word_excludes_the_first_document() {
+    let index = create_index();
+
+    let (documents_ids, _) = search(&index, "laptop");
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 3, 2]");
+
+    let (documents_ids, used_negative_operator) = search(&index, "laptop -refurbished");
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 3, 2]");
+    assert!(used_negative_operator);
+
+    // the negated word can come first
+    let (documents_ids, _) = search(&index, "-refurbished laptop");
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 3, 2]");
+}
+
+#[test]
+fn test_negative_word_is_exact() {
+    let index = create_index();
+
+    // neither a typo nor a prefix of the word excludes it
+    for query in ["laptop -refurbishd", "laptop -refurbish"] {
+        let (documents_ids, used_negative_operator) = search(&index, query);
+        insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 3, 2]");
+        assert!(used_negative_operator, "{query}");
+    }
+}
+
+#[test]
+fn test_negative_phrase() {
+    let index = create_index();
+
+    let (documents_ids, used_negative_operator) = search(&index, "laptop -\"open box\"");
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 3]");
+    assert!(used_negative_operator);
+}
+
+#[test]
+fn test_dash_as_a_separator() {
+    let index = create_index();
+
+    // inside a word the dash separates two words searched as usual
+    let (documents_ids, used_negative_operator) = search(&index, "laptop-refurbished");
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 3, 2]");
+    assert!(!used_negative_operator);
+
+    // followed by nothing, or by a space, it negates nothing
+    for query in ["laptop -", "laptop - "] {
+        let (documents_ids, used_negative_operator) = search(&index, query);
+        insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 3, 2]");
+        assert!(!used_negative_operator, "{query}");
+    }
+}
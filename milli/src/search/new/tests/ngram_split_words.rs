@@ -15,6 +15,8 @@ This module tests the following properties:
 12. Prefix tolerance is disabled for the last word if a space follows it
 13. Ngrams cannot be formed by combining a phrase and a word or two phrases
 14. Split words are not disabled by the `disableOnAttribute` or `disableOnWords` typo settings
+15. The two words of a split word must be adjacent in the documents
+16. Ngrams cannot be formed across a hard separator
 */
 
 use crate::index::tests::TempIndex;
@@ -426,3 +428,52 @@ fn test_split_words_never_disabled() {
     ]
     "###);
 }
+
+#[test]
+fn test_split_words_are_adjacent() {
+    let index = create_index();
+    index.add_documents(documents!([{ "id": 7, "text": "the flower is in the sun" }])).unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    s.query("sunflower ");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+
+    // the document containing both `sun` and `flower`, but not next to each other, is not returned
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 2, 3, 4]");
+}
+
+#[test]
+fn test_no_ngram_across_hard_separator() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_autorize_typos(false);
+        })
+        .unwrap();
+    index.add_documents(documents!([{ "id": 7, "text": "the flower is in the sun" }])).unwrap();
+
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    s.query("sun flower ");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 3, 7]");
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    s.query("sun. flower ");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    // `sunflower` is not searched, only the documents containing both `sun` and `flower` are returned
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 7]");
+    let texts = collect_field_values(&index, &txn, "text", &documents_ids);
+    insta::assert_debug_snapshot!(texts, @r###"
+    [
+        "\"the sun flower is tall\"",
+        "\"the flower is in the sun\"",
+    ]
+    "###);
+}
@@ -0,0 +1,117 @@
+/*!
+This module tests the handling of a short number ending the query:
+
+- it is searched as an exact word instead of a prefix, so `202` does not
+  match all the years between `2020` and `2029`
+- the words ranking rule does not fetch the bitmaps of all the derivations
+- the `min_numeric_prefix_length` setting allows to disable this behavior
+*/
+
+use crate::index::tests::TempIndex;
+use crate::score_details::ScoringStrategy;
+use crate::search::new::tests::collect_field_values;
+use crate::{
+    execute_search, filtered_universe, Criterion, DefaultSearchLogger, GeoSortStrategy, Search,
+    SearchContext, SearchResult, TermsMatchingStrategy, TimeBudget,
+};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_criteria(vec![Criterion::Words]);
+        })
+        .unwrap();
+
+    let documents = (0..30)
+        .map(|i| {
+            serde_json::json!({
+                "id": i,
+                "text": format!("released in {}", 2000 + i),
+            })
+            .as_object()
+            .unwrap()
+            .clone()
+        })
+        .collect::<Vec<_>>();
+    index.add_documents(crate::documents::documents_batch_reader_from_objects(documents)).unwrap();
+    index
+}
+
+/// Returns the number of word bitmaps fetched from the database to answer the query.
+fn fetched_word_bitmaps(index: &TempIndex, query: &str) -> usize {
+    let txn = index.read_txn().unwrap();
+    let mut ctx = SearchContext::new(index, &txn);
+    let universe = filtered_universe(&ctx, &None).unwrap();
+    execute_search(
+        &mut ctx,
+        Some(query),
+        TermsMatchingStrategy::Last,
+        ScoringStrategy::Skip,
+        false,
+        universe,
+        &None,
+        GeoSortStrategy::default(),
+        0,
+        20,
+        None,
+        &mut DefaultSearchLogger,
+        &mut DefaultSearchLogger,
+        TimeBudget::max(),
+    )
+    .unwrap();
+    ctx.db_cache.word_docids.len() + ctx.db_cache.word_prefix_docids.len()
+}
+
+#[test]
+fn test_short_number_is_not_a_prefix() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("202");
+    let SearchResult { documents_ids, numeric_prefix_disabled, .. } = s.execute().unwrap();
+    assert!(numeric_prefix_disabled);
+    assert!(documents_ids.is_empty());
+
+    let mut s = Search::new(&txn, &index);
+    s.query("2020");
+    let SearchResult { documents_ids, numeric_prefix_disabled, .. } = s.execute().unwrap();
+    assert!(!numeric_prefix_disabled);
+    let texts = collect_field_values(&index, &txn, "text", &documents_ids);
+    insta::assert_debug_snapshot!(texts, @r###"
+    [
+        "\"released in 2020\"",
+    ]
+    "###);
+
+    // only the last word of the query can be a prefix
+    let mut s = Search::new(&txn, &index);
+    s.query("202 released");
+    let SearchResult { numeric_prefix_disabled, .. } = s.execute().unwrap();
+    assert!(!numeric_prefix_disabled);
+}
+
+#[test]
+fn test_short_number_prefix_setting() {
+    let index = create_index();
+    let with_heuristic = fetched_word_bitmaps(&index, "202");
+
+    index
+        .update_settings(|s| {
+            s.set_min_numeric_prefix_length(0);
+        })
+        .unwrap();
+    let without_heuristic = fetched_word_bitmaps(&index, "202");
+    assert!(with_heuristic < without_heuristic, "{with_heuristic} >= {without_heuristic}");
+
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, &index);
+    s.query("202");
+    let SearchResult { documents_ids, numeric_prefix_disabled, .. } = s.execute().unwrap();
+    assert!(!numeric_prefix_disabled);
+    assert_eq!(documents_ids.len(), 10);
+}
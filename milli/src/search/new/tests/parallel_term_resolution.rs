@@ -0,0 +1,97 @@
+/*!
+This module tests that decoding the docids of the terms in parallel doesn't change the results:
+
+1. with the default ranking rules, the words being removed by the term matching strategy
+2. with typos, prefixes, and phrases, whose docids are resolved differently
+3. with queries whose number of terms is below the parallel resolution threshold
+*/
+
+use crate::index::tests::TempIndex;
+use crate::score_details::ScoringStrategy;
+use crate::{Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned(), "title".to_owned()]);
+        })
+        .unwrap();
+
+    let sentences = [
+        "the quick brown fox jumps over the lazy dog",
+        "a quick brown dog jumps over the lazy fox",
+        "the lazy cat sleeps under the warm sun all day long",
+        "quick thinking saves the day when the brown river floods",
+        "over the hills and far away the fox runs quickly",
+        "dogs and cats living together in a sunny apartment",
+        "the sun rises over the brown hills every morning",
+        "jumping foxes are quicker than sleeping dogs",
+    ];
+    let documents: Vec<_> = (0..64)
+        .map(|id| {
+            let text = format!(
+                "{} {}",
+                sentences[id % sentences.len()],
+                sentences[(id * 3 + 1) % sentences.len()]
+            );
+            let title = sentences[(id * 5 + 2) % sentences.len()];
+            serde_json::json!({ "id": id, "text": text, "title": title })
+        })
+        .collect();
+    index.add_documents(documents!(documents)).unwrap();
+
+    index
+}
+
+fn search(
+    index: &TempIndex,
+    query: &str,
+    strategy: TermsMatchingStrategy,
+    parallel: bool,
+) -> SearchResult {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    s.query(query);
+    s.terms_matching_strategy(strategy);
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    s.limit(64);
+    s.parallel_term_resolution(parallel);
+    s.execute().unwrap()
+}
+
+#[test]
+fn test_parallel_term_resolution_same_results() {
+    let index = create_index();
+
+    for query in [
+        "the quick brown fox jumps over the lazy dog",
+        "quikc brwn fox jumsp ovr lazzy dgo sleeps",
+        "quick brown \"lazy dog\" over sunny hills morning qui",
+        "dogs cats sun hills river morning apartment together",
+        "brown fox",
+    ] {
+        for strategy in [TermsMatchingStrategy::Last, TermsMatchingStrategy::All] {
+            let sequential = search(&index, query, strategy, false);
+            let parallel = search(&index, query, strategy, true);
+            assert_eq!(sequential.documents_ids, parallel.documents_ids, "{query}");
+            assert_eq!(sequential.candidates, parallel.candidates, "{query}");
+            assert_eq!(sequential.document_scores, parallel.document_scores, "{query}");
+        }
+    }
+}
+
+#[test]
+fn test_parallel_term_resolution_decodes_all_the_terms() {
+    let index = create_index();
+
+    let query = "quick brown fox jumps over lazy dog";
+    let sequential = search(&index, query, TermsMatchingStrategy::Last, false);
+    let parallel = search(&index, query, TermsMatchingStrategy::Last, true);
+    assert!(!parallel.documents_ids.is_empty());
+    assert_eq!(sequential.documents_ids, parallel.documents_ids);
+    // the docids of every term are decoded upfront instead of being intersected lazily
+    assert!(parallel.metrics.bitmap_decodes >= sequential.metrics.bitmap_decodes);
+}
@@ -0,0 +1,102 @@
+/*!
+This module tests the `phraseAcrossFields` setting, which lets a phrase start at the
+end of a searchable attribute and continue at the beginning of the next one.
+
+1. the phrases never match across attributes when the setting is disabled
+2. when enabled, the phrases match across adjacent attributes only, in the searchable order
+3. the words must end the first attribute and start the second one
+4. the documents matching the phrase within a single attribute are ranked first
+*/
+
+use crate::index::tests::TempIndex;
+use crate::{Search, SearchResult};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec![
+                "first_name".to_owned(),
+                "last_name".to_owned(),
+                "city".to_owned(),
+            ]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            // the phrase spans the first and last names
+            { "id": 0, "first_name": "john", "last_name": "smith", "city": "paris" },
+            { "id": 1, "first_name": "jane", "last_name": "john smith", "city": "london" },
+            { "id": 2, "first_name": "john smith", "last_name": "doe", "city": "berlin" },
+            // the words are in the wrong order
+            { "id": 3, "first_name": "smith", "last_name": "john", "city": "rome" },
+            // the attributes are not adjacent
+            { "id": 4, "first_name": "john", "last_name": "doe", "city": "smith" },
+            // the first name does not end with `john`
+            { "id": 5, "first_name": "john paul", "last_name": "smith", "city": "oslo" },
+            // the last name does not start with `smith`
+            { "id": 6, "first_name": "john", "last_name": "doe smith", "city": "madrid" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+#[test]
+fn test_phrase_across_fields_disabled() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("\"john smith\"");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[2, 1]");
+}
+
+#[test]
+fn test_phrase_across_fields_enabled() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_phrase_across_fields(true);
+        })
+        .unwrap();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("\"john smith\"");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[2, 1, 0]");
+
+    // the phrases of a single word are not affected
+    let mut s = Search::new(&txn, &index);
+    s.query("\"smith\"");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids.len(), 7);
+}
+
+#[test]
+fn test_phrase_across_fields_follows_searchable_order() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_phrase_across_fields(true);
+            s.set_searchable_fields(vec![
+                "first_name".to_owned(),
+                "city".to_owned(),
+                "last_name".to_owned(),
+            ]);
+        })
+        .unwrap();
+    let txn = index.read_txn().unwrap();
+
+    // now the first name is only adjacent to the city
+    let mut s = Search::new(&txn, &index);
+    s.query("\"john smith\"");
+    let SearchResult { mut documents_ids, .. } = s.execute().unwrap();
+    documents_ids.sort_unstable();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 2, 4]");
+}
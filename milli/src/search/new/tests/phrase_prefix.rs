@@ -0,0 +1,118 @@
+/*!
+This module tests the prefix ending a phrase:
+
+1. the last word of a phrase is a prefix when the query ends inside the unclosed phrase
+2. it is an exact word when the phrase is closed or followed by a space
+3. a short number ending the phrase is never a prefix, like the last word of the query
+4. the prefix is highlighted in the phrase
+*/
+
+use big_s::S;
+use charabia::TokenizerBuilder;
+
+use crate::index::tests::TempIndex;
+use crate::search::new::matches::MatcherBuilder;
+use crate::search::new::tests::collect_field_values;
+use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec![S("text")]);
+            s.set_criteria(vec![Criterion::Words]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "harry potter and the philosopher's stone" },
+            { "id": 1, "text": "harry potter and the chamber of secrets" },
+            { "id": 2, "text": "harry went to the pottery" },
+            { "id": 3, "text": "dirty harry" },
+            { "id": 4, "text": "the pot of harry" },
+            { "id": 5, "text": "harry pot" },
+            { "id": 6, "text": "released in 1997 and 2001" },
+        ]))
+        .unwrap();
+    index
+}
+
+fn search(index: &TempIndex, query: &str) -> Vec<String> {
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, index);
+    s.query(query);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    collect_field_values(index, &txn, "text", &documents_ids)
+}
+
+#[test]
+fn test_unclosed_phrase_ends_with_a_prefix() {
+    let index = create_index();
+
+    let texts = search(&index, "\"harry pot");
+    insta::assert_debug_snapshot!(texts, @r###"
+    [
+        "\"harry potter and the philosopher's stone\"",
+        "\"harry potter and the chamber of secrets\"",
+        "\"harry pot\"",
+    ]
+    "###);
+
+    // a single word phrase
+    let texts = search(&index, "dirty \"har");
+    insta::assert_debug_snapshot!(texts, @r###"
+    [
+        "\"dirty harry\"",
+    ]
+    "###);
+}
+
+#[test]
+fn test_closed_phrase_ends_with_a_word() {
+    let index = create_index();
+
+    for query in ["\"harry pot\"", "\"harry pot ", "\"harry pot\" "] {
+        let texts = search(&index, query);
+        assert_eq!(texts, vec![S("\"harry pot\"")], "{query}");
+    }
+}
+
+#[test]
+fn test_phrase_ending_with_a_short_number() {
+    let index = create_index();
+
+    // `1` is too short to be a prefix, it would match all the numbers starting with it
+    let texts = search(&index, "\"released in 1");
+    insta::assert_debug_snapshot!(texts, @"[]");
+
+    let texts = search(&index, "\"released in 1997 and 2");
+    insta::assert_debug_snapshot!(texts, @"[]");
+
+    let texts = search(&index, "\"released in 1997");
+    insta::assert_debug_snapshot!(texts, @r###"
+    [
+        "\"released in 1997 and 2001\"",
+    ]
+    "###);
+}
+
+#[test]
+fn test_phrase_prefix_highlighted() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("\"harry pot");
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    let SearchResult { matching_words, .. } = s.execute().unwrap();
+
+    let builder = MatcherBuilder::new(matching_words, TokenizerBuilder::default().into_tokenizer());
+    let mut matcher = builder.build("harry potter and the chamber of secrets");
+    let highlighted = matcher.format(Default::default());
+    insta::assert_snapshot!(highlighted, @"<em>harry</em> <em>pot</em>ter and the chamber of secrets");
+}
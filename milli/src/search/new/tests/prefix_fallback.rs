@@ -0,0 +1,101 @@
+/*!
+This module tests the resolution of the prefixes missing from the prefix databases:
+
+1. a prefix missing from the prefix databases of the restricted attributes is resolved from the
+   words starting with it, and the fallback is reported in the metrics of the search
+2. without any prefix database, the prefixes are derived from the words FST
+*/
+
+use big_s::S;
+
+use crate::documents::documents_batch_reader_from_objects;
+use crate::index::tests::TempIndex;
+use crate::{Search, SearchResult, TermsMatchingStrategy};
+
+/// The number of documents containing a word starting with `ba`, enough for the prefix to be
+/// stored in the prefix databases.
+const BA_WORDS_COUNT: usize = 60;
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec![S("title"), S("text")]);
+        })
+        .unwrap();
+
+    let letters: Vec<char> = ('a'..='z').collect();
+    let mut documents: Vec<_> = (0..BA_WORDS_COUNT)
+        .map(|id| {
+            let word = format!("ba{}{}", letters[id / letters.len()], letters[id % letters.len()]);
+            serde_json::json!({ "id": id, "title": "document", "text": word })
+        })
+        .collect();
+    documents
+        .push(serde_json::json!({ "id": BA_WORDS_COUNT, "title": "document", "text": "other" }));
+    let documents = documents.into_iter().map(|doc| doc.as_object().unwrap().clone()).collect();
+    index.add_documents(documents_batch_reader_from_objects(documents)).unwrap();
+
+    index
+}
+
+fn search(index: &TempIndex, query: &str, attributes: Option<&[String]>) -> (Vec<String>, u64) {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    s.query(query);
+    s.limit(BA_WORDS_COUNT + 1);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    if let Some(attributes) = attributes {
+        s.searchable_attributes(attributes);
+    }
+    let SearchResult { documents_ids, metrics, .. } = s.execute().unwrap();
+    let mut ids = index.external_ids_of(&txn, documents_ids).unwrap();
+    ids.sort_by_key(|id| id.parse::<usize>().unwrap());
+    (ids, metrics.prefix_docids_fallbacks)
+}
+
+fn expected_ids() -> Vec<String> {
+    (0..BA_WORDS_COUNT).map(|id| id.to_string()).collect()
+}
+
+#[test]
+fn test_prefix_missing_from_the_restricted_prefix_databases() {
+    let index = create_index();
+    let text = [S("text")];
+
+    let txn = index.read_txn().unwrap();
+    assert!(index.word_prefix_docids.get(&txn, "ba").unwrap().is_some());
+    drop(txn);
+
+    let (ids, fallbacks) = search(&index, "ba", Some(&text));
+    assert_eq!(ids, expected_ids());
+    assert_eq!(fallbacks, 0);
+
+    // the prefix databases of the attributes lag behind the words
+    let mut wtxn = index.write_txn().unwrap();
+    index.word_prefix_fid_docids.clear(&mut wtxn).unwrap();
+    index.exact_word_prefix_docids.clear(&mut wtxn).unwrap();
+    wtxn.commit().unwrap();
+
+    let (ids, fallbacks) = search(&index, "ba", Some(&text));
+    assert_eq!(ids, expected_ids());
+    assert!(fallbacks > 0);
+}
+
+#[test]
+fn test_without_prefix_databases() {
+    let index = create_index();
+
+    let mut wtxn = index.write_txn().unwrap();
+    index.word_prefix_docids.clear(&mut wtxn).unwrap();
+    index.exact_word_prefix_docids.clear(&mut wtxn).unwrap();
+    index.word_prefix_fid_docids.clear(&mut wtxn).unwrap();
+    wtxn.commit().unwrap();
+
+    // the prefix is derived from the words FST, without looking up the prefix databases
+    let (ids, fallbacks) = search(&index, "ba", None);
+    assert_eq!(ids, expected_ids());
+    assert_eq!(fallbacks, 0);
+}
@@ -10,8 +10,8 @@ proximity condition. This is especially relevant with split words and synonyms.
 3. An ngram has the same sprximity cost as its component words being consecutive.
 e.g. `sunflower` equivalent to `sun flower`.
 
-4. The prefix databases can be used to find the sprximity between two words, but
-they store fewer sprximities than the regular word sprximity DB.
+4. The prefix databases can be used to find the sprximity between two words, and
+rank the documents the same as the words starting with the prefix, in both directions.
 
 */
 
@@ -371,26 +371,26 @@ fn test_proximity_prefix_db() {
     s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
     s.query("best s");
     let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
-    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[10, 9, 6, 7, 8, 11, 12, 13, 15]");
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[10, 13, 9, 12, 6, 7, 8, 11, 15]");
     insta::assert_snapshot!(format!("{document_scores:#?}"));
     let texts = collect_field_values(&index, &txn, "text", &documents_ids);
 
-    // This test illustrates the loss of precision from using the prefix DB
+    // The words starting with the prefix before `best` are found with the completions of the prefix
     insta::assert_debug_snapshot!(texts, @r###"
     [
         "\"this is the best summer meal\"",
+        "\"summer best\"",
         "\"this is the best meal of summer\"",
+        "\"summer x best\"",
         "\"this is the best meal I have ever had in such a beautiful summer day\"",
         "\"this is the best cooked meal of the summer\"",
         "\"this is the best meal of the summer\"",
         "\"summer x y best\"",
-        "\"summer x best\"",
-        "\"summer best\"",
         "\"this is the best meal I have ever had in such a beautiful winter day\"",
     ]
     "###);
 
-    // Difference when using the `su` prefix, which is not in the prefix DB
+    // Same ranking when using the `su` prefix, which is not in the prefix DB
     let mut s = Search::new(&txn, &index);
     s.terms_matching_strategy(TermsMatchingStrategy::All);
     s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
@@ -414,29 +414,28 @@ fn test_proximity_prefix_db() {
     ]
     "###);
 
-    // Note that there is a case where a prefix is in the prefix DB but not in the
-    // **proximity** prefix DB. In that case, its sprximity score will always be
-    // the maximum. This happens for prefixes that are larger than 2 bytes.
+    // Whether a prefix is in the prefix DB or not, the documents are ranked the same,
+    // including when the words starting with the prefix come before `best`.
 
     let mut s = Search::new(&txn, &index);
     s.terms_matching_strategy(TermsMatchingStrategy::All);
     s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
     s.query("best win");
     let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
-    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[19, 18, 15, 16, 17, 20, 21, 22]");
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[19, 22, 18, 21, 15, 16, 17, 20]");
     insta::assert_snapshot!(format!("{document_scores:#?}"));
     let texts = collect_field_values(&index, &txn, "text", &documents_ids);
 
     insta::assert_debug_snapshot!(texts, @r###"
     [
         "\"this is the best winter meal\"",
+        "\"winter best\"",
         "\"this is the best meal of winter\"",
+        "\"winter x best\"",
         "\"this is the best meal I have ever had in such a beautiful winter day\"",
         "\"this is the best cooked meal of the winter\"",
         "\"this is the best meal of the winter\"",
         "\"winter x y best\"",
-        "\"winter x best\"",
-        "\"winter best\"",
     ]
     "###);
 
@@ -471,20 +470,40 @@ fn test_proximity_prefix_db() {
     s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
     s.query("best wi");
     let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
-    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[19, 18, 15, 16, 17, 20, 21, 22]");
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[19, 22, 18, 21, 15, 16, 17, 20]");
     insta::assert_snapshot!(format!("{document_scores:#?}"));
     let texts = collect_field_values(&index, &txn, "text", &documents_ids);
 
     insta::assert_debug_snapshot!(texts, @r###"
     [
         "\"this is the best winter meal\"",
+        "\"winter best\"",
         "\"this is the best meal of winter\"",
+        "\"winter x best\"",
         "\"this is the best meal I have ever had in such a beautiful winter day\"",
         "\"this is the best cooked meal of the winter\"",
         "\"this is the best meal of the winter\"",
         "\"winter x y best\"",
-        "\"winter x best\"",
-        "\"winter best\"",
     ]
     "###);
 }
+
+#[test]
+fn test_proximity_prefix_db_same_as_words() {
+    let index = create_edge_cases_index();
+    let txn = index.read_txn().unwrap();
+
+    let search = |query: &str| {
+        let mut s = Search::new(&txn, &index);
+        s.terms_matching_strategy(TermsMatchingStrategy::All);
+        s.query(query);
+        s.execute().unwrap().documents_ids
+    };
+
+    // `win` is in the prefix DB, `wint` is not, and `winter` is the only word starting with it
+    // in these documents: the adjacent words rank first, in both directions.
+    let documents_ids = search("best winter");
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[19, 22, 18, 21, 15, 16, 17, 20]");
+    assert_eq!(search("best win"), documents_ids);
+    assert_eq!(search("best wint"), documents_ids);
+}
@@ -0,0 +1,73 @@
+/*!
+This module tests the pruning of the query graph before its resolution:
+
+1. The alternatives matching no documents, such as the ngrams of garbage tokens, are removed
+2. The terms that all the paths go through are kept, even when they match no documents
+3. The documents matching the graph and their ranking are the same with and without the pruning
+*/
+
+use crate::index::tests::TempIndex;
+use crate::{Search, SearchContext, SearchResult};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "the quick brown fox" },
+            { "id": 1, "text": "the quick dog" },
+            { "id": 2, "text": "a brown fox jumps" },
+            { "id": 3, "text": "quick brown foxes" },
+            { "id": 4, "text": "the lazy dog" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+#[test]
+fn test_prune_empty_terms() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+    let universe = index.documents_ids(&txn).unwrap();
+
+    for query in ["the quick zzzz brown fox", "the quick brown fox", "qqqq zzzz"] {
+        let mut s = Search::new(&txn, &index);
+        s.query(query);
+        let SearchResult { documents_ids, .. } = s.execute().unwrap();
+
+        let mut ctx = SearchContext::new(&index, &txn);
+        let graph = ctx.query_graph(query, None).unwrap().unwrap();
+        let mut pruned = graph.clone();
+        pruned.prune_empty_terms(&mut ctx).unwrap();
+
+        let dot = graph.graphviz(&ctx);
+        let pruned_dot = pruned.graphviz(&ctx);
+        assert!(pruned_dot.len() < dot.len(), "query: {query}\n{dot}\n{pruned_dot}");
+        // the ngrams match no documents
+        assert!(dot.contains("\"thequick\"") || dot.contains("\"qqqqzzzz\""), "{dot}");
+        assert!(!pruned_dot.contains("\"thequick\""), "{pruned_dot}");
+        assert!(!pruned_dot.contains("\"qqqqzzzz\""), "{pruned_dot}");
+        // but all the words of the query are kept
+        for word in query.split_whitespace() {
+            assert!(pruned_dot.contains(&format!("\"{word}\"")), "query: {query}\n{pruned_dot}");
+        }
+
+        let docids = ctx.resolve_query_graph(&graph, &universe).unwrap();
+        let pruned_docids = ctx.resolve_query_graph(&pruned, &universe).unwrap();
+        assert_eq!(pruned_docids, docids, "query: {query}");
+
+        let ranked = ctx.bucket_sort_query_graph(&graph, &universe, 0, 20).unwrap();
+        let pruned_ranked = ctx.bucket_sort_query_graph(&pruned, &universe, 0, 20).unwrap();
+        assert_eq!(pruned_ranked, ranked, "query: {query}");
+        // the search prunes the graph before ranking the documents
+        assert_eq!(documents_ids, ranked, "query: {query}");
+    }
+}
@@ -0,0 +1,151 @@
+/*!
+This module tests the limit on the number of alternatives of the query graph:
+
+1. The ngrams of the query are dropped first when its query graph has too many alternatives
+2. The synonyms of its least frequent terms are dropped next, until the limit is respected
+3. The search still returns documents, and their score details record what was dropped
+4. The words of a query past its maximum number of terms are ignored, and the search reports it
+*/
+
+use std::collections::BTreeMap;
+
+use crate::documents::documents_batch_reader_from_objects;
+use crate::index::tests::TempIndex;
+use crate::score_details::{QueryGraphDegradation, ScoreDetails, ScoringStrategy};
+use crate::search::new::limits::{DEFAULT_MAX_QUERY_TERMS, MAX_QUERY_GRAPH_ALTERNATIVES};
+use crate::search::new::query_graph::QueryNodeData;
+use crate::test_utils::word;
+use crate::{Criterion, Search, SearchContext, SearchResult};
+
+fn create_index(texts: Vec<String>, synonyms: BTreeMap<String, Vec<String>>) -> TempIndex {
+    let index = TempIndex::new();
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_criteria(vec![Criterion::Words]);
+            s.set_synonyms(synonyms);
+        })
+        .unwrap();
+
+    let documents = texts
+        .into_iter()
+        .enumerate()
+        .map(|(id, text)| match serde_json::json!({ "id": id, "text": text }) {
+            serde_json::Value::Object(object) => object,
+            _ => unreachable!(),
+        })
+        .collect();
+    index.add_documents(documents_batch_reader_from_objects(documents)).unwrap();
+    index
+}
+
+#[test]
+fn the_ngrams_are_dropped_first() {
+    let words: Vec<_> = (0..10).map(word).collect();
+    let mut synonyms = BTreeMap::new();
+    for (i, word) in words.iter().enumerate() {
+        synonyms.insert(word.clone(), (0..5).map(|j| format!("word{i} synonym{j}")).collect());
+    }
+    // the ngrams are not limited in synonyms like the words
+    for (i, pair) in words.windows(2).enumerate() {
+        synonyms.insert(pair.join(" "), (0..120).map(|j| format!("pair{i} synonym{j}")).collect());
+    }
+    let query = words.join(" ");
+    let index = create_index(vec![query.clone()], synonyms);
+    let txn = index.read_txn().unwrap();
+
+    // 10 words, 9 bigrams and 8 trigrams
+    let degradation = QueryGraphDegradation { dropped_ngrams: 17, dropped_synonym_terms: 0 };
+    let mut ctx = SearchContext::new(&index, &txn);
+    let graph = ctx.query_graph(&query, None).unwrap().unwrap();
+    assert_eq!(graph.degradation, Some(degradation));
+    assert_eq!(graph.number_of_alternatives(&ctx), 10 + 10 * 5);
+    for (_, node) in graph.nodes.iter() {
+        if let QueryNodeData::Term(term) = &node.data {
+            assert_eq!(term.term_ids.start(), term.term_ids.end());
+        }
+    }
+
+    let mut s = Search::new(&txn, &index);
+    s.query(&query);
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, vec![0]);
+    assert_eq!(document_scores[0].last(), Some(&ScoreDetails::QueryGraphDegraded(degradation)));
+}
+
+#[test]
+fn the_synonyms_of_the_least_frequent_terms_are_dropped_next() {
+    let words: Vec<_> = (0..100).map(word).collect();
+    let mut synonyms = BTreeMap::new();
+    for (i, word) in words.iter().enumerate() {
+        synonyms.insert(word.clone(), (0..10).map(|j| format!("word{i} synonym{j}")).collect());
+    }
+    // the document `d` contains the words from the `d`th one, the word `i` is in `i + 1` documents
+    let texts = (0..words.len()).map(|d| words[d..].join(" ")).collect();
+    let query = words.join(" ");
+    let index = create_index(texts, synonyms);
+    let txn = index.read_txn().unwrap();
+
+    // 100 words and 1000 synonyms, the synonyms of the 8 least frequent words must be dropped
+    let degradation = QueryGraphDegradation { dropped_ngrams: 99 + 98, dropped_synonym_terms: 8 };
+    let mut ctx = SearchContext::new(&index, &txn);
+    let graph = ctx.query_graph(&query, None).unwrap().unwrap();
+    assert_eq!(graph.degradation, Some(degradation));
+    assert!(graph.number_of_alternatives(&ctx) <= MAX_QUERY_GRAPH_ALTERNATIVES);
+    for (_, node) in graph.nodes.iter() {
+        if let QueryNodeData::Term(term) = &node.data {
+            let expected = if *term.term_ids.start() < 8 { 0 } else { 10 };
+            assert_eq!(term.term_subset.synonyms_len(&ctx), expected, "term: {:?}", term.term_ids);
+        }
+    }
+
+    let mut s = Search::new(&txn, &index);
+    s.query(&query);
+    s.words_limit(words.len());
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids.first(), Some(&0));
+    assert_eq!(document_scores[0].last(), Some(&ScoreDetails::QueryGraphDegraded(degradation)));
+}
+
+#[test]
+fn the_words_of_a_long_query_are_truncated() {
+    let words: Vec<_> = (0..300).map(word).collect();
+    let query = words.join(" ");
+    let index = create_index(vec![words[..10].join(" ")], BTreeMap::new());
+    let txn = index.read_txn().unwrap();
+
+    // even without a words limit, only the first terms are in the graph
+    let mut ctx = SearchContext::new(&index, &txn);
+    let graph = ctx.query_graph(&query, None).unwrap().unwrap();
+    // the terms, their bigrams and trigrams, and the root and end nodes
+    assert!(graph.nodes.len() as usize <= 3 * DEFAULT_MAX_QUERY_TERMS + 2);
+    for (_, node) in graph.nodes.iter() {
+        if let QueryNodeData::Term(term) = &node.data {
+            assert!((*term.term_ids.end() as usize) < DEFAULT_MAX_QUERY_TERMS);
+        }
+    }
+
+    let mut s = Search::new(&txn, &index);
+    s.query(&query);
+    s.words_limit(words.len());
+    let SearchResult { documents_ids, query_truncated, .. } = s.execute().unwrap();
+    assert!(query_truncated);
+    assert_eq!(documents_ids, vec![0]);
+
+    // the query is not truncated when the limit is reached on its last word
+    let mut s = Search::new(&txn, &index);
+    s.query(&words[..10].join(" "));
+    let SearchResult { documents_ids, query_truncated, .. } = s.execute().unwrap();
+    assert!(!query_truncated);
+    assert_eq!(documents_ids, vec![0]);
+
+    let mut s = Search::new(&txn, &index);
+    s.query(&words[..10].join(" "));
+    s.max_query_terms(5);
+    let SearchResult { documents_ids, query_truncated, .. } = s.execute().unwrap();
+    assert!(query_truncated);
+    assert_eq!(documents_ids, vec![0]);
+}
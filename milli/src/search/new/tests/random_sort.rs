@@ -0,0 +1,170 @@
+/*!
+This module tests the `random` ranking rule:
+
+1. the same seed always returns the same permutation of the documents, even when the
+   ranking rule is restarted for each bucket of the previous ranking rules
+2. different seeds return different permutations of the same documents
+3. the subsequent ranking rules sort the documents within each shuffled bucket
+4. the documents can be seeded by the value of a field, the documents without a value come last
+*/
+
+use big_s::S;
+
+use crate::index::tests::TempIndex;
+use crate::{Criterion, RandomSeed, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_criteria(vec![random(42, 1)]);
+        })
+        .unwrap();
+
+    let documents: Vec<_> = (0..20)
+        .map(|id| {
+            let text = if id % 2 == 0 { "the quick brown fox" } else { "the quick dog" };
+            serde_json::json!({ "id": id, "text": text, "rank": (id * 7) % 10 })
+        })
+        .collect();
+    index.add_documents(documents!(documents)).unwrap();
+
+    index
+}
+
+fn random(seed: u64, bucket_size: u32) -> Criterion {
+    Criterion::Random { seed: RandomSeed::Literal(seed), bucket_size }
+}
+
+fn search(index: &TempIndex, query: Option<&str>) -> Vec<u32> {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    if let Some(query) = query {
+        s.query(query);
+        s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    }
+    s.limit(100);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    documents_ids
+}
+
+#[test]
+fn test_random_same_seed() {
+    let index = create_index();
+
+    let first = search(&index, None);
+    assert_eq!(first.len(), 20);
+    assert_eq!(search(&index, None), first);
+    // the order is not the one of the ids
+    assert_ne!(first, (0..20).collect::<Vec<_>>());
+
+    // the ranking rule is restarted for each bucket of the `words` ranking rule
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::Words, random(42, 1)]);
+        })
+        .unwrap();
+    let first = search(&index, Some("the quick brown fox"));
+    assert_eq!(search(&index, Some("the quick brown fox")), first);
+
+    // the pages are slices of the whole results
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, &index);
+    s.query("the quick brown fox");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.offset(7);
+    s.limit(5);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    assert_eq!(documents_ids, first[7..12]);
+}
+
+#[test]
+fn test_random_different_seeds() {
+    let index = create_index();
+    let first = search(&index, None);
+
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![random(43, 1)]);
+        })
+        .unwrap();
+    let second = search(&index, None);
+    assert_ne!(second, first);
+
+    let (mut first, mut second) = (first, second);
+    first.sort_unstable();
+    second.sort_unstable();
+    assert_eq!(second, first);
+}
+
+#[test]
+fn test_random_bucket_size() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_sortable_fields(maplit::hashset! { S("rank") });
+            s.set_criteria(vec![random(42, 5)]);
+        })
+        .unwrap();
+    let shuffled = search(&index, None);
+
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![random(42, 5), Criterion::Asc(S("rank"))]);
+        })
+        .unwrap();
+    let sorted = search(&index, None);
+
+    for (shuffled, sorted) in shuffled.chunks(5).zip(sorted.chunks(5)) {
+        // the buckets contain the same documents
+        let mut shuffled = shuffled.to_vec();
+        let mut sorted_ids = sorted.to_vec();
+        shuffled.sort_unstable();
+        sorted_ids.sort_unstable();
+        assert_eq!(sorted_ids, shuffled);
+
+        // and are sorted by the next ranking rule
+        let ranks: Vec<_> = sorted.iter().map(|id| (id * 7) % 10).collect();
+        let mut sorted_ranks = ranks.clone();
+        sorted_ranks.sort_unstable();
+        assert_eq!(ranks, sorted_ranks);
+    }
+}
+
+#[test]
+fn test_random_field_seed() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_criteria(vec![Criterion::Random {
+                seed: RandomSeed::Field(S("shuffle")),
+                bucket_size: 1,
+            }]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "shuffle": 12 },
+            { "id": 1 },
+            { "id": 2, "shuffle": "blue" },
+            { "id": 3, "shuffle": 12 },
+            { "id": 4, "shuffle": 5 },
+            { "id": 5 },
+            { "id": 6, "shuffle": "blue" },
+        ]))
+        .unwrap();
+
+    let documents_ids = search(&index, None);
+    assert_eq!(search(&index, None), documents_ids);
+
+    // the documents without a seed come last, in the order of their ids
+    assert_eq!(documents_ids[5..], [1, 5]);
+    // the documents with the same seed are next to each other
+    let position = |id| documents_ids.iter().position(|docid| *docid == id).unwrap();
+    assert_eq!(position(3), position(0) + 1);
+    assert_eq!(position(6), position(2) + 1);
+}
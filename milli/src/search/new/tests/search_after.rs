@@ -0,0 +1,123 @@
+/*!
+This module tests the `search_after` pagination, which returns the documents ranked
+after a given one instead of skipping the first `offset` ones.
+
+1. paging with `search_after` returns the same documents as paging with `offset`,
+   with several ranking rules and a distinct attribute
+2. the documents of a bucket are paged in the order of their ids
+3. the document must be part of the results of the search
+*/
+
+use big_s::S;
+use maplit::hashset;
+
+use crate::index::tests::TempIndex;
+use crate::{Criterion, Filter, Search, SearchResult, UserError};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_sortable_fields(hashset! { S("rank"), S("score") });
+            s.set_filterable_fields(hashset! { S("rank") });
+            s.set_distinct_field("letter".to_owned());
+            s.set_criteria(vec![Criterion::Asc(S("rank")), Criterion::Desc(S("score"))]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "letter": "A", "rank": 2, "score": 10 },
+            { "id": 1, "letter": "B", "rank": 1, "score": 5 },
+            { "id": 2, "letter": "C", "rank": 1, "score": 7 },
+            { "id": 3, "letter": "A", "rank": 1, "score": 1 },
+            { "id": 4, "letter": "D", "rank": 3, "score": 3 },
+            { "id": 5, "letter": "E", "rank": 2, "score": 3 },
+            { "id": 6, "letter": "B", "rank": 3, "score": 9 },
+            { "id": 7, "letter": "F", "rank": 2, "score": 3 },
+            { "id": 8, "letter": "G", "rank": 1, "score": 5 },
+            { "id": 9, "letter": "H", "rank": 3, "score": 8 },
+            { "id": 10, "letter": "I", "rank": 2, "score": 3 },
+            { "id": 11, "letter": "C", "rank": 3, "score": 1 },
+        ]))
+        .unwrap();
+
+    index
+}
+
+#[test]
+fn test_search_after_same_pages_as_offset() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.limit(100);
+    let SearchResult { documents_ids: all_documents_ids, .. } = s.execute().unwrap();
+    // the documents with the same rank and score are in the order of their ids
+    insta::assert_snapshot!(format!("{all_documents_ids:?}"), @"[2, 1, 8, 3, 5, 7, 10, 9, 4]");
+
+    let mut offset_pages = vec![];
+    for page in 0..3 {
+        let mut s = Search::new(&txn, &index);
+        s.offset(page * 3);
+        s.limit(3);
+        offset_pages.push(s.execute().unwrap().documents_ids);
+    }
+
+    let mut search_after_pages: Vec<Vec<u32>> = vec![];
+    for _ in 0..3 {
+        let mut s = Search::new(&txn, &index);
+        s.limit(3);
+        if let Some(last) = search_after_pages.last().and_then(|page| page.last()) {
+            s.search_after(last.to_string());
+        }
+        search_after_pages.push(s.execute().unwrap().documents_ids);
+    }
+
+    insta::assert_snapshot!(format!("{offset_pages:?}"), @"[[2, 1, 8], [3, 5, 7], [10, 9, 4]]");
+    assert_eq!(search_after_pages, offset_pages);
+}
+
+#[test]
+fn test_search_after_candidates() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.limit(3);
+    s.exhaustive_number_hits(true);
+    let SearchResult { candidates, .. } = s.execute().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.limit(3);
+    s.exhaustive_number_hits(true);
+    s.search_after("5");
+    let SearchResult { documents_ids, candidates: search_after_candidates, .. } =
+        s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[7, 10, 9]");
+    assert_eq!(search_after_candidates, candidates);
+}
+
+#[test]
+fn test_search_after_not_in_results() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.search_after("42");
+    let error = s.execute().unwrap_err();
+    assert!(matches!(
+        error,
+        crate::Error::UserError(UserError::InvalidSearchAfter { ref document_id }) if document_id == "42"
+    ));
+    insta::assert_snapshot!(error, @"The document `42` given in `searchAfter` is not part of the results of the search.");
+
+    // the document exists but is filtered out
+    let mut s = Search::new(&txn, &index);
+    s.filter(Filter::from_str("rank = 1").unwrap().unwrap());
+    s.search_after("0");
+    let error = s.execute().unwrap_err();
+    insta::assert_snapshot!(error, @"The document `0` given in `searchAfter` is not part of the results of the search.");
+}
@@ -0,0 +1,91 @@
+/*!
+This module tests the trace of the bucket sort recorded by the structured search logger:
+
+1. the trace is only recorded when it is requested
+2. the events follow the iterations of the ranking rules, with the size of their universes and buckets
+3. the documents skipped because of the offset are reported
+*/
+
+use big_s::S;
+use maplit::hashset;
+
+use crate::index::tests::TempIndex;
+use crate::score_details::ScoringStrategy;
+use crate::{Criterion, Search, SearchResult, SearchTrace};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_sortable_fields(hashset! { S("rank"), S("score") });
+            s.set_criteria(vec![Criterion::Asc(S("rank")), Criterion::Desc(S("score"))]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "rank": 1, "score": 10 },
+            { "id": 1, "rank": 1, "score": 5 },
+            { "id": 2, "rank": 1, "score": 7 },
+            { "id": 3, "rank": 2, "score": 1 },
+            { "id": 4, "rank": 2, "score": 3 },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn format_trace(trace: &SearchTrace) -> String {
+    let SearchTrace { ranking_rules, initial_universe, events } = trace;
+    let mut s = format!("{ranking_rules:?} on {initial_universe} documents\n");
+    for event in events {
+        s.push_str(&serde_json::to_string(event).unwrap());
+        s.push('\n');
+    }
+    s
+}
+
+#[test]
+fn test_search_trace_not_requested() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let s = Search::new(&txn, &index);
+    let SearchResult { documents_ids, trace, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2, 1, 4, 3]");
+    assert_eq!(trace, None);
+}
+
+#[test]
+fn test_search_trace_two_rules() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    s.offset(1);
+    s.limit(3);
+    s.trace(true);
+    let SearchResult { documents_ids, trace, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[2, 1, 4]");
+    insta::assert_snapshot!(format_trace(&trace.unwrap()), @r###"
+    ["rank:asc", "score:desc"] on 5 documents
+    {"event":"startIteration","rankingRuleIndex":0,"rankingRule":"rank:asc","universe":5}
+    {"event":"nextBucket","rankingRuleIndex":0,"rankingRule":"rank:asc","universe":5,"bucket":3}
+    {"event":"startIteration","rankingRuleIndex":1,"rankingRule":"score:desc","universe":3}
+    {"event":"nextBucket","rankingRuleIndex":1,"rankingRule":"score:desc","universe":3,"bucket":1}
+    {"event":"skipBucket","rankingRuleIndex":1,"rankingRule":"score:desc","bucket":1}
+    {"event":"addToResults","docids":[]}
+    {"event":"nextBucket","rankingRuleIndex":1,"rankingRule":"score:desc","universe":2,"bucket":1}
+    {"event":"addToResults","docids":[2]}
+    {"event":"nextBucket","rankingRuleIndex":1,"rankingRule":"score:desc","universe":1,"bucket":1}
+    {"event":"addToResults","docids":[1]}
+    {"event":"endIteration","rankingRuleIndex":1,"rankingRule":"score:desc","universe":0}
+    {"event":"nextBucket","rankingRuleIndex":0,"rankingRule":"rank:asc","universe":2,"bucket":2}
+    {"event":"startIteration","rankingRuleIndex":1,"rankingRule":"score:desc","universe":2}
+    {"event":"nextBucket","rankingRuleIndex":1,"rankingRule":"score:desc","universe":2,"bucket":1}
+    {"event":"addToResults","docids":[4]}
+    "###);
+}
@@ -0,0 +1,77 @@
+/*!
+This module tests the settings overlay of a search:
+1. its ranking rules are used instead of the ones of the index
+2. its typo tolerance is used instead of the one of the index
+3. the settings of the index are untouched
+*/
+
+use big_s::S;
+use maplit::hashset;
+
+use crate::index::tests::TempIndex;
+use crate::{Criterion, Search, SearchResult, SettingsOverlay};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec![S("text")]);
+            s.set_sortable_fields(hashset! { S("rank") });
+            s.set_criteria(vec![Criterion::Words, Criterion::Asc(S("rank"))]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "hello world", "rank": 3 },
+            { "id": 1, "text": "hello", "rank": 1 },
+            { "id": 2, "text": "hello there", "rank": 2 },
+            { "id": 3, "text": "goodbye", "rank": 0 },
+        ]))
+        .unwrap();
+    index
+}
+
+#[test]
+fn test_settings_overlay_ranking_rules() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("hello");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 2, 0]");
+
+    s.settings_overlay(SettingsOverlay {
+        criteria: Some(vec![Criterion::Words, Criterion::Desc(S("rank"))]),
+        ..Default::default()
+    });
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2, 1]");
+
+    assert_eq!(index.criteria(&txn).unwrap(), vec![Criterion::Words, Criterion::Asc(S("rank"))]);
+}
+
+#[test]
+fn test_settings_overlay_typo_tolerance() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("hellp");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 2, 0]");
+
+    s.settings_overlay(SettingsOverlay { authorize_typos: Some(false), ..Default::default() });
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[]");
+
+    // `hellp` is too short for a typo when the words need six letters for one
+    s.settings_overlay(SettingsOverlay { min_word_len_one_typo: Some(6), ..Default::default() });
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[]");
+
+    assert!(index.authorize_typos(&txn).unwrap());
+}
@@ -0,0 +1,116 @@
+/*!
+This module tests that verifying the documents of a small universe against the docids of
+each term returns the same results as resolving the docids of the terms:
+
+1. with random universes, selected by a filter, of a few documents up to the whole index
+2. with typos, prefixes, phrases, and words removed by the term matching strategy
+3. with the parallel resolution of the terms, which is skipped for the small universes
+*/
+
+use big_s::S;
+use maplit::hashset;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use crate::index::tests::TempIndex;
+use crate::score_details::ScoringStrategy;
+use crate::{Criterion, Filter, Search, SearchResult, TermsMatchingStrategy};
+
+const WORDS: &[&str] = &[
+    "the", "quick", "brown", "fox", "jumps", "over", "lazy", "dog", "sleeps", "under", "warm",
+    "sun",
+];
+const QUERIES: &[&str] = &[
+    "quick fox",
+    "the lazy dog jumps",
+    "brwn foxx",
+    "over the qu",
+    "\"lazy dog\" sun",
+    "quick brown fox jumps over the lazy dog",
+];
+const NB_DOCUMENTS: usize = 1500;
+
+fn create_index(rng: &mut SmallRng) -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_filterable_fields(hashset! { S("id") });
+            s.set_criteria(vec![
+                Criterion::Words,
+                Criterion::Typo,
+                Criterion::Proximity,
+                Criterion::Attribute,
+                Criterion::Exactness,
+            ]);
+        })
+        .unwrap();
+
+    let documents: Vec<_> = (0..NB_DOCUMENTS)
+        .map(|id| {
+            let text: Vec<_> =
+                (0..rng.gen_range(1..10)).map(|_| WORDS[rng.gen_range(0..WORDS.len())]).collect();
+            serde_json::json!({ "id": id, "text": text.join(" ") })
+        })
+        .collect();
+    index.add_documents(documents!(documents)).unwrap();
+
+    index
+}
+
+fn random_filter(rng: &mut SmallRng) -> String {
+    if rng.gen_bool(0.5) {
+        let ids: Vec<_> =
+            (0..rng.gen_range(1..50)).map(|_| rng.gen_range(0..NB_DOCUMENTS).to_string()).collect();
+        format!("id IN [{}]", ids.join(", "))
+    } else {
+        let start = rng.gen_range(0..NB_DOCUMENTS);
+        format!("id {start} TO {}", start + rng.gen_range(0..NB_DOCUMENTS))
+    }
+}
+
+fn search(
+    index: &TempIndex,
+    query: &str,
+    filter: &str,
+    strategy: TermsMatchingStrategy,
+    threshold: u64,
+) -> SearchResult {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    s.query(query);
+    s.filter(Filter::from_str(filter).unwrap().unwrap());
+    s.terms_matching_strategy(strategy);
+    s.scoring_strategy(ScoringStrategy::Detailed);
+    s.parallel_term_resolution(true);
+    s.small_universe_threshold(threshold);
+    s.limit(NB_DOCUMENTS);
+    s.execute().unwrap()
+}
+
+#[test]
+fn test_small_universe_same_results() {
+    let mut rng = SmallRng::seed_from_u64(42);
+    let index = create_index(&mut rng);
+
+    for _ in 0..100 {
+        let query = QUERIES[rng.gen_range(0..QUERIES.len())];
+        let filter = random_filter(&mut rng);
+        let strategy = if rng.gen_bool(0.5) {
+            TermsMatchingStrategy::Last
+        } else {
+            TermsMatchingStrategy::All
+        };
+
+        // with a threshold of 0 the docids of the terms are always resolved
+        let resolved = search(&index, query, &filter, strategy, 0);
+        let verified = search(&index, query, &filter, strategy, NB_DOCUMENTS as u64);
+
+        let context = format!("query: {query:?}, filter: {filter:?}, strategy: {strategy:?}");
+        assert_eq!(resolved.documents_ids, verified.documents_ids, "{context}");
+        assert_eq!(resolved.candidates, verified.candidates, "{context}");
+        assert_eq!(resolved.document_scores, verified.document_scores, "{context}");
+    }
+}
@@ -8,17 +8,24 @@ This module tests the `sort` ranking rule:
 5. numbers appear before strings
 6. documents with either: (1) no value, (2) null, or (3) an object for the field-to-sort appear at the end of the bucket
 7. boolean values are translated to strings
-8. if a field contains an array, it is sorted by the best value in the array according to the sort rule
+8. if a field contains an array, an ascending sort uses its lowest value and a descending sort its highest,
+and an empty array is sorted like a missing value
+9. strings are sorted alphabetically, in the order of the locale of the field if any
+10. the `sort` ranking rule is reported as skipped when the search has nothing to sort by
+11. the fields of nested objects are sorted by their dotted path, and the documents are sorted by the
+ranking rules following the sort within its buckets
 */
 
 use big_s::S;
 use maplit::hashset;
 use meili_snap::insta;
 
+use crate::collation::Locale;
 use crate::index::tests::TempIndex;
 use crate::search::new::tests::collect_field_values;
 use crate::{
-    score_details, AscDesc, Criterion, Member, Search, SearchResult, TermsMatchingStrategy,
+    score_details, AscDesc, Criterion, LocalizedAttributesRule, Member, Search, SearchResult,
+    TermsMatchingStrategy,
 };
 
 fn create_index() -> TempIndex {
@@ -325,6 +332,91 @@ fn test_sort() {
     "###);
 }
 
+#[test]
+fn test_sort_arrays() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_sortable_fields(hashset! { S("tags"), S("sizes"), S("rank") });
+            s.set_criteria(vec![Criterion::Sort]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "rank": 0, "tags": ["b", "y"], "sizes": [2, 8] },
+            { "id": 1, "rank": 0, "tags": ["a", "z"], "sizes": [1, 9] },
+            { "id": 2, "rank": 0, "tags": ["c"], "sizes": [3] },
+            { "id": 3, "rank": 0, "tags": [], "sizes": [] },
+            { "id": 4, "rank": 1 },
+            { "id": 5, "rank": 0, "tags": ["c", "x"], "sizes": [3, 7] },
+            { "id": 6, "rank": 0, "tags": ["m", "a"], "sizes": [5, 1] },
+        ]))
+        .unwrap();
+
+    let sorted_ids = |index: &TempIndex, sort: AscDesc| {
+        let txn = index.read_txn().unwrap();
+        let mut s = Search::new(&txn, index);
+        // the documents sorted last by the first rule are ordered by the rank
+        s.sort_criteria(vec![sort, AscDesc::Desc(Member::Field(S("rank")))]);
+        let SearchResult { documents_ids, .. } = s.execute().unwrap();
+        format!("{documents_ids:?}")
+    };
+
+    // ascending by the lowest value, the empty array along with the missing field
+    insta::assert_snapshot!(sorted_ids(&index, AscDesc::Asc(Member::Field(S("tags")))), @"[1, 6, 0, 2, 5, 4, 3]");
+    insta::assert_snapshot!(sorted_ids(&index, AscDesc::Asc(Member::Field(S("sizes")))), @"[1, 6, 0, 2, 5, 4, 3]");
+    // descending by the highest value
+    insta::assert_snapshot!(sorted_ids(&index, AscDesc::Desc(Member::Field(S("tags")))), @"[1, 0, 5, 6, 2, 4, 3]");
+    insta::assert_snapshot!(sorted_ids(&index, AscDesc::Desc(Member::Field(S("sizes")))), @"[1, 0, 5, 6, 2, 4, 3]");
+}
+
+#[test]
+fn test_sort_nested_fields() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_sortable_fields(hashset! { S("author.name"), S("prices") });
+            s.set_criteria(vec![Criterion::Sort, Criterion::Asc(S("rank"))]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "rank": 0, "author": { "name": "b" }, "prices": [3, 10] },
+            { "id": 1, "rank": 1, "author": { "name": "a" }, "prices": [5] },
+            { "id": 2, "rank": 1, "author": { "name": "b" }, "prices": [4, 6] },
+            { "id": 3, "rank": 0 },
+            { "id": 4, "rank": 0, "author": { "name": "a" }, "prices": [1, 2] },
+            { "id": 5, "rank": 0, "author": { "name": "c" }, "prices": [] },
+            { "id": 6, "rank": -1, "author": { "name": "a" }, "prices": [3, 4] },
+        ]))
+        .unwrap();
+
+    let sorted_ids = |index: &TempIndex, sort: AscDesc| {
+        let txn = index.read_txn().unwrap();
+        let mut s = Search::new(&txn, index);
+        s.sort_criteria(vec![sort]);
+        let SearchResult { documents_ids, .. } = s.execute().unwrap();
+        format!("{documents_ids:?}")
+    };
+
+    // the documents of the same author are sorted by their rank, the ones without author last
+    insta::assert_snapshot!(sorted_ids(&index, AscDesc::Asc(Member::Field(S("author.name")))), @"[6, 4, 1, 0, 2, 5, 3]");
+    insta::assert_snapshot!(sorted_ids(&index, AscDesc::Desc(Member::Field(S("author.name")))), @"[5, 0, 2, 6, 4, 1, 3]");
+    // ascending by the lowest price, descending by the highest one
+    insta::assert_snapshot!(sorted_ids(&index, AscDesc::Asc(Member::Field(S("prices")))), @"[4, 6, 0, 2, 1, 3, 5]");
+    insta::assert_snapshot!(sorted_ids(&index, AscDesc::Desc(Member::Field(S("prices")))), @"[0, 2, 1, 6, 4, 3, 5]");
+
+    // the nested fields are also sortable when their parent object is
+    index
+        .update_settings(|s| {
+            s.set_sortable_fields(hashset! { S("author") });
+        })
+        .unwrap();
+    insta::assert_snapshot!(sorted_ids(&index, AscDesc::Asc(Member::Field(S("author.name")))), @"[6, 4, 1, 0, 2, 5, 3]");
+}
+
 #[test]
 fn test_redacted() {
     let index = create_index();
@@ -354,3 +446,144 @@ fn test_redacted() {
     insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2, 4, 5, 22, 23, 13, 1, 3, 12, 21, 11, 20, 6, 7, 8, 9, 10, 14, 15]");
     insta::assert_json_snapshot!(document_scores_json);
 }
+
+#[test]
+fn test_sort_accented_strings() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_sortable_fields(hashset! { S("word") });
+            s.set_criteria(vec![Criterion::Sort]);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "word": "eux" },
+            { "id": 1, "word": "été" },
+            { "id": 2, "word": "zèbre" },
+            { "id": 3, "word": "ete" },
+            { "id": 4, "word": "Écrire" },
+            { "id": 5, "word": "école" },
+            { "id": 6, "word": "öl" },
+        ]))
+        .unwrap();
+
+    let sorted_words = |index: &TempIndex, sort: AscDesc| {
+        let txn = index.read_txn().unwrap();
+        let mut s = Search::new(&txn, index);
+        s.sort_criteria(vec![sort]);
+        let SearchResult { documents_ids, .. } = s.execute().unwrap();
+        collect_field_values(index, &txn, "word", &documents_ids)
+    };
+
+    // the accented letters are sorted like their base letter, not after the `z`
+    insta::assert_debug_snapshot!(sorted_words(&index, AscDesc::Asc(Member::Field(S("word")))), @r###"
+    [
+        "\"école\"",
+        "\"Écrire\"",
+        "\"ete\"",
+        "\"été\"",
+        "\"eux\"",
+        "\"öl\"",
+        "\"zèbre\"",
+    ]
+    "###);
+    insta::assert_debug_snapshot!(sorted_words(&index, AscDesc::Desc(Member::Field(S("word")))), @r###"
+    [
+        "\"zèbre\"",
+        "\"öl\"",
+        "\"eux\"",
+        "\"été\"",
+        "\"ete\"",
+        "\"Écrire\"",
+        "\"école\"",
+    ]
+    "###);
+
+    // in swedish the `ö` comes after the `z`
+    index
+        .update_settings(|s| {
+            s.set_localized_attributes_rules(vec![LocalizedAttributesRule {
+                attribute_patterns: vec![S("word")],
+                locales: vec![Locale::Swe],
+            }]);
+        })
+        .unwrap();
+    insta::assert_debug_snapshot!(sorted_words(&index, AscDesc::Asc(Member::Field(S("word")))), @r###"
+    [
+        "\"école\"",
+        "\"Écrire\"",
+        "\"ete\"",
+        "\"été\"",
+        "\"eux\"",
+        "\"zèbre\"",
+        "\"öl\"",
+    ]
+    "###);
+}
+
+#[test]
+fn test_skipped_ranking_rules() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.set_criteria(vec![Criterion::Words, Criterion::Sort]);
+        })
+        .unwrap();
+    let txn = index.read_txn().unwrap();
+
+    let s = Search::new(&txn, &index);
+    let SearchResult { skipped_ranking_rules, .. } = s.execute().unwrap();
+    insta::assert_debug_snapshot!(skipped_ranking_rules, @r###"
+    [
+        SkippedRankingRule {
+            ranking_rule: "words",
+            reason: EmptyQuery,
+        },
+        SkippedRankingRule {
+            ranking_rule: "sort",
+            reason: NoSortParameter,
+        },
+    ]
+    "###);
+
+    let mut s = Search::new(&txn, &index);
+    s.query("text");
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    s.sort_criteria(vec![AscDesc::Desc(Member::Field(S("rank")))]);
+    let SearchResult { skipped_ranking_rules, .. } = s.execute().unwrap();
+    insta::assert_debug_snapshot!(skipped_ranking_rules, @r###"
+    [
+        SkippedRankingRule {
+            ranking_rule: "words",
+            reason: MatchingStrategyAll,
+        },
+    ]
+    "###);
+
+    let mut s = Search::new(&txn, &index);
+    s.query("text");
+    s.sort_criteria(vec![AscDesc::Desc(Member::Field(S("rank")))]);
+    let SearchResult { skipped_ranking_rules, .. } = s.execute().unwrap();
+    assert!(skipped_ranking_rules.is_empty());
+    drop(txn);
+
+    index
+        .update_settings(|s| {
+            s.set_sortable_fields(Default::default());
+        })
+        .unwrap();
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, &index);
+    s.query("text");
+    let SearchResult { skipped_ranking_rules, .. } = s.execute().unwrap();
+    insta::assert_debug_snapshot!(skipped_ranking_rules, @r###"
+    [
+        SkippedRankingRule {
+            ranking_rule: "sort",
+            reason: NoSortableAttributes,
+        },
+    ]
+    "###);
+}
@@ -0,0 +1,110 @@
+/*!
+This module tests the stemming of the words of the attributes:
+
+1. a plural word of the query matches the documents only containing its singular, and the reverse
+2. the documents containing the word of the query as it is typed are ranked first by exactness
+3. without stemming locales, or in an exact attribute, the words are not stemmed
+*/
+
+use std::collections::BTreeSet;
+
+use big_s::S;
+use maplit::hashset;
+
+use crate::collation::Locale;
+use crate::index::tests::TempIndex;
+use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index(stemming_locales: BTreeSet<Locale>) -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec![S("text"), S("code")]);
+            s.set_exact_attributes(hashset! { S("code") });
+            s.set_criteria(vec![Criterion::Words, Criterion::Exactness]);
+            s.set_stemming_locales(stemming_locales);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "red shoe" },
+            { "id": 1, "text": "red shoes" },
+            { "id": 2, "code": "shoes" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn search(index: &TempIndex, query: &str, attributes: &[String]) -> Vec<String> {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    s.query(query);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    s.searchable_attributes(attributes);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    index.external_ids_of(&txn, documents_ids).unwrap()
+}
+
+#[test]
+fn test_stemming() {
+    let index = create_index(BTreeSet::from([Locale::Eng]));
+    let text = [S("text")];
+
+    // the document containing `shoes` comes first, by exactness
+    insta::assert_debug_snapshot!(search(&index, "shoes", &text), @r###"
+    [
+        "1",
+        "0",
+    ]
+    "###);
+    // the stem of `shoes` is indexed at its position
+    insta::assert_debug_snapshot!(search(&index, "red shoe", &text), @r###"
+    [
+        "0",
+        "1",
+    ]
+    "###);
+}
+
+#[test]
+fn test_no_stemming_locales() {
+    let index = create_index(BTreeSet::new());
+    let text = [S("text")];
+
+    insta::assert_debug_snapshot!(search(&index, "shoes", &text), @r###"
+    [
+        "1",
+    ]
+    "###);
+    insta::assert_debug_snapshot!(search(&index, "shoe", &text), @r###"
+    [
+        "0",
+    ]
+    "###);
+
+    // enabling the stemming reindexes the documents
+    index.update_settings(|s| s.set_stemming_locales(BTreeSet::from([Locale::Eng]))).unwrap();
+    insta::assert_debug_snapshot!(search(&index, "shoe", &text), @r###"
+    [
+        "0",
+        "1",
+    ]
+    "###);
+}
+
+#[test]
+fn test_exact_attributes_are_not_stemmed() {
+    let index = create_index(BTreeSet::from([Locale::Eng]));
+    let code = [S("code")];
+
+    insta::assert_debug_snapshot!(search(&index, "shoe", &code), @"[]");
+    insta::assert_debug_snapshot!(search(&index, "shoes", &code), @r###"
+    [
+        "2",
+    ]
+    "###);
+}
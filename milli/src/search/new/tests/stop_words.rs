@@ -1,19 +1,19 @@
 /*!
 This module tests the following properties about stop words:
 - they are not indexed
-- they are not searchable
+- they are not searchable outside of phrases
 - they are case sensitive
-- they are ignored in phrases
+- they are matched literally in phrases, by checking the documents matching the other words
 - If a query consists only of stop words, a placeholder query is used instead
 - A prefix word is never ignored, even if the prefix is a stop word
-- Phrases consisting only of stop words are ignored
+- Phrases consisting only of stop words are ignored, and reported in the query tokens
 */
 
 use std::collections::BTreeSet;
 use std::iter::FromIterator;
 
 use crate::index::tests::TempIndex;
-use crate::{db_snap, Search, SearchResult, TermsMatchingStrategy};
+use crate::{db_snap, QueryTokenKind, Search, SearchResult, TermsMatchingStrategy};
 
 fn create_index() -> TempIndex {
     let index = TempIndex::new();
@@ -166,7 +166,7 @@ fn test_ignore_stop_words() {
             ),
             Position(
                 Rank {
-                    rank: 9,
+                    rank: 8,
                     max_rank: 11,
                 },
             ),
@@ -219,7 +219,7 @@ fn test_ignore_stop_words() {
             ),
             Position(
                 Rank {
-                    rank: 9,
+                    rank: 8,
                     max_rank: 11,
                 },
             ),
@@ -271,7 +271,7 @@ fn test_ignore_stop_words() {
             ),
             Position(
                 Rank {
-                    rank: 17,
+                    rank: 16,
                     max_rank: 21,
                 },
             ),
@@ -440,21 +440,75 @@ fn test_stop_words_in_phrase() {
     ]
     "###);
 
+    // no document contains `The dragon`, and a phrase is never removed from the query
     let mut s = Search::new(&txn, &index);
     s.query("how \"to\" train \"The dragon");
     s.terms_matching_strategy(TermsMatchingStrategy::Last);
     s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
     let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
-    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[3, 6, 5]");
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[]");
     insta::assert_snapshot!(format!("{document_scores:#?}"));
 
     let mut s = Search::new(&txn, &index);
     s.query("\"to\"");
     s.terms_matching_strategy(TermsMatchingStrategy::Last);
     s.scoring_strategy(crate::score_details::ScoringStrategy::Detailed);
-    let SearchResult { documents_ids, document_scores, .. } = s.execute().unwrap();
+    let SearchResult { documents_ids, document_scores, query_tokens, .. } = s.execute().unwrap();
     insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 2, 3, 4, 5, 6]");
     // The search is handled as a placeholder search because it doesn't have any non-stop words in it.
     // As a result the scores are empty lists
     insta::assert_snapshot!(format!("{document_scores:#?}"));
+    // the phrase can't be looked up as the stop words are not indexed
+    let kinds: Vec<_> =
+        query_tokens.iter().map(|token| (token.token.as_str(), token.kind)).collect();
+    assert!(kinds.contains(&("to", QueryTokenKind::PhraseStopWordSkipped)), "{kinds:?}");
+}
+
+#[test]
+fn test_stop_words_matched_literally_in_phrase() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("\"attempt to train\"");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[5]");
+
+    // the stop words must be at their place in the phrase
+    let mut s = Search::new(&txn, &index);
+    s.query("\"how attempt to\"");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[]");
+
+    let mut s = Search::new(&txn, &index);
+    s.query("\"how to\"");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[3, 5, 6]");
+
+    // outside of a phrase, the stop words are still ignored
+    let mut s = Search::new(&txn, &index);
+    s.query("attempt to train");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[5]");
+}
+
+#[test]
+fn test_stop_words_in_phrase_of_index_built_before() {
+    // The phrases don't rely on any database dedicated to the stop words, they are checked
+    // against the documents: the indexes built without the stop words are searched the same way.
+    let index = create_index();
+    db_snap!(index, word_docids, @"6288f9d7db3703b02c57025eb4a69264");
+
+    let txn = index.read_txn().unwrap();
+    assert!(index.word_docids.get(&txn, "to").unwrap().is_none());
+
+    let mut s = Search::new(&txn, &index);
+    s.query("\"how to train\"");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[3, 6]");
+
+    let mut s = Search::new(&txn, &index);
+    s.query("\"how train\"");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[]");
 }
@@ -0,0 +1,143 @@
+/*!
+This module tests the streaming of the documents of a search:
+
+1. the batches given to the hits callback, concatenated, are the documents of the search
+2. the search stops right after the first batch when the callback asks to stop
+3. an error of the callback stops the search with this error
+*/
+
+use std::ops::ControlFlow;
+
+use big_s::S;
+use maplit::hashset;
+
+use crate::index::tests::TempIndex;
+use crate::{AscDesc, Criterion, Member, Result, Search, SearchResult};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["text".to_owned()]);
+            s.set_sortable_fields(hashset! { S("rank") });
+            s.set_criteria(vec![Criterion::Words, Criterion::Typo, Criterion::Sort]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "hello puppy kefir", "rank": 3 },
+            { "id": 1, "text": "hello puppy", "rank": 1 },
+            { "id": 2, "text": "hello", "rank": 4 },
+            { "id": 3, "text": "hella puppy kefir", "rank": 0 },
+            { "id": 4, "text": "hella puppo kefir", "rank": 2 },
+            { "id": 5, "text": "hello kefir", "rank": 5 },
+            { "id": 6, "text": "puppy", "rank": 6 },
+        ]))
+        .unwrap();
+    index
+}
+
+/// Executes the search with the hits callback, returns its result and the batches it received.
+fn search_streaming(
+    index: &TempIndex,
+    query: Option<&str>,
+    sort: bool,
+    offset: usize,
+    limit: usize,
+    on_hits: fn(&[u32]) -> Result<ControlFlow<()>>,
+) -> (Result<SearchResult>, Vec<Vec<u32>>) {
+    let txn = index.read_txn().unwrap();
+    let mut batches = Vec::new();
+    let result = {
+        let mut s = Search::new(&txn, index);
+        if let Some(query) = query {
+            s.query(query);
+        }
+        if sort {
+            s.sort_criteria(vec![AscDesc::Asc(Member::Field(S("rank")))]);
+        }
+        s.offset(offset);
+        s.limit(limit);
+        s.execute_streaming(|docids| {
+            batches.push(docids.to_vec());
+            on_hits(docids)
+        })
+    };
+    (result, batches)
+}
+
+fn search(
+    index: &TempIndex,
+    query: Option<&str>,
+    sort: bool,
+    offset: usize,
+    limit: usize,
+) -> Vec<u32> {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    if let Some(query) = query {
+        s.query(query);
+    }
+    if sort {
+        s.sort_criteria(vec![AscDesc::Asc(Member::Field(S("rank")))]);
+    }
+    s.offset(offset);
+    s.limit(limit);
+    s.execute().unwrap().documents_ids
+}
+
+#[test]
+fn test_streamed_batches_are_the_documents_of_the_search() {
+    let index = create_index();
+
+    for query in [None, Some("hello puppy kefir"), Some("hello")] {
+        for sort in [false, true] {
+            for (offset, limit) in [(0, 20), (0, 3), (2, 3), (10, 5)] {
+                let context = format!("{query:?}, sort: {sort}, {offset}..+{limit}");
+                let (result, batches) =
+                    search_streaming(&index, query, sort, offset, limit, |_| {
+                        Ok(ControlFlow::Continue(()))
+                    });
+                let result = result.unwrap();
+
+                assert!(batches.iter().all(|batch| !batch.is_empty()), "{context}");
+                let streamed: Vec<_> = batches.concat();
+                assert_eq!(streamed, result.documents_ids, "{context}");
+                assert_eq!(streamed, search(&index, query, sort, offset, limit), "{context}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_break_stops_after_the_first_batch() {
+    let index = create_index();
+
+    let complete = search(&index, Some("hello puppy kefir"), false, 0, 20);
+    let (result, batches) =
+        search_streaming(&index, Some("hello puppy kefir"), false, 0, 20, |_| {
+            Ok(ControlFlow::Break(()))
+        });
+    let result = result.unwrap();
+
+    // the first bucket only contains the document matching all the words without typos
+    assert_eq!(batches, vec![vec![0]]);
+    assert_eq!(result.documents_ids, vec![0]);
+    assert!(complete.len() > result.documents_ids.len());
+    assert!(!result.degraded);
+}
+
+#[test]
+fn test_callback_error_stops_the_search() {
+    let index = create_index();
+
+    let (result, batches) =
+        search_streaming(&index, Some("hello puppy kefir"), false, 0, 20, |_| {
+            Err(crate::Error::SearchCancelled)
+        });
+    assert!(matches!(result, Err(crate::Error::SearchCancelled)));
+    assert_eq!(batches.len(), 1);
+}
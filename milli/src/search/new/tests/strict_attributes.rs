@@ -0,0 +1,112 @@
+/*!
+This module tests the strict attributes, which only match the query words as they are typed:
+
+1. a query word with a typo never matches a strict attribute
+2. a prefix never matches a strict attribute, even on the last word of the query
+3. the split words of a query word never match a strict attribute
+4. the phrases written in the query still match the strict attributes
+5. the other attributes keep matching the typos, the prefixes, and the split words
+*/
+
+use crate::index::tests::TempIndex;
+use crate::{Search, SearchResult};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["title".to_owned(), "sku".to_owned()]);
+            s.set_strict_attributes(["sku".to_owned()].into_iter().collect());
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "title": "sunflower", "sku": "rose" },
+            { "id": 1, "title": "rose", "sku": "sunflower" },
+            { "id": 2, "title": "tulip", "sku": "sunflowers" },
+            { "id": 3, "title": "tulip", "sku": "sun flower" },
+            { "id": 4, "title": "sunflowers", "sku": "lily" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn search(index: &TempIndex, query: &str, searchable: Option<&[String]>) -> Vec<u32> {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    s.query(query);
+    if let Some(searchable) = searchable {
+        s.searchable_attributes(searchable);
+    }
+    let SearchResult { mut documents_ids, .. } = s.execute().unwrap();
+    documents_ids.sort_unstable();
+    documents_ids
+}
+
+#[test]
+fn test_strict_attributes_no_typo() {
+    let index = create_index();
+
+    // the title matches with one typo, the sku doesn't
+    let documents_ids = search(&index, "sunflowr", None);
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 4]");
+}
+
+#[test]
+fn test_strict_attributes_no_prefix() {
+    let index = create_index();
+
+    // `sunflowers` is a prefix match in the title of 4, but not in the sku of 2
+    let documents_ids = search(&index, "sunflower", None);
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 4]");
+
+    let documents_ids = search(&index, "sunflo", None);
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 4]");
+}
+
+#[test]
+fn test_strict_attributes_no_split_words() {
+    let index = create_index();
+
+    // `sunflower` can be split into `sun flower`, which is only found in the sku of 3,
+    // and `sunflowers` is a typo away, which is only found in the title of 4
+    let documents_ids = search(&index, "sunflower ", None);
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 4]");
+
+    // but the phrase written in the query matches it
+    let documents_ids = search(&index, "\"sun flower\"", None);
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[3]");
+}
+
+#[test]
+fn test_strict_attributes_attributes_to_search_on() {
+    let index = create_index();
+
+    let sku = ["sku".to_owned()];
+    let documents_ids = search(&index, "sunflower", Some(&sku));
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1]");
+    let documents_ids = search(&index, "sunflowr", Some(&sku));
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[]");
+
+    let title = ["title".to_owned()];
+    let documents_ids = search(&index, "sunflower", Some(&title));
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 4]");
+}
+
+#[test]
+fn test_strict_attributes_reset() {
+    let index = create_index();
+    index
+        .update_settings(|s| {
+            s.reset_strict_attributes();
+        })
+        .unwrap();
+
+    // all the derivations are back in the sku
+    let documents_ids = search(&index, "sunflowr", None);
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 2, 4]");
+}
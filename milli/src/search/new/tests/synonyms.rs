@@ -0,0 +1,125 @@
+/*!
+This module tests the following properties about synonyms:
+
+1. A word is searched with its synonyms, a multi-word synonym must be matched as a phrase
+2. The synonyms of the last word of the query are not prefix searched, but the word itself is
+3. Overlapping consecutive words can each have their own multi-word synonyms
+4. A stop word with synonyms is searched with them instead of being ignored
+*/
+
+use std::collections::BTreeSet;
+use std::iter::FromIterator;
+
+use big_s::S;
+use maplit::btreemap;
+
+use crate::index::tests::TempIndex;
+use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec!["title".to_owned()]);
+            s.set_criteria(vec![Criterion::Words]);
+            s.set_stop_words(BTreeSet::from_iter([S("and")]));
+            s.set_synonyms(btreemap! {
+                S("nyc") => vec![S("new york")],
+                S("ny") => vec![S("new york")],
+                S("new york") => vec![S("nyc")],
+                S("york city") => vec![S("big apple")],
+                S("and") => vec![S("plus")],
+            });
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "title": "the statue of liberty in new york" },
+            { "id": 1, "title": "i love nyc" },
+            { "id": 2, "title": "new york city" },
+            { "id": 3, "title": "york is not new" },
+            { "id": 4, "title": "san francisco" },
+            { "id": 5, "title": "nyc city hall" },
+            { "id": 6, "title": "a new big apple" },
+            { "id": 7, "title": "bread plus butter" },
+            { "id": 8, "title": "bread and butter" },
+            { "id": 9, "title": "peanut butter" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+#[test]
+fn test_synonyms_multi_words() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("nyc trip");
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    // the documents only containing `new york` are found, but not the one with `york` and `new` apart
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 2, 5]");
+
+    let mut s = Search::new(&txn, &index);
+    s.query("new york");
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 2, 3, 5]");
+}
+
+#[test]
+fn test_synonyms_prefix() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("ny");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    // `ny` is a prefix of `nyc`, and `new york` is its synonym
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 1, 2, 5]");
+
+    let mut s = Search::new(&txn, &index);
+    s.query("new yo");
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    // `yo` is only a prefix of `york`, the synonym of `new york` is not searched
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 2, 3]");
+}
+
+#[test]
+fn test_synonyms_overlapping() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("new york city");
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    // `new york` => `nyc` and `york city` => `big apple` overlap on `york`
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[2, 5, 6]");
+}
+
+#[test]
+fn test_synonyms_of_stop_word() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, &index);
+    s.query("and butter");
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    // the stop word is not ignored, `peanut butter` contains neither `and` nor `plus`,
+    // but as the stop words are not indexed, only its synonyms match the documents
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[7]");
+
+    let mut s = Search::new(&txn, &index);
+    s.query("butter and");
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[7]");
+}
@@ -0,0 +1,145 @@
+/*!
+This module tests the tie break of the documents ranked the same by all the ranking rules:
+1. by default, they are returned in the order they were indexed in
+2. with `TieBreak::DocumentId`, they are returned in the order of their document ids,
+whatever the order they were indexed in, numeric ids first
+3. the offset and the distinct attribute are applied after this ordering
+*/
+
+use big_s::S;
+use serde_json::{json, Value};
+
+use crate::index::tests::TempIndex;
+use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy, TieBreak};
+
+fn documents() -> Vec<Value> {
+    vec![
+        json!({ "id": "b", "text": "hello", "group": 0 }),
+        json!({ "id": 10, "text": "hello", "group": 1 }),
+        json!({ "id": "a", "text": "hello", "group": 1 }),
+        json!({ "id": 2, "text": "hello", "group": 0 }),
+        json!({ "id": "c", "text": "hello", "group": 2 }),
+        json!({ "id": 1, "text": "hello world", "group": 3 }),
+    ]
+}
+
+fn create_index(documents: Vec<Value>, distinct: bool) -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec![S("text")]);
+            s.set_criteria(vec![Criterion::Words]);
+            if distinct {
+                s.set_distinct_field(S("group"));
+            }
+        })
+        .unwrap();
+
+    index.add_documents(documents!(documents)).unwrap();
+    index
+}
+
+fn search_external_ids(
+    index: &TempIndex,
+    query: Option<&str>,
+    tie_break: TieBreak,
+    offset: usize,
+) -> Vec<String> {
+    let txn = index.read_txn().unwrap();
+
+    let mut s = Search::new(&txn, index);
+    if let Some(query) = query {
+        s.query(query);
+    }
+    s.terms_matching_strategy(TermsMatchingStrategy::Last);
+    s.tie_break(tie_break);
+    s.offset(offset);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    index.external_ids_of(&txn, documents_ids).unwrap()
+}
+
+#[test]
+fn test_tie_break_internal_id() {
+    let index = create_index(documents(), false);
+
+    let ids = search_external_ids(&index, Some("hello world"), TieBreak::InternalId, 0);
+    insta::assert_debug_snapshot!(ids, @r###"
+    [
+        "1",
+        "b",
+        "10",
+        "a",
+        "2",
+        "c",
+    ]
+    "###);
+}
+
+#[test]
+fn test_tie_break_document_id_is_stable_across_indexing_orders() {
+    let mut reversed = documents();
+    reversed.reverse();
+
+    for distinct in [false, true] {
+        let index = create_index(documents(), distinct);
+        let reversed_index = create_index(reversed.clone(), distinct);
+        for query in [Some("hello world"), None] {
+            for offset in [0, 2] {
+                let ids = search_external_ids(&index, query, TieBreak::DocumentId, offset);
+                let reversed_ids =
+                    search_external_ids(&reversed_index, query, TieBreak::DocumentId, offset);
+                assert_eq!(ids, reversed_ids, "{query:?} {offset} {distinct}");
+            }
+        }
+    }
+
+    let index = create_index(documents(), false);
+    let ids = search_external_ids(&index, Some("hello world"), TieBreak::DocumentId, 0);
+    insta::assert_debug_snapshot!(ids, @r###"
+    [
+        "1",
+        "2",
+        "10",
+        "a",
+        "b",
+        "c",
+    ]
+    "###);
+
+    // the offset skips the documents in the order of their ids
+    let ids = search_external_ids(&index, Some("hello world"), TieBreak::DocumentId, 2);
+    insta::assert_debug_snapshot!(ids, @r###"
+    [
+        "10",
+        "a",
+        "b",
+        "c",
+    ]
+    "###);
+
+    // the document kept for each distinct value is the one with the smallest id
+    let distinct_index = create_index(documents(), true);
+    let ids = search_external_ids(&distinct_index, Some("hello world"), TieBreak::DocumentId, 0);
+    insta::assert_debug_snapshot!(ids, @r###"
+    [
+        "1",
+        "2",
+        "10",
+        "c",
+    ]
+    "###);
+
+    let ids = search_external_ids(&index, None, TieBreak::DocumentId, 0);
+    insta::assert_debug_snapshot!(ids, @r###"
+    [
+        "1",
+        "2",
+        "10",
+        "a",
+        "b",
+        "c",
+    ]
+    "###);
+}
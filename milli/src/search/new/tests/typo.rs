@@ -16,13 +16,18 @@ if `words` doesn't exist before it.
 12. Prefix tolerance costs nothing according to the typo ranking rule
 13. Split words cost 1 typo according to the typo ranking rule
 14. Synonyms cost nothing according to the typo ranking rule
+15. The typo derivations of a word follow the typo tolerance settings, and there are none
+when typos are disabled for the word, for the index, or for all the attributes searched
+16. The minimum sizes of the words allowing typos are counted in characters
 */
 
 use std::collections::BTreeMap;
 
 use crate::index::tests::TempIndex;
+use crate::search::new::query_graph::QueryNodeData;
+use crate::search::new::query_term::LocatedQueryTermSubset;
 use crate::search::new::tests::collect_field_values;
-use crate::{Criterion, Search, SearchResult, TermsMatchingStrategy};
+use crate::{Criterion, Search, SearchContext, SearchResult, TermsMatchingStrategy};
 
 fn create_index() -> TempIndex {
     let index = TempIndex::new();
@@ -635,3 +640,137 @@ fn test_typo_synonyms() {
     ]
     "###);
 }
+
+#[test]
+fn test_query_synonyms() {
+    let index = create_index();
+    let txn = index.read_txn().unwrap();
+
+    let mut synonyms = BTreeMap::new();
+    synonyms.insert("Sluggish".to_owned(), vec!["LAZY".to_owned()]);
+
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    s.query("fox sluggish dog");
+    s.synonyms(&synonyms);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0, 23]");
+    let texts = collect_field_values(&index, &txn, "text", &documents_ids);
+    insta::assert_debug_snapshot!(texts, @r###"
+    [
+        "\"the quick brown fox jumps over the lazy dog\"",
+        "\"the quivk brown fox jumps over the lazy dog\"",
+    ]
+    "###);
+
+    // the synonyms only apply to the search they were given to
+    let mut s = Search::new(&txn, &index);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    s.query("fox sluggish dog");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[]");
+}
+
+/// Returns the derivations found in the index of the single word of the query.
+fn word_derivations(index: &TempIndex, query: &str) -> Vec<String> {
+    let txn = index.read_txn().unwrap();
+    let mut ctx = SearchContext::new(index, &txn);
+    let graph = ctx.query_graph(query, None).unwrap().unwrap();
+    let term_subset = graph
+        .nodes
+        .iter()
+        .find_map(|(_, node)| match &node.data {
+            QueryNodeData::Term(LocatedQueryTermSubset { term_subset, .. }) => {
+                Some(term_subset.clone())
+            }
+            _ => None,
+        })
+        .unwrap();
+
+    let words = term_subset.all_single_words_except_prefix_db(&mut ctx).unwrap();
+    words.into_iter().map(|w| ctx.word_interner.get(w.interned()).clone()).collect()
+}
+
+fn search_quivk(index: &TempIndex) -> Vec<u32> {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    s.query("the quivk brown fox jumps over the lazy dog");
+    s.execute().unwrap().documents_ids
+}
+
+#[test]
+fn test_typo_derivations_follow_settings() {
+    let index = create_index();
+    let words = word_derivations(&index, "quivk");
+    assert!(words.contains(&"quick".to_owned()), "{words:?}");
+    insta::assert_snapshot!(format!("{:?}", search_quivk(&index)), @"[0, 23]");
+
+    // the word is too short for a typo
+    index.update_settings(|s| s.set_min_word_len_one_typo(6)).unwrap();
+    let words = word_derivations(&index, "quivk");
+    insta::assert_snapshot!(format!("{words:?}"), @r###"["quivk"]"###);
+    insta::assert_snapshot!(format!("{:?}", search_quivk(&index)), @"[23]");
+    index.update_settings(|s| s.reset_min_word_len_one_typo()).unwrap();
+
+    // typos are disabled on the word
+    index.update_settings(|s| s.set_exact_words(["quivk".to_owned()].into())).unwrap();
+    let words = word_derivations(&index, "quivk");
+    insta::assert_snapshot!(format!("{words:?}"), @r###"["quivk"]"###);
+    insta::assert_snapshot!(format!("{:?}", search_quivk(&index)), @"[23]");
+    index.update_settings(|s| s.reset_exact_words()).unwrap();
+
+    // typos are disabled on the index
+    index.update_settings(|s| s.set_autorize_typos(false)).unwrap();
+    let words = word_derivations(&index, "quivk");
+    insta::assert_snapshot!(format!("{words:?}"), @r###"["quivk"]"###);
+    insta::assert_snapshot!(format!("{:?}", search_quivk(&index)), @"[23]");
+    index.update_settings(|s| s.reset_authorize_typos()).unwrap();
+
+    // typos are disabled on all the searchable attributes
+    index.update_settings(|s| s.set_exact_attributes(["text".to_owned()].into())).unwrap();
+    let words = word_derivations(&index, "quivk");
+    insta::assert_snapshot!(format!("{words:?}"), @r###"["quivk"]"###);
+    insta::assert_snapshot!(format!("{:?}", search_quivk(&index)), @"[23]");
+
+    // but not on all of them
+    index
+        .update_settings(|s| {
+            s.set_searchable_fields(vec!["text".to_owned(), "tolerant_text".to_owned()])
+        })
+        .unwrap();
+    let words = word_derivations(&index, "quivk");
+    assert!(words.contains(&"quick".to_owned()), "{words:?}");
+    insta::assert_snapshot!(format!("{:?}", search_quivk(&index)), @"[23, 24, 25]");
+}
+
+#[test]
+fn test_typo_min_word_len_in_characters() {
+    let index = TempIndex::new();
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_min_word_len_one_typo(4);
+        })
+        .unwrap();
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "кит" },
+            { "id": 1, "text": "кот" },
+            { "id": 2, "text": "котик" },
+        ]))
+        .unwrap();
+    let txn = index.read_txn().unwrap();
+
+    // the word has 3 characters but 6 bytes, no typo is allowed
+    let words = word_derivations(&index, "кот");
+    insta::assert_snapshot!(format!("{words:?}"), @r###"["кот", "котик"]"###);
+    let mut s = Search::new(&txn, &index);
+    s.query("кот");
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[1, 2]");
+
+    // the word has 4 characters, one typo is allowed
+    let words = word_derivations(&index, "киты");
+    assert!(words.contains(&"кит".to_owned()), "{words:?}");
+}
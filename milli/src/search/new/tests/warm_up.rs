@@ -0,0 +1,129 @@
+/*!
+This module tests the warm-up of the search cache of an index:
+
+1. the shared cache contains the docids of the words and of the prefixes starting with the warmed up prefixes
+2. the searches find the docids in the shared cache and return the same results
+3. the shared cache is not used anymore once the index is written
+*/
+
+use big_s::S;
+use heed::types::Bytes;
+
+use crate::index::tests::TempIndex;
+use crate::search::new::SharedSearchCache;
+use crate::{Criterion, Search, SearchResult};
+
+fn create_index() -> TempIndex {
+    let index = TempIndex::new();
+
+    index
+        .update_settings(|s| {
+            s.set_primary_key("id".to_owned());
+            s.set_searchable_fields(vec![S("text")]);
+            s.set_criteria(vec![Criterion::Words, Criterion::Typo]);
+        })
+        .unwrap();
+
+    index
+        .add_documents(documents!([
+            { "id": 0, "text": "the sun is shining" },
+            { "id": 1, "text": "a sunflower in the sun" },
+            { "id": 2, "text": "sunny monday" },
+            { "id": 3, "text": "moonlight sunset" },
+            { "id": 4, "text": "the moon" },
+        ]))
+        .unwrap();
+
+    index
+}
+
+fn search(index: &TempIndex, query: &str) -> SearchResult {
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, index);
+    s.query(query);
+    s.execute().unwrap()
+}
+
+#[test]
+fn test_warm_up_populates_the_shared_cache() {
+    let index = create_index();
+    let before = search(&index, "sun");
+    assert_eq!(before.metrics.shared_cache_hits, 0);
+
+    let mut wtxn = index.write_txn().unwrap();
+    let stats = index.warm_up_search_cache(&mut wtxn, &[S("sun")], true).unwrap();
+    wtxn.commit().unwrap();
+
+    let txn = index.read_txn().unwrap();
+    let shared = SharedSearchCache::of(&index, &txn).unwrap().unwrap();
+    let mut words: Vec<_> =
+        shared.word_docids.keys().map(|word| std::str::from_utf8(word).unwrap()).collect();
+    words.sort_unstable();
+    insta::assert_debug_snapshot!(words, @r###"
+    [
+        "sun",
+        "sunflower",
+        "sunny",
+        "sunset",
+    ]
+    "###);
+    for (word, docids) in shared.word_docids.iter() {
+        let expected = index.word_docids.remap_types::<Bytes, Bytes>().get(&txn, word).unwrap();
+        assert_eq!(expected, Some(&docids[..]));
+    }
+    // the prefixes are the same as the ones of the database
+    let prefixes: Vec<_> = index
+        .word_prefix_docids
+        .remap_types::<Bytes, Bytes>()
+        .prefix_iter(&txn, b"sun")
+        .unwrap()
+        .map(|result| result.unwrap().0.to_vec())
+        .collect();
+    let mut shared_prefixes: Vec<_> =
+        shared.word_prefix_docids.keys().map(|p| p.to_vec()).collect();
+    shared_prefixes.sort_unstable();
+    assert_eq!(prefixes, shared_prefixes);
+    assert_eq!(stats.word_entries, words.len() as u64);
+    assert_eq!(stats.prefix_entries, prefixes.len() as u64);
+    drop(txn);
+
+    let after = search(&index, "sun");
+    assert!(after.metrics.shared_cache_hits > 0);
+    assert_eq!(before.documents_ids, after.documents_ids);
+    assert_eq!(before.candidates, after.candidates);
+}
+
+#[test]
+fn test_shared_cache_is_dropped_by_a_write() {
+    let index = create_index();
+
+    let mut wtxn = index.write_txn().unwrap();
+    index.warm_up_search_cache(&mut wtxn, &[S("sun"), S("moon")], true).unwrap();
+    wtxn.commit().unwrap();
+
+    let txn = index.read_txn().unwrap();
+    assert!(SharedSearchCache::of(&index, &txn).unwrap().is_some());
+    drop(txn);
+
+    index.add_documents(documents!([{ "id": 5, "text": "sunday" }])).unwrap();
+
+    let txn = index.read_txn().unwrap();
+    assert!(SharedSearchCache::of(&index, &txn).unwrap().is_none());
+    drop(txn);
+    let after = search(&index, "sun");
+    assert_eq!(after.metrics.shared_cache_hits, 0);
+    assert!(after.documents_ids.contains(&5));
+}
+
+#[test]
+fn test_warm_up_without_populating_the_shared_cache() {
+    let index = create_index();
+
+    let mut wtxn = index.write_txn().unwrap();
+    let stats = index.warm_up_search_cache(&mut wtxn, &[S("sun")], false).unwrap();
+    wtxn.commit().unwrap();
+
+    assert_eq!(stats.word_entries, 4);
+    let txn = index.read_txn().unwrap();
+    assert!(SharedSearchCache::of(&index, &txn).unwrap().is_none());
+}
@@ -10,6 +10,7 @@ account by the proximity ranking rule.
 5. Unclosed double quotes still make a phrase
 6. The `all` term matching strategy does not remove any term from the query
 7. The search is capable of returning no results if no documents match the query
+8. The `frequency` term matching strategy starts removing the terms matching the fewest documents
 */
 
 use crate::index::tests::TempIndex;
@@ -458,3 +459,25 @@ fn test_words_tms_all() {
     let texts = collect_field_values(&index, &txn, "text", &documents_ids);
     insta::assert_debug_snapshot!(texts, @"[]");
 }
+
+#[test]
+fn test_words_tms_frequency() {
+    let index = create_index();
+
+    let txn = index.read_txn().unwrap();
+    let mut s = Search::new(&txn, &index);
+    s.query("extravagant the quick brown fox jumps over the lazy dog");
+    s.terms_matching_strategy(TermsMatchingStrategy::Frequency);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+
+    // `extravagant` matches no document, it is removed first and the documents
+    // matching all the other terms come first
+    insta::assert_snapshot!(format!("{:?}", &documents_ids[..12]), @"[9, 10, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22]");
+    assert!(documents_ids.len() > 12, "{documents_ids:?}");
+
+    let mut s = Search::new(&txn, &index);
+    s.query("extravagant the quick brown fox jumps over the lazy dog");
+    s.terms_matching_strategy(TermsMatchingStrategy::All);
+    let SearchResult { documents_ids, .. } = s.execute().unwrap();
+    insta::assert_snapshot!(format!("{documents_ids:?}"), @"[]");
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use charabia::{Normalize, TokenizerBuilder};
+use heed::types::Bytes;
+use heed::{BytesDecode, BytesEncode, RoTxn};
+use roaring::RoaringBitmap;
+use serde::Serialize;
+
+use crate::heed_codec::BytesDecodeOwned;
+use crate::proximity::MAX_DISTANCE;
+use crate::{CboRoaringBitmapCodec, FieldId, Filter, Index, Result, U8StrStrCodec};
+
+/// The default number of related terms returned.
+pub const DEFAULT_RELATED_TERMS_LIMIT: usize = 10;
+
+/// An indexed word found close to the searched word.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatedTerm {
+    pub term: String,
+    /// The number of documents in which both words are close to each other.
+    pub documents: u64,
+}
+
+/// Finds the words the most frequently found close to a word, to suggest related searches.
+///
+/// The words are read from the word pair proximity database, so only the words following the
+/// searched word are found, and nothing is found when the proximity precision is by attribute.
+pub struct RelatedTerms<'a> {
+    word: String,
+    rtxn: &'a RoTxn<'a>,
+    index: &'a Index,
+    attributes: Option<Vec<String>>,
+    limit: usize,
+    filter: Option<Filter<'a>>,
+}
+
+impl<'a> RelatedTerms<'a> {
+    pub fn new(word: impl Into<String>, rtxn: &'a RoTxn<'a>, index: &'a Index) -> Self {
+        RelatedTerms {
+            word: word.into(),
+            rtxn,
+            index,
+            attributes: None,
+            limit: DEFAULT_RELATED_TERMS_LIMIT,
+            filter: None,
+        }
+    }
+
+    /// Only counts the documents matching this filter.
+    pub fn filter(&mut self, filter: Filter<'a>) -> &mut Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Only counts the documents in which both words are found in one of these attributes.
+    pub fn attributes(&mut self, attributes: Vec<String>) -> &mut Self {
+        self.attributes = Some(attributes);
+        self
+    }
+
+    pub fn limit(&mut self, limit: usize) -> &mut Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Returns the related terms, the ones found close to the word in the most documents first.
+    pub fn execute(&self) -> Result<Vec<RelatedTerm>> {
+        let Some(word) = self.normalized_word() else { return Ok(Vec::new()) };
+        let stop_words = self.index.stop_words(self.rtxn)?;
+        let is_stop_word = |term: &str| stop_words.as_ref().map_or(false, |sw| sw.contains(term));
+        if is_stop_word(&word) {
+            return Ok(Vec::new());
+        }
+
+        let universe = match &self.filter {
+            Some(filter) => Some(filter.evaluate(self.rtxn, self.index)?),
+            None => None,
+        };
+        let fids = self.attributes_fids()?;
+        let word_docids = match &fids {
+            Some(fids) => Some(self.word_docids_within(&word, fids)?),
+            None => None,
+        };
+
+        let mut cooccurrences: HashMap<String, RoaringBitmap> = HashMap::new();
+        for proximity in 1..MAX_DISTANCE as u8 {
+            let prefix = U8StrStrCodec::bytes_encode(&(proximity, word.as_str(), ""))
+                .map_err(heed::Error::Encoding)?
+                .into_owned();
+            let iter = self
+                .index
+                .word_pair_proximity_docids
+                .remap_types::<Bytes, Bytes>()
+                .prefix_iter(self.rtxn, &prefix)?;
+            for result in iter {
+                let (key, docids) = result?;
+                let (_, _, term) =
+                    U8StrStrCodec::bytes_decode(key).map_err(heed::Error::Decoding)?;
+                if term == word || is_stop_word(term) {
+                    continue;
+                }
+                let docids = CboRoaringBitmapCodec::bytes_decode_owned(docids)
+                    .map_err(heed::Error::Decoding)?;
+                *cooccurrences.entry(term.to_string()).or_default() |= docids;
+            }
+        }
+
+        let mut related = Vec::with_capacity(cooccurrences.len());
+        for (term, mut docids) in cooccurrences {
+            if let (Some(fids), Some(word_docids)) = (&fids, &word_docids) {
+                docids &= word_docids;
+                docids &= self.word_docids_within(&term, fids)?;
+            }
+            if let Some(universe) = &universe {
+                docids &= universe;
+            }
+            if !docids.is_empty() {
+                related.push(RelatedTerm { term, documents: docids.len() });
+            }
+        }
+        related.sort_unstable_by(|a, b| b.documents.cmp(&a.documents).then(a.term.cmp(&b.term)));
+        related.truncate(self.limit);
+
+        Ok(related)
+    }
+
+    /// The word as it is indexed, `None` if it isn't a single word.
+    fn normalized_word(&self) -> Option<String> {
+        let tokenizer = TokenizerBuilder::default().into_tokenizer();
+        let mut words = tokenizer.tokenize(&self.word).filter(|token| token.is_word());
+        let word = words.next()?;
+        match words.next() {
+            Some(_) => None,
+            None => Some(word.normalize(&Default::default()).lemma().to_string()),
+        }
+    }
+
+    /// The ids of the restricted attributes, the unknown ones being ignored.
+    fn attributes_fids(&self) -> Result<Option<Vec<FieldId>>> {
+        let Some(attributes) = &self.attributes else { return Ok(None) };
+        let fields_ids_map = self.index.fields_ids_map(self.rtxn)?;
+        Ok(Some(attributes.iter().filter_map(|name| fields_ids_map.id(name)).collect()))
+    }
+
+    /// The documents containing the word in one of the attributes.
+    fn word_docids_within(&self, word: &str, fids: &[FieldId]) -> Result<RoaringBitmap> {
+        let mut docids = RoaringBitmap::new();
+        for fid in fids {
+            if let Some(fid_docids) = self.index.word_fid_docids.get(self.rtxn, &(word, *fid))? {
+                docids |= fid_docids;
+            }
+        }
+        Ok(docids)
+    }
+}
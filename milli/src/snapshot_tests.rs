@@ -390,7 +390,7 @@ pub fn snap_documents(index: &Index) -> String {
     let display = fields_ids_map.ids().collect::<Vec<_>>();
 
     for document in index.all_documents(&rtxn).unwrap() {
-        let doc = obkv_to_json(&display, &fields_ids_map, document.unwrap().1).unwrap();
+        let doc = obkv_to_json(&display, &fields_ids_map, document.unwrap().1.reader()).unwrap();
         snap.push_str(&serde_json::to_string(&doc).unwrap());
         snap.push('\n');
     }
@@ -0,0 +1,103 @@
+//! The light stemming of the words of the searchable attributes.
+//!
+//! When the `stemming` setting lists locales, the words of the attributes in these locales are
+//! also indexed under their stem, at the same position, and the words of the queries made on
+//! them are also searched under their stem, so that `shoes` matches a document only containing
+//! `shoe`. The documents containing the words as they are typed are still ranked first by the
+//! exactness ranking rule.
+
+use crate::collation::Locale;
+
+/// Minimum number of characters of a word for it to be stemmed.
+const MIN_STEMMED_WORD_LEN: usize = 4;
+
+/// The locales whose words can be stemmed.
+pub const SUPPORTED_LOCALES: &[Locale] = &[Locale::Eng];
+
+/// Whether the words of the locale can be stemmed.
+pub fn is_supported(locale: Locale) -> bool {
+    SUPPORTED_LOCALES.contains(&locale)
+}
+
+/// Returns the stem of the word in the locale, `None` if the locale isn't supported or if
+/// the word is already its own stem.
+pub fn stem(word: &str, locale: Locale) -> Option<String> {
+    if word.chars().count() < MIN_STEMMED_WORD_LEN {
+        return None;
+    }
+    let stem = match locale {
+        Locale::Eng => english_stem(word),
+        _ => return None,
+    };
+    (stem != word).then_some(stem)
+}
+
+/// Returns the stems of the word in the locales that differ from the word.
+pub fn stems<'a>(word: &'a str, locales: &'a [Locale]) -> impl Iterator<Item = String> + 'a {
+    locales.iter().filter_map(move |&locale| stem(word, locale))
+}
+
+/// Strips the possessive and then the plural suffix of an English word.
+///
+/// The plural is stripped with the rules of the S-stemmer, only the first matching one applies:
+/// - `ies` becomes `y`, except after an `a` or an `e`,
+/// - `es` becomes `e`, except after an `a`, an `e` or an `o`,
+/// - `s` is removed, except after an `i`, an `s` or an `u`.
+fn english_stem(word: &str) -> String {
+    let word = ["'s", "’s", "'", "’"]
+        .iter()
+        .find_map(|possessive| word.strip_suffix(possessive))
+        .unwrap_or(word);
+
+    if let Some(base) = word.strip_suffix("ies").filter(|base| !base.ends_with(['a', 'e'])) {
+        format!("{base}y")
+    } else if let Some(base) =
+        word.strip_suffix("es").filter(|base| !base.ends_with(['a', 'e', 'o']))
+    {
+        format!("{base}e")
+    } else if let Some(base) =
+        word.strip_suffix('s').filter(|base| !base.ends_with(['i', 's', 'u']))
+    {
+        base.to_owned()
+    } else {
+        word.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn english(word: &str) -> String {
+        stem(word, Locale::Eng).unwrap_or_else(|| word.to_string())
+    }
+
+    #[test]
+    fn english_plurals() {
+        assert_eq!(english("shoes"), "shoe");
+        assert_eq!(english("cats"), "cat");
+        assert_eq!(english("berries"), "berry");
+        assert_eq!(english("horses"), "horse");
+        assert_eq!(english("queries"), "query");
+    }
+
+    #[test]
+    fn english_possessives() {
+        assert_eq!(english("shoe's"), "shoe");
+        assert_eq!(english("shoe’s"), "shoe");
+        assert_eq!(english("dogs'"), "dog");
+    }
+
+    #[test]
+    fn english_words_without_suffix() {
+        for word in ["shoe", "glass", "this", "bonus", "analysis", "virus", "cat"] {
+            assert_eq!(stem(word, Locale::Eng), None, "{word}");
+        }
+    }
+
+    #[test]
+    fn unsupported_locales() {
+        assert_eq!(stem("shoes", Locale::Fra), None);
+        assert!(!is_supported(Locale::Fra));
+    }
+}
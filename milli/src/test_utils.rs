@@ -0,0 +1,155 @@
+//! A generated corpus of documents, shared by the relevancy tests and the benchmarks.
+//!
+//! The documents only depend on the seed and the number of documents, so that two
+//! runs, or two machines, search in exactly the same index.
+
+use serde_json::json;
+
+use crate::update::Settings;
+use crate::Object;
+
+/// The syllables the words of the vocabulary are made of.
+const SYLLABLES: &[&str] = &[
+    "ba", "ce", "di", "fo", "gu", "ha", "je", "ki", "lo", "mu", "na", "pe", "qui", "ro", "su",
+    "ta", "ve", "wi", "xo", "zu", "bra", "cli", "dro", "fle", "gri", "pla", "sto", "tre",
+];
+
+/// The number of values of the `bucket` field, a filter on a single value
+/// selects 1% of the documents.
+pub const BUCKET_COUNT: u64 = 100;
+
+/// Builds a deterministic corpus of documents with a `title`, a `text`, a `bucket` to filter on
+/// and a `popularity` to sort on.
+///
+/// The words are drawn from a vocabulary following a skewed distribution, so that, like in a
+/// real dataset, a few words are in most of the documents and most of the words are rare.
+#[derive(Debug, Clone)]
+pub struct CorpusBuilder {
+    documents: usize,
+    vocabulary: usize,
+    seed: u64,
+}
+
+impl CorpusBuilder {
+    pub fn new(documents: usize) -> Self {
+        CorpusBuilder { documents, vocabulary: 20_000, seed: 42 }
+    }
+
+    /// The number of distinct words in the corpus.
+    pub fn vocabulary(&mut self, vocabulary: usize) -> &mut Self {
+        self.vocabulary = vocabulary.max(1);
+        self
+    }
+
+    pub fn seed(&mut self, seed: u64) -> &mut Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The settings of the index the corpus is meant to be searched in.
+    pub fn configure(settings: &mut Settings) {
+        settings.set_primary_key("id".to_string());
+        settings.set_searchable_fields(vec!["title".to_string(), "text".to_string()]);
+        settings.set_filterable_fields(["bucket".to_string()].into_iter().collect());
+        settings.set_sortable_fields(["popularity".to_string()].into_iter().collect());
+    }
+
+    pub fn build(&self) -> Vec<Object> {
+        let mut rng = SplitMix64(self.seed);
+        (0..self.documents)
+            .map(|id| {
+                let title = self.sentence(&mut rng, 2, 8);
+                let text = self.sentence(&mut rng, 20, 60);
+                let document = json!({
+                    "id": id,
+                    "title": title,
+                    "text": text,
+                    "bucket": id as u64 % BUCKET_COUNT,
+                    "popularity": rng.below(1000),
+                });
+                match document {
+                    serde_json::Value::Object(object) => object,
+                    _ => unreachable!(),
+                }
+            })
+            .collect()
+    }
+
+    /// A query made of `terms` words of the vocabulary, picked from the frequent ones.
+    pub fn query(&self, terms: usize) -> String {
+        let mut rng = SplitMix64(self.seed ^ terms as u64);
+        let words: Vec<_> = (0..terms)
+            .map(|_| {
+                let rank = rng.below(self.vocabulary.min(100) as u64) as usize;
+                word(rank)
+            })
+            .collect();
+        words.join(" ")
+    }
+
+    fn sentence(&self, rng: &mut SplitMix64, min: u64, max: u64) -> String {
+        let len = min + rng.below(max - min + 1);
+        let words: Vec<_> = (0..len).map(|_| word(self.word_rank(rng))).collect();
+        words.join(" ")
+    }
+
+    /// The square of a uniform number favors the first ranks of the vocabulary.
+    fn word_rank(&self, rng: &mut SplitMix64) -> usize {
+        let uniform = rng.unit();
+        (uniform * uniform * self.vocabulary as f64) as usize
+    }
+}
+
+/// The word of the vocabulary at the given rank, the first ones being the most frequent.
+pub fn word(mut rank: usize) -> String {
+    let mut word = String::new();
+    loop {
+        word.push_str(SYLLABLES[rank % SYLLABLES.len()]);
+        rank /= SYLLABLES.len();
+        if rank == 0 {
+            return word;
+        }
+        rank -= 1;
+    }
+}
+
+/// A small seeded generator, to not depend on the algorithm of an external crate.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+
+    /// A number between 0 and 1.
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corpus_is_deterministic() {
+        let corpus = CorpusBuilder::new(100).build();
+        assert_eq!(corpus, CorpusBuilder::new(100).build());
+        assert_ne!(corpus, CorpusBuilder::new(100).seed(7).build());
+        assert_eq!(CorpusBuilder::new(10).query(3), CorpusBuilder::new(10).query(3));
+    }
+
+    #[test]
+    fn words_are_distinct() {
+        let words: std::collections::HashSet<_> = (0..5000).map(word).collect();
+        assert_eq!(words.len(), 5000);
+    }
+}
@@ -2,6 +2,7 @@ use heed::RwTxn;
 use roaring::RoaringBitmap;
 use time::OffsetDateTime;
 
+use crate::facet::FacetType;
 use crate::{FieldDistribution, Index, Result};
 
 pub struct ClearDocuments<'t, 'i> {
@@ -46,6 +47,7 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
             facet_id_exists_docids,
             facet_id_is_null_docids,
             facet_id_is_empty_docids,
+            facet_id_bool_docids,
             field_id_docid_facet_f64s,
             field_id_docid_facet_strings,
             vector_arroy,
@@ -65,6 +67,12 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
         self.index.put_field_distribution(self.wtxn, &FieldDistribution::default())?;
         self.index.delete_geo_rtree(self.wtxn)?;
         self.index.delete_geo_faceted_documents_ids(self.wtxn)?;
+        self.index.delete_facet_entries_counts(self.wtxn, FacetType::Number)?;
+        self.index.delete_facet_entries_counts(self.wtxn, FacetType::String)?;
+        // Once empty, the index can store its booleans in the dedicated facet database
+        // and fold its string facet values with the full case folding.
+        self.index.put_boolean_facets(self.wtxn)?;
+        self.index.put_full_case_folding(self.wtxn)?;
 
         // Clear the other databases.
         external_documents_ids.clear(self.wtxn)?;
@@ -85,6 +93,7 @@ impl<'t, 'i> ClearDocuments<'t, 'i> {
         facet_id_exists_docids.clear(self.wtxn)?;
         facet_id_is_null_docids.clear(self.wtxn)?;
         facet_id_is_empty_docids.clear(self.wtxn)?;
+        facet_id_bool_docids.clear(self.wtxn)?;
         facet_id_string_docids.clear(self.wtxn)?;
         field_id_docid_facet_f64s.clear(self.wtxn)?;
         field_id_docid_facet_strings.clear(self.wtxn)?;
@@ -139,6 +148,7 @@ mod tests {
         assert!(index.field_id_word_count_docids.is_empty(&rtxn).unwrap());
         assert!(index.facet_id_f64_docids.is_empty(&rtxn).unwrap());
         assert!(index.facet_id_string_docids.is_empty(&rtxn).unwrap());
+        assert!(index.facet_id_bool_docids.is_empty(&rtxn).unwrap());
         assert!(index.field_id_docid_facet_f64s.is_empty(&rtxn).unwrap());
         assert!(index.field_id_docid_facet_strings.is_empty(&rtxn).unwrap());
         assert!(index.documents.is_empty(&rtxn).unwrap());
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::BufReader;
 
@@ -16,7 +17,7 @@ use crate::search::facet::get_highest_level;
 use crate::update::del_add::DelAdd;
 use crate::update::index_documents::valid_lmdb_key;
 use crate::update::MergeFn;
-use crate::{CboRoaringBitmapCodec, Index, Result};
+use crate::{CboRoaringBitmapCodec, FieldId, Index, Result};
 
 /// Enum used as a return value for the facet incremental indexing.
 ///
@@ -87,8 +88,11 @@ impl FacetsUpdateIncremental {
         }
     }
 
+    /// Returns, for each modified field, the number of facet values added
+    /// to the level 0 minus the number of facet values removed from it.
     #[tracing::instrument(level = "trace", skip_all, target = "indexing::facets::incremental")]
-    pub fn execute(self, wtxn: &mut RwTxn) -> crate::Result<()> {
+    pub fn execute(self, wtxn: &mut RwTxn) -> crate::Result<BTreeMap<FieldId, i64>> {
+        let mut level0_entries_delta = BTreeMap::new();
         let mut current_field_id = None;
         let mut facet_level_may_be_updated = false;
         let mut iter = self.delta_data.into_stream_merger_iter()?;
@@ -122,6 +126,7 @@ impl FacetsUpdateIncremental {
                 .map(|o| o.map_err(heed::Error::Encoding))
                 .transpose()?;
 
+            let existed = self.inner.level0_entry_exists(wtxn, key.field_id, key.left_bound)?;
             let level_size_changed = self.inner.modify(
                 wtxn,
                 key.field_id,
@@ -129,6 +134,9 @@ impl FacetsUpdateIncremental {
                 docids_to_add.as_ref(),
                 docids_to_delete.as_ref(),
             )?;
+            let exists = self.inner.level0_entry_exists(wtxn, key.field_id, key.left_bound)?;
+            *level0_entries_delta.entry(key.field_id).or_default() +=
+                exists as i64 - existed as i64;
 
             if level_size_changed {
                 // if a node has been added or removed from the highest level,
@@ -143,7 +151,7 @@ impl FacetsUpdateIncremental {
             }
         }
 
-        Ok(())
+        Ok(level0_entries_delta)
     }
 }
 
@@ -554,6 +562,11 @@ impl FacetsUpdateIncrementalInner {
         }
     }
 
+    fn level0_entry_exists(&self, txn: &RoTxn, field_id: u16, facet_value: &[u8]) -> Result<bool> {
+        let key = FacetGroupKey { field_id, level: 0, left_bound: facet_value };
+        Ok(self.db.remap_data_type::<DecodeIgnore>().get(txn, &key)?.is_some())
+    }
+
     /// Check whether the highest level has exceeded `min_level_size` * `self.group_size`.
     /// If it has, we must build an addition level above it.
     /// Then check whether the highest level is under `min_level_size`.
@@ -67,7 +67,8 @@ minimise indexing time. The incremental method is faster when adding few new fac
 bulk method is faster when a large part of the database is modified. Empirically, it seems that
 it takes 50x more time to incrementally add N facet values to an existing database than it is to
 construct a database of N facet values. This is the heuristic that is used to choose between the
-two methods.
+two methods, for each field: the number of modified facet values of the field is compared to its
+number of facet values, which is kept up to date in the main database.
 
 Related PR: https://github.com/meilisearch/milli/pull/619
 */
@@ -76,12 +77,19 @@ pub const FACET_MAX_GROUP_SIZE: u8 = 8;
 pub const FACET_GROUP_SIZE: u8 = 4;
 pub const FACET_MIN_LEVEL_SIZE: u8 = 5;
 
-use std::collections::BTreeSet;
+/// How many facet values the bulk method processes in the time
+/// the incremental method adds a single one.
+///
+/// See self::comparison_bench::benchmark_facet_indexing
+const INCREMENTAL_UPDATE_COST: u64 = 50;
+
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::File;
 use std::io::BufReader;
 
-use grenad::Merger;
+use grenad::{CompressionType, Merger, MergerBuilder};
 use heed::types::{Bytes, DecodeIgnore};
+use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use tracing::debug;
 
@@ -91,6 +99,9 @@ use crate::facet::FacetType;
 use crate::heed_codec::facet::{FacetGroupKey, FacetGroupKeyCodec, FacetGroupValueCodec};
 use crate::heed_codec::BytesRefCodec;
 use crate::update::del_add::{DelAdd, KvReaderDelAdd};
+use crate::update::index_documents::{
+    create_writer, merge_deladd_cbo_roaring_bitmaps, valid_lmdb_key, writer_into_reader,
+};
 use crate::update::MergeFn;
 use crate::{try_split_array_at, FieldId, Index, Result};
 
@@ -148,31 +159,85 @@ impl<'i> FacetsUpdate<'i> {
         debug!("Computing and writing the facet values levels docids into LMDB on disk...");
         self.index.set_updated_at(wtxn, &OffsetDateTime::now_utc())?;
 
-        // See self::comparison_bench::benchmark_facet_indexing
-        if self.data_size >= (self.database.len(wtxn)? / 500) {
-            let field_ids =
-                self.index.faceted_fields_ids(wtxn)?.iter().copied().collect::<Vec<_>>();
+        // The number of modified facet values of each field must be known to choose its strategy.
+        let mut modified_entries = BTreeMap::<FieldId, u64>::new();
+        let mut writer = create_writer(CompressionType::None, None, tempfile::tempfile()?);
+        let mut iter = self.delta_data.into_stream_merger_iter()?;
+        while let Some((key, value)) = iter.next()? {
+            if let Some((field_id, _)) = try_split_array_at(key).filter(|_| valid_lmdb_key(key)) {
+                *modified_entries.entry(FieldId::from_be_bytes(field_id)).or_default() += 1;
+                writer.insert(key, value)?;
+            }
+        }
+        let delta = writer_into_reader(writer)?;
+
+        let mut entries_counts = self.index.facet_entries_counts(wtxn, self.facet_type)?;
+        let mut strategies = BTreeMap::new();
+        for (&field_id, &modified) in &modified_entries {
+            let count = match entries_counts.get(&field_id) {
+                Some(&count) => count,
+                None => count_level0_entries(self.database, wtxn, field_id)?,
+            };
+            let strategy = FacetUpdateStrategy::choose(modified, count);
+            debug!(field_id, modified, count, ?strategy, "Choosing the facet update strategy");
+            strategies.insert(field_id, strategy);
+        }
+
+        // Split the modified facet values between the two strategies.
+        let mut bulk_writer = create_writer(CompressionType::None, None, tempfile::tempfile()?);
+        let mut incremental_writer =
+            create_writer(CompressionType::None, None, tempfile::tempfile()?);
+        let mut cursor = delta.into_cursor()?;
+        while let Some((key, value)) = cursor.move_on_next()? {
+            let (field_id, _) = try_split_array_at(key).unwrap();
+            match strategies[&FieldId::from_be_bytes(field_id)] {
+                FacetUpdateStrategy::Bulk => bulk_writer.insert(key, value)?,
+                FacetUpdateStrategy::Incremental => incremental_writer.insert(key, value)?,
+            }
+        }
+
+        let bulk_field_ids: Vec<_> = strategies
+            .iter()
+            .filter(|(_, strategy)| **strategy == FacetUpdateStrategy::Bulk)
+            .map(|(field_id, _)| *field_id)
+            .collect();
+        if !bulk_field_ids.is_empty() {
             let bulk_update = FacetsUpdateBulk::new(
                 self.index,
-                field_ids,
+                bulk_field_ids.clone(),
                 self.facet_type,
-                self.delta_data,
+                into_merger(writer_into_reader(bulk_writer)?)?,
                 self.group_size,
                 self.min_level_size,
             );
             bulk_update.execute(wtxn)?;
-        } else {
+            for field_id in bulk_field_ids {
+                let count = count_level0_entries(self.database, wtxn, field_id)?;
+                entries_counts.insert(field_id, count);
+            }
+        }
+
+        if strategies.values().any(|strategy| *strategy == FacetUpdateStrategy::Incremental) {
             let incremental_update = FacetsUpdateIncremental::new(
                 self.index,
                 self.facet_type,
-                self.delta_data,
+                into_merger(writer_into_reader(incremental_writer)?)?,
                 self.group_size,
                 self.min_level_size,
                 self.max_group_size,
             );
-            incremental_update.execute(wtxn)?;
+            for (field_id, added) in incremental_update.execute(wtxn)? {
+                let count = match entries_counts.get(&field_id) {
+                    Some(&count) => count,
+                    None => count_level0_entries(self.database, wtxn, field_id)?,
+                };
+                entries_counts.insert(field_id, count.saturating_add_signed(added));
+            }
         }
 
+        self.index.put_facet_entries_counts(wtxn, self.facet_type, &entries_counts)?;
+        record_strategies(self.index, wtxn, &strategies)?;
+
         match self.normalized_delta_data {
             Some(data) => index_facet_search(wtxn, data, self.index),
             None => Ok(()),
@@ -180,6 +245,74 @@ impl<'i> FacetsUpdate<'i> {
     }
 }
 
+/// How the facet databases of a field are updated with its modified facet values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FacetUpdateStrategy {
+    /// The modified facet values are written, then all the levels of the field are rebuilt.
+    Bulk,
+    /// The modified facet values are inserted one by one in the existing levels.
+    Incremental,
+}
+
+impl FacetUpdateStrategy {
+    /// Chooses the fastest strategy to modify `modified` facet values of a field
+    /// that has `count` facet values.
+    pub fn choose(modified: u64, count: u64) -> Self {
+        if modified.saturating_mul(INCREMENTAL_UPDATE_COST) >= count {
+            FacetUpdateStrategy::Bulk
+        } else {
+            FacetUpdateStrategy::Incremental
+        }
+    }
+}
+
+/// Counts the facet values of the field, i.e. its number of level 0 entries.
+fn count_level0_entries(
+    database: heed::Database<FacetGroupKeyCodec<BytesRefCodec>, FacetGroupValueCodec>,
+    rtxn: &heed::RoTxn,
+    field_id: FieldId,
+) -> Result<u64> {
+    let mut prefix = field_id.to_be_bytes().to_vec();
+    prefix.push(0);
+    let database = database.remap_types::<Bytes, DecodeIgnore>();
+    let mut count = 0;
+    for result in database.prefix_iter(rtxn, &prefix)? {
+        result?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Adds the strategies to the ones of the indexing, by field name.
+///
+/// The bulk strategy is kept when the numbers and the strings of a field were not updated the same way.
+fn record_strategies(
+    index: &Index,
+    wtxn: &mut heed::RwTxn,
+    strategies: &BTreeMap<FieldId, FacetUpdateStrategy>,
+) -> Result<()> {
+    let fields_ids_map = index.fields_ids_map(wtxn)?;
+    let mut recorded = index.last_facet_update_strategies(wtxn)?;
+    for (&field_id, &strategy) in strategies {
+        let Some(name) = fields_ids_map.name(field_id) else { continue };
+        let entry = recorded.entry(name.to_string()).or_insert(strategy);
+        if strategy == FacetUpdateStrategy::Bulk {
+            *entry = strategy;
+        }
+    }
+    index.put_last_facet_update_strategies(wtxn, &recorded)?;
+    Ok(())
+}
+
+fn into_merger(
+    reader: grenad::Reader<BufReader<File>>,
+) -> Result<Merger<BufReader<File>, MergeFn>> {
+    let mut builder = MergerBuilder::new(merge_deladd_cbo_roaring_bitmaps as MergeFn);
+    builder.push(reader.into_cursor()?);
+    Ok(builder.build())
+}
+
 fn index_facet_search(
     wtxn: &mut heed::RwTxn,
     normalized_delta_data: Merger<BufReader<File>, MergeFn>,
@@ -639,3 +772,82 @@ mod comparison_bench {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+    use maplit::{btreemap, hashset};
+
+    use super::FacetUpdateStrategy;
+    use crate::documents::{documents_batch_reader_from_objects, DocumentsBatchReader};
+    use crate::facet::FacetType;
+    use crate::index::tests::TempIndex;
+    use crate::{Filter, Index, Search};
+
+    fn documents(ids: std::ops::Range<u32>) -> DocumentsBatchReader<std::io::Cursor<Vec<u8>>> {
+        documents_batch_reader_from_objects(
+            ids.map(|id| serde_json::json!({ "id": id, "price": id }).as_object().unwrap().clone()),
+        )
+    }
+
+    /// An index with 100 different prices.
+    fn index_with_prices() -> TempIndex {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_primary_key(S("id"));
+                settings.set_filterable_fields(hashset! { S("price") });
+            })
+            .unwrap();
+        index.add_documents(documents(0..100)).unwrap();
+        index
+    }
+
+    fn filter(index: &Index, filter: &str) -> Vec<u32> {
+        let rtxn = index.read_txn().unwrap();
+        let mut search = Search::new(&rtxn, index);
+        search.filter(Filter::from_str(filter).unwrap().unwrap());
+        search.limit(1000);
+        search.execute().unwrap().documents_ids
+    }
+
+    #[test]
+    fn facet_update_strategy_depends_on_the_ratio_of_modified_facet_values() {
+        assert_eq!(FacetUpdateStrategy::choose(1, 100), FacetUpdateStrategy::Incremental);
+        assert_eq!(FacetUpdateStrategy::choose(2, 100), FacetUpdateStrategy::Bulk);
+
+        // a single price added at a time is just below the crossover
+        let incremental = index_with_prices();
+        for id in 100..102 {
+            incremental.add_documents(documents(id..id + 1)).unwrap();
+            let rtxn = incremental.read_txn().unwrap();
+            assert_eq!(
+                incremental.last_facet_update_strategies(&rtxn).unwrap(),
+                btreemap! { S("price") => FacetUpdateStrategy::Incremental }
+            );
+        }
+
+        // two prices added at once are at the crossover
+        let bulk = index_with_prices();
+        bulk.add_documents(documents(100..102)).unwrap();
+        let rtxn = bulk.read_txn().unwrap();
+        assert_eq!(
+            bulk.last_facet_update_strategies(&rtxn).unwrap(),
+            btreemap! { S("price") => FacetUpdateStrategy::Bulk }
+        );
+        drop(rtxn);
+
+        for index in [&incremental, &bulk] {
+            let rtxn = index.read_txn().unwrap();
+            let price = index.fields_ids_map(&rtxn).unwrap().id("price").unwrap();
+            assert_eq!(
+                index.facet_entries_counts(&rtxn, FacetType::Number).unwrap(),
+                btreemap! { price => 102 }
+            );
+        }
+        for expression in ["price > 50", "price = 101", "price 95 TO 100", "price != 3"] {
+            assert_eq!(filter(&incremental, expression), filter(&bulk, expression));
+        }
+        assert_eq!(filter(&bulk, "price >= 100"), vec![100, 101]);
+    }
+}
@@ -1,23 +1,31 @@
+use std::collections::HashSet;
 use std::fmt;
 use std::io::{BufWriter, Read, Seek};
 use std::result::Result as StdResult;
 
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 use crate::documents::{
     DocumentIdExtractionError, DocumentsBatchIndex, DocumentsBatchReader,
     EnrichedDocumentsBatchReader, PrimaryKey, DEFAULT_PRIMARY_KEY,
 };
 use crate::error::{GeoError, InternalError, UserError};
-use crate::update::index_documents::{obkv_to_object, writer_into_reader};
-use crate::{FieldId, Index, Result};
+use crate::update::index_documents::{
+    obkv_to_object, writer_into_reader, InvalidDocuments, MAX_DOCUMENT_ERROR_SAMPLES,
+};
+use crate::{FieldId, Index, Result, VectorOrArrayOfVectors};
 
 /// This function validates and enrich the documents by checking that:
 ///  - we can infer a primary key,
 ///  - all the documents id exist and are extracted,
 ///  - the validity of them but also,
-///  - the validity of the `_geo` field depending on the settings.
+///  - the validity of the `_geo` field depending on the settings,
+///  - the validity of the `_vectors` field when embedders are configured,
+///  - the absence of reserved fields, starting with `_`, depending on the settings.
+///
+/// It doesn't stop at the first invalid document, the errors of up to
+/// [`MAX_DOCUMENT_ERROR_SAMPLES`] invalid documents are returned.
 ///
 /// # Panics
 ///
@@ -28,7 +36,7 @@ pub fn enrich_documents_batch<R: Read + Seek>(
     index: &Index,
     autogenerate_docids: bool,
     reader: DocumentsBatchReader<R>,
-) -> Result<StdResult<EnrichedDocumentsBatchReader<R>, UserError>> {
+) -> Result<StdResult<EnrichedDocumentsBatchReader<R>, InvalidDocuments>> {
     puffin::profile_function!();
 
     let (mut cursor, mut documents_batch_index) = reader.into_cursor_and_fields_index();
@@ -50,7 +58,8 @@ pub fn enrich_documents_batch<R: Read + Seek>(
                     Some(first_document) => Ok(Err(UserError::MissingDocumentId {
                         primary_key: primary_key.to_string(),
                         document: obkv_to_object(&first_document, &documents_batch_index)?,
-                    })),
+                    }
+                    .into())),
                     None => unreachable!("Called with reader.is_empty()"),
                 };
             }
@@ -76,7 +85,7 @@ pub fn enrich_documents_batch<R: Read + Seek>(
                     name: DEFAULT_PRIMARY_KEY,
                     field_id: documents_batch_index.insert(DEFAULT_PRIMARY_KEY),
                 },
-                [] => return Ok(Err(UserError::NoPrimaryKeyCandidateFound)),
+                [] => return Ok(Err(UserError::NoPrimaryKeyCandidateFound.into())),
                 [(field_id, name)] => {
                     tracing::info!("Primary key was not specified in index. Inferred to '{name}'");
                     PrimaryKey::Flat { name, field_id: *field_id }
@@ -87,7 +96,8 @@ pub fn enrich_documents_batch<R: Read + Seek>(
                             .iter()
                             .map(|(_, candidate)| candidate.to_string())
                             .collect(),
-                    }));
+                    }
+                    .into()));
                 }
             }
         }
@@ -105,30 +115,81 @@ pub fn enrich_documents_batch<R: Read + Seek>(
         _otherwise => None,
     };
 
+    // Only the top-level fields are reserved, `a._b` is a regular field.
+    let reserved_fields: Vec<_> = if index.allow_underscore_fields(rtxn)? {
+        Vec::new()
+    } else {
+        documents_batch_index
+            .iter()
+            .filter(|(_, name)| is_reserved_field(name))
+            .map(|(field_id, name)| (*field_id, name.clone()))
+            .collect()
+    };
+
+    // The `_vectors` field is only read, and must be valid, when there are embedders.
+    let embedders: Vec<String> =
+        index.embedding_configs(rtxn)?.into_iter().map(|(name, _)| name).collect();
+    let vectors_field_id = documents_batch_index.id("_vectors").filter(|_| !embedders.is_empty());
+
+    let mut errors = DocumentErrors::default();
     let mut count = 0;
     while let Some(document) = cursor.next_document()? {
+        if errors.is_full() {
+            break;
+        }
+        let document_nth = count;
+        count += 1;
+
         let document_id = match fetch_or_generate_document_id(
             &document,
             &documents_batch_index,
             primary_key,
             autogenerate_docids,
             &mut uuid_buffer,
-            count,
+            document_nth,
         )? {
             Ok(document_id) => document_id,
-            Err(user_error) => return Ok(Err(user_error)),
+            Err(user_error) => {
+                errors.push(user_error);
+                continue;
+            }
         };
 
+        if let Some((_, field)) =
+            reserved_fields.iter().find(|(fid, _)| document.get(*fid).is_some())
+        {
+            let user_error = UserError::ReservedDocumentField {
+                document_id: Value::from(document_id.value()),
+                field: field.clone(),
+            };
+            errors.push(user_error);
+            continue;
+        }
+
         if let Some(geo_value) = geo_field_id.and_then(|fid| document.get(fid)) {
             if let Err(user_error) = validate_geo_from_json(&document_id, geo_value)? {
-                return Ok(Err(UserError::from(user_error)));
+                errors.push(UserError::from(user_error));
+                continue;
+            }
+        }
+
+        if let Some(vectors) = vectors_field_id.and_then(|fid| document.get(fid)) {
+            if let Err(user_error) = validate_vectors_from_json(&document_id, vectors, &embedders)?
+            {
+                errors.push(user_error);
+                continue;
             }
         }
 
-        let document_id = serde_json::to_vec(&document_id).map_err(InternalError::SerdeJson)?;
-        external_ids.insert(count.to_be_bytes(), document_id)?;
+        // There is no need to keep the ids once a document is invalid, the payload is refused.
+        if errors.is_empty() {
+            let document_id = serde_json::to_vec(&document_id).map_err(InternalError::SerdeJson)?;
+            external_ids.insert(document_nth.to_be_bytes(), document_id)?;
+        }
+    }
 
-        count += 1;
+    if let Some(invalid_documents) = errors.into_invalid_documents() {
+        return Ok(Err(invalid_documents));
     }
 
     let external_ids = writer_into_reader(external_ids)?;
@@ -142,6 +203,44 @@ pub fn enrich_documents_batch<R: Read + Seek>(
     Ok(Ok(reader))
 }
 
+/// Whether this top-level field is reserved for the engine.
+///
+/// The fields starting with `_` are reserved so that the future ones never collide with the
+/// fields of the documents, only `_geo` and `_vectors` already have a meaning.
+fn is_reserved_field(name: &str) -> bool {
+    name.starts_with('_') && !matches!(name, "_geo" | "_vectors")
+}
+
+/// The distinct errors of the invalid documents of a payload.
+#[derive(Default)]
+struct DocumentErrors {
+    errors: Vec<UserError>,
+    messages: HashSet<String>,
+}
+
+impl DocumentErrors {
+    /// Records the error of an invalid document, unless another document had the same error.
+    fn push(&mut self, error: UserError) {
+        if self.messages.insert(error.to_string()) {
+            self.errors.push(error);
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    fn is_full(&self) -> bool {
+        self.errors.len() >= MAX_DOCUMENT_ERROR_SAMPLES
+    }
+
+    fn into_invalid_documents(self) -> Option<InvalidDocuments> {
+        let mut errors = self.errors.into_iter();
+        let error = errors.next()?;
+        Some(InvalidDocuments { error, other_errors: errors.collect() })
+    }
+}
+
 /// Retrieve the document id after validating it, returning a `UserError`
 /// if the id is invalid or can't be guessed.
 #[tracing::instrument(level = "trace", skip(uuid_buffer, documents_batch_index, document)
@@ -263,3 +362,40 @@ pub fn validate_geo_from_json(id: &DocumentId, bytes: &[u8]) -> Result<StdResult
         value => Ok(Err(NotAnObject { document_id: debug_id(), value })),
     }
 }
+
+/// Validates the `_vectors` field of a document like the extraction of the vectors reads it:
+/// it must be an object, and the vectors of the embedders must be arrays of floats or arrays of arrays of floats.
+pub fn validate_vectors_from_json(
+    id: &DocumentId,
+    bytes: &[u8],
+    embedders: &[String],
+) -> Result<StdResult<(), UserError>> {
+    let document_id = || Value::from(id.value());
+    let vectors: Map<String, Value> = match serde_json::from_slice(bytes) {
+        Ok(vectors) => vectors,
+        Err(_) => {
+            let value = serde_json::from_slice(bytes).map_err(InternalError::SerdeJson)?;
+            return Ok(Err(UserError::InvalidVectorsMapType { document_id: document_id(), value }));
+        }
+    };
+
+    for (name, value) in vectors.into_iter().filter(|(name, _)| embedders.contains(name)) {
+        match serde_json::from_value::<VectorOrArrayOfVectors>(value.clone()) {
+            Ok(vectors) => {
+                let count = vectors.into_array_of_vectors().map_or(0, |vectors| vectors.len());
+                if count > usize::from(u8::MAX) {
+                    return Ok(Err(UserError::TooManyVectors(document_id().to_string(), count)));
+                }
+            }
+            Err(_) => {
+                return Ok(Err(UserError::InvalidVectorsType {
+                    document_id: document_id(),
+                    value,
+                    subfield: name,
+                }))
+            }
+        }
+    }
+
+    Ok(Ok(()))
+}
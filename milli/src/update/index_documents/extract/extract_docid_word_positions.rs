@@ -11,6 +11,7 @@ use serde_json::Value;
 
 use super::helpers::{create_sorter, keep_latest_obkv, sorter_into_reader, GrenadParameters};
 use crate::error::{InternalError, SerializationError};
+use crate::number_normalization::normalize_number;
 use crate::update::del_add::{del_add_from_two_obkvs, DelAdd, KvReaderDelAdd};
 use crate::{FieldId, Result, MAX_POSITION_PER_ATTRIBUTE, MAX_WORD_LENGTH};
 
@@ -30,6 +31,7 @@ pub fn extract_docid_word_positions<R: io::Read + io::Seek>(
     allowed_separators: Option<&[&str]>,
     dictionary: Option<&[&str]>,
     max_positions_per_attributes: Option<u32>,
+    number_normalization: bool,
 ) -> Result<(grenad::Reader<BufReader<File>>, ScriptLanguageDocidsMap)> {
     puffin::profile_function!();
 
@@ -91,6 +93,7 @@ pub fn extract_docid_word_positions<R: io::Read + io::Seek>(
                     allowed_separators,
                     dictionary,
                     max_positions_per_attributes,
+                    number_normalization,
                     DelAdd::Deletion,
                     &mut del_buffers,
                 )
@@ -105,6 +108,7 @@ pub fn extract_docid_word_positions<R: io::Read + io::Seek>(
                     allowed_separators,
                     dictionary,
                     max_positions_per_attributes,
+                    number_normalization,
                     DelAdd::Addition,
                     &mut add_buffers,
                 )
@@ -215,6 +219,7 @@ fn lang_safe_tokens_from_document<'a>(
     allowed_separators: Option<&[&str]>,
     dictionary: Option<&[&str]>,
     max_positions_per_attributes: u32,
+    number_normalization: bool,
     del_add: DelAdd,
     buffers: &'a mut Buffers,
 ) -> Result<(&'a [u8], HashMap<Script, Vec<(Language, usize)>>)> {
@@ -225,6 +230,7 @@ fn lang_safe_tokens_from_document<'a>(
         searchable_fields,
         tokenizer,
         max_positions_per_attributes,
+        number_normalization,
         del_add,
         buffers,
         &mut script_language_word_count,
@@ -262,6 +268,7 @@ fn lang_safe_tokens_from_document<'a>(
                 searchable_fields,
                 &tokenizer,
                 max_positions_per_attributes,
+                number_normalization,
                 del_add,
                 buffers,
                 &mut script_language_word_count,
@@ -274,11 +281,13 @@ fn lang_safe_tokens_from_document<'a>(
 }
 
 /// Extract words mapped with their positions of a document.
+#[allow(clippy::too_many_arguments)]
 fn tokens_from_document<'a>(
     obkv: &KvReader<FieldId>,
     searchable_fields: &Option<HashSet<FieldId>>,
     tokenizer: &Tokenizer,
     max_positions_per_attributes: u32,
+    number_normalization: bool,
     del_add: DelAdd,
     buffers: &'a mut Buffers,
     script_language_word_count: &mut HashMap<Script, Vec<(Language, usize)>>,
@@ -301,8 +310,16 @@ fn tokens_from_document<'a>(
                 // convert json into a unique string.
                 buffers.field_buffer.clear();
                 if let Some(field) = json_to_string(&value, &mut buffers.field_buffer) {
+                    // the formatted numbers replace the literal ones at the same position.
+                    let tokens = tokenizer.tokenize(field).map(|token| {
+                        if number_normalization {
+                            normalize_number(token)
+                        } else {
+                            token
+                        }
+                    });
                     // create an iterator of token with their positions.
-                    let tokens = process_tokens(tokenizer.tokenize(field))
+                    let tokens = process_tokens(tokens)
                         .take_while(|(p, _)| (*p as u32) < max_positions_per_attributes);
 
                     for (index, token) in tokens {
@@ -339,7 +356,7 @@ fn tokens_from_document<'a>(
 }
 
 /// Transform a JSON value into a string that can be indexed.
-fn json_to_string<'a>(value: &'a Value, buffer: &'a mut String) -> Option<&'a str> {
+pub(crate) fn json_to_string<'a>(value: &'a Value, buffer: &'a mut String) -> Option<&'a str> {
     fn inner(value: &Value, output: &mut String) -> bool {
         use std::fmt::Write;
         match value {
@@ -17,10 +17,14 @@ use FilterableValues::{Empty, Null, Values};
 
 use super::helpers::{create_sorter, keep_first, sorter_into_reader, GrenadParameters};
 use crate::error::InternalError;
+use crate::facet::parse_facet_date;
 use crate::facet::value_encoding::f64_into_bytes;
 use crate::update::del_add::{DelAdd, KvWriterDelAdd};
 use crate::update::index_documents::{create_writer, writer_into_reader};
-use crate::{CboRoaringBitmapCodec, DocumentId, Error, FieldId, Result, MAX_FACET_VALUE_LENGTH};
+use crate::{
+    normalize_facet, normalize_facet_number, CaseFolding, CboRoaringBitmapCodec, DocumentId, Error,
+    FieldId, FieldIdBoolCodec, Result, MAX_EXACT_FACET_INTEGER, MAX_FACET_VALUE_LENGTH,
+};
 
 /// The length of the elements that are always in the buffer when inserting new values.
 const TRUNCATE_SIZE: usize = size_of::<FieldId>() + size_of::<DocumentId>();
@@ -32,19 +36,23 @@ pub struct ExtractedFacetValues {
     pub fid_facet_is_null_docids_chunk: grenad::Reader<BufReader<File>>,
     pub fid_facet_is_empty_docids_chunk: grenad::Reader<BufReader<File>>,
     pub fid_facet_exists_docids_chunk: grenad::Reader<BufReader<File>>,
+    pub fid_facet_bool_docids_chunk: grenad::Reader<BufReader<File>>,
 }
 
 /// Extracts the facet values of each faceted field of each document.
 ///
 /// Returns the generated grenad reader containing the docid the fid and the original value as key
 /// and the normalized value as value extracted from the given chunk of documents.
-/// We need the fid of the geofields to correctly parse them as numbers if they were sent as strings initially.
+/// We need the fid of the geofields to correctly parse them as numbers if they were sent as strings initially,
+/// and the fids of the date fields to also store their dates as numbers.
 #[tracing::instrument(level = "trace", skip_all, target = "indexing::extract")]
 pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
     obkv_documents: grenad::Reader<R>,
     indexer: GrenadParameters,
     faceted_fields: &HashSet<FieldId>,
+    date_fields_ids: &HashSet<FieldId>,
     geo_fields_ids: Option<(FieldId, FieldId)>,
+    case_folding: CaseFolding,
 ) -> Result<ExtractedFacetValues> {
     puffin::profile_function!();
 
@@ -72,11 +80,14 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
     let mut facet_exists_docids = BTreeMap::<FieldId, (RoaringBitmap, RoaringBitmap)>::new();
     let mut facet_is_null_docids = BTreeMap::<FieldId, (RoaringBitmap, RoaringBitmap)>::new();
     let mut facet_is_empty_docids = BTreeMap::<FieldId, (RoaringBitmap, RoaringBitmap)>::new();
+    let mut facet_bool_docids = BTreeMap::<(FieldId, bool), (RoaringBitmap, RoaringBitmap)>::new();
 
     // We create two buffers for mutable ref issues with closures.
     let mut numbers_key_buffer = Vec::new();
     let mut strings_key_buffer = Vec::new();
 
+    let mut warnings = ExtractionWarnings::default();
+
     let mut cursor = obkv_documents.into_cursor()?;
     while let Some((docid_bytes, value)) = cursor.move_on_next()? {
         let obkv = obkv::KvReader::new(value);
@@ -123,12 +134,44 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
                     add_exists.insert(document);
                 }
 
-                let geo_support =
-                    geo_fields_ids.map_or(false, |(lat, lng)| field_id == lat || field_id == lng);
-                let del_filterable_values =
-                    del_value.map(|value| extract_facet_values(&value, geo_support));
-                let add_filterable_values =
-                    add_value.map(|value| extract_facet_values(&value, geo_support));
+                // The booleans are also stored as strings but we keep track of them
+                // separately to be able to tell them apart from the "true" and "false" strings.
+                let del_booleans = del_value.as_ref().map_or([false; 2], extract_booleans);
+                let add_booleans = add_value.as_ref().map_or([false; 2], extract_booleans);
+                for boolean in [false, true] {
+                    let index = boolean as usize;
+                    if del_booleans[index] != add_booleans[index] {
+                        let (del_bool, add_bool) =
+                            facet_bool_docids.entry((field_id, boolean)).or_default();
+                        if del_booleans[index] {
+                            del_bool.insert(document);
+                        } else {
+                            add_bool.insert(document);
+                        }
+                    }
+                }
+
+                let strings_as_numbers = if geo_fields_ids
+                    .map_or(false, |(lat, lng)| field_id == lat || field_id == lng)
+                {
+                    StringsAsNumbers::GeoCoordinates
+                } else if date_fields_ids.contains(&field_id) {
+                    StringsAsNumbers::Dates
+                } else {
+                    StringsAsNumbers::No
+                };
+                // only the values that are added to the index are reported
+                let del_filterable_values = del_value.map(|value| {
+                    extract_facet_values(
+                        &value,
+                        strings_as_numbers,
+                        case_folding,
+                        &mut ExtractionWarnings::default(),
+                    )
+                });
+                let add_filterable_values = add_value.map(|value| {
+                    extract_facet_values(&value, strings_as_numbers, case_folding, &mut warnings)
+                });
 
                 // Those closures are just here to simplify things a bit.
                 let mut insert_numbers_diff = |del_numbers, add_numbers| {
@@ -253,12 +296,39 @@ pub fn extract_fid_docid_facet_values<R: io::Read + io::Seek>(
     }
     let facet_is_empty_docids_reader = writer_into_reader(facet_is_empty_docids_writer)?;
 
+    let mut facet_bool_docids_writer = create_writer(
+        indexer.chunk_compression_type,
+        indexer.chunk_compression_level,
+        tempfile::tempfile()?,
+    );
+    for ((fid, boolean), (del_bitmap, add_bitmap)) in facet_bool_docids.into_iter() {
+        deladd_obkv_cbo_roaring_bitmaps(&mut buffer, &del_bitmap, &add_bitmap)?;
+        let key = FieldIdBoolCodec::bytes_encode(&(fid, boolean)).map_err(heed::Error::Encoding)?;
+        facet_bool_docids_writer.insert(key, &buffer)?;
+    }
+    let facet_bool_docids_reader = writer_into_reader(facet_bool_docids_writer)?;
+
+    let ExtractionWarnings { imprecise_numbers, unparseable_dates } = warnings;
+    if imprecise_numbers != 0 {
+        tracing::warn!(
+            imprecise_numbers,
+            "{imprecise_numbers} facet numbers are integers bigger than 2^53 and have been rounded to the nearest float, filtering and sorting on them may be imprecise"
+        );
+    }
+    if unparseable_dates != 0 {
+        tracing::warn!(
+            unparseable_dates,
+            "{unparseable_dates} strings of the date attributes are neither RFC3339 datetimes nor `YYYY-MM-DD` dates and have only been indexed as strings"
+        );
+    }
+
     Ok(ExtractedFacetValues {
         fid_docid_facet_numbers_chunk: sorter_into_reader(fid_docid_facet_numbers_sorter, indexer)?,
         fid_docid_facet_strings_chunk: sorter_into_reader(fid_docid_facet_strings_sorter, indexer)?,
         fid_facet_is_null_docids_chunk: facet_is_null_docids_reader,
         fid_facet_is_empty_docids_chunk: facet_is_empty_docids_reader,
         fid_facet_exists_docids_chunk: facet_exists_docids_reader,
+        fid_facet_bool_docids_chunk: facet_bool_docids_reader,
     })
 }
 
@@ -407,36 +477,97 @@ enum FilterableValues {
     Values { numbers: Vec<f64>, strings: Vec<(String, String)> },
 }
 
+/// How the strings of a faceted field are also stored as facet numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StringsAsNumbers {
+    No,
+    /// The strings of the `_geo.lat` and `_geo.lng` fields, already validated as numbers.
+    GeoCoordinates,
+    /// The dates of the fields hinted as dates, stored as timestamps.
+    Dates,
+}
+
+/// The values that have been faceted in a degraded way, reported after the extraction.
+#[derive(Debug, Default)]
+struct ExtractionWarnings {
+    /// The integers too big to be represented exactly as facet numbers.
+    imprecise_numbers: usize,
+    /// The strings of the date fields that couldn't be parsed as dates.
+    unparseable_dates: usize,
+}
+
+/// Returns whether the `false` and the `true` booleans, in this order, are facet values of a JSON field.
+fn extract_booleans(value: &Value) -> [bool; 2] {
+    let mut booleans = [false; 2];
+    match value {
+        Value::Bool(b) => booleans[*b as usize] = true,
+        Value::Array(values) => {
+            for value in values {
+                if let Value::Bool(b) = value {
+                    booleans[*b as usize] = true;
+                }
+            }
+        }
+        _ => (),
+    }
+    booleans
+}
+
 /// Extracts the facet values of a JSON field.
-fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
+///
+/// The numbers are normalized with [`normalize_facet_number`], the strings with [`normalize_facet`],
+/// and the `warnings` are incremented for every integer that is too big to be represented exactly
+/// and every string of a date field that isn't a date.
+fn extract_facet_values(
+    value: &Value,
+    strings_as_numbers: StringsAsNumbers,
+    case_folding: CaseFolding,
+    warnings: &mut ExtractionWarnings,
+) -> FilterableValues {
     fn inner_extract_facet_values(
         value: &Value,
         can_recurse: bool,
         output_numbers: &mut Vec<f64>,
         output_strings: &mut Vec<(String, String)>,
-        geo_field: bool,
+        strings_as_numbers: StringsAsNumbers,
+        case_folding: CaseFolding,
+        warnings: &mut ExtractionWarnings,
     ) {
         match value {
             Value::Null => (),
             Value::Bool(b) => output_strings.push((b.to_string(), b.to_string())),
             Value::Number(number) => {
                 if let Some(float) = number.as_f64() {
-                    output_numbers.push(float);
+                    let integer =
+                        number.as_u64().or_else(|| number.as_i64().map(i64::unsigned_abs));
+                    if integer.map_or(false, |integer| integer > MAX_EXACT_FACET_INTEGER) {
+                        warnings.imprecise_numbers += 1;
+                    }
+                    output_numbers.push(normalize_facet_number(float));
                 }
             }
             Value::String(original) => {
                 // if we're working on a geofield it MUST be something we can parse or else there was an internal error
                 // in the enrich pipeline. But since the enrich pipeline worked, we want to avoid crashing at all costs.
-                if geo_field {
-                    if let Ok(float) = original.parse() {
-                        output_numbers.push(float);
-                    } else {
-                        tracing::warn!(
-                            "Internal error, could not parse a geofield that has been validated. Please open an issue."
-                        )
+                match strings_as_numbers {
+                    StringsAsNumbers::No => (),
+                    StringsAsNumbers::GeoCoordinates => {
+                        if let Ok(float) = original.parse() {
+                            output_numbers.push(normalize_facet_number(float));
+                        } else {
+                            tracing::warn!(
+                                "Internal error, could not parse a geofield that has been validated. Please open an issue."
+                            )
+                        }
                     }
+                    // the unparseable dates are still faceted as strings
+                    StringsAsNumbers::Dates => match parse_facet_date(original) {
+                        Some(timestamp) => output_numbers.push(normalize_facet_number(timestamp)),
+                        None if original.trim().is_empty() => (),
+                        None => warnings.unparseable_dates += 1,
+                    },
                 }
-                let normalized = crate::normalize_facet(original);
+                let normalized = normalize_facet(original, case_folding);
                 output_strings.push((normalized, original.clone()));
             }
             Value::Array(values) => {
@@ -447,7 +578,9 @@ fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
                             false,
                             output_numbers,
                             output_strings,
-                            geo_field,
+                            strings_as_numbers,
+                            case_folding,
+                            warnings,
                         );
                     }
                 }
@@ -464,7 +597,15 @@ fn extract_facet_values(value: &Value, geo_field: bool) -> FilterableValues {
         otherwise => {
             let mut numbers = Vec::new();
             let mut strings = Vec::new();
-            inner_extract_facet_values(otherwise, true, &mut numbers, &mut strings, geo_field);
+            inner_extract_facet_values(
+                otherwise,
+                true,
+                &mut numbers,
+                &mut strings,
+                strings_as_numbers,
+                case_folding,
+                warnings,
+            );
             FilterableValues::Values { numbers, strings }
         }
     }
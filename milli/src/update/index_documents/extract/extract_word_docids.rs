@@ -1,6 +1,7 @@
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs::File;
 use std::io::{self, BufReader};
+use std::str;
 
 use heed::BytesDecode;
 use obkv::KvReaderU16;
@@ -9,9 +10,11 @@ use super::helpers::{
     create_sorter, create_writer, merge_deladd_cbo_roaring_bitmaps, sorter_into_reader,
     try_split_array_at, writer_into_reader, GrenadParameters,
 };
+use crate::collation::Locale;
 use crate::error::SerializationError;
 use crate::heed_codec::StrBEU16Codec;
 use crate::index::db_name::DOCID_WORD_POSITIONS;
+use crate::stemming::stems;
 use crate::update::del_add::{is_noop_del_add_obkv, DelAdd, KvReaderDelAdd, KvWriterDelAdd};
 use crate::update::MergeFn;
 use crate::{DocumentId, FieldId, Result};
@@ -22,12 +25,13 @@ use crate::{DocumentId, FieldId, Result};
 /// documents ids from the given chunk of docid word positions.
 ///
 /// The first returned reader is the one for normal word_docids, and the second one is for
-/// exact_word_docids
+/// exact_word_docids. The stems of the words of the stemmed fields are extracted along with them.
 #[tracing::instrument(level = "trace", skip_all, target = "indexing::extract")]
 pub fn extract_word_docids<R: io::Read + io::Seek>(
     docid_word_positions: grenad::Reader<R>,
     indexer: GrenadParameters,
     exact_attributes: &HashSet<FieldId>,
+    stemmed_fields_ids: &HashMap<FieldId, Vec<Locale>>,
 ) -> Result<(
     grenad::Reader<BufReader<File>>,
     grenad::Reader<BufReader<File>>,
@@ -56,12 +60,16 @@ pub fn extract_word_docids<R: io::Read + io::Seek>(
             .ok_or(SerializationError::Decoding { db_name: Some(DOCID_WORD_POSITIONS) })?;
         let document_id = u32::from_be_bytes(document_id_bytes);
         let fid = u16::from_be_bytes(fid_bytes);
+        let locales = stemmed_fields_ids.get(&fid).map_or(&[][..], Vec::as_slice);
 
         let del_add_reader = KvReaderDelAdd::new(value);
         // extract all unique words to remove.
         if let Some(deletion) = del_add_reader.get(DelAdd::Deletion) {
             for (_pos, word) in KvReaderU16::new(deletion).iter() {
                 del_words.insert(word.to_vec());
+                if let Ok(word) = str::from_utf8(word) {
+                    del_words.extend(stems(word, locales).map(String::into_bytes));
+                }
             }
         }
 
@@ -69,6 +77,9 @@ pub fn extract_word_docids<R: io::Read + io::Seek>(
         if let Some(addition) = del_add_reader.get(DelAdd::Addition) {
             for (_pos, word) in KvReaderU16::new(addition).iter() {
                 add_words.insert(word.to_vec());
+                if let Ok(word) = str::from_utf8(word) {
+                    add_words.extend(stems(word, locales).map(String::into_bytes));
+                }
             }
         }
 
@@ -1,6 +1,7 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::fs::File;
 use std::io::{self, BufReader};
+use std::str;
 
 use obkv::KvReaderU16;
 
@@ -8,20 +9,24 @@ use super::helpers::{
     create_sorter, merge_deladd_cbo_roaring_bitmaps, sorter_into_reader, try_split_array_at,
     GrenadParameters,
 };
+use crate::collation::Locale;
 use crate::error::SerializationError;
 use crate::index::db_name::DOCID_WORD_POSITIONS;
+use crate::stemming::stems;
 use crate::update::del_add::{DelAdd, KvReaderDelAdd, KvWriterDelAdd};
 use crate::update::MergeFn;
-use crate::{bucketed_position, DocumentId, Result};
+use crate::{bucketed_position, DocumentId, FieldId, Result};
 
 /// Extracts the word positions and the documents ids where this word appear.
 ///
 /// Returns a grenad reader with the list of extracted words at positions and
 /// documents ids from the given chunk of docid word positions.
+/// The stems of the words of the stemmed fields are extracted at the positions of the words.
 #[tracing::instrument(level = "trace", skip_all, target = "indexing::extract")]
 pub fn extract_word_position_docids<R: io::Read + io::Seek>(
     docid_word_positions: grenad::Reader<R>,
     indexer: GrenadParameters,
+    stemmed_fields_ids: &HashMap<FieldId, Vec<Locale>>,
 ) -> Result<grenad::Reader<BufReader<File>>> {
     puffin::profile_function!();
 
@@ -42,9 +47,13 @@ pub fn extract_word_position_docids<R: io::Read + io::Seek>(
     let mut key_buffer = Vec::new();
     let mut cursor = docid_word_positions.into_cursor()?;
     while let Some((key, value)) = cursor.move_on_next()? {
-        let (document_id_bytes, _fid_bytes) = try_split_array_at(key)
+        let (document_id_bytes, fid_bytes) = try_split_array_at(key)
+            .ok_or(SerializationError::Decoding { db_name: Some(DOCID_WORD_POSITIONS) })?;
+        let (fid_bytes, _) = try_split_array_at(fid_bytes)
             .ok_or(SerializationError::Decoding { db_name: Some(DOCID_WORD_POSITIONS) })?;
         let document_id = DocumentId::from_be_bytes(document_id_bytes);
+        let fid = FieldId::from_be_bytes(fid_bytes);
+        let locales = stemmed_fields_ids.get(&fid).map_or(&[][..], Vec::as_slice);
 
         if current_document_id.map_or(false, |id| document_id != id) {
             words_position_into_sorter(
@@ -66,6 +75,10 @@ pub fn extract_word_position_docids<R: io::Read + io::Seek>(
             for (position, word_bytes) in KvReaderU16::new(deletion).iter() {
                 let position = bucketed_position(position);
                 del_word_positions.insert((position, word_bytes.to_vec()));
+                if let Ok(word) = str::from_utf8(word_bytes) {
+                    del_word_positions
+                        .extend(stems(word, locales).map(|stem| (position, stem.into_bytes())));
+                }
             }
         }
 
@@ -74,6 +87,10 @@ pub fn extract_word_position_docids<R: io::Read + io::Seek>(
             for (position, word_bytes) in KvReaderU16::new(addition).iter() {
                 let position = bucketed_position(position);
                 add_word_positions.insert((position, word_bytes.to_vec()));
+                if let Ok(word) = str::from_utf8(word_bytes) {
+                    add_word_positions
+                        .extend(stems(word, locales).map(|stem| (position, stem.into_bytes())));
+                }
             }
         }
     }
@@ -9,7 +9,7 @@ mod extract_word_docids;
 mod extract_word_pair_proximity_docids;
 mod extract_word_position_docids;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::BufReader;
 
@@ -17,6 +17,7 @@ use crossbeam_channel::Sender;
 use rayon::prelude::*;
 
 use self::extract_docid_word_positions::extract_docid_word_positions;
+pub(crate) use self::extract_docid_word_positions::json_to_string;
 use self::extract_facet_number_docids::extract_facet_number_docids;
 use self::extract_facet_string_docids::extract_facet_string_docids;
 use self::extract_fid_docid_facet_values::{extract_fid_docid_facet_values, ExtractedFacetValues};
@@ -30,9 +31,10 @@ use self::extract_word_pair_proximity_docids::extract_word_pair_proximity_docids
 use self::extract_word_position_docids::extract_word_position_docids;
 use super::helpers::{as_cloneable_grenad, CursorClonableMmap, GrenadParameters};
 use super::{helpers, TypedChunk};
+use crate::collation::Locale;
 use crate::proximity::ProximityPrecision;
 use crate::vector::EmbeddingConfigs;
-use crate::{FieldId, FieldsIdsMap, Result};
+use crate::{CaseFolding, FieldId, FieldsIdsMap, Result};
 
 /// Extract data for each databases from obkv documents in parallel.
 /// Send data in grenad file over provided Sender.
@@ -45,14 +47,18 @@ pub(crate) fn data_from_obkv_documents(
     lmdb_writer_sx: Sender<Result<TypedChunk>>,
     searchable_fields: Option<HashSet<FieldId>>,
     faceted_fields: HashSet<FieldId>,
+    date_fields_ids: HashSet<FieldId>,
     primary_key_id: FieldId,
     geo_fields_ids: Option<(FieldId, FieldId)>,
+    case_folding: CaseFolding,
     field_id_map: FieldsIdsMap,
     stop_words: Option<fst::Set<Vec<u8>>>,
     allowed_separators: Option<&[&str]>,
     dictionary: Option<&[&str]>,
     max_positions_per_attributes: Option<u32>,
+    number_normalization: bool,
     exact_attributes: HashSet<FieldId>,
+    stemmed_fields_ids: HashMap<FieldId, Vec<Locale>>,
     proximity_precision: ProximityPrecision,
     embedders: EmbeddingConfigs,
 ) -> Result<()> {
@@ -83,12 +89,15 @@ pub(crate) fn data_from_obkv_documents(
                         lmdb_writer_sx.clone(),
                         &searchable_fields,
                         &faceted_fields,
+                        &date_fields_ids,
                         primary_key_id,
                         geo_fields_ids,
+                        case_folding,
                         &stop_words,
                         &allowed_separators,
                         &dictionary,
                         max_positions_per_attributes,
+                        number_normalization,
                     )
                 })
                 .map(|result| {
@@ -107,6 +116,7 @@ pub(crate) fn data_from_obkv_documents(
                         );
 
                         let exact_attributes = exact_attributes.clone();
+                        let word_docids_stemmed_fields_ids = stemmed_fields_ids.clone();
                         run_extraction_task::<
                             _,
                             _,
@@ -120,7 +130,12 @@ pub(crate) fn data_from_obkv_documents(
                             indexer,
                             lmdb_writer_sx.clone(),
                             move |doc_word_pos, indexer| {
-                                extract_word_docids(doc_word_pos, indexer, &exact_attributes)
+                                extract_word_docids(
+                                    doc_word_pos,
+                                    indexer,
+                                    &exact_attributes,
+                                    &word_docids_stemmed_fields_ids,
+                                )
                             },
                             |(
                                 word_docids_reader,
@@ -136,11 +151,18 @@ pub(crate) fn data_from_obkv_documents(
                             "word-docids",
                         );
 
+                        let stemmed_fields_ids = stemmed_fields_ids.clone();
                         run_extraction_task::<_, _, grenad::Reader<BufReader<File>>>(
                             docid_word_positions_chunk.clone(),
                             indexer,
                             lmdb_writer_sx.clone(),
-                            extract_word_position_docids,
+                            move |doc_word_pos, indexer| {
+                                extract_word_position_docids(
+                                    doc_word_pos,
+                                    indexer,
+                                    &stemmed_fields_ids,
+                                )
+                            },
                             TypedChunk::WordPositionDocids,
                             "word-position-docids",
                         );
@@ -308,12 +330,15 @@ fn send_and_extract_flattened_documents_data(
     lmdb_writer_sx: Sender<Result<TypedChunk>>,
     searchable_fields: &Option<HashSet<FieldId>>,
     faceted_fields: &HashSet<FieldId>,
+    date_fields_ids: &HashSet<FieldId>,
     primary_key_id: FieldId,
     geo_fields_ids: Option<(FieldId, FieldId)>,
+    case_folding: CaseFolding,
     stop_words: &Option<fst::Set<Vec<u8>>>,
     allowed_separators: &Option<&[&str]>,
     dictionary: &Option<&[&str]>,
     max_positions_per_attributes: Option<u32>,
+    number_normalization: bool,
 ) -> Result<(
     grenad::Reader<CursorClonableMmap>,
     (grenad::Reader<CursorClonableMmap>, grenad::Reader<CursorClonableMmap>),
@@ -346,6 +371,7 @@ fn send_and_extract_flattened_documents_data(
                         *allowed_separators,
                         *dictionary,
                         max_positions_per_attributes,
+                        number_normalization,
                     )?;
 
                 // send docid_word_positions_chunk to DB writer
@@ -364,11 +390,14 @@ fn send_and_extract_flattened_documents_data(
                     fid_facet_is_null_docids_chunk,
                     fid_facet_is_empty_docids_chunk,
                     fid_facet_exists_docids_chunk,
+                    fid_facet_bool_docids_chunk,
                 } = extract_fid_docid_facet_values(
                     flattened_documents_chunk.clone(),
                     indexer,
                     faceted_fields,
+                    date_fields_ids,
                     geo_fields_ids,
+                    case_folding,
                 )?;
 
                 // send fid_docid_facet_numbers_chunk to DB writer
@@ -397,6 +426,9 @@ fn send_and_extract_flattened_documents_data(
                 let _ = lmdb_writer_sx
                     .send(Ok(TypedChunk::FieldIdFacetExistsDocids(fid_facet_exists_docids_chunk)));
 
+                let _ = lmdb_writer_sx
+                    .send(Ok(TypedChunk::FieldIdFacetBoolDocids(fid_facet_bool_docids_chunk)));
+
                 Ok((fid_docid_facet_numbers_chunk, fid_docid_facet_strings_chunk))
             },
         );
@@ -167,6 +167,74 @@ pub fn grenad_obkv_into_chunks<R: io::Read + io::Seek>(
     Ok(std::iter::from_fn(move || transposer().transpose()))
 }
 
+/// The original and flattened versions of the same documents.
+pub type DocumentsChunk = (grenad::Reader<BufReader<File>>, grenad::Reader<BufReader<File>>);
+
+/// Splits the original and the flattened documents into chunks of the same documents, a chunk
+/// being cut once its flattened documents weigh `chunk_size` bytes.
+///
+/// Returns the chunks along with the size of their flattened documents.
+pub fn grenad_obkv_into_paired_chunks<R: io::Read + io::Seek>(
+    original_reader: grenad::Reader<R>,
+    flattened_reader: grenad::Reader<R>,
+    indexer: GrenadParameters,
+    chunk_size: usize,
+) -> Result<Vec<(DocumentsChunk, u64)>> {
+    let mut original_cursor = original_reader.into_cursor()?;
+    let mut flattened_cursor = flattened_reader.into_cursor()?;
+    let new_writer = || -> Result<_> {
+        Ok(create_writer(
+            indexer.chunk_compression_type,
+            indexer.chunk_compression_level,
+            tempfile::tempfile()?,
+        ))
+    };
+
+    let mut chunks = Vec::new();
+    // the first original document of the next chunk, read while filling the previous one
+    let mut next_original: Option<(Vec<u8>, Vec<u8>)> = None;
+    loop {
+        let mut current_chunk_size = 0u64;
+        let mut flattened_documents = new_writer()?;
+        let mut last_document_id = None;
+        while let Some((document_id, obkv)) = flattened_cursor.move_on_next()? {
+            if obkv.is_empty() {
+                continue;
+            }
+            flattened_documents.insert(document_id, obkv)?;
+            current_chunk_size += document_id.len() as u64 + obkv.len() as u64;
+
+            if current_chunk_size >= chunk_size as u64 {
+                last_document_id = Some(document_id.to_vec());
+                break;
+            }
+        }
+
+        // the original documents are cut after the last flattened document of the chunk
+        let mut original_documents = new_writer()?;
+        if let Some((document_id, obkv)) = next_original.take() {
+            original_documents.insert(document_id, obkv)?;
+        }
+        while let Some((document_id, obkv)) = original_cursor.move_on_next()? {
+            if obkv.is_empty() {
+                continue;
+            }
+            if last_document_id.as_ref().map_or(false, |last| document_id > last.as_slice()) {
+                next_original = Some((document_id.to_vec(), obkv.to_vec()));
+                break;
+            }
+            original_documents.insert(document_id, obkv)?;
+        }
+
+        let chunk =
+            (writer_into_reader(original_documents)?, writer_into_reader(flattened_documents)?);
+        chunks.push((chunk, current_chunk_size));
+        if last_document_id.is_none() {
+            return Ok(chunks);
+        }
+    }
+}
+
 /// Write provided sorter in database using serialize_value function.
 /// merge_values function is used if an entry already exist in the database.
 #[tracing::instrument(level = "trace", skip_all, target = "indexing::grenad")]
@@ -5,7 +5,7 @@ mod transform;
 mod typed_chunk;
 
 use std::collections::{HashMap, HashSet};
-use std::io::{Read, Seek};
+use std::io::{BufReader, Read, Seek};
 use std::iter::FromIterator;
 use std::num::NonZeroU32;
 use std::result::Result as StdResult;
@@ -23,13 +23,15 @@ use typed_chunk::{write_typed_chunk_into_index, ChunkAccumulator, TypedChunk};
 
 use self::enrich::enrich_documents_batch;
 pub use self::enrich::{extract_finite_float_from_value, DocumentId};
+pub(crate) use self::extract::json_to_string;
 pub use self::helpers::{
     as_cloneable_grenad, create_sorter, create_writer, fst_stream_into_hashset,
     fst_stream_into_vec, merge_cbo_roaring_bitmaps, merge_deladd_cbo_roaring_bitmaps,
     merge_deladd_cbo_roaring_bitmaps_into_cbo_roaring_bitmap, merge_roaring_bitmaps,
     valid_lmdb_key, write_sorter_into_database, writer_into_reader, MergeFn,
 };
-use self::helpers::{grenad_obkv_into_chunks, GrenadParameters};
+use self::helpers::{grenad_obkv_into_chunks, grenad_obkv_into_paired_chunks, GrenadParameters};
+use self::transform::DocumentTask;
 pub use self::transform::{Transform, TransformOutput};
 use crate::documents::{obkv_to_object, DocumentsBatchReader};
 use crate::error::{Error, InternalError, UserError};
@@ -44,6 +46,10 @@ static MERGED_DATABASE_COUNT: usize = 7;
 static PREFIX_DATABASE_COUNT: usize = 4;
 static TOTAL_POSTING_DATABASE_COUNT: usize = MERGED_DATABASE_COUNT + PREFIX_DATABASE_COUNT;
 
+/// The size of the transformed documents above which they are extracted and merged by chunks,
+/// when the [`IndexerConfig`] doesn't specify it.
+pub const DEFAULT_TRANSFORM_CHUNK_SIZE: usize = 512 * 1024 * 1024; // 512MiB
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DocumentAdditionResult {
     /// The number of documents that were indexed during the update
@@ -52,6 +58,37 @@ pub struct DocumentAdditionResult {
     pub number_of_documents: u64,
 }
 
+/// The result of reading one payload of documents with [`IndexDocuments::add_payload`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadAdditionResult {
+    /// The number of documents of the payload
+    pub indexed_documents: u64,
+    /// The number of documents sharing their primary key with an earlier document of the same payload.
+    ///
+    /// They replace the earlier document when replacing documents, and are merged into it in order when updating documents.
+    pub duplicate_documents: u64,
+}
+
+/// The maximum number of errors collected from the invalid documents of a payload.
+pub const MAX_DOCUMENT_ERROR_SAMPLES: usize = 100;
+
+/// Why a payload of documents was refused with [`IndexDocuments::add_payload`].
+#[derive(Debug)]
+pub struct InvalidDocuments {
+    /// The error of the first invalid document, that is the error of the whole payload.
+    pub error: UserError,
+    /// The distinct errors of the following invalid documents, in the order of the documents.
+    ///
+    /// There are at most [`MAX_DOCUMENT_ERROR_SAMPLES`] errors, `error` included.
+    pub other_errors: Vec<UserError>,
+}
+
+impl From<UserError> for InvalidDocuments {
+    fn from(error: UserError) -> Self {
+        InvalidDocuments { error, other_errors: Vec::new() }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum IndexDocumentsMethod {
@@ -136,14 +173,27 @@ where
     /// Returns the number of documents added to the builder.
     #[tracing::instrument(level = "trace", skip_all, target = "indexing::documents")]
     pub fn add_documents<R: Read + Seek>(
-        mut self,
+        self,
         reader: DocumentsBatchReader<R>,
     ) -> Result<(Self, StdResult<u64, UserError>)> {
+        let (this, result) = self.add_payload(reader)?;
+        Ok((this, result.map(|result| result.indexed_documents).map_err(|invalid| invalid.error)))
+    }
+
+    /// Same as [`Self::add_documents`], but also counts the documents of the payload sharing their primary key,
+    /// and returns the errors of all the invalid documents of the payload instead of only the first one.
+    pub fn add_payload<R: Read + Seek>(
+        mut self,
+        reader: DocumentsBatchReader<R>,
+    ) -> Result<(Self, StdResult<PayloadAdditionResult, InvalidDocuments>)> {
         puffin::profile_function!();
 
         // Early return when there is no document to add
         if reader.is_empty() {
-            return Ok((self, Ok(0)));
+            return Ok((
+                self,
+                Ok(PayloadAdditionResult { indexed_documents: 0, duplicate_documents: 0 }),
+            ));
         }
 
         // We check for user errors in this validator and if there is one, we can return
@@ -156,20 +206,20 @@ where
             reader,
         )? {
             Ok(reader) => reader,
-            Err(user_error) => return Ok((self, Err(user_error))),
+            Err(invalid_documents) => return Ok((self, Err(invalid_documents))),
         };
 
-        let indexed_documents =
+        let result =
             self.transform.as_mut().expect("Invalid document addition state").read_documents(
                 enriched_documents_reader,
                 self.wtxn,
                 &self.progress,
                 &self.should_abort,
-            )? as u64;
+            )?;
 
-        self.added_documents += indexed_documents;
+        self.added_documents += result.indexed_documents;
 
-        Ok((self, Ok(indexed_documents)))
+        Ok((self, Ok(result)))
     }
 
     pub fn with_embedders(mut self, embedders: EmbeddingConfigs) -> Self {
@@ -177,6 +227,17 @@ where
         self
     }
 
+    /// Records the uid of the task in the history of the documents added, updated, or deleted
+    /// by the next payloads and deletions, keeping the `history_size` last tasks of each document.
+    ///
+    /// `None` stops recording the history.
+    pub fn with_document_task(mut self, task: Option<(u32, usize)>) -> Self {
+        let transform = self.transform.as_mut().expect("Invalid document addition state");
+        transform.document_task =
+            task.map(|(uid, history_size)| DocumentTask { uid, history_size });
+        self
+    }
+
     /// Remove a batch of documents from the current builder.
     ///
     /// Returns the number of documents deleted from the builder.
@@ -249,6 +310,9 @@ where
     pub fn execute(mut self) -> Result<DocumentAdditionResult> {
         puffin::profile_function!();
 
+        // The strategies are recorded again by the facet updates of these documents.
+        self.index.delete_last_facet_update_strategies(self.wtxn)?;
+
         if self.added_documents == 0 && self.deleted_documents == 0 {
             let number_of_documents = self.index.number_of_documents(self.wtxn)?;
             return Ok(DocumentAdditionResult { indexed_documents: 0, number_of_documents });
@@ -326,12 +390,6 @@ where
             }
         };
 
-        // create LMDB writer channel
-        let (lmdb_writer_sx, lmdb_writer_rx): (
-            Sender<Result<TypedChunk>>,
-            Receiver<Result<TypedChunk>>,
-        ) = crossbeam_channel::unbounded();
-
         // get the primary key field id
         let primary_key_id = fields_ids_map.id(&primary_key).unwrap();
 
@@ -362,10 +420,16 @@ where
             None => None,
         };
 
+        let case_folding = self.index.case_folding(self.wtxn)?;
+        let date_fields_ids = self.index.date_fields_ids(self.wtxn)?;
         let stop_words = self.index.stop_words(self.wtxn)?;
         let separators = self.index.allowed_separators(self.wtxn)?;
         let dictionary = self.index.dictionary(self.wtxn)?;
-        let exact_attributes = self.index.exact_attributes_ids(self.wtxn)?;
+        let number_normalization = self.index.number_normalization(self.wtxn)?;
+        // the words of the strict attributes are only ever looked up as they are typed
+        let mut exact_attributes = self.index.exact_attributes_ids(self.wtxn)?;
+        exact_attributes.extend(self.index.strict_attributes_ids(self.wtxn)?);
+        let stemmed_fields_ids = self.index.stemmed_fields_ids(self.wtxn)?;
         let proximity_precision = self.index.proximity_precision(self.wtxn)?.unwrap_or_default();
 
         let pool_params = GrenadParameters {
@@ -374,204 +438,257 @@ where
             max_memory: self.indexer_config.max_memory,
             max_nb_chunks: self.indexer_config.max_nb_chunks, // default value, may be chosen.
         };
-        let documents_chunk_size = match self.indexer_config.documents_chunk_size {
-            Some(chunk_size) => chunk_size,
-            None => {
-                let default_chunk_size = 1024 * 1024 * 4; // 4MiB
-                let min_chunk_size = 1024 * 512; // 512KiB
-
-                // compute the chunk size from the number of available threads and the inputed data size.
-                let total_size = flattened_documents.metadata().map(|m| m.len());
-                let current_num_threads = pool.current_num_threads();
-                // if we have more than 2 thread, create a number of chunk equal to 3/4 threads count
-                let chunk_count = if current_num_threads > 2 {
-                    (current_num_threads * 3 / 4).max(2)
-                } else {
-                    current_num_threads
-                };
-                total_size
-                    .map_or(default_chunk_size, |size| (size as usize) / chunk_count)
-                    .max(min_chunk_size)
+
+        // The large transforms are extracted and merged by chunks of documents, one after the
+        // other and in the same transaction, for the memory used by the extraction to stay bounded.
+        let transform_chunk_size =
+            self.indexer_config.transform_chunk_size.unwrap_or(DEFAULT_TRANSFORM_CHUNK_SIZE);
+        let chunks = match flattened_documents.metadata().map(|m| m.len()) {
+            Ok(total_size) if total_size > transform_chunk_size as u64 => {
+                grenad_obkv_into_paired_chunks(
+                    grenad::Reader::new(original_documents)?,
+                    grenad::Reader::new(flattened_documents)?,
+                    pool_params,
+                    transform_chunk_size,
+                )?
+                .into_iter()
+                .map(|(chunk, size)| (chunk, Some(size)))
+                .collect()
+            }
+            total_size => {
+                let original_documents = grenad::Reader::new(BufReader::new(original_documents))?;
+                let flattened_documents = grenad::Reader::new(BufReader::new(flattened_documents))?;
+                vec![((original_documents, flattened_documents), total_size.ok())]
             }
         };
-
-        let original_documents = grenad::Reader::new(original_documents)?;
-        let flattened_documents = grenad::Reader::new(flattened_documents)?;
+        let total_chunks = chunks.len();
 
         let max_positions_per_attributes = self.indexer_config.max_positions_per_attributes;
 
-        let cloned_embedder = self.embedders.clone();
-
         let mut final_documents_ids = RoaringBitmap::new();
-        let mut databases_seen = 0;
         let mut word_position_docids = None;
         let mut word_fid_docids = None;
         let mut word_docids = None;
         let mut exact_word_docids = None;
-        let mut chunk_accumulator = ChunkAccumulator::default();
         let mut dimension = HashMap::new();
         let stop_words = stop_words.map(|sw| sw.map_data(Vec::from).unwrap());
 
-        let current_span = tracing::Span::current();
-
-        // Run extraction pipeline in parallel.
-        pool.install(|| {
-            rayon::spawn(move || {
-                let child_span = tracing::trace_span!(target: "indexing::details", parent: &current_span, "extract_and_send_grenad_chunks");
-            let _enter = child_span.enter();
-            puffin::profile_scope!("extract_and_send_grenad_chunks");
-                // split obkv file into several chunks
-                let original_chunk_iter =
-                    grenad_obkv_into_chunks(original_documents, pool_params, documents_chunk_size);
-
-                // split obkv file into several chunks
-                let flattened_chunk_iter =
-                    grenad_obkv_into_chunks(flattened_documents, pool_params, documents_chunk_size);
-
-                let separators: Option<Vec<_>> =
-                    separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
-                let dictionary: Option<Vec<_>> =
-                    dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
-                let result = original_chunk_iter.and_then(|original_chunk| {
-                    let flattened_chunk = flattened_chunk_iter?;
-                    // extract all databases from the chunked obkv douments
-                    extract::data_from_obkv_documents(
-                        original_chunk,
-                        flattened_chunk,
-                        pool_params,
-                        lmdb_writer_sx.clone(),
-                        searchable_fields,
-                        faceted_fields,
-                        primary_key_id,
-                        geo_fields_ids,
-                        field_id_map,
-                        stop_words,
-                        separators.as_deref(),
-                        dictionary.as_deref(),
-                        max_positions_per_attributes,
-                        exact_attributes,
-                        proximity_precision,
-                        cloned_embedder,
-                    )
+        for (chunk_index, ((original_documents, flattened_documents), total_size)) in
+            chunks.into_iter().enumerate()
+        {
+            if total_chunks > 1 {
+                (self.progress)(UpdateIndexingStep::ExtractAndMergeChunk {
+                    chunk: chunk_index + 1,
+                    total_chunks,
                 });
+                debug!(chunk = chunk_index + 1, total = total_chunks, "Extracting documents chunk");
+            }
 
-                if let Err(e) = result {
-                    let _ = lmdb_writer_sx.send(Err(e));
+            let documents_chunk_size = match self.indexer_config.documents_chunk_size {
+                Some(chunk_size) => chunk_size,
+                None => {
+                    let default_chunk_size = 1024 * 1024 * 4; // 4MiB
+                    let min_chunk_size = 1024 * 512; // 512KiB
+
+                    // compute the chunk size from the number of available threads and the inputed data size.
+                    let current_num_threads = pool.current_num_threads();
+                    // if we have more than 2 thread, create a number of chunk equal to 3/4 threads count
+                    let chunk_count = if current_num_threads > 2 {
+                        (current_num_threads * 3 / 4).max(2)
+                    } else {
+                        current_num_threads
+                    };
+                    total_size
+                        .map_or(default_chunk_size, |size| (size as usize) / chunk_count)
+                        .max(min_chunk_size)
                 }
+            };
+
+            // create LMDB writer channel
+            let (lmdb_writer_sx, lmdb_writer_rx): (
+                Sender<Result<TypedChunk>>,
+                Receiver<Result<TypedChunk>>,
+            ) = crossbeam_channel::unbounded();
+
+            // the extraction of each chunk takes its own copy of the settings
+            let searchable_fields = searchable_fields.clone();
+            let faceted_fields = faceted_fields.clone();
+            let date_fields_ids = date_fields_ids.clone();
+            let field_id_map = field_id_map.clone();
+            let stop_words = stop_words.clone();
+            let separators = separators.clone();
+            let dictionary = dictionary.clone();
+            let exact_attributes = exact_attributes.clone();
+            let stemmed_fields_ids = stemmed_fields_ids.clone();
+            let cloned_embedder = self.embedders.clone();
+
+            let mut databases_seen = 0;
+            let mut chunk_accumulator = ChunkAccumulator::default();
+            let current_span = tracing::Span::current();
+
+            // Run extraction pipeline in parallel.
+            pool.install(|| {
+                rayon::spawn(move || {
+                    let child_span = tracing::trace_span!(target: "indexing::details", parent: &current_span, "extract_and_send_grenad_chunks");
+                let _enter = child_span.enter();
+                puffin::profile_scope!("extract_and_send_grenad_chunks");
+                    // split obkv file into several chunks
+                    let original_chunk_iter =
+                        grenad_obkv_into_chunks(original_documents, pool_params, documents_chunk_size);
+
+                    // split obkv file into several chunks
+                    let flattened_chunk_iter =
+                        grenad_obkv_into_chunks(flattened_documents, pool_params, documents_chunk_size);
+
+                    let separators: Option<Vec<_>> =
+                        separators.as_ref().map(|x| x.iter().map(String::as_str).collect());
+                    let dictionary: Option<Vec<_>> =
+                        dictionary.as_ref().map(|x| x.iter().map(String::as_str).collect());
+                    let result = original_chunk_iter.and_then(|original_chunk| {
+                        let flattened_chunk = flattened_chunk_iter?;
+                        // extract all databases from the chunked obkv douments
+                        extract::data_from_obkv_documents(
+                            original_chunk,
+                            flattened_chunk,
+                            pool_params,
+                            lmdb_writer_sx.clone(),
+                            searchable_fields,
+                            faceted_fields,
+                            date_fields_ids,
+                            primary_key_id,
+                            geo_fields_ids,
+                            case_folding,
+                            field_id_map,
+                            stop_words,
+                            separators.as_deref(),
+                            dictionary.as_deref(),
+                            max_positions_per_attributes,
+                            number_normalization,
+                            exact_attributes,
+                            stemmed_fields_ids,
+                            proximity_precision,
+                            cloned_embedder,
+                        )
+                    });
+
+                    if let Err(e) = result {
+                        let _ = lmdb_writer_sx.send(Err(e));
+                    }
 
-                // needs to be dropped to avoid channel waiting lock.
-                drop(lmdb_writer_sx);
-            });
+                    // needs to be dropped to avoid channel waiting lock.
+                    drop(lmdb_writer_sx);
+                });
 
-            (self.progress)(UpdateIndexingStep::MergeDataIntoFinalDatabase {
-                databases_seen,
-                total_databases: TOTAL_POSTING_DATABASE_COUNT,
-            });
+                (self.progress)(UpdateIndexingStep::MergeDataIntoFinalDatabase {
+                    databases_seen,
+                    total_databases: TOTAL_POSTING_DATABASE_COUNT,
+                });
 
-            loop {
-                if (self.should_abort)() {
-                    return Err(Error::InternalError(InternalError::AbortedIndexation));
-                }
+                loop {
+                    if (self.should_abort)() {
+                        return Err(Error::InternalError(InternalError::AbortedIndexation));
+                    }
 
-                match lmdb_writer_rx.clone().recv_timeout(std::time::Duration::from_millis(500)) {
-                    Err(status) => {
-                        if let Some(typed_chunks) = chunk_accumulator.pop_longest() {
-                            let (docids, is_merged_database) =
-                                write_typed_chunk_into_index(typed_chunks, self.index, self.wtxn)?;
-                            if !docids.is_empty() {
-                                final_documents_ids |= docids;
-                                let documents_seen_count = final_documents_ids.len();
-                                (self.progress)(UpdateIndexingStep::IndexDocuments {
-                                    documents_seen: documents_seen_count as usize,
-                                    total_documents: documents_count,
-                                });
-                                debug!(documents = documents_seen_count, total = documents_count, "Seen");
-                            }
-                            if is_merged_database {
-                                databases_seen += 1;
-                                (self.progress)(UpdateIndexingStep::MergeDataIntoFinalDatabase {
-                                    databases_seen,
-                                    total_databases: TOTAL_POSTING_DATABASE_COUNT,
-                                });
+                    match lmdb_writer_rx.clone().recv_timeout(std::time::Duration::from_millis(500)) {
+                        Err(status) => {
+                            if let Some(typed_chunks) = chunk_accumulator.pop_longest() {
+                                let (docids, is_merged_database) =
+                                    write_typed_chunk_into_index(typed_chunks, self.index, self.wtxn)?;
+                                if !docids.is_empty() {
+                                    final_documents_ids |= docids;
+                                    let documents_seen_count = final_documents_ids.len();
+                                    (self.progress)(UpdateIndexingStep::IndexDocuments {
+                                        documents_seen: documents_seen_count as usize,
+                                        total_documents: documents_count,
+                                    });
+                                    debug!(documents = documents_seen_count, total = documents_count, "Seen");
+                                }
+                                if is_merged_database {
+                                    databases_seen += 1;
+                                    (self.progress)(UpdateIndexingStep::MergeDataIntoFinalDatabase {
+                                        databases_seen,
+                                        total_databases: TOTAL_POSTING_DATABASE_COUNT,
+                                    });
+                                }
+                            // If no more chunk remains in the chunk accumulator and the channel is disconected, break.
+                            } else if status == crossbeam_channel::RecvTimeoutError::Disconnected {
+                                break;
+                            } else {
+                                rayon::yield_now();
                             }
-                        // If no more chunk remains in the chunk accumulator and the channel is disconected, break.
-                        } else if status == crossbeam_channel::RecvTimeoutError::Disconnected {
-                            break;
-                        } else {
-                            rayon::yield_now();
                         }
-                    }
-                    Ok(result) => {
-                        let typed_chunk = match result? {
-                            TypedChunk::WordDocids {
-                                word_docids_reader,
-                                exact_word_docids_reader,
-                                word_fid_docids_reader,
-                            } => {
-                                let cloneable_chunk =
-                                    unsafe { as_cloneable_grenad(&word_docids_reader)? };
-                                let word_docids = word_docids.get_or_insert_with(|| {
-                                    MergerBuilder::new(merge_deladd_cbo_roaring_bitmaps as MergeFn)
-                                });
-                                word_docids.push(cloneable_chunk.into_cursor()?);
-                                let cloneable_chunk =
-                                    unsafe { as_cloneable_grenad(&exact_word_docids_reader)? };
-                                let exact_word_docids =
-                                    exact_word_docids.get_or_insert_with(|| {
-                                        MergerBuilder::new(
-                                            merge_deladd_cbo_roaring_bitmaps as MergeFn,
-                                        )
-                                    });
-                                exact_word_docids.push(cloneable_chunk.into_cursor()?);
-                                let cloneable_chunk =
-                                    unsafe { as_cloneable_grenad(&word_fid_docids_reader)? };
-                                let word_fid_docids = word_fid_docids.get_or_insert_with(|| {
-                                    MergerBuilder::new(merge_deladd_cbo_roaring_bitmaps as MergeFn)
-                                });
-                                word_fid_docids.push(cloneable_chunk.into_cursor()?);
+                        Ok(result) => {
+                            let typed_chunk = match result? {
                                 TypedChunk::WordDocids {
                                     word_docids_reader,
                                     exact_word_docids_reader,
                                     word_fid_docids_reader,
-                                }
-                            }
-                            TypedChunk::WordPositionDocids(chunk) => {
-                                let cloneable_chunk = unsafe { as_cloneable_grenad(&chunk)? };
-                                let word_position_docids =
-                                    word_position_docids.get_or_insert_with(|| {
-                                        MergerBuilder::new(
-                                            merge_deladd_cbo_roaring_bitmaps as MergeFn,
-                                        )
+                                } => {
+                                    let cloneable_chunk =
+                                        unsafe { as_cloneable_grenad(&word_docids_reader)? };
+                                    let word_docids = word_docids.get_or_insert_with(|| {
+                                        MergerBuilder::new(merge_deladd_cbo_roaring_bitmaps as MergeFn)
                                     });
-                                word_position_docids.push(cloneable_chunk.into_cursor()?);
-                                TypedChunk::WordPositionDocids(chunk)
-                            }
-                            TypedChunk::VectorPoints {
-                                expected_dimension,
-                                remove_vectors,
-                                embeddings,
-                                manual_vectors,
-                                embedder_name,
-                            } => {
-                                dimension.insert(embedder_name.clone(), expected_dimension);
+                                    word_docids.push(cloneable_chunk.into_cursor()?);
+                                    let cloneable_chunk =
+                                        unsafe { as_cloneable_grenad(&exact_word_docids_reader)? };
+                                    let exact_word_docids =
+                                        exact_word_docids.get_or_insert_with(|| {
+                                            MergerBuilder::new(
+                                                merge_deladd_cbo_roaring_bitmaps as MergeFn,
+                                            )
+                                        });
+                                    exact_word_docids.push(cloneable_chunk.into_cursor()?);
+                                    let cloneable_chunk =
+                                        unsafe { as_cloneable_grenad(&word_fid_docids_reader)? };
+                                    let word_fid_docids = word_fid_docids.get_or_insert_with(|| {
+                                        MergerBuilder::new(merge_deladd_cbo_roaring_bitmaps as MergeFn)
+                                    });
+                                    word_fid_docids.push(cloneable_chunk.into_cursor()?);
+                                    TypedChunk::WordDocids {
+                                        word_docids_reader,
+                                        exact_word_docids_reader,
+                                        word_fid_docids_reader,
+                                    }
+                                }
+                                TypedChunk::WordPositionDocids(chunk) => {
+                                    let cloneable_chunk = unsafe { as_cloneable_grenad(&chunk)? };
+                                    let word_position_docids =
+                                        word_position_docids.get_or_insert_with(|| {
+                                            MergerBuilder::new(
+                                                merge_deladd_cbo_roaring_bitmaps as MergeFn,
+                                            )
+                                        });
+                                    word_position_docids.push(cloneable_chunk.into_cursor()?);
+                                    TypedChunk::WordPositionDocids(chunk)
+                                }
                                 TypedChunk::VectorPoints {
+                                    expected_dimension,
                                     remove_vectors,
                                     embeddings,
-                                    expected_dimension,
                                     manual_vectors,
                                     embedder_name,
+                                } => {
+                                    dimension.insert(embedder_name.clone(), expected_dimension);
+                                    TypedChunk::VectorPoints {
+                                        remove_vectors,
+                                        embeddings,
+                                        expected_dimension,
+                                        manual_vectors,
+                                        embedder_name,
+                                    }
                                 }
-                            }
-                            otherwise => otherwise,
-                        };
+                                otherwise => otherwise,
+                            };
 
-                        chunk_accumulator.insert(typed_chunk);
+                            chunk_accumulator.insert(typed_chunk);
+                        }
                     }
                 }
-            }
 
-            Ok(())
-        })?;
+                Ok(())
+            })?;
+        }
 
         // We write the field distribution into the main database
         self.index.put_field_distribution(self.wtxn, &field_distribution)?;
@@ -912,8 +1029,8 @@ mod tests {
         // Check that we get only one document from the database.
         let docs = index.documents(&rtxn, Some(0)).unwrap();
         assert_eq!(docs.len(), 1);
-        let (id, doc) = docs[0];
-        assert_eq!(id, 0);
+        let (id, doc) = &docs[0];
+        assert_eq!(*id, 0);
 
         // Check that this document is equal to the last one sent.
         let mut doc_iter = doc.iter();
@@ -933,8 +1050,8 @@ mod tests {
         // Check that we get only one document from the database.
         let docs = index.documents(&rtxn, Some(0)).unwrap();
         assert_eq!(docs.len(), 1);
-        let (id, doc) = docs[0];
-        assert_eq!(id, 0);
+        let (id, doc) = &docs[0];
+        assert_eq!(*id, 0);
 
         // Check that this document is equal to the last one sent.
         let mut doc_iter = doc.iter();
@@ -999,7 +1116,7 @@ mod tests {
         let kevin_position =
             docs.iter().position(|(_, d)| d.get(0).unwrap() == br#""updated kevin""#).unwrap();
         assert_eq!(kevin_position, 2);
-        let (_, doc) = docs[kevin_position];
+        let (_, doc) = &docs[kevin_position];
 
         // Check that this document is equal to the last
         // one sent and that an UUID has been generated.
@@ -2509,6 +2626,113 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn add_payload_returns_the_errors_of_all_the_invalid_documents() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_primary_key(S("id"));
+                settings.set_filterable_fields(hashset! { S("_geo") });
+            })
+            .unwrap();
+
+        let mut wtxn = index.write_txn().unwrap();
+        let builder = IndexDocuments::new(
+            &mut wtxn,
+            &index,
+            &index.indexer_config,
+            index.index_documents_config.clone(),
+            |_| (),
+            || false,
+        )
+        .unwrap();
+
+        let documents = documents!([
+            { "id": 1, "name": "kevin" },
+            { "id": "invalid id" },
+            { "name": "no id" },
+            { "id": 4, "_geo": { "lat": "north", "lng": 2 } },
+            { "id": 5, "_private": true },
+            { "id": "invalid id" },
+        ]);
+        let (builder, result) = builder.add_payload(documents).unwrap();
+        let invalid = result.unwrap_err();
+        assert!(matches!(invalid.error, UserError::InvalidDocumentId { .. }));
+        // the second document with the same invalid id is only reported once
+        assert_eq!(invalid.other_errors.len(), 3);
+        assert!(matches!(invalid.other_errors[0], UserError::MissingDocumentId { .. }));
+        assert!(matches!(invalid.other_errors[1], UserError::InvalidGeoField(_)));
+        assert!(matches!(invalid.other_errors[2], UserError::ReservedDocumentField { .. }));
+
+        // the builder can still index the valid payloads
+        let (builder, result) = builder.add_payload(documents!([{ "id": 1 }])).unwrap();
+        assert_eq!(result.unwrap().indexed_documents, 1);
+        builder.execute().unwrap();
+        wtxn.commit().unwrap();
+    }
+
+    fn add_duplicate_documents_in_one_payload(method: IndexDocumentsMethod) -> TempIndex {
+        let mut index = TempIndex::new();
+        index.add_documents(documents!([{ "id": 1, "name": "kevin", "age": 20 }])).unwrap();
+        index.index_documents_config.update_method = method;
+
+        let mut wtxn = index.write_txn().unwrap();
+        let builder = IndexDocuments::new(
+            &mut wtxn,
+            &index,
+            &index.indexer_config,
+            index.index_documents_config.clone(),
+            |_| (),
+            || false,
+        )
+        .unwrap();
+
+        let documents = documents!([
+            { "id": 1, "name": "jean" },
+            { "id": 2, "name": "bob" },
+            { "id": 1, "age": 25 },
+            { "id": 1, "doggo": "kefir" },
+        ]);
+        let (builder, added) = builder.add_payload(documents).unwrap();
+        assert_eq!(
+            added.unwrap(),
+            PayloadAdditionResult { indexed_documents: 4, duplicate_documents: 2 }
+        );
+
+        // the duplicates are only counted within a payload
+        let (builder, added) = builder.add_payload(documents!([{ "id": 2, "age": 30 }])).unwrap();
+        assert_eq!(
+            added.unwrap(),
+            PayloadAdditionResult { indexed_documents: 1, duplicate_documents: 0 }
+        );
+
+        builder.execute().unwrap();
+        wtxn.commit().unwrap();
+        index
+    }
+
+    #[test]
+    fn replace_duplicate_documents_in_one_payload() {
+        let index = add_duplicate_documents_in_one_payload(IndexDocumentsMethod::ReplaceDocuments);
+
+        // the last occurrence wins
+        db_snap!(index, documents, @r###"
+        {"id":1,"doggo":"kefir"}
+        {"id":2,"age":30}
+        "###);
+    }
+
+    #[test]
+    fn update_duplicate_documents_in_one_payload() {
+        let index = add_duplicate_documents_in_one_payload(IndexDocumentsMethod::UpdateDocuments);
+
+        // the occurrences are merged in order
+        db_snap!(index, documents, @r###"
+        {"id":1,"name":"jean","age":25,"doggo":"kefir"}
+        {"id":2,"name":"bob","age":30}
+        "###);
+    }
+
     #[test]
     fn add_document_and_in_another_transform_delete_the_document_then_add_it_again() {
         let mut index = TempIndex::new();
@@ -2643,6 +2867,8 @@ mod tests {
                         source: Setting::Set(crate::vector::settings::EmbedderSource::UserProvided),
                         model: Setting::NotSet,
                         revision: Setting::NotSet,
+                        cache_dir: Setting::NotSet,
+                        pooling: Setting::NotSet,
                         api_key: Setting::NotSet,
                         dimensions: Setting::Set(3),
                         document_template: Setting::NotSet,
@@ -2674,8 +2900,9 @@ mod tests {
         let rtxn = index.read_txn().unwrap();
         let mut embedding_configs = index.embedding_configs(&rtxn).unwrap();
         let (embedder_name, embedder) = embedding_configs.pop().unwrap();
-        let embedder =
-            std::sync::Arc::new(crate::vector::Embedder::new(embedder.embedder_options).unwrap());
+        let embedder = std::sync::Arc::new(
+            crate::vector::Embedder::new(embedder.embedder_options, &Default::default()).unwrap(),
+        );
         assert_eq!("manual", embedder_name);
         let res = index
             .search(&rtxn)
@@ -3378,4 +3605,100 @@ mod tests {
         let crate::SearchResult { documents_ids, .. } = s.execute().unwrap();
         insta::assert_snapshot!(format!("{documents_ids:?}"), @"[0]");
     }
+
+    #[test]
+    fn transform_chunks_produce_the_same_databases() {
+        const WORDS: &[&str] = &[
+            "the", "quick", "brown", "fox", "jumps", "over", "lazy", "dog", "hello", "world",
+            "search", "engine", "fast", "relevant", "typo", "tolerant", "meili", "index",
+        ];
+        let sentence = |seed: usize, len: usize| {
+            (0..len)
+                .map(|i| WORDS[(seed * 7 + i * i * 13 + i) % WORDS.len()])
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let documents = |count: usize, version: usize| {
+            documents_batch_reader_from_objects((0..count).map(|id| {
+                let document = serde_json::json!({
+                    "id": id,
+                    "title": sentence(id + version, 4),
+                    "description": sentence(id * 3 + version, 40),
+                    "genre": WORDS[(id + version) % 5],
+                });
+                document.as_object().unwrap().clone()
+            }))
+        };
+
+        let unchunked = TempIndex::new_with_map_size(4096 * 100_000);
+        let mut chunked = TempIndex::new_with_map_size(4096 * 100_000);
+        chunked.indexer_config.transform_chunk_size = Some(256 * 1024);
+
+        let mut steps = Vec::new();
+        for index in [&unchunked, &chunked] {
+            index
+                .update_settings(|settings| {
+                    settings.set_primary_key(S("id"));
+                    settings.set_filterable_fields(hashset! { S("genre") });
+                })
+                .unwrap();
+
+            // the second payload replaces the documents indexed by the first one
+            for (count, version) in [(8_000, 0), (10_000, 1)] {
+                let progress = std::sync::Mutex::new(Vec::new());
+                let mut wtxn = index.write_txn().unwrap();
+                let builder = IndexDocuments::new(
+                    &mut wtxn,
+                    index,
+                    &index.indexer_config,
+                    index.index_documents_config.clone(),
+                    |step| progress.lock().unwrap().push(step),
+                    || false,
+                )
+                .unwrap();
+                let (builder, user_error) =
+                    builder.add_documents(documents(count, version)).unwrap();
+                user_error.unwrap();
+                builder.execute().unwrap();
+                wtxn.commit().unwrap();
+
+                steps.extend(progress.into_inner().unwrap().into_iter().filter_map(
+                    |step| match step {
+                        UpdateIndexingStep::ExtractAndMergeChunk { chunk, total_chunks } => {
+                            Some((chunk, total_chunks))
+                        }
+                        _ => None,
+                    },
+                ));
+            }
+        }
+
+        // only the chunked indexations report their chunks, each one processed in order
+        assert!(steps.len() > 2, "{steps:?}");
+        for (chunk, total_chunks) in &steps {
+            assert!(*chunk >= 1 && chunk <= total_chunks, "{steps:?}");
+        }
+
+        use crate::snapshot_tests::*;
+        for (name, snap) in [
+            ("documents", snap_documents as fn(&Index) -> String),
+            ("documents_ids", snap_documents_ids),
+            ("external_documents_ids", snap_external_documents_ids),
+            ("word_docids", snap_word_docids),
+            ("exact_word_docids", snap_exact_word_docids),
+            ("word_prefix_docids", snap_word_prefix_docids),
+            ("word_pair_proximity_docids", snap_word_pair_proximity_docids),
+            ("word_position_docids", snap_word_position_docids),
+            ("word_fid_docids", snap_word_fid_docids),
+            ("word_prefix_position_docids", snap_word_prefix_position_docids),
+            ("field_id_word_count_docids", snap_field_id_word_count_docids),
+            ("field_id_docid_facet_strings", snap_field_id_docid_facet_strings),
+            ("facet_id_exists_docids", snap_facet_id_exists_docids),
+            ("field_distribution", snap_field_distributions),
+            ("words_fst", snap_words_fst),
+            ("words_prefixes_fst", snap_words_prefixes_fst),
+        ] {
+            assert!(snap(&unchunked) == snap(&chunked), "the `{name}` databases differ");
+        }
+    }
 }
@@ -17,7 +17,7 @@ use super::helpers::{
     create_sorter, create_writer, keep_first, obkvs_keep_last_addition_merge_deletions,
     obkvs_merge_additions_and_deletions, sorter_into_reader, MergeFn,
 };
-use super::{IndexDocumentsMethod, IndexerConfig};
+use super::{IndexDocumentsMethod, IndexerConfig, PayloadAdditionResult};
 use crate::documents::{DocumentsBatchIndex, EnrichedDocument, EnrichedDocumentsBatchReader};
 use crate::error::{Error, InternalError, UserError};
 use crate::index::{db_name, main_key};
@@ -61,6 +61,16 @@ pub struct Transform<'a, 'i> {
     // To increase the cache locality and decrease the heap usage we use compact smartstring.
     new_external_documents_ids_builder: FxHashMap<SmartString<smartstring::Compact>, u64>,
     documents_count: usize,
+    /// The task recorded in the history of the documents read or removed next, if any.
+    pub(crate) document_task: Option<DocumentTask>,
+}
+
+/// A task to record in the history of the documents it adds, updates, or deletes.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DocumentTask {
+    pub uid: u32,
+    /// The number of tasks kept in the history of a document.
+    pub history_size: usize,
 }
 
 /// This enum is specific to the grenad sorter stored in the transform.
@@ -143,6 +153,7 @@ impl<'a, 'i> Transform<'a, 'i> {
             new_documents_ids: RoaringBitmap::new(),
             new_external_documents_ids_builder: FxHashMap::default(),
             documents_count: 0,
+            document_task: None,
         })
     }
 
@@ -153,7 +164,7 @@ impl<'a, 'i> Transform<'a, 'i> {
         wtxn: &mut heed::RwTxn,
         progress_callback: FP,
         should_abort: FA,
-    ) -> Result<usize>
+    ) -> Result<PayloadAdditionResult>
     where
         R: Read + Seek,
         FP: Fn(UpdateIndexingStep) + Sync,
@@ -173,6 +184,9 @@ impl<'a, 'i> Transform<'a, 'i> {
         let mut document_sorter_value_buffer = Vec::new();
         let mut document_sorter_key_buffer = Vec::new();
         let mut documents_count = 0;
+        // The external ids of this payload, to count the documents sharing their id with an earlier one.
+        let mut payload_external_ids = HashSet::new();
+        let mut duplicate_documents = 0;
         let mut docid_buffer: Vec<u8> = Vec::new();
         let mut field_buffer: Vec<(u16, Cow<[u8]>)> = Vec::new();
         while let Some(enriched_document) = cursor.next_enriched_document()? {
@@ -194,6 +208,11 @@ impl<'a, 'i> Transform<'a, 'i> {
             // When the document id has been auto-generated by the `enrich_documents_batch`
             // we must insert this document id into the remaped document.
             let external_id = document_id.value();
+            if payload_external_ids.insert(external_id.to_string()) {
+                self.push_document_task(wtxn, external_id)?;
+            } else {
+                duplicate_documents += 1;
+            }
             if document_id.is_generated() {
                 serde_json::to_writer(&mut docid_buffer, external_id)
                     .map_err(InternalError::SerdeJson)?;
@@ -244,18 +263,12 @@ impl<'a, 'i> Transform<'a, 'i> {
             let mut skip_insertion = false;
             if let Some(original_docid) = original_docid {
                 let original_key = original_docid;
-                let base_obkv = self
-                    .index
-                    .documents
-                    .remap_data_type::<heed::types::Bytes>()
-                    .get(wtxn, &original_key)?
-                    .ok_or(InternalError::DatabaseMissingEntry {
-                        db_name: db_name::DOCUMENTS,
-                        key: None,
-                    })?;
+                let base_obkv = self.index.document_bytes(wtxn, original_key)?.ok_or(
+                    InternalError::DatabaseMissingEntry { db_name: db_name::DOCUMENTS, key: None },
+                )?;
 
                 // we check if the two documents are exactly equal. If it's the case we can skip this document entirely
-                if base_obkv == obkv_buffer {
+                if *base_obkv == obkv_buffer[..] {
                     // we're not replacing anything
                     self.replaced_documents_ids.remove(original_docid);
                     // and we need to put back the original id as it was before
@@ -275,13 +288,13 @@ impl<'a, 'i> Transform<'a, 'i> {
                     document_sorter_value_buffer.clear();
                     document_sorter_value_buffer.push(Operation::Addition as u8);
                     into_del_add_obkv(
-                        KvReaderU16::new(base_obkv),
+                        KvReaderU16::new(&base_obkv),
                         deladd_operation,
                         &mut document_sorter_value_buffer,
                     )?;
                     self.original_sorter
                         .insert(&document_sorter_key_buffer, &document_sorter_value_buffer)?;
-                    let base_obkv = KvReader::new(base_obkv);
+                    let base_obkv = KvReader::new(&base_obkv);
                     if let Some(flattened_obkv) = self.flatten_from_fields_ids_map(base_obkv)? {
                         // we recreate our buffer with the flattened documents
                         document_sorter_value_buffer.clear();
@@ -346,7 +359,7 @@ impl<'a, 'i> Transform<'a, 'i> {
         self.documents_count += documents_count;
         // Now that we have a valid sorter that contains the user id and the obkv we
         // give it to the last transforming function which returns the TransformOutput.
-        Ok(documents_count)
+        Ok(PayloadAdditionResult { indexed_documents: documents_count as u64, duplicate_documents })
     }
 
     /// The counter part of `read_documents` that removes documents either from the transform or the database.
@@ -419,6 +432,7 @@ impl<'a, 'i> Transform<'a, 'i> {
             // Then we push the document in sorters in deletion mode.
             let deleted_from_db = match external_documents_ids.get(wtxn, &to_remove)? {
                 Some(docid) => {
+                    self.push_document_task(wtxn, &to_remove)?;
                     self.remove_document_from_db(
                         docid,
                         to_remove,
@@ -428,7 +442,12 @@ impl<'a, 'i> Transform<'a, 'i> {
                     )?;
                     true
                 }
-                None => false,
+                None => {
+                    if deleted_from_current {
+                        self.push_document_task(wtxn, &to_remove)?;
+                    }
+                    false
+                }
             };
 
             // increase counter only if the document existed somewhere before.
@@ -465,6 +484,8 @@ impl<'a, 'i> Transform<'a, 'i> {
         let mut documents_deleted = 0;
         let mut document_sorter_value_buffer = Vec::new();
         let mut document_sorter_key_buffer = Vec::new();
+        // The external ids to record the task in the history of, once the documents are read.
+        let mut removed_external_ids = Vec::new();
         let external_ids = self.index.external_id_of(wtxn, to_remove.iter())?;
 
         for (internal_docid, external_docid) in to_remove.iter().zip(external_ids) {
@@ -472,6 +493,9 @@ impl<'a, 'i> Transform<'a, 'i> {
             if should_abort() {
                 return Err(Error::InternalError(InternalError::AbortedIndexation));
             }
+            if self.document_task.is_some() {
+                removed_external_ids.push(external_docid.clone());
+            }
             self.remove_document_from_db(
                 internal_docid,
                 external_docid,
@@ -483,9 +507,21 @@ impl<'a, 'i> Transform<'a, 'i> {
             documents_deleted += 1;
         }
 
+        for external_id in removed_external_ids {
+            self.push_document_task(wtxn, &external_id)?;
+        }
+
         Ok(documents_deleted)
     }
 
+    /// Records the task of this transform, if any, in the history of the document.
+    fn push_document_task(&self, wtxn: &mut heed::RwTxn, external_id: &str) -> Result<()> {
+        if let Some(DocumentTask { uid, history_size }) = self.document_task {
+            self.index.push_document_task(wtxn, external_id, uid, history_size)?;
+        }
+        Ok(())
+    }
+
     fn remove_document_from_db(
         &mut self,
         internal_docid: u32,
@@ -498,15 +534,9 @@ impl<'a, 'i> Transform<'a, 'i> {
 
         // fetch the obkv document
         let original_key = internal_docid;
-        let base_obkv = self
-            .index
-            .documents
-            .remap_data_type::<heed::types::Bytes>()
-            .get(txn, &original_key)?
-            .ok_or(InternalError::DatabaseMissingEntry {
-                db_name: db_name::DOCUMENTS,
-                key: None,
-            })?;
+        let base_obkv = self.index.document_bytes(txn, original_key)?.ok_or(
+            InternalError::DatabaseMissingEntry { db_name: db_name::DOCUMENTS, key: None },
+        )?;
 
         // Key is the concatenation of the internal docid and the external one.
         document_sorter_key_buffer.clear();
@@ -516,14 +546,14 @@ impl<'a, 'i> Transform<'a, 'i> {
         document_sorter_value_buffer.clear();
         document_sorter_value_buffer.push(Operation::Deletion as u8);
         into_del_add_obkv(
-            KvReaderU16::new(base_obkv),
+            KvReaderU16::new(&base_obkv),
             DelAddOperation::Deletion,
             document_sorter_value_buffer,
         )?;
         self.original_sorter.insert(&document_sorter_key_buffer, &document_sorter_value_buffer)?;
 
         // flatten it and push it as to delete in the flattened_sorter
-        let flattened_obkv = KvReader::new(base_obkv);
+        let flattened_obkv = KvReader::new(&base_obkv);
         if let Some(obkv) = self.flatten_from_fields_ids_map(flattened_obkv)? {
             // we recreate our buffer with the flattened documents
             document_sorter_value_buffer.clear();
@@ -853,9 +883,10 @@ impl<'a, 'i> Transform<'a, 'i> {
         let mut document_sorter_value_buffer = Vec::new();
         for result in self.index.external_documents_ids().iter(wtxn)? {
             let (external_id, docid) = result?;
-            let obkv = self.index.documents.get(wtxn, &docid)?.ok_or(
+            let obkv = self.index.document_bytes(wtxn, docid)?.ok_or(
                 InternalError::DatabaseMissingEntry { db_name: db_name::DOCUMENTS, key: None },
             )?;
+            let obkv = KvReaderU16::new(&obkv);
 
             obkv_buffer.clear();
             let mut obkv_writer = KvWriter::<_, FieldId>::new(&mut obkv_buffer);
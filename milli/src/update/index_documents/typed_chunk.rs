@@ -80,6 +80,7 @@ pub(crate) enum TypedChunk {
     FieldIdFacetExistsDocids(grenad::Reader<BufReader<File>>),
     FieldIdFacetIsNullDocids(grenad::Reader<BufReader<File>>),
     FieldIdFacetIsEmptyDocids(grenad::Reader<BufReader<File>>),
+    FieldIdFacetBoolDocids(grenad::Reader<BufReader<File>>),
     GeoPoints(grenad::Reader<BufReader<File>>),
     VectorPoints {
         remove_vectors: grenad::Reader<BufReader<File>>,
@@ -107,6 +108,7 @@ impl TypedChunk {
             | (FieldIdFacetExistsDocids(_), FieldIdFacetExistsDocids(_))
             | (FieldIdFacetIsNullDocids(_), FieldIdFacetIsNullDocids(_))
             | (FieldIdFacetIsEmptyDocids(_), FieldIdFacetIsEmptyDocids(_))
+            | (FieldIdFacetBoolDocids(_), FieldIdFacetBoolDocids(_))
             | (GeoPoints(_), GeoPoints(_))
             | (ScriptLanguageDocids(_), ScriptLanguageDocids(_)) => true,
             (
@@ -164,6 +166,9 @@ impl TypedChunk {
             TypedChunk::FieldIdFacetIsEmptyDocids(grenad) => {
                 format!("FieldIdFacetIsEmptyDocids {{ number_of_entries: {} }}", grenad.len())
             }
+            TypedChunk::FieldIdFacetBoolDocids(grenad) => {
+                format!("FieldIdFacetBoolDocids {{ number_of_entries: {} }}", grenad.len())
+            }
             TypedChunk::GeoPoints(grenad) => {
                 format!("GeoPoints {{ number_of_entries: {} }}", grenad.len())
             }
@@ -223,10 +228,8 @@ pub(crate) fn write_typed_chunk_into_index(
                     }
                 }
 
-                let db = index.documents.remap_data_type::<Bytes>();
-
                 if !writer.is_empty() {
-                    db.put(wtxn, &docid, &writer.into_inner().unwrap())?;
+                    index.put_document_bytes(wtxn, docid, &writer.into_inner().unwrap())?;
                     operations.push(DocumentOperation {
                         external_id: external_id.to_string(),
                         internal_id: docid,
@@ -234,7 +237,7 @@ pub(crate) fn write_typed_chunk_into_index(
                     });
                     docids.insert(docid);
                 } else {
-                    db.delete(wtxn, &docid)?;
+                    index.documents.delete(wtxn, &docid)?;
                     operations.push(DocumentOperation {
                         external_id: external_id.to_string(),
                         internal_id: docid,
@@ -495,6 +498,30 @@ pub(crate) fn write_typed_chunk_into_index(
             )?;
             is_merged_database = true;
         }
+        TypedChunk::FieldIdFacetBoolDocids(_) => {
+            let span =
+                tracing::trace_span!(target: "indexing::write_db", "field_id_facet_bool_docids");
+            let _entered = span.enter();
+
+            let mut builder = MergerBuilder::new(merge_deladd_cbo_roaring_bitmaps as MergeFn);
+            for typed_chunk in typed_chunks {
+                let TypedChunk::FieldIdFacetBoolDocids(chunk) = typed_chunk else {
+                    unreachable!();
+                };
+
+                builder.push(chunk.into_cursor()?);
+            }
+            let merger = builder.build();
+
+            write_entries_into_database(
+                merger,
+                &index.facet_id_bool_docids,
+                wtxn,
+                deladd_serialize_add_side,
+                merge_deladd_cbo_roaring_bitmaps_into_cbo_roaring_bitmap,
+            )?;
+            is_merged_database = true;
+        }
         TypedChunk::WordPairProximityDocids(_) => {
             let span =
                 tracing::trace_span!(target: "indexing::write_db", "word_pair_proximity_docids");
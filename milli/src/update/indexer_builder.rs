@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+use std::io::Cursor;
+
+use heed::RwTxn;
+
+use super::{IndexDocuments, IndexDocumentsConfig, IndexerConfig, Settings};
+use crate::documents::{DocumentsBatchBuilder, DocumentsBatchReader};
+use crate::error::InternalError;
+use crate::{Criterion, Error, Index, Object, Result};
+
+/// Updates the settings of an index and adds documents to it without depending on
+/// the internals of the indexing.
+///
+/// The settings are applied before the documents are indexed, and the documents replace
+/// the existing documents with the same primary key.
+///
+/// ```no_run
+/// # fn index(index: &milli::Index, documents: Vec<milli::Object>) -> milli::Result<()> {
+/// let mut wtxn = index.write_txn()?;
+/// let indexed = milli::IndexerBuilder::new(index)
+///     .primary_key("id")
+///     .searchable_fields(["title", "overview"])
+///     .filterable_fields(["genre"])
+///     .add_documents(documents)
+///     .execute(&mut wtxn)?;
+/// wtxn.commit()?;
+/// println!("{indexed} documents indexed");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct IndexerBuilder<'a> {
+    index: &'a Index,
+    config: IndexerConfig,
+    primary_key: Option<String>,
+    searchable_fields: Option<Vec<String>>,
+    filterable_fields: Option<HashSet<String>>,
+    sortable_fields: Option<HashSet<String>>,
+    distinct_field: Option<String>,
+    criteria: Option<Vec<Criterion>>,
+    documents: Vec<Object>,
+}
+
+impl<'a> IndexerBuilder<'a> {
+    pub fn new(index: &'a Index) -> Self {
+        IndexerBuilder {
+            index,
+            config: IndexerConfig::default(),
+            primary_key: None,
+            searchable_fields: None,
+            filterable_fields: None,
+            sortable_fields: None,
+            distinct_field: None,
+            criteria: None,
+            documents: Vec::new(),
+        }
+    }
+
+    /// The memory and the threads the indexing can use.
+    pub fn config(&mut self, config: IndexerConfig) -> &mut Self {
+        self.config = config;
+        self
+    }
+
+    /// The attribute identifying the documents, it can't be changed once documents are indexed.
+    pub fn primary_key(&mut self, primary_key: impl Into<String>) -> &mut Self {
+        self.primary_key = Some(primary_key.into());
+        self
+    }
+
+    /// The attributes to search in, ordered by importance.
+    pub fn searchable_fields(
+        &mut self,
+        fields: impl IntoIterator<Item = impl Into<String>>,
+    ) -> &mut Self {
+        self.searchable_fields = Some(fields.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn filterable_fields(
+        &mut self,
+        fields: impl IntoIterator<Item = impl Into<String>>,
+    ) -> &mut Self {
+        self.filterable_fields = Some(fields.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn sortable_fields(
+        &mut self,
+        fields: impl IntoIterator<Item = impl Into<String>>,
+    ) -> &mut Self {
+        self.sortable_fields = Some(fields.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Only returns the best document of the ones sharing the same value for this attribute.
+    pub fn distinct_field(&mut self, field: impl Into<String>) -> &mut Self {
+        self.distinct_field = Some(field.into());
+        self
+    }
+
+    /// The ranking rules, the default ones are used if not set.
+    pub fn ranking_rules(&mut self, criteria: Vec<Criterion>) -> &mut Self {
+        self.criteria = Some(criteria);
+        self
+    }
+
+    pub fn add_documents(&mut self, documents: impl IntoIterator<Item = Object>) -> &mut Self {
+        self.documents.extend(documents);
+        self
+    }
+
+    /// Applies the settings and indexes the documents, returns the number of indexed documents.
+    pub fn execute(&self, wtxn: &mut RwTxn<'a>) -> Result<u64> {
+        let mut settings = Settings::new(wtxn, self.index, &self.config);
+        if let Some(primary_key) = &self.primary_key {
+            settings.set_primary_key(primary_key.clone());
+        }
+        if let Some(fields) = &self.searchable_fields {
+            settings.set_searchable_fields(fields.clone());
+        }
+        if let Some(fields) = &self.filterable_fields {
+            settings.set_filterable_fields(fields.clone());
+        }
+        if let Some(fields) = &self.sortable_fields {
+            settings.set_sortable_fields(fields.clone());
+        }
+        if let Some(field) = &self.distinct_field {
+            settings.set_distinct_field(field.clone());
+        }
+        if let Some(criteria) = &self.criteria {
+            settings.set_criteria(criteria.clone());
+        }
+        settings.execute(|_| (), || false)?;
+
+        if self.documents.is_empty() {
+            return Ok(0);
+        }
+
+        let mut builder = DocumentsBatchBuilder::new(Vec::new());
+        for document in &self.documents {
+            builder.append_json_object(document).map_err(InternalError::from)?;
+        }
+        let documents = builder.into_inner().map_err(InternalError::from)?;
+        let documents = DocumentsBatchReader::from_reader(Cursor::new(documents))?;
+
+        let indexing = IndexDocuments::new(
+            wtxn,
+            self.index,
+            &self.config,
+            IndexDocumentsConfig::default(),
+            |_| (),
+            || false,
+        )?;
+        let (indexing, user_error) = indexing.add_documents(documents)?;
+        let indexed = user_error.map_err(Error::UserError)?;
+        indexing.execute()?;
+
+        Ok(indexed)
+    }
+}
@@ -1,17 +1,27 @@
 use grenad::CompressionType;
 use rayon::ThreadPool;
 
+use crate::outbound::OutboundPolicy;
+
 #[derive(Debug)]
 pub struct IndexerConfig {
     pub log_every_n: Option<usize>,
     pub max_nb_chunks: Option<usize>,
     pub documents_chunk_size: Option<usize>,
+    /// The size of the transformed documents above which they are extracted and merged into
+    /// the index by chunks of this size, one after the other in the same transaction.
+    pub transform_chunk_size: Option<usize>,
     pub max_memory: Option<usize>,
     pub chunk_compression_type: CompressionType,
     pub chunk_compression_level: Option<u32>,
     pub thread_pool: Option<ThreadPool>,
     pub max_positions_per_attributes: Option<u32>,
     pub skip_index_budget: bool,
+    /// Opens the LMDB environments without flushing them to disk after each commit.
+    /// Only for instances whose data can be lost, e.g. in tests.
+    pub ephemeral_storage: bool,
+    /// The hosts the embedders may send requests to.
+    pub outbound_policy: OutboundPolicy,
 }
 
 impl Default for IndexerConfig {
@@ -20,12 +30,15 @@ impl Default for IndexerConfig {
             log_every_n: None,
             max_nb_chunks: None,
             documents_chunk_size: None,
+            transform_chunk_size: None,
             max_memory: None,
             chunk_compression_type: CompressionType::None,
             chunk_compression_level: None,
             thread_pool: None,
             max_positions_per_attributes: None,
             skip_index_budget: false,
+            ephemeral_storage: false,
+            outbound_policy: OutboundPolicy::default(),
         }
     }
 }
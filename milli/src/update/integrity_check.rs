@@ -0,0 +1,563 @@
+//! Verifies the invariants binding the databases of an index together.
+//!
+//! The check is meant to be run after a crash or a disk failure: it reports the violations it
+//! finds, and can remove the entries referencing documents that don't exist anymore.
+
+use std::collections::BTreeMap;
+
+use heed::types::{Bytes, DecodeIgnore};
+use heed::{Database, RoTxn, RwTxn};
+use rand::SeedableRng;
+use roaring::RoaringBitmap;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::heed_codec::facet::{
+    FacetGroupKey, FacetGroupKeyCodec, FacetGroupValue, FacetGroupValueCodec,
+};
+use crate::heed_codec::BytesRefCodec;
+use crate::search::facet::get_highest_level;
+use crate::{CboRoaringBitmapCodec, DocumentId, FieldId, FieldsIdsMap, Index, Result};
+
+/// The maximum number of examples reported for each kind of violation.
+pub const MAX_INTEGRITY_VIOLATION_EXAMPLES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IntegrityViolation {
+    /// A document id is part of the documents ids, but its document is missing.
+    DanglingDocumentsIds,
+    /// A word references documents that are missing.
+    DanglingWordDocids,
+    /// A facet group doesn't contain exactly the documents of its children.
+    UncoveredFacetGroup,
+    /// An external id references a document that is missing.
+    DanglingExternalId,
+    /// A document is referenced by several external ids.
+    DuplicatedInternalId,
+    /// A document isn't referenced by any external id.
+    MissingExternalId,
+    /// A vector belongs to a document that is missing.
+    DanglingVector,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityViolationReport {
+    pub count: u64,
+    /// The first violations found, at most [`MAX_INTEGRITY_VIOLATION_EXAMPLES`].
+    pub examples: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub violations: BTreeMap<IntegrityViolation, IntegrityViolationReport>,
+}
+
+impl IntegrityReport {
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    fn record(&mut self, violation: IntegrityViolation, example: impl FnOnce() -> String) {
+        let report = self.violations.entry(violation).or_default();
+        report.count += 1;
+        if report.examples.len() < MAX_INTEGRITY_VIOLATION_EXAMPLES {
+            report.examples.push(example());
+        }
+    }
+}
+
+/// Checks the integrity of an index and, if asked to, repairs what can be repaired:
+/// - the missing documents are removed from the documents ids and from the word databases,
+/// - the external ids of the missing documents are removed,
+/// - the facet groups are recomputed from their children,
+/// - the vectors of the missing documents are removed.
+///
+/// The documents without an external id and the documents shared by several external ids
+/// are only reported, the documents must be reindexed to fix them.
+pub struct IntegrityCheck<'t, 'i> {
+    wtxn: &'t mut RwTxn<'i>,
+    index: &'i Index,
+    repair: bool,
+}
+
+impl<'t, 'i> IntegrityCheck<'t, 'i> {
+    pub fn new(wtxn: &'t mut RwTxn<'i>, index: &'i Index, repair: bool) -> Self {
+        IntegrityCheck { wtxn, index, repair }
+    }
+
+    #[tracing::instrument(level = "trace", skip(self), target = "indexing::integrity_check")]
+    pub fn execute(mut self) -> Result<IntegrityReport> {
+        let mut report = IntegrityReport::default();
+
+        // The documents that are really stored, all the other databases must agree with them.
+        let documents: RoaringBitmap = self
+            .index
+            .documents
+            .remap_data_type::<DecodeIgnore>()
+            .iter(self.wtxn)?
+            .map(|result| result.map(|(docid, _)| docid))
+            .collect::<heed::Result<_>>()?;
+
+        self.check_documents_ids(&documents, &mut report)?;
+        self.check_word_docids(&documents, &mut report)?;
+        self.check_external_ids(&documents, &mut report)?;
+        self.check_facet_groups(&mut report)?;
+        self.check_vectors(&documents, &mut report)?;
+
+        if self.repair && !report.is_empty() {
+            self.index.set_updated_at(self.wtxn, &OffsetDateTime::now_utc())?;
+        }
+
+        Ok(report)
+    }
+
+    fn check_documents_ids(
+        &mut self,
+        documents: &RoaringBitmap,
+        report: &mut IntegrityReport,
+    ) -> Result<()> {
+        let documents_ids = self.index.documents_ids(self.wtxn)?;
+        let dangling = &documents_ids - documents;
+        for docid in &dangling {
+            report.record(IntegrityViolation::DanglingDocumentsIds, || {
+                format!("the document {docid} is part of the documents ids but is missing")
+            });
+        }
+        if self.repair && !dangling.is_empty() {
+            self.index.put_documents_ids(self.wtxn, &(documents_ids - dangling))?;
+        }
+        Ok(())
+    }
+
+    fn check_word_docids(
+        &mut self,
+        documents: &RoaringBitmap,
+        report: &mut IntegrityReport,
+    ) -> Result<()> {
+        let Index {
+            word_docids,
+            exact_word_docids,
+            word_prefix_docids,
+            exact_word_prefix_docids,
+            word_pair_proximity_docids,
+            word_position_docids,
+            word_fid_docids,
+            field_id_word_count_docids,
+            word_prefix_position_docids,
+            word_prefix_fid_docids,
+            ..
+        } = self.index;
+
+        let mut missing = RoaringBitmap::new();
+        for database in [word_docids, exact_word_docids] {
+            for result in database.iter(self.wtxn)? {
+                let (word, docids) = result?;
+                let dangling = docids - documents;
+                if !dangling.is_empty() {
+                    report.record(IntegrityViolation::DanglingWordDocids, || {
+                        format!(
+                            "the word `{word}` references the missing documents {}",
+                            display_docids(&dangling)
+                        )
+                    });
+                    missing |= dangling;
+                }
+            }
+        }
+
+        if self.repair && !missing.is_empty() {
+            // The missing documents are removed from all the databases derived from the words.
+            let databases = [
+                word_docids.remap_key_type::<Bytes>(),
+                exact_word_docids.remap_key_type(),
+                word_prefix_docids.remap_key_type(),
+                exact_word_prefix_docids.remap_key_type(),
+                word_pair_proximity_docids.remap_key_type(),
+                word_position_docids.remap_key_type(),
+                word_fid_docids.remap_key_type(),
+                field_id_word_count_docids.remap_key_type(),
+                word_prefix_position_docids.remap_key_type(),
+                word_prefix_fid_docids.remap_key_type(),
+            ];
+            for database in databases {
+                remove_docids(self.wtxn, database, &missing)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_external_ids(
+        &mut self,
+        documents: &RoaringBitmap,
+        report: &mut IntegrityReport,
+    ) -> Result<()> {
+        let external_documents_ids = self.index.external_documents_ids();
+        let mut referenced = RoaringBitmap::new();
+        let mut dangling = Vec::new();
+        for result in external_documents_ids.iter(self.wtxn)? {
+            let (external_id, docid) = result?;
+            if !documents.contains(docid) {
+                report.record(IntegrityViolation::DanglingExternalId, || {
+                    format!(
+                        "the external id `{external_id}` references the missing document {docid}"
+                    )
+                });
+                dangling.push(external_id.to_owned());
+            } else if !referenced.insert(docid) {
+                report.record(IntegrityViolation::DuplicatedInternalId, || {
+                    format!("the external id `{external_id}` references the document {docid}, which is referenced by another external id")
+                });
+            }
+        }
+
+        for docid in documents - referenced {
+            report.record(IntegrityViolation::MissingExternalId, || {
+                format!("the document {docid} has no external id")
+            });
+        }
+
+        if self.repair {
+            for external_id in dangling {
+                self.index.external_documents_ids.delete(self.wtxn, &external_id)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_facet_groups(&mut self, report: &mut IntegrityReport) -> Result<()> {
+        let fields_ids_map = self.index.fields_ids_map(self.wtxn)?;
+        let databases = [
+            self.index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+            self.index.facet_id_string_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>(),
+        ];
+
+        for database in databases {
+            for field_id in facet_field_ids(self.wtxn, database)? {
+                let highest_level = get_highest_level(self.wtxn, database, field_id)?;
+                // The levels are checked, and repaired, from the bottom so that the
+                // groups of a level are compared to the repaired groups below them.
+                for level in 1..=highest_level {
+                    let repairs = check_facet_level(
+                        self.wtxn,
+                        database,
+                        field_id,
+                        level,
+                        |left_bound| {
+                            report.record(IntegrityViolation::UncoveredFacetGroup, || {
+                                format!(
+                                    "the facet group of level {level} starting at {left_bound:?} of the `{}` field doesn't match its children",
+                                    field_name(&fields_ids_map, field_id)
+                                )
+                            })
+                        },
+                    )?;
+
+                    if self.repair {
+                        let database = database.remap_key_type::<Bytes>();
+                        for (key, group) in repairs {
+                            database.put(self.wtxn, &key, &group)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_vectors(
+        &mut self,
+        documents: &RoaringBitmap,
+        report: &mut IntegrityReport,
+    ) -> Result<()> {
+        let mut embedders = Vec::new();
+        for result in self.index.embedder_category_id.iter(self.wtxn)? {
+            let (name, embedder_index) = result?;
+            embedders.push((name.to_owned(), embedder_index));
+        }
+
+        for (name, embedder_index) in embedders {
+            let writer_index = (embedder_index as u16) << 8;
+            let mut dimensions = None;
+            let mut dangling = RoaringBitmap::new();
+            for k in 0..=u8::MAX {
+                let reader = match arroy::Reader::open(
+                    self.wtxn,
+                    writer_index | (k as u16),
+                    self.index.vector_arroy,
+                ) {
+                    Ok(reader) => reader,
+                    Err(arroy::Error::MissingMetadata) => break,
+                    Err(e) => return Err(e.into()),
+                };
+                dimensions = Some(reader.dimensions());
+                for result in reader.iter(self.wtxn)? {
+                    let (docid, _vector) = result?;
+                    if !documents.contains(docid) && dangling.insert(docid) {
+                        report.record(IntegrityViolation::DanglingVector, || {
+                            format!("the `{name}` embedder has a vector for the missing document {docid}")
+                        });
+                    }
+                }
+            }
+
+            let Some(dimensions) = dimensions.filter(|_| self.repair && !dangling.is_empty())
+            else {
+                continue;
+            };
+            let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+            for k in 0..=u8::MAX {
+                let writer = arroy::Writer::new(
+                    self.index.vector_arroy,
+                    writer_index | (k as u16),
+                    dimensions,
+                )?;
+                if writer.is_empty(self.wtxn)? {
+                    break;
+                }
+                for docid in &dangling {
+                    writer.del_item(self.wtxn, docid)?;
+                }
+                writer.build(self.wtxn, &mut rng, None)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Removes the documents from all the bitmaps of the database, and the entries left empty.
+fn remove_docids(
+    wtxn: &mut RwTxn,
+    database: Database<Bytes, CboRoaringBitmapCodec>,
+    docids: &RoaringBitmap,
+) -> Result<()> {
+    let mut iter = database.iter_mut(wtxn)?;
+    while let Some(result) = iter.next() {
+        let (key, bitmap) = result?;
+        if bitmap.is_disjoint(docids) {
+            continue;
+        }
+        let key = key.to_vec();
+        let bitmap = bitmap - docids;
+        // safety: the key and the bitmap are owned, they don't borrow the database.
+        unsafe {
+            if bitmap.is_empty() {
+                iter.del_current()?;
+            } else {
+                iter.put_current(&key, &bitmap)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the ids of the fields that have an entry in a facet database.
+fn facet_field_ids(
+    rtxn: &RoTxn,
+    database: Database<FacetGroupKeyCodec<BytesRefCodec>, FacetGroupValueCodec>,
+) -> heed::Result<Vec<FieldId>> {
+    let database = database.remap_types::<Bytes, DecodeIgnore>();
+    let mut field_ids = Vec::new();
+    let mut next_field_id = Some(0u16);
+    while let Some(start) = next_field_id {
+        let start = start.to_be_bytes();
+        let Some(result) = database.range(rtxn, &(start.as_slice()..))?.next() else { break };
+        let (key, ()) = result?;
+        let field_id = u16::from_be_bytes([key[0], key[1]]);
+        field_ids.push(field_id);
+        next_field_id = field_id.checked_add(1);
+    }
+    Ok(field_ids)
+}
+
+/// Compares the groups of a level to the union of their children, calls `on_violation`
+/// with the left bound of the groups that don't match, and returns the groups recomputed
+/// from their children when the number of children is right.
+fn check_facet_level(
+    rtxn: &RoTxn,
+    database: Database<FacetGroupKeyCodec<BytesRefCodec>, FacetGroupValueCodec>,
+    field_id: FieldId,
+    level: u8,
+    mut on_violation: impl FnMut(&[u8]),
+) -> heed::Result<Vec<(Vec<u8>, FacetGroupValue)>> {
+    let mut prefix = field_id.to_be_bytes().to_vec();
+    prefix.push(level);
+
+    let mut repairs = Vec::new();
+    for result in database.remap_key_type::<Bytes>().prefix_iter(rtxn, &prefix)? {
+        let (key, group) = result?;
+        let left_bound = &key[prefix.len()..];
+        let first_child = FacetGroupKey { field_id, level: level - 1, left_bound };
+
+        let mut size = 0;
+        let mut bitmap = RoaringBitmap::new();
+        for result in database.range(rtxn, &(first_child..))?.take(group.size as usize) {
+            let (child_key, child) = result?;
+            if child_key.field_id != field_id || child_key.level != level - 1 {
+                break;
+            }
+            size += 1;
+            bitmap |= child.bitmap;
+        }
+
+        if size != group.size || bitmap != group.bitmap {
+            on_violation(left_bound);
+            if size == group.size {
+                repairs.push((key.to_vec(), FacetGroupValue { size, bitmap }));
+            }
+        }
+    }
+
+    Ok(repairs)
+}
+
+fn field_name(fields_ids_map: &FieldsIdsMap, field_id: FieldId) -> String {
+    match fields_ids_map.name(field_id) {
+        Some(name) => name.to_owned(),
+        None => field_id.to_string(),
+    }
+}
+
+/// Displays the first documents ids of a bitmap, e.g. `[1, 2, 3, ...]`.
+fn display_docids(docids: &RoaringBitmap) -> String {
+    let mut ids: Vec<_> =
+        docids.iter().take(10).map(|docid: DocumentId| docid.to_string()).collect();
+    if docids.len() > 10 {
+        ids.push(String::from("..."));
+    }
+    format!("[{}]", ids.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use big_s::S;
+
+    use super::*;
+    use crate::index::tests::TempIndex;
+    use crate::SearchBuilder;
+
+    #[test]
+    fn missing_document() {
+        let index = TempIndex::new();
+        index
+            .add_documents(documents!([
+                { "id": 0, "name": "kevin" },
+                { "id": 1, "name": "kevina" },
+                { "id": 2, "name": "benoit" },
+            ]))
+            .unwrap();
+
+        // the document is removed, but not its entries in the other databases
+        let mut wtxn = index.write_txn().unwrap();
+        let docid = index.external_documents_ids().get(&wtxn, "1").unwrap().unwrap();
+        index.documents.delete(&mut wtxn, &docid).unwrap();
+        wtxn.commit().unwrap();
+
+        let mut wtxn = index.write_txn().unwrap();
+        let report = IntegrityCheck::new(&mut wtxn, &index, false).execute().unwrap();
+        wtxn.commit().unwrap();
+        assert_eq!(
+            report.violations.keys().copied().collect::<Vec<_>>(),
+            [
+                IntegrityViolation::DanglingDocumentsIds,
+                IntegrityViolation::DanglingWordDocids,
+                IntegrityViolation::DanglingExternalId,
+            ]
+        );
+        assert_eq!(
+            report.violations[&IntegrityViolation::DanglingWordDocids],
+            IntegrityViolationReport {
+                count: 2,
+                examples: vec![
+                    S("the word `1` references the missing documents [1]"),
+                    S("the word `kevina` references the missing documents [1]"),
+                ],
+            }
+        );
+        assert_eq!(
+            report.violations[&IntegrityViolation::DanglingExternalId].examples,
+            [S("the external id `1` references the missing document 1")]
+        );
+
+        // without the repair, nothing is modified
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.number_of_documents(&rtxn).unwrap(), 3);
+        let hits = SearchBuilder::new(&index).query("kevina").execute(&rtxn).unwrap();
+        assert_eq!(hits.candidates.len(), 1);
+        drop(rtxn);
+
+        let mut wtxn = index.write_txn().unwrap();
+        let report = IntegrityCheck::new(&mut wtxn, &index, true).execute().unwrap();
+        wtxn.commit().unwrap();
+        assert_eq!(report.violations.len(), 3);
+
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.number_of_documents(&rtxn).unwrap(), 2);
+        assert_eq!(index.external_documents_ids().get(&rtxn, "1").unwrap(), None);
+        let hits = SearchBuilder::new(&index).query("kevina").execute(&rtxn).unwrap();
+        assert!(hits.candidates.is_empty());
+        let hits = SearchBuilder::new(&index).query("kevin").execute(&rtxn).unwrap();
+        assert_eq!(hits.documents_ids, [0]);
+        assert_eq!(index.word_docids.get(&rtxn, "kevina").unwrap(), None);
+        assert_eq!(index.word_docids.get(&rtxn, "kevin").unwrap(), Some(RoaringBitmap::from([0])));
+        drop(rtxn);
+
+        let mut wtxn = index.write_txn().unwrap();
+        let report = IntegrityCheck::new(&mut wtxn, &index, false).execute().unwrap();
+        assert!(report.is_empty(), "{report:?}");
+    }
+
+    #[test]
+    fn corrupted_facet_group() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(["price".to_string()].into_iter().collect());
+            })
+            .unwrap();
+        let documents: Vec<_> =
+            (0..200).map(|id| serde_json::json!({ "id": id, "price": id })).collect();
+        index.add_documents(documents!(documents)).unwrap();
+
+        let mut wtxn = index.write_txn().unwrap();
+        let report = IntegrityCheck::new(&mut wtxn, &index, false).execute().unwrap();
+        assert!(report.is_empty(), "{report:?}");
+
+        // a document that doesn't have this price is added to a group of the first level
+        let database =
+            index.facet_id_f64_docids.remap_key_type::<FacetGroupKeyCodec<BytesRefCodec>>();
+        let fid = index.fields_ids_map(&wtxn).unwrap().id("price").unwrap();
+        let mut first_level = fid.to_be_bytes().to_vec();
+        first_level.push(1);
+        let (key, mut group) = database
+            .remap_key_type::<Bytes>()
+            .prefix_iter(&wtxn, &first_level)
+            .unwrap()
+            .next()
+            .unwrap()
+            .map(|(key, group)| (key.to_vec(), group))
+            .unwrap();
+        group.bitmap.insert(150);
+        database.remap_key_type::<Bytes>().put(&mut wtxn, &key, &group).unwrap();
+
+        let report = IntegrityCheck::new(&mut wtxn, &index, false).execute().unwrap();
+        let violations = &report.violations[&IntegrityViolation::UncoveredFacetGroup];
+        assert!(violations.count >= 1);
+        assert!(violations.examples[0].contains("the facet group of level 1"));
+
+        let report = IntegrityCheck::new(&mut wtxn, &index, true).execute().unwrap();
+        assert!(!report.is_empty());
+        let report = IntegrityCheck::new(&mut wtxn, &index, false).execute().unwrap();
+        assert!(report.is_empty(), "{report:?}");
+        wtxn.commit().unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let hits = SearchBuilder::new(&index).filter("price < 10").execute(&rtxn).unwrap();
+        assert_eq!(hits.candidates, (0..10).collect::<RoaringBitmap>());
+    }
+}
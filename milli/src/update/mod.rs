@@ -2,12 +2,23 @@ pub use self::available_documents_ids::AvailableDocumentsIds;
 pub use self::clear_documents::ClearDocuments;
 pub use self::facet::bulk::FacetsUpdateBulk;
 pub use self::facet::incremental::FacetsUpdateIncrementalInner;
+pub use self::facet::FacetUpdateStrategy;
+pub(crate) use self::index_documents::json_to_string;
 pub use self::index_documents::{
     merge_cbo_roaring_bitmaps, merge_roaring_bitmaps, DocumentAdditionResult, DocumentId,
-    IndexDocuments, IndexDocumentsConfig, IndexDocumentsMethod, MergeFn,
+    IndexDocuments, IndexDocumentsConfig, IndexDocumentsMethod, InvalidDocuments, MergeFn,
+    PayloadAdditionResult, MAX_DOCUMENT_ERROR_SAMPLES,
 };
+pub use self::indexer_builder::IndexerBuilder;
 pub use self::indexer_config::IndexerConfig;
-pub use self::settings::{validate_embedding_settings, Setting, Settings};
+pub use self::integrity_check::{
+    IntegrityCheck, IntegrityReport, IntegrityViolation, IntegrityViolationReport,
+    MAX_INTEGRITY_VIOLATION_EXAMPLES,
+};
+pub(crate) use self::settings::normalize_synonyms;
+pub use self::settings::{
+    validate_embedding_settings, validate_search_defaults, Setting, Settings,
+};
 pub use self::update_step::UpdateIndexingStep;
 pub use self::word_prefix_docids::WordPrefixDocids;
 pub use self::words_prefix_integer_docids::WordPrefixIntegerDocids;
@@ -18,7 +29,9 @@ mod clear_documents;
 pub(crate) mod del_add;
 pub(crate) mod facet;
 mod index_documents;
+mod indexer_builder;
 mod indexer_config;
+mod integrity_check;
 mod settings;
 mod update_step;
 mod word_prefix_docids;
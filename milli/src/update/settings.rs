@@ -1,3 +1,4 @@
+use std::collections::hash_map::Entry;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::convert::TryInto;
 use std::result::Result as StdResult;
@@ -6,21 +7,26 @@ use std::sync::Arc;
 use charabia::{Normalize, Tokenizer, TokenizerBuilder};
 use deserr::{DeserializeError, Deserr};
 use itertools::{EitherOrBoth, Itertools};
+use roaring::RoaringBitmap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use time::OffsetDateTime;
 
 use super::index_documents::{IndexDocumentsConfig, Transform};
 use super::IndexerConfig;
+use crate::collation::Locale;
 use crate::criterion::Criterion;
+use crate::documents::PrimaryKey;
 use crate::error::UserError;
+use crate::external_documents_ids::{DocumentOperation, DocumentOperationKind};
+use crate::facet::FacetTypeHint;
 use crate::index::{DEFAULT_MIN_WORD_LEN_ONE_TYPO, DEFAULT_MIN_WORD_LEN_TWO_TYPOS};
 use crate::order_by_map::OrderByMap;
 use crate::proximity::ProximityPrecision;
 use crate::update::index_documents::IndexDocumentsMethod;
 use crate::update::{IndexDocuments, UpdateIndexingStep};
 use crate::vector::settings::{check_set, check_unset, EmbedderSource, EmbeddingSettings};
-use crate::vector::{Embedder, EmbeddingConfig, EmbeddingConfigs};
-use crate::{FieldsIdsMap, Index, Result};
+use crate::vector::{Embedder, EmbedderOptions, EmbeddingConfig, EmbeddingConfigs};
+use crate::{FieldsIdsMap, Index, LocalizedAttributesRule, Result};
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub enum Setting<T> {
@@ -121,6 +127,52 @@ impl<'de, T: Deserialize<'de>> Deserialize<'de> for Setting<T> {
     }
 }
 
+/// Normalizes the words and synonyms defined by the user, the way they are tokenized
+/// at search time, and merges the synonyms of the words that normalize the same.
+pub(crate) fn normalize_synonyms(
+    tokenizer: &Tokenizer,
+    user_synonyms: &BTreeMap<String, Vec<String>>,
+) -> HashMap<Vec<String>, Vec<Vec<String>>> {
+    fn normalize(tokenizer: &Tokenizer, text: &str) -> Vec<String> {
+        tokenizer
+            .tokenize(text)
+            .filter_map(|token| {
+                if token.is_word() && !token.lemma().is_empty() {
+                    Some(token.lemma().to_string())
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+    }
+
+    let mut new_synonyms = HashMap::new();
+    for (word, synonyms) in user_synonyms {
+        // Normalize both the word and associated synonyms.
+        let normalized_word = normalize(tokenizer, word);
+        let normalized_synonyms: Vec<_> = synonyms
+            .iter()
+            .map(|synonym| normalize(tokenizer, synonym))
+            .filter(|synonym| !synonym.is_empty())
+            .collect();
+
+        // Store the normalized synonyms under the normalized word,
+        // merging the possible duplicate words.
+        if !normalized_word.is_empty() && !normalized_synonyms.is_empty() {
+            let entry = new_synonyms.entry(normalized_word).or_insert_with(Vec::new);
+            entry.extend(normalized_synonyms.into_iter());
+        }
+    }
+
+    // Make sure that we don't have duplicate synonyms.
+    new_synonyms.iter_mut().for_each(|(_, synonyms)| {
+        synonyms.sort_unstable();
+        synonyms.dedup();
+    });
+
+    new_synonyms
+}
+
 pub struct Settings<'a, 't, 'i> {
     wtxn: &'t mut heed::RwTxn<'i>,
     index: &'i Index,
@@ -132,6 +184,7 @@ pub struct Settings<'a, 't, 'i> {
     filterable_fields: Setting<HashSet<String>>,
     sortable_fields: Setting<HashSet<String>>,
     criteria: Setting<Vec<Criterion>>,
+    criteria_max_buckets: Setting<BTreeMap<String, usize>>,
     stop_words: Setting<BTreeSet<String>>,
     non_separator_tokens: Setting<BTreeSet<String>>,
     separator_tokens: Setting<BTreeSet<String>>,
@@ -151,6 +204,19 @@ pub struct Settings<'a, 't, 'i> {
     proximity_precision: Setting<ProximityPrecision>,
     embedder_settings: Setting<BTreeMap<String, Setting<EmbeddingSettings>>>,
     search_cutoff: Setting<u64>,
+    search_defaults: Setting<serde_json::Value>,
+    min_numeric_prefix_length: Setting<u8>,
+    allow_underscore_fields: Setting<bool>,
+    localized_attributes_rules: Setting<Vec<LocalizedAttributesRule>>,
+    number_normalization: Setting<bool>,
+    phrase_across_fields: Setting<bool>,
+    strict_attributes: Setting<BTreeSet<String>>,
+    decompounding_locales: Setting<BTreeSet<Locale>>,
+    facet_types: Setting<BTreeMap<String, FacetTypeHint>>,
+    stemming_locales: Setting<BTreeSet<Locale>>,
+    search_presets: Setting<BTreeMap<String, Setting<serde_json::Value>>>,
+    /// The presets must be cleared before applying `search_presets`.
+    clear_search_presets: bool,
 }
 
 impl<'a, 't, 'i> Settings<'a, 't, 'i> {
@@ -167,6 +233,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             filterable_fields: Setting::NotSet,
             sortable_fields: Setting::NotSet,
             criteria: Setting::NotSet,
+            criteria_max_buckets: Setting::NotSet,
             stop_words: Setting::NotSet,
             non_separator_tokens: Setting::NotSet,
             separator_tokens: Setting::NotSet,
@@ -185,6 +252,18 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             proximity_precision: Setting::NotSet,
             embedder_settings: Setting::NotSet,
             search_cutoff: Setting::NotSet,
+            search_defaults: Setting::NotSet,
+            min_numeric_prefix_length: Setting::NotSet,
+            allow_underscore_fields: Setting::NotSet,
+            localized_attributes_rules: Setting::NotSet,
+            number_normalization: Setting::NotSet,
+            phrase_across_fields: Setting::NotSet,
+            strict_attributes: Setting::NotSet,
+            decompounding_locales: Setting::NotSet,
+            facet_types: Setting::NotSet,
+            stemming_locales: Setting::NotSet,
+            search_presets: Setting::NotSet,
+            clear_search_presets: false,
             indexer_config,
         }
     }
@@ -229,6 +308,16 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.criteria = Setting::Set(criteria);
     }
 
+    pub fn reset_criteria_max_buckets(&mut self) {
+        self.criteria_max_buckets = Setting::Reset;
+    }
+
+    /// Caps the number of buckets of the ranking rules created from the criteria,
+    /// by the criteria as written by their `Display` implementation.
+    pub fn set_criteria_max_buckets(&mut self, max_buckets: BTreeMap<String, usize>) {
+        self.criteria_max_buckets = Setting::Set(max_buckets);
+    }
+
     pub fn reset_stop_words(&mut self) {
         self.stop_words = Setting::Reset;
     }
@@ -383,6 +472,105 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         self.search_cutoff = Setting::Reset;
     }
 
+    pub fn set_search_defaults(&mut self, value: serde_json::Value) {
+        self.search_defaults = Setting::Set(value);
+    }
+
+    pub fn reset_search_defaults(&mut self) {
+        self.search_defaults = Setting::Reset;
+    }
+
+    pub fn set_min_numeric_prefix_length(&mut self, value: u8) {
+        self.min_numeric_prefix_length = Setting::Set(value);
+    }
+
+    pub fn reset_min_numeric_prefix_length(&mut self) {
+        self.min_numeric_prefix_length = Setting::Reset;
+    }
+
+    pub fn set_allow_underscore_fields(&mut self, value: bool) {
+        self.allow_underscore_fields = Setting::Set(value);
+    }
+
+    pub fn reset_allow_underscore_fields(&mut self) {
+        self.allow_underscore_fields = Setting::Reset;
+    }
+
+    pub fn set_localized_attributes_rules(&mut self, value: Vec<LocalizedAttributesRule>) {
+        self.localized_attributes_rules = Setting::Set(value);
+    }
+
+    pub fn reset_localized_attributes_rules(&mut self) {
+        self.localized_attributes_rules = Setting::Reset;
+    }
+
+    pub fn set_number_normalization(&mut self, value: bool) {
+        self.number_normalization = Setting::Set(value);
+    }
+
+    pub fn reset_number_normalization(&mut self) {
+        self.number_normalization = Setting::Reset;
+    }
+
+    pub fn set_phrase_across_fields(&mut self, value: bool) {
+        self.phrase_across_fields = Setting::Set(value);
+    }
+
+    pub fn reset_phrase_across_fields(&mut self) {
+        self.phrase_across_fields = Setting::Reset;
+    }
+
+    pub fn set_decompounding_locales(&mut self, locales: BTreeSet<Locale>) {
+        self.decompounding_locales = Setting::Set(locales);
+    }
+
+    pub fn reset_decompounding_locales(&mut self) {
+        self.decompounding_locales = Setting::Reset;
+    }
+
+    pub fn set_facet_types(&mut self, facet_types: BTreeMap<String, FacetTypeHint>) {
+        self.facet_types = Setting::Set(facet_types);
+    }
+
+    pub fn reset_facet_types(&mut self) {
+        self.facet_types = Setting::Reset;
+    }
+
+    pub fn set_stemming_locales(&mut self, locales: BTreeSet<Locale>) {
+        self.stemming_locales = Setting::Set(locales);
+    }
+
+    pub fn reset_stemming_locales(&mut self) {
+        self.stemming_locales = Setting::Reset;
+    }
+
+    pub fn set_strict_attributes(&mut self, attrs: BTreeSet<String>) {
+        self.strict_attributes = Setting::Set(attrs);
+    }
+
+    pub fn reset_strict_attributes(&mut self) {
+        self.strict_attributes = Setting::Reset;
+    }
+
+    /// Sets or removes, with `Setting::Reset`, some of the search presets, the others are kept.
+    ///
+    /// The updates are merged with the ones already applied to this builder.
+    pub fn set_search_presets(&mut self, value: BTreeMap<String, Setting<serde_json::Value>>) {
+        match &mut self.search_presets {
+            Setting::Set(presets) => presets.extend(value),
+            Setting::Reset => {
+                self.clear_search_presets = true;
+                self.search_presets = Setting::Set(value);
+            }
+            Setting::NotSet => self.search_presets = Setting::Set(value),
+        }
+    }
+
+    pub fn reset_search_presets(&mut self) {
+        self.search_presets = Setting::Reset;
+        self.clear_search_presets = false;
+    }
+
     #[tracing::instrument(
         level = "trace"
         skip(self, progress_callback, should_abort, old_fields_ids_map),
@@ -452,7 +640,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                 let prompt = Arc::new(prompt.try_into().map_err(crate::Error::from)?);
 
                 let embedder = Arc::new(
-                    Embedder::new(embedder_options.clone())
+                    Embedder::new(embedder_options.clone(), &self.indexer_config.outbound_policy)
                         .map_err(crate::vector::Error::from)
                         .map_err(crate::Error::from)?,
                 );
@@ -650,19 +838,6 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
     fn update_synonyms(&mut self) -> Result<bool> {
         match self.synonyms {
             Setting::Set(ref user_synonyms) => {
-                fn normalize(tokenizer: &Tokenizer, text: &str) -> Vec<String> {
-                    tokenizer
-                        .tokenize(text)
-                        .filter_map(|token| {
-                            if token.is_word() && !token.lemma().is_empty() {
-                                Some(token.lemma().to_string())
-                            } else {
-                                None
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                }
-
                 let mut builder = TokenizerBuilder::new();
                 let stop_words = self.index.stop_words(self.wtxn)?;
                 if let Some(ref stop_words) = stop_words {
@@ -685,29 +860,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
 
                 let tokenizer = builder.build();
 
-                let mut new_synonyms = HashMap::new();
-                for (word, synonyms) in user_synonyms {
-                    // Normalize both the word and associated synonyms.
-                    let normalized_word = normalize(&tokenizer, word);
-                    let normalized_synonyms: Vec<_> = synonyms
-                        .iter()
-                        .map(|synonym| normalize(&tokenizer, synonym))
-                        .filter(|synonym| !synonym.is_empty())
-                        .collect();
-
-                    // Store the normalized synonyms under the normalized word,
-                    // merging the possible duplicate words.
-                    if !normalized_word.is_empty() && !normalized_synonyms.is_empty() {
-                        let entry = new_synonyms.entry(normalized_word).or_insert_with(Vec::new);
-                        entry.extend(normalized_synonyms.into_iter());
-                    }
-                }
-
-                // Make sure that we don't have duplicate synonyms.
-                new_synonyms.iter_mut().for_each(|(_, synonyms)| {
-                    synonyms.sort_unstable();
-                    synonyms.dedup();
-                });
+                let new_synonyms = normalize_synonyms(&tokenizer, user_synonyms);
 
                 let old_synonyms = self.index.synonyms(self.wtxn)?;
 
@@ -742,6 +895,25 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         }
     }
 
+    fn update_strict_attributes(&mut self) -> Result<bool> {
+        match self.strict_attributes {
+            Setting::Set(ref attrs) => {
+                let old_attrs = self.index.strict_attributes(self.wtxn)?;
+                let old_attrs = old_attrs.into_iter().map(String::from).collect::<BTreeSet<_>>();
+
+                if attrs != &old_attrs {
+                    let attrs = attrs.iter().map(String::as_str).collect::<Vec<_>>();
+                    self.index.put_strict_attributes(self.wtxn, &attrs)?;
+                    Ok(true)
+                } else {
+                    Ok(false)
+                }
+            }
+            Setting::Reset => Ok(self.index.delete_strict_attributes(self.wtxn)?),
+            Setting::NotSet => Ok(false),
+        }
+    }
+
     fn update_filterable(&mut self) -> Result<()> {
         match self.filterable_fields {
             Setting::Set(ref fields) => {
@@ -786,6 +958,15 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             }
             Setting::NotSet => (),
         }
+        match &self.criteria_max_buckets {
+            Setting::Set(max_buckets) if !max_buckets.is_empty() => {
+                self.index.put_criteria_max_buckets(self.wtxn, max_buckets)?;
+            }
+            Setting::Set(_) | Setting::Reset => {
+                self.index.delete_criteria_max_buckets(self.wtxn)?;
+            }
+            Setting::NotSet => (),
+        }
         Ok(())
     }
 
@@ -803,7 +984,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                     if primary_key == &curr_primary_key {
                         Ok(())
                     } else {
-                        Err(UserError::PrimaryKeyCannotBeChanged(curr_primary_key).into())
+                        change_primary_key(self.wtxn, self.index, primary_key)
                     }
                 }
             }
@@ -969,6 +1150,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                     old_configs.into_iter().map(|(k, v)| (k, Setting::Set(v.into()))).collect();
 
                 let mut new_configs = BTreeMap::new();
+                let mut updated_embedders = HashSet::new();
                 for joined in old_configs
                     .into_iter()
                     .merge_join_by(configs.into_iter(), |(left, _), (right, _)| left.cmp(right))
@@ -983,6 +1165,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                                 tracing::debug!(embedder = name, "skip reindex");
                             }
                             let new = validate_embedding_settings(old, &name)?;
+                            updated_embedders.insert(name.clone());
                             new_configs.insert(name, new);
                         }
                         // unchanged config
@@ -1000,6 +1183,7 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                             );
                             let setting = validate_embedding_settings(setting, &name)?;
                             changed = true;
+                            updated_embedders.insert(name.clone());
                             new_configs.insert(name, setting);
                         }
                     }
@@ -1013,6 +1197,21 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
                     })
                     .collect();
 
+                // Load the local models right away so that a model that cannot be loaded fails
+                // the update, even when there are no documents to embed.
+                for (name, config) in &new_configs {
+                    if updated_embedders.contains(name)
+                        && matches!(config.embedder_options, EmbedderOptions::HuggingFace(_))
+                    {
+                        Embedder::new(
+                            config.embedder_options.clone(),
+                            &self.indexer_config.outbound_policy,
+                        )
+                        .map_err(crate::vector::Error::from)
+                        .map_err(crate::Error::from)?;
+                    }
+                }
+
                 self.index.embedder_category_id.clear(self.wtxn)?;
                 for (index, (embedder_name, _)) in new_configs.iter().enumerate() {
                     self.index.embedder_category_id.put_with_flags(
@@ -1059,6 +1258,203 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         Ok(changed)
     }
 
+    fn update_search_defaults(&mut self) -> Result<bool> {
+        let changed = match self.search_defaults {
+            Setting::Set(ref new) => {
+                validate_search_defaults(new)?;
+                let old = self.index.search_defaults(self.wtxn)?;
+                if old.as_ref() == Some(new) {
+                    false
+                } else {
+                    self.index.put_search_defaults(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_search_defaults(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    fn update_min_numeric_prefix_length(&mut self) -> Result<bool> {
+        let changed = match self.min_numeric_prefix_length {
+            Setting::Set(new) => {
+                let old = self.index.min_numeric_prefix_length(self.wtxn)?;
+                if old == new {
+                    false
+                } else {
+                    self.index.put_min_numeric_prefix_length(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_min_numeric_prefix_length(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    fn update_allow_underscore_fields(&mut self) -> Result<bool> {
+        let changed = match self.allow_underscore_fields {
+            Setting::Set(new) => {
+                let old = self.index.allow_underscore_fields(self.wtxn)?;
+                if old == new {
+                    false
+                } else {
+                    self.index.put_allow_underscore_fields(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_allow_underscore_fields(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    fn update_localized_attributes_rules(&mut self) -> Result<bool> {
+        let changed = match &self.localized_attributes_rules {
+            Setting::Set(new) => {
+                let old = self.index.localized_attributes_rules(self.wtxn)?;
+                if old.as_ref() == Some(new) {
+                    false
+                } else {
+                    self.index.put_localized_attributes_rules(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_localized_attributes_rules(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    fn update_number_normalization(&mut self) -> Result<bool> {
+        let changed = match self.number_normalization {
+            Setting::Set(new) => {
+                let old = self.index.number_normalization(self.wtxn)?;
+                if old == new {
+                    false
+                } else {
+                    self.index.put_number_normalization(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_number_normalization(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    fn update_phrase_across_fields(&mut self) -> Result<bool> {
+        let changed = match self.phrase_across_fields {
+            Setting::Set(new) => {
+                let old = self.index.phrase_across_fields(self.wtxn)?;
+                if old == new {
+                    false
+                } else {
+                    self.index.put_phrase_across_fields(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_phrase_across_fields(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    fn update_decompounding_locales(&mut self) -> Result<bool> {
+        let changed = match &self.decompounding_locales {
+            Setting::Set(new) => {
+                let old = self.index.decompounding_locales(self.wtxn)?;
+                if old == *new {
+                    false
+                } else {
+                    self.index.put_decompounding_locales(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_decompounding_locales(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    fn update_facet_types(&mut self) -> Result<bool> {
+        let changed = match &self.facet_types {
+            Setting::Set(new) => {
+                let old = self.index.facet_types(self.wtxn)?;
+                if old == *new {
+                    false
+                } else {
+                    self.index.put_facet_types(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_facet_types(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    fn update_stemming_locales(&mut self) -> Result<bool> {
+        let changed = match &self.stemming_locales {
+            Setting::Set(new) => {
+                let old = self.index.stemming_locales(self.wtxn)?;
+                if old == *new {
+                    false
+                } else {
+                    self.index.put_stemming_locales(self.wtxn, new)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_stemming_locales(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
+    fn update_search_presets(&mut self) -> Result<bool> {
+        let changed = match &self.search_presets {
+            Setting::Set(updates) => {
+                let old = self.index.search_presets(self.wtxn)?;
+                let mut presets =
+                    if self.clear_search_presets { BTreeMap::new() } else { old.clone() };
+                for (name, update) in updates {
+                    match update {
+                        Setting::Set(preset) => {
+                            presets.insert(name.clone(), preset.clone());
+                        }
+                        Setting::Reset => {
+                            presets.remove(name);
+                        }
+                        Setting::NotSet => (),
+                    }
+                }
+
+                if presets == old {
+                    false
+                } else if presets.is_empty() {
+                    self.index.delete_search_presets(self.wtxn)?
+                } else {
+                    self.index.put_search_presets(self.wtxn, &presets)?;
+                    true
+                }
+            }
+            Setting::Reset => self.index.delete_search_presets(self.wtxn)?,
+            Setting::NotSet => false,
+        };
+
+        Ok(changed)
+    }
+
     pub fn execute<FP, FA>(mut self, progress_callback: FP, should_abort: FA) -> Result<()>
     where
         FP: Fn(UpdateIndexingStep) + Sync,
@@ -1098,7 +1494,16 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
         let synonyms_updated = self.update_synonyms()?;
         let searchable_updated = self.update_searchable()?;
         let exact_attributes_updated = self.update_exact_attributes()?;
+        let strict_attributes_updated = self.update_strict_attributes()?;
         let proximity_precision = self.update_proximity_precision()?;
+        let number_normalization_updated = self.update_number_normalization()?;
+        // the dates are indexed as facet numbers
+        let facet_types_updated = self.update_facet_types()?;
+        let stemming_locales_updated = self.update_stemming_locales()?;
+        // the localized attributes are stemmed in the locales of their rule
+        let localized_attributes_rules_updated = self.update_localized_attributes_rules()?;
+        let stemmed_localized_attributes_updated = localized_attributes_rules_updated
+            && !self.index.stemming_locales(self.wtxn)?.is_empty();
         // TODO: very rough approximation of the needs for reindexing where any change will result in
         // a full reindexing.
         // What can be done instead:
@@ -1109,6 +1514,14 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
 
         // never trigger re-indexing
         self.update_search_cutoff()?;
+        self.update_search_defaults()?;
+        self.update_min_numeric_prefix_length()?;
+        self.update_allow_underscore_fields()?;
+        // the pairs spanning two fields are computed at search time from the position databases
+        self.update_phrase_across_fields()?;
+        // the compound words are only split in the queries
+        self.update_decompounding_locales()?;
+        self.update_search_presets()?;
 
         if stop_words_updated
             || non_separator_tokens_updated
@@ -1118,7 +1531,12 @@ impl<'a, 't, 'i> Settings<'a, 't, 'i> {
             || synonyms_updated
             || searchable_updated
             || exact_attributes_updated
+            || strict_attributes_updated
             || proximity_precision
+            || number_normalization_updated
+            || facet_types_updated
+            || stemming_locales_updated
+            || stemmed_localized_attributes_updated
             || embedding_configs_updated
         {
             self.reindex(&progress_callback, &should_abort, old_fields_ids_map)?;
@@ -1165,6 +1583,8 @@ fn validate_prompt(
             source,
             model,
             revision,
+            cache_dir,
+            pooling,
             api_key,
             dimensions,
             document_template: Setting::Set(template),
@@ -1185,6 +1605,8 @@ fn validate_prompt(
                 source,
                 model,
                 revision,
+                cache_dir,
+                pooling,
                 api_key,
                 dimensions,
                 document_template: Setting::Set(template),
@@ -1201,6 +1623,21 @@ fn validate_prompt(
     }
 }
 
+/// Checks the shape of the `searchDefaults` setting, the search parameters it contains are
+/// checked by the search engine before the settings are sent to the index.
+pub fn validate_search_defaults(defaults: &serde_json::Value) -> Result<()> {
+    let Some(defaults) = defaults.as_object() else {
+        return Err(UserError::InvalidSearchDefaults("expected an object.".to_string()).into());
+    };
+    if defaults.contains_key("preset") {
+        return Err(UserError::InvalidSearchDefaults(
+            "stored search parameters cannot reference a preset.".to_string(),
+        )
+        .into());
+    }
+    Ok(())
+}
+
 pub fn validate_embedding_settings(
     settings: Setting<EmbeddingSettings>,
     name: &str,
@@ -1211,6 +1648,8 @@ pub fn validate_embedding_settings(
         source,
         model,
         revision,
+        cache_dir,
+        pooling,
         api_key,
         dimensions,
         document_template,
@@ -1243,6 +1682,8 @@ pub fn validate_embedding_settings(
             source,
             model,
             revision,
+            cache_dir,
+            pooling,
             api_key,
             dimensions,
             document_template,
@@ -1258,6 +1699,8 @@ pub fn validate_embedding_settings(
     match inferred_source {
         EmbedderSource::OpenAi => {
             check_unset(&revision, EmbeddingSettings::REVISION, inferred_source, name)?;
+            check_unset(&cache_dir, EmbeddingSettings::CACHE_DIR, inferred_source, name)?;
+            check_unset(&pooling, EmbeddingSettings::POOLING, inferred_source, name)?;
 
             check_unset(&url, EmbeddingSettings::URL, inferred_source, name)?;
             check_unset(&query, EmbeddingSettings::QUERY, inferred_source, name)?;
@@ -1311,6 +1754,8 @@ pub fn validate_embedding_settings(
             check_unset(&dimensions, EmbeddingSettings::DIMENSIONS, inferred_source, name)?;
             check_set(&model, EmbeddingSettings::MODEL, inferred_source, name)?;
             check_unset(&revision, EmbeddingSettings::REVISION, inferred_source, name)?;
+            check_unset(&cache_dir, EmbeddingSettings::CACHE_DIR, inferred_source, name)?;
+            check_unset(&pooling, EmbeddingSettings::POOLING, inferred_source, name)?;
 
             check_unset(&query, EmbeddingSettings::QUERY, inferred_source, name)?;
             check_unset(&input_field, EmbeddingSettings::INPUT_FIELD, inferred_source, name)?;
@@ -1352,6 +1797,8 @@ pub fn validate_embedding_settings(
         EmbedderSource::UserProvided => {
             check_unset(&model, EmbeddingSettings::MODEL, inferred_source, name)?;
             check_unset(&revision, EmbeddingSettings::REVISION, inferred_source, name)?;
+            check_unset(&cache_dir, EmbeddingSettings::CACHE_DIR, inferred_source, name)?;
+            check_unset(&pooling, EmbeddingSettings::POOLING, inferred_source, name)?;
             check_unset(&api_key, EmbeddingSettings::API_KEY, inferred_source, name)?;
             check_unset(
                 &document_template,
@@ -1381,6 +1828,8 @@ pub fn validate_embedding_settings(
         EmbedderSource::Rest => {
             check_unset(&model, EmbeddingSettings::MODEL, inferred_source, name)?;
             check_unset(&revision, EmbeddingSettings::REVISION, inferred_source, name)?;
+            check_unset(&cache_dir, EmbeddingSettings::CACHE_DIR, inferred_source, name)?;
+            check_unset(&pooling, EmbeddingSettings::POOLING, inferred_source, name)?;
             check_set(&url, EmbeddingSettings::URL, inferred_source, name)?;
         }
     }
@@ -1388,6 +1837,8 @@ pub fn validate_embedding_settings(
         source,
         model,
         revision,
+        cache_dir,
+        pooling,
         api_key,
         dimensions,
         document_template,
@@ -1401,6 +1852,80 @@ pub fn validate_embedding_settings(
     }))
 }
 
+/// The maximum number of documents listed in the errors of a primary key change.
+const MAX_LISTED_DOCUMENTS: usize = 10;
+
+/// Changes the primary key of an index that contains documents.
+///
+/// Every document must contain a valid and unique value for the new primary key.
+/// Only the external ids mapping is rebuilt, the documents themselves are not modified.
+fn change_primary_key(wtxn: &mut heed::RwTxn, index: &Index, primary_key: &str) -> Result<()> {
+    let fields_ids_map = index.fields_ids_map(wtxn)?;
+    let new_primary_key = PrimaryKey::new(primary_key, &fields_ids_map);
+
+    let mut external_ids = HashMap::new();
+    let mut missing = RoaringBitmap::new();
+    let mut duplicates = RoaringBitmap::new();
+    for result in index.all_documents(wtxn)? {
+        let (docid, obkv) = result?;
+        let external_id = match new_primary_key {
+            Some(new_primary_key) => {
+                new_primary_key.document_id(&obkv.reader(), &fields_ids_map)?.ok()
+            }
+            // the field doesn't exist in any document
+            None => None,
+        };
+        match external_id {
+            Some(external_id) => match external_ids.entry(external_id) {
+                Entry::Vacant(entry) => {
+                    entry.insert(docid);
+                }
+                Entry::Occupied(entry) => {
+                    duplicates.insert(*entry.get());
+                    duplicates.insert(docid);
+                }
+            },
+            None => {
+                missing.insert(docid);
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        let document_ids =
+            index.external_ids_of(wtxn, missing.into_iter().take(MAX_LISTED_DOCUMENTS))?;
+        return Err(UserError::PrimaryKeyMissingInDocuments {
+            primary_key: primary_key.to_string(),
+            document_ids,
+        }
+        .into());
+    }
+    if !duplicates.is_empty() {
+        let document_ids =
+            index.external_ids_of(wtxn, duplicates.into_iter().take(MAX_LISTED_DOCUMENTS))?;
+        return Err(UserError::PrimaryKeyValueNotUnique {
+            primary_key: primary_key.to_string(),
+            document_ids,
+        }
+        .into());
+    }
+
+    let external_documents_ids = index.external_documents_ids();
+    external_documents_ids.clear(wtxn)?;
+    let operations = external_ids
+        .into_iter()
+        .map(|(external_id, internal_id)| DocumentOperation {
+            external_id,
+            internal_id,
+            kind: DocumentOperationKind::Create,
+        })
+        .collect();
+    external_documents_ids.apply(wtxn, operations)?;
+    index.put_primary_key(wtxn, primary_key)?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use big_s::S;
@@ -1611,8 +2136,12 @@ mod tests {
         let fidmap = index.fields_ids_map(&rtxn).unwrap();
         for document in index.all_documents(&rtxn).unwrap() {
             let document = document.unwrap();
-            let json = crate::obkv_to_json(&fidmap.ids().collect::<Vec<_>>(), &fidmap, document.1)
-                .unwrap();
+            let json = crate::obkv_to_json(
+                &fidmap.ids().collect::<Vec<_>>(),
+                &fidmap,
+                document.1.reader(),
+            )
+            .unwrap();
             println!("json: {:?}", json);
         }
         let count = index
@@ -1753,6 +2282,66 @@ mod tests {
         assert_eq!(documents_ids.len(), 3);
     }
 
+    #[test]
+    fn distinct_field_is_implicitly_faceted() {
+        let index = TempIndex::new();
+
+        // the distinct attribute is neither filterable nor sortable
+        index
+            .update_settings(|settings| {
+                settings.set_primary_key(S("id"));
+                settings.set_distinct_field(S("color"));
+            })
+            .unwrap();
+
+        index
+            .add_documents(documents!([
+                { "id": 0, "color": "red" },
+                { "id": 1, "color": "blue" },
+                { "id": 2, "color": "red" },
+                { "id": 3, "color": "green" },
+                { "id": 4, "color": "blue" },
+            ]))
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        let SearchResult { documents_ids, .. } = index.search(&rtxn).execute().unwrap();
+        assert_eq!(documents_ids, vec![0, 1, 3]);
+        drop(rtxn);
+
+        db_snap!(index, facet_id_string_docids, @r###"
+        1   0  blue         1  [1, 4, ]
+        1   0  green        1  [3, ]
+        1   0  red          1  [0, 2, ]
+        "###);
+
+        // removing the distinct attribute removes its facets
+        index
+            .update_settings(|settings| {
+                settings.reset_distinct_field();
+            })
+            .unwrap();
+        db_snap!(index, facet_id_string_docids, @"");
+
+        // unless the attribute is also filterable
+        index
+            .update_settings(|settings| {
+                settings.set_filterable_fields(hashset! { S("color") });
+                settings.set_distinct_field(S("color"));
+            })
+            .unwrap();
+        index
+            .update_settings(|settings| {
+                settings.reset_distinct_field();
+            })
+            .unwrap();
+        db_snap!(index, facet_id_string_docids, @r###"
+        1   0  blue         1  [1, 4, ]
+        1   0  green        1  [3, ]
+        1   0  red          1  [0, 2, ]
+        "###);
+    }
+
     #[test]
     fn default_stop_words() {
         let mut index = TempIndex::new();
@@ -2068,6 +2657,64 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn changing_primary_key_of_non_empty_index() {
+        let index = TempIndex::new();
+        index
+            .update_settings(|settings| {
+                settings.set_primary_key(S("mykey"));
+            })
+            .unwrap();
+        index
+            .add_documents(documents!([
+                { "mykey": 1, "name": "kevin", "age": 23, "nick": "kev" },
+                { "mykey": 2, "name": "kevina", "age": 21, "nick": "kev" },
+                { "mykey": 3, "name": "benoit", "age": 34 },
+                { "mykey": 4, "name": "bernard", "age": 34, "nick": "ber" }
+            ]))
+            .unwrap();
+
+        // some documents don't have the new primary key
+        let error = index
+            .update_settings(|settings| {
+                settings.set_primary_key(S("nick"));
+            })
+            .unwrap_err();
+        insta::assert_display_snapshot!(error, @"The primary key cannot be changed to `nick` because it is missing or invalid in the following documents: `3`.");
+
+        // some documents share the same value
+        let error = index
+            .update_settings(|settings| {
+                settings.set_primary_key(S("age"));
+            })
+            .unwrap_err();
+        insta::assert_display_snapshot!(error, @"The primary key cannot be changed to `age` because the following documents share the same value for it: `3`, `4`.");
+
+        // the primary key is left untouched by the failed changes
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.primary_key(&rtxn).unwrap(), Some("mykey"));
+        drop(rtxn);
+
+        index
+            .update_settings(|settings| {
+                settings.set_primary_key(S("name"));
+            })
+            .unwrap();
+
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.primary_key(&rtxn).unwrap(), Some("name"));
+        let external_documents_ids = index.external_documents_ids();
+        assert_eq!(external_documents_ids.get(&rtxn, "kevina").unwrap(), Some(1));
+        assert_eq!(external_documents_ids.get(&rtxn, "2").unwrap(), None);
+        assert_eq!(index.number_of_documents(&rtxn).unwrap(), 4);
+        drop(rtxn);
+
+        // the documents are now updated by their new primary key
+        index.add_documents(documents!([{ "name": "kevina", "age": 22 }])).unwrap();
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.number_of_documents(&rtxn).unwrap(), 4);
+    }
+
     #[test]
     fn setting_impact_relevancy() {
         let mut index = TempIndex::new();
@@ -2203,6 +2850,7 @@ mod tests {
                     filterable_fields,
                     sortable_fields,
                     criteria,
+                    criteria_max_buckets,
                     stop_words,
                     non_separator_tokens,
                     separator_tokens,
@@ -2221,12 +2869,25 @@ mod tests {
                     proximity_precision,
                     embedder_settings,
                     search_cutoff,
+                    search_defaults,
+                    min_numeric_prefix_length,
+                    allow_underscore_fields,
+                    localized_attributes_rules,
+                    number_normalization,
+                    phrase_across_fields,
+                    strict_attributes,
+                    decompounding_locales,
+                    facet_types,
+                    stemming_locales,
+                    search_presets,
+                    clear_search_presets: _,
                 } = settings;
                 assert!(matches!(searchable_fields, Setting::NotSet));
                 assert!(matches!(displayed_fields, Setting::NotSet));
                 assert!(matches!(filterable_fields, Setting::NotSet));
                 assert!(matches!(sortable_fields, Setting::NotSet));
                 assert!(matches!(criteria, Setting::NotSet));
+                assert!(matches!(criteria_max_buckets, Setting::NotSet));
                 assert!(matches!(stop_words, Setting::NotSet));
                 assert!(matches!(non_separator_tokens, Setting::NotSet));
                 assert!(matches!(separator_tokens, Setting::NotSet));
@@ -2245,6 +2906,17 @@ mod tests {
                 assert!(matches!(proximity_precision, Setting::NotSet));
                 assert!(matches!(embedder_settings, Setting::NotSet));
                 assert!(matches!(search_cutoff, Setting::NotSet));
+                assert!(matches!(search_defaults, Setting::NotSet));
+                assert!(matches!(min_numeric_prefix_length, Setting::NotSet));
+                assert!(matches!(allow_underscore_fields, Setting::NotSet));
+                assert!(matches!(localized_attributes_rules, Setting::NotSet));
+                assert!(matches!(number_normalization, Setting::NotSet));
+                assert!(matches!(phrase_across_fields, Setting::NotSet));
+                assert!(matches!(strict_attributes, Setting::NotSet));
+                assert!(matches!(decompounding_locales, Setting::NotSet));
+                assert!(matches!(facet_types, Setting::NotSet));
+                assert!(matches!(stemming_locales, Setting::NotSet));
+                assert!(matches!(search_presets, Setting::NotSet));
             })
             .unwrap();
     }
@@ -2276,4 +2948,82 @@ mod tests {
         let docs = docs.unwrap();
         assert_eq!(docs.len(), 5);
     }
+
+    #[test]
+    fn invalid_search_defaults_are_not_persisted() {
+        use serde_json::json;
+
+        let index = TempIndex::new();
+
+        let err = index
+            .update_settings(|settings| settings.set_search_defaults(json!([{ "limit": 10 }])))
+            .unwrap_err();
+        assert!(matches!(err, Error::UserError(UserError::InvalidSearchDefaults(_))), "{err}");
+
+        let err = index
+            .update_settings(|settings| {
+                settings.set_search_defaults(json!({ "limit": 10, "preset": "storefront" }))
+            })
+            .unwrap_err();
+        assert!(matches!(err, Error::UserError(UserError::InvalidSearchDefaults(_))), "{err}");
+
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.search_defaults(&rtxn).unwrap(), None);
+        drop(rtxn);
+
+        index
+            .update_settings(|settings| settings.set_search_defaults(json!({ "limit": 10 })))
+            .unwrap();
+        let rtxn = index.read_txn().unwrap();
+        assert_eq!(index.search_defaults(&rtxn).unwrap(), Some(json!({ "limit": 10 })));
+    }
+
+    #[test]
+    fn search_presets_updates_are_merged() {
+        use serde_json::json;
+
+        let index = TempIndex::new();
+
+        index
+            .update_settings(|settings| {
+                settings.set_search_presets(BTreeMap::from([
+                    (S("storefront"), Setting::Set(json!({ "limit": 10 }))),
+                    (S("backoffice"), Setting::Set(json!({ "limit": 100 }))),
+                ]));
+                settings.set_search_presets(BTreeMap::from([(
+                    S("storefront"),
+                    Setting::Set(json!({ "limit": 5 })),
+                )]));
+            })
+            .unwrap();
+        let rtxn = index.read_txn().unwrap();
+        let presets = index.search_presets(&rtxn).unwrap();
+        assert_eq!(presets.len(), 2);
+        assert_eq!(presets["storefront"], json!({ "limit": 5 }));
+        drop(rtxn);
+
+        index
+            .update_settings(|settings| {
+                settings.set_search_presets(BTreeMap::from([(S("backoffice"), Setting::Reset)]));
+            })
+            .unwrap();
+        let rtxn = index.read_txn().unwrap();
+        let presets = index.search_presets(&rtxn).unwrap();
+        assert_eq!(presets.keys().collect::<Vec<_>>(), vec!["storefront"]);
+        drop(rtxn);
+
+        // a reset followed by an update only keeps the updated presets
+        index
+            .update_settings(|settings| {
+                settings.reset_search_presets();
+                settings.set_search_presets(BTreeMap::from([(
+                    S("backoffice"),
+                    Setting::Set(json!({ "limit": 100 })),
+                )]));
+            })
+            .unwrap();
+        let rtxn = index.read_txn().unwrap();
+        let presets = index.search_presets(&rtxn).unwrap();
+        assert_eq!(presets.keys().collect::<Vec<_>>(), vec!["backoffice"]);
+    }
 }
@@ -10,6 +10,10 @@ pub enum UpdateIndexingStep {
     /// the documents that are already present in the database.
     ComputeIdsAndMergeDocuments { documents_seen: usize, total_documents: usize },
 
+    /// The large transforms are extracted and merged by chunks, this is the chunk that the
+    /// following steps are processing.
+    ExtractAndMergeChunk { chunk: usize, total_chunks: usize },
+
     /// Extract the documents words using the tokenizer and compute the documents
     /// facets. Stores those words, facets and documents ids on disk.
     IndexDocuments { documents_seen: usize, total_documents: usize },
@@ -24,12 +28,13 @@ impl UpdateIndexingStep {
         match self {
             RemapDocumentAddition { .. } => 0,
             ComputeIdsAndMergeDocuments { .. } => 1,
-            IndexDocuments { .. } => 2,
-            MergeDataIntoFinalDatabase { .. } => 3,
+            ExtractAndMergeChunk { .. } => 2,
+            IndexDocuments { .. } => 3,
+            MergeDataIntoFinalDatabase { .. } => 4,
         }
     }
 
     pub const fn number_of_steps(&self) -> usize {
-        4
+        5
     }
 }
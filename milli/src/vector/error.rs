@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use hf_hub::api::sync::ApiError;
 
 use crate::error::FaultSource;
+use crate::outbound::OutboundHostNotAllowed;
 
 #[derive(Debug, thiserror::Error)]
 #[error("Error while generating embeddings: {inner}")]
@@ -23,6 +24,25 @@ impl Error {
             ErrorKind::EmbedError(inner) => inner.fault,
         }
     }
+
+    /// Whether the embedder was refused because its URL is not allowed by the outbound allow-list.
+    pub fn is_outbound_host_not_allowed(&self) -> bool {
+        matches!(
+            &*self.inner,
+            ErrorKind::NewEmbedderError(NewEmbedderError {
+                kind: NewEmbedderErrorKind::OutboundHostNotAllowed(_),
+                ..
+            })
+        )
+    }
+
+    /// Whether the error may not happen again if the embeddings are generated again later.
+    pub fn is_transient(&self) -> bool {
+        match &*self.inner {
+            ErrorKind::NewEmbedderError(_) => false,
+            ErrorKind::EmbedError(inner) => inner.is_transient(),
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -83,6 +103,16 @@ pub enum EmbedErrorKind {
 }
 
 impl EmbedError {
+    /// Whether the embedder was temporarily unavailable, in which case the request can be sent again later.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self.kind,
+            EmbedErrorKind::RestTooManyRequests(_)
+                | EmbedErrorKind::RestInternalServerError(..)
+                | EmbedErrorKind::RestNetwork(_)
+        )
+    }
+
     pub fn tokenize(inner: Box<dyn std::error::Error + Send + Sync>) -> Self {
         Self { kind: EmbedErrorKind::Tokenize(inner), fault: FaultSource::Runtime }
     }
@@ -254,6 +284,10 @@ impl NewEmbedderError {
         Self { kind: NewEmbedderErrorKind::LoadModel(inner), fault: FaultSource::Runtime }
     }
 
+    pub fn outbound_host_not_allowed(inner: OutboundHostNotAllowed) -> NewEmbedderError {
+        Self { kind: NewEmbedderErrorKind::OutboundHostNotAllowed(inner), fault: FaultSource::User }
+    }
+
     pub fn could_not_determine_dimension(inner: EmbedError) -> NewEmbedderError {
         Self {
             kind: NewEmbedderErrorKind::CouldNotDetermineDimension(inner),
@@ -306,4 +340,7 @@ pub enum NewEmbedderErrorKind {
     CouldNotDetermineDimension(EmbedError),
     #[error("loading model failed: {0}")]
     LoadModel(candle_core::Error),
+    // rest
+    #[error(transparent)]
+    OutboundHostNotAllowed(OutboundHostNotAllowed),
 }
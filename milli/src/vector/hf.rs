@@ -1,9 +1,14 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, Weak};
+
 use candle_core::Tensor;
 use candle_nn::VarBuilder;
 use candle_transformers::models::bert::{BertModel, Config, DTYPE};
 // FIXME: currently we'll be using the hub to retrieve model, in the future we might want to embed it into Meilisearch itself
-use hf_hub::api::sync::Api;
+use hf_hub::api::sync::{Api, ApiBuilder};
 use hf_hub::{Repo, RepoType};
+use once_cell::sync::Lazy;
 use tokenizers::{PaddingParams, Tokenizer};
 
 pub use super::error::{EmbedError, Error, NewEmbedderError};
@@ -29,11 +34,39 @@ enum WeightSource {
     Pytorch,
 }
 
+/// How the embeddings of the tokens of a text are combined into the embedding of the text.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Hash,
+    PartialEq,
+    Eq,
+    serde::Deserialize,
+    serde::Serialize,
+    deserr::Deserr,
+)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+#[deserr(rename_all = camelCase, deny_unknown_fields)]
+pub enum Pooling {
+    /// The mean of the embeddings of all the tokens.
+    #[default]
+    Mean,
+    /// The embedding of the first token, the `[CLS]` token of the BERT models.
+    Cls,
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 pub struct EmbedderOptions {
     pub model: String,
     pub revision: Option<String>,
     pub distribution: Option<DistributionShift>,
+    /// The directory the model is downloaded to, the default cache of the Hugging Face hub otherwise.
+    #[serde(default)]
+    pub cache_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub pooling: Pooling,
 }
 
 impl EmbedderOptions {
@@ -42,6 +75,16 @@ impl EmbedderOptions {
             model: "BAAI/bge-base-en-v1.5".to_string(),
             revision: Some("617ca489d9e86b49b8167676d8220688b99db36e".into()),
             distribution: None,
+            cache_dir: None,
+            pooling: Pooling::default(),
+        }
+    }
+
+    fn model_key(&self) -> ModelKey {
+        ModelKey {
+            model: self.model.clone(),
+            revision: self.revision.clone(),
+            cache_dir: self.cache_dir.clone(),
         }
     }
 }
@@ -52,10 +95,27 @@ impl Default for EmbedderOptions {
     }
 }
 
-/// Perform embedding of documents and queries
-pub struct Embedder {
+/// Identifies the files of a loaded model.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct ModelKey {
+    model: String,
+    revision: Option<String>,
+    cache_dir: Option<PathBuf>,
+}
+
+/// A model and its tokenizer, loaded once and shared by all the embedders using the same files.
+struct LoadedModel {
     model: BertModel,
     tokenizer: Tokenizer,
+}
+
+/// The models currently in use, the weak references are upgraded by the new embedders.
+static LOADED_MODELS: Lazy<Mutex<HashMap<ModelKey, Weak<LoadedModel>>>> =
+    Lazy::new(Default::default);
+
+/// Perform embedding of documents and queries
+pub struct Embedder {
+    loaded: Arc<LoadedModel>,
     options: EmbedderOptions,
     dimensions: usize,
 }
@@ -64,7 +124,7 @@ impl std::fmt::Debug for Embedder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Embedder")
             .field("model", &self.options.model)
-            .field("tokenizer", &self.tokenizer)
+            .field("tokenizer", &self.loaded.tokenizer)
             .field("options", &self.options)
             .finish()
     }
@@ -72,64 +132,22 @@ impl std::fmt::Debug for Embedder {
 
 impl Embedder {
     pub fn new(options: EmbedderOptions) -> std::result::Result<Self, NewEmbedderError> {
-        let device = match candle_core::Device::cuda_if_available(0) {
-            Ok(device) => device,
-            Err(error) => {
-                tracing::warn!("could not initialize CUDA device for Hugging Face embedder, defaulting to CPU: {}", error);
-                candle_core::Device::Cpu
+        let loaded = {
+            // The lock is held while loading so that a model is never loaded twice at once.
+            let mut loaded_models = LOADED_MODELS.lock().unwrap_or_else(|e| e.into_inner());
+            let key = options.model_key();
+            match loaded_models.get(&key).and_then(Weak::upgrade) {
+                Some(loaded) => loaded,
+                None => {
+                    let loaded = Arc::new(LoadedModel::load(&options)?);
+                    loaded_models.retain(|_, loaded| loaded.strong_count() > 0);
+                    loaded_models.insert(key, Arc::downgrade(&loaded));
+                    loaded
+                }
             }
         };
-        let repo = match options.revision.clone() {
-            Some(revision) => Repo::with_revision(options.model.clone(), RepoType::Model, revision),
-            None => Repo::model(options.model.clone()),
-        };
-        let (config_filename, tokenizer_filename, weights_filename, weight_source) = {
-            let api = Api::new().map_err(NewEmbedderError::new_api_fail)?;
-            let api = api.repo(repo);
-            let config = api.get("config.json").map_err(NewEmbedderError::api_get)?;
-            let tokenizer = api.get("tokenizer.json").map_err(NewEmbedderError::api_get)?;
-            let (weights, source) = {
-                api.get("model.safetensors")
-                    .map(|filename| (filename, WeightSource::Safetensors))
-                    .or_else(|_| {
-                        api.get("pytorch_model.bin")
-                            .map(|filename| (filename, WeightSource::Pytorch))
-                    })
-                    .map_err(NewEmbedderError::api_get)?
-            };
-            (config, tokenizer, weights, source)
-        };
-
-        let config = std::fs::read_to_string(&config_filename)
-            .map_err(|inner| NewEmbedderError::open_config(config_filename.clone(), inner))?;
-        let config: Config = serde_json::from_str(&config).map_err(|inner| {
-            NewEmbedderError::deserialize_config(config, config_filename, inner)
-        })?;
-        let mut tokenizer = Tokenizer::from_file(&tokenizer_filename)
-            .map_err(|inner| NewEmbedderError::open_tokenizer(tokenizer_filename, inner))?;
-
-        let vb = match weight_source {
-            WeightSource::Pytorch => VarBuilder::from_pth(&weights_filename, DTYPE, &device)
-                .map_err(NewEmbedderError::pytorch_weight)?,
-            WeightSource::Safetensors => unsafe {
-                VarBuilder::from_mmaped_safetensors(&[weights_filename], DTYPE, &device)
-                    .map_err(NewEmbedderError::safetensor_weight)?
-            },
-        };
-
-        let model = BertModel::load(vb, &config).map_err(NewEmbedderError::load_model)?;
-
-        if let Some(pp) = tokenizer.get_padding_mut() {
-            pp.strategy = tokenizers::PaddingStrategy::BatchLongest
-        } else {
-            let pp = PaddingParams {
-                strategy: tokenizers::PaddingStrategy::BatchLongest,
-                ..Default::default()
-            };
-            tokenizer.with_padding(Some(pp));
-        }
 
-        let mut this = Self { model, tokenizer, options, dimensions: 0 };
+        let mut this = Self { loaded, options, dimensions: 0 };
 
         let embeddings = this
             .embed(vec!["test".into()])
@@ -145,31 +163,44 @@ impl Embedder {
     ) -> std::result::Result<Vec<Embeddings<f32>>, EmbedError> {
         let tokens = match texts.len() {
             1 => vec![self
+                .loaded
                 .tokenizer
                 .encode(texts.pop().unwrap(), true)
                 .map_err(EmbedError::tokenize)?],
-            _ => self.tokenizer.encode_batch(texts, true).map_err(EmbedError::tokenize)?,
+            _ => self.loaded.tokenizer.encode_batch(texts, true).map_err(EmbedError::tokenize)?,
         };
         let token_ids = tokens
             .iter()
             .map(|tokens| {
                 let mut tokens = tokens.get_ids().to_vec();
                 tokens.truncate(512);
-                Tensor::new(tokens.as_slice(), &self.model.device).map_err(EmbedError::tensor_shape)
+                Tensor::new(tokens.as_slice(), &self.loaded.model.device)
+                    .map_err(EmbedError::tensor_shape)
             })
             .collect::<Result<Vec<_>, EmbedError>>()?;
 
         let token_ids = Tensor::stack(&token_ids, 0).map_err(EmbedError::tensor_shape)?;
         let token_type_ids = token_ids.zeros_like().map_err(EmbedError::tensor_shape)?;
-        let embeddings =
-            self.model.forward(&token_ids, &token_type_ids).map_err(EmbedError::model_forward)?;
+        let embeddings = self
+            .loaded
+            .model
+            .forward(&token_ids, &token_type_ids)
+            .map_err(EmbedError::model_forward)?;
 
-        // Apply some avg-pooling by taking the mean embedding value for all tokens (including padding)
-        let (_n_sentence, n_tokens, _hidden_size) =
-            embeddings.dims3().map_err(EmbedError::tensor_shape)?;
+        let embeddings = match self.options.pooling {
+            Pooling::Mean => {
+                // Apply some avg-pooling by taking the mean embedding value for all tokens (including padding)
+                let (_n_sentence, n_tokens, _hidden_size) =
+                    embeddings.dims3().map_err(EmbedError::tensor_shape)?;
 
-        let embeddings = (embeddings.sum(1).map_err(EmbedError::tensor_value)? / (n_tokens as f64))
-            .map_err(EmbedError::tensor_shape)?;
+                (embeddings.sum(1).map_err(EmbedError::tensor_value)? / (n_tokens as f64))
+                    .map_err(EmbedError::tensor_shape)?
+            }
+            Pooling::Cls => embeddings
+                .narrow(1, 0, 1)
+                .and_then(|embeddings| embeddings.squeeze(1))
+                .map_err(EmbedError::tensor_shape)?,
+        };
 
         let embeddings: Vec<Embedding> = embeddings.to_vec2().map_err(EmbedError::tensor_shape)?;
         Ok(embeddings.into_iter().map(Embeddings::from_single_embedding).collect())
@@ -207,3 +238,133 @@ impl Embedder {
         })
     }
 }
+
+impl LoadedModel {
+    fn load(options: &EmbedderOptions) -> std::result::Result<Self, NewEmbedderError> {
+        let device = match candle_core::Device::cuda_if_available(0) {
+            Ok(device) => device,
+            Err(error) => {
+                tracing::warn!("could not initialize CUDA device for Hugging Face embedder, defaulting to CPU: {}", error);
+                candle_core::Device::Cpu
+            }
+        };
+        let repo = match options.revision.clone() {
+            Some(revision) => Repo::with_revision(options.model.clone(), RepoType::Model, revision),
+            None => Repo::model(options.model.clone()),
+        };
+        let (config_filename, tokenizer_filename, weights_filename, weight_source) = {
+            let api = match &options.cache_dir {
+                Some(cache_dir) => ApiBuilder::new().with_cache_dir(cache_dir.clone()).build(),
+                None => Api::new(),
+            };
+            let api = api.map_err(NewEmbedderError::new_api_fail)?;
+            let api = api.repo(repo);
+            let config = api.get("config.json").map_err(NewEmbedderError::api_get)?;
+            let tokenizer = api.get("tokenizer.json").map_err(NewEmbedderError::api_get)?;
+            let (weights, source) = {
+                api.get("model.safetensors")
+                    .map(|filename| (filename, WeightSource::Safetensors))
+                    .or_else(|_| {
+                        api.get("pytorch_model.bin")
+                            .map(|filename| (filename, WeightSource::Pytorch))
+                    })
+                    .map_err(NewEmbedderError::api_get)?
+            };
+            (config, tokenizer, weights, source)
+        };
+
+        let config = std::fs::read_to_string(&config_filename)
+            .map_err(|inner| NewEmbedderError::open_config(config_filename.clone(), inner))?;
+        let config: Config = serde_json::from_str(&config).map_err(|inner| {
+            NewEmbedderError::deserialize_config(config, config_filename, inner)
+        })?;
+        let mut tokenizer = Tokenizer::from_file(&tokenizer_filename)
+            .map_err(|inner| NewEmbedderError::open_tokenizer(tokenizer_filename, inner))?;
+
+        let vb = match weight_source {
+            WeightSource::Pytorch => VarBuilder::from_pth(&weights_filename, DTYPE, &device)
+                .map_err(NewEmbedderError::pytorch_weight)?,
+            WeightSource::Safetensors => unsafe {
+                VarBuilder::from_mmaped_safetensors(&[weights_filename], DTYPE, &device)
+                    .map_err(NewEmbedderError::safetensor_weight)?
+            },
+        };
+
+        let model = BertModel::load(vb, &config).map_err(NewEmbedderError::load_model)?;
+
+        if let Some(pp) = tokenizer.get_padding_mut() {
+            pp.strategy = tokenizers::PaddingStrategy::BatchLongest
+        } else {
+            let pp = PaddingParams {
+                strategy: tokenizers::PaddingStrategy::BatchLongest,
+                ..Default::default()
+            };
+            tokenizer.with_padding(Some(pp));
+        }
+
+        Ok(Self { model, tokenizer })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tiny_bert(pooling: Pooling) -> EmbedderOptions {
+        EmbedderOptions {
+            model: "meilisearch/tiny-bert".into(),
+            revision: None,
+            distribution: None,
+            cache_dir: Some(
+                PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/assets/hf-cache"),
+            ),
+            pooling,
+        }
+    }
+
+    fn embed(embedder: &Embedder, texts: &[&str]) -> Vec<Vec<f32>> {
+        let texts = texts.iter().map(|text| text.to_string()).collect();
+        embedder.embed(texts).unwrap().into_iter().map(Embeddings::into_inner).collect()
+    }
+
+    #[test]
+    fn identical_embeddings_across_calls_and_threads() {
+        let texts = ["the quick brown fox", "hello world"];
+        let embedder = Arc::new(Embedder::new(tiny_bert(Pooling::Mean)).unwrap());
+        assert_eq!(embedder.dimensions(), 8);
+
+        let expected = embed(&embedder, &texts);
+        assert_eq!(embed(&embedder, &texts), expected);
+
+        // the embedders using the same files share the loaded model
+        let other = Embedder::new(tiny_bert(Pooling::Mean)).unwrap();
+        assert!(Arc::ptr_eq(&embedder.loaded, &other.loaded));
+        assert_eq!(embed(&other, &texts), expected);
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let embedder = embedder.clone();
+                std::thread::spawn(move || embed(&embedder, &texts))
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn pooling() {
+        let mean = Embedder::new(tiny_bert(Pooling::Mean)).unwrap();
+        let cls = Embedder::new(tiny_bert(Pooling::Cls)).unwrap();
+        assert!(Arc::ptr_eq(&mean.loaded, &cls.loaded));
+        assert_eq!(cls.dimensions(), 8);
+        assert_ne!(embed(&mean, &["hello world"]), embed(&cls, &["hello world"]));
+    }
+
+    #[test]
+    fn unknown_revision() {
+        let options =
+            EmbedderOptions { revision: Some("unknown".into()), ..tiny_bert(Pooling::Mean) };
+        assert!(Embedder::new(options).is_err());
+    }
+}
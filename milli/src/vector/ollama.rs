@@ -3,6 +3,7 @@ use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
 use super::error::{EmbedError, EmbedErrorKind, NewEmbedderError, NewEmbedderErrorKind};
 use super::rest::{Embedder as RestEmbedder, EmbedderOptions as RestEmbedderOptions};
 use super::{DistributionShift, Embeddings};
+use crate::outbound::OutboundPolicy;
 
 #[derive(Debug)]
 pub struct Embedder {
@@ -24,21 +25,27 @@ impl EmbedderOptions {
 }
 
 impl Embedder {
-    pub fn new(options: EmbedderOptions) -> Result<Self, NewEmbedderError> {
+    pub fn new(
+        options: EmbedderOptions,
+        outbound_policy: &OutboundPolicy,
+    ) -> Result<Self, NewEmbedderError> {
         let model = options.embedding_model.as_str();
-        let rest_embedder = match RestEmbedder::new(RestEmbedderOptions {
-            api_key: options.api_key,
-            dimensions: None,
-            distribution: options.distribution,
-            url: options.url.unwrap_or_else(get_ollama_path),
-            query: serde_json::json!({
-                "model": model,
-            }),
-            input_field: vec!["prompt".to_owned()],
-            path_to_embeddings: Default::default(),
-            embedding_object: vec!["embedding".to_owned()],
-            input_type: super::rest::InputType::Text,
-        }) {
+        let rest_embedder = match RestEmbedder::new(
+            RestEmbedderOptions {
+                api_key: options.api_key,
+                dimensions: None,
+                distribution: options.distribution,
+                url: options.url.unwrap_or_else(get_ollama_path),
+                query: serde_json::json!({
+                    "model": model,
+                }),
+                input_field: vec!["prompt".to_owned()],
+                path_to_embeddings: Default::default(),
+                embedding_object: vec!["embedding".to_owned()],
+                input_type: super::rest::InputType::Text,
+            },
+            outbound_policy,
+        ) {
             Ok(embedder) => embedder,
             Err(NewEmbedderError {
                 kind:
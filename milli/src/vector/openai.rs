@@ -4,6 +4,7 @@ use rayon::iter::{IntoParallelIterator, ParallelIterator as _};
 use super::error::{EmbedError, NewEmbedderError};
 use super::rest::{Embedder as RestEmbedder, EmbedderOptions as RestEmbedderOptions};
 use super::{DistributionShift, Embeddings};
+use crate::outbound::OutboundPolicy;
 use crate::vector::error::EmbedErrorKind;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
@@ -166,24 +167,30 @@ pub struct Embedder {
 }
 
 impl Embedder {
-    pub fn new(options: EmbedderOptions) -> Result<Self, NewEmbedderError> {
+    pub fn new(
+        options: EmbedderOptions,
+        outbound_policy: &OutboundPolicy,
+    ) -> Result<Self, NewEmbedderError> {
         let mut inferred_api_key = Default::default();
         let api_key = options.api_key.as_ref().unwrap_or_else(|| {
             inferred_api_key = infer_api_key();
             &inferred_api_key
         });
 
-        let rest_embedder = RestEmbedder::new(RestEmbedderOptions {
-            api_key: Some(api_key.clone()),
-            distribution: None,
-            dimensions: Some(options.dimensions()),
-            url: OPENAI_EMBEDDINGS_URL.to_owned(),
-            query: options.query(),
-            input_field: vec!["input".to_owned()],
-            input_type: crate::vector::rest::InputType::TextArray,
-            path_to_embeddings: vec!["data".to_owned()],
-            embedding_object: vec!["embedding".to_owned()],
-        })?;
+        let rest_embedder = RestEmbedder::new(
+            RestEmbedderOptions {
+                api_key: Some(api_key.clone()),
+                distribution: None,
+                dimensions: Some(options.dimensions()),
+                url: OPENAI_EMBEDDINGS_URL.to_owned(),
+                query: options.query(),
+                input_field: vec!["input".to_owned()],
+                input_type: crate::vector::rest::InputType::TextArray,
+                path_to_embeddings: vec!["data".to_owned()],
+                embedding_object: vec!["embedding".to_owned()],
+            },
+            outbound_policy,
+        )?;
 
         // looking at the code it is very unclear that this can actually fail.
         let tokenizer = tiktoken_rs::cl100k_base().unwrap();
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use super::{
     DistributionShift, EmbedError, Embedding, Embeddings, NewEmbedderError, REQUEST_PARALLELISM,
 };
+use crate::outbound::OutboundPolicy;
 
 // retrying in case of failure
 
@@ -121,10 +122,17 @@ pub enum InputType {
 }
 
 impl Embedder {
-    pub fn new(options: EmbedderOptions) -> Result<Self, NewEmbedderError> {
+    pub fn new(
+        options: EmbedderOptions,
+        outbound_policy: &OutboundPolicy,
+    ) -> Result<Self, NewEmbedderError> {
+        outbound_policy
+            .check_url(&options.url)
+            .map_err(NewEmbedderError::outbound_host_not_allowed)?;
         let bearer = options.api_key.as_deref().map(|api_key| format!("Bearer {api_key}"));
 
-        let client = ureq::AgentBuilder::new()
+        let client = outbound_policy
+            .agent_builder()
             .max_idle_connections(REQUEST_PARALLELISM * 2)
             .max_idle_connections_per_host(REQUEST_PARALLELISM * 2)
             .build();
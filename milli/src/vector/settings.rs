@@ -1,6 +1,9 @@
+use std::path::PathBuf;
+
 use deserr::Deserr;
 use serde::{Deserialize, Serialize};
 
+use super::hf::Pooling;
 use super::rest::InputType;
 use super::{ollama, openai, DistributionShift};
 use crate::prompt::PromptData;
@@ -23,6 +26,12 @@ pub struct EmbeddingSettings {
     pub revision: Setting<String>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
+    pub cache_dir: Setting<String>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
+    pub pooling: Setting<Pooling>,
+    #[serde(default, skip_serializing_if = "Setting::is_not_set")]
+    #[deserr(default)]
     pub api_key: Setting<String>,
     #[serde(default, skip_serializing_if = "Setting::is_not_set")]
     #[deserr(default)]
@@ -93,6 +102,8 @@ impl EmbeddingSettings {
     pub const SOURCE: &'static str = "source";
     pub const MODEL: &'static str = "model";
     pub const REVISION: &'static str = "revision";
+    pub const CACHE_DIR: &'static str = "cacheDir";
+    pub const POOLING: &'static str = "pooling";
     pub const API_KEY: &'static str = "apiKey";
     pub const DIMENSIONS: &'static str = "dimensions";
     pub const DOCUMENT_TEMPLATE: &'static str = "documentTemplate";
@@ -119,6 +130,8 @@ impl EmbeddingSettings {
                 &[EmbedderSource::HuggingFace, EmbedderSource::OpenAi, EmbedderSource::Ollama]
             }
             Self::REVISION => &[EmbedderSource::HuggingFace],
+            Self::CACHE_DIR => &[EmbedderSource::HuggingFace],
+            Self::POOLING => &[EmbedderSource::HuggingFace],
             Self::API_KEY => {
                 &[EmbedderSource::OpenAi, EmbedderSource::Ollama, EmbedderSource::Rest]
             }
@@ -162,6 +175,8 @@ impl EmbeddingSettings {
                 Self::SOURCE,
                 Self::MODEL,
                 Self::REVISION,
+                Self::CACHE_DIR,
+                Self::POOLING,
                 Self::DOCUMENT_TEMPLATE,
                 Self::DISTRIBUTION,
             ],
@@ -221,6 +236,8 @@ impl EmbeddingSettings {
                     source: old_source,
                     model: old_model,
                     revision: old_revision,
+                    cache_dir: old_cache_dir,
+                    pooling: old_pooling,
                     api_key: old_api_key,
                     dimensions: old_dimensions,
                     document_template: old_document_template,
@@ -236,6 +253,8 @@ impl EmbeddingSettings {
                     source: new_source,
                     model: new_model,
                     revision: new_revision,
+                    cache_dir: new_cache_dir,
+                    pooling: new_pooling,
                     api_key: new_api_key,
                     dimensions: new_dimensions,
                     document_template: new_document_template,
@@ -253,6 +272,7 @@ impl EmbeddingSettings {
                 needs_reindex |= old_source.apply(new_source);
                 needs_reindex |= old_model.apply(new_model);
                 needs_reindex |= old_revision.apply(new_revision);
+                needs_reindex |= old_pooling.apply(new_pooling);
                 needs_reindex |= old_dimensions.apply(new_dimensions);
                 needs_reindex |= old_document_template.apply(new_document_template);
                 needs_reindex |= old_url.apply(new_url);
@@ -264,6 +284,7 @@ impl EmbeddingSettings {
 
                 old_distribution.apply(new_distribution);
                 old_api_key.apply(new_api_key);
+                old_cache_dir.apply(new_cache_dir);
                 needs_reindex
             }
             (Setting::Reset, Setting::Reset) | (_, Setting::NotSet) => false,
@@ -305,6 +326,11 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 source: Setting::Set(EmbedderSource::HuggingFace),
                 model: Setting::Set(options.model),
                 revision: options.revision.map(Setting::Set).unwrap_or_default(),
+                cache_dir: options
+                    .cache_dir
+                    .map(|cache_dir| Setting::Set(cache_dir.to_string_lossy().into_owned()))
+                    .unwrap_or_default(),
+                pooling: Setting::Set(options.pooling),
                 api_key: Setting::NotSet,
                 dimensions: Setting::NotSet,
                 document_template: Setting::Set(prompt.template),
@@ -320,6 +346,8 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 source: Setting::Set(EmbedderSource::OpenAi),
                 model: Setting::Set(options.embedding_model.name().to_owned()),
                 revision: Setting::NotSet,
+                cache_dir: Setting::NotSet,
+                pooling: Setting::NotSet,
                 api_key: options.api_key.map(Setting::Set).unwrap_or_default(),
                 dimensions: options.dimensions.map(Setting::Set).unwrap_or_default(),
                 document_template: Setting::Set(prompt.template),
@@ -335,6 +363,8 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 source: Setting::Set(EmbedderSource::Ollama),
                 model: Setting::Set(options.embedding_model.to_owned()),
                 revision: Setting::NotSet,
+                cache_dir: Setting::NotSet,
+                pooling: Setting::NotSet,
                 api_key: Setting::NotSet,
                 dimensions: Setting::NotSet,
                 document_template: Setting::Set(prompt.template),
@@ -350,6 +380,8 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 source: Setting::Set(EmbedderSource::UserProvided),
                 model: Setting::NotSet,
                 revision: Setting::NotSet,
+                cache_dir: Setting::NotSet,
+                pooling: Setting::NotSet,
                 api_key: Setting::NotSet,
                 dimensions: Setting::Set(options.dimensions),
                 document_template: Setting::NotSet,
@@ -375,6 +407,8 @@ impl From<EmbeddingConfig> for EmbeddingSettings {
                 source: Setting::Set(EmbedderSource::Rest),
                 model: Setting::NotSet,
                 revision: Setting::NotSet,
+                cache_dir: Setting::NotSet,
+                pooling: Setting::NotSet,
                 api_key: api_key.map(Setting::Set).unwrap_or_default(),
                 dimensions: dimensions.map(Setting::Set).unwrap_or_default(),
                 document_template: Setting::Set(prompt.template),
@@ -397,6 +431,8 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
             source,
             model,
             revision,
+            cache_dir,
+            pooling,
             api_key,
             dimensions,
             document_template,
@@ -454,6 +490,10 @@ impl From<EmbeddingSettings> for EmbeddingConfig {
                     if let Some(revision) = revision.set() {
                         options.revision = Some(revision);
                     }
+                    options.cache_dir = cache_dir.set().map(PathBuf::from);
+                    if let Some(pooling) = pooling.set() {
+                        options.pooling = pooling;
+                    }
                     options.distribution = distribution.set();
                     this.embedder_options = super::EmbedderOptions::HuggingFace(options);
                 }
@@ -0,0 +1,75 @@
+use heed::EnvOpenOptions;
+use milli::{Index, IndexerBuilder, Object, SearchBuilder, SearchHits};
+use serde_json::json;
+
+use crate::search::internal_to_external_ids;
+
+fn documents() -> Vec<Object> {
+    let documents = json!([
+        { "id": 0, "title": "the quick brown fox", "genre": "animal", "year": 2001 },
+        { "id": 1, "title": "the lazy dog", "genre": "animal", "year": 1999 },
+        { "id": 2, "title": "the brown chair", "genre": "furniture", "year": 2010 },
+        { "id": 3, "title": "a brown fox and a brown dog", "genre": "animal", "year": 2005 },
+    ]);
+    serde_json::from_value(documents).unwrap()
+}
+
+fn setup() -> (tempfile::TempDir, Index) {
+    let path = tempfile::tempdir().unwrap();
+    let mut options = EnvOpenOptions::new();
+    options.map_size(10 * 1024 * 1024); // 10 MB
+    let index = Index::new(options, &path).unwrap();
+
+    let mut wtxn = index.write_txn().unwrap();
+    let indexed = IndexerBuilder::new(&index)
+        .primary_key("id")
+        .searchable_fields(["title"])
+        .filterable_fields(["genre"])
+        .sortable_fields(["year"])
+        .add_documents(documents())
+        .execute(&mut wtxn)
+        .unwrap();
+    wtxn.commit().unwrap();
+    assert_eq!(indexed, 4);
+
+    (path, index)
+}
+
+#[test]
+fn search_with_the_builder() {
+    let (_path, index) = setup();
+    let rtxn = index.read_txn().unwrap();
+
+    let SearchHits { documents_ids, candidates, scores } =
+        SearchBuilder::new(&index).query("brown fox").execute(&rtxn).unwrap();
+    assert_eq!(candidates.len(), 3);
+    assert_eq!(documents_ids.len(), 3);
+    assert_eq!(scores.len(), 3);
+    assert!(scores.windows(2).all(|w| w[0] >= w[1]), "{scores:?}");
+
+    let SearchHits { documents_ids, candidates, .. } = SearchBuilder::new(&index)
+        .query("brown")
+        .filter("genre = animal")
+        .sort(["year:desc"])
+        .execute(&rtxn)
+        .unwrap();
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(internal_to_external_ids(&index, &documents_ids), vec!["3", "0"]);
+
+    let SearchHits { documents_ids, candidates, .. } =
+        SearchBuilder::new(&index).offset(1).limit(2).execute(&rtxn).unwrap();
+    assert_eq!(candidates.len(), 4);
+    assert_eq!(documents_ids.len(), 2);
+}
+
+#[test]
+fn search_builder_errors() {
+    let (_path, index) = setup();
+    let rtxn = index.read_txn().unwrap();
+
+    let error = SearchBuilder::new(&index).filter("year > 2000").execute(&rtxn).unwrap_err();
+    assert!(error.to_string().contains("Attribute `year` is not filterable"), "{error}");
+
+    let error = SearchBuilder::new(&index).sort(["title:asc"]).execute(&rtxn).unwrap_err();
+    assert!(error.to_string().contains("Attribute `title` is not sortable"), "{error}");
+}
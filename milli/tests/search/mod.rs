@@ -12,6 +12,7 @@ use milli::{AscDesc, Criterion, DocumentId, Index, Member, Object, TermsMatching
 use serde::{Deserialize, Deserializer};
 use slice_group_by::GroupBy;
 
+mod builder;
 mod distinct;
 mod facet_distribution;
 mod filters;
@@ -149,9 +150,11 @@ pub fn expected_order(
                     new_groups
                         .extend(group.linear_group_by_key(|d| d.asc_desc_rank).map(Vec::from));
                 }
-                Criterion::Asc(_) | Criterion::Desc(_) | Criterion::Sort => {
-                    new_groups.push(group.clone())
-                }
+                Criterion::Asc(_)
+                | Criterion::Desc(_)
+                | Criterion::Sort
+                | Criterion::Random { .. }
+                | Criterion::GeoPoint { .. } => new_groups.push(group.clone()),
             }
         }
         groups = std::mem::take(&mut new_groups);
@@ -4,7 +4,7 @@ use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
-use crate::entry::{Entry, NewCallsite, SpanClose, SpanEnter, SpanExit};
+use crate::entry::{Entry, NewCallsite, ResourceId, SpanClose, SpanEnter, SpanExit, SpanId};
 use crate::{Error, TraceReader};
 
 #[derive(Debug, Clone, Copy)]
@@ -155,3 +155,66 @@ fn calls_to_stats(calls: Vec<(Duration, Duration)>) -> CallStats {
     let self_sum: Duration = calls.iter().map(|(_, self_duration)| self_duration).sum();
     CallStats { call_count: nb, time: sum.as_nanos() as u64, self_time: self_sum.as_nanos() as u64 }
 }
+
+/// The statistics of the calls to a span, updated while the entries are received.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveCallStats {
+    pub call_count: usize,
+    /// The total time spent in the span, in nanoseconds.
+    pub total_time: u64,
+    /// The longest time spent in the span during a single call, in nanoseconds.
+    pub max_time: u64,
+}
+
+/// Aggregates the calls to the spans from a live stream of entries, for instance from a [`crate::Trace`].
+#[derive(Debug, Default)]
+pub struct LiveSpanStats {
+    calls: HashMap<ResourceId, String>,
+    spans: HashMap<SpanId, (ResourceId, SpanStatus)>,
+    stats: BTreeMap<String, LiveCallStats>,
+}
+
+impl LiveSpanStats {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn process(&mut self, entry: &Entry) {
+        match entry {
+            Entry::NewCallsite(callsite) => {
+                let name = format!("{}::{}", callsite.target, callsite.name);
+                self.calls.insert(callsite.call_id, name);
+            }
+            Entry::NewSpan(span) => {
+                self.spans.insert(span.id, (span.call_id, SpanStatus::Outside));
+            }
+            Entry::SpanEnter(SpanEnter { id, time, memory: _ }) => {
+                if let Some((_, status @ SpanStatus::Outside)) = self.spans.get_mut(id) {
+                    *status = SpanStatus::Inside(*time);
+                }
+            }
+            Entry::SpanExit(SpanExit { id, time: end, memory: _ }) => {
+                let Some((call_id, status)) = self.spans.get_mut(id) else { return };
+                let SpanStatus::Inside(begin) = *status else { return };
+                *status = SpanStatus::Outside;
+
+                let Some(name) = self.calls.get(call_id) else { return };
+                let duration = end.saturating_sub(begin).as_nanos() as u64;
+                let stats = self.stats.entry(name.clone()).or_default();
+                stats.call_count += 1;
+                stats.total_time += duration;
+                stats.max_time = stats.max_time.max(duration);
+            }
+            Entry::SpanClose(SpanClose { id, time: _ }) => {
+                self.spans.remove(id);
+            }
+            Entry::NewThread(_) | Entry::Event(_) => {}
+        }
+    }
+
+    /// The statistics of the spans called since the aggregation started, by `target::name`.
+    pub fn stats(&self) -> &BTreeMap<String, LiveCallStats> {
+        &self.stats
+    }
+}